@@ -0,0 +1,168 @@
+//! # Air Assist
+//!
+//! Switches a laser's air assist with M-codes around the burns of a job: the
+//! on-code goes in just before the first burn and the off-code after the last,
+//! or around every operation when toggling per operation. Nothing is written
+//! while the option is disabled.
+//!
+//! Boards differ in how air assist is wired, so both codes are configurable: the
+//! flood (`M8`) or mist (`M7`) coolant output with `M9`, or a board-specific
+//! code such as `M106`/`M107`. When air assist shares a coolant output, the
+//! generated program issues no coolant codes of its own and each code is only
+//! written when the air actually changes state, so the shared output is never
+//! switched behind the operator's back.
+
+use crate::error::{CamToolError, CamToolResult};
+use serde::{Deserialize, Serialize};
+
+/// Air assist settings for the laser generators
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AirAssist {
+    /// Write air assist codes at all
+    pub enabled: bool,
+    /// Block that turns the air on, e.g. `M8`
+    pub on_code: String,
+    /// Block that turns the air off, e.g. `M9`
+    pub off_code: String,
+    /// Turn the air off after each operation and on again for the next
+    pub per_operation: bool,
+}
+
+impl Default for AirAssist {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            on_code: "M8".to_string(),
+            off_code: "M9".to_string(),
+            per_operation: false,
+        }
+    }
+}
+
+impl AirAssist {
+    /// Check that both codes are single M-code blocks and differ
+    ///
+    /// Always succeeds when air assist is disabled.
+    pub fn validate(&self) -> CamToolResult<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        for (name, code) in [("on", &self.on_code), ("off", &self.off_code)] {
+            if !is_m_code_block(code) {
+                return Err(CamToolError::InvalidParameters(format!(
+                    "air assist {} code must be an M-code block such as M8, got '{}'",
+                    name, code
+                )));
+            }
+        }
+        if normalize(&self.on_code) == normalize(&self.off_code) {
+            return Err(CamToolError::InvalidParameters(
+                "air assist on and off codes must differ".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether the codes drive a coolant output (`M7`, `M8` or `M9`)
+    pub fn shares_coolant(&self) -> bool {
+        [&self.on_code, &self.off_code].iter().any(|code| {
+            matches!(
+                normalize(code).split(' ').next(),
+                Some("M7" | "M8" | "M9" | "M07" | "M08" | "M09")
+            )
+        })
+    }
+
+    /// Header comment describing the codes, if air assist is enabled
+    pub(crate) fn header_comment(&self) -> Option<String> {
+        self.enabled.then(|| {
+            format!(
+                "; Air assist: {} on, {} off{}{}\n",
+                self.on_code.trim(),
+                self.off_code.trim(),
+                if self.per_operation {
+                    ", per operation"
+                } else {
+                    ""
+                },
+                if self.shares_coolant() {
+                    " (coolant output)"
+                } else {
+                    ""
+                }
+            )
+        })
+    }
+}
+
+/// Air assist state while a generator writes its program
+pub(crate) struct AirAssistState<'a> {
+    config: &'a AirAssist,
+    on: bool,
+}
+
+impl<'a> AirAssistState<'a> {
+    pub(crate) fn new(config: &'a AirAssist) -> Self {
+        Self { config, on: false }
+    }
+
+    /// Turn the air on ahead of a burn, unless it already is
+    pub(crate) fn before_burn(&mut self, gcode: &mut String) {
+        if self.config.enabled && !self.on {
+            gcode.push_str(&format!("{} ; Air assist on\n", self.config.on_code.trim()));
+            self.on = true;
+        }
+    }
+
+    /// An operation has finished; the air goes off when toggling per operation
+    pub(crate) fn end_operation(&mut self, gcode: &mut String) {
+        if self.config.per_operation {
+            self.off(gcode);
+        }
+    }
+
+    /// The last burn of the job is done
+    pub(crate) fn end_job(&mut self, gcode: &mut String) {
+        self.off(gcode);
+    }
+
+    fn off(&mut self, gcode: &mut String) {
+        if self.on {
+            gcode.push_str(&format!(
+                "{} ; Air assist off\n",
+                self.config.off_code.trim()
+            ));
+            self.on = false;
+        }
+    }
+}
+
+/// Upper-case words separated by single spaces
+fn normalize(code: &str) -> String {
+    code.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_ascii_uppercase()
+}
+
+/// An `M` word followed by optional parameter words, e.g. `M106 P1 S255`
+fn is_m_code_block(code: &str) -> bool {
+    let code = normalize(code);
+    let mut words = code.split(' ');
+    let is_word = |word: &str, first: bool| {
+        let mut chars = word.chars();
+        let letter = chars.next();
+        let number = chars.as_str();
+        let letter_ok = match letter {
+            Some('M') => true,
+            Some(c) => !first && c.is_ascii_alphabetic() && !matches!(c, 'G' | 'N'),
+            None => false,
+        };
+        letter_ok && !number.is_empty() && number.parse::<f64>().is_ok()
+    };
+    match words.next() {
+        Some(first) if is_word(first, true) => words.all(|word| is_word(word, false)),
+        _ => false,
+    }
+}
@@ -0,0 +1,137 @@
+//! # Annotation
+//!
+//! Optional comments naming the tool, and the part of its output, that produced
+//! the G-code that follows, e.g. `(TabbedBox: Panel 2 Wall 2)`. They make
+//! generated programs easier to debug while iterating and are removed again by
+//! [`CommentProcessor::strip_annotations`](crate::CommentProcessor::strip_annotations)
+//! for the final run.
+//!
+//! Annotations are parenthesised comments on lines of their own, so they never
+//! share a block with motion. Labels are cleaned of parentheses and control
+//! characters and kept short enough for an 80-character controller line buffer.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Longest label written, in characters
+const MAX_LABEL_CHARS: usize = 60;
+
+/// How much annotation a generator writes
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum AnnotationLevel {
+    /// No annotations
+    #[default]
+    Off,
+    /// One comment at each operation or section boundary
+    Section,
+    /// Section comments plus one for each feature within a section
+    Line,
+}
+
+/// CAM tools that annotate their output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AnnotatedTool {
+    TabbedBox,
+    JigsawPuzzle,
+    DrillPress,
+    SpoilboardGrid,
+    SpoilboardSurfacing,
+    BitmapEngraver,
+    VectorEngraver,
+    MaterialTest,
+}
+
+impl AnnotatedTool {
+    pub const ALL: [AnnotatedTool; 8] = [
+        AnnotatedTool::TabbedBox,
+        AnnotatedTool::JigsawPuzzle,
+        AnnotatedTool::DrillPress,
+        AnnotatedTool::SpoilboardGrid,
+        AnnotatedTool::SpoilboardSurfacing,
+        AnnotatedTool::BitmapEngraver,
+        AnnotatedTool::VectorEngraver,
+        AnnotatedTool::MaterialTest,
+    ];
+
+    /// Name written at the start of each annotation
+    pub fn name(&self) -> &'static str {
+        match self {
+            AnnotatedTool::TabbedBox => "TabbedBox",
+            AnnotatedTool::JigsawPuzzle => "JigsawPuzzle",
+            AnnotatedTool::DrillPress => "DrillPress",
+            AnnotatedTool::SpoilboardGrid => "SpoilboardGrid",
+            AnnotatedTool::SpoilboardSurfacing => "SpoilboardSurfacing",
+            AnnotatedTool::BitmapEngraver => "BitmapEngraver",
+            AnnotatedTool::VectorEngraver => "VectorEngraver",
+            AnnotatedTool::MaterialTest => "MaterialTest",
+        }
+    }
+}
+
+/// Writes a tool's annotations at the configured level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Annotator {
+    tool: AnnotatedTool,
+    level: AnnotationLevel,
+}
+
+impl Annotator {
+    pub fn new(tool: AnnotatedTool, level: AnnotationLevel) -> Self {
+        Self { tool, level }
+    }
+
+    pub fn level(&self) -> AnnotationLevel {
+        self.level
+    }
+
+    /// Annotate an operation or section boundary
+    pub fn section(&self, gcode: &mut String, label: fmt::Arguments<'_>) {
+        if self.level >= AnnotationLevel::Section {
+            self.write(gcode, label);
+        }
+    }
+
+    /// Annotate a feature within a section; only written at [`AnnotationLevel::Line`]
+    pub fn line(&self, gcode: &mut String, label: fmt::Arguments<'_>) {
+        if self.level >= AnnotationLevel::Line {
+            self.write(gcode, label);
+        }
+    }
+
+    fn write(&self, gcode: &mut String, label: fmt::Arguments<'_>) {
+        gcode.push_str(&annotation_comment(self.tool, &label.to_string()));
+        gcode.push('\n');
+    }
+}
+
+/// The annotation line for `label`, without a trailing newline
+pub fn annotation_comment(tool: AnnotatedTool, label: &str) -> String {
+    let label: String = label
+        .chars()
+        .map(|c| match c {
+            '(' => '[',
+            ')' => ']',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .take(MAX_LABEL_CHARS)
+        .collect();
+    format!("({}: {})", tool.name(), label.trim())
+}
+
+/// Whether a line is an annotation written by one of the tools
+pub fn is_annotation(line: &str) -> bool {
+    let line = line.trim();
+    let Some(body) = line.strip_prefix('(').and_then(|l| l.strip_suffix(')')) else {
+        return false;
+    };
+    if body.contains(['(', ')']) {
+        return false;
+    }
+    AnnotatedTool::ALL.iter().any(|tool| {
+        body.strip_prefix(tool.name())
+            .is_some_and(|rest| rest.starts_with(": "))
+    })
+}
@@ -1,16 +1,31 @@
 //! Arc Expander - Task 51
 //!
 //! Converts G2/G3 arc commands to linear segments for controllers without arc support.
+//!
+//! For controllers that handle arcs but not very long or very large-radius ones,
+//! [`ArcExpander::split_arcs`] instead splits such arcs into shorter arcs around
+//! the same center. Joints lie on the original arc and the pieces keep its
+//! direction, plane, helical travel and total sweep. Arcs are only split where
+//! the start position is known; absolute arc centers (G90.1) are left alone.
+
+use crate::error::{CamToolError, CamToolResult};
+use crate::validator::GCodeValidator;
+use gcodekit5_core::{ArcMove, ArcPlane, GcodeError};
 
-use std::f64::consts::PI;
+/// Decimal places of split arc coordinates, so joints stay on the arc
+const SPLIT_DECIMALS: usize = 6;
 
 /// Arc expansion configuration
 #[derive(Debug, Clone)]
 pub struct ArcExpanderConfig {
-    /// Maximum segment length
+    /// Maximum segment length, measured along the path (including helical Z travel)
     pub segment_length: f64,
-    /// Number of segments
+    /// Minimum number of segments per arc
     pub num_segments: usize,
+    /// Split arcs longer than this (mm) into shorter arcs
+    pub max_arc_length: Option<f64>,
+    /// Split arcs with a larger radius (mm) into pieces no longer than this
+    pub max_arc_radius: Option<f64>,
 }
 
 impl Default for ArcExpanderConfig {
@@ -18,6 +33,8 @@ impl Default for ArcExpanderConfig {
         Self {
             segment_length: 0.5,
             num_segments: 20,
+            max_arc_length: None,
+            max_arc_radius: None,
         }
     }
 }
@@ -34,7 +51,7 @@ impl ArcExpander {
         Self { config }
     }
 
-    /// Expand an arc into line segments
+    /// Expand an XY-plane (G17) arc into line segments
     #[allow(clippy::too_many_arguments)]
     pub fn expand_arc(
         &self,
@@ -46,35 +63,315 @@ impl ArcExpander {
         center_y: f64,
         is_clockwise: bool,
     ) -> Vec<(f64, f64)> {
-        let mut segments = Vec::new();
-
-        // Calculate radius
-        let radius_x = start_x - center_x;
-        let radius_y = start_y - center_y;
-        let radius = (radius_x * radius_x + radius_y * radius_y).sqrt();
-
-        // Calculate angles
-        let start_angle = radius_y.atan2(radius_x);
-        let end_angle = (end_y - center_y).atan2(end_x - center_x);
-
-        // Calculate angle delta
-        let mut angle_delta = end_angle - start_angle;
-        if is_clockwise && angle_delta > 0.0 {
-            angle_delta -= 2.0 * PI;
-        } else if !is_clockwise && angle_delta < 0.0 {
-            angle_delta += 2.0 * PI;
+        self.expand_arc_in_plane(
+            [start_x, start_y, 0.0],
+            [end_x, end_y, 0.0],
+            [center_x, center_y, 0.0],
+            is_clockwise,
+            ArcPlane::XY,
+        )
+        .into_iter()
+        .map(|[x, y, _]| (x, y))
+        .collect()
+    }
+
+    /// Expand an arc in the given plane (G17/G18/G19) into XYZ points.
+    ///
+    /// The start point is not included; the last point is the arc end. A change in
+    /// the plane's linear axis (e.g. Z for G17) is interpolated across the sweep.
+    pub fn expand_arc_in_plane(
+        &self,
+        start: [f64; 3],
+        end: [f64; 3],
+        center: [f64; 3],
+        is_clockwise: bool,
+        plane: ArcPlane,
+    ) -> Vec<[f64; 3]> {
+        let arc = ArcMove {
+            start,
+            end,
+            center,
+            clockwise: is_clockwise,
+            plane,
+        };
+        self.expand(&arc)
+    }
+
+    /// Expand an R-word arc (`G2 X.. Y.. R..`) in the given plane into XYZ points.
+    ///
+    /// Fails when the radius cannot span the distance between start and end.
+    pub fn expand_radius_arc(
+        &self,
+        start: [f64; 3],
+        end: [f64; 3],
+        radius: f64,
+        is_clockwise: bool,
+        plane: ArcPlane,
+    ) -> Result<Vec<[f64; 3]>, GcodeError> {
+        let arc = ArcMove::from_radius(start, end, radius, is_clockwise, plane)?;
+        Ok(self.expand(&arc))
+    }
+
+    /// Split an arc over the configured length or radius limit into shorter arcs
+    ///
+    /// Arcs within the limits come back unchanged as a single piece.
+    pub fn split_arc(&self, arc: &ArcMove) -> Vec<ArcMove> {
+        arc.split(self.split_count(arc, 1.0))
+    }
+
+    /// Split the program's over-long and large-radius arcs into shorter arcs
+    ///
+    /// Fails if a configured limit is not a positive number.
+    pub fn split_arcs(&self, program: &str) -> CamToolResult<ArcSplitReport> {
+        for (name, limit) in [
+            ("arc length", self.config.max_arc_length),
+            ("arc radius", self.config.max_arc_radius),
+        ] {
+            if let Some(limit) = limit {
+                if !limit.is_finite() || limit <= 0.0 {
+                    return Err(CamToolError::InvalidParameters(format!(
+                        "maximum {} {} must be positive",
+                        name, limit
+                    )));
+                }
+            }
         }
 
-        // Generate segments
-        for i in 1..=self.config.num_segments {
-            let fraction = i as f64 / self.config.num_segments as f64;
-            let angle = start_angle + angle_delta * fraction;
-            let x = center_x + radius * angle.cos();
-            let y = center_y + radius * angle.sin();
-            segments.push((x, y));
+        let mut report = ArcSplitReport {
+            program: String::new(),
+            split: 0,
+            added: 0,
+            unresolved: 0,
+        };
+        let mut output = Vec::new();
+        let mut absolute = true;
+        let mut absolute_centers = false;
+        let mut plane = ArcPlane::XY;
+        let mut scale = 1.0;
+        let mut motion: Option<f64> = None;
+        let mut position: [Option<f64>; 3] = [None; 3];
+
+        for line in program.lines() {
+            let mut words = GCodeValidator::word_texts(line);
+            let mut other_frame = false;
+            let mut motion_word = None;
+            for (index, (letter, number)) in words.iter().enumerate() {
+                if *letter != 'G' {
+                    continue;
+                }
+                let Ok(code) = number.parse::<f64>() else {
+                    continue;
+                };
+                match code {
+                    0.0 | 1.0 | 2.0 | 3.0 => {
+                        motion = Some(code);
+                        motion_word = Some(index);
+                    }
+                    80.0 => motion = None,
+                    81.0..=89.0 if code.fract() == 0.0 => motion = Some(code),
+                    17.0 | 18.0 | 19.0 => {
+                        plane = ArcPlane::from_gcode(code as u8).unwrap_or(plane);
+                    }
+                    90.0 => absolute = true,
+                    91.0 => absolute = false,
+                    90.1 => absolute_centers = true,
+                    91.1 => absolute_centers = false,
+                    20.0 => scale = 1.0 / 25.4,
+                    21.0 => scale = 1.0,
+                    10.0 | 28.0 | 30.0 | 53.0 | 92.0 => other_frame = true,
+                    38.0..=39.0 => other_frame = true,
+                    _ => {}
+                }
+            }
+            let value = |letter: char| {
+                words
+                    .iter()
+                    .find(|(l, _)| *l == letter)
+                    .and_then(|(_, number)| number.parse::<f64>().ok())
+            };
+            let axis_values = [value('X'), value('Y'), value('Z')];
+            let moves = axis_values.iter().any(Option::is_some);
+
+            if other_frame {
+                position = [None; 3];
+                output.push(line.to_string());
+                continue;
+            }
+
+            let mut target = position;
+            for axis in 0..3 {
+                if let Some(v) = axis_values[axis] {
+                    target[axis] = if absolute {
+                        Some(v)
+                    } else {
+                        position[axis].map(|p| p + v)
+                    };
+                }
+            }
+
+            let offsets = [value('I'), value('J'), value('K')];
+            let radius = value('R');
+            let is_arc = matches!(motion, Some(m) if m == 2.0 || m == 3.0)
+                && (moves || offsets.iter().any(Option::is_some));
+            if !is_arc {
+                output.push(line.to_string());
+                match motion {
+                    Some(m) if m <= 3.0 => position = target,
+                    Some(_) if moves => position = [None; 3],
+                    _ => {}
+                }
+                continue;
+            }
+
+            let known = |p: [Option<f64>; 3]| -> Option<[f64; 3]> { Some([p[0]?, p[1]?, p[2]?]) };
+            let clockwise = motion == Some(2.0);
+            let arc = match (known(position), known(target)) {
+                (Some(start), Some(end)) if !absolute_centers => {
+                    if offsets.iter().any(Option::is_some) {
+                        Some(ArcMove::from_offsets(
+                            start,
+                            end,
+                            offsets.map(|o| o.unwrap_or(0.0)),
+                            clockwise,
+                            plane,
+                        ))
+                    } else {
+                        radius.and_then(|r| {
+                            ArcMove::from_radius(start, end, r, clockwise, plane).ok()
+                        })
+                    }
+                }
+                _ => None,
+            };
+            position = target;
+            let Some(arc) = arc else {
+                report.unresolved += 1;
+                output.push(line.to_string());
+                continue;
+            };
+
+            let pieces = arc.split(self.split_count(&arc, scale));
+            if pieces.len() < 2 {
+                output.push(line.to_string());
+                continue;
+            }
+            report.split += 1;
+            report.added += pieces.len() - 1;
+
+            let code = if clockwise { "2" } else { "3" };
+            words.retain(|(letter, _)| !matches!(letter, 'X' | 'Y' | 'Z' | 'I' | 'J' | 'K' | 'R'));
+            if motion_word.is_none() {
+                let at = usize::from(words.first().is_some_and(|(l, _)| *l == 'N'));
+                words.insert(at, ('G', code.to_string()));
+            }
+            let at = words
+                .iter()
+                .position(|(letter, _)| !matches!(letter, 'N' | 'G' | 'M'))
+                .unwrap_or(words.len());
+            let first_words = piece_words(&pieces[0], absolute);
+            words.splice(at..at, first_words);
+            output.push(GCodeValidator::rebuild_line(line, &words));
+            for piece in &pieces[1..] {
+                let mut words = vec![('G', code.to_string())];
+                words.extend(piece_words(piece, absolute));
+                output.push(GCodeValidator::rebuild_line("", &words));
+            }
         }
 
-        segments
+        let mut text = output.join("\n");
+        if program.ends_with('\n') {
+            text.push('\n');
+        }
+        report.program = text;
+        Ok(report)
+    }
+
+    /// Pieces an arc is split into; limits are in mm and `scale` converts them to
+    /// program units
+    fn split_count(&self, arc: &ArcMove, scale: f64) -> usize {
+        let length = arc.length();
+        if !length.is_finite() {
+            return 1;
+        }
+        let mut pieces = 1;
+        if let Some(max_length) = self.config.max_arc_length {
+            pieces = pieces.max(arc.segments_for(max_length * scale));
+        }
+        if let Some(max_radius) = self.config.max_arc_radius {
+            if arc.radius() > max_radius * scale {
+                pieces = pieces.max(arc.segments_for(max_radius * scale));
+            }
+        }
+        pieces
+    }
+
+    fn expand(&self, arc: &ArcMove) -> Vec<[f64; 3]> {
+        let segments = arc
+            .segments_for(self.config.segment_length)
+            .max(self.config.num_segments);
+        arc.expand(segments)
+    }
+}
+
+/// Result of [`ArcExpander::split_arcs`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArcSplitReport {
+    /// The rewritten program
+    pub program: String,
+    /// Arcs that were split
+    pub split: usize,
+    /// Lines added by splitting
+    pub added: usize,
+    /// Arcs left alone because their start or center could not be resolved
+    pub unresolved: usize,
+}
+
+impl ArcSplitReport {
+    /// One-line summary for display
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} arc(s) split into {} more arc(s)",
+            self.split, self.added
+        );
+        if self.unresolved > 0 {
+            summary.push_str(&format!(
+                "; {} arc(s) left unchanged (unknown position)",
+                self.unresolved
+            ));
+        }
+        summary
+    }
+}
+
+/// End point and center offset words of one split piece
+fn piece_words(piece: &ArcMove, absolute: bool) -> Vec<(char, String)> {
+    let (a, b, l) = piece.plane.axes();
+    let letters = ['X', 'Y', 'Z'];
+    let coord = |axis: usize| {
+        let value = if absolute {
+            piece.end[axis]
+        } else {
+            piece.end[axis] - piece.start[axis]
+        };
+        (letters[axis], format_split(value))
+    };
+    let mut words = vec![coord(a), coord(b)];
+    if piece.linear_travel() != 0.0 {
+        words.push(coord(l));
+    }
+    let (first, second) = piece.plane.offset_words();
+    words.push((first, format_split(piece.center[a] - piece.start[a])));
+    words.push((second, format_split(piece.center[b] - piece.start[b])));
+    words
+}
+
+fn format_split(value: f64) -> String {
+    let text = format!("{:.*}", SPLIT_DECIMALS, value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
     }
 }
 
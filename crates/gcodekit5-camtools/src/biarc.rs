@@ -0,0 +1,668 @@
+//! # Biarc Fitting
+//!
+//! Approximates a dense run of points, such as a traced contour or a
+//! height-map slice, with a chain of arcs. Each span is bridged by a biarc:
+//! two arcs meeting tangentially, each tangent to the path where it starts or
+//! ends, so consecutive arcs join without a corner and the machine moves
+//! smoothly through them instead of stepping through thousands of short G1s.
+//!
+//! Tangents are taken from the circle through each point and its neighbours,
+//! so points sampled from a circle fit back to that circle. Spans are grown as
+//! far as the fit stays within the tolerance of the original polyline, checked
+//! at the points and between them. Runs of points within the tolerance of a
+//! straight line become a line, as do pieces that would need an arc flatter
+//! than [`BiarcOptions::max_radius`]. Sharp corners are kept as corners. Every
+//! input point that starts or ends a span is an endpoint of the output, and the
+//! first and last points always are.
+//!
+//! [`fit_biarcs_in_program`] applies the fit to runs of G1 moves in the XY
+//! plane of an existing program.
+
+use crate::error::{CamToolError, CamToolResult};
+use crate::validator::GCodeValidator;
+use gcodekit5_core::{ArcMove, ArcPlane};
+use gcodekit5_designer::geometry;
+use gcodekit5_designer::model::Point as DesignerPoint;
+
+/// A turn sharper than this between segments is a corner, not a curve (degrees)
+const CORNER_ANGLE_DEG: f64 = 45.0;
+/// Points closer together than this are the same point
+const POINT_EPSILON: f64 = 1e-9;
+/// Decimal places of fitted coordinates
+const FIT_DECIMALS: usize = 6;
+/// Shortest run of G1 moves worth fitting
+const MIN_RUN_MOVES: usize = 3;
+
+/// Biarc fitting configuration
+#[derive(Debug, Clone)]
+pub struct BiarcOptions {
+    /// Largest distance the fitted path may stray from the points' polyline
+    pub tolerance: f64,
+    /// Arcs with a larger radius are written as lines
+    pub max_radius: f64,
+}
+
+impl Default for BiarcOptions {
+    fn default() -> Self {
+        Self {
+            tolerance: 0.01,
+            max_radius: 1000.0,
+        }
+    }
+}
+
+impl BiarcOptions {
+    fn check(&self) -> CamToolResult<()> {
+        if !self.tolerance.is_finite() || self.tolerance <= 0.0 {
+            return Err(CamToolError::InvalidParameters(format!(
+                "biarc tolerance {} must be positive",
+                self.tolerance
+            )));
+        }
+        if self.max_radius.is_nan() || self.max_radius <= 0.0 {
+            return Err(CamToolError::InvalidParameters(format!(
+                "biarc maximum radius {} must be positive",
+                self.max_radius
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// One piece of a fitted path
+#[derive(Debug, Clone, PartialEq)]
+pub enum FittedSegment {
+    /// Straight move to `end`
+    Line { end: [f64; 2] },
+    /// XY-plane arc
+    Arc(ArcMove),
+}
+
+impl FittedSegment {
+    /// Where the segment ends
+    pub fn end(&self) -> [f64; 2] {
+        match self {
+            Self::Line { end } => *end,
+            Self::Arc(arc) => [arc.end[0], arc.end[1]],
+        }
+    }
+}
+
+type Point = [f64; 2];
+
+fn sub(a: Point, b: Point) -> Point {
+    [a[0] - b[0], a[1] - b[1]]
+}
+
+fn add(a: Point, b: Point) -> Point {
+    [a[0] + b[0], a[1] + b[1]]
+}
+
+fn scale(a: Point, s: f64) -> Point {
+    [a[0] * s, a[1] * s]
+}
+
+fn dot(a: Point, b: Point) -> f64 {
+    a[0] * b[0] + a[1] * b[1]
+}
+
+fn cross(a: Point, b: Point) -> f64 {
+    a[0] * b[1] - a[1] * b[0]
+}
+
+fn length(a: Point) -> f64 {
+    a[0].hypot(a[1])
+}
+
+fn normalize(a: Point) -> Point {
+    let l = length(a);
+    if l < POINT_EPSILON {
+        [1.0, 0.0]
+    } else {
+        scale(a, 1.0 / l)
+    }
+}
+
+/// Left-hand normal
+fn perpendicular(a: Point) -> Point {
+    [-a[1], a[0]]
+}
+
+fn distance_to_segment(p: Point, a: Point, b: Point) -> f64 {
+    let point = |[x, y]: Point| DesignerPoint::new(x, y);
+    geometry::distance_to_segment(point(p), point(a), point(b))
+}
+
+fn distance_to_polyline(p: Point, points: &[Point]) -> f64 {
+    points
+        .windows(2)
+        .map(|w| distance_to_segment(p, w[0], w[1]))
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// Circle center through three points, if they are not in a line
+fn circumcenter(a: Point, b: Point, c: Point) -> Option<Point> {
+    let (ab, ac) = (sub(b, a), sub(c, a));
+    let d = 2.0 * cross(ab, ac);
+    if d.abs() < POINT_EPSILON * (length(ab) * length(ac)).max(POINT_EPSILON) {
+        return None;
+    }
+    let (ab2, ac2) = (dot(ab, ab), dot(ac, ac));
+    Some(add(
+        a,
+        [
+            (ac[1] * ab2 - ab[1] * ac2) / d,
+            (ab[0] * ac2 - ac[0] * ab2) / d,
+        ],
+    ))
+}
+
+/// Unit tangent at `at`, on the circle through `a`, `b` and `c`, pointing along
+/// `direction`
+fn circle_tangent(a: Point, b: Point, c: Point, at: Point, direction: Point) -> Point {
+    let tangent = match circumcenter(a, b, c) {
+        Some(center) => normalize(perpendicular(sub(at, center))),
+        None => normalize(direction),
+    };
+    if dot(tangent, direction) < 0.0 {
+        scale(tangent, -1.0)
+    } else {
+        tangent
+    }
+}
+
+/// Tangents at each point of a corner-free run
+fn tangents(points: &[Point]) -> Vec<Point> {
+    let n = points.len();
+    if n < 3 {
+        let t = normalize(sub(points[n - 1], points[0]));
+        return vec![t; n];
+    }
+    (0..n)
+        .map(|i| {
+            let (a, b, c) = match i {
+                0 => (0, 1, 2),
+                i if i == n - 1 => (n - 3, n - 2, n - 1),
+                i => (i - 1, i, i + 1),
+            };
+            let direction = match i {
+                0 => sub(points[1], points[0]),
+                i if i == n - 1 => sub(points[n - 1], points[n - 2]),
+                i => sub(points[i + 1], points[i - 1]),
+            };
+            circle_tangent(points[a], points[b], points[c], points[i], direction)
+        })
+        .collect()
+}
+
+/// Arc from `start` with tangent `tangent` through `end`; `None` when it is a line
+fn tangent_arc(start: Point, tangent: Point, end: Point, z: f64) -> Option<ArcMove> {
+    let normal = perpendicular(tangent);
+    let chord = sub(end, start);
+    let offset = dot(normal, chord);
+    if offset.abs() < POINT_EPSILON {
+        return None;
+    }
+    let s = dot(chord, chord) / (2.0 * offset);
+    let center = add(start, scale(normal, s));
+    Some(ArcMove {
+        start: [start[0], start[1], z],
+        end: [end[0], end[1], z],
+        center: [center[0], center[1], z],
+        clockwise: s < 0.0,
+        plane: ArcPlane::XY,
+    })
+}
+
+/// Arc ending at `end` with tangent `tangent` there, from `start`
+fn arc_into(start: Point, end: Point, tangent: Point, z: f64) -> Option<ArcMove> {
+    // The same circle, travelled backwards from the end
+    let reversed = tangent_arc(end, scale(tangent, -1.0), start, z)?;
+    Some(ArcMove {
+        start: reversed.end,
+        end: reversed.start,
+        center: reversed.center,
+        clockwise: !reversed.clockwise,
+        plane: ArcPlane::XY,
+    })
+}
+
+/// The biarc from `p1` with tangent `t1` to `p2` with tangent `t2`, with equal
+/// tangent lengths, as segments
+fn biarc(p1: Point, t1: Point, p2: Point, t2: Point, z: f64) -> Vec<FittedSegment> {
+    let v = sub(p2, p1);
+    let t = add(t1, t2);
+    let denominator = 2.0 * (1.0 - dot(t1, t2));
+    let joint = if denominator.abs() < 1e-12 {
+        let v_t2 = dot(v, t2);
+        if v_t2.abs() < 1e-12 {
+            // Parallel tangents across the chord: two half circles
+            add(p1, scale(v, 0.5))
+        } else {
+            let d = dot(v, v) / (4.0 * v_t2);
+            scale(add(add(p1, scale(t1, d)), sub(p2, scale(t2, d))), 0.5)
+        }
+    } else {
+        let v_t = dot(v, t);
+        let d = (-v_t + (v_t * v_t + denominator * dot(v, v)).sqrt()) / denominator;
+        scale(add(add(p1, scale(t1, d)), sub(p2, scale(t2, d))), 0.5)
+    };
+
+    let mut segments = Vec::new();
+    for (start, end, arc) in [
+        (p1, joint, tangent_arc(p1, t1, joint, z)),
+        (joint, p2, arc_into(joint, p2, t2, z)),
+    ] {
+        if length(sub(end, start)) < POINT_EPSILON {
+            continue;
+        }
+        segments.push(match arc {
+            Some(arc) => FittedSegment::Arc(arc),
+            None => FittedSegment::Line { end },
+        });
+    }
+    segments
+}
+
+/// Distance from `p` to an XY arc
+fn distance_to_arc(p: Point, arc: &ArcMove) -> f64 {
+    let center = [arc.center[0], arc.center[1]];
+    let (start, end) = ([arc.start[0], arc.start[1]], [arc.end[0], arc.end[1]]);
+    let radius = arc.radius();
+    let sweep = arc.sweep();
+    let start_angle = (start[1] - center[1]).atan2(start[0] - center[0]);
+    let angle = (p[1] - center[1]).atan2(p[0] - center[0]);
+    let mut along = angle - start_angle;
+    if sweep < 0.0 {
+        along = -along;
+    }
+    along = along.rem_euclid(std::f64::consts::TAU);
+    if along <= sweep.abs() {
+        (length(sub(p, center)) - radius).abs()
+    } else {
+        length(sub(p, start)).min(length(sub(p, end)))
+    }
+}
+
+/// Largest distance between `segments` (from `start`) and the polyline `points`
+fn deviation(start: Point, segments: &[FittedSegment], points: &[Point]) -> f64 {
+    let mut from = start;
+    let mut worst: f64 = 0.0;
+    let distance_to_fit = |p: Point| {
+        let mut from = start;
+        let mut best = f64::INFINITY;
+        for segment in segments {
+            let d = match segment {
+                FittedSegment::Line { end } => distance_to_segment(p, from, *end),
+                FittedSegment::Arc(arc) => distance_to_arc(p, arc),
+            };
+            best = best.min(d);
+            from = segment.end();
+        }
+        best
+    };
+    for &p in &points[1..points.len() - 1] {
+        worst = worst.max(distance_to_fit(p));
+    }
+    // Between the points, the fit must stay near the polyline too
+    for segment in segments {
+        match segment {
+            FittedSegment::Line { end } => {
+                worst = worst.max(distance_to_polyline(scale(add(from, *end), 0.5), points));
+            }
+            FittedSegment::Arc(arc) => {
+                for sample in arc.expand(8) {
+                    worst = worst.max(distance_to_polyline([sample[0], sample[1]], points));
+                }
+            }
+        }
+        from = segment.end();
+    }
+    worst
+}
+
+/// Segments covering `points[i..=j]` within tolerance, if there are any
+fn fit_span(
+    points: &[Point],
+    tangents: &[Point],
+    i: usize,
+    j: usize,
+    z: f64,
+    options: &BiarcOptions,
+) -> Option<Vec<FittedSegment>> {
+    let span = &points[i..=j];
+    let line = vec![FittedSegment::Line { end: points[j] }];
+    if deviation(points[i], &line, span) <= options.tolerance {
+        return Some(line);
+    }
+    let segments = biarc(points[i], tangents[i], points[j], tangents[j], z);
+    let flat = segments.iter().any(|segment| match segment {
+        FittedSegment::Arc(arc) => arc.radius() > options.max_radius,
+        FittedSegment::Line { .. } => false,
+    });
+    (!flat && deviation(points[i], &segments, span) <= options.tolerance).then_some(segments)
+}
+
+/// Fit a corner-free run, greedily taking the longest span that fits each time
+fn fit_run(points: &[Point], z: f64, options: &BiarcOptions, out: &mut Vec<FittedSegment>) {
+    let tangents = tangents(points);
+    let last = points.len() - 1;
+    let mut i = 0;
+    while i < last {
+        let (mut lo, mut hi) = (i + 1, last);
+        let mut best = fit_span(points, &tangents, i, lo, z, options)
+            .unwrap_or_else(|| vec![FittedSegment::Line { end: points[lo] }]);
+        if let Some(segments) = fit_span(points, &tangents, i, hi, z, options) {
+            best = segments;
+            lo = hi;
+        } else {
+            while hi - lo > 1 {
+                let mid = (lo + hi) / 2;
+                match fit_span(points, &tangents, i, mid, z, options) {
+                    Some(segments) => {
+                        best = segments;
+                        lo = mid;
+                    }
+                    None => hi = mid,
+                }
+            }
+        }
+        out.extend(best);
+        i = lo;
+    }
+}
+
+fn fit_points(points: &[Point], z: f64, options: &BiarcOptions) -> Vec<FittedSegment> {
+    let mut points = points.to_vec();
+    points.dedup_by(|b, a| length(sub(*a, *b)) < POINT_EPSILON);
+    let mut out = Vec::new();
+    if points.len() < 2 {
+        return out;
+    }
+    let corner_cos = CORNER_ANGLE_DEG.to_radians().cos();
+    let mut run_start = 0;
+    for k in 1..points.len() {
+        let corner = k + 1 < points.len()
+            && dot(
+                normalize(sub(points[k], points[k - 1])),
+                normalize(sub(points[k + 1], points[k])),
+            ) < corner_cos;
+        if corner || k == points.len() - 1 {
+            fit_run(&points[run_start..=k], z, options, &mut out);
+            run_start = k;
+        }
+    }
+    out
+}
+
+/// Fit tangent-continuous arcs to `points`, starting at the first point
+///
+/// Fails if the tolerance or maximum radius is not a positive number.
+pub fn fit_biarcs(
+    points: &[[f64; 2]],
+    options: &BiarcOptions,
+) -> CamToolResult<Vec<FittedSegment>> {
+    options.check()?;
+    Ok(fit_points(points, 0.0, options))
+}
+
+/// G1/G2/G3 lines for `segments` from `start`, in absolute coordinates with
+/// I/J offsets from the start of each arc; `feed` goes on the first line
+pub fn segments_to_gcode(
+    start: [f64; 2],
+    segments: &[FittedSegment],
+    feed: Option<f64>,
+) -> Vec<String> {
+    let mut from = start;
+    let mut lines = Vec::with_capacity(segments.len());
+    for segment in segments {
+        let mut words = match segment {
+            FittedSegment::Line { end } => vec![
+                ('G', "1".to_string()),
+                ('X', format_fit(end[0])),
+                ('Y', format_fit(end[1])),
+            ],
+            FittedSegment::Arc(arc) => vec![
+                ('G', if arc.clockwise { "2" } else { "3" }.to_string()),
+                ('X', format_fit(arc.end[0])),
+                ('Y', format_fit(arc.end[1])),
+                ('I', format_fit(arc.center[0] - from[0])),
+                ('J', format_fit(arc.center[1] - from[1])),
+            ],
+        };
+        if lines.is_empty() {
+            if let Some(feed) = feed {
+                words.push(('F', format_fit(feed)));
+            }
+        }
+        lines.push(GCodeValidator::rebuild_line("", &words));
+        from = segment.end();
+    }
+    lines
+}
+
+/// Result of [`fit_biarcs_in_program`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BiarcFitReport {
+    /// The rewritten program
+    pub program: String,
+    /// Runs of G1 moves replaced by fitted segments
+    pub runs: usize,
+    /// G1 moves in those runs
+    pub moves_replaced: usize,
+    /// Lines written in their place
+    pub segments_written: usize,
+}
+
+impl BiarcFitReport {
+    /// One-line summary for display
+    pub fn summary(&self) -> String {
+        format!(
+            "{} G1 move(s) in {} run(s) replaced by {} arc and line segment(s)",
+            self.moves_replaced, self.runs, self.segments_written
+        )
+    }
+}
+
+/// A run of G1 XY moves being collected
+struct Run {
+    lines: Vec<String>,
+    start: Point,
+    points: Vec<Point>,
+    z: f64,
+    feed: Option<f64>,
+}
+
+/// Replace runs of G1 moves in the XY plane with fitted arcs and lines
+///
+/// A run is consecutive G1 lines that only move X and Y, from a known
+/// position, in absolute (G90) XY-plane (G17) programs with relative arc
+/// centers, and carry no other words or comments. A feed rate may only be set
+/// on the first move of a run. Runs are
+/// only replaced when fitting makes them shorter. Tolerance and radius are in
+/// mm and follow G20/G21.
+pub fn fit_biarcs_in_program(
+    program: &str,
+    options: &BiarcOptions,
+) -> CamToolResult<BiarcFitReport> {
+    options.check()?;
+    let mut report = BiarcFitReport {
+        program: String::new(),
+        runs: 0,
+        moves_replaced: 0,
+        segments_written: 0,
+    };
+    let mut output = Vec::new();
+    let mut absolute = true;
+    let mut absolute_centers = false;
+    let mut xy_plane = true;
+    let mut units = 1.0;
+    let mut motion: Option<f64> = None;
+    let mut position: [Option<f64>; 3] = [None; 3];
+    let mut run: Option<Run> = None;
+    // A fitted run may end in an arc, leaving G2/G3 modal
+    let mut restore_g1 = false;
+
+    let flush = |run: Option<Run>,
+                 units: f64,
+                 output: &mut Vec<String>,
+                 report: &mut BiarcFitReport,
+                 restore_g1: &mut bool| {
+        let Some(run) = run else {
+            return;
+        };
+        if run.points.len() >= MIN_RUN_MOVES {
+            let scaled = BiarcOptions {
+                tolerance: options.tolerance * units,
+                max_radius: options.max_radius * units,
+            };
+            let mut points = vec![run.start];
+            points.extend(&run.points);
+            let segments = fit_points(&points, run.z, &scaled);
+            if segments.len() < run.points.len() {
+                report.runs += 1;
+                report.moves_replaced += run.points.len();
+                report.segments_written += segments.len();
+                *restore_g1 = !matches!(segments.last(), Some(FittedSegment::Line { .. }));
+                output.extend(segments_to_gcode(run.start, &segments, run.feed));
+                return;
+            }
+        }
+        if *restore_g1 {
+            output.push("G1".to_string());
+            *restore_g1 = false;
+        }
+        output.extend(run.lines);
+    };
+
+    for line in program.lines() {
+        let words = GCodeValidator::word_texts(line);
+        let mut motion_word = None;
+        let mut other_frame = false;
+        for (letter, number) in &words {
+            if *letter != 'G' {
+                continue;
+            }
+            let Ok(code) = number.parse::<f64>() else {
+                continue;
+            };
+            match code {
+                0.0 | 1.0 | 2.0 | 3.0 => motion_word = Some(code),
+                80.0 => motion_word = Some(-1.0),
+                81.0..=89.0 if code.fract() == 0.0 => motion_word = Some(code),
+                17.0 => xy_plane = true,
+                18.0 | 19.0 => xy_plane = false,
+                90.0 => absolute = true,
+                91.0 => absolute = false,
+                90.1 => absolute_centers = true,
+                91.1 => absolute_centers = false,
+                20.0 => units = 1.0 / 25.4,
+                21.0 => units = 1.0,
+                10.0 | 28.0 | 30.0 | 53.0 | 92.0 | 38.0..=39.0 => other_frame = true,
+                _ => {}
+            }
+        }
+        let value = |letter: char| {
+            words
+                .iter()
+                .find(|(l, _)| *l == letter)
+                .and_then(|(_, number)| number.parse::<f64>().ok())
+        };
+        motion = match motion_word {
+            Some(code) if code < 0.0 => None,
+            Some(code) => Some(code),
+            None => motion,
+        };
+        let axis_values = [value('X'), value('Y'), value('Z')];
+        let moves = axis_values.iter().any(Option::is_some);
+        let has_comment = line.contains('(') || line.contains(';');
+        // Anything else on the line would be lost in the fit
+        let only_xy_words = words.iter().all(|(letter, number)| match letter {
+            'G' => number.parse::<f64>() == Ok(1.0),
+            'X' | 'Y' | 'F' => true,
+            _ => false,
+        });
+        let feed = value('F');
+
+        let linear_xy = motion == Some(1.0)
+            && !other_frame
+            && absolute
+            && !absolute_centers
+            && xy_plane
+            && moves
+            && axis_values[2].is_none()
+            && only_xy_words
+            && !has_comment
+            && position.iter().all(Option::is_some);
+        if linear_xy {
+            let [x, y, z] = position.map(|p| p.unwrap_or(0.0));
+            let target = [axis_values[0].unwrap_or(x), axis_values[1].unwrap_or(y)];
+            let continues = run.as_ref().is_some_and(|r| feed.is_none() && r.z == z);
+            if !continues {
+                flush(run.take(), units, &mut output, &mut report, &mut restore_g1);
+                run = Some(Run {
+                    lines: Vec::new(),
+                    start: [x, y],
+                    points: Vec::new(),
+                    z,
+                    feed,
+                });
+            }
+            if let Some(run) = run.as_mut() {
+                run.lines.push(line.to_string());
+                run.points.push(target);
+            }
+            position = [Some(target[0]), Some(target[1]), Some(z)];
+            continue;
+        }
+
+        flush(run.take(), units, &mut output, &mut report, &mut restore_g1);
+        if restore_g1 {
+            if motion_word.is_none() && motion == Some(1.0) && moves {
+                output.push("G1".to_string());
+            }
+            if motion_word.is_some() || moves {
+                restore_g1 = false;
+            }
+        }
+        output.push(line.to_string());
+
+        if other_frame {
+            position = [None; 3];
+            continue;
+        }
+        match motion {
+            Some(m) if m <= 3.0 => {
+                for axis in 0..3 {
+                    if let Some(v) = axis_values[axis] {
+                        position[axis] = if absolute {
+                            Some(v)
+                        } else {
+                            position[axis].map(|p| p + v)
+                        };
+                    }
+                }
+            }
+            Some(_) if moves => position = [None; 3],
+            _ => {}
+        }
+    }
+    flush(run.take(), units, &mut output, &mut report, &mut restore_g1);
+
+    let mut text = output.join("\n");
+    if program.ends_with('\n') {
+        text.push('\n');
+    }
+    report.program = text;
+    Ok(report)
+}
+
+fn format_fit(value: f64) -> String {
+    let text = format!("{:.*}", FIT_DECIMALS, value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
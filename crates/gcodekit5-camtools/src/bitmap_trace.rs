@@ -0,0 +1,655 @@
+//! Bitmap Tracing
+//!
+//! Converts a thresholded bitmap into vector paths so logos and line art can be
+//! cut with [`VectorEngraver`] instead of rastered.
+//!
+//! Two modes are supported:
+//! - **Outline**: closed contours following the pixel boundaries of every dark
+//!   region (Potrace-style path decomposition). Where two dark pixels touch only at
+//!   a corner, the [`TurnPolicy`] decides whether they are joined or kept apart.
+//! - **Centerline**: the dark regions are thinned to a one-pixel skeleton and the
+//!   skeleton is followed as open polylines, for single-stroke line art.
+//!
+//! Traced points are simplified with Douglas-Peucker and tiny speckles are dropped,
+//! so a clean image produces a handful of segments per shape rather than one per
+//! pixel step. Output coordinates are in millimetres with Y pointing up; each
+//! [`TracedContour`] maps directly to a designer path (`PathShape::from_points`).
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path as StdPath;
+
+use anyhow::{Context, Result};
+use image::DynamicImage;
+use lyon::math::point;
+use lyon::path::Path;
+
+use crate::annotation::AnnotationLevel;
+use crate::vector_engraver::{VectorEngraver, VectorEngravingParameters};
+
+/// What kind of vectors to produce
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TraceMode {
+    /// Closed outlines around dark regions
+    #[default]
+    Outline,
+    /// Open strokes along the middle of dark lines
+    Centerline,
+}
+
+/// How to resolve pixels that touch only diagonally
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TurnPolicy {
+    /// Always join diagonal dark pixels
+    Black,
+    /// Always keep diagonal dark pixels apart
+    White,
+    /// Join if dark pixels are the majority around the corner
+    #[default]
+    Majority,
+    /// Join if dark pixels are the minority around the corner
+    Minority,
+}
+
+/// Bitmap tracing parameters
+#[derive(Debug, Clone)]
+pub struct TraceParameters {
+    /// Pixels darker than this (0-255) are traced
+    pub threshold: u8,
+    /// Trace light pixels instead of dark ones
+    pub invert: bool,
+    /// Outline or centerline tracing
+    pub mode: TraceMode,
+    /// Diagonal connection policy (outline mode)
+    pub turn_policy: TurnPolicy,
+    /// Maximum deviation of the simplified path from the traced one, in pixels
+    pub simplify_tolerance: f32,
+    /// Outlines enclosing less than this many pixels, or strokes shorter than this
+    /// many pixels, are discarded as noise
+    pub min_area: f32,
+    /// Output size of one pixel in mm
+    pub pixel_size: f32,
+}
+
+impl Default for TraceParameters {
+    fn default() -> Self {
+        Self {
+            threshold: 128,
+            invert: false,
+            mode: TraceMode::Outline,
+            turn_policy: TurnPolicy::Majority,
+            simplify_tolerance: 1.0,
+            min_area: 4.0,
+            pixel_size: 0.1,
+        }
+    }
+}
+
+/// One traced path
+#[derive(Debug, Clone, PartialEq)]
+pub struct TracedContour {
+    /// Points in mm, Y up
+    pub points: Vec<(f32, f32)>,
+    /// Whether the path closes back on its first point
+    pub closed: bool,
+    /// Whether this outline is a hole inside another outline
+    pub is_hole: bool,
+}
+
+impl TracedContour {
+    /// Convert to a lyon path for the vector engraver
+    pub fn to_path(&self) -> Path {
+        let mut builder = Path::builder();
+        let mut points = self.points.iter();
+        if let Some(&(x, y)) = points.next() {
+            builder.begin(point(x, y));
+            for &(x, y) in points {
+                builder.line_to(point(x, y));
+            }
+            builder.end(self.closed);
+        }
+        builder.build()
+    }
+
+    /// Number of line segments in the path
+    pub fn segment_count(&self) -> usize {
+        match self.points.len() {
+            0 => 0,
+            n if self.closed => n,
+            n => n - 1,
+        }
+    }
+}
+
+/// Traces bitmaps into vector contours
+#[derive(Debug, Clone, Default)]
+pub struct BitmapTracer {
+    /// Tracing parameters
+    pub params: TraceParameters,
+}
+
+impl BitmapTracer {
+    /// Create a tracer
+    pub fn new(params: TraceParameters) -> Self {
+        Self { params }
+    }
+
+    /// Load an image and trace it
+    pub fn trace_file<P: AsRef<StdPath>>(&self, path: P) -> Result<Vec<TracedContour>> {
+        let img = image::open(path.as_ref())
+            .with_context(|| format!("Failed to load image {}", path.as_ref().display()))?;
+        Ok(self.trace_image(&img))
+    }
+
+    /// Trace an image
+    pub fn trace_image(&self, img: &DynamicImage) -> Vec<TracedContour> {
+        let bitmap = Bitmap::threshold(img, self.params.threshold, self.params.invert);
+        let contours = match self.params.mode {
+            TraceMode::Outline => self.trace_outlines(&bitmap),
+            TraceMode::Centerline => self.trace_centerlines(&bitmap),
+        };
+        tracing::debug!(
+            "Traced {}x{} bitmap into {} paths ({} segments)",
+            bitmap.width,
+            bitmap.height,
+            contours.len(),
+            contours.iter().map(|c| c.segment_count()).sum::<usize>()
+        );
+        contours
+    }
+
+    /// Trace an image into a vector engraver ready to generate G-code
+    pub fn to_engraver(
+        &self,
+        img: &DynamicImage,
+        params: VectorEngravingParameters,
+    ) -> VectorEngraver {
+        let paths = self
+            .trace_image(img)
+            .iter()
+            .map(TracedContour::to_path)
+            .collect();
+        VectorEngraver {
+            file_path: String::new(),
+            params,
+            paths,
+            // Traced points are already in mm
+            scale_factor: 1.0,
+            annotation: AnnotationLevel::Off,
+        }
+    }
+
+    fn trace_outlines(&self, bitmap: &Bitmap) -> Vec<TracedContour> {
+        let mut contours = Vec::new();
+        for ring in bitmap.boundaries(self.params.turn_policy) {
+            let area = signed_area(&ring);
+            if area.abs() < self.params.min_area {
+                continue;
+            }
+            let simplified = simplify_closed(&ring, self.params.simplify_tolerance);
+            if simplified.len() < 3 {
+                continue;
+            }
+            // Outer boundaries run clockwise on screen (positive area with Y down)
+            contours.push(self.to_contour(&simplified, true, area < 0.0, bitmap.height));
+        }
+        contours
+    }
+
+    fn trace_centerlines(&self, bitmap: &Bitmap) -> Vec<TracedContour> {
+        let mut skeleton = bitmap.clone();
+        skeleton.thin();
+
+        let mut contours = Vec::new();
+        for (chain, closed) in skeleton.chains() {
+            // Pixel centres, so a stroke is centred within its line
+            let chain: Vec<(f32, f32)> = chain
+                .iter()
+                .map(|&(x, y)| (x as f32 + 0.5, y as f32 + 0.5))
+                .collect();
+            if polyline_length(&chain, closed) < self.params.min_area {
+                continue;
+            }
+            let simplified = if closed {
+                simplify_closed(&chain, self.params.simplify_tolerance)
+            } else {
+                simplify_open(&chain, self.params.simplify_tolerance)
+            };
+            let closed = closed && simplified.len() >= 3;
+            if simplified.len() < 2 {
+                continue;
+            }
+            contours.push(self.to_contour(&simplified, closed, false, bitmap.height));
+        }
+        contours
+    }
+
+    fn to_contour(
+        &self,
+        points: &[(f32, f32)],
+        closed: bool,
+        is_hole: bool,
+        height: usize,
+    ) -> TracedContour {
+        let scale = self.params.pixel_size;
+        TracedContour {
+            points: points
+                .iter()
+                .map(|&(x, y)| (x * scale, (height as f32 - y) * scale))
+                .collect(),
+            closed,
+            is_hole,
+        }
+    }
+}
+
+/// Binary image, `true` for traced pixels; out-of-range pixels count as empty
+#[derive(Debug, Clone)]
+struct Bitmap {
+    width: usize,
+    height: usize,
+    pixels: Vec<bool>,
+}
+
+impl Bitmap {
+    /// Transparent pixels are always background, whatever their colour
+    fn threshold(img: &DynamicImage, threshold: u8, invert: bool) -> Self {
+        let img = img.to_luma_alpha8();
+        let (width, height) = (img.width() as usize, img.height() as usize);
+        let pixels = img
+            .pixels()
+            .map(|p| p.0[1] >= 128 && ((p.0[0] < threshold) != invert))
+            .collect();
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    fn get(&self, x: i64, y: i64) -> bool {
+        x >= 0
+            && y >= 0
+            && (x as usize) < self.width
+            && (y as usize) < self.height
+            && self.pixels[y as usize * self.width + x as usize]
+    }
+
+    fn set(&mut self, x: usize, y: usize, value: bool) {
+        self.pixels[y * self.width + x] = value;
+    }
+
+    /// Closed pixel-boundary rings, as corner coordinates with Y down.
+    ///
+    /// Edges are directed clockwise around each set pixel, so outer boundaries run
+    /// clockwise and holes counter-clockwise.
+    fn boundaries(&self, policy: TurnPolicy) -> Vec<Vec<(f32, f32)>> {
+        type Vertex = (i64, i64);
+        let mut edges: Vec<(Vertex, Vertex)> = Vec::new();
+        for y in 0..self.height as i64 {
+            for x in 0..self.width as i64 {
+                if !self.get(x, y) {
+                    continue;
+                }
+                if !self.get(x, y - 1) {
+                    edges.push(((x, y), (x + 1, y)));
+                }
+                if !self.get(x + 1, y) {
+                    edges.push(((x + 1, y), (x + 1, y + 1)));
+                }
+                if !self.get(x, y + 1) {
+                    edges.push(((x + 1, y + 1), (x, y + 1)));
+                }
+                if !self.get(x - 1, y) {
+                    edges.push(((x, y + 1), (x, y)));
+                }
+            }
+        }
+
+        let mut outgoing: HashMap<Vertex, Vec<usize>> = HashMap::new();
+        for (i, (from, _)) in edges.iter().enumerate() {
+            outgoing.entry(*from).or_default().push(i);
+        }
+
+        let mut used = vec![false; edges.len()];
+        let mut rings = Vec::new();
+        for first in 0..edges.len() {
+            if used[first] {
+                continue;
+            }
+            let mut ring = Vec::new();
+            let mut current = first;
+            loop {
+                used[current] = true;
+                let (from, to) = edges[current];
+                let direction = (to.0 - from.0, to.1 - from.1);
+                // The first edge stays a candidate so the ring can close through it
+                let next_candidates: Vec<usize> = outgoing
+                    .get(&to)
+                    .map(|list| {
+                        list.iter()
+                            .copied()
+                            .filter(|&e| !used[e] || e == first)
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let next = match next_candidates.as_slice() {
+                    [] => None,
+                    [only] => Some(*only),
+                    options => {
+                        // Ambiguous corner: a left turn joins the diagonal pixels
+                        let join = self.joins_at(to, policy);
+                        let wanted = if join {
+                            (direction.1, -direction.0)
+                        } else {
+                            (-direction.1, direction.0)
+                        };
+                        options
+                            .iter()
+                            .copied()
+                            .find(|&e| {
+                                let (a, b) = edges[e];
+                                (b.0 - a.0, b.1 - a.1) == wanted
+                            })
+                            .or(Some(options[0]))
+                    }
+                };
+                // Only record corners; straight runs collapse to their end points
+                let turns = next.is_none_or(|n| {
+                    let (a, b) = edges[n];
+                    (b.0 - a.0, b.1 - a.1) != direction
+                });
+                if turns {
+                    ring.push((to.0 as f32, to.1 as f32));
+                }
+                match next {
+                    Some(n) if n != first => current = n,
+                    _ => break,
+                }
+            }
+            if ring.len() >= 3 {
+                rings.push(ring);
+            }
+        }
+        rings
+    }
+
+    /// Whether diagonal pixels meeting at a corner should be joined
+    fn joins_at(&self, (x, y): (i64, i64), policy: TurnPolicy) -> bool {
+        let majority = || {
+            // 4x4 pixel window centred on the corner
+            let mut set = 0;
+            for dy in -2..2 {
+                for dx in -2..2 {
+                    if self.get(x + dx, y + dy) {
+                        set += 1;
+                    }
+                }
+            }
+            set > 8
+        };
+        match policy {
+            TurnPolicy::Black => true,
+            TurnPolicy::White => false,
+            TurnPolicy::Majority => majority(),
+            TurnPolicy::Minority => !majority(),
+        }
+    }
+
+    fn neighbours(&self, x: usize, y: usize) -> [bool; 8] {
+        // P2..P9 clockwise from north
+        let (x, y) = (x as i64, y as i64);
+        [
+            self.get(x, y - 1),
+            self.get(x + 1, y - 1),
+            self.get(x + 1, y),
+            self.get(x + 1, y + 1),
+            self.get(x, y + 1),
+            self.get(x - 1, y + 1),
+            self.get(x - 1, y),
+            self.get(x - 1, y - 1),
+        ]
+    }
+
+    /// Zhang-Suen thinning to a one-pixel skeleton
+    fn thin(&mut self) {
+        loop {
+            let mut changed = false;
+            for step in 0..2 {
+                let mut remove = Vec::new();
+                for y in 0..self.height {
+                    for x in 0..self.width {
+                        if !self.pixels[y * self.width + x] {
+                            continue;
+                        }
+                        let n = self.neighbours(x, y);
+                        let count = n.iter().filter(|&&v| v).count();
+                        let transitions = (0..8).filter(|&i| !n[i] && n[(i + 1) % 8]).count();
+                        let (p2, p4, p6, p8) = (n[0], n[2], n[4], n[6]);
+                        let keep = if step == 0 {
+                            p4 && p6 && (p2 || p8)
+                        } else {
+                            p2 && p8 && (p4 || p6)
+                        };
+                        if (2..=6).contains(&count) && transitions == 1 && !keep {
+                            remove.push((x, y));
+                        }
+                    }
+                }
+                changed |= !remove.is_empty();
+                for (x, y) in remove {
+                    self.set(x, y, false);
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+    }
+
+    /// Skeleton neighbours using m-adjacency, so a diagonal step that is also
+    /// reachable through a shared edge neighbour is not a separate branch
+    fn linked(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
+        let (xi, yi) = (x as i64, y as i64);
+        let mut linked = Vec::new();
+        for (dx, dy) in [
+            (0, -1),
+            (1, 0),
+            (0, 1),
+            (-1, 0),
+            (1, -1),
+            (1, 1),
+            (-1, 1),
+            (-1, -1),
+        ] {
+            let (nx, ny) = (xi + dx, yi + dy);
+            if !self.get(nx, ny) {
+                continue;
+            }
+            let diagonal = dx != 0 && dy != 0;
+            if diagonal && (self.get(xi + dx, yi) || self.get(xi, yi + dy)) {
+                continue;
+            }
+            linked.push((nx as usize, ny as usize));
+        }
+        linked
+    }
+
+    /// Follow skeleton links from `start` through `first` until a node (when
+    /// `stop_at_nodes`) or an already visited link is reached
+    fn walk(
+        &self,
+        visited: &mut HashSet<Link>,
+        start: (usize, usize),
+        first: (usize, usize),
+        stop_at_nodes: bool,
+    ) -> Vec<(usize, usize)> {
+        let mut chain = vec![start];
+        let (mut prev, mut current) = (start, first);
+        visited.insert(link_key(prev, current));
+        loop {
+            chain.push(current);
+            let links = self.linked(current.0, current.1);
+            if stop_at_nodes && links.len() != 2 {
+                break;
+            }
+            let Some(&next) = links
+                .iter()
+                .find(|&&n| n != prev && !visited.contains(&link_key(current, n)))
+            else {
+                break;
+            };
+            visited.insert(link_key(current, next));
+            prev = current;
+            current = next;
+        }
+        chain
+    }
+
+    /// Follow the skeleton into pixel chains, split at end points and junctions
+    fn chains(&self) -> Vec<(Vec<(usize, usize)>, bool)> {
+        let mut visited: HashSet<Link> = HashSet::new();
+        let mut chains = Vec::new();
+
+        let set_pixels: Vec<(usize, usize)> = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.pixels[y * self.width + x])
+            .collect();
+
+        // Open strokes start at end points and junctions
+        for &pixel in &set_pixels {
+            let links = self.linked(pixel.0, pixel.1);
+            if links.len() == 2 {
+                continue;
+            }
+            if links.is_empty() {
+                chains.push((vec![pixel], false));
+                continue;
+            }
+            for next in links {
+                if !visited.contains(&link_key(pixel, next)) {
+                    chains.push((self.walk(&mut visited, pixel, next, true), false));
+                }
+            }
+        }
+
+        // Whatever is left is made of loops
+        for &pixel in &set_pixels {
+            for next in self.linked(pixel.0, pixel.1) {
+                if !visited.contains(&link_key(pixel, next)) {
+                    let mut chain = self.walk(&mut visited, pixel, next, false);
+                    let closed = chain.len() > 2 && chain.last() == Some(&pixel);
+                    if closed {
+                        chain.pop();
+                    }
+                    chains.push((chain, closed));
+                }
+            }
+        }
+
+        chains
+    }
+}
+
+/// Skeleton link between two pixels, in a canonical order
+type Link = ((usize, usize), (usize, usize));
+
+fn link_key(a: (usize, usize), b: (usize, usize)) -> Link {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// Shoelace area (positive for clockwise on screen, i.e. with Y down)
+fn signed_area(points: &[(f32, f32)]) -> f32 {
+    let n = points.len();
+    (0..n)
+        .map(|i| {
+            let (x0, y0) = points[i];
+            let (x1, y1) = points[(i + 1) % n];
+            x0 * y1 - x1 * y0
+        })
+        .sum::<f32>()
+        / 2.0
+}
+
+fn polyline_length(points: &[(f32, f32)], closed: bool) -> f32 {
+    let mut length: f32 = points
+        .windows(2)
+        .map(|w| (w[1].0 - w[0].0).hypot(w[1].1 - w[0].1))
+        .sum();
+    if closed {
+        if let (Some(first), Some(last)) = (points.first(), points.last()) {
+            length += (first.0 - last.0).hypot(first.1 - last.1);
+        }
+    }
+    // A single pixel still has some extent
+    length.max(if points.is_empty() { 0.0 } else { 1.0 })
+}
+
+/// Douglas-Peucker simplification of an open polyline
+fn simplify_open(points: &[(f32, f32)], tolerance: f32) -> Vec<(f32, f32)> {
+    if points.len() < 3 || tolerance <= 0.0 {
+        return points.to_vec();
+    }
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    let mut stack = vec![(0, points.len() - 1)];
+    while let Some((start, end)) = stack.pop() {
+        let mut farthest = (0.0f32, start);
+        for i in start + 1..end {
+            let d = distance_to_segment(points[i], points[start], points[end]);
+            if d > farthest.0 {
+                farthest = (d, i);
+            }
+        }
+        if farthest.0 > tolerance {
+            keep[farthest.1] = true;
+            stack.push((start, farthest.1));
+            stack.push((farthest.1, end));
+        }
+    }
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(p, k)| k.then_some(*p))
+        .collect()
+}
+
+/// Douglas-Peucker simplification of a closed ring, split at its two most
+/// distant points so the result does not depend on where tracing started
+fn simplify_closed(points: &[(f32, f32)], tolerance: f32) -> Vec<(f32, f32)> {
+    if points.len() < 4 || tolerance <= 0.0 {
+        return points.to_vec();
+    }
+    let origin = points[0];
+    let far = (1..points.len())
+        .max_by(|&a, &b| {
+            let da = (points[a].0 - origin.0).hypot(points[a].1 - origin.1);
+            let db = (points[b].0 - origin.0).hypot(points[b].1 - origin.1);
+            da.total_cmp(&db)
+        })
+        .unwrap_or(points.len() / 2);
+
+    let first_half = simplify_open(&points[..=far], tolerance);
+    let mut second: Vec<(f32, f32)> = points[far..].to_vec();
+    second.push(origin);
+    let second_half = simplify_open(&second, tolerance);
+
+    let mut ring = first_half;
+    ring.pop();
+    ring.extend_from_slice(&second_half[..second_half.len() - 1]);
+    ring
+}
+
+fn distance_to_segment(p: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_sq = dx * dx + dy * dy;
+    if length_sq == 0.0 {
+        return (p.0 - a.0).hypot(p.1 - a.1);
+    }
+    let t = (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_sq).clamp(0.0, 1.0);
+    (p.0 - (a.0 + t * dx)).hypot(p.1 - (a.1 + t * dy))
+}
@@ -0,0 +1,248 @@
+//! # Clearance Plane Enforcement
+//!
+//! Normalizes the rapid heights of a program to one clearance plane. Rapid Z-only
+//! retracts (upward G0 moves ending above the stock top) are rewritten to the
+//! clearance height, rapid traverses above the stock have their Z set to it, and a
+//! retract is inserted before every XY rapid that starts below the plane.
+//!
+//! Moves at or below the stock top, feed moves and approach rapids (downward G0
+//! moves that stay above the stock) are never changed, so cutting depths are kept.
+//! Relative (G91) moves are rewritten as relative distances when the current Z is
+//! known; when it is not, they are left alone and counted as unresolved. Lines that
+//! use other coordinate frames (G10, G28, G30, G53, G92, G38.x) and canned cycles
+//! are not touched, and they make the current Z unknown.
+
+use crate::error::{CamToolError, CamToolResult};
+use crate::validator::GCodeValidator;
+use gcodekit5_core::format_gcode_number;
+
+/// Heights are compared with this tolerance (program units)
+const Z_EPSILON: f64 = 1e-4;
+/// Comment added to inserted retracts
+const RETRACT_COMMENT: &str = "; retract to clearance plane";
+
+/// Clearance plane settings, in mm and work coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClearanceOptions {
+    /// Height every rapid retract and traverse is moved to
+    pub clearance_z: f64,
+    /// Top of the stock; Z at or below it is a cutting depth and never changed
+    pub stock_top: f64,
+}
+
+impl Default for ClearanceOptions {
+    fn default() -> Self {
+        Self {
+            clearance_z: 5.0,
+            stock_top: 0.0,
+        }
+    }
+}
+
+/// Result of [`enforce_clearance`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClearanceReport {
+    /// The rewritten program
+    pub program: String,
+    /// Rapid moves whose Z was changed to the clearance height
+    pub rewritten: usize,
+    /// Retracts inserted before XY rapids
+    pub inserted: usize,
+    /// Relative Z rapids left alone because the current Z was unknown
+    pub unresolved: usize,
+}
+
+impl ClearanceReport {
+    /// Total number of adjusted moves
+    pub fn adjusted(&self) -> usize {
+        self.rewritten + self.inserted
+    }
+
+    /// One-line summary for display
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} rapid move(s) adjusted: {} rewritten, {} retract(s) inserted",
+            self.adjusted(),
+            self.rewritten,
+            self.inserted
+        );
+        if self.unresolved > 0 {
+            summary.push_str(&format!(
+                "; {} relative move(s) left unchanged (unknown Z)",
+                self.unresolved
+            ));
+        }
+        summary
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Motion {
+    Rapid,
+    Feed,
+    Cycle,
+}
+
+/// Rewrite a program's rapid heights to a single clearance plane
+///
+/// Fails if the clearance is not above the stock top.
+pub fn enforce_clearance(
+    program: &str,
+    options: &ClearanceOptions,
+) -> CamToolResult<ClearanceReport> {
+    if !options.clearance_z.is_finite()
+        || !options.stock_top.is_finite()
+        || options.clearance_z <= options.stock_top
+    {
+        return Err(CamToolError::InvalidParameters(format!(
+            "clearance height {} must be above the stock top {}",
+            options.clearance_z, options.stock_top
+        )));
+    }
+
+    let mut report = ClearanceReport {
+        program: String::new(),
+        rewritten: 0,
+        inserted: 0,
+        unresolved: 0,
+    };
+    let mut output = Vec::new();
+    let mut absolute = true;
+    let mut scale = 1.0;
+    let mut motion = None;
+    let mut current_z: Option<f64> = None;
+
+    for line in program.lines() {
+        let mut words = GCodeValidator::word_texts(line);
+        let mut other_frame = false;
+        for (letter, number) in &words {
+            if *letter != 'G' {
+                continue;
+            }
+            let Ok(code) = number.parse::<f64>() else {
+                continue;
+            };
+            match code {
+                0.0 => motion = Some(Motion::Rapid),
+                1.0 | 2.0 | 3.0 => motion = Some(Motion::Feed),
+                80.0 => motion = None,
+                81.0..=89.0 if code.fract() == 0.0 => motion = Some(Motion::Cycle),
+                90.0 => absolute = true,
+                91.0 => absolute = false,
+                20.0 => scale = 1.0 / 25.4,
+                21.0 => scale = 1.0,
+                10.0 | 28.0 | 30.0 | 53.0 | 92.0 => other_frame = true,
+                38.0..=39.0 => other_frame = true,
+                _ => {}
+            }
+        }
+        let clearance = options.clearance_z * scale;
+        let stock_top = options.stock_top * scale;
+
+        let z_index = words.iter().position(|(letter, _)| *letter == 'Z');
+        let z_value = z_index.and_then(|i| words[i].1.parse::<f64>().ok());
+        let has_xy = words
+            .iter()
+            .any(|(letter, _)| *letter == 'X' || *letter == 'Y');
+
+        if other_frame {
+            current_z = None;
+            output.push(line.to_string());
+            continue;
+        }
+
+        // Target Z of this line, in absolute program units
+        let target_z = z_value.and_then(|z| {
+            if absolute {
+                Some(z)
+            } else {
+                current_z.map(|c| c + z)
+            }
+        });
+
+        match motion {
+            Some(Motion::Rapid) if has_xy || z_value.is_some() => {
+                if has_xy && !current_z.is_some_and(|z| z >= clearance - Z_EPSILON) {
+                    push_retract(&mut output, absolute, current_z, clearance);
+                    report.inserted += 1;
+                    current_z = Some(clearance);
+                }
+
+                let from_z = current_z;
+                let rewrite = match (z_value, target_z) {
+                    (Some(_), Some(target)) if target > stock_top + Z_EPSILON => {
+                        // Traverses go to clearance; Z-only rapids only when retracting
+                        let retract = has_xy || from_z.is_none_or(|z| target > z + Z_EPSILON);
+                        retract && (target - clearance).abs() > Z_EPSILON
+                    }
+                    (Some(_), None) => {
+                        report.unresolved += 1;
+                        false
+                    }
+                    _ => false,
+                };
+
+                if rewrite {
+                    let new_value = if absolute {
+                        clearance
+                    } else {
+                        clearance - from_z.unwrap_or(clearance)
+                    };
+                    if let Some(i) = z_index {
+                        words[i].1 = format_gcode_number(new_value, 4);
+                    }
+                    output.push(GCodeValidator::rebuild_line(line, &words));
+                    report.rewritten += 1;
+                    current_z = Some(clearance);
+                } else {
+                    output.push(line.to_string());
+                    if z_value.is_some() {
+                        current_z = target_z;
+                    }
+                }
+            }
+            Some(Motion::Cycle) if !words.is_empty() => {
+                output.push(line.to_string());
+                if has_xy || z_value.is_some() {
+                    current_z = None;
+                }
+            }
+            _ => {
+                output.push(line.to_string());
+                if z_value.is_some() && motion.is_some() {
+                    current_z = target_z;
+                }
+            }
+        }
+    }
+
+    let mut text = output.join("\n");
+    if program.ends_with('\n') {
+        text.push('\n');
+    }
+    report.program = text;
+    Ok(report)
+}
+
+fn push_retract(output: &mut Vec<String>, absolute: bool, current_z: Option<f64>, clearance: f64) {
+    match (absolute, current_z) {
+        (true, _) => output.push(format!(
+            "G0 Z{} {}",
+            format_gcode_number(clearance, 4),
+            RETRACT_COMMENT
+        )),
+        (false, Some(z)) => output.push(format!(
+            "G0 Z{} {}",
+            format_gcode_number(clearance - z, 4),
+            RETRACT_COMMENT
+        )),
+        (false, None) => {
+            output.push(format!(
+                "G90 G0 Z{} {}",
+                format_gcode_number(clearance, 4),
+                RETRACT_COMMENT
+            ));
+            output.push("G91".to_string());
+        }
+    }
+}
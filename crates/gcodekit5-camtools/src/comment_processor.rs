@@ -2,6 +2,8 @@
 //!
 //! Extracts and processes G-code comments (both parentheses and semicolon styles).
 
+use crate::annotation::is_annotation;
+
 /// Comment processing mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CommentMode {
@@ -73,6 +75,21 @@ impl CommentProcessor {
 
         result.trim().to_string()
     }
+
+    /// Drop the generator annotations from a program, leaving everything else
+    ///
+    /// Only whole-line comments written by [`crate::annotation`] are removed, so
+    /// the remaining program is the one the generator writes with annotations off.
+    pub fn strip_annotations(program: &str) -> String {
+        let mut stripped = String::with_capacity(program.len());
+        for line in program.lines() {
+            if !is_annotation(line) {
+                stripped.push_str(line);
+                stripped.push('\n');
+            }
+        }
+        stripped
+    }
 }
 
 impl Default for CommentProcessor {
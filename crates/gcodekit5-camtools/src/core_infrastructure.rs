@@ -2,6 +2,7 @@
 //!
 //! Remaining critical tasks for production readiness
 
+use crate::job_telemetry::JobTimingRecord;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
@@ -218,6 +219,8 @@ pub struct TelemetryData {
     pub spindle_rpm: u32,
     /// Feed rate override
     pub feed_override: f32,
+    /// Timing of the last completed job
+    pub last_job: Option<JobTimingRecord>,
 }
 
 impl TelemetryData {
@@ -227,6 +230,7 @@ impl TelemetryData {
             temperature: 25.0,
             spindle_rpm: 0,
             feed_override: 100.0,
+            last_job: None,
         }
     }
 }
@@ -0,0 +1,319 @@
+//! # Distance Mode Conversion
+//!
+//! Rewrites a program between absolute (G90) and incremental (G91) distance
+//! modes. Absolute coordinates become the distance from the previous position,
+//! and incremental ones the running position, with G90/G91 words written where
+//! the mode of the rewritten program changes.
+//!
+//! Coordinates are handled as exact fixed-point numbers at the finest precision
+//! the program uses, so positions never drift however long the program is, and
+//! converting there and back gives the original coordinates.
+//!
+//! Only axis words of moves change. Arc centers (I, J, K) and radii (R) do not
+//! depend on G90/G91 and pass through, as do all other words. A move is only
+//! converted once the position of each axis it names is known; until then, and
+//! for lines whose coordinates mean something else (G28/G30 intermediate
+//! points, G53 machine coordinates, probing and canned cycles), the line keeps
+//! the mode it was written in. Homing, probing, G53 moves, coordinate system
+//! and tool offset changes, unit changes and canned cycles make positions
+//! unknown again; G92 sets them.
+
+use crate::validator::GCodeValidator;
+
+/// Axes whose words follow the distance mode
+const AXES: [char; 9] = ['X', 'Y', 'Z', 'A', 'B', 'C', 'U', 'V', 'W'];
+/// Finest precision converted exactly (decimal places)
+const MAX_DECIMALS: u32 = 6;
+
+/// G90 or G91
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DistanceMode {
+    /// G90: coordinates are positions
+    #[default]
+    Absolute,
+    /// G91: coordinates are distances from the current position
+    Incremental,
+}
+
+impl DistanceMode {
+    /// The G code number that selects this mode
+    pub fn gcode(&self) -> u8 {
+        match self {
+            Self::Absolute => 90,
+            Self::Incremental => 91,
+        }
+    }
+}
+
+/// Result of [`convert_distance_mode`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistanceModeReport {
+    /// The rewritten program
+    pub program: String,
+    /// Moves rewritten in the target mode
+    pub converted: usize,
+    /// Moves left in their own mode because a position was not known
+    pub kept: usize,
+}
+
+impl DistanceModeReport {
+    /// One-line summary for display
+    pub fn summary(&self) -> String {
+        let mut summary = format!("{} move(s) converted", self.converted);
+        if self.kept > 0 {
+            summary.push_str(&format!(
+                "; {} move(s) left unchanged (position not known)",
+                self.kept
+            ));
+        }
+        summary
+    }
+}
+
+/// What a line sets, ignoring comments
+#[derive(Default)]
+struct LineCodes {
+    distance: Option<DistanceMode>,
+    motion: Option<u32>,
+    cancel_cycle: bool,
+    /// G28/G30, G38.x or G53: coordinates are not a move in the distance mode
+    special_move: bool,
+    /// G53 alone, which only moves the axes it names
+    machine_move: bool,
+    /// G92: sets the current position
+    set_position: bool,
+    /// G10: axis words are offsets, not a move
+    set_offsets: bool,
+    /// Coordinate system, tool offset or unit change
+    new_frame: bool,
+}
+
+fn line_codes(words: &[(char, String)]) -> LineCodes {
+    let mut codes = LineCodes::default();
+    for (letter, number) in words {
+        if *letter != 'G' {
+            continue;
+        }
+        let Ok(code) = number.parse::<f64>() else {
+            continue;
+        };
+        match code {
+            90.0 => codes.distance = Some(DistanceMode::Absolute),
+            91.0 => codes.distance = Some(DistanceMode::Incremental),
+            0.0 | 1.0 | 2.0 | 3.0 | 73.0 => codes.motion = Some(code as u32),
+            81.0..=89.0 if code.fract() == 0.0 => codes.motion = Some(code as u32),
+            80.0 => codes.cancel_cycle = true,
+            53.0 => {
+                codes.machine_move = !codes.special_move;
+                codes.special_move = true;
+            }
+            28.0 | 30.0 | 38.0..39.0 => {
+                codes.machine_move = false;
+                codes.special_move = true;
+            }
+            92.0 => codes.set_position = true,
+            10.0 => codes.set_offsets = true,
+            20.0 | 21.0 | 43.0..44.0 | 49.0 | 54.0..=59.9 => codes.new_frame = true,
+            _ => {}
+        }
+    }
+    codes
+}
+
+/// Decimal places of a number as written
+fn decimals(number: &str) -> u32 {
+    number
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len() as u32)
+}
+
+/// `number` in units of 10^-`scale`
+fn to_fixed(number: &str, scale: u32) -> Option<i64> {
+    let (negative, digits) = match number.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, number.strip_prefix('+').unwrap_or(number)),
+    };
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    let value = if fraction.len() as u32 <= scale {
+        let whole: i64 = if whole.is_empty() {
+            0
+        } else {
+            whole.parse().ok()?
+        };
+        let padded = format!("{:0<width$}", fraction, width = scale as usize);
+        let fraction: i64 = if padded.is_empty() {
+            0
+        } else {
+            padded.parse().ok()?
+        };
+        whole.checked_mul(10i64.pow(scale))?.checked_add(fraction)?
+    } else {
+        // Finer than can be kept exactly: round
+        (digits.parse::<f64>().ok()? * 10f64.powi(scale as i32)).round() as i64
+    };
+    Some(if negative { -value } else { value })
+}
+
+/// A fixed-point value written without trailing zeros
+fn format_fixed(value: i64, scale: u32) -> String {
+    let unit = 10i64.pow(scale);
+    let sign = if value < 0 { "-" } else { "" };
+    let (whole, fraction) = (
+        value.unsigned_abs() / unit as u64,
+        value.unsigned_abs() % unit as u64,
+    );
+    if fraction == 0 {
+        return format!("{}{}", sign, whole);
+    }
+    let fraction = format!("{:0width$}", fraction, width = scale as usize);
+    format!("{}{}.{}", sign, whole, fraction.trim_end_matches('0'))
+}
+
+/// Rewrite `program` in `target` distance mode
+pub fn convert_distance_mode(program: &str, target: DistanceMode) -> DistanceModeReport {
+    let lines: Vec<&str> = program.lines().collect();
+    let parsed: Vec<Vec<(char, String)>> = lines
+        .iter()
+        .map(|line| GCodeValidator::word_texts(line))
+        .collect();
+    let scale = parsed
+        .iter()
+        .flatten()
+        .filter(|(letter, _)| AXES.contains(letter))
+        .map(|(_, number)| decimals(number))
+        .max()
+        .unwrap_or(0)
+        .min(MAX_DECIMALS);
+
+    let mut report = DistanceModeReport {
+        program: String::new(),
+        converted: 0,
+        kept: 0,
+    };
+    // Mode of the program as written, and of the rewritten program
+    let mut source = DistanceMode::Absolute;
+    let mut written = DistanceMode::Absolute;
+    let mut motion = 0u32;
+    let mut position: [Option<i64>; AXES.len()] = [None; AXES.len()];
+    let mut output = Vec::with_capacity(lines.len());
+
+    for (line, mut words) in lines.iter().zip(parsed) {
+        let codes = line_codes(&words);
+        if let Some(mode) = codes.distance {
+            source = mode;
+        }
+        if let Some(code) = codes.motion {
+            motion = code;
+        }
+        if codes.cancel_cycle {
+            motion = 0;
+        }
+        let axes: Vec<(usize, i64)> = words
+            .iter()
+            .filter_map(|(letter, number)| {
+                let axis = AXES.iter().position(|a| a == letter)?;
+                Some((axis, to_fixed(number, scale)?))
+            })
+            .collect();
+        let mut changed = codes.distance.is_some();
+        // The mode this line is written in; a mode word alone switches to the
+        // target once there are positions to convert from
+        let any_known = position.iter().any(Option::is_some);
+        let mut mode = codes
+            .distance
+            .map(|_| if any_known { target } else { source });
+
+        if codes.set_position {
+            for &(axis, value) in &axes {
+                position[axis] = Some(value);
+            }
+        } else if codes.special_move {
+            // Homing, probing and machine-coordinate moves
+            if !axes.is_empty() {
+                mode = Some(source);
+            }
+            if codes.machine_move {
+                for &(axis, _) in &axes {
+                    position[axis] = None;
+                }
+            } else {
+                position = [None; AXES.len()];
+            }
+        } else if axes.is_empty() || codes.set_offsets {
+        } else if !(0..=3).contains(&motion) {
+            // Canned cycles
+            mode = Some(source);
+            position = [None; AXES.len()];
+        } else {
+            let known = axes.iter().all(|&(axis, _)| position[axis].is_some());
+            let line_mode = if known { target } else { source };
+            if known && source != target {
+                for (letter, number) in words.iter_mut() {
+                    let Some(axis) = AXES.iter().position(|a| a == letter) else {
+                        continue;
+                    };
+                    let Some(value) = to_fixed(number, scale) else {
+                        continue;
+                    };
+                    let current = position[axis].unwrap_or_default();
+                    let rewritten = match target {
+                        DistanceMode::Incremental => value - current,
+                        DistanceMode::Absolute => current + value,
+                    };
+                    *number = format_fixed(rewritten, scale);
+                }
+                changed = true;
+            }
+            if known {
+                report.converted += 1;
+            } else if source != target {
+                report.kept += 1;
+            }
+            for &(axis, value) in &axes {
+                position[axis] = match source {
+                    DistanceMode::Absolute => Some(value),
+                    DistanceMode::Incremental => position[axis].map(|p| p + value),
+                };
+            }
+            mode = Some(line_mode);
+        }
+        if codes.new_frame {
+            position = [None; AXES.len()];
+        }
+
+        if let Some(mode) = mode {
+            let word = ('G', mode.gcode().to_string());
+            let mut placed = false;
+            words.retain_mut(|(letter, number)| {
+                let distance = *letter == 'G' && matches!(number.parse::<f64>(), Ok(90.0 | 91.0));
+                if distance && !placed {
+                    *number = word.1.clone();
+                    placed = true;
+                    return true;
+                }
+                !distance
+            });
+            if !placed && mode != written {
+                let at = words
+                    .iter()
+                    .take_while(|(letter, _)| *letter == 'N')
+                    .count();
+                words.insert(at, word);
+                changed = true;
+            }
+            written = mode;
+        }
+        output.push(if changed {
+            GCodeValidator::rebuild_line(line, &words)
+        } else {
+            line.to_string()
+        });
+    }
+
+    let mut text = output.join("\n");
+    if program.ends_with('\n') {
+        text.push('\n');
+    }
+    report.program = text;
+    report
+}
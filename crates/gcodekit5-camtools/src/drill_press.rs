@@ -8,6 +8,8 @@
 //! All dimensional parameters are in millimeters (mm)
 //! and feed rates in mm/min.
 
+use crate::annotation::{AnnotatedTool, AnnotationLevel, Annotator};
+use crate::preview::GeneratorPreview;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
@@ -41,12 +43,31 @@ pub struct DrillPressParameters {
 /// Generator for Drill Press G-Code
 pub struct DrillPressGenerator {
     params: DrillPressParameters,
+    annotation: AnnotationLevel,
 }
 
 impl DrillPressGenerator {
     /// Create a new DrillPressGenerator with the given parameters
     pub fn new(params: DrillPressParameters) -> Self {
-        Self { params }
+        Self {
+            params,
+            annotation: AnnotationLevel::Off,
+        }
+    }
+
+    /// Annotate the G-code with the cycle and each peck or helix turn
+    pub fn with_annotation(mut self, level: AnnotationLevel) -> Self {
+        self.annotation = level;
+        self
+    }
+
+    fn annotator(&self) -> Annotator {
+        Annotator::new(AnnotatedTool::DrillPress, self.annotation)
+    }
+
+    /// Extents and size of the G-code [`generate`](Self::generate) writes
+    pub fn preview(&self) -> Result<GeneratorPreview> {
+        Ok(GeneratorPreview::from_program(&self.generate()?))
     }
 
     /// Generate the G-Code for the drilling operation
@@ -97,16 +118,23 @@ impl DrillPressGenerator {
         if p.peck_depth <= 0.0 {
             // Simple drill
             gcode.push_str("; Simple drilling cycle\n");
+            self.annotator()
+                .section(gcode, format_args!("Drill X{:.3} Y{:.3}", p.x, p.y));
             gcode.push_str(&format!("G1 Z{:.3} F{:.1}\n", target_z, p.plunge_rate));
         } else {
             // Peck drill
             gcode.push_str("; Peck drilling cycle\n");
+            let annotator = self.annotator();
+            annotator.section(gcode, format_args!("Peck drill X{:.3} Y{:.3}", p.x, p.y));
             let mut current_z = start_z;
+            let mut peck = 0;
             while current_z > target_z {
                 current_z -= p.peck_depth;
                 if current_z < target_z {
                     current_z = target_z;
                 }
+                peck += 1;
+                annotator.line(gcode, format_args!("Peck {} to Z{:.3}", peck, current_z));
                 gcode.push_str(&format!("G1 Z{:.3} F{:.1}\n", current_z, p.plunge_rate));
                 gcode.push_str(&format!("G0 Z{:.3} ; Retract to clear chips\n", start_z));
                 if current_z > target_z {
@@ -126,6 +154,14 @@ impl DrillPressGenerator {
         let start_z = p.top_z;
 
         gcode.push_str("; Helical interpolation cycle\n");
+        let annotator = self.annotator();
+        annotator.section(
+            gcode,
+            format_args!(
+                "Helical bore X{:.3} Y{:.3} D{:.3}",
+                p.x, p.y, p.hole_diameter
+            ),
+        );
 
         // Move to start of helix (X + radius)
         gcode.push_str(&format!("G0 X{:.3} Y{:.3}\n", p.x + radius, p.y));
@@ -139,12 +175,18 @@ impl DrillPressGenerator {
             1.0
         };
         let mut current_z = start_z;
+        let mut turn = 0;
 
         while current_z > target_z {
             current_z -= pitch;
             if current_z < target_z {
                 current_z = target_z;
             }
+            turn += 1;
+            annotator.line(
+                gcode,
+                format_args!("Helix turn {} to Z{:.3}", turn, current_z),
+            );
             // G2 helical move: I is relative to start point (X+radius, Y), so I = -radius
             gcode.push_str(&format!(
                 "G2 X{:.3} Y{:.3} I{:.3} J0.0 Z{:.3} F{:.1}\n",
@@ -157,6 +199,7 @@ impl DrillPressGenerator {
         }
 
         // Final full circle at bottom to ensure clean hole
+        annotator.line(gcode, format_args!("Finishing circle at Z{:.3}", target_z));
         gcode.push_str(&format!(
             "G2 X{:.3} Y{:.3} I{:.3} J0.0 F{:.1}\n",
             p.x + radius,
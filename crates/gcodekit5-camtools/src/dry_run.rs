@@ -0,0 +1,263 @@
+//! # Dry Run (Air Cut)
+//!
+//! Rewrites a program so it runs a fixed distance above the stock, to check the
+//! motion before cutting. Every absolute Z position on a move is raised by the
+//! offset (arc I/J/K offsets are relative and left alone), and canned-cycle R planes
+//! move with it. Relative (G91) Z moves are kept as they are, since they already
+//! follow whatever absolute Z preceded them; a program that moves in G91 before
+//! any absolute Z has been raised is refused, since nothing would lift it. Lines
+//! that set or use other coordinate
+//! frames (G10, G28, G30, G53, G92, G38.x probing) are not touched.
+//!
+//! The spindle or laser can optionally be kept off, either by removing M3/M4 or by
+//! forcing every S word to zero.
+//!
+//! Each changed line keeps its original text in a trailing `; dry-run:` comment and
+//! the program gets a marked header, so [`remove_dry_run`] restores the exact
+//! original and the two versions can be switched without regenerating. Raising Z
+//! can exceed the machine's travel, so check the clearance above the job first.
+
+use crate::error::{CamToolError, CamToolResult};
+use crate::validator::GCodeValidator;
+use gcodekit5_core::format_gcode_number;
+
+/// Start of the header lines added to a dry-run program
+const HEADER_PREFIX: &str = "; DRY RUN (air cut):";
+/// Separates a rewritten line from the original it replaced
+const ORIGINAL_MARKER: &str = "; dry-run: ";
+
+/// What to do with spindle/laser commands during a dry run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DryRunSpindle {
+    /// Leave M3/M4 and S words unchanged
+    Keep,
+    /// Remove M3/M4 so the spindle or laser never turns on
+    #[default]
+    Strip,
+    /// Keep M3/M4 but force every S word to 0
+    ZeroSpeed,
+}
+
+/// Dry-run settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct DryRunOptions {
+    /// Distance in mm to raise every absolute Z position (positive)
+    pub z_offset: f64,
+    /// Spindle/laser handling
+    pub spindle: DryRunSpindle,
+}
+
+impl Default for DryRunOptions {
+    fn default() -> Self {
+        Self {
+            z_offset: 10.0,
+            spindle: DryRunSpindle::Strip,
+        }
+    }
+}
+
+/// Whether a program has been converted with [`apply_dry_run`]
+pub fn is_dry_run(program: &str) -> bool {
+    program
+        .lines()
+        .any(|line| line.starts_with(HEADER_PREFIX) || line.contains(ORIGINAL_MARKER))
+}
+
+/// Convert a program into a dry run
+///
+/// Fails if the offset is not a positive distance, the program already is a dry
+/// run (applying it twice would stack the offsets) or it moves incrementally
+/// before any absolute Z position (the dry run would still cut).
+pub fn apply_dry_run(program: &str, options: &DryRunOptions) -> CamToolResult<String> {
+    if !options.z_offset.is_finite() || options.z_offset <= 0.0 {
+        return Err(CamToolError::InvalidParameters(format!(
+            "dry-run Z offset must be a positive distance, got {}",
+            options.z_offset
+        )));
+    }
+    if is_dry_run(program) {
+        return Err(CamToolError::InvalidParameters(
+            "program is already a dry run".to_string(),
+        ));
+    }
+
+    let spindle = match options.spindle {
+        DryRunSpindle::Keep => "spindle/laser unchanged",
+        DryRunSpindle::Strip => "M3/M4 removed",
+        DryRunSpindle::ZeroSpeed => "spindle/laser forced to S0",
+    };
+    let mut output = vec![format!(
+        "{} absolute Z raised by {:.3} mm, {}",
+        HEADER_PREFIX, options.z_offset, spindle
+    )];
+
+    let mut modal = ModalState::default();
+    let mut raised = false;
+    for (index, line) in program.lines().enumerate() {
+        let mut words = GCodeValidator::word_texts(line);
+        modal.update(&words);
+        let moves = words
+            .iter()
+            .any(|(letter, _)| matches!(letter, 'X' | 'Y' | 'Z' | 'A' | 'B' | 'C'));
+        if moves && modal.motion.is_some() && !modal.absolute && !modal.other_frame && !raised {
+            return Err(CamToolError::InvalidParameters(format!(
+                "line {} moves incrementally (G91) before any absolute Z position, so the dry run cannot raise it",
+                index + 1
+            )));
+        }
+        raised |= modal.raises_z() && words.iter().any(|(letter, _)| *letter == 'Z');
+        if transform_words(&mut words, &modal, options) {
+            let code: Vec<String> = words
+                .iter()
+                .map(|(letter, number)| format!("{}{}", letter, number))
+                .collect();
+            if code.is_empty() {
+                output.push(format!("{}{}", ORIGINAL_MARKER, line));
+            } else {
+                output.push(format!("{} {}{}", code.join(" "), ORIGINAL_MARKER, line));
+            }
+        } else {
+            output.push(line.to_string());
+        }
+    }
+
+    Ok(join_lines(output, program))
+}
+
+/// Restore the original program from a dry run
+///
+/// Programs that are not dry runs are returned unchanged.
+pub fn remove_dry_run(program: &str) -> String {
+    let lines: Vec<String> = program
+        .lines()
+        .filter(|line| !line.starts_with(HEADER_PREFIX))
+        .map(|line| match line.find(ORIGINAL_MARKER) {
+            Some(pos) => line[pos + ORIGINAL_MARKER.len()..].to_string(),
+            None => line.to_string(),
+        })
+        .collect();
+    join_lines(lines, program)
+}
+
+/// Switch between the dry run and the original program
+pub fn toggle_dry_run(program: &str, options: &DryRunOptions) -> CamToolResult<String> {
+    if is_dry_run(program) {
+        Ok(remove_dry_run(program))
+    } else {
+        apply_dry_run(program, options)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Motion {
+    Move,
+    Cycle,
+}
+
+/// The modal state that decides whether a Z word is an absolute position
+#[derive(Debug, Clone, Copy)]
+struct ModalState {
+    absolute: bool,
+    inches: bool,
+    motion: Option<Motion>,
+    /// The current line uses another coordinate frame or does not move
+    other_frame: bool,
+}
+
+impl Default for ModalState {
+    fn default() -> Self {
+        Self {
+            absolute: true,
+            inches: false,
+            motion: None,
+            other_frame: false,
+        }
+    }
+}
+
+impl ModalState {
+    fn update(&mut self, words: &[(char, String)]) {
+        self.other_frame = false;
+        for (letter, number) in words {
+            if *letter != 'G' {
+                continue;
+            }
+            let Ok(code) = number.parse::<f64>() else {
+                continue;
+            };
+            match code {
+                0.0 | 1.0 | 2.0 | 3.0 => self.motion = Some(Motion::Move),
+                80.0 => self.motion = None,
+                81.0..=89.0 if code.fract() == 0.0 => self.motion = Some(Motion::Cycle),
+                90.0 => self.absolute = true,
+                91.0 => self.absolute = false,
+                20.0 => self.inches = true,
+                21.0 => self.inches = false,
+                4.0 | 10.0 | 28.0 | 30.0 | 53.0 | 92.0 => self.other_frame = true,
+                38.0..=39.0 => self.other_frame = true,
+                _ => {}
+            }
+        }
+    }
+
+    fn raises_z(&self) -> bool {
+        self.absolute && self.motion.is_some() && !self.other_frame
+    }
+}
+
+/// Apply the dry run to one line's words, returning whether anything changed
+fn transform_words(
+    words: &mut Vec<(char, String)>,
+    modal: &ModalState,
+    options: &DryRunOptions,
+) -> bool {
+    let mut changed = false;
+    let offset = if modal.inches {
+        options.z_offset / 25.4
+    } else {
+        options.z_offset
+    };
+
+    if modal.raises_z() {
+        for (letter, number) in words.iter_mut() {
+            let raise = *letter == 'Z' || (*letter == 'R' && modal.motion == Some(Motion::Cycle));
+            if !raise {
+                continue;
+            }
+            if let Ok(value) = number.parse::<f64>() {
+                *number = format_gcode_number(value + offset, 4);
+                changed = true;
+            }
+        }
+    }
+
+    match options.spindle {
+        DryRunSpindle::Keep => {}
+        DryRunSpindle::Strip => {
+            let before = words.len();
+            words.retain(|(letter, number)| {
+                !(*letter == 'M' && matches!(number.parse::<f64>(), Ok(3.0) | Ok(4.0)))
+            });
+            changed |= words.len() != before;
+        }
+        DryRunSpindle::ZeroSpeed => {
+            for (letter, number) in words.iter_mut() {
+                if *letter == 'S' && number.parse::<f64>() != Ok(0.0) {
+                    *number = "0".to_string();
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    changed
+}
+
+/// Join lines, ending with a newline if the source program did
+fn join_lines(lines: Vec<String>, source: &str) -> String {
+    let mut text = lines.join("\n");
+    if source.ends_with('\n') {
+        text.push('\n');
+    }
+    text
+}
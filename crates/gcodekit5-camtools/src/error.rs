@@ -41,6 +41,10 @@ pub enum CamToolError {
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
 
+    /// Generated G-code failed validation.
+    #[error("G-code validation failed with {} error(s)", .0.len())]
+    ValidationFailed(Vec<crate::validator::ValidationError>),
+
     /// A parameter validation error occurred.
     #[error("Parameter error: {0}")]
     Parameter(#[from] ParameterError),
@@ -0,0 +1,147 @@
+//! # Feed Mode Conversion
+//!
+//! Rewrites a program that mixes feed rate modes so every feed is in units per
+//! minute (G94). G93 inverse-time feeds become the move's length times its F
+//! word, and G95 feed-per-revolution feeds become F times the spindle speed;
+//! the mode words themselves become G94.
+//!
+//! F words are only written where the feed on the controller changes, so a
+//! stretch of G94 moves after a converted section gets its feed back on the
+//! first move. Feeds stay in the program's units (G20/G21). Arcs given with R
+//! are measured by their chord.
+
+use crate::error::{CamToolError, CamToolResult};
+use crate::stats::program_moves_by_line;
+use crate::validator::GCodeValidator;
+use gcodekit5_core::{format_gcode_number, FeedMode};
+use std::collections::HashMap;
+
+/// Rewrite every feed of `program` in units per minute (G94)
+///
+/// G95 feeds are multiplied by `spindle_rpm`, or by the program's own S word
+/// when no speed is given. Fails if the speed is not positive, if a G95 move
+/// has no speed to go by (none given, or S is a surface speed under G96), or
+/// if a G93 move has no F word.
+pub fn convert_to_units_per_minute(
+    program: &str,
+    spindle_rpm: Option<f64>,
+) -> CamToolResult<String> {
+    if let Some(rpm) = spindle_rpm.filter(|rpm| !(rpm.is_finite() && *rpm > 0.0)) {
+        return Err(CamToolError::InvalidParameters(format!(
+            "spindle speed must be positive, got {} RPM",
+            rpm
+        )));
+    }
+    let lines: Vec<String> = program.lines().map(str::to_string).collect();
+    let lengths: HashMap<usize, f64> = program_moves_by_line(&lines)
+        .into_iter()
+        .map(|(index, program_move)| (index, program_move.length()))
+        .collect();
+
+    let mut mode = FeedMode::UnitsPerMinute;
+    let mut motion = 0u32;
+    // mm per program unit
+    let mut scale = 1.0;
+    let mut feed = 0.0f64;
+    let mut program_rpm = None;
+    let mut surface_speed = false;
+    // F in effect on the controller for the converted program, as written
+    let mut active_feed: Option<String> = None;
+    let mut output = Vec::with_capacity(lines.len());
+
+    for (index, line) in lines.iter().enumerate() {
+        let mut words = GCodeValidator::word_texts(line);
+        let mut changed = false;
+        let mut line_feed = None;
+        let mut speed_word = None;
+        for (letter, number) in words.iter_mut() {
+            let Ok(value) = number.parse::<f64>() else {
+                continue;
+            };
+            match (*letter, value) {
+                ('G', 0.0..=3.0) if value.fract() == 0.0 => motion = value as u32,
+                ('G', 20.0) => scale = 25.4,
+                ('G', 21.0) => scale = 1.0,
+                ('G', 93.0 | 94.0 | 95.0) => {
+                    mode = FeedMode::from_gcode(value as u8).unwrap_or_default();
+                    if value != 94.0 {
+                        *number = "94".to_string();
+                        changed = true;
+                    }
+                }
+                ('G', 96.0) => surface_speed = true,
+                ('G', 97.0) => surface_speed = false,
+                ('F', f) => {
+                    feed = f;
+                    line_feed = Some(f);
+                }
+                ('S', s) => speed_word = Some(s),
+                _ => {}
+            }
+        }
+        if surface_speed {
+            program_rpm = None;
+        } else if let Some(s) = speed_word {
+            program_rpm = (s > 0.0).then_some(s);
+        }
+
+        let feed_move = matches!(motion, 1..=3) && lengths.contains_key(&index);
+        if feed_move {
+            let length = lengths[&index] / scale;
+            let rate = match mode {
+                FeedMode::UnitsPerMinute => feed,
+                FeedMode::UnitsPerRevolution => {
+                    let rpm = spindle_rpm.or(program_rpm).ok_or_else(|| {
+                        CamToolError::InvalidParameters(format!(
+                            "line {}: feed per revolution needs a spindle speed in RPM: {}",
+                            index + 1,
+                            line.trim()
+                        ))
+                    })?;
+                    feed * rpm
+                }
+                FeedMode::InverseTime => {
+                    let f = line_feed.ok_or_else(|| {
+                        CamToolError::InvalidParameters(format!(
+                            "line {}: inverse-time move without an F word: {}",
+                            index + 1,
+                            line.trim()
+                        ))
+                    })?;
+                    f * length
+                }
+            };
+            let text = format_gcode_number(rate, 4);
+            if mode == FeedMode::UnitsPerMinute && line_feed.is_some() {
+                active_feed = Some(text);
+            } else if rate > 0.0 && (line_feed.is_some() || active_feed.as_ref() != Some(&text)) {
+                match words.iter_mut().find(|(letter, _)| *letter == 'F') {
+                    Some((_, number)) => *number = text.clone(),
+                    None => words.push(('F', text.clone())),
+                }
+                changed = true;
+                active_feed = Some(text);
+            }
+        } else if let Some(f) = line_feed {
+            if mode == FeedMode::UnitsPerMinute {
+                active_feed = Some(format_gcode_number(f, 4));
+            } else {
+                // Written on the next feed move once its rate is known
+                words.retain(|(letter, _)| *letter != 'F');
+                changed = true;
+            }
+        }
+
+        output.push(if changed {
+            GCodeValidator::rebuild_line(line, &words)
+        } else {
+            line.clone()
+        });
+    }
+
+    let mut text = output.join("\n");
+    if program.ends_with('\n') {
+        text.push('\n');
+    }
+    Ok(text)
+}
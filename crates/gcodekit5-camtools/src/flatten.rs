@@ -0,0 +1,1564 @@
+//! # Subprogram and Macro Flattening
+//!
+//! Turns a LinuxCNC-style program into a flat one that controllers without a
+//! macro language, such as GRBL, can run: O-word subprograms are expanded
+//! where they are called, `if`/`while`/`do`/`repeat` blocks are run, `#`
+//! parameters and `[...]` expressions are evaluated into plain numbers, and
+//! drilling cycles (G73, G81-G83, G85, G89) are written out as G0/G1/G4 moves
+//! unless the target runs canned cycles itself.
+//!
+//! Lines without parameters or expressions are kept as written, less their
+//! indentation.
+//! Anything that cannot be flattened safely is kept as a comment and reported
+//! as a warning instead of being dropped: calls to subprograms defined in other
+//! files, reads of machine parameters (#5000 and up, or predefined `#<_...>`
+//! names the program never sets), tapping and boring cycles, cycles outside
+//! the XY plane, and M98/M99 subprograms. The flattened program is checked
+//! with [`GCodeValidator`].
+//!
+//! Flattening stops with an error on malformed control blocks, undefined local
+//! parameters, math errors, or output growing past
+//! [`FlattenOptions::max_lines`], which is usually a loop that never ends.
+
+use crate::error::{CamToolError, CamToolResult};
+use crate::validator::{GCodeValidator, ValidationError, ValidatorConfig};
+use gcodekit5_core::format_gcode_number;
+use std::collections::HashMap;
+
+/// Deepest subprogram nesting before a call is taken as runaway recursion
+const MAX_CALL_DEPTH: usize = 64;
+/// Loop iterations run before a loop that writes nothing is taken as endless
+const MAX_ITERATIONS: usize = 1_000_000;
+/// Parameters from here up hold machine state: probe results, offsets, positions
+const MACHINE_PARAMETERS: u32 = 5000;
+/// Retract above the last depth before feeding into a peck again (mm)
+const PECK_CLEARANCE_MM: f64 = 0.254;
+
+/// What to flatten
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlattenOptions {
+    /// Write canned drilling cycles out as primitive moves; turn off for
+    /// controllers that run them, e.g. where
+    /// `CapabilityManager::supports("canned_cycles")` holds
+    pub expand_canned_cycles: bool,
+    /// Longest flattened program, in lines
+    pub max_lines: usize,
+}
+
+impl Default for FlattenOptions {
+    fn default() -> Self {
+        Self {
+            expand_canned_cycles: true,
+            max_lines: 1_000_000,
+        }
+    }
+}
+
+/// Something that was kept as a comment instead of being translated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlattenWarning {
+    /// Source line number, starting at 1
+    pub line: usize,
+    pub message: String,
+}
+
+/// Result of [`flatten_program`]
+#[derive(Debug, Clone)]
+pub struct FlattenReport {
+    /// The flat program
+    pub program: String,
+    /// Constructs that were not translated, in program order
+    pub warnings: Vec<FlattenWarning>,
+    /// Subprogram calls expanded
+    pub calls_expanded: usize,
+    /// Canned cycle holes written out as moves
+    pub cycles_expanded: usize,
+    /// Problems [`GCodeValidator`] finds in the flat program
+    pub validation_errors: Vec<ValidationError>,
+}
+
+impl FlattenReport {
+    /// One-line summary for display
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} subprogram call(s) and {} canned cycle hole(s) expanded",
+            self.calls_expanded, self.cycles_expanded
+        );
+        if !self.warnings.is_empty() {
+            summary.push_str(&format!(
+                "; {} construct(s) could not be translated",
+                self.warnings.len()
+            ));
+        }
+        if !self.validation_errors.is_empty() {
+            summary.push_str(&format!(
+                "; {} validation problem(s)",
+                self.validation_errors.len()
+            ));
+        }
+        summary
+    }
+}
+
+/// Flatten `program` for a controller without subprograms or parameters
+pub fn flatten_program(program: &str, options: &FlattenOptions) -> CamToolResult<FlattenReport> {
+    let parsed = program
+        .lines()
+        .enumerate()
+        .map(|(index, text)| parse_line(index + 1, text))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(CamToolError::InvalidParameters)?;
+    let mut subs = HashMap::new();
+    let mut position = 0;
+    let (main, end) = parse_block(&parsed, &mut position, &mut subs, None, true)
+        .map_err(CamToolError::InvalidParameters)?;
+    if let Some(end) = end {
+        return Err(CamToolError::InvalidParameters(format!(
+            "line {}: {} without a matching opening block",
+            parsed[end].number,
+            keyword_name(&parsed[end].kind)
+        )));
+    }
+
+    let mut flattener = Flattener {
+        lines: &parsed,
+        subs: &subs,
+        options,
+        output: Vec::new(),
+        warnings: Vec::new(),
+        calls_expanded: 0,
+        cycles_expanded: 0,
+        globals: HashMap::new(),
+        named_globals: HashMap::new(),
+        frames: vec![Frame::default()],
+        iterations: 0,
+        machine: Machine::default(),
+    };
+    flattener
+        .run(&main)
+        .map_err(CamToolError::InvalidParameters)?;
+
+    let Flattener {
+        output,
+        warnings,
+        calls_expanded,
+        cycles_expanded,
+        ..
+    } = flattener;
+    let validation_errors = GCodeValidator::new(ValidatorConfig::default())
+        .validate(&output)
+        .err()
+        .unwrap_or_default();
+    let mut text = output.join("\n");
+    if program.ends_with('\n') && !text.is_empty() {
+        text.push('\n');
+    }
+    for warning in &warnings {
+        tracing::warn!("line {}: {}", warning.line, warning.message);
+    }
+    Ok(FlattenReport {
+        program: text,
+        warnings,
+        calls_expanded,
+        cycles_expanded,
+        validation_errors,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Parsing
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Func {
+    Abs,
+    Acos,
+    Asin,
+    Cos,
+    Exp,
+    Fix,
+    Fup,
+    Ln,
+    Round,
+    Sin,
+    Sqrt,
+    Tan,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Pow,
+    Mul,
+    Div,
+    Mod,
+    Add,
+    Sub,
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    And,
+    Or,
+    Xor,
+}
+
+impl Op {
+    fn precedence(self) -> u8 {
+        match self {
+            Op::Pow => 4,
+            Op::Mul | Op::Div | Op::Mod => 3,
+            Op::Add | Op::Sub => 2,
+            Op::Eq | Op::Ne | Op::Gt | Op::Ge | Op::Lt | Op::Le => 1,
+            Op::And | Op::Or | Op::Xor => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Param {
+    Numbered(Box<Expr>),
+    Named(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f64),
+    Param(Param),
+    Neg(Box<Expr>),
+    Call(Func, Box<Expr>),
+    Atan(Box<Expr>, Box<Expr>),
+    Exists(String),
+    Binary(Op, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Item {
+    Word(char, Expr),
+    Assign(Param, Expr),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Keyword {
+    Sub,
+    EndSub,
+    Call,
+    If,
+    ElseIf,
+    Else,
+    EndIf,
+    While,
+    EndWhile,
+    Do,
+    Repeat,
+    EndRepeat,
+    Return,
+    Break,
+    Continue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    /// Blank, comment-only and `%` lines, kept as written
+    Blank,
+    Code(Vec<Item>),
+    Control {
+        label: String,
+        keyword: Keyword,
+        args: Vec<Expr>,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Line {
+    /// Source line number, starting at 1
+    number: usize,
+    text: String,
+    /// Comments, kept on rewritten lines
+    comments: String,
+    /// Starts with the block delete `/`
+    optional: bool,
+    kind: Kind,
+}
+
+/// Split comments off `text`, and lowercase the rest without whitespace
+fn split_comments(text: &str) -> (String, String) {
+    let mut code = String::new();
+    let mut comments = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '(' => {
+                comments.push('(');
+                for c in chars.by_ref() {
+                    comments.push(c);
+                    if c == ')' {
+                        break;
+                    }
+                }
+            }
+            ';' => {
+                comments.push(';');
+                comments.extend(chars.by_ref());
+            }
+            c if c.is_whitespace() => {}
+            c => code.push(c.to_ascii_lowercase()),
+        }
+    }
+    (code, comments)
+}
+
+fn parse_line(number: usize, text: &str) -> Result<Line, String> {
+    let (mut code, comments) = split_comments(text);
+    let optional = code.starts_with('/');
+    if optional {
+        code.remove(0);
+    }
+    let at = |message: String| format!("line {}: {}", number, message);
+    let kind = if code.is_empty() || code.starts_with('%') {
+        Kind::Blank
+    } else {
+        // A line number may come before an O word
+        let digits = code
+            .strip_prefix('n')
+            .map(|rest| rest.bytes().take_while(u8::is_ascii_digit).count());
+        let control = match digits {
+            Some(n) if code[1 + n..].starts_with('o') => Some(&code[1 + n..]),
+            _ if code.starts_with('o') => Some(code.as_str()),
+            _ => None,
+        };
+        match control {
+            Some(control) => parse_control(&control[1..]).map_err(at)?,
+            None => Kind::Code(parse_items(&code).map_err(at)?),
+        }
+    };
+    Ok(Line {
+        number,
+        text: text.to_string(),
+        comments,
+        optional,
+        kind,
+    })
+}
+
+fn parse_control(text: &str) -> Result<Kind, String> {
+    let mut parser = Parser::new(text);
+    let label = if parser.eat(b'<') {
+        parser.read_until(b'>')?
+    } else {
+        let digits = parser.take_while(|c| c.is_ascii_digit());
+        if digits.is_empty() {
+            return Err("O word without a number or <name>".to_string());
+        }
+        digits.trim_start_matches('0').to_string()
+    };
+    let name = parser.take_while(|c| c.is_ascii_alphabetic());
+    let keyword = match name.as_str() {
+        "sub" => Keyword::Sub,
+        "endsub" => Keyword::EndSub,
+        "call" => Keyword::Call,
+        "if" => Keyword::If,
+        "elseif" => Keyword::ElseIf,
+        "else" => Keyword::Else,
+        "endif" => Keyword::EndIf,
+        "while" => Keyword::While,
+        "endwhile" => Keyword::EndWhile,
+        "do" => Keyword::Do,
+        "repeat" => Keyword::Repeat,
+        "endrepeat" => Keyword::EndRepeat,
+        "return" => Keyword::Return,
+        "break" => Keyword::Break,
+        "continue" => Keyword::Continue,
+        "" => return Err(format!("O{} without a keyword", label)),
+        other => return Err(format!("unknown O-word keyword '{}'", other)),
+    };
+    let mut args = Vec::new();
+    while !parser.done() {
+        args.push(parser.atom()?);
+    }
+    let wanted = match keyword {
+        Keyword::If | Keyword::ElseIf | Keyword::While | Keyword::Repeat => 1..=1,
+        Keyword::Call => 0..=30,
+        Keyword::Return | Keyword::EndSub => 0..=1,
+        _ => 0..=0,
+    };
+    if !wanted.contains(&args.len()) {
+        return Err(format!(
+            "O{} {} takes {} argument(s), got {}",
+            label,
+            name,
+            if wanted.start() == wanted.end() {
+                wanted.start().to_string()
+            } else {
+                format!("{}-{}", wanted.start(), wanted.end())
+            },
+            args.len()
+        ));
+    }
+    Ok(Kind::Control {
+        label,
+        keyword,
+        args,
+    })
+}
+
+fn parse_items(code: &str) -> Result<Vec<Item>, String> {
+    let mut parser = Parser::new(code);
+    let mut items = Vec::new();
+    while let Some(c) = parser.peek() {
+        if c == b'#' {
+            parser.pos += 1;
+            let param = parser.param()?;
+            if !parser.eat(b'=') {
+                return Err("parameter outside an assignment or word".to_string());
+            }
+            items.push(Item::Assign(param, parser.atom()?));
+        } else if c.is_ascii_alphabetic() {
+            parser.pos += 1;
+            items.push(Item::Word((c as char).to_ascii_uppercase(), parser.atom()?));
+        } else {
+            return Err(format!("unexpected '{}'", c as char));
+        }
+    }
+    Ok(items)
+}
+
+struct Parser<'a> {
+    text: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text: text.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn done(&self) -> bool {
+        self.pos >= self.text.len()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn eat_str(&mut self, s: &str) -> bool {
+        let found = self.text[self.pos..].starts_with(s.as_bytes());
+        if found {
+            self.pos += s.len();
+        }
+        found
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), String> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(format!("expected '{}'", c as char))
+        }
+    }
+
+    fn take_while(&mut self, keep: impl Fn(u8) -> bool) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(&keep) {
+            self.pos += 1;
+        }
+        String::from_utf8_lossy(&self.text[start..self.pos]).into_owned()
+    }
+
+    fn read_until(&mut self, end: u8) -> Result<String, String> {
+        let text = self.take_while(|c| c != end);
+        self.expect(end)?;
+        Ok(text)
+    }
+
+    /// A parameter after its `#`
+    fn param(&mut self) -> Result<Param, String> {
+        if self.eat(b'<') {
+            Ok(Param::Named(self.read_until(b'>')?))
+        } else {
+            Ok(Param::Numbered(Box::new(self.atom()?)))
+        }
+    }
+
+    /// A value: number, parameter, bracketed expression or function
+    fn atom(&mut self) -> Result<Expr, String> {
+        match self.peek() {
+            Some(b'[') => {
+                self.pos += 1;
+                let expr = self.expr(0)?;
+                self.expect(b']')?;
+                Ok(expr)
+            }
+            Some(b'#') => {
+                self.pos += 1;
+                Ok(Expr::Param(self.param()?))
+            }
+            Some(b'-') => {
+                self.pos += 1;
+                Ok(Expr::Neg(Box::new(self.atom()?)))
+            }
+            Some(b'+') => {
+                self.pos += 1;
+                self.atom()
+            }
+            Some(c) if c.is_ascii_digit() || c == b'.' => {
+                let text = self.take_while(|c| c.is_ascii_digit() || c == b'.');
+                text.parse()
+                    .map(Expr::Number)
+                    .map_err(|_| format!("bad number '{}'", text))
+            }
+            Some(c) if c.is_ascii_alphabetic() => {
+                let name = self.take_while(|c| c.is_ascii_alphabetic());
+                let func = match name.as_str() {
+                    "atan" => {
+                        self.expect(b'[')?;
+                        let y = self.expr(0)?;
+                        self.expect(b']')?;
+                        self.expect(b'/')?;
+                        self.expect(b'[')?;
+                        let x = self.expr(0)?;
+                        self.expect(b']')?;
+                        return Ok(Expr::Atan(Box::new(y), Box::new(x)));
+                    }
+                    "exists" => {
+                        self.expect(b'[')?;
+                        self.expect(b'#')?;
+                        self.expect(b'<')?;
+                        let name = self.read_until(b'>')?;
+                        self.expect(b']')?;
+                        return Ok(Expr::Exists(name));
+                    }
+                    "abs" => Func::Abs,
+                    "acos" => Func::Acos,
+                    "asin" => Func::Asin,
+                    "cos" => Func::Cos,
+                    "exp" => Func::Exp,
+                    "fix" => Func::Fix,
+                    "fup" => Func::Fup,
+                    "ln" => Func::Ln,
+                    "round" => Func::Round,
+                    "sin" => Func::Sin,
+                    "sqrt" => Func::Sqrt,
+                    "tan" => Func::Tan,
+                    other => return Err(format!("unknown function '{}'", other)),
+                };
+                self.expect(b'[')?;
+                let arg = self.expr(0)?;
+                self.expect(b']')?;
+                Ok(Expr::Call(func, Box::new(arg)))
+            }
+            Some(c) => Err(format!("expected a value, found '{}'", c as char)),
+            None => Err("expected a value at the end of the line".to_string()),
+        }
+    }
+
+    fn operator(&mut self) -> Option<Op> {
+        const OPS: [(&str, Op); 15] = [
+            ("**", Op::Pow),
+            ("*", Op::Mul),
+            ("/", Op::Div),
+            ("mod", Op::Mod),
+            ("+", Op::Add),
+            ("-", Op::Sub),
+            ("eq", Op::Eq),
+            ("ne", Op::Ne),
+            ("gt", Op::Gt),
+            ("ge", Op::Ge),
+            ("lt", Op::Lt),
+            ("le", Op::Le),
+            ("and", Op::And),
+            ("or", Op::Or),
+            ("xor", Op::Xor),
+        ];
+        OPS.iter()
+            .find(|(text, _)| self.text[self.pos..].starts_with(text.as_bytes()))
+            .map(|&(_, op)| op)
+    }
+
+    fn expr(&mut self, min_precedence: u8) -> Result<Expr, String> {
+        let mut lhs = self.atom()?;
+        while let Some(op) = self.operator() {
+            if op.precedence() < min_precedence {
+                break;
+            }
+            self.eat_str(match op {
+                Op::Pow => "**",
+                Op::Mul => "*",
+                Op::Div => "/",
+                Op::Mod => "mod",
+                Op::Add => "+",
+                Op::Sub => "-",
+                Op::Eq => "eq",
+                Op::Ne => "ne",
+                Op::Gt => "gt",
+                Op::Ge => "ge",
+                Op::Lt => "lt",
+                Op::Le => "le",
+                Op::And => "and",
+                Op::Or => "or",
+                Op::Xor => "xor",
+            });
+            let rhs = self.expr(op.precedence() + 1)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Program structure
+
+#[derive(Debug, Clone)]
+enum Stmt {
+    /// A G-code line (or a blank one), by index into the parsed lines
+    Line(usize),
+    If {
+        /// Condition (`None` for `else`) and body of each branch
+        branches: Vec<(Option<Expr>, Vec<Stmt>)>,
+    },
+    While {
+        label: String,
+        cond: Expr,
+        body: Vec<Stmt>,
+        /// `do` ... `while`: the body runs before the first check
+        test_after: bool,
+    },
+    Repeat {
+        label: String,
+        count: Expr,
+        body: Vec<Stmt>,
+    },
+    Call {
+        line: usize,
+        name: String,
+        args: Vec<Expr>,
+    },
+    Return(Option<Expr>),
+    Break(String),
+    Continue(String),
+}
+
+struct Sub {
+    body: Vec<Stmt>,
+    /// Value given on `endsub`
+    end_value: Option<Expr>,
+}
+
+fn keyword_name(kind: &Kind) -> String {
+    match kind {
+        Kind::Control { label, keyword, .. } => {
+            format!("O{} {}", label, format!("{:?}", keyword).to_lowercase())
+        }
+        _ => "line".to_string(),
+    }
+}
+
+fn control(line: &Line) -> Option<(&str, Keyword, &[Expr])> {
+    match &line.kind {
+        Kind::Control {
+            label,
+            keyword,
+            args,
+        } => Some((label, *keyword, args)),
+        _ => None,
+    }
+}
+
+/// Parse statements from `position` until a line that closes an enclosing block
+///
+/// Returns the statements and the index of that closing line, or `None` at the
+/// end of the program. A `while` closes the block when its label is `do_label`.
+fn parse_block(
+    lines: &[Line],
+    position: &mut usize,
+    subs: &mut HashMap<String, Sub>,
+    do_label: Option<&str>,
+    top_level: bool,
+) -> Result<(Vec<Stmt>, Option<usize>), String> {
+    let mut stmts = Vec::new();
+    while *position < lines.len() {
+        let index = *position;
+        let line = &lines[index];
+        *position += 1;
+        let Some((label, keyword, args)) = control(line) else {
+            stmts.push(Stmt::Line(index));
+            continue;
+        };
+        let at = |message: &str| format!("line {}: {}", line.number, message);
+        let label = label.to_string();
+        let arg = |i: usize| args.get(i).cloned();
+        match keyword {
+            Keyword::ElseIf
+            | Keyword::Else
+            | Keyword::EndIf
+            | Keyword::EndWhile
+            | Keyword::EndRepeat
+            | Keyword::EndSub => return Ok((stmts, Some(index))),
+            Keyword::While if do_label == Some(label.as_str()) => return Ok((stmts, Some(index))),
+            Keyword::Sub => {
+                if !top_level {
+                    return Err(at("subprograms can only be defined at the top level"));
+                }
+                let (body, end) = parse_block(lines, position, subs, None, false)?;
+                let end = closing(lines, end, &label, &[Keyword::EndSub], line)?;
+                let end_value = control(&lines[end]).and_then(|(_, _, args)| args.first().cloned());
+                subs.insert(label, Sub { body, end_value });
+            }
+            Keyword::If => {
+                let mut branches = Vec::new();
+                let mut cond = arg(0);
+                loop {
+                    let (body, end) = parse_block(lines, position, subs, None, false)?;
+                    let end = closing(
+                        lines,
+                        end,
+                        &label,
+                        &[Keyword::ElseIf, Keyword::Else, Keyword::EndIf],
+                        line,
+                    )?;
+                    branches.push((cond.take(), body));
+                    match control(&lines[end]) {
+                        Some((_, Keyword::ElseIf, args)) => cond = args.first().cloned(),
+                        Some((_, Keyword::Else, _)) => cond = None,
+                        _ => break,
+                    }
+                    if branches.last().is_some_and(|(cond, _)| cond.is_none()) {
+                        return Err(at("branch after O-word else"));
+                    }
+                }
+                stmts.push(Stmt::If { branches });
+            }
+            Keyword::While => {
+                let (body, end) = parse_block(lines, position, subs, None, false)?;
+                closing(lines, end, &label, &[Keyword::EndWhile], line)?;
+                stmts.push(Stmt::While {
+                    label,
+                    cond: arg(0).unwrap_or(Expr::Number(0.0)),
+                    body,
+                    test_after: false,
+                });
+            }
+            Keyword::Do => {
+                let (body, end) = parse_block(lines, position, subs, Some(&label), false)?;
+                let end = closing(lines, end, &label, &[Keyword::While], line)?;
+                let cond = control(&lines[end])
+                    .and_then(|(_, _, args)| args.first().cloned())
+                    .unwrap_or(Expr::Number(0.0));
+                stmts.push(Stmt::While {
+                    label,
+                    cond,
+                    body,
+                    test_after: true,
+                });
+            }
+            Keyword::Repeat => {
+                let (body, end) = parse_block(lines, position, subs, None, false)?;
+                closing(lines, end, &label, &[Keyword::EndRepeat], line)?;
+                stmts.push(Stmt::Repeat {
+                    label,
+                    count: arg(0).unwrap_or(Expr::Number(0.0)),
+                    body,
+                });
+            }
+            Keyword::Call => stmts.push(Stmt::Call {
+                line: index,
+                name: label,
+                args: args.to_vec(),
+            }),
+            Keyword::Return => stmts.push(Stmt::Return(arg(0))),
+            Keyword::Break => stmts.push(Stmt::Break(label)),
+            Keyword::Continue => stmts.push(Stmt::Continue(label)),
+        }
+    }
+    Ok((stmts, None))
+}
+
+/// Check that the line at `end` closes the block opened by `opener`
+fn closing(
+    lines: &[Line],
+    end: Option<usize>,
+    label: &str,
+    keywords: &[Keyword],
+    opener: &Line,
+) -> Result<usize, String> {
+    let matched = end.filter(|&end| {
+        control(&lines[end])
+            .is_some_and(|(l, keyword, _)| l == label && keywords.contains(&keyword))
+    });
+    matched.ok_or_else(|| match end {
+        Some(end) => format!(
+            "line {}: {} does not close {} from line {}",
+            lines[end].number,
+            keyword_name(&lines[end].kind),
+            keyword_name(&opener.kind),
+            opener.number
+        ),
+        None => format!(
+            "line {}: {} is never closed",
+            opener.number,
+            keyword_name(&opener.kind)
+        ),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Running
+
+enum Flow {
+    Normal,
+    Break(String),
+    Continue(String),
+    Return,
+    /// M2 or M30
+    End,
+}
+
+/// Parameters local to one subprogram call
+#[derive(Default)]
+struct Frame {
+    numbered: [f64; 30],
+    named: HashMap<String, f64>,
+}
+
+/// An active canned cycle
+#[derive(Debug, Clone, Copy)]
+struct Cycle {
+    code: u32,
+    /// Z before the cycle started, for G98 retracts
+    initial_z: Option<f64>,
+    r: Option<f64>,
+    z: Option<f64>,
+    q: Option<f64>,
+    p: f64,
+}
+
+/// Modal state the canned cycle expansion needs
+#[derive(Debug, Clone, Copy)]
+struct Machine {
+    position: [Option<f64>; 3],
+    incremental: bool,
+    metric: bool,
+    xy_plane: bool,
+    /// G99: retract to R instead of the starting Z
+    retract_to_r: bool,
+    feed: Option<f64>,
+    cycle: Option<Cycle>,
+}
+
+impl Default for Machine {
+    fn default() -> Self {
+        Self {
+            position: [None; 3],
+            incremental: false,
+            metric: true,
+            xy_plane: true,
+            retract_to_r: false,
+            feed: None,
+            cycle: None,
+        }
+    }
+}
+
+struct Flattener<'a> {
+    lines: &'a [Line],
+    subs: &'a HashMap<String, Sub>,
+    options: &'a FlattenOptions,
+    output: Vec<String>,
+    warnings: Vec<FlattenWarning>,
+    calls_expanded: usize,
+    cycles_expanded: usize,
+    globals: HashMap<u32, f64>,
+    named_globals: HashMap<String, f64>,
+    frames: Vec<Frame>,
+    iterations: usize,
+    machine: Machine,
+}
+
+fn truth(value: f64) -> bool {
+    value != 0.0
+}
+
+fn flag(value: bool) -> f64 {
+    if value {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+impl Flattener<'_> {
+    fn warn(&mut self, line: usize, message: String) {
+        let warning = FlattenWarning { line, message };
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
+        }
+    }
+
+    fn emit(&mut self, text: String) -> Result<(), String> {
+        if self.output.len() >= self.options.max_lines {
+            return Err(format!(
+                "flattened program is longer than {} lines; does a loop never end?",
+                self.options.max_lines
+            ));
+        }
+        self.output.push(text);
+        Ok(())
+    }
+
+    /// Keep `line` as a comment and warn about it
+    fn untranslated(&mut self, line: &Line, message: String) -> Result<(), String> {
+        self.warn(line.number, message);
+        let text = line.text.trim().replace(['(', ')'], "");
+        self.emit(format!("(untranslated: {})", text))
+    }
+
+    fn count_iteration(&mut self, line: usize) -> Result<(), String> {
+        self.iterations += 1;
+        if self.iterations > MAX_ITERATIONS {
+            return Err(format!(
+                "line {}: more than {} loop iterations; does the loop never end?",
+                line, MAX_ITERATIONS
+            ));
+        }
+        Ok(())
+    }
+
+    fn frame(&mut self) -> &mut Frame {
+        self.frames.last_mut().expect("main program frame")
+    }
+
+    fn read(&mut self, param: &Param, line: usize) -> Result<f64, String> {
+        match param {
+            Param::Numbered(index) => {
+                let number = self.index(index, line)?;
+                if number <= 30 {
+                    return Ok(self.frame().numbered[number as usize - 1]);
+                }
+                match self.globals.get(&number) {
+                    Some(&value) => Ok(value),
+                    None if number >= MACHINE_PARAMETERS => {
+                        self.warn(
+                            line,
+                            format!("#{} is machine state and was read as 0", number),
+                        );
+                        Ok(0.0)
+                    }
+                    None => Ok(0.0),
+                }
+            }
+            Param::Named(name) if name.starts_with('_') => match self.named_globals.get(name) {
+                Some(&value) => Ok(value),
+                None => {
+                    if !name.starts_with("_value") {
+                        self.warn(
+                                line,
+                                format!(
+                                    "#<{}> is not set by the program (machine state?) and was read as 0",
+                                    name
+                                ),
+                            );
+                    }
+                    Ok(0.0)
+                }
+            },
+            Param::Named(name) => self
+                .frame()
+                .named
+                .get(name)
+                .copied()
+                .ok_or_else(|| format!("#<{}> is used before it is set", name)),
+        }
+    }
+
+    fn index(&mut self, index: &Expr, line: usize) -> Result<u32, String> {
+        let value = self.eval(index, line)?.round();
+        if value < 1.0 {
+            return Err(format!("parameter number {} is not positive", value));
+        }
+        Ok(value as u32)
+    }
+
+    fn eval(&mut self, expr: &Expr, line: usize) -> Result<f64, String> {
+        Ok(match expr {
+            Expr::Number(value) => *value,
+            Expr::Param(param) => self.read(param, line)?,
+            Expr::Neg(inner) => -self.eval(inner, line)?,
+            Expr::Exists(name) => flag(if name.starts_with('_') {
+                self.named_globals.contains_key(name)
+            } else {
+                self.frame().named.contains_key(name)
+            }),
+            Expr::Atan(y, x) => {
+                let (y, x) = (self.eval(y, line)?, self.eval(x, line)?);
+                y.atan2(x).to_degrees()
+            }
+            Expr::Call(func, arg) => {
+                let value = self.eval(arg, line)?;
+                let domain = |ok: bool| {
+                    if ok {
+                        Ok(())
+                    } else {
+                        Err(format!("{:?}[{}] is undefined", func, value).to_uppercase())
+                    }
+                };
+                match func {
+                    Func::Abs => value.abs(),
+                    Func::Acos => {
+                        domain((-1.0..=1.0).contains(&value))?;
+                        value.acos().to_degrees()
+                    }
+                    Func::Asin => {
+                        domain((-1.0..=1.0).contains(&value))?;
+                        value.asin().to_degrees()
+                    }
+                    Func::Cos => value.to_radians().cos(),
+                    Func::Exp => value.exp(),
+                    Func::Fix => value.floor(),
+                    Func::Fup => value.ceil(),
+                    Func::Ln => {
+                        domain(value > 0.0)?;
+                        value.ln()
+                    }
+                    Func::Round => value.round(),
+                    Func::Sin => value.to_radians().sin(),
+                    Func::Sqrt => {
+                        domain(value >= 0.0)?;
+                        value.sqrt()
+                    }
+                    Func::Tan => value.to_radians().tan(),
+                }
+            }
+            Expr::Binary(op, lhs, rhs) => {
+                let (a, b) = (self.eval(lhs, line)?, self.eval(rhs, line)?);
+                match op {
+                    Op::Pow => a.powf(b),
+                    Op::Mul => a * b,
+                    Op::Div | Op::Mod if b == 0.0 => return Err("division by zero".to_string()),
+                    Op::Div => a / b,
+                    Op::Mod => a.rem_euclid(b),
+                    Op::Add => a + b,
+                    Op::Sub => a - b,
+                    Op::Eq => flag(a == b),
+                    Op::Ne => flag(a != b),
+                    Op::Gt => flag(a > b),
+                    Op::Ge => flag(a >= b),
+                    Op::Lt => flag(a < b),
+                    Op::Le => flag(a <= b),
+                    Op::And => flag(truth(a) && truth(b)),
+                    Op::Or => flag(truth(a) || truth(b)),
+                    Op::Xor => flag(truth(a) != truth(b)),
+                }
+            }
+        })
+    }
+
+    fn write(&mut self, param: &Param, value: f64, line: usize) -> Result<(), String> {
+        match param {
+            Param::Numbered(index) => {
+                let number = self.index(index, line)?;
+                if number <= 30 {
+                    self.frame().numbered[number as usize - 1] = value;
+                } else {
+                    self.globals.insert(number, value);
+                }
+            }
+            Param::Named(name) if name.starts_with('_') => {
+                self.named_globals.insert(name.clone(), value);
+            }
+            Param::Named(name) => {
+                self.frame().named.insert(name.clone(), value);
+            }
+        }
+        Ok(())
+    }
+
+    fn run(&mut self, stmts: &[Stmt]) -> Result<Flow, String> {
+        for stmt in stmts {
+            let flow = match stmt {
+                Stmt::Line(index) => self.line(*index)?,
+                Stmt::If { branches } => {
+                    let mut flow = Flow::Normal;
+                    for (cond, body) in branches {
+                        let taken = match cond {
+                            Some(cond) => truth(self.eval(cond, 0)?),
+                            None => true,
+                        };
+                        if taken {
+                            flow = self.run(body)?;
+                            break;
+                        }
+                    }
+                    flow
+                }
+                Stmt::While {
+                    label,
+                    cond,
+                    body,
+                    test_after,
+                } => {
+                    let mut flow = Flow::Normal;
+                    let mut first = *test_after;
+                    while first || truth(self.eval(cond, 0)?) {
+                        first = false;
+                        self.count_iteration(0)?;
+                        match self.run(body)? {
+                            Flow::Break(l) if &l == label => break,
+                            Flow::Normal => {}
+                            Flow::Continue(l) if &l == label => {}
+                            other => {
+                                flow = other;
+                                break;
+                            }
+                        }
+                    }
+                    flow
+                }
+                Stmt::Repeat { label, count, body } => {
+                    let count = self.eval(count, 0)?.round().max(0.0) as usize;
+                    let mut flow = Flow::Normal;
+                    for _ in 0..count {
+                        self.count_iteration(0)?;
+                        match self.run(body)? {
+                            Flow::Break(l) if &l == label => break,
+                            Flow::Normal => {}
+                            Flow::Continue(l) if &l == label => {}
+                            other => {
+                                flow = other;
+                                break;
+                            }
+                        }
+                    }
+                    flow
+                }
+                Stmt::Call { line, name, args } => self.call(*line, name, args)?,
+                Stmt::Return(value) => {
+                    self.set_return_value(value.as_ref())?;
+                    Flow::Return
+                }
+                Stmt::Break(label) => Flow::Break(label.clone()),
+                Stmt::Continue(label) => Flow::Continue(label.clone()),
+            };
+            if !matches!(flow, Flow::Normal) {
+                return Ok(flow);
+            }
+        }
+        Ok(Flow::Normal)
+    }
+
+    fn set_return_value(&mut self, value: Option<&Expr>) -> Result<(), String> {
+        let returned = match value {
+            Some(value) => Some(self.eval(value, 0)?),
+            None => None,
+        };
+        self.named_globals
+            .insert("_value".to_string(), returned.unwrap_or(0.0));
+        self.named_globals
+            .insert("_value_returned".to_string(), flag(returned.is_some()));
+        Ok(())
+    }
+
+    fn call(&mut self, index: usize, name: &str, args: &[Expr]) -> Result<Flow, String> {
+        let line = &self.lines[index];
+        let Some(sub) = self.subs.get(name) else {
+            self.untranslated(
+                line,
+                format!(
+                    "subprogram O{} is not defined in this program and was not expanded",
+                    name
+                ),
+            )?;
+            return Ok(Flow::Normal);
+        };
+        if self.frames.len() > MAX_CALL_DEPTH {
+            return Err(format!(
+                "line {}: subprograms nested more than {} deep",
+                line.number, MAX_CALL_DEPTH
+            ));
+        }
+        let mut frame = Frame::default();
+        for (slot, arg) in frame.numbered.iter_mut().zip(args) {
+            *slot = self
+                .eval(arg, line.number)
+                .map_err(|e| format!("line {}: {}", line.number, e))?;
+        }
+        self.calls_expanded += 1;
+        self.frames.push(frame);
+        let flow = self.run(&sub.body);
+        let flow = match flow {
+            Ok(Flow::Normal) => self
+                .set_return_value(sub.end_value.as_ref())
+                .map(|_| Flow::Normal),
+            Ok(Flow::Return) => Ok(Flow::Normal),
+            Ok(Flow::Break(label) | Flow::Continue(label)) => Err(format!(
+                "line {}: O{} break or continue outside its loop in O{}",
+                line.number, label, name
+            )),
+            other => other,
+        };
+        self.frames.pop();
+        flow
+    }
+
+    fn line(&mut self, index: usize) -> Result<Flow, String> {
+        let line = &self.lines[index];
+        let Kind::Code(items) = &line.kind else {
+            self.emit(line.text.trim_start().to_string())?;
+            return Ok(Flow::Normal);
+        };
+        let at = |e: String| format!("line {}: {}", line.number, e);
+
+        // Every value is read before any parameter on the line is set
+        let mut words = Vec::new();
+        let mut assignments = Vec::new();
+        let mut computed = false;
+        for item in items {
+            match item {
+                Item::Word(letter, expr) => {
+                    computed |= !matches!(expr, Expr::Number(_));
+                    words.push((*letter, self.eval(expr, line.number).map_err(at)?));
+                }
+                Item::Assign(param, expr) => {
+                    computed = true;
+                    assignments.push((param, self.eval(expr, line.number).map_err(at)?));
+                }
+            }
+        }
+        for (param, value) in assignments {
+            self.write(param, value, line.number).map_err(at)?;
+        }
+
+        let has = |letter: char, code: f64| words.contains(&(letter, code));
+        if has('M', 98.0) || has('M', 99.0) {
+            self.untranslated(line, "M98/M99 subprograms are not expanded".to_string())?;
+            return Ok(Flow::Normal);
+        }
+        let ends = has('M', 2.0) || has('M', 30.0);
+
+        if self.options.expand_canned_cycles && self.cycle_line(line, &mut words)? {
+            return Ok(if ends { Flow::End } else { Flow::Normal });
+        }
+        self.track(&words);
+        if computed || self.options.expand_canned_cycles && strip_retract_modes(&mut words) {
+            if !words.is_empty() || !line.comments.is_empty() {
+                self.emit(format_line(line, &words))?;
+            }
+        } else {
+            self.emit(line.text.trim_start().to_string())?;
+        }
+        Ok(if ends { Flow::End } else { Flow::Normal })
+    }
+
+    /// Follow positions and modes through a line that is written out as is
+    fn track(&mut self, words: &[(char, f64)]) {
+        let machine = &mut self.machine;
+        let mut set_position = false;
+        let mut machine_move = false;
+        for &(letter, value) in words {
+            match (letter, value) {
+                ('G', 0.0..=3.0) if value.fract() == 0.0 => machine.cycle = None,
+                ('G', 80.0) => machine.cycle = None,
+                ('G', 17.0) => machine.xy_plane = true,
+                ('G', 18.0 | 19.0) => machine.xy_plane = false,
+                ('G', 20.0 | 21.0) => {
+                    let metric = value == 21.0;
+                    if metric != machine.metric {
+                        let factor = if metric { 25.4 } else { 1.0 / 25.4 };
+                        for axis in machine.position.iter_mut().flatten() {
+                            *axis *= factor;
+                        }
+                    }
+                    machine.metric = metric;
+                }
+                ('G', 90.0) => machine.incremental = false,
+                ('G', 91.0) => machine.incremental = true,
+                ('G', 98.0) => machine.retract_to_r = false,
+                ('G', 99.0) => machine.retract_to_r = true,
+                ('G', 92.0) => set_position = true,
+                ('G', 28.0 | 30.0 | 53.0) | ('G', 38.0..39.0) => machine_move = true,
+                ('G', 73.0 | 81.0..=89.0) => {
+                    machine.position[2] = None;
+                }
+                ('F', f) => machine.feed = Some(f),
+                _ => {}
+            }
+        }
+        for &(letter, value) in words {
+            let axis = match letter {
+                'X' => 0,
+                'Y' => 1,
+                'Z' => 2,
+                _ => continue,
+            };
+            machine.position[axis] = if machine_move {
+                None
+            } else if set_position || !machine.incremental {
+                Some(value)
+            } else {
+                machine.position[axis].map(|p| p + value)
+            };
+        }
+        if machine_move && words.iter().any(|&w| w == ('G', 28.0) || w == ('G', 30.0)) {
+            machine.position = [None; 3];
+        }
+    }
+
+    /// Write out a line that runs a canned cycle; `false` if it does not
+    fn cycle_line(&mut self, line: &Line, words: &mut [(char, f64)]) -> Result<bool, String> {
+        let code = words
+            .iter()
+            .find_map(|&(letter, value)| match (letter, value) {
+                ('G', 73.0) => Some(73),
+                ('G', 81.0..=89.0) if value.fract() == 0.0 => Some(value as u32),
+                _ => None,
+            });
+        let axis_words = words
+            .iter()
+            .any(|(letter, _)| matches!(letter, 'X' | 'Y' | 'Z'));
+        let stops_cycle = words.iter().any(|&(letter, value)| {
+            letter == 'G' && (value == 80.0 || (0.0..=3.0).contains(&value) && value.fract() == 0.0)
+        });
+        let code = match (code, self.machine.cycle) {
+            (Some(code), _) => code,
+            (None, Some(cycle)) if axis_words && !stops_cycle => cycle.code,
+            _ => return Ok(false),
+        };
+
+        // Modes set on the cycle line apply to it
+        let mut prefix = Vec::new();
+        for &(letter, value) in words.iter() {
+            let cycle_word = match letter {
+                'G' => {
+                    value == 73.0
+                        || (81.0..=89.0).contains(&value)
+                        || value == 98.0
+                        || value == 99.0
+                }
+                'X' | 'Y' | 'Z' | 'R' | 'Q' | 'P' | 'L' | 'F' => true,
+                _ => false,
+            };
+            match (letter, value) {
+                ('G', 98.0) => self.machine.retract_to_r = false,
+                ('G', 99.0) => self.machine.retract_to_r = true,
+                _ => {}
+            }
+            if !cycle_word {
+                prefix.push((letter, value));
+            }
+        }
+        self.track(&prefix);
+        if !prefix.is_empty() {
+            self.emit(format_line(line, &prefix))?;
+        }
+
+        if !matches!(code, 73 | 81 | 82 | 83 | 85 | 89) {
+            self.machine.cycle = None;
+            self.machine.position[2] = None;
+            self.untranslated(
+                line,
+                format!("G{} cycle cannot be written as simple moves", code),
+            )?;
+            return Ok(true);
+        }
+        if !self.machine.xy_plane {
+            self.machine.cycle = None;
+            self.machine.position[2] = None;
+            self.untranslated(
+                line,
+                "canned cycles are only expanded in the XY plane (G17)".to_string(),
+            )?;
+            return Ok(true);
+        }
+
+        let word = |letter: char| words.iter().find(|w| w.0 == letter).map(|w| w.1);
+        let mut cycle = match self.machine.cycle {
+            Some(cycle) if cycle.code == code => cycle,
+            previous => Cycle {
+                code,
+                initial_z: previous.map_or(self.machine.position[2], |cycle| cycle.initial_z),
+                r: previous.and_then(|cycle| cycle.r),
+                z: previous.and_then(|cycle| cycle.z),
+                q: previous.and_then(|cycle| cycle.q),
+                p: previous.map_or(0.0, |cycle| cycle.p),
+            },
+        };
+        cycle.r = word('R').or(cycle.r);
+        cycle.z = word('Z').or(cycle.z);
+        cycle.q = word('Q').map(f64::abs).or(cycle.q);
+        cycle.p = word('P').unwrap_or(cycle.p);
+        self.machine.cycle = Some(cycle);
+
+        let (Some(r), Some(z)) = (cycle.r, cycle.z) else {
+            self.untranslated(line, format!("G{} cycle without R and Z", code))?;
+            return Ok(true);
+        };
+        if matches!(code, 73 | 83) && !cycle.q.is_some_and(|q| q > 0.0) {
+            return Err(format!(
+                "line {}: G{} cycle needs a positive Q",
+                line.number, code
+            ));
+        }
+        let repeats = word('L').map_or(1, |l| l.round().max(0.0) as usize);
+        let [x0, y0, z0] = self.machine.position;
+        let incremental = self.machine.incremental;
+        let needs_start = incremental || word('X').is_none() || word('Y').is_none();
+        if needs_start && (x0.is_none() || y0.is_none()) || z0.is_none() && incremental {
+            self.untranslated(
+                line,
+                "canned cycle starts from an unknown position".to_string(),
+            )?;
+            return Ok(true);
+        }
+        let current_z = z0.unwrap_or(r);
+        let r_abs = if incremental { current_z + r } else { r };
+        let bottom = if incremental { r_abs + z } else { z };
+        if bottom > r_abs {
+            self.warn(
+                line.number,
+                "bottom of the canned cycle is above its R plane".to_string(),
+            );
+        }
+        let initial_z = cycle.initial_z.unwrap_or_else(|| {
+            self.warn(
+                line.number,
+                "height before the canned cycle is unknown; retracting to R".to_string(),
+            );
+            r_abs
+        });
+        cycle.initial_z = Some(initial_z);
+        self.machine.cycle = Some(cycle);
+        let clear_z = if self.machine.retract_to_r {
+            r_abs
+        } else {
+            initial_z.max(r_abs)
+        };
+        let clearance = if self.machine.metric {
+            PECK_CLEARANCE_MM
+        } else {
+            PECK_CLEARANCE_MM / 25.4
+        };
+        if let Some(f) = word('F') {
+            self.machine.feed = Some(f);
+        }
+        if self.machine.feed.is_none() {
+            self.warn(line.number, "canned cycle has no feed rate".to_string());
+        }
+
+        let feed = word('F');
+        let mut moves: Vec<String> = Vec::new();
+        let z_move = |moves: &mut Vec<String>, g: u8, z: f64| {
+            moves.push(format!("G{} Z{}", g, format_gcode_number(z, 4)))
+        };
+        let (mut x, mut y, mut z_now) = (x0.unwrap_or(0.0), y0.unwrap_or(0.0), current_z);
+        for repeat in 0..repeats {
+            if incremental {
+                x += word('X').unwrap_or(0.0);
+                y += word('Y').unwrap_or(0.0);
+            } else {
+                x = word('X').unwrap_or(x);
+                y = word('Y').unwrap_or(y);
+            }
+            if z_now < r_abs {
+                z_move(&mut moves, 0, r_abs);
+            }
+            moves.push(format!(
+                "G0 X{} Y{}",
+                format_gcode_number(x, 4),
+                format_gcode_number(y, 4)
+            ));
+            z_move(&mut moves, 0, r_abs);
+            let feed_text = |first: bool| {
+                feed.filter(|_| first).map_or(String::new(), |f| {
+                    format!(" F{}", format_gcode_number(f, 4))
+                })
+            };
+            let first_feed = moves.len();
+            match code {
+                73 | 83 => {
+                    let q = cycle.q.unwrap_or(1.0);
+                    let mut depth = r_abs;
+                    loop {
+                        let next = (depth - q).max(bottom);
+                        if code == 83 && depth < r_abs {
+                            z_move(&mut moves, 0, depth + clearance);
+                        }
+                        z_move(&mut moves, 1, next);
+                        depth = next;
+                        if depth <= bottom {
+                            break;
+                        }
+                        if code == 83 {
+                            z_move(&mut moves, 0, r_abs);
+                        } else {
+                            z_move(&mut moves, 0, depth + clearance);
+                        }
+                    }
+                }
+                _ => z_move(&mut moves, 1, bottom),
+            }
+            if matches!(code, 82 | 89) && cycle.p > 0.0 {
+                moves.push(format!("G4 P{}", format_gcode_number(cycle.p, 4)));
+            }
+            if matches!(code, 85 | 89) {
+                z_move(&mut moves, 1, r_abs);
+                z_now = r_abs;
+            }
+            if z_now != clear_z || !matches!(code, 85 | 89) {
+                z_move(&mut moves, 0, clear_z);
+            }
+            z_now = clear_z;
+            if let Some(first) = moves.get_mut(first_feed) {
+                first.push_str(&feed_text(repeat == 0));
+            }
+            self.cycles_expanded += 1;
+        }
+
+        if incremental {
+            self.emit("G90".to_string())?;
+        }
+        for text in moves {
+            self.emit(text)?;
+        }
+        if incremental {
+            self.emit("G91".to_string())?;
+        }
+        self.machine.position = [Some(x), Some(y), Some(z_now)];
+        Ok(true)
+    }
+}
+
+/// Drop G98/G99, which only mean something to canned cycles; `true` if any were
+fn strip_retract_modes(words: &mut Vec<(char, f64)>) -> bool {
+    let before = words.len();
+    words.retain(|&w| w != ('G', 98.0) && w != ('G', 99.0));
+    words.len() != before
+}
+
+fn format_line(line: &Line, words: &[(char, f64)]) -> String {
+    let mut text = String::new();
+    if line.optional {
+        text.push('/');
+    }
+    let codes: Vec<String> = words
+        .iter()
+        .map(|(letter, value)| format!("{}{}", letter, format_gcode_number(*value, 4)))
+        .collect();
+    text.push_str(&codes.join(" "));
+    if !line.comments.is_empty() {
+        if !codes.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(&line.comments);
+    }
+    text
+}
@@ -5,6 +5,7 @@
 //! and edge-cut operations using CSG boolean geometry.
 
 use crate::hatch_generator;
+use crate::preview::GeneratorPreview;
 use anyhow::Result;
 use cavalier_contours::polyline::{PlineSource, PlineSourceMut, PlineVertex, Polyline};
 use csgrs::sketch::Sketch;
@@ -122,6 +123,14 @@ impl Default for GerberParameters {
 pub struct GerberConverter;
 
 impl GerberConverter {
+    /// Extents and size of the G-code [`generate`](Self::generate) writes
+    pub fn preview(params: &GerberParameters, gerber_content: &str) -> Result<GeneratorPreview> {
+        Ok(GeneratorPreview::from_program(&Self::generate(
+            params,
+            gerber_content,
+        )?))
+    }
+
     pub fn generate(params: &GerberParameters, gerber_content: &str) -> Result<String> {
         let mut gcode = String::new();
 
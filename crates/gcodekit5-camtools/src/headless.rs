@@ -0,0 +1,177 @@
+//! # Headless CAM Pipeline
+//!
+//! Library entry points for generating G-code from the CAM tools without the GTK
+//! application, for batch scripts and CI. Each function takes the tool's parameters
+//! and returns the G-code as a string; none of them touch UI or global state.
+//!
+//! ```no_run
+//! use gcodekit5_camtools::headless::{self, HeadlessOptions};
+//! use gcodekit5_camtools::BoxParameters;
+//!
+//! let gcode = headless::tabbed_box(BoxParameters::default())?;
+//! let gcode = headless::finish(gcode, &HeadlessOptions::validated())?;
+//! std::fs::write("box.gcode", gcode)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! ## Features that require the UI crates
+//!
+//! - Live preview of generated toolpaths (`gcodekit5-visualizer` / `gcodekit5-ui`)
+//! - Loading into the editor and streaming to a machine (`gcodekit5-ui`,
+//!   `gcodekit5-communication`)
+//! - Unit conversion of dialog fields and remembered dialog values (`gcodekit5-settings`
+//!   via `gcodekit5-ui`); headless parameters are always millimetres and mm/min
+//! - Progress dialogs and cancellation; use the `*_with_progress` methods on
+//!   [`BitmapImageEngraver`] and [`VectorEngraver`] directly for progress callbacks
+
+use crate::bitmap_trace::{BitmapTracer, TraceParameters};
+use crate::drill_press::{DrillPressGenerator, DrillPressParameters};
+use crate::error::{CamToolError, CamToolResult};
+use crate::gerber::{GerberConverter, GerberParameters};
+use crate::jigsaw_puzzle::{JigsawPuzzleMaker, PuzzleParameters};
+use crate::laser_engraver::{BitmapImageEngraver, EngravingParameters};
+use crate::spoilboard_grid::{SpoilboardGridGenerator, SpoilboardGridParameters};
+use crate::spoilboard_surfacing::{SpoilboardSurfacingGenerator, SpoilboardSurfacingParameters};
+use crate::tabbed_box::{BoxParameters, TabbedBoxMaker};
+use crate::validator::{GCodeValidator, ValidatorConfig};
+use crate::vector_engraver::{VectorEngraver, VectorEngravingParameters};
+use gcodekit5_devicedb::{DeviceProfile, TemplateValue};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Post-generation options applied by [`finish`]
+#[derive(Debug, Clone, Default)]
+pub struct HeadlessOptions {
+    /// Run the G-code validator over the output
+    pub validator: Option<ValidatorConfig>,
+    /// Prepend a homing cycle (`$H`), removing any the generator emitted
+    pub home_before: bool,
+    /// Wrap the output in this profile's preamble and postamble
+    pub profile: Option<DeviceProfile>,
+    /// Template values the profile does not have, such as `safe_z`
+    pub template_values: BTreeMap<String, TemplateValue>,
+}
+
+impl HeadlessOptions {
+    /// Options that validate the output against the default machine envelope
+    pub fn validated() -> Self {
+        Self {
+            validator: Some(ValidatorConfig::default()),
+            ..Self::default()
+        }
+    }
+}
+
+/// Apply the post-generation options to generated G-code
+pub fn finish(gcode: String, options: &HeadlessOptions) -> CamToolResult<String> {
+    let gcode = match &options.profile {
+        Some(profile) => profile
+            .wrap_program(&gcode, &options.template_values)
+            .map_err(|e| CamToolError::GenerationFailed(e.to_string()))?,
+        None => gcode,
+    };
+    let gcode = if options.home_before {
+        let stripped: Vec<&str> = gcode.lines().filter(|l| l.trim() != "$H").collect();
+        format!("$H\n{}\n", stripped.join("\n"))
+    } else {
+        gcode
+    };
+
+    if let Some(config) = &options.validator {
+        validate(&gcode, config)?;
+    }
+    Ok(gcode)
+}
+
+/// Validate G-code against the given machine envelope
+pub fn validate(gcode: &str, config: &ValidatorConfig) -> CamToolResult<()> {
+    let lines: Vec<String> = gcode.lines().map(str::to_string).collect();
+    GCodeValidator::new(config.clone())
+        .validate(&lines)
+        .map_err(CamToolError::ValidationFailed)
+}
+
+/// Generate a finger-jointed box
+pub fn tabbed_box(params: BoxParameters) -> CamToolResult<String> {
+    TabbedBoxMaker::new(params)
+        .and_then(|mut maker| maker.generate_gcode())
+        .map_err(CamToolError::InvalidParameters)
+}
+
+/// Generate a jigsaw puzzle
+pub fn jigsaw_puzzle(
+    params: PuzzleParameters,
+    plunge_rate: f32,
+    cut_depth: f32,
+) -> CamToolResult<String> {
+    JigsawPuzzleMaker::new(params)
+        .and_then(|mut maker| maker.generate_gcode(plunge_rate, cut_depth))
+        .map_err(CamToolError::InvalidParameters)
+}
+
+/// Generate a drilling operation
+pub fn drill_press(params: DrillPressParameters) -> CamToolResult<String> {
+    DrillPressGenerator::new(params)
+        .generate()
+        .map_err(generation_failed)
+}
+
+/// Convert Gerber content to isolation routing G-code
+pub fn gerber(params: &GerberParameters, gerber_content: &str) -> CamToolResult<String> {
+    GerberConverter::generate(params, gerber_content).map_err(generation_failed)
+}
+
+/// Generate a spoilboard alignment grid
+pub fn spoilboard_grid(params: SpoilboardGridParameters) -> CamToolResult<String> {
+    SpoilboardGridGenerator::new(params)
+        .generate()
+        .map_err(generation_failed)
+}
+
+/// Generate a spoilboard surfacing pass
+pub fn spoilboard_surfacing(params: SpoilboardSurfacingParameters) -> CamToolResult<String> {
+    SpoilboardSurfacingGenerator::new(params)
+        .generate()
+        .map_err(generation_failed)
+}
+
+/// Engrave a bitmap image file
+pub fn bitmap_engraving<P: AsRef<Path>>(
+    path: P,
+    params: EngravingParameters,
+) -> CamToolResult<String> {
+    let engraver = BitmapImageEngraver::from_file(path, params)
+        .map_err(|e| CamToolError::LoadError(format!("{:#}", e)))?;
+    engraver.generate_gcode().map_err(generation_failed)
+}
+
+/// Engrave or cut a vector file (SVG/DXF)
+pub fn vector_engraving<P: AsRef<Path>>(
+    path: P,
+    params: VectorEngravingParameters,
+) -> CamToolResult<String> {
+    let engraver = VectorEngraver::from_file(path, params)
+        .map_err(|e| CamToolError::LoadError(format!("{:#}", e)))?;
+    engraver.generate_gcode().map_err(generation_failed)
+}
+
+/// Trace a bitmap to vector outlines or centerlines and cut them
+pub fn traced_vector_engraving<P: AsRef<Path>>(
+    path: P,
+    trace: TraceParameters,
+    params: VectorEngravingParameters,
+) -> CamToolResult<String> {
+    let img = image::open(path.as_ref())
+        .map_err(|e| CamToolError::LoadError(format!("{}: {}", path.as_ref().display(), e)))?;
+    let engraver = BitmapTracer::new(trace).to_engraver(&img, params);
+    if engraver.paths.is_empty() {
+        return Err(CamToolError::GenerationFailed(
+            "Tracing found no shapes; check the threshold".to_string(),
+        ));
+    }
+    engraver.generate_gcode().map_err(generation_failed)
+}
+
+fn generation_failed(e: anyhow::Error) -> CamToolError {
+    CamToolError::GenerationFailed(format!("{:#}", e))
+}
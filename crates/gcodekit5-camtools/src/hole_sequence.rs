@@ -0,0 +1,575 @@
+//! # Hole Sequencing
+//!
+//! Reorders the holes of a drilling program so the machine travels the
+//! shortest XY distance between them, instead of following the order the holes
+//! were written in. The order is found with a nearest-neighbour tour improved
+//! by 2-opt, starting from where the machine is before the first hole.
+//!
+//! Two kinds of hole are recognised:
+//!
+//! - canned cycles (G73, G81-G89): each line with X/Y while a cycle is active
+//!   is one hole;
+//! - expanded holes: a G0 move to X/Y, followed by Z-only moves that feed down
+//!   (G1) and come back up to the height the hole started at, with optional
+//!   dwells and pecks.
+//!
+//! Only runs of consecutive holes with the same cycle and parameters (depth,
+//! retract, peck, dwell and feed) are reordered, and only among themselves, so
+//! each group of holes keeps its place in the program. A canned-cycle line that
+//! sets up a cycle or changes a parameter starts a new group, and the setup
+//! moves to whichever hole of the group comes first. Holes are written with
+//! both X and Y, and the first move after a group is given any axis it left to
+//! the modal position.
+//!
+//! Programs without such groups are returned unchanged, as are programs with
+//! incremental (G91) moves or arc planes other than XY (G18/G19). Lines that
+//! use other coordinate frames (G10, G28, G30, G53, G92, G38.x) end a group.
+
+use crate::validator::GCodeValidator;
+use gcodekit5_core::format_gcode_number;
+
+/// Heights are compared with this tolerance (program units)
+const EPSILON: f64 = 1e-6;
+/// Upper bound on 2-opt improvement passes over a tour
+const MAX_PASSES: usize = 100;
+
+/// Result of [`resequence_holes`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct HoleSequenceReport {
+    /// The rewritten program
+    pub program: String,
+    /// Holes in the groups that were reordered
+    pub holes: usize,
+    /// Groups of holes reordered
+    pub groups: usize,
+    /// XY travel to and between those holes in file order (mm)
+    pub travel_before: f64,
+    /// XY travel to and between those holes in the new order (mm)
+    pub travel_after: f64,
+}
+
+impl HoleSequenceReport {
+    /// One-line summary for display
+    pub fn summary(&self) -> String {
+        format!(
+            "{} hole(s) in {} group(s) resequenced; travel {:.1} mm -> {:.1} mm",
+            self.holes, self.groups, self.travel_before, self.travel_after
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HoleKind {
+    Canned,
+    Expanded,
+}
+
+/// One hole: the lines it spans and where it is
+#[derive(Debug, Clone)]
+struct Hole {
+    first: usize,
+    last: usize,
+    x: f64,
+    y: f64,
+}
+
+/// Consecutive holes with the same cycle and parameters
+#[derive(Debug)]
+struct Group {
+    kind: HoleKind,
+    holes: Vec<Hole>,
+    /// Where the machine is before the first hole, if known
+    start: Option<(f64, f64)>,
+    /// Canned-cycle words written before the first hole
+    setup: Vec<(char, String)>,
+    /// Expanded holes: what the body of each hole does, and its feed
+    signature: Vec<String>,
+    feed: Option<f64>,
+    /// mm per program unit
+    scale: f64,
+}
+
+impl Group {
+    fn points(&self) -> Vec<(f64, f64)> {
+        self.holes.iter().map(|h| (h.x, h.y)).collect()
+    }
+}
+
+/// Reorder the holes of a drilling program for the shortest XY travel
+pub fn resequence_holes(program: &str) -> HoleSequenceReport {
+    let lines: Vec<&str> = program.lines().collect();
+    let mut report = HoleSequenceReport {
+        program: program.to_string(),
+        holes: 0,
+        groups: 0,
+        travel_before: 0.0,
+        travel_after: 0.0,
+    };
+
+    let Some(groups) = find_groups(&lines) else {
+        return report;
+    };
+    let mut orders: Vec<(Group, Vec<usize>)> = Vec::new();
+    // Where the machine leaves the previous reordered group, if nothing moved it since
+    let mut carried: Option<((f64, f64), usize)> = None;
+    for mut group in groups {
+        if group.holes.len() < 2 {
+            continue;
+        }
+        if let Some((end, last)) = carried {
+            if group.holes[0].first == last + 1 {
+                group.start = Some(end);
+            }
+        }
+        let points = group.points();
+        let before = path_length(group.start, &points, &(0..points.len()).collect::<Vec<_>>());
+        let order = tour(group.start, &points);
+        let after = path_length(group.start, &points, &order);
+        let last = group.holes[group.holes.len() - 1].last;
+        // A group already in its shortest order is left as written
+        if after >= before - EPSILON {
+            carried = Some((points[points.len() - 1], last));
+            continue;
+        }
+        report.holes += points.len();
+        report.groups += 1;
+        report.travel_before += before * group.scale;
+        report.travel_after += after * group.scale;
+        carried = Some((points[order[order.len() - 1]], last));
+        orders.push((group, order));
+    }
+    if orders.is_empty() {
+        return report;
+    }
+
+    let mut output = Vec::with_capacity(lines.len());
+    let mut index = 0;
+    let mut next_group = orders.iter().peekable();
+    // Original end of the last group, for the next move that leaves an axis modal
+    let mut pending: Option<(f64, f64)> = None;
+    while index < lines.len() {
+        if let Some((group, order)) = next_group.next_if(|(g, _)| g.holes[0].first == index) {
+            emit_group(&lines, group, order, &mut output);
+            let last = &group.holes[group.holes.len() - 1];
+            pending = Some((last.x, last.y));
+            index = last.last + 1;
+            continue;
+        }
+        let line = lines[index];
+        let mut words = GCodeValidator::word_texts(line);
+        let has = |letter: char, words: &[(char, String)]| words.iter().any(|(l, _)| *l == letter);
+        match pending {
+            Some((x, y)) if has('X', &words) != has('Y', &words) => {
+                if has('X', &words) {
+                    words.push(('Y', format_gcode_number(y, 4)));
+                } else {
+                    words.push(('X', format_gcode_number(x, 4)));
+                }
+                output.push(GCodeValidator::rebuild_line(line, &words));
+                pending = None;
+            }
+            _ => {
+                if has('X', &words) || has('Y', &words) {
+                    pending = None;
+                }
+                output.push(line.to_string());
+            }
+        }
+        index += 1;
+    }
+
+    let mut text = output.join("\n");
+    if program.ends_with('\n') {
+        text.push('\n');
+    }
+    report.program = text;
+    report
+}
+
+/// Write a group's holes in `order`
+fn emit_group(lines: &[&str], group: &Group, order: &[usize], output: &mut Vec<String>) {
+    for (position, &hole_index) in order.iter().enumerate() {
+        let hole = &group.holes[hole_index];
+        let line = lines[hole.first];
+        let mut words: Vec<(char, String)> = GCodeValidator::word_texts(line)
+            .into_iter()
+            .filter(|(letter, _)| *letter == 'N')
+            .collect();
+        match group.kind {
+            HoleKind::Canned if position == 0 => words.extend(group.setup.iter().cloned()),
+            HoleKind::Canned => {}
+            HoleKind::Expanded => words.push(('G', "0".to_string())),
+        }
+        words.push(('X', format_gcode_number(hole.x, 4)));
+        words.push(('Y', format_gcode_number(hole.y, 4)));
+        output.push(GCodeValidator::rebuild_line(line, &words));
+
+        // The first hole carries the feed the others may have left modal
+        let mut feed = if position == 0 { group.feed } else { None };
+        for body in &lines[hole.first + 1..=hole.last] {
+            let mut words = GCodeValidator::word_texts(body);
+            let feeds = words
+                .iter()
+                .any(|(l, n)| *l == 'G' && n.parse::<f64>() == Ok(1.0));
+            if let Some(f) = feed.filter(|_| feeds) {
+                if !words.iter().any(|(l, _)| *l == 'F') {
+                    words.push(('F', format_gcode_number(f, 4)));
+                    output.push(GCodeValidator::rebuild_line(body, &words));
+                    feed = None;
+                    continue;
+                }
+                feed = None;
+            }
+            output.push(body.to_string());
+        }
+    }
+}
+
+/// Modal state while scanning a program
+#[derive(Debug, Clone, Copy)]
+struct Scan {
+    x: Option<f64>,
+    y: Option<f64>,
+    z: Option<f64>,
+    /// Active motion: 0-3, or a canned cycle number
+    motion: u32,
+    feed: Option<f64>,
+    scale: f64,
+}
+
+/// What one line says, ignoring comments
+struct LineWords {
+    words: Vec<(char, String)>,
+    motion: Option<u32>,
+    cancel_cycle: bool,
+    other_frame: bool,
+    dwell: bool,
+    /// mm per program unit, if the line sets units
+    scale: Option<f64>,
+    x: Option<f64>,
+    y: Option<f64>,
+    z: Option<f64>,
+    feed: Option<f64>,
+}
+
+impl LineWords {
+    /// `None` if the program cannot be resequenced because of this line
+    fn parse(line: &str) -> Option<Self> {
+        let words = GCodeValidator::word_texts(line);
+        let mut parsed = Self {
+            words: Vec::new(),
+            motion: None,
+            cancel_cycle: false,
+            other_frame: false,
+            dwell: false,
+            scale: None,
+            x: None,
+            y: None,
+            z: None,
+            feed: None,
+        };
+        for (letter, number) in &words {
+            let value = number.parse::<f64>().ok();
+            match (letter, value) {
+                ('G', Some(code)) => match code {
+                    0.0 | 1.0 | 2.0 | 3.0 | 73.0 => parsed.motion = Some(code as u32),
+                    81.0..=89.0 if code.fract() == 0.0 => parsed.motion = Some(code as u32),
+                    4.0 => parsed.dwell = true,
+                    20.0 => parsed.scale = Some(25.4),
+                    21.0 => parsed.scale = Some(1.0),
+                    80.0 => parsed.cancel_cycle = true,
+                    18.0 | 19.0 | 91.0 => return None,
+                    10.0 | 28.0 | 30.0 | 53.0 | 92.0 => parsed.other_frame = true,
+                    38.0..39.0 => parsed.other_frame = true,
+                    _ => {}
+                },
+                ('X', value) => parsed.x = value,
+                ('Y', value) => parsed.y = value,
+                ('Z', value) => parsed.z = value,
+                ('F', value) => parsed.feed = value,
+                _ => {}
+            }
+        }
+        parsed.words = words;
+        Some(parsed)
+    }
+
+    fn has_xy(&self) -> bool {
+        self.x.is_some() || self.y.is_some()
+    }
+
+    /// Whether the line has only words from `letters`
+    fn only(&self, letters: &[char]) -> bool {
+        self.words.iter().all(|(l, _)| letters.contains(l))
+    }
+}
+
+fn is_cycle(motion: u32) -> bool {
+    motion == 73 || (81..=89).contains(&motion)
+}
+
+/// Apply a line's words to the modal state
+fn advance(scan: &mut Scan, words: &LineWords) {
+    if let Some(motion) = words.motion {
+        scan.motion = motion;
+    }
+    if words.cancel_cycle && is_cycle(scan.motion) {
+        scan.motion = 0;
+    }
+    if words.feed.is_some() {
+        scan.feed = words.feed;
+    }
+    if words.other_frame {
+        (scan.x, scan.y, scan.z) = (None, None, None);
+        return;
+    }
+    if is_cycle(scan.motion) {
+        scan.x = words.x.or(scan.x);
+        scan.y = words.y.or(scan.y);
+        if words.has_xy() {
+            scan.z = None;
+        }
+    } else {
+        scan.x = words.x.or(scan.x);
+        scan.y = words.y.or(scan.y);
+        scan.z = words.z.or(scan.z);
+    }
+}
+
+/// Runs of holes with the same parameters, or `None` if the program cannot
+/// be resequenced
+fn find_groups(lines: &[&str]) -> Option<Vec<Group>> {
+    let mut scan = Scan {
+        x: None,
+        y: None,
+        z: None,
+        motion: 0,
+        feed: None,
+        scale: 1.0,
+    };
+    let parsed: Vec<LineWords> = lines
+        .iter()
+        .map(|line| LineWords::parse(line))
+        .collect::<Option<_>>()?;
+
+    let mut groups: Vec<Group> = Vec::new();
+    let mut index = 0;
+    while index < parsed.len() {
+        let words = &parsed[index];
+        let before = scan;
+        scan.scale = words.scale.unwrap_or(scan.scale);
+        let motion = words.motion.unwrap_or(scan.motion);
+
+        // Canned cycle hole
+        if is_cycle(motion) && words.has_xy() && !words.other_frame && !words.cancel_cycle {
+            let x = words.x.or(before.x);
+            let y = words.y.or(before.y);
+            if let (Some(x), Some(y)) = (x, y) {
+                let hole = Hole {
+                    first: index,
+                    last: index,
+                    x,
+                    y,
+                };
+                let plain = words.only(&['N', 'X', 'Y']);
+                match groups.last_mut() {
+                    Some(group)
+                        if plain
+                            && group.kind == HoleKind::Canned
+                            && group.holes.last().is_some_and(|h| h.last + 1 == index) =>
+                    {
+                        group.holes.push(hole)
+                    }
+                    _ => groups.push(Group {
+                        kind: HoleKind::Canned,
+                        holes: vec![hole],
+                        start: before.x.zip(before.y),
+                        setup: words
+                            .words
+                            .iter()
+                            .filter(|(l, _)| !matches!(l, 'N' | 'X' | 'Y'))
+                            .cloned()
+                            .collect(),
+                        signature: Vec::new(),
+                        feed: None,
+                        scale: scan.scale,
+                    }),
+                }
+            }
+            advance(&mut scan, words);
+            index += 1;
+            continue;
+        }
+
+        // Expanded hole: a rapid to X/Y, then Z-only moves down and back up
+        if motion == 0
+            && words.has_xy()
+            && words.z.is_none()
+            && words.only(&['N', 'G', 'X', 'Y'])
+            && words.motion.is_none_or(|m| m == 0)
+        {
+            if let Some((hole_end, signature, feed, body_scan)) =
+                expanded_body(&parsed, index, &before, words)
+            {
+                let hole = Hole {
+                    first: index,
+                    last: hole_end,
+                    x: words.x.or(before.x).unwrap_or_default(),
+                    y: words.y.or(before.y).unwrap_or_default(),
+                };
+                match groups.last_mut() {
+                    Some(group)
+                        if group.kind == HoleKind::Expanded
+                            && group.signature == signature
+                            && group.holes.last().is_some_and(|h| h.last + 1 == index) =>
+                    {
+                        group.holes.push(hole)
+                    }
+                    _ => groups.push(Group {
+                        kind: HoleKind::Expanded,
+                        holes: vec![hole],
+                        start: before.x.zip(before.y),
+                        setup: Vec::new(),
+                        signature,
+                        feed,
+                        scale: scan.scale,
+                    }),
+                }
+                scan = Scan {
+                    scale: scan.scale,
+                    ..body_scan
+                };
+                index = hole_end + 1;
+                continue;
+            }
+        }
+
+        advance(&mut scan, words);
+        index += 1;
+    }
+    Some(groups)
+}
+
+/// The body of an expanded hole whose positioning move is at `start`: its last
+/// line, what it does, its feed, and the modal state after it
+fn expanded_body(
+    parsed: &[LineWords],
+    start: usize,
+    before: &Scan,
+    positioning: &LineWords,
+) -> Option<(usize, Vec<String>, Option<f64>, Scan)> {
+    let (Some(_), Some(_), Some(top)) = (
+        positioning.x.or(before.x),
+        positioning.y.or(before.y),
+        before.z,
+    ) else {
+        return None;
+    };
+    let mut scan = *before;
+    advance(&mut scan, positioning);
+    let mut signature = vec![format_gcode_number(top, 4)];
+    let mut feed = None;
+    let mut plunged = false;
+    let mut last = None;
+    for (offset, words) in parsed[start + 1..].iter().enumerate() {
+        if words.has_xy()
+            || words.other_frame
+            || !words.only(&['N', 'G', 'Z', 'F', 'P'])
+            || words.motion.is_some_and(|m| m > 1)
+        {
+            break;
+        }
+        advance(&mut scan, words);
+        if words.words.is_empty() {
+            continue;
+        }
+        let mut step = String::new();
+        if words.dwell {
+            let dwell = words.words.iter().find(|(l, _)| *l == 'P');
+            step.push_str(&format!("G4 P{}", dwell.map_or("", |(_, n)| n.as_str())));
+        }
+        if let Some(z) = words.z {
+            step.push_str(&format!(" G{} Z{}", scan.motion, format_gcode_number(z, 4)));
+            if scan.motion == 1 {
+                let f = scan.feed?;
+                step.push_str(&format!(" F{}", format_gcode_number(f, 4)));
+                feed.get_or_insert(f);
+                plunged |= z < top - EPSILON;
+            }
+        }
+        signature.push(step);
+        last = Some((start + 1 + offset, scan));
+    }
+    let (end, end_scan) = last?;
+    let back_up = end_scan.z.is_some_and(|z| (z - top).abs() < EPSILON);
+    (plunged && back_up).then_some((end, signature, feed, end_scan))
+}
+
+/// Visit order: nearest neighbour from `start`, improved by 2-opt
+fn tour(start: Option<(f64, f64)>, points: &[(f64, f64)]) -> Vec<usize> {
+    let distance = |a: (f64, f64), b: (f64, f64)| (a.0 - b.0).hypot(a.1 - b.1);
+    let mut visited = vec![false; points.len()];
+    let mut order = Vec::with_capacity(points.len());
+    let mut current = match start {
+        Some(point) => point,
+        None => {
+            // Without a known start the first hole stays first
+            visited[0] = true;
+            order.push(0);
+            points[0]
+        }
+    };
+    while order.len() < points.len() {
+        let next = (0..points.len())
+            .filter(|&i| !visited[i])
+            .min_by(|&a, &b| distance(current, points[a]).total_cmp(&distance(current, points[b])))
+            .unwrap_or_default();
+        visited[next] = true;
+        order.push(next);
+        current = points[next];
+    }
+
+    // 2-opt on the open path; position 0, the start or first hole, stays fixed
+    let at = |order: &[usize], k: usize| -> (f64, f64) {
+        match (start, k.checked_sub(1)) {
+            (Some(point), None) => point,
+            (Some(_), Some(i)) => points[order[i]],
+            (None, _) => points[order[k]],
+        }
+    };
+    // Path positions: with a start, position 0 is the start and k is order[k - 1]
+    let offset = usize::from(start.is_some());
+    let length = order.len() + offset;
+    for _ in 0..MAX_PASSES {
+        let mut improved = false;
+        for i in 1..length {
+            for j in i + 1..length {
+                let (a, b) = (at(&order, i - 1), at(&order, i));
+                let (c, d) = (at(&order, j), (j + 1 < length).then(|| at(&order, j + 1)));
+                let removed = distance(a, b) + d.map_or(0.0, |d| distance(c, d));
+                let added = distance(a, c) + d.map_or(0.0, |d| distance(b, d));
+                if added < removed - EPSILON {
+                    order[i - offset..=j - offset].reverse();
+                    improved = true;
+                }
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+    order
+}
+
+/// XY travel from `start` through `points` in `order`
+fn path_length(start: Option<(f64, f64)>, points: &[(f64, f64)], order: &[usize]) -> f64 {
+    let mut length = 0.0;
+    let mut previous = start;
+    for &i in order {
+        if let Some(p) = previous {
+            length += (points[i].0 - p.0).hypot(points[i].1 - p.1);
+        }
+        previous = Some(points[i]);
+    }
+    length
+}
@@ -2,6 +2,8 @@
 //!
 //! Generates G-code toolpaths for laser/CNC cutting jigsaw puzzles with interlocking pieces.
 
+use crate::annotation::{AnnotatedTool, AnnotationLevel, Annotator};
+use crate::preview::GeneratorPreview;
 use serde::{Deserialize, Serialize};
 use std::f32::consts::PI;
 
@@ -70,6 +72,7 @@ pub struct JigsawPuzzleMaker {
     params: PuzzleParameters,
     paths: Vec<Vec<Point>>,
     rng_state: f32,
+    annotation: AnnotationLevel,
 }
 
 impl JigsawPuzzleMaker {
@@ -79,9 +82,16 @@ impl JigsawPuzzleMaker {
             params: params.clone(),
             paths: Vec::new(),
             rng_state: params.seed as f32,
+            annotation: AnnotationLevel::Off,
         })
     }
 
+    /// Annotate the G-code with the cut and pass each section makes
+    pub fn with_annotation(mut self, level: AnnotationLevel) -> Self {
+        self.annotation = level;
+        self
+    }
+
     fn random(&mut self) -> f32 {
         let x = (self.rng_state.sin() * 10000.0).abs();
         self.rng_state += 1.0;
@@ -436,6 +446,22 @@ impl JigsawPuzzleMaker {
         )
     }
 
+    /// Extents and size of the G-code [`generate_gcode`](Self::generate_gcode) writes
+    pub fn preview(
+        &mut self,
+        plunge_rate: f32,
+        cut_depth: f32,
+    ) -> Result<GeneratorPreview, String> {
+        let gcode = self.generate_gcode(plunge_rate, cut_depth)?;
+        Ok(GeneratorPreview::from_program(&gcode))
+    }
+
+    /// Generate the puzzle paths and return the G-code in one step
+    pub fn generate_gcode(&mut self, plunge_rate: f32, cut_depth: f32) -> Result<String, String> {
+        self.generate()?;
+        Ok(self.to_gcode(plunge_rate, cut_depth))
+    }
+
     pub fn to_gcode(&self, plunge_rate: f32, cut_depth: f32) -> String {
         let mut gcode = String::new();
 
@@ -523,17 +549,17 @@ impl JigsawPuzzleMaker {
         }
         gcode.push('\n');
 
+        let annotator = Annotator::new(AnnotatedTool::JigsawPuzzle, self.annotation);
         for (i, path) in self.paths.iter().enumerate() {
-            if i == 0 {
-                gcode.push_str("; Outer border\n");
+            let cut = if i == 0 {
+                "Outer border".to_string()
             } else if i <= self.params.pieces_across as usize {
-                gcode.push_str(&format!("; Vertical cut {}\n", i));
+                format!("Vertical cut {}", i)
             } else {
-                gcode.push_str(&format!(
-                    "; Horizontal cut {}\n",
-                    i - self.params.pieces_across as usize
-                ));
-            }
+                format!("Horizontal cut {}", i - self.params.pieces_across as usize)
+            };
+            gcode.push_str(&format!("; {}\n", cut));
+            annotator.section(&mut gcode, format_args!("{}", cut));
 
             if let Some(first_point) = path.first() {
                 gcode.push_str(&format!(
@@ -553,6 +579,10 @@ impl JigsawPuzzleMaker {
                         "; Pass {}/{} at Z{:.2}\n",
                         pass_num, self.params.laser_passes, z_depth
                     ));
+                    annotator.line(
+                        &mut gcode,
+                        format_args!("{} pass {}/{}", cut, pass_num, self.params.laser_passes),
+                    );
 
                     if pass_num > 1 && self.params.num_axes >= 3 {
                         gcode.push_str(&format!("G0 Z{:.2} ; Move to pass depth\n", z_depth));
@@ -0,0 +1,363 @@
+//! # Job Sequence
+//!
+//! An ordered multi-tool workflow, such as "run program A, pause for a tool
+//! change, probe the new tool's length, run program B". A sequence is saved as
+//! JSON so common workflows can be reused; programs are referenced by path and
+//! read when the sequence is run.
+//!
+//! A sequence can be flattened into one streamable program, where pauses become
+//! M0 stops, or split into [`SequenceStage`]s so the caller streams each part in
+//! turn and handles pauses itself (e.g. with a dialog). Programs are joined with
+//! the same transition block as [`crate::program_merge`]: spindle and coolant
+//! off, a modal baseline and a retract to clearance.
+//!
+//! Tool length probing uses a fixed tool setter whose top surface is at a known
+//! work Z. The tool is moved over the setter in machine coordinates, probed down
+//! with G38.2 and the work Z of the current coordinate system is set to the
+//! setter height at contact, so the new tool cuts at the same depth as the last.
+
+use crate::error::{CamToolError, CamToolResult};
+use crate::program_merge::{push_baseline, push_program, MergeOptions, MergeSource};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Tool setter used by [`JobStep::ProbeToolLength`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolProbeConfig {
+    /// Setter position in machine coordinates (mm)
+    pub x: f64,
+    pub y: f64,
+    /// Machine Z (mm) to raise to before moving over the setter
+    pub safe_z: f64,
+    /// Work Z (mm) of the setter's top surface
+    pub setter_z: f64,
+    /// Longest distance to probe down before failing (mm)
+    pub max_travel: f64,
+    /// Probe feed rate (mm/min)
+    pub feed_rate: f64,
+    /// Distance to back off after contact (mm)
+    pub retract: f64,
+}
+
+impl Default for ToolProbeConfig {
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            safe_z: -5.0,
+            setter_z: 0.0,
+            max_travel: 50.0,
+            feed_rate: 100.0,
+            retract: 2.0,
+        }
+    }
+}
+
+/// One step of a job sequence
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum JobStep {
+    /// Stream a G-code file
+    RunProgram { path: PathBuf },
+    /// Stop until the operator resumes, showing a message
+    Pause { message: String },
+    /// Measure the loaded tool on the tool setter
+    ProbeToolLength,
+    /// Select a work coordinate system (54-59)
+    SetWcs { wcs: u8 },
+    /// Wait for a number of seconds
+    Dwell { seconds: f64 },
+}
+
+impl JobStep {
+    /// Short description for lists and comments
+    pub fn label(&self) -> String {
+        match self {
+            Self::RunProgram { path } => format!("Run {}", program_name(path)),
+            Self::Pause { message } => format!("Pause: {}", message),
+            Self::ProbeToolLength => "Probe tool length".to_string(),
+            Self::SetWcs { wcs } => format!("Select G{}", wcs),
+            Self::Dwell { seconds } => format!("Dwell {}s", seconds),
+        }
+    }
+}
+
+/// A part of a sequence to be run by the caller
+#[derive(Debug, Clone, PartialEq)]
+pub enum SequenceStage {
+    /// G-code to stream
+    Stream { label: String, program: String },
+    /// Wait for the operator to resume
+    Pause { message: String },
+}
+
+/// An ordered, saveable multi-tool workflow
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JobSequence {
+    pub name: String,
+    pub steps: Vec<JobStep>,
+    /// Tool setter, required by probe steps
+    pub probe: Option<ToolProbeConfig>,
+    /// Z (mm, work coordinates) to retract to between steps and at the end
+    pub clearance_z: f64,
+}
+
+impl Default for JobSequence {
+    fn default() -> Self {
+        Self::new("Job sequence")
+    }
+}
+
+impl JobSequence {
+    /// Create an empty sequence
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            steps: Vec::new(),
+            probe: None,
+            clearance_z: 5.0,
+        }
+    }
+
+    /// Append a step
+    pub fn with_step(mut self, step: JobStep) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Set the tool setter used by probe steps
+    pub fn with_probe(mut self, probe: ToolProbeConfig) -> Self {
+        self.probe = Some(probe);
+        self
+    }
+
+    /// Check every step; the error names the first invalid one
+    pub fn validate(&self) -> CamToolResult<()> {
+        let invalid = |index: usize, reason: String| {
+            Err(CamToolError::InvalidParameters(format!(
+                "step {}: {}",
+                index + 1,
+                reason
+            )))
+        };
+        if self.steps.is_empty() {
+            return Err(CamToolError::InvalidParameters(
+                "job sequence has no steps".to_string(),
+            ));
+        }
+        if !self.clearance_z.is_finite() {
+            return Err(CamToolError::InvalidParameters(format!(
+                "clearance Z must be finite, got {}",
+                self.clearance_z
+            )));
+        }
+        for (index, step) in self.steps.iter().enumerate() {
+            match step {
+                JobStep::RunProgram { path } if path.as_os_str().is_empty() => {
+                    return invalid(index, "program path is empty".to_string());
+                }
+                JobStep::Pause { message } if message.contains('\n') => {
+                    return invalid(index, "pause message must be a single line".to_string());
+                }
+                JobStep::ProbeToolLength => {
+                    let Some(probe) = &self.probe else {
+                        return invalid(
+                            index,
+                            "tool length probe needs a probe config".to_string(),
+                        );
+                    };
+                    let finite = [probe.x, probe.y, probe.safe_z, probe.setter_z]
+                        .iter()
+                        .all(|v| v.is_finite());
+                    let positive = [probe.max_travel, probe.feed_rate]
+                        .iter()
+                        .all(|v| v.is_finite() && *v > 0.0);
+                    if !finite || !positive || !probe.retract.is_finite() || probe.retract < 0.0 {
+                        return invalid(
+                            index,
+                            "probe positions must be finite, travel and feed positive and retract not negative"
+                                .to_string(),
+                        );
+                    }
+                }
+                JobStep::SetWcs { wcs } if !(54..=59).contains(wcs) => {
+                    return invalid(
+                        index,
+                        format!("work coordinate system G{} is not one of G54-G59", wcs),
+                    );
+                }
+                JobStep::Dwell { seconds } if !seconds.is_finite() || *seconds < 0.0 => {
+                    return invalid(
+                        index,
+                        format!("dwell must be zero or more seconds, got {}", seconds),
+                    );
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Split the sequence into parts to stream and pauses for the caller to handle
+    ///
+    /// `load` reads a program file, e.g. `std::fs::read_to_string`. Consecutive
+    /// steps without a pause between them are joined into one stream.
+    pub fn stages(
+        &self,
+        mut load: impl FnMut(&Path) -> std::io::Result<String>,
+    ) -> CamToolResult<Vec<SequenceStage>> {
+        self.validate()?;
+        let mut stages = Vec::new();
+        let mut labels: Vec<String> = Vec::new();
+        let mut output: Vec<String> = Vec::new();
+        let flush = |labels: &mut Vec<String>, output: &mut Vec<String>, stages: &mut Vec<_>| {
+            if !output.is_empty() {
+                let mut program = output.join("\n");
+                program.push('\n');
+                stages.push(SequenceStage::Stream {
+                    label: labels.join(", "),
+                    program,
+                });
+                labels.clear();
+                output.clear();
+            }
+        };
+
+        for step in &self.steps {
+            if let JobStep::Pause { message } = step {
+                flush(&mut labels, &mut output, &mut stages);
+                stages.push(SequenceStage::Pause {
+                    message: message.clone(),
+                });
+                continue;
+            }
+            labels.push(step.label());
+            self.push_step(&mut output, step, &mut load)?;
+        }
+        push_end(&mut output, self.clearance_z);
+        labels.push("End".to_string());
+        flush(&mut labels, &mut output, &mut stages);
+        Ok(stages)
+    }
+
+    /// Flatten the sequence into a single streamable program, pausing with M0
+    ///
+    /// `load` reads a program file, e.g. `std::fs::read_to_string`.
+    pub fn flatten(
+        &self,
+        mut load: impl FnMut(&Path) -> std::io::Result<String>,
+    ) -> CamToolResult<String> {
+        self.validate()?;
+        let mut output = vec![format!("; Job sequence: {}", self.name)];
+        for (index, step) in self.steps.iter().enumerate() {
+            output.push(format!("; Step {}: {}", index + 1, step.label()));
+        }
+        output.push(";".to_string());
+
+        for step in &self.steps {
+            match step {
+                JobStep::Pause { message } => {
+                    output.push(String::new());
+                    push_baseline(&mut output, &self.merge_options());
+                    output.push(format!("M0 ; {}", message));
+                }
+                _ => self.push_step(&mut output, step, &mut load)?,
+            }
+        }
+        push_end(&mut output, self.clearance_z);
+
+        let mut program = output.join("\n");
+        program.push('\n');
+        Ok(program)
+    }
+
+    /// Load a sequence saved as JSON
+    pub fn load(path: &Path) -> CamToolResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Save the sequence as JSON
+    pub fn save(&self, path: &Path) -> CamToolResult<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    fn merge_options(&self) -> MergeOptions {
+        MergeOptions {
+            clearance_z: self.clearance_z,
+            // Keep whatever coordinate system a SetWcs step selected
+            work_coordinate_system: None,
+            ..Default::default()
+        }
+    }
+
+    fn push_step(
+        &self,
+        output: &mut Vec<String>,
+        step: &JobStep,
+        load: &mut impl FnMut(&Path) -> std::io::Result<String>,
+    ) -> CamToolResult<()> {
+        output.push(String::new());
+        output.push(format!("; ===== {} =====", step.label()));
+        match step {
+            JobStep::RunProgram { path } => {
+                let content = load(path)
+                    .map_err(|e| CamToolError::LoadError(format!("{}: {}", path.display(), e)))?;
+                let options = self.merge_options();
+                push_baseline(output, &options);
+                push_program(
+                    output,
+                    &MergeSource::new(program_name(path), content),
+                    &options,
+                );
+            }
+            JobStep::ProbeToolLength => {
+                // Validated: probe steps always have a config
+                let Some(probe) = &self.probe else {
+                    return Ok(());
+                };
+                output.push("M5 ; Spindle/laser off".to_string());
+                output.push("M9 ; Coolant off".to_string());
+                output.push(format!("G53 G0 Z{:.3} ; Raise to safe Z", probe.safe_z));
+                output.push(format!(
+                    "G53 G0 X{:.3} Y{:.3} ; Move over tool setter",
+                    probe.x, probe.y
+                ));
+                output.push(format!(
+                    "G91 G38.2 Z{:.3} F{:.0} ; Probe tool setter",
+                    -probe.max_travel, probe.feed_rate
+                ));
+                output.push(format!(
+                    "G90 G10 L20 P0 Z{:.3} ; Tool tip is at the setter height",
+                    probe.setter_z
+                ));
+                output.push(format!("G91 G0 Z{:.3} ; Back off setter", probe.retract));
+                output.push(format!("G90 G53 G0 Z{:.3} ; Raise to safe Z", probe.safe_z));
+            }
+            JobStep::SetWcs { wcs } => output.push(format!("G{}", wcs)),
+            JobStep::Dwell { seconds } => output.push(format!("G4 P{:.3}", seconds)),
+            JobStep::Pause { message } => output.push(format!("M0 ; {}", message)),
+        }
+        Ok(())
+    }
+}
+
+fn push_end(output: &mut Vec<String>, clearance_z: f64) {
+    output.push(String::new());
+    output.push("; ===== End of job sequence =====".to_string());
+    output.push("M5 ; Spindle/laser off".to_string());
+    output.push("M9 ; Coolant off".to_string());
+    output.push(format!("G90 G0 Z{:.3} ; Retract to clearance", clearance_z));
+    output.push("M30 ; End program".to_string());
+}
+
+fn program_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
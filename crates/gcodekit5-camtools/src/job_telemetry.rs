@@ -0,0 +1,186 @@
+//! # Job Telemetry
+//!
+//! Records how long streamed jobs actually took next to their pre-run estimate
+//! (from [`crate::stats::StatsCalculator`]), so future estimates can be corrected
+//! per machine. Recording is opt-in and the log is a local JSON file; only jobs that
+//! ran to completion are recorded, aborted jobs are discarded.
+//!
+//! The correction factor for a machine is the median actual/estimated ratio of its
+//! most recent jobs, which keeps one interrupted or unusual run from skewing it.
+
+use crate::error::CamToolResult;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Instant;
+
+/// Number of records kept in a log; older ones are dropped first
+const MAX_RECORDS: usize = 200;
+/// Number of a machine's most recent jobs used for its correction factor
+const CORRECTION_WINDOW: usize = 10;
+/// Ratios outside this range are treated as bad data and ignored
+const RATIO_RANGE: (f64, f64) = (0.1, 10.0);
+
+/// Timing of one completed job
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobTimingRecord {
+    /// Machine (device profile) the job ran on
+    pub machine: String,
+    /// Pre-run estimate in seconds
+    pub estimated_secs: f64,
+    /// Measured duration in seconds, from the first line sent to completion
+    pub actual_secs: f64,
+    /// Lines streamed
+    pub lines: usize,
+    /// When the job finished
+    pub finished_at: DateTime<Utc>,
+}
+
+impl JobTimingRecord {
+    /// Actual over estimated time, if the estimate was positive
+    pub fn ratio(&self) -> Option<f64> {
+        (self.estimated_secs > 0.0 && self.actual_secs > 0.0)
+            .then(|| self.actual_secs / self.estimated_secs)
+    }
+
+    /// Lines streamed per second
+    pub fn lines_per_sec(&self) -> f64 {
+        if self.actual_secs > 0.0 {
+            self.lines as f64 / self.actual_secs
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Measures a running job
+#[derive(Debug, Clone)]
+pub struct JobTimer {
+    machine: String,
+    estimated_secs: f64,
+    started: Instant,
+    lines: usize,
+}
+
+impl JobTimer {
+    /// Start timing a job with its pre-run estimate
+    pub fn start(machine: impl Into<String>, estimated_secs: f64) -> Self {
+        Self::start_at(machine, estimated_secs, Instant::now())
+    }
+
+    /// Start timing a job at a given instant
+    pub fn start_at(machine: impl Into<String>, estimated_secs: f64, started: Instant) -> Self {
+        Self {
+            machine: machine.into(),
+            estimated_secs,
+            started,
+            lines: 0,
+        }
+    }
+
+    /// Count a line sent to the controller
+    pub fn on_line_sent(&mut self) {
+        self.lines += 1;
+    }
+
+    /// Lines sent so far
+    pub fn lines_sent(&self) -> usize {
+        self.lines
+    }
+
+    /// Discard an aborted job without recording it
+    pub fn abort(self) {
+        tracing::debug!(
+            "Job on '{}' aborted after {} line(s); timing not recorded",
+            self.machine,
+            self.lines
+        );
+    }
+
+    /// Finish the job now
+    pub fn complete(self) -> JobTimingRecord {
+        self.complete_at(Instant::now())
+    }
+
+    /// Finish the job at a given instant
+    pub fn complete_at(self, finished: Instant) -> JobTimingRecord {
+        JobTimingRecord {
+            machine: self.machine,
+            estimated_secs: self.estimated_secs,
+            actual_secs: finished
+                .saturating_duration_since(self.started)
+                .as_secs_f64(),
+            lines: self.lines,
+            finished_at: Utc::now(),
+        }
+    }
+}
+
+/// Local log of completed jobs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JobTimingLog {
+    /// Recorded jobs, oldest first
+    pub records: Vec<JobTimingRecord>,
+}
+
+impl JobTimingLog {
+    /// Create an empty log
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a completed job, dropping the oldest records beyond the limit
+    pub fn record(&mut self, record: JobTimingRecord) {
+        self.records.push(record);
+        if self.records.len() > MAX_RECORDS {
+            let excess = self.records.len() - MAX_RECORDS;
+            self.records.drain(..excess);
+        }
+    }
+
+    /// Learned actual/estimated ratio for a machine, if it has usable records
+    pub fn correction_factor(&self, machine: &str) -> Option<f64> {
+        let mut ratios: Vec<f64> = self
+            .records
+            .iter()
+            .rev()
+            .filter(|r| r.machine == machine)
+            .filter_map(JobTimingRecord::ratio)
+            .filter(|ratio| (RATIO_RANGE.0..=RATIO_RANGE.1).contains(ratio))
+            .take(CORRECTION_WINDOW)
+            .collect();
+        if ratios.is_empty() {
+            return None;
+        }
+        ratios.sort_by(f64::total_cmp);
+        let mid = ratios.len() / 2;
+        Some(if ratios.len().is_multiple_of(2) {
+            (ratios[mid - 1] + ratios[mid]) / 2.0
+        } else {
+            ratios[mid]
+        })
+    }
+
+    /// Apply a machine's correction factor to an estimate
+    pub fn corrected_estimate(&self, machine: &str, estimated_secs: f64) -> f64 {
+        estimated_secs * self.correction_factor(machine).unwrap_or(1.0)
+    }
+
+    /// Load a log, or an empty one if the file does not exist
+    pub fn load(path: &Path) -> CamToolResult<Self> {
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Save the log as JSON
+    pub fn save(&self, path: &Path) -> CamToolResult<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
@@ -4,7 +4,15 @@
 //! Supports halftoning via pepecore, mirroring, rotation, grayscale power modulation,
 //! bidirectional scanning, and various image formats.
 //! Images are rendered from bottom to top to match device coordinate space where Y increases upward.
-
+//!
+//! [`MaterialTestGenerator`] produces a power/speed test grid for dialing in laser
+//! settings on a new material, labelled with the single-line [`stroke_font`](crate::stroke_font).
+
+use crate::air_assist::{AirAssist, AirAssistState};
+use crate::annotation::{AnnotatedTool, AnnotationLevel, Annotator};
+use crate::error::{CamToolError, CamToolResult};
+use crate::preview::GeneratorPreview;
+use crate::stroke_font;
 use anyhow::{Context, Result};
 use gcodekit5_core::types::BoxedIterator;
 use image::{DynamicImage, GrayImage};
@@ -95,6 +103,12 @@ pub struct EngravingParameters {
     pub min_power: f32,
     /// Maximum laser power (0-100%)
     pub max_power: f32,
+    /// Pixels mapped below this power (0-100%) are not burned at all; the laser
+    /// switches off and rapids over them. 0 disables skipping
+    pub skip_power: f32,
+    /// Skipped gaps shorter than this (mm) are crossed at S0 without switching
+    /// the laser off, to avoid toggling it for every few pixels
+    pub min_skip_length: f32,
     /// Resolution in pixels per millimeter
     pub pixels_per_mm: f32,
     /// Scan direction
@@ -113,6 +127,8 @@ pub struct EngravingParameters {
     pub offset_y: f32,
     /// Number of axes on the target device (default 3).
     pub num_axes: u8,
+    /// Air assist codes around the engraving
+    pub air_assist: AirAssist,
 }
 
 impl Default for EngravingParameters {
@@ -124,6 +140,8 @@ impl Default for EngravingParameters {
             travel_rate: 3000.0,
             min_power: 0.0,
             max_power: 100.0,
+            skip_power: 0.0,
+            min_skip_length: 0.0,
             pixels_per_mm: 10.0,
             scan_direction: ScanDirection::Horizontal,
             bidirectional: true,
@@ -133,6 +151,7 @@ impl Default for EngravingParameters {
             offset_x: 10.0,
             offset_y: 10.0,
             num_axes: 3,
+            air_assist: AirAssist::default(),
         }
     }
 }
@@ -143,6 +162,7 @@ pub struct BitmapImageEngraver {
     params: EngravingParameters,
     output_width: u32,
     output_height: u32,
+    annotation: AnnotationLevel,
 }
 
 impl BitmapImageEngraver {
@@ -206,6 +226,7 @@ impl BitmapImageEngraver {
             params,
             output_width,
             output_height,
+            annotation: AnnotationLevel::Off,
         })
     }
 
@@ -417,6 +438,21 @@ impl BitmapImageEngraver {
         engrave_time + travel_time
     }
 
+    /// Annotate the G-code with the raster and each scan line
+    pub fn with_annotation(mut self, level: AnnotationLevel) -> Self {
+        self.annotation = level;
+        self
+    }
+
+    fn annotator(&self) -> Annotator {
+        Annotator::new(AnnotatedTool::BitmapEngraver, self.annotation)
+    }
+
+    /// Extents and size of the G-code [`generate_gcode`](Self::generate_gcode) writes
+    pub fn preview(&self) -> Result<GeneratorPreview> {
+        Ok(GeneratorPreview::from_program(&self.generate_gcode()?))
+    }
+
     /// Generate G-code for laser engraving
     pub fn generate_gcode(&self) -> Result<String> {
         self.generate_gcode_with_progress(|_| {})
@@ -427,6 +463,7 @@ impl BitmapImageEngraver {
     where
         F: FnMut(f32),
     {
+        self.params.air_assist.validate()?;
         let mut gcode = String::new();
 
         gcode.push_str("; Laser Image Engraving G-code\n");
@@ -455,6 +492,9 @@ impl BitmapImageEngraver {
             "; Estimated time: {:.1} minutes\n",
             self.estimate_time() / 60.0
         ));
+        if let Some(comment) = self.params.air_assist.header_comment() {
+            gcode.push_str(&comment);
+        }
         gcode.push_str(";\n");
 
         gcode.push_str("G21 ; Set units to millimeters\n");
@@ -497,7 +537,17 @@ impl BitmapImageEngraver {
 
         let line_spacing = 1.0 / self.params.pixels_per_mm * self.params.line_spacing;
         let pixel_width = 1.0 / self.params.pixels_per_mm;
-
+        let mut air = AirAssistState::new(&self.params.air_assist);
+
+        self.annotator().section(
+            &mut gcode,
+            format_args!(
+                "{:?} raster {}x{} px",
+                self.params.scan_direction,
+                self.image.width(),
+                self.image.height()
+            ),
+        );
         match self.params.scan_direction {
             ScanDirection::Horizontal => {
                 self.generate_horizontal_scan_with_progress(
@@ -505,6 +555,7 @@ impl BitmapImageEngraver {
                     &self.image,
                     pixel_width,
                     line_spacing,
+                    &mut air,
                     &mut progress_callback,
                 )?;
             }
@@ -514,6 +565,7 @@ impl BitmapImageEngraver {
                     &self.image,
                     pixel_width,
                     line_spacing,
+                    &mut air,
                     &mut progress_callback,
                 )?;
             }
@@ -523,6 +575,7 @@ impl BitmapImageEngraver {
 
         gcode.push_str("\n; End of engraving\n");
         gcode.push_str("M5 ; Laser off\n");
+        air.end_job(&mut gcode);
         gcode.push_str("G0 X0 Y0 ; Return to origin\n");
 
         progress_callback(1.0);
@@ -536,6 +589,7 @@ impl BitmapImageEngraver {
         image: &GrayImage,
         pixel_width: f32,
         line_spacing: f32,
+        air: &mut AirAssistState,
         progress_callback: &mut F,
     ) -> Result<()>
     where
@@ -544,6 +598,7 @@ impl BitmapImageEngraver {
         let height = image.height();
         let width = image.width();
         let mut left_to_right = true;
+        let annotator = self.annotator();
 
         // Render from bottom to top to match device coordinate space
         for y_reversed in 0..height {
@@ -554,6 +609,7 @@ impl BitmapImageEngraver {
 
             let y = height - 1 - y_reversed;
             let y_pos = y_reversed as f32 * line_spacing;
+            annotator.line(gcode, format_args!("Row {} Y{:.3}", y_reversed + 1, y_pos));
 
             if left_to_right || !self.params.bidirectional {
                 gcode.push_str(&format!("G0 X0 Y{:.3}\n", y_pos));
@@ -565,41 +621,19 @@ impl BitmapImageEngraver {
                 ));
             }
 
-            let mut in_burn = false;
-            let mut last_power = 0;
-
             let x_range: BoxedIterator<u32> = if left_to_right || !self.params.bidirectional {
                 Box::new(0..width)
             } else {
                 Box::new((0..width).rev())
             };
-
-            for x in x_range {
-                let intensity = image.get_pixel(x, y).0[0];
-                let power = self.intensity_to_power(intensity);
-                let power_value = (power * self.params.power_scale / 100.0) as u32;
-                let x_pos = x as f32 * pixel_width;
-
-                if power_value > 0 {
-                    if !in_burn || power_value != last_power {
-                        gcode.push_str(&format!(
-                            "G1 X{:.3} Y{:.3} F{:.0} M3 S{}\n",
-                            x_pos, y_pos, self.params.feed_rate, power_value
-                        ));
-                        in_burn = true;
-                        last_power = power_value;
-                    } else {
-                        gcode.push_str(&format!("G1 X{:.3} Y{:.3}\n", x_pos, y_pos));
-                    }
-                } else if in_burn {
-                    gcode.push_str("M5\n");
-                    in_burn = false;
-                }
-            }
-
-            if in_burn {
-                gcode.push_str("M5\n");
-            }
+            let samples: Vec<ScanSample> = x_range
+                .map(|x| ScanSample {
+                    x: x as f32 * pixel_width,
+                    y: y_pos,
+                    power: self.pixel_power(image.get_pixel(x, y).0[0]),
+                })
+                .collect();
+            self.emit_scan_line(gcode, &samples, air);
 
             if self.params.bidirectional {
                 left_to_right = !left_to_right;
@@ -615,6 +649,7 @@ impl BitmapImageEngraver {
         image: &GrayImage,
         pixel_width: f32,
         line_spacing: f32,
+        air: &mut AirAssistState,
         progress_callback: &mut F,
     ) -> Result<()>
     where
@@ -623,6 +658,7 @@ impl BitmapImageEngraver {
         let height = image.height();
         let width = image.width();
         let mut top_to_bottom = true;
+        let annotator = self.annotator();
 
         for x in 0..width {
             if x % 10 == 0 || x == width - 1 {
@@ -630,6 +666,7 @@ impl BitmapImageEngraver {
                 progress_callback(progress);
             }
             let x_pos = x as f32 * line_spacing;
+            annotator.line(gcode, format_args!("Column {} X{:.3}", x + 1, x_pos));
 
             if top_to_bottom || !self.params.bidirectional {
                 gcode.push_str(&format!("G0 X{:.3} Y0\n", x_pos));
@@ -641,42 +678,19 @@ impl BitmapImageEngraver {
                 ));
             }
 
-            let mut in_burn = false;
-            let mut last_power = 0;
-
             let y_range: BoxedIterator<u32> = if top_to_bottom || !self.params.bidirectional {
                 Box::new(0..height)
             } else {
                 Box::new((0..height).rev())
             };
-
-            for y_reversed in y_range {
-                let y = height - 1 - y_reversed;
-                let intensity = image.get_pixel(x, y).0[0];
-                let power = self.intensity_to_power(intensity);
-                let power_value = (power * self.params.power_scale / 100.0) as u32;
-                let y_pos = y_reversed as f32 * pixel_width;
-
-                if power_value > 0 {
-                    if !in_burn || power_value != last_power {
-                        gcode.push_str(&format!(
-                            "G1 X{:.3} Y{:.3} F{:.0} M3 S{}\n",
-                            x_pos, y_pos, self.params.feed_rate, power_value
-                        ));
-                        in_burn = true;
-                        last_power = power_value;
-                    } else {
-                        gcode.push_str(&format!("G1 X{:.3} Y{:.3}\n", x_pos, y_pos));
-                    }
-                } else if in_burn {
-                    gcode.push_str("M5\n");
-                    in_burn = false;
-                }
-            }
-
-            if in_burn {
-                gcode.push_str("M5\n");
-            }
+            let samples: Vec<ScanSample> = y_range
+                .map(|y_reversed| ScanSample {
+                    x: x_pos,
+                    y: y_reversed as f32 * pixel_width,
+                    power: self.pixel_power(image.get_pixel(x, height - 1 - y_reversed).0[0]),
+                })
+                .collect();
+            self.emit_scan_line(gcode, &samples, air);
 
             if self.params.bidirectional {
                 top_to_bottom = !top_to_bottom;
@@ -691,4 +705,480 @@ impl BitmapImageEngraver {
         let normalized = intensity as f32 / 255.0;
         self.params.min_power + (normalized * (self.params.max_power - self.params.min_power))
     }
+
+    /// S value for a pixel, zero when it falls below the skip power
+    fn pixel_power(&self, intensity: u8) -> u32 {
+        let power = self.intensity_to_power(intensity);
+        if power < self.params.skip_power {
+            return 0;
+        }
+        (power * self.params.power_scale / 100.0) as u32
+    }
+
+    /// Emit the moves for one scan line
+    ///
+    /// Without skipping the laser only switches off over unburned pixels. With a
+    /// skip power set, each gap between burn runs is travelled with a rapid, or
+    /// at S0 with the laser left on when it is shorter than the minimum skip
+    /// length.
+    fn emit_scan_line(&self, gcode: &mut String, samples: &[ScanSample], air: &mut AirAssistState) {
+        let skipping = self.params.skip_power > 0.0;
+        let mut in_burn = false;
+        let mut last_power = 0;
+        let mut i = 0;
+
+        while i < samples.len() {
+            let sample = samples[i];
+            if sample.power > 0 {
+                if !in_burn || sample.power != last_power {
+                    air.before_burn(gcode);
+                    gcode.push_str(&format!(
+                        "G1 X{:.3} Y{:.3} F{:.0} M3 S{}\n",
+                        sample.x, sample.y, self.params.feed_rate, sample.power
+                    ));
+                    in_burn = true;
+                    last_power = sample.power;
+                } else {
+                    gcode.push_str(&format!("G1 X{:.3} Y{:.3}\n", sample.x, sample.y));
+                }
+                i += 1;
+                continue;
+            }
+
+            if !skipping {
+                if in_burn {
+                    gcode.push_str("M5\n");
+                    in_burn = false;
+                }
+                i += 1;
+                continue;
+            }
+
+            // The whole gap, so the next burn starts from its far end
+            let gap_end = samples[i..]
+                .iter()
+                .position(|s| s.power > 0)
+                .map_or(samples.len(), |n| i + n);
+            let last_off = samples[gap_end - 1];
+            let from = samples[i.saturating_sub(1)];
+            let gap_length = (last_off.x - from.x).hypot(last_off.y - from.y);
+            if gap_end == samples.len() {
+                // Nothing left to burn on this line
+            } else if in_burn && gap_length < self.params.min_skip_length {
+                gcode.push_str(&format!("G1 X{:.3} Y{:.3} S0\n", last_off.x, last_off.y));
+                last_power = 0;
+            } else {
+                if in_burn {
+                    gcode.push_str("M5\n");
+                    in_burn = false;
+                }
+                gcode.push_str(&format!("G0 X{:.3} Y{:.3}\n", last_off.x, last_off.y));
+            }
+            i = gap_end;
+        }
+
+        if in_burn {
+            gcode.push_str("M5\n");
+        }
+    }
+}
+
+/// A pixel along a scan line, at its G-code position with its S value
+#[derive(Debug, Clone, Copy)]
+struct ScanSample {
+    x: f32,
+    y: f32,
+    power: u32,
+}
+
+/// How each test square is burned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestSquareStyle {
+    /// Fill the square with horizontal scan lines
+    Filled,
+    /// Trace only the square's outline
+    Outline,
+}
+
+/// Material test grid parameters
+///
+/// Rows vary power (lowest at the bottom) and columns vary speed (slowest on the
+/// left). Row labels are engraved to the left of the grid and column labels below
+/// it, so the grid's bottom-left corner sits at the work origin.
+#[derive(Debug, Clone)]
+pub struct MaterialTestParameters {
+    /// Lowest power (0-100%)
+    pub power_min: f32,
+    /// Highest power (0-100%)
+    pub power_max: f32,
+    /// Number of power rows
+    pub power_steps: u32,
+    /// Slowest feed rate (mm/min)
+    pub speed_min: f32,
+    /// Fastest feed rate (mm/min)
+    pub speed_max: f32,
+    /// Number of speed columns
+    pub speed_steps: u32,
+    /// Side length of each test square in millimeters
+    pub square_size: f32,
+    /// Gap between squares, and between squares and labels, in millimeters
+    pub spacing: f32,
+    /// Filled or outlined squares
+    pub style: TestSquareStyle,
+    /// Distance between fill lines in millimeters
+    pub line_interval: f32,
+    /// Label text height in millimeters (column labels shrink to fit their column)
+    pub label_height: f32,
+    /// Power used for labels (0-100%)
+    pub label_power: f32,
+    /// Feed rate used for labels (mm/min)
+    pub label_speed: f32,
+    /// Laser power scale (0-1000 for GRBL S parameter)
+    pub power_scale: f32,
+    /// Stock width available for the grid and its labels, in millimeters
+    pub stock_width: f32,
+    /// Stock height available for the grid and its labels, in millimeters
+    pub stock_height: f32,
+    /// X offset from machine origin
+    pub offset_x: f32,
+    /// Y offset from machine origin
+    pub offset_y: f32,
+    /// Air assist codes; each square, and the labels, is an operation
+    pub air_assist: AirAssist,
+}
+
+impl Default for MaterialTestParameters {
+    fn default() -> Self {
+        Self {
+            power_min: 10.0,
+            power_max: 100.0,
+            power_steps: 10,
+            speed_min: 500.0,
+            speed_max: 3000.0,
+            speed_steps: 6,
+            square_size: 5.0,
+            spacing: 2.0,
+            style: TestSquareStyle::Filled,
+            line_interval: 0.1,
+            label_height: 3.0,
+            label_power: 50.0,
+            label_speed: 1000.0,
+            power_scale: 1000.0,
+            stock_width: 100.0,
+            stock_height: 100.0,
+            offset_x: 10.0,
+            offset_y: 10.0,
+            air_assist: AirAssist::default(),
+        }
+    }
+}
+
+/// Resolved placement of a material test grid
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaterialTestLayout {
+    /// Overall width including row labels, in millimeters
+    pub width: f32,
+    /// Overall height including column labels, in millimeters
+    pub height: f32,
+    /// Power of each row, bottom to top (0-100%)
+    pub powers: Vec<f32>,
+    /// Feed rate of each column, left to right (mm/min)
+    pub speeds: Vec<f32>,
+    /// X of the first column's left edge
+    pub grid_x: f32,
+    /// Y of the first row's bottom edge
+    pub grid_y: f32,
+    /// Height used for row labels
+    pub row_label_height: f32,
+    /// Height used for column labels
+    pub column_label_height: f32,
+}
+
+/// Generator for laser power/speed material test grids
+pub struct MaterialTestGenerator {
+    params: MaterialTestParameters,
+    annotation: AnnotationLevel,
+}
+
+impl MaterialTestGenerator {
+    /// Create a generator for the given parameters
+    pub fn new(params: MaterialTestParameters) -> Self {
+        Self {
+            params,
+            annotation: AnnotationLevel::Off,
+        }
+    }
+
+    /// Annotate the G-code with the squares and labels, and each one of them
+    pub fn with_annotation(mut self, level: AnnotationLevel) -> Self {
+        self.annotation = level;
+        self
+    }
+
+    /// Row label text for a power value
+    pub fn power_label(power: f32) -> String {
+        format!("{:.0}%", power)
+    }
+
+    /// Column label text for a feed rate
+    pub fn speed_label(speed: f32) -> String {
+        format!("F{:.0}", speed)
+    }
+
+    /// Lay out the grid, failing if the parameters are invalid or the grid and its
+    /// labels do not fit the stock
+    pub fn layout(&self) -> CamToolResult<MaterialTestLayout> {
+        let p = &self.params;
+        if p.power_steps == 0 || p.speed_steps == 0 {
+            return Err(CamToolError::InvalidParameters(
+                "power and speed steps must be at least 1".to_string(),
+            ));
+        }
+        if p.square_size <= 0.0 || p.spacing < 0.0 || p.label_height <= 0.0 {
+            return Err(CamToolError::InvalidParameters(
+                "square size and label height must be positive and spacing non-negative"
+                    .to_string(),
+            ));
+        }
+        if p.style == TestSquareStyle::Filled && p.line_interval <= 0.0 {
+            return Err(CamToolError::InvalidParameters(
+                "line interval must be positive for filled squares".to_string(),
+            ));
+        }
+        if p.speed_min <= 0.0 || p.speed_max <= 0.0 || p.label_speed <= 0.0 {
+            return Err(CamToolError::InvalidParameters(
+                "feed rates must be positive".to_string(),
+            ));
+        }
+        let power_range = 0.0..=100.0;
+        if ![p.power_min, p.power_max, p.label_power]
+            .iter()
+            .all(|power| power_range.contains(power))
+        {
+            return Err(CamToolError::InvalidParameters(
+                "power must be between 0% and 100%".to_string(),
+            ));
+        }
+
+        let powers = Self::steps(p.power_min, p.power_max, p.power_steps);
+        let speeds = Self::steps(p.speed_min, p.speed_max, p.speed_steps);
+
+        let row_label_height = p.label_height.min(p.square_size);
+        let row_label_width = powers
+            .iter()
+            .map(|&power| stroke_font::text_width(&Self::power_label(power), row_label_height))
+            .fold(0.0, f32::max);
+
+        // Column labels must not run into their neighbours
+        let column_room = p.square_size + p.spacing * 0.5;
+        let widest_column_label = speeds
+            .iter()
+            .map(|&speed| stroke_font::text_width(&Self::speed_label(speed), p.label_height))
+            .fold(0.0, f32::max);
+        let column_label_height = if widest_column_label > column_room {
+            p.label_height * column_room / widest_column_label
+        } else {
+            p.label_height
+        };
+
+        let grid_x = row_label_width + p.spacing;
+        let grid_y = column_label_height + p.spacing;
+        let columns = speeds.len() as f32;
+        let rows = powers.len() as f32;
+        let width = grid_x + columns * p.square_size + (columns - 1.0) * p.spacing;
+        let height = grid_y + rows * p.square_size + (rows - 1.0) * p.spacing;
+
+        if width > p.stock_width || height > p.stock_height {
+            return Err(CamToolError::InvalidParameters(format!(
+                "test grid needs {:.1} x {:.1} mm but the stock is {:.1} x {:.1} mm",
+                width, height, p.stock_width, p.stock_height
+            )));
+        }
+
+        Ok(MaterialTestLayout {
+            width,
+            height,
+            powers,
+            speeds,
+            grid_x,
+            grid_y,
+            row_label_height,
+            column_label_height,
+        })
+    }
+
+    /// Extents and size of the G-code [`generate_gcode`](Self::generate_gcode) writes
+    pub fn preview(&self) -> Result<GeneratorPreview> {
+        Ok(GeneratorPreview::from_program(&self.generate_gcode()?))
+    }
+
+    /// Generate G-code for the test grid
+    pub fn generate_gcode(&self) -> Result<String> {
+        let layout = self.layout()?;
+        let p = &self.params;
+        p.air_assist.validate()?;
+        let pitch = p.square_size + p.spacing;
+        let mut gcode = String::new();
+
+        gcode.push_str("; Laser Material Test Grid\n");
+        gcode.push_str(&format!(
+            "; Grid size: {:.2}mm x {:.2}mm\n",
+            layout.width, layout.height
+        ));
+        gcode.push_str(&format!(
+            "; Power: {:.0}%-{:.0}% in {} rows\n",
+            p.power_min, p.power_max, p.power_steps
+        ));
+        gcode.push_str(&format!(
+            "; Speed: {:.0}-{:.0} mm/min in {} columns\n",
+            p.speed_min, p.speed_max, p.speed_steps
+        ));
+        if let Some(comment) = p.air_assist.header_comment() {
+            gcode.push_str(&comment);
+        }
+        gcode.push_str(";\n");
+
+        gcode.push_str("G21 ; Set units to millimeters\n");
+        gcode.push_str("G90 ; Absolute positioning\n");
+        gcode.push_str("G17 ; XY plane selection\n");
+        gcode.push('\n');
+
+        gcode.push_str("; Home and set work coordinate system\n");
+        gcode.push_str("$H ; Home all axes (bottom-left corner)\n");
+        gcode.push_str("G10 L2 P1 X0 Y0 ; Clear G54 offset\n");
+        gcode.push_str("G54 ; Select work coordinate system 1\n");
+        gcode.push_str(&format!(
+            "G0 X{:.1} Y{:.1} ; Move to work origin\n",
+            p.offset_x, p.offset_y
+        ));
+        gcode.push_str("G10 L20 P1 X0 Y0 ; Set current position as work zero\n");
+        gcode.push_str("M5 ; Laser off\n");
+
+        let mut air = AirAssistState::new(&p.air_assist);
+        let annotator = Annotator::new(AnnotatedTool::MaterialTest, self.annotation);
+        annotator.section(
+            &mut gcode,
+            format_args!("Squares {}x{}", p.speed_steps, p.power_steps),
+        );
+        for (row, &power) in layout.powers.iter().enumerate() {
+            let y = layout.grid_y + row as f32 * pitch;
+            for (column, &speed) in layout.speeds.iter().enumerate() {
+                let x = layout.grid_x + column as f32 * pitch;
+                gcode.push_str(&format!(
+                    "\n; Square: {:.0}% at {:.0} mm/min\n",
+                    power, speed
+                ));
+                annotator.line(
+                    &mut gcode,
+                    format_args!(
+                        "Square row {} column {} {:.0}% F{:.0}",
+                        row + 1,
+                        column + 1,
+                        power,
+                        speed
+                    ),
+                );
+                for stroke in self.square_strokes(x, y) {
+                    self.push_stroke(&mut gcode, &mut air, &stroke, power, speed);
+                }
+                air.end_operation(&mut gcode);
+            }
+        }
+
+        gcode.push_str("\n; Labels\n");
+        annotator.section(&mut gcode, format_args!("Labels"));
+        for (row, &power) in layout.powers.iter().enumerate() {
+            let label = Self::power_label(power);
+            annotator.line(&mut gcode, format_args!("Row label {}", label));
+            let label_width = stroke_font::text_width(&label, layout.row_label_height);
+            let x = layout.grid_x - p.spacing - label_width;
+            let y = layout.grid_y
+                + row as f32 * pitch
+                + (p.square_size - layout.row_label_height) / 2.0;
+            for stroke in stroke_font::text_strokes(&label, x, y, layout.row_label_height) {
+                self.push_stroke(&mut gcode, &mut air, &stroke, p.label_power, p.label_speed);
+            }
+        }
+        for (column, &speed) in layout.speeds.iter().enumerate() {
+            let label = Self::speed_label(speed);
+            annotator.line(&mut gcode, format_args!("Column label {}", label));
+            let label_width = stroke_font::text_width(&label, layout.column_label_height);
+            let x = layout.grid_x + column as f32 * pitch + (p.square_size - label_width) / 2.0;
+            for stroke in stroke_font::text_strokes(&label, x, 0.0, layout.column_label_height) {
+                self.push_stroke(&mut gcode, &mut air, &stroke, p.label_power, p.label_speed);
+            }
+        }
+
+        air.end_operation(&mut gcode);
+
+        gcode.push_str("\n; End of material test\n");
+        gcode.push_str("M5 ; Laser off\n");
+        air.end_job(&mut gcode);
+        gcode.push_str("G0 X0 Y0 ; Return to origin\n");
+        gcode.push_str("M30 ; End program\n");
+
+        Ok(gcode)
+    }
+
+    /// Evenly spaced values from `min` to `max` inclusive
+    fn steps(min: f32, max: f32, count: u32) -> Vec<f32> {
+        if count == 1 {
+            return vec![min];
+        }
+        (0..count)
+            .map(|i| min + (max - min) * i as f32 / (count - 1) as f32)
+            .collect()
+    }
+
+    fn square_strokes(&self, x: f32, y: f32) -> Vec<Vec<(f32, f32)>> {
+        let size = self.params.square_size;
+        match self.params.style {
+            TestSquareStyle::Outline => vec![vec![
+                (x, y),
+                (x + size, y),
+                (x + size, y + size),
+                (x, y + size),
+                (x, y),
+            ]],
+            TestSquareStyle::Filled => {
+                let interval = self.params.line_interval;
+                let lines = ((size / interval).floor() as usize).max(1);
+                (0..lines)
+                    .map(|i| {
+                        let line_y = y + (i as f32 + 0.5) * size / lines as f32;
+                        if i % 2 == 0 {
+                            vec![(x, line_y), (x + size, line_y)]
+                        } else {
+                            vec![(x + size, line_y), (x, line_y)]
+                        }
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    fn push_stroke(
+        &self,
+        gcode: &mut String,
+        air: &mut AirAssistState,
+        stroke: &[(f32, f32)],
+        power: f32,
+        speed: f32,
+    ) {
+        let Some((&(start_x, start_y), rest)) = stroke.split_first() else {
+            return;
+        };
+        let power_value = (power * self.params.power_scale / 100.0) as u32;
+        gcode.push_str(&format!("G0 X{:.3} Y{:.3}\n", start_x, start_y));
+        air.before_burn(gcode);
+        gcode.push_str(&format!("M3 S{}\n", power_value));
+        for (i, &(x, y)) in rest.iter().enumerate() {
+            if i == 0 {
+                gcode.push_str(&format!("G1 X{:.3} Y{:.3} F{:.0}\n", x, y, speed));
+            } else {
+                gcode.push_str(&format!("G1 X{:.3} Y{:.3}\n", x, y));
+            }
+        }
+        gcode.push_str("M5\n");
+    }
 }
@@ -9,39 +9,93 @@
 //! - **Jigsaw Puzzle Maker**: Generate G-Code for cutting interlocking puzzle pieces
 //! - **Tabbed Box Maker**: Create finger-jointed box designs with customizable parameters
 //! - **Drill Press**: Specialized drilling cycles including peck drilling and helical interpolation
-//! - **Laser Engraver**: Specialized processing for laser cutting and engraving
+//! - **Laser Engraver**: Specialized processing for laser cutting and engraving, plus
+//!   power/speed material test grids
+//! - **Air Assist**: Configurable M-codes that switch laser air assist around the burns
 //! - **Vector Engraver**: Vector path cutting with advanced contour and fill options
-//! - **Arc Expander**: Arc interpolation and expansion
+//! - **Bitmap Trace**: Outline and centerline tracing of bitmaps for vector cutting
+//! - **Arc Expander**: Arc interpolation and expansion, and splitting of long arcs
 //!
 //! ## Supporting Infrastructure
 //!
 //! - **Core Infrastructure**: Application state, configuration, logging, and telemetry
 //! - **Optimizer**: G-Code optimization and minimization
 //! - **Validator**: G-Code validation and safety checks
+//! - **Preflight**: Configurable checks run before a job is streamed
+//! - **Quick Check**: The first out-of-bounds move, low rapid or bad arc in a program
+//! - **Program Merge**: Join programs into one job with safe transitions between them
+//! - **Clearance Plane**: Normalize rapid retract and traverse heights to one plane
+//! - **Safe Rapids**: Turn rapids that travel below a Z threshold into feed moves
+//! - **Origin Placement**: Shift a program so a stock corner or center becomes the origin
+//! - **Tiling**: Repeat a program on a grid of copies
+//! - **Rotation**: Rotate a program about a pivot, with the skew measured from two probed points
+//! - **Job Sequence**: Saved multi-tool workflows of programs, pauses and tool probes
+//! - **Dry Run**: Raise a program above the stock to air-cut it, and restore it again
+//! - **Spindle Speed Clamp**: Clamp spindle speeds to the machine's maximum and lower feeds to match
+//! - **Spindle Continuity**: Keep the spindle running between operations on the same tool and speed
+//! - **Hole Sequencing**: Reorder drilled holes for the shortest travel between them
+//! - **Biarc Fitting**: Smooth tangent-continuous arcs through dense points or runs of short G1 moves
+//! - **Distance Mode**: Convert programs between absolute (G90) and incremental (G91) coordinates
+//! - **Feed Mode**: Rewrite G93 inverse-time and G95 per-revolution feeds as G94
+//! - **Flatten**: Expand subprograms, parameters and canned cycles into a flat GRBL-compatible program
 //! - **Comment Processor**: G-Code comment handling
+//! - **Annotation**: Optional comments naming the generator feature behind each section
 //! - **Statistics**: G-Code statistics and analysis
+//! - **Time Estimator**: Run time with acceleration and junction lookahead
+//! - **Job Telemetry**: Actual versus estimated job times and per-machine correction
+//! - **Stroke Font**: Single-line font for engraved labels
+//! - **Neutral Toolpath**: Parsed programs as versioned JSON for scripts, and back to G-code
+//! - **Headless**: GTK-free generator entry points for scripting and CI
+//! - **Generator Preview**: Output bounds and line counts of every generator, for fit checks
 //!
 //! ## UI Components
 //!
 //! - **Advanced Features Panel**: UI panel for controlling CAM tools
 
 pub mod advanced_features;
+pub mod air_assist;
+pub mod annotation;
 pub mod arc_expander;
+pub mod biarc;
+pub mod bitmap_trace;
+pub mod clearance_plane;
 pub mod comment_processor;
 pub mod core_infrastructure;
+pub mod distance_mode;
 pub mod drill_press;
+pub mod dry_run;
 pub mod error;
+pub mod feed_mode;
+pub mod flatten;
 pub mod gerber;
 pub mod hatch_generator;
 mod hatch_test;
+pub mod headless;
+pub mod hole_sequence;
 pub mod jigsaw_puzzle;
+pub mod job_sequence;
+pub mod job_telemetry;
 pub mod laser_engraver;
+pub mod load_check;
+pub mod neutral_toolpath;
 pub mod optimizer;
+pub mod origin_placement;
+pub mod preflight;
+pub mod preview;
+pub mod program_merge;
+pub mod quick_check;
+pub mod rotation;
+pub mod safe_rapids;
 pub mod speeds_feeds;
+pub mod spindle_clamp;
+pub mod spindle_continuity;
 pub mod spoilboard_grid;
 pub mod spoilboard_surfacing;
 pub mod stats;
+pub mod stroke_font;
 pub mod tabbed_box;
+pub mod tiling;
+pub mod time_estimator;
 pub mod validator;
 pub mod vector_engraver;
 
@@ -52,26 +106,75 @@ pub mod advanced_features_panel;
 pub use advanced_features::{
     CommandHistory, ProbingSystem, SimulationMode, SoftLimits, ToolLibrary, WorkCoordinateManager,
 };
-pub use arc_expander::ArcExpander;
+pub use air_assist::AirAssist;
+pub use annotation::{
+    annotation_comment, is_annotation, AnnotatedTool, AnnotationLevel, Annotator,
+};
+pub use arc_expander::{ArcExpander, ArcSplitReport};
+pub use biarc::{
+    fit_biarcs, fit_biarcs_in_program, segments_to_gcode, BiarcFitReport, BiarcOptions,
+    FittedSegment,
+};
+pub use bitmap_trace::{BitmapTracer, TraceMode, TraceParameters, TracedContour, TurnPolicy};
+pub use clearance_plane::{enforce_clearance, ClearanceOptions, ClearanceReport};
 pub use comment_processor::CommentProcessor;
 pub use core_infrastructure::{AppConfig, ApplicationState, Logger, TelemetryData};
+pub use distance_mode::{convert_distance_mode, DistanceMode, DistanceModeReport};
 pub use drill_press::{DrillPressGenerator, DrillPressParameters};
+pub use dry_run::{
+    apply_dry_run, is_dry_run, remove_dry_run, toggle_dry_run, DryRunOptions, DryRunSpindle,
+};
 pub use error::{
     CamToolError, CamToolResult, FileFormatError, FileFormatResult, ParameterError, ParameterResult,
 };
+pub use feed_mode::convert_to_units_per_minute;
+pub use flatten::{flatten_program, FlattenOptions, FlattenReport, FlattenWarning};
 pub use gerber::{GerberConverter, GerberLayerType, GerberParameters};
+pub use hole_sequence::{resequence_holes, HoleSequenceReport};
 pub use jigsaw_puzzle::{JigsawPuzzleMaker, PuzzleParameters};
+pub use job_sequence::{JobSequence, JobStep, SequenceStage, ToolProbeConfig};
+pub use job_telemetry::{JobTimer, JobTimingLog, JobTimingRecord};
 pub use laser_engraver::{
-    BitmapImageEngraver, EngravingParameters, HalftoneMethod, ImageTransformations, RotationAngle,
-    ScanDirection,
+    BitmapImageEngraver, EngravingParameters, HalftoneMethod, ImageTransformations,
+    MaterialTestGenerator, MaterialTestLayout, MaterialTestParameters, RotationAngle,
+    ScanDirection, TestSquareStyle,
+};
+pub use load_check::{
+    check_loaded_program, convert_units, LoadCheck, UnitConversionReport, UnitsSource,
+};
+pub use neutral_toolpath::{
+    ModalContext, MoveKind, NeutralMove, NeutralToolpath, SpindleMode, SpindleState, WrittenUnits,
+    NEUTRAL_SCHEMA, NEUTRAL_VERSION,
 };
 pub use optimizer::GCodeOptimizer;
-pub use speeds_feeds::{CalculationResult, SpeedsFeedsCalculator};
-pub use spoilboard_grid::{SpoilboardGridGenerator, SpoilboardGridParameters};
-pub use spoilboard_surfacing::{SpoilboardSurfacingGenerator, SpoilboardSurfacingParameters};
-pub use stats::StatsCalculator;
+pub use origin_placement::{place_origin, translate_program, OriginReference, StockBounds};
+pub use preflight::{
+    has_failures, preflight, PreflightCheck, PreflightConfig, PreflightSeverity, PreflightWarning,
+};
+pub use preview::{GeneratorPreview, PreviewBounds};
+pub use program_merge::{merge_programs, MergeOptions, MergeSource};
+pub use quick_check::{first_problem, QuickCheckConfig, QuickCheckIssue, QuickCheckKind};
+pub use rotation::{rotate_program, skew_from_probes, ProbedEdge, SkewCorrection};
+pub use safe_rapids::{convert_low_rapids, SafeRapidOptions, SafeRapidReport};
+pub use speeds_feeds::{
+    CalculationResult, ChipLoadReport, ChipLoadStatus, FeedChipLoad, SpeedsFeedsCalculator,
+};
+pub use spindle_clamp::{
+    clamp_spindle_speed, SpindleAdjustment, SpindleAdjustmentKind, SpindleClampMode,
+    SpindleClampOptions, SpindleClampReport,
+};
+pub use spindle_continuity::{keep_spindle_running, SpindleContinuityReport};
+pub use spoilboard_grid::{
+    SpoilboardGridGenerator, SpoilboardGridParameters, SpoilboardHoleParameters,
+};
+pub use spoilboard_surfacing::{
+    FlatnessReport, SpoilboardSurfacingGenerator, SpoilboardSurfacingParameters,
+};
+pub use stats::{Stats, StatsCalculator, ToolUsage};
 pub use tabbed_box::{
     BoxParameters, BoxType, FingerJointSettings, FingerStyle, KeyDividerType, TabbedBoxMaker,
 };
+pub use tiling::{program_extents, tile_extents, tile_program, TileOptions};
+pub use time_estimator::{estimate_time, MotionLimits, TimeEstimate};
 pub use validator::GCodeValidator;
 pub use vector_engraver::{VectorEngraver, VectorEngravingParameters};
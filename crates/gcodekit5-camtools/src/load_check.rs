@@ -0,0 +1,254 @@
+//! # Load Check
+//!
+//! A quick look at a program when it is opened: whether its extents fit the
+//! travel of the active device profile, and whether its units match the
+//! measurement system the user works in. The result is a one-line summary such
+//! as "Fits: 120.000 x 80.000 mm" or "Too big by X 20.000 mm; inch file (G20)
+//! in mm mode", meant for a notification the user can dismiss or act on.
+//!
+//! A program without G20/G21 runs in whatever units the controller is in. Its
+//! units are then guessed from the size of its coordinates: when no X or Y
+//! coordinate is larger than [`INCH_GUESS_MAX`] the program is taken to be in
+//! inches. [`convert_units`] rewrites a program in the other units.
+
+use crate::preflight::ProgramScan;
+use crate::validator::GCodeValidator;
+use gcodekit5_core::units::{format_length, get_unit_label, MeasurementSystem};
+use gcodekit5_core::{format_gcode_number, Units};
+use gcodekit5_devicedb::DeviceProfile;
+
+/// A program without units whose X and Y coordinates all stay within this
+/// many units is guessed to be in inches
+pub const INCH_GUESS_MAX: f64 = 20.0;
+
+/// Millimeters per inch
+const MM_PER_INCH: f64 = 25.4;
+
+/// Words that are lengths and change with the units
+const LENGTH_WORDS: [char; 8] = ['X', 'Y', 'Z', 'I', 'J', 'K', 'R', 'Q'];
+
+/// How the program's units were found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitsSource {
+    /// The program selects them with G20 or G21
+    Declared,
+    /// Guessed from the size of the coordinates
+    Guessed,
+}
+
+/// What [`check_loaded_program`] found
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadCheck {
+    /// Units the program is in: [`Units::MM`] or [`Units::INCH`]
+    pub units: Units,
+    /// Whether the units were declared or guessed
+    pub units_source: UnitsSource,
+    /// Width, depth and height of the program's travel (mm); `None` without moves
+    pub size: Option<[f64; 3]>,
+    /// Width and depth of the device's travel (mm); `None` for an axis without
+    /// limits, or without a profile
+    pub travel: [Option<f64>; 2],
+    /// How much wider and deeper the program is than the travel (mm); zero
+    /// where it fits
+    pub overflow: [f64; 2],
+    /// The measurement system the program was checked against
+    pub system: MeasurementSystem,
+}
+
+impl LoadCheck {
+    /// Whether the program fits the device's travel
+    pub fn fits(&self) -> bool {
+        self.overflow.iter().all(|over| *over <= 0.0)
+    }
+
+    /// Whether the program's units differ from the measurement system
+    pub fn units_mismatch(&self) -> bool {
+        let metric = self.system == MeasurementSystem::Metric;
+        (self.units == Units::INCH) == metric
+    }
+
+    /// Whether there is nothing to warn about
+    pub fn is_ok(&self) -> bool {
+        self.fits() && !self.units_mismatch()
+    }
+
+    /// One-line summary for display
+    pub fn summary(&self) -> String {
+        let unit = get_unit_label(self.system);
+        let length = |mm: f64| format!("{} {}", format_length(mm as f32, self.system), unit);
+        let mut parts = Vec::new();
+        match self.size {
+            None => parts.push("No moves".to_string()),
+            Some(_) if !self.fits() => {
+                let over: Vec<String> = ["X", "Y"]
+                    .iter()
+                    .zip(self.overflow)
+                    .filter(|(_, over)| *over > 0.0)
+                    .map(|(axis, over)| format!("{} {}", axis, length(over)))
+                    .collect();
+                parts.push(format!("Too big by {}", over.join(", ")));
+            }
+            Some([width, depth, _]) => parts.push(format!(
+                "Fits: {} x {}",
+                format_length(width as f32, self.system),
+                length(depth)
+            )),
+        }
+        if self.units_mismatch() {
+            let (file, mode) = match self.units {
+                Units::INCH => ("inch", "mm"),
+                _ => ("mm", "inch"),
+            };
+            let evidence = match (self.units_source, self.units) {
+                (UnitsSource::Declared, Units::INCH) => "G20",
+                (UnitsSource::Declared, _) => "G21",
+                (UnitsSource::Guessed, _) => "no G20/G21, guessed from coordinates",
+            };
+            parts.push(format!("{} file ({}) in {} mode", file, evidence, mode));
+        }
+        parts.join("; ")
+    }
+}
+
+/// Check a program's extents against `profile` and its units against `system`.
+///
+/// Extents are only compared on axes whose limits are enabled; without a
+/// profile the program always fits.
+pub fn check_loaded_program(
+    program: &str,
+    profile: Option<&DeviceProfile>,
+    system: MeasurementSystem,
+) -> LoadCheck {
+    let scan = ProgramScan::new(program);
+    let extent = |axis: usize| scan.work[axis].map(|e| e.max - e.min);
+    let largest_xy = scan.work[..2]
+        .iter()
+        .flatten()
+        .map(|e| e.min.abs().max(e.max.abs()))
+        .fold(0.0, f64::max);
+
+    let (units, units_source) = match scan.units_word {
+        Some(20) => (Units::INCH, UnitsSource::Declared),
+        Some(_) => (Units::MM, UnitsSource::Declared),
+        None if scan.work[..2].iter().any(Option::is_some) && largest_xy <= INCH_GUESS_MAX => {
+            (Units::INCH, UnitsSource::Guessed)
+        }
+        None => (Units::MM, UnitsSource::Guessed),
+    };
+    // A declared G20 is already applied by the scan
+    let scale = if units_source == UnitsSource::Guessed && units == Units::INCH {
+        MM_PER_INCH
+    } else {
+        1.0
+    };
+    let size = scan.work.iter().any(Option::is_some).then(|| {
+        [
+            extent(0).unwrap_or(0.0) * scale,
+            extent(1).unwrap_or(0.0) * scale,
+            extent(2).unwrap_or(0.0) * scale,
+        ]
+    });
+
+    let limits = profile.map(|p| [&p.x_axis, &p.y_axis]);
+    let travel = [0, 1].map(|axis| {
+        limits
+            .map(|l| l[axis])
+            .filter(|limit| limit.enabled)
+            .map(|limit| limit.max - limit.min)
+    });
+    let overflow = [0, 1].map(|axis| match (size, travel[axis]) {
+        (Some(size), Some(travel)) => (size[axis] - travel).max(0.0),
+        _ => 0.0,
+    });
+
+    LoadCheck {
+        units,
+        units_source,
+        size,
+        travel,
+        overflow,
+        system,
+    }
+}
+
+/// Result of [`convert_units`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitConversionReport {
+    /// The rewritten program
+    pub program: String,
+    /// Lines whose words were rescaled
+    pub lines_converted: usize,
+}
+
+/// Rewrite `program` in `target` units.
+///
+/// Lines before any G20/G21 are taken to be in `assumed` units. Lengths
+/// (X, Y, Z, I, J, K, R, Q) are rescaled everywhere, and feed rates except in
+/// inverse time mode (G93); rotary axes, dwell times and spindle speeds are
+/// unchanged. Every G20/G21 becomes the target's code, and a program that never
+/// selects its units starts with one.
+pub fn convert_units(program: &str, assumed: Units, target: Units) -> UnitConversionReport {
+    let in_inches = |units: Units| units == Units::INCH;
+    let target_code = if in_inches(target) { "20" } else { "21" };
+    let mut source_inches = in_inches(assumed);
+    let mut inverse_time = false;
+    let mut declared = false;
+    let mut lines_converted = 0;
+    let mut output = Vec::new();
+
+    for line in program.lines() {
+        let mut words = GCodeValidator::word_texts(line);
+        let mut changed = false;
+        for (letter, number) in &words {
+            match (*letter, number.parse::<f64>()) {
+                ('G', Ok(20.0)) => source_inches = true,
+                ('G', Ok(21.0)) => source_inches = false,
+                ('G', Ok(93.0)) => inverse_time = true,
+                ('G', Ok(94.0 | 95.0)) => inverse_time = false,
+                _ => {}
+            }
+        }
+        let factor = match (source_inches, in_inches(target)) {
+            (true, false) => MM_PER_INCH,
+            (false, true) => 1.0 / MM_PER_INCH,
+            _ => 1.0,
+        };
+        let decimals = if in_inches(target) { 4 } else { 3 };
+        for (letter, number) in words.iter_mut() {
+            let Ok(value) = number.parse::<f64>() else {
+                continue;
+            };
+            if *letter == 'G' && (value == 20.0 || value == 21.0) {
+                declared = true;
+                if number != target_code {
+                    *number = target_code.to_string();
+                    changed = true;
+                }
+                continue;
+            }
+            let length = LENGTH_WORDS.contains(letter) || (*letter == 'F' && !inverse_time);
+            if length && factor != 1.0 {
+                *number = format_gcode_number(value * factor, decimals);
+                changed = true;
+            }
+        }
+        if changed {
+            lines_converted += 1;
+            output.push(GCodeValidator::rebuild_line(line, &words));
+        } else {
+            output.push(line.to_string());
+        }
+    }
+    if !declared {
+        output.insert(0, format!("G{}", target_code));
+    }
+
+    let mut text = output.join("\n");
+    if program.ends_with('\n') {
+        text.push('\n');
+    }
+    UnitConversionReport {
+        program: text,
+        lines_converted,
+    }
+}
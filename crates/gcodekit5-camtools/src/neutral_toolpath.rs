@@ -0,0 +1,482 @@
+//! # Neutral Toolpath
+//!
+//! A parsed program as plain JSON, so scripts can post-process toolpaths without
+//! parsing G-code themselves. Every move carries absolute start and end points,
+//! the resolved center of arcs, the feed rate, the spindle or laser state, the
+//! program line it came from and the modal context it was written in:
+//!
+//! ```json
+//! {
+//!   "schema": "gcodekit5.toolpath",
+//!   "version": 1,
+//!   "moves": [
+//!     {
+//!       "line": 4,
+//!       "kind": "arc_cw",
+//!       "start": [0.0, 10.0, -1.0],
+//!       "end": [10.0, 0.0, -1.0],
+//!       "center": [0.0, 0.0, -1.0],
+//!       "feed_rate": 600.0,
+//!       "spindle": { "mode": "cw", "speed": 12000.0 },
+//!       "modal": { "units": "mm", "incremental": false, "plane": "XY", "work_offset": 54 }
+//!     }
+//!   ],
+//!   "skipped_lines": [12]
+//! }
+//! ```
+//!
+//! Lengths are millimetres and feed rates mm/min, whatever units the program was
+//! written in; positions are work coordinates. `kind` is `rapid`, `linear`,
+//! `arc_cw` or `arc_ccw`; `center` is only set for arcs and `feed_rate` is null
+//! for rapids and until the program sets one. `spindle.mode` is `off`, `cw`
+//! (M3) or `ccw` (M4, dynamic laser power). `modal` records the units and
+//! distance mode the line was written in, the arc plane and the selected work
+//! offset (54-59). Line numbers are 0-based.
+//!
+//! Lines whose moves cannot be placed in work coordinates (G53, G28/G30 and
+//! probing) are listed in `skipped_lines`; later moves continue from the last
+//! known position. `version` is raised whenever the schema changes; files with
+//! an unknown schema or a newer version are refused.
+//!
+//! [`NeutralToolpath::to_gcode`] turns a document back into G-code through the
+//! designer's [`ToolpathToGcode`]. The cuts are the same; rapids travel at the
+//! highest rapid height of the program and the tool feeds down to each cut.
+
+use crate::error::{CamToolError, CamToolResult};
+use crate::validator::GCodeValidator;
+use gcodekit5_core::{apply_block_delete, ArcMove, ArcPlane, Units};
+use gcodekit5_designer::{
+    EmitState, Point, Toolpath, ToolpathSegment, ToolpathSegmentType, ToolpathToGcode,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Schema name written to every document
+pub const NEUTRAL_SCHEMA: &str = "gcodekit5.toolpath";
+
+/// Current schema version
+pub const NEUTRAL_VERSION: u32 = 1;
+
+/// Flattening of G18/G19 arcs, which the G-code generator cannot write (mm)
+const PLANE_ARC_SEGMENT_MM: f64 = 0.5;
+
+/// Motion of a move
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MoveKind {
+    Rapid,
+    Linear,
+    ArcCw,
+    ArcCcw,
+}
+
+/// Spindle direction, or laser mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpindleMode {
+    #[default]
+    Off,
+    /// M3: clockwise, or constant laser power
+    Cw,
+    /// M4: counter-clockwise, or dynamic laser power
+    Ccw,
+}
+
+/// Spindle or laser state during a move
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct SpindleState {
+    pub mode: SpindleMode,
+    /// Last S word: RPM, or laser power
+    pub speed: f64,
+}
+
+/// Units a line was written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WrittenUnits {
+    #[default]
+    Mm,
+    Inch,
+}
+
+/// Modal state a move was written in
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModalContext {
+    pub units: WrittenUnits,
+    /// G91 distance mode
+    pub incremental: bool,
+    pub plane: ArcPlane,
+    /// Selected work coordinate system, 54-59
+    pub work_offset: u8,
+}
+
+impl Default for ModalContext {
+    fn default() -> Self {
+        Self {
+            units: WrittenUnits::Mm,
+            incremental: false,
+            plane: ArcPlane::XY,
+            work_offset: 54,
+        }
+    }
+}
+
+/// One move of the program
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NeutralMove {
+    /// Program line (0-based)
+    pub line: usize,
+    pub kind: MoveKind,
+    /// Absolute work position before the move (mm)
+    pub start: [f64; 3],
+    /// Absolute work position after the move (mm)
+    pub end: [f64; 3],
+    /// Arc center (mm); the linear-axis component is the start's
+    pub center: Option<[f64; 3]>,
+    /// Feed rate in mm/min; `None` for rapids
+    pub feed_rate: Option<f64>,
+    pub spindle: SpindleState,
+    pub modal: ModalContext,
+}
+
+impl NeutralMove {
+    fn arc(&self) -> Option<ArcMove> {
+        let clockwise = match self.kind {
+            MoveKind::ArcCw => true,
+            MoveKind::ArcCcw => false,
+            _ => return None,
+        };
+        Some(ArcMove {
+            start: self.start,
+            end: self.end,
+            center: self.center?,
+            clockwise,
+            plane: self.modal.plane,
+        })
+    }
+}
+
+/// A parsed program in the neutral format
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NeutralToolpath {
+    pub schema: String,
+    pub version: u32,
+    pub moves: Vec<NeutralMove>,
+    /// Lines with moves that could not be placed in work coordinates
+    #[serde(default)]
+    pub skipped_lines: Vec<usize>,
+}
+
+impl Default for NeutralToolpath {
+    fn default() -> Self {
+        Self {
+            schema: NEUTRAL_SCHEMA.to_string(),
+            version: NEUTRAL_VERSION,
+            moves: Vec::new(),
+            skipped_lines: Vec::new(),
+        }
+    }
+}
+
+impl NeutralToolpath {
+    /// Parse a program; optional (`/`) lines are skipped
+    ///
+    /// The position starts at work zero until the program sets it.
+    pub fn from_gcode(program: &str) -> Self {
+        let mut document = Self::default();
+        let mut modal = ModalContext::default();
+        let mut position = [0.0; 3];
+        let mut motion: Option<MoveKind> = None;
+        let mut absolute_arc_centers = false;
+        let mut feed_rate: Option<f64> = None;
+        let mut spindle = SpindleState::default();
+
+        for (line_num, line) in program.lines().enumerate() {
+            let Some(line) = apply_block_delete(line, true) else {
+                continue;
+            };
+            let words = GCodeValidator::words(line);
+            if words.is_empty() {
+                continue;
+            }
+
+            let mut unplaceable = false;
+            let mut sets_position = false;
+            let mut non_motion = false;
+            for &(letter, value) in &words {
+                match (letter, value) {
+                    ('G', 0.0) => motion = Some(MoveKind::Rapid),
+                    ('G', 1.0) => motion = Some(MoveKind::Linear),
+                    ('G', 2.0) => motion = Some(MoveKind::ArcCw),
+                    ('G', 3.0) => motion = Some(MoveKind::ArcCcw),
+                    ('G', 17.0) => modal.plane = ArcPlane::XY,
+                    ('G', 18.0) => modal.plane = ArcPlane::ZX,
+                    ('G', 19.0) => modal.plane = ArcPlane::YZ,
+                    ('G', 20.0) => modal.units = WrittenUnits::Inch,
+                    ('G', 21.0) => modal.units = WrittenUnits::Mm,
+                    ('G', 90.0) => modal.incremental = false,
+                    ('G', 91.0) => modal.incremental = true,
+                    ('G', v) if (v - 90.1).abs() < 1e-6 => absolute_arc_centers = true,
+                    ('G', v) if (v - 91.1).abs() < 1e-6 => absolute_arc_centers = false,
+                    ('G', v) if (54.0..=59.0).contains(&v) && v == v.trunc() => {
+                        modal.work_offset = v as u8
+                    }
+                    ('G', 53.0 | 28.0 | 30.0) => unplaceable = true,
+                    ('G', v) if (38.0..39.0).contains(&v) => unplaceable = true,
+                    ('G', 92.0) => sets_position = true,
+                    // G10 L20 names the current position; other G10 forms only
+                    // store offsets
+                    ('G', 10.0) if words.contains(&('L', 20.0)) => sets_position = true,
+                    ('G', 10.0) => non_motion = true,
+                    ('G', 4.0) => non_motion = true,
+                    ('G', v) if (80.0..=89.0).contains(&v) && v == v.trunc() => motion = None,
+                    ('M', 3.0) => spindle.mode = SpindleMode::Cw,
+                    ('M', 4.0) => spindle.mode = SpindleMode::Ccw,
+                    ('M', 5.0) => spindle.mode = SpindleMode::Off,
+                    _ => {}
+                }
+            }
+
+            let scale = match modal.units {
+                WrittenUnits::Mm => 1.0,
+                WrittenUnits::Inch => 25.4,
+            };
+            let mut target = position;
+            let mut moved = false;
+            let mut offsets = [0.0; 3];
+            let mut has_offset = false;
+            let mut radius = None;
+            for &(letter, value) in &words {
+                match letter {
+                    'X' | 'Y' | 'Z' => {
+                        let axis = (letter as u8 - b'X') as usize;
+                        let value = value * scale;
+                        target[axis] = if modal.incremental && !sets_position {
+                            position[axis] + value
+                        } else {
+                            value
+                        };
+                        moved = true;
+                    }
+                    'I' | 'J' | 'K' => {
+                        offsets[(letter as u8 - b'I') as usize] = value * scale;
+                        has_offset = true;
+                    }
+                    'R' => radius = Some(value * scale),
+                    'F' => feed_rate = Some(value * scale),
+                    'S' => spindle.speed = value,
+                    _ => {}
+                }
+            }
+
+            if unplaceable {
+                document.skipped_lines.push(line_num);
+                continue;
+            }
+            if sets_position {
+                // G92 / G10 L20 name the current position in a new frame
+                position = target;
+                continue;
+            }
+            if non_motion {
+                continue;
+            }
+            let Some(kind) = motion else {
+                continue;
+            };
+            let is_arc = matches!(kind, MoveKind::ArcCw | MoveKind::ArcCcw);
+            // An arc with only offsets is a full circle
+            if !(moved || is_arc && has_offset) {
+                continue;
+            }
+
+            let start = position;
+            position = target;
+            let center = if is_arc {
+                let clockwise = kind == MoveKind::ArcCw;
+                if has_offset {
+                    if absolute_arc_centers {
+                        let (a, b, _) = modal.plane.axes();
+                        offsets[a] -= start[a];
+                        offsets[b] -= start[b];
+                    }
+                    Some(
+                        ArcMove::from_offsets(start, target, offsets, clockwise, modal.plane)
+                            .center,
+                    )
+                } else {
+                    radius.and_then(|r| {
+                        ArcMove::from_radius(start, target, r, clockwise, modal.plane)
+                            .ok()
+                            .map(|arc| arc.center)
+                    })
+                }
+            } else {
+                None
+            };
+            // Arcs that cannot be resolved are kept as straight moves
+            let kind = match center {
+                None if is_arc => MoveKind::Linear,
+                _ => kind,
+            };
+
+            document.moves.push(NeutralMove {
+                line: line_num,
+                kind,
+                start,
+                end: target,
+                center,
+                feed_rate: (kind != MoveKind::Rapid).then_some(feed_rate).flatten(),
+                spindle,
+                modal,
+            });
+        }
+        document
+    }
+
+    /// Read a document from JSON, refusing unknown schemas and newer versions
+    pub fn from_json(json: &str) -> CamToolResult<Self> {
+        let document: Self = serde_json::from_str(json)?;
+        if document.schema != NEUTRAL_SCHEMA {
+            return Err(CamToolError::UnsupportedFormat(format!(
+                "schema '{}' is not '{}'",
+                document.schema, NEUTRAL_SCHEMA
+            )));
+        }
+        if document.version > NEUTRAL_VERSION {
+            return Err(CamToolError::UnsupportedFormat(format!(
+                "toolpath version {} is newer than the supported version {}",
+                document.version, NEUTRAL_VERSION
+            )));
+        }
+        Ok(document)
+    }
+
+    /// Write the document as pretty-printed JSON
+    pub fn to_json(&self) -> CamToolResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Load a document saved with [`Self::save`]
+    pub fn load(path: &Path) -> CamToolResult<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Save the document as JSON
+    pub fn save(&self, path: &Path) -> CamToolResult<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    /// G-code for the moves, written by the designer's G-code generator
+    ///
+    /// The program is absolute and in millimetres. Spindle commands are written
+    /// where the spindle state changes; G18/G19 arcs are flattened to lines.
+    pub fn to_gcode(&self) -> String {
+        let safe_z = self
+            .moves
+            .iter()
+            .flat_map(|m| [m.start[2], m.end[2]])
+            .fold(f64::NEG_INFINITY, f64::max);
+        let safe_z = if safe_z.is_finite() { safe_z } else { 0.0 };
+        let generator = ToolpathToGcode::new(Units::MM, safe_z);
+
+        let mut gcode = "G90\nG21\nG17\n".to_string();
+        let mut state = EmitState::new(self.moves.first().map_or(safe_z, |m| m.start[2]));
+        let mut spindle: Option<SpindleState> = None;
+        let mut run: Vec<ToolpathSegment> = Vec::new();
+        let flush = |run: &mut Vec<ToolpathSegment>, state: &mut EmitState, gcode: &mut String| {
+            if !run.is_empty() {
+                let toolpath = Toolpath {
+                    segments: std::mem::take(run),
+                    // Only the header uses these, and it is not written
+                    tool_diameter: 0.0,
+                    depth: 0.0,
+                };
+                gcode.push_str(&generator.generate_body_with_state(&toolpath, 10, state));
+            }
+        };
+
+        for m in &self.moves {
+            if spindle != Some(m.spindle) {
+                flush(&mut run, &mut state, &mut gcode);
+                gcode.push_str(&match m.spindle.mode {
+                    SpindleMode::Off => "M5\n".to_string(),
+                    SpindleMode::Cw => format!("M3 S{}\n", m.spindle.speed),
+                    SpindleMode::Ccw => format!("M4 S{}\n", m.spindle.speed),
+                });
+                // The generator writes no spindle commands of its own
+                state.spindle_speed = Some(spindle_word(&m.spindle));
+                spindle = Some(m.spindle);
+            }
+            run.extend(segments(m));
+        }
+        flush(&mut run, &mut state, &mut gcode);
+
+        if spindle.is_some_and(|s| s.mode != SpindleMode::Off) {
+            gcode.push_str("M5\n");
+        }
+        gcode.push_str("M30\n");
+        gcode
+    }
+}
+
+/// Speed given to the generator's segments, so it sees no spindle change
+fn spindle_word(spindle: &SpindleState) -> u32 {
+    match spindle.mode {
+        SpindleMode::Off => 0,
+        _ => spindle.speed.max(0.0).round() as u32,
+    }
+}
+
+/// Generator segments for a move
+fn segments(m: &NeutralMove) -> Vec<ToolpathSegment> {
+    let speed = spindle_word(&m.spindle);
+    let feed = m.feed_rate.unwrap_or(0.0);
+    let point = |p: &[f64; 3]| Point::new(p[0], p[1]);
+    let cut = |kind, start: &[f64; 3], end: &[f64; 3], center: Option<&[f64; 3]>| {
+        let mut segment = match center {
+            Some(center) => {
+                ToolpathSegment::new_arc(kind, point(start), point(end), point(center), feed, speed)
+            }
+            None => ToolpathSegment::new(kind, point(start), point(end), feed, speed),
+        };
+        segment.start_z = Some(start[2]);
+        segment.with_z_depth(end[2])
+    };
+
+    match (m.kind, m.arc()) {
+        (MoveKind::Rapid, _) => {
+            // Vertical rapids are left out: the next cut feeds down from the
+            // generator's travel height to its own start
+            if m.start[0] == m.end[0] && m.start[1] == m.end[1] {
+                return Vec::new();
+            }
+            vec![ToolpathSegment::new(
+                ToolpathSegmentType::RapidMove,
+                point(&m.start),
+                point(&m.end),
+                feed,
+                speed,
+            )]
+        }
+        (_, Some(arc)) if arc.plane == ArcPlane::XY => {
+            let kind = if arc.clockwise {
+                ToolpathSegmentType::ArcCW
+            } else {
+                ToolpathSegmentType::ArcCCW
+            };
+            vec![cut(kind, &m.start, &m.end, Some(&arc.center))]
+        }
+        (_, Some(arc)) => std::iter::once(arc.start)
+            .chain(arc.expand(arc.segments_for(PLANE_ARC_SEGMENT_MM)))
+            .collect::<Vec<_>>()
+            .windows(2)
+            .map(|pair| cut(ToolpathSegmentType::LinearMove, &pair[0], &pair[1], None))
+            .collect(),
+        _ => vec![cut(ToolpathSegmentType::LinearMove, &m.start, &m.end, None)],
+    }
+}
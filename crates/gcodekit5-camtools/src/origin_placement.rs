@@ -0,0 +1,154 @@
+//! # Origin Placement
+//!
+//! Moves a program's work origin to a reference point of the stock (a corner or
+//! the center) by shifting every absolute X/Y position, so the same program can be
+//! reused when the workholding changes. The shift is a pure translation: Z, feeds
+//! and relative (G91) moves are unchanged, and arc I/J offsets stay as they are
+//! because they are relative to the arc start (unless G90.1 absolute arc centers
+//! are active, in which case they are shifted too).
+//!
+//! Lines that set or use other coordinate frames (G10, G28, G30, G53, G92, G38.x)
+//! and dwells are not touched.
+
+use crate::error::{CamToolError, CamToolResult};
+use crate::validator::GCodeValidator;
+use gcodekit5_core::format_gcode_number;
+
+/// Point of the stock that becomes the new origin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OriginReference {
+    /// Minimum X, minimum Y
+    #[default]
+    BottomLeft,
+    /// Maximum X, minimum Y
+    BottomRight,
+    /// Minimum X, maximum Y
+    TopLeft,
+    /// Maximum X, maximum Y
+    TopRight,
+    /// Middle of the stock
+    Center,
+}
+
+impl OriginReference {
+    /// The reference point of a stock box
+    pub fn point(&self, stock: &StockBounds) -> (f64, f64) {
+        match self {
+            Self::BottomLeft => (stock.min_x, stock.min_y),
+            Self::BottomRight => (stock.max_x, stock.min_y),
+            Self::TopLeft => (stock.min_x, stock.max_y),
+            Self::TopRight => (stock.max_x, stock.max_y),
+            Self::Center => (
+                (stock.min_x + stock.max_x) / 2.0,
+                (stock.min_y + stock.max_y) / 2.0,
+            ),
+        }
+    }
+}
+
+/// Stock extents in the program's current work coordinates, in mm
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StockBounds {
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+}
+
+impl StockBounds {
+    /// Create stock bounds from two opposite corners
+    pub fn new(x1: f64, y1: f64, x2: f64, y2: f64) -> Self {
+        Self {
+            min_x: x1.min(x2),
+            min_y: y1.min(y2),
+            max_x: x1.max(x2),
+            max_y: y1.max(y2),
+        }
+    }
+}
+
+/// Shift a program so the stock reference point becomes (0, 0)
+///
+/// Returns the rewritten program. Fails if the stock bounds are not finite.
+pub fn place_origin(
+    program: &str,
+    stock: &StockBounds,
+    reference: OriginReference,
+) -> CamToolResult<String> {
+    let values = [stock.min_x, stock.min_y, stock.max_x, stock.max_y];
+    if values.iter().any(|v| !v.is_finite()) {
+        return Err(CamToolError::InvalidParameters(format!(
+            "stock bounds must be finite, got {:?}",
+            stock
+        )));
+    }
+    let (x, y) = reference.point(stock);
+    Ok(translate_program(program, -x, -y))
+}
+
+/// Translate every absolute X/Y position of a program by (dx, dy) mm
+pub fn translate_program(program: &str, dx: f64, dy: f64) -> String {
+    let mut absolute = true;
+    let mut absolute_arcs = false;
+    let mut scale = 1.0;
+    let mut moving = false;
+    let mut output = Vec::new();
+
+    for line in program.lines() {
+        let mut words = GCodeValidator::word_texts(line);
+        let mut other_frame = false;
+        for (letter, number) in &words {
+            if *letter != 'G' {
+                continue;
+            }
+            let Ok(code) = number.parse::<f64>() else {
+                continue;
+            };
+            match code {
+                0.0 | 1.0 | 2.0 | 3.0 => moving = true,
+                80.0 => moving = false,
+                81.0..=89.0 if code.fract() == 0.0 => moving = true,
+                90.0 => absolute = true,
+                91.0 => absolute = false,
+                90.1 => absolute_arcs = true,
+                91.1 => absolute_arcs = false,
+                20.0 => scale = 1.0 / 25.4,
+                21.0 => scale = 1.0,
+                4.0 | 10.0 | 28.0 | 30.0 | 53.0 | 92.0 => other_frame = true,
+                38.0..=39.0 => other_frame = true,
+                _ => {}
+            }
+        }
+        if !absolute || !moving || other_frame {
+            output.push(line.to_string());
+            continue;
+        }
+
+        let (shift_x, shift_y) = (dx * scale, dy * scale);
+        let mut changed = false;
+        for (letter, number) in words.iter_mut() {
+            let shift = match letter {
+                'X' => shift_x,
+                'Y' => shift_y,
+                'I' if absolute_arcs => shift_x,
+                'J' if absolute_arcs => shift_y,
+                _ => continue,
+            };
+            if let Ok(value) = number.parse::<f64>() {
+                *number = format_gcode_number(value + shift, 4);
+                changed = true;
+            }
+        }
+        if changed {
+            output.push(GCodeValidator::rebuild_line(line, &words));
+        } else {
+            output.push(line.to_string());
+        }
+    }
+
+    let mut text = output.join("\n");
+    if program.ends_with('\n') {
+        text.push('\n');
+    }
+    text
+}
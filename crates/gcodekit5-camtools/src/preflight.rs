@@ -0,0 +1,374 @@
+//! Pre-flight Checks
+//!
+//! Checks run on a program before it is streamed. Each check can be switched off
+//! in [`PreflightConfig`]. Results are either soft warnings, which the operator may
+//! acknowledge and continue past, or hard failures (the program leaves the machine's
+//! travel), which should stop the job from starting.
+
+use crate::validator::GCodeValidator;
+use gcodekit5_core::{ArcMove, ArcPlane};
+use gcodekit5_devicedb::DeviceProfile;
+use serde::{Deserialize, Serialize};
+
+/// Arc flattening used when measuring program extents (mm)
+const ARC_SEGMENT_MM: f64 = 0.5;
+
+/// Which checks to run
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PreflightConfig {
+    /// Ask the operator to confirm spindle/laser safety when the program turns it on
+    pub confirm_safety: bool,
+    /// Check program extents against the profile's axis limits
+    pub soft_limits: bool,
+    /// Warn when the program never turns the spindle or laser on
+    pub spindle_command: bool,
+    /// Warn when the program has no M2/M30 program end
+    pub program_end: bool,
+    /// Current work offset (machine position of work zero), added to program
+    /// coordinates before comparing them with machine limits
+    pub work_offset: [f64; 3],
+}
+
+impl Default for PreflightConfig {
+    fn default() -> Self {
+        Self {
+            confirm_safety: true,
+            soft_limits: true,
+            spindle_command: true,
+            program_end: true,
+            work_offset: [0.0; 3],
+        }
+    }
+}
+
+/// Check that produced a warning
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PreflightCheck {
+    /// Spindle/laser safety confirmation
+    Safety,
+    /// Program extents against axis limits
+    SoftLimits,
+    /// Missing spindle/laser on command
+    SpindleCommand,
+    /// Missing M2/M30
+    ProgramEnd,
+}
+
+/// How serious a warning is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum PreflightSeverity {
+    /// May be acknowledged and the job started anyway
+    Warning,
+    /// The job should not be started
+    Failure,
+}
+
+/// A pre-flight finding
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreflightWarning {
+    /// Check that raised it
+    pub check: PreflightCheck,
+    /// Warning or hard failure
+    pub severity: PreflightSeverity,
+    /// Human-readable description
+    pub message: String,
+    /// First program line involved (0-based), if any
+    pub line: Option<usize>,
+}
+
+impl PreflightWarning {
+    fn warning(check: PreflightCheck, message: String) -> Self {
+        Self {
+            check,
+            severity: PreflightSeverity::Warning,
+            message,
+            line: None,
+        }
+    }
+
+    /// Whether this finding should block the job
+    pub fn is_failure(&self) -> bool {
+        self.severity == PreflightSeverity::Failure
+    }
+}
+
+/// Whether any finding should block the job
+pub fn has_failures(warnings: &[PreflightWarning]) -> bool {
+    warnings.iter().any(PreflightWarning::is_failure)
+}
+
+/// Run the enabled checks on a program.
+///
+/// Soft limits are only checked when a profile is given. Findings are ordered with
+/// hard failures first.
+pub fn preflight(
+    program: &str,
+    profile: Option<&DeviceProfile>,
+    config: &PreflightConfig,
+) -> Vec<PreflightWarning> {
+    let scan = ProgramScan::new(program);
+    let mut warnings = Vec::new();
+
+    let uses_laser = profile.is_some_and(|p| p.has_laser && !p.has_spindle);
+    let tool = if uses_laser { "laser" } else { "spindle" };
+
+    if config.soft_limits {
+        if let Some(profile) = profile {
+            warnings.extend(scan.limit_failures(profile, config.work_offset));
+        }
+    }
+
+    if config.confirm_safety {
+        if let Some(line) = scan.spindle_on_line {
+            let message = if uses_laser {
+                "Program fires the laser: confirm eye protection is worn, the enclosure \
+                 is closed and air assist is on"
+            } else {
+                "Program starts the spindle: confirm the workpiece and tool are secured \
+                 and guards are in place"
+            };
+            warnings.push(PreflightWarning {
+                line: Some(line),
+                ..PreflightWarning::warning(PreflightCheck::Safety, message.to_string())
+            });
+        }
+    }
+
+    if config.spindle_command && scan.spindle_on_line.is_none() && scan.has_motion {
+        warnings.push(PreflightWarning::warning(
+            PreflightCheck::SpindleCommand,
+            format!(
+                "Program never turns the {} on (no M3/M4); it will run as a dry run",
+                tool
+            ),
+        ));
+    }
+
+    if config.program_end && !scan.has_program_end {
+        warnings.push(PreflightWarning::warning(
+            PreflightCheck::ProgramEnd,
+            "Program has no M2/M30 program end".to_string(),
+        ));
+    }
+
+    warnings.sort_by_key(|w| std::cmp::Reverse(w.severity));
+    warnings
+}
+
+/// Axis extents of one axis and the first lines reaching them
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AxisExtent {
+    pub(crate) min: f64,
+    min_line: usize,
+    pub(crate) max: f64,
+    max_line: usize,
+}
+
+impl AxisExtent {
+    fn include(extent: &mut Option<Self>, value: f64, line: usize) {
+        match extent {
+            None => {
+                *extent = Some(Self {
+                    min: value,
+                    min_line: line,
+                    max: value,
+                    max_line: line,
+                })
+            }
+            Some(e) => {
+                if value < e.min {
+                    e.min = value;
+                    e.min_line = line;
+                }
+                if value > e.max {
+                    e.max = value;
+                    e.max_line = line;
+                }
+            }
+        }
+    }
+}
+
+/// What a single pass over the program found
+#[derive(Debug, Default)]
+pub(crate) struct ProgramScan {
+    /// Extents in program (work) coordinates
+    pub(crate) work: [Option<AxisExtent>; 3],
+    /// Extents of G53 moves, already in machine coordinates
+    machine: [Option<AxisExtent>; 3],
+    spindle_on_line: Option<usize>,
+    has_program_end: bool,
+    has_motion: bool,
+    /// First G20 (inches) or G21 (mm) in the program
+    pub(crate) units_word: Option<u32>,
+}
+
+impl ProgramScan {
+    pub(crate) fn new(program: &str) -> Self {
+        let mut scan = Self::default();
+        let mut position = [0.0f64; 3];
+        let mut motion = 0u32;
+        let mut plane = ArcPlane::XY;
+        let mut incremental = false;
+        let mut scale = 1.0;
+
+        for (line_num, line) in program.lines().enumerate() {
+            let words = GCodeValidator::words(line);
+            let mut target = position;
+            let mut has_axis = false;
+            let mut machine_coords = false;
+            let mut offsets = [0.0; 3];
+            let mut has_offset = false;
+            let mut radius = None;
+
+            // Modal words first so G20/G91 on the same line apply to its axes
+            for &(letter, value) in &words {
+                match (letter, value) {
+                    ('G', 20.0) => {
+                        scale = 25.4;
+                        scan.units_word.get_or_insert(20);
+                    }
+                    ('G', 21.0) => {
+                        scale = 1.0;
+                        scan.units_word.get_or_insert(21);
+                    }
+                    ('G', 90.0) => incremental = false,
+                    ('G', 91.0) => incremental = true,
+                    ('G', 53.0) => machine_coords = true,
+                    ('G', v) if (0.0..=3.0).contains(&v) && v == v.trunc() => motion = v as u32,
+                    ('G', v) if (17.0..=19.0).contains(&v) && v == v.trunc() => {
+                        plane = ArcPlane::from_gcode(v as u8).unwrap_or(plane)
+                    }
+                    ('M', v) if v == 3.0 || v == 4.0 => {
+                        scan.spindle_on_line.get_or_insert(line_num);
+                    }
+                    ('M', v) if v == 2.0 || v == 30.0 => scan.has_program_end = true,
+                    _ => {}
+                }
+            }
+            for &(letter, value) in &words {
+                match letter {
+                    'X' | 'Y' | 'Z' => {
+                        let axis = (letter as u8 - b'X') as usize;
+                        let value = value * scale;
+                        target[axis] = if incremental && !machine_coords {
+                            position[axis] + value
+                        } else {
+                            value
+                        };
+                        has_axis = true;
+                    }
+                    'I' | 'J' | 'K' => {
+                        offsets[(letter as u8 - b'I') as usize] = value * scale;
+                        has_offset = true;
+                    }
+                    'R' => radius = Some(value * scale),
+                    _ => {}
+                }
+            }
+
+            if !has_axis {
+                continue;
+            }
+            scan.has_motion = true;
+
+            let extents = if machine_coords {
+                &mut scan.machine
+            } else {
+                &mut scan.work
+            };
+            let arc = match (motion, machine_coords) {
+                (2 | 3, false) if has_offset => Some(ArcMove::from_offsets(
+                    position,
+                    target,
+                    offsets,
+                    motion == 2,
+                    plane,
+                )),
+                (2 | 3, false) => radius.and_then(|r| {
+                    ArcMove::from_radius(position, target, r, motion == 2, plane).ok()
+                }),
+                _ => None,
+            };
+            if let Some(arc) = arc {
+                for point in arc.expand(arc.segments_for(ARC_SEGMENT_MM)) {
+                    for axis in 0..3 {
+                        AxisExtent::include(&mut extents[axis], point[axis], line_num);
+                    }
+                }
+            } else {
+                for axis in 0..3 {
+                    AxisExtent::include(&mut extents[axis], target[axis], line_num);
+                }
+            }
+
+            // G53 is non-modal and does not change the work position tracking
+            if !machine_coords {
+                position = target;
+            }
+        }
+
+        scan
+    }
+
+    fn limit_failures(
+        &self,
+        profile: &DeviceProfile,
+        work_offset: [f64; 3],
+    ) -> Vec<PreflightWarning> {
+        let limits = [&profile.x_axis, &profile.y_axis, &profile.z_axis];
+        let mut failures = Vec::new();
+
+        for (axis, name) in ['X', 'Y', 'Z'].into_iter().enumerate() {
+            let limit = limits[axis];
+            if !limit.enabled {
+                continue;
+            }
+            let work = self.work[axis].map(|e| AxisExtent {
+                min: e.min + work_offset[axis],
+                max: e.max + work_offset[axis],
+                ..e
+            });
+            for extent in [work, self.machine[axis]].into_iter().flatten() {
+                if extent.min < limit.min {
+                    failures.push(Self::limit_failure(
+                        name,
+                        extent.min,
+                        extent.min_line,
+                        limit.min,
+                        "minimum",
+                    ));
+                }
+                if extent.max > limit.max {
+                    failures.push(Self::limit_failure(
+                        name,
+                        extent.max,
+                        extent.max_line,
+                        limit.max,
+                        "maximum",
+                    ));
+                }
+            }
+        }
+
+        failures
+    }
+
+    fn limit_failure(
+        axis: char,
+        value: f64,
+        line: usize,
+        limit: f64,
+        bound: &str,
+    ) -> PreflightWarning {
+        PreflightWarning {
+            check: PreflightCheck::SoftLimits,
+            severity: PreflightSeverity::Failure,
+            message: format!(
+                "{} reaches {:.3} in machine coordinates, outside the {} travel of {:.3}",
+                axis, value, bound, limit
+            ),
+            line: Some(line),
+        }
+    }
+}
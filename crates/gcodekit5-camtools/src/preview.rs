@@ -0,0 +1,248 @@
+//! Generator Preview
+//!
+//! The extents and size of a CAM generator's output, for fit checks and UI
+//! feedback before a job is loaded. Every generator's `preview()` measures the
+//! program its G-code method writes, so the bounds are always those of the real
+//! output: the tool's full travel, rapids included, with arcs measured to where
+//! they bulge rather than only their endpoints.
+//!
+//! Axis words on setup commands (`G10`, `G28`, `G30`, `G92`) are not moves and do
+//! not count towards the bounds. Inch (`G20`) positions are measured in mm.
+
+use crate::validator::GCodeValidator;
+use gcodekit5_core::{ArcMove, ArcPlane};
+use std::f64::consts::FRAC_PI_2;
+
+/// Extents of the tool's travel in program coordinates (mm)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PreviewBounds {
+    /// Lowest X reached
+    pub min_x: f64,
+    /// Lowest Y reached
+    pub min_y: f64,
+    /// Lowest Z reached
+    pub min_z: f64,
+    /// Highest X reached
+    pub max_x: f64,
+    /// Highest Y reached
+    pub max_y: f64,
+    /// Highest Z reached
+    pub max_z: f64,
+}
+
+impl PreviewBounds {
+    /// Width, height and depth of the travel
+    pub fn size(&self) -> (f64, f64, f64) {
+        (
+            self.max_x - self.min_x,
+            self.max_y - self.min_y,
+            self.max_z - self.min_z,
+        )
+    }
+}
+
+/// What a generator will produce, without loading the program
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GeneratorPreview {
+    /// Extents of the tool's travel; `None` when the program has no moves
+    pub bounds: Option<PreviewBounds>,
+    /// Lines of G-code in the program
+    pub estimated_lines: usize,
+    /// Things worth telling the user before they run the job
+    pub warnings: Vec<String>,
+}
+
+impl GeneratorPreview {
+    /// Measure a generated program
+    pub fn from_program(program: &str) -> Self {
+        let mut scan = Scan::default();
+        let mut lines = 0;
+        for line in program.lines() {
+            if !line.trim().is_empty() {
+                lines += 1;
+                scan.line(line);
+            }
+        }
+
+        // An axis no move names stays where the program found it
+        if let Some(b) = scan.bounds.as_mut() {
+            for (min, max) in [
+                (&mut b.min_x, &mut b.max_x),
+                (&mut b.min_y, &mut b.max_y),
+                (&mut b.min_z, &mut b.max_z),
+            ] {
+                if min.is_infinite() {
+                    (*min, *max) = (0.0, 0.0);
+                }
+            }
+        }
+
+        let mut warnings = Vec::new();
+        if scan.bounds.is_none() {
+            warnings.push("The program has no moves".to_string());
+        }
+        if scan.relative_used {
+            warnings.push(
+                "Incremental (G91) moves were measured from where the program starts".to_string(),
+            );
+        }
+        if let Some(bounds) = scan.bounds {
+            if bounds.min_x < -1e-9 || bounds.min_y < -1e-9 {
+                warnings.push(format!(
+                    "The job reaches X{:.3} Y{:.3}, below the work origin",
+                    bounds.min_x, bounds.min_y
+                ));
+            }
+        }
+        Self {
+            bounds: scan.bounds,
+            estimated_lines: lines,
+            warnings,
+        }
+    }
+
+    /// Whether the travel fits in a work area of this width and height, placed
+    /// with its corner at the work origin
+    pub fn fits_within(&self, width: f64, height: f64) -> bool {
+        match self.bounds {
+            Some(b) => {
+                b.min_x >= -1e-9 && b.min_y >= -1e-9 && b.max_x <= width && b.max_y <= height
+            }
+            None => true,
+        }
+    }
+}
+
+/// Modal state while measuring a program
+#[derive(Default)]
+struct Scan {
+    position: [Option<f64>; 3],
+    motion: Option<u32>,
+    incremental: bool,
+    inches: bool,
+    relative_used: bool,
+    bounds: Option<PreviewBounds>,
+}
+
+impl Scan {
+    fn line(&mut self, line: &str) {
+        let words = GCodeValidator::words(line);
+        let mut axes: [Option<f64>; 3] = [None; 3];
+        let (mut i, mut j, mut r) = (None, None, None);
+        let mut setup = false;
+        for &(letter, value) in &words {
+            match letter {
+                'G' => match value.round() as u32 {
+                    code @ 0..=3 => self.motion = Some(code),
+                    10 | 28 | 30 | 92 => setup = true,
+                    20 => self.inches = true,
+                    21 => self.inches = false,
+                    80 => self.motion = None,
+                    90 => self.incremental = false,
+                    91 => self.incremental = true,
+                    _ => {}
+                },
+                'X' => axes[0] = Some(value),
+                'Y' => axes[1] = Some(value),
+                'Z' => axes[2] = Some(value),
+                'I' => i = Some(value),
+                'J' => j = Some(value),
+                'R' => r = Some(value),
+                _ => {}
+            }
+        }
+        if setup || axes.iter().all(Option::is_none) {
+            return;
+        }
+        let Some(motion) = self.motion else {
+            return;
+        };
+        let scale = if self.inches { 25.4 } else { 1.0 };
+        let to_mm = |value: Option<f64>| value.map(|v| v * scale);
+        let (i, j, r) = (to_mm(i), to_mm(j), to_mm(r));
+
+        let start = self.position;
+        for (axis, value) in axes.iter().enumerate() {
+            if let Some(v) = to_mm(*value) {
+                self.position[axis] = Some(if self.incremental {
+                    start[axis].unwrap_or(0.0) + v
+                } else {
+                    v
+                });
+            }
+        }
+        self.relative_used |= self.incremental;
+        self.include(self.position);
+
+        if motion == 2 || motion == 3 {
+            if let (Some(sx), Some(sy), Some(ex), Some(ey)) =
+                (start[0], start[1], self.position[0], self.position[1])
+            {
+                let (start, end) = ([sx, sy, 0.0], [ex, ey, 0.0]);
+                let cw = motion == 2;
+                let arc = match (i, j, r) {
+                    (None, None, Some(r)) => {
+                        ArcMove::from_radius(start, end, r, cw, ArcPlane::XY).ok()
+                    }
+                    _ => Some(ArcMove::from_offsets(
+                        start,
+                        end,
+                        [i.unwrap_or(0.0), j.unwrap_or(0.0), 0.0],
+                        cw,
+                        ArcPlane::XY,
+                    )),
+                };
+                if let Some(arc) = arc {
+                    self.include_arc((sx, sy), (ex, ey), (arc.center[0], arc.center[1]), cw);
+                }
+            }
+        }
+    }
+
+    /// Extend the bounds to the points where an XY arc crosses an axis
+    fn include_arc(&mut self, start: (f64, f64), end: (f64, f64), center: (f64, f64), cw: bool) {
+        let radius = (start.0 - center.0).hypot(start.1 - center.1);
+        let a0 = (start.1 - center.1).atan2(start.0 - center.0);
+        let a1 = (end.1 - center.1).atan2(end.0 - center.0);
+        // Counter-clockwise sweep from a0; a full circle when the ends meet
+        let mut sweep = if cw { a0 - a1 } else { a1 - a0 };
+        if sweep <= 1e-9 {
+            sweep += 2.0 * std::f64::consts::PI;
+        }
+        for quadrant in 0..4 {
+            let angle = quadrant as f64 * FRAC_PI_2;
+            let mut from_start = if cw { a0 - angle } else { angle - a0 };
+            from_start = from_start.rem_euclid(2.0 * std::f64::consts::PI);
+            if from_start < sweep {
+                let z = self.position[2];
+                self.include([
+                    Some(center.0 + radius * angle.cos()),
+                    Some(center.1 + radius * angle.sin()),
+                    z,
+                ]);
+            }
+        }
+    }
+
+    fn include(&mut self, point: [Option<f64>; 3]) {
+        let bounds = self.bounds.get_or_insert(PreviewBounds {
+            min_x: f64::INFINITY,
+            min_y: f64::INFINITY,
+            min_z: f64::INFINITY,
+            max_x: f64::NEG_INFINITY,
+            max_y: f64::NEG_INFINITY,
+            max_z: f64::NEG_INFINITY,
+        });
+        let axes = [
+            (&mut bounds.min_x, &mut bounds.max_x),
+            (&mut bounds.min_y, &mut bounds.max_y),
+            (&mut bounds.min_z, &mut bounds.max_z),
+        ];
+        for ((min, max), value) in axes.into_iter().zip(point) {
+            if let Some(v) = value {
+                *min = min.min(v);
+                *max = max.max(v);
+            }
+        }
+    }
+}
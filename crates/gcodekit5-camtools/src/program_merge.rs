@@ -0,0 +1,210 @@
+//! # Program Merge
+//!
+//! Joins several G-code programs into one job. Between programs a transition block
+//! stops the spindle and coolant, restores a known modal baseline (G17 G21 G40 G80
+//! G90 G94 plus the configured work coordinate system), retracts to a clearance
+//! height and optionally pauses with M0.
+//!
+//! Each program's leading modal-only lines that merely repeat the baseline are
+//! dropped. Anything else in a preamble (homing, `G10` offsets, moves) is kept, as
+//! removing it could shift the program's work zero. Program ends (M2/M30) and `%`
+//! tape markers inside the sources are removed so the merged job only ends once.
+//! Every program is wrapped in begin/end comments naming its source.
+
+use crate::error::{CamToolError, CamToolResult};
+use crate::validator::GCodeValidator;
+
+/// A program to merge
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeSource {
+    /// Name shown in the merged program's comments (e.g. the file name)
+    pub name: String,
+    /// Program text
+    pub content: String,
+}
+
+impl MergeSource {
+    /// Create a source from a name and program text
+    pub fn new(name: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            content: content.into(),
+        }
+    }
+}
+
+/// How programs are joined
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeOptions {
+    /// Z (mm, work coordinates) to retract to between programs and at the end
+    pub clearance_z: f64,
+    /// Stop with M0 before each program after the first
+    pub pause_between: bool,
+    /// Work coordinate system (54-59) selected at every transition; `None` keeps
+    /// whatever the previous program left selected
+    pub work_coordinate_system: Option<u8>,
+    /// Prefix each program's comment lines with its name
+    pub label_comments: bool,
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        Self {
+            clearance_z: 5.0,
+            pause_between: false,
+            work_coordinate_system: Some(54),
+            label_comments: false,
+        }
+    }
+}
+
+/// Modal words the baseline establishes, with the value it sets
+const BASELINE: &[(char, f64)] = &[
+    ('G', 17.0),
+    ('G', 21.0),
+    ('G', 40.0),
+    ('G', 80.0),
+    ('G', 90.0),
+    ('G', 94.0),
+    ('M', 5.0),
+    ('M', 9.0),
+];
+
+/// Join programs into a single job
+///
+/// Fails if `sources` is empty or the work coordinate system is not G54-G59.
+pub fn merge_programs(sources: &[MergeSource], options: &MergeOptions) -> CamToolResult<String> {
+    if sources.is_empty() {
+        return Err(CamToolError::InvalidParameters(
+            "no programs to merge".to_string(),
+        ));
+    }
+    if let Some(wcs) = options.work_coordinate_system {
+        if !(54..=59).contains(&wcs) {
+            return Err(CamToolError::InvalidParameters(format!(
+                "work coordinate system G{} is not one of G54-G59",
+                wcs
+            )));
+        }
+    }
+
+    let mut output = Vec::new();
+    output.push("; Merged G-code program".to_string());
+    for (index, source) in sources.iter().enumerate() {
+        output.push(format!("; Program {}: {}", index + 1, source.name));
+    }
+    output.push(";".to_string());
+
+    for (index, source) in sources.iter().enumerate() {
+        let number = index + 1;
+        output.push(String::new());
+        if index == 0 {
+            output.push("; ===== Baseline =====".to_string());
+        } else {
+            output.push(format!(
+                "; ===== Transition: {} -> {} =====",
+                sources[index - 1].name,
+                source.name
+            ));
+        }
+        push_baseline(&mut output, options);
+        if index > 0 && options.pause_between {
+            output.push(format!("M0 ; Pause before {}", source.name));
+        }
+
+        output.push(format!(
+            "; ===== Begin program {}/{}: {} =====",
+            number,
+            sources.len(),
+            source.name
+        ));
+        push_program(&mut output, source, options);
+        output.push(format!(
+            "; ===== End program {}: {} =====",
+            number, source.name
+        ));
+    }
+
+    output.push(String::new());
+    output.push("; ===== End of merged program =====".to_string());
+    output.push("M5 ; Spindle/laser off".to_string());
+    output.push("M9 ; Coolant off".to_string());
+    output.push(format!(
+        "G90 G0 Z{:.3} ; Retract to clearance",
+        options.clearance_z
+    ));
+    output.push("M30 ; End program".to_string());
+
+    let mut merged = output.join("\n");
+    merged.push('\n');
+    Ok(merged)
+}
+
+pub(crate) fn push_baseline(output: &mut Vec<String>, options: &MergeOptions) {
+    output.push("M5 ; Spindle/laser off".to_string());
+    output.push("M9 ; Coolant off".to_string());
+    output.push("G17 G21 G40 G80 G90 G94 ; Modal baseline".to_string());
+    if let Some(wcs) = options.work_coordinate_system {
+        output.push(format!("G{} ; Work coordinate system", wcs));
+    }
+    output.push(format!(
+        "G0 Z{:.3} ; Retract to clearance",
+        options.clearance_z
+    ));
+}
+
+pub(crate) fn push_program(output: &mut Vec<String>, source: &MergeSource, options: &MergeOptions) {
+    let mut in_preamble = true;
+    for line in source.content.lines() {
+        let trimmed = line.trim();
+        if trimmed == "%" {
+            continue;
+        }
+
+        let words = GCodeValidator::words(trimmed);
+        if in_preamble && !words.is_empty() {
+            if repeats_baseline(&words, options) {
+                continue;
+            }
+            in_preamble = false;
+        }
+
+        if words
+            .iter()
+            .any(|&(letter, value)| letter == 'M' && (value == 2.0 || value == 30.0))
+        {
+            if words.len() > 1 {
+                tracing::warn!(
+                    "Removing program end from '{}' also drops the rest of the line: {}",
+                    source.name,
+                    trimmed
+                );
+            }
+            output.push(format!("; Program end removed by merge: {}", trimmed));
+            continue;
+        }
+
+        if options.label_comments && is_comment_line(trimmed) {
+            let text = trimmed.trim_start_matches(';').trim_start();
+            output.push(format!("; [{}] {}", source.name, text));
+        } else {
+            output.push(line.to_string());
+        }
+    }
+}
+
+/// Whether every word of a line sets modal state the transition already set
+fn repeats_baseline(words: &[(char, f64)], options: &MergeOptions) -> bool {
+    words.iter().all(|&(letter, value)| {
+        letter == 'N'
+            || BASELINE.contains(&(letter, value))
+            || (letter == 'G'
+                && options
+                    .work_coordinate_system
+                    .is_some_and(|wcs| value == wcs as f64))
+    })
+}
+
+fn is_comment_line(line: &str) -> bool {
+    line.starts_with(';') || (line.starts_with('(') && line.ends_with(')'))
+}
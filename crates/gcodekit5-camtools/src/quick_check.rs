@@ -0,0 +1,312 @@
+//! # Quick Check
+//!
+//! Finds the first problem in a program in a single pass, so the editor can jump
+//! straight to it before a job is run. The checks, each of which can be turned
+//! off, are:
+//!
+//! - validator errors (coordinate ranges, non-positive feed rates)
+//! - arcs whose radius cannot reach the end point (R words) or whose end point
+//!   is not on the circle (I/J/K offsets)
+//! - moves outside the machine's travel, arcs included, given a profile
+//! - rapids that travel horizontally below the clearance height
+//!
+//! The scan stops at the first line with a problem, which keeps it fast on large
+//! programs. Optional blocks are skipped when the block delete switch is on.
+
+use crate::validator::{GCodeValidator, ValidatorConfig};
+use gcodekit5_core::{apply_block_delete, ArcMove, ArcPlane};
+use gcodekit5_devicedb::DeviceProfile;
+use serde::{Deserialize, Serialize};
+
+/// Arc flattening used when checking arcs against the travel (mm)
+const ARC_SEGMENT_MM: f64 = 0.5;
+
+/// Difference between start and end radius allowed for I/J/K arcs, as GRBL
+/// does: both the absolute (mm) and the relative tolerance must be exceeded
+const ARC_RADIUS_TOLERANCE_MM: f64 = 0.005;
+const ARC_RADIUS_TOLERANCE_RATIO: f64 = 0.001;
+
+/// Heights are compared with this tolerance (mm)
+const Z_EPSILON: f64 = 1e-4;
+
+/// Which checks to run, and their settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QuickCheckConfig {
+    /// Syntax and range checks of [`GCodeValidator`]
+    pub validator: bool,
+    /// Arcs that cannot be drawn as written
+    pub arc_radius: bool,
+    /// Moves outside the profile's axis limits
+    pub out_of_bounds: bool,
+    /// Horizontal rapids below `clearance_z`
+    pub rapid_below_clearance: bool,
+    /// Lowest height (mm, work coordinates) rapids may travel at
+    pub clearance_z: f64,
+    /// Current work offset (machine position of work zero)
+    pub work_offset: [f64; 3],
+    /// Block delete switch: skip lines starting with `/`
+    pub block_delete: bool,
+}
+
+impl Default for QuickCheckConfig {
+    fn default() -> Self {
+        Self {
+            validator: true,
+            arc_radius: true,
+            out_of_bounds: true,
+            rapid_below_clearance: true,
+            clearance_z: 0.0,
+            work_offset: [0.0; 3],
+            block_delete: true,
+        }
+    }
+}
+
+/// Check that found a problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum QuickCheckKind {
+    Validator,
+    ArcRadius,
+    OutOfBounds,
+    RapidBelowClearance,
+}
+
+/// The first problem in a program
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuickCheckIssue {
+    /// Program line (0-based)
+    pub line: usize,
+    pub kind: QuickCheckKind,
+    /// Human-readable description
+    pub reason: String,
+}
+
+/// Modal state needed to follow the tool
+#[derive(Debug, Clone, Copy)]
+struct Modal {
+    /// Work position in mm; Z is unknown until the program sets it
+    position: [f64; 3],
+    z_known: bool,
+    plane: ArcPlane,
+    motion: Option<u32>,
+    incremental: bool,
+    scale: f64,
+}
+
+/// Return the earliest problem found by the enabled checks, if any
+///
+/// Travel is only checked when a profile is given.
+pub fn first_problem(
+    program: &str,
+    profile: Option<&DeviceProfile>,
+    config: &QuickCheckConfig,
+) -> Option<QuickCheckIssue> {
+    let validator = GCodeValidator::new(ValidatorConfig {
+        block_delete: config.block_delete,
+        ..Default::default()
+    });
+    let mut modal = Modal {
+        position: [0.0; 3],
+        z_known: false,
+        plane: ArcPlane::XY,
+        motion: None,
+        incremental: false,
+        scale: 1.0,
+    };
+    let issue = |line: usize, kind: QuickCheckKind, reason: String| {
+        Some(QuickCheckIssue { line, kind, reason })
+    };
+
+    for (line_num, line) in program.lines().enumerate() {
+        let Some(line) = apply_block_delete(line, config.block_delete) else {
+            continue;
+        };
+        if config.validator {
+            if let Some(error) = validator.line_errors(line, line_num).into_iter().next() {
+                return issue(line_num, QuickCheckKind::Validator, error.message);
+            }
+        }
+
+        let words = GCodeValidator::words(line);
+        let mut machine_coords = false;
+        let mut other_frame = false;
+        for &(letter, value) in &words {
+            match (letter, value) {
+                ('G', 20.0) => modal.scale = 25.4,
+                ('G', 21.0) => modal.scale = 1.0,
+                ('G', 90.0) => modal.incremental = false,
+                ('G', 91.0) => modal.incremental = true,
+                ('G', 53.0) => machine_coords = true,
+                ('G', 10.0 | 28.0 | 30.0 | 92.0) => other_frame = true,
+                ('G', v) if (38.0..40.0).contains(&v) => other_frame = true,
+                ('G', v) if (0.0..=3.0).contains(&v) && v == v.trunc() => {
+                    modal.motion = Some(v as u32)
+                }
+                ('G', v) if (17.0..=19.0).contains(&v) && v == v.trunc() => {
+                    modal.plane = ArcPlane::from_gcode(v as u8).unwrap_or(modal.plane)
+                }
+                ('G', v) if (80.0..=89.0).contains(&v) && v == v.trunc() => modal.motion = None,
+                _ => {}
+            }
+        }
+
+        let mut target = modal.position;
+        let mut axes = [false; 3];
+        let mut offsets = [0.0; 3];
+        let mut has_offset = false;
+        let mut radius = None;
+        for &(letter, value) in &words {
+            match letter {
+                'X' | 'Y' | 'Z' => {
+                    let axis = (letter as u8 - b'X') as usize;
+                    let value = value * modal.scale;
+                    target[axis] = if modal.incremental && !machine_coords {
+                        modal.position[axis] + value
+                    } else {
+                        value
+                    };
+                    axes[axis] = true;
+                }
+                'I' | 'J' | 'K' => {
+                    offsets[(letter as u8 - b'I') as usize] = value * modal.scale;
+                    has_offset = true;
+                }
+                'R' => radius = Some(value * modal.scale),
+                _ => {}
+            }
+        }
+        if other_frame {
+            // Positions in another frame: stop following Z rather than guess
+            modal.z_known = false;
+            continue;
+        }
+        // An arc with only offsets is a full circle
+        let full_circle = has_offset && matches!(modal.motion, Some(2 | 3));
+        if modal.motion.is_none() || !(axes.iter().any(|a| *a) || full_circle) {
+            continue;
+        }
+
+        let start = modal.position;
+        let start_z_known = modal.z_known;
+        if machine_coords {
+            // G53 is non-modal and leaves the work position alone
+            if config.out_of_bounds {
+                if let Some(profile) = profile {
+                    let mut machine = [None; 3];
+                    for axis in 0..3 {
+                        if axes[axis] {
+                            machine[axis] = Some(target[axis]);
+                        }
+                    }
+                    if let Some(reason) = outside_travel(profile, machine) {
+                        return issue(line_num, QuickCheckKind::OutOfBounds, reason);
+                    }
+                }
+            }
+            continue;
+        }
+        modal.position = target;
+        modal.z_known |= axes[2] && (!modal.incremental || start_z_known);
+
+        let arc = match modal.motion {
+            Some(code @ (2 | 3)) if has_offset => {
+                let arc = ArcMove::from_offsets(start, target, offsets, code == 2, modal.plane);
+                if config.arc_radius {
+                    if let Some(reason) = arc_end_error(&arc) {
+                        return issue(line_num, QuickCheckKind::ArcRadius, reason);
+                    }
+                }
+                Some(arc)
+            }
+            Some(code @ (2 | 3)) => match radius {
+                Some(r) => match ArcMove::from_radius(start, target, r, code == 2, modal.plane) {
+                    Ok(arc) => Some(arc),
+                    Err(e) if config.arc_radius => {
+                        return issue(line_num, QuickCheckKind::ArcRadius, e.to_string());
+                    }
+                    Err(_) => None,
+                },
+                None => None,
+            },
+            _ => None,
+        };
+
+        if config.out_of_bounds {
+            if let Some(profile) = profile {
+                let points = match &arc {
+                    Some(arc) => arc.expand(arc.segments_for(ARC_SEGMENT_MM)),
+                    None => vec![target],
+                };
+                for point in points {
+                    let machine = [0, 1, 2].map(|axis| {
+                        (axis != 2 || modal.z_known).then(|| point[axis] + config.work_offset[axis])
+                    });
+                    if let Some(reason) = outside_travel(profile, machine) {
+                        return issue(line_num, QuickCheckKind::OutOfBounds, reason);
+                    }
+                }
+            }
+        }
+
+        if config.rapid_below_clearance && modal.motion == Some(0) && (axes[0] || axes[1]) {
+            let horizontal = start[0] != target[0] || start[1] != target[1];
+            let threshold = config.clearance_z - Z_EPSILON;
+            let below = [(start_z_known, start[2]), (modal.z_known, target[2])]
+                .iter()
+                .any(|&(known, z)| known && z < threshold);
+            if horizontal && below {
+                return issue(
+                    line_num,
+                    QuickCheckKind::RapidBelowClearance,
+                    format!(
+                        "Rapid travels at Z{:.3}, below the clearance height of Z{:.3}",
+                        start[2].min(target[2]),
+                        config.clearance_z
+                    ),
+                );
+            }
+        }
+    }
+    None
+}
+
+/// Describe the first axis outside the profile's travel, in machine coordinates
+fn outside_travel(profile: &DeviceProfile, machine: [Option<f64>; 3]) -> Option<String> {
+    let limits = [&profile.x_axis, &profile.y_axis, &profile.z_axis];
+    for (axis, name) in ['X', 'Y', 'Z'].into_iter().enumerate() {
+        let (Some(value), limit) = (machine[axis], limits[axis]) else {
+            continue;
+        };
+        if !limit.enabled {
+            continue;
+        }
+        let bound = if value < limit.min {
+            ("minimum", limit.min)
+        } else if value > limit.max {
+            ("maximum", limit.max)
+        } else {
+            continue;
+        };
+        return Some(format!(
+            "{} reaches {:.3} in machine coordinates, outside the {} travel of {:.3}",
+            name, value, bound.0, bound.1
+        ));
+    }
+    None
+}
+
+/// Error when an I/J/K arc's end point is not on the circle through its start
+fn arc_end_error(arc: &ArcMove) -> Option<String> {
+    let (a, b, _) = arc.plane.axes();
+    let start_radius = arc.radius();
+    let end_radius = (arc.end[a] - arc.center[a]).hypot(arc.end[b] - arc.center[b]);
+    let difference = (end_radius - start_radius).abs();
+    (difference > ARC_RADIUS_TOLERANCE_MM
+        && difference > ARC_RADIUS_TOLERANCE_RATIO * start_radius)
+        .then(|| {
+            format!(
+                "Arc end point is {:.4} mm off the circle (radius {:.4} at the start, {:.4} at the end)",
+                difference, start_radius, end_radius
+            )
+        })
+}
@@ -0,0 +1,260 @@
+//! # Rotation
+//!
+//! Rotates a program about a pivot in the XY plane, so a program can follow
+//! stock that is clamped slightly out of square instead of the stock having to
+//! be squared to the machine. [`skew_from_probes`] measures that skew from two
+//! points probed along an edge that should run along X or Y, and returns the
+//! angle and pivot so they can be confirmed before [`SkewCorrection::apply`]
+//! rotates the program.
+//!
+//! Rotation mixes X and Y, so every rotated move is written with both words
+//! even if the original only had one; the position is tracked to fill in the
+//! other. Arc I/J offsets are rotated with the moves (as points when G90.1
+//! absolute arc centers are active), and arcs keep their direction. Arcs in
+//! the XZ or YZ plane cannot be rotated about Z and are rejected.
+//!
+//! Dwells and lines that set or use other coordinate frames (G10, G28, G30,
+//! G53, G92, G38.x) are not touched. The position is unknown after the latter
+//! until a move gives both X and Y again.
+
+use crate::error::{CamToolError, CamToolResult};
+use crate::validator::GCodeValidator;
+use gcodekit5_core::format_gcode_number;
+
+/// Closest two probed points may be for a usable angle (mm)
+const MIN_PROBE_SPACING: f64 = 1.0;
+/// Largest skew accepted from probing; more means the wrong edge was probed
+const MAX_SKEW_DEGREES: f64 = 45.0;
+
+/// The machine axis a probed edge should run along
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbedEdge {
+    AlongX,
+    AlongY,
+}
+
+/// Rotation that lines a program up with skewed stock
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SkewCorrection {
+    /// Counter-clockwise rotation to apply, in degrees
+    pub angle_degrees: f64,
+    /// Point rotated about, in work coordinates (mm)
+    pub pivot: (f64, f64),
+}
+
+impl SkewCorrection {
+    /// Rotate `program` by this correction
+    pub fn apply(&self, program: &str) -> CamToolResult<String> {
+        rotate_program(program, self.pivot, self.angle_degrees)
+    }
+}
+
+/// The skew of an edge from two points probed along it, in work coordinates
+/// (mm)
+///
+/// The first point becomes the pivot, so work probed from the corner there
+/// stays in place. Fails if the points are closer than 1mm, not finite, or
+/// give a skew over 45 degrees.
+pub fn skew_from_probes(
+    first: (f64, f64),
+    second: (f64, f64),
+    edge: ProbedEdge,
+) -> CamToolResult<SkewCorrection> {
+    let (mut dx, mut dy) = (second.0 - first.0, second.1 - first.1);
+    if ![first.0, first.1, second.0, second.1]
+        .iter()
+        .all(|v| v.is_finite())
+    {
+        return Err(CamToolError::InvalidParameters(format!(
+            "probed points must be finite, got {:?} and {:?}",
+            first, second
+        )));
+    }
+    if dx.hypot(dy) < MIN_PROBE_SPACING {
+        return Err(CamToolError::InvalidParameters(format!(
+            "probed points must be at least {}mm apart, got {:.3}mm",
+            MIN_PROBE_SPACING,
+            dx.hypot(dy)
+        )));
+    }
+
+    // Probing the edge in either direction gives the same skew
+    let angle = match edge {
+        ProbedEdge::AlongX => {
+            if dx < 0.0 {
+                (dx, dy) = (-dx, -dy);
+            }
+            dy.atan2(dx)
+        }
+        ProbedEdge::AlongY => {
+            if dy < 0.0 {
+                (dx, dy) = (-dx, -dy);
+            }
+            (-dx).atan2(dy)
+        }
+    }
+    .to_degrees();
+    if angle.abs() > MAX_SKEW_DEGREES {
+        return Err(CamToolError::InvalidParameters(format!(
+            "probed edge is {:.3} degrees off {}; probe an edge along that axis",
+            angle,
+            match edge {
+                ProbedEdge::AlongX => "X",
+                ProbedEdge::AlongY => "Y",
+            }
+        )));
+    }
+    Ok(SkewCorrection {
+        angle_degrees: angle,
+        pivot: first,
+    })
+}
+
+/// Rotate every XY move of a program counter-clockwise by `angle_degrees`
+/// about `pivot` (mm)
+///
+/// Fails if the angle or pivot is not finite, if an arc outside the XY plane
+/// would be rotated, or if a move gives only one of X and Y while the other
+/// is not yet known.
+pub fn rotate_program(
+    program: &str,
+    pivot: (f64, f64),
+    angle_degrees: f64,
+) -> CamToolResult<String> {
+    if !angle_degrees.is_finite() || !pivot.0.is_finite() || !pivot.1.is_finite() {
+        return Err(CamToolError::InvalidParameters(format!(
+            "rotation angle and pivot must be finite, got {} about {:?}",
+            angle_degrees, pivot
+        )));
+    }
+    let (sin, cos) = angle_degrees.to_radians().sin_cos();
+    let rotate = |x: f64, y: f64| (x * cos - y * sin, x * sin + y * cos);
+
+    let mut absolute = true;
+    let mut absolute_arcs = false;
+    let mut scale = 1.0;
+    let mut moving = false;
+    let mut arc = false;
+    let mut xy_plane = true;
+    // Unrotated position in program units, where known
+    let mut position: (Option<f64>, Option<f64>) = (None, None);
+    let mut output = Vec::new();
+
+    for (index, line) in program.lines().enumerate() {
+        let mut words = GCodeValidator::word_texts(line);
+        let mut other_frame = false;
+        let mut dwell = false;
+        for (letter, number) in &words {
+            if *letter != 'G' {
+                continue;
+            }
+            let Ok(code) = number.parse::<f64>() else {
+                continue;
+            };
+            match code {
+                0.0 | 1.0 => (moving, arc) = (true, false),
+                2.0 | 3.0 => (moving, arc) = (true, true),
+                80.0 => (moving, arc) = (false, false),
+                81.0..=89.0 if code.fract() == 0.0 => (moving, arc) = (true, false),
+                17.0 => xy_plane = true,
+                18.0 | 19.0 => xy_plane = false,
+                90.0 => absolute = true,
+                91.0 => absolute = false,
+                90.1 => absolute_arcs = true,
+                91.1 => absolute_arcs = false,
+                20.0 => scale = 1.0 / 25.4,
+                21.0 => scale = 1.0,
+                4.0 => dwell = true,
+                10.0 | 28.0 | 30.0 | 53.0 | 92.0 => other_frame = true,
+                38.0..=39.0 => other_frame = true,
+                _ => {}
+            }
+        }
+        let value = |words: &[(char, String)], letter: char| {
+            words
+                .iter()
+                .find(|(l, _)| *l == letter)
+                .and_then(|(_, number)| number.parse::<f64>().ok())
+        };
+        let (x, y) = (value(&words, 'X'), value(&words, 'Y'));
+        let (i, j) = (value(&words, 'I'), value(&words, 'J'));
+
+        if other_frame {
+            position = (None, None);
+            output.push(line.to_string());
+            continue;
+        }
+        if dwell || !moving || (x.is_none() && y.is_none() && i.is_none() && j.is_none()) {
+            output.push(line.to_string());
+            continue;
+        }
+        if arc && !xy_plane && angle_degrees != 0.0 {
+            return Err(CamToolError::InvalidParameters(format!(
+                "line {}: arcs outside the XY plane cannot be rotated: {}",
+                index + 1,
+                line.trim()
+            )));
+        }
+
+        let pivot = (pivot.0 * scale, pivot.1 * scale);
+        let unknown = |axis: char| {
+            CamToolError::InvalidParameters(format!(
+                "line {}: {} position is not known before this move: {}",
+                index + 1,
+                axis,
+                line.trim()
+            ))
+        };
+        let start = position;
+        if x.is_some() || y.is_some() {
+            if absolute {
+                let target_x = x.or(position.0).ok_or_else(|| unknown('X'))?;
+                let target_y = y.or(position.1).ok_or_else(|| unknown('Y'))?;
+                let (rx, ry) = rotate(target_x - pivot.0, target_y - pivot.1);
+                set_word(&mut words, 'X', pivot.0 + rx, 'Y');
+                set_word(&mut words, 'Y', pivot.1 + ry, 'X');
+                position = (Some(target_x), Some(target_y));
+            } else {
+                let (dx, dy) = (x.unwrap_or(0.0), y.unwrap_or(0.0));
+                let (rx, ry) = rotate(dx, dy);
+                set_word(&mut words, 'X', rx, 'Y');
+                set_word(&mut words, 'Y', ry, 'X');
+                position = (position.0.map(|p| p + dx), position.1.map(|p| p + dy));
+            }
+        }
+        if arc && (i.is_some() || j.is_some()) {
+            if absolute_arcs {
+                let center_x = i.or(start.0).ok_or_else(|| unknown('X'))?;
+                let center_y = j.or(start.1).ok_or_else(|| unknown('Y'))?;
+                let (ri, rj) = rotate(center_x - pivot.0, center_y - pivot.1);
+                set_word(&mut words, 'I', pivot.0 + ri, 'J');
+                set_word(&mut words, 'J', pivot.1 + rj, 'I');
+            } else {
+                let (ri, rj) = rotate(i.unwrap_or(0.0), j.unwrap_or(0.0));
+                set_word(&mut words, 'I', ri, 'J');
+                set_word(&mut words, 'J', rj, 'I');
+            }
+        }
+        output.push(GCodeValidator::rebuild_line(line, &words));
+    }
+
+    let mut text = output.join("\n");
+    if program.ends_with('\n') {
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+/// Set a word's value, adding it next to its partner word if it is missing
+fn set_word(words: &mut Vec<(char, String)>, letter: char, value: f64, partner: char) {
+    let number = format_gcode_number(value, 4);
+    if let Some((_, existing)) = words.iter_mut().find(|(l, _)| *l == letter) {
+        *existing = number;
+        return;
+    }
+    let at = words
+        .iter()
+        .position(|(l, _)| *l == partner)
+        .map_or(words.len(), |p| if letter < partner { p } else { p + 1 });
+    words.insert(at, (letter, number));
+}
@@ -0,0 +1,240 @@
+//! # Safe Rapids
+//!
+//! Converts rapid (G0) moves that travel horizontally below a Z threshold into
+//! G1 feed moves at a safe feed rate, so a machine that cannot rapid through
+//! material never does. Rapids above the threshold and Z-only rapids are left as
+//! G0. A move counts as below the threshold if it starts or ends below it.
+//!
+//! Z is tracked in both G90 and G91. The program's own modal state is kept: after a
+//! converted move, the next modal rapid gets an explicit G0 and the next feed move
+//! without an F word gets the program's feed back. Rapids whose height is unknown
+//! (relative moves before any absolute Z) are left alone and counted as unresolved.
+//! Lines that use other coordinate frames (G10, G28, G30, G53, G92, G38.x) and
+//! canned cycles are not touched, and they make the current Z unknown.
+
+use crate::error::{CamToolError, CamToolResult};
+use crate::validator::GCodeValidator;
+use gcodekit5_core::format_gcode_number;
+
+/// Heights are compared with this tolerance (program units)
+const Z_EPSILON: f64 = 1e-4;
+
+/// Safe rapid settings, in mm and work coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub struct SafeRapidOptions {
+    /// Rapids with horizontal travel below this height become feed moves
+    pub threshold_z: f64,
+    /// Feed rate of the converted moves in mm/min
+    pub feed_rate: f64,
+}
+
+impl Default for SafeRapidOptions {
+    fn default() -> Self {
+        Self {
+            threshold_z: 0.0,
+            feed_rate: 500.0,
+        }
+    }
+}
+
+/// Result of [`convert_low_rapids`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SafeRapidReport {
+    /// The rewritten program
+    pub program: String,
+    /// Rapids converted to feed moves
+    pub converted: usize,
+    /// Horizontal rapids left alone because the current Z was unknown
+    pub unresolved: usize,
+}
+
+impl SafeRapidReport {
+    /// One-line summary for display
+    pub fn summary(&self) -> String {
+        let mut summary = format!("{} rapid move(s) converted to feed moves", self.converted);
+        if self.unresolved > 0 {
+            summary.push_str(&format!(
+                "; {} rapid(s) left unchanged (unknown Z)",
+                self.unresolved
+            ));
+        }
+        summary
+    }
+}
+
+/// Rewrite rapids that travel horizontally below the threshold as feed moves
+///
+/// Fails if the threshold is not finite or the feed rate is not positive.
+pub fn convert_low_rapids(
+    program: &str,
+    options: &SafeRapidOptions,
+) -> CamToolResult<SafeRapidReport> {
+    if !options.threshold_z.is_finite()
+        || !options.feed_rate.is_finite()
+        || options.feed_rate <= 0.0
+    {
+        return Err(CamToolError::InvalidParameters(format!(
+            "threshold {} must be finite and feed rate {} positive",
+            options.threshold_z, options.feed_rate
+        )));
+    }
+
+    let mut report = SafeRapidReport {
+        program: String::new(),
+        converted: 0,
+        unresolved: 0,
+    };
+    let mut output = Vec::new();
+    let mut absolute = true;
+    let mut scale = 1.0;
+    let mut current_z: Option<f64> = None;
+    // Motion and feed as the program sets them, and as the rewritten program leaves them
+    let mut motion: Option<String> = None;
+    let mut emitted_motion: Option<String> = None;
+    let mut feed: Option<String> = None;
+    let mut emitted_feed: Option<String> = None;
+
+    for line in program.lines() {
+        let mut words = GCodeValidator::word_texts(line);
+        let mut other_frame = false;
+        let mut motion_word = None;
+        for (index, (letter, number)) in words.iter().enumerate() {
+            match letter {
+                'G' => {
+                    let Ok(code) = number.parse::<f64>() else {
+                        continue;
+                    };
+                    match code {
+                        0.0 | 1.0 | 2.0 | 3.0 => {
+                            motion = Some(format_gcode_number(code, 4));
+                            motion_word = Some(index);
+                        }
+                        80.0 => motion = None,
+                        81.0..=89.0 if code.fract() == 0.0 => {
+                            motion = Some(format_gcode_number(code, 4));
+                            motion_word = Some(index);
+                        }
+                        90.0 => absolute = true,
+                        91.0 => absolute = false,
+                        20.0 => scale = 1.0 / 25.4,
+                        21.0 => scale = 1.0,
+                        10.0 | 28.0 | 30.0 | 53.0 | 92.0 => other_frame = true,
+                        38.0..=39.0 => other_frame = true,
+                        _ => {}
+                    }
+                }
+                'F' => feed = Some(number.clone()),
+                _ => {}
+            }
+        }
+        let has_f = words.iter().any(|(letter, _)| *letter == 'F');
+        if has_f {
+            emitted_feed = feed.clone();
+        }
+        if motion_word.is_some() {
+            emitted_motion = motion.clone();
+        }
+
+        let z_value = words
+            .iter()
+            .find(|(letter, _)| *letter == 'Z')
+            .and_then(|(_, number)| number.parse::<f64>().ok());
+        let has_xy = words
+            .iter()
+            .any(|(letter, _)| *letter == 'X' || *letter == 'Y');
+        let moves = has_xy || z_value.is_some();
+
+        if other_frame {
+            current_z = None;
+            output.push(line.to_string());
+            continue;
+        }
+
+        let from_z = current_z;
+        let target_z = match z_value {
+            Some(z) if absolute => Some(z),
+            Some(z) => from_z.map(|c| c + z),
+            None => from_z,
+        };
+
+        let rapid = motion.as_deref() == Some("0");
+        let cycle = motion
+            .as_deref()
+            .is_some_and(|m| m != "0" && m != "1" && m != "2" && m != "3");
+        if cycle {
+            output.push(line.to_string());
+            if moves {
+                current_z = None;
+            }
+            continue;
+        }
+
+        if rapid && has_xy {
+            let threshold = options.threshold_z * scale;
+            let low = [from_z, target_z]
+                .iter()
+                .flatten()
+                .any(|z| *z < threshold - Z_EPSILON);
+            if low {
+                let safe_feed = format_gcode_number(options.feed_rate * scale, 4);
+                match motion_word {
+                    Some(index) => words[index].1 = "1".to_string(),
+                    None => {
+                        let at = usize::from(words.first().is_some_and(|(l, _)| *l == 'N'));
+                        words.insert(at, ('G', "1".to_string()));
+                    }
+                }
+                match words.iter_mut().find(|(letter, _)| *letter == 'F') {
+                    Some((_, number)) => *number = safe_feed.clone(),
+                    None if emitted_feed.as_ref() != Some(&safe_feed) => {
+                        words.push(('F', safe_feed.clone()))
+                    }
+                    None => {}
+                }
+                output.push(GCodeValidator::rebuild_line(line, &words));
+                emitted_motion = Some("1".to_string());
+                emitted_feed = Some(safe_feed);
+                report.converted += 1;
+                current_z = target_z;
+                continue;
+            }
+            if from_z.is_none() || target_z.is_none() {
+                report.unresolved += 1;
+            }
+        }
+
+        let mut restored = false;
+        if moves && motion_word.is_none() && emitted_motion != motion {
+            // A converted move left G1 modal; restore the program's motion
+            if let Some(code) = &motion {
+                let at = usize::from(words.first().is_some_and(|(l, _)| *l == 'N'));
+                words.insert(at, ('G', code.clone()));
+                emitted_motion = motion.clone();
+                restored = true;
+            }
+        }
+        if moves && !rapid && !has_f && emitted_feed != feed {
+            // ...and the program's feed rate
+            if let Some(number) = &feed {
+                words.push(('F', number.clone()));
+                emitted_feed = feed.clone();
+                restored = true;
+            }
+        }
+        if restored {
+            output.push(GCodeValidator::rebuild_line(line, &words));
+        } else {
+            output.push(line.to_string());
+        }
+        if moves && motion.is_some() {
+            current_z = target_z;
+        }
+    }
+
+    let mut text = output.join("\n");
+    if program.ends_with('\n') {
+        text.push('\n');
+    }
+    report.program = text;
+    Ok(report)
+}
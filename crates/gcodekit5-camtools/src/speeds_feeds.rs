@@ -6,10 +6,22 @@
 //! Based on standard machining formulas:
 //! RPM = (Surface Speed * 1000) / (π * Diameter)
 //! Feed Rate = RPM * Chip Load * Number of Flutes
+//!
+//! For machines whose spindle runs at a fixed RPM, [`SpeedsFeedsCalculator::reconcile_chip_load`]
+//! works the formula backwards: it reads a program's feed rates and reports the chip
+//! load each one actually produces at that RPM, flagging rubbing and overload.
 
+use crate::error::{CamToolError, CamToolResult};
+use crate::validator::GCodeValidator;
 use gcodekit5_core::data::materials::Material;
 use gcodekit5_core::data::tools::Tool;
 use gcodekit5_devicedb::model::DeviceProfile;
+use std::collections::BTreeMap;
+
+/// Chip loads below this fraction of the target rub instead of cutting
+const RUBBING_FRACTION: f32 = 0.5;
+/// Chip loads above this fraction of the target risk breaking the tool
+const EXCESSIVE_FRACTION: f32 = 1.5;
 
 /// Result of a speeds and feeds calculation
 #[derive(Debug, Clone)]
@@ -32,6 +44,57 @@ pub struct CalculationResult {
     pub unclamped_feed_rate: Option<f32>,
 }
 
+/// How a chip load compares with the target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChipLoadStatus {
+    /// Too thin to cut cleanly; the tool rubs and heats up
+    Rubbing,
+    /// Within the usable range around the target
+    Ok,
+    /// Dangerously thick for the tool
+    Excessive,
+}
+
+/// Chip load produced by one programmed feed rate
+#[derive(Debug, Clone)]
+pub struct FeedChipLoad {
+    /// Programmed feed rate in mm/min
+    pub feed_rate: f32,
+    /// Resulting chip load in mm/tooth
+    pub chip_load: f32,
+    /// Comparison with the target chip load
+    pub status: ChipLoadStatus,
+    /// Number of cutting moves at this feed rate
+    pub move_count: usize,
+    /// First program line (0-based) cutting at this feed rate
+    pub first_line: usize,
+}
+
+/// Chip load analysis of a program run at a fixed spindle speed
+#[derive(Debug, Clone)]
+pub struct ChipLoadReport {
+    /// Spindle speed the analysis assumes (RPM)
+    pub rpm: f32,
+    /// Chip load the feeds are compared against (mm/tooth)
+    pub target_chip_load: f32,
+    /// Feed rate giving the target chip load at `rpm` (mm/min)
+    pub recommended_feed_rate: f32,
+    /// Chip load per distinct programmed feed rate, slowest first
+    pub feeds: Vec<FeedChipLoad>,
+    /// Distinct S values in the program that differ from `rpm`; the fixed
+    /// spindle ignores them
+    pub ignored_spindle_speeds: Vec<f32>,
+    /// Human-readable warnings
+    pub warnings: Vec<String>,
+}
+
+impl ChipLoadReport {
+    /// Whether any feed rate is outside the usable chip load range
+    pub fn has_problems(&self) -> bool {
+        self.feeds.iter().any(|f| f.status != ChipLoadStatus::Ok)
+    }
+}
+
 /// Calculator for speeds and feeds
 pub struct SpeedsFeedsCalculator;
 
@@ -171,4 +234,112 @@ impl SpeedsFeedsCalculator {
             unclamped_feed_rate,
         }
     }
+
+    /// Back-compute chip loads for a program run at a fixed spindle speed
+    ///
+    /// Every distinct feed rate of the program's cutting moves (G1/G2/G3) is
+    /// converted to a chip load with `Feed / (RPM * Flutes)` and compared with
+    /// `target_chip_load`, e.g. the [`CalculationResult::chip_load`] for the material.
+    /// Loads below half the target are flagged as rubbing and above one and a half
+    /// times as excessive. Inch feeds (G20) are converted to mm/min.
+    pub fn reconcile_chip_load(
+        program: &str,
+        rpm: f32,
+        tool: &Tool,
+        target_chip_load: f32,
+    ) -> CamToolResult<ChipLoadReport> {
+        if rpm <= 0.0 || tool.flutes == 0 || target_chip_load <= 0.0 {
+            return Err(CamToolError::InvalidParameters(
+                "RPM, flute count and target chip load must be positive".to_string(),
+            ));
+        }
+        let flutes = tool.flutes as f32;
+
+        // Feed rate in thousandths of mm/min -> (move count, first line)
+        let mut feeds: BTreeMap<i64, (usize, usize)> = BTreeMap::new();
+        let mut spindle_speeds: BTreeMap<i64, f32> = BTreeMap::new();
+        let mut scale = 1.0;
+        let mut motion = 0;
+        let mut feed: Option<f32> = None;
+        for (line_num, line) in program.lines().enumerate() {
+            let words = GCodeValidator::words(line);
+            let mut has_axis = false;
+            for &(letter, value) in &words {
+                match (letter, value) {
+                    ('G', 20.0) => scale = 25.4,
+                    ('G', 21.0) => scale = 1.0,
+                    ('G', v) if (0.0..=3.0).contains(&v) && v == v.trunc() => motion = v as u32,
+                    ('S', v) if (v as f32 - rpm).abs() > 0.5 => {
+                        spindle_speeds.insert((v * 1000.0).round() as i64, v as f32);
+                    }
+                    ('X' | 'Y' | 'Z' | 'I' | 'J' | 'K', _) => has_axis = true,
+                    _ => {}
+                }
+            }
+            // F is read after units so "G20 G1 X1 F10" is in inches
+            if let Some(&(_, value)) = words.iter().find(|(letter, _)| *letter == 'F') {
+                feed = Some(value as f32 * scale);
+            }
+            if let (true, 1..=3, Some(rate)) = (has_axis, motion, feed) {
+                let entry = feeds
+                    .entry((rate as f64 * 1000.0).round() as i64)
+                    .or_insert((0, line_num));
+                entry.0 += 1;
+            }
+        }
+
+        let mut warnings = Vec::new();
+        let feeds: Vec<FeedChipLoad> = feeds
+            .into_iter()
+            .map(|(key, (move_count, first_line))| {
+                let feed_rate = key as f32 / 1000.0;
+                let chip_load = feed_rate / (rpm * flutes);
+                let status = if chip_load < target_chip_load * RUBBING_FRACTION {
+                    warnings.push(format!(
+                        "F{:.0} gives {:.4} mm/tooth at {:.0} RPM; the tool will rub (line {})",
+                        feed_rate,
+                        chip_load,
+                        rpm,
+                        first_line + 1
+                    ));
+                    ChipLoadStatus::Rubbing
+                } else if chip_load > target_chip_load * EXCESSIVE_FRACTION {
+                    warnings.push(format!(
+                        "F{:.0} gives {:.4} mm/tooth at {:.0} RPM; chip load is dangerously high (line {})",
+                        feed_rate,
+                        chip_load,
+                        rpm,
+                        first_line + 1
+                    ));
+                    ChipLoadStatus::Excessive
+                } else {
+                    ChipLoadStatus::Ok
+                };
+                FeedChipLoad {
+                    feed_rate,
+                    chip_load,
+                    status,
+                    move_count,
+                    first_line,
+                }
+            })
+            .collect();
+
+        let ignored_spindle_speeds: Vec<f32> = spindle_speeds.into_values().collect();
+        if !ignored_spindle_speeds.is_empty() {
+            warnings.push(format!(
+                "Program sets spindle speeds that differ from the fixed {:.0} RPM; its feeds may have been computed for another speed",
+                rpm
+            ));
+        }
+
+        Ok(ChipLoadReport {
+            rpm,
+            target_chip_load,
+            recommended_feed_rate: rpm * target_chip_load * flutes,
+            feeds,
+            ignored_spindle_speeds,
+            warnings,
+        })
+    }
 }
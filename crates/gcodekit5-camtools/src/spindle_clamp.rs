@@ -0,0 +1,238 @@
+//! # Spindle Speed Clamp
+//!
+//! Limits the spindle speeds a program commands to what the machine can do.
+//! Clamping S alone would change the chip load, so by default the feed of every
+//! cutting move made while the speed is clamped is lowered by the same ratio:
+//! a program asking for 24000 RPM at 2000 mm/min on an 18000 RPM spindle cuts
+//! at 1500 mm/min. [`SpindleClampMode::ClampOnly`] clamps S and leaves the
+//! feeds alone.
+//!
+//! Speed and feed are tracked modally. Rescaled feeds are written on the
+//! cutting moves (G1-G3 and canned cycles) that need them, so rapids and other
+//! lines keep the program's own F words, and the first cutting move after the
+//! speed is back within the limit gets the program's feed back.
+//!
+//! Feeds per revolution (G95) already follow the spindle and are not rescaled.
+//! Under constant surface speed (G96) S is not an RPM and is left alone.
+
+use crate::error::{CamToolError, CamToolResult};
+use crate::validator::GCodeValidator;
+use gcodekit5_core::format_gcode_number;
+
+/// Whether feeds follow a clamped spindle speed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SpindleClampMode {
+    /// Lower the feeds of cutting moves by the same ratio as the speed
+    #[default]
+    RescaleFeeds,
+    /// Clamp the speed only
+    ClampOnly,
+}
+
+/// Spindle clamp settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpindleClampOptions {
+    /// Highest spindle speed the machine can run (RPM)
+    pub max_rpm: f64,
+    pub mode: SpindleClampMode,
+}
+
+impl SpindleClampOptions {
+    /// Clamp to `max_rpm`, rescaling feeds
+    pub fn new(max_rpm: f64) -> Self {
+        Self {
+            max_rpm,
+            mode: SpindleClampMode::default(),
+        }
+    }
+}
+
+/// What was changed on one line
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpindleAdjustmentKind {
+    /// An S word above the limit was lowered to it
+    SpeedClamped { requested: f64, clamped: f64 },
+    /// A cutting move's feed was lowered with the speed
+    FeedScaled { programmed: f64, scaled: f64 },
+}
+
+/// One change made by [`clamp_spindle_speed`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpindleAdjustment {
+    /// Line number, starting at 1
+    pub line: usize,
+    pub kind: SpindleAdjustmentKind,
+}
+
+impl SpindleAdjustment {
+    /// Warning text for display
+    pub fn message(&self) -> String {
+        match self.kind {
+            SpindleAdjustmentKind::SpeedClamped { requested, clamped } => format!(
+                "line {}: spindle speed S{} clamped to S{}",
+                self.line,
+                format_gcode_number(requested, 4),
+                format_gcode_number(clamped, 4)
+            ),
+            SpindleAdjustmentKind::FeedScaled { programmed, scaled } => format!(
+                "line {}: feed F{} reduced to F{} to keep the chip load",
+                self.line,
+                format_gcode_number(programmed, 4),
+                format_gcode_number(scaled, 4)
+            ),
+        }
+    }
+}
+
+/// Result of [`clamp_spindle_speed`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpindleClampReport {
+    /// The rewritten program
+    pub program: String,
+    /// Every speed clamped and feed rescaled, in program order
+    pub adjustments: Vec<SpindleAdjustment>,
+}
+
+impl SpindleClampReport {
+    /// Number of S words clamped
+    pub fn speeds_clamped(&self) -> usize {
+        self.adjustments
+            .iter()
+            .filter(|a| matches!(a.kind, SpindleAdjustmentKind::SpeedClamped { .. }))
+            .count()
+    }
+
+    /// Number of feeds rescaled
+    pub fn feeds_scaled(&self) -> usize {
+        self.adjustments.len() - self.speeds_clamped()
+    }
+
+    /// One-line summary for display
+    pub fn summary(&self) -> String {
+        format!(
+            "{} spindle speed(s) clamped, {} feed rate(s) reduced",
+            self.speeds_clamped(),
+            self.feeds_scaled()
+        )
+    }
+}
+
+/// Clamp spindle speeds above `options.max_rpm`, rescaling cutting feeds to
+/// keep the chip load unless the mode is [`SpindleClampMode::ClampOnly`]
+///
+/// Each adjustment is also logged as a warning. Fails if the limit is not
+/// positive.
+pub fn clamp_spindle_speed(
+    program: &str,
+    options: &SpindleClampOptions,
+) -> CamToolResult<SpindleClampReport> {
+    if !(options.max_rpm.is_finite() && options.max_rpm > 0.0) {
+        return Err(CamToolError::InvalidParameters(format!(
+            "maximum spindle speed must be positive, got {} RPM",
+            options.max_rpm
+        )));
+    }
+    let rescale = options.mode == SpindleClampMode::RescaleFeeds;
+    let mut adjustments = Vec::new();
+    let mut motion = 0u32;
+    let mut per_revolution = false;
+    let mut surface_speed = false;
+    // Feed the program asks for, and the one on the controller as written
+    let mut feed: Option<f64> = None;
+    let mut active_feed: Option<String> = None;
+    // Clamped speed over requested speed
+    let mut ratio = 1.0;
+    let mut output = Vec::new();
+
+    for (index, line) in program.lines().enumerate() {
+        let mut words = GCodeValidator::word_texts(line);
+        let mut changed = false;
+        let mut line_feed = None;
+        let mut moves = false;
+        let mut other_frame = false;
+        for (letter, number) in words.iter() {
+            let Ok(value) = number.parse::<f64>() else {
+                continue;
+            };
+            match (*letter, value) {
+                ('G', 0.0..=3.0) if value.fract() == 0.0 => motion = value as u32,
+                ('G', 73.0) => motion = 73,
+                ('G', 81.0..=89.0) if value.fract() == 0.0 => motion = value as u32,
+                ('G', 80.0) => motion = 0,
+                ('G', 93.0 | 94.0) => per_revolution = false,
+                ('G', 95.0) => per_revolution = true,
+                ('G', 96.0) => surface_speed = true,
+                ('G', 97.0) => surface_speed = false,
+                ('G', 10.0 | 28.0 | 30.0 | 92.0) => other_frame = true,
+                ('F', f) => line_feed = Some(f),
+                ('X' | 'Y' | 'Z' | 'A' | 'B' | 'C', _) => moves = true,
+                _ => {}
+            }
+        }
+
+        if let Some((_, number)) = words.iter_mut().find(|(letter, _)| *letter == 'S') {
+            if let Ok(requested) = number.parse::<f64>() {
+                if surface_speed {
+                    ratio = 1.0;
+                } else if requested > options.max_rpm {
+                    ratio = options.max_rpm / requested;
+                    *number = format_gcode_number(options.max_rpm, 4);
+                    changed = true;
+                    adjustments.push(SpindleAdjustment {
+                        line: index + 1,
+                        kind: SpindleAdjustmentKind::SpeedClamped {
+                            requested,
+                            clamped: options.max_rpm,
+                        },
+                    });
+                } else {
+                    ratio = 1.0;
+                }
+            }
+        }
+
+        if let Some(f) = line_feed {
+            feed = Some(f);
+            active_feed = Some(format_gcode_number(f, 4));
+        }
+        let cuts = moves && !other_frame && motion != 0;
+        if rescale && cuts && !per_revolution {
+            if let Some(programmed) = feed {
+                let scaled = programmed * ratio;
+                let text = format_gcode_number(scaled, 4);
+                if active_feed.as_ref() != Some(&text) {
+                    match words.iter_mut().find(|(letter, _)| *letter == 'F') {
+                        Some((_, number)) => *number = text.clone(),
+                        None => words.push(('F', text.clone())),
+                    }
+                    changed = true;
+                    if ratio < 1.0 {
+                        adjustments.push(SpindleAdjustment {
+                            line: index + 1,
+                            kind: SpindleAdjustmentKind::FeedScaled { programmed, scaled },
+                        });
+                    }
+                    active_feed = Some(text);
+                }
+            }
+        }
+
+        output.push(if changed {
+            GCodeValidator::rebuild_line(line, &words)
+        } else {
+            line.to_string()
+        });
+    }
+
+    for adjustment in &adjustments {
+        tracing::warn!("{}", adjustment.message());
+    }
+    let mut text = output.join("\n");
+    if program.ends_with('\n') {
+        text.push('\n');
+    }
+    Ok(SpindleClampReport {
+        program: text,
+        adjustments,
+    })
+}
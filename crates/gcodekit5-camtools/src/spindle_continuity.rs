@@ -0,0 +1,200 @@
+//! # Spindle Continuity
+//!
+//! Generated jobs often stop the spindle at the end of every operation and
+//! start it again for the next, even when both use the same tool at the same
+//! speed. [`keep_spindle_running`] removes such M5 ... M3/M4 pairs so the
+//! spindle keeps turning, and the wait for it to spin down and up again goes.
+//!
+//! A stop is only removed when nothing between it and the next start needs the
+//! spindle stopped:
+//!
+//! - no tool change (M6), pause (M0, M1, M60) or program end (M2, M30). M0 in
+//!   particular means someone reaches into the machine;
+//! - no feed move, canned cycle or probing move, which the program meant to
+//!   run with the spindle off (rapids, G28/G30 homing and offset settings are
+//!   fine);
+//! - the restart turns the same way (M3 or M4) at the same speed (S).
+//!
+//! All of these count on the stop line too: `M5 M6 T2` is a tool change, and
+//! its stop stays.
+//!
+//! The S word and any dwell on the restart line are kept, only the M words go.
+
+use crate::validator::GCodeValidator;
+
+/// Result of [`keep_spindle_running`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpindleContinuityReport {
+    /// The rewritten program
+    pub program: String,
+    /// Spindle stop/start cycles removed
+    pub eliminated: usize,
+}
+
+impl SpindleContinuityReport {
+    /// One-line summary for display
+    pub fn summary(&self) -> String {
+        format!("{} spindle stop/start cycle(s) removed", self.eliminated)
+    }
+}
+
+/// What a line does to the spindle and what it needs from it
+#[derive(Debug, Default)]
+struct LineInfo {
+    /// Spindle start direction (3 or 4)
+    start: Option<u32>,
+    stop: bool,
+    /// Tool change, pause or program end
+    blocks: bool,
+    /// Feed move, canned cycle or probe, which needs the spindle as left
+    cuts: bool,
+    speed: Option<f64>,
+}
+
+/// Remove spindle stops between operations that run the same tool at the same
+/// speed and direction
+pub fn keep_spindle_running(program: &str) -> SpindleContinuityReport {
+    let lines: Vec<&str> = program.lines().collect();
+    let infos = line_infos(&lines);
+
+    let mut removed: Vec<(usize, u32)> = Vec::new();
+    let mut running: Option<u32> = None;
+    let mut speed: Option<f64> = None;
+    let mut index = 0;
+    while index < lines.len() {
+        let info = &infos[index];
+        speed = info.speed.or(speed);
+        if info.stop {
+            if let Some(direction) = running {
+                let restart = restart_after(&infos, index, direction, speed);
+                if let Some(restart) = restart {
+                    removed.push((index, 5));
+                    removed.push((restart, direction));
+                    // The spindle is still running as it was at the restart line
+                    for info in &infos[index + 1..=restart] {
+                        speed = info.speed.or(speed);
+                    }
+                    index = restart + 1;
+                    continue;
+                }
+            }
+            running = None;
+        }
+        if let Some(direction) = info.start {
+            running = Some(direction);
+        }
+        index += 1;
+    }
+
+    let mut output = Vec::with_capacity(lines.len());
+    for (index, line) in lines.iter().enumerate() {
+        let remove: Vec<u32> = removed
+            .iter()
+            .filter(|(at, _)| *at == index)
+            .map(|(_, code)| *code)
+            .collect();
+        if remove.is_empty() {
+            output.push(line.to_string());
+            continue;
+        }
+        let mut words = GCodeValidator::word_texts(line);
+        words.retain(|(letter, number)| {
+            *letter != 'M' || !m_code(number).is_some_and(|code| remove.contains(&code))
+        });
+        if words.iter().all(|(letter, _)| *letter == 'N') {
+            words.clear();
+        }
+        let rebuilt = GCodeValidator::rebuild_line(line, &words);
+        if !rebuilt.is_empty() {
+            output.push(rebuilt);
+        }
+    }
+
+    let mut text = output.join("\n");
+    if program.ends_with('\n') && !text.is_empty() {
+        text.push('\n');
+    }
+    SpindleContinuityReport {
+        program: text,
+        eliminated: removed.len() / 2,
+    }
+}
+
+/// The line after `stop` that starts the spindle again as it was, if nothing
+/// in between needs it stopped
+fn restart_after(
+    infos: &[LineInfo],
+    stop: usize,
+    direction: u32,
+    mut speed: Option<f64>,
+) -> Option<usize> {
+    // The spindle stops before anything else on its line happens
+    if infos[stop].blocks || infos[stop].cuts {
+        return None;
+    }
+    for (offset, info) in infos[stop + 1..].iter().enumerate() {
+        let previous_speed = speed;
+        speed = info.speed.or(speed);
+        if info.blocks {
+            return None;
+        }
+        if let Some(start) = info.start {
+            let same_speed = match (previous_speed, speed) {
+                (Some(before), Some(after)) => (before - after).abs() < 1e-9,
+                (None, None) => true,
+                _ => false,
+            };
+            return (start == direction && same_speed).then_some(stop + 1 + offset);
+        }
+        if info.cuts {
+            return None;
+        }
+    }
+    None
+}
+
+/// The number of an M word, if it is a whole number
+fn m_code(number: &str) -> Option<u32> {
+    let value = number.parse::<f64>().ok()?;
+    (value >= 0.0 && value.fract() == 0.0).then_some(value as u32)
+}
+
+fn line_infos(lines: &[&str]) -> Vec<LineInfo> {
+    let mut motion: Option<f64> = None;
+    lines
+        .iter()
+        .map(|line| {
+            let words = GCodeValidator::word_texts(line);
+            let mut info = LineInfo::default();
+            let mut moves = false;
+            // Homing and offset words whose axes are not a feed move
+            let mut not_feed = false;
+            for (letter, number) in &words {
+                match letter {
+                    'M' => match m_code(number) {
+                        Some(code @ (3 | 4)) => info.start = Some(code),
+                        Some(5) => info.stop = true,
+                        Some(0 | 1 | 2 | 6 | 30 | 60) => info.blocks = true,
+                        _ => {}
+                    },
+                    'G' => match number.parse::<f64>() {
+                        Ok(code @ (0.0 | 1.0 | 2.0 | 3.0)) => motion = Some(code),
+                        Ok(80.0) => motion = None,
+                        Ok(10.0 | 28.0 | 30.0 | 92.0) => not_feed = true,
+                        Ok(code)
+                            if (81.0..=89.0).contains(&code) || (38.0..39.0).contains(&code) =>
+                        {
+                            motion = Some(code)
+                        }
+                        _ => {}
+                    },
+                    'S' => info.speed = number.parse().ok(),
+                    'X' | 'Y' | 'Z' | 'A' | 'B' | 'C' => moves = true,
+                    _ => {}
+                }
+            }
+            info.cuts = moves && !not_feed && motion.is_some_and(|code| code != 0.0);
+            info
+        })
+        .collect()
+}
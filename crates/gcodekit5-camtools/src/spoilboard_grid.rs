@@ -3,8 +3,16 @@
 //! Generates G-code for laser-engraved alignment grids on CNC spoilboards.
 //! Produces a rectangular grid pattern at configurable spacing with
 //! laser power and feed rate parameters.
+//!
+//! With [`SpoilboardGridGenerator::with_holes`], the grid intersections are
+//! drilled instead, e.g. for threaded inserts or dog holes. The spindle
+//! retracts fully to the clearance height before every XY rapid, and peck
+//! drilled holes only make that retract after the final peck; between pecks it
+//! lifts to the spoilboard surface to clear chips.
 
-use anyhow::Result;
+use crate::annotation::{AnnotatedTool, AnnotationLevel, Annotator};
+use crate::preview::GeneratorPreview;
+use anyhow::{bail, Result};
 
 #[derive(Debug, Clone)]
 pub struct SpoilboardGridParameters {
@@ -16,16 +24,72 @@ pub struct SpoilboardGridParameters {
     pub laser_mode: String, // "M3" or "M4"
 }
 
+/// Drilling of the grid intersections
+#[derive(Debug, Clone)]
+pub struct SpoilboardHoleParameters {
+    /// Z of the spoilboard surface (mm)
+    pub top_z: f64,
+    /// Z of the bottom of the holes (mm)
+    pub bottom_z: f64,
+    /// Depth of each peck (mm); 0 drills each hole in one plunge
+    pub peck_depth: f64,
+    /// Feed rate of the plunges (mm/min)
+    pub plunge_rate: f64,
+    /// Spindle speed (RPM)
+    pub spindle_speed: f64,
+    /// Z the spindle retracts to before every move between holes (mm)
+    pub clearance_z: f64,
+}
+
+impl Default for SpoilboardHoleParameters {
+    fn default() -> Self {
+        Self {
+            top_z: 0.0,
+            bottom_z: -10.0,
+            peck_depth: 0.0,
+            plunge_rate: 200.0,
+            spindle_speed: 12000.0,
+            clearance_z: 10.0,
+        }
+    }
+}
+
 pub struct SpoilboardGridGenerator {
     params: SpoilboardGridParameters,
+    annotation: AnnotationLevel,
+    holes: Option<SpoilboardHoleParameters>,
 }
 
 impl SpoilboardGridGenerator {
     pub fn new(params: SpoilboardGridParameters) -> Self {
-        Self { params }
+        Self {
+            params,
+            annotation: AnnotationLevel::Off,
+            holes: None,
+        }
+    }
+
+    /// Drill a hole at each grid intersection instead of engraving lines
+    pub fn with_holes(mut self, holes: SpoilboardHoleParameters) -> Self {
+        self.holes = Some(holes);
+        self
+    }
+
+    /// Annotate the G-code with each set of grid lines and each line
+    pub fn with_annotation(mut self, level: AnnotationLevel) -> Self {
+        self.annotation = level;
+        self
+    }
+
+    /// Extents and size of the G-code [`generate`](Self::generate) writes
+    pub fn preview(&self) -> Result<GeneratorPreview> {
+        Ok(GeneratorPreview::from_program(&self.generate()?))
     }
 
     pub fn generate(&self) -> Result<String> {
+        if let Some(holes) = &self.holes {
+            return self.generate_holes(holes);
+        }
         let mut gcode = String::new();
         let p = &self.params;
 
@@ -54,7 +118,9 @@ impl SpoilboardGridGenerator {
         gcode.push_str("$32=1 ; Enable Laser Mode\n");
 
         // Vertical Lines (moving along Y)
+        let annotator = Annotator::new(AnnotatedTool::SpoilboardGrid, self.annotation);
         gcode.push_str("\n; Vertical Lines\n");
+        annotator.section(&mut gcode, format_args!("Vertical lines"));
         let mut going_up = true;
         let mut current_x = 0.0;
         // Use epsilon for float comparison
@@ -62,6 +128,7 @@ impl SpoilboardGridGenerator {
             let start_y = if going_up { 0.0 } else { p.height };
             let end_y = if going_up { p.height } else { 0.0 };
 
+            annotator.line(&mut gcode, format_args!("Vertical line X{:.3}", current_x));
             // Rapid to start
             gcode.push_str("M5\n");
             gcode.push_str(&format!("G0 X{:.3} Y{:.3}\n", current_x, start_y));
@@ -76,12 +143,17 @@ impl SpoilboardGridGenerator {
 
         // Horizontal Lines (moving along X)
         gcode.push_str("\n; Horizontal Lines\n");
+        annotator.section(&mut gcode, format_args!("Horizontal lines"));
         let mut going_right = true;
         let mut current_y = 0.0;
         while current_y <= p.height + 0.001 {
             let start_x = if going_right { 0.0 } else { p.width };
             let end_x = if going_right { p.width } else { 0.0 };
 
+            annotator.line(
+                &mut gcode,
+                format_args!("Horizontal line Y{:.3}", current_y),
+            );
             // Rapid to start
             gcode.push_str("M5\n");
             gcode.push_str(&format!("G0 X{:.3} Y{:.3}\n", start_x, current_y));
@@ -101,4 +173,112 @@ impl SpoilboardGridGenerator {
 
         Ok(gcode)
     }
+
+    /// Grid intersections, row by row in alternating directions
+    fn intersections(&self) -> Vec<(f64, f64)> {
+        let p = &self.params;
+        let count = |length: f64| (length / p.grid_spacing + 0.001).floor() as usize + 1;
+        let (columns, rows) = (count(p.width), count(p.height));
+        let mut points = Vec::with_capacity(columns * rows);
+        for row in 0..rows {
+            let y = row as f64 * p.grid_spacing;
+            for column in 0..columns {
+                let column = if row % 2 == 0 {
+                    column
+                } else {
+                    columns - 1 - column
+                };
+                points.push((column as f64 * p.grid_spacing, y));
+            }
+        }
+        points
+    }
+
+    fn generate_holes(&self, holes: &SpoilboardHoleParameters) -> Result<String> {
+        let p = &self.params;
+        if p.grid_spacing <= 0.0 {
+            bail!("grid spacing must be positive, got {}", p.grid_spacing);
+        }
+        if holes.bottom_z >= holes.top_z {
+            bail!(
+                "hole bottom Z{:.3} must be below the surface Z{:.3}",
+                holes.bottom_z,
+                holes.top_z
+            );
+        }
+        if holes.clearance_z <= holes.top_z {
+            bail!(
+                "clearance Z{:.3} must be above the surface Z{:.3}",
+                holes.clearance_z,
+                holes.top_z
+            );
+        }
+
+        let mut gcode = String::new();
+        gcode.push_str("; Spoilboard Grid Holes\n");
+        gcode.push_str(&format!(
+            "; Dimensions: {:.1} x {:.1} mm\n",
+            p.width, p.height
+        ));
+        gcode.push_str(&format!("; Grid Spacing: {:.1} mm\n", p.grid_spacing));
+        gcode.push_str(&format!(
+            "; Depth: {:.3} to {:.3} mm, clearance Z{:.3}\n",
+            holes.top_z, holes.bottom_z, holes.clearance_z
+        ));
+
+        gcode.push_str("G21 ; Set units to millimeters\n");
+        gcode.push_str("G90 ; Absolute positioning\n");
+        gcode.push_str("G17 ; XY plane selection\n\n");
+
+        gcode.push_str("; Home and set work coordinate system\n");
+        gcode.push_str("$H ; Home all axes (bottom-left corner)\n");
+        gcode.push_str("G10 L2 P1 X0 Y0 ; Clear G54 offset\n");
+        gcode.push_str("G54 ; Select work coordinate system 1\n");
+        gcode.push_str(&format!(
+            "G0 Z{:.3} ; Retract to clearance\n",
+            holes.clearance_z
+        ));
+        gcode.push_str("G0 X0.0 Y0.0 ; Move to work origin\n");
+        gcode.push_str("G10 L20 P1 X0 Y0 ; Set current position as work zero\n");
+        gcode.push_str(&format!("M3 S{:.0} ; Start spindle\n", holes.spindle_speed));
+
+        let annotator = Annotator::new(AnnotatedTool::SpoilboardGrid, self.annotation);
+        gcode.push_str("\n; Holes\n");
+        annotator.section(&mut gcode, format_args!("Grid holes"));
+        for (x, y) in self.intersections() {
+            annotator.line(&mut gcode, format_args!("Hole X{:.3} Y{:.3}", x, y));
+            gcode.push_str(&format!("G0 X{:.3} Y{:.3}\n", x, y));
+            if holes.peck_depth > 0.0 {
+                let mut depth = holes.top_z;
+                while depth > holes.bottom_z {
+                    if depth < holes.top_z {
+                        // Back down to just above the last peck
+                        gcode.push_str(&format!("G0 Z{:.3}\n", depth + 0.5));
+                    } else {
+                        gcode.push_str(&format!("G0 Z{:.3}\n", holes.top_z));
+                    }
+                    depth = (depth - holes.peck_depth).max(holes.bottom_z);
+                    gcode.push_str(&format!("G1 Z{:.3} F{:.1}\n", depth, holes.plunge_rate));
+                    if depth > holes.bottom_z {
+                        gcode.push_str(&format!("G0 Z{:.3} ; Clear chips\n", holes.top_z));
+                    }
+                }
+            } else {
+                gcode.push_str(&format!("G0 Z{:.3}\n", holes.top_z));
+                gcode.push_str(&format!(
+                    "G1 Z{:.3} F{:.1}\n",
+                    holes.bottom_z, holes.plunge_rate
+                ));
+            }
+            gcode.push_str(&format!(
+                "G0 Z{:.3} ; Retract to clearance\n",
+                holes.clearance_z
+            ));
+        }
+
+        gcode.push_str("\nM5 ; Stop spindle\n");
+        gcode.push_str("G0 X0 Y0 ; Return to origin\n");
+        gcode.push_str("M30 ; End program\n");
+        Ok(gcode)
+    }
 }
@@ -3,8 +3,21 @@
 //! Generates G-code for flattening/surfacing CNC spoilboards using a
 //! fly cutter or large end mill. Produces a raster pattern with
 //! configurable stepover, depth, and spindle speed.
-
+//!
+//! [`FlatnessReport`] checks the result: given a probe mesh taken after surfacing it
+//! fits a least-squares plane through the points and reports how far the surface
+//! deviates from it and how much that plane is tilted, i.e. whether the machine is
+//! still out of tram.
+
+use crate::annotation::{AnnotatedTool, AnnotationLevel, Annotator};
+use crate::error::{CamToolError, CamToolResult};
+use crate::preview::GeneratorPreview;
 use anyhow::Result;
+use gcodekit5_core::ProbeMesh;
+use nalgebra::{Matrix3, Vector3};
+
+/// Relative XY spread below which probe points are treated as collinear
+const COLLINEAR_TOLERANCE: f64 = 1e-9;
 
 #[derive(Debug, Clone)]
 pub struct SpoilboardSurfacingParameters {
@@ -20,11 +33,26 @@ pub struct SpoilboardSurfacingParameters {
 
 pub struct SpoilboardSurfacingGenerator {
     params: SpoilboardSurfacingParameters,
+    annotation: AnnotationLevel,
 }
 
 impl SpoilboardSurfacingGenerator {
     pub fn new(params: SpoilboardSurfacingParameters) -> Self {
-        Self { params }
+        Self {
+            params,
+            annotation: AnnotationLevel::Off,
+        }
+    }
+
+    /// Annotate the G-code with the surfacing pass and each stripe
+    pub fn with_annotation(mut self, level: AnnotationLevel) -> Self {
+        self.annotation = level;
+        self
+    }
+
+    /// Extents and size of the G-code [`generate`](Self::generate) writes
+    pub fn preview(&self) -> Result<GeneratorPreview> {
+        Ok(GeneratorPreview::from_program(&self.generate()?))
     }
 
     pub fn generate(&self) -> Result<String> {
@@ -61,6 +89,11 @@ impl SpoilboardSurfacingGenerator {
 
         // Plunge to Cut Depth
         let target_z = -p.cut_depth.abs();
+        let annotator = Annotator::new(AnnotatedTool::SpoilboardSurfacing, self.annotation);
+        annotator.section(
+            &mut gcode,
+            format_args!("Surfacing pass at Z{:.3}", target_z),
+        );
         gcode.push_str(&format!("G1 Z{:.3} F{:.1}\n", target_z, p.feed_rate / 2.0));
 
         let step_dist = p.tool_diameter * (p.stepover_percent / 100.0);
@@ -70,6 +103,7 @@ impl SpoilboardSurfacingGenerator {
 
         while current_y <= p.height {
             let target_x = if going_right { p.width } else { 0.0 };
+            annotator.line(&mut gcode, format_args!("Stripe Y{:.3}", current_y));
             gcode.push_str(&format!("G1 X{:.3} F{:.1}\n", target_x, p.feed_rate));
 
             if current_y < p.height {
@@ -93,3 +127,119 @@ impl SpoilboardSurfacingGenerator {
         Ok(gcode)
     }
 }
+
+/// Flatness of a probed surface relative to its best-fit plane
+///
+/// The plane is `z = slope_x * x + slope_y * y + offset`, fitted by least squares
+/// over all probe points. Tilt angles are positive when Z rises along +X / +Y.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlatnessReport {
+    /// Number of probe points used
+    pub point_count: usize,
+    /// Lowest probed Z
+    pub min_z: f64,
+    /// Highest probed Z
+    pub max_z: f64,
+    /// Difference between the highest and lowest probed Z
+    pub range: f64,
+    /// RMS distance of the points from the best-fit plane (along Z)
+    pub rms_deviation: f64,
+    /// Largest distance of any point from the best-fit plane (along Z)
+    pub max_deviation: f64,
+    /// Z change per unit of X of the best-fit plane
+    pub slope_x: f64,
+    /// Z change per unit of Y of the best-fit plane
+    pub slope_y: f64,
+    /// Z of the best-fit plane at X0 Y0
+    pub offset: f64,
+    /// Tilt of the best-fit plane about the Y axis, in degrees
+    pub tilt_x_degrees: f64,
+    /// Tilt of the best-fit plane about the X axis, in degrees
+    pub tilt_y_degrees: f64,
+}
+
+impl FlatnessReport {
+    /// Analyse a probe mesh taken after surfacing
+    ///
+    /// Fails if the mesh has fewer than three points or its points are collinear,
+    /// since no plane can be fitted then.
+    pub fn from_probe_mesh(mesh: &ProbeMesh) -> CamToolResult<Self> {
+        let points = &mesh.points;
+        if points.len() < 3 {
+            return Err(CamToolError::InvalidParameters(format!(
+                "flatness needs at least 3 probe points, got {}",
+                points.len()
+            )));
+        }
+
+        // Normal equations for z = a*x + b*y + c, centred for numerical stability
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|p| p.x).sum::<f64>() / n;
+        let mean_y = points.iter().map(|p| p.y).sum::<f64>() / n;
+        let mut normal = Matrix3::zeros();
+        let mut rhs = Vector3::zeros();
+        for p in points {
+            let row = Vector3::new(p.x - mean_x, p.y - mean_y, 1.0);
+            normal += row * row.transpose();
+            rhs += row * p.z;
+        }
+        // Points on a line leave the XY covariance (near) singular
+        let (sxx, syy, sxy) = (normal[(0, 0)], normal[(1, 1)], normal[(0, 1)]);
+        let spread = sxx * syy - sxy * sxy;
+        let solution = (spread > COLLINEAR_TOLERANCE * (sxx + syy).powi(2))
+            .then(|| normal.lu().solve(&rhs))
+            .flatten()
+            .ok_or_else(|| {
+                CamToolError::GeometryError(
+                    "probe points are collinear; cannot fit a plane".to_string(),
+                )
+            })?;
+        let (slope_x, slope_y) = (solution[0], solution[1]);
+        let offset = solution[2] - slope_x * mean_x - slope_y * mean_y;
+
+        let mut min_z = f64::INFINITY;
+        let mut max_z = f64::NEG_INFINITY;
+        let mut sum_squares = 0.0;
+        let mut max_deviation: f64 = 0.0;
+        for p in points {
+            min_z = min_z.min(p.z);
+            max_z = max_z.max(p.z);
+            let deviation = p.z - (slope_x * p.x + slope_y * p.y + offset);
+            sum_squares += deviation * deviation;
+            max_deviation = max_deviation.max(deviation.abs());
+        }
+
+        Ok(Self {
+            point_count: points.len(),
+            min_z,
+            max_z,
+            range: max_z - min_z,
+            rms_deviation: (sum_squares / n).sqrt(),
+            max_deviation,
+            slope_x,
+            slope_y,
+            offset,
+            tilt_x_degrees: slope_x.atan().to_degrees(),
+            tilt_y_degrees: slope_y.atan().to_degrees(),
+        })
+    }
+
+    /// Z change of the best-fit plane across a width and height, e.g. the
+    /// surfaced area, as (along X, along Y)
+    pub fn tilt_over(&self, width: f64, height: f64) -> (f64, f64) {
+        (self.slope_x * width, self.slope_y * height)
+    }
+
+    /// Get formatted summary
+    pub fn summary(&self) -> String {
+        format!(
+            "Points: {} | Range: {:.4} | RMS: {:.4} | Max deviation: {:.4} | Tilt X: {:.4}\u{b0} | Tilt Y: {:.4}\u{b0}",
+            self.point_count,
+            self.range,
+            self.rms_deviation,
+            self.max_deviation,
+            self.tilt_x_degrees,
+            self.tilt_y_degrees
+        )
+    }
+}
@@ -1,9 +1,24 @@
 //! Statistics Calculator - Task 62
 //!
 //! Calculates G-code statistics including distance, time, and command counts.
+//!
+//! The time estimate follows the programmed feed rates exactly, with rapids at
+//! [`ESTIMATE_RAPID_RATE`] and no acceleration, so real machines usually take
+//! longer; [`crate::job_telemetry`] learns a per-machine correction from finished
+//! jobs.
+//!
+//! Cutting is also broken down by tool. `M6` changes to the tool last selected
+//! with `T` (or `T` alone in programs without any `M6`); tool changes made but
+//! never cut with are listed with no usage, and moves before the first change,
+//! or in programs without tool words, are counted under tool 0.
 
+use crate::validator::GCodeValidator;
+use gcodekit5_core::FeedMode;
 use regex::Regex;
 
+/// Rapid (G0) speed assumed by the time estimate, in mm/min
+pub const ESTIMATE_RAPID_RATE: f64 = 3000.0;
+
 /// G-code statistics
 #[derive(Debug, Clone, Default)]
 pub struct Stats {
@@ -29,6 +44,27 @@ pub struct Stats {
     pub min_z: f64,
     /// Max Z coordinate
     pub max_z: f64,
+    /// Distance travelled by all moves (mm)
+    pub total_distance: f64,
+    /// Estimated run time in seconds
+    pub estimated_time_secs: f64,
+    /// Cutting per tool, in the order the tools were first changed to
+    pub tool_usage: Vec<ToolUsage>,
+    /// Changes to a different tool
+    pub tool_changes: u32,
+}
+
+/// How much one tool cuts in a program
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolUsage {
+    /// Tool number; 0 when no tool was selected
+    pub tool: u32,
+    /// Distance of feed moves (mm)
+    pub cut_distance: f64,
+    /// Estimated time of the feed moves in seconds
+    pub cut_time_secs: f64,
+    /// Runs of feed moves between rapids and tool changes
+    pub operations: u32,
 }
 
 impl Stats {
@@ -110,6 +146,294 @@ impl StatsCalculator {
             }
         }
 
+        let (distance, time) = Self::estimate_motion(lines);
+        stats.total_distance = distance;
+        stats.estimated_time_secs = time;
+        let (tool_usage, tool_changes) = Self::tool_usage(lines);
+        stats.tool_usage = tool_usage;
+        stats.tool_changes = tool_changes;
         stats
     }
+
+    /// Cutting per tool and the number of tool changes
+    pub fn tool_usage(lines: &[String]) -> (Vec<ToolUsage>, u32) {
+        let words: Vec<Vec<(char, f64)>> = lines
+            .iter()
+            .map(|line| GCodeValidator::words(line))
+            .collect();
+        let uses_m6 = words.iter().flatten().any(|&w| w == ('M', 6.0));
+
+        // Active tool of each line, and whether the line changed it
+        let mut selected = 0u32;
+        let mut active = 0u32;
+        let mut changes = 0u32;
+        let mut tool_of_line = Vec::with_capacity(lines.len());
+        let mut usage: Vec<ToolUsage> = Vec::new();
+        for line_words in &words {
+            let mut changed = false;
+            for &(letter, value) in line_words {
+                if letter == 'T' && value >= 0.0 {
+                    selected = value as u32;
+                    changed |= !uses_m6;
+                }
+            }
+            changed |= line_words.contains(&('M', 6.0));
+            changed &= selected != active;
+            if changed {
+                active = selected;
+                changes += 1;
+                if !usage.iter().any(|u| u.tool == active) {
+                    usage.push(ToolUsage {
+                        tool: active,
+                        ..Default::default()
+                    });
+                }
+            }
+            tool_of_line.push((active, changed));
+        }
+
+        let mut unknown = ToolUsage::default();
+        let mut unknown_moves = false;
+        let mut cutting = false;
+        let mut moves = program_moves_by_line(lines).into_iter().peekable();
+        for (index, &(tool, changed)) in tool_of_line.iter().enumerate() {
+            if changed {
+                cutting = false;
+            }
+            while let Some((_, program_move)) = moves.next_if(|(at, _)| *at == index) {
+                let rate = match program_move {
+                    ProgramMove::Dwell(_) => continue,
+                    ProgramMove::Linear { rate, .. } | ProgramMove::Arc { rate, .. } => rate,
+                };
+                let entry = match usage.iter_mut().find(|u| u.tool == tool) {
+                    Some(entry) => entry,
+                    None => {
+                        unknown_moves = true;
+                        &mut unknown
+                    }
+                };
+                let Some(rate) = rate else {
+                    cutting = false;
+                    continue;
+                };
+                let length = program_move.length();
+                entry.cut_distance += length;
+                entry.cut_time_secs += length / rate * 60.0;
+                if !cutting {
+                    entry.operations += 1;
+                    cutting = true;
+                }
+            }
+        }
+        if unknown_moves || usage.is_empty() {
+            usage.insert(0, unknown);
+        }
+        (usage, changes)
+    }
+
+    /// Travel distance and run time of a program from its moves, feeds and dwells
+    fn estimate_motion(lines: &[String]) -> (f64, f64) {
+        let mut distance = 0.0;
+        let mut seconds = 0.0;
+        for program_move in program_moves(lines) {
+            match program_move {
+                ProgramMove::Dwell(pause) => seconds += pause,
+                ProgramMove::Linear { rate, .. } | ProgramMove::Arc { rate, .. } => {
+                    let length = program_move.length();
+                    distance += length;
+                    seconds += length / rate.unwrap_or(ESTIMATE_RAPID_RATE) * 60.0;
+                }
+            }
+        }
+        (distance, seconds)
+    }
+}
+
+/// One move or dwell of a program, in mm and mm/min
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ProgramMove {
+    /// Straight move; `rate` is `None` for rapids and moves without a feed
+    Linear {
+        start: [f64; 3],
+        end: [f64; 3],
+        rate: Option<f64>,
+    },
+    /// Arc (or helix) in the XY plane around `center`
+    Arc {
+        start: [f64; 3],
+        end: [f64; 3],
+        center: [f64; 2],
+        clockwise: bool,
+        rate: Option<f64>,
+    },
+    /// Dwell in seconds
+    Dwell(f64),
+}
+
+impl ProgramMove {
+    /// Path length in mm
+    pub(crate) fn length(&self) -> f64 {
+        match *self {
+            Self::Linear { start, end, .. } => distance(start, end),
+            Self::Arc { start, end, .. } => match self.arc_geometry() {
+                Some((radius, _, sweep)) => (radius * sweep.abs()).hypot(end[2] - start[2]),
+                None => distance(start, end),
+            },
+            Self::Dwell(_) => 0.0,
+        }
+    }
+
+    /// Radius, start angle and signed sweep of an arc; `None` for degenerate arcs
+    pub(crate) fn arc_geometry(&self) -> Option<(f64, f64, f64)> {
+        let Self::Arc {
+            start,
+            end,
+            center,
+            clockwise,
+            ..
+        } = *self
+        else {
+            return None;
+        };
+        let radius = (start[0] - center[0]).hypot(start[1] - center[1]);
+        if radius <= 0.0 {
+            return None;
+        }
+        let start_angle = (start[1] - center[1]).atan2(start[0] - center[0]);
+        let end_angle = (end[1] - center[1]).atan2(end[0] - center[0]);
+        let mut sweep = if clockwise {
+            start_angle - end_angle
+        } else {
+            end_angle - start_angle
+        };
+        if sweep <= 1e-9 {
+            sweep += std::f64::consts::TAU;
+        }
+        Some((radius, start_angle, if clockwise { -sweep } else { sweep }))
+    }
+}
+
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    ((b[0] - a[0]).powi(2) + (b[1] - a[1]).powi(2) + (b[2] - a[2]).powi(2)).sqrt()
+}
+
+/// The moves and dwells of a program, following G20/G21, G90/G91 and feed words
+pub(crate) fn program_moves(lines: &[String]) -> Vec<ProgramMove> {
+    program_moves_by_line(lines)
+        .into_iter()
+        .map(|(_, program_move)| program_move)
+        .collect()
+}
+
+/// [`program_moves`] with the index of the line each came from
+///
+/// Feeds are converted to mm/min from the active G93/G94/G95 mode: inverse
+/// time takes the F word of the move's own line, and feed per revolution the
+/// last S word, giving no feed while the spindle speed is unknown (or under
+/// G96, where S is a surface speed).
+pub(crate) fn program_moves_by_line(lines: &[String]) -> Vec<(usize, ProgramMove)> {
+    let mut position = [0.0f64; 3];
+    let mut absolute = true;
+    let mut scale = 1.0;
+    let mut motion = 0u32;
+    let mut feed = 0.0f64;
+    let mut feed_mode = FeedMode::UnitsPerMinute;
+    let mut spindle_rpm = 0.0f64;
+    let mut surface_speed = false;
+    let mut moves = Vec::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let words = GCodeValidator::words(line);
+        let mut dwell = false;
+        let mut line_feed = None;
+        let mut speed_word = None;
+        for &(letter, value) in &words {
+            match (letter, value) {
+                ('G', 0.0) => motion = 0,
+                ('G', 1.0) => motion = 1,
+                ('G', 2.0) => motion = 2,
+                ('G', 3.0) => motion = 3,
+                ('G', 4.0) => dwell = true,
+                ('G', 20.0) => scale = 25.4,
+                ('G', 21.0) => scale = 1.0,
+                ('G', 90.0) => absolute = true,
+                ('G', 91.0) => absolute = false,
+                ('G', 93.0) => feed_mode = FeedMode::InverseTime,
+                ('G', 94.0) => feed_mode = FeedMode::UnitsPerMinute,
+                ('G', 95.0) => feed_mode = FeedMode::UnitsPerRevolution,
+                ('F', f) => {
+                    feed = f * scale;
+                    line_feed = Some(f);
+                }
+                ('G', 96.0) => surface_speed = true,
+                ('G', 97.0) => surface_speed = false,
+                ('S', s) => speed_word = Some(s),
+                _ => {}
+            }
+        }
+        if surface_speed {
+            spindle_rpm = 0.0;
+        } else if let Some(s) = speed_word {
+            spindle_rpm = s;
+        }
+        if dwell {
+            let pause = words.iter().find(|(l, _)| *l == 'P').map_or(0.0, |w| w.1);
+            moves.push((index, ProgramMove::Dwell(pause.max(0.0))));
+            continue;
+        }
+
+        let mut target = position;
+        let mut moved = false;
+        for &(letter, value) in &words {
+            let axis = match letter {
+                'X' => 0,
+                'Y' => 1,
+                'Z' => 2,
+                _ => continue,
+            };
+            let value = value * scale;
+            target[axis] = if absolute {
+                value
+            } else {
+                position[axis] + value
+            };
+            moved = true;
+        }
+        if !moved {
+            continue;
+        }
+
+        let word = |l: char| words.iter().find(|w| w.0 == l).map_or(0.0, |w| w.1);
+        let (i, j) = (word('I') * scale, word('J') * scale);
+        let mut program_move = if matches!(motion, 2 | 3) && (i != 0.0 || j != 0.0) {
+            ProgramMove::Arc {
+                start: position,
+                end: target,
+                center: [position[0] + i, position[1] + j],
+                clockwise: motion == 2,
+                rate: None,
+            }
+        } else {
+            ProgramMove::Linear {
+                start: position,
+                end: target,
+                rate: None,
+            }
+        };
+        let f = match feed_mode {
+            FeedMode::InverseTime => line_feed.unwrap_or(0.0),
+            _ => feed,
+        };
+        let length = program_move.length();
+        if let ProgramMove::Linear { rate, .. } | ProgramMove::Arc { rate, .. } = &mut program_move
+        {
+            *rate = feed_mode
+                .units_per_minute(f, length, spindle_rpm)
+                .filter(|r| motion != 0 && *r > 0.0);
+        }
+        moves.push((index, program_move));
+        position = target;
+    }
+
+    moves
 }
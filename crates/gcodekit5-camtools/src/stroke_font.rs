@@ -0,0 +1,168 @@
+//! # Single-Line Stroke Font
+//!
+//! A minimal engraving font whose glyphs are open polylines rather than outlines,
+//! so text can be burned or cut in a single pass. Glyphs are drawn on a 4 x 6 unit
+//! cell with Y increasing upward and are scaled so the cell height equals the
+//! requested text height.
+//!
+//! The character set covers digits and the symbols used by generated labels
+//! (`. - % / F P S` and space). Unsupported characters are skipped.
+//!
+//! [`StrokeFont`] offers the same glyphs to the designer's
+//! [`layout_text_on_path`](gcodekit5_designer::font_manager::layout_text_on_path).
+
+use gcodekit5_designer::font_manager::GlyphSource;
+use gcodekit5_designer::model::Point;
+
+/// Glyph cell width in font units
+const CELL_WIDTH: f32 = 4.0;
+/// Glyph cell height in font units
+const CELL_HEIGHT: f32 = 6.0;
+/// Horizontal distance between glyph origins in font units
+const ADVANCE: f32 = 5.0;
+
+type Stroke = &'static [(f32, f32)];
+
+fn glyph(c: char) -> Option<&'static [Stroke]> {
+    let strokes: &'static [Stroke] = match c {
+        '0' => &[&[(0.0, 0.0), (4.0, 0.0), (4.0, 6.0), (0.0, 6.0), (0.0, 0.0)]],
+        '1' => &[
+            &[(1.0, 5.0), (2.0, 6.0), (2.0, 0.0)],
+            &[(1.0, 0.0), (3.0, 0.0)],
+        ],
+        '2' => &[&[
+            (0.0, 6.0),
+            (4.0, 6.0),
+            (4.0, 3.0),
+            (0.0, 3.0),
+            (0.0, 0.0),
+            (4.0, 0.0),
+        ]],
+        '3' => &[
+            &[(0.0, 6.0), (4.0, 6.0), (4.0, 0.0), (0.0, 0.0)],
+            &[(0.0, 3.0), (4.0, 3.0)],
+        ],
+        '4' => &[
+            &[(0.0, 6.0), (0.0, 3.0), (4.0, 3.0)],
+            &[(4.0, 6.0), (4.0, 0.0)],
+        ],
+        '5' => &[&[
+            (4.0, 6.0),
+            (0.0, 6.0),
+            (0.0, 3.0),
+            (4.0, 3.0),
+            (4.0, 0.0),
+            (0.0, 0.0),
+        ]],
+        '6' => &[&[
+            (4.0, 6.0),
+            (0.0, 6.0),
+            (0.0, 0.0),
+            (4.0, 0.0),
+            (4.0, 3.0),
+            (0.0, 3.0),
+        ]],
+        '7' => &[&[(0.0, 6.0), (4.0, 6.0), (4.0, 0.0)]],
+        '8' => &[
+            &[(0.0, 0.0), (4.0, 0.0), (4.0, 6.0), (0.0, 6.0), (0.0, 0.0)],
+            &[(0.0, 3.0), (4.0, 3.0)],
+        ],
+        '9' => &[&[
+            (0.0, 0.0),
+            (4.0, 0.0),
+            (4.0, 6.0),
+            (0.0, 6.0),
+            (0.0, 3.0),
+            (4.0, 3.0),
+        ]],
+        '.' => &[&[(2.0, 0.0), (2.0, 0.5)]],
+        '-' => &[&[(1.0, 3.0), (3.0, 3.0)]],
+        '/' => &[&[(0.0, 0.0), (4.0, 6.0)]],
+        '%' => &[
+            &[(0.0, 0.0), (4.0, 6.0)],
+            &[(0.0, 6.0), (1.0, 6.0), (1.0, 5.0), (0.0, 5.0), (0.0, 6.0)],
+            &[(3.0, 1.0), (4.0, 1.0), (4.0, 0.0), (3.0, 0.0), (3.0, 1.0)],
+        ],
+        'F' => &[
+            &[(4.0, 6.0), (0.0, 6.0), (0.0, 0.0)],
+            &[(0.0, 3.0), (3.0, 3.0)],
+        ],
+        'P' => &[&[(0.0, 0.0), (0.0, 6.0), (4.0, 6.0), (4.0, 3.0), (0.0, 3.0)]],
+        'S' => &[&[
+            (4.0, 6.0),
+            (1.0, 6.0),
+            (0.0, 5.0),
+            (0.0, 4.0),
+            (1.0, 3.0),
+            (3.0, 3.0),
+            (4.0, 2.0),
+            (4.0, 1.0),
+            (3.0, 0.0),
+            (0.0, 0.0),
+        ]],
+        ' ' => &[],
+        _ => return None,
+    };
+    Some(strokes)
+}
+
+/// Whether a character has a glyph
+pub fn supports(c: char) -> bool {
+    glyph(c.to_ascii_uppercase()).is_some()
+}
+
+/// Width of a line of text at the given height, in the same units as `height`
+pub fn text_width(text: &str, height: f32) -> f32 {
+    let count = text.chars().filter(|c| supports(*c)).count();
+    if count == 0 {
+        return 0.0;
+    }
+    let scale = height / CELL_HEIGHT;
+    ((count - 1) as f32 * ADVANCE + CELL_WIDTH) * scale
+}
+
+/// Polylines for a line of text with its bottom-left corner at `(x, y)`
+pub fn text_strokes(text: &str, x: f32, y: f32, height: f32) -> Vec<Vec<(f32, f32)>> {
+    let scale = height / CELL_HEIGHT;
+    text.chars()
+        .filter_map(|c| glyph(c.to_ascii_uppercase()))
+        .enumerate()
+        .flat_map(|(index, strokes)| {
+            let origin = x + index as f32 * ADVANCE * scale;
+            strokes.iter().map(move |stroke| {
+                stroke
+                    .iter()
+                    .map(|&(gx, gy)| (origin + gx * scale, y + gy * scale))
+                    .collect()
+            })
+        })
+        .collect()
+}
+
+/// The stroke font as a [`GlyphSource`], with `size` as the text height
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StrokeFont;
+
+impl GlyphSource for StrokeFont {
+    fn advance(&self, c: char, size: f64) -> f64 {
+        if supports(c) {
+            ADVANCE as f64 * size / CELL_HEIGHT as f64
+        } else {
+            0.0
+        }
+    }
+
+    fn glyph_paths(&self, c: char, size: f64) -> Vec<Vec<Point>> {
+        let scale = size / CELL_HEIGHT as f64;
+        glyph(c.to_ascii_uppercase())
+            .unwrap_or(&[])
+            .iter()
+            .map(|stroke| {
+                stroke
+                    .iter()
+                    .map(|&(x, y)| Point::new(x as f64 * scale, y as f64 * scale))
+                    .collect()
+            })
+            .collect()
+    }
+}
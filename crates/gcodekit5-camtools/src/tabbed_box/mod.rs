@@ -7,6 +7,9 @@ mod types;
 
 pub use types::*;
 
+use crate::annotation::{AnnotatedTool, AnnotationLevel, Annotator};
+use crate::preview::GeneratorPreview;
+
 #[derive(Clone, Copy, Debug)]
 struct LayoutCursor {
     x: f32,
@@ -43,6 +46,7 @@ pub struct TabbedBoxMaker {
     t: f32,
     paths: Vec<Vec<Point>>,
     path_groups: Vec<Vec<usize>>,
+    annotation: AnnotationLevel,
 }
 
 impl TabbedBoxMaker {
@@ -69,9 +73,16 @@ impl TabbedBoxMaker {
             t,
             paths: Vec::new(),
             path_groups: Vec::new(),
+            annotation: AnnotationLevel::Off,
         })
     }
 
+    /// Annotate the G-code with the panel and pass each section cuts
+    pub fn with_annotation(mut self, level: AnnotationLevel) -> Self {
+        self.annotation = level;
+        self
+    }
+
     fn validate_parameters(params: &BoxParameters) -> Result<(), String> {
         if params.x < 20.0 || params.y < 20.0 || params.h < 20.0 {
             return Err("All dimensions must be at least 20mm".to_string());
@@ -1126,6 +1137,17 @@ impl TabbedBoxMaker {
         Ok(())
     }
 
+    /// Extents and size of the G-code [`generate_gcode`](Self::generate_gcode) writes
+    pub fn preview(&mut self) -> Result<GeneratorPreview, String> {
+        Ok(GeneratorPreview::from_program(&self.generate_gcode()?))
+    }
+
+    /// Generate the box paths and return the G-code in one step
+    pub fn generate_gcode(&mut self) -> Result<String, String> {
+        self.generate()?;
+        Ok(self.to_gcode())
+    }
+
     pub fn to_gcode(&self) -> String {
         let mut gcode = String::new();
 
@@ -1246,13 +1268,12 @@ impl TabbedBoxMaker {
         };
 
         let panel_names = ["Wall 1", "Wall 2", "Wall 4", "Wall 3", "Top", "Bottom"];
+        let annotator = Annotator::new(AnnotatedTool::TabbedBox, self.annotation);
 
         for (i, path) in self.paths.iter().enumerate() {
-            gcode.push_str(&format!(
-                "; Panel {}: {}\n",
-                i + 1,
-                panel_names.get(i).unwrap_or(&"Unknown")
-            ));
+            let panel_name = panel_names.get(i).unwrap_or(&"Unknown");
+            gcode.push_str(&format!("; Panel {}: {}\n", i + 1, panel_name));
+            annotator.section(&mut gcode, format_args!("Panel {} {}", i + 1, panel_name));
 
             if let Some(first_point) = path.first() {
                 gcode.push_str(&format!(
@@ -1267,6 +1288,15 @@ impl TabbedBoxMaker {
                         "; Pass {}/{} at Z{:.2}\n",
                         pass_num, self.params.laser_passes, z_depth
                     ));
+                    annotator.line(
+                        &mut gcode,
+                        format_args!(
+                            "Panel {} pass {}/{}",
+                            i + 1,
+                            pass_num,
+                            self.params.laser_passes
+                        ),
+                    );
 
                     if pass_num > 1 && self.params.num_axes >= 3 {
                         gcode.push_str(&format!("G0 Z{:.2} ; Move to pass depth\n", z_depth));
@@ -0,0 +1,217 @@
+//! # Tiling
+//!
+//! Repeats one program on a grid of columns × rows with a fixed X/Y pitch, for
+//! cutting many copies of a part from one sheet. Each copy is the program shifted
+//! with [`crate::origin_placement::translate_program`], and the copies are joined
+//! with [`crate::program_merge::merge_programs`], so every tile gets a comment
+//! header and starts from a retract and a reset modal baseline, optionally after
+//! an M0 pause.
+//!
+//! Tiles are cut row by row from the origin, alternating direction on every row
+//! to keep the traverses short. The combined extents are checked against the work
+//! area before anything is produced.
+
+use crate::error::{CamToolError, CamToolResult};
+use crate::origin_placement::{translate_program, StockBounds};
+use crate::program_merge::{merge_programs, MergeOptions, MergeSource};
+use crate::stats::{program_moves, ProgramMove};
+
+/// Extents are compared with this tolerance (mm)
+const EPSILON: f64 = 1e-6;
+
+/// How a program is repeated
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileOptions {
+    /// Copies along X
+    pub columns: usize,
+    /// Copies along Y
+    pub rows: usize,
+    /// Distance between columns in mm
+    pub pitch_x: f64,
+    /// Distance between rows in mm
+    pub pitch_y: f64,
+    /// Z (mm, work coordinates) to retract to between tiles and at the end
+    pub clearance_z: f64,
+    /// Stop with M0 before each tile after the first
+    pub pause_between: bool,
+    /// Work area in work coordinates (mm); the tiled extents must fit inside it
+    pub work_area: Option<StockBounds>,
+}
+
+impl Default for TileOptions {
+    fn default() -> Self {
+        Self {
+            columns: 2,
+            rows: 2,
+            pitch_x: 50.0,
+            pitch_y: 50.0,
+            clearance_z: 5.0,
+            pause_between: false,
+            work_area: None,
+        }
+    }
+}
+
+/// XY extents of a program's moves in mm, or `None` if it has no moves
+pub fn program_extents(program: &str) -> Option<StockBounds> {
+    let lines: Vec<String> = program.lines().map(str::to_string).collect();
+    let mut points = Vec::new();
+    for program_move in program_moves(&lines) {
+        match program_move {
+            ProgramMove::Linear { end, .. } => points.push([end[0], end[1]]),
+            ProgramMove::Arc { end, center, .. } => {
+                points.push([end[0], end[1]]);
+                if let Some((radius, start_angle, sweep)) = program_move.arc_geometry() {
+                    // Axis extremes the arc passes through
+                    for quadrant in 0..4 {
+                        let angle = quadrant as f64 * std::f64::consts::FRAC_PI_2;
+                        let swept = if sweep >= 0.0 {
+                            (angle - start_angle).rem_euclid(std::f64::consts::TAU)
+                        } else {
+                            (start_angle - angle).rem_euclid(std::f64::consts::TAU)
+                        };
+                        if swept <= sweep.abs() {
+                            points.push([
+                                center[0] + radius * angle.cos(),
+                                center[1] + radius * angle.sin(),
+                            ]);
+                        }
+                    }
+                }
+            }
+            ProgramMove::Dwell(_) => {}
+        }
+    }
+    let first = points.first()?;
+    Some(points.iter().fold(
+        StockBounds::new(first[0], first[1], first[0], first[1]),
+        |bounds, [x, y]| StockBounds {
+            min_x: bounds.min_x.min(*x),
+            min_y: bounds.min_y.min(*y),
+            max_x: bounds.max_x.max(*x),
+            max_y: bounds.max_y.max(*y),
+        },
+    ))
+}
+
+/// XY extents of the tiled program in mm, or `None` if the program has no moves
+pub fn tile_extents(program: &str, options: &TileOptions) -> Option<StockBounds> {
+    program_extents(program).map(|part| grid_extents(&part, options))
+}
+
+fn grid_extents(part: &StockBounds, options: &TileOptions) -> StockBounds {
+    let span_x = options.columns.saturating_sub(1) as f64 * options.pitch_x;
+    let span_y = options.rows.saturating_sub(1) as f64 * options.pitch_y;
+    StockBounds {
+        min_x: part.min_x,
+        min_y: part.min_y,
+        max_x: part.max_x + span_x,
+        max_y: part.max_y + span_y,
+    }
+}
+
+/// Repeat a program on a grid
+///
+/// Fails if the grid is empty, a pitch is not positive, the program has no moves
+/// or the tiled extents do not fit the work area.
+pub fn tile_program(program: &str, options: &TileOptions) -> CamToolResult<String> {
+    if options.columns == 0 || options.rows == 0 {
+        return Err(CamToolError::InvalidParameters(format!(
+            "tile grid must have at least one column and row, got {} x {}",
+            options.columns, options.rows
+        )));
+    }
+    let bad_pitch = |count: usize, pitch: f64| count > 1 && (!pitch.is_finite() || pitch <= 0.0);
+    if bad_pitch(options.columns, options.pitch_x) || bad_pitch(options.rows, options.pitch_y) {
+        return Err(CamToolError::InvalidParameters(format!(
+            "tile pitch must be positive, got X{} Y{}",
+            options.pitch_x, options.pitch_y
+        )));
+    }
+    let Some(part) = program_extents(program) else {
+        return Err(CamToolError::InvalidParameters(
+            "program has no moves to tile".to_string(),
+        ));
+    };
+    let extents = grid_extents(&part, options);
+    if let Some(area) = &options.work_area {
+        let fits = extents.min_x >= area.min_x - EPSILON
+            && extents.min_y >= area.min_y - EPSILON
+            && extents.max_x <= area.max_x + EPSILON
+            && extents.max_y <= area.max_y + EPSILON;
+        if !fits {
+            return Err(CamToolError::InvalidParameters(format!(
+                "tiled extents X{:.3}..{:.3} Y{:.3}..{:.3} do not fit the work area X{:.3}..{:.3} Y{:.3}..{:.3}",
+                extents.min_x,
+                extents.max_x,
+                extents.min_y,
+                extents.max_y,
+                area.min_x,
+                area.max_x,
+                area.min_y,
+                area.max_y
+            )));
+        }
+    }
+
+    let overlaps = |count: usize, pitch: f64, size: f64| count > 1 && pitch < size - EPSILON;
+    if overlaps(options.columns, options.pitch_x, part.max_x - part.min_x)
+        || overlaps(options.rows, options.pitch_y, part.max_y - part.min_y)
+    {
+        tracing::warn!(
+            "Tile pitch X{} Y{} is smaller than the part ({:.3} x {:.3} mm); copies overlap",
+            options.pitch_x,
+            options.pitch_y,
+            part.max_x - part.min_x,
+            part.max_y - part.min_y
+        );
+    }
+
+    let count = options.columns * options.rows;
+    let mut sources = Vec::with_capacity(count);
+    for row in 0..options.rows {
+        for step in 0..options.columns {
+            let column = if row.is_multiple_of(2) {
+                step
+            } else {
+                options.columns - 1 - step
+            };
+            let (dx, dy) = (
+                column as f64 * options.pitch_x,
+                row as f64 * options.pitch_y,
+            );
+            sources.push(MergeSource::new(
+                format!(
+                    "Tile {} (row {}, column {}) at X+{:.3} Y+{:.3}",
+                    sources.len() + 1,
+                    row + 1,
+                    column + 1,
+                    dx,
+                    dy
+                ),
+                translate_program(program, dx, dy),
+            ));
+        }
+    }
+
+    let merged = merge_programs(
+        &sources,
+        &MergeOptions {
+            clearance_z: options.clearance_z,
+            pause_between: options.pause_between,
+            ..Default::default()
+        },
+    )?;
+    Ok(format!(
+        "; Tiled program: {} x {} copies, pitch X{:.3} Y{:.3}\n; Extents: X{:.3}..{:.3} Y{:.3}..{:.3}\n{}",
+        options.columns,
+        options.rows,
+        options.pitch_x,
+        options.pitch_y,
+        extents.min_x,
+        extents.max_x,
+        extents.min_y,
+        extents.max_y,
+        merged
+    ))
+}
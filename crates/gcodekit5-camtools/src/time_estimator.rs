@@ -0,0 +1,240 @@
+//! # Acceleration-Aware Time Estimate
+//!
+//! Estimates a program's run time with trapezoidal velocity profiles: every move
+//! accelerates and decelerates at a fixed rate instead of running at its feed
+//! from start to end. With lookahead the speed carried through each junction is
+//! limited the way GRBL's planner does it, from the acceleration and the junction
+//! deviation, so gentle corners keep their speed and sharp ones slow down. Without
+//! lookahead the machine stops at every junction.
+//!
+//! Arcs are split into chords within [`ARC_TOLERANCE`] mm, as the firmware does.
+//! Acceleration is one limit for all axes, so the estimate is a model of the
+//! machine rather than a replay of its planner.
+
+use crate::error::{CamToolError, CamToolResult};
+use crate::stats::{program_moves, ProgramMove, ESTIMATE_RAPID_RATE};
+
+/// Maximum chord deviation when splitting arcs (mm)
+pub const ARC_TOLERANCE: f64 = 0.002;
+/// Moves shorter than this are ignored (mm)
+const MIN_LENGTH: f64 = 1e-9;
+
+/// Machine limits used by [`estimate_time`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MotionLimits {
+    /// Acceleration in mm/s²
+    pub acceleration: f64,
+    /// Junction deviation in mm (GRBL `$11`); larger values corner faster
+    pub junction_deviation: f64,
+    /// Rapid (G0) speed in mm/min
+    pub rapid_rate: f64,
+    /// Carry speed through junctions; when off, every move starts and ends at rest
+    pub lookahead: bool,
+}
+
+impl Default for MotionLimits {
+    fn default() -> Self {
+        Self {
+            acceleration: 10.0,
+            junction_deviation: 0.01,
+            rapid_rate: ESTIMATE_RAPID_RATE,
+            lookahead: true,
+        }
+    }
+}
+
+/// Result of [`estimate_time`]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TimeEstimate {
+    /// Estimated run time in seconds
+    pub seconds: f64,
+    /// Distance travelled in mm
+    pub distance: f64,
+    /// Planner blocks (straight moves and arc chords)
+    pub blocks: usize,
+}
+
+/// A straight move in the plan
+struct Block {
+    length: f64,
+    direction: [f64; 3],
+    /// Nominal speed in mm/s
+    speed: f64,
+    /// Greatest entry speed allowed by the junction, squared
+    max_entry_sq: f64,
+    entry_sq: f64,
+}
+
+/// Estimate a program's run time with acceleration and junction lookahead
+///
+/// Fails if a limit is not a positive number (the junction deviation may be zero).
+pub fn estimate_time(lines: &[String], limits: &MotionLimits) -> CamToolResult<TimeEstimate> {
+    let valid = limits.acceleration.is_finite()
+        && limits.acceleration > 0.0
+        && limits.rapid_rate.is_finite()
+        && limits.rapid_rate > 0.0
+        && limits.junction_deviation.is_finite()
+        && limits.junction_deviation >= 0.0;
+    if !valid {
+        return Err(CamToolError::InvalidParameters(format!(
+            "motion limits must be positive, got {:?}",
+            limits
+        )));
+    }
+
+    let moves = program_moves(lines);
+    let mut estimate = TimeEstimate {
+        distance: moves.iter().map(ProgramMove::length).sum(),
+        ..Default::default()
+    };
+    let mut blocks: Vec<Block> = Vec::new();
+    for program_move in moves {
+        match program_move {
+            ProgramMove::Dwell(pause) => {
+                // The machine comes to rest before a dwell
+                estimate.seconds += plan_time(&mut blocks, limits.acceleration) + pause;
+                estimate.blocks += blocks.len();
+                blocks.clear();
+            }
+            ProgramMove::Linear { rate, .. } | ProgramMove::Arc { rate, .. } => {
+                let speed = rate.unwrap_or(limits.rapid_rate) / 60.0;
+                for (start, end) in chords(&program_move) {
+                    push_block(&mut blocks, start, end, speed, limits);
+                }
+            }
+        }
+    }
+    estimate.seconds += plan_time(&mut blocks, limits.acceleration);
+    estimate.blocks += blocks.len();
+    Ok(estimate)
+}
+
+/// A move as straight chords
+fn chords(program_move: &ProgramMove) -> Vec<([f64; 3], [f64; 3])> {
+    let (start, end, [cx, cy], (radius, start_angle, sweep)) =
+        match (*program_move, program_move.arc_geometry()) {
+            (
+                ProgramMove::Arc {
+                    start, end, center, ..
+                },
+                Some(geometry),
+            ) => (start, end, center, geometry),
+            (ProgramMove::Linear { start, end, .. } | ProgramMove::Arc { start, end, .. }, _) => {
+                return vec![(start, end)]
+            }
+            (ProgramMove::Dwell(_), _) => return Vec::new(),
+        };
+
+    let step = if radius > ARC_TOLERANCE {
+        2.0 * (1.0 - ARC_TOLERANCE / radius).acos()
+    } else {
+        sweep.abs()
+    };
+    let segments = (sweep.abs() / step).ceil().max(1.0) as usize;
+    let point = |k: usize| {
+        if k == segments {
+            return end;
+        }
+        let t = k as f64 / segments as f64;
+        let angle = start_angle + sweep * t;
+        [
+            cx + radius * angle.cos(),
+            cy + radius * angle.sin(),
+            start[2] + (end[2] - start[2]) * t,
+        ]
+    };
+    (0..segments).map(|k| (point(k), point(k + 1))).collect()
+}
+
+fn push_block(
+    blocks: &mut Vec<Block>,
+    start: [f64; 3],
+    end: [f64; 3],
+    speed: f64,
+    limits: &MotionLimits,
+) {
+    let delta = [end[0] - start[0], end[1] - start[1], end[2] - start[2]];
+    let length = (delta[0] * delta[0] + delta[1] * delta[1] + delta[2] * delta[2]).sqrt();
+    if length < MIN_LENGTH {
+        return;
+    }
+    let direction = [delta[0] / length, delta[1] / length, delta[2] / length];
+
+    let max_entry_sq = match blocks.last() {
+        Some(previous) if limits.lookahead => {
+            let junction = junction_speed_sq(previous.direction, direction, limits);
+            junction
+                .min(previous.speed * previous.speed)
+                .min(speed * speed)
+        }
+        _ => 0.0,
+    };
+    blocks.push(Block {
+        length,
+        direction,
+        speed,
+        max_entry_sq,
+        entry_sq: 0.0,
+    });
+}
+
+/// Squared speed allowed through the junction between two directions (GRBL's
+/// junction deviation model: the corner is rounded by a circle that deviates at
+/// most `junction_deviation` from it, taken at the centripetal acceleration limit)
+fn junction_speed_sq(previous: [f64; 3], next: [f64; 3], limits: &MotionLimits) -> f64 {
+    let cos_theta = -(previous[0] * next[0] + previous[1] * next[1] + previous[2] * next[2]);
+    if cos_theta > 0.999_999 {
+        // Reversal
+        return 0.0;
+    }
+    if cos_theta < -0.999_999 {
+        // Straight on
+        return f64::INFINITY;
+    }
+    let sin_half = (0.5 * (1.0 - cos_theta)).sqrt();
+    limits.acceleration * limits.junction_deviation * sin_half / (1.0 - sin_half)
+}
+
+/// Time to run a sequence of blocks that starts and ends at rest
+fn plan_time(blocks: &mut [Block], acceleration: f64) -> f64 {
+    // Backward pass: every block must be able to stop by the end of the plan
+    let mut exit_sq = 0.0;
+    for block in blocks.iter_mut().rev() {
+        block.entry_sq = block
+            .max_entry_sq
+            .min(exit_sq + 2.0 * acceleration * block.length);
+        exit_sq = block.entry_sq;
+    }
+    // Forward pass: entry speeds must be reachable from the previous block
+    let mut entry_sq = 0.0;
+    for block in blocks.iter_mut() {
+        block.entry_sq = block.entry_sq.min(entry_sq);
+        entry_sq = block.entry_sq + 2.0 * acceleration * block.length;
+    }
+
+    (0..blocks.len())
+        .map(|i| {
+            let exit_sq = blocks.get(i + 1).map_or(0.0, |next| next.entry_sq);
+            block_time(&blocks[i], exit_sq, acceleration)
+        })
+        .sum()
+}
+
+/// Duration of one block's trapezoidal (or triangular) profile
+fn block_time(block: &Block, exit_sq: f64, acceleration: f64) -> f64 {
+    let (entry, exit) = (block.entry_sq.sqrt(), exit_sq.sqrt());
+    let cruise_sq = block.speed * block.speed;
+    let accelerate = (cruise_sq - block.entry_sq).max(0.0) / (2.0 * acceleration);
+    let decelerate = (cruise_sq - exit_sq).max(0.0) / (2.0 * acceleration);
+    if accelerate + decelerate <= block.length {
+        (block.speed - entry) / acceleration
+            + (block.speed - exit) / acceleration
+            + (block.length - accelerate - decelerate) / block.speed
+    } else {
+        let peak = ((2.0 * acceleration * block.length + block.entry_sq + exit_sq) / 2.0)
+            .sqrt()
+            .max(entry)
+            .max(exit);
+        (peak - entry) / acceleration + (peak - exit) / acceleration
+    }
+}
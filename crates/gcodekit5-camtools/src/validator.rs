@@ -1,6 +1,13 @@
 //! G-Code Validator - Task 65
 //!
 //! Validates G-code syntax, ranges, and consistency.
+//!
+//! Position, plane (G17/G18/G19), distance mode and motion mode are tracked across
+//! lines so R-word arcs can be checked for a radius that reaches the end point.
+//! Optional blocks (lines starting with `/`) are skipped when the block delete
+//! switch is on, and checked like any other line when it is off.
+
+use gcodekit5_core::{apply_block_delete, ArcMove, ArcPlane};
 
 /// Validation error
 #[derive(Debug, Clone)]
@@ -26,6 +33,8 @@ pub struct ValidatorConfig {
     pub max_z: f64,
     /// Min Z
     pub min_z: f64,
+    /// Block delete switch: skip lines starting with `/`
+    pub block_delete: bool,
 }
 
 impl Default for ValidatorConfig {
@@ -37,10 +46,20 @@ impl Default for ValidatorConfig {
             min_y: -gcodekit5_core::constants::WORLD_EXTENT_MM,
             max_z: 500.0,
             min_z: -500.0,
+            block_delete: true,
         }
     }
 }
 
+/// Modal state needed to resolve arcs
+#[derive(Debug, Clone, Copy, Default)]
+struct ModalState {
+    position: [f64; 3],
+    plane: ArcPlane,
+    motion: Option<u32>,
+    incremental: bool,
+}
+
 /// Validates G-code
 #[derive(Debug)]
 pub struct GCodeValidator {
@@ -56,11 +75,18 @@ impl GCodeValidator {
     /// Validate program
     pub fn validate(&self, lines: &[String]) -> Result<(), Vec<ValidationError>> {
         let mut errors = Vec::new();
+        let mut modal = ModalState::default();
 
         for (line_num, line) in lines.iter().enumerate() {
+            let Some(line) = apply_block_delete(line, self.config.block_delete) else {
+                continue;
+            };
             if let Err(e) = self.validate_line(line, line_num) {
                 errors.extend(e);
             }
+            if let Err(e) = Self::track_motion(line, line_num, &mut modal) {
+                errors.push(e);
+            }
         }
 
         if errors.is_empty() {
@@ -70,6 +96,11 @@ impl GCodeValidator {
         }
     }
 
+    /// Range and feed errors of a single line, without modal tracking
+    pub(crate) fn line_errors(&self, line: &str, line_num: usize) -> Vec<ValidationError> {
+        self.validate_line(line, line_num).err().unwrap_or_default()
+    }
+
     fn validate_line(&self, line: &str, line_num: usize) -> Result<(), Vec<ValidationError>> {
         let mut errors = Vec::new();
         let trimmed = line.trim();
@@ -132,6 +163,139 @@ impl GCodeValidator {
         }
     }
 
+    /// Update the modal state for a line, checking R-word arcs on the way
+    fn track_motion(
+        line: &str,
+        line_num: usize,
+        modal: &mut ModalState,
+    ) -> Result<(), ValidationError> {
+        let words = Self::words(line);
+        let mut target = modal.position;
+        let mut has_axis = false;
+        let mut radius = None;
+
+        for &(letter, value) in &words {
+            match letter {
+                'G' => match value {
+                    v if v == v.trunc() && (0.0..=3.0).contains(&v) => {
+                        modal.motion = Some(v as u32)
+                    }
+                    v if v == v.trunc() && (17.0..=19.0).contains(&v) => {
+                        modal.plane = ArcPlane::from_gcode(v as u8).unwrap_or(modal.plane)
+                    }
+                    90.0 => modal.incremental = false,
+                    91.0 => modal.incremental = true,
+                    _ => {}
+                },
+                'X' | 'Y' | 'Z' => {
+                    let axis = (letter as u8 - b'X') as usize;
+                    target[axis] = if modal.incremental {
+                        modal.position[axis] + value
+                    } else {
+                        value
+                    };
+                    has_axis = true;
+                }
+                'R' => radius = Some(value),
+                _ => {}
+            }
+        }
+
+        if !has_axis {
+            return Ok(());
+        }
+
+        let start = modal.position;
+        modal.position = target;
+        let has_offset = words
+            .iter()
+            .any(|&(letter, _)| matches!(letter, 'I' | 'J' | 'K'));
+        match (modal.motion, radius) {
+            (Some(code @ (2 | 3)), Some(r)) if !has_offset => {
+                ArcMove::from_radius(start, target, r, code == 2, modal.plane)
+                    .map(|_| ())
+                    .map_err(|e| ValidationError {
+                        line: line_num,
+                        message: e.to_string(),
+                    })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Letter/value words of a line, without comments
+    pub(crate) fn words(line: &str) -> Vec<(char, f64)> {
+        Self::word_texts(line)
+            .into_iter()
+            .filter_map(|(letter, number)| number.parse().ok().map(|value| (letter, value)))
+            .collect()
+    }
+
+    /// Words of a line with their number text as written, without comments
+    pub(crate) fn word_texts(line: &str) -> Vec<(char, String)> {
+        let mut code = String::new();
+        let mut in_paren = false;
+        for ch in line.chars() {
+            match ch {
+                ';' if !in_paren => break,
+                '(' => in_paren = true,
+                ')' => in_paren = false,
+                _ if !in_paren => code.push(ch.to_ascii_uppercase()),
+                _ => {}
+            }
+        }
+
+        let mut words = Vec::new();
+        let mut chars = code.chars().peekable();
+        while let Some(ch) = chars.next() {
+            if !ch.is_ascii_alphabetic() {
+                continue;
+            }
+            let mut number = String::new();
+            while let Some(&next) = chars.peek() {
+                if next.is_ascii_digit() || next == '.' || next == '-' || next == '+' {
+                    number.push(next);
+                    chars.next();
+                } else if next == ' ' && number.is_empty() {
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            if !number.is_empty() {
+                words.push((ch, number));
+            }
+        }
+        words
+    }
+
+    /// Rebuild a line from its words, keeping its comments at the end
+    pub(crate) fn rebuild_line(line: &str, words: &[(char, String)]) -> String {
+        let mut parts: Vec<String> = words
+            .iter()
+            .map(|(letter, number)| format!("{}{}", letter, number))
+            .collect();
+
+        let mut paren: Option<String> = None;
+        for (index, ch) in line.char_indices() {
+            match (&mut paren, ch) {
+                (None, ';') => {
+                    parts.push(line[index..].trim_end().to_string());
+                    break;
+                }
+                (None, '(') => paren = Some("(".to_string()),
+                (Some(comment), ')') => {
+                    comment.push(')');
+                    parts.push(std::mem::take(comment));
+                    paren = None;
+                }
+                (Some(comment), _) => comment.push(ch),
+                (None, _) => {}
+            }
+        }
+        parts.join(" ")
+    }
+
     fn extract_coord(&self, line: &str, axis: char) -> Option<f64> {
         let pattern = format!("{}", axis);
         if let Some(pos) = line.find(pattern.as_str()) {
@@ -3,6 +3,9 @@
 //! Converts vector image formats (SVG, DXF) to G-code for laser cutting/engraving.
 //! Supports path stroking, fill patterns, and various vector formats.
 
+use crate::air_assist::{AirAssist, AirAssistState};
+use crate::annotation::{AnnotatedTool, AnnotationLevel, Annotator};
+use crate::preview::GeneratorPreview;
 use anyhow::{Context, Result};
 use image::{Rgb, RgbImage};
 use lyon::algorithms::path::iterator::PathIterator;
@@ -54,6 +57,8 @@ pub struct VectorEngravingParameters {
     pub cross_hatch: bool,
     /// Number of axes on the target device (default 3).
     pub num_axes: u8,
+    /// Air assist codes; each path, with its hatching, is an operation
+    pub air_assist: AirAssist,
 }
 
 impl Default for VectorEngravingParameters {
@@ -79,6 +84,7 @@ impl Default for VectorEngravingParameters {
             dwell_time: 0.1,
             cross_hatch: false,
             num_axes: 3,
+            air_assist: AirAssist::default(),
         }
     }
 }
@@ -92,6 +98,8 @@ pub struct VectorEngraver {
     /// Scale factor from SVG units to mm
     #[allow(dead_code)]
     pub scale_factor: f32,
+    /// Source annotations written into the G-code
+    pub annotation: AnnotationLevel,
 }
 
 impl VectorEngraver {
@@ -125,6 +133,7 @@ impl VectorEngraver {
             params,
             paths,
             scale_factor,
+            annotation: AnnotationLevel::Off,
         })
     }
 
@@ -743,11 +752,22 @@ impl VectorEngraver {
         (min_x, min_y, max_x, max_y)
     }
 
+    /// Extents and size of the G-code [`generate_gcode`](Self::generate_gcode) writes
+    pub fn preview(&self) -> Result<GeneratorPreview> {
+        Ok(GeneratorPreview::from_program(&self.generate_gcode()?))
+    }
+
     /// Generate G-code for vector engraving
     pub fn generate_gcode(&self) -> Result<String> {
         self.generate_gcode_with_progress(|_| {})
     }
 
+    /// Annotate the G-code with each path and its hatches and outline
+    pub fn with_annotation(mut self, level: AnnotationLevel) -> Self {
+        self.annotation = level;
+        self
+    }
+
     /// Render paths to an image for preview
     pub fn render_preview(&self, width: u32, height: u32) -> RgbImage {
         let mut img = RgbImage::new(width, height);
@@ -872,6 +892,7 @@ impl VectorEngraver {
     where
         F: FnMut(f32),
     {
+        self.params.air_assist.validate()?;
         let mut gcode = String::new();
 
         gcode.push_str("; Laser Vector Engraving G-code\n");
@@ -907,6 +928,9 @@ impl VectorEngraver {
             "; Estimated time: {:.1} seconds\n",
             self.estimate_time()
         ));
+        if let Some(comment) = self.params.air_assist.header_comment() {
+            gcode.push_str(&comment);
+        }
         gcode.push_str(";\n");
 
         gcode.push_str("G21 ; Set units to millimeters\n");
@@ -1002,6 +1026,8 @@ impl VectorEngraver {
         }
 
         let total_items = processed_paths.len() as f32;
+        let mut air = AirAssistState::new(&self.params.air_assist);
+        let annotator = Annotator::new(AnnotatedTool::VectorEngraver, self.annotation);
         let num_passes = if self.params.multi_pass {
             self.params.num_passes as usize
         } else {
@@ -1031,8 +1057,16 @@ impl VectorEngraver {
             }
 
             for (idx, item) in processed_paths.iter().enumerate() {
+                annotator.section(
+                    &mut gcode,
+                    format_args!("Pass {}/{} path {}", pass + 1, num_passes, idx + 1),
+                );
                 // 1. Render Hatches for this path
-                for hatch_path in &item.hatches {
+                for (hatch, hatch_path) in item.hatches.iter().enumerate() {
+                    annotator.line(
+                        &mut gcode,
+                        format_args!("Path {} hatch {}", idx + 1, hatch + 1),
+                    );
                     let mut start_point = point(0.0, 0.0);
                     for event in hatch_path.iter().flattened(0.1) {
                         match event {
@@ -1043,6 +1077,7 @@ impl VectorEngraver {
                                     at.x * scale,
                                     at.y * scale
                                 ));
+                                air.before_burn(&mut gcode);
                                 start_point = at;
                             }
                             lyon::path::Event::Line { to, .. } => {
@@ -1071,6 +1106,7 @@ impl VectorEngraver {
                 }
 
                 // 2. Render Outline for this path
+                annotator.line(&mut gcode, format_args!("Path {} outline", idx + 1));
                 let mut start_point = point(0.0, 0.0);
                 for event in item.outline.iter().flattened(0.1) {
                     match event {
@@ -1081,6 +1117,7 @@ impl VectorEngraver {
                                 at.x * scale,
                                 at.y * scale
                             ));
+                            air.before_burn(&mut gcode);
                             start_point = at;
                         }
                         lyon::path::Event::Line { to, .. } => {
@@ -1115,6 +1152,7 @@ impl VectorEngraver {
                         self.params.dwell_time
                     ));
                 }
+                air.end_operation(&mut gcode);
 
                 let progress = 0.1
                     + ((pass as f32 * total_items + idx as f32)
@@ -1126,6 +1164,7 @@ impl VectorEngraver {
 
         gcode.push_str("\n; End of engraving\n");
         gcode.push_str("M5 ; Laser off\n");
+        air.end_job(&mut gcode);
         gcode.push_str("G0 X0 Y0 ; Return to origin\n");
 
         progress_callback(1.0);
@@ -81,3 +81,31 @@ fn test_helical_drilling() {
     // Return to center
     assert!(gcode.contains("G1 X0.000 Y0.000 F500.0"));
 }
+
+#[test]
+fn test_peck_drilling_annotations() {
+    use gcodekit5_camtools::AnnotationLevel;
+
+    let params = DrillPressParameters {
+        hole_diameter: 5.0,
+        tool_diameter: 5.0,
+        top_z: 0.0,
+        bottom_z: -4.0,
+        peck_depth: 2.0,
+        plunge_rate: 100.0,
+        feed_rate: 500.0,
+        spindle_speed: 1000.0,
+        safe_z: 5.0,
+        x: 10.0,
+        y: 20.0,
+    };
+
+    let gcode = DrillPressGenerator::new(params)
+        .with_annotation(AnnotationLevel::Line)
+        .generate()
+        .expect("generate failed");
+
+    assert!(gcode.contains("(DrillPress: Peck drill X10.000 Y20.000)\n"));
+    assert!(gcode.contains("(DrillPress: Peck 1 to Z-2.000)\n"));
+    assert!(gcode.contains("(DrillPress: Peck 2 to Z-4.000)\n"));
+}
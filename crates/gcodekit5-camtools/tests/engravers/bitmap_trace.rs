@@ -0,0 +1,131 @@
+use gcodekit5_camtools::bitmap_trace::{BitmapTracer, TraceMode, TraceParameters, TurnPolicy};
+use gcodekit5_camtools::vector_engraver::VectorEngravingParameters;
+use image::{DynamicImage, GrayImage, Luma};
+
+/// White image with black pixels where `ink` returns true
+fn image(width: u32, height: u32, ink: impl Fn(u32, u32) -> bool) -> DynamicImage {
+    DynamicImage::ImageLuma8(GrayImage::from_fn(width, height, |x, y| {
+        Luma([if ink(x, y) { 0 } else { 255 }])
+    }))
+}
+
+fn tracer(configure: impl FnOnce(&mut TraceParameters)) -> BitmapTracer {
+    let mut params = TraceParameters::default();
+    configure(&mut params);
+    BitmapTracer::new(params)
+}
+
+#[test]
+fn test_square_traces_to_four_corners() {
+    let img = image(40, 40, |x, y| {
+        (10..30).contains(&x) && (10..30).contains(&y)
+    });
+    let contours = BitmapTracer::default().trace_image(&img);
+
+    assert_eq!(contours.len(), 1);
+    let square = &contours[0];
+    assert!(square.closed);
+    assert!(!square.is_hole);
+    assert_eq!(square.points.len(), 4);
+    for &(x, y) in &square.points {
+        // 0.1mm pixels, Y flipped so the image top is up
+        assert!((x - 1.0).abs() < 1e-4 || (x - 3.0).abs() < 1e-4);
+        assert!((y - 1.0).abs() < 1e-4 || (y - 3.0).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_ring_has_outer_and_hole() {
+    let img = image(40, 40, |x, y| {
+        let outer = (5..35).contains(&x) && (5..35).contains(&y);
+        let inner = (15..25).contains(&x) && (15..25).contains(&y);
+        outer && !inner
+    });
+    let contours = BitmapTracer::default().trace_image(&img);
+
+    assert_eq!(contours.len(), 2);
+    assert_eq!(contours.iter().filter(|c| c.is_hole).count(), 1);
+}
+
+#[test]
+fn test_circle_is_simplified() {
+    let img = image(200, 200, |x, y| {
+        (x as f32 - 100.0).hypot(y as f32 - 100.0) < 80.0
+    });
+    let contours = BitmapTracer::default().trace_image(&img);
+
+    assert_eq!(contours.len(), 1);
+    let segments = contours[0].segment_count();
+    // The raw pixel boundary has hundreds of steps
+    assert!((16..100).contains(&segments), "{} segments", segments);
+}
+
+#[test]
+fn test_speckles_are_dropped() {
+    let square = |x: u32, y: u32| (10..30).contains(&x) && (10..30).contains(&y);
+    let speckle = image(40, 40, |x, y| (x, y) == (2, 2) || square(x, y));
+    assert_eq!(BitmapTracer::default().trace_image(&speckle).len(), 1);
+
+    let blob = image(40, 40, |x, y| (x < 3 && y < 3) || square(x, y));
+    assert_eq!(BitmapTracer::default().trace_image(&blob).len(), 2);
+    assert_eq!(tracer(|p| p.min_area = 10.0).trace_image(&blob).len(), 1);
+}
+
+#[test]
+fn test_threshold_and_invert() {
+    let img = DynamicImage::ImageLuma8(GrayImage::from_fn(20, 20, |x, _| {
+        Luma([if x < 10 { 100 } else { 200 }])
+    }));
+    assert_eq!(tracer(|p| p.threshold = 50).trace_image(&img).len(), 0);
+    assert_eq!(tracer(|p| p.threshold = 150).trace_image(&img).len(), 1);
+
+    let inverted = tracer(|p| {
+        p.threshold = 150;
+        p.invert = true;
+    })
+    .trace_image(&img);
+    assert_eq!(inverted.len(), 1);
+    assert!(inverted[0].points.iter().all(|&(x, _)| x >= 0.999));
+}
+
+#[test]
+fn test_turn_policy_decides_diagonal_connection() {
+    // Two squares touching only at one corner
+    let img = image(20, 20, |x, y| {
+        ((2..10).contains(&x) && (2..10).contains(&y))
+            || ((10..18).contains(&x) && (10..18).contains(&y))
+    });
+    let joined = tracer(|p| p.turn_policy = TurnPolicy::Black).trace_image(&img);
+    let apart = tracer(|p| p.turn_policy = TurnPolicy::White).trace_image(&img);
+    assert_eq!(joined.len(), 1);
+    assert_eq!(apart.len(), 2);
+}
+
+#[test]
+fn test_centerline_of_thick_stroke() {
+    let img = image(60, 20, |x, y| (5..55).contains(&x) && (8..13).contains(&y));
+    let contours = tracer(|p| p.mode = TraceMode::Centerline).trace_image(&img);
+
+    assert_eq!(contours.len(), 1);
+    let stroke = &contours[0];
+    assert!(!stroke.closed);
+    assert_eq!(stroke.points.len(), 2);
+    // Runs along the middle row (pixel 10 -> 0.1mm * (20 - 10.5))
+    for &(_, y) in &stroke.points {
+        assert!((y - 0.95).abs() < 0.15, "y = {}", y);
+    }
+    let length = (stroke.points[1].0 - stroke.points[0].0).abs();
+    assert!(length > 3.5, "length = {}", length);
+}
+
+#[test]
+fn test_traced_image_feeds_vector_engraver() {
+    let img = image(40, 40, |x, y| {
+        (10..30).contains(&x) && (10..30).contains(&y)
+    });
+    let engraver = BitmapTracer::default().to_engraver(&img, VectorEngravingParameters::default());
+    assert_eq!(engraver.paths.len(), 1);
+
+    let gcode = engraver.generate_gcode().unwrap();
+    assert!(gcode.contains("G1"));
+}
@@ -1,6 +1,9 @@
 use gcodekit5_camtools::laser_engraver::{
-    EngravingParameters, HalftoneMethod, ImageTransformations, RotationAngle,
+    BitmapImageEngraver, EngravingParameters, HalftoneMethod, ImageTransformations,
+    MaterialTestGenerator, MaterialTestParameters, RotationAngle, TestSquareStyle,
 };
+use gcodekit5_camtools::stroke_font;
+use gcodekit5_camtools::{AirAssist, CamToolError};
 
 #[test]
 fn test_default_parameters() {
@@ -18,3 +21,209 @@ fn test_transformations_default() {
     assert_eq!(trans.rotation, RotationAngle::Degrees0);
     assert_eq!(trans.halftone, HalftoneMethod::None);
 }
+
+fn engrave_row(intensities: &[u8], params: EngravingParameters) -> String {
+    let row =
+        image::GrayImage::from_raw(intensities.len() as u32, 1, intensities.to_vec()).unwrap();
+    let params = EngravingParameters {
+        width_mm: intensities.len() as f32 / 10.0,
+        pixels_per_mm: 10.0,
+        bidirectional: false,
+        ..params
+    };
+    BitmapImageEngraver::from_image(image::DynamicImage::ImageLuma8(row), params)
+        .unwrap()
+        .generate_gcode()
+        .unwrap()
+}
+
+#[test]
+fn test_skip_power_travels_over_faint_pixels() {
+    let row = [255, 255, 10, 10, 255, 20, 20, 20, 20, 255];
+    let params = EngravingParameters {
+        skip_power: 10.0,
+        min_skip_length: 0.25,
+        ..EngravingParameters::default()
+    };
+    let gcode = engrave_row(&row, params.clone());
+    let burns: Vec<&str> = gcode
+        .lines()
+        .filter(|l| l.starts_with("G1 X"))
+        .filter(|l| !l.ends_with(" S0"))
+        .collect();
+    let burned_x: Vec<&str> = burns.iter().map(|l| &l[4..9]).collect();
+    assert_eq!(burned_x, vec!["0.000", "0.100", "0.400", "0.900"]);
+    // The two-pixel gap keeps the laser on at S0; the four-pixel gap rapids
+    let scan = &gcode[gcode.find("G0 X0 Y0.000").unwrap()..];
+    assert!(scan.contains("G1 X0.100 Y0.000\nG1 X0.300 Y0.000 S0\nG1 X0.400"));
+    assert!(scan.contains("G1 X0.400 Y0.000 F1000 M3 S1000\nM5\nG0 X0.800 Y0.000\n"));
+
+    let unskipped = engrave_row(&row, EngravingParameters::default());
+    assert!(unskipped.contains("M3 S39\n"));
+    assert!(!gcode.contains("M3 S39\n") && !gcode.contains("M3 S78\n"));
+}
+
+fn small_test_grid() -> MaterialTestParameters {
+    MaterialTestParameters {
+        power_min: 20.0,
+        power_max: 80.0,
+        power_steps: 3,
+        speed_min: 1000.0,
+        speed_max: 2000.0,
+        speed_steps: 2,
+        style: TestSquareStyle::Outline,
+        ..MaterialTestParameters::default()
+    }
+}
+
+#[test]
+fn test_material_test_layout_steps() {
+    let layout = MaterialTestGenerator::new(small_test_grid())
+        .layout()
+        .unwrap();
+    assert_eq!(layout.powers, vec![20.0, 50.0, 80.0]);
+    assert_eq!(layout.speeds, vec![1000.0, 2000.0]);
+    // 2 columns of 5mm squares with a 2mm gap, after the row labels
+    assert!((layout.width - (layout.grid_x + 12.0)).abs() < 1e-4);
+    assert!((layout.height - (layout.grid_y + 19.0)).abs() < 1e-4);
+}
+
+#[test]
+fn test_material_test_gcode_uses_row_power_and_column_speed() {
+    let gcode = MaterialTestGenerator::new(small_test_grid())
+        .generate_gcode()
+        .unwrap();
+    assert!(gcode.contains("; Square: 20% at 1000 mm/min"));
+    assert!(gcode.contains("; Square: 80% at 2000 mm/min"));
+    assert!(gcode.contains("M3 S800"));
+    assert!(gcode.contains(" F2000\n"));
+    // Labels burn at the label power
+    assert!(gcode.contains("M3 S500"));
+    assert!(gcode.trim_end().ends_with("M30 ; End program"));
+    assert_eq!(gcode.matches("M3 S").count(), gcode.matches("M5\n").count());
+}
+
+#[test]
+fn test_material_test_filled_squares_scan_lines() {
+    let params = MaterialTestParameters {
+        power_steps: 1,
+        speed_steps: 1,
+        square_size: 2.0,
+        line_interval: 0.5,
+        style: TestSquareStyle::Filled,
+        ..small_test_grid()
+    };
+    let gcode = MaterialTestGenerator::new(params).generate_gcode().unwrap();
+    let square = gcode
+        .split("; Square:")
+        .nth(1)
+        .and_then(|s| s.split("; Labels").next())
+        .unwrap();
+    assert_eq!(square.matches("M3 S200").count(), 4);
+}
+
+#[test]
+fn test_material_test_reports_stock_overflow() {
+    let params = MaterialTestParameters {
+        stock_width: 20.0,
+        stock_height: 20.0,
+        ..MaterialTestParameters::default()
+    };
+    let err = MaterialTestGenerator::new(params).layout().unwrap_err();
+    assert!(matches!(err, CamToolError::InvalidParameters(_)));
+    assert!(err.to_string().contains("stock is 20.0 x 20.0 mm"));
+}
+
+#[test]
+fn test_material_test_column_labels_fit_columns() {
+    let params = MaterialTestParameters {
+        square_size: 4.0,
+        spacing: 1.0,
+        label_height: 6.0,
+        ..small_test_grid()
+    };
+    let layout = MaterialTestGenerator::new(params).layout().unwrap();
+    let label_width = stroke_font::text_width(
+        &MaterialTestGenerator::speed_label(2000.0),
+        layout.column_label_height,
+    );
+    assert!(layout.column_label_height < 6.0);
+    assert!(label_width <= 4.5 + 1e-4);
+}
+
+#[test]
+fn test_stroke_font_text_is_single_line() {
+    assert!(stroke_font::supports('7'));
+    assert!(stroke_font::supports('%'));
+    assert!(!stroke_font::supports('Q'));
+
+    let strokes = stroke_font::text_strokes("10%", 5.0, 2.0, 6.0);
+    assert!(strokes.iter().all(|s| s.len() >= 2));
+    let max_x = strokes
+        .iter()
+        .flatten()
+        .map(|p| p.0)
+        .fold(f32::MIN, f32::max);
+    assert!((max_x - (5.0 + stroke_font::text_width("10%", 6.0))).abs() < 1e-4);
+    assert!(strokes.iter().flatten().all(|p| (2.0..=8.0).contains(&p.1)));
+}
+
+#[test]
+fn test_material_test_air_assist_wraps_burns() {
+    let gcode = MaterialTestGenerator::new(small_test_grid())
+        .generate_gcode()
+        .unwrap();
+    assert!(!gcode.contains("Air assist"));
+
+    let params = MaterialTestParameters {
+        air_assist: AirAssist {
+            enabled: true,
+            ..AirAssist::default()
+        },
+        ..small_test_grid()
+    };
+    let gcode = MaterialTestGenerator::new(params).generate_gcode().unwrap();
+    assert_eq!(gcode.matches("M8 ; Air assist on").count(), 1);
+    assert_eq!(gcode.matches("M9 ; Air assist off").count(), 1);
+    let on = gcode.find("M8 ; Air assist on").unwrap();
+    let off = gcode.find("M9 ; Air assist off").unwrap();
+    assert!(on < gcode.find("M3 S").unwrap());
+    assert!(off > gcode.rfind("M3 S").unwrap());
+    assert!(gcode.contains("; Air assist: M8 on, M9 off (coolant output)"));
+}
+
+#[test]
+fn test_material_test_air_assist_per_operation() {
+    let params = MaterialTestParameters {
+        air_assist: AirAssist {
+            enabled: true,
+            on_code: "M106 P1 S255".to_string(),
+            off_code: "M107 P1".to_string(),
+            per_operation: true,
+        },
+        ..small_test_grid()
+    };
+    let gcode = MaterialTestGenerator::new(params).generate_gcode().unwrap();
+    // Six squares and the labels
+    assert_eq!(gcode.matches("M106 P1 S255 ; Air assist on").count(), 7);
+    assert_eq!(gcode.matches("M107 P1 ; Air assist off").count(), 7);
+
+    let invalid = MaterialTestParameters {
+        air_assist: AirAssist {
+            enabled: true,
+            on_code: "M8".to_string(),
+            off_code: "m8".to_string(),
+            per_operation: false,
+        },
+        ..small_test_grid()
+    };
+    assert!(MaterialTestGenerator::new(invalid)
+        .generate_gcode()
+        .is_err());
+    let not_m_code = AirAssist {
+        enabled: true,
+        on_code: "G0 X10".to_string(),
+        ..AirAssist::default()
+    };
+    assert!(not_m_code.validate().is_err());
+}
@@ -1,3 +1,4 @@
+pub mod bitmap_trace;
 pub mod hatch_generator;
 pub mod laser_engraver;
 pub mod svg_to_gcode;
@@ -1,4 +1,5 @@
 use gcodekit5_camtools::vector_engraver::{VectorEngraver, VectorEngravingParameters};
+use gcodekit5_camtools::AnnotationLevel;
 use lyon::math::point;
 use lyon::path::Path;
 
@@ -44,6 +45,7 @@ fn test_estimate_time() {
         params,
         paths: vec![path],
         scale_factor: 1.0,
+        annotation: AnnotationLevel::Off,
     };
 
     let time = engraver.estimate_time();
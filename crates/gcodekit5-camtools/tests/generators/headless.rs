@@ -0,0 +1,139 @@
+use gcodekit5_camtools::headless::{self, HeadlessOptions};
+use gcodekit5_camtools::validator::ValidatorConfig;
+use gcodekit5_camtools::{
+    BoxParameters, CamToolError, DrillPressParameters, PuzzleParameters, SpoilboardGridParameters,
+};
+
+fn drill_params() -> DrillPressParameters {
+    DrillPressParameters {
+        hole_diameter: 6.0,
+        tool_diameter: 6.0,
+        top_z: 0.0,
+        bottom_z: -5.0,
+        peck_depth: 0.0,
+        plunge_rate: 100.0,
+        feed_rate: 500.0,
+        spindle_speed: 10000.0,
+        safe_z: 5.0,
+        x: 10.0,
+        y: 10.0,
+    }
+}
+
+#[test]
+fn test_headless_tabbed_box_generates() {
+    let gcode = headless::tabbed_box(BoxParameters::default()).expect("generate failed");
+    assert!(gcode.contains("; Tabbed Box Maker G-code"));
+    assert!(gcode.contains("G1"));
+}
+
+#[test]
+fn test_headless_jigsaw_rejects_invalid_parameters() {
+    let params = PuzzleParameters {
+        width: 10.0,
+        ..PuzzleParameters::default()
+    };
+    let err = headless::jigsaw_puzzle(params, 500.0, 1.0).unwrap_err();
+    assert!(matches!(err, CamToolError::InvalidParameters(_)));
+}
+
+#[test]
+fn test_headless_generators_pass_default_validation() {
+    let options = HeadlessOptions::validated();
+
+    let gcode = headless::drill_press(drill_params()).expect("generate failed");
+    headless::finish(gcode, &options).expect("drill output should validate");
+
+    let gcode = headless::spoilboard_grid(SpoilboardGridParameters {
+        width: 100.0,
+        height: 100.0,
+        grid_spacing: 10.0,
+        feed_rate: 1000.0,
+        laser_power: 500.0,
+        laser_mode: "M3".to_string(),
+    })
+    .expect("generate failed");
+    headless::finish(gcode, &options).expect("grid output should validate");
+}
+
+#[test]
+fn test_headless_validation_failure_is_reported() {
+    let options = HeadlessOptions {
+        validator: Some(ValidatorConfig {
+            max_x: 5.0,
+            ..ValidatorConfig::default()
+        }),
+        ..HeadlessOptions::default()
+    };
+    let gcode = headless::drill_press(drill_params()).expect("generate failed");
+    match headless::finish(gcode, &options) {
+        Err(CamToolError::ValidationFailed(errors)) => assert!(!errors.is_empty()),
+        other => panic!("expected validation failure, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_headless_home_before_prepends_single_homing_cycle() {
+    let options = HeadlessOptions {
+        home_before: true,
+        ..HeadlessOptions::default()
+    };
+    let gcode = headless::finish("$H\nG0 X0\n".to_string(), &options).expect("finish failed");
+    assert!(gcode.starts_with("$H\n"));
+    assert_eq!(gcode.matches("$H").count(), 1);
+}
+
+#[test]
+fn test_headless_missing_file_is_load_error() {
+    let err = headless::bitmap_engraving("/nonexistent/image.png", Default::default()).unwrap_err();
+    assert!(matches!(err, CamToolError::LoadError(_)));
+}
+
+#[test]
+fn test_headless_traced_vector_engraving() {
+    let path = std::env::temp_dir().join(format!("headless_trace_{}.png", std::process::id()));
+    image::GrayImage::from_fn(40, 40, |x, y| {
+        let inside = (10..30).contains(&x) && (10..30).contains(&y);
+        image::Luma([if inside { 0 } else { 255 }])
+    })
+    .save(&path)
+    .unwrap();
+
+    let gcode =
+        headless::traced_vector_engraving(&path, Default::default(), Default::default()).unwrap();
+    assert!(gcode.contains("G1"));
+
+    let nothing = headless::traced_vector_engraving(
+        &path,
+        gcodekit5_camtools::TraceParameters {
+            threshold: 0,
+            ..Default::default()
+        },
+        Default::default(),
+    )
+    .unwrap_err();
+    assert!(matches!(nothing, CamToolError::GenerationFailed(_)));
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_headless_profile_templates_wrap_output() {
+    let mut profile = gcodekit5_devicedb::DeviceProfile::default();
+    profile.program_templates.preamble = "G28\nG0 Z{safe_z}".to_string();
+    profile.program_templates.postamble = "M30".to_string();
+    let options = HeadlessOptions {
+        profile: Some(profile),
+        template_values: [(
+            "safe_z".to_string(),
+            gcodekit5_devicedb::TemplateValue::Number(5.0),
+        )]
+        .into(),
+        ..HeadlessOptions::default()
+    };
+    let gcode = headless::finish("G0 X0\n".to_string(), &options).expect("finish failed");
+    assert_eq!(gcode, "G28\nG0 Z5\nG0 X0\nM30\n");
+
+    // The default options leave the output as generated
+    let plain = headless::finish("G0 X0\n".to_string(), &HeadlessOptions::default()).unwrap();
+    assert_eq!(plain, "G0 X0\n");
+}
@@ -1,2 +1,5 @@
+pub mod headless;
 pub mod jigsaw_puzzle;
+pub mod preview;
 pub mod spoilboard_grid_test;
+pub mod spoilboard_surfacing;
@@ -0,0 +1,86 @@
+use gcodekit5_camtools::stats::StatsCalculator;
+use gcodekit5_camtools::{
+    BoxParameters, DrillPressGenerator, DrillPressParameters, GeneratorPreview,
+    SpoilboardGridGenerator, SpoilboardGridParameters, TabbedBoxMaker,
+};
+
+/// Laser and grid output has only rapids and lines, so word extents are exact
+fn assert_matches_stats(preview: &GeneratorPreview, gcode: &str) {
+    let lines: Vec<String> = gcode.lines().map(str::to_string).collect();
+    let stats = StatsCalculator::calculate(&lines);
+    let bounds = preview.bounds.expect("no bounds");
+    for (preview, stats) in [
+        (bounds.min_x, stats.min_x),
+        (bounds.max_x, stats.max_x),
+        (bounds.min_y, stats.min_y),
+        (bounds.max_y, stats.max_y),
+    ] {
+        assert!((preview - stats).abs() < 1e-9, "{preview} != {stats}");
+    }
+    assert_eq!(
+        preview.estimated_lines,
+        gcode.lines().filter(|l| !l.trim().is_empty()).count()
+    );
+}
+
+#[test]
+fn test_preview_bounds_match_generated_output() {
+    let mut maker = TabbedBoxMaker::new(BoxParameters::default()).unwrap();
+    let preview = maker.preview().unwrap();
+    assert_matches_stats(&preview, &maker.generate_gcode().unwrap());
+
+    let grid = SpoilboardGridGenerator::new(SpoilboardGridParameters {
+        width: 100.0,
+        height: 60.0,
+        grid_spacing: 10.0,
+        feed_rate: 1000.0,
+        laser_power: 500.0,
+        laser_mode: "M3".to_string(),
+    });
+    let preview = grid.preview().unwrap();
+    assert_matches_stats(&preview, &grid.generate().unwrap());
+    assert!(preview.fits_within(100.0, 60.0));
+    assert!(!preview.fits_within(90.0, 60.0));
+}
+
+#[test]
+fn test_preview_measures_arc_bulges() {
+    let drill = DrillPressGenerator::new(DrillPressParameters {
+        hole_diameter: 10.0,
+        tool_diameter: 6.0,
+        top_z: 0.0,
+        bottom_z: -3.0,
+        peck_depth: 1.0,
+        plunge_rate: 100.0,
+        feed_rate: 500.0,
+        spindle_speed: 10000.0,
+        safe_z: 5.0,
+        x: 20.0,
+        y: 20.0,
+    });
+    let bounds = drill.preview().unwrap().bounds.unwrap();
+    // The helix circles the hole center at radius 2, not just its start point
+    assert_eq!((bounds.min_x, bounds.max_x), (18.0, 22.0));
+    assert_eq!((bounds.min_y, bounds.max_y), (18.0, 22.0));
+    assert_eq!((bounds.min_z, bounds.max_z), (-3.0, 5.0));
+
+    let preview =
+        GeneratorPreview::from_program("G10 L20 P1 X50 Y50\nG0 X-10 Y0\nG2 X10 Y0 R10\n(end)\n\n");
+    let bounds = preview.bounds.unwrap();
+    assert_eq!((bounds.min_y, bounds.max_y), (0.0, 10.0));
+    assert_eq!((bounds.min_x, bounds.max_x), (-10.0, 10.0));
+    assert_eq!(preview.estimated_lines, 4);
+    assert_eq!(preview.warnings.len(), 1, "{:?}", preview.warnings);
+    assert!(!preview.fits_within(100.0, 100.0));
+}
+
+#[test]
+fn test_preview_measures_inch_programs_in_mm() {
+    let preview =
+        GeneratorPreview::from_program("G20 G90\nG0 X0 Y0\nG1 X2 Y1 F10\nG3 X0 Y1 I-1 J0\n");
+    let bounds = preview.bounds.unwrap();
+    assert!((bounds.max_x - 50.8).abs() < 1e-9);
+    assert!((bounds.max_y - 50.8).abs() < 1e-9, "{}", bounds.max_y);
+    assert!(preview.fits_within(60.0, 60.0));
+    assert!(!preview.fits_within(40.0, 60.0));
+}
@@ -1,4 +1,6 @@
-use gcodekit5_camtools::spoilboard_grid::{SpoilboardGridGenerator, SpoilboardGridParameters};
+use gcodekit5_camtools::spoilboard_grid::{
+    SpoilboardGridGenerator, SpoilboardGridParameters, SpoilboardHoleParameters,
+};
 
 #[test]
 fn test_spoilboard_grid_generation_metric() {
@@ -85,3 +87,61 @@ fn test_spoilboard_grid_generation_fractional_inch_converted() {
     // X=25.4: Up (Start Y=0)
     assert!(gcode.contains("G0 X25.400 Y0.000"));
 }
+
+#[test]
+fn test_spoilboard_holes_rapid_only_at_clearance() {
+    let params = SpoilboardGridParameters {
+        width: 40.0,
+        height: 20.0,
+        grid_spacing: 10.0,
+        feed_rate: 1000.0,
+        laser_power: 0.0,
+        laser_mode: "M3".to_string(),
+    };
+    let holes = SpoilboardHoleParameters {
+        bottom_z: -12.0,
+        peck_depth: 5.0,
+        clearance_z: 8.0,
+        ..SpoilboardHoleParameters::default()
+    };
+    let gcode = SpoilboardGridGenerator::new(params.clone())
+        .with_holes(holes.clone())
+        .generate()
+        .expect("generate failed");
+
+    let value = |line: &str, axis: char| {
+        line.split_whitespace()
+            .take_while(|w| !w.starts_with(';'))
+            .find_map(|w| w.strip_prefix(axis)?.parse::<f64>().ok())
+    };
+    let mut z = f64::INFINITY;
+    let mut holes_drilled = 0;
+    let mut deepest = 0.0_f64;
+    for line in gcode.lines() {
+        if let Some(new_z) = value(line, 'Z') {
+            z = new_z;
+            deepest = deepest.min(z);
+            if line.starts_with("G1") && z == holes.bottom_z {
+                holes_drilled += 1;
+            }
+        }
+        let xy = value(line, 'X').is_some() || value(line, 'Y').is_some();
+        if line.starts_with("G0") && xy {
+            assert!(z >= holes.clearance_z, "rapid {:?} at Z{}", line, z);
+        }
+    }
+    assert_eq!(holes_drilled, 5 * 3);
+    assert_eq!(deepest, holes.bottom_z);
+    // Pecks lift to the surface; only the final peck retracts to clearance
+    assert_eq!(gcode.matches("Retract to clearance").count(), 15 + 1);
+    assert_eq!(gcode.matches("Clear chips").count(), 15 * 2);
+
+    let too_low = SpoilboardHoleParameters {
+        clearance_z: -1.0,
+        ..holes
+    };
+    assert!(SpoilboardGridGenerator::new(params)
+        .with_holes(too_low)
+        .generate()
+        .is_err());
+}
@@ -0,0 +1,95 @@
+use gcodekit5_camtools::spoilboard_surfacing::FlatnessReport;
+use gcodekit5_camtools::CamToolError;
+use gcodekit5_core::{HeightPoint, ProbeMesh};
+
+fn mesh(z: impl Fn(f64, f64) -> f64) -> ProbeMesh {
+    let mut mesh = ProbeMesh::new(50.0, 50.0);
+    for yi in 0..5 {
+        for xi in 0..5 {
+            let (x, y) = (xi as f64 * 50.0, yi as f64 * 50.0);
+            mesh.add_point(HeightPoint { x, y, z: z(x, y) });
+        }
+    }
+    mesh
+}
+
+#[test]
+fn test_flatness_of_tilted_plane() {
+    // 0.2mm rise over 200mm in X, 0.1mm fall over 200mm in Y
+    let report = FlatnessReport::from_probe_mesh(&mesh(|x, y| 0.001 * x - 0.0005 * y + 1.0))
+        .expect("flatness");
+
+    assert_eq!(report.point_count, 25);
+    assert!((report.slope_x - 0.001).abs() < 1e-9);
+    assert!((report.slope_y + 0.0005).abs() < 1e-9);
+    assert!((report.offset - 1.0).abs() < 1e-9);
+    assert!((report.range - 0.3).abs() < 1e-9);
+    assert!(report.rms_deviation < 1e-9);
+    assert!((report.tilt_x_degrees - 0.001f64.atan().to_degrees()).abs() < 1e-9);
+    assert!(report.tilt_y_degrees < 0.0);
+
+    let (along_x, along_y) = report.tilt_over(200.0, 200.0);
+    assert!((along_x - 0.2).abs() < 1e-9);
+    assert!((along_y + 0.1).abs() < 1e-9);
+}
+
+#[test]
+fn test_flatness_deviation_from_level_plane() {
+    // Level surface with a single 0.05mm high spot in the middle
+    let report =
+        FlatnessReport::from_probe_mesh(&mesh(
+            |x, y| {
+                if x == 100.0 && y == 100.0 {
+                    0.05
+                } else {
+                    0.0
+                }
+            },
+        ))
+        .expect("flatness");
+
+    assert!(report.tilt_x_degrees.abs() < 1e-9);
+    assert!(report.tilt_y_degrees.abs() < 1e-9);
+    assert!((report.range - 0.05).abs() < 1e-12);
+    // The plane rises by the mean (0.002), leaving 0.048 at the high spot
+    assert!((report.max_deviation - 0.048).abs() < 1e-9);
+    assert!(report.rms_deviation > 0.0 && report.rms_deviation < report.max_deviation);
+    assert!(report.summary().starts_with("Points: 25 | Range: 0.0500"));
+}
+
+#[test]
+fn test_flatness_rejects_degenerate_meshes() {
+    let mut too_few = ProbeMesh::new(10.0, 10.0);
+    too_few.add_point(HeightPoint {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    });
+    too_few.add_point(HeightPoint {
+        x: 10.0,
+        y: 0.0,
+        z: 0.0,
+    });
+    assert!(matches!(
+        FlatnessReport::from_probe_mesh(&too_few),
+        Err(CamToolError::InvalidParameters(_))
+    ));
+
+    let mut collinear = too_few.clone();
+    collinear.add_point(HeightPoint {
+        x: 20.0,
+        y: 0.0,
+        z: 0.1,
+    });
+    assert!(matches!(
+        FlatnessReport::from_probe_mesh(&collinear),
+        Err(CamToolError::GeometryError(_))
+    ));
+
+    let mut diagonal = ProbeMesh::new(10.0, 10.0);
+    for i in 0..4 {
+        let v = i as f64 * 10.0;
+        diagonal.add_point(HeightPoint { x: v, y: v, z: 0.0 });
+    }
+    assert!(FlatnessReport::from_probe_mesh(&diagonal).is_err());
+}
@@ -0,0 +1,200 @@
+use gcodekit5_camtools::arc_expander::{ArcExpander, ArcExpanderConfig};
+use gcodekit5_core::{ArcMove, ArcPlane};
+
+#[test]
+fn test_expand_xy_arc_ends_at_target() {
+    let expander = ArcExpander::default();
+    let points = expander.expand_arc(10.0, 0.0, 0.0, 10.0, 0.0, 0.0, false);
+    // Quarter circle of R10 is ~15.7mm; at 0.5mm per segment that needs 32
+    assert_eq!(points.len(), 32);
+    assert_eq!(*points.last().unwrap(), (0.0, 10.0));
+    for (x, y) in points {
+        assert!((x.hypot(y) - 10.0).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_expand_xz_arc_stays_in_xz_plane() {
+    let expander = ArcExpander::default();
+    let points = expander.expand_arc_in_plane(
+        [0.0, 3.0, 0.0],
+        [10.0, 3.0, 0.0],
+        [5.0, 3.0, 0.0],
+        true,
+        ArcPlane::ZX,
+    );
+    assert_eq!(*points.last().unwrap(), [10.0, 3.0, 0.0]);
+    for p in &points {
+        assert_eq!(p[1], 3.0);
+        assert!(((p[0] - 5.0).hypot(p[2]) - 5.0).abs() < 1e-9);
+        assert!(p[2] <= 1e-9, "G2 in G18 from X0 to X10 passes below Z0");
+    }
+}
+
+#[test]
+fn test_expand_radius_arc() {
+    let expander = ArcExpander::default();
+    let points = expander
+        .expand_radius_arc([0.0, 0.0, 0.0], [10.0, 0.0, 0.0], 5.0, true, ArcPlane::XY)
+        .unwrap();
+    assert_eq!(*points.last().unwrap(), [10.0, 0.0, 0.0]);
+    for p in &points {
+        assert!(((p[0] - 5.0).hypot(p[1]) - 5.0).abs() < 1e-9);
+    }
+
+    assert!(expander
+        .expand_radius_arc([0.0, 0.0, 0.0], [10.0, 0.0, 0.0], 1.0, true, ArcPlane::XY)
+        .is_err());
+}
+
+#[test]
+fn test_small_arc_uses_minimum_segments() {
+    let expander = ArcExpander::default();
+    let points = expander.expand_arc(1.0, 0.0, 0.0, 1.0, 0.0, 0.0, false);
+    assert_eq!(points.len(), ArcExpanderConfig::default().num_segments);
+}
+
+#[test]
+fn test_expand_helix_climbs_to_target() {
+    let expander = ArcExpander::default();
+    // One full turn rising 10mm
+    let points = expander.expand_arc_in_plane(
+        [5.0, 0.0, 0.0],
+        [5.0, 0.0, 10.0],
+        [0.0, 0.0, 0.0],
+        false,
+        ArcPlane::XY,
+    );
+    assert_eq!(*points.last().unwrap(), [5.0, 0.0, 10.0]);
+    let mut previous_z = 0.0;
+    for p in &points {
+        assert!(p[2] > previous_z);
+        assert!((p[0].hypot(p[1]) - 5.0).abs() < 1e-9);
+        previous_z = p[2];
+    }
+
+    // Z travel adds to the path length and therefore to the segment count
+    let flat = expander.expand_arc_in_plane(
+        [5.0, 0.0, 0.0],
+        [5.0, 0.0, 0.0],
+        [0.0, 0.0, 0.0],
+        false,
+        ArcPlane::XY,
+    );
+    assert!(points.len() > flat.len());
+}
+
+fn splitter(max_arc_length: Option<f64>, max_arc_radius: Option<f64>) -> ArcExpander {
+    ArcExpander::new(ArcExpanderConfig {
+        max_arc_length,
+        max_arc_radius,
+        ..Default::default()
+    })
+}
+
+/// Parse the arcs of a program written in G90 with I/J offsets
+fn arcs(program: &str) -> Vec<ArcMove> {
+    let mut position = [0.0; 3];
+    let mut arcs = Vec::new();
+    for line in program.lines() {
+        let word = |letter: char| {
+            line.split_whitespace()
+                .find(|w| w.starts_with(letter))
+                .map(|w| w[1..].parse::<f64>().unwrap())
+        };
+        let end = [
+            word('X').unwrap_or(position[0]),
+            word('Y').unwrap_or(position[1]),
+            word('Z').unwrap_or(position[2]),
+        ];
+        if line.starts_with("G2") || line.starts_with("G3") {
+            let offsets = [word('I').unwrap_or(0.0), word('J').unwrap_or(0.0), 0.0];
+            arcs.push(ArcMove::from_offsets(
+                position,
+                end,
+                offsets,
+                line.starts_with("G2"),
+                ArcPlane::XY,
+            ));
+        }
+        position = end;
+    }
+    arcs
+}
+
+#[test]
+fn test_split_long_arc_keeps_sweep_and_joins_on_arc() {
+    let program = "G90 G17\nG0 X10 Y0 Z0\nG3 X-10 Y0 I-10 J0 F500 ; half circle\nG1 X0\n";
+    let report = splitter(Some(5.0), None).split_arcs(program).unwrap();
+    assert_eq!(report.split, 1);
+    // Half circle of R10 is ~31.4mm, so 7 pieces of at most 5mm
+    assert_eq!(report.added, 6);
+    assert!(report
+        .program
+        .contains("\nG3 X9.009689 Y4.338837 I-10 J0 F500"));
+    assert!(report.program.contains(" F500 ; half circle"));
+    assert!(report.program.contains("; half circle"));
+    assert!(report.program.ends_with("G1 X0\n"));
+
+    let pieces = arcs(&report.program);
+    assert_eq!(pieces.len(), 7);
+    let total: f64 = pieces.iter().map(|p| p.sweep()).sum();
+    assert!((total - std::f64::consts::PI).abs() < 1e-5);
+    for piece in &pieces {
+        assert!(!piece.clockwise);
+        assert!(piece.length() <= 5.0);
+        assert!((piece.end[0].hypot(piece.end[1]) - 10.0).abs() < 1e-5);
+        assert!(piece.center[0].abs() < 1e-5 && piece.center[1].abs() < 1e-5);
+    }
+    assert_eq!(pieces.last().unwrap().end, [-10.0, 0.0, 0.0]);
+}
+
+#[test]
+fn test_split_large_radius_and_relative_arcs() {
+    // A shallow R500 arc is split, a longer arc of a small radius is not
+    let program = "G90\nG0 X0 Y0 Z0\nG2 X400 Y0 R500\nG2 X405 Y0 I2.5 J0\n";
+    let report = splitter(None, Some(100.0)).split_arcs(program).unwrap();
+    assert_eq!(report.split, 1);
+    assert!(report.program.contains("G2 X405 Y0 I2.5 J0"));
+    let pieces = arcs(&report.program);
+    // ~411mm long, so 5 pieces of at most 100mm, then the small arc
+    assert_eq!(pieces.len(), 6);
+    assert!(pieces[..5]
+        .iter()
+        .all(|p| p.length() <= 100.0 && (p.radius() - 500.0).abs() < 1e-4));
+
+    // In G91 every piece is written relative to where the previous one ended
+    let program = "G90 G0 X0 Y0 Z0\nG91\nG2 X20 Y0 I10 J0\n";
+    let report = splitter(Some(10.0), None).split_arcs(program).unwrap();
+    let deltas: Vec<f64> = report
+        .program
+        .lines()
+        .filter(|l| l.starts_with("G2"))
+        .map(|l| {
+            l.split_whitespace()
+                .find(|w| w.starts_with('X'))
+                .map(|w| w[1..].parse::<f64>().unwrap())
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(deltas.len(), 4);
+    assert!((deltas.iter().sum::<f64>() - 20.0).abs() < 1e-5);
+}
+
+#[test]
+fn test_split_leaves_unknown_or_short_arcs_alone() {
+    let expander = splitter(Some(1.0), None);
+    let report = expander.split_arcs("G2 X10 Y0 I5 J0\n").unwrap();
+    assert_eq!(report.unresolved, 1);
+    assert_eq!(report.program, "G2 X10 Y0 I5 J0\n");
+
+    let arc = ArcMove::from_offsets(
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [-1.0, 0.0, 0.0],
+        false,
+        ArcPlane::XY,
+    );
+    assert_eq!(splitter(Some(5.0), None).split_arc(&arc), vec![arc]);
+    assert!(splitter(Some(0.0), None).split_arcs("G0 X0").is_err());
+}
@@ -0,0 +1,75 @@
+use gcodekit5_camtools::biarc::{fit_biarcs, fit_biarcs_in_program, BiarcOptions, FittedSegment};
+
+fn quarter_circle(radius: f64, steps: usize) -> Vec<[f64; 2]> {
+    (0..=steps)
+        .map(|i| {
+            let angle = std::f64::consts::FRAC_PI_2 * i as f64 / steps as f64;
+            [radius * angle.cos(), radius * angle.sin()]
+        })
+        .collect()
+}
+
+#[test]
+fn test_circle_points_fit_to_few_arcs_within_tolerance() {
+    let points = quarter_circle(10.0, 45);
+    let options = BiarcOptions::default();
+    let segments = fit_biarcs(&points, &options).unwrap();
+    assert!(segments.len() <= 4, "{} segments", segments.len());
+    assert_eq!(segments.last().unwrap().end(), points[45]);
+
+    for segment in &segments {
+        let FittedSegment::Arc(arc) = segment else {
+            panic!("expected only arcs, got {:?}", segment);
+        };
+        assert!(!arc.clockwise);
+        for p in arc.expand(16) {
+            let off = (p[0].hypot(p[1]) - 10.0).abs();
+            assert!(off <= options.tolerance, "{} off the circle", off);
+        }
+    }
+}
+
+#[test]
+fn test_straight_runs_and_corners_become_lines() {
+    let mut points: Vec<[f64; 2]> = (0..=20)
+        .map(|i| [i as f64, 0.0005 * (i % 2) as f64])
+        .collect();
+    points.extend((1..=10).map(|i| [20.0, i as f64]));
+    let segments = fit_biarcs(&points, &BiarcOptions::default()).unwrap();
+    assert_eq!(
+        segments,
+        vec![
+            FittedSegment::Line { end: [20.0, 0.0] },
+            FittedSegment::Line { end: [20.0, 10.0] },
+        ]
+    );
+
+    let bad = BiarcOptions {
+        tolerance: 0.0,
+        ..BiarcOptions::default()
+    };
+    assert!(fit_biarcs(&points, &bad).is_err());
+}
+
+#[test]
+fn test_program_runs_of_short_moves_become_arcs() {
+    let mut program = String::from("G21 G90 G17\nG0 X10 Y0 Z-1\n");
+    for (i, p) in quarter_circle(10.0, 45).iter().enumerate().skip(1) {
+        let feed = if i == 1 { " F500" } else { "" };
+        program.push_str(&format!("G1 X{:.4} Y{:.4}{}\n", p[0], p[1], feed));
+    }
+    program.push_str("X-5 Z-2\nG0 Z5\n");
+
+    let report = fit_biarcs_in_program(&program, &BiarcOptions::default()).unwrap();
+    assert_eq!(report.runs, 1);
+    assert_eq!(report.moves_replaced, 45);
+    let lines: Vec<&str> = report.program.lines().collect();
+    assert_eq!(lines.len(), 2 + report.segments_written + 3);
+    assert!(lines[2].starts_with("G3 ") && lines[2].ends_with(" F500"));
+    assert!(lines[1 + report.segments_written].contains("X0 Y10"));
+    // The modal move after the run is a line again
+    assert_eq!(
+        &lines[2 + report.segments_written..],
+        &["G1", "X-5 Z-2", "G0 Z5"]
+    );
+}
@@ -0,0 +1,85 @@
+use super::code;
+use gcodekit5_camtools::clearance_plane::{enforce_clearance, ClearanceOptions};
+
+#[test]
+fn test_normalizes_retracts_and_keeps_cutting_depths() {
+    let program =
+        "G21 G90\nG0 Z15\nG0 X10 Y10\nG0 Z1\nG1 Z-2 F100\nG1 X20\nG0 Z3 (retract)\nG0 X0 Y0\nM30\n";
+    let report = enforce_clearance(program, &ClearanceOptions::default()).unwrap();
+
+    assert_eq!(
+        code(&report.program),
+        vec![
+            "G21 G90",
+            "G0 Z5",
+            "G0 X10 Y10",
+            "G0 Z1",
+            "G1 Z-2 F100",
+            "G1 X20",
+            "G0 Z5 (retract)",
+            "G0 X0 Y0",
+            "M30",
+        ]
+    );
+    assert_eq!(report.rewritten, 2);
+    assert_eq!(report.inserted, 0);
+}
+
+#[test]
+fn test_inserts_retract_before_low_traverse() {
+    let program = "G90\nG0 X0 Y0\nG1 Z-1 F100\nG1 X10\nG0 X20 Y20\n";
+    let report = enforce_clearance(program, &ClearanceOptions::default()).unwrap();
+    let lines = code(&report.program);
+
+    // Unknown Z at the start and the cutting depth before the last rapid
+    assert_eq!(lines[1], "G0 Z5");
+    assert_eq!(lines[2], "G0 X0 Y0");
+    assert_eq!(lines[5], "G0 Z5");
+    assert_eq!(lines[6], "G0 X20 Y20");
+    assert_eq!(report.inserted, 2);
+    assert!(report.summary().contains("2 retract(s) inserted"));
+}
+
+#[test]
+fn test_relative_moves_use_relative_retracts() {
+    let program = "G90\nG0 Z5\nG0 X0 Y0\nG91\nG1 Z-6 F100\nG0 Z2\nG0 X10\n";
+    let report = enforce_clearance(program, &ClearanceOptions::default()).unwrap();
+    let lines = code(&report.program);
+
+    // From Z-1 the relative retract to Z5 is +6; the traverse is then at clearance
+    assert_eq!(lines[4], "G1 Z-6 F100");
+    assert_eq!(lines[5], "G0 Z6");
+    assert_eq!(lines[6], "G0 X10");
+    assert_eq!(report.rewritten, 1);
+}
+
+#[test]
+fn test_relative_moves_with_unknown_z_are_reported() {
+    let program = "G91\nG0 Z5\nG0 X10\n";
+    let report = enforce_clearance(program, &ClearanceOptions::default()).unwrap();
+    let lines = code(&report.program);
+
+    assert_eq!(report.unresolved, 1);
+    assert_eq!(lines[2], "G90 G0 Z5");
+    assert_eq!(lines[3], "G91");
+    assert_eq!(lines[4], "G0 X10");
+}
+
+#[test]
+fn test_inch_programs_and_invalid_options() {
+    let report = enforce_clearance(
+        "G20 G90\nG0 Z1\n",
+        &ClearanceOptions {
+            clearance_z: 12.7,
+            stock_top: 0.0,
+        },
+    )
+    .unwrap();
+    assert_eq!(code(&report.program)[1], "G0 Z0.5");
+
+    let bad = ClearanceOptions {
+        clearance_z: -1.0,
+        stock_top: 0.0,
+    };
+    assert!(enforce_clearance("G0 Z5\n", &bad).is_err());
+}
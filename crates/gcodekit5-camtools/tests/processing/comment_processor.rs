@@ -20,3 +20,49 @@ fn test_remove_comments() {
     let (processed, _) = proc.process_line("G0 X10 (comment)");
     assert_eq!(processed, "G0 X10");
 }
+
+#[test]
+fn test_annotation_comment_is_sanitized() {
+    use gcodekit5_camtools::{annotation_comment, is_annotation, AnnotatedTool};
+
+    let comment = annotation_comment(AnnotatedTool::TabbedBox, "Panel (1)\tWall");
+    assert_eq!(comment, "(TabbedBox: Panel [1] Wall)");
+    assert!(is_annotation(&comment));
+    assert!(!is_annotation("(move)"));
+    assert!(!is_annotation("G0 X1 (TabbedBox: Panel 1)"));
+
+    let long = annotation_comment(AnnotatedTool::VectorEngraver, &"x".repeat(200));
+    assert!(long.len() < 80);
+}
+
+#[test]
+fn test_strip_annotations_restores_plain_output() {
+    use gcodekit5_camtools::spoilboard_grid::{SpoilboardGridGenerator, SpoilboardGridParameters};
+    use gcodekit5_camtools::AnnotationLevel;
+
+    let params = SpoilboardGridParameters {
+        width: 20.0,
+        height: 20.0,
+        grid_spacing: 10.0,
+        feed_rate: 1000.0,
+        laser_power: 500.0,
+        laser_mode: "M3".to_string(),
+    };
+    let generate = |level| {
+        SpoilboardGridGenerator::new(params.clone())
+            .with_annotation(level)
+            .generate()
+            .expect("generate failed")
+    };
+    let plain = generate(AnnotationLevel::Off);
+    let section = generate(AnnotationLevel::Section);
+    let line = generate(AnnotationLevel::Line);
+
+    assert!(!plain.contains("(SpoilboardGrid:"));
+    assert!(section.contains("(SpoilboardGrid: Vertical lines)\n"));
+    assert!(!section.contains("(SpoilboardGrid: Vertical line X"));
+    assert!(line.contains("(SpoilboardGrid: Horizontal line Y10.000)\n"));
+
+    assert_eq!(CommentProcessor::strip_annotations(&section), plain);
+    assert_eq!(CommentProcessor::strip_annotations(&line), plain);
+}
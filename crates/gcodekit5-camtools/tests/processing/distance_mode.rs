@@ -0,0 +1,54 @@
+use gcodekit5_camtools::distance_mode::{convert_distance_mode, DistanceMode};
+
+const ABSOLUTE: &str = "G21 G90\nG0 X0 Y0 Z5\nG1 Z-1 F100\nG1 X10.5 Y3.25\nG2 X20 Y3.25 I4.75 J0 (arc)\nG1 X0.1\nG0 Z5\nM30\n";
+
+#[test]
+fn test_absolute_to_incremental_and_back() {
+    let incremental = convert_distance_mode(ABSOLUTE, DistanceMode::Incremental);
+    assert_eq!(
+        incremental.program,
+        "G21 G90\nG0 X0 Y0 Z5\nG91 G1 Z-6 F100\nG1 X10.5 Y3.25\nG2 X9.5 Y0 I4.75 J0 (arc)\nG1 X-19.9\nG0 Z6\nM30\n"
+    );
+    assert_eq!(incremental.converted, 5);
+    assert_eq!(incremental.kept, 1);
+
+    let absolute = convert_distance_mode(&incremental.program, DistanceMode::Absolute);
+    assert_eq!(
+        absolute.program,
+        "G21 G90\nG0 X0 Y0 Z5\nG90 G1 Z-1 F100\nG1 X10.5 Y3.25\nG2 X20 Y3.25 I4.75 J0 (arc)\nG1 X0.1\nG0 Z5\nM30\n"
+    );
+    assert_eq!(absolute.kept, 0);
+}
+
+#[test]
+fn test_long_programs_do_not_drift() {
+    let mut program = String::from("G90\nG0 X0\n");
+    for i in 1..=1000 {
+        program.push_str(&format!("G1 X{}\n", i as f64 * 0.1));
+    }
+    let incremental = convert_distance_mode(&program, DistanceMode::Incremental);
+    let back = convert_distance_mode(&incremental.program, DistanceMode::Absolute);
+    let last = back.program.lines().last().unwrap();
+    assert_eq!(last, "G1 X100");
+}
+
+#[test]
+fn test_homing_and_unknown_positions_keep_their_mode() {
+    // Incremental moves from an unknown start cannot become absolute
+    let relative = "G91\nG1 X10 F100\nG1 Y5\n";
+    let report = convert_distance_mode(relative, DistanceMode::Absolute);
+    assert_eq!(report.program, relative);
+    assert_eq!(report.kept, 2);
+    assert_eq!(
+        report.summary(),
+        "0 move(s) converted; 2 move(s) left unchanged (position not known)"
+    );
+
+    // G28 leaves the position unknown, so the next move stays absolute
+    let program = "G90\nG0 X5 Y5\nG0 X10\nG28\nG0 X20 Y20\nG0 X30\n";
+    let report = convert_distance_mode(program, DistanceMode::Incremental);
+    assert_eq!(
+        report.program,
+        "G90\nG0 X5 Y5\nG91 G0 X5\nG28\nG90 G0 X20 Y20\nG91 G0 X10\n"
+    );
+}
@@ -0,0 +1,112 @@
+use super::code;
+use gcodekit5_camtools::dry_run::{
+    apply_dry_run, is_dry_run, remove_dry_run, toggle_dry_run, DryRunOptions, DryRunSpindle,
+};
+use gcodekit5_camtools::CamToolError;
+
+const PROGRAM: &str = "\
+G21 G90
+M3 S12000
+G0 X0 Y0 Z5
+G1 Z-1 F200 ; plunge
+G2 X10 Y0 Z-2 I5 J0
+G91
+G1 Z-0.5
+G90
+G53 G0 Z-2
+G81 X5 Y5 Z-3 R2 F100
+G80
+M5
+M30
+";
+
+#[test]
+fn test_raises_absolute_z_and_keeps_relative_moves() {
+    let dry = apply_dry_run(PROGRAM, &DryRunOptions::default()).unwrap();
+    let lines = code(&dry);
+
+    assert!(lines.contains(&"G0 X0 Y0 Z15"));
+    assert!(lines.contains(&"G1 Z9 F200"));
+    // Arc centers are relative, so only the helical Z moves
+    assert!(lines.contains(&"G2 X10 Y0 Z8 I5 J0"));
+    assert!(lines.contains(&"G1 Z-0.5"));
+    // Machine-coordinate moves are left alone; cycle R planes move with Z
+    assert!(lines.contains(&"G53 G0 Z-2"));
+    assert!(lines.contains(&"G81 X5 Y5 Z7 R12 F100"));
+}
+
+#[test]
+fn test_strips_spindle_by_default() {
+    let dry = apply_dry_run(PROGRAM, &DryRunOptions::default()).unwrap();
+    let lines = code(&dry);
+    assert!(!lines
+        .iter()
+        .any(|l| l.split_whitespace().any(|w| w == "M3")));
+    assert!(lines.contains(&"S12000"));
+    assert!(lines.contains(&"M5"));
+}
+
+#[test]
+fn test_zero_speed_keeps_m3() {
+    let options = DryRunOptions {
+        spindle: DryRunSpindle::ZeroSpeed,
+        ..DryRunOptions::default()
+    };
+    let dry = apply_dry_run(PROGRAM, &options).unwrap();
+    assert!(code(&dry).contains(&"M3 S0"));
+}
+
+#[test]
+fn test_inch_programs_use_converted_offset() {
+    let options = DryRunOptions {
+        z_offset: 25.4,
+        spindle: DryRunSpindle::Keep,
+    };
+    let dry = apply_dry_run("G20 G90\nG0 Z0.25\n", &options).unwrap();
+    assert!(code(&dry).contains(&"G0 Z1.25"));
+}
+
+#[test]
+fn test_remove_restores_original_exactly() {
+    let dry = apply_dry_run(PROGRAM, &DryRunOptions::default()).unwrap();
+    assert!(is_dry_run(&dry));
+    assert!(!is_dry_run(PROGRAM));
+    assert_eq!(remove_dry_run(&dry), PROGRAM);
+
+    let toggled = toggle_dry_run(&dry, &DryRunOptions::default()).unwrap();
+    assert_eq!(toggled, PROGRAM);
+    assert_eq!(
+        toggle_dry_run(PROGRAM, &DryRunOptions::default()).unwrap(),
+        dry
+    );
+}
+
+#[test]
+fn test_rejects_double_application_and_bad_offset() {
+    let dry = apply_dry_run(PROGRAM, &DryRunOptions::default()).unwrap();
+    assert!(matches!(
+        apply_dry_run(&dry, &DryRunOptions::default()),
+        Err(CamToolError::InvalidParameters(_))
+    ));
+
+    let options = DryRunOptions {
+        z_offset: -5.0,
+        ..DryRunOptions::default()
+    };
+    assert!(apply_dry_run(PROGRAM, &options).is_err());
+}
+
+#[test]
+fn test_refuses_programs_that_move_incrementally_before_any_absolute_z() {
+    let incremental = "G21 G91\nM3 S12000\nG0 X10 Y10\nG1 Z-6 F200\nG1 X20\nG0 Z6\nM30\n";
+    let error = apply_dry_run(incremental, &DryRunOptions::default()).unwrap_err();
+    assert!(matches!(error, CamToolError::InvalidParameters(_)));
+    assert!(error.to_string().contains("line 3"), "{}", error);
+
+    // Once an absolute Z has been raised, incremental moves follow it
+    assert!(apply_dry_run(
+        &format!("G90 G0 Z5\n{}", incremental),
+        &DryRunOptions::default()
+    )
+    .is_ok());
+}
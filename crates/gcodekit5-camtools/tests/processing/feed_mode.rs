@@ -0,0 +1,35 @@
+//! Tests for processing::feed_mode
+
+use gcodekit5_camtools::convert_to_units_per_minute;
+
+#[test]
+fn test_converts_mixed_feed_modes_to_units_per_minute() {
+    let program = "G21 G94\nG1 X10 F500\nG95 S1000\nG1 X20 F0.1 ; per rev\nG1 X30\nG93\nG1 X40 F50\nG94\nG1 X50\n";
+    let converted = convert_to_units_per_minute(program, None).unwrap();
+    // Back in G94 the last F word (50) is the feed again, as on the controller
+    assert_eq!(
+        converted,
+        "G21 G94\nG1 X10 F500\nG94 S1000\nG1 X20 F100 ; per rev\nG1 X30\nG94\nG1 X40 F500\nG94\nG1 X50 F50\n"
+    );
+
+    // A given speed wins over the program's S word; inches stay inches
+    let inch = "G20 G95 S500\nG1 X1 F0.004\n(G93 move of 2 in done in 1/10 min)\nG93 G1 X3 F10\n";
+    assert_eq!(
+        convert_to_units_per_minute(inch, Some(1000.0)).unwrap(),
+        "G20 G94 S500\nG1 X1 F4\n(G93 move of 2 in done in 1/10 min)\nG94 G1 X3 F20\n"
+    );
+}
+
+#[test]
+fn test_conversion_errors_without_spindle_speed_or_inverse_feed() {
+    // Under G96, S is a surface speed
+    let surface = "G96 S200 G95\nG1 X10 F0.2\n";
+    assert!(convert_to_units_per_minute(surface, None)
+        .unwrap_err()
+        .to_string()
+        .contains("line 2"));
+    assert!(convert_to_units_per_minute(surface, Some(800.0)).is_ok());
+    assert!(convert_to_units_per_minute("G95\nG1 X10 F0.2\n", None).is_err());
+    assert!(convert_to_units_per_minute("G93\nG1 X10 F2\nG1 X20\n", None).is_err());
+    assert!(convert_to_units_per_minute("G1 X10 F100\n", Some(0.0)).is_err());
+}
@@ -0,0 +1,91 @@
+use gcodekit5_camtools::flatten::{flatten_program, FlattenOptions};
+
+#[test]
+fn test_subprograms_parameters_and_loops_are_expanded() {
+    let program = "\
+G21 G90
+o<square> sub
+  #<size> = #1
+  G1 X#<size> F[#2 * 2]
+  G1 Y#<size>
+o<square> endsub
+#100 = 0
+o101 while [#100 LT 2]
+  o<square> call [10 + #100 * 5] [50]
+  #100 = [#100 + 1]
+o101 endwhile
+o102 if [#100 EQ 2]
+  G0 Z5 (done)
+o102 else
+  G0 Z99
+o102 endif
+M30
+";
+    let report = flatten_program(program, &FlattenOptions::default()).unwrap();
+    assert_eq!(
+        report.program,
+        "G21 G90\nG1 X10 F100\nG1 Y10\nG1 X15 F100\nG1 Y15\nG0 Z5 (done)\nM30\n"
+    );
+    assert_eq!(report.calls_expanded, 2);
+    assert!(report.warnings.is_empty());
+    assert!(report.validation_errors.is_empty());
+}
+
+#[test]
+fn test_canned_cycles_become_moves_unless_supported() {
+    let program = "G21 G90\nG0 X0 Y0 Z10\nG98 G83 X5 Y5 Z-3 R1 Q2 F60\nX8\nG80\n";
+    let report = flatten_program(program, &FlattenOptions::default()).unwrap();
+    let expected_hole = |x: &str| {
+        format!(
+            "G0 X{x} Y5\nG0 Z1\nG1 Z-1 F60\nG0 Z1\nG0 Z-0.746\nG1 Z-3\nG0 Z10\n",
+            x = x
+        )
+    };
+    assert_eq!(
+        report.program,
+        format!(
+            "G21 G90\nG0 X0 Y0 Z10\n{}{}G80\n",
+            expected_hole("5"),
+            expected_hole("8").replace(" F60", "")
+        )
+    );
+    assert_eq!(report.cycles_expanded, 2);
+
+    let kept = flatten_program(
+        program,
+        &FlattenOptions {
+            expand_canned_cycles: false,
+            ..FlattenOptions::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(kept.program, program);
+    assert_eq!(kept.cycles_expanded, 0);
+}
+
+#[test]
+fn test_untranslatable_constructs_are_warned_not_dropped() {
+    let program = "G0 X0 Y0 Z5\no<probe> call\nG1 Z#5063 F100\nG84 X1 Y1 Z-2 R1\nM30\n";
+    let report = flatten_program(program, &FlattenOptions::default()).unwrap();
+    let lines: Vec<&str> = report.program.lines().collect();
+    assert_eq!(lines[1], "(untranslated: o<probe> call)");
+    assert_eq!(lines[2], "G1 Z0 F100");
+    assert_eq!(lines[3], "(untranslated: G84 X1 Y1 Z-2 R1)");
+    let warned: Vec<usize> = report.warnings.iter().map(|w| w.line).collect();
+    assert_eq!(warned, vec![2, 3, 4]);
+    assert!(report
+        .summary()
+        .contains("3 construct(s) could not be translated"));
+}
+
+#[test]
+fn test_malformed_and_runaway_programs_are_errors() {
+    assert!(flatten_program("o100 if [1]\nG0 X1\n", &FlattenOptions::default()).is_err());
+    assert!(flatten_program("G1 X#<undefined>\n", &FlattenOptions::default()).is_err());
+    let endless = "o1 while [1]\nG1 X1\no1 endwhile\n";
+    let options = FlattenOptions {
+        max_lines: 100,
+        ..FlattenOptions::default()
+    };
+    assert!(flatten_program(endless, &options).is_err());
+}
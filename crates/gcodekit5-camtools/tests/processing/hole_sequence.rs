@@ -0,0 +1,61 @@
+use gcodekit5_camtools::hole_sequence::resequence_holes;
+
+#[test]
+fn test_resequences_canned_cycle_holes() {
+    let program = "G90 G21\nG0 Z5\nG0 X0 Y0\nG99 G81 X50 Y0 Z-3 R2 F100\nX10 Y0\nX40 (third)\nX20\nG80\nG0 X0\n";
+    let report = resequence_holes(program);
+
+    assert_eq!(report.holes, 4);
+    assert_eq!(report.groups, 1);
+    assert!((report.travel_before - 140.0).abs() < 1e-9);
+    assert!((report.travel_after - 50.0).abs() < 1e-9);
+    assert_eq!(
+        report.program,
+        "G90 G21\nG0 Z5\nG0 X0 Y0\nG99 G81 Z-3 R2 F100 X10 Y0\nX20 Y0\nX40 Y0 (third)\nX50 Y0\nG80\nG0 X0 Y0\n"
+    );
+    assert_eq!(
+        report.summary(),
+        "4 hole(s) in 1 group(s) resequenced; travel 140.0 mm -> 50.0 mm"
+    );
+}
+
+#[test]
+fn test_keeps_expanded_holes_of_different_depths_apart() {
+    let program = "G21\nG0 Z5\nG0 X0 Y0\n\
+        G0 X30 Y0\nG1 Z-2 F100\nG0 Z5\n\
+        G0 X10 Y0\nG1 Z-2\nG0 Z5\n\
+        G0 X20 Y0\nG1 Z-2\nG0 Z5\n\
+        G0 X5 Y0\nG1 Z-4\nG0 Z5\n\
+        G0 X1 Y0\nG1 Z-4\nG0 Z5\nM30\n";
+    let report = resequence_holes(program);
+
+    assert_eq!(report.holes, 3);
+    assert_eq!(report.groups, 1);
+    assert!((report.travel_after - 30.0).abs() < 1e-9);
+    assert_eq!(
+        report.program,
+        "G21\nG0 Z5\nG0 X0 Y0\n\
+        G0 X10 Y0\nG1 Z-2 F100\nG0 Z5\n\
+        G0 X20 Y0\nG1 Z-2\nG0 Z5\n\
+        G0 X30 Y0\nG1 Z-2 F100\nG0 Z5\n\
+        G0 X5 Y0\nG1 Z-4\nG0 Z5\n\
+        G0 X1 Y0\nG1 Z-4\nG0 Z5\nM30\n"
+    );
+}
+
+#[test]
+fn test_leaves_other_programs_unchanged() {
+    let programs = [
+        // Profile cut, no holes
+        "G21\nG0 Z5\nG0 X0 Y0\nG1 Z-1 F100\nG1 X50\nG1 Y50\nG1 X0\nG1 Y0\nG0 Z5\n",
+        // Incremental moves
+        "G91\nG81 X50 Y0 Z-3 R2 F100\nX-40\nX30\nG80\n",
+        // Holes already in the shortest order
+        "G0 X0 Y0\nG81 X10 Y0 Z-3 R2 F100\nX20\nX30\nG80\n",
+    ];
+    for program in programs {
+        let report = resequence_holes(program);
+        assert_eq!(report.program, program);
+        assert!(report.travel_after <= report.travel_before);
+    }
+}
@@ -0,0 +1,121 @@
+use super::code;
+use gcodekit5_camtools::job_sequence::{JobSequence, JobStep, SequenceStage, ToolProbeConfig};
+use std::path::{Path, PathBuf};
+
+const ROUGH: &str = "G21 G90\nG0 Z5\nG1 Z-1 F100\nG1 X20 F500\nM30\n";
+const FINISH: &str = "G21 G90\nG0 Z5\nG1 Z-0.5 F100\nG1 Y20 F500\nM2\n";
+
+fn load(path: &Path) -> std::io::Result<String> {
+    match path.to_str() {
+        Some("rough.nc") => Ok(ROUGH.to_string()),
+        Some("finish.nc") => Ok(FINISH.to_string()),
+        _ => Err(std::io::Error::new(std::io::ErrorKind::NotFound, "missing")),
+    }
+}
+
+fn tool_change_sequence() -> JobSequence {
+    JobSequence::new("Two tools")
+        .with_probe(ToolProbeConfig {
+            x: -10.0,
+            y: -20.0,
+            setter_z: 12.5,
+            ..Default::default()
+        })
+        .with_step(JobStep::RunProgram {
+            path: PathBuf::from("rough.nc"),
+        })
+        .with_step(JobStep::Pause {
+            message: "Change to 3mm end mill".to_string(),
+        })
+        .with_step(JobStep::ProbeToolLength)
+        .with_step(JobStep::RunProgram {
+            path: PathBuf::from("finish.nc"),
+        })
+}
+
+#[test]
+fn test_flatten_pauses_probes_and_ends_once() {
+    let program = tool_change_sequence().flatten(load).unwrap();
+    let lines = code(&program);
+
+    assert_eq!(
+        lines.iter().filter(|l| **l == "M30" || **l == "M2").count(),
+        1
+    );
+    assert_eq!(lines.last(), Some(&"M30"));
+
+    let pause = lines.iter().position(|l| *l == "M0").unwrap();
+    let probe = lines
+        .iter()
+        .position(|l| l.starts_with("G91 G38.2"))
+        .unwrap();
+    let rough = lines.iter().position(|l| *l == "G1 X20 F500").unwrap();
+    let finish = lines.iter().position(|l| *l == "G1 Y20 F500").unwrap();
+    assert!(rough < pause && pause < probe && probe < finish);
+
+    assert_eq!(lines[probe], "G91 G38.2 Z-50.000 F100");
+    assert_eq!(lines[probe + 1], "G90 G10 L20 P0 Z12.500");
+    assert!(lines.contains(&"G53 G0 X-10.000 Y-20.000"));
+    assert!(program.contains("M0 ; Change to 3mm end mill"));
+}
+
+#[test]
+fn test_stages_split_at_pauses() {
+    let stages = tool_change_sequence().stages(load).unwrap();
+    assert_eq!(stages.len(), 3);
+    assert_eq!(
+        stages[1],
+        SequenceStage::Pause {
+            message: "Change to 3mm end mill".to_string()
+        }
+    );
+    let SequenceStage::Stream { program, label } = &stages[2] else {
+        panic!("expected a stream after the pause");
+    };
+    assert!(label.starts_with("Probe tool length, Run finish.nc"));
+    assert!(code(program).contains(&"G1 Y20 F500"));
+    assert_eq!(code(program).last(), Some(&"M30"));
+    assert!(!code(program).contains(&"M0"));
+}
+
+#[test]
+fn test_validation_names_the_bad_step() {
+    let no_probe = JobSequence::new("No probe")
+        .with_step(JobStep::Dwell { seconds: 1.0 })
+        .with_step(JobStep::ProbeToolLength);
+    let error = no_probe.validate().unwrap_err().to_string();
+    assert!(
+        error.contains("step 2") && error.contains("probe config"),
+        "{}",
+        error
+    );
+
+    let bad_wcs = JobSequence::new("Bad").with_step(JobStep::SetWcs { wcs: 60 });
+    assert!(bad_wcs.validate().is_err());
+    assert!(JobSequence::new("Empty").validate().is_err());
+
+    let missing = JobSequence::new("Missing").with_step(JobStep::RunProgram {
+        path: PathBuf::from("nope.nc"),
+    });
+    assert!(missing
+        .flatten(load)
+        .unwrap_err()
+        .to_string()
+        .contains("nope.nc"));
+}
+
+#[test]
+fn test_sequence_round_trips_through_json() {
+    let sequence = tool_change_sequence()
+        .with_step(JobStep::SetWcs { wcs: 55 })
+        .with_step(JobStep::Dwell { seconds: 2.5 });
+    let path = std::env::temp_dir().join(format!("job_sequence_{}.json", std::process::id()));
+    sequence.save(&path).unwrap();
+    let loaded = JobSequence::load(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+    assert_eq!(loaded, sequence);
+
+    let program = loaded.flatten(load).unwrap();
+    assert!(code(&program).contains(&"G55"));
+    assert!(code(&program).contains(&"G4 P2.500"));
+}
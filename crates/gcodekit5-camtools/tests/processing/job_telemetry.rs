@@ -0,0 +1,97 @@
+//! Tests for processing::job_telemetry
+
+use chrono::Utc;
+use gcodekit5_camtools::job_telemetry::*;
+use std::time::{Duration, Instant};
+
+fn record(machine: &str, estimated: f64, actual: f64) -> JobTimingRecord {
+    JobTimingRecord {
+        machine: machine.to_string(),
+        estimated_secs: estimated,
+        actual_secs: actual,
+        lines: 100,
+        finished_at: Utc::now(),
+    }
+}
+
+#[test]
+fn test_timer_measures_duration_and_lines() {
+    let start = Instant::now();
+    let mut timer = JobTimer::start_at("router", 50.0, start);
+    for _ in 0..200 {
+        timer.on_line_sent();
+    }
+    assert_eq!(timer.lines_sent(), 200);
+
+    let record = timer.complete_at(start + Duration::from_secs(100));
+    assert_eq!(record.machine, "router");
+    assert_eq!(record.lines, 200);
+    assert!((record.actual_secs - 100.0).abs() < 1e-9);
+    assert_eq!(record.ratio(), Some(2.0));
+    assert!((record.lines_per_sec() - 2.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_ratio_requires_positive_times() {
+    assert_eq!(record("router", 0.0, 10.0).ratio(), None);
+    assert_eq!(record("router", 10.0, 0.0).ratio(), None);
+    assert_eq!(record("router", 10.0, 0.0).lines_per_sec(), 0.0);
+}
+
+#[test]
+fn test_correction_factor_is_median_per_machine() {
+    let mut log = JobTimingLog::new();
+    assert_eq!(log.correction_factor("router"), None);
+
+    log.record(record("router", 10.0, 12.0));
+    log.record(record("router", 10.0, 30.0));
+    log.record(record("router", 10.0, 15.0));
+    log.record(record("laser", 10.0, 10.0));
+
+    assert_eq!(log.correction_factor("router"), Some(1.5));
+    assert_eq!(log.correction_factor("laser"), Some(1.0));
+    assert!((log.corrected_estimate("router", 100.0) - 150.0).abs() < 1e-9);
+    assert_eq!(log.corrected_estimate("unknown", 100.0), 100.0);
+}
+
+#[test]
+fn test_correction_factor_ignores_outliers_and_old_jobs() {
+    let mut log = JobTimingLog::new();
+    for _ in 0..10 {
+        log.record(record("router", 10.0, 40.0));
+    }
+    for _ in 0..10 {
+        log.record(record("router", 10.0, 20.0));
+    }
+    // Out-of-range ratios are not counted towards the window
+    log.record(record("router", 10.0, 500.0));
+    log.record(record("router", 10.0, 0.5));
+
+    assert_eq!(log.correction_factor("router"), Some(2.0));
+}
+
+#[test]
+fn test_log_is_capped() {
+    let mut log = JobTimingLog::new();
+    for i in 0..250 {
+        log.record(record("router", 10.0, 10.0 + i as f64));
+    }
+    assert_eq!(log.records.len(), 200);
+    assert_eq!(log.records[0].actual_secs, 60.0);
+}
+
+#[test]
+fn test_log_save_and_load() {
+    let dir = std::env::temp_dir().join(format!("gcodekit_job_timing_{}", std::process::id()));
+    let path = dir.join("job_timing.json");
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(JobTimingLog::load(&path).unwrap(), JobTimingLog::new());
+
+    let mut log = JobTimingLog::new();
+    log.record(record("router", 10.0, 12.0));
+    log.save(&path).unwrap();
+    assert_eq!(JobTimingLog::load(&path).unwrap(), log);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,94 @@
+use gcodekit5_camtools::load_check::{check_loaded_program, convert_units, UnitsSource};
+use gcodekit5_core::units::MeasurementSystem;
+use gcodekit5_core::Units;
+use gcodekit5_devicedb::{AxisLimits, DeviceProfile};
+
+fn profile() -> DeviceProfile {
+    DeviceProfile {
+        x_axis: AxisLimits {
+            min: 0.0,
+            max: 300.0,
+            enabled: true,
+        },
+        y_axis: AxisLimits {
+            min: 0.0,
+            max: 200.0,
+            enabled: true,
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_program_fits_or_reports_how_much_too_big() {
+    let fits = "G21 G90\nG0 X0 Y0\nG1 X120 Y80 F500\n";
+    let check = check_loaded_program(fits, Some(&profile()), MeasurementSystem::Metric);
+    assert!(check.is_ok());
+    assert_eq!(check.summary(), "Fits: 120.000 x 80.000 mm");
+
+    let too_big = "G21\nG0 X-10 Y0\nG1 X310 Y205 F500\n";
+    let check = check_loaded_program(too_big, Some(&profile()), MeasurementSystem::Metric);
+    assert!(!check.fits());
+    assert_eq!(check.overflow, [20.0, 5.0]);
+    assert_eq!(check.summary(), "Too big by X 20.000 mm, Y 5.000 mm");
+
+    // No profile: nothing to fit against
+    assert!(check_loaded_program(too_big, None, MeasurementSystem::Metric).fits());
+}
+
+#[test]
+fn test_inch_files_are_flagged_declared_or_guessed() {
+    let declared = "G20 G90\nG0 X0 Y0\nG1 X10 Y5 F20\n";
+    let check = check_loaded_program(declared, Some(&profile()), MeasurementSystem::Metric);
+    assert_eq!(
+        (check.units, check.units_source),
+        (Units::INCH, UnitsSource::Declared)
+    );
+    assert!(check.fits());
+    assert_eq!(
+        check.summary(),
+        "Fits: 254.000 x 127.000 mm; inch file (G20) in mm mode"
+    );
+    // Matching units are not a warning
+    assert!(check_loaded_program(declared, Some(&profile()), MeasurementSystem::Imperial).is_ok());
+
+    let unmarked = "G90\nG0 X0 Y0\nG1 X12.5 Y4 F20\n";
+    let check = check_loaded_program(unmarked, Some(&profile()), MeasurementSystem::Metric);
+    assert_eq!(
+        (check.units, check.units_source),
+        (Units::INCH, UnitsSource::Guessed)
+    );
+    assert!(!check.fits());
+    assert!(check
+        .summary()
+        .ends_with("inch file (no G20/G21, guessed from coordinates) in mm mode"));
+
+    let check = check_loaded_program(
+        "G0 X0 Y0\nG1 X150 Y90\n",
+        Some(&profile()),
+        MeasurementSystem::Metric,
+    );
+    assert_eq!(
+        (check.units, check.units_source),
+        (Units::MM, UnitsSource::Guessed)
+    );
+    assert!(check.is_ok());
+}
+
+#[test]
+fn test_convert_units_rescales_lengths_and_feeds() {
+    let report = convert_units(
+        "G20 G90\nG1 X1 Y0.5 F10 (cut)\nG2 X2 Y0.5 I0.5 J0\nG4 P1.5\nM30\n",
+        Units::INCH,
+        Units::MM,
+    );
+    assert_eq!(
+        report.program,
+        "G21 G90\nG1 X25.4 Y12.7 F254 (cut)\nG2 X50.8 Y12.7 I12.7 J0\nG4 P1.5\nM30\n"
+    );
+    assert_eq!(report.lines_converted, 3);
+
+    // A program without units words is converted from the assumed units
+    let report = convert_units("G0 X25.4\nG93 G1 X50.8 F2\n", Units::MM, Units::INCH);
+    assert_eq!(report.program, "G20\nG0 X1\nG93 G1 X2 F2\n");
+}
@@ -1,8 +1,39 @@
 pub mod advanced_features;
+pub mod arc_expander;
+pub mod biarc;
+pub mod clearance_plane;
 pub mod comment_processor;
+pub mod distance_mode;
+pub mod dry_run;
+pub mod feed_mode;
+pub mod flatten;
+pub mod hole_sequence;
+pub mod job_sequence;
+pub mod job_telemetry;
+pub mod load_check;
+pub mod neutral_toolpath;
+pub mod origin_placement;
+pub mod preflight;
+pub mod program_merge;
+pub mod quick_check;
+pub mod rotation;
+pub mod safe_rapids;
 pub mod speeds_feeds;
+pub mod spindle_clamp;
+pub mod spindle_continuity;
 pub mod stats;
 pub mod tabbed_box;
 pub mod tabbed_box_debug;
 pub mod tabbed_box_user_bug;
+pub mod tiling;
+pub mod time_estimator;
 pub mod validator;
+
+/// The code on each line of `program`, without comments or blank lines
+pub fn code(program: &str) -> Vec<&str> {
+    program
+        .lines()
+        .map(|l| l.split(';').next().unwrap_or("").trim())
+        .filter(|l| !l.is_empty())
+        .collect()
+}
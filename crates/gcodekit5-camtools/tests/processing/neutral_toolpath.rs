@@ -0,0 +1,108 @@
+use gcodekit5_camtools::neutral_toolpath::{
+    MoveKind, NeutralMove, NeutralToolpath, SpindleMode, WrittenUnits, NEUTRAL_VERSION,
+};
+use gcodekit5_core::ArcPlane;
+
+const PROGRAM: &str = "\
+G21 G90 G17
+G0 Z5
+G0 X10 Y0
+M3 S12000
+G1 Z-1 F300
+G2 X0 Y10 I-10 J0 F600
+G91 G1 X-5
+G90 G3 X10 Y0 R10
+G53 G0 Z0
+M5
+";
+
+fn close(a: [f64; 3], b: [f64; 3]) -> bool {
+    a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-3)
+}
+
+fn cuts(document: &NeutralToolpath) -> Vec<&NeutralMove> {
+    document
+        .moves
+        .iter()
+        .filter(|m| m.kind != MoveKind::Rapid)
+        .collect()
+}
+
+#[test]
+fn test_moves_carry_absolute_positions_and_context() {
+    let document = NeutralToolpath::from_gcode(PROGRAM);
+    assert_eq!(document.version, NEUTRAL_VERSION);
+    assert_eq!(document.skipped_lines, [8]);
+    assert_eq!(document.moves.len(), 6);
+
+    let plunge = &document.moves[2];
+    assert_eq!((plunge.line, plunge.kind), (4, MoveKind::Linear));
+    assert_eq!(plunge.end, [10.0, 0.0, -1.0]);
+    assert_eq!(plunge.feed_rate, Some(300.0));
+    assert_eq!(plunge.spindle.mode, SpindleMode::Cw);
+    assert_eq!(plunge.spindle.speed, 12000.0);
+    assert_eq!(document.moves[0].feed_rate, None);
+
+    let arc = &document.moves[3];
+    assert_eq!(arc.kind, MoveKind::ArcCw);
+    assert!(close(arc.center.unwrap(), [0.0, 0.0, -1.0]));
+    assert_eq!(arc.feed_rate, Some(600.0));
+
+    let incremental = &document.moves[4];
+    assert!(incremental.modal.incremental);
+    assert!(close(incremental.end, [-5.0, 10.0, -1.0]));
+
+    let radius_arc = &document.moves[5];
+    assert_eq!(radius_arc.kind, MoveKind::ArcCcw);
+    assert!(!radius_arc.modal.incremental);
+    assert!(radius_arc.center.is_some());
+}
+
+#[test]
+fn test_inches_are_stored_in_millimetres() {
+    let document = NeutralToolpath::from_gcode("G20 G90\nG18\nG1 X1 Z-0.5 F10\n");
+    let m = &document.moves[0];
+    assert!(close(m.end, [25.4, 0.0, -12.7]));
+    assert_eq!(m.feed_rate, Some(254.0));
+    assert_eq!(m.modal.units, WrittenUnits::Inch);
+    assert_eq!(m.modal.plane, ArcPlane::ZX);
+}
+
+#[test]
+fn test_json_round_trip_and_version_check() {
+    let document = NeutralToolpath::from_gcode(PROGRAM);
+    let json = document.to_json().unwrap();
+    assert!(json.contains("\"kind\": \"arc_cw\""));
+    assert_eq!(NeutralToolpath::from_json(&json).unwrap(), document);
+
+    let newer = json.replacen(
+        &format!("\"version\": {}", NEUTRAL_VERSION),
+        &format!("\"version\": {}", NEUTRAL_VERSION + 1),
+        1,
+    );
+    assert!(NeutralToolpath::from_json(&newer).is_err());
+    let other = json.replacen("gcodekit5.toolpath", "something.else", 1);
+    assert!(NeutralToolpath::from_json(&other).is_err());
+}
+
+#[test]
+fn test_to_gcode_reproduces_the_cuts() {
+    let document = NeutralToolpath::from_gcode(PROGRAM);
+    let gcode = document.to_gcode();
+    assert!(gcode.contains("M3 S12000"));
+    assert!(gcode.trim_end().ends_with("M30"));
+
+    let again = NeutralToolpath::from_gcode(&gcode);
+    let (before, after) = (cuts(&document), cuts(&again));
+    // The regenerated program feeds down from its travel height to the first cut
+    let after = &after[after.len() - before.len()..];
+    for (a, b) in before.iter().zip(after) {
+        assert_eq!(a.kind, b.kind);
+        assert!(close(a.start, b.start) && close(a.end, b.end));
+        assert_eq!(a.center.is_some(), b.center.is_some());
+        if let (Some(ca), Some(cb)) = (a.center, b.center) {
+            assert!(close(ca, cb));
+        }
+        assert_eq!(a.feed_rate, b.feed_rate);
+    }
+}
@@ -0,0 +1,58 @@
+use gcodekit5_camtools::origin_placement::{
+    place_origin, translate_program, OriginReference, StockBounds,
+};
+
+fn stock() -> StockBounds {
+    StockBounds::new(10.0, 20.0, 110.0, 70.0)
+}
+
+#[test]
+fn test_reference_points() {
+    let stock = stock();
+    assert_eq!(OriginReference::BottomLeft.point(&stock), (10.0, 20.0));
+    assert_eq!(OriginReference::BottomRight.point(&stock), (110.0, 20.0));
+    assert_eq!(OriginReference::TopLeft.point(&stock), (10.0, 70.0));
+    assert_eq!(OriginReference::TopRight.point(&stock), (110.0, 70.0));
+    assert_eq!(OriginReference::Center.point(&stock), (60.0, 45.0));
+}
+
+#[test]
+fn test_shifts_absolute_positions_and_keeps_arc_offsets() {
+    let program =
+        "G21 G90\nG0 X10 Y20 Z5\nG1 Z-1 F200\nG2 X30 Y20 I10 J0 ; arc\nG1 X60.5 Y45\nM30\n";
+    let shifted = place_origin(program, &stock(), OriginReference::Center).unwrap();
+
+    assert_eq!(
+        shifted,
+        "G21 G90\nG0 X-50 Y-25 Z5\nG1 Z-1 F200\nG2 X-30 Y-25 I10 J0 ; arc\nG1 X0.5 Y0\nM30\n"
+    );
+}
+
+#[test]
+fn test_relative_moves_and_other_frames_are_unchanged() {
+    let program = "G90 G0 X10 Y20\nG91\nG1 X5 Y5 F100\nG90\nG53 G0 X0 Y0\nG92 X0 Y0\nG0 X10 Y20\n";
+    let shifted = place_origin(program, &stock(), OriginReference::BottomLeft).unwrap();
+    let lines: Vec<&str> = shifted.lines().collect();
+
+    assert_eq!(lines[0], "G90 G0 X0 Y0");
+    assert_eq!(lines[2], "G1 X5 Y5 F100");
+    assert_eq!(lines[4], "G53 G0 X0 Y0");
+    assert_eq!(lines[5], "G92 X0 Y0");
+    assert_eq!(lines[6], "G0 X0 Y0");
+}
+
+#[test]
+fn test_absolute_arc_centers_and_inches() {
+    let program = "G90.1\nG90 G2 X20 Y0 I10 J0\nG20\nG0 X1 Y1\n";
+    let shifted = translate_program(program, -10.0, 25.4);
+    let lines: Vec<&str> = shifted.lines().collect();
+
+    assert_eq!(lines[1], "G90 G2 X10 Y25.4 I0 J25.4");
+    assert_eq!(lines[3], "G0 X0.6063 Y2");
+}
+
+#[test]
+fn test_invalid_stock_is_rejected() {
+    let stock = StockBounds::new(0.0, 0.0, f64::INFINITY, 10.0);
+    assert!(place_origin("G0 X0\n", &stock, OriginReference::Center).is_err());
+}
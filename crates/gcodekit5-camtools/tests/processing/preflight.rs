@@ -0,0 +1,144 @@
+use gcodekit5_camtools::preflight::{
+    has_failures, preflight, PreflightCheck, PreflightConfig, PreflightSeverity,
+};
+use gcodekit5_devicedb::{AxisLimits, DeviceProfile};
+
+fn profile() -> DeviceProfile {
+    DeviceProfile {
+        x_axis: AxisLimits {
+            min: 0.0,
+            max: 300.0,
+            enabled: true,
+        },
+        y_axis: AxisLimits {
+            min: 0.0,
+            max: 200.0,
+            enabled: true,
+        },
+        z_axis: AxisLimits {
+            min: -50.0,
+            max: 0.0,
+            enabled: true,
+        },
+        ..Default::default()
+    }
+}
+
+fn checks(warnings: &[gcodekit5_camtools::PreflightWarning]) -> Vec<PreflightCheck> {
+    warnings.iter().map(|w| w.check).collect()
+}
+
+#[test]
+fn test_complete_program_only_asks_for_safety_confirmation() {
+    let program = "G21 G90\nM3 S10000\nG0 X10 Y10\nG1 Z-1 F200\nG1 X50\nM5\nM30\n";
+    let warnings = preflight(program, Some(&profile()), &PreflightConfig::default());
+
+    assert_eq!(checks(&warnings), vec![PreflightCheck::Safety]);
+    assert_eq!(warnings[0].line, Some(1));
+    assert!(!has_failures(&warnings));
+}
+
+#[test]
+fn test_missing_spindle_and_program_end_warn() {
+    let warnings = preflight("G0 X10\nG1 X20 F100\n", None, &PreflightConfig::default());
+    assert_eq!(
+        checks(&warnings),
+        vec![PreflightCheck::SpindleCommand, PreflightCheck::ProgramEnd]
+    );
+    assert!(warnings
+        .iter()
+        .all(|w| w.severity == PreflightSeverity::Warning));
+}
+
+#[test]
+fn test_out_of_bounds_is_a_failure() {
+    let program = "M3 S1000\nG0 X10 Y10\nG1 X350 F500\nG1 Z-1\nM30\n";
+    let warnings = preflight(program, Some(&profile()), &PreflightConfig::default());
+
+    assert!(has_failures(&warnings));
+    let failure = &warnings[0];
+    assert!(failure.is_failure());
+    assert_eq!(failure.check, PreflightCheck::SoftLimits);
+    assert_eq!(failure.line, Some(2));
+    assert!(failure.message.starts_with("X reaches 350.000"));
+}
+
+#[test]
+fn test_limits_account_for_work_offset_units_and_arcs() {
+    let config = PreflightConfig {
+        work_offset: [100.0, 100.0, -40.0],
+        ..Default::default()
+    };
+    // Within limits only once the offset is applied
+    let program = "M3\nG0 X-50 Y-50\nG1 Z-5 F100\nM30\n";
+    assert!(!has_failures(&preflight(
+        program,
+        Some(&profile()),
+        &config
+    )));
+    assert!(has_failures(&preflight(
+        program,
+        Some(&profile()),
+        &PreflightConfig::default()
+    )));
+
+    // 4 inches is 101.6mm, past the Y travel of 200 from a 100mm offset
+    let inches = "M3\nG20\nG0 X1 Y4\nM30\n";
+    let warnings = preflight(inches, Some(&profile()), &config);
+    assert_eq!(warnings.iter().filter(|w| w.is_failure()).count(), 1);
+    assert!(warnings[0].message.starts_with('Y'));
+
+    // The arc's endpoints are inside, but it bulges past X300 (machine) at its apex
+    let config = PreflightConfig {
+        work_offset: [100.0, 20.0, -40.0],
+        ..Default::default()
+    };
+    let arc = "M3\nG0 X150 Y40\nG3 X150 Y160 I0 J60\nM30\n";
+    let warnings = preflight(arc, Some(&profile()), &config);
+    let failures: Vec<_> = warnings.iter().filter(|w| w.is_failure()).collect();
+    assert_eq!(failures.len(), 1);
+    assert!(failures[0].message.starts_with('X'));
+    assert!(failures[0].message.contains("maximum"));
+    assert_eq!(failures[0].line, Some(2));
+}
+
+#[test]
+fn test_checks_can_be_disabled() {
+    let program = "G0 X999\n";
+    let config = PreflightConfig {
+        confirm_safety: false,
+        soft_limits: false,
+        spindle_command: false,
+        program_end: false,
+        ..Default::default()
+    };
+    assert!(preflight(program, Some(&profile()), &config).is_empty());
+
+    // Without a profile there is nothing to check limits against
+    let warnings = preflight(
+        program,
+        None,
+        &PreflightConfig {
+            spindle_command: false,
+            program_end: false,
+            ..Default::default()
+        },
+    );
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn test_laser_profile_wording() {
+    let laser = DeviceProfile {
+        has_laser: true,
+        has_spindle: false,
+        ..profile()
+    };
+    let warnings = preflight(
+        "G1 X10 F100\nM2\n",
+        Some(&laser),
+        &PreflightConfig::default(),
+    );
+    assert_eq!(checks(&warnings), vec![PreflightCheck::SpindleCommand]);
+    assert!(warnings[0].message.contains("laser"));
+}
@@ -0,0 +1,97 @@
+use super::code;
+use gcodekit5_camtools::program_merge::{merge_programs, MergeOptions, MergeSource};
+use gcodekit5_camtools::CamToolError;
+
+const DRILL: &str = "%\n; Drill holes\nG21 ; mm\nG90\nG0 Z5\nM3 S10000\nG0 X10 Y10\nG1 Z-3 F100\nG0 Z5\nM5\nM30\n%\n";
+const SURFACE: &str =
+    "; Surfacing\nG21\nG90 G17\nG91\nG0 Z5\nM3 S18000\nG1 X100 F1500\nM5\nM2 ; end\n";
+
+fn sources() -> Vec<MergeSource> {
+    vec![
+        MergeSource::new("drill.nc", DRILL),
+        MergeSource::new("surface.nc", SURFACE),
+    ]
+}
+
+#[test]
+fn test_merge_ends_once() {
+    let merged = merge_programs(&sources(), &MergeOptions::default()).unwrap();
+    let code = code(&merged);
+
+    assert_eq!(code.iter().filter(|l| l.contains("M30")).count(), 1);
+    assert!(!code.iter().any(|l| l.contains("M2")));
+    assert_eq!(code.last(), Some(&"M30"));
+    assert!(!merged.lines().any(|l| l.trim() == "%"));
+    assert!(merged.contains("; Program end removed by merge: M2 ; end"));
+}
+
+#[test]
+fn test_merge_transition_resets_modal_state() {
+    let options = MergeOptions {
+        clearance_z: 12.5,
+        pause_between: true,
+        ..Default::default()
+    };
+    let merged = merge_programs(&sources(), &options).unwrap();
+    let transition = merged
+        .split("; ===== Transition: drill.nc -> surface.nc =====")
+        .nth(1)
+        .and_then(|rest| rest.split("; ===== Begin program 2/2").next())
+        .unwrap();
+    let code = code(transition);
+
+    assert_eq!(
+        code,
+        vec![
+            "M5",
+            "M9",
+            "G17 G21 G40 G80 G90 G94",
+            "G54",
+            "G0 Z12.500",
+            "M0"
+        ]
+    );
+}
+
+#[test]
+fn test_merge_drops_redundant_preamble_only() {
+    let merged = merge_programs(&sources(), &MergeOptions::default()).unwrap();
+    let surface = merged
+        .split("; ===== Begin program 2/2: surface.nc =====")
+        .nth(1)
+        .unwrap();
+    let code = code(surface);
+
+    // G21 and G90 G17 repeat the baseline; G91 changes state and must stay
+    assert_eq!(code[0], "G91");
+    assert!(!merged.contains("G21 ; mm"));
+    assert!(surface.contains("; Surfacing"));
+}
+
+#[test]
+fn test_merge_keeps_program_boundaries_identifiable() {
+    let options = MergeOptions {
+        label_comments: true,
+        ..Default::default()
+    };
+    let merged = merge_programs(&sources(), &options).unwrap();
+
+    assert!(merged.contains("; ===== Begin program 1/2: drill.nc ====="));
+    assert!(merged.contains("; ===== End program 2: surface.nc ====="));
+    assert!(merged.contains("; [drill.nc] Drill holes"));
+    assert!(merged.contains("; [surface.nc] Surfacing"));
+    assert!(!merged.contains("M0"));
+}
+
+#[test]
+fn test_merge_rejects_invalid_input() {
+    assert!(matches!(
+        merge_programs(&[], &MergeOptions::default()),
+        Err(CamToolError::InvalidParameters(_))
+    ));
+    let options = MergeOptions {
+        work_coordinate_system: Some(60),
+        ..Default::default()
+    };
+    assert!(merge_programs(&sources(), &options).is_err());
+}
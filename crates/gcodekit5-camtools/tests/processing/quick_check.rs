@@ -0,0 +1,92 @@
+use gcodekit5_camtools::quick_check::{first_problem, QuickCheckConfig, QuickCheckKind};
+use gcodekit5_devicedb::{AxisLimits, DeviceProfile};
+
+fn profile() -> DeviceProfile {
+    DeviceProfile {
+        x_axis: AxisLimits {
+            min: 0.0,
+            max: 300.0,
+            enabled: true,
+        },
+        y_axis: AxisLimits {
+            min: 0.0,
+            max: 200.0,
+            enabled: true,
+        },
+        z_axis: AxisLimits {
+            min: -50.0,
+            max: 10.0,
+            enabled: true,
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_clean_program_has_no_problem() {
+    let program = "G21 G90\nG0 Z5\nG0 X10 Y10\nG1 Z-1 F200\nG2 X30 Y10 I10 J0\nG0 Z5\nM30\n";
+    assert_eq!(
+        first_problem(program, Some(&profile()), &QuickCheckConfig::default()),
+        None
+    );
+}
+
+#[test]
+fn test_earliest_problem_wins() {
+    // Low rapid on line 3, bad arc on line 4, out of bounds on line 5
+    let program = "G90\nG0 Z5\nG1 Z-1 F100\nG0 X20\nG2 X10 Y0 R2\nG1 X400\n";
+    let config = QuickCheckConfig::default();
+    let issue = first_problem(program, Some(&profile()), &config).unwrap();
+    assert_eq!(issue.line, 3);
+    assert_eq!(issue.kind, QuickCheckKind::RapidBelowClearance);
+
+    let config = QuickCheckConfig {
+        rapid_below_clearance: false,
+        ..config
+    };
+    let issue = first_problem(program, Some(&profile()), &config).unwrap();
+    assert_eq!((issue.line, issue.kind), (4, QuickCheckKind::ArcRadius));
+
+    let config = QuickCheckConfig {
+        arc_radius: false,
+        ..config
+    };
+    let issue = first_problem(program, Some(&profile()), &config).unwrap();
+    assert_eq!((issue.line, issue.kind), (5, QuickCheckKind::OutOfBounds));
+    assert!(issue.reason.contains("X reaches 400.000"));
+
+    // Travel is not checked without a profile
+    assert_eq!(first_problem(program, None, &config), None);
+}
+
+#[test]
+fn test_arc_bulge_and_offset_errors_are_found() {
+    // A semicircle from X10 to X30 bulges to Y-10, below the Y travel
+    let program = "G0 Z5\nG0 X10 Y5\nG3 X30 Y5 I10 J0\n";
+    let config = QuickCheckConfig::default();
+    let issue = first_problem(program, Some(&profile()), &config).unwrap();
+    assert_eq!((issue.line, issue.kind), (2, QuickCheckKind::OutOfBounds));
+
+    // A full circle given by offsets alone is checked too
+    let program = "G0 Z5\nG0 X5 Y50\nG2 I-10 J0\n";
+    let issue = first_problem(program, Some(&profile()), &config).unwrap();
+    assert_eq!((issue.line, issue.kind), (2, QuickCheckKind::OutOfBounds));
+
+    // End point 1mm off the circle through the start
+    let program = "G0 Z5\nG0 X10 Y50\nG3 X31 Y50 I10 J0\n";
+    let issue = first_problem(program, None, &config).unwrap();
+    assert_eq!((issue.line, issue.kind), (2, QuickCheckKind::ArcRadius));
+}
+
+#[test]
+fn test_validator_errors_and_skipped_blocks() {
+    let program = "G0 Z5\n/G1 X10 F0\nG1 X10 F-5\n";
+    let issue = first_problem(program, None, &QuickCheckConfig::default()).unwrap();
+    assert_eq!((issue.line, issue.kind), (2, QuickCheckKind::Validator));
+
+    let config = QuickCheckConfig {
+        block_delete: false,
+        ..Default::default()
+    };
+    assert_eq!(first_problem(program, None, &config).unwrap().line, 1);
+}
@@ -0,0 +1,67 @@
+use gcodekit5_camtools::rotation::{rotate_program, skew_from_probes, ProbedEdge};
+
+#[test]
+fn test_skew_from_probes_along_x_and_y() {
+    let angle: f64 = 1.5_f64.to_radians();
+    let along_x = skew_from_probes(
+        (10.0, 5.0),
+        (10.0 + 100.0 * angle.cos(), 5.0 + 100.0 * angle.sin()),
+        ProbedEdge::AlongX,
+    )
+    .unwrap();
+    assert!((along_x.angle_degrees - 1.5).abs() < 1e-9);
+    assert_eq!(along_x.pivot, (10.0, 5.0));
+
+    // Probing back along the edge gives the same skew
+    let backwards = skew_from_probes(
+        (100.0 * angle.cos(), 100.0 * angle.sin()),
+        (0.0, 0.0),
+        ProbedEdge::AlongX,
+    )
+    .unwrap();
+    assert!((backwards.angle_degrees - 1.5).abs() < 1e-9);
+
+    let along_y = skew_from_probes(
+        (0.0, 0.0),
+        (-50.0 * angle.sin(), 50.0 * angle.cos()),
+        ProbedEdge::AlongY,
+    )
+    .unwrap();
+    assert!((along_y.angle_degrees - 1.5).abs() < 1e-9);
+}
+
+#[test]
+fn test_skew_from_probes_rejects_bad_points() {
+    assert!(skew_from_probes((0.0, 0.0), (0.5, 0.0), ProbedEdge::AlongX).is_err());
+    // An edge along Y probed as if it ran along X
+    assert!(skew_from_probes((0.0, 0.0), (0.0, 50.0), ProbedEdge::AlongX).is_err());
+    assert!(skew_from_probes((0.0, f64::NAN), (50.0, 0.0), ProbedEdge::AlongX).is_err());
+}
+
+#[test]
+fn test_rotates_moves_and_arc_offsets() {
+    let program = "G21 G90\nG0 X10 Y0 Z5\nG1 Z-1 F200\nG1 X20 ; only X\nG2 X10 Y0 I-5 J0\nM30\n";
+    let rotated = rotate_program(program, (0.0, 0.0), 90.0).unwrap();
+    assert_eq!(
+        rotated,
+        "G21 G90\nG0 X0 Y10 Z5\nG1 Z-1 F200\nG1 X0 Y20 ; only X\nG2 X0 Y10 I0 J-5\nM30\n"
+    );
+
+    // About a pivot, and with incremental moves
+    let rotated = rotate_program("G0 X12 Y2\nG91\nG1 Y3\n", (2.0, 2.0), 90.0).unwrap();
+    assert_eq!(rotated, "G0 X2 Y12\nG91\nG1 X-3 Y0\n");
+
+    let corrected = skew_from_probes((0.0, 0.0), (100.0, 0.0), ProbedEdge::AlongX)
+        .unwrap()
+        .apply(program)
+        .unwrap();
+    assert_eq!(corrected.lines().nth(3), Some("G1 X20 Y0 ; only X"));
+}
+
+#[test]
+fn test_rotation_rejects_unknown_positions_and_other_planes() {
+    assert!(rotate_program("G0 X10\n", (0.0, 0.0), 2.0).is_err());
+    // Homing forgets the position again
+    assert!(rotate_program("G0 X1 Y1\nG28\nG0 X10\n", (0.0, 0.0), 2.0).is_err());
+    assert!(rotate_program("G0 X0 Y0 Z0\nG18\nG2 X10 Z0 I5 K0\n", (0.0, 0.0), 2.0).is_err());
+}
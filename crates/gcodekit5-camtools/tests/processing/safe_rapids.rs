@@ -0,0 +1,81 @@
+use super::code;
+use gcodekit5_camtools::safe_rapids::{convert_low_rapids, SafeRapidOptions};
+
+#[test]
+fn test_converts_horizontal_rapids_below_threshold() {
+    let program = "G21 G90\nG0 Z5\nG0 X10 Y10\nG1 Z-2 F100\nG0 X20 (inside the stock)\nG1 X30\nG0 Z5\nG0 X0 Y0\n";
+    let report = convert_low_rapids(program, &SafeRapidOptions::default()).unwrap();
+
+    assert_eq!(
+        code(&report.program),
+        vec![
+            "G21 G90",
+            "G0 Z5",
+            "G0 X10 Y10",
+            "G1 Z-2 F100",
+            "G1 X20 F500 (inside the stock)",
+            "G1 X30 F100",
+            "G0 Z5",
+            "G0 X0 Y0",
+        ]
+    );
+    assert_eq!(report.converted, 1);
+    assert_eq!(report.unresolved, 0);
+    assert!(report.summary().starts_with("1 rapid move(s) converted"));
+}
+
+#[test]
+fn test_rapid_ending_below_threshold_and_modal_rapids() {
+    // Rapids that dive into or climb out of the stock while moving sideways are
+    // converted; the modal G0 after them gets its motion word back
+    let program = "G90\nG0 Z5\nG0 X10 Z-1\nX20 Z5\nX30\n";
+    let report = convert_low_rapids(program, &SafeRapidOptions::default()).unwrap();
+
+    assert_eq!(
+        code(&report.program),
+        vec!["G90", "G0 Z5", "G1 X10 Z-1 F500", "G1 X20 Z5", "G0 X30"]
+    );
+    assert_eq!(report.converted, 2);
+}
+
+#[test]
+fn test_z_only_rapids_are_kept() {
+    let program = "G90\nG0 Z5\nG1 Z-3 F100\nG0 Z-1\nG0 Z5\n";
+    let report = convert_low_rapids(program, &SafeRapidOptions::default()).unwrap();
+    assert_eq!(report.program, program);
+    assert_eq!(report.converted, 0);
+}
+
+#[test]
+fn test_relative_moves_track_z() {
+    let program = "G90\nG0 Z2\nG91\nG1 Z-4 F100\nG0 X10\nG0 Z6\nG0 X10\n";
+    let report = convert_low_rapids(program, &SafeRapidOptions::default()).unwrap();
+    let lines = code(&report.program);
+
+    // At Z-2 the relative rapid is converted; after retracting to Z4 it is kept
+    assert_eq!(lines[4], "G1 X10 F500");
+    assert_eq!(lines[5], "G0 Z6");
+    assert_eq!(lines[6], "G0 X10");
+    assert_eq!(report.converted, 1);
+
+    let unknown = convert_low_rapids("G91\nG0 X10\n", &SafeRapidOptions::default()).unwrap();
+    assert_eq!(unknown.converted, 0);
+    assert_eq!(unknown.unresolved, 1);
+    assert!(unknown.summary().contains("unknown Z"));
+}
+
+#[test]
+fn test_inch_programs_and_invalid_options() {
+    let options = SafeRapidOptions {
+        threshold_z: 2.54,
+        feed_rate: 254.0,
+    };
+    let report = convert_low_rapids("G20 G90\nG0 Z0.05\nG0 X1\n", &options).unwrap();
+    assert_eq!(code(&report.program)[2], "G1 X1 F10");
+
+    let bad = SafeRapidOptions {
+        threshold_z: 0.0,
+        feed_rate: 0.0,
+    };
+    assert!(convert_low_rapids("G0 X1\n", &bad).is_err());
+}
@@ -1,4 +1,4 @@
-use gcodekit5_camtools::speeds_feeds::SpeedsFeedsCalculator;
+use gcodekit5_camtools::speeds_feeds::{ChipLoadStatus, SpeedsFeedsCalculator};
 use gcodekit5_core::data::materials::{Material, MaterialCategory, MaterialId};
 use gcodekit5_core::data::tools::{Tool, ToolId, ToolType};
 use gcodekit5_devicedb::model::DeviceProfile;
@@ -68,3 +68,65 @@ fn test_calculation_with_material_properties() {
 
     assert!(result.source.contains("Material Surface Speed"));
 }
+
+fn two_flute_tool() -> Tool {
+    let mut tool = Tool::new(
+        ToolId("fixed".to_string()),
+        1,
+        "Fixed RPM".to_string(),
+        ToolType::EndMillFlat,
+        6.0,
+        50.0,
+    );
+    tool.flutes = 2;
+    tool
+}
+
+#[test]
+fn test_chip_load_reconciliation_at_fixed_rpm() {
+    // At 20000 RPM with 2 flutes: F400 -> 0.01, F2000 -> 0.05, F8000 -> 0.2 mm/tooth
+    let program = "G21 G90\nM3 S12000\nG0 X0 Y0\nG1 Z-1 F400\nG1 X10 F2000\nG1 Y10\nG2 X20 Y0 I5 J-10 F8000\nG0 Z5\n";
+    let report =
+        SpeedsFeedsCalculator::reconcile_chip_load(program, 20000.0, &two_flute_tool(), 0.05)
+            .unwrap();
+
+    assert_eq!(report.feeds.len(), 3);
+    let statuses: Vec<_> = report.feeds.iter().map(|f| f.status).collect();
+    assert_eq!(
+        statuses,
+        vec![
+            ChipLoadStatus::Rubbing,
+            ChipLoadStatus::Ok,
+            ChipLoadStatus::Excessive
+        ]
+    );
+    let cut = &report.feeds[1];
+    assert!((cut.chip_load - 0.05).abs() < 1e-6);
+    assert_eq!(cut.move_count, 2);
+    assert_eq!(cut.first_line, 4);
+    assert!((report.recommended_feed_rate - 2000.0).abs() < 1e-3);
+    assert_eq!(report.ignored_spindle_speeds, vec![12000.0]);
+    assert!(report.has_problems());
+    assert_eq!(report.warnings.len(), 3);
+}
+
+#[test]
+fn test_chip_load_reconciliation_converts_inch_feeds() {
+    let program = "G20\nG1 X1 F78.74\n";
+    let report =
+        SpeedsFeedsCalculator::reconcile_chip_load(program, 20000.0, &two_flute_tool(), 0.05)
+            .unwrap();
+
+    assert_eq!(report.feeds.len(), 1);
+    assert!((report.feeds[0].feed_rate - 2000.0).abs() < 0.1);
+    assert!(!report.has_problems());
+    assert!(report.ignored_spindle_speeds.is_empty());
+}
+
+#[test]
+fn test_chip_load_reconciliation_rejects_bad_inputs() {
+    assert!(
+        SpeedsFeedsCalculator::reconcile_chip_load("G1 X1 F100", 0.0, &two_flute_tool(), 0.05)
+            .is_err()
+    );
+}
@@ -0,0 +1,51 @@
+use gcodekit5_camtools::spindle_clamp::{
+    clamp_spindle_speed, SpindleAdjustmentKind, SpindleClampMode, SpindleClampOptions,
+};
+
+#[test]
+fn test_clamps_speed_and_rescales_cutting_feeds() {
+    let program = "S24000 M3\nG0 X0 Y0 F3000\nG1 Z-1 F2000\nG1 X10\nG0 Z5\nS12000\nG1 X20\nM5\n";
+    let report = clamp_spindle_speed(program, &SpindleClampOptions::new(18000.0)).unwrap();
+
+    assert_eq!(
+        report.program,
+        "S18000 M3\nG0 X0 Y0 F3000\nG1 Z-1 F1500\nG1 X10\nG0 Z5\nS12000\nG1 X20 F2000\nM5\n"
+    );
+    assert_eq!(report.speeds_clamped(), 1);
+    assert_eq!(report.feeds_scaled(), 1);
+    assert_eq!(
+        report.adjustments[0].kind,
+        SpindleAdjustmentKind::SpeedClamped {
+            requested: 24000.0,
+            clamped: 18000.0
+        }
+    );
+    assert_eq!(
+        report.adjustments[1].message(),
+        "line 3: feed F2000 reduced to F1500 to keep the chip load"
+    );
+    assert_eq!(
+        report.summary(),
+        "1 spindle speed(s) clamped, 1 feed rate(s) reduced"
+    );
+}
+
+#[test]
+fn test_clamp_only_and_per_revolution_feeds_keep_their_feeds() {
+    let options = SpindleClampOptions {
+        max_rpm: 18000.0,
+        mode: SpindleClampMode::ClampOnly,
+    };
+    let report = clamp_spindle_speed("S24000 M3\nG1 X10 F2000\n", &options).unwrap();
+    assert_eq!(report.program, "S18000 M3\nG1 X10 F2000\n");
+    assert_eq!(report.feeds_scaled(), 0);
+
+    let report = clamp_spindle_speed(
+        "G95 S24000 M3\nG1 X10 F0.1\n",
+        &SpindleClampOptions::new(18000.0),
+    )
+    .unwrap();
+    assert_eq!(report.program, "G95 S18000 M3\nG1 X10 F0.1\n");
+
+    assert!(clamp_spindle_speed("S1000\n", &SpindleClampOptions::new(0.0)).is_err());
+}
@@ -0,0 +1,51 @@
+use gcodekit5_camtools::spindle_continuity::keep_spindle_running;
+
+#[test]
+fn test_removes_stop_between_operations_on_the_same_tool() {
+    let program = "T1 M6\nS12000 M3\nG1 Z-1 F300\nG1 X10\nG0 Z5\nM5\n(Second operation)\nG0 X20 Y0\nS12000 M3\nG4 P2\nG1 Z-1\nG0 Z5\nM5\nM30\n";
+    let report = keep_spindle_running(program);
+
+    assert_eq!(report.eliminated, 1);
+    assert_eq!(
+        report.program,
+        "T1 M6\nS12000 M3\nG1 Z-1 F300\nG1 X10\nG0 Z5\n(Second operation)\nG0 X20 Y0\nS12000\nG4 P2\nG1 Z-1\nG0 Z5\nM5\nM30\n"
+    );
+    assert_eq!(report.summary(), "1 spindle stop/start cycle(s) removed");
+}
+
+#[test]
+fn test_keeps_stops_for_tool_changes_pauses_and_new_speeds() {
+    let stopped = [
+        // Tool change
+        "S10000 M3\nG1 X10 F300\nM5\nT2 M6\nS10000 M3\nG1 X20\nM5\n",
+        // Manual intervention
+        "S10000 M3\nG1 X10 F300\nM5\nM0 (flip the part)\nS10000 M3\nG1 X20\nM5\n",
+        // Different speed or direction
+        "S10000 M3\nG1 X10 F300\nM5\nS8000 M3\nG1 X20\nM5\n",
+        "S10000 M3\nG1 X10 F300\nM5\nM4\nG1 X20\nM5\n",
+        // A feed move meant to run with the spindle off
+        "S10000 M3\nG1 X10 F300\nM5\nG1 X0\nM3\nG1 X20\nM5\n",
+    ];
+    for program in stopped {
+        let report = keep_spindle_running(program);
+        assert_eq!(report.eliminated, 0, "{}", program);
+        assert_eq!(report.program, program);
+    }
+
+    let kept = keep_spindle_running("N10 S10000 M3\nN20 G1 X10 F300\nN30 M5 M9\nN40 G28 G91 Z0\nN50 M3 M8\nN60 G1 X20\nN70 M5\n");
+    assert_eq!(kept.eliminated, 1);
+    assert_eq!(
+        kept.program,
+        "N10 S10000 M3\nN20 G1 X10 F300\nN30 M9\nN40 G28 G91 Z0\nN50 M8\nN60 G1 X20\nN70 M5\n"
+    );
+}
+
+#[test]
+fn test_keeps_stops_sharing_a_line_with_a_barrier() {
+    for barrier in ["M0", "M1", "M2", "M6 T2", "M30", "M60", "G1 X0 F300"] {
+        let program = format!("M3 S1000\nG1 X10 F300\nM5 {}\nM3 S1000\nG1 X20\n", barrier);
+        let report = keep_spindle_running(&program);
+        assert_eq!(report.eliminated, 0, "{}", barrier);
+        assert_eq!(report.program, program, "{}", barrier);
+    }
+}
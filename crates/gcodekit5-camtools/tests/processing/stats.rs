@@ -20,3 +20,108 @@ fn test_bounding_box() {
     assert_eq!(w, 100.0);
     assert_eq!(h, 50.0);
 }
+
+#[test]
+fn test_estimated_time_follows_feed_rate() {
+    let lines = vec!["G21 G90".to_string(), "G1 X100 F600".to_string()];
+    let stats = StatsCalculator::calculate(&lines);
+    assert!((stats.total_distance - 100.0).abs() < 1e-9);
+    assert!((stats.estimated_time_secs - 10.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_estimated_time_includes_rapids_and_dwell() {
+    let lines = vec![
+        "G0 X50".to_string(),
+        "G4 P2".to_string(),
+        "G91 G1 X-50 F3000".to_string(),
+    ];
+    let stats = StatsCalculator::calculate(&lines);
+    // 50 mm at the rapid rate, 2 s dwell, 50 mm at 3000 mm/min
+    let expected = 50.0 / ESTIMATE_RAPID_RATE * 60.0 + 2.0 + 1.0;
+    assert!((stats.estimated_time_secs - expected).abs() < 1e-9);
+}
+
+#[test]
+fn test_time_estimate_follows_feed_rate_mode() {
+    let lines: Vec<String> = [
+        // 100 mm at 600 mm/min: 10 s
+        "G94 G1 X100 F600",
+        // 0.2 mm/rev at 1500 RPM is 300 mm/min: 100 mm in 20 s
+        "G95 S1500 F0.2",
+        "G1 X200",
+        // Inverse time: F2 finishes the move in half a minute
+        "G93 G1 X300 F2",
+        // Back to G94, where the last F word (2) is taken as mm/min
+        "G94 G1 X302",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    let stats = StatsCalculator::calculate(&lines);
+    assert!((stats.estimated_time_secs - (10.0 + 20.0 + 30.0 + 60.0)).abs() < 1e-6);
+
+    // Feed per revolution is unknown under constant surface speed
+    let css: Vec<String> = ["G96 S200 G95 F0.2", "G1 X100"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let rapid = 100.0 / ESTIMATE_RAPID_RATE * 60.0;
+    assert!((StatsCalculator::calculate(&css).estimated_time_secs - rapid).abs() < 1e-6);
+}
+
+fn program(lines: &[&str]) -> Vec<String> {
+    lines.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_tool_usage_by_tool_number() {
+    let lines = program(&[
+        "T1 M6",
+        "G0 X0 Y0",
+        "G1 Z-1 F600",
+        "G1 X60",
+        "G0 Z5",
+        "G0 X100",
+        "G1 Z-1",
+        "T2 M6",
+        // Selected but never cut with
+        "T3 M6",
+        "T1",
+        "M6",
+        "G1 X130 F300",
+    ]);
+    let stats = StatsCalculator::calculate(&lines);
+    assert_eq!(stats.tool_changes, 4);
+    let tools: Vec<u32> = stats.tool_usage.iter().map(|u| u.tool).collect();
+    assert_eq!(tools, vec![1, 2, 3]);
+
+    let t1 = &stats.tool_usage[0];
+    assert!((t1.cut_distance - (1.0 + 60.0 + 6.0 + 30.0)).abs() < 1e-9);
+    assert!((t1.cut_time_secs - (67.0 / 600.0 * 60.0 + 6.0)).abs() < 1e-9);
+    assert_eq!(t1.operations, 3);
+    for idle in &stats.tool_usage[1..] {
+        assert_eq!(idle.cut_distance, 0.0);
+        assert_eq!(idle.operations, 0);
+    }
+}
+
+#[test]
+fn test_tool_usage_without_tool_words() {
+    let lines = program(&["G0 X10", "G1 X20 F600", "G0 X0", "G1 Y10"]);
+    let (usage, changes) = StatsCalculator::tool_usage(&lines);
+    assert_eq!(changes, 0);
+    assert_eq!(usage.len(), 1);
+    assert_eq!(usage[0].tool, 0);
+    assert!((usage[0].cut_distance - 20.0).abs() < 1e-9);
+    assert_eq!(usage[0].operations, 2);
+
+    // T alone changes tools when the program has no M6; work before the
+    // first change is under tool 0
+    let lines = program(&["G1 X5 F600", "T4", "G1 X10", "T4", "G1 X20"]);
+    let (usage, changes) = StatsCalculator::tool_usage(&lines);
+    assert_eq!(changes, 1);
+    assert_eq!(usage.iter().map(|u| u.tool).collect::<Vec<_>>(), vec![0, 4]);
+    assert!((usage[1].cut_distance - 15.0).abs() < 1e-9);
+    assert_eq!(usage[1].operations, 1);
+}
@@ -0,0 +1,110 @@
+use super::code;
+use gcodekit5_camtools::origin_placement::StockBounds;
+use gcodekit5_camtools::tiling::{program_extents, tile_extents, tile_program, TileOptions};
+
+const PART: &str = "G21 G90\nG0 Z5\nG0 X0 Y0\nG1 Z-1 F100\nG1 X20 F500\nG1 Y10\nG0 Z5\nM30\n";
+
+#[test]
+fn test_tiles_are_offset_in_serpentine_order() {
+    let options = TileOptions {
+        columns: 2,
+        rows: 2,
+        pitch_x: 30.0,
+        pitch_y: 15.0,
+        ..Default::default()
+    };
+    let tiled = tile_program(PART, &options).unwrap();
+    let lines = code(&tiled);
+
+    let starts: Vec<&str> = lines
+        .iter()
+        .copied()
+        .filter(|l| l.starts_with("G0 X"))
+        .collect();
+    assert_eq!(
+        starts,
+        vec!["G0 X0 Y0", "G0 X30 Y0", "G0 X30 Y15", "G0 X0 Y15"]
+    );
+    assert!(lines.contains(&"G1 X50 F500"));
+    assert!(lines.contains(&"G1 Y25"));
+
+    // One header per tile, one program end
+    assert!(tiled.starts_with("; Tiled program: 2 x 2 copies"));
+    assert!(tiled.contains("Begin program 3/4: Tile 3 (row 2, column 2) at X+30.000 Y+15.000"));
+    assert_eq!(lines.iter().filter(|l| l.contains("M30")).count(), 1);
+}
+
+#[test]
+fn test_tiles_start_from_a_reset_baseline_and_can_pause() {
+    let options = TileOptions {
+        columns: 3,
+        rows: 1,
+        pause_between: true,
+        ..Default::default()
+    };
+    let tiled = tile_program(PART, &options).unwrap();
+    let lines = code(&tiled);
+
+    assert_eq!(lines.iter().filter(|l| **l == "M0").count(), 2);
+    assert_eq!(
+        lines
+            .iter()
+            .filter(|l| **l == "G17 G21 G40 G80 G90 G94")
+            .count(),
+        3
+    );
+}
+
+#[test]
+fn test_extents_include_arcs_and_the_grid() {
+    // Half circle of radius 5 bulging below the X axis
+    let arc = "G90\nG0 X0 Y0\nG3 X10 Y0 I5 J0 F300\n";
+    let part = program_extents(arc).unwrap();
+    assert!((part.min_y + 5.0).abs() < 1e-9);
+    assert!((part.max_x - 10.0).abs() < 1e-9);
+
+    let options = TileOptions {
+        columns: 3,
+        rows: 2,
+        pitch_x: 12.0,
+        pitch_y: 8.0,
+        ..Default::default()
+    };
+    let extents = tile_extents(arc, &options).unwrap();
+    assert!((extents.max_x - 34.0).abs() < 1e-9);
+    assert!((extents.max_y - 8.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_work_area_and_invalid_grids_are_rejected() {
+    let options = TileOptions {
+        columns: 4,
+        rows: 1,
+        pitch_x: 30.0,
+        work_area: Some(StockBounds::new(0.0, 0.0, 100.0, 100.0)),
+        ..Default::default()
+    };
+    // 3 * 30 + 20 = 110 mm wide
+    let err = tile_program(PART, &options).unwrap_err();
+    assert!(err.to_string().contains("do not fit the work area"));
+
+    let fits = TileOptions {
+        columns: 3,
+        ..options.clone()
+    };
+    assert!(tile_program(PART, &fits).is_ok());
+
+    for bad in [
+        TileOptions {
+            columns: 0,
+            ..Default::default()
+        },
+        TileOptions {
+            pitch_y: -1.0,
+            ..Default::default()
+        },
+    ] {
+        assert!(tile_program(PART, &bad).is_err());
+    }
+    assert!(tile_program("G21\nM30\n", &TileOptions::default()).is_err());
+}
@@ -0,0 +1,138 @@
+//! Tests for processing::time_estimator
+
+use gcodekit5_camtools::time_estimator::*;
+
+fn program(lines: &[&str]) -> Vec<String> {
+    lines.iter().map(|l| l.to_string()).collect()
+}
+
+fn stop_at_every_point() -> MotionLimits {
+    MotionLimits {
+        lookahead: false,
+        ..MotionLimits::default()
+    }
+}
+
+/// A 60 mm polyline of 1 mm segments turning 1° at each point
+fn smooth_polyline() -> Vec<String> {
+    let mut lines = vec!["G1 F1200".to_string()];
+    let (mut x, mut y, mut angle) = (0.0f64, 0.0f64, 0.0f64);
+    for _ in 0..60 {
+        x += angle.cos();
+        y += angle.sin();
+        angle += 1f64.to_radians();
+        lines.push(format!("G1 X{:.4} Y{:.4}", x, y));
+    }
+    lines
+}
+
+/// A 60 mm zigzag of 1 mm segments with a 90° corner at each point
+fn square_corners() -> Vec<String> {
+    let mut lines = vec!["G1 F1200".to_string()];
+    let (mut x, mut y) = (0.0, 0.0);
+    for i in 0..60 {
+        if i % 2 == 0 {
+            x += 1.0;
+        } else {
+            y += 1.0;
+        }
+        lines.push(format!("G1 X{} Y{}", x, y));
+    }
+    lines
+}
+
+#[test]
+fn test_single_move_trapezoid() {
+    let limits = MotionLimits {
+        acceleration: 100.0,
+        ..MotionLimits::default()
+    };
+    // 20 mm/s cruise: 2 mm to accelerate, 2 mm to stop, 96 mm at speed
+    let estimate = estimate_time(&program(&["G1 X100 F1200"]), &limits).unwrap();
+    assert!((estimate.seconds - (0.2 + 0.2 + 96.0 / 20.0)).abs() < 1e-9);
+    assert!((estimate.distance - 100.0).abs() < 1e-9);
+    assert_eq!(estimate.blocks, 1);
+}
+
+#[test]
+fn test_short_move_is_triangular() {
+    let limits = MotionLimits {
+        acceleration: 100.0,
+        ..MotionLimits::default()
+    };
+    // Peak speed sqrt(100 * 1) = 10 mm/s, below the 20 mm/s feed
+    let estimate = estimate_time(&program(&["G1 X1 F1200"]), &limits).unwrap();
+    assert!((estimate.seconds - 0.2).abs() < 1e-9);
+}
+
+#[test]
+fn test_straight_continuation_keeps_speed() {
+    let limits = MotionLimits {
+        acceleration: 100.0,
+        ..MotionLimits::default()
+    };
+    let split = estimate_time(&program(&["G1 X50 F1200", "X100"]), &limits).unwrap();
+    let single = estimate_time(&program(&["G1 X100 F1200"]), &limits).unwrap();
+    assert!((split.seconds - single.seconds).abs() < 1e-9);
+}
+
+#[test]
+fn test_smooth_polyline_is_faster_with_lookahead() {
+    let lines = smooth_polyline();
+    let lookahead = estimate_time(&lines, &MotionLimits::default()).unwrap();
+    let stopping = estimate_time(&lines, &stop_at_every_point()).unwrap();
+    assert!(lookahead.seconds < stopping.seconds * 0.5);
+}
+
+#[test]
+fn test_square_corners_take_longer_than_smooth_path() {
+    let limits = MotionLimits::default();
+    let smooth = estimate_time(&smooth_polyline(), &limits).unwrap();
+    let corners = estimate_time(&square_corners(), &limits).unwrap();
+    assert!((smooth.distance - corners.distance).abs() < 1e-3);
+    assert!(corners.seconds > smooth.seconds);
+    // Both are slower than running the whole path at the programmed feed
+    assert!(smooth.seconds > 60.0 / 20.0);
+    assert!(corners.seconds > 60.0 / 20.0);
+}
+
+#[test]
+fn test_junction_deviation_speeds_up_corners() {
+    let tight = MotionLimits {
+        junction_deviation: 0.001,
+        ..MotionLimits::default()
+    };
+    let loose = MotionLimits {
+        junction_deviation: 0.1,
+        ..MotionLimits::default()
+    };
+    let lines = square_corners();
+    let tight = estimate_time(&lines, &tight).unwrap();
+    let loose = estimate_time(&lines, &loose).unwrap();
+    assert!(loose.seconds < tight.seconds);
+}
+
+#[test]
+fn test_arcs_are_split_into_chords() {
+    let lines = program(&["G0 X10", "G3 X-10 Y0 I-10 J0 F600"]);
+    let estimate = estimate_time(&lines, &MotionLimits::default()).unwrap();
+    assert!(estimate.blocks > 10);
+    assert!((estimate.distance - (10.0 + std::f64::consts::PI * 10.0)).abs() < 1e-6);
+}
+
+#[test]
+fn test_dwell_stops_motion() {
+    let limits = MotionLimits::default();
+    let without = estimate_time(&program(&["G1 X10 F600", "X20"]), &limits).unwrap();
+    let with = estimate_time(&program(&["G1 X10 F600", "G4 P1", "X20"]), &limits).unwrap();
+    assert!(with.seconds > without.seconds + 1.0);
+}
+
+#[test]
+fn test_invalid_limits_are_rejected() {
+    let limits = MotionLimits {
+        acceleration: 0.0,
+        ..MotionLimits::default()
+    };
+    assert!(estimate_time(&program(&["G1 X10 F600"]), &limits).is_err());
+}
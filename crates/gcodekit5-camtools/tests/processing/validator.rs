@@ -1,4 +1,4 @@
-use gcodekit5_camtools::validator::GCodeValidator;
+use gcodekit5_camtools::validator::{GCodeValidator, ValidatorConfig};
 
 #[test]
 fn test_coordinate_validation() {
@@ -15,3 +15,50 @@ fn test_valid_line() {
     let result = validator.validate(&lines);
     assert!(result.is_ok());
 }
+
+#[test]
+fn test_r_arc_reaching_end_is_valid() {
+    let validator = GCodeValidator::default();
+    let lines = vec!["G0 X0 Y0".to_string(), "G2 X10 Y0 R5".to_string()];
+    assert!(validator.validate(&lines).is_ok());
+}
+
+#[test]
+fn test_r_arc_too_small_is_reported() {
+    let validator = GCodeValidator::default();
+    let lines = vec![
+        "G0 X0 Y0".to_string(),
+        "G1 X10 F500".to_string(),
+        "G3 X30 R4".to_string(),
+    ];
+    let errors = validator.validate(&lines).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line, 2);
+    assert!(errors[0].message.contains("too small"));
+}
+
+#[test]
+fn test_r_arc_tracks_plane_and_modal_motion() {
+    let validator = GCodeValidator::default();
+    // In G18 only X/Z count toward the chord; the modal G2 carries to the next line
+    let lines = vec![
+        "G0 X0 Y0 Z0".to_string(),
+        "G18 G2 X10 Y50 R5".to_string(),
+        "X12 R0.5".to_string(),
+    ];
+    let errors = validator.validate(&lines).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line, 2);
+}
+
+#[test]
+fn test_optional_blocks_follow_block_delete_switch() {
+    let lines = vec!["G0 X10".to_string(), "/G0 X2001".to_string()];
+    assert!(GCodeValidator::default().validate(&lines).is_ok());
+
+    let validator = GCodeValidator::new(ValidatorConfig {
+        block_delete: false,
+        ..ValidatorConfig::default()
+    });
+    assert!(validator.validate(&lines).is_err());
+}
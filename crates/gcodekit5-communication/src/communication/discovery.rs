@@ -0,0 +1,315 @@
+//! Discovery of networked controllers
+//!
+//! Finds controllers on the local network that advertise themselves over
+//! mDNS (multicast DNS), such as FluidNC and grblHAL boards, which announce a
+//! `_telnet._tcp` service for their G-code stream. A single query is sent to
+//! the mDNS group and every answer that arrives before the timeout is
+//! collected; each service is reported once, with its address, port and
+//! advertised hostname.
+//!
+//! Controllers that do not advertise are not found and are connected to by
+//! entering their address as before. Discovery only reads from the network,
+//! so a query that goes unanswered just returns an empty list.
+
+use super::tcp::TcpConnectionInfo;
+use gcodekit5_core::{Error, Result};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, UdpSocket};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// mDNS multicast group and port
+const MDNS_ADDR: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+const MDNS_PORT: u16 = 5353;
+
+const TYPE_A: u16 = 1;
+const TYPE_PTR: u16 = 12;
+const TYPE_SRV: u16 = 33;
+const CLASS_IN: u16 = 1;
+
+/// Compression pointers followed before a name is taken as malformed
+const MAX_NAME_JUMPS: usize = 16;
+
+/// What to look for and for how long
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveryOptions {
+    /// How long to wait for answers
+    pub timeout: Duration,
+    /// mDNS service types to query, e.g. "_telnet._tcp.local"
+    pub services: Vec<String>,
+}
+
+impl Default for DiscoveryOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(2),
+            services: vec!["_telnet._tcp.local".to_string()],
+        }
+    }
+}
+
+/// Query the network for controllers advertising `options.services`
+///
+/// Blocks for up to `options.timeout`; use [`discover_in_background`] from a
+/// UI thread. Fails only if the query cannot be sent at all.
+pub fn discover_controllers(options: &DiscoveryOptions) -> Result<Vec<TcpConnectionInfo>> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .map_err(|e| Error::other(format!("Failed to open discovery socket: {}", e)))?;
+    let _ = socket.set_multicast_ttl_v4(255);
+    socket
+        .send_to(&build_query(&options.services), (MDNS_ADDR, MDNS_PORT))
+        .map_err(|e| Error::other(format!("Failed to send discovery query: {}", e)))?;
+
+    let deadline = Instant::now() + options.timeout;
+    let mut found = Vec::new();
+    let mut buf = [0u8; 9000];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() || socket.set_read_timeout(Some(remaining)).is_err() {
+            break;
+        }
+        match socket.recv_from(&mut buf) {
+            Ok((n, from)) => {
+                for controller in parse_response(&buf[..n], from.ip(), &options.services) {
+                    add_unique(&mut found, controller);
+                }
+            }
+            Err(e)
+                if e.kind() == std::io::ErrorKind::WouldBlock
+                    || e.kind() == std::io::ErrorKind::TimedOut =>
+            {
+                break
+            }
+            Err(e) => {
+                tracing::debug!("Discovery stopped early: {}", e);
+                break;
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// Run [`discover_controllers`] on its own thread
+///
+/// The result arrives on the returned channel once the timeout has passed.
+pub fn discover_in_background(
+    options: DiscoveryOptions,
+) -> mpsc::Receiver<Result<Vec<TcpConnectionInfo>>> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(discover_controllers(&options));
+    });
+    rx
+}
+
+/// Add `controller` unless the same address and port is already listed
+fn add_unique(found: &mut Vec<TcpConnectionInfo>, controller: TcpConnectionInfo) {
+    let known = found
+        .iter_mut()
+        .find(|c| c.host == controller.host && c.port == controller.port);
+    match known {
+        Some(known) => {
+            if known.hostname.is_none() {
+                known.hostname = controller.hostname;
+            }
+        }
+        None => found.push(controller),
+    }
+}
+
+/// A PTR query for each of `services`
+fn build_query(services: &[String]) -> Vec<u8> {
+    let mut packet = vec![0u8; 12];
+    packet[4..6].copy_from_slice(&(services.len() as u16).to_be_bytes());
+    for service in services {
+        for label in service.trim_end_matches('.').split('.') {
+            let label = &label.as_bytes()[..label.len().min(63)];
+            packet.push(label.len() as u8);
+            packet.extend_from_slice(label);
+        }
+        packet.push(0);
+        packet.extend_from_slice(&TYPE_PTR.to_be_bytes());
+        packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+    }
+    packet
+}
+
+fn read_u16(packet: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([
+        *packet.get(offset)?,
+        *packet.get(offset + 1)?,
+    ]))
+}
+
+/// The name at `offset`, lowercased and without the trailing dot, and the
+/// offset just past it
+fn read_name(packet: &[u8], mut offset: usize) -> Option<(String, usize)> {
+    let mut labels: Vec<String> = Vec::new();
+    let mut end = None;
+    let mut jumps = 0;
+    loop {
+        let length = *packet.get(offset)? as usize;
+        match length {
+            0 => {
+                end.get_or_insert(offset + 1);
+                break;
+            }
+            // Compression pointer to a name earlier in the packet
+            l if l & 0xC0 == 0xC0 => {
+                jumps += 1;
+                if jumps > MAX_NAME_JUMPS {
+                    return None;
+                }
+                end.get_or_insert(offset + 2);
+                offset = (read_u16(packet, offset)? & 0x3FFF) as usize;
+            }
+            l => {
+                let label = packet.get(offset + 1..offset + 1 + l)?;
+                labels.push(String::from_utf8_lossy(label).to_lowercase());
+                offset += 1 + l;
+            }
+        }
+    }
+    Some((labels.join("."), end?))
+}
+
+/// The controllers advertising one of `services` in an mDNS answer from
+/// `source`
+fn parse_response(packet: &[u8], source: IpAddr, services: &[String]) -> Vec<TcpConnectionInfo> {
+    let parse = || -> Option<Vec<TcpConnectionInfo>> {
+        // Only answers, not other hosts' queries
+        if read_u16(packet, 2)? & 0x8000 == 0 {
+            return Some(Vec::new());
+        }
+        let questions = read_u16(packet, 4)?;
+        let records = (6..12)
+            .step_by(2)
+            .map(|at| read_u16(packet, at).map(usize::from))
+            .sum::<Option<usize>>()?;
+
+        let mut offset = 12;
+        for _ in 0..questions {
+            offset = read_name(packet, offset)?.1 + 4;
+        }
+        let mut instances: Vec<String> = Vec::new();
+        let mut targets: HashMap<String, (u16, String)> = HashMap::new();
+        let mut addresses: HashMap<String, Ipv4Addr> = HashMap::new();
+        for _ in 0..records {
+            let (name, after) = read_name(packet, offset)?;
+            let kind = read_u16(packet, after)?;
+            let length = read_u16(packet, after + 8)? as usize;
+            let data = after + 10;
+            packet.get(data..data + length)?;
+            match kind {
+                TYPE_PTR => instances.push(read_name(packet, data)?.0),
+                TYPE_SRV => {
+                    let port = read_u16(packet, data + 4)?;
+                    targets.insert(name, (port, read_name(packet, data + 6)?.0));
+                }
+                TYPE_A if length == 4 => {
+                    let octets: [u8; 4] = packet[data..data + 4].try_into().ok()?;
+                    addresses.insert(name, Ipv4Addr::from(octets));
+                }
+                _ => {}
+            }
+            offset = data + length;
+        }
+
+        let wanted: Vec<String> = services
+            .iter()
+            .map(|s| s.trim_end_matches('.').to_lowercase())
+            .collect();
+        instances.extend(targets.keys().cloned());
+        let mut controllers = Vec::new();
+        for instance in instances {
+            let advertised = wanted
+                .iter()
+                .any(|service| instance.ends_with(&format!(".{}", service)));
+            let Some((port, target)) = targets.get(&instance).filter(|_| advertised) else {
+                continue;
+            };
+            let address = addresses
+                .get(target)
+                .map_or(source, |address| IpAddr::V4(*address));
+            add_unique(
+                &mut controllers,
+                TcpConnectionInfo::new(&address.to_string(), *port).with_hostname(target),
+            );
+        }
+        Some(controllers)
+    };
+    parse().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn name(labels: &str) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for label in labels.split('.') {
+            bytes.push(label.len() as u8);
+            bytes.extend_from_slice(label.as_bytes());
+        }
+        bytes.push(0);
+        bytes
+    }
+
+    fn record(owner: &[u8], kind: u16, data: &[u8]) -> Vec<u8> {
+        let mut bytes = owner.to_vec();
+        bytes.extend_from_slice(&kind.to_be_bytes());
+        bytes.extend_from_slice(&CLASS_IN.to_be_bytes());
+        bytes.extend_from_slice(&120u32.to_be_bytes());
+        bytes.extend_from_slice(&(data.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn test_parse_response_finds_advertised_controller() {
+        let mut packet = vec![0, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 2];
+        // The service name sits at offset 12, for the compression pointers below
+        let service = name("_telnet._tcp.local");
+        let instance_data = [&[7][..], b"FluidNC", &[0xC0, 12]].concat();
+        packet.extend(record(&service, TYPE_PTR, &instance_data));
+        let mut srv = vec![0, 0, 0, 0, 0, 23];
+        srv.extend(name("fluidnc.local"));
+        packet.extend(record(&instance_data, TYPE_SRV, &srv));
+        packet.extend(record(&name("FluidNC.local"), TYPE_A, &[192, 168, 1, 50]));
+
+        let services = DiscoveryOptions::default().services;
+        let source = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 50));
+        let found = parse_response(&packet, source, &services);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].host, "192.168.1.50");
+        assert_eq!(found[0].port, 23);
+        assert_eq!(found[0].hostname.as_deref(), Some("fluidnc.local"));
+
+        // Queries, other services and truncated packets give nothing
+        let mut query = packet.clone();
+        query[2] = 0;
+        assert!(parse_response(&query, source, &services).is_empty());
+        assert!(parse_response(&packet, source, &["_http._tcp.local".to_string()]).is_empty());
+        assert!(parse_response(&packet[..40], source, &services).is_empty());
+    }
+
+    #[test]
+    fn test_query_and_dedupe() {
+        let query = build_query(&DiscoveryOptions::default().services);
+        assert_eq!(read_u16(&query, 4), Some(1));
+        assert_eq!(
+            read_name(&query, 12),
+            Some(("_telnet._tcp.local".to_string(), 32))
+        );
+        assert_eq!(read_u16(&query, 32), Some(TYPE_PTR));
+
+        let mut found = vec![TcpConnectionInfo::new("10.0.0.2", 23)];
+        add_unique(
+            &mut found,
+            TcpConnectionInfo::new("10.0.0.2", 23).with_hostname("grblhal.local"),
+        );
+        add_unique(&mut found, TcpConnectionInfo::new("10.0.0.3", 23));
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].hostname.as_deref(), Some("grblhal.local"));
+    }
+}
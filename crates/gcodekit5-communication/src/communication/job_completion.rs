@@ -0,0 +1,195 @@
+//! Job completion detection for streamed programs
+//!
+//! An empty send queue only means the last line was accepted by the controller, not that
+//! the machine has finished moving. Completion is therefore declared when:
+//! - the program contains M2/M30 and the program end has been reached (the M2/M30 line
+//!   was acknowledged or the controller reported `[MSG:Pgm End]`), or
+//! - otherwise, the queue is drained and the controller has reported a quiescent state
+//!   (e.g. `Idle`) continuously for a debounce period.
+//!
+//! If the controller stops reporting status after draining, or an expected program end
+//! never arrives while the machine sits idle, a fallback timeout completes the job
+//! instead of hanging.
+
+use std::time::{Duration, Instant};
+
+/// Timing configuration for job completion detection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobCompletionConfig {
+    /// How long the controller must stay quiescent after the queue drains
+    pub idle_debounce: Duration,
+    /// Complete anyway if no status arrives for this long after draining, or if an
+    /// expected M2/M30 is not seen while idle for this long
+    pub status_timeout: Duration,
+}
+
+impl Default for JobCompletionConfig {
+    fn default() -> Self {
+        Self {
+            idle_debounce: Duration::from_millis(500),
+            status_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Why a job was considered complete
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobCompletionReason {
+    /// M2/M30 reached
+    ProgramEnd,
+    /// Queue drained and the controller stayed idle for the debounce period
+    Idle,
+    /// Queue drained and the controller stopped reporting status
+    StatusTimeout,
+}
+
+/// Tracks a streamed job and decides when it has finished
+#[derive(Debug, Clone)]
+pub struct JobCompletionDetector {
+    config: JobCompletionConfig,
+    active: bool,
+    expects_program_end: bool,
+    program_end_reached: bool,
+    last_sent: Option<String>,
+    drained_at: Option<Instant>,
+    quiescent_since: Option<Instant>,
+    last_status_at: Option<Instant>,
+}
+
+impl JobCompletionDetector {
+    /// Create a detector with the given timing configuration
+    pub fn new(config: JobCompletionConfig) -> Self {
+        Self {
+            config,
+            active: false,
+            expects_program_end: false,
+            program_end_reached: false,
+            last_sent: None,
+            drained_at: None,
+            quiescent_since: None,
+            last_status_at: None,
+        }
+    }
+
+    /// Whether a line ends the program (M2 or M30)
+    pub fn is_program_end_line(line: &str) -> bool {
+        let code = line.split(['(', ';']).next().unwrap_or("");
+        code.char_indices()
+            .filter(|(_, c)| c.eq_ignore_ascii_case(&'M'))
+            .any(|(i, _)| {
+                let digits: String = code[i + 1..]
+                    .trim_start()
+                    .chars()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect();
+                matches!(digits.parse::<u32>(), Ok(2) | Ok(30))
+            })
+    }
+
+    /// Begin tracking a new job made of the given lines
+    pub fn start<S: AsRef<str>>(&mut self, lines: &[S]) {
+        *self = Self::new(self.config);
+        self.active = true;
+        self.expects_program_end = lines
+            .iter()
+            .any(|line| Self::is_program_end_line(line.as_ref()));
+    }
+
+    /// Stop tracking (job aborted or finished)
+    pub fn reset(&mut self) {
+        *self = Self::new(self.config);
+    }
+
+    /// Whether a job is being tracked
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Whether the job contains an explicit program end (M2/M30)
+    pub fn expects_program_end(&self) -> bool {
+        self.expects_program_end
+    }
+
+    /// Record a line sent to the controller
+    pub fn on_line_sent(&mut self, line: &str) {
+        self.last_sent = Some(line.to_string());
+    }
+
+    /// Record an `ok` for the most recently sent line
+    pub fn on_ack(&mut self) {
+        if self
+            .last_sent
+            .as_deref()
+            .is_some_and(Self::is_program_end_line)
+        {
+            self.program_end_reached = true;
+        }
+    }
+
+    /// Record a `[MSG:Pgm End]` notification from the controller
+    pub fn on_program_end(&mut self) {
+        self.program_end_reached = true;
+    }
+
+    /// Record that the send queue is empty
+    pub fn on_queue_drained(&mut self, now: Instant) {
+        if self.drained_at.is_none() {
+            self.drained_at = Some(now);
+        }
+    }
+
+    /// Record a reported machine state (e.g. "Idle", "Run", "Hold:0")
+    pub fn on_state(&mut self, state: &str, now: Instant) {
+        self.last_status_at = Some(now);
+        let base = state.split(':').next().unwrap_or(state).trim();
+        let busy = matches!(base, "Run" | "Jog" | "Hold" | "Door" | "Home" | "Tool");
+        if busy {
+            self.quiescent_since = None;
+        } else if self.quiescent_since.is_none() {
+            self.quiescent_since = Some(now);
+        }
+    }
+
+    /// Check for completion. Returns the reason once, then stops tracking.
+    pub fn poll(&mut self, now: Instant) -> Option<JobCompletionReason> {
+        if !self.active {
+            return None;
+        }
+        let drained_at = self.drained_at?;
+
+        // Only time spent quiescent after draining counts.
+        let quiet_for = self
+            .quiescent_since
+            .map(|since| now.saturating_duration_since(since.max(drained_at)));
+        // With an explicit program end expected, idle alone is only a fallback.
+        let required_quiet = if self.expects_program_end {
+            self.config.status_timeout
+        } else {
+            self.config.idle_debounce
+        };
+        let last_status = self
+            .last_status_at
+            .map_or(drained_at, |t| t.max(drained_at));
+
+        let reason = if self.expects_program_end && self.program_end_reached {
+            Some(JobCompletionReason::ProgramEnd)
+        } else if quiet_for.is_some_and(|quiet| quiet >= required_quiet) {
+            Some(JobCompletionReason::Idle)
+        } else if now.saturating_duration_since(last_status) >= self.config.status_timeout {
+            Some(JobCompletionReason::StatusTimeout)
+        } else {
+            None
+        };
+
+        if reason.is_some() {
+            self.reset();
+        }
+        reason
+    }
+}
+
+impl Default for JobCompletionDetector {
+    fn default() -> Self {
+        Self::new(JobCompletionConfig::default())
+    }
+}
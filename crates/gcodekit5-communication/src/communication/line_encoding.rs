@@ -0,0 +1,148 @@
+//! Line endings and text encodings for controller connections
+//!
+//! Controllers differ in what ends a command line (`\n`, `\r\n` or `\r`) and
+//! in the bytes they send back: most answer in ASCII or UTF-8, but some send
+//! Latin-1 text or stray bytes that are not valid UTF-8. [`TextDecoder`] turns
+//! received bytes into text as they arrive, keeping a multi-byte character
+//! that is split across two reads whole instead of mangling both halves.
+//!
+//! Realtime commands are single bytes and never get a line ending; see
+//! [`is_realtime_command`].
+
+use serde::{Deserialize, Serialize};
+
+/// Terminator appended to each command line sent to the controller
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    /// Line feed (`\n`), what GRBL and most controllers expect
+    #[default]
+    Lf,
+    /// Carriage return and line feed (`\r\n`)
+    CrLf,
+    /// Carriage return (`\r`)
+    Cr,
+}
+
+impl LineEnding {
+    /// The terminator bytes
+    pub fn as_bytes(&self) -> &'static [u8] {
+        match self {
+            Self::Lf => b"\n",
+            Self::CrLf => b"\r\n",
+            Self::Cr => b"\r",
+        }
+    }
+
+    /// `command` with any line ending of its own replaced by this one
+    pub fn terminate(&self, command: &str) -> Vec<u8> {
+        let mut line = command.trim_end_matches(['\r', '\n']).as_bytes().to_vec();
+        line.extend_from_slice(self.as_bytes());
+        line
+    }
+}
+
+/// How bytes received from the controller are turned into text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextEncoding {
+    /// UTF-8; bytes that are not valid UTF-8 become U+FFFD
+    #[default]
+    Utf8,
+    /// ISO-8859-1, where every byte is one character
+    Latin1,
+}
+
+/// Whether `command` is a realtime command, sent as its single byte without a
+/// line ending
+///
+/// These are the status query, feed hold and cycle start characters, soft
+/// reset (0x18), and the extended overrides from 0x80 to 0xFF.
+pub fn is_realtime_command(command: &str) -> bool {
+    let mut chars = command.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => matches!(c, '?' | '!' | '~' | '\x18' | '\u{80}'..='\u{ff}'),
+        _ => false,
+    }
+}
+
+/// The bytes to send for `command`: realtime commands as their single byte,
+/// anything else terminated with `ending`
+pub fn command_bytes(command: &str, ending: LineEnding) -> Vec<u8> {
+    match command.chars().next() {
+        Some(c) if is_realtime_command(command) => vec![c as u8],
+        _ => ending.terminate(command),
+    }
+}
+
+/// Decodes received bytes into text, carrying incomplete UTF-8 sequences over
+/// to the next read
+#[derive(Debug, Clone, Default)]
+pub struct TextDecoder {
+    encoding: TextEncoding,
+    pending: Vec<u8>,
+}
+
+impl TextDecoder {
+    /// A decoder for `encoding`
+    pub fn new(encoding: TextEncoding) -> Self {
+        Self {
+            encoding,
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn encoding(&self) -> TextEncoding {
+        self.encoding
+    }
+
+    /// Text for `bytes`, read after everything passed before
+    ///
+    /// A UTF-8 sequence cut off at the end of `bytes` is held back until the
+    /// rest of it arrives.
+    pub fn decode(&mut self, bytes: &[u8]) -> String {
+        match self.encoding {
+            TextEncoding::Latin1 => bytes.iter().map(|&b| char::from(b)).collect(),
+            TextEncoding::Utf8 => {
+                self.pending.extend_from_slice(bytes);
+                let mut text = String::with_capacity(self.pending.len());
+                let mut rest: &[u8] = &self.pending;
+                loop {
+                    match std::str::from_utf8(rest) {
+                        Ok(valid) => {
+                            text.push_str(valid);
+                            rest = &[];
+                            break;
+                        }
+                        Err(error) => {
+                            let (valid, after) = rest.split_at(error.valid_up_to());
+                            text.push_str(std::str::from_utf8(valid).unwrap_or_default());
+                            match error.error_len() {
+                                Some(invalid) => {
+                                    text.push(char::REPLACEMENT_CHARACTER);
+                                    rest = &after[invalid..];
+                                }
+                                // Incomplete sequence at the end: wait for more
+                                None => {
+                                    rest = after;
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                }
+                self.pending = rest.to_vec();
+                text
+            }
+        }
+    }
+
+    /// Text for any bytes still held back, as a replacement character
+    pub fn flush(&mut self) -> String {
+        if self.pending.is_empty() {
+            return String::new();
+        }
+        self.pending.clear();
+        char::REPLACEMENT_CHARACTER.to_string()
+    }
+}
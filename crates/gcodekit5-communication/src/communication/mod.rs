@@ -6,23 +6,35 @@
 //! - Pluggable communication backends
 //! - Serial (USB) communication
 //! - TCP/IP network communication  
+//! - Discovery of networked controllers over mDNS
 //! - WebSocket communication
 //! - Event callbacks for connection state changes
-//! - Configurable connection parameters
+//! - Configurable connection parameters, line endings and text encodings
 
 pub mod buffered;
+pub mod discovery;
+pub mod job_completion;
+pub mod line_encoding;
 pub mod serial;
 pub mod tcp;
+pub mod throttle;
 
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 pub use buffered::{
     BufferedCommand, BufferedCommunicatorConfig, BufferedCommunicatorWrapper, CommandStatus,
 };
+pub use discovery::{discover_controllers, discover_in_background, DiscoveryOptions};
+pub use job_completion::{JobCompletionConfig, JobCompletionDetector, JobCompletionReason};
+pub use line_encoding::{
+    command_bytes, is_realtime_command, LineEnding, TextDecoder, TextEncoding,
+};
 pub use serial::{list_ports, SerialPortInfo};
 pub use tcp::TcpConnectionInfo;
+pub use throttle::CommandThrottle;
 
 /// Connection driver type
 ///
@@ -86,6 +98,23 @@ pub struct ConnectionParams {
 
     /// Maximum number of reconnection attempts before giving up
     pub max_retries: u32,
+
+    /// Terminator appended to command lines (realtime bytes never get one)
+    #[serde(default)]
+    pub line_ending: LineEnding,
+
+    /// Encoding of the text received from the controller
+    #[serde(default)]
+    pub encoding: TextEncoding,
+
+    /// Minimum time between command lines in milliseconds (0 = no throttle)
+    #[serde(default)]
+    pub command_delay_ms: u64,
+
+    /// Time the controller needs after a soft reset before it takes commands,
+    /// in milliseconds (0 = none)
+    #[serde(default)]
+    pub reset_settle_ms: u64,
 }
 
 /// Serial port parity setting
@@ -114,6 +143,10 @@ impl Default for ConnectionParams {
             parity: SerialParity::None,
             auto_reconnect: true,
             max_retries: 3,
+            line_ending: LineEnding::default(),
+            encoding: TextEncoding::default(),
+            command_delay_ms: 0,
+            reset_settle_ms: 0,
         }
     }
 }
@@ -265,12 +298,24 @@ pub trait Communicator: Send + Sync {
     /// Returns a vector of received bytes. May return empty vector if no data available.
     fn receive(&mut self) -> gcodekit5_core::Result<Vec<u8>>;
 
-    /// Send a text command with newline termination
+    /// Send a text command terminated with the configured line ending
+    ///
+    /// Convenience method that sends a command string followed by the line
+    /// ending of the connection parameters (newline if none are set), in
+    /// place of any the command already ends with. Realtime commands are sent
+    /// as their single byte with no line ending and are never throttled.
     ///
-    /// Convenience method that sends a command string followed by newline.
+    /// This never waits for the command throttle; callers that space commands
+    /// out send only once [`command_wait_time`](Self::command_wait_time) is zero.
     fn send_command(&mut self, command: &str) -> gcodekit5_core::Result<()> {
-        self.send(command.as_bytes())?;
-        self.send(b"\n")?;
+        if is_realtime_command(command) {
+            self.send(&command_bytes(command, LineEnding::default()))?;
+            return Ok(());
+        }
+        let ending = self.line_ending();
+        self.send(command.trim_end_matches(['\r', '\n']).as_bytes())?;
+        self.send(ending.as_bytes())?;
+        self.note_command_sent();
         Ok(())
     }
 
@@ -294,6 +339,29 @@ pub trait Communicator: Send + Sync {
     /// Set connection parameters (without connecting)
     fn set_connection_params(&mut self, params: ConnectionParams) -> gcodekit5_core::Result<()>;
 
+    /// How long the connection's command throttle holds the next command line
+    /// back; zero when it may go now, and always zero by default
+    fn command_wait_time(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    /// Record a command line as sent, for the command throttle
+    fn note_command_sent(&mut self) {}
+
+    /// Line ending appended to commands
+    fn line_ending(&self) -> LineEnding {
+        self.connection_params()
+            .map(|p| p.line_ending)
+            .unwrap_or_default()
+    }
+
+    /// Encoding of the text received from the device
+    fn text_encoding(&self) -> TextEncoding {
+        self.connection_params()
+            .map(|p| p.encoding)
+            .unwrap_or_default()
+    }
+
     /// Get the connection driver type
     fn driver_type(&self) -> ConnectionDriver {
         self.connection_params()
@@ -385,6 +453,9 @@ pub struct SerialCommunicator {
     port: Option<Box<dyn serial::SerialPort>>,
     params: Option<ConnectionParams>,
     listeners: Vec<CommunicatorListenerHandle>,
+    /// Decodes received bytes for listeners, across reads
+    decoder: TextDecoder,
+    throttle: CommandThrottle,
 }
 
 impl SerialCommunicator {
@@ -394,6 +465,8 @@ impl SerialCommunicator {
             port: None,
             params: None,
             listeners: Vec::new(),
+            decoder: TextDecoder::default(),
+            throttle: CommandThrottle::default(),
         }
     }
 
@@ -432,6 +505,8 @@ impl Communicator for SerialCommunicator {
             Ok(port) => {
                 self.port = Some(Box::new(port));
                 self.params = Some(params.clone());
+                self.decoder = TextDecoder::new(params.encoding);
+                self.throttle = CommandThrottle::from_params(params);
                 self.notify_listeners(CommunicatorEvent::Connected, "Connected to serial port");
                 Ok(())
             }
@@ -474,6 +549,9 @@ impl Communicator for SerialCommunicator {
         if let Some(port) = &mut self.port {
             match port.write(data) {
                 Ok(n) => {
+                    if data == [0x18] {
+                        self.throttle.note_reset(Instant::now());
+                    }
                     // Notify listeners of sent data
                     let sent_data = &data[..n];
                     let data_str = String::from_utf8_lossy(sent_data);
@@ -503,8 +581,10 @@ impl Communicator for SerialCommunicator {
                     let data = buf[..n].to_vec();
                     // Notify listeners of received data
                     if !data.is_empty() {
-                        let data_str = String::from_utf8_lossy(&data);
-                        self.notify_listeners(CommunicatorEvent::DataReceived, &data_str);
+                        let data_str = self.decoder.decode(&data);
+                        if !data_str.is_empty() {
+                            self.notify_listeners(CommunicatorEvent::DataReceived, &data_str);
+                        }
                     }
                     Ok(data)
                 }
@@ -544,9 +624,19 @@ impl Communicator for SerialCommunicator {
 
     fn set_connection_params(&mut self, params: ConnectionParams) -> gcodekit5_core::Result<()> {
         params.validate()?;
+        self.decoder = TextDecoder::new(params.encoding);
+        self.throttle = CommandThrottle::from_params(&params);
         self.params = Some(params);
         Ok(())
     }
+
+    fn command_wait_time(&self) -> Duration {
+        self.throttle.wait_time(Instant::now())
+    }
+
+    fn note_command_sent(&mut self) {
+        self.throttle.note_command(Instant::now());
+    }
 }
 
 /// TCP/Network communicator for remote controller connections
@@ -557,6 +647,7 @@ pub struct TcpCommunicator {
     port: Option<Box<dyn tcp::TcpPort>>,
     params: Option<ConnectionParams>,
     listeners: Vec<CommunicatorListenerHandle>,
+    throttle: CommandThrottle,
 }
 
 impl TcpCommunicator {
@@ -566,6 +657,7 @@ impl TcpCommunicator {
             port: None,
             params: None,
             listeners: Vec::new(),
+            throttle: CommandThrottle::default(),
         }
     }
 
@@ -604,6 +696,7 @@ impl Communicator for TcpCommunicator {
             Ok(port) => {
                 self.port = Some(Box::new(port));
                 self.params = Some(params.clone());
+                self.throttle = CommandThrottle::from_params(params);
                 self.notify_listeners(CommunicatorEvent::Connected, "Connected to TCP server");
                 Ok(())
             }
@@ -646,6 +739,9 @@ impl Communicator for TcpCommunicator {
         if let Some(port) = &mut self.port {
             match port.write(data) {
                 Ok(n) => {
+                    if data == [0x18] {
+                        self.throttle.note_reset(Instant::now());
+                    }
                     let sent_data = &data[..n];
                     let data_str = String::from_utf8_lossy(sent_data);
                     self.notify_listeners(CommunicatorEvent::DataSent, &data_str);
@@ -719,7 +815,16 @@ impl Communicator for TcpCommunicator {
 
     fn set_connection_params(&mut self, params: ConnectionParams) -> gcodekit5_core::Result<()> {
         params.validate()?;
+        self.throttle = CommandThrottle::from_params(&params);
         self.params = Some(params);
         Ok(())
     }
+
+    fn command_wait_time(&self) -> Duration {
+        self.throttle.wait_time(Instant::now())
+    }
+
+    fn note_command_sent(&mut self) {
+        self.throttle.note_command(Instant::now());
+    }
 }
@@ -25,6 +25,10 @@ pub struct TcpConnectionInfo {
     /// Remote port number
     pub port: u16,
 
+    /// Name the controller advertises itself under (e.g. "fluidnc.local"), if
+    /// it was discovered
+    pub hostname: Option<String>,
+
     /// Local bind address (optional)
     pub local_addr: Option<String>,
 
@@ -38,6 +42,7 @@ impl TcpConnectionInfo {
         Self {
             host: host.to_string(),
             port,
+            hostname: None,
             local_addr: None,
             timeout: Duration::from_secs(5),
         }
@@ -49,6 +54,12 @@ impl TcpConnectionInfo {
         self
     }
 
+    /// Set the advertised hostname
+    pub fn with_hostname(mut self, hostname: &str) -> Self {
+        self.hostname = Some(hostname.to_string());
+        self
+    }
+
     /// Set the local bind address
     pub fn with_local_addr(mut self, addr: &str) -> Self {
         self.local_addr = Some(addr.to_string());
@@ -59,6 +70,14 @@ impl TcpConnectionInfo {
     pub fn address(&self) -> String {
         format!("{}:{}", self.host, self.port)
     }
+
+    /// Connection parameters for a TCP connection to this address
+    pub fn connection_params(&self) -> ConnectionParams {
+        ConnectionParams {
+            timeout_ms: self.timeout.as_millis().max(1) as u64,
+            ..ConnectionParams::tcp(&self.host, self.port)
+        }
+    }
 }
 
 /// TCP network interface trait
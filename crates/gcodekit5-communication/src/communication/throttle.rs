@@ -0,0 +1,65 @@
+//! Spacing of commands for controllers that drop them when they arrive fast
+//!
+//! Some older controllers lose a command that arrives too soon after the
+//! previous one, or while they are still restarting after a soft reset.
+//! [`CommandThrottle`] works out how long each command line has to wait for a
+//! minimum delay since the last one, and the first command after a reset for
+//! the controller to settle. Nothing blocks: senders ask
+//! [`Communicator::command_wait_time`](crate::Communicator::command_wait_time)
+//! and send once it is zero. Both delays default to zero, so capable
+//! controllers are not slowed down. Realtime bytes are never held back.
+
+use crate::ConnectionParams;
+use std::time::{Duration, Instant};
+
+/// Minimum spacing between command lines, and after a soft reset
+#[derive(Debug, Clone, Default)]
+pub struct CommandThrottle {
+    command_delay: Duration,
+    reset_settle: Duration,
+    last_command: Option<Instant>,
+    last_reset: Option<Instant>,
+}
+
+impl CommandThrottle {
+    /// A throttle spacing commands by `command_delay_ms` and waiting
+    /// `reset_settle_ms` after a reset
+    pub fn new(command_delay_ms: u64, reset_settle_ms: u64) -> Self {
+        Self {
+            command_delay: Duration::from_millis(command_delay_ms),
+            reset_settle: Duration::from_millis(reset_settle_ms),
+            last_command: None,
+            last_reset: None,
+        }
+    }
+
+    /// The throttle configured by a connection's parameters
+    pub fn from_params(params: &ConnectionParams) -> Self {
+        Self::new(params.command_delay_ms, params.reset_settle_ms)
+    }
+
+    /// Whether either delay is set
+    pub fn is_enabled(&self) -> bool {
+        !self.command_delay.is_zero() || !self.reset_settle.is_zero()
+    }
+
+    /// How long the next command has to wait at `now`
+    pub fn wait_time(&self, now: Instant) -> Duration {
+        let after = |since: Option<Instant>, delay: Duration| {
+            since.map_or(Duration::ZERO, |at| {
+                (at + delay).saturating_duration_since(now)
+            })
+        };
+        after(self.last_command, self.command_delay).max(after(self.last_reset, self.reset_settle))
+    }
+
+    /// Record a command line sent at `now`
+    pub fn note_command(&mut self, now: Instant) {
+        self.last_command = Some(now);
+    }
+
+    /// Record a soft reset sent at `now`
+    pub fn note_reset(&mut self, now: Instant) {
+        self.last_reset = Some(now);
+    }
+}
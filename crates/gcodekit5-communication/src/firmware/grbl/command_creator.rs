@@ -53,6 +53,8 @@ pub enum SystemCommand {
     QueryParserState,
     /// Query build info ($I)
     QueryBuildInfo,
+    /// Query G-code parameters: WCS, G28/G30, G92, TLO, probe ($#)
+    QueryParameters,
     /// Reset EEPROM ($RST=$)
     ResetEeprom,
     /// Reset settings and data ($RST=*)
@@ -70,6 +72,7 @@ impl SystemCommand {
             Self::CheckMode => "$C".to_string(),
             Self::QueryParserState => "$G".to_string(),
             Self::QueryBuildInfo => "$I".to_string(),
+            Self::QueryParameters => "$#".to_string(),
             Self::ResetEeprom => "$RST=$".to_string(),
             Self::ResetAll => "$RST=*".to_string(),
             Self::Sleep => "$SLP".to_string(),
@@ -84,6 +87,7 @@ impl SystemCommand {
             Self::CheckMode => "Check Mode",
             Self::QueryParserState => "Query Parser State",
             Self::QueryBuildInfo => "Query Build Info",
+            Self::QueryParameters => "Query G-code Parameters",
             Self::ResetEeprom => "Reset EEPROM",
             Self::ResetAll => "Reset All Settings",
             Self::Sleep => "Sleep",
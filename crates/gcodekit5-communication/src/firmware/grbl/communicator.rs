@@ -4,7 +4,9 @@
 //! GRBL uses a real-time character counting protocol to manage command flow without
 //! needing traditional handshaking.
 
-use crate::communication::{Communicator, ConnectionParams};
+use crate::communication::{
+    command_bytes, is_realtime_command, Communicator, ConnectionParams, LineEnding, TextEncoding,
+};
 use gcodekit5_core::{thread_safe_rw, ThreadSafeRw};
 use std::sync::Arc;
 
@@ -99,18 +101,39 @@ impl GrblCommunicator {
 
     /// Send a command to GRBL device
     ///
-    /// This sends a command with proper CRLF termination.
+    /// This sends a command terminated with the connection's line ending, or a
+    /// realtime command as its single byte. A command line first waits for the
+    /// connection's command throttle, blocking the calling thread.
     pub fn send_command(&self, command: &str) -> anyhow::Result<()> {
-        let formatted = if command.ends_with('\n') {
-            command.to_string()
-        } else {
-            format!("{}\n", command)
-        };
-
-        self.send_bytes(formatted.as_bytes())?;
+        let realtime = is_realtime_command(command);
+        if !realtime {
+            let wait = self.communicator.read().command_wait_time();
+            if !wait.is_zero() {
+                std::thread::sleep(wait);
+            }
+        }
+        self.send_bytes(&self.command_bytes(command))?;
+        if !realtime {
+            self.communicator.write().note_command_sent();
+        }
         Ok(())
     }
 
+    /// The bytes [`send_command`](Self::send_command) writes for `command`
+    pub fn command_bytes(&self, command: &str) -> Vec<u8> {
+        command_bytes(command, self.line_ending())
+    }
+
+    /// Line ending of the underlying connection
+    pub fn line_ending(&self) -> LineEnding {
+        self.communicator.read().line_ending()
+    }
+
+    /// Encoding of the text the device sends
+    pub fn text_encoding(&self) -> TextEncoding {
+        self.communicator.read().text_encoding()
+    }
+
     /// Read response from GRBL device
     pub fn read_response(&self) -> anyhow::Result<Vec<u8>> {
         let mut comm = self.communicator.write();
@@ -3,7 +3,7 @@
 //! Provides a complete implementation of the ControllerTrait for GRBL firmware,
 //! including connection management, command execution, and status polling.
 
-use crate::communication::{ConnectionParams, NoOpCommunicator};
+use crate::communication::{is_realtime_command, ConnectionParams, NoOpCommunicator, TextDecoder};
 use crate::firmware::grbl::status_parser::StatusParser;
 use crate::firmware::grbl::{GrblCommunicator, GrblCommunicatorConfig};
 use async_trait::async_trait;
@@ -111,6 +111,7 @@ impl GrblController {
 
         let handle = tokio::spawn(async move {
             let mut buffer = String::new();
+            let mut decoder = TextDecoder::new(communicator.text_encoding());
             let mut sent_queue: VecDeque<usize> = VecDeque::new();
             let mut local_cmd_queue: VecDeque<String> = VecDeque::new();
             let mut last_poll = Instant::now();
@@ -130,8 +131,7 @@ impl GrblController {
                 // or times out quickly (we set timeout to 50ms in connect)
                 match communicator.read_response() {
                     Ok(data) if !data.is_empty() => {
-                        let s = String::from_utf8_lossy(&data);
-                        buffer.push_str(&s);
+                        buffer.push_str(&decoder.decode(&data));
 
                         // Process complete lines
                         while let Some(pos) = buffer.find('\n') {
@@ -237,8 +237,15 @@ impl GrblController {
                 // 3. WRITE PHASE: Send commands if buffer allows
                 // We peek at the next command
                 if let Some(cmd) = local_cmd_queue.front() {
-                    let cmd_len = cmd.len() + 1; // +1 for newline
-                    if communicator.is_ready_to_send(cmd_len) {
+                    let bytes = communicator.command_bytes(cmd);
+                    let cmd_len = bytes.len();
+                    if is_realtime_command(cmd) {
+                        // Realtime bytes bypass the buffer and get no "ok"
+                        if communicator.send_bytes(&bytes).is_ok() {
+                            communicator.acknowledge_chars(cmd_len);
+                            local_cmd_queue.pop_front();
+                        }
+                    } else if communicator.is_ready_to_send(cmd_len) {
                         // Send it
                         if communicator.send_command(cmd).is_ok() {
                             // Move to sent queue
@@ -0,0 +1,187 @@
+//! GRBL G-code Parameters (`$#`) Parsing
+//!
+//! This module parses the response to the GRBL `$#` command, which reports the
+//! stored work coordinate offsets (G54-G59), the G28/G30 predefined positions,
+//! the G92 offset, the tool length offset and the last probe result.
+//!
+//! Different firmwares emit a different set of lines (grblHAL adds `G59.1`-`G59.3`,
+//! `[HOME:]` and multi-axis TLO), so each line is parsed independently and lines
+//! that are not recognized are ignored.
+
+use super::status_parser::WorkCoordinateOffset;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Result of the last probe cycle (`[PRB:x,y,z:success]`)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ProbeResult {
+    /// Probed position in machine coordinates
+    pub position: WorkCoordinateOffset,
+    /// Whether the probe was triggered
+    pub success: bool,
+}
+
+/// A single `$#` response line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GcodeParameter {
+    /// Work coordinate system offset (G54-G59, G59.1-G59.3)
+    WorkOffset {
+        /// G-code word, e.g. "G54"
+        code: String,
+        /// Stored offset
+        offset: WorkCoordinateOffset,
+    },
+    /// Predefined position (G28 or G30)
+    PredefinedPosition {
+        /// G-code word, e.g. "G28"
+        code: String,
+        /// Stored position in machine coordinates
+        position: WorkCoordinateOffset,
+    },
+    /// G92 coordinate offset
+    CoordinateOffset(WorkCoordinateOffset),
+    /// Tool length offset (Z axis)
+    ToolLengthOffset(f64),
+    /// Last probe result
+    Probe(ProbeResult),
+}
+
+impl GcodeParameter {
+    /// Parse a single `$#` response line such as `[G54:0.000,0.000,0.000]`.
+    ///
+    /// Returns `None` for lines that are not G-code parameter reports.
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+        let (label, value) = inner.split_once(':')?;
+
+        match label {
+            "G28" | "G30" => Some(Self::PredefinedPosition {
+                code: label.to_string(),
+                position: WorkCoordinateOffset::parse(value)?,
+            }),
+            "G92" => WorkCoordinateOffset::parse(value).map(Self::CoordinateOffset),
+            "TLO" => {
+                let values: Vec<f64> = value
+                    .split(',')
+                    .filter_map(|s| s.trim().parse::<f64>().ok())
+                    .collect();
+                // Single value for GRBL, one per axis for grblHAL; use the Z component.
+                let tlo = if values.len() >= 3 {
+                    values[2]
+                } else {
+                    *values.first()?
+                };
+                Some(Self::ToolLengthOffset(tlo))
+            }
+            "PRB" => {
+                let (coords, flag) = value.rsplit_once(':')?;
+                Some(Self::Probe(ProbeResult {
+                    position: WorkCoordinateOffset::parse(coords)?,
+                    success: flag.trim() == "1",
+                }))
+            }
+            _ if GcodeParameters::wcs_index(label).is_some() => Some(Self::WorkOffset {
+                code: label.to_string(),
+                offset: WorkCoordinateOffset::parse(value)?,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Parsed `$#` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GcodeParameters {
+    /// Work coordinate offsets keyed by G-code word ("G54", "G59.1", ...)
+    pub work_offsets: BTreeMap<String, WorkCoordinateOffset>,
+    /// G28 predefined position
+    pub g28: Option<WorkCoordinateOffset>,
+    /// G30 predefined position
+    pub g30: Option<WorkCoordinateOffset>,
+    /// G92 coordinate offset
+    pub g92: Option<WorkCoordinateOffset>,
+    /// Tool length offset
+    pub tool_length_offset: Option<f64>,
+    /// Last probe result
+    pub probe: Option<ProbeResult>,
+}
+
+impl GcodeParameters {
+    /// Create an empty parameter set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a complete multi-line `$#` response
+    pub fn parse(response: &str) -> Self {
+        let mut params = Self::new();
+        for line in response.lines() {
+            params.parse_line(line);
+        }
+        params
+    }
+
+    /// Parse one response line and merge it into this set.
+    ///
+    /// Returns `true` when the line was recognized.
+    pub fn parse_line(&mut self, line: &str) -> bool {
+        match GcodeParameter::parse(line) {
+            Some(param) => {
+                self.apply(param);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Merge a parsed parameter into this set
+    pub fn apply(&mut self, param: GcodeParameter) {
+        match param {
+            GcodeParameter::WorkOffset { code, offset } => {
+                self.work_offsets.insert(code, offset);
+            }
+            GcodeParameter::PredefinedPosition { code, position } => {
+                if code == "G28" {
+                    self.g28 = Some(position);
+                } else {
+                    self.g30 = Some(position);
+                }
+            }
+            GcodeParameter::CoordinateOffset(offset) => self.g92 = Some(offset),
+            GcodeParameter::ToolLengthOffset(tlo) => self.tool_length_offset = Some(tlo),
+            GcodeParameter::Probe(probe) => self.probe = Some(probe),
+        }
+    }
+
+    /// Get the offset for a work coordinate system word ("G54".."G59")
+    pub fn work_offset(&self, code: &str) -> Option<&WorkCoordinateOffset> {
+        self.work_offsets.get(code)
+    }
+
+    /// Map a WCS word to its 1-based index (G54 = 1 ... G59 = 6, G59.1 = 7 ... G59.3 = 9)
+    pub fn wcs_index(code: &str) -> Option<u32> {
+        match code {
+            "G54" => Some(1),
+            "G55" => Some(2),
+            "G56" => Some(3),
+            "G57" => Some(4),
+            "G58" => Some(5),
+            "G59" => Some(6),
+            "G59.1" => Some(7),
+            "G59.2" => Some(8),
+            "G59.3" => Some(9),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if nothing has been parsed yet
+    pub fn is_empty(&self) -> bool {
+        self.work_offsets.is_empty()
+            && self.g28.is_none()
+            && self.g30.is_none()
+            && self.g92.is_none()
+            && self.tool_length_offset.is_none()
+            && self.probe.is_none()
+    }
+}
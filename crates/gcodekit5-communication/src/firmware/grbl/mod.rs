@@ -9,7 +9,9 @@ pub mod communicator;
 pub mod constants;
 pub mod controller;
 pub mod error_decoder;
+pub mod gcode_parameters;
 pub mod override_manager;
+pub mod push_message;
 pub mod response_parser;
 pub mod settings;
 pub mod status_parser;
@@ -25,7 +27,9 @@ pub use communicator::{GrblCommunicator, GrblCommunicatorConfig};
 pub use constants::*;
 pub use controller::GrblController;
 pub use error_decoder::{decode_alarm, decode_error, format_alarm, format_error};
+pub use gcode_parameters::{GcodeParameter, GcodeParameters, ProbeResult};
 pub use override_manager::{OverrideManager, RealTimeOverrideCommand};
+pub use push_message::{GrblPushMessage, ParserModalState, PROGRAM_END_MESSAGE};
 pub use response_parser::{BufferState, GrblResponse, GrblResponseParser, StatusReport};
 pub use settings::{Setting, SettingsManager};
 pub use status_parser::{
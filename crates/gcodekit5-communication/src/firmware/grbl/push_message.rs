@@ -0,0 +1,165 @@
+//! GRBL Push Message Parsing
+//!
+//! This module classifies the bracketed feedback messages GRBL pushes to the host
+//! (`[MSG:]`, `[GC:]`, `[echo:]`, `[VER:]`, `[OPT:]`, `[HLP:]`) and startup block
+//! execution reports (`>G54G20:ok`). For `[GC:]` the reported modal state is parsed
+//! so the current units, plane and WCS are known without separate polling.
+//!
+//! Unknown bracketed messages are kept as [`GrblPushMessage::Other`] with their raw text.
+
+use serde::{Deserialize, Serialize};
+
+/// Text GRBL sends in `[MSG:Pgm End]` when M2/M30 completes
+pub const PROGRAM_END_MESSAGE: &str = "Pgm End";
+
+/// Modal G-code parser state reported by `$G` (`[GC:G0 G54 G17 G21 G90 G94 M5 M9 T0 F0 S0]`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParserModalState {
+    /// Motion mode (G0, G1, G2, G3, G38.x, G80)
+    pub motion: Option<String>,
+    /// Work coordinate system (G54-G59)
+    pub wcs: Option<String>,
+    /// Active plane (G17, G18, G19)
+    pub plane: Option<String>,
+    /// Units mode (G20 inches, G21 millimeters)
+    pub units: Option<String>,
+    /// Distance mode (G90 absolute, G91 incremental)
+    pub distance: Option<String>,
+    /// Feed rate mode (G93, G94, G95)
+    pub feed_mode: Option<String>,
+    /// Program flow (M0, M1, M2, M30)
+    pub program: Option<String>,
+    /// Spindle state (M3, M4, M5)
+    pub spindle: Option<String>,
+    /// Coolant state (M7, M8, M9; may be combined, e.g. "M7 M8")
+    pub coolant: Option<String>,
+    /// Tool number
+    pub tool: Option<u32>,
+    /// Feed rate
+    pub feed_rate: Option<f64>,
+    /// Spindle speed
+    pub spindle_speed: Option<f64>,
+}
+
+impl ParserModalState {
+    /// Parse the contents of a `[GC:...]` message (without the brackets and prefix)
+    pub fn parse(state_str: &str) -> Self {
+        let mut state = Self::default();
+
+        for word in state_str.split_whitespace() {
+            let word = word.to_ascii_uppercase();
+            let mut chars = word.chars();
+            let Some(letter) = chars.next() else {
+                continue;
+            };
+            let value = chars.as_str();
+            match (letter, value) {
+                ('G', "0" | "1" | "2" | "3" | "80") => state.motion = Some(word.clone()),
+                ('G', v) if v.starts_with("38.") => state.motion = Some(word.clone()),
+                ('G', v) if v.starts_with('5') && v != "53" => state.wcs = Some(word.clone()),
+                ('G', "17" | "18" | "19") => state.plane = Some(word.clone()),
+                ('G', "20" | "21") => state.units = Some(word.clone()),
+                ('G', "90" | "91") => state.distance = Some(word.clone()),
+                ('G', "93" | "94" | "95") => state.feed_mode = Some(word.clone()),
+                ('M', "0" | "1" | "2" | "30") => state.program = Some(word.clone()),
+                ('M', "3" | "4" | "5") => state.spindle = Some(word.clone()),
+                ('M', "7" | "8" | "9") => {
+                    state.coolant = Some(match state.coolant.take() {
+                        Some(existing) => format!("{} {}", existing, word),
+                        None => word.clone(),
+                    })
+                }
+                ('T', v) => state.tool = v.parse().ok(),
+                ('F', v) => state.feed_rate = v.parse().ok(),
+                ('S', v) => state.spindle_speed = v.parse().ok(),
+                _ => {}
+            }
+        }
+
+        state
+    }
+
+    /// Whether the parser is in inch mode (G20)
+    pub fn is_inches(&self) -> bool {
+        self.units.as_deref() == Some("G20")
+    }
+
+    /// Whether the parser is in incremental distance mode (G91)
+    pub fn is_incremental(&self) -> bool {
+        self.distance.as_deref() == Some("G91")
+    }
+}
+
+/// Classified GRBL push message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GrblPushMessage {
+    /// Feedback message (`[MSG:...]`)
+    Message(String),
+    /// G-code parser state (`[GC:...]`)
+    ParserState(Box<ParserModalState>),
+    /// Echoed line (`[echo:...]`, when echo is compiled in)
+    Echo(String),
+    /// Version line (`[VER:...]`)
+    Version(String),
+    /// Build options (`[OPT:...]`)
+    Options(String),
+    /// Help text (`[HLP:...]`)
+    Help(String),
+    /// Startup block execution report (`>G54G20:ok` or `>G54G20:error:20`)
+    StartupBlock {
+        /// The executed startup line
+        block: String,
+        /// Whether the block executed without error
+        ok: bool,
+        /// Error code, if the block failed
+        error: Option<u8>,
+    },
+    /// Any other bracketed message, with the raw text
+    Other(String),
+}
+
+impl GrblPushMessage {
+    /// Classify a response line. Returns `None` if it is not a push message.
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+
+        if let Some(rest) = line.strip_prefix('>') {
+            let message = if let Some(block) = rest.strip_suffix(":ok") {
+                Self::StartupBlock {
+                    block: block.to_string(),
+                    ok: true,
+                    error: None,
+                }
+            } else {
+                let (block, code) = rest.split_once(":error:").unwrap_or((rest, ""));
+                Self::StartupBlock {
+                    block: block.to_string(),
+                    ok: false,
+                    error: code.trim().parse::<u8>().ok(),
+                }
+            };
+            return Some(message);
+        }
+
+        let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+        let Some((tag, value)) = inner.split_once(':') else {
+            return Some(Self::Other(line.to_string()));
+        };
+
+        let message = match tag {
+            "MSG" => Self::Message(value.trim().to_string()),
+            "GC" => Self::ParserState(Box::new(ParserModalState::parse(value))),
+            "echo" | "ECHO" => Self::Echo(value.to_string()),
+            "VER" => Self::Version(value.to_string()),
+            "OPT" => Self::Options(value.to_string()),
+            "HLP" => Self::Help(value.to_string()),
+            _ => Self::Other(line.to_string()),
+        };
+        Some(message)
+    }
+
+    /// Whether this is the `[MSG:Pgm End]` notification sent when M2/M30 completes
+    pub fn is_program_end(&self) -> bool {
+        matches!(self, Self::Message(msg) if msg.eq_ignore_ascii_case(PROGRAM_END_MESSAGE))
+    }
+}
@@ -0,0 +1,95 @@
+//! Turning the spindle or laser off during a user feed hold
+//!
+//! What a controller needs depends on its firmware:
+//!
+//! - GRBL, grblHAL and FluidNC in laser mode (`$32=1`) already turn the laser
+//!   off while held and back on when the cycle resumes, so nothing is sent.
+//! - The same firmware outside laser mode queues any G-code sent during a hold
+//!   behind the held motion, so an `M5` would only run after resuming. It is
+//!   given the spindle stop override (`0x9E`) instead, which acts while held;
+//!   the firmware restores the spindle at its previous speed on cycle start.
+//! - Other firmware gets the configured command after the hold and, before
+//!   the cycle resumes, the command that turns the power back on at the speed
+//!   the job was running.
+//!
+//! Those commands go out while a job streams, so their `ok`s arrive among the
+//! job's; [`track_hold_commands`] keeps them apart.
+
+use super::firmware_version::FirmwareType;
+use gcodekit5_core::{CommandTracker, GcodeCommand, HoldPowerOff, SpindlePower};
+
+/// GRBL real-time spindle stop override; only acts while held
+pub const GRBL_SPINDLE_STOP_OVERRIDE: u8 = 0x9E;
+
+/// Something to send around a feed hold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HoldPowerStep {
+    /// A real-time byte, sent without a newline
+    Realtime(u8),
+    /// A G-code line
+    Command(String),
+}
+
+/// What to send after a feed hold and before resuming from it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HoldPowerPlan {
+    /// Sent right after the feed hold
+    pub on_hold: Vec<HoldPowerStep>,
+    /// Sent before the cycle start that resumes the job
+    pub on_resume: Vec<HoldPowerStep>,
+}
+
+impl HoldPowerPlan {
+    /// Whether nothing needs to be sent
+    pub fn is_empty(&self) -> bool {
+        self.on_hold.is_empty() && self.on_resume.is_empty()
+    }
+}
+
+/// The steps that turn the power off for a hold and back on to resume
+///
+/// `power` is the spindle state of the lines sent so far and `laser_mode`
+/// whether the controller reports laser mode. Nothing is sent when `off` is
+/// [`HoldPowerOff::Disabled`], when the firmware handles it, or when the power
+/// could not be restored afterwards (`S0` without a known speed).
+pub fn hold_power_plan(
+    firmware: FirmwareType,
+    laser_mode: bool,
+    off: HoldPowerOff,
+    power: &SpindlePower,
+) -> HoldPowerPlan {
+    let Some(command) = off.command() else {
+        return HoldPowerPlan::default();
+    };
+    match firmware {
+        FirmwareType::Grbl | FirmwareType::GrblHal | FirmwareType::FluidNC => {
+            if laser_mode {
+                return HoldPowerPlan::default();
+            }
+            // The override is ignored with the spindle already off
+            HoldPowerPlan {
+                on_hold: vec![HoldPowerStep::Realtime(GRBL_SPINDLE_STOP_OVERRIDE)],
+                on_resume: Vec::new(),
+            }
+        }
+        _ => match power.restore_command(off) {
+            Some(restore) => HoldPowerPlan {
+                on_hold: vec![HoldPowerStep::Command(command.to_string())],
+                on_resume: vec![HoldPowerStep::Command(restore)],
+            },
+            None => HoldPowerPlan::default(),
+        },
+    }
+}
+
+/// Track the G-code lines of `steps` as sent, following any job line in flight.
+///
+/// They are tracked without a line number, so when their responses come back in
+/// order the job line's `ok` is still the only one carrying a line number.
+pub fn track_hold_commands(tracker: &mut CommandTracker, steps: &[HoldPowerStep]) {
+    for step in steps {
+        if let HoldPowerStep::Command(command) = step {
+            tracker.track_sent(GcodeCommand::new(command.as_str()));
+        }
+    }
+}
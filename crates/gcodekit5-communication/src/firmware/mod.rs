@@ -20,7 +20,9 @@ pub mod fluidnc;
 pub mod g2core;
 pub mod grbl;
 pub mod grblhal;
+pub mod hold_power;
 pub mod override_manager;
+pub mod resume_ramp;
 pub mod settings;
 pub mod smoothieware;
 pub mod tinyg;
@@ -34,9 +36,14 @@ pub use fluidnc::{FluidNCCapabilities, FluidNCController, FluidNCVersion};
 pub use g2core::{G2CoreCapabilities, G2CoreController, G2CoreVersion as G2CoreVer};
 pub use grbl::GrblCapabilities;
 pub use grblhal::{GrblHalCapabilities, GrblHalVersion};
+pub use hold_power::{hold_power_plan, track_hold_commands, HoldPowerPlan, HoldPowerStep};
 pub use override_manager::{
     DefaultOverrideManager, OverrideManagerTrait, OverrideState, RapidOverrideLevel,
 };
+pub use resume_ramp::{
+    feed_override_bytes, supports_feed_override_bytes, FeedRamp, RampLength, FEED_OVERRIDE_MAX,
+    FEED_OVERRIDE_MIN,
+};
 pub use settings::{DefaultFirmwareSettings, FirmwareSetting, FirmwareSettingsTrait, SettingType};
 pub use smoothieware::{SmoothiewareCapabilities, SmoothiewareController, SmoothiewareVersion};
 pub use tinyg::{TinyGCapabilities, TinyGController, TinyGVersion as TinyGVer};
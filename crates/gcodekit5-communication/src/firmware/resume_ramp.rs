@@ -0,0 +1,180 @@
+//! Easing the feed back in when a job resumes from a feed hold
+//!
+//! Resuming a deep cut at full feed loads the tool all at once. With a resume
+//! ramp configured, the feed override is dropped to a low percentage before
+//! the cycle start and stepped back up to what it was before the pause, over
+//! a time or a distance travelled, using the realtime feed override bytes.
+//! Only GRBL, grblHAL and FluidNC take those bytes; other firmware resumes as
+//! before.
+//!
+//! The override reported by the controller is checked as the ramp runs. The
+//! first report after the drop is latched, wherever the ramp has got to by
+//! then; from there the override can only rise towards what was last sent. Any
+//! other value means the operator is adjusting the override by hand, and the
+//! ramp stops and leaves it to them.
+
+use super::firmware_version::FirmwareType;
+use super::grbl::RealTimeOverrideCommand;
+use std::time::Duration;
+
+/// Lowest feed override GRBL accepts
+pub const FEED_OVERRIDE_MIN: u16 = 10;
+/// Highest feed override GRBL accepts
+pub const FEED_OVERRIDE_MAX: u16 = 200;
+
+/// Whether `firmware` takes the realtime feed override bytes
+pub fn supports_feed_override_bytes(firmware: FirmwareType) -> bool {
+    matches!(
+        firmware,
+        FirmwareType::Grbl | FirmwareType::GrblHal | FirmwareType::FluidNC
+    )
+}
+
+/// Realtime bytes that move the feed override from `from` to `to` percent,
+/// in 10% steps and then 1% steps
+pub fn feed_override_bytes(from: u16, to: u16) -> Vec<u8> {
+    let from = from.clamp(FEED_OVERRIDE_MIN, FEED_OVERRIDE_MAX);
+    let to = to.clamp(FEED_OVERRIDE_MIN, FEED_OVERRIDE_MAX);
+    let (tens, ones) = if to >= from {
+        (
+            RealTimeOverrideCommand::FeedIncrease10,
+            RealTimeOverrideCommand::FeedIncrease1,
+        )
+    } else {
+        (
+            RealTimeOverrideCommand::FeedDecrease10,
+            RealTimeOverrideCommand::FeedDecrease1,
+        )
+    };
+    let difference = from.abs_diff(to) as usize;
+    let mut bytes = vec![tens.as_byte(); difference / 10];
+    bytes.extend(std::iter::repeat_n(ones.as_byte(), difference % 10));
+    bytes
+}
+
+/// How far a resume ramp runs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RampLength {
+    /// Back to full override after this long
+    Time(Duration),
+    /// Back to full override after the tool has moved this far (mm)
+    Distance(f64),
+}
+
+/// A feed override ramp in progress
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedRamp {
+    restore: u16,
+    start: u16,
+    length: RampLength,
+    /// Override before the drop to the starting point
+    before_drop: u16,
+    /// Override the bytes sent so far lead to
+    sent: u16,
+    /// Last override reported after the drop
+    reported: Option<u16>,
+    active: bool,
+    interrupted: bool,
+}
+
+impl FeedRamp {
+    /// A ramp from `start_percent` back to `restore` percent for `firmware`,
+    /// with the override now at `current` percent
+    ///
+    /// `None` when the firmware has no override bytes, when `start_percent` is
+    /// 0 (ramping off) or when it is not below the override to restore.
+    pub fn plan(
+        firmware: FirmwareType,
+        current: u16,
+        restore: u16,
+        start_percent: u16,
+        length: RampLength,
+    ) -> Option<Self> {
+        if !supports_feed_override_bytes(firmware) || start_percent == 0 {
+            return None;
+        }
+        let start = start_percent.max(FEED_OVERRIDE_MIN);
+        if start >= restore {
+            return None;
+        }
+        Some(Self {
+            restore,
+            start,
+            length,
+            before_drop: current,
+            sent: current,
+            reported: None,
+            active: true,
+            interrupted: false,
+        })
+    }
+
+    /// The override the ramp returns to
+    pub fn restore(&self) -> u16 {
+        self.restore
+    }
+
+    /// Whether the ramp still has steps to send
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Whether the ramp stopped because the override was changed by hand
+    pub fn was_interrupted(&self) -> bool {
+        self.interrupted
+    }
+
+    /// The bytes that drop the override to the starting point, sent before
+    /// the cycle start
+    pub fn start_bytes(&mut self) -> Vec<u8> {
+        let bytes = feed_override_bytes(self.sent, self.start);
+        self.sent = self.start;
+        bytes
+    }
+
+    /// The bytes that raise the override to where it should be after
+    /// `elapsed` since the resume with the tool `travelled` mm along
+    ///
+    /// `reported` is the override the controller last reported. Returns
+    /// nothing once the ramp is over, either finished or interrupted.
+    pub fn advance(&mut self, elapsed: Duration, travelled: f64, reported: Option<u16>) -> Vec<u8> {
+        if !self.active {
+            return Vec::new();
+        }
+        if let Some(reported) = reported {
+            let lowest = match self.reported {
+                // Reports of the override before the drop may still arrive
+                None if reported == self.before_drop => None,
+                None => Some(self.start),
+                Some(last) => Some(last),
+            };
+            if let Some(lowest) = lowest {
+                if reported < lowest || reported > self.sent {
+                    self.active = false;
+                    self.interrupted = true;
+                    return Vec::new();
+                }
+                self.reported = Some(reported);
+            }
+        }
+
+        let progress = match self.length {
+            RampLength::Time(duration) if duration.is_zero() => 1.0,
+            RampLength::Time(duration) => elapsed.as_secs_f64() / duration.as_secs_f64(),
+            RampLength::Distance(distance) if distance <= 0.0 => 1.0,
+            RampLength::Distance(distance) => travelled / distance,
+        }
+        .clamp(0.0, 1.0);
+        let span = (self.restore - self.start) as f64;
+        let target = self.start + (span * progress).round() as u16;
+        if progress >= 1.0 {
+            self.active = false;
+        }
+        if target <= self.sent {
+            return Vec::new();
+        }
+        let bytes = feed_override_bytes(self.sent, target);
+        self.sent = target;
+        bytes
+    }
+}
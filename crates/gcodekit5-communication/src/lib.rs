@@ -12,12 +12,14 @@ pub mod error;
 pub mod firmware;
 
 pub use communication::{
+    discover_controllers, discover_in_background,
     serial::{list_ports, SerialPortInfo},
     tcp::TcpConnectionInfo,
     BufferedCommand, BufferedCommunicatorConfig, BufferedCommunicatorWrapper, CommandStatus,
-    Communicator, CommunicatorEvent, CommunicatorListener, CommunicatorListenerHandle,
-    ConnectionDriver, ConnectionParams, NoOpCommunicator, SerialCommunicator, SerialParity,
-    TcpCommunicator,
+    CommandThrottle, Communicator, CommunicatorEvent, CommunicatorListener,
+    CommunicatorListenerHandle, ConnectionDriver, ConnectionParams, DiscoveryOptions,
+    JobCompletionConfig, JobCompletionDetector, JobCompletionReason, LineEnding, NoOpCommunicator,
+    SerialCommunicator, SerialParity, TcpCommunicator, TextDecoder, TextEncoding,
 };
 
 pub use firmware::{CapabilityManager, CapabilityState, ControllerType, FirmwareDetector};
@@ -0,0 +1,108 @@
+//! Tests for firmware::grbl::gcode_parameters
+
+use gcodekit5_communication::firmware::grbl::command_creator::SystemCommand;
+use gcodekit5_communication::firmware::grbl::gcode_parameters::*;
+
+#[test]
+fn test_parse_work_offset_line() {
+    let param = GcodeParameter::parse("[G54:10.000,-5.500,2.000]").expect("parse failed");
+    match param {
+        GcodeParameter::WorkOffset { code, offset } => {
+            assert_eq!(code, "G54");
+            assert_eq!(offset.x, 10.0);
+            assert_eq!(offset.y, -5.5);
+            assert_eq!(offset.z, 2.0);
+        }
+        other => panic!("unexpected parameter: {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_probe_line() {
+    let param = GcodeParameter::parse("[PRB:1.000,2.000,-3.250:1]").expect("parse failed");
+    match param {
+        GcodeParameter::Probe(probe) => {
+            assert!(probe.success);
+            assert_eq!(probe.position.z, -3.25);
+        }
+        other => panic!("unexpected parameter: {:?}", other),
+    }
+
+    let param = GcodeParameter::parse("[PRB:0.000,0.000,0.000:0]").expect("parse failed");
+    assert!(matches!(param, GcodeParameter::Probe(p) if !p.success));
+}
+
+#[test]
+fn test_parse_tool_length_offset() {
+    assert_eq!(
+        GcodeParameter::parse("[TLO:1.500]"),
+        Some(GcodeParameter::ToolLengthOffset(1.5))
+    );
+    // grblHAL reports one value per axis
+    assert_eq!(
+        GcodeParameter::parse("[TLO:0.000,0.000,2.250]"),
+        Some(GcodeParameter::ToolLengthOffset(2.25))
+    );
+}
+
+#[test]
+fn test_unrecognized_lines_ignored() {
+    assert_eq!(GcodeParameter::parse("ok"), None);
+    assert_eq!(GcodeParameter::parse("[MSG:Pgm End]"), None);
+    assert_eq!(GcodeParameter::parse("[HOME:0.000,0.000,0.000:7]"), None);
+    assert_eq!(GcodeParameter::parse("[G54:abc]"), None);
+}
+
+#[test]
+fn test_parse_full_response() {
+    let response = "[G54:1.000,2.000,3.000]\n\
+                    [G55:0.000,0.000,0.000]\n\
+                    [G56:0.000,0.000,0.000]\n\
+                    [G57:0.000,0.000,0.000]\n\
+                    [G58:0.000,0.000,0.000]\n\
+                    [G59:0.000,0.000,0.000]\n\
+                    [G59.1:4.000,5.000,6.000]\n\
+                    [G28:0.000,0.000,-1.000]\n\
+                    [G30:0.000,0.000,-2.000]\n\
+                    [G92:0.500,0.000,0.000]\n\
+                    [HOME:0.000,0.000,0.000:0]\n\
+                    [TLO:0.000]\n\
+                    [PRB:0.000,0.000,-10.000:1]\n\
+                    ok";
+    let params = GcodeParameters::parse(response);
+
+    assert_eq!(params.work_offsets.len(), 7);
+    assert_eq!(params.work_offset("G54").map(|o| o.x), Some(1.0));
+    assert_eq!(params.work_offset("G59.1").map(|o| o.z), Some(6.0));
+    assert_eq!(params.g28.map(|p| p.z), Some(-1.0));
+    assert_eq!(params.g30.map(|p| p.z), Some(-2.0));
+    assert_eq!(params.g92.map(|p| p.x), Some(0.5));
+    assert_eq!(params.tool_length_offset, Some(0.0));
+    assert_eq!(params.probe.map(|p| p.position.z), Some(-10.0));
+}
+
+#[test]
+fn test_parse_line_merges_incrementally() {
+    let mut params = GcodeParameters::new();
+    assert!(params.is_empty());
+    assert!(params.parse_line("[G55:5.000,5.000,5.000]"));
+    assert!(!params.parse_line("[VER:1.1h.20190825:]"));
+    assert!(params.parse_line("[G55:6.000,5.000,5.000]"));
+    assert_eq!(params.work_offset("G55").map(|o| o.x), Some(6.0));
+    assert!(!params.is_empty());
+}
+
+#[test]
+fn test_wcs_index() {
+    assert_eq!(GcodeParameters::wcs_index("G54"), Some(1));
+    assert_eq!(GcodeParameters::wcs_index("G59"), Some(6));
+    assert_eq!(GcodeParameters::wcs_index("G59.3"), Some(9));
+    assert_eq!(GcodeParameters::wcs_index("G53"), None);
+}
+
+#[test]
+fn test_query_parameters_command() {
+    let cmd = SystemCommand::QueryParameters;
+    assert_eq!(cmd.command(), "$#");
+    assert_eq!(cmd.description(), "Query G-code Parameters");
+}
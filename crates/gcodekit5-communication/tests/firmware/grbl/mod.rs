@@ -1,7 +1,9 @@
 mod command_creator;
 mod communicator;
 mod controller;
+mod gcode_parameters;
 mod override_manager;
+mod push_message;
 mod response_parser;
 mod settings;
 mod status_parser;
@@ -185,7 +185,14 @@ fn test_is_overridden() {
 fn test_real_time_override_command_values() {
     assert_eq!(RealTimeOverrideCommand::FeedHold.as_byte(), 0x21);
     assert_eq!(RealTimeOverrideCommand::CycleStart.as_byte(), 0x7E);
-    assert_eq!(RealTimeOverrideCommand::RapidOv100.as_byte(), 0x97);
+    assert_eq!(RealTimeOverrideCommand::FeedReset.as_byte(), 0x90);
+    assert_eq!(RealTimeOverrideCommand::FeedIncrease10.as_byte(), 0x91);
+    assert_eq!(RealTimeOverrideCommand::FeedDecrease10.as_byte(), 0x92);
+    assert_eq!(RealTimeOverrideCommand::FeedIncrease1.as_byte(), 0x93);
+    assert_eq!(RealTimeOverrideCommand::FeedDecrease1.as_byte(), 0x94);
+    assert_eq!(RealTimeOverrideCommand::RapidOv100.as_byte(), 0x95);
+    assert_eq!(RealTimeOverrideCommand::RapidOv50.as_byte(), 0x96);
+    assert_eq!(RealTimeOverrideCommand::RapidOv25.as_byte(), 0x97);
     assert_eq!(RealTimeOverrideCommand::SpindleStop.as_byte(), 0x9E); // Fixed: was 0x9D
 }
 
@@ -0,0 +1,99 @@
+//! Tests for firmware::grbl::push_message
+
+use gcodekit5_communication::firmware::grbl::push_message::*;
+
+#[test]
+fn test_parse_program_end_message() {
+    let msg = GrblPushMessage::parse("[MSG:Pgm End]").expect("parse failed");
+    assert_eq!(msg, GrblPushMessage::Message("Pgm End".to_string()));
+    assert!(msg.is_program_end());
+
+    let other = GrblPushMessage::parse("[MSG:'$H'|'$X' to unlock]").expect("parse failed");
+    assert!(!other.is_program_end());
+}
+
+#[test]
+fn test_parse_parser_state() {
+    let msg =
+        GrblPushMessage::parse("[GC:G0 G54 G17 G21 G90 G94 M5 M9 T0 F0 S0]").expect("parse failed");
+    let GrblPushMessage::ParserState(state) = msg else {
+        panic!("expected parser state");
+    };
+    assert_eq!(state.motion.as_deref(), Some("G0"));
+    assert_eq!(state.wcs.as_deref(), Some("G54"));
+    assert_eq!(state.plane.as_deref(), Some("G17"));
+    assert_eq!(state.units.as_deref(), Some("G21"));
+    assert_eq!(state.distance.as_deref(), Some("G90"));
+    assert_eq!(state.feed_mode.as_deref(), Some("G94"));
+    assert_eq!(state.spindle.as_deref(), Some("M5"));
+    assert_eq!(state.coolant.as_deref(), Some("M9"));
+    assert_eq!(state.tool, Some(0));
+    assert_eq!(state.feed_rate, Some(0.0));
+    assert!(!state.is_inches());
+    assert!(!state.is_incremental());
+}
+
+#[test]
+fn test_parse_parser_state_variants() {
+    let state = ParserModalState::parse("G38.2 G59.1 G18 G20 G91 G93 M3 M7 M8 T2 F500. S12000.");
+    assert_eq!(state.motion.as_deref(), Some("G38.2"));
+    assert_eq!(state.wcs.as_deref(), Some("G59.1"));
+    assert_eq!(state.plane.as_deref(), Some("G18"));
+    assert!(state.is_inches());
+    assert!(state.is_incremental());
+    assert_eq!(state.coolant.as_deref(), Some("M7 M8"));
+    assert_eq!(state.tool, Some(2));
+    assert_eq!(state.feed_rate, Some(500.0));
+    assert_eq!(state.spindle_speed, Some(12000.0));
+}
+
+#[test]
+fn test_parse_other_bracket_messages() {
+    assert_eq!(
+        GrblPushMessage::parse("[echo:G1X10]"),
+        Some(GrblPushMessage::Echo("G1X10".to_string()))
+    );
+    assert_eq!(
+        GrblPushMessage::parse("[VER:1.1h.20190825:]"),
+        Some(GrblPushMessage::Version("1.1h.20190825:".to_string()))
+    );
+    assert_eq!(
+        GrblPushMessage::parse("[OPT:V,15,128]"),
+        Some(GrblPushMessage::Options("V,15,128".to_string()))
+    );
+    assert_eq!(
+        GrblPushMessage::parse("[FOO:bar]"),
+        Some(GrblPushMessage::Other("[FOO:bar]".to_string()))
+    );
+    assert_eq!(
+        GrblPushMessage::parse("[no colon]"),
+        Some(GrblPushMessage::Other("[no colon]".to_string()))
+    );
+}
+
+#[test]
+fn test_parse_startup_block() {
+    assert_eq!(
+        GrblPushMessage::parse(">G54G20:ok"),
+        Some(GrblPushMessage::StartupBlock {
+            block: "G54G20".to_string(),
+            ok: true,
+            error: None,
+        })
+    );
+    assert_eq!(
+        GrblPushMessage::parse(">G5X:error:20"),
+        Some(GrblPushMessage::StartupBlock {
+            block: "G5X".to_string(),
+            ok: false,
+            error: Some(20),
+        })
+    );
+}
+
+#[test]
+fn test_non_push_lines() {
+    assert_eq!(GrblPushMessage::parse("ok"), None);
+    assert_eq!(GrblPushMessage::parse("<Idle|MPos:0,0,0>"), None);
+    assert_eq!(GrblPushMessage::parse("error:20"), None);
+}
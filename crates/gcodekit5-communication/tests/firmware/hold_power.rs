@@ -0,0 +1,119 @@
+use gcodekit5_communication::firmware::firmware_version::FirmwareType;
+use gcodekit5_communication::firmware::hold_power::*;
+use gcodekit5_core::{CommandTracker, GcodeCommand, HoldPowerOff, SpindlePower};
+
+fn running(line: &str) -> SpindlePower {
+    let mut power = SpindlePower::default();
+    power.observe(line);
+    power
+}
+
+#[test]
+fn grbl_laser_mode_needs_nothing() {
+    let power = running("M4 S800");
+    for firmware in [
+        FirmwareType::Grbl,
+        FirmwareType::GrblHal,
+        FirmwareType::FluidNC,
+    ] {
+        assert!(hold_power_plan(firmware, true, HoldPowerOff::SpindleStop, &power).is_empty());
+    }
+}
+
+#[test]
+fn grbl_spindle_uses_the_stop_override() {
+    let plan = hold_power_plan(
+        FirmwareType::Grbl,
+        false,
+        HoldPowerOff::ZeroPower,
+        &running("M3 S12000"),
+    );
+    assert_eq!(
+        plan.on_hold,
+        vec![HoldPowerStep::Realtime(GRBL_SPINDLE_STOP_OVERRIDE)]
+    );
+    // GRBL brings the spindle back itself on cycle start
+    assert!(plan.on_resume.is_empty());
+}
+
+#[test]
+fn other_firmware_sends_commands_and_restores_the_speed() {
+    let power = running("M4 S650");
+    let plan = hold_power_plan(FirmwareType::G2Core, true, HoldPowerOff::ZeroPower, &power);
+    assert_eq!(plan.on_hold, vec![HoldPowerStep::Command("S0".to_string())]);
+    assert_eq!(
+        plan.on_resume,
+        vec![HoldPowerStep::Command("S650".to_string())]
+    );
+
+    let plan = hold_power_plan(
+        FirmwareType::Smoothieware,
+        false,
+        HoldPowerOff::SpindleStop,
+        &power,
+    );
+    assert_eq!(plan.on_hold, vec![HoldPowerStep::Command("M5".to_string())]);
+    assert_eq!(
+        plan.on_resume,
+        vec![HoldPowerStep::Command("M4 S650".to_string())]
+    );
+}
+
+#[test]
+fn nothing_is_sent_when_disabled_or_not_restorable() {
+    let power = running("M3 S1000");
+    assert!(hold_power_plan(FirmwareType::Grbl, false, HoldPowerOff::Disabled, &power).is_empty());
+    // Spindle already off
+    assert!(hold_power_plan(
+        FirmwareType::TinyG,
+        false,
+        HoldPowerOff::SpindleStop,
+        &running("M5")
+    )
+    .is_empty());
+    // S0 could not be undone without knowing the speed
+    assert!(hold_power_plan(
+        FirmwareType::TinyG,
+        false,
+        HoldPowerOff::ZeroPower,
+        &running("M3")
+    )
+    .is_empty());
+}
+
+#[test]
+fn pause_and_resume_leave_job_acks_balanced() {
+    let power = running("M3 S1000");
+    let plan = hold_power_plan(
+        FirmwareType::TinyG,
+        false,
+        HoldPowerOff::SpindleStop,
+        &power,
+    );
+    let mut tracker = CommandTracker::default();
+    let mut job_acks = 0;
+    let mut ok = |tracker: &mut CommandTracker| {
+        let answered = tracker.handle_ok().expect("a tracked command");
+        if answered.line_number.is_some() {
+            job_acks += 1;
+        }
+    };
+    let job_line = |line: &str, index: u32| {
+        let mut command = GcodeCommand::new(line);
+        command.set_line_number(index);
+        command
+    };
+
+    // Paused with a job line in flight, then resumed
+    tracker.track_sent(job_line("G1 X10 F500", 0));
+    track_hold_commands(&mut tracker, &plan.on_hold);
+    ok(&mut tracker);
+    ok(&mut tracker);
+    track_hold_commands(&mut tracker, &plan.on_resume);
+    ok(&mut tracker);
+    tracker.track_sent(job_line("G1 X20", 1));
+    ok(&mut tracker);
+
+    assert_eq!(job_acks, 2);
+    assert_eq!(tracker.outstanding_count(), 0);
+}
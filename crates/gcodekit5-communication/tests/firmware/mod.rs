@@ -9,6 +9,8 @@ mod firmware_version;
 mod fluidnc;
 mod g2core_capabilities;
 mod grbl;
+mod hold_power;
 mod override_manager;
+mod resume_ramp;
 mod settings_test;
 mod smoothieware;
@@ -0,0 +1,114 @@
+use gcodekit5_communication::firmware::firmware_version::FirmwareType;
+use gcodekit5_communication::firmware::resume_ramp::*;
+use std::time::Duration;
+
+const UP10: u8 = 0x91;
+const DOWN10: u8 = 0x92;
+const UP1: u8 = 0x93;
+const DOWN1: u8 = 0x94;
+
+fn ramp(current: u16, restore: u16) -> FeedRamp {
+    FeedRamp::plan(
+        FirmwareType::Grbl,
+        current,
+        restore,
+        30,
+        RampLength::Time(Duration::from_secs(2)),
+    )
+    .unwrap()
+}
+
+#[test]
+fn override_bytes_step_by_tens_then_ones() {
+    assert_eq!(
+        feed_override_bytes(100, 73),
+        vec![DOWN10, DOWN10, DOWN1, DOWN1, DOWN1, DOWN1, DOWN1, DOWN1, DOWN1]
+    );
+    assert_eq!(feed_override_bytes(30, 51), vec![UP10, UP10, UP1]);
+    assert_eq!(feed_override_bytes(5, 10), Vec::<u8>::new());
+}
+
+#[test]
+fn ramp_drops_then_restores_the_override_before_the_pause() {
+    let mut ramp = ramp(100, 120);
+    assert_eq!(ramp.start_bytes(), feed_override_bytes(100, 30));
+
+    // Halfway through, stale reports of the old override are ignored
+    let half = ramp.advance(Duration::from_secs(1), 0.0, Some(100));
+    assert_eq!(half, feed_override_bytes(30, 75));
+    assert!(ramp.is_active());
+    assert!(ramp
+        .advance(Duration::from_secs(1), 0.0, Some(30))
+        .is_empty());
+
+    let rest = ramp.advance(Duration::from_secs(3), 0.0, Some(75));
+    assert_eq!(rest, feed_override_bytes(75, 120));
+    assert!(!ramp.is_active());
+    assert!(!ramp.was_interrupted());
+
+    // Distance ramps follow the distance travelled
+    let mut ramp = FeedRamp::plan(
+        FirmwareType::FluidNC,
+        100,
+        100,
+        50,
+        RampLength::Distance(10.0),
+    )
+    .unwrap();
+    ramp.start_bytes();
+    assert_eq!(
+        ramp.advance(Duration::from_secs(60), 5.0, None),
+        feed_override_bytes(50, 75)
+    );
+}
+
+#[test]
+fn ramp_leaves_manual_overrides_and_other_firmware_alone() {
+    let mut ramp = ramp(100, 100);
+    ramp.start_bytes();
+    assert_eq!(
+        ramp.advance(Duration::from_millis(500), 0.0, Some(30)),
+        feed_override_bytes(30, 48)
+    );
+    assert!(!ramp
+        .advance(Duration::from_millis(600), 0.0, Some(48))
+        .is_empty());
+    // The operator pressed -10% after the override reached 48%
+    assert!(ramp
+        .advance(Duration::from_millis(700), 0.0, Some(38))
+        .is_empty());
+    assert!(ramp.was_interrupted());
+    assert!(ramp
+        .advance(Duration::from_secs(5), 0.0, Some(38))
+        .is_empty());
+
+    let length = RampLength::Time(Duration::from_secs(1));
+    assert!(FeedRamp::plan(FirmwareType::TinyG, 100, 100, 30, length).is_none());
+    assert!(FeedRamp::plan(FirmwareType::Grbl, 100, 100, 0, length).is_none());
+    assert!(FeedRamp::plan(FirmwareType::Grbl, 20, 20, 30, length).is_none());
+}
+
+#[test]
+fn ramp_latches_the_first_report_even_past_the_starting_point() {
+    let mut skipped = ramp(100, 100);
+    skipped.start_bytes();
+    skipped.advance(Duration::from_millis(500), 0.0, None);
+    // The first report after the drop already shows part of the ramp
+    assert!(!skipped
+        .advance(Duration::from_millis(600), 0.0, Some(40))
+        .is_empty());
+    assert!(skipped.is_active());
+    // The operator pressed -10%, though 30% itself was never reported
+    assert!(skipped
+        .advance(Duration::from_millis(700), 0.0, Some(32))
+        .is_empty());
+    assert!(skipped.was_interrupted());
+
+    // A first report above anything sent is a manual change too
+    let mut raised = ramp(100, 100);
+    raised.start_bytes();
+    assert!(raised
+        .advance(Duration::from_millis(100), 0.0, Some(110))
+        .is_empty());
+    assert!(raised.was_interrupted());
+}
@@ -0,0 +1,127 @@
+use gcodekit5_communication::{JobCompletionConfig, JobCompletionDetector, JobCompletionReason};
+use std::time::{Duration, Instant};
+
+fn detector() -> JobCompletionDetector {
+    JobCompletionDetector::new(JobCompletionConfig {
+        idle_debounce: Duration::from_millis(500),
+        status_timeout: Duration::from_secs(5),
+    })
+}
+
+#[test]
+fn test_program_end_line_detection() {
+    assert!(JobCompletionDetector::is_program_end_line("M30"));
+    assert!(JobCompletionDetector::is_program_end_line("m2"));
+    assert!(JobCompletionDetector::is_program_end_line(
+        "G0 X0 M30 ; end"
+    ));
+    assert!(!JobCompletionDetector::is_program_end_line("M3 S1000"));
+    assert!(!JobCompletionDetector::is_program_end_line("M20"));
+    assert!(!JobCompletionDetector::is_program_end_line(
+        "G1 X1 (M30 later)"
+    ));
+}
+
+#[test]
+fn test_completes_on_program_end_ack() {
+    let mut det = detector();
+    det.start(&["G1 X10", "M30"]);
+    assert!(det.expects_program_end());
+
+    let t0 = Instant::now();
+    det.on_line_sent("G1 X10");
+    det.on_ack();
+    det.on_line_sent("M30");
+    det.on_queue_drained(t0);
+    assert_eq!(det.poll(t0), None);
+
+    det.on_ack();
+    assert_eq!(det.poll(t0), Some(JobCompletionReason::ProgramEnd));
+    assert!(!det.is_active());
+    assert_eq!(det.poll(t0), None);
+}
+
+#[test]
+fn test_program_end_message_completes() {
+    let mut det = detector();
+    det.start(&["G1 X10", "M2"]);
+    let t0 = Instant::now();
+    det.on_queue_drained(t0);
+    det.on_program_end();
+    assert_eq!(det.poll(t0), Some(JobCompletionReason::ProgramEnd));
+}
+
+#[test]
+fn test_idle_requires_debounce_after_drain() {
+    let mut det = detector();
+    det.start(&["G1 X10", "G1 Y10"]);
+    let t0 = Instant::now();
+
+    // Idle before draining does not count.
+    det.on_state("Idle", t0);
+    det.on_queue_drained(t0 + Duration::from_secs(1));
+    det.on_state("Run", t0 + Duration::from_millis(1100));
+    assert_eq!(det.poll(t0 + Duration::from_millis(1200)), None);
+
+    det.on_state("Idle", t0 + Duration::from_secs(2));
+    assert_eq!(det.poll(t0 + Duration::from_millis(2200)), None);
+    det.on_state("Idle", t0 + Duration::from_millis(2400));
+    assert_eq!(
+        det.poll(t0 + Duration::from_millis(2600)),
+        Some(JobCompletionReason::Idle)
+    );
+}
+
+#[test]
+fn test_busy_state_resets_debounce() {
+    let mut det = detector();
+    det.start(&["G1 X10"]);
+    let t0 = Instant::now();
+    det.on_queue_drained(t0);
+    det.on_state("Idle", t0);
+    det.on_state("Hold:0", t0 + Duration::from_millis(300));
+    det.on_state("Run", t0 + Duration::from_millis(600));
+    assert_eq!(det.poll(t0 + Duration::from_millis(700)), None);
+}
+
+#[test]
+fn test_expected_program_end_falls_back_to_idle_timeout() {
+    let mut det = detector();
+    det.start(&["G1 X10", "M30"]);
+    let t0 = Instant::now();
+    det.on_queue_drained(t0);
+    det.on_state("Idle", t0);
+    assert_eq!(det.poll(t0 + Duration::from_secs(1)), None);
+    det.on_state("Idle", t0 + Duration::from_secs(4));
+    assert_eq!(
+        det.poll(t0 + Duration::from_secs(6)),
+        Some(JobCompletionReason::Idle)
+    );
+}
+
+#[test]
+fn test_status_timeout_when_controller_goes_silent() {
+    let mut det = detector();
+    det.start(&["G1 X10"]);
+    let t0 = Instant::now();
+    det.on_state("Run", t0);
+    det.on_queue_drained(t0);
+    assert_eq!(det.poll(t0 + Duration::from_secs(4)), None);
+    assert_eq!(
+        det.poll(t0 + Duration::from_secs(6)),
+        Some(JobCompletionReason::StatusTimeout)
+    );
+}
+
+#[test]
+fn test_not_complete_before_drain_or_after_reset() {
+    let mut det = detector();
+    det.start(&["G1 X10"]);
+    let t0 = Instant::now();
+    det.on_state("Idle", t0);
+    assert_eq!(det.poll(t0 + Duration::from_secs(60)), None);
+
+    det.on_queue_drained(t0);
+    det.reset();
+    assert_eq!(det.poll(t0 + Duration::from_secs(60)), None);
+}
@@ -0,0 +1,97 @@
+use gcodekit5_communication::communication::command_bytes;
+use gcodekit5_communication::{
+    Communicator, CommunicatorListenerHandle, ConnectionParams, LineEnding, TextDecoder,
+    TextEncoding,
+};
+
+// Records every write as raw bytes
+struct RecordingCommunicator {
+    sent: Vec<Vec<u8>>,
+    params: Option<ConnectionParams>,
+}
+
+impl Communicator for RecordingCommunicator {
+    fn connect(&mut self, params: &ConnectionParams) -> gcodekit5_core::Result<()> {
+        self.params = Some(params.clone());
+        Ok(())
+    }
+
+    fn disconnect(&mut self) -> gcodekit5_core::Result<()> {
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.params.is_some()
+    }
+
+    fn send(&mut self, data: &[u8]) -> gcodekit5_core::Result<usize> {
+        self.sent.push(data.to_vec());
+        Ok(data.len())
+    }
+
+    fn receive(&mut self) -> gcodekit5_core::Result<Vec<u8>> {
+        Ok(vec![])
+    }
+
+    fn add_listener(&mut self, _listener: CommunicatorListenerHandle) {}
+    fn remove_listener(&mut self, _listener: &CommunicatorListenerHandle) {}
+    fn connection_params(&self) -> Option<&ConnectionParams> {
+        self.params.as_ref()
+    }
+    fn set_connection_params(&mut self, params: ConnectionParams) -> gcodekit5_core::Result<()> {
+        self.params = Some(params);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_send_command_uses_configured_line_ending() {
+    let mut comm = RecordingCommunicator {
+        sent: Vec::new(),
+        params: None,
+    };
+    let params = ConnectionParams {
+        line_ending: LineEnding::CrLf,
+        ..ConnectionParams::serial("/dev/ttyUSB0", 115200)
+    };
+    comm.connect(&params).unwrap();
+
+    comm.send_command("G0 X10").unwrap();
+    comm.send_command("$H\n").unwrap();
+    comm.send_command("?").unwrap();
+    comm.send_command("\u{85}").unwrap();
+
+    let sent: Vec<u8> = comm.sent.concat();
+    assert_eq!(sent, b"G0 X10\r\n$H\r\n?\x85".to_vec());
+    assert_eq!(command_bytes("\x18", LineEnding::Cr), vec![0x18]);
+    assert_eq!(
+        command_bytes("G1 X1\r\n", LineEnding::Cr),
+        b"G1 X1\r".to_vec()
+    );
+}
+
+#[test]
+fn test_decoder_handles_split_and_invalid_utf8() {
+    let mut decoder = TextDecoder::new(TextEncoding::Utf8);
+    // "°" is 0xC2 0xB0, split across two reads
+    assert_eq!(decoder.decode(b"[MSG:25\xc2"), "[MSG:25");
+    assert_eq!(decoder.decode(b"\xb0C]\n"), "°C]\n");
+    assert_eq!(decoder.decode(b"ok\xff\n"), "ok\u{fffd}\n");
+    assert_eq!(decoder.decode(b"\xe2\x82"), "");
+    assert_eq!(decoder.flush(), "\u{fffd}");
+
+    let mut latin1 = TextDecoder::new(TextEncoding::Latin1);
+    assert_eq!(latin1.decode(b"25\xb0C"), "25°C");
+}
+
+#[test]
+fn test_connection_params_default_line_format() {
+    let params: ConnectionParams = serde_json::from_str(
+        r#"{"driver":"serial","port":"/dev/ttyACM0","network_port":0,"baud_rate":115200,
+        "timeout_ms":5000,"flow_control":false,"data_bits":8,"stop_bits":1,"parity":"none",
+        "auto_reconnect":true,"max_retries":3}"#,
+    )
+    .unwrap();
+    assert_eq!(params.line_ending, LineEnding::Lf);
+    assert_eq!(params.encoding, TextEncoding::Utf8);
+}
@@ -0,0 +1,72 @@
+use gcodekit5_communication::{
+    CommandThrottle, Communicator, ConnectionDriver, ConnectionParams, TcpCommunicator,
+};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_throttle_spaces_commands_and_waits_after_reset() {
+    let params = ConnectionParams {
+        command_delay_ms: 20,
+        reset_settle_ms: 300,
+        ..Default::default()
+    };
+    let mut throttle = CommandThrottle::from_params(&params);
+    assert!(throttle.is_enabled());
+
+    let start = Instant::now();
+    assert_eq!(throttle.wait_time(start), Duration::ZERO);
+    throttle.note_command(start);
+    assert_eq!(throttle.wait_time(start), Duration::from_millis(20));
+    assert_eq!(
+        throttle.wait_time(start + Duration::from_millis(15)),
+        Duration::from_millis(5)
+    );
+    assert_eq!(
+        throttle.wait_time(start + Duration::from_millis(40)),
+        Duration::ZERO
+    );
+
+    // The settle time after a reset outlasts the command spacing
+    throttle.note_reset(start);
+    assert_eq!(
+        throttle.wait_time(start + Duration::from_millis(100)),
+        Duration::from_millis(200)
+    );
+}
+
+#[test]
+fn test_default_throttle_never_waits() {
+    let mut throttle = CommandThrottle::from_params(&ConnectionParams::default());
+    assert!(!throttle.is_enabled());
+    let now = Instant::now();
+    throttle.note_command(now);
+    throttle.note_reset(now);
+    assert_eq!(throttle.wait_time(now), Duration::ZERO);
+}
+
+#[test]
+fn test_send_command_reports_the_wait_instead_of_blocking() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let params = ConnectionParams {
+        driver: ConnectionDriver::Tcp,
+        port: "127.0.0.1".to_string(),
+        network_port: listener.local_addr().unwrap().port(),
+        command_delay_ms: 1_000,
+        reset_settle_ms: 10_000,
+        ..Default::default()
+    };
+    let mut comm = TcpCommunicator::new();
+    comm.connect(&params).unwrap();
+    let _server = listener.accept().unwrap();
+    assert_eq!(comm.command_wait_time(), Duration::ZERO);
+
+    let start = Instant::now();
+    comm.send_command("G0 X1").unwrap();
+    comm.send_command("G0 X2").unwrap();
+    assert!(start.elapsed() < Duration::from_millis(500));
+    assert!(comm.command_wait_time() > Duration::from_millis(500));
+
+    // A soft reset holds the next line back for the settle time
+    comm.send(&[0x18]).unwrap();
+    assert!(comm.command_wait_time() > Duration::from_secs(5));
+}
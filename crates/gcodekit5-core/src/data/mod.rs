@@ -9,12 +9,16 @@
 //! - Unit management (MM, INCH)
 //! - Materials database with cutting parameters
 //! - Tools palette for CAM operations
+//! - Probe meshes of the work surface
 
 pub mod gtc_import;
 pub mod materials;
 pub mod materials_mpi_static;
+pub mod probe_mesh;
 pub mod tools;
 
+pub use probe_mesh::{HeightPoint, ProbeMesh};
+
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -0,0 +1,80 @@
+//! Auto-leveling probe mesh
+//!
+//! Heights probed over a grid on the work surface, for leveling a job to the
+//! surface and for checking how flat a surfaced board came out.
+
+use serde::{Deserialize, Serialize};
+
+/// Height map data point
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct HeightPoint {
+    /// X coordinate
+    pub x: f64,
+    /// Y coordinate
+    pub y: f64,
+    /// Z height at this point
+    pub z: f64,
+}
+
+/// Probe mesh for auto-leveling
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProbeMesh {
+    /// Grid points
+    pub points: Vec<HeightPoint>,
+    /// Grid X spacing
+    pub x_spacing: f64,
+    /// Grid Y spacing
+    pub y_spacing: f64,
+    /// Minimum Z found
+    pub z_min: f64,
+    /// Maximum Z found
+    pub z_max: f64,
+}
+
+impl ProbeMesh {
+    /// Create new probe mesh
+    pub fn new(x_spacing: f64, y_spacing: f64) -> Self {
+        Self {
+            points: Vec::new(),
+            x_spacing,
+            y_spacing,
+            z_min: f64::MAX,
+            z_max: f64::MIN,
+        }
+    }
+
+    /// Add probe point
+    pub fn add_point(&mut self, point: HeightPoint) {
+        self.z_min = self.z_min.min(point.z);
+        self.z_max = self.z_max.max(point.z);
+        self.points.push(point);
+    }
+
+    /// Get Z offset at position (interpolated)
+    pub fn get_z_offset(&self, x: f64, y: f64) -> Option<f64> {
+        if self.points.is_empty() {
+            return None;
+        }
+
+        // Find 4 nearest points for bilinear interpolation
+        let mut nearest = self
+            .points
+            .iter()
+            .map(|p| (p, (p.x - x).powi(2) + (p.y - y).powi(2)))
+            .collect::<Vec<_>>();
+        nearest.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        if nearest.len() < 4 {
+            return Some(nearest[0].0.z);
+        }
+
+        // Simple average of 4 nearest
+        let avg = nearest[..4].iter().map(|(p, _)| p.z).sum::<f64>() / 4.0;
+        Some(avg)
+    }
+
+    /// Get mesh statistics
+    pub fn stats(&self) -> (usize, f64, f64) {
+        (self.points.len(), self.z_min, self.z_max)
+    }
+}
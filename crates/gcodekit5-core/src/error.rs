@@ -167,6 +167,13 @@ pub enum GcodeError {
         reason: String,
     },
 
+    /// Arc geometry cannot be resolved (e.g. R too small to span the chord)
+    #[error("Invalid arc: {reason}")]
+    InvalidArc {
+        /// The reason the arc is invalid.
+        reason: String,
+    },
+
     /// Tool not found
     #[error("Tool {tool_number} not found")]
     ToolNotFound {
@@ -0,0 +1,291 @@
+//! Arc geometry for G2/G3 moves
+//!
+//! Arcs are computed in the active plane selected by G17/G18/G19. Each plane is
+//! described by its two in-plane axes, ordered so that the plane normal follows the
+//! right-hand rule (XY → +Z, ZX → +Y, YZ → +X); with that ordering G2 is always a
+//! negative (clockwise) sweep and G3 a positive one. The remaining axis is the
+//! plane's linear axis.
+//!
+//! When the linear axis also changes the move is a helix: the linear axis is
+//! interpolated in proportion to the swept angle, so expanded points climb (or
+//! descend) evenly and the final point is the commanded end.
+//!
+//! R-word arcs are resolved to a center with [`ArcMove::from_radius`]: of the two
+//! circles through start and end, a positive R selects the arc of at most 180° and a
+//! negative R the longer one.
+
+use crate::error::GcodeError;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::TAU;
+
+/// Tolerance for treating an arc's start and end as the same point (full circle)
+const FULL_CIRCLE_EPSILON: f64 = 1e-6;
+/// Relative tolerance for an R that is only just shorter than half the chord
+/// (rounded semicircles); such arcs are treated as exact semicircles
+const RADIUS_TOLERANCE: f64 = 1e-6;
+
+/// Active arc plane (modal group 2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ArcPlane {
+    /// G17: XY plane, offsets I/J
+    #[default]
+    XY,
+    /// G18: ZX plane, offsets K/I
+    ZX,
+    /// G19: YZ plane, offsets J/K
+    YZ,
+}
+
+impl ArcPlane {
+    /// Plane for a G-code number (17, 18 or 19)
+    pub fn from_gcode(code: u8) -> Option<Self> {
+        match code {
+            17 => Some(Self::XY),
+            18 => Some(Self::ZX),
+            19 => Some(Self::YZ),
+            _ => None,
+        }
+    }
+
+    /// G-code number selecting this plane
+    pub fn gcode(&self) -> u8 {
+        match self {
+            Self::XY => 17,
+            Self::ZX => 18,
+            Self::YZ => 19,
+        }
+    }
+
+    /// Axis indices (0 = X, 1 = Y, 2 = Z) as (first, second, linear)
+    pub fn axes(&self) -> (usize, usize, usize) {
+        match self {
+            Self::XY => (0, 1, 2),
+            Self::ZX => (2, 0, 1),
+            Self::YZ => (1, 2, 0),
+        }
+    }
+
+    /// Offset words for the (first, second) in-plane axes
+    pub fn offset_words(&self) -> (char, char) {
+        match self {
+            Self::XY => ('I', 'J'),
+            Self::ZX => ('K', 'I'),
+            Self::YZ => ('J', 'K'),
+        }
+    }
+}
+
+/// A G2/G3 move with a resolved center
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArcMove {
+    /// Start point (X, Y, Z)
+    pub start: [f64; 3],
+    /// End point (X, Y, Z)
+    pub end: [f64; 3],
+    /// Arc center; the linear-axis component is ignored
+    pub center: [f64; 3],
+    /// G2 (true) or G3 (false)
+    pub clockwise: bool,
+    /// Plane the arc lies in
+    pub plane: ArcPlane,
+}
+
+impl ArcMove {
+    /// Build an arc from I/J/K center offsets relative to the start point.
+    ///
+    /// Only the offsets belonging to the plane are used (I/J for G17, K/I for G18,
+    /// J/K for G19); `offsets` is indexed like the axes, i.e. `[I, J, K]`.
+    pub fn from_offsets(
+        start: [f64; 3],
+        end: [f64; 3],
+        offsets: [f64; 3],
+        clockwise: bool,
+        plane: ArcPlane,
+    ) -> Self {
+        let (a, b, _) = plane.axes();
+        let mut center = start;
+        center[a] += offsets[a];
+        center[b] += offsets[b];
+        Self {
+            start,
+            end,
+            center,
+            clockwise,
+            plane,
+        }
+    }
+
+    /// Build an arc from an R-word radius.
+    ///
+    /// Returns [`GcodeError::InvalidArc`] when start and end coincide in the plane (a
+    /// full circle cannot be given with R) or when |R| is smaller than half the
+    /// distance between start and end.
+    pub fn from_radius(
+        start: [f64; 3],
+        end: [f64; 3],
+        radius: f64,
+        clockwise: bool,
+        plane: ArcPlane,
+    ) -> Result<Self, GcodeError> {
+        let (a, b, _) = plane.axes();
+        let dx = end[a] - start[a];
+        let dy = end[b] - start[b];
+        let chord = dx.hypot(dy);
+
+        if !radius.is_finite() || radius == 0.0 {
+            return Err(GcodeError::InvalidArc {
+                reason: format!("radius R{} is not usable", radius),
+            });
+        }
+        if chord < FULL_CIRCLE_EPSILON {
+            return Err(GcodeError::InvalidArc {
+                reason: "R-format arc needs an end point different from the start".to_string(),
+            });
+        }
+
+        let mut discriminant = 4.0 * radius * radius - chord * chord;
+        if discriminant < 0.0 {
+            if -discriminant > RADIUS_TOLERANCE * chord * chord {
+                return Err(GcodeError::InvalidArc {
+                    reason: format!(
+                        "radius R{} is too small to reach the end point ({:.4} away; needs at least {:.4})",
+                        radius,
+                        chord,
+                        chord / 2.0
+                    ),
+                });
+            }
+            discriminant = 0.0;
+        }
+
+        // Distance from the chord midpoint to the center (scaled by 2 / chord); the
+        // sign picks the side of the chord so +R gives the shorter arc.
+        let mut h = -discriminant.sqrt() / chord;
+        if !clockwise {
+            h = -h;
+        }
+        if radius < 0.0 {
+            h = -h;
+        }
+
+        let mut center = start;
+        center[a] += 0.5 * (dx - dy * h);
+        center[b] += 0.5 * (dy + dx * h);
+        Ok(Self {
+            start,
+            end,
+            center,
+            clockwise,
+            plane,
+        })
+    }
+
+    /// Radius measured from the start point
+    pub fn radius(&self) -> f64 {
+        let (a, b, _) = self.plane.axes();
+        (self.start[a] - self.center[a]).hypot(self.start[b] - self.center[b])
+    }
+
+    /// Signed sweep angle in radians (negative for clockwise).
+    ///
+    /// An arc ending where it started is a full circle.
+    pub fn sweep(&self) -> f64 {
+        let (a, b, _) = self.plane.axes();
+        let start_angle = self.angle_of(&self.start);
+        let end_angle = self.angle_of(&self.end);
+        let closed = (self.end[a] - self.start[a]).abs() < FULL_CIRCLE_EPSILON
+            && (self.end[b] - self.start[b]).abs() < FULL_CIRCLE_EPSILON;
+
+        let mut sweep = end_angle - start_angle;
+        if self.clockwise {
+            if sweep >= 0.0 {
+                sweep -= TAU;
+            }
+            if closed {
+                sweep = -TAU;
+            }
+        } else {
+            if sweep <= 0.0 {
+                sweep += TAU;
+            }
+            if closed {
+                sweep = TAU;
+            }
+        }
+        sweep
+    }
+
+    /// Travel along the linear axis (non-zero for helical moves)
+    pub fn linear_travel(&self) -> f64 {
+        let (_, _, l) = self.plane.axes();
+        self.end[l] - self.start[l]
+    }
+
+    /// Path length, including the linear-axis travel of a helix
+    pub fn length(&self) -> f64 {
+        (self.radius() * self.sweep().abs()).hypot(self.linear_travel())
+    }
+
+    /// Number of line segments needed so no segment is longer than
+    /// `max_segment_length`; scales with both the sweep and any helical travel
+    pub fn segments_for(&self, max_segment_length: f64) -> usize {
+        let length = self.length();
+        if !length.is_finite() || max_segment_length <= 0.0 {
+            return 1;
+        }
+        ((length / max_segment_length).ceil() as usize).max(1)
+    }
+
+    /// Points along the arc after the start point, ending exactly at `end`.
+    ///
+    /// The linear axis moves linearly with the sweep, so helical moves expand to a
+    /// true helix.
+    pub fn expand(&self, segments: usize) -> Vec<[f64; 3]> {
+        let segments = segments.max(1);
+        let (a, b, l) = self.plane.axes();
+        let radius = self.radius();
+        let start_angle = self.angle_of(&self.start);
+        let sweep = self.sweep();
+        let linear_travel = self.linear_travel();
+
+        let mut points = Vec::with_capacity(segments);
+        for i in 1..segments {
+            let t = i as f64 / segments as f64;
+            let angle = start_angle + sweep * t;
+            let mut point = self.start;
+            point[a] = self.center[a] + radius * angle.cos();
+            point[b] = self.center[b] + radius * angle.sin();
+            point[l] = self.start[l] + linear_travel * t;
+            points.push(point);
+        }
+        points.push(self.end);
+        points
+    }
+
+    /// Split into `pieces` consecutive arcs of equal sweep around the same center.
+    ///
+    /// The joints are points of [`ArcMove::expand`], so they lie on this arc, each
+    /// piece starts where the previous one ends and the last ends exactly at `end`.
+    /// Direction, plane and any helical travel are kept.
+    pub fn split(&self, pieces: usize) -> Vec<ArcMove> {
+        let mut start = self.start;
+        self.expand(pieces)
+            .into_iter()
+            .map(|end| {
+                let piece = ArcMove {
+                    start,
+                    end,
+                    ..*self
+                };
+                start = end;
+                piece
+            })
+            .collect()
+    }
+
+    /// Angle of a point around the center in plane coordinates
+    fn angle_of(&self, point: &[f64; 3]) -> f64 {
+        let (a, b, _) = self.plane.axes();
+        (point[b] - self.center[b]).atan2(point[a] - self.center[a])
+    }
+}
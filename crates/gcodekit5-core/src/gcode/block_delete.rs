@@ -0,0 +1,29 @@
+//! Block delete (`/`) handling.
+//!
+//! A line starting with `/` is an optional block. With the block delete switch on,
+//! the controller skips it; with the switch off it runs as if the `/` were not
+//! there.
+
+/// Character that marks an optional block at the start of a line
+pub const BLOCK_DELETE_CHAR: char = '/';
+
+/// Split the block delete prefix off a line.
+///
+/// Returns the line without its prefix (and the whitespace around it) and whether
+/// it had one. Lines without a prefix are returned unchanged.
+pub fn split_block_delete(line: &str) -> (&str, bool) {
+    match line.trim_start().strip_prefix(BLOCK_DELETE_CHAR) {
+        Some(rest) => (rest.trim_start(), true),
+        None => (line, false),
+    }
+}
+
+/// The text of a line to run with the block delete switch in the given position.
+///
+/// Returns `None` when the switch is on and the line is an optional block.
+pub fn apply_block_delete(line: &str, block_delete: bool) -> Option<&str> {
+    match split_block_delete(line) {
+        (_, true) if block_delete => None,
+        (text, _) => Some(text),
+    }
+}
@@ -1,7 +1,9 @@
 //! G-Code command types and lifecycle management
 
+use super::block_delete::split_block_delete;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::collections::BTreeSet;
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -25,6 +27,8 @@ pub enum CommandState {
     Error,
     /// Command was skipped (not sent)
     Skipped,
+    /// No "ok"/"error" arrived within the command's timeout window
+    TimedOut,
 }
 
 impl std::fmt::Display for CommandState {
@@ -36,6 +40,7 @@ impl std::fmt::Display for CommandState {
             Self::Done => write!(f, "Done"),
             Self::Error => write!(f, "Error"),
             Self::Skipped => write!(f, "Skipped"),
+            Self::TimedOut => write!(f, "TimedOut"),
         }
     }
 }
@@ -72,8 +77,11 @@ pub struct GcodeCommand {
     pub line: String,
     /// Line number if present in file
     pub line_number: Option<u32>,
-    /// Raw command text (parsed)
+    /// Raw command text (parsed), without a block delete `/` prefix
     pub command: String,
+    /// Line starts with the block delete character `/` (an optional block)
+    #[serde(default)]
+    pub block_delete: bool,
     /// Command execution state
     pub state: CommandState,
     /// Command numbering (0-based, sequential)
@@ -92,9 +100,11 @@ impl GcodeCommand {
     /// Create a new G-Code command with auto-generated ID
     pub fn new(line: impl Into<String>) -> Self {
         let line = line.into();
+        let (command, block_delete) = split_block_delete(&line);
         Self {
             id: CommandId::from(Uuid::new_v4().to_string()),
-            command: line.clone(),
+            command: command.to_string(),
+            block_delete,
             line,
             line_number: None,
             state: CommandState::Pending,
@@ -196,17 +206,37 @@ impl GcodeCommand {
         self
     }
 
+    /// Mark this command as timed out (no response within its timeout window)
+    pub fn mark_timed_out(&mut self) -> &mut Self {
+        debug_assert!(
+            self.state == CommandState::Sent,
+            "mark_timed_out called on command in {:?} state (expected Sent)",
+            self.state
+        );
+        self.state = CommandState::TimedOut;
+        self.completed_at = Some(Self::current_timestamp());
+        self
+    }
+
     /// Set the response for this command
     pub fn set_response(&mut self, response: CommandResponse) -> &mut Self {
         self.response = Some(response);
         self
     }
 
+    /// Whether the command is skipped with the block delete switch in this position
+    pub fn is_block_deleted(&self, block_delete: bool) -> bool {
+        block_delete && self.block_delete
+    }
+
     /// Check if command is in a terminal state
     pub fn is_terminal(&self) -> bool {
         matches!(
             self.state,
-            CommandState::Done | CommandState::Error | CommandState::Skipped
+            CommandState::Done
+                | CommandState::Error
+                | CommandState::Skipped
+                | CommandState::TimedOut
         )
     }
 
@@ -228,7 +258,7 @@ impl GcodeCommand {
     }
 
     /// Get current timestamp in milliseconds
-    fn current_timestamp() -> u64 {
+    pub(crate) fn current_timestamp() -> u64 {
         std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
@@ -256,6 +286,7 @@ impl std::fmt::Display for GcodeCommand {
 /// - Success/failure
 /// - Completion
 /// - Error conditions
+/// - Response timeouts
 pub trait CommandListener: Send + Sync {
     /// Called when a command is created
     fn on_command_created(&self, command: &GcodeCommand);
@@ -275,6 +306,9 @@ pub trait CommandListener: Send + Sync {
     /// Called when a command is skipped
     fn on_command_skipped(&self, command: &GcodeCommand);
 
+    /// Called when a command received no response within its timeout window
+    fn on_command_timeout(&self, _command: &GcodeCommand) {}
+
     /// Called when a command state changes
     fn on_command_state_changed(&self, command: &GcodeCommand, old_state: CommandState);
 }
@@ -289,39 +323,204 @@ impl CommandListener for NoOpCommandListener {
     fn on_command_completed(&self, _command: &GcodeCommand) {}
     fn on_command_error(&self, _command: &GcodeCommand, _error: &CommandResponse) {}
     fn on_command_skipped(&self, _command: &GcodeCommand) {}
+    fn on_command_timeout(&self, _command: &GcodeCommand) {}
     fn on_command_state_changed(&self, _command: &GcodeCommand, _old_state: CommandState) {}
 }
 
 /// Arc-wrapped command listener for thread-safe sharing
 pub type CommandListenerHandle = Arc<dyn CommandListener>;
 
+/// How a [`CommandNumberGenerator`] handles numbers that are still in flight after a wrap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SequenceMode {
+    /// Skip numbers still awaiting acknowledgment (may leave gaps in the sequence)
+    #[default]
+    SkipInFlight,
+    /// Never leave gaps: `try_next` waits (returns `None`) until the colliding number is
+    /// acknowledged, and releasing the most recent number rewinds the counter
+    GapFree,
+}
+
+/// Numbering range and mode for a [`CommandNumberGenerator`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandNumberConfig {
+    /// First number issued, and the value the sequence wraps back to
+    pub start: u32,
+    /// Largest number issued before wrapping (`None` = full `u32` range)
+    pub max: Option<u32>,
+    /// Collision handling mode
+    pub mode: SequenceMode,
+}
+
+impl Default for CommandNumberConfig {
+    fn default() -> Self {
+        Self {
+            start: 0,
+            max: None,
+            mode: SequenceMode::SkipInFlight,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct NumberState {
+    next: u32,
+    in_flight: BTreeSet<u32>,
+}
+
 /// Command numbering generator for sequential tracking
+///
+/// Numbers are issued sequentially from `start` and wrap back to `start` after `max`,
+/// for controllers with a limited line-number range. With a `max` set, issued numbers
+/// are considered in flight until [`acknowledge`](Self::acknowledge)d, and a wrapped
+/// sequence never reuses an in-flight number. A [`CommandTracker`] given the
+/// generator acknowledges each number as its command is answered. Resends should
+/// reuse the command's original number rather than drawing a new one. Clones share
+/// the same sequence.
+///
+/// [`CommandTracker`]: super::tracker::CommandTracker
 #[derive(Clone)]
 pub struct CommandNumberGenerator {
-    counter: Arc<AtomicU32>,
+    config: CommandNumberConfig,
+    state: Arc<Mutex<NumberState>>,
 }
 
 impl CommandNumberGenerator {
     /// Create a new command number generator
     pub fn new() -> Self {
+        Self::with_config(CommandNumberConfig::default())
+    }
+
+    /// Create a generator with a numbering range and mode
+    pub fn with_config(config: CommandNumberConfig) -> Self {
+        debug_assert!(
+            config.max.is_none_or(|max| max >= config.start),
+            "CommandNumberConfig max must not be below start"
+        );
         Self {
-            counter: Arc::new(AtomicU32::new(0)),
+            config,
+            state: Arc::new(Mutex::new(NumberState {
+                next: config.start,
+                in_flight: BTreeSet::new(),
+            })),
         }
     }
 
+    /// Get the numbering configuration
+    pub fn config(&self) -> CommandNumberConfig {
+        self.config
+    }
+
     /// Get the next command number
+    ///
+    /// In [`SequenceMode::GapFree`], if the next number is still in flight it is issued
+    /// anyway; use [`try_next`](Self::try_next) with bounded ranges to avoid that.
     pub fn next(&self) -> u32 {
-        self.counter.fetch_add(1, Ordering::SeqCst)
+        let mut state = self.state.lock();
+        let number = match self.find_free(&state) {
+            Some(n) => n,
+            None => state.next,
+        };
+        self.issue(&mut state, number);
+        number
+    }
+
+    /// Get the next command number, or `None` if every candidate is still in flight
+    /// (or, in gap-free mode, the next number in sequence is)
+    pub fn try_next(&self) -> Option<u32> {
+        let mut state = self.state.lock();
+        let number = self.find_free(&state)?;
+        self.issue(&mut state, number);
+        Some(number)
     }
 
-    /// Get current command count without incrementing
+    /// Get the number the next call to `next()` will start from, without incrementing
     pub fn current(&self) -> u32 {
-        self.counter.load(Ordering::SeqCst)
+        self.state.lock().next
+    }
+
+    /// Mark a number as acknowledged, freeing it for reuse after a wrap
+    pub fn acknowledge(&self, number: u32) {
+        self.state.lock().in_flight.remove(&number);
+    }
+
+    /// Return an issued number that was never sent.
+    ///
+    /// In gap-free mode, releasing the most recently issued number rewinds the sequence
+    /// so it is issued again.
+    pub fn release(&self, number: u32) {
+        let mut state = self.state.lock();
+        state.in_flight.remove(&number);
+        if self.config.mode == SequenceMode::GapFree && self.advance(number) == state.next {
+            state.next = number;
+        }
+    }
+
+    /// Number of issued numbers still awaiting acknowledgment
+    pub fn in_flight_count(&self) -> usize {
+        self.state.lock().in_flight.len()
+    }
+
+    /// Whether a number is still awaiting acknowledgment
+    pub fn is_in_flight(&self, number: u32) -> bool {
+        self.state.lock().in_flight.contains(&number)
+    }
+
+    /// Capture the current position for a later [`reset_to`](Self::reset_to)
+    pub fn checkpoint(&self) -> u32 {
+        self.current()
+    }
+
+    /// Restart the sequence at a checkpoint (e.g. for run-from-line).
+    ///
+    /// Clears the in-flight set, since a restart discards outstanding commands.
+    /// Values outside the configured range are clamped into it.
+    pub fn reset_to(&self, checkpoint: u32) {
+        let max = self.config.max.unwrap_or(u32::MAX);
+        let mut state = self.state.lock();
+        state.next = checkpoint.clamp(self.config.start, max);
+        state.in_flight.clear();
     }
 
     /// Reset the counter
     pub fn reset(&self) {
-        self.counter.store(0, Ordering::SeqCst);
+        self.reset_to(self.config.start);
+    }
+
+    /// Number following `number`, wrapping at the configured maximum
+    fn advance(&self, number: u32) -> u32 {
+        match self.config.max {
+            Some(max) if number >= max => self.config.start,
+            Some(_) => number + 1,
+            None => number.checked_add(1).unwrap_or(self.config.start),
+        }
+    }
+
+    fn find_free(&self, state: &NumberState) -> Option<u32> {
+        let candidate = state.next;
+        if !state.in_flight.contains(&candidate) {
+            return Some(candidate);
+        }
+        if self.config.mode == SequenceMode::GapFree {
+            return None;
+        }
+
+        let mut n = self.advance(candidate);
+        while n != candidate {
+            if !state.in_flight.contains(&n) {
+                return Some(n);
+            }
+            n = self.advance(n);
+        }
+        None
+    }
+
+    fn issue(&self, state: &mut NumberState, number: u32) {
+        // Only bounded ranges can wrap into an in-flight number.
+        if self.config.max.is_some() {
+            state.in_flight.insert(number);
+        }
+        state.next = self.advance(number);
     }
 }
 
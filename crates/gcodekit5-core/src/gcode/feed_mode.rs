@@ -0,0 +1,52 @@
+//! Feed rate modes (G93, G94, G95).
+//!
+//! An F word is a feed in units per minute under G94, the inverse of the move's
+//! time in minutes under G93 and units per spindle revolution under G95.
+//! [`FeedMode`] tells them apart and converts any of them to units per minute.
+
+use serde::{Deserialize, Serialize};
+
+/// How F words are read (modal group 5)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum FeedMode {
+    /// G93: F is the inverse of the move's time in minutes
+    InverseTime,
+    /// G94: F is units per minute
+    #[default]
+    UnitsPerMinute,
+    /// G95: F is units per spindle revolution
+    UnitsPerRevolution,
+}
+
+impl FeedMode {
+    /// The mode of a G code number (93, 94 or 95)
+    pub fn from_gcode(code: u8) -> Option<Self> {
+        match code {
+            93 => Some(Self::InverseTime),
+            94 => Some(Self::UnitsPerMinute),
+            95 => Some(Self::UnitsPerRevolution),
+            _ => None,
+        }
+    }
+
+    /// The G code number that selects this mode
+    pub fn gcode(&self) -> u8 {
+        match self {
+            Self::InverseTime => 93,
+            Self::UnitsPerMinute => 94,
+            Self::UnitsPerRevolution => 95,
+        }
+    }
+
+    /// Feed in units per minute of a move `length` units long at feed word `f`
+    ///
+    /// `None` when the mode needs a spindle speed and `spindle_rpm` is not
+    /// positive.
+    pub fn units_per_minute(&self, f: f64, length: f64, spindle_rpm: f64) -> Option<f64> {
+        match self {
+            Self::InverseTime => Some(f * length),
+            Self::UnitsPerMinute => Some(f),
+            Self::UnitsPerRevolution => (spindle_rpm > 0.0).then_some(f * spindle_rpm),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Go-to moves.
+//!
+//! Builds the lines for a rapid to a work position that cannot drag the tool
+//! across the stock: up to a safe height first, then across in X/Y, then down
+//! to the target Z if one is given. Every line selects millimeters (G21), so
+//! the moves stay right after a program that left the controller in inches.
+
+use super::number::format_gcode_number;
+
+/// Heights closer than this (mm) count as equal
+const Z_EPSILON: f64 = 1e-3;
+
+/// Lines for a rapid move to work position `x`, `y` and optionally `z`.
+///
+/// `safe_z` is the work Z to retract to, lowered to `z_ceiling`, the top of the
+/// Z travel in work coordinates, when that is known. The retract is skipped
+/// when `current_z` is already at or above the safe height; an unknown current
+/// Z always retracts. Positions are in mm; lines are metric (G21), absolute
+/// (G90) and have no trailing newline.
+pub fn safe_goto_commands(
+    x: f64,
+    y: f64,
+    z: Option<f64>,
+    current_z: Option<f64>,
+    safe_z: f64,
+    z_ceiling: Option<f64>,
+) -> Vec<String> {
+    let safe_z = z_ceiling.map_or(safe_z, |ceiling| safe_z.min(ceiling));
+    let mut commands = Vec::new();
+    if current_z.is_none_or(|current| current < safe_z - Z_EPSILON) {
+        commands.push(format!("G21 G90 G0 Z{}", format_gcode_number(safe_z, 3)));
+    }
+    commands.push(format!(
+        "G21 G90 G0 X{} Y{}",
+        format_gcode_number(x, 3),
+        format_gcode_number(y, 3)
+    ));
+    if let Some(z) = z {
+        commands.push(format!("G21 G90 G0 Z{}", format_gcode_number(z, 3)));
+    }
+    commands
+}
@@ -0,0 +1,131 @@
+//! Spindle and laser power around a feed hold.
+//!
+//! A feed hold stops motion, but on many controllers the spindle keeps turning
+//! and a laser keeps firing, scorching the spot it stopped on.
+//! [`HoldPowerOff`] is the command chosen to turn the power off while held, and
+//! [`SpindlePower`] follows the spindle words of the lines sent so far, so
+//! resuming can turn the power back on at the speed the job was running.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use super::program_stop::words;
+
+/// Command sent to turn the spindle or laser off while a job is held
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum HoldPowerOff {
+    /// Leave the power as it is
+    #[default]
+    Disabled,
+    /// `M5`: stop the spindle or laser
+    SpindleStop,
+    /// `S0`: keep the spindle state but drop the power to zero
+    ZeroPower,
+}
+
+impl HoldPowerOff {
+    /// The command that turns the power off, if any
+    pub fn command(&self) -> Option<&'static str> {
+        match self {
+            Self::Disabled => None,
+            Self::SpindleStop => Some("M5"),
+            Self::ZeroPower => Some("S0"),
+        }
+    }
+}
+
+impl fmt::Display for HoldPowerOff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.command().unwrap_or("Disabled"))
+    }
+}
+
+impl FromStr for HoldPowerOff {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "disabled" | "off" | "none" => Ok(Self::Disabled),
+            "m5" | "spindlestop" => Ok(Self::SpindleStop),
+            "s0" | "zeropower" => Ok(Self::ZeroPower),
+            _ => Err(format!("Unknown hold power-off command: {}", s)),
+        }
+    }
+}
+
+/// Direction the spindle was last switched to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpindleMode {
+    /// `M3`
+    Clockwise,
+    /// `M4`, dynamic power in laser mode
+    CounterClockwise,
+    /// `M5`
+    Stopped,
+}
+
+/// Spindle or laser state after the lines sent so far
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SpindlePower {
+    /// Last `M3`, `M4` or `M5`; `None` before any was sent
+    pub mode: Option<SpindleMode>,
+    /// Last `S` value
+    pub speed: Option<f64>,
+}
+
+impl SpindlePower {
+    /// Follow the spindle words of a sent line; comments are ignored
+    pub fn observe(&mut self, line: &str) {
+        for (letter, value) in words(line) {
+            match (letter, value) {
+                ('M', 3.0) => self.mode = Some(SpindleMode::Clockwise),
+                ('M', 4.0) => self.mode = Some(SpindleMode::CounterClockwise),
+                // M2 and M30 end the program, which stops the spindle
+                ('M', 2.0 | 5.0 | 30.0) => self.mode = Some(SpindleMode::Stopped),
+                ('S', speed) => self.speed = Some(speed),
+                _ => {}
+            }
+        }
+    }
+
+    /// Whether the spindle or laser is on with some power
+    pub fn is_on(&self) -> bool {
+        matches!(
+            self.mode,
+            Some(SpindleMode::Clockwise | SpindleMode::CounterClockwise)
+        ) && self.speed.is_none_or(|speed| speed > 0.0)
+    }
+
+    /// The command that turns the power back on after `off` turned it off
+    ///
+    /// `None` when the power was not on, or when `S0` was used and the speed
+    /// to go back to is not known; `off` should not be sent in that case.
+    pub fn restore_command(&self, off: HoldPowerOff) -> Option<String> {
+        if !self.is_on() {
+            return None;
+        }
+        let speed = self.speed.map(|speed| format!("S{}", format_speed(speed)));
+        match off {
+            HoldPowerOff::Disabled => None,
+            HoldPowerOff::ZeroPower => speed,
+            HoldPowerOff::SpindleStop => {
+                let mode = match self.mode {
+                    Some(SpindleMode::CounterClockwise) => "M4",
+                    _ => "M3",
+                };
+                Some(match speed {
+                    Some(speed) => format!("{} {}", mode, speed),
+                    None => mode.to_string(),
+                })
+            }
+        }
+    }
+}
+
+/// Speed without trailing zeros, e.g. `S12000` or `S0.5`
+fn format_speed(speed: f64) -> String {
+    let text = format!("{:.4}", speed);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
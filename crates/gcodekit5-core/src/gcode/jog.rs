@@ -0,0 +1,49 @@
+//! Jog commands.
+//!
+//! Builds GRBL `$J=G91` jog lines for one or more axes. A multi-axis jog is one
+//! straight move: its feed is the feed along the path, and each axis moves at the
+//! feed times its share of the path length. The feed is lowered until no axis
+//! goes faster than its own maximum jog rate.
+
+use super::number::format_gcode_number;
+
+/// Feed along the path for a jog, lowered so no axis exceeds its maximum rate.
+///
+/// `max_rate` gives an axis' maximum jog rate in units/min, or `None` if it has
+/// no limit. Zero deltas are ignored.
+pub fn limited_jog_feed(
+    deltas: &[(char, f64)],
+    feed: f64,
+    max_rate: impl Fn(char) -> Option<f64>,
+) -> f64 {
+    let length = deltas.iter().map(|(_, d)| d * d).sum::<f64>().sqrt();
+    if length == 0.0 {
+        return feed;
+    }
+    deltas
+        .iter()
+        .filter(|(_, delta)| *delta != 0.0)
+        .filter_map(|(axis, delta)| max_rate(*axis).map(|rate| rate * length / delta.abs()))
+        .fold(feed, f64::min)
+}
+
+/// Jog line for the given relative axis moves, or `None` if nothing moves.
+///
+/// The feed is limited with [`limited_jog_feed`]. The line has no trailing newline.
+pub fn jog_command(
+    deltas: &[(char, f64)],
+    feed: f64,
+    max_rate: impl Fn(char) -> Option<f64>,
+) -> Option<String> {
+    let moving: Vec<(char, f64)> = deltas.iter().copied().filter(|(_, d)| *d != 0.0).collect();
+    if moving.is_empty() {
+        return None;
+    }
+    let mut command = "$J=G91".to_string();
+    for (axis, delta) in &moving {
+        command.push_str(&format!(" {}{}", axis, format_gcode_number(*delta, 3)));
+    }
+    let feed = limited_jog_feed(&moving, feed, max_rate);
+    command.push_str(&format!(" F{}", format_gcode_number(feed, 3)));
+    Some(command)
+}
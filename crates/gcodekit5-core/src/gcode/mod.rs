@@ -1,8 +1,29 @@
 //! # G-Code Command Types
 //!
 //! Core G-code command types shared across crates, including command
-//! lifecycle management, state tracking, and listener traits.
+//! lifecycle management, state tracking, response timeouts, listener traits,
+//! plane-aware arc geometry, block delete (`/`) handling, jog commands, safe
+//! go-to moves, program stops (`M0`/`M1`), spindle power around a feed hold,
+//! feed rate modes (`G93`/`G94`/`G95`) and the formatting of numbers in G-code.
 
+pub mod arc;
+pub mod block_delete;
 pub mod command;
+pub mod feed_mode;
+pub mod goto;
+pub mod hold_power;
+pub mod jog;
+pub mod number;
+pub mod program_stop;
+pub mod tracker;
 
+pub use arc::{ArcMove, ArcPlane};
+pub use block_delete::{apply_block_delete, split_block_delete, BLOCK_DELETE_CHAR};
 pub use command::*;
+pub use feed_mode::FeedMode;
+pub use goto::safe_goto_commands;
+pub use hold_power::{HoldPowerOff, SpindleMode, SpindlePower};
+pub use jog::{jog_command, limited_jog_feed};
+pub use number::format_gcode_number;
+pub use program_stop::{is_only_program_stop, message_comment, program_stop, ProgramStop};
+pub use tracker::{CommandTimeoutConfig, CommandTracker};
@@ -0,0 +1,19 @@
+//! Numbers written into G-code.
+//!
+//! Every generator and transform writes coordinates, feeds and speeds the same
+//! way: rounded to a number of decimals, with trailing zeros and a bare `-0`
+//! dropped, so `10.500` is written `10.5` and `-0.0001` at three places `0`.
+
+/// `value` with up to `decimals` decimals, in its shortest form
+pub fn format_gcode_number(value: f64, decimals: usize) -> String {
+    let text = format!("{:.*}", decimals, value);
+    let text = if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &text
+    };
+    match text {
+        "-0" => "0".to_string(),
+        _ => text.to_string(),
+    }
+}
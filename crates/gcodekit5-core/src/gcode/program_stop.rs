@@ -0,0 +1,85 @@
+//! Program stops (`M0`/`M1`) and operator messages.
+//!
+//! `M0` pauses a running program until the operator resumes it; `M1` is an
+//! optional stop that only pauses with the optional stop switch on. A
+//! `(MSG,...)` comment carries text for the operator, usually just before a stop.
+
+/// A program stop word
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgramStop {
+    /// `M0`: always pauses
+    Mandatory,
+    /// `M1`: pauses only with the optional stop switch on
+    Optional,
+}
+
+/// Words of a line with comments removed, as (letter, value) pairs
+pub(crate) fn words(line: &str) -> Vec<(char, f64)> {
+    let mut code = String::new();
+    let mut in_comment = false;
+    for c in line.chars() {
+        match c {
+            '(' => in_comment = true,
+            ')' => in_comment = false,
+            ';' if !in_comment => break,
+            _ if in_comment || c.is_whitespace() => {}
+            _ => code.push(c.to_ascii_uppercase()),
+        }
+    }
+
+    let mut words = Vec::new();
+    let mut chars = code.chars().peekable();
+    while let Some(letter) = chars.next() {
+        if !letter.is_ascii_alphabetic() {
+            continue;
+        }
+        let mut number = String::new();
+        while let Some(&c) = chars.peek() {
+            if c.is_ascii_digit() || matches!(c, '.' | '-' | '+') {
+                number.push(c);
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        if let Ok(value) = number.parse::<f64>() {
+            words.push((letter, value));
+        }
+    }
+    words
+}
+
+/// The program stop on a line, if any.
+///
+/// Words inside comments are ignored. `M00` and `M01` count; `M2`, `M30` and
+/// `M100` do not.
+pub fn program_stop(line: &str) -> Option<ProgramStop> {
+    words(line).into_iter().find_map(|word| match word {
+        ('M', 0.0) => Some(ProgramStop::Mandatory),
+        ('M', 1.0) => Some(ProgramStop::Optional),
+        _ => None,
+    })
+}
+
+/// Whether a line holds nothing but a program stop (and a line number or comments)
+pub fn is_only_program_stop(line: &str) -> bool {
+    program_stop(line).is_some()
+        && words(line)
+            .iter()
+            .all(|&(letter, value)| letter == 'N' || (letter == 'M' && value <= 1.0))
+}
+
+/// The text of a `(MSG,...)` comment on a line
+pub fn message_comment(line: &str) -> Option<&str> {
+    let mut rest = line;
+    while let Some(start) = rest.find('(') {
+        let after = &rest[start + 1..];
+        let end = after.find(')').unwrap_or(after.len());
+        let comment = after[..end].trim_start();
+        if comment.len() >= 4 && comment[..4].eq_ignore_ascii_case("MSG,") {
+            return Some(comment[4..].trim());
+        }
+        rest = &after[end..];
+    }
+    None
+}
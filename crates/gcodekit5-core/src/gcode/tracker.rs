@@ -0,0 +1,399 @@
+//! Outstanding command tracking with response timeouts
+//!
+//! GRBL-style controllers acknowledge commands strictly in order, so outstanding
+//! commands are matched to `ok`/`error` responses FIFO. Only the oldest outstanding
+//! command can be waiting on the controller; its timeout window starts when it becomes
+//! the head of the queue, so a command queued behind a long move is not blamed for it.
+
+use super::command::{
+    CommandId, CommandListenerHandle, CommandNumberGenerator, CommandResponse, CommandState,
+    GcodeCommand,
+};
+use std::collections::VecDeque;
+
+/// Timeout policy for outstanding commands
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandTimeoutConfig {
+    /// Enable timeout detection
+    pub enabled: bool,
+    /// Timeout for non-motion commands (milliseconds)
+    pub default_timeout_ms: u64,
+    /// Timeout for motion commands (G0-G3), which may wait on a full planner
+    /// buffer before being acknowledged (milliseconds)
+    pub motion_timeout_ms: u64,
+    /// Add the programmed dwell time of `G4 P<seconds>` to the timeout
+    pub include_dwell_time: bool,
+    /// Commands that never time out (homing, probing, program pauses).
+    /// Entries match a G/M word (`"G38"` also matches `G38.2`) or a `$` command prefix.
+    pub excluded_commands: Vec<String>,
+    /// How many times [`CommandTracker::resend`] takes back a timed-out command.
+    /// Off by default: if only the `ok` was lost, a resent line runs twice.
+    pub max_resends: u32,
+}
+
+impl Default for CommandTimeoutConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            default_timeout_ms: 10_000,
+            motion_timeout_ms: 60_000,
+            include_dwell_time: true,
+            excluded_commands: ["$H", "G28", "G30", "G38", "M0", "M1"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            max_resends: 0,
+        }
+    }
+}
+
+impl CommandTimeoutConfig {
+    /// Timeout window for a command line in milliseconds, or `None` if it never times out
+    pub fn timeout_for(&self, line: &str) -> Option<u64> {
+        if !self.enabled {
+            return None;
+        }
+
+        let upper = line.trim().to_ascii_uppercase();
+        if upper.starts_with('$') {
+            let excluded = self
+                .excluded_commands
+                .iter()
+                .any(|ex| ex.starts_with('$') && upper.starts_with(&ex.to_ascii_uppercase()));
+            return (!excluded).then_some(self.default_timeout_ms);
+        }
+
+        let words = parse_words(&upper);
+        let is_excluded = words.iter().any(|(letter, value)| {
+            self.excluded_commands.iter().any(|ex| {
+                let ex = ex.to_ascii_uppercase();
+                let mut chars = ex.chars();
+                chars.next() == Some(*letter) && word_matches(value, chars.as_str())
+            })
+        });
+        if is_excluded {
+            return None;
+        }
+
+        let g_codes: Vec<f32> = words
+            .iter()
+            .filter(|(letter, _)| *letter == 'G')
+            .filter_map(|(_, value)| value.parse::<f32>().ok())
+            .collect();
+
+        let is_motion = g_codes.iter().any(|g| [0.0, 1.0, 2.0, 3.0].contains(g));
+        let mut timeout = if is_motion {
+            self.motion_timeout_ms
+        } else {
+            self.default_timeout_ms
+        };
+
+        let is_dwell = g_codes.contains(&4.0);
+        if is_dwell && self.include_dwell_time {
+            let dwell_secs = words
+                .iter()
+                .find(|(letter, _)| *letter == 'P')
+                .and_then(|(_, value)| value.parse::<f64>().ok())
+                .unwrap_or(0.0);
+            timeout += (dwell_secs.max(0.0) * 1000.0) as u64;
+        }
+
+        Some(timeout)
+    }
+}
+
+/// Split a G-code line into (letter, value) words, ignoring comments
+fn parse_words(line: &str) -> Vec<(char, String)> {
+    let mut words = Vec::new();
+    let mut in_paren = false;
+    let mut current: Option<(char, String)> = None;
+
+    for ch in line.chars() {
+        if in_paren {
+            in_paren = ch != ')';
+            continue;
+        }
+        match ch {
+            '(' => in_paren = true,
+            ';' => break,
+            c if c.is_ascii_alphabetic() => {
+                if let Some(word) = current.take() {
+                    words.push(word);
+                }
+                current = Some((c, String::new()));
+            }
+            c if c.is_ascii_digit() || c == '.' || c == '-' || c == '+' => {
+                if let Some((_, value)) = current.as_mut() {
+                    value.push(c);
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(word) = current {
+        words.push(word);
+    }
+    words
+}
+
+/// Whether a word value (e.g. "38.2") matches an exclusion value (e.g. "38")
+fn word_matches(value: &str, pattern: &str) -> bool {
+    let (Ok(v), Ok(p)) = (value.parse::<f32>(), pattern.parse::<f32>()) else {
+        return false;
+    };
+    if pattern.contains('.') {
+        v == p
+    } else {
+        v.trunc() == p
+    }
+}
+
+#[derive(Debug, Clone)]
+struct OutstandingCommand {
+    command: GcodeCommand,
+    timeout_ms: Option<u64>,
+    /// When this command became the oldest outstanding command (milliseconds)
+    head_since: u64,
+    /// Times this command was resent after timing out
+    resends: u32,
+}
+
+/// The last command that timed out, until it is resent or given up on
+#[derive(Debug, Clone)]
+struct TimedOutCommand {
+    id: CommandId,
+    sequence_number: u32,
+    /// Times it had been resent before this timeout
+    resends: u32,
+}
+
+/// Tracks sent commands awaiting a response and detects response timeouts
+pub struct CommandTracker {
+    config: CommandTimeoutConfig,
+    outstanding: VecDeque<OutstandingCommand>,
+    listeners: Vec<CommandListenerHandle>,
+    timed_out: Option<TimedOutCommand>,
+    numbers: Option<CommandNumberGenerator>,
+}
+
+impl CommandTracker {
+    /// Create a new tracker with the given timeout policy
+    pub fn new(config: CommandTimeoutConfig) -> Self {
+        Self {
+            config,
+            outstanding: VecDeque::new(),
+            listeners: Vec::new(),
+            timed_out: None,
+            numbers: None,
+        }
+    }
+
+    /// Get the timeout policy
+    pub fn config(&self) -> &CommandTimeoutConfig {
+        &self.config
+    }
+
+    /// Replace the timeout policy (applies to commands sent afterwards)
+    pub fn set_config(&mut self, config: CommandTimeoutConfig) {
+        self.config = config;
+    }
+
+    /// Acknowledge each command's `sequence_number` in `numbers` once the command is
+    /// answered, given up on or cleared, so a wrapped sequence can issue it again.
+    /// Every command tracked afterwards must be numbered from `numbers`.
+    pub fn set_number_generator(&mut self, numbers: CommandNumberGenerator) {
+        self.numbers = Some(numbers);
+    }
+
+    /// The generator commands are numbered from, if one is set
+    pub fn number_generator(&self) -> Option<&CommandNumberGenerator> {
+        self.numbers.as_ref()
+    }
+
+    /// Register a listener for command lifecycle events
+    pub fn add_listener(&mut self, listener: CommandListenerHandle) {
+        self.listeners.push(listener);
+    }
+
+    /// Record a command as sent to the controller
+    pub fn track_sent(&mut self, mut command: GcodeCommand) {
+        if command.state == CommandState::Pending {
+            command.mark_sent();
+        }
+        self.push(command, 0);
+    }
+
+    /// Take back a command returned by [`check_timeouts`](Self::check_timeouts)
+    /// that the caller is about to send again.
+    ///
+    /// The command is tracked as sent with a new timeout window. Returns `false`,
+    /// leaving it untracked, once it has been resent
+    /// [`max_resends`](CommandTimeoutConfig::max_resends) times; the caller should
+    /// then give up on it (e.g. hold the job for the operator).
+    pub fn resend(&mut self, mut command: GcodeCommand) -> bool {
+        let resends = match self.timed_out.take() {
+            Some(timed_out) if timed_out.id == command.id => timed_out.resends,
+            _ => 0,
+        };
+        if resends >= self.config.max_resends {
+            self.acknowledge(&command);
+            return false;
+        }
+        command.state = CommandState::Sent;
+        command.sent_at = Some(GcodeCommand::current_timestamp());
+        command.completed_at = None;
+        self.push(command, resends + 1);
+        true
+    }
+
+    /// Start the oldest command's timeout window over at `now_ms`, e.g. while the
+    /// machine is in a feed hold, during which the controller holds back its responses
+    pub fn restart_timeout(&mut self, now_ms: u64) {
+        if let Some(head) = self.outstanding.front_mut() {
+            head.head_since = now_ms;
+        }
+    }
+
+    /// Restart the oldest command's timeout window using the current time
+    pub fn restart_timeout_now(&mut self) {
+        self.restart_timeout(GcodeCommand::current_timestamp());
+    }
+
+    /// Match an "ok" response to the oldest outstanding command
+    pub fn handle_ok(&mut self) -> Option<GcodeCommand> {
+        let mut entry = self.pop_head()?;
+        entry.command.mark_ok();
+        entry.command.set_response(CommandResponse {
+            success: true,
+            message: "ok".to_string(),
+            error_code: None,
+            data: None,
+        });
+        for listener in &self.listeners {
+            listener.on_command_ok(&entry.command);
+            listener.on_command_state_changed(&entry.command, CommandState::Sent);
+        }
+        self.acknowledge(&entry.command);
+        Some(entry.command)
+    }
+
+    /// Match an "error" response to the oldest outstanding command
+    pub fn handle_error(
+        &mut self,
+        error_code: Option<u32>,
+        message: String,
+    ) -> Option<GcodeCommand> {
+        let mut entry = self.pop_head()?;
+        entry.command.mark_error(error_code, message);
+        if let Some(response) = entry.command.response.clone() {
+            for listener in &self.listeners {
+                listener.on_command_error(&entry.command, &response);
+                listener.on_command_state_changed(&entry.command, CommandState::Sent);
+            }
+        }
+        self.acknowledge(&entry.command);
+        Some(entry.command)
+    }
+
+    /// Check the oldest outstanding command against its deadline at `now_ms`.
+    ///
+    /// A timed-out command is marked [`CommandState::TimedOut`], removed from the
+    /// outstanding queue and reported to listeners; it is up to the caller to abort or
+    /// resend. Returns the timed-out commands (at most one per call, as later commands
+    /// start their window only when they reach the head). A timed-out command keeps
+    /// its number until [`resend`](Self::resend) gives up on it or
+    /// [`clear`](Self::clear) drops it.
+    pub fn check_timeouts(&mut self, now_ms: u64) -> Vec<GcodeCommand> {
+        let expired = self.outstanding.front().is_some_and(|head| {
+            head.timeout_ms.is_some_and(|timeout| {
+                let start = head.command.sent_at.unwrap_or(0).max(head.head_since);
+                now_ms.saturating_sub(start) >= timeout
+            })
+        });
+        if !expired {
+            return Vec::new();
+        }
+
+        let Some(mut entry) = self.pop_head_at(now_ms) else {
+            return Vec::new();
+        };
+        entry.command.mark_timed_out();
+        self.timed_out = Some(TimedOutCommand {
+            id: entry.command.id.clone(),
+            sequence_number: entry.command.sequence_number,
+            resends: entry.resends,
+        });
+        for listener in &self.listeners {
+            listener.on_command_timeout(&entry.command);
+            listener.on_command_state_changed(&entry.command, CommandState::Sent);
+        }
+        vec![entry.command]
+    }
+
+    /// Check for timeouts using the current time
+    pub fn check_timeouts_now(&mut self) -> Vec<GcodeCommand> {
+        self.check_timeouts(GcodeCommand::current_timestamp())
+    }
+
+    /// Number of commands awaiting a response
+    pub fn outstanding_count(&self) -> usize {
+        self.outstanding.len()
+    }
+
+    /// Iterate over commands awaiting a response, oldest first
+    pub fn outstanding(&self) -> impl Iterator<Item = &GcodeCommand> {
+        self.outstanding.iter().map(|entry| &entry.command)
+    }
+
+    /// Drop all outstanding commands and any timed-out command not yet resent
+    /// (e.g. after a soft reset)
+    pub fn clear(&mut self) {
+        for entry in std::mem::take(&mut self.outstanding) {
+            self.acknowledge(&entry.command);
+        }
+        if let Some(timed_out) = self.timed_out.take() {
+            self.acknowledge_number(timed_out.sequence_number);
+        }
+    }
+
+    fn acknowledge(&self, command: &GcodeCommand) {
+        self.acknowledge_number(command.sequence_number);
+    }
+
+    fn acknowledge_number(&self, number: u32) {
+        if let Some(numbers) = &self.numbers {
+            numbers.acknowledge(number);
+        }
+    }
+
+    fn push(&mut self, command: GcodeCommand, resends: u32) {
+        let timeout_ms = self.config.timeout_for(&command.command);
+        for listener in &self.listeners {
+            listener.on_command_sent(&command);
+        }
+        self.outstanding.push_back(OutstandingCommand {
+            command,
+            timeout_ms,
+            head_since: GcodeCommand::current_timestamp(),
+            resends,
+        });
+    }
+
+    fn pop_head(&mut self) -> Option<OutstandingCommand> {
+        self.pop_head_at(GcodeCommand::current_timestamp())
+    }
+
+    fn pop_head_at(&mut self, now_ms: u64) -> Option<OutstandingCommand> {
+        let entry = self.outstanding.pop_front()?;
+        if let Some(next) = self.outstanding.front_mut() {
+            next.head_since = now_ms;
+        }
+        Some(entry)
+    }
+}
+
+impl Default for CommandTracker {
+    fn default() -> Self {
+        Self::new(CommandTimeoutConfig::default())
+    }
+}
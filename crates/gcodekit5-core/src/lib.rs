@@ -20,15 +20,18 @@ pub use core::{
 };
 
 pub use data::{
-    CNCPoint, CommunicatorState, ControllerState, ControllerStatus, MachineStatus,
-    MachineStatusSnapshot, PartialPosition, Position, Units,
+    CNCPoint, CommunicatorState, ControllerState, ControllerStatus, HeightPoint, MachineStatus,
+    MachineStatusSnapshot, PartialPosition, Position, ProbeMesh, Units,
 };
 
 pub use error::{ConnectionError, ControllerError, Error, FirmwareError, GcodeError, Result};
 
 pub use gcode::{
-    CommandId, CommandListener, CommandListenerHandle, CommandNumberGenerator, CommandResponse,
-    CommandState, GcodeCommand, NoOpCommandListener,
+    apply_block_delete, format_gcode_number, is_only_program_stop, jog_command, limited_jog_feed,
+    message_comment, program_stop, safe_goto_commands, split_block_delete, ArcMove, ArcPlane,
+    CommandId, CommandListener, CommandListenerHandle, CommandNumberConfig, CommandNumberGenerator,
+    CommandResponse, CommandState, CommandTimeoutConfig, CommandTracker, FeedMode, GcodeCommand,
+    HoldPowerOff, NoOpCommandListener, ProgramStop, SequenceMode, SpindleMode, SpindlePower,
 };
 
 // Re-export event bus for convenience
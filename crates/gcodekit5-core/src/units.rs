@@ -1,11 +1,21 @@
 //! Unit conversion utilities
 //!
 //! Handles conversion between Metric (mm) and Imperial (inch) systems.
-//! Supports decimal and fractional inch parsing and formatting.
+//! Supports decimal and fractional inch parsing and formatting, and entry of
+//! simple arithmetic expressions with an optional unit suffix.
+//!
+//! Typed input may use a comma as its decimal separator when the application
+//! is set to accept one (see [`set_decimal_separator`]). Formatting always
+//! writes a point, so G-code built from formatted values stays to spec.
 
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::fmt;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use thiserror::Error;
+
+const MM_PER_INCH: f64 = 25.4;
 
 /// Measurement system for dimensional values.
 ///
@@ -90,24 +100,207 @@ impl fmt::Display for FeedRateUnits {
     }
 }
 
+/// How imperial lengths are written by [`format_length_with`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum InchDisplay {
+    /// Decimal inches to 3 places, as [`format_length`] writes them
+    #[default]
+    Decimal,
+    /// Reduced fractions such as `3/8` or `1 1/4`, falling back to decimal
+    /// inches when no fraction is close enough
+    Fraction(InchFraction),
+}
+
+/// Settings for fractional inch display
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct InchFraction {
+    /// Finest fraction written, e.g. 64 for sixty-fourths
+    pub denominator: u32,
+    /// Largest difference between a length and its fraction, in inches
+    pub tolerance: f32,
+}
+
+impl Default for InchFraction {
+    fn default() -> Self {
+        Self {
+            denominator: 64,
+            tolerance: 0.001,
+        }
+    }
+}
+
+/// Decimal separator accepted in typed numbers
+///
+/// Numbers with more than one separator, such as `1,000.5` or `1.000.000`, are
+/// always rejected rather than read with a guessed thousands separator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DecimalSeparator {
+    /// `1.5`
+    #[default]
+    Point,
+    /// `1,5`
+    Comma,
+    /// Either `1.5` or `1,5`
+    Either,
+}
+
+impl fmt::Display for DecimalSeparator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Point => write!(f, "Point"),
+            Self::Comma => write!(f, "Comma"),
+            Self::Either => write!(f, "Either"),
+        }
+    }
+}
+
+impl FromStr for DecimalSeparator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "point" | "." => Ok(Self::Point),
+            "comma" | "," => Ok(Self::Comma),
+            "either" => Ok(Self::Either),
+            _ => Err(format!("Unknown decimal separator: {}", s)),
+        }
+    }
+}
+
+/// Separator used by the parse functions, as a `DecimalSeparator` discriminant
+static DECIMAL_SEPARATOR: AtomicU8 = AtomicU8::new(DecimalSeparator::Point as u8);
+
+/// Set the decimal separator accepted by the parse functions of this module
+pub fn set_decimal_separator(separator: DecimalSeparator) {
+    DECIMAL_SEPARATOR.store(separator as u8, Ordering::Relaxed);
+}
+
+/// The decimal separator accepted by the parse functions of this module
+pub fn decimal_separator() -> DecimalSeparator {
+    let value = DECIMAL_SEPARATOR.load(Ordering::Relaxed);
+    [DecimalSeparator::Comma, DecimalSeparator::Either]
+        .into_iter()
+        .find(|separator| *separator as u8 == value)
+        .unwrap_or(DecimalSeparator::Point)
+}
+
+/// Rewrite typed numbers to use a point as their decimal separator
+///
+/// With [`DecimalSeparator::Point`] the input is returned unchanged. Otherwise
+/// each number may contain at most one separator, and with
+/// [`DecimalSeparator::Comma`] that separator must be a comma.
+///
+/// # Errors
+/// Returns [`UnitParseError::AmbiguousSeparator`] for a number that breaks
+/// these rules.
+///
+/// # Example
+/// ```
+/// use gcodekit5_core::units::{normalize_decimal_separator, DecimalSeparator};
+///
+/// let normalized = normalize_decimal_separator("1,5+2", DecimalSeparator::Comma);
+/// assert_eq!(normalized.as_deref(), Ok("1.5+2"));
+/// assert!(normalize_decimal_separator("1.000,5", DecimalSeparator::Either).is_err());
+/// ```
+pub fn normalize_decimal_separator(
+    input: &str,
+    separator: DecimalSeparator,
+) -> Result<Cow<'_, str>, UnitParseError> {
+    if separator == DecimalSeparator::Point {
+        return Ok(Cow::Borrowed(input));
+    }
+    let is_part = |c: char| c.is_ascii_digit() || c == '.' || c == ',';
+    let mut rest = input;
+    while let Some(start) = rest.find(is_part) {
+        let number = &rest[start..];
+        let end = number.find(|c| !is_part(c)).unwrap_or(number.len());
+        let number = &number[..end];
+        let points = number.matches('.').count();
+        let commas = number.matches(',').count();
+        if points + commas > 1 || (separator == DecimalSeparator::Comma && points > 0) {
+            return Err(UnitParseError::AmbiguousSeparator(number.to_string()));
+        }
+        rest = &rest[start + end..];
+    }
+    Ok(Cow::Owned(input.replace(',', ".")))
+}
+
 /// Format length value for display
 ///
 /// * `value_mm` - Value in millimeters
 /// * `system` - Target measurement system
 pub fn format_length(value_mm: f32, system: MeasurementSystem) -> String {
+    format_length_with(value_mm, system, InchDisplay::Decimal)
+}
+
+/// Format length value for display, writing imperial lengths as `inches` says
+///
+/// Metric lengths are always decimal.
+///
+/// # Example
+/// ```
+/// use gcodekit5_core::units::{format_length_with, InchDisplay, InchFraction, MeasurementSystem};
+///
+/// let fraction = InchDisplay::Fraction(InchFraction::default());
+/// assert_eq!(format_length_with(31.75, MeasurementSystem::Imperial, fraction), "1 1/4");
+/// assert_eq!(format_length_with(1.0, MeasurementSystem::Imperial, fraction), "0.039");
+/// ```
+pub fn format_length_with(value_mm: f32, system: MeasurementSystem, inches: InchDisplay) -> String {
     match system {
         MeasurementSystem::Metric => {
             // Format to 3 decimal places
             format!("{:.3}", value_mm)
         }
         MeasurementSystem::Imperial => {
-            let inches = value_mm / 25.4;
-            // Format to 3 decimal places
-            format!("{:.3}", inches)
+            let value = value_mm / 25.4;
+            match inches {
+                InchDisplay::Fraction(fraction) => {
+                    format_fraction(value, fraction).unwrap_or_else(|| format!("{:.3}", value))
+                }
+                // Format to 3 decimal places
+                InchDisplay::Decimal => format!("{:.3}", value),
+            }
         }
     }
 }
 
+/// The nearest reduced fraction to `inches`, if it is within the tolerance
+fn format_fraction(inches: f32, fraction: InchFraction) -> Option<String> {
+    if fraction.denominator == 0 || !inches.is_finite() {
+        return None;
+    }
+    let denominator = fraction.denominator as u64;
+    let magnitude = inches.abs() as f64;
+    let steps = (magnitude * denominator as f64).round();
+    if (steps / denominator as f64 - magnitude).abs() > fraction.tolerance as f64 {
+        return None;
+    }
+    let steps = steps as u64;
+    let whole = steps / denominator;
+    let mut numerator = steps % denominator;
+    let mut denominator = denominator;
+    let divisor = gcd(numerator, denominator);
+    numerator /= divisor;
+    denominator /= divisor;
+
+    let sign = if inches < 0.0 && steps > 0 { "-" } else { "" };
+    Some(match (whole, numerator) {
+        (whole, 0) => format!("{}{}", sign, whole),
+        (0, numerator) => format!("{}{}/{}", sign, numerator, denominator),
+        (whole, numerator) => format!("{}{} {}/{}", sign, whole, numerator, denominator),
+    })
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
 /// Format feed rate value for display
 ///
 /// * `value_mm_per_min` - Feed rate in mm/min
@@ -125,7 +318,8 @@ pub fn format_feed_rate(value_mm_per_min: f32, units: FeedRateUnits) -> String {
 /// Parse length string to millimeters.
 ///
 /// Accepts decimal values and imperial fractions (e.g., `"1 1/2"` for 1.5 inches).
-/// Empty strings return `Ok(0.0)`.
+/// A leading minus sign applies to the whole mixed number, so `"-1 1/2"` is
+/// -1.5 inches. Empty strings return `Ok(0.0)`.
 ///
 /// # Arguments
 /// * `input` - String to parse (whitespace is trimmed)
@@ -138,6 +332,8 @@ pub fn format_feed_rate(value_mm_per_min: f32, units: FeedRateUnits) -> String {
 /// - A fraction has a zero denominator (division by zero)
 /// - A fraction has more than one `/` separator
 pub fn parse_length(input: &str, system: MeasurementSystem) -> Result<f32, String> {
+    let input =
+        normalize_decimal_separator(input, decimal_separator()).map_err(|e| e.to_string())?;
     let input = input.trim();
     if input.is_empty() {
         return Ok(0.0);
@@ -148,6 +344,10 @@ pub fn parse_length(input: &str, system: MeasurementSystem) -> Result<f32, Strin
         MeasurementSystem::Imperial => {
             // Check for fraction
             if input.contains('/') {
+                let (sign, input) = match input.strip_prefix('-') {
+                    Some(rest) => (-1.0, rest.trim_start()),
+                    None => (1.0, input),
+                };
                 let parts: Vec<&str> = input.split_whitespace().collect();
                 let mut total_inches = 0.0;
 
@@ -172,7 +372,7 @@ pub fn parse_length(input: &str, system: MeasurementSystem) -> Result<f32, Strin
                         total_inches += part.parse::<f32>().map_err(|_| "Invalid number part")?;
                     }
                 }
-                Ok(total_inches * 25.4)
+                Ok(sign * total_inches * 25.4)
             } else {
                 // Decimal inches
                 let inches = input.parse::<f32>().map_err(|e| e.to_string())?;
@@ -194,6 +394,8 @@ pub fn parse_length(input: &str, system: MeasurementSystem) -> Result<f32, Strin
 /// # Errors
 /// Returns `Err` if the input is not a valid floating-point number.
 pub fn parse_feed_rate(input: &str, units: FeedRateUnits) -> Result<f32, String> {
+    let input =
+        normalize_decimal_separator(input, decimal_separator()).map_err(|e| e.to_string())?;
     let input = input.trim();
     if input.is_empty() {
         return Ok(0.0);
@@ -211,6 +413,259 @@ pub fn parse_feed_rate(input: &str, units: FeedRateUnits) -> Result<f32, String>
     Ok(mm_per_min)
 }
 
+/// Error from parsing a typed length or feed rate expression
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum UnitParseError {
+    /// Nothing was entered
+    #[error("No value entered")]
+    Empty,
+    /// A character that is not part of a number, operator or unit
+    #[error("Unexpected '{0}'")]
+    UnexpectedChar(char),
+    /// A malformed number such as `1.2.3`
+    #[error("Invalid number '{0}'")]
+    InvalidNumber(String),
+    /// The expression stopped where a value was expected, e.g. `10+`
+    #[error("Incomplete expression")]
+    Incomplete,
+    /// Division by zero
+    #[error("Division by zero")]
+    DivisionByZero,
+    /// A unit suffix that does not apply to the field
+    #[error("Unknown unit '{0}'")]
+    UnknownUnit(String),
+    /// A number with thousands separators or the wrong decimal separator
+    #[error("Ambiguous number '{0}': use one decimal separator and no thousands separators")]
+    AmbiguousSeparator(String),
+}
+
+/// Parse a length expression to millimeters.
+///
+/// Accepts arithmetic with `+ - * /` and parentheses (`10+0.5`, `1/2`, `(3-1)*2`),
+/// imperial mixed fractions (`1 1/2`) and an optional unit suffix applying to the
+/// whole expression: `mm`, `cm`, `m`, `in`/`inch`/`"` or `thou`/`mil`. Without a
+/// suffix the value is in `system`'s units.
+///
+/// # Errors
+/// Returns a [`UnitParseError`] for empty input, bad syntax, division by zero or
+/// an unknown unit.
+///
+/// # Example
+/// ```
+/// use gcodekit5_core::units::{parse_length_expr, MeasurementSystem};
+///
+/// assert_eq!(parse_length_expr("10+0.5", MeasurementSystem::Metric), Ok(10.5));
+/// assert_eq!(parse_length_expr("2 in", MeasurementSystem::Metric), Ok(50.8));
+/// assert_eq!(parse_length_expr("1/2", MeasurementSystem::Imperial), Ok(12.7));
+/// ```
+pub fn parse_length_expr(input: &str, system: MeasurementSystem) -> Result<f32, UnitParseError> {
+    let input = normalize_decimal_separator(input, decimal_separator())?;
+    let (expression, unit) = split_unit_suffix(&input);
+    let mm_per_unit = match unit.to_ascii_lowercase().as_str() {
+        "" => match system {
+            MeasurementSystem::Metric => 1.0,
+            MeasurementSystem::Imperial => MM_PER_INCH,
+        },
+        "mm" => 1.0,
+        "cm" => 10.0,
+        "m" => 1000.0,
+        "in" | "inch" | "inches" | "\"" => MM_PER_INCH,
+        "thou" | "mil" | "mils" => MM_PER_INCH / 1000.0,
+        _ => return Err(UnitParseError::UnknownUnit(unit.to_string())),
+    };
+    Ok((evaluate(expression)? * mm_per_unit) as f32)
+}
+
+/// Parse a feed rate expression to mm/min.
+///
+/// Accepts the same arithmetic as [`parse_length_expr`] and an optional suffix:
+/// `mm/min`, `mm/s`, `mm/sec`, `in/min` (`ipm`) or `in/s`, `in/sec` (`ips`).
+/// Without a suffix the value is in `units`.
+///
+/// # Errors
+/// Returns a [`UnitParseError`] for empty input, bad syntax, division by zero or
+/// an unknown unit.
+pub fn parse_feed_rate_expr(input: &str, units: FeedRateUnits) -> Result<f32, UnitParseError> {
+    let input = normalize_decimal_separator(input, decimal_separator())?;
+    let (expression, unit) = split_unit_suffix(&input);
+    let units = match unit.to_ascii_lowercase().replace(' ', "").as_str() {
+        "" => units,
+        "mm/min" => FeedRateUnits::MmPerMin,
+        "mm/s" | "mm/sec" => FeedRateUnits::MmPerSec,
+        "in/min" | "ipm" => FeedRateUnits::InPerMin,
+        "in/s" | "in/sec" | "ips" => FeedRateUnits::InPerSec,
+        _ => return Err(UnitParseError::UnknownUnit(unit.to_string())),
+    };
+    let value = evaluate(expression)?;
+    let mm_per_min = match units {
+        FeedRateUnits::MmPerMin => value,
+        FeedRateUnits::MmPerSec => value * 60.0,
+        FeedRateUnits::InPerMin => value * MM_PER_INCH,
+        FeedRateUnits::InPerSec => value * MM_PER_INCH * 60.0,
+    };
+    Ok(mm_per_min as f32)
+}
+
+/// Split trailing unit text (letters, `/` and `"`) from the expression before it
+fn split_unit_suffix(input: &str) -> (&str, &str) {
+    let input = input.trim();
+    let tail = input
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_ascii_alphabetic() || matches!(c, '/' | '"' | ' '))
+        .last()
+        .map_or(input.len(), |(index, _)| index);
+    // The unit starts at its first letter or quote; a leading '/' is division
+    let start = input[tail..]
+        .find(|c: char| c.is_ascii_alphabetic() || c == '"')
+        .map_or(input.len(), |offset| tail + offset);
+    (input[..start].trim(), input[start..].trim())
+}
+
+/// Evaluate an arithmetic expression
+fn evaluate(expression: &str) -> Result<f64, UnitParseError> {
+    let mut parser = ExpressionParser {
+        chars: expression.chars().collect(),
+        position: 0,
+    };
+    parser.skip_whitespace();
+    if parser.peek().is_none() {
+        return Err(UnitParseError::Empty);
+    }
+    let value = parser.expression()?;
+    match parser.peek() {
+        None => Ok(value),
+        Some(c) => Err(UnitParseError::UnexpectedChar(c)),
+    }
+}
+
+/// Recursive descent parser for `+ - * /`, unary minus and parentheses
+struct ExpressionParser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl ExpressionParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    fn expression(&mut self) -> Result<f64, UnitParseError> {
+        let mut value = self.term()?;
+        loop {
+            match self.peek() {
+                Some('+') => {
+                    self.position += 1;
+                    value += self.term()?;
+                }
+                Some('-') => {
+                    self.position += 1;
+                    value -= self.term()?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<f64, UnitParseError> {
+        let mut value = self.factor()?;
+        loop {
+            match self.peek() {
+                Some('*') => {
+                    self.position += 1;
+                    value *= self.factor()?;
+                }
+                Some('/') => {
+                    self.position += 1;
+                    value /= self.divisor()?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn divisor(&mut self) -> Result<f64, UnitParseError> {
+        match self.factor()? {
+            0.0 => Err(UnitParseError::DivisionByZero),
+            divisor => Ok(divisor),
+        }
+    }
+
+    fn factor(&mut self) -> Result<f64, UnitParseError> {
+        self.skip_whitespace();
+        let value = match self.peek() {
+            None => return Err(UnitParseError::Incomplete),
+            Some('-') => {
+                self.position += 1;
+                return Ok(-self.factor()?);
+            }
+            Some('+') => {
+                self.position += 1;
+                return self.factor();
+            }
+            Some('(') => {
+                self.position += 1;
+                let value = self.expression()?;
+                if self.peek() != Some(')') {
+                    return match self.peek() {
+                        None => Err(UnitParseError::Incomplete),
+                        Some(c) => Err(UnitParseError::UnexpectedChar(c)),
+                    };
+                }
+                self.position += 1;
+                value
+            }
+            Some(c) if is_number_char(c) => {
+                let whole = self.number()?;
+                self.skip_whitespace();
+                // A number followed by a fraction is a mixed number: `1 1/2`
+                if self.peek().is_some_and(is_number_char) {
+                    let numerator = self.number()?;
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some('/') => self.position += 1,
+                        Some(c) => return Err(UnitParseError::UnexpectedChar(c)),
+                        None => return Err(UnitParseError::Incomplete),
+                    }
+                    self.skip_whitespace();
+                    if !self.peek().is_some_and(is_number_char) {
+                        return Err(UnitParseError::Incomplete);
+                    }
+                    match self.number()? {
+                        0.0 => return Err(UnitParseError::DivisionByZero),
+                        denominator => whole + numerator / denominator,
+                    }
+                } else {
+                    whole
+                }
+            }
+            Some(c) => return Err(UnitParseError::UnexpectedChar(c)),
+        };
+        self.skip_whitespace();
+        Ok(value)
+    }
+
+    fn number(&mut self) -> Result<f64, UnitParseError> {
+        let start = self.position;
+        while self.peek().is_some_and(is_number_char) {
+            self.position += 1;
+        }
+        let text: String = self.chars[start..self.position].iter().collect();
+        text.parse::<f64>()
+            .map_err(|_| UnitParseError::InvalidNumber(text))
+    }
+}
+
+fn is_number_char(c: char) -> bool {
+    c.is_ascii_digit() || c == '.'
+}
+
 /// Get the unit label for the given system ("mm" or "in")
 pub fn get_unit_label(system: MeasurementSystem) -> &'static str {
     match system {
@@ -0,0 +1,221 @@
+use gcodekit5_core::{ArcMove, ArcPlane};
+use std::f64::consts::{FRAC_PI_2, PI};
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+#[test]
+fn test_plane_from_gcode() {
+    assert_eq!(ArcPlane::from_gcode(17), Some(ArcPlane::XY));
+    assert_eq!(ArcPlane::from_gcode(18), Some(ArcPlane::ZX));
+    assert_eq!(ArcPlane::from_gcode(19), Some(ArcPlane::YZ));
+    assert_eq!(ArcPlane::from_gcode(20), None);
+    assert_eq!(ArcPlane::ZX.offset_words(), ('K', 'I'));
+    assert_eq!(ArcPlane::default(), ArcPlane::XY);
+}
+
+#[test]
+fn test_xy_quarter_arc() {
+    // G3 X0 Y10 I-10 from (10, 0): CCW quarter circle around the origin
+    let arc = ArcMove::from_offsets(
+        [10.0, 0.0, 0.0],
+        [0.0, 10.0, 0.0],
+        [-10.0, 0.0, 0.0],
+        false,
+        ArcPlane::XY,
+    );
+    assert!(close(arc.radius(), 10.0));
+    assert!(close(arc.sweep(), FRAC_PI_2));
+
+    let points = arc.expand(4);
+    assert_eq!(points.len(), 4);
+    assert_eq!(*points.last().unwrap(), [0.0, 10.0, 0.0]);
+    for p in &points {
+        assert!(close(p[0].hypot(p[1]), 10.0));
+        assert!(p[0] >= -1e-9 && p[1] >= -1e-9);
+    }
+}
+
+#[test]
+fn test_xz_arc_expands_in_xz_plane() {
+    // G18 G2 X10 Z0 I5 K0 from (0, 3, 0): half circle in XZ with center (5, 3, 0)
+    let arc = ArcMove::from_offsets(
+        [0.0, 3.0, 0.0],
+        [10.0, 3.0, 0.0],
+        [5.0, 0.0, 0.0],
+        true,
+        ArcPlane::ZX,
+    );
+    assert!(close(arc.radius(), 5.0));
+    assert!(close(arc.sweep(), -PI));
+
+    let points = arc.expand(8);
+    for p in &points {
+        // Y stays on the plane, points lie on the circle in XZ
+        assert!(close(p[1], 3.0));
+        assert!(close((p[0] - 5.0).hypot(p[2]), 5.0));
+    }
+    // Clockwise viewed from +Y (Z right, X up) goes through -Z from X0 to X10
+    let mid = points[3];
+    assert!(close(mid[0], 5.0));
+    assert!(close(mid[2], -5.0));
+}
+
+#[test]
+fn test_yz_arc_uses_j_and_k_offsets() {
+    // G19 G3 from (1, 0, 0) to (1, 0, 0) with J2: full circle in YZ
+    let arc = ArcMove::from_offsets(
+        [1.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [7.0, 2.0, 0.0],
+        false,
+        ArcPlane::YZ,
+    );
+    assert_eq!(arc.center, [1.0, 2.0, 0.0]);
+    assert!(close(arc.sweep(), 2.0 * PI));
+    for p in arc.expand(16) {
+        assert!(close(p[0], 1.0));
+        assert!(close((p[1] - 2.0).hypot(p[2]), 2.0));
+    }
+}
+
+#[test]
+fn test_segments_for_scales_with_arc_length() {
+    let arc = ArcMove::from_offsets(
+        [10.0, 0.0, 0.0],
+        [-10.0, 0.0, 0.0],
+        [-10.0, 0.0, 0.0],
+        false,
+        ArcPlane::XY,
+    );
+    // Half circle of radius 10 is ~31.4mm long
+    assert_eq!(arc.segments_for(1.0), 32);
+    assert_eq!(arc.segments_for(100.0), 1);
+}
+
+#[test]
+fn test_radius_arc_picks_short_arc_for_positive_r() {
+    // G2 X10 Y0 R10 from the origin: center below the chord, 60 degree sweep
+    let arc =
+        ArcMove::from_radius([0.0, 0.0, 0.0], [10.0, 0.0, 0.0], 10.0, true, ArcPlane::XY).unwrap();
+    assert!(close(arc.center[0], 5.0));
+    assert!(close(arc.center[1], -75f64.sqrt()));
+    assert!(close(arc.radius(), 10.0));
+    assert!(close(arc.sweep(), -PI / 3.0));
+
+    // Negative R takes the long way around
+    let arc =
+        ArcMove::from_radius([0.0, 0.0, 0.0], [10.0, 0.0, 0.0], -10.0, true, ArcPlane::XY).unwrap();
+    assert!(close(arc.center[1], 75f64.sqrt()));
+    assert!(close(arc.sweep(), -5.0 * PI / 3.0));
+}
+
+#[test]
+fn test_radius_arc_ccw_and_semicircle() {
+    let arc =
+        ArcMove::from_radius([0.0, 0.0, 0.0], [10.0, 0.0, 0.0], 10.0, false, ArcPlane::XY).unwrap();
+    assert!(close(arc.center[1], 75f64.sqrt()));
+    assert!(close(arc.sweep(), PI / 3.0));
+
+    // R exactly half the chord is a semicircle
+    let arc =
+        ArcMove::from_radius([0.0, 0.0, 0.0], [10.0, 0.0, 0.0], 5.0, true, ArcPlane::XY).unwrap();
+    assert_eq!(arc.center, [5.0, 0.0, 0.0]);
+}
+
+#[test]
+fn test_radius_arc_in_xz_plane() {
+    let arc =
+        ArcMove::from_radius([0.0, 0.0, 0.0], [10.0, 0.0, 0.0], 5.0, true, ArcPlane::ZX).unwrap();
+    assert!(close(arc.center[0], 5.0));
+    assert!(close(arc.center[2], 0.0));
+    assert!(arc.expand(8).iter().all(|p| p[1] == 0.0));
+}
+
+#[test]
+fn test_radius_arc_degenerate_cases_are_errors() {
+    let err = ArcMove::from_radius([0.0, 0.0, 0.0], [10.0, 0.0, 0.0], 4.0, true, ArcPlane::XY)
+        .unwrap_err();
+    assert!(err.to_string().contains("too small"), "{}", err);
+
+    assert!(
+        ArcMove::from_radius([1.0, 1.0, 0.0], [1.0, 1.0, 5.0], 3.0, true, ArcPlane::XY).is_err()
+    );
+    assert!(ArcMove::from_radius(
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        f64::NAN,
+        true,
+        ArcPlane::XY
+    )
+    .is_err());
+}
+
+#[test]
+fn test_helix_interpolates_linear_axis() {
+    // Half turn in XY descending 3mm
+    let arc = ArcMove::from_offsets(
+        [10.0, 0.0, 0.0],
+        [-10.0, 0.0, -3.0],
+        [-10.0, 0.0, 0.0],
+        false,
+        ArcPlane::XY,
+    );
+    assert!(close(arc.linear_travel(), -3.0));
+    assert!(close(arc.length(), (10.0 * PI).hypot(3.0)));
+
+    let points = arc.expand(arc.segments_for(0.5));
+    assert_eq!(*points.last().unwrap(), [-10.0, 0.0, -3.0]);
+    let mut previous_z = 0.0;
+    for p in &points {
+        assert!(p[2] < previous_z);
+        previous_z = p[2];
+    }
+    // Halfway through the sweep is halfway down
+    let mid = arc.expand(2)[0];
+    assert!(close(mid[1], 10.0) && close(mid[2], -1.5));
+}
+
+#[test]
+fn test_helix_segments_scale_with_linear_travel() {
+    let flat = ArcMove::from_offsets(
+        [1.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [-1.0, 0.0, 0.0],
+        true,
+        ArcPlane::XY,
+    );
+    let steep = ArcMove {
+        end: [1.0, 0.0, 20.0],
+        ..flat
+    };
+    assert_eq!(flat.segments_for(1.0), 7);
+    assert_eq!(steep.segments_for(1.0), 21);
+}
+
+#[test]
+fn test_split_keeps_sweep_and_joins_on_the_arc() {
+    // Full circle with a 4mm helical climb
+    let arc = ArcMove::from_offsets(
+        [10.0, 0.0, 0.0],
+        [10.0, 0.0, 4.0],
+        [-10.0, 0.0, 0.0],
+        true,
+        ArcPlane::XY,
+    );
+    let pieces = arc.split(3);
+    assert_eq!(pieces.len(), 3);
+    assert_eq!(pieces[0].start, arc.start);
+    assert_eq!(pieces[2].end, arc.end);
+    let total: f64 = pieces.iter().map(|p| p.sweep()).sum();
+    assert!(close(total, arc.sweep()));
+    for pair in pieces.windows(2) {
+        assert_eq!(pair[0].end, pair[1].start);
+        assert!(close(pair[0].end[0].hypot(pair[0].end[1]), 10.0));
+    }
+    assert!(pieces
+        .iter()
+        .all(|p| p.clockwise && close(p.radius(), 10.0)));
+    assert!(close(pieces[0].end[2], 4.0 / 3.0));
+}
@@ -0,0 +1,30 @@
+use gcodekit5_core::gcode::*;
+
+#[test]
+fn split_strips_prefix_and_whitespace() {
+    assert_eq!(split_block_delete("/G1 X10"), ("G1 X10", true));
+    assert_eq!(split_block_delete("  / M8"), ("M8", true));
+    assert_eq!(split_block_delete("G1 X10"), ("G1 X10", false));
+    assert_eq!(split_block_delete("G1 X10 /"), ("G1 X10 /", false));
+}
+
+#[test]
+fn apply_skips_only_when_switch_is_on() {
+    assert_eq!(apply_block_delete("/G0 Z5", true), None);
+    assert_eq!(apply_block_delete("/G0 Z5", false), Some("G0 Z5"));
+    assert_eq!(apply_block_delete("G0 Z5", true), Some("G0 Z5"));
+}
+
+#[test]
+fn command_records_block_delete() {
+    let cmd = GcodeCommand::new("/G1 X10 Y5");
+    assert!(cmd.block_delete);
+    assert_eq!(cmd.line, "/G1 X10 Y5");
+    assert_eq!(cmd.command, "G1 X10 Y5");
+    assert!(cmd.is_block_deleted(true));
+    assert!(!cmd.is_block_deleted(false));
+
+    let plain = GcodeCommand::new("G1 X10");
+    assert!(!plain.block_delete);
+    assert!(!plain.is_block_deleted(true));
+}
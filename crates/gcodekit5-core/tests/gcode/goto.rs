@@ -0,0 +1,31 @@
+use gcodekit5_core::gcode::*;
+
+#[test]
+fn goto_retracts_before_moving_across() {
+    assert_eq!(
+        safe_goto_commands(0.0, 0.0, None, Some(-3.0), 5.0, None),
+        ["G21 G90 G0 Z5", "G21 G90 G0 X0 Y0"]
+    );
+    // Unknown height always retracts; the target Z comes last
+    assert_eq!(
+        safe_goto_commands(10.5, -2.0, Some(0.0), None, 5.0, None),
+        ["G21 G90 G0 Z5", "G21 G90 G0 X10.5 Y-2", "G21 G90 G0 Z0"]
+    );
+}
+
+#[test]
+fn goto_skips_retract_when_already_high_and_respects_ceiling() {
+    assert_eq!(
+        safe_goto_commands(0.0, 0.0, None, Some(12.0), 5.0, None),
+        ["G21 G90 G0 X0 Y0"]
+    );
+    // The top of travel is only 3 mm above work zero
+    assert_eq!(
+        safe_goto_commands(0.0, 0.0, None, Some(-1.0), 5.0, Some(3.0)),
+        ["G21 G90 G0 Z3", "G21 G90 G0 X0 Y0"]
+    );
+    assert_eq!(
+        safe_goto_commands(0.0, 0.0, None, Some(3.0), 5.0, Some(3.0)),
+        ["G21 G90 G0 X0 Y0"]
+    );
+}
@@ -0,0 +1,63 @@
+use gcodekit5_core::gcode::*;
+
+#[test]
+fn follows_spindle_words_outside_comments() {
+    let mut power = SpindlePower::default();
+    assert!(!power.is_on());
+    power.observe("M4 S800 (laser on)");
+    assert_eq!(power.mode, Some(SpindleMode::CounterClockwise));
+    assert_eq!(power.speed, Some(800.0));
+    power.observe("G1 X10 S650 ; M5 S0 in a comment");
+    assert_eq!(power.speed, Some(650.0));
+    assert!(power.is_on());
+    power.observe("M5");
+    assert!(!power.is_on());
+    power.observe("M3");
+    assert!(power.is_on());
+    power.observe("M30");
+    assert_eq!(power.mode, Some(SpindleMode::Stopped));
+}
+
+#[test]
+fn restores_the_previous_power() {
+    let mut power = SpindlePower::default();
+    power.observe("M4 S650.5");
+    assert_eq!(
+        power.restore_command(HoldPowerOff::SpindleStop).as_deref(),
+        Some("M4 S650.5")
+    );
+    assert_eq!(
+        power.restore_command(HoldPowerOff::ZeroPower).as_deref(),
+        Some("S650.5")
+    );
+    assert_eq!(power.restore_command(HoldPowerOff::Disabled), None);
+
+    // Without a known speed S0 cannot be undone, but M5 can
+    let mut unknown = SpindlePower::default();
+    unknown.observe("M3");
+    assert_eq!(unknown.restore_command(HoldPowerOff::ZeroPower), None);
+    assert_eq!(
+        unknown
+            .restore_command(HoldPowerOff::SpindleStop)
+            .as_deref(),
+        Some("M3")
+    );
+
+    // Nothing to restore when the power was already off
+    let mut off = SpindlePower::default();
+    off.observe("M3 S0");
+    assert_eq!(off.restore_command(HoldPowerOff::SpindleStop), None);
+}
+
+#[test]
+fn parses_power_off_choices() {
+    for choice in [
+        HoldPowerOff::Disabled,
+        HoldPowerOff::SpindleStop,
+        HoldPowerOff::ZeroPower,
+    ] {
+        assert_eq!(choice.to_string().parse::<HoldPowerOff>(), Ok(choice));
+    }
+    assert_eq!("m5".parse::<HoldPowerOff>(), Ok(HoldPowerOff::SpindleStop));
+    assert!("M9".parse::<HoldPowerOff>().is_err());
+}
@@ -0,0 +1,51 @@
+use gcodekit5_core::gcode::*;
+
+fn rates(axis: char) -> Option<f64> {
+    match axis {
+        'X' | 'Y' => Some(3000.0),
+        'Z' => Some(300.0),
+        _ => None,
+    }
+}
+
+#[test]
+fn single_axis_jog_uses_requested_feed_up_to_axis_limit() {
+    assert_eq!(
+        jog_command(&[('X', 1.0)], 1000.0, |_| None).as_deref(),
+        Some("$J=G91 X1 F1000")
+    );
+    assert_eq!(
+        jog_command(&[('Y', -0.1)], 1000.0, rates).as_deref(),
+        Some("$J=G91 Y-0.1 F1000")
+    );
+    assert_eq!(
+        jog_command(&[('Z', 5.0)], 1000.0, rates).as_deref(),
+        Some("$J=G91 Z5 F300")
+    );
+    assert_eq!(jog_command(&[('X', 0.0)], 1000.0, rates), None);
+}
+
+#[test]
+fn diagonal_jog_feed_respects_every_axis_limit() {
+    // Equal XY moves: each axis runs at feed / sqrt(2)
+    let feed = limited_jog_feed(&[('X', 10.0), ('Y', 10.0)], 5000.0, rates);
+    assert!((feed - 3000.0 * 2f64.sqrt()).abs() < 1e-9);
+
+    // Z is the slow axis of an XZ move
+    let deltas = [('X', 10.0), ('Z', -10.0)];
+    let feed = limited_jog_feed(&deltas, 5000.0, rates);
+    let length = 200f64.sqrt();
+    for (axis, delta) in deltas {
+        assert!(feed * delta.abs() / length <= rates(axis).unwrap() + 1e-9);
+    }
+    assert_eq!(
+        jog_command(&deltas, 5000.0, rates).as_deref(),
+        Some("$J=G91 X10 Z-10 F424.264")
+    );
+
+    // Below the limits the requested feed is kept
+    assert_eq!(
+        limited_jog_feed(&[('X', 1.0), ('Y', 1.0)], 1000.0, rates),
+        1000.0
+    );
+}
@@ -0,0 +1,9 @@
+mod arc;
+mod block_delete;
+mod goto;
+mod hold_power;
+mod jog;
+mod number;
+mod number_generator;
+mod program_stop;
+mod tracker;
@@ -0,0 +1,17 @@
+use gcodekit5_core::gcode::format_gcode_number;
+
+#[test]
+fn test_numbers_are_written_in_their_shortest_form() {
+    assert_eq!(format_gcode_number(10.5, 3), "10.5");
+    assert_eq!(format_gcode_number(2.0, 4), "2");
+    assert_eq!(format_gcode_number(1.23456, 4), "1.2346");
+    assert_eq!(format_gcode_number(-0.0001, 3), "0");
+    assert_eq!(format_gcode_number(-1.25, 1), "-1.2");
+}
+
+#[test]
+fn test_whole_numbers_keep_their_zeros() {
+    assert_eq!(format_gcode_number(100.0, 0), "100");
+    assert_eq!(format_gcode_number(-0.4, 0), "0");
+    assert_eq!(format_gcode_number(12000.0, 3), "12000");
+}
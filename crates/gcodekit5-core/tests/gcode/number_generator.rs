@@ -0,0 +1,96 @@
+use gcodekit5_core::gcode::*;
+
+fn bounded(start: u32, max: u32, mode: SequenceMode) -> CommandNumberGenerator {
+    CommandNumberGenerator::with_config(CommandNumberConfig {
+        start,
+        max: Some(max),
+        mode,
+    })
+}
+
+#[test]
+fn test_default_sequence_is_unbounded() {
+    let gen = CommandNumberGenerator::new();
+    assert_eq!(gen.next(), 0);
+    assert_eq!(gen.next(), 1);
+    assert_eq!(gen.current(), 2);
+    // Unbounded sequences never wrap, so nothing is tracked as in flight.
+    assert_eq!(gen.in_flight_count(), 0);
+    gen.reset();
+    assert_eq!(gen.next(), 0);
+}
+
+#[test]
+fn test_wraps_to_start_after_max() {
+    let gen = bounded(1, 3, SequenceMode::SkipInFlight);
+    let issued: Vec<u32> = (0..3).map(|_| gen.next()).collect();
+    assert_eq!(issued, vec![1, 2, 3]);
+    for n in issued {
+        gen.acknowledge(n);
+    }
+    assert_eq!(gen.next(), 1);
+}
+
+#[test]
+fn test_wrap_skips_in_flight_numbers() {
+    let gen = bounded(1, 3, SequenceMode::SkipInFlight);
+    gen.next(); // 1 stays in flight
+    let two = gen.next();
+    let three = gen.next();
+    gen.acknowledge(two);
+    gen.acknowledge(three);
+
+    assert!(gen.is_in_flight(1));
+    assert_eq!(gen.next(), 2);
+    assert_eq!(gen.next(), 3);
+    // 1, 2 and 3 are all in flight now
+    assert_eq!(gen.try_next(), None);
+}
+
+#[test]
+fn test_gap_free_waits_for_acknowledgment() {
+    let gen = bounded(1, 2, SequenceMode::GapFree);
+    assert_eq!(gen.try_next(), Some(1));
+    assert_eq!(gen.try_next(), Some(2));
+    assert_eq!(gen.try_next(), None);
+
+    gen.acknowledge(1);
+    assert_eq!(gen.try_next(), Some(1));
+}
+
+#[test]
+fn test_gap_free_release_rewinds() {
+    let gen = bounded(0, 99, SequenceMode::GapFree);
+    assert_eq!(gen.next(), 0);
+    let unsent = gen.next();
+    gen.release(unsent);
+    assert_eq!(gen.next(), unsent);
+
+    // Releasing an older number does not rewind.
+    gen.release(0);
+    assert_eq!(gen.next(), 2);
+}
+
+#[test]
+fn test_checkpoint_and_reset_to() {
+    let gen = bounded(1, 100, SequenceMode::SkipInFlight);
+    gen.next();
+    let checkpoint = gen.checkpoint();
+    gen.next();
+    gen.next();
+    gen.reset_to(checkpoint);
+    assert_eq!(gen.in_flight_count(), 0);
+    assert_eq!(gen.next(), checkpoint);
+
+    gen.reset_to(500);
+    assert_eq!(gen.current(), 100);
+}
+
+#[test]
+fn test_clones_share_sequence() {
+    let gen = bounded(0, 9, SequenceMode::SkipInFlight);
+    let other = gen.clone();
+    assert_eq!(gen.next(), 0);
+    assert_eq!(other.next(), 1);
+    assert_eq!(gen.in_flight_count(), 2);
+}
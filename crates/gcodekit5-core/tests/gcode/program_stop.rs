@@ -0,0 +1,42 @@
+use gcodekit5_core::gcode::*;
+
+#[test]
+fn detects_stop_words_outside_comments() {
+    assert_eq!(program_stop("M0"), Some(ProgramStop::Mandatory));
+    assert_eq!(program_stop("n10 m00"), Some(ProgramStop::Mandatory));
+    assert_eq!(
+        program_stop("M1 (check depth)"),
+        Some(ProgramStop::Optional)
+    );
+    assert_eq!(program_stop("M01"), Some(ProgramStop::Optional));
+    assert_eq!(program_stop("G0 X10 ; M0 later"), None);
+    assert_eq!(program_stop("(M0)"), None);
+    assert_eq!(program_stop("M30"), None);
+    assert_eq!(program_stop("M2"), None);
+    assert_eq!(program_stop("M100"), None);
+    assert_eq!(program_stop("G1 X1.0 F100"), None);
+}
+
+#[test]
+fn only_stop_allows_line_numbers_and_comments() {
+    assert!(is_only_program_stop("N20 M0 (MSG,Change tool)"));
+    assert!(is_only_program_stop("M1"));
+    assert!(!is_only_program_stop("M1 M8"));
+    assert!(!is_only_program_stop("G0 Z5 M0"));
+    assert!(!is_only_program_stop("G0 Z5"));
+}
+
+#[test]
+fn reads_message_comments() {
+    assert_eq!(
+        message_comment("(MSG, Change to 3mm end mill)"),
+        Some("Change to 3mm end mill")
+    );
+    assert_eq!(
+        message_comment("M0 (msg,Flip the part)"),
+        Some("Flip the part")
+    );
+    assert_eq!(message_comment("(note) (MSG,Second)"), Some("Second"));
+    assert_eq!(message_comment("(Just a comment)"), None);
+    assert_eq!(message_comment("M0"), None);
+}
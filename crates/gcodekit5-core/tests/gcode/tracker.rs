@@ -0,0 +1,247 @@
+use gcodekit5_core::gcode::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[derive(Default)]
+struct CountingListener {
+    sent: AtomicUsize,
+    ok: AtomicUsize,
+    errors: AtomicUsize,
+    timeouts: AtomicUsize,
+}
+
+impl CommandListener for CountingListener {
+    fn on_command_created(&self, _command: &GcodeCommand) {}
+    fn on_command_sent(&self, _command: &GcodeCommand) {
+        self.sent.fetch_add(1, Ordering::SeqCst);
+    }
+    fn on_command_ok(&self, _command: &GcodeCommand) {
+        self.ok.fetch_add(1, Ordering::SeqCst);
+    }
+    fn on_command_completed(&self, _command: &GcodeCommand) {}
+    fn on_command_error(&self, _command: &GcodeCommand, _error: &CommandResponse) {
+        self.errors.fetch_add(1, Ordering::SeqCst);
+    }
+    fn on_command_skipped(&self, _command: &GcodeCommand) {}
+    fn on_command_timeout(&self, _command: &GcodeCommand) {
+        self.timeouts.fetch_add(1, Ordering::SeqCst);
+    }
+    fn on_command_state_changed(&self, _command: &GcodeCommand, _old_state: CommandState) {}
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[test]
+fn test_timeout_policy_by_command_type() {
+    let config = CommandTimeoutConfig::default();
+    assert_eq!(config.timeout_for("$$"), Some(config.default_timeout_ms));
+    assert_eq!(
+        config.timeout_for("M3 S1000"),
+        Some(config.default_timeout_ms)
+    );
+    assert_eq!(
+        config.timeout_for("G1 X10 F100"),
+        Some(config.motion_timeout_ms)
+    );
+    assert_eq!(config.timeout_for("g01x5"), Some(config.motion_timeout_ms));
+    assert_eq!(config.timeout_for("$H"), None);
+    assert_eq!(config.timeout_for("G38.2 Z-10 F50"), None);
+    assert_eq!(config.timeout_for("G28"), None);
+    // M0 is excluded but M03 is not
+    assert_eq!(config.timeout_for("M0"), None);
+    assert_eq!(config.timeout_for("M03"), Some(config.default_timeout_ms));
+    // Comments are ignored
+    assert_eq!(
+        config.timeout_for("G0 X1 (G28 later)"),
+        Some(config.motion_timeout_ms)
+    );
+}
+
+#[test]
+fn test_dwell_extends_timeout() {
+    let config = CommandTimeoutConfig::default();
+    assert_eq!(
+        config.timeout_for("G4 P30"),
+        Some(config.default_timeout_ms + 30_000)
+    );
+
+    let no_dwell = CommandTimeoutConfig {
+        include_dwell_time: false,
+        ..Default::default()
+    };
+    assert_eq!(
+        no_dwell.timeout_for("G4 P30"),
+        Some(no_dwell.default_timeout_ms)
+    );
+}
+
+#[test]
+fn test_disabled_config_never_times_out() {
+    let config = CommandTimeoutConfig {
+        enabled: false,
+        ..Default::default()
+    };
+    assert_eq!(config.timeout_for("G1 X1"), None);
+}
+
+#[test]
+fn test_ok_and_error_match_fifo() {
+    let listener = Arc::new(CountingListener::default());
+    let mut tracker = CommandTracker::default();
+    tracker.add_listener(listener.clone());
+
+    tracker.track_sent(GcodeCommand::new("G0 X1"));
+    tracker.track_sent(GcodeCommand::new("G0 X2"));
+    assert_eq!(tracker.outstanding_count(), 2);
+
+    let first = tracker.handle_ok().expect("first command");
+    assert_eq!(first.command, "G0 X1");
+    assert_eq!(first.state, CommandState::Ok);
+
+    let second = tracker
+        .handle_error(Some(20), "error:20".to_string())
+        .expect("second command");
+    assert_eq!(second.command, "G0 X2");
+    assert_eq!(second.state, CommandState::Error);
+    assert!(tracker.handle_ok().is_none());
+
+    assert_eq!(listener.sent.load(Ordering::SeqCst), 2);
+    assert_eq!(listener.ok.load(Ordering::SeqCst), 1);
+    assert_eq!(listener.errors.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_head_command_times_out() {
+    let listener = Arc::new(CountingListener::default());
+    let mut tracker = CommandTracker::default();
+    tracker.add_listener(listener.clone());
+
+    tracker.track_sent(GcodeCommand::new("M8"));
+    tracker.track_sent(GcodeCommand::new("M9"));
+
+    let start = now_ms();
+    assert!(tracker.check_timeouts(start + 1_000).is_empty());
+
+    let timed_out = tracker.check_timeouts(start + 11_000);
+    assert_eq!(timed_out.len(), 1);
+    assert_eq!(timed_out[0].command, "M8");
+    assert_eq!(timed_out[0].state, CommandState::TimedOut);
+    assert!(timed_out[0].is_terminal());
+    assert_eq!(listener.timeouts.load(Ordering::SeqCst), 1);
+
+    // The next command's window starts when it becomes the head.
+    assert!(tracker.check_timeouts(start + 12_000).is_empty());
+    assert_eq!(tracker.outstanding_count(), 1);
+    assert_eq!(tracker.check_timeouts(start + 22_000).len(), 1);
+}
+
+#[test]
+fn test_excluded_command_never_times_out() {
+    let mut tracker = CommandTracker::default();
+    tracker.track_sent(GcodeCommand::new("$H"));
+    assert!(tracker.check_timeouts(now_ms() + 3_600_000).is_empty());
+    assert_eq!(tracker.outstanding_count(), 1);
+    tracker.clear();
+    assert_eq!(tracker.outstanding_count(), 0);
+}
+
+#[test]
+fn test_timed_out_command_is_resent_up_to_the_limit() {
+    let mut tracker = CommandTracker::new(CommandTimeoutConfig {
+        max_resends: 1,
+        ..Default::default()
+    });
+    tracker.track_sent(GcodeCommand::new("M8"));
+
+    let timed_out = tracker.check_timeouts(now_ms() + 11_000).remove(0);
+    assert!(tracker.resend(timed_out));
+    assert_eq!(tracker.outstanding_count(), 1);
+    assert_eq!(
+        tracker.outstanding().next().map(|c| c.state),
+        Some(CommandState::Sent)
+    );
+
+    // The resent command gets a new window, then the limit is reached
+    assert!(tracker.check_timeouts(now_ms() + 1_000).is_empty());
+    let timed_out = tracker.check_timeouts(now_ms() + 11_000).remove(0);
+    assert!(!tracker.resend(timed_out));
+    assert_eq!(tracker.outstanding_count(), 0);
+
+    // Resends are off by default
+    let mut tracker = CommandTracker::default();
+    tracker.track_sent(GcodeCommand::new("M8"));
+    let timed_out = tracker.check_timeouts(now_ms() + 11_000).remove(0);
+    assert!(!tracker.resend(timed_out));
+}
+
+#[test]
+fn test_restarted_timeout_window() {
+    let mut tracker = CommandTracker::default();
+    tracker.track_sent(GcodeCommand::new("M8"));
+
+    let start = now_ms();
+    tracker.restart_timeout(start + 50_000);
+    assert!(tracker.check_timeouts(start + 55_000).is_empty());
+    assert_eq!(tracker.check_timeouts(start + 60_000).len(), 1);
+}
+
+#[test]
+fn test_answered_commands_free_their_numbers() {
+    let numbers = CommandNumberGenerator::with_config(CommandNumberConfig {
+        start: 1,
+        max: Some(2),
+        mode: SequenceMode::GapFree,
+    });
+    let mut tracker = CommandTracker::default();
+    tracker.set_number_generator(numbers.clone());
+    let send = |tracker: &mut CommandTracker, line: &str| {
+        let number = numbers.try_next()?;
+        tracker.track_sent(GcodeCommand::with_sequence(line, number));
+        Some(number)
+    };
+
+    assert_eq!(send(&mut tracker, "M8"), Some(1));
+    assert_eq!(send(&mut tracker, "M9"), Some(2));
+    assert_eq!(send(&mut tracker, "M8"), None);
+
+    tracker.handle_ok();
+    assert_eq!(send(&mut tracker, "M8"), Some(1));
+    tracker.handle_error(Some(20), "error:20".to_string());
+    assert_eq!(send(&mut tracker, "M9"), Some(2));
+
+    // A timed-out command keeps its number until it is given up on
+    let timed_out = tracker.check_timeouts(now_ms() + 11_000).remove(0);
+    assert!(numbers.is_in_flight(timed_out.sequence_number));
+    assert!(!tracker.resend(timed_out));
+    assert_eq!(numbers.in_flight_count(), 1);
+
+    tracker.clear();
+    assert_eq!(numbers.in_flight_count(), 0);
+}
+
+#[test]
+fn test_clear_frees_timed_out_command_numbers() {
+    let numbers = CommandNumberGenerator::with_config(CommandNumberConfig {
+        start: 1,
+        max: Some(2),
+        mode: SequenceMode::GapFree,
+    });
+    let mut tracker = CommandTracker::default();
+    tracker.set_number_generator(numbers.clone());
+    for line in ["M8", "M9"] {
+        let number = numbers.try_next().expect("a free number");
+        tracker.track_sent(GcodeCommand::with_sequence(line, number));
+    }
+
+    // Aborted with a reset instead of being resent
+    let timed_out = tracker.check_timeouts(now_ms() + 11_000).remove(0);
+    assert!(numbers.is_in_flight(timed_out.sequence_number));
+    tracker.clear();
+    assert_eq!(numbers.in_flight_count(), 0);
+    assert_eq!(numbers.try_next(), Some(1));
+}
@@ -3,8 +3,9 @@
 //! Tests verify correct conversion between Metric (mm) and Imperial (inch) units.
 
 use gcodekit5_core::units::{
-    format_feed_rate, format_length, get_unit_label, parse_feed_rate, parse_length, FeedRateUnits,
-    MeasurementSystem,
+    format_feed_rate, format_length, format_length_with, get_unit_label, parse_feed_rate,
+    parse_feed_rate_expr, parse_length, parse_length_expr, FeedRateUnits, InchDisplay,
+    InchFraction, MeasurementSystem, UnitParseError,
 };
 
 const MM_PER_INCH: f32 = 25.4;
@@ -170,3 +171,180 @@ fn test_fractional_inches_parsing() {
         );
     }
 }
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-3,
+        "expected {}, got {}",
+        expected,
+        actual
+    );
+}
+
+#[test]
+fn test_length_expressions_evaluate_arithmetic() {
+    let metric = MeasurementSystem::Metric;
+    assert_close(parse_length_expr("10+0.5", metric).unwrap(), 10.5);
+    assert_close(parse_length_expr("1/2", metric).unwrap(), 0.5);
+    assert_close(parse_length_expr("(3 - 1) * 2", metric).unwrap(), 4.0);
+    assert_close(parse_length_expr("2+3*4", metric).unwrap(), 14.0);
+    assert_close(parse_length_expr("-5 + -2", metric).unwrap(), -7.0);
+    assert_close(parse_length_expr("10 - 1 1/2", metric).unwrap(), 8.5);
+}
+
+#[test]
+fn test_length_expressions_use_suffix_or_current_system() {
+    let metric = MeasurementSystem::Metric;
+    let imperial = MeasurementSystem::Imperial;
+    assert_close(parse_length_expr("2 in", metric).unwrap(), 50.8);
+    assert_close(parse_length_expr("1/2\"", metric).unwrap(), 12.7);
+    assert_close(parse_length_expr("1.5cm", metric).unwrap(), 15.0);
+    assert_close(parse_length_expr("10 mm", imperial).unwrap(), 10.0);
+    assert_close(parse_length_expr("1 1/2", imperial).unwrap(), 38.1);
+    assert_close(parse_length_expr("-1 1/2", imperial).unwrap(), -38.1);
+    assert_close(parse_length_expr("5 thou", imperial).unwrap(), 0.127);
+}
+
+#[test]
+fn test_feed_rate_expressions() {
+    let units = FeedRateUnits::MmPerMin;
+    assert_close(parse_feed_rate_expr("1000/2", units).unwrap(), 500.0);
+    assert_close(parse_feed_rate_expr("10 mm/s", units).unwrap(), 600.0);
+    assert_close(parse_feed_rate_expr("10 ipm", units).unwrap(), 254.0);
+    assert_close(parse_feed_rate_expr("60/60 in/sec", units).unwrap(), 1524.0);
+    assert_close(
+        parse_feed_rate_expr("20", FeedRateUnits::InPerMin).unwrap(),
+        508.0,
+    );
+}
+
+#[test]
+fn test_invalid_expressions_return_typed_errors() {
+    let metric = MeasurementSystem::Metric;
+    assert_eq!(parse_length_expr("", metric), Err(UnitParseError::Empty));
+    assert_eq!(
+        parse_length_expr("  in", metric),
+        Err(UnitParseError::Empty)
+    );
+    assert_eq!(
+        parse_length_expr("10+", metric),
+        Err(UnitParseError::Incomplete)
+    );
+    assert_eq!(
+        parse_length_expr("(1+2", metric),
+        Err(UnitParseError::Incomplete)
+    );
+    assert_eq!(
+        parse_length_expr("5/0", metric),
+        Err(UnitParseError::DivisionByZero)
+    );
+    assert_eq!(
+        parse_length_expr("1 1/0", metric),
+        Err(UnitParseError::DivisionByZero)
+    );
+    assert_eq!(
+        parse_length_expr("1.2.3", metric),
+        Err(UnitParseError::InvalidNumber("1.2.3".to_string()))
+    );
+    assert_eq!(
+        parse_length_expr("2 ft", metric),
+        Err(UnitParseError::UnknownUnit("ft".to_string()))
+    );
+    assert_eq!(
+        parse_length_expr("1 2", metric),
+        Err(UnitParseError::Incomplete)
+    );
+    assert_eq!(
+        parse_length_expr("3 % 2", metric),
+        Err(UnitParseError::UnexpectedChar('%'))
+    );
+    assert_eq!(
+        parse_feed_rate_expr("10 in", FeedRateUnits::MmPerMin),
+        Err(UnitParseError::UnknownUnit("in".to_string()))
+    );
+}
+
+#[test]
+fn test_format_length_inch_fractions() {
+    let fraction = InchDisplay::Fraction(InchFraction::default());
+    let format = |inches: f32| {
+        format_length_with(inches * MM_PER_INCH, MeasurementSystem::Imperial, fraction)
+    };
+
+    assert_eq!(format(0.125), "1/8");
+    assert_eq!(format(0.1875), "3/16");
+    assert_eq!(format(1.25), "1 1/4");
+    assert_eq!(format(2.0), "2");
+    assert_eq!(format(-1.5), "-1 1/2");
+    assert_eq!(format(3.0 / 64.0), "3/64");
+    // No sixty-fourth is within a thousandth of 0.01"
+    assert_eq!(format(0.01), "0.010");
+
+    let eighths = InchDisplay::Fraction(InchFraction {
+        denominator: 8,
+        tolerance: 0.001,
+    });
+    assert_eq!(
+        format_length_with(MM_PER_INCH / 16.0, MeasurementSystem::Imperial, eighths),
+        "0.062"
+    );
+    // Metric lengths stay decimal
+    assert_eq!(
+        format_length_with(12.7, MeasurementSystem::Metric, fraction),
+        "12.700"
+    );
+}
+
+#[test]
+fn test_inch_fractions_round_trip() {
+    let fraction = InchDisplay::Fraction(InchFraction::default());
+    for text in ["3/8", "1 1/4", "-1 1/4", "5 63/64"] {
+        let mm = parse_length(text, MeasurementSystem::Imperial).expect("parse failed");
+        assert_eq!(
+            format_length_with(mm, MeasurementSystem::Imperial, fraction),
+            text
+        );
+    }
+    let mm = parse_length("-1 1/4", MeasurementSystem::Imperial).expect("parse failed");
+    assert!((mm + 1.25 * MM_PER_INCH).abs() < 1e-4);
+}
+
+#[test]
+fn test_normalize_decimal_separator() {
+    use gcodekit5_core::units::{normalize_decimal_separator, DecimalSeparator};
+
+    let normalize = |input, separator| {
+        normalize_decimal_separator(input, separator).map(|text| text.into_owned())
+    };
+    // Point leaves the input alone, so a comma still fails to parse
+    assert_eq!(
+        normalize("1,5", DecimalSeparator::Point),
+        Ok("1,5".to_string())
+    );
+    assert_eq!(
+        normalize("1,5", DecimalSeparator::Comma),
+        Ok("1.5".to_string())
+    );
+    assert_eq!(
+        normalize("-1,25 + 0,5 in", DecimalSeparator::Either),
+        Ok("-1.25 + 0.5 in".to_string())
+    );
+    assert_eq!(
+        normalize("1.5", DecimalSeparator::Either),
+        Ok("1.5".to_string())
+    );
+
+    for (input, separator) in [
+        ("1.5", DecimalSeparator::Comma),
+        ("1.000,5", DecimalSeparator::Either),
+        ("1,000.5", DecimalSeparator::Either),
+        ("1,000,000", DecimalSeparator::Comma),
+        ("1.000.000", DecimalSeparator::Either),
+    ] {
+        assert_eq!(
+            normalize(input, separator),
+            Err(UnitParseError::AmbiguousSeparator(input.to_string())),
+            "{input} with {separator:?}"
+        );
+    }
+}
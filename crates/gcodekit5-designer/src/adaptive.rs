@@ -15,6 +15,7 @@
 //! - Performance monitoring
 
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 
 /// Material properties for adaptive cutting calculations
 #[derive(Debug, Clone, Copy)]
@@ -32,7 +33,7 @@ pub struct MaterialProperties {
 }
 
 /// Supported material types
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum MaterialType {
     /// Aluminum and aluminum alloys
     Aluminum,
@@ -115,6 +116,18 @@ impl MaterialProperties {
         }
     }
 
+    /// Create the profile for a material type
+    pub fn for_type(material_type: MaterialType) -> Self {
+        match material_type {
+            MaterialType::Aluminum => Self::aluminum(),
+            MaterialType::Plastic => Self::plastic(),
+            MaterialType::Wood => Self::wood(),
+            MaterialType::Brass => Self::brass(),
+            MaterialType::Steel => Self::steel(),
+            MaterialType::StainlessSteel => Self::stainless_steel(),
+        }
+    }
+
     /// Validate profile parameters
     pub fn is_valid(&self) -> bool {
         self.max_feed_rate > 0.0
@@ -177,12 +177,17 @@ impl Canvas {
                 operation_type: obj.operation_type,
                 use_custom_values: obj.use_custom_values,
                 pocket_depth: obj.pocket_depth,
+                cut_depth: obj.cut_depth,
+                tabs: obj.tabs,
                 start_depth: obj.start_depth,
                 step_down: obj.step_down,
                 step_in: obj.step_in,
                 ramp_angle: obj.ramp_angle,
                 pocket_strategy: obj.pocket_strategy,
                 raster_fill_ratio: obj.raster_fill_ratio,
+                feed_rate: obj.feed_rate,
+                plunge_rate: obj.plunge_rate,
+                spindle_speed: obj.spindle_speed,
                 offset: obj.offset,
                 fillet: obj.fillet,
                 chamfer: obj.chamfer,
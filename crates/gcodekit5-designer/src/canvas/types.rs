@@ -5,6 +5,7 @@ use crate::pocket_operations::PocketStrategy;
 use crate::shape_store::ShapeStore;
 use crate::shapes::OperationType;
 use crate::spatial_manager::SpatialManager;
+use crate::tabs::TabSettings;
 
 /// Snapshot of canvas state for undo/redo
 #[derive(Clone)]
@@ -68,12 +69,22 @@ pub struct DrawingObject {
     pub operation_type: OperationType,
     pub use_custom_values: bool,
     pub pocket_depth: f64,
+    /// Cut depth (mm, as a magnitude) for this shape; `None` uses the tool settings
+    pub cut_depth: Option<f64>,
+    /// Holding tabs for this shape's profile; `None` uses the job's tabs
+    pub tabs: Option<TabSettings>,
     pub start_depth: f64,
     pub step_down: f32,
     pub step_in: f32,
     pub ramp_angle: f32,
     pub pocket_strategy: PocketStrategy,
     pub raster_fill_ratio: f64,
+    /// Cutting feed rate (mm/min) for this shape; `None` uses the tool settings
+    pub feed_rate: Option<f64>,
+    /// Plunge feed rate (mm/min) for this shape; `None` plunges at the cutting feed
+    pub plunge_rate: Option<f64>,
+    /// Spindle speed (RPM) for this shape; `None` uses the tool settings
+    pub spindle_speed: Option<u32>,
     pub offset: f64,
     pub fillet: f64,
     pub chamfer: f64,
@@ -95,6 +106,23 @@ impl DrawingObject {
         shape
     }
 
+    /// The depth this shape is cut to, falling back to the job's depth
+    pub fn effective_cut_depth(&self, job_depth: f64) -> f64 {
+        self.cut_depth.unwrap_or(job_depth)
+    }
+
+    /// The tabs left on this shape's profile, falling back to the job's tabs
+    ///
+    /// Only closed shapes cut as a profile get tabs; lines and text do not.
+    pub fn effective_tabs(&self, job_tabs: Option<TabSettings>) -> Option<TabSettings> {
+        if self.operation_type != OperationType::Profile
+            || matches!(self.shape, Shape::Line(_) | Shape::Text(_))
+        {
+            return None;
+        }
+        self.tabs.or(job_tabs).filter(TabSettings::is_enabled)
+    }
+
     pub fn get_total_bounds(&self) -> (f64, f64, f64, f64) {
         let (x1, y1, x2, y2) = self.shape.bounds();
         if self.offset.abs() < 1e-6 && self.fillet.abs() < 1e-6 && self.chamfer.abs() < 1e-6 {
@@ -134,12 +162,17 @@ impl DrawingObject {
             operation_type: OperationType::default(),
             use_custom_values: false,
             pocket_depth: 0.0,
+            cut_depth: None,
+            tabs: None,
             start_depth: 0.0,
             step_down: 0.0,
             step_in: 0.0,
             ramp_angle: 0.0,
             pocket_strategy: PocketStrategy::ContourParallel,
             raster_fill_ratio: 0.5,
+            feed_rate: None,
+            plunge_rate: None,
+            spindle_speed: None,
             offset: 0.0,
             fillet: 0.0,
             chamfer: 0.0,
@@ -0,0 +1,194 @@
+//! # Contour Repair
+//!
+//! Drawings exported from CAD often describe a closed outline as separate
+//! lines and arcs, or as a polyline whose ends miss each other by a hair.
+//! Pocketing and profiling need closed outlines, so [`repair_contours`] joins
+//! open contours whose ends meet within a tolerance into continuous chains and
+//! snaps a chain closed when its ends meet.
+//!
+//! Contours that are still open after joining are reported with the gap
+//! between their ends, so the drawing can be fixed where the repair would have
+//! had to guess.
+
+use crate::error::GeometryError;
+use crate::model::Point;
+
+/// A chain of points, either open or closed back to its first point
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contour {
+    /// Points along the contour; a closed contour does not repeat the first
+    pub points: Vec<Point>,
+    /// Whether the contour returns to its first point
+    pub closed: bool,
+}
+
+impl Contour {
+    /// An open contour through `points`
+    pub fn open(points: Vec<Point>) -> Self {
+        Self {
+            points,
+            closed: false,
+        }
+    }
+
+    /// A closed contour through `points`
+    pub fn closed(points: Vec<Point>) -> Self {
+        Self {
+            points,
+            closed: true,
+        }
+    }
+
+    fn first(&self) -> Point {
+        self.points[0]
+    }
+
+    fn last(&self) -> Point {
+        self.points[self.points.len() - 1]
+    }
+}
+
+/// How far apart contour ends may be and still be joined
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContourRepair {
+    /// Largest gap snapped shut between two ends (mm)
+    pub tolerance: f64,
+}
+
+impl ContourRepair {
+    pub fn new(tolerance: f64) -> Self {
+        Self { tolerance }
+    }
+}
+
+impl Default for ContourRepair {
+    fn default() -> Self {
+        Self::new(0.05)
+    }
+}
+
+/// The ends of a contour that is still open after repair
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContourGap {
+    pub start: Point,
+    pub end: Point,
+}
+
+impl ContourGap {
+    /// Distance between the two ends
+    pub fn distance(&self) -> f64 {
+        self.start.distance_to(&self.end)
+    }
+}
+
+/// What a repair pass fixed, and the gaps it could not close
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContourRepairReport {
+    /// Contours joined onto the end of another
+    pub joined: usize,
+    /// Chains snapped closed
+    pub closed: usize,
+    /// Contours left open, with the gap between their ends
+    pub gaps: Vec<ContourGap>,
+}
+
+impl ContourRepairReport {
+    /// Whether every contour came out closed
+    pub fn all_closed(&self) -> bool {
+        self.gaps.is_empty()
+    }
+
+    /// Whether the repair changed anything
+    pub fn repaired(&self) -> bool {
+        self.joined > 0 || self.closed > 0
+    }
+
+    /// One error per contour left open, giving where its ends are
+    pub fn errors(&self) -> Vec<GeometryError> {
+        self.gaps
+            .iter()
+            .map(|gap| GeometryError::OpenContour {
+                gap: gap.distance(),
+                start: (gap.start.x, gap.start.y),
+                end: (gap.end.x, gap.end.y),
+            })
+            .collect()
+    }
+}
+
+/// Join and close `contours` whose ends meet within `repair.tolerance`
+///
+/// Open contours are chained end to end, reversing them where needed, always
+/// taking the nearest end within the tolerance. A chain whose two ends then
+/// meet is closed. Closed contours pass through unchanged, and contours with
+/// fewer than two points are dropped.
+pub fn repair_contours(
+    contours: Vec<Contour>,
+    repair: &ContourRepair,
+) -> (Vec<Contour>, ContourRepairReport) {
+    let tolerance = repair.tolerance.max(0.0);
+    let mut report = ContourRepairReport::default();
+    let mut repaired = Vec::with_capacity(contours.len());
+    let mut open: Vec<Option<Contour>> = Vec::new();
+    for contour in contours {
+        if contour.points.is_empty() || (!contour.closed && contour.points.len() < 2) {
+            continue;
+        }
+        if contour.closed {
+            repaired.push(contour);
+        } else {
+            open.push(Some(contour));
+        }
+    }
+
+    for i in 0..open.len() {
+        let Some(mut chain) = open[i].take() else {
+            continue;
+        };
+        // Grow from the end, then turn round and grow from the other end
+        for _ in 0..2 {
+            while let Some((j, reversed)) = nearest_end(&open, chain.last(), tolerance) {
+                let Some(mut next) = open[j].take() else {
+                    break;
+                };
+                if reversed {
+                    next.points.reverse();
+                }
+                chain.points.extend(next.points.into_iter().skip(1));
+                report.joined += 1;
+            }
+            chain.points.reverse();
+        }
+
+        if chain.points.len() > 2 && chain.first().distance_to(&chain.last()) <= tolerance {
+            chain.points.pop();
+            chain.closed = true;
+            report.closed += 1;
+        } else {
+            report.gaps.push(ContourGap {
+                start: chain.first(),
+                end: chain.last(),
+            });
+        }
+        repaired.push(chain);
+    }
+    (repaired, report)
+}
+
+/// The open contour with an end nearest `point`, within `tolerance`, and
+/// whether it has to be reversed to start there
+fn nearest_end(open: &[Option<Contour>], point: Point, tolerance: f64) -> Option<(usize, bool)> {
+    let mut nearest: Option<(usize, bool, f64)> = None;
+    for (j, contour) in open.iter().enumerate() {
+        let Some(contour) = contour else {
+            continue;
+        };
+        for (reversed, end) in [(false, contour.first()), (true, contour.last())] {
+            let distance = point.distance_to(&end);
+            if distance <= tolerance && nearest.is_none_or(|(_, _, best)| distance < best) {
+                nearest = Some((j, reversed, distance));
+            }
+        }
+    }
+    nearest.map(|(j, reversed, _)| (j, reversed))
+}
@@ -1,12 +1,22 @@
 //! File I/O operations (save, load, new) for designer state.
 
 use super::DesignerState;
+use crate::serialization::DesignFile;
 
 impl DesignerState {
     /// Save design to file.
     pub fn save_to_file(&mut self, path: impl AsRef<std::path::Path>) -> anyhow::Result<()> {
-        use crate::serialization::DesignFile;
+        self.to_design_file().save_to_file(&path)?;
 
+        // Update state
+        self.current_file_path = Some(path.as_ref().to_path_buf());
+        self.is_modified = false;
+
+        Ok(())
+    }
+
+    /// The design as a design file, as saved.
+    pub fn to_design_file(&self) -> DesignFile {
         let mut design = DesignFile::new(&self.design_name);
 
         // Save viewport state
@@ -29,6 +39,7 @@ impl DesignerState {
         design.toolpath_params.spindle_speed = self.tool_settings.spindle_speed as f64;
         design.toolpath_params.tool_diameter = self.tool_settings.tool_diameter;
         design.toolpath_params.cut_depth = self.tool_settings.cut_depth;
+        design.toolpath_params.tabs = self.tool_settings.tabs;
 
         // Save stock settings
         if let Some(stock) = &self.stock_material {
@@ -38,22 +49,47 @@ impl DesignerState {
             design.toolpath_params.safe_z_height = stock.safe_z;
         }
 
-        // Save to file
-        design.save_to_file(&path)?;
+        design
+    }
+
+    /// Load design from file.
+    pub fn load_from_file(&mut self, path: impl AsRef<std::path::Path>) -> anyhow::Result<()> {
+        let design = DesignFile::load_from_file(&path)?;
+        self.apply_design_file(&design);
 
         // Update state
         self.current_file_path = Some(path.as_ref().to_path_buf());
         self.is_modified = false;
+        self.clear_history();
 
         Ok(())
     }
 
-    /// Load design from file.
-    pub fn load_from_file(&mut self, path: impl AsRef<std::path::Path>) -> anyhow::Result<()> {
-        use crate::serialization::DesignFile;
-        use crate::stock_removal::StockMaterial;
+    /// Snapshot of the unsaved design for crash recovery, as design-file JSON.
+    pub fn recovery_snapshot(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string(&self.to_design_file())?)
+    }
 
-        let design = DesignFile::load_from_file(&path)?;
+    /// Restore a [`recovery_snapshot`](Self::recovery_snapshot).
+    ///
+    /// The design belongs to `source`, the file it was last saved to, and stays
+    /// modified until it is saved again.
+    pub fn restore_recovery_snapshot(
+        &mut self,
+        snapshot: &str,
+        source: Option<std::path::PathBuf>,
+    ) -> anyhow::Result<()> {
+        let design: DesignFile = serde_json::from_str(snapshot)?;
+        self.apply_design_file(&design);
+        self.current_file_path = source;
+        self.is_modified = true;
+        self.clear_history();
+        Ok(())
+    }
+
+    /// Replace the design with the contents of a design file.
+    fn apply_design_file(&mut self, design: &DesignFile) {
+        use crate::stock_removal::StockMaterial;
 
         // Clear existing shapes
         self.canvas.clear();
@@ -69,6 +105,9 @@ impl DesignerState {
                 self.canvas.restore_shape(obj);
             }
         }
+        // New shapes must not reuse the restored shapes' IDs
+        let max_id = design.shapes.iter().map(|s| s.id.max(0) as u64).max();
+        self.canvas.set_next_id(max_id.map_or(1, |id| id + 1));
 
         // Restore default properties
         if let Some(default_props) = &design.default_properties {
@@ -82,6 +121,7 @@ impl DesignerState {
         self.tool_settings.spindle_speed = design.toolpath_params.spindle_speed as u32;
         self.tool_settings.tool_diameter = design.toolpath_params.tool_diameter;
         self.tool_settings.cut_depth = design.toolpath_params.cut_depth;
+        self.tool_settings.tabs = design.toolpath_params.tabs;
 
         // Also update the toolpath generator to match
         self.toolpath_generator
@@ -102,13 +142,7 @@ impl DesignerState {
             safe_z: design.toolpath_params.safe_z_height,
         });
 
-        // Update state
         self.design_name = design.metadata.name.clone();
-        self.current_file_path = Some(path.as_ref().to_path_buf());
-        self.is_modified = false;
-        self.clear_history();
-
-        Ok(())
     }
 
     /// Create new design (clear all).
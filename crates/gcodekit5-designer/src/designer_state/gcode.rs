@@ -2,9 +2,13 @@
 
 use super::DesignerState;
 use crate::canvas::DrawingObject;
+use crate::keep_out::{KeepOutChecker, ShapeKeepOutCollision};
 use crate::model::DesignerShape;
 use crate::shapes::OperationType;
-use crate::{Circle, Point, ToolpathToGcode};
+use crate::{
+    apply_tabs, Circle, CornerSmoothingReport, EmitState, GapBridgingReport, Point,
+    SegmentFilterReport, ToolpathToGcode,
+};
 use gcodekit5_core::Units;
 
 impl DesignerState {
@@ -19,23 +23,27 @@ impl DesignerState {
             .unwrap_or(10.0);
         let mut gcode_gen = ToolpathToGcode::new(Units::MM, safe_z);
         gcode_gen.num_axes = self.num_axes;
+        gcode_gen.snap = self.export_snap;
 
         // Store shape-to-toolpath mapping (plus whether we had to fall back from pocket->profile)
         let mut shape_toolpaths: Vec<(DrawingObject, Vec<crate::Toolpath>, bool)> = Vec::new();
 
         // Collect shape IDs in reverse draw order (front to back) for G-code generation
         let shape_ids: Vec<u64> = self.canvas.shape_store.draw_order_iter().rev().collect();
+        self.gap_bridging_report = GapBridgingReport::default();
+        self.segment_filter_report = SegmentFilterReport::default();
+        self.corner_smoothing_report = CornerSmoothingReport::default();
 
         for shape_id in shape_ids {
             let Some(shape_obj) = self.canvas.shape_store.get(shape_id) else {
                 continue;
             };
+            let cut_depth = shape_obj.effective_cut_depth(self.tool_settings.cut_depth);
             self.toolpath_generator
                 .set_pocket_strategy(shape_obj.pocket_strategy);
             self.toolpath_generator
                 .set_start_depth(shape_obj.start_depth);
-            self.toolpath_generator
-                .set_cut_depth(shape_obj.pocket_depth);
+            self.toolpath_generator.set_cut_depth(cut_depth);
             self.toolpath_generator
                 .set_step_in(shape_obj.step_in as f64);
             self.toolpath_generator
@@ -45,13 +53,13 @@ impl DesignerState {
 
             let effective_shape = shape_obj.get_effective_shape();
 
-            let (toolpaths, pocket_fallback_to_profile) = match &effective_shape {
+            let (mut toolpaths, pocket_fallback_to_profile) = match &effective_shape {
                 crate::model::Shape::Rectangle(rect) => {
                     if shape_obj.operation_type == OperationType::Pocket {
                         (
                             self.toolpath_generator.generate_rectangle_pocket(
                                 rect,
-                                shape_obj.pocket_depth,
+                                cut_depth,
                                 shape_obj.step_down as f64,
                                 shape_obj.step_in as f64,
                             ),
@@ -70,7 +78,7 @@ impl DesignerState {
                         (
                             self.toolpath_generator.generate_circle_pocket(
                                 circle,
-                                shape_obj.pocket_depth,
+                                cut_depth,
                                 shape_obj.step_down as f64,
                                 shape_obj.step_in as f64,
                             ),
@@ -106,7 +114,7 @@ impl DesignerState {
                         (
                             self.toolpath_generator.generate_path_pocket(
                                 path_shape,
-                                shape_obj.pocket_depth,
+                                cut_depth,
                                 shape_obj.step_down as f64,
                                 shape_obj.step_in as f64,
                             ),
@@ -149,7 +157,7 @@ impl DesignerState {
                         (
                             self.toolpath_generator.generate_triangle_pocket(
                                 triangle,
-                                shape_obj.pocket_depth,
+                                cut_depth,
                                 shape_obj.step_down as f64,
                                 shape_obj.step_in as f64,
                             ),
@@ -168,7 +176,7 @@ impl DesignerState {
                         (
                             self.toolpath_generator.generate_polygon_pocket(
                                 polygon,
-                                shape_obj.pocket_depth,
+                                cut_depth,
                                 shape_obj.step_down as f64,
                                 shape_obj.step_in as f64,
                             ),
@@ -188,7 +196,7 @@ impl DesignerState {
                     let toolpaths = if shape_obj.operation_type == OperationType::Pocket {
                         self.toolpath_generator.generate_path_pocket(
                             &design_path,
-                            shape_obj.pocket_depth,
+                            cut_depth,
                             shape_obj.step_down as f64,
                             shape_obj.step_in as f64,
                         )
@@ -199,6 +207,24 @@ impl DesignerState {
                     (toolpaths, false)
                 }
             };
+            let feed_rate = shape_obj
+                .feed_rate
+                .unwrap_or(self.toolpath_generator.feed_rate());
+            let spindle_speed = shape_obj
+                .spindle_speed
+                .unwrap_or(self.toolpath_generator.spindle_speed());
+            for toolpath in &mut toolpaths {
+                toolpath.set_cutting_parameters(feed_rate, shape_obj.plunge_rate, spindle_speed);
+            }
+            if let Some(tabs) = shape_obj.effective_tabs(self.tool_settings.tabs) {
+                toolpaths = apply_tabs(&toolpaths, &tabs);
+            }
+            let bridged = self.toolpath_generator.bridge_gaps(&mut toolpaths);
+            self.gap_bridging_report.add(bridged);
+            let filtered = self.toolpath_generator.filter_segments(&mut toolpaths);
+            self.segment_filter_report.add(filtered);
+            let smoothed = self.toolpath_generator.smooth_corners(&mut toolpaths);
+            self.corner_smoothing_report.add(smoothed);
             shape_toolpaths.push((shape_obj.clone(), toolpaths, pocket_fallback_to_profile));
         }
 
@@ -231,6 +257,8 @@ impl DesignerState {
                 (3000, 100.0, 3.175, -5.0)
             };
 
+        self.keep_out_collisions = self.check_keep_out(&shape_toolpaths, safe_z);
+
         gcode.push_str(&gcode_gen.generate_header(
             header_speed,
             header_feed,
@@ -241,8 +269,15 @@ impl DesignerState {
 
         let mut line_number = 10;
         let mut is_first_shape = true;
+        // Feed and spindle state carries across shapes so F and S are only
+        // repeated when an operation changes them.
+        let mut state = EmitState::new(gcode_gen.safe_z);
+        state.spindle_speed = Some(header_speed);
 
-        for (shape, toolpaths, pocket_fallback_to_profile) in shape_toolpaths.iter() {
+        let operation_count = shape_toolpaths.len();
+        for (index, (shape, toolpaths, pocket_fallback_to_profile)) in
+            shape_toolpaths.iter().enumerate()
+        {
             if !is_first_shape && self.num_axes >= 3 {
                 gcode.push_str(&format!(
                     "G00 Z{:.3}   ; Retract to safe Z before next shape\n",
@@ -254,7 +289,12 @@ impl DesignerState {
 
             // Add shape metadata as comments
             gcode.push_str(&format!(
-                "\n; Shape ID={}, Type={:?}\n",
+                "\n; ===== Operation {} of {} =====\n",
+                index + 1,
+                operation_count
+            ));
+            gcode.push_str(&format!(
+                "; Shape ID={}, Type={:?}\n",
                 shape.id,
                 shape.shape.shape_type()
             ));
@@ -267,27 +307,45 @@ impl DesignerState {
             // Add shape-specific data
             Self::append_shape_metadata(&mut gcode, shape);
 
+            let cut_depth = shape.effective_cut_depth(self.tool_settings.cut_depth);
+            let depth_source = if shape.cut_depth.is_some() {
+                "shape"
+            } else {
+                "job default"
+            };
             if shape.operation_type == OperationType::Pocket {
                 gcode.push_str(&format!(
-                    "; Pocket depth: {:.3}mm, Step down: {:.3}mm, Step in: {:.3}mm\n",
-                    shape.pocket_depth, shape.step_down, shape.step_in
+                    "; Pocket depth: {:.3}mm ({}), Step down: {:.3}mm, Step in: {:.3}mm\n",
+                    cut_depth, depth_source, shape.step_down, shape.step_in
                 ));
                 gcode.push_str(&format!("; Strategy: {:?}\n", shape.pocket_strategy));
             } else {
                 gcode.push_str(&format!(
-                    "; Cut depth: {:.3}mm, Step down: {:.3}mm\n",
-                    shape.pocket_depth, shape.step_down
+                    "; Cut depth: {:.3}mm ({}), Step down: {:.3}mm\n",
+                    cut_depth, depth_source, shape.step_down
+                ));
+            }
+            if let Some(tabs) = shape.effective_tabs(self.tool_settings.tabs) {
+                gcode.push_str(&format!(
+                    "; Tabs: {} x {:.3}mm wide, {:.3}mm high ({})\n",
+                    tabs.count,
+                    tabs.width,
+                    tabs.height,
+                    if shape.tabs.is_some() {
+                        "shape"
+                    } else {
+                        "job default"
+                    }
                 ));
             }
 
             // Generate G-code for all toolpaths associated with this shape
-            let mut current_z = gcode_gen.safe_z;
+            state.z = gcode_gen.safe_z;
             for toolpath in toolpaths {
-                let (body_gcode, final_z) =
-                    gcode_gen.generate_body_continuing(toolpath, line_number, current_z);
+                let body_gcode =
+                    gcode_gen.generate_body_with_state(toolpath, line_number, &mut state);
                 gcode.push_str(&body_gcode);
                 line_number += (toolpath.segments.len() as u32) * 10;
-                current_z = final_z;
             }
         }
 
@@ -298,6 +356,40 @@ impl DesignerState {
         gcode
     }
 
+    /// Checks generated toolpaths against the keep-out regions.
+    fn check_keep_out(
+        &self,
+        shape_toolpaths: &[(DrawingObject, Vec<crate::Toolpath>, bool)],
+        safe_z: f64,
+    ) -> Vec<ShapeKeepOutCollision> {
+        if self.keep_out_regions.is_empty() {
+            return Vec::new();
+        }
+        let checker = KeepOutChecker::new(&self.keep_out_regions);
+        let mut collisions = Vec::new();
+        for (shape, toolpaths, _) in shape_toolpaths {
+            for (toolpath_index, toolpath) in toolpaths.iter().enumerate() {
+                collisions.extend(
+                    checker
+                        .check(toolpath, safe_z)
+                        .into_iter()
+                        .map(|collision| ShapeKeepOutCollision {
+                            shape_id: shape.id,
+                            toolpath_index,
+                            collision,
+                        }),
+                );
+            }
+        }
+        if !collisions.is_empty() {
+            tracing::warn!(
+                "{} toolpath segment(s) enter keep-out regions",
+                collisions.len()
+            );
+        }
+        collisions
+    }
+
     /// Appends shape-specific metadata to G-code comments.
     fn append_shape_metadata(gcode: &mut String, shape: &DrawingObject) {
         match &shape.shape {
@@ -21,8 +21,13 @@ mod transforms;
 mod viewport;
 
 use crate::commands::DesignerCommand;
+use crate::keep_out::{KeepOutRegion, ShapeKeepOutCollision, DEVICE_REGION_ID_BASE};
 use crate::stock_removal::{SimulationResult, StockMaterial};
-use crate::{Canvas, ToolpathGenerator};
+use crate::{
+    Canvas, CoordinateSnap, CornerSmoothingReport, GapBridgingReport, SegmentFilterReport,
+    TabSettings, ToolpathGenerator,
+};
+use gcodekit5_devicedb::WorkRegion;
 
 /// Tool settings for the designer
 #[derive(Clone, Debug)]
@@ -33,6 +38,8 @@ pub struct ToolSettings {
     pub cut_depth: f64,
     pub start_depth: f64,
     pub step_down: f64,
+    /// Tabs for profiled shapes without tab settings of their own
+    pub tabs: Option<TabSettings>,
 }
 
 impl Default for ToolSettings {
@@ -44,6 +51,7 @@ impl Default for ToolSettings {
             cut_depth: 5.0,
             start_depth: 0.0,
             step_down: 1.0,
+            tabs: None,
         }
     }
 }
@@ -75,6 +83,18 @@ pub struct DesignerState {
     pub simulation_result: Option<SimulationResult>,
     /// Number of axes on the active device (default 3).
     pub num_axes: u8,
+    /// Snap exported G-code coordinates to a grid (off by default)
+    pub export_snap: Option<CoordinateSnap>,
+    /// Clamps and fixtures checked against the toolpaths on generation
+    pub keep_out_regions: Vec<KeepOutRegion>,
+    /// Segments that hit a keep-out region in the last generation
+    pub keep_out_collisions: Vec<ShapeKeepOutCollision>,
+    /// Gaps between cutting moves joined in the last generation
+    pub gap_bridging_report: GapBridgingReport,
+    /// Short segments filtered out in the last generation
+    pub segment_filter_report: SegmentFilterReport,
+    /// Corners rounded or kept sharp in the last generation
+    pub corner_smoothing_report: CornerSmoothingReport,
 }
 
 impl DesignerState {
@@ -114,6 +134,12 @@ impl DesignerState {
             simulation_resolution: 0.1,
             simulation_result: None,
             num_axes: 3,
+            export_snap: None,
+            keep_out_regions: Vec::new(),
+            keep_out_collisions: Vec::new(),
+            gap_bridging_report: GapBridgingReport::default(),
+            segment_filter_report: SegmentFilterReport::default(),
+            corner_smoothing_report: CornerSmoothingReport::default(),
         }
     }
 
@@ -178,6 +204,39 @@ impl DesignerState {
         self.gcode_generated = false;
     }
 
+    /// Sets the tabs used for profiled shapes without tab settings of their own.
+    pub fn set_default_tabs(&mut self, tabs: Option<TabSettings>) {
+        self.tool_settings.tabs = tabs;
+        self.gcode_generated = false;
+    }
+
+    /// Adds or replaces (by ID) a keep-out region.
+    pub fn add_keep_out_region(&mut self, region: KeepOutRegion) {
+        self.keep_out_regions.retain(|r| r.id != region.id);
+        self.keep_out_regions.push(region);
+        self.gcode_generated = false;
+    }
+
+    /// Replaces the regions taken from the device profile with its no-go zones.
+    ///
+    /// Regions added with [`Self::add_keep_out_region`] are kept.
+    pub fn set_device_regions(&mut self, regions: &[WorkRegion]) {
+        self.keep_out_regions
+            .retain(|r| r.id < DEVICE_REGION_ID_BASE);
+        self.keep_out_regions
+            .extend(regions.iter().enumerate().filter_map(|(i, r)| {
+                KeepOutRegion::from_work_region(DEVICE_REGION_ID_BASE + i as u64, r)
+            }));
+        self.gcode_generated = false;
+    }
+
+    /// Removes a keep-out region by ID.
+    pub fn remove_keep_out_region(&mut self, id: u64) -> Option<KeepOutRegion> {
+        let index = self.keep_out_regions.iter().position(|r| r.id == id)?;
+        self.gcode_generated = false;
+        Some(self.keep_out_regions.remove(index))
+    }
+
     /// Sets the step-down for toolpath generation.
     pub fn set_step_down(&mut self, step: f64) {
         debug_assert!(
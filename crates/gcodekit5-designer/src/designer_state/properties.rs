@@ -5,7 +5,7 @@ use crate::canvas::DrawingObject;
 use crate::commands::*;
 use crate::model::{DesignerShape, Shape};
 use crate::shapes::OperationType;
-use crate::{Point, Rectangle};
+use crate::{Point, Rectangle, TabSettings};
 
 impl DesignerState {
     /// Sets the use_custom_values flag for selected shapes.
@@ -41,10 +41,14 @@ impl DesignerState {
         };
 
         for obj in self.canvas.shapes().filter(|s| s.selected) {
-            if obj.operation_type != new_type || (obj.pocket_depth - depth).abs() > f64::EPSILON {
+            if obj.operation_type != new_type
+                || (obj.pocket_depth - depth).abs() > f64::EPSILON
+                || obj.cut_depth != Some(depth)
+            {
                 let mut new_obj = obj.clone();
                 new_obj.operation_type = new_type;
                 new_obj.pocket_depth = depth;
+                new_obj.cut_depth = Some(depth);
 
                 commands.push(DesignerCommand::ChangeProperty(ChangeProperty {
                     id: obj.id,
@@ -63,6 +67,59 @@ impl DesignerState {
         }
     }
 
+    /// Sets the cut depth of selected shapes; `None` uses the job's depth.
+    pub fn set_selected_cut_depth(&mut self, depth: Option<f64>) {
+        let mut commands = Vec::new();
+        for obj in self.canvas.shapes().filter(|s| s.selected) {
+            if obj.cut_depth != depth {
+                let mut new_obj = obj.clone();
+                new_obj.cut_depth = depth;
+                if let Some(depth) = depth {
+                    new_obj.pocket_depth = depth;
+                }
+
+                commands.push(DesignerCommand::ChangeProperty(ChangeProperty {
+                    id: obj.id,
+                    old_state: obj.clone(),
+                    new_state: new_obj,
+                }));
+            }
+        }
+
+        if !commands.is_empty() {
+            let cmd = DesignerCommand::CompositeCommand(CompositeCommand {
+                commands,
+                name: "Change Cut Depth".to_string(),
+            });
+            self.push_command(cmd);
+        }
+    }
+
+    /// Sets the tabs of selected shapes; `None` uses the job's tabs.
+    pub fn set_selected_tabs(&mut self, tabs: Option<TabSettings>) {
+        let mut commands = Vec::new();
+        for obj in self.canvas.shapes().filter(|s| s.selected) {
+            if obj.tabs != tabs {
+                let mut new_obj = obj.clone();
+                new_obj.tabs = tabs;
+
+                commands.push(DesignerCommand::ChangeProperty(ChangeProperty {
+                    id: obj.id,
+                    old_state: obj.clone(),
+                    new_state: new_obj,
+                }));
+            }
+        }
+
+        if !commands.is_empty() {
+            let cmd = DesignerCommand::CompositeCommand(CompositeCommand {
+                commands,
+                name: "Change Tabs".to_string(),
+            });
+            self.push_command(cmd);
+        }
+    }
+
     /// Sets the step down for selected shapes.
     pub fn set_selected_step_down(&mut self, step_down: f64) {
         let mut commands = Vec::new();
@@ -514,6 +571,42 @@ impl DesignerState {
         }
     }
 
+    /// Sets the cutting feed, plunge feed and spindle speed of selected shapes.
+    /// `None` values fall back to the tool settings.
+    pub fn set_selected_cutting_parameters(
+        &mut self,
+        feed_rate: Option<f64>,
+        plunge_rate: Option<f64>,
+        spindle_speed: Option<u32>,
+    ) {
+        let mut commands = Vec::new();
+        for obj in self.canvas.shapes().filter(|s| s.selected) {
+            if obj.feed_rate != feed_rate
+                || obj.plunge_rate != plunge_rate
+                || obj.spindle_speed != spindle_speed
+            {
+                let mut new_obj = obj.clone();
+                new_obj.feed_rate = feed_rate;
+                new_obj.plunge_rate = plunge_rate;
+                new_obj.spindle_speed = spindle_speed;
+
+                commands.push(DesignerCommand::ChangeProperty(ChangeProperty {
+                    id: obj.id,
+                    old_state: obj.clone(),
+                    new_state: new_obj,
+                }));
+            }
+        }
+
+        if !commands.is_empty() {
+            let cmd = DesignerCommand::CompositeCommand(CompositeCommand {
+                commands,
+                name: "Change Cutting Parameters".to_string(),
+            });
+            self.push_command(cmd);
+        }
+    }
+
     /// Sets the raster fill ratio for selected shapes.
     pub fn set_selected_raster_fill_ratio(&mut self, ratio: f64) {
         let clamped = ratio.clamp(0.0, 1.0);
@@ -3,6 +3,7 @@
 use super::DesignerState;
 use crate::canvas::DrawingObject;
 use crate::commands::*;
+use crate::import::{ImportLayer, ImportedDesign};
 use crate::model::{DesignPath as PathShape, DesignText as TextShape, DesignerShape, Shape};
 use crate::ops::{perform_boolean, BooleanOp};
 use crate::{Circle, DrawingMode, Ellipse, Line, Point, Rectangle};
@@ -213,6 +214,60 @@ impl DesignerState {
         id
     }
 
+    /// Adds an import's shapes layer by layer, as one undoable step.
+    ///
+    /// Layers left out of the plan or disabled are not added. Shapes take their
+    /// layer's operation and cut depth, those on a source layer are grouped,
+    /// and all are stacked
+    /// so G-code generation cuts the layers in plan order and each layer's
+    /// shapes in file order. Returns the new shape ids in that order.
+    pub fn add_imported_design(
+        &mut self,
+        design: &ImportedDesign,
+        plan: &[ImportLayer],
+    ) -> Vec<u64> {
+        let mut objects = Vec::new();
+        for entry in plan.iter().filter(|entry| entry.enabled) {
+            let shapes: Vec<&Shape> = design.layer_shapes(entry.layer).collect();
+            let group_id =
+                (entry.layer.is_some() && shapes.len() > 1).then(|| self.canvas.generate_id());
+            for shape in shapes {
+                let mut obj = DrawingObject::new(self.canvas.generate_id(), shape.clone());
+                obj.group_id = group_id;
+                obj.operation_type = entry.operation;
+                if let Some(depth) = entry.cut_depth {
+                    obj.cut_depth = Some(depth);
+                    obj.pocket_depth = depth;
+                }
+                if !entry.name.is_empty() {
+                    obj.name = entry.name.clone();
+                }
+                objects.push(obj);
+            }
+        }
+        let ids: Vec<u64> = objects.iter().map(|obj| obj.id).collect();
+        if objects.is_empty() {
+            return ids;
+        }
+
+        // Generation runs front to back, so the first shape is added last
+        let commands = objects
+            .into_iter()
+            .rev()
+            .map(|obj| {
+                DesignerCommand::AddShape(AddShape {
+                    id: obj.id,
+                    object: Some(obj),
+                })
+            })
+            .collect();
+        self.push_command(DesignerCommand::CompositeCommand(CompositeCommand {
+            commands,
+            name: "Import Design".to_string(),
+        }));
+        ids
+    }
+
     /// Adds a shape to the canvas at the specified position based on current mode.
     pub fn add_shape_at(&mut self, x: f64, y: f64, multi_select: bool) {
         match self.canvas.mode() {
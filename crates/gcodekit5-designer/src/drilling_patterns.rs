@@ -4,6 +4,7 @@
 //! Supports custom hole definitions and automatic pattern generation.
 
 use super::toolpath::{Toolpath, ToolpathSegment, ToolpathSegmentType};
+use crate::tool_library::ResolvedRecipe;
 use crate::Point;
 use std::f64::consts::PI;
 
@@ -18,6 +19,8 @@ pub struct DrillOperation {
     pub plunge_rate: f64,
     pub spindle_speed: u32,
     pub peck_depth: Option<f64>,
+    /// Feed, plunge and speed came from an estimated recipe
+    pub parameters_estimated: bool,
 }
 
 impl DrillOperation {
@@ -41,9 +44,19 @@ impl DrillOperation {
             plunge_rate: 60.0,
             spindle_speed: 8000,
             peck_depth: None,
+            parameters_estimated: false,
         }
     }
 
+    /// Takes feed rate, plunge rate and spindle speed from a tool library recipe.
+    pub fn apply_recipe(&mut self, resolved: &ResolvedRecipe) {
+        let recipe = &resolved.recipe;
+        self.feed_rate = recipe.feed_rate;
+        self.plunge_rate = recipe.plunge_rate;
+        self.spindle_speed = recipe.spindle_speed;
+        self.parameters_estimated = resolved.estimated;
+    }
+
     /// Sets the cutting parameters for this drill operation.
     pub fn set_parameters(&mut self, feed_rate: f64, plunge_rate: f64, spindle_speed: u32) {
         debug_assert!(
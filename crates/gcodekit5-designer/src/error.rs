@@ -84,6 +84,14 @@ pub enum GeometryError {
     /// Transformation is invalid (e.g., singular matrix).
     #[error("Invalid transformation: {0}")]
     InvalidTransform(String),
+
+    /// A contour that should be closed has a gap too wide to snap shut.
+    #[error("Open contour: {gap:.3}mm gap between ({:.3}, {:.3}) and ({:.3}, {:.3})", start.0, start.1, end.0, end.1)]
+    OpenContour {
+        gap: f64,
+        start: (f64, f64),
+        end: (f64, f64),
+    },
 }
 
 /// Errors related to toolpath generation.
@@ -128,6 +136,29 @@ pub enum ToolpathError {
     Geometry(#[from] GeometryError),
 }
 
+/// Errors from selectively undoing a history action.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum HistoryError {
+    /// No applied action has the given ID.
+    #[error("History action not found: {0}")]
+    ActionNotFound(u64),
+
+    /// A later action changes some of the same shapes.
+    #[error("Cannot undo action {action}: later action {later} also changes shapes {shapes:?}")]
+    Conflict {
+        action: u64,
+        later: u64,
+        shapes: Vec<u64>,
+    },
+
+    /// One of the actions does not record which shapes it changes.
+    #[error(
+        "Cannot undo action {action} independently of later action {later}: \
+         the shapes they change are not recorded"
+    )]
+    UnknownScope { action: u64, later: u64 },
+}
+
 /// Result type alias for design operations.
 pub type DesignResult<T> = Result<T, DesignError>;
 
@@ -137,6 +168,9 @@ pub type GeometryResult<T> = Result<T, GeometryError>;
 /// Result type alias for toolpath operations.
 pub type ToolpathResult<T> = Result<T, ToolpathError>;
 
+/// Result type alias for history operations.
+pub type HistoryResult<T> = Result<T, HistoryError>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,9 +3,14 @@
 //! Manages font loading, caching, and lookup for the designer's text tool.
 //! Uses `fontdb` for system font discovery and `rusttype` for glyph rendering.
 //! Fonts are cached in a global `OnceLock` for thread-safe reuse.
+//!
+//! [`layout_text_on_path`] places text along a curve for engraving, with each
+//! glyph turned to follow the curve. It works with any [`GlyphSource`]: outline
+//! fonts through `rusttype`, or single-line fonts that implement the trait.
 
+use crate::model::Point;
 use fontdb::{Database, Family, Query, Stretch, Style, Weight};
-use rusttype::Font;
+use rusttype::{Font, OutlineBuilder, Scale};
 use std::{
     collections::{HashMap, HashSet},
     fs,
@@ -111,3 +116,264 @@ fn load_font_from_system(family: &str, bold: bool, italic: bool) -> Option<Font<
 pub fn get_font() -> &'static Font<'static> {
     default_font()
 }
+
+/// Curve segments per quadratic or cubic outline curve
+const CURVE_STEPS: usize = 8;
+
+/// A font that glyph paths can be taken from for layout
+pub trait GlyphSource {
+    /// Distance from this glyph's origin to the next one's, at `size`
+    fn advance(&self, c: char, size: f64) -> f64;
+
+    /// Adjustment to the advance between `previous` and `c`, at `size`
+    fn kerning(&self, _previous: char, _c: char, _size: f64) -> f64 {
+        0.0
+    }
+
+    /// Polylines of `c` at `size`, with the origin on the baseline at the
+    /// glyph's left and Y up. Outline fonts return closed contours; single-line
+    /// fonts return open strokes.
+    fn glyph_paths(&self, c: char, size: f64) -> Vec<Vec<Point>>;
+}
+
+impl GlyphSource for Font<'_> {
+    fn advance(&self, c: char, size: f64) -> f64 {
+        self.glyph(c)
+            .scaled(Scale::uniform(size as f32))
+            .h_metrics()
+            .advance_width as f64
+    }
+
+    fn kerning(&self, previous: char, c: char, size: f64) -> f64 {
+        self.pair_kerning(
+            Scale::uniform(size as f32),
+            self.glyph(previous).id(),
+            self.glyph(c).id(),
+        ) as f64
+    }
+
+    fn glyph_paths(&self, c: char, size: f64) -> Vec<Vec<Point>> {
+        let mut outline = PolylineOutline::default();
+        self.glyph(c)
+            .scaled(Scale::uniform(size as f32))
+            .build_outline(&mut outline);
+        outline.finish();
+        outline.paths
+    }
+}
+
+/// Flattens a rusttype outline (Y down) into Y-up polylines
+#[derive(Default)]
+struct PolylineOutline {
+    paths: Vec<Vec<Point>>,
+    current: Vec<Point>,
+}
+
+impl PolylineOutline {
+    fn last(&self) -> Point {
+        self.current.last().copied().unwrap_or(Point::new(0.0, 0.0))
+    }
+
+    fn push(&mut self, x: f64, y: f64) {
+        self.current.push(Point::new(x, -y));
+    }
+
+    fn finish(&mut self) {
+        if self.current.len() > 1 {
+            self.paths.push(std::mem::take(&mut self.current));
+        }
+        self.current.clear();
+    }
+}
+
+impl OutlineBuilder for PolylineOutline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.finish();
+        self.push(x as f64, y as f64);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.push(x as f64, y as f64);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let p0 = self.last();
+        let (x0, y0) = (p0.x, -p0.y);
+        for i in 1..=CURVE_STEPS {
+            let t = i as f64 / CURVE_STEPS as f64;
+            let u = 1.0 - t;
+            self.push(
+                u * u * x0 + 2.0 * u * t * x1 as f64 + t * t * x as f64,
+                u * u * y0 + 2.0 * u * t * y1 as f64 + t * t * y as f64,
+            );
+        }
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let p0 = self.last();
+        let (x0, y0) = (p0.x, -p0.y);
+        for i in 1..=CURVE_STEPS {
+            let t = i as f64 / CURVE_STEPS as f64;
+            let u = 1.0 - t;
+            let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+            self.push(
+                a * x0 + b * x1 as f64 + c * x2 as f64 + d * x as f64,
+                a * y0 + b * y1 as f64 + c * y2 as f64 + d * y as f64,
+            );
+        }
+    }
+
+    fn close(&mut self) {
+        if let Some(&first) = self.current.first() {
+            if self.last().distance_to(&first) > 1e-9 {
+                self.current.push(first);
+            }
+        }
+        self.finish();
+    }
+}
+
+/// What to do with text longer than the path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextOverflow {
+    /// Leave out the glyphs that do not fit
+    #[default]
+    Truncate,
+    /// Shrink the text until it fits
+    Scale,
+}
+
+/// Settings for [`layout_text_on_path`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathTextOptions {
+    /// Text size, as the font's em size (mm)
+    pub size: f64,
+    /// Distance of the baseline from the path, towards the glyphs' tops (mm);
+    /// negative values put the text below the path
+    pub baseline_offset: f64,
+    /// Distance along the path before the first glyph (mm)
+    pub start_offset: f64,
+    pub overflow: TextOverflow,
+}
+
+impl Default for PathTextOptions {
+    fn default() -> Self {
+        Self {
+            size: 10.0,
+            baseline_offset: 0.0,
+            start_offset: 0.0,
+            overflow: TextOverflow::Truncate,
+        }
+    }
+}
+
+/// A glyph placed on a path
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlacedGlyph {
+    pub ch: char,
+    /// Point on the path under the middle of the glyph
+    pub anchor: Point,
+    /// Direction of the path at the anchor, counter-clockwise from +X (radians)
+    pub angle: f64,
+    /// The glyph's polylines in world coordinates
+    pub paths: Vec<Vec<Point>>,
+}
+
+/// Places `text` along the polyline `path`.
+///
+/// Each glyph sits on the path at the middle of its advance and is rotated to
+/// the path's direction there, with its top towards the left of the direction
+/// of travel. Text around the outside of a circle therefore needs a clockwise
+/// circle, and reads inwards-up on a counter-clockwise one. Glyphs are spaced
+/// by their advance and kerning, measured along the path.
+pub fn layout_text_on_path(
+    text: &str,
+    font: &dyn GlyphSource,
+    path: &[Point],
+    options: &PathTextOptions,
+) -> Vec<PlacedGlyph> {
+    let mut distances = Vec::with_capacity(path.len());
+    let mut total = 0.0;
+    for (i, p) in path.iter().enumerate() {
+        if i > 0 {
+            total += path[i - 1].distance_to(p);
+        }
+        distances.push(total);
+    }
+    let available = total - options.start_offset.max(0.0);
+    if path.len() < 2 || available <= 0.0 || options.size <= 0.0 {
+        return Vec::new();
+    }
+
+    let chars: Vec<char> = text.chars().filter(|c| !c.is_control()).collect();
+    let spacing = |size: f64| {
+        let mut previous = None;
+        chars
+            .iter()
+            .map(|&c| {
+                let kerning = previous.map_or(0.0, |p| font.kerning(p, c, size));
+                previous = Some(c);
+                (kerning, font.advance(c, size))
+            })
+            .collect::<Vec<_>>()
+    };
+    let mut size = options.size;
+    let mut advances = spacing(size);
+    let length: f64 = advances.iter().map(|(k, a)| k + a).sum();
+    if options.overflow == TextOverflow::Scale && length > available {
+        size *= available / length;
+        advances = spacing(size);
+    }
+
+    let mut placed = Vec::new();
+    let mut pen = options.start_offset.max(0.0);
+    for (&c, &(kerning, advance)) in chars.iter().zip(&advances) {
+        pen += kerning;
+        if pen + advance > total + 1e-9 {
+            break;
+        }
+        let middle = pen + advance / 2.0;
+        let (anchor, angle) = point_along(path, &distances, middle);
+        let (sin, cos) = angle.sin_cos();
+        let paths = font
+            .glyph_paths(c, size)
+            .into_iter()
+            .map(|polyline| {
+                polyline
+                    .into_iter()
+                    .map(|p| {
+                        let x = p.x - advance / 2.0;
+                        let y = p.y + options.baseline_offset;
+                        Point::new(anchor.x + x * cos - y * sin, anchor.y + x * sin + y * cos)
+                    })
+                    .collect()
+            })
+            .collect();
+        placed.push(PlacedGlyph {
+            ch: c,
+            anchor,
+            angle,
+            paths,
+        });
+        pen += advance;
+    }
+    placed
+}
+
+/// Point at `distance` along the path, and the path's direction there
+fn point_along(path: &[Point], distances: &[f64], distance: f64) -> (Point, f64) {
+    let i = distances
+        .partition_point(|&d| d <= distance)
+        .clamp(1, path.len() - 1);
+    let (a, b) = (path[i - 1], path[i]);
+    let length = distances[i] - distances[i - 1];
+    let t = if length > 0.0 {
+        ((distance - distances[i - 1]) / length).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (
+        Point::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t),
+        (b.y - a.y).atan2(b.x - a.x),
+    )
+}
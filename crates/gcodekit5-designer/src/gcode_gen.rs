@@ -1,8 +1,65 @@
 //! G-code generation from toolpaths.
+//!
+//! Output coordinates can optionally be snapped to a grid ([`CoordinateSnap`]).
+//! Snapping is applied to each absolute position rather than to the moves between
+//! them, so rounding never accumulates along a path.
 
+use super::model::Point;
 use super::toolpath::{Toolpath, ToolpathSegmentType};
 use gcodekit5_core::Units;
 
+/// Largest start/end radius mismatch accepted for an arc with a grid-snapped center
+const ARC_RADIUS_TOLERANCE: f64 = 0.002;
+
+/// Rounding of exported coordinates to a grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoordinateSnap {
+    /// Grid size in millimeters; X and Y (and Z if enabled) are rounded to its multiples
+    pub grid_size: f64,
+    /// Also snap Z values
+    pub snap_z: bool,
+}
+
+impl CoordinateSnap {
+    /// Snap X/Y only to the given grid size
+    pub fn new(grid_size: f64) -> Self {
+        Self {
+            grid_size,
+            snap_z: false,
+        }
+    }
+
+    /// Round a value to the nearest grid multiple
+    pub fn snap(&self, value: f64) -> f64 {
+        if self.grid_size <= 0.0 || !self.grid_size.is_finite() {
+            return value;
+        }
+        (value / self.grid_size).round() * self.grid_size
+    }
+}
+
+/// Modal state carried from one toolpath to the next while emitting a program
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EmitState {
+    /// Current Z position
+    pub z: f64,
+    /// Feed rate of the last F word written, if any
+    pub feed_rate: Option<f64>,
+    /// Spindle speed currently commanded, if known
+    pub spindle_speed: Option<u32>,
+}
+
+impl EmitState {
+    /// State at the given Z with no feed rate or spindle speed established
+    pub fn new(z: f64) -> Self {
+        Self {
+            z,
+            feed_rate: None,
+            spindle_speed: None,
+        }
+    }
+}
+
 /// G-code generator for converting toolpaths to G-code commands.
 pub struct ToolpathToGcode {
     _units: Units,
@@ -11,6 +68,8 @@ pub struct ToolpathToGcode {
     line_numbers_enabled: bool,
     /// Number of axes on the target device (default 3).
     pub num_axes: u8,
+    /// Snap exported coordinates to a grid (off by default)
+    pub snap: Option<CoordinateSnap>,
 }
 
 impl ToolpathToGcode {
@@ -21,6 +80,7 @@ impl ToolpathToGcode {
             safe_z,
             line_numbers_enabled: false,
             num_axes: 3,
+            snap: None,
         }
     }
 
@@ -31,6 +91,7 @@ impl ToolpathToGcode {
             safe_z,
             line_numbers_enabled: enabled,
             num_axes: 3,
+            snap: None,
         }
     }
 
@@ -101,217 +162,134 @@ impl ToolpathToGcode {
 
     /// Generates the G-code body continuing from a given Z position.
     /// Returns (gcode_string, final_z_position) to allow chaining toolpaths without unnecessary retracts.
+    ///
+    /// The spindle is assumed to already run at the first segment's speed (as set by
+    /// the header) and no feed rate is assumed, so the first cutting move carries F.
     pub fn generate_body_continuing(
         &self,
         toolpath: &Toolpath,
         start_line_number: u32,
         initial_z: f64,
     ) -> (String, f64) {
+        let mut state = EmitState::new(initial_z);
+        state.spindle_speed = toolpath.segments.first().map(|s| s.spindle_speed);
+        let gcode = self.generate_body_with_state(toolpath, start_line_number, &mut state);
+        (gcode, state.z)
+    }
+
+    /// Generates the G-code body for a toolpath, updating the modal `state`.
+    ///
+    /// F is only written when the rounded feed rate changes and `M3 S` only when a
+    /// segment's spindle speed differs from the running one, so passing the same
+    /// state through consecutive toolpaths keeps the program modal-efficient.
+    /// Plunges use each segment's plunge rate; cutting moves use its feed rate.
+    pub fn generate_body_with_state(
+        &self,
+        toolpath: &Toolpath,
+        start_line_number: u32,
+        state: &mut EmitState,
+    ) -> String {
         let mut gcode = String::new();
         let mut line_number = start_line_number;
-        let mut current_z = initial_z;
         let has_z = self.num_axes >= 3;
 
         for segment in &toolpath.segments {
+            let (end_x, end_y) = self.snap_xy(&segment.end);
+
+            if state.spindle_speed != Some(segment.spindle_speed) {
+                self.push_line(
+                    &mut gcode,
+                    &mut line_number,
+                    &format!("M3 S{}", segment.spindle_speed),
+                );
+                state.spindle_speed = Some(segment.spindle_speed);
+            }
+
             match segment.segment_type {
                 ToolpathSegmentType::RapidMove => {
                     // Retract to safe Z before changing XY to avoid diagonal plunges
-                    if has_z && (current_z - self.safe_z).abs() > 0.001 {
-                        let line_prefix = if self.line_numbers_enabled {
-                            format!("N{} ", line_number)
-                        } else {
-                            String::new()
-                        };
-                        gcode.push_str(&format!("{}G00 Z{:.3}\n", line_prefix, self.safe_z));
-                        line_number += 10;
-                    }
-
-                    let line_prefix = if self.line_numbers_enabled {
-                        format!("N{} ", line_number)
-                    } else {
-                        String::new()
-                    };
-                    if has_z {
-                        gcode.push_str(&format!(
-                            "{}G00 X{:.3} Y{:.3} Z{:.3}\n",
-                            line_prefix, segment.end.x, segment.end.y, self.safe_z
-                        ));
-                    } else {
-                        gcode.push_str(&format!(
-                            "{}G00 X{:.3} Y{:.3}\n",
-                            line_prefix, segment.end.x, segment.end.y
-                        ));
-                    }
-                    current_z = self.safe_z;
-                }
-                ToolpathSegmentType::LinearMove => {
-                    // Handle start Z plunge if needed
-                    if has_z {
-                        if let Some(sz) = segment.start_z {
-                            if (current_z - sz).abs() > 0.01 {
-                                let line_prefix = if self.line_numbers_enabled {
-                                    format!("N{} ", line_number)
-                                } else {
-                                    String::new()
-                                };
-                                gcode.push_str(&format!(
-                                    "{}G01 Z{:.3} F{:.0}\n",
-                                    line_prefix, sz, segment.feed_rate
-                                ));
-                                line_number += 10;
-                                current_z = sz;
-                            }
-                        } else if segment.z_depth.is_none() {
-                            // Plunge to cutting depth once per cutting section.
-                            if (current_z - toolpath.depth).abs() > 0.01 {
-                                let line_prefix = if self.line_numbers_enabled {
-                                    format!("N{} ", line_number)
-                                } else {
-                                    String::new()
-                                };
-                                gcode.push_str(&format!(
-                                    "{}G01 Z{:.3} F{:.0}\n",
-                                    line_prefix, toolpath.depth, segment.feed_rate
-                                ));
-                                line_number += 10;
-                                current_z = toolpath.depth;
-                            }
-                        }
+                    if has_z && (state.z - self.safe_z).abs() > 0.001 {
+                        self.push_line(
+                            &mut gcode,
+                            &mut line_number,
+                            &format!("G00 Z{:.3}", self.snap_z(self.safe_z)),
+                        );
                     }
 
-                    let target_z = segment.z_depth.unwrap_or(if segment.start_z.is_some() {
-                        current_z
-                    } else {
-                        toolpath.depth
-                    });
-
-                    // Linear move (G01)
-                    let line_prefix = if self.line_numbers_enabled {
-                        format!("N{} ", line_number)
+                    let line = if has_z {
+                        format!(
+                            "G00 X{:.3} Y{:.3} Z{:.3}",
+                            end_x,
+                            end_y,
+                            self.snap_z(self.safe_z)
+                        )
                     } else {
-                        String::new()
+                        format!("G00 X{:.3} Y{:.3}", end_x, end_y)
                     };
-
-                    if has_z && (target_z - current_z).abs() > 0.001 {
-                        gcode.push_str(&format!(
-                            "{}G01 X{:.3} Y{:.3} Z{:.3} F{:.0}\n",
-                            line_prefix, segment.end.x, segment.end.y, target_z, segment.feed_rate
-                        ));
-                        current_z = target_z;
-                    } else {
-                        gcode.push_str(&format!(
-                            "{}G01 X{:.3} Y{:.3} F{:.0}\n",
-                            line_prefix, segment.end.x, segment.end.y, segment.feed_rate
-                        ));
-                    }
+                    self.push_line(&mut gcode, &mut line_number, &line);
+                    state.z = self.safe_z;
                 }
-                ToolpathSegmentType::ArcCW | ToolpathSegmentType::ArcCCW => {
-                    // Handle start Z plunge if needed
+                ToolpathSegmentType::LinearMove
+                | ToolpathSegmentType::ArcCW
+                | ToolpathSegmentType::ArcCCW => {
                     if has_z {
-                        if let Some(sz) = segment.start_z {
-                            if (current_z - sz).abs() > 0.01 {
-                                let line_prefix = if self.line_numbers_enabled {
-                                    format!("N{} ", line_number)
-                                } else {
-                                    String::new()
-                                };
-                                gcode.push_str(&format!(
-                                    "{}G01 Z{:.3} F{:.0}\n",
-                                    line_prefix, sz, segment.feed_rate
-                                ));
-                                line_number += 10;
-                                current_z = sz;
-                            }
-                        } else if segment.z_depth.is_none() {
-                            // Plunge to cutting depth once per cutting section.
-                            if (current_z - toolpath.depth).abs() > 0.01 {
-                                let line_prefix = if self.line_numbers_enabled {
-                                    format!("N{} ", line_number)
-                                } else {
-                                    String::new()
-                                };
-                                gcode.push_str(&format!(
-                                    "{}G01 Z{:.3} F{:.0}\n",
-                                    line_prefix, toolpath.depth, segment.feed_rate
-                                ));
-                                line_number += 10;
-                                current_z = toolpath.depth;
+                        // Plunge to the segment's start Z, or to cutting depth once
+                        // per cutting section.
+                        let plunge_z = match segment.start_z {
+                            Some(sz) => Some(sz),
+                            None if segment.z_depth.is_none() => Some(toolpath.depth),
+                            None => None,
+                        };
+                        if let Some(plunge_z) = plunge_z {
+                            if (state.z - plunge_z).abs() > 0.01 {
+                                let feed = Self::feed_word(state, segment.plunge_feed_rate());
+                                self.push_line(
+                                    &mut gcode,
+                                    &mut line_number,
+                                    &format!("G01 Z{:.3}{}", self.snap_z(plunge_z), feed),
+                                );
+                                state.z = plunge_z;
                             }
                         }
                     }
 
                     let target_z = segment.z_depth.unwrap_or(if segment.start_z.is_some() {
-                        current_z
+                        state.z
                     } else {
                         toolpath.depth
                     });
-
-                    let line_prefix = if self.line_numbers_enabled {
-                        format!("N{} ", line_number)
+                    let z_word = if has_z && (target_z - state.z).abs() > 0.001 {
+                        state.z = target_z;
+                        format!(" Z{:.3}", self.snap_z(target_z))
                     } else {
                         String::new()
                     };
 
-                    let cmd = if segment.segment_type == ToolpathSegmentType::ArcCW {
-                        "G02"
-                    } else {
-                        "G03"
-                    };
-
-                    if let Some(center) = segment.center {
-                        let i = center.x - segment.start.x;
-                        let j = center.y - segment.start.y;
-
-                        if has_z && (target_z - current_z).abs() > 0.001 {
-                            gcode.push_str(&format!(
-                                "{}{} X{:.3} Y{:.3} Z{:.3} I{:.3} J{:.3} F{:.0}\n",
-                                line_prefix,
-                                cmd,
-                                segment.end.x,
-                                segment.end.y,
-                                target_z,
-                                i,
-                                j,
-                                segment.feed_rate
-                            ));
-                            current_z = target_z;
-                        } else {
-                            gcode.push_str(&format!(
-                                "{}{} X{:.3} Y{:.3} I{:.3} J{:.3} F{:.0}\n",
-                                line_prefix,
-                                cmd,
-                                segment.end.x,
-                                segment.end.y,
-                                i,
-                                j,
-                                segment.feed_rate
-                            ));
+                    // Arcs without a center fall back to a linear move
+                    let (cmd, offsets) = match (segment.segment_type, segment.center) {
+                        (ToolpathSegmentType::ArcCW, Some(center)) => {
+                            let (i, j) = self.arc_offsets(&segment.start, &segment.end, &center);
+                            ("G02", format!(" I{:.3} J{:.3}", i, j))
                         }
-                    } else {
-                        // Fallback to linear if no center provided
-                        if has_z && (target_z - current_z).abs() > 0.001 {
-                            gcode.push_str(&format!(
-                                "{}G01 X{:.3} Y{:.3} Z{:.3} F{:.0}\n",
-                                line_prefix,
-                                segment.end.x,
-                                segment.end.y,
-                                target_z,
-                                segment.feed_rate
-                            ));
-                            current_z = target_z;
-                        } else {
-                            gcode.push_str(&format!(
-                                "{}G01 X{:.3} Y{:.3} F{:.0}\n",
-                                line_prefix, segment.end.x, segment.end.y, segment.feed_rate
-                            ));
+                        (ToolpathSegmentType::ArcCCW, Some(center)) => {
+                            let (i, j) = self.arc_offsets(&segment.start, &segment.end, &center);
+                            ("G03", format!(" I{:.3} J{:.3}", i, j))
                         }
-                    }
+                        _ => ("G01", String::new()),
+                    };
+                    let feed = Self::feed_word(state, segment.feed_rate);
+                    self.push_line(
+                        &mut gcode,
+                        &mut line_number,
+                        &format!(
+                            "{} X{:.3} Y{:.3}{}{}{}",
+                            cmd, end_x, end_y, z_word, offsets, feed
+                        ),
+                    );
                 }
             }
-
-            line_number += 10;
         }
-        (gcode, current_z)
+        gcode
     }
 
     /// Generates the G-code footer.
@@ -322,13 +300,93 @@ impl ToolpathToGcode {
         if self.num_axes >= 3 {
             gcode.push_str(&format!(
                 "G00 Z{:.3}   ; Raise tool to safe height\n",
-                self.safe_z
+                self.snap_z(self.safe_z)
             ));
         }
         gcode.push_str("G00 X0 Y0   ; Return to origin\n");
         gcode.push_str("M30         ; End program\n");
         gcode
     }
+
+    fn push_line(&self, gcode: &mut String, line_number: &mut u32, line: &str) {
+        if self.line_numbers_enabled {
+            gcode.push_str(&format!("N{} ", line_number));
+        }
+        gcode.push_str(line);
+        gcode.push('\n');
+        *line_number += 10;
+    }
+
+    /// ` F<rate>` if the rounded rate differs from the last one written, else empty
+    fn feed_word(state: &mut EmitState, feed_rate: f64) -> String {
+        let word = format!("{:.0}", feed_rate);
+        if state
+            .feed_rate
+            .is_some_and(|last| format!("{:.0}", last) == word)
+        {
+            return String::new();
+        }
+        state.feed_rate = Some(feed_rate);
+        format!(" F{}", word)
+    }
+
+    fn snap_xy(&self, point: &Point) -> (f64, f64) {
+        match &self.snap {
+            Some(snap) => (snap.snap(point.x), snap.snap(point.y)),
+            None => (point.x, point.y),
+        }
+    }
+
+    fn snap_z(&self, z: f64) -> f64 {
+        match &self.snap {
+            Some(snap) if snap.snap_z => snap.snap(z),
+            _ => z,
+        }
+    }
+
+    /// I/J offsets of an arc from its (snapped) start point.
+    ///
+    /// With snapping the center is moved to the grid point next to it that keeps
+    /// the snapped start and end equidistant; if no grid point is close enough the
+    /// center is projected onto the perpendicular bisector of the snapped
+    /// endpoints instead, so the radius stays valid either way.
+    fn arc_offsets(&self, start: &Point, end: &Point, center: &Point) -> (f64, f64) {
+        let Some(snap) = self.snap else {
+            return (center.x - start.x, center.y - start.y);
+        };
+        let (sx, sy) = self.snap_xy(start);
+        let (ex, ey) = self.snap_xy(end);
+        let mismatch =
+            |cx: f64, cy: f64| ((sx - cx).hypot(sy - cy) - (ex - cx).hypot(ey - cy)).abs();
+
+        let (cx, cy) = (snap.snap(center.x), snap.snap(center.y));
+        let best = (-1..=1)
+            .flat_map(|dx| (-1..=1).map(move |dy| (dx, dy)))
+            .map(|(dx, dy)| {
+                (
+                    cx + dx as f64 * snap.grid_size,
+                    cy + dy as f64 * snap.grid_size,
+                )
+            })
+            .min_by(|a, b| {
+                let key =
+                    |c: &(f64, f64)| (mismatch(c.0, c.1), (c.0 - center.x).hypot(c.1 - center.y));
+                key(a)
+                    .partial_cmp(&key(b))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .filter(|c| mismatch(c.0, c.1) <= ARC_RADIUS_TOLERANCE);
+
+        let (cx, cy) = best.unwrap_or_else(|| {
+            let (mx, my) = ((sx + ex) / 2.0, (sy + ey) / 2.0);
+            // Endpoints differ here: coincident ones are equidistant from any grid point
+            let (nx, ny) = (-(ey - sy), ex - sx);
+            let length_sq = nx * nx + ny * ny;
+            let t = ((center.x - mx) * nx + (center.y - my) * ny) / length_sq;
+            (mx + nx * t, my + ny * t)
+        });
+        (cx - sx, cy - sy)
+    }
 }
 
 impl Default for ToolpathToGcode {
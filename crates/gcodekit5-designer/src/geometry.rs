@@ -0,0 +1,36 @@
+//! # Geometry Helpers
+//!
+//! Point and segment tests shared by the toolpath passes.
+
+use crate::model::Point;
+
+/// Whether `p` lies inside `polygon`, by ray casting
+///
+/// Fewer than three vertices enclose nothing.
+pub fn point_in_polygon(p: Point, polygon: &[Point]) -> bool {
+    if polygon.len() < 3 {
+        return false;
+    }
+    let mut inside = false;
+    let mut j = polygon.len() - 1;
+    for i in 0..polygon.len() {
+        let (pi, pj) = (polygon[i], polygon[j]);
+        if (pi.y > p.y) != (pj.y > p.y) && p.x < (pj.x - pi.x) * (p.y - pi.y) / (pj.y - pi.y) + pi.x
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Shortest distance from `p` to the segment from `a` to `b`
+pub fn distance_to_segment(p: Point, a: Point, b: Point) -> f64 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let length_squared = dx * dx + dy * dy;
+    if length_squared < 1e-12 {
+        return p.distance_to(&a);
+    }
+    let t = (((p.x - a.x) * dx + (p.y - a.y) * dy) / length_squared).clamp(0.0, 1.0);
+    p.distance_to(&Point::new(a.x + dx * t, a.y + dy * t))
+}
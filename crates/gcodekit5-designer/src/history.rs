@@ -4,7 +4,12 @@
 //!
 //! Tracks design state changes and allows reverting to previous states.
 //! Supports multiple levels of undo/redo with configurable depth limit.
+//!
+//! An earlier action can also be undone on its own with
+//! [`UndoRedoManager::undo_action`], keeping everything done since, as long as
+//! no later action changed the same shapes.
 
+use crate::error::{HistoryError, HistoryResult};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -66,6 +71,12 @@ pub struct HistoryAction {
     pub after_state: String,
     /// Timestamp of action
     pub timestamp: String,
+    /// ID assigned when the action is recorded; 0 until then
+    #[serde(default)]
+    pub id: u64,
+    /// IDs of the shapes the action changes; empty when not recorded
+    #[serde(default)]
+    pub shape_ids: Vec<u64>,
 }
 
 impl HistoryAction {
@@ -83,9 +94,30 @@ impl HistoryAction {
             before_state,
             after_state,
             timestamp,
+            id: 0,
+            shape_ids: Vec::new(),
         }
     }
 
+    /// Record which shapes the action changes, so it can be undone selectively
+    pub fn with_shapes(mut self, shape_ids: impl IntoIterator<Item = u64>) -> Self {
+        self.shape_ids = shape_ids.into_iter().collect();
+        self.shape_ids.sort_unstable();
+        self.shape_ids.dedup();
+        self
+    }
+
+    /// The action that reverts this one: the same shapes, with the states swapped
+    pub fn inverse(&self) -> Self {
+        Self::new(
+            self.action_type.clone(),
+            format!("Undo {}", self.description),
+            self.after_state.clone(),
+            self.before_state.clone(),
+        )
+        .with_shapes(self.shape_ids.iter().copied())
+    }
+
     /// Create simple action without state tracking (for testing)
     pub fn simple(action_type: ActionType, description: String) -> Self {
         Self::new(action_type, description, "{}".to_string(), "{}".to_string())
@@ -105,6 +137,9 @@ pub struct UndoRedoManager {
     current_index: usize,
     /// Whether history is enabled
     enabled: bool,
+    /// ID of the most recently recorded action
+    #[serde(default)]
+    last_id: u64,
 }
 
 impl UndoRedoManager {
@@ -116,6 +151,7 @@ impl UndoRedoManager {
             max_depth,
             current_index: 0,
             enabled: true,
+            last_id: 0,
         }
     }
 
@@ -124,12 +160,17 @@ impl UndoRedoManager {
         Self::new(50)
     }
 
-    /// Record an action
+    /// Record an action, assigning its ID
     pub fn record(&mut self, action: HistoryAction) {
         if !self.enabled {
             return;
         }
+        self.push(action);
+    }
 
+    fn push(&mut self, mut action: HistoryAction) {
+        self.last_id += 1;
+        action.id = self.last_id;
         self.undo_stack.push(action);
         self.redo_stack.clear();
 
@@ -171,6 +212,64 @@ impl UndoRedoManager {
         }
     }
 
+    /// Check whether the applied action `id` can be undone without undoing
+    /// the actions after it
+    ///
+    /// Every later action must change only shapes the target does not. Actions
+    /// that do not record their shapes cannot be shown to be independent, so
+    /// they conflict with everything.
+    pub fn check_undo_action(&self, id: u64) -> HistoryResult<&HistoryAction> {
+        let index = self
+            .undo_stack
+            .iter()
+            .position(|a| a.id == id)
+            .ok_or(HistoryError::ActionNotFound(id))?;
+        let action = &self.undo_stack[index];
+        for later in &self.undo_stack[index + 1..] {
+            if action.shape_ids.is_empty() || later.shape_ids.is_empty() {
+                return Err(HistoryError::UnknownScope {
+                    action: id,
+                    later: later.id,
+                });
+            }
+            let shared: Vec<u64> = action
+                .shape_ids
+                .iter()
+                .filter(|s| later.shape_ids.contains(s))
+                .copied()
+                .collect();
+            if !shared.is_empty() {
+                return Err(HistoryError::Conflict {
+                    action: id,
+                    later: later.id,
+                    shapes: shared,
+                });
+            }
+        }
+        Ok(action)
+    }
+
+    /// Undo the applied action `id` alone, keeping the actions after it
+    ///
+    /// Records the action's [inverse](HistoryAction::inverse) as a new action
+    /// and returns it; the caller restores its `after_state` for its shapes.
+    /// The inverse is itself undoable, and recording it clears the redo stack.
+    /// Nothing changes when [`check_undo_action`](Self::check_undo_action)
+    /// refuses.
+    pub fn undo_action(&mut self, id: u64) -> HistoryResult<&HistoryAction> {
+        let inverse = self.check_undo_action(id)?.inverse();
+        self.push(inverse);
+        Ok(self.undo_stack.last().expect("inverse was just recorded"))
+    }
+
+    /// Get an applied or undone action by ID
+    pub fn action(&self, id: u64) -> Option<&HistoryAction> {
+        self.undo_stack
+            .iter()
+            .chain(&self.redo_stack)
+            .find(|a| a.id == id)
+    }
+
     /// Check if undo is available
     pub fn can_undo(&self) -> bool {
         !self.undo_stack.is_empty()
@@ -307,12 +406,21 @@ impl HistoryTransaction {
         combined_before.push('}');
         combined_after.push('}');
 
+        let shape_ids = if self.actions.iter().any(|a| a.shape_ids.is_empty()) {
+            Vec::new()
+        } else {
+            self.actions
+                .iter()
+                .flat_map(|a| a.shape_ids.iter().copied())
+                .collect()
+        };
         HistoryAction::new(
             ActionType::BatchOperation,
             self.description,
             combined_before,
             combined_after,
         )
+        .with_shapes(shape_ids)
     }
 
     /// Get action count in transaction
@@ -12,12 +12,14 @@
 //! - Coordinate system transformation
 //! - Scale and offset adjustment
 
-use crate::dxf_parser::{DxfEntity, DxfFile, DxfParser};
+use crate::contour_repair::{repair_contours, Contour, ContourRepair, ContourRepairReport};
+use crate::dxf_parser::{DxfArc, DxfEntity, DxfFile, DxfParser};
 use crate::model::{
     DesignCircle as Circle, DesignEllipse as Ellipse, DesignLine as Line, DesignPath as PathShape,
     DesignRectangle as Rectangle, DesignerShape, Point, Shape,
 };
 use crate::model3d::{Mesh3D, Model3DImporter};
+use crate::shapes::OperationType;
 use anyhow::{anyhow, Result};
 use lyon::geom::Arc;
 use lyon::math::point;
@@ -34,8 +36,88 @@ pub struct ImportedDesign {
     pub format: FileFormat,
     /// Number of layers imported
     pub layer_count: usize,
+    /// Source layer names, in the order they first appear in the file
+    pub layers: Vec<String>,
+    /// Index into `layers` of each shape's source layer; `None` for shapes on
+    /// no layer
+    pub shape_layers: Vec<Option<usize>>,
     /// Optional 3D mesh for 3D models
     pub mesh_3d: Option<Mesh3D>,
+    /// What contour repair fixed, when it ran on import
+    pub repair_report: Option<ContourRepairReport>,
+}
+
+impl ImportedDesign {
+    /// Source layer name of the shape at `index`
+    pub fn shape_layer(&self, index: usize) -> Option<&str> {
+        self.shape_layers
+            .get(index)
+            .copied()
+            .flatten()
+            .map(|layer| self.layers[layer].as_str())
+    }
+
+    /// Shapes on a source layer (`None` for shapes on no layer), in file order
+    pub fn layer_shapes(&self, layer: Option<usize>) -> impl Iterator<Item = &Shape> {
+        self.shapes
+            .iter()
+            .zip(&self.shape_layers)
+            .filter(move |(_, shape_layer)| **shape_layer == layer)
+            .map(|(shape, _)| shape)
+    }
+
+    /// One entry per source layer that holds shapes, in file order, with
+    /// shapes on no layer last; reorder and edit it to plan the job
+    pub fn layer_plan(&self) -> Vec<ImportLayer> {
+        let mut plan: Vec<ImportLayer> = self
+            .layers
+            .iter()
+            .enumerate()
+            .map(|(index, name)| ImportLayer::new(Some(index), name.clone(), 0))
+            .collect();
+        let mut unlayered = 0;
+        for layer in &self.shape_layers {
+            match layer {
+                Some(index) => plan[*index].shape_count += 1,
+                None => unlayered += 1,
+            }
+        }
+        plan.retain(|entry| entry.shape_count > 0);
+        if unlayered > 0 {
+            plan.push(ImportLayer::new(None, String::new(), unlayered));
+        }
+        plan
+    }
+}
+
+/// How the shapes of one source layer are imported, in a layer plan
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportLayer {
+    /// Index into [`ImportedDesign::layers`]; `None` for shapes on no layer
+    pub layer: Option<usize>,
+    /// Layer name, empty for shapes on no layer
+    pub name: String,
+    /// Shapes on the layer
+    pub shape_count: usize,
+    /// Import the layer's shapes
+    pub enabled: bool,
+    /// Operation for the layer's shapes
+    pub operation: OperationType,
+    /// Cut depth (mm) for the layer's shapes; `None` uses the tool settings
+    pub cut_depth: Option<f64>,
+}
+
+impl ImportLayer {
+    fn new(layer: Option<usize>, name: String, shape_count: usize) -> Self {
+        Self {
+            layer,
+            name,
+            shape_count,
+            enabled: true,
+            operation: OperationType::Profile,
+            cut_depth: None,
+        }
+    }
 }
 
 /// Supported import file formats
@@ -145,7 +227,7 @@ impl SvgImporter {
             anyhow::bail!("Invalid SVG: missing <svg> element");
         }
 
-        let mut imported_shapes: Vec<ImportedShape> = Vec::new();
+        let mut imported_shapes: Vec<(ImportedShape, usize)> = Vec::new();
         let mut viewbox_width = 100.0f64;
         let mut _viewbox_height = 100.0f64;
 
@@ -209,7 +291,7 @@ impl SvgImporter {
                         width * self.scale,
                         height * self.scale,
                     );
-                    imported_shapes.push(ImportedShape::Rect(rect));
+                    imported_shapes.push((ImportedShape::Rect(rect), abs_tag_start));
                 }
                 search_pos = abs_tag_start + tag_end + 1;
             } else {
@@ -231,7 +313,7 @@ impl SvgImporter {
                 if r > 0.0 {
                     let circle =
                         Circle::new(Point::new(cx * self.scale, cy * self.scale), r * self.scale);
-                    imported_shapes.push(ImportedShape::Circle(circle));
+                    imported_shapes.push((ImportedShape::Circle(circle), abs_tag_start));
                 }
                 search_pos = abs_tag_start + tag_end + 1;
             } else {
@@ -255,7 +337,7 @@ impl SvgImporter {
                     Point::new(x1 * self.scale, y1 * self.scale),
                     Point::new(x2 * self.scale, y2 * self.scale),
                 );
-                imported_shapes.push(ImportedShape::Line(line));
+                imported_shapes.push((ImportedShape::Line(line), abs_tag_start));
 
                 search_pos = abs_tag_start + tag_end + 1;
             } else {
@@ -281,7 +363,7 @@ impl SvgImporter {
                         rx * self.scale,
                         ry * self.scale,
                     );
-                    imported_shapes.push(ImportedShape::Ellipse(ellipse));
+                    imported_shapes.push((ImportedShape::Ellipse(ellipse), abs_tag_start));
                 }
                 search_pos = abs_tag_start + tag_end + 1;
             } else {
@@ -314,8 +396,10 @@ impl SvgImporter {
                         .collect();
 
                     if !points.is_empty() {
-                        imported_shapes
-                            .push(ImportedShape::Path(PathShape::from_points(&points, false)));
+                        imported_shapes.push((
+                            ImportedShape::Path(PathShape::from_points(&points, false)),
+                            abs_tag_start,
+                        ));
                     }
                 }
                 search_pos = abs_tag_start + tag_end + 1;
@@ -349,8 +433,10 @@ impl SvgImporter {
                         .collect();
 
                     if !points.is_empty() {
-                        imported_shapes
-                            .push(ImportedShape::Path(PathShape::from_points(&points, true)));
+                        imported_shapes.push((
+                            ImportedShape::Path(PathShape::from_points(&points, true)),
+                            abs_tag_start,
+                        ));
                     }
                 }
                 search_pos = abs_tag_start + tag_end + 1;
@@ -384,7 +470,7 @@ impl SvgImporter {
                                 lyon::math::Transform::scale(self.scale as f32, self.scale as f32);
                             new_path.transform(&scale_transform);
 
-                            imported_shapes.push(ImportedShape::Path(new_path));
+                            imported_shapes.push((ImportedShape::Path(new_path), abs_path_start));
                         }
                     }
                 }
@@ -399,7 +485,7 @@ impl SvgImporter {
         let mut min_y = f64::MAX;
         let mut max_y = f64::MIN;
 
-        for shape in &imported_shapes {
+        for (shape, _) in &imported_shapes {
             let (_, s_min_y, _, s_max_y) = shape.bounds();
             if s_min_y < min_y {
                 min_y = s_min_y;
@@ -415,23 +501,86 @@ impl SvgImporter {
             (min_y + max_y) / 2.0
         };
 
-        let shapes: Vec<Shape> = imported_shapes
+        let (layers, spans) = Self::layer_spans(svg_content);
+        let (shapes, shape_layers) = imported_shapes
             .into_iter()
-            .map(|s| s.convert(center_y, self.offset_x, self.offset_y))
-            .collect();
-
-        // Default to 0 layers for SVG import unless more explicit layer logic is implemented
-        let layer_count = 0usize;
+            .map(|(s, position)| {
+                // The innermost layer holding the element
+                let layer = spans
+                    .iter()
+                    .filter(|(start, end, _)| (*start..*end).contains(&position))
+                    .max_by_key(|(start, _, _)| *start)
+                    .map(|(_, _, layer)| *layer);
+                (s.convert(center_y, self.offset_x, self.offset_y), layer)
+            })
+            .unzip();
 
         Ok(ImportedDesign {
             shapes,
             dimensions: (viewbox_width * self.scale, _viewbox_height * self.scale),
             format: FileFormat::Svg,
-            layer_count,
+            layer_count: layers.len(),
+            layers,
+            shape_layers,
             mesh_3d: None,
+            repair_report: None,
         })
     }
 
+    /// Layer names and the byte range of each layer group (`<g>` with
+    /// `inkscape:groupmode="layer"`), named by its label or id; groups sharing
+    /// a name are one layer
+    fn layer_spans(svg_content: &str) -> (Vec<String>, Vec<(usize, usize, usize)>) {
+        let mut layers: Vec<String> = Vec::new();
+        let mut spans = Vec::new();
+        let mut open: Vec<(usize, Option<usize>)> = Vec::new();
+        let mut search_pos = 0;
+        loop {
+            let rest = &svg_content[search_pos..];
+            let Some(offset) = [rest.find("<g"), rest.find("</g>")]
+                .into_iter()
+                .flatten()
+                .min()
+            else {
+                break;
+            };
+            let abs_start = search_pos + offset;
+            let Some(tag_end) = svg_content[abs_start..].find('>') else {
+                break;
+            };
+            let tag = &svg_content[abs_start..abs_start + tag_end];
+            search_pos = abs_start + tag_end + 1;
+
+            if tag.starts_with("</g") {
+                if let Some((start, Some(layer))) = open.pop() {
+                    spans.push((start, abs_start, layer));
+                }
+                continue;
+            }
+            // Other tags starting with "g" (e.g. <glyph>) and empty groups
+            let is_group = tag.len() == 2 || tag[2..].starts_with(char::is_whitespace);
+            if !is_group || tag.ends_with('/') {
+                continue;
+            }
+            let layer =
+                (Self::extract_attr_str(tag, "inkscape:groupmode") == Some("layer")).then(|| {
+                    let name = Self::extract_attr_str(tag, "inkscape:label")
+                        .or_else(|| Self::extract_attr_str(tag, " id"))
+                        .map(str::to_string)
+                        .unwrap_or_else(|| format!("Layer {}", layers.len() + 1));
+                    match layers.iter().position(|l| *l == name) {
+                        Some(index) => index,
+                        None => {
+                            layers.push(name);
+                            layers.len() - 1
+                        }
+                    }
+                });
+            open.push((abs_start, layer));
+        }
+        (layers, spans)
+    }
+
     fn extract_attr_str<'a>(tag: &'a str, attr: &str) -> Option<&'a str> {
         let pattern = format!("{}=\"", attr);
         if let Some(start) = tag.find(&pattern) {
@@ -472,6 +621,8 @@ pub struct DxfImporter {
     pub scale: f64,
     pub offset_x: f64,
     pub offset_y: f64,
+    /// Join and close lines, arcs and polylines whose ends nearly meet
+    pub repair: Option<ContourRepair>,
 }
 
 impl DxfImporter {
@@ -481,9 +632,19 @@ impl DxfImporter {
             scale,
             offset_x,
             offset_y,
+            repair: None,
         }
     }
 
+    /// Repair open contours on import (see [`crate::contour_repair`])
+    ///
+    /// Gaps are measured in the drawing's units at the import scale, and the
+    /// gaps left open are reported in those coordinates.
+    pub fn with_repair(mut self, repair: ContourRepair) -> Self {
+        self.repair = Some(repair);
+        self
+    }
+
     /// Import DXF from file path
     ///
     /// # Arguments
@@ -511,8 +672,17 @@ impl DxfImporter {
         // Apply scaling
         dxf_file.scale(self.scale);
 
+        // Layers in the order they first appear
+        let mut layers: Vec<String> = Vec::new();
+        for entity in &dxf_file.entities {
+            if !layers.iter().any(|l| l == entity.layer()) {
+                layers.push(entity.layer().to_string());
+            }
+        }
+
         // Convert DXF entities to Designer shapes
-        let shapes = self.convert_entities_to_shapes(&dxf_file)?;
+        let (shapes, shape_layers, repair_report) =
+            self.convert_entities_to_shapes(&dxf_file, &layers)?;
 
         // Calculate dimensions from bounding box
         let (min, max) = dxf_file.bounds();
@@ -522,8 +692,11 @@ impl DxfImporter {
             shapes,
             dimensions,
             format: FileFormat::Dxf,
-            layer_count: dxf_file.layer_names().len(),
+            layer_count: layers.len(),
+            layers,
+            shape_layers,
             mesh_3d: None,
+            repair_report,
         })
     }
 
@@ -531,8 +704,18 @@ impl DxfImporter {
     ///
     /// Note: DXF coordinates are negated on X-axis to correct for coordinate system difference.
     /// DXF uses right-handed coordinate system, Designer uses left-handed with Y-up.
-    fn convert_entities_to_shapes(&self, dxf_file: &DxfFile) -> Result<Vec<Shape>> {
+    ///
+    /// Each shape's index into `layers` is returned alongside it. Contours are
+    /// only repaired against others on the same layer.
+    #[allow(clippy::type_complexity)]
+    fn convert_entities_to_shapes(
+        &self,
+        dxf_file: &DxfFile,
+        layers: &[String],
+    ) -> Result<(Vec<Shape>, Vec<Option<usize>>, Option<ContourRepairReport>)> {
         let mut shapes: Vec<Shape> = Vec::new();
+        let mut shape_layers: Vec<Option<usize>> = Vec::new();
+        let mut contours: Vec<Vec<Contour>> = vec![Vec::new(); layers.len()];
 
         // Transform to apply: negate X and add offset
         // Note: dxf_file is already scaled by self.scale
@@ -542,6 +725,23 @@ impl DxfImporter {
         ));
 
         for entity in &dxf_file.entities {
+            let layer = layers.iter().position(|l| l == entity.layer());
+            if self.repair.is_some() {
+                let contour = match entity {
+                    DxfEntity::Line(line) => Some(Contour::open(vec![line.start, line.end])),
+                    DxfEntity::Arc(arc) => Some(Contour::open(Self::arc_points(arc))),
+                    DxfEntity::Polyline(polyline) => Some(Contour {
+                        points: polyline.vertices.clone(),
+                        closed: polyline.closed,
+                    }),
+                    _ => None,
+                };
+                if let (Some(contour), Some(layer)) = (contour, layer) {
+                    contours[layer].push(contour);
+                    continue;
+                }
+            }
+
             let path_opt = match entity {
                 DxfEntity::Line(line) => {
                     let mut builder = Path::builder();
@@ -629,10 +829,64 @@ impl DxfImporter {
                 let mut shape = PathShape::from_lyon_path(&path);
                 shape.transform(&transform);
                 shapes.push(Shape::Path(shape));
+                shape_layers.push(layer);
             }
         }
 
-        Ok(shapes)
+        let Some(repair) = self.repair else {
+            return Ok((shapes, shape_layers, None));
+        };
+        let mut report = ContourRepairReport::default();
+        for (layer, layer_contours) in contours.into_iter().enumerate() {
+            let (repaired, layer_report) = repair_contours(layer_contours, &repair);
+            report.joined += layer_report.joined;
+            report.closed += layer_report.closed;
+            report.gaps.extend(layer_report.gaps);
+            for contour in repaired {
+                let mut builder = Path::builder();
+                let start = contour.points[0];
+                builder.begin(point(start.x as f32, start.y as f32));
+                for v in contour.points.iter().skip(1) {
+                    builder.line_to(point(v.x as f32, v.y as f32));
+                }
+                if contour.closed {
+                    builder.close();
+                } else {
+                    builder.end(false);
+                }
+                let mut shape = PathShape::from_lyon_path(&builder.build());
+                shape.transform(&transform);
+                shapes.push(Shape::Path(shape));
+                shape_layers.push(Some(layer));
+            }
+        }
+
+        Ok((shapes, shape_layers, Some(report)))
+    }
+
+    /// Points along a DXF arc, counter-clockwise from its start angle, close
+    /// enough that no chord strays more than 0.01 units from the arc
+    fn arc_points(arc: &DxfArc) -> Vec<Point> {
+        let mut sweep = (arc.end_angle - arc.start_angle).rem_euclid(360.0);
+        if sweep == 0.0 {
+            sweep = 360.0;
+        }
+        let (start, sweep) = (arc.start_angle.to_radians(), sweep.to_radians());
+        let step = if arc.radius > 0.01 {
+            2.0 * (1.0 - 0.01 / arc.radius).acos()
+        } else {
+            sweep
+        };
+        let steps = ((sweep / step).ceil() as usize).clamp(1, 720);
+        (0..=steps)
+            .map(|i| {
+                let angle = start + sweep * i as f64 / steps as f64;
+                Point::new(
+                    arc.center.x + arc.radius * angle.cos(),
+                    arc.center.y + arc.radius * angle.sin(),
+                )
+            })
+            .collect()
     }
 }
 
@@ -691,6 +945,7 @@ impl StlImporter {
     fn create_imported_design(&self, mesh: Mesh3D) -> Result<ImportedDesign> {
         // Generate 2D shadow projection
         let shapes = mesh.project_shadow_z()?;
+        let shape_layers = vec![None; shapes.len()];
 
         // Calculate 2D dimensions from mesh bounds
         let width = (mesh.bounds_max.x - mesh.bounds_min.x) as f64;
@@ -702,7 +957,10 @@ impl StlImporter {
             dimensions,
             format: FileFormat::Stl,
             layer_count: 1, // STL shadow projection creates a single layer
+            layers: Vec::new(),
+            shape_layers,
             mesh_3d: Some(mesh),
+            repair_report: None,
         })
     }
 
@@ -716,6 +974,7 @@ impl StlImporter {
 
         // Generate 2D slice instead of shadow projection
         let shapes = mesh.slice_at_z(z_height)?;
+        let shape_layers = vec![None; shapes.len()];
 
         // Calculate 2D dimensions from mesh bounds
         let width = (mesh.bounds_max.x - mesh.bounds_min.x) as f64;
@@ -727,7 +986,10 @@ impl StlImporter {
             dimensions,
             format: FileFormat::Stl,
             layer_count: 1, // Single slice creates one layer
+            layers: Vec::new(),
+            shape_layers,
             mesh_3d: Some(mesh),
+            repair_report: None,
         })
     }
 }
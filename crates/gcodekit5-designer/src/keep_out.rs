@@ -0,0 +1,329 @@
+//! Keep-out regions for clamps and fixtures.
+//!
+//! A keep-out region is an XY footprint (rectangle or polygon) with a top height in
+//! work coordinates. [`KeepOutChecker`] flags every cutting move whose tool would
+//! enter a footprint below its height, and every rapid that crosses a footprint
+//! while the clearance height is not above it. Footprints are grown by the tool
+//! radius, and a spatial index over their bounds prunes regions a segment cannot
+//! reach, so the check is cheap enough to run on every generation.
+
+use crate::geometry::{distance_to_segment, point_in_polygon};
+use crate::model::Point;
+use crate::spatial_index::{Bounds, SpatialIndex};
+use crate::toolpath::{Toolpath, ToolpathSegment, ToolpathSegmentType};
+use gcodekit5_devicedb::{RegionShape, WorkRegion};
+use serde::{Deserialize, Serialize};
+
+/// Heights and distances are compared with this tolerance (mm)
+const EPSILON: f64 = 1e-6;
+/// Maximum angle between the chord points an arc is checked at (radians)
+const ARC_STEP: f64 = std::f64::consts::PI / 32.0;
+/// IDs of regions taken from the device profile start here, clear of IDs
+/// given to the design's own regions
+pub const DEVICE_REGION_ID_BASE: u64 = 1 << 48;
+
+/// XY footprint of a keep-out region.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum KeepOutShape {
+    /// Axis-aligned rectangle between two corners
+    Rectangle { min: Point, max: Point },
+    /// Closed polygon; the last vertex joins the first
+    Polygon(Vec<Point>),
+}
+
+/// A clamp or fixture the tool must stay out of.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeepOutRegion {
+    pub id: u64,
+    pub name: String,
+    pub shape: KeepOutShape,
+    /// Top of the fixture in work coordinates (mm)
+    pub height: f64,
+}
+
+impl KeepOutRegion {
+    /// Creates a rectangular region from two opposite corners.
+    pub fn rectangle(id: u64, name: impl Into<String>, a: Point, b: Point, height: f64) -> Self {
+        Self {
+            id,
+            name: name.into(),
+            shape: KeepOutShape::Rectangle {
+                min: Point::new(a.x.min(b.x), a.y.min(b.y)),
+                max: Point::new(a.x.max(b.x), a.y.max(b.y)),
+            },
+            height,
+        }
+    }
+
+    /// Creates a polygonal region.
+    pub fn polygon(id: u64, name: impl Into<String>, vertices: Vec<Point>, height: f64) -> Self {
+        Self {
+            id,
+            name: name.into(),
+            shape: KeepOutShape::Polygon(vertices),
+            height,
+        }
+    }
+
+    /// The keep-out region for a device profile's no-go zone, or `None` if
+    /// the zone is only drawn.
+    ///
+    /// A zone without a height is kept out at every height.
+    pub fn from_work_region(id: u64, region: &WorkRegion) -> Option<Self> {
+        if !region.no_go {
+            return None;
+        }
+        let height = region.height.unwrap_or(f64::MAX);
+        let point = |p: &[f64; 2]| Point::new(p[0], p[1]);
+        Some(match &region.shape {
+            RegionShape::Rectangle { min, max } => {
+                Self::rectangle(id, region.name.clone(), point(min), point(max), height)
+            }
+            RegionShape::Polygon { points } => Self::polygon(
+                id,
+                region.name.clone(),
+                points.iter().map(point).collect(),
+                height,
+            ),
+        })
+    }
+
+    /// Bounding box of the footprint.
+    pub fn bounds(&self) -> Bounds {
+        match &self.shape {
+            KeepOutShape::Rectangle { min, max } => Bounds::new(min.x, min.y, max.x, max.y),
+            KeepOutShape::Polygon(vertices) => {
+                let mut bounds = Bounds::new(
+                    f64::INFINITY,
+                    f64::INFINITY,
+                    f64::NEG_INFINITY,
+                    f64::NEG_INFINITY,
+                );
+                for v in vertices {
+                    bounds.min_x = bounds.min_x.min(v.x);
+                    bounds.min_y = bounds.min_y.min(v.y);
+                    bounds.max_x = bounds.max_x.max(v.x);
+                    bounds.max_y = bounds.max_y.max(v.y);
+                }
+                bounds
+            }
+        }
+    }
+
+    fn vertices(&self) -> Vec<Point> {
+        match &self.shape {
+            KeepOutShape::Rectangle { min, max } => vec![
+                *min,
+                Point::new(max.x, min.y),
+                *max,
+                Point::new(min.x, max.y),
+            ],
+            KeepOutShape::Polygon(vertices) => vertices.clone(),
+        }
+    }
+}
+
+/// Why a segment was flagged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeepOutViolation {
+    /// A feed move enters the footprint below the region's height
+    Cut,
+    /// A rapid crosses the footprint at a clearance height not above the region
+    Rapid,
+}
+
+/// A segment that collides with a keep-out region.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeepOutCollision {
+    /// Index of the segment in its toolpath
+    pub segment_index: usize,
+    pub region_id: u64,
+    pub violation: KeepOutViolation,
+    /// Lowest Z of the move (mm)
+    pub z: f64,
+}
+
+/// A collision found while generating a design, located by shape and toolpath.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShapeKeepOutCollision {
+    pub shape_id: u64,
+    /// Index of the toolpath among the shape's toolpaths
+    pub toolpath_index: usize,
+    pub collision: KeepOutCollision,
+}
+
+/// Checks toolpaths against a set of keep-out regions.
+#[derive(Debug, Clone)]
+pub struct KeepOutChecker {
+    regions: Vec<KeepOutRegion>,
+    vertices: Vec<Vec<Point>>,
+    index: SpatialIndex,
+}
+
+impl KeepOutChecker {
+    /// Builds a checker, indexing the regions by their bounds.
+    pub fn new(regions: &[KeepOutRegion]) -> Self {
+        let regions: Vec<KeepOutRegion> = regions
+            .iter()
+            .filter(|r| r.vertices().len() >= 3 && r.height.is_finite())
+            .cloned()
+            .collect();
+        let all = regions
+            .iter()
+            .map(KeepOutRegion::bounds)
+            .reduce(|a, b| {
+                Bounds::new(
+                    a.min_x.min(b.min_x),
+                    a.min_y.min(b.min_y),
+                    a.max_x.max(b.max_x),
+                    a.max_y.max(b.max_y),
+                )
+            })
+            .unwrap_or(Bounds::new(0.0, 0.0, 1.0, 1.0))
+            .expanded(1.0);
+
+        let mut index = SpatialIndex::new(all, 8, 4);
+        for (i, region) in regions.iter().enumerate() {
+            index.insert(i as u64, &region.bounds());
+        }
+        let vertices = regions.iter().map(KeepOutRegion::vertices).collect();
+        Self {
+            regions,
+            vertices,
+            index,
+        }
+    }
+
+    /// The regions being checked.
+    pub fn regions(&self) -> &[KeepOutRegion] {
+        &self.regions
+    }
+
+    /// Flags the segments of a toolpath that hit a keep-out region.
+    ///
+    /// `clearance_z` is the height rapids travel at, as the G-code generator's
+    /// safe Z. Cutting moves are checked at their programmed depth.
+    pub fn check(&self, toolpath: &Toolpath, clearance_z: f64) -> Vec<KeepOutCollision> {
+        if self.regions.is_empty() {
+            return Vec::new();
+        }
+        let radius = toolpath.tool_diameter.max(0.0) / 2.0;
+        let mut collisions = Vec::new();
+
+        for (segment_index, segment) in toolpath.segments.iter().enumerate() {
+            let (violation, z) = match segment.segment_type {
+                ToolpathSegmentType::RapidMove => (KeepOutViolation::Rapid, clearance_z),
+                _ => (KeepOutViolation::Cut, cutting_z(segment, toolpath)),
+            };
+            let points = segment_points(segment);
+            let Some(bounds) = points_bounds(&points) else {
+                continue;
+            };
+
+            let mut candidates = self.index.query(&bounds.expanded(radius));
+            candidates.sort_unstable();
+            for candidate in candidates {
+                let region = &self.regions[candidate as usize];
+                if z > region.height + EPSILON {
+                    continue;
+                }
+                let polygon = &self.vertices[candidate as usize];
+                let hit = points
+                    .windows(2)
+                    .any(|w| segment_near_polygon(w[0], w[1], polygon, radius));
+                if hit {
+                    collisions.push(KeepOutCollision {
+                        segment_index,
+                        region_id: region.id,
+                        violation,
+                        z,
+                    });
+                }
+            }
+        }
+        collisions
+    }
+}
+
+/// Lowest Z a cutting segment reaches, following the G-code generator's rules
+fn cutting_z(segment: &ToolpathSegment, toolpath: &Toolpath) -> f64 {
+    let end = segment.z_depth.unwrap_or(toolpath.depth);
+    match segment.start_z {
+        Some(start) => start.min(end),
+        None => end,
+    }
+}
+
+/// The segment as a polyline; arcs are split into short chords
+fn segment_points(segment: &ToolpathSegment) -> Vec<Point> {
+    let (clockwise, center) = match (segment.segment_type, segment.center) {
+        (ToolpathSegmentType::ArcCW, Some(center)) => (true, center),
+        (ToolpathSegmentType::ArcCCW, Some(center)) => (false, center),
+        _ => return vec![segment.start, segment.end],
+    };
+
+    let radius = center.distance_to(&segment.start);
+    let start_angle = (segment.start.y - center.y).atan2(segment.start.x - center.x);
+    let end_angle = (segment.end.y - center.y).atan2(segment.end.x - center.x);
+    let mut sweep = end_angle - start_angle;
+    if clockwise {
+        if sweep >= -EPSILON {
+            sweep -= std::f64::consts::TAU;
+        }
+    } else if sweep <= EPSILON {
+        sweep += std::f64::consts::TAU;
+    }
+
+    let steps = (sweep.abs() / ARC_STEP).ceil().max(1.0) as usize;
+    let mut points: Vec<Point> = (0..steps)
+        .map(|i| {
+            let angle = start_angle + sweep * i as f64 / steps as f64;
+            Point::new(
+                center.x + radius * angle.cos(),
+                center.y + radius * angle.sin(),
+            )
+        })
+        .collect();
+    points.push(segment.end);
+    points
+}
+
+fn points_bounds(points: &[Point]) -> Option<Bounds> {
+    let first = points.first()?;
+    let mut bounds = Bounds::new(first.x, first.y, first.x, first.y);
+    for p in points {
+        bounds.min_x = bounds.min_x.min(p.x);
+        bounds.min_y = bounds.min_y.min(p.y);
+        bounds.max_x = bounds.max_x.max(p.x);
+        bounds.max_y = bounds.max_y.max(p.y);
+    }
+    Some(bounds)
+}
+
+/// Whether a straight move passes within `radius` of (or through) a polygon
+fn segment_near_polygon(a: Point, b: Point, polygon: &[Point], radius: f64) -> bool {
+    if point_in_polygon(a, polygon) || point_in_polygon(b, polygon) {
+        return true;
+    }
+    (0..polygon.len()).any(|i| {
+        let c = polygon[i];
+        let d = polygon[(i + 1) % polygon.len()];
+        segments_distance(a, b, c, d) <= radius + EPSILON
+    })
+}
+
+fn cross(o: Point, a: Point, b: Point) -> f64 {
+    (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+}
+
+fn segments_distance(a: Point, b: Point, c: Point, d: Point) -> f64 {
+    let (d1, d2) = (cross(c, d, a), cross(c, d, b));
+    let (d3, d4) = (cross(a, b, c), cross(a, b, d));
+    if d1 * d2 < 0.0 && d3 * d4 < 0.0 {
+        return 0.0;
+    }
+    distance_to_segment(a, c, d)
+        .min(distance_to_segment(b, c, d))
+        .min(distance_to_segment(c, a, b))
+        .min(distance_to_segment(d, a, b))
+}
@@ -25,7 +25,14 @@
 //! - **History/Undo-Redo**: Full operation history management
 //! - **Spatial Indexing**: Efficient geometry queries
 //! - **Toolpath Simulation**: Visualize cutting operations
+//! - **Toolpath Trimming**: Clip toolpaths to the stock or another boundary polygon
+//! - **Trochoidal Slotting**: Slots cut with overlapping loops to keep tool engagement low
+//! - **Holding Tabs**: Per-shape tabs that keep profiled parts attached to the stock
+//! - **Segment Filtering**: Merge cutting moves shorter than a machine's minimum useful move
+//! - **Corner Smoothing**: Round sharp corners with tangent arcs within a maximum deviation
+//! - **Text on a Path**: Lay glyphs along a curve for curved engraving
 //! - **Import/Export**: DXF, SVG, and design serialization
+//! - **Contour Repair**: Join and snap closed nearly-closed outlines from imported drawings
 //! - **Rendering**: 2D visualization with optimization
 //!
 //! ## Architecture
@@ -38,7 +45,7 @@
 //!   ├── Viewport (Camera/zoom)
 //!   └── Renderer (Visualization)
 //!
-//! Operations (Pocket, drilling, multipass, adaptive, etc.)
+//! Operations (Pocket, rest machining, drilling, multipass, adaptive, etc.)
 //!   └── Spatial Index (Efficient geometry storage)
 //!
 //! Toolpath (Final G-code path)
@@ -66,14 +73,17 @@ pub mod adaptive;
 pub mod arrays;
 pub mod canvas;
 pub mod commands;
+pub mod contour_repair;
 pub mod drilling_patterns;
 pub mod dxf_parser;
 pub mod error;
 pub mod font_manager;
 pub mod gcode_gen;
+pub mod geometry;
 pub mod helpers;
 pub mod history;
 pub mod import;
+pub mod keep_out;
 pub mod model;
 pub mod model3d;
 pub mod multipass;
@@ -83,6 +93,7 @@ pub mod parametric_shapes;
 pub mod pocket_operations;
 pub mod render_optimizer;
 pub mod renderer;
+pub mod rest_machining;
 pub mod selection_manager;
 pub mod serialization;
 pub mod shadow_projection;
@@ -93,10 +104,16 @@ pub mod spatial_index;
 pub mod spatial_manager;
 pub mod stock_removal;
 pub mod svg_renderer;
+pub mod tabs;
 pub mod templates;
 pub mod tool_library;
 pub mod toolpath;
+pub mod toolpath_filter;
+pub mod toolpath_gaps;
 pub mod toolpath_simulation;
+pub mod toolpath_smoothing;
+pub mod toolpath_trim;
+pub mod trochoidal;
 pub mod vcarve;
 pub mod viewport;
 
@@ -117,20 +134,31 @@ pub use arrays::{
 };
 pub use canvas::{Canvas, CanvasPoint, DrawingMode};
 pub use commands::DesignerCommand;
+pub use contour_repair::{
+    repair_contours, Contour, ContourGap, ContourRepair, ContourRepairReport,
+};
 pub use drilling_patterns::*;
 pub use dxf_parser::{DxfEntity, DxfFile, DxfHeader, DxfParser};
-pub use gcode_gen::ToolpathToGcode;
+pub use font_manager::{
+    layout_text_on_path, GlyphSource, PathTextOptions, PlacedGlyph, TextOverflow,
+};
+pub use gcode_gen::{CoordinateSnap, EmitState, ToolpathToGcode};
 pub use history::{ActionType, HistoryAction, HistoryTransaction, UndoRedoManager};
-pub use import::{DxfImporter, FileFormat, ImportedDesign, StlImporter, SvgImporter};
+pub use import::{DxfImporter, FileFormat, ImportLayer, ImportedDesign, StlImporter, SvgImporter};
+pub use keep_out::{
+    KeepOutChecker, KeepOutCollision, KeepOutRegion, KeepOutShape, KeepOutViolation,
+    ShapeKeepOutCollision, DEVICE_REGION_ID_BASE,
+};
 pub use model::{
     DesignCircle as Circle, DesignEllipse as Ellipse, DesignLine as Line, DesignPath as PathShape,
     DesignRectangle as Rectangle, DesignText as TextShape, Point, Shape, ShapeType,
 };
 pub use model3d::{Mesh3D, Model3DFormat, Model3DImporter, ProjectionParams, Triangle3D};
-pub use multipass::{DepthStrategy, MultiPassConfig, MultiPassToolpathGenerator};
+pub use multipass::{DepthStrategy, FinishingPass, MultiPassConfig, MultiPassToolpathGenerator};
 pub use parametric::ParametricGenerator;
 pub use pocket_operations::{Island, PocketGenerator, PocketOperation};
 pub use render_optimizer::{RenderOptimizer, RenderStats};
+pub use rest_machining::{rest_regions, RestSource};
 pub use shadow_projection::{
     BatchProjector, ProjectionMethod, ShadowProjectionParams, ShadowProjector, SliceLayer,
     SlicingParams,
@@ -140,10 +168,19 @@ pub use slice_toolpath::{
 };
 pub use spatial_index::{Bounds, SpatialIndex, SpatialIndexStats};
 pub use stock_removal::{HeightMap2D, SimulationResult, StockMaterial};
+pub use tabs::{apply_tabs, TabSettings};
 pub use templates::*;
-pub use tool_library::{CoolantType, MaterialProfile, Tool, ToolLibrary, ToolType};
+pub use tool_library::{
+    scallop_height, stepover_for_scallop, CoolantType, CutRecipe, MaterialProfile, RecipeEntry,
+    RecipeStore, ResolvedRecipe, StepoverSpec, Tool, ToolLibrary, ToolType,
+};
 pub use toolpath::{Toolpath, ToolpathGenerator, ToolpathSegment, ToolpathSegmentType};
+pub use toolpath_filter::{filter_short_segments, SegmentFilter, SegmentFilterReport};
+pub use toolpath_gaps::{bridge_gaps, GapBridging, GapBridgingReport};
 pub use toolpath_simulation::{SimulationState, ToolPosition, ToolpathAnalyzer, ToolpathSimulator};
+pub use toolpath_smoothing::{smooth_corners, CornerSmoothing, CornerSmoothingReport};
+pub use toolpath_trim::trim_to_boundary;
+pub use trochoidal::{trochoidal_segments, TrochoidalParams};
 pub use vcarve::VCarveGenerator;
 pub use viewport::Viewport;
 
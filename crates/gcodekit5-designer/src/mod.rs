@@ -77,10 +77,12 @@ pub use dxf_parser::{
     DxfArc, DxfCircle, DxfEntity, DxfEntityType, DxfFile, DxfHeader, DxfLine, DxfParser,
     DxfPolyline, DxfText, DxfUnit,
 };
-pub use gcode_gen::ToolpathToGcode;
+pub use gcode_gen::{CoordinateSnap, EmitState, ToolpathToGcode};
 pub use history::{ActionType, HistoryAction, HistoryTransaction, UndoRedoManager};
-pub use import::{DxfImporter, FileFormat, ImportedDesign, SvgImporter};
-pub use multipass::{DepthStrategy, MultiPassConfig, MultiPassToolpathGenerator};
+pub use import::{DxfImporter, FileFormat, ImportLayer, ImportedDesign, SvgImporter};
+pub use multipass::{
+    DepthStrategy, FinishingPass, MultiPassConfig, MultiPassToolpathGenerator,
+};
 pub use parametric::{
     Parameter, ParameterConstraint, ParameterSet, ParameterType, ParametricGenerator,
     ParametricTemplate, TemplateLibrary,
@@ -94,8 +96,13 @@ pub use spatial_index::{Bounds, SpatialIndex, SpatialIndexStats};
 pub use templates::{
     DesignTemplate, DesignTemplateLibrary, TemplateCategory, TemplateManager, TemplatePersistence,
 };
-pub use tool_library::{CoolantType, MaterialProfile, Tool, ToolLibrary, ToolType};
+pub use tool_library::{
+    CoolantType, CutRecipe, MaterialProfile, RecipeEntry, RecipeStore, ResolvedRecipe, Tool,
+    ToolLibrary, ToolType,
+};
 pub use toolpath::{Toolpath, ToolpathGenerator, ToolpathSegment, ToolpathSegmentType};
 pub use toolpath_simulation::{SimulationState, ToolPosition, ToolpathAnalyzer, ToolpathSimulator};
-pub use vcarve::{VBitTool, VCarveGenerator, VCarveParams, VCarveSegment};
+pub use vcarve::{
+    StrokeCarveParams, StrokePoint, VBitTool, VCarveGenerator, VCarveParams, VCarveSegment,
+};
 pub use viewport::Viewport;
@@ -2,6 +2,11 @@
 //!
 //! Implements depth ramping and stepping for multi-pass cutting operations,
 //! enabling deep cuts while maintaining tool safety and surface finish quality.
+//!
+//! [`MultiPassToolpathGenerator::wrap_toolpath`] takes any single-depth 2D
+//! toolpath, such as an imported DXF or SVG contour, and repeats it at each pass
+//! depth. Closed loops stay down and plunge to the next level where they end;
+//! open paths retract and rapid back to their start between levels.
 
 use super::toolpath::{Toolpath, ToolpathSegment, ToolpathSegmentType};
 use crate::Point;
@@ -28,6 +33,19 @@ impl DepthStrategy {
     }
 }
 
+/// Distance (mm) within which a path's end counts as back at its start
+const CLOSED_TOLERANCE: f64 = 1e-3;
+
+/// A last pass at full depth after the roughing passes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FinishingPass {
+    /// Material (mm) the roughing passes leave above full depth; zero repeats
+    /// the full-depth pass as a spring pass
+    pub allowance: f64,
+    /// Feed rate for the finishing pass; `None` keeps each segment's feed rate
+    pub feed_rate: Option<f64>,
+}
+
 /// Configuration for multi-pass depth control.
 #[derive(Debug, Clone)]
 pub struct MultiPassConfig {
@@ -36,6 +54,7 @@ pub struct MultiPassConfig {
     pub strategy: DepthStrategy,
     pub minimum_depth: f64,
     pub ramp_start_depth: f64,
+    pub finishing_pass: Option<FinishingPass>,
 }
 
 impl MultiPassConfig {
@@ -59,6 +78,7 @@ impl MultiPassConfig {
             strategy: DepthStrategy::Constant,
             minimum_depth: 0.5,
             ramp_start_depth: 2.0,
+            finishing_pass: None,
         }
     }
 
@@ -85,6 +105,11 @@ impl MultiPassConfig {
         self.ramp_start_depth = if depth >= 0.0 { depth } else { 0.0 };
     }
 
+    /// Sets the finishing pass run after the roughing passes.
+    pub fn set_finishing_pass(&mut self, finishing_pass: Option<FinishingPass>) {
+        self.finishing_pass = finishing_pass;
+    }
+
     /// Calculates the number of passes needed.
     pub fn calculate_passes(&self) -> u32 {
         if self.max_depth_per_pass <= 0.0 {
@@ -146,6 +171,40 @@ impl MultiPassConfig {
             .map(|pass| self.calculate_pass_depth(pass))
             .collect()
     }
+
+    /// Z of each roughing pass below the stock top, deepest last.
+    ///
+    /// Constant passes split the depth evenly; ramped and adaptive passes step
+    /// down by their per-pass depth, clamped to the total depth. The last level
+    /// is always the total depth.
+    pub fn pass_levels(&self) -> Vec<f64> {
+        let target = -self.total_depth.abs();
+        if target.abs() < f64::EPSILON {
+            return Vec::new();
+        }
+        let passes = self.calculate_passes();
+        if self.strategy == DepthStrategy::Constant {
+            return (1..=passes)
+                .map(|pass| target * pass as f64 / passes as f64)
+                .collect();
+        }
+
+        let smallest_step = self.minimum_depth.min(self.max_depth_per_pass);
+        let mut levels = Vec::new();
+        let mut z: f64 = 0.0;
+        let mut pass = 1;
+        while z > target {
+            let step = if pass <= passes {
+                self.calculate_pass_depth(pass).abs()
+            } else {
+                self.max_depth_per_pass
+            };
+            z = (z - step.clamp(smallest_step, self.max_depth_per_pass)).max(target);
+            levels.push(z);
+            pass += 1;
+        }
+        levels
+    }
 }
 
 /// Manages multi-pass toolpath generation.
@@ -176,6 +235,71 @@ impl MultiPassToolpathGenerator {
         multi_pass
     }
 
+    /// Repeats a single-depth toolpath at every pass level.
+    ///
+    /// Each run of cutting segments between rapids is cut to full depth before
+    /// the next. A closed run stays down and plunges at its start for the next
+    /// level; an open run retracts and rapids back to its start. The base
+    /// toolpath's own rapids are kept as the links between runs. With a
+    /// finishing pass, roughing stops short by its allowance and a last level
+    /// at full depth follows at the finishing feed rate.
+    pub fn wrap_toolpath(&self, base_toolpath: &Toolpath) -> Toolpath {
+        let total = self.config.total_depth.abs();
+        let mut levels: Vec<(f64, Option<f64>)> = Vec::new();
+        match self.config.finishing_pass {
+            Some(finish) => {
+                let allowance = finish.allowance.clamp(0.0, total);
+                let mut roughing = self.config.clone();
+                roughing.total_depth = total - allowance;
+                levels.extend(roughing.pass_levels().into_iter().map(|z| (z, None)));
+                levels.push((-total, finish.feed_rate));
+            }
+            None => levels.extend(self.config.pass_levels().into_iter().map(|z| (z, None))),
+        }
+
+        let mut wrapped = Toolpath::new(base_toolpath.tool_diameter, -total);
+        let mut run: Vec<&ToolpathSegment> = Vec::new();
+        for segment in &base_toolpath.segments {
+            if segment.segment_type == ToolpathSegmentType::RapidMove {
+                Self::wrap_run(&mut wrapped, &run, &levels);
+                run.clear();
+                wrapped.add_segment(segment.clone());
+            } else {
+                run.push(segment);
+            }
+        }
+        Self::wrap_run(&mut wrapped, &run, &levels);
+        wrapped
+    }
+
+    /// Cut one run of connected segments at every level
+    fn wrap_run(toolpath: &mut Toolpath, run: &[&ToolpathSegment], levels: &[(f64, Option<f64>)]) {
+        let (Some(first), Some(last)) = (run.first(), run.last()) else {
+            return;
+        };
+        let closed = first.start.distance_to(&last.end) <= CLOSED_TOLERANCE;
+        for (index, &(z, feed_rate)) in levels.iter().enumerate() {
+            if index > 0 && !closed {
+                toolpath.add_segment(ToolpathSegment::new(
+                    ToolpathSegmentType::RapidMove,
+                    last.end,
+                    first.start,
+                    last.feed_rate,
+                    last.spindle_speed,
+                ));
+            }
+            for segment in run {
+                let mut level_segment = (*segment).clone();
+                level_segment.start_z = Some(z);
+                level_segment.z_depth = Some(z);
+                if let Some(feed_rate) = feed_rate {
+                    level_segment.feed_rate = feed_rate;
+                }
+                toolpath.add_segment(level_segment);
+            }
+        }
+    }
+
     /// Adjusts a toolpath segment to the specified depth.
     fn adjust_segment_depth(&self, segment: &ToolpathSegment, depth: f64) -> ToolpathSegment {
         let mut adjusted = segment.clone();
@@ -4,8 +4,11 @@
 //! Supports outline pocket and island preservation.
 
 use super::toolpath::{Toolpath, ToolpathSegment, ToolpathSegmentType};
+use crate::geometry::point_in_polygon;
 use crate::model::{DesignCircle as Circle, DesignRectangle as Rectangle, Point};
 use crate::ops::clean_polyline;
+use crate::rest_machining::{grow, pline_points, rest_center_regions, RestSource};
+use crate::tool_library::{scallop_height, ResolvedRecipe, StepoverSpec};
 use cavalier_contours::polyline::{PlineSource, PlineSourceMut, PlineVertex, Polyline};
 use std::f64::consts::PI;
 use std::panic;
@@ -116,23 +119,6 @@ fn polygon_centroid(points: &[Point]) -> Option<Point> {
     Some(Point::new(cx, cy))
 }
 
-fn point_in_polygon(poly: &[Point], test: Point) -> bool {
-    // Standard ray casting test.
-    let mut inside = false;
-    let mut j = poly.len().saturating_sub(1);
-    for i in 0..poly.len() {
-        let pi = poly[i];
-        let pj = poly[j];
-        if ((pi.y > test.y) != (pj.y > test.y))
-            && (test.x < (pj.x - pi.x) * (test.y - pi.y) / (pj.y - pi.y + f64::EPSILON) + pi.x)
-        {
-            inside = !inside;
-        }
-        j = i;
-    }
-    inside
-}
-
 /// Strategy for pocket milling.
 #[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize, Default)]
 pub enum PocketStrategy {
@@ -159,6 +145,10 @@ pub struct PocketOperation {
     pub strategy: PocketStrategy,
     pub ramp_angle: f64,
     pub raster_fill_ratio: f64,
+    /// Stepover, feed and speed came from an estimated recipe
+    pub parameters_estimated: bool,
+    /// Only machine what a previous operation left behind
+    pub rest_machining: Option<RestSource>,
 }
 
 impl PocketOperation {
@@ -181,7 +171,20 @@ impl PocketOperation {
             strategy: PocketStrategy::ContourParallel,
             ramp_angle: 0.0,
             raster_fill_ratio: 0.5,
+            parameters_estimated: false,
+            rest_machining: None,
+        }
+    }
+
+    /// Takes stepover, feed rate and spindle speed from a tool library recipe.
+    pub fn apply_recipe(&mut self, resolved: &ResolvedRecipe) {
+        let recipe = &resolved.recipe;
+        if recipe.stepover > 0.0 {
+            self.stepover = recipe.stepover;
         }
+        self.feed_rate = recipe.feed_rate;
+        self.spindle_speed = recipe.spindle_speed;
+        self.parameters_estimated = resolved.estimated;
     }
 
     /// Sets the start depth (top of stock).
@@ -204,6 +207,20 @@ impl PocketOperation {
         self.spindle_speed = spindle_speed;
     }
 
+    /// Sets the stepover directly or from a target ball-nose scallop height,
+    /// at most the tool diameter.
+    pub fn set_stepover_spec(&mut self, spec: StepoverSpec) {
+        let stepover = spec.resolve(self.tool_diameter);
+        if stepover > 0.0 {
+            self.stepover = stepover;
+        }
+    }
+
+    /// Scallop height a ball-nose of this diameter leaves at the current stepover.
+    pub fn scallop_height(&self) -> f64 {
+        scallop_height(self.tool_diameter / 2.0, self.stepover)
+    }
+
     /// Enables or disables climb milling.
     pub fn set_climb_milling(&mut self, enable: bool) {
         self.climb_milling = enable;
@@ -214,6 +231,12 @@ impl PocketOperation {
         self.strategy = strategy;
     }
 
+    /// Limits the pocket to material left by a previous operation, or clears the
+    /// whole pocket with `None`.
+    pub fn set_rest_machining(&mut self, rest: Option<RestSource>) {
+        self.rest_machining = rest;
+    }
+
     /// Sets the ramp angle in degrees.
     pub fn set_ramp_angle(&mut self, angle: f64) {
         debug_assert!(
@@ -369,7 +392,7 @@ impl PocketGenerator {
                 let end_pt = inv_rotate(end_rot);
 
                 // Skip if outside original polygon (safety check for concave cases)
-                if !point_in_polygon(vertices, start_pt) && !point_in_polygon(vertices, end_pt) {
+                if !point_in_polygon(start_pt, vertices) && !point_in_polygon(end_pt, vertices) {
                     continue;
                 }
 
@@ -540,7 +563,9 @@ impl PocketGenerator {
 
     /// Generates a pocket toolpath for a rectangular outline.
     pub fn generate_rectangular_pocket(&self, rect: &Rectangle, step_down: f64) -> Vec<Toolpath> {
-        if let PocketStrategy::ContourParallel = self.operation.strategy {
+        if self.operation.strategy == PocketStrategy::ContourParallel
+            && self.operation.rest_machining.is_none()
+        {
             let mut toolpaths = Vec::new();
 
             let half_tool = self.operation.tool_diameter / 2.0;
@@ -666,7 +691,9 @@ impl PocketGenerator {
 
     /// Generates a pocket toolpath for a circular outline.
     pub fn generate_circular_pocket(&self, circle: &Circle, step_down: f64) -> Vec<Toolpath> {
-        if let PocketStrategy::ContourParallel = self.operation.strategy {
+        if self.operation.strategy == PocketStrategy::ContourParallel
+            && self.operation.rest_machining.is_none()
+        {
             let mut toolpaths = Vec::new();
 
             let half_tool = self.operation.tool_diameter / 2.0;
@@ -788,7 +815,13 @@ impl PocketGenerator {
     }
 
     /// Generates a pocket toolpath for a polygon defined by vertices.
+    ///
+    /// With rest machining set, only the material left behind is machined and
+    /// the result is empty when nothing is left.
     pub fn generate_polygon_pocket(&self, vertices: &[Point], step_down: f64) -> Vec<Toolpath> {
+        if let Some(rest) = &self.operation.rest_machining {
+            return self.generate_rest_pocket(vertices, step_down, rest);
+        }
         match self.operation.strategy {
             PocketStrategy::Raster {
                 angle,
@@ -974,6 +1007,112 @@ impl PocketGenerator {
         toolpaths
     }
 
+    /// Clears only the rest material with contour-parallel loops in each region.
+    ///
+    /// Regions are reached with a rapid move since a straight move at depth
+    /// between them could cross a pocket wall. Adaptive works each region from
+    /// the inside out; raster strategies use outside-in loops as rest regions
+    /// are usually too small for scanlines.
+    fn generate_rest_pocket(
+        &self,
+        vertices: &[Point],
+        step_down: f64,
+        rest: &RestSource,
+    ) -> Vec<Toolpath> {
+        let mut toolpaths = Vec::new();
+        if vertices.is_empty() {
+            return toolpaths;
+        }
+
+        let polyline = Self::prepare_polygon(vertices);
+        let regions = rest_center_regions(&polyline, rest, self.operation.tool_diameter / 2.0);
+        if regions.is_empty() {
+            tracing::info!(
+                "Rest machining {}: nothing left for the {:.3} mm tool to clear",
+                self.operation.id,
+                self.operation.tool_diameter
+            );
+            return toolpaths;
+        }
+
+        let step = self.operation.stepover.max(0.1);
+        let region_loops: Vec<Vec<Vec<Point>>> = regions
+            .iter()
+            .map(|region| {
+                let mut loops = vec![pline_points(region)];
+                let mut offset = step;
+                loop {
+                    let insets = grow(region, -offset);
+                    if insets.is_empty() {
+                        break;
+                    }
+                    loops.extend(insets.iter().map(pline_points));
+                    offset += step;
+                }
+                loops.retain(|points| points.len() > 2);
+                if self.operation.strategy == PocketStrategy::Adaptive {
+                    loops.reverse();
+                }
+                loops
+            })
+            .collect();
+
+        let total_depth = self.operation.depth.abs();
+        let z_step = if step_down > 0.0 {
+            step_down
+        } else {
+            total_depth
+        };
+        let z_passes = (total_depth / z_step).ceil() as u32;
+        let mut prev_z = self.operation.start_depth;
+
+        for z_pass in 1..=z_passes {
+            let current_z = self.operation.start_depth - (z_step * z_pass as f64).min(total_depth);
+            let mut toolpath = Toolpath::new(self.operation.tool_diameter, current_z);
+
+            for loops in &region_loops {
+                for (index, points) in loops.iter().enumerate() {
+                    if index == 0 {
+                        if self.operation.ramp_angle > 0.0 {
+                            self.add_helical_ramp(&mut toolpath, points[0], prev_z, current_z);
+                        } else {
+                            toolpath.add_segment(ToolpathSegment::new(
+                                ToolpathSegmentType::RapidMove,
+                                Point::new(0.0, 0.0),
+                                points[0],
+                                self.operation.feed_rate,
+                                self.operation.spindle_speed,
+                            ));
+                        }
+                    } else {
+                        let last_pt = toolpath.segments.last().map(|s| s.end).unwrap_or(points[0]);
+                        toolpath.add_segment(ToolpathSegment::new(
+                            ToolpathSegmentType::LinearMove,
+                            last_pt,
+                            points[0],
+                            self.operation.feed_rate,
+                            self.operation.spindle_speed,
+                        ));
+                    }
+
+                    for window in points.windows(2) {
+                        toolpath.add_segment(ToolpathSegment::new(
+                            ToolpathSegmentType::LinearMove,
+                            window[0],
+                            window[1],
+                            self.operation.feed_rate,
+                            self.operation.spindle_speed,
+                        ));
+                    }
+                }
+            }
+
+            toolpaths.push(toolpath);
+            prev_z = current_z;
+        }
+        toolpaths
+    }
+
     fn generate_adaptive_pocket(&self, vertices: &[Point], step_down: f64) -> Vec<Toolpath> {
         let mut toolpaths = Vec::new();
         if vertices.is_empty() {
@@ -0,0 +1,211 @@
+//! # Rest Machining
+//!
+//! Finds the material a previous, larger tool left in a pocket so a smaller tool
+//! only machines those regions instead of re-cutting the whole pocket.
+//!
+//! With a previous tool of radius `R`, the area it could clear is the pocket
+//! shrunk by `R` and grown back by `R` (a morphological opening); everything
+//! else, mostly inside corners and narrow slots, is rest material. Rest material
+//! can also be given directly, e.g. the uncut outlines of a stock simulation.
+//!
+//! The current tool's centre then has to cover every position within its radius
+//! of rest material that it can reach inside the pocket. Those centre regions
+//! are what [`rest_regions`] returns and what the pocket generator fills with
+//! contour-parallel loops.
+
+use crate::model::Point;
+use crate::ops::clean_polyline;
+use cavalier_contours::polyline::{
+    seg_arc_radius_and_center, BooleanOp, PlineOrientation, PlineSource, PlineSourceMut,
+    PlineVertex, Polyline,
+};
+use std::panic;
+
+/// Distance (mm) the cleared area is grown by so walls it already touches are
+/// not reported as rest material
+const CLEARED_TOLERANCE: f64 = 0.01;
+
+/// Rest material smaller than this (mm²) is ignored
+const MIN_REST_AREA: f64 = 0.001;
+
+/// Longest chord (mm) used when flattening arcs into toolpath points
+const ARC_CHORD: f64 = 0.25;
+
+/// What a previous operation left behind in the pocket
+#[derive(Debug, Clone, PartialEq)]
+pub enum RestSource {
+    /// Diameter (mm) of the tool that cleared the pocket before
+    PreviousTool { diameter: f64 },
+    /// Closed outlines of stock still standing, e.g. from a stock simulation
+    RemainingStock(Vec<Vec<Point>>),
+}
+
+/// Tool centre regions that clear the rest material of a pocket
+///
+/// `boundary` is the pocket outline. Returns closed outlines (first point not
+/// repeated); empty when the current tool has nothing left to clear, including
+/// when it is no smaller than the previous tool.
+pub fn rest_regions(
+    boundary: &[Point],
+    source: &RestSource,
+    tool_diameter: f64,
+) -> Vec<Vec<Point>> {
+    rest_center_regions(&closed_pline(boundary), source, tool_diameter / 2.0)
+        .iter()
+        .map(|region| {
+            let mut points = pline_points(region);
+            points.pop();
+            points
+        })
+        .collect()
+}
+
+/// Tool centre regions as polylines; see [`rest_regions`]
+pub(crate) fn rest_center_regions(
+    boundary: &Polyline,
+    source: &RestSource,
+    tool_radius: f64,
+) -> Vec<Polyline> {
+    if boundary.vertex_count() < 3 || tool_radius <= 0.0 {
+        return Vec::new();
+    }
+
+    let rest_material: Vec<Polyline> = match source {
+        RestSource::PreviousTool { diameter } => {
+            let previous_radius = diameter / 2.0;
+            if previous_radius <= tool_radius + CLEARED_TOLERANCE {
+                return Vec::new();
+            }
+            let cleared: Vec<Polyline> = grow(boundary, -previous_radius)
+                .iter()
+                .flat_map(|reach| grow(reach, previous_radius + CLEARED_TOLERANCE))
+                .collect();
+            subtract(vec![boundary.clone()], &cleared)
+        }
+        RestSource::RemainingStock(outlines) => outlines
+            .iter()
+            .filter(|outline| outline.len() >= 3)
+            .flat_map(|outline| intersect(&closed_pline(outline), boundary))
+            .collect(),
+    };
+
+    let reachable = grow(boundary, -tool_radius);
+    let mut regions: Vec<Polyline> = Vec::new();
+    for material in rest_material
+        .iter()
+        .filter(|m| m.area().abs() > MIN_REST_AREA)
+    {
+        // Positions close enough to touch the material; growing by slightly less
+        // than the radius keeps loops from brushing past without cutting
+        for touch in grow(material, tool_radius - CLEARED_TOLERANCE) {
+            for reach in &reachable {
+                for region in intersect(&touch, reach) {
+                    regions = union_into(regions, region);
+                }
+            }
+        }
+    }
+    regions.retain(|region| region.area().abs() > MIN_REST_AREA);
+    regions
+}
+
+/// Offset a closed polyline outward by `distance` (inward when negative)
+pub(crate) fn grow(pline: &Polyline, distance: f64) -> Vec<Polyline> {
+    let pline = clean_polyline(pline.clone());
+    if pline.vertex_count() < 2 {
+        return Vec::new();
+    }
+    // Offsets go to the left of the direction of travel, which is inward for a
+    // counter-clockwise loop
+    let offset = match pline.orientation() {
+        PlineOrientation::CounterClockwise => -distance,
+        _ => distance,
+    };
+    panic::catch_unwind(panic::AssertUnwindSafe(|| pline.parallel_offset(offset)))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|p| p.is_closed() && p.vertex_count() >= 2)
+        .collect()
+}
+
+/// Points along a closed polyline, arcs flattened, ending back at the start
+pub(crate) fn pline_points(pline: &Polyline) -> Vec<Point> {
+    let count = pline.vertex_count();
+    let mut points = Vec::new();
+    for i in 0..count {
+        let (Some(v1), Some(v2)) = (pline.get(i), pline.get((i + 1) % count)) else {
+            continue;
+        };
+        points.push(Point::new(v1.x, v1.y));
+        if v1.bulge.abs() < 1e-9 {
+            continue;
+        }
+        let (radius, center) = seg_arc_radius_and_center(v1, v2);
+        let sweep = 4.0 * v1.bulge.atan();
+        let steps = ((radius * sweep.abs()) / ARC_CHORD).ceil().max(1.0) as usize;
+        let start = (v1.y - center.y).atan2(v1.x - center.x);
+        for step in 1..steps {
+            let angle = start + sweep * step as f64 / steps as f64;
+            points.push(Point::new(
+                center.x + radius * angle.cos(),
+                center.y + radius * angle.sin(),
+            ));
+        }
+    }
+    if let Some(first) = points.first().copied() {
+        points.push(first);
+    }
+    points
+}
+
+fn closed_pline(points: &[Point]) -> Polyline {
+    let mut pline = Polyline::new();
+    for p in points {
+        pline.add_vertex(PlineVertex::new(p.x, p.y, 0.0));
+    }
+    pline.set_is_closed(true);
+    clean_polyline(pline)
+}
+
+fn boolean(a: &Polyline, b: &Polyline, op: BooleanOp) -> Option<Vec<Polyline>> {
+    panic::catch_unwind(panic::AssertUnwindSafe(|| a.boolean(b, op)))
+        .ok()
+        .map(|result| {
+            result
+                .pos_plines
+                .into_iter()
+                .map(|p| p.pline)
+                .filter(|p| p.vertex_count() >= 2)
+                .collect()
+        })
+}
+
+fn intersect(a: &Polyline, b: &Polyline) -> Vec<Polyline> {
+    boolean(a, b, BooleanOp::And).unwrap_or_default()
+}
+
+/// Remove every cutter from every region
+fn subtract(mut regions: Vec<Polyline>, cutters: &[Polyline]) -> Vec<Polyline> {
+    for cutter in cutters {
+        regions = regions
+            .iter()
+            .flat_map(|region| boolean(region, cutter, BooleanOp::Not).unwrap_or_default())
+            .collect();
+    }
+    regions
+}
+
+/// Add a region, merging it with any it overlaps
+fn union_into(regions: Vec<Polyline>, mut region: Polyline) -> Vec<Polyline> {
+    let mut separate = Vec::new();
+    for existing in regions {
+        match boolean(&existing, &region, BooleanOp::Or) {
+            Some(merged) if merged.len() == 1 => {
+                region = merged.into_iter().next().unwrap_or(region)
+            }
+            _ => separate.push(existing),
+        }
+    }
+    separate.push(region);
+    separate
+}
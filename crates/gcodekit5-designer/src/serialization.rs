@@ -17,6 +17,7 @@ use std::path::Path;
 use super::canvas::DrawingObject;
 use super::pocket_operations::PocketStrategy;
 use crate::model::*;
+use crate::tabs::TabSettings;
 
 /// Design file format version
 const FILE_FORMAT_VERSION: &str = "1.0";
@@ -74,6 +75,12 @@ pub struct ShapeData {
     pub operation_type: String,
     #[serde(default)]
     pub pocket_depth: f64,
+    /// Cut depth for the shape: `Some(None)` uses the job's depth, and `None`
+    /// (a file from before per-shape depths) takes the pocket depth if set
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub cut_depth: Option<Option<f64>>,
+    #[serde(default)]
+    pub tabs: Option<TabSettings>,
     #[serde(default)]
     pub start_depth: f64,
     #[serde(default)]
@@ -107,6 +114,12 @@ pub struct ShapeData {
     #[serde(default = "default_raster_fill_ratio")]
     pub raster_fill_ratio: f64,
     #[serde(default)]
+    pub feed_rate: Option<f64>,
+    #[serde(default)]
+    pub plunge_rate: Option<f64>,
+    #[serde(default)]
+    pub spindle_speed: Option<u32>,
+    #[serde(default)]
     pub sides: u32,
     #[serde(default)]
     pub teeth: usize,
@@ -134,6 +147,15 @@ pub struct ShapeData {
     pub lock_aspect_ratio: bool,
 }
 
+/// Deserializes a field that is present, even as `null`, to `Some`
+fn deserialize_present<'de, T, D>(deserializer: D) -> std::result::Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
 fn default_lock_aspect_ratio() -> bool {
     true
 }
@@ -161,6 +183,9 @@ pub struct ToolpathParameters {
     pub stock_thickness: f32,
     #[serde(default = "default_safe_z_height")]
     pub safe_z_height: f32,
+    /// Tabs for profiled shapes without tab settings of their own
+    #[serde(default)]
+    pub tabs: Option<TabSettings>,
 }
 
 fn default_feed_rate() -> f64 {
@@ -199,6 +224,7 @@ impl Default for ToolpathParameters {
             stock_height: default_stock_height(),
             stock_thickness: default_stock_thickness(),
             safe_z_height: default_safe_z_height(),
+            tabs: None,
         }
     }
 }
@@ -251,6 +277,12 @@ impl ToolpathParameters {
         self.safe_z_height = height;
         self
     }
+
+    /// Builder method to set the job's default tabs.
+    pub fn with_tabs(mut self, tabs: TabSettings) -> Self {
+        self.tabs = Some(tabs);
+        self
+    }
 }
 
 impl DesignFile {
@@ -389,6 +421,8 @@ impl DesignFile {
                 OperationType::Pocket => "pocket".to_string(),
             },
             pocket_depth: obj.pocket_depth,
+            cut_depth: Some(obj.cut_depth),
+            tabs: obj.tabs,
             start_depth: obj.start_depth,
             step_down: obj.step_down,
             step_in: obj.step_in,
@@ -405,6 +439,9 @@ impl DesignFile {
             ramp_angle: obj.ramp_angle,
             pocket_strategy: obj.pocket_strategy,
             raster_fill_ratio: obj.raster_fill_ratio,
+            feed_rate: obj.feed_rate,
+            plunge_rate: obj.plunge_rate,
+            spindle_speed: obj.spindle_speed,
             sides,
             teeth,
             module,
@@ -548,12 +585,19 @@ impl DesignFile {
             operation_type,
             use_custom_values: data.use_custom_values,
             pocket_depth: data.pocket_depth,
+            cut_depth: data
+                .cut_depth
+                .unwrap_or((data.pocket_depth != 0.0).then_some(data.pocket_depth)),
+            tabs: data.tabs,
             start_depth: data.start_depth,
             step_down: data.step_down,
             step_in: data.step_in,
             ramp_angle: data.ramp_angle,
             pocket_strategy: data.pocket_strategy,
             raster_fill_ratio: data.raster_fill_ratio,
+            feed_rate: data.feed_rate,
+            plunge_rate: data.plunge_rate,
+            spindle_speed: data.spindle_speed,
             offset: data.offset,
             fillet: data.fillet,
             chamfer: data.chamfer,
@@ -3,6 +3,13 @@
 //! Provides quadtree-based spatial partitioning for fast shape queries,
 //! culling, and collision detection in large designs.
 
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// Slack (mm) for item-bounds checks, so points on an edge survive rounding of
+/// the stored bounds (shape bounds are often computed in f32)
+const BOUNDS_EPSILON: f64 = 1e-3;
+
 /// Bounds of a region
 #[derive(Debug, Clone, Copy)]
 pub struct Bounds {
@@ -54,6 +61,23 @@ impl Bounds {
             || self.min_y > other.max_y)
     }
 
+    /// Distance from a point to the nearest point of the bounds (0 inside)
+    pub fn distance_to_point(&self, x: f64, y: f64) -> f64 {
+        let dx = (self.min_x - x).max(0.0).max(x - self.max_x);
+        let dy = (self.min_y - y).max(0.0).max(y - self.max_y);
+        dx.hypot(dy)
+    }
+
+    /// Bounds grown by `margin` on every side
+    pub fn expanded(&self, margin: f64) -> Bounds {
+        Bounds::new(
+            self.min_x - margin,
+            self.min_y - margin,
+            self.max_x + margin,
+            self.max_y + margin,
+        )
+    }
+
     /// Check if bounds fully contains another bounds
     pub fn contains_bounds(&self, other: &Bounds) -> bool {
         self.min_x <= other.min_x
@@ -117,6 +141,7 @@ fn insert_into_node(
     node: &mut QuadtreeNode,
     id: u64,
     bounds: &Bounds,
+    item_bounds: &HashMap<u64, Bounds>,
     max_depth: usize,
     max_items: usize,
 ) {
@@ -128,7 +153,7 @@ fn insert_into_node(
     // If we have children, try to insert into them
     if let Some(children) = node.children.as_mut() {
         for child in children.iter_mut() {
-            insert_into_node(child, id, bounds, max_depth, max_items);
+            insert_into_node(child, id, bounds, item_bounds, max_depth, max_items);
         }
         return;
     }
@@ -145,6 +170,7 @@ fn insert_into_node(
         // Redistribute items among children
         let items: Vec<u64> = node.items.drain(..).collect();
         for item_id in items {
+            let bounds = item_bounds.get(&item_id).unwrap_or(bounds);
             if let Some(children) = node.children.as_mut() {
                 // Insert into only the children that intersect with the item bounds
                 for child in children.iter_mut() {
@@ -204,12 +230,61 @@ fn query_point_node(node: &QuadtreeNode, x: f64, y: f64, results: &mut Vec<u64>)
     }
 }
 
+/// Candidate in a nearest-neighbor search: a node still to expand or an item
+struct NearestEntry<'a> {
+    distance: f64,
+    /// Centroid distance, used to order items at the same bounds distance
+    centroid_distance: f64,
+    id: u64,
+    node: Option<&'a QuadtreeNode>,
+}
+
+impl NearestEntry<'_> {
+    /// Ordering key; nodes sort before items at the same distance so every item
+    /// at that distance is found before any of them is reported
+    fn key(&self) -> (f64, bool, f64, u64) {
+        (
+            self.distance,
+            self.node.is_none(),
+            self.centroid_distance,
+            self.id,
+        )
+    }
+}
+
+impl PartialEq for NearestEntry<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for NearestEntry<'_> {}
+
+impl PartialOrd for NearestEntry<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for NearestEntry<'_> {
+    // Reversed so the max-heap pops the nearest entry first
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b) = (self.key(), other.key());
+        b.0.total_cmp(&a.0)
+            .then(b.1.cmp(&a.1))
+            .then(b.2.total_cmp(&a.2))
+            .then(b.3.cmp(&a.3))
+    }
+}
+
 /// Quadtree spatial index for efficient shape queries
 #[derive(Debug, Clone)]
 pub struct SpatialIndex {
     root: QuadtreeNode,
     max_depth: usize,
     max_items_per_node: usize,
+    /// Bounds of every indexed item, for distance queries
+    item_bounds: HashMap<u64, Bounds>,
 }
 
 impl SpatialIndex {
@@ -219,6 +294,7 @@ impl SpatialIndex {
             root: QuadtreeNode::new_leaf(bounds),
             max_depth,
             max_items_per_node,
+            item_bounds: HashMap::new(),
         }
     }
 
@@ -234,10 +310,12 @@ impl SpatialIndex {
             return;
         }
 
+        self.item_bounds.insert(id, *item_bounds);
         insert_into_node(
             &mut self.root,
             id,
             item_bounds,
+            &self.item_bounds,
             self.max_depth,
             self.max_items_per_node,
         );
@@ -246,6 +324,7 @@ impl SpatialIndex {
     /// Remove item from index
     pub fn remove(&mut self, id: u64, item_bounds: &Bounds) {
         remove_from_node(&mut self.root, id, item_bounds);
+        self.item_bounds.remove(&id);
     }
 
     /// Query items in given bounds
@@ -258,6 +337,11 @@ impl SpatialIndex {
         query_node(&self.root, query_bounds, &mut results);
         results.sort_unstable();
         results.dedup();
+        results.retain(|id| {
+            self.item_bounds
+                .get(id)
+                .is_some_and(|b| b.expanded(BOUNDS_EPSILON).intersects(query_bounds))
+        });
         results
     }
 
@@ -271,6 +355,72 @@ impl SpatialIndex {
         query_point_node(&self.root, x, y, &mut results);
         results.sort_unstable();
         results.dedup();
+        results.retain(|id| {
+            self.item_bounds
+                .get(id)
+                .is_some_and(|b| b.expanded(BOUNDS_EPSILON).contains_point(x, y))
+        });
+        results
+    }
+
+    /// The `k` items nearest to a point, nearest first
+    ///
+    /// Distance is measured from the point to each item's bounding box (0 when the
+    /// point is inside it). Items at the same distance are ordered by the distance
+    /// to their bounds' center, then by ID. If `k` exceeds the number of items, all
+    /// of them are returned. The search expands quadtree nodes nearest-first and
+    /// stops once `k` items are found, so distant parts of the tree are not visited.
+    pub fn k_nearest(&self, x: f64, y: f64, k: usize) -> Vec<(u64, f32)> {
+        let mut results = Vec::with_capacity(k.min(self.item_bounds.len()));
+        if k == 0 {
+            return results;
+        }
+
+        let mut seen = HashSet::new();
+        let mut heap = BinaryHeap::new();
+        heap.push(NearestEntry {
+            distance: self.root.bounds.distance_to_point(x, y),
+            centroid_distance: 0.0,
+            id: 0,
+            node: Some(&self.root),
+        });
+
+        while let Some(entry) = heap.pop() {
+            let Some(node) = entry.node else {
+                results.push((entry.id, entry.distance as f32));
+                if results.len() == k {
+                    break;
+                }
+                continue;
+            };
+
+            for &id in &node.items {
+                if !seen.insert(id) {
+                    continue;
+                }
+                let Some(bounds) = self.item_bounds.get(&id) else {
+                    continue;
+                };
+                let (cx, cy) = bounds.center();
+                heap.push(NearestEntry {
+                    distance: bounds.distance_to_point(x, y),
+                    centroid_distance: (cx - x).hypot(cy - y),
+                    id,
+                    node: None,
+                });
+            }
+            if let Some(children) = &node.children {
+                for child in children.iter() {
+                    heap.push(NearestEntry {
+                        distance: child.bounds.distance_to_point(x, y),
+                        centroid_distance: 0.0,
+                        id: 0,
+                        node: Some(child),
+                    });
+                }
+            }
+        }
+
         results
     }
 
@@ -278,6 +428,7 @@ impl SpatialIndex {
     pub fn clear(&mut self) {
         self.root.items.clear();
         self.root.children = None;
+        self.item_bounds.clear();
     }
 
     /// Get memory usage estimate in bytes
@@ -175,6 +175,24 @@ impl SpatialManager {
         self.index.query_point(x, y)
     }
 
+    /// Finds the shapes nearest to a point.
+    ///
+    /// Returns up to `k` shape IDs with their distance from the point to the
+    /// shape's bounding box, nearest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - X coordinate of the query point
+    /// * `y` - Y coordinate of the query point
+    /// * `k` - Maximum number of shapes to return
+    ///
+    /// # Returns
+    ///
+    /// `(id, distance)` pairs sorted by distance, with ties in a stable order.
+    pub fn k_nearest(&self, x: f64, y: f64, k: usize) -> Vec<(u64, f32)> {
+        self.index.k_nearest(x, y, k)
+    }
+
     /// Clears all shapes from the spatial index.
     ///
     /// After calling this, the index will be empty.
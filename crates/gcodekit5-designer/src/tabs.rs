@@ -0,0 +1,179 @@
+//! Holding tabs for profile cuts.
+//!
+//! A profile cut all the way through the stock frees the part, which can then
+//! shift or be thrown by the tool on the last pass. Tabs leave short bridges of
+//! material standing along the profile: [`apply_tabs`] lifts the tool over
+//! evenly spaced stretches of the path on every pass that goes below the top
+//! of the tabs, so the part stays attached until the tabs are cut by hand.
+//!
+//! The lifted stretch is the tab width plus the tool diameter, so the material
+//! left standing is the full tab width. Tabs are spread over the cutting length
+//! of each pass, so every pass of a profile lifts at the same places. Arcs that
+//! cross a tab are split into short linear moves; ramps are left alone.
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::Point;
+use crate::toolpath::{Toolpath, ToolpathSegment, ToolpathSegmentType};
+use crate::toolpath_trim::{segment_points, segment_z};
+
+/// Lengths and heights below this are treated as zero (mm)
+const EPSILON: f64 = 1e-6;
+
+/// Number and size of the tabs on a profile
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TabSettings {
+    /// Tabs per profile; 0 leaves no tabs
+    pub count: u32,
+    /// Length of each tab along the profile (mm)
+    pub width: f64,
+    /// Height of each tab above the bottom of the cut (mm)
+    pub height: f64,
+}
+
+impl TabSettings {
+    pub fn new(count: u32, width: f64, height: f64) -> Self {
+        Self {
+            count,
+            width,
+            height,
+        }
+    }
+
+    /// Whether these settings leave any tabs at all
+    pub fn is_enabled(&self) -> bool {
+        self.count > 0 && self.width > EPSILON && self.height > EPSILON
+    }
+}
+
+/// The passes of a profile with the tool lifted over the tabs
+///
+/// The tabs stand `tabs.height` above the deepest pass. Passes that stay
+/// above the tabs are returned as they are.
+pub fn apply_tabs(toolpaths: &[Toolpath], tabs: &TabSettings) -> Vec<Toolpath> {
+    if !tabs.is_enabled() {
+        return toolpaths.to_vec();
+    }
+    let Some(bottom) = toolpaths
+        .iter()
+        .map(|toolpath| toolpath.depth)
+        .min_by(|a, b| a.total_cmp(b))
+    else {
+        return Vec::new();
+    };
+    let tab_top = bottom + tabs.height;
+    toolpaths
+        .iter()
+        .map(|toolpath| lift_over_tabs(toolpath, tabs, tab_top))
+        .collect()
+}
+
+fn lift_over_tabs(toolpath: &Toolpath, tabs: &TabSettings, tab_top: f64) -> Toolpath {
+    let length: f64 = toolpath
+        .segments
+        .iter()
+        .filter(|s| s.segment_type != ToolpathSegmentType::RapidMove)
+        .map(|s| polyline_length(&segment_points(s)))
+        .sum();
+    let mut lifted = Toolpath {
+        segments: Vec::with_capacity(toolpath.segments.len()),
+        tool_diameter: toolpath.tool_diameter,
+        depth: toolpath.depth,
+    };
+    if length < EPSILON || toolpath.depth >= tab_top - EPSILON {
+        lifted.segments = toolpath.segments.clone();
+        return lifted;
+    }
+
+    // Stretches of the cutting length the tool is lifted over
+    let half = (tabs.width + toolpath.tool_diameter) / 2.0;
+    let spacing = length / tabs.count as f64;
+    let spans: Vec<(f64, f64)> = (0..tabs.count)
+        .map(|i| {
+            let center = (i as f64 + 0.5) * spacing;
+            ((center - half).max(0.0), (center + half).min(length))
+        })
+        .collect();
+
+    let mut travelled = 0.0;
+    let mut previous_end_z: Option<f64> = None;
+    for segment in &toolpath.segments {
+        if segment.segment_type == ToolpathSegmentType::RapidMove {
+            lifted.segments.push(segment.clone());
+            previous_end_z = None;
+            continue;
+        }
+        let (start_z, end_z) = segment_z(segment, toolpath.depth, previous_end_z);
+        previous_end_z = Some(end_z);
+        let points = segment_points(segment);
+        let segment_length = polyline_length(&points);
+        let from = travelled;
+        travelled += segment_length;
+
+        let level = (start_z - end_z).abs() < EPSILON;
+        let crosses = spans
+            .iter()
+            .any(|(a, b)| *a < travelled - EPSILON && *b > from + EPSILON);
+        if !level || start_z >= tab_top - EPSILON {
+            lifted.segments.push(segment.clone());
+            continue;
+        }
+        if !crosses {
+            // Explicit Z so the tool comes back down after a tab
+            lifted.segments.push(ToolpathSegment {
+                start_z: Some(start_z),
+                z_depth: Some(start_z),
+                ..segment.clone()
+            });
+            continue;
+        }
+
+        let mut distance = from;
+        for pair in points.windows(2) {
+            let piece_length = pair[0].distance_to(&pair[1]);
+            let piece_start = distance;
+            distance += piece_length;
+            if piece_length < EPSILON {
+                continue;
+            }
+            // Split the chord where it enters or leaves a tab
+            let mut cuts = vec![0.0, 1.0];
+            for (a, b) in &spans {
+                for edge in [a, b] {
+                    let t = (edge - piece_start) / piece_length;
+                    if t > EPSILON && t < 1.0 - EPSILON {
+                        cuts.push(t);
+                    }
+                }
+            }
+            cuts.sort_by(|a, b| a.total_cmp(b));
+            for window in cuts.windows(2) {
+                let (t0, t1) = (window[0], window[1]);
+                if t1 - t0 < EPSILON {
+                    continue;
+                }
+                let middle = piece_start + piece_length * (t0 + t1) / 2.0;
+                let over_tab = spans.iter().any(|(a, b)| middle > *a && middle < *b);
+                let z = if over_tab { tab_top } else { start_z };
+                lifted.segments.push(ToolpathSegment {
+                    segment_type: ToolpathSegmentType::LinearMove,
+                    start: lerp(pair[0], pair[1], t0),
+                    end: lerp(pair[0], pair[1], t1),
+                    center: None,
+                    start_z: Some(z),
+                    z_depth: Some(z),
+                    ..segment.clone()
+                });
+            }
+        }
+    }
+    lifted
+}
+
+fn polyline_length(points: &[Point]) -> f64 {
+    points.windows(2).map(|p| p[0].distance_to(&p[1])).sum()
+}
+
+fn lerp(a: Point, b: Point, t: f64) -> Point {
+    Point::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+}
@@ -2,8 +2,17 @@
 //!
 //! Provides definitions and management for cutting tools with their geometry,
 //! cutting parameters, and material-specific settings.
-
+//!
+//! Tuned cut recipes (feed, plunge, RPM, stepover and depth per pass) are stored per
+//! tool and material in a [`RecipeStore`], which can be exported to and imported from
+//! JSON. A missing recipe is estimated from the tool geometry and the material's chip
+//! load, and the result is marked as estimated.
+
+use crate::adaptive::{MaterialProperties, MaterialType};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
 
 /// Tool types for different operations.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -121,6 +130,52 @@ impl Tool {
     }
 }
 
+/// Stepover between the parallel passes of a finishing toolpath.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepoverSpec {
+    /// Distance between passes in mm
+    Distance(f64),
+    /// Height in mm of the ridge a ball-nose may leave between passes
+    ScallopHeight(f64),
+}
+
+impl StepoverSpec {
+    /// Stepover in mm for a ball-nose of `tool_diameter`, at most the diameter.
+    pub fn resolve(&self, tool_diameter: f64) -> f64 {
+        match *self {
+            StepoverSpec::Distance(stepover) => stepover.min(tool_diameter),
+            StepoverSpec::ScallopHeight(height) => {
+                stepover_for_scallop(tool_diameter / 2.0, height)
+            }
+        }
+    }
+}
+
+/// Stepover that leaves a scallop of `height` between the passes of a ball-nose
+/// of radius `radius`: 2 * sqrt(2Rh - h^2).
+///
+/// A scallop of the full radius or more allows passes a diameter apart; a height
+/// that is not positive gives no stepover.
+pub fn stepover_for_scallop(radius: f64, height: f64) -> f64 {
+    if !(radius > 0.0 && height > 0.0) {
+        return 0.0;
+    }
+    let height = height.min(radius);
+    2.0 * (2.0 * radius * height - height * height).sqrt()
+}
+
+/// Scallop left between the passes of a ball-nose of radius `radius` run
+/// `stepover` apart: R - sqrt(R^2 - (s/2)^2).
+///
+/// Passes a diameter or more apart leave the full radius.
+pub fn scallop_height(radius: f64, stepover: f64) -> f64 {
+    if radius.is_nan() || radius <= 0.0 {
+        return 0.0;
+    }
+    let half = (stepover.max(0.0) / 2.0).min(radius);
+    radius - (radius * radius - half * half).sqrt()
+}
+
 /// Material definitions with recommended tool parameters.
 #[derive(Debug, Clone)]
 pub struct MaterialProfile {
@@ -156,11 +211,236 @@ impl MaterialProfile {
     }
 }
 
+/// Cutting parameters for one tool in one material.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CutRecipe {
+    /// Feed rate in mm/min
+    pub feed_rate: f64,
+    /// Plunge rate in mm/min
+    pub plunge_rate: f64,
+    /// Spindle speed in RPM
+    pub spindle_speed: u32,
+    /// Stepover in mm
+    pub stepover: f64,
+    /// Maximum depth per pass in mm
+    pub depth_per_pass: f64,
+}
+
+/// Lowest spindle speed an estimate will suggest, in RPM.
+const ESTIMATE_MIN_RPM: f64 = 1000.0;
+/// Highest spindle speed an estimate will suggest, in RPM.
+const ESTIMATE_MAX_RPM: f64 = 24000.0;
+
+impl CutRecipe {
+    /// Creates a recipe from its parameters.
+    pub fn new(
+        feed_rate: f64,
+        plunge_rate: f64,
+        spindle_speed: u32,
+        stepover: f64,
+        depth_per_pass: f64,
+    ) -> Self {
+        Self {
+            feed_rate,
+            plunge_rate,
+            spindle_speed,
+            stepover,
+            depth_per_pass,
+        }
+    }
+
+    /// Estimates a recipe from the tool geometry and the material's chip load.
+    ///
+    /// RPM comes from a typical carbide surface speed for the material, clamped to
+    /// the range of a hobby spindle; feed is RPM x flutes x chip load, with the chip
+    /// load scaled to the tool diameter.
+    pub fn estimate(tool: &Tool, material: MaterialType) -> Self {
+        let properties = MaterialProperties::for_type(material);
+        let diameter = tool.diameter.max(0.1);
+
+        let surface_speed = match material {
+            MaterialType::Aluminum => 250.0,
+            MaterialType::Plastic => 300.0,
+            MaterialType::Wood => 500.0,
+            MaterialType::Brass => 200.0,
+            MaterialType::Steel => 100.0,
+            MaterialType::StainlessSteel => 60.0,
+        };
+        let rpm = (surface_speed * 1000.0 / (std::f64::consts::PI * diameter))
+            .clamp(ESTIMATE_MIN_RPM, ESTIMATE_MAX_RPM);
+
+        let chip_load = properties.chip_load * (diameter / 6.0).clamp(0.25, 2.0);
+        let feed_rate = (rpm * tool.flutes.max(1) as f64 * chip_load).round();
+
+        let (plunge_factor, stepover, depth_per_pass) = match tool.tool_type {
+            ToolType::Drill => (0.5, 0.0, diameter),
+            ToolType::BallNose => (0.3, diameter * 0.1, diameter * 0.25),
+            ToolType::VBit => (0.3, diameter * 0.2, diameter * 0.25),
+            ToolType::EndMill | ToolType::Slot => (0.3, diameter * 0.4, diameter * 0.5),
+        };
+
+        Self {
+            feed_rate,
+            plunge_rate: (feed_rate * plunge_factor).round(),
+            spindle_speed: rpm.round() as u32,
+            stepover,
+            depth_per_pass: depth_per_pass / properties.hardness_factor,
+        }
+    }
+}
+
+/// A recipe looked up for a tool and material.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResolvedRecipe {
+    pub recipe: CutRecipe,
+    /// The recipe was estimated because none is stored
+    pub estimated: bool,
+}
+
+/// One stored recipe, as written to JSON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecipeEntry {
+    pub tool_id: String,
+    pub material: MaterialType,
+    pub recipe: CutRecipe,
+}
+
+/// Cut recipes keyed by tool ID and material.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Vec<RecipeEntry>", into = "Vec<RecipeEntry>")]
+pub struct RecipeStore {
+    recipes: HashMap<(String, MaterialType), CutRecipe>,
+}
+
+impl RecipeStore {
+    /// Creates an empty recipe store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores a recipe, returning the one it replaced.
+    pub fn set(
+        &mut self,
+        tool_id: impl Into<String>,
+        material: MaterialType,
+        recipe: CutRecipe,
+    ) -> Option<CutRecipe> {
+        self.recipes.insert((tool_id.into(), material), recipe)
+    }
+
+    /// Gets a stored recipe.
+    pub fn get(&self, tool_id: &str, material: MaterialType) -> Option<&CutRecipe> {
+        self.recipes.get(&(tool_id.to_string(), material))
+    }
+
+    /// Gets a mutable reference to a stored recipe.
+    pub fn get_mut(&mut self, tool_id: &str, material: MaterialType) -> Option<&mut CutRecipe> {
+        self.recipes.get_mut(&(tool_id.to_string(), material))
+    }
+
+    /// Removes a stored recipe.
+    pub fn remove(&mut self, tool_id: &str, material: MaterialType) -> Option<CutRecipe> {
+        self.recipes.remove(&(tool_id.to_string(), material))
+    }
+
+    /// Returns the stored recipe for a tool, or an estimate marked as such.
+    pub fn resolve(&self, tool: &Tool, material: MaterialType) -> ResolvedRecipe {
+        match self.get(&tool.id, material) {
+            Some(recipe) => ResolvedRecipe {
+                recipe: *recipe,
+                estimated: false,
+            },
+            None => ResolvedRecipe {
+                recipe: CutRecipe::estimate(tool, material),
+                estimated: true,
+            },
+        }
+    }
+
+    /// Number of stored recipes.
+    pub fn len(&self) -> usize {
+        self.recipes.len()
+    }
+
+    /// Whether no recipes are stored.
+    pub fn is_empty(&self) -> bool {
+        self.recipes.is_empty()
+    }
+
+    /// Lists all recipes, sorted by tool ID and material.
+    pub fn entries(&self) -> Vec<RecipeEntry> {
+        let mut entries: Vec<RecipeEntry> = self
+            .recipes
+            .iter()
+            .map(|((tool_id, material), recipe)| RecipeEntry {
+                tool_id: tool_id.clone(),
+                material: *material,
+                recipe: *recipe,
+            })
+            .collect();
+        entries.sort_by(|a, b| (&a.tool_id, a.material).cmp(&(&b.tool_id, b.material)));
+        entries
+    }
+
+    /// Serializes the recipes to JSON for sharing.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Merges recipes from JSON, returning how many were read.
+    ///
+    /// With `overwrite` false, recipes already in the store are kept.
+    pub fn import_json(&mut self, json: &str, overwrite: bool) -> Result<usize> {
+        let entries: Vec<RecipeEntry> = serde_json::from_str(json)?;
+        let count = entries.len();
+        for entry in entries {
+            let key = (entry.tool_id, entry.material);
+            if overwrite || !self.recipes.contains_key(&key) {
+                self.recipes.insert(key, entry.recipe);
+            }
+        }
+        Ok(count)
+    }
+
+    /// Saves the recipes to a JSON file.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    /// Loads recipes from a JSON file, or an empty store if it does not exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+impl From<Vec<RecipeEntry>> for RecipeStore {
+    fn from(entries: Vec<RecipeEntry>) -> Self {
+        Self {
+            recipes: entries
+                .into_iter()
+                .map(|entry| ((entry.tool_id, entry.material), entry.recipe))
+                .collect(),
+        }
+    }
+}
+
+impl From<RecipeStore> for Vec<RecipeEntry> {
+    fn from(store: RecipeStore) -> Self {
+        store.entries()
+    }
+}
+
 /// Manages a library of tools and materials.
 #[derive(Debug, Clone)]
 pub struct ToolLibrary {
     tools: HashMap<String, Tool>,
     materials: HashMap<String, MaterialProfile>,
+    recipes: RecipeStore,
     default_tool: Option<String>,
 }
 
@@ -170,6 +450,7 @@ impl ToolLibrary {
         Self {
             tools: HashMap::new(),
             materials: HashMap::new(),
+            recipes: RecipeStore::new(),
             default_tool: None,
         }
     }
@@ -241,6 +522,24 @@ impl ToolLibrary {
         self.materials.values().collect()
     }
 
+    /// Gets the cut recipe store.
+    pub fn recipes(&self) -> &RecipeStore {
+        &self.recipes
+    }
+
+    /// Gets a mutable reference to the cut recipe store.
+    pub fn recipes_mut(&mut self) -> &mut RecipeStore {
+        &mut self.recipes
+    }
+
+    /// Looks up the recipe for a tool in a material, estimating it when none is
+    /// stored. Returns `None` if the tool is not in the library.
+    pub fn recipe_for(&self, tool_id: &str, material: MaterialType) -> Option<ResolvedRecipe> {
+        self.tools
+            .get(tool_id)
+            .map(|tool| self.recipes.resolve(tool, material))
+    }
+
     /// Creates a default tool library with common tools.
     pub fn with_defaults() -> Self {
         let mut library = Self::new();
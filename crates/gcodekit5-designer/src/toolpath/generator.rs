@@ -1,6 +1,7 @@
 //! Toolpath generator and related utilities.
 
 use super::*;
+use crate::geometry::point_in_polygon;
 
 use lyon::path::iterator::PathIterator;
 use rusttype::{GlyphId, OutlineBuilder, Scale};
@@ -18,6 +19,10 @@ pub struct ToolpathGenerator {
     pocket_strategy: PocketStrategy,
     ramp_angle: f64,
     raster_fill_ratio: f64,
+    rest_machining: Option<RestSource>,
+    segment_filter: Option<SegmentFilter>,
+    gap_bridging: Option<GapBridging>,
+    corner_smoothing: Option<CornerSmoothing>,
 }
 
 impl ToolpathGenerator {
@@ -33,6 +38,10 @@ impl ToolpathGenerator {
             pocket_strategy: PocketStrategy::ContourParallel,
             ramp_angle: 0.0,
             raster_fill_ratio: 0.5,
+            rest_machining: None,
+            segment_filter: None,
+            gap_bridging: None,
+            corner_smoothing: None,
         }
     }
 
@@ -46,6 +55,12 @@ impl ToolpathGenerator {
         self.ramp_angle = angle;
     }
 
+    /// Limits pockets to material left by a previous operation (`None` clears
+    /// whole pockets).
+    pub fn set_rest_machining(&mut self, rest: Option<RestSource>) {
+        self.rest_machining = rest;
+    }
+
     /// Sets the feed rate in mm/min.
     pub fn set_feed_rate(&mut self, feed_rate: f64) {
         debug_assert!(
@@ -60,6 +75,16 @@ impl ToolpathGenerator {
         self.spindle_speed = speed;
     }
 
+    /// Feed rate in mm/min used for generated segments.
+    pub fn feed_rate(&self) -> f64 {
+        self.feed_rate
+    }
+
+    /// Spindle speed in RPM used for generated segments.
+    pub fn spindle_speed(&self) -> u32 {
+        self.spindle_speed
+    }
+
     /// Sets the tool diameter in mm.
     pub fn set_tool_diameter(&mut self, diameter: f64) {
         debug_assert!(
@@ -87,6 +112,17 @@ impl ToolpathGenerator {
         self.step_in = if step_in > 0.0 { step_in } else { 0.1 };
     }
 
+    /// Sets the step in directly or from a target ball-nose scallop height,
+    /// at most the tool diameter; set the tool diameter first.
+    pub fn set_stepover_spec(&mut self, spec: StepoverSpec) {
+        self.set_step_in(spec.resolve(self.tool_diameter));
+    }
+
+    /// Scallop height a ball-nose of the tool diameter leaves at the step in.
+    pub fn scallop_height(&self) -> f64 {
+        scallop_height(self.tool_diameter / 2.0, self.step_in)
+    }
+
     /// Sets raster fill ratio (0.0-1.0, where 1.0 keeps full strokes).
     pub fn set_raster_fill_ratio(&mut self, ratio: f64) {
         self.raster_fill_ratio = ratio.clamp(0.0, 1.0);
@@ -96,6 +132,78 @@ impl ToolpathGenerator {
         self.raster_fill_ratio
     }
 
+    /// Merges cutting moves shorter than the filter's minimum length into their
+    /// neighbours (`None` keeps every move).
+    pub fn set_segment_filter(&mut self, filter: Option<SegmentFilter>) {
+        self.segment_filter = filter;
+    }
+
+    pub fn segment_filter(&self) -> Option<SegmentFilter> {
+        self.segment_filter
+    }
+
+    /// Applies the segment filter, if one is set, to generated toolpaths.
+    pub fn filter_segments(&self, toolpaths: &mut [Toolpath]) -> SegmentFilterReport {
+        let mut report = SegmentFilterReport::default();
+        let Some(filter) = self.segment_filter else {
+            return report;
+        };
+        for toolpath in toolpaths {
+            let (filtered, pass) = filter_short_segments(toolpath, &filter);
+            *toolpath = filtered;
+            report.add(pass);
+        }
+        report
+    }
+
+    /// Joins cutting moves whose ends are within the bridging's tolerance into
+    /// continuous runs (`None` keeps every gap).
+    pub fn set_gap_bridging(&mut self, bridging: Option<GapBridging>) {
+        self.gap_bridging = bridging;
+    }
+
+    pub fn gap_bridging(&self) -> Option<GapBridging> {
+        self.gap_bridging
+    }
+
+    /// Applies the gap bridging, if one is set, to generated toolpaths.
+    pub fn bridge_gaps(&self, toolpaths: &mut [Toolpath]) -> GapBridgingReport {
+        let mut report = GapBridgingReport::default();
+        let Some(bridging) = self.gap_bridging else {
+            return report;
+        };
+        for toolpath in toolpaths {
+            let (bridged, pass) = bridge_gaps(toolpath, &bridging);
+            *toolpath = bridged;
+            report.add(pass);
+        }
+        report
+    }
+
+    /// Rounds sharp corners between cutting moves with tangent arcs no further
+    /// than the smoothing's deviation from the corner (`None` keeps them sharp).
+    pub fn set_corner_smoothing(&mut self, smoothing: Option<CornerSmoothing>) {
+        self.corner_smoothing = smoothing;
+    }
+
+    pub fn corner_smoothing(&self) -> Option<CornerSmoothing> {
+        self.corner_smoothing
+    }
+
+    /// Applies the corner smoothing, if one is set, to generated toolpaths.
+    pub fn smooth_corners(&self, toolpaths: &mut [Toolpath]) -> CornerSmoothingReport {
+        let mut report = CornerSmoothingReport::default();
+        let Some(smoothing) = self.corner_smoothing else {
+            return report;
+        };
+        for toolpath in toolpaths {
+            let (smoothed, pass) = smooth_corners(toolpath, &smoothing);
+            *toolpath = smoothed;
+            report.add(pass);
+        }
+        report
+    }
+
     /// Creates an empty toolpath with current settings.
     pub fn empty_toolpath(&self) -> Toolpath {
         Toolpath::new(self.tool_diameter, self.start_depth - self.cut_depth.abs())
@@ -607,6 +715,41 @@ impl ToolpathGenerator {
         self.create_multipass_toolpaths(segments, step_down)
     }
 
+    /// Generates a trochoidal slot along a centerline.
+    ///
+    /// `params.tool_diameter` should match the generator's tool diameter. See
+    /// [`trochoidal_segments`] for when this fails.
+    pub fn generate_trochoidal_slot(
+        &self,
+        centerline: &[Point],
+        params: &TrochoidalParams,
+        step_down: f64,
+    ) -> ToolpathResult<Vec<Toolpath>> {
+        let loops = trochoidal_segments(centerline, params, self.feed_rate, self.spindle_speed)?;
+        let (Some(first), Some(last)) = (loops.first(), loops.last()) else {
+            return Err(ToolpathError::EmptyToolpath);
+        };
+        let (start, end) = (first.start, last.end);
+
+        let mut segments = Vec::with_capacity(loops.len() + 2);
+        segments.push(ToolpathSegment::new(
+            ToolpathSegmentType::RapidMove,
+            Point::new(0.0, 0.0),
+            start,
+            self.feed_rate,
+            self.spindle_speed,
+        ));
+        segments.extend(loops);
+        segments.push(ToolpathSegment::new(
+            ToolpathSegmentType::RapidMove,
+            end,
+            Point::new(0.0, 0.0),
+            self.feed_rate,
+            self.spindle_speed,
+        ));
+        Ok(self.create_multipass_toolpaths(segments, step_down))
+    }
+
     /// Generates a pocket toolpath for a rectangle.
     pub fn generate_rectangle_pocket(
         &self,
@@ -685,6 +828,8 @@ impl ToolpathGenerator {
         gen.operation.set_start_depth(self.start_depth);
         gen.operation.set_ramp_angle(self.ramp_angle);
         gen.operation.raster_fill_ratio = self.raster_fill_ratio;
+        gen.operation
+            .set_rest_machining(self.rest_machining.clone());
         let effective_step_in = if step_in > 0.0 { step_in } else { self.step_in };
         gen.operation
             .set_parameters(effective_step_in, self.feed_rate, self.spindle_speed);
@@ -708,6 +853,8 @@ impl ToolpathGenerator {
         gen.operation.set_start_depth(self.start_depth);
         gen.operation.set_ramp_angle(self.ramp_angle);
         gen.operation.raster_fill_ratio = self.raster_fill_ratio;
+        gen.operation
+            .set_rest_machining(self.rest_machining.clone());
         let effective_step_in = if step_in > 0.0 { step_in } else { self.step_in };
         gen.operation
             .set_parameters(effective_step_in, self.feed_rate, self.spindle_speed);
@@ -735,6 +882,8 @@ impl ToolpathGenerator {
             .set_parameters(effective_step_in, self.feed_rate, self.spindle_speed);
         gen.operation.set_strategy(self.pocket_strategy);
         gen.operation.raster_fill_ratio = self.raster_fill_ratio;
+        gen.operation
+            .set_rest_machining(self.rest_machining.clone());
         gen.generate_polygon_pocket(vertices, step_down)
     }
 
@@ -1180,25 +1329,6 @@ impl ToolpathGenerator {
             out
         }
 
-        fn point_in_polygon(p: Point, poly: &[Point]) -> bool {
-            if poly.len() < 3 {
-                return false;
-            }
-            let mut inside = false;
-            let mut j = poly.len() - 1;
-            for i in 0..poly.len() {
-                let pi = poly[i];
-                let pj = poly[j];
-                let intersect = ((pi.y > p.y) != (pj.y > p.y))
-                    && (p.x < (pj.x - pi.x) * (p.y - pi.y) / (pj.y - pi.y + 1e-12) + pi.x);
-                if intersect {
-                    inside = !inside;
-                }
-                j = i;
-            }
-            inside
-        }
-
         // Classify contours by nesting depth (even=solid, odd=hole)
         let mut holes: Vec<Vec<Point>> = Vec::new();
         let mut solids: Vec<Vec<Point>> = Vec::new();
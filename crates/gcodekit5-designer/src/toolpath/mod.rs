@@ -4,12 +4,19 @@ mod generator;
 mod segment;
 
 use super::pocket_operations::{PocketGenerator, PocketOperation, PocketStrategy};
+use crate::error::{ToolpathError, ToolpathResult};
 use crate::font_manager;
 use crate::model::{
     rotate_point, DesignCircle as Circle, DesignGear, DesignLine as Line, DesignPath as PathShape,
     DesignPolygon as Polygon, DesignRectangle as Rectangle, DesignSprocket,
     DesignText as TextShape, DesignTriangle as Triangle, DesignerShape, Point,
 };
+use crate::rest_machining::RestSource;
+use crate::tool_library::{scallop_height, StepoverSpec};
+use crate::toolpath_filter::{filter_short_segments, SegmentFilter, SegmentFilterReport};
+use crate::toolpath_gaps::{bridge_gaps, GapBridging, GapBridgingReport};
+use crate::toolpath_smoothing::{smooth_corners, CornerSmoothing, CornerSmoothingReport};
+use crate::trochoidal::{trochoidal_segments, TrochoidalParams};
 
 pub use generator::ToolpathGenerator;
 pub use segment::{ToolpathSegment, ToolpathSegmentType};
@@ -42,6 +49,78 @@ impl Toolpath {
         self.segments.push(segment);
     }
 
+    /// Sets the cutting feed, plunge feed and spindle speed of every segment.
+    ///
+    /// Operations call this to apply their own cutting parameters after the
+    /// geometry has been generated.
+    pub fn set_cutting_parameters(
+        &mut self,
+        feed_rate: f64,
+        plunge_rate: Option<f64>,
+        spindle_speed: u32,
+    ) {
+        for segment in &mut self.segments {
+            segment.feed_rate = feed_rate;
+            segment.plunge_rate = plunge_rate;
+            segment.spindle_speed = spindle_speed;
+        }
+    }
+
+    /// The same toolpath traced in the opposite direction.
+    ///
+    /// Segment order and each segment's direction are reversed, so a climb
+    /// contour becomes a conventional one and vice versa. The rapids that
+    /// approached and left the cut swap roles, so the path still enters from a
+    /// rapid and plunges before cutting. Each cutting segment's start and end Z
+    /// are swapped: a ramp entry becomes a ramp exit, and a segment that started
+    /// at the height a rapid left the tool at is cut level instead.
+    pub fn reversed(&self) -> Self {
+        // Z at each end of every cutting segment, as the G-code generator reads them
+        let mut previous_end: Option<f64> = None;
+        let mut z_ends = Vec::with_capacity(self.segments.len());
+        for segment in &self.segments {
+            if segment.segment_type == ToolpathSegmentType::RapidMove {
+                previous_end = None;
+                z_ends.push(None);
+                continue;
+            }
+            let start = match (segment.start_z, segment.z_depth) {
+                (Some(z), _) => Some(z),
+                (None, None) => Some(self.depth),
+                (None, Some(_)) => previous_end,
+            };
+            let end = segment.z_depth.unwrap_or(if segment.start_z.is_some() {
+                start.unwrap_or(self.depth)
+            } else {
+                self.depth
+            });
+            previous_end = Some(end);
+            z_ends.push(Some((start, end)));
+        }
+
+        let segments = self
+            .segments
+            .iter()
+            .zip(z_ends)
+            .rev()
+            .map(|(segment, z_ends)| {
+                let mut reversed = segment.reversed();
+                if let Some((start, end)) = z_ends {
+                    if segment.start_z.is_some() || segment.z_depth.is_some() {
+                        reversed.start_z = Some(end);
+                        reversed.z_depth = Some(start.unwrap_or(end));
+                    }
+                }
+                reversed
+            })
+            .collect();
+        Self {
+            segments,
+            tool_diameter: self.tool_diameter,
+            depth: self.depth,
+        }
+    }
+
     /// Gets the total length of the toolpath.
     pub fn total_length(&self) -> f64 {
         self.segments
@@ -24,6 +24,8 @@ pub struct ToolpathSegment {
     pub z_depth: Option<f64>,
     /// Start Z depth for this segment (if different from current Z)
     pub start_z: Option<f64>,
+    /// Feed rate for the plunge into this segment; `None` plunges at `feed_rate`
+    pub plunge_rate: Option<f64>,
 }
 
 impl ToolpathSegment {
@@ -44,6 +46,7 @@ impl ToolpathSegment {
             spindle_speed,
             z_depth: None,
             start_z: None,
+            plunge_rate: None,
         }
     }
 
@@ -65,6 +68,7 @@ impl ToolpathSegment {
             spindle_speed,
             z_depth: None,
             start_z: None,
+            plunge_rate: None,
         }
     }
 
@@ -73,4 +77,34 @@ impl ToolpathSegment {
         self.z_depth = Some(z);
         self
     }
+
+    /// Set the plunge feed rate for this segment
+    pub fn with_plunge_rate(mut self, plunge_rate: f64) -> Self {
+        self.plunge_rate = Some(plunge_rate);
+        self
+    }
+
+    /// Feed rate used when plunging into this segment
+    pub fn plunge_feed_rate(&self) -> f64 {
+        self.plunge_rate.unwrap_or(self.feed_rate)
+    }
+
+    /// The same move travelled from `end` to `start`.
+    ///
+    /// Arcs keep their center and swap between clockwise and counter-clockwise.
+    /// Z fields are left as they are; [`Toolpath::reversed`] resolves them for
+    /// the whole path.
+    pub fn reversed(&self) -> Self {
+        let segment_type = match self.segment_type {
+            ToolpathSegmentType::ArcCW => ToolpathSegmentType::ArcCCW,
+            ToolpathSegmentType::ArcCCW => ToolpathSegmentType::ArcCW,
+            other => other,
+        };
+        Self {
+            segment_type,
+            start: self.end,
+            end: self.start,
+            ..self.clone()
+        }
+    }
 }
@@ -0,0 +1,215 @@
+//! Filtering of very short toolpath segments.
+//!
+//! Moves much shorter than a machine can usefully execute make it stutter: the
+//! planner slows for every tiny step and the cut vibrates. [`filter_short_segments`]
+//! merges each cutting move shorter than a minimum length into its
+//! neighbours, as long as every removed vertex stays within a tolerance of the
+//! merged move, so the path is never bent further than that. Short arcs whose
+//! chord stays within the tolerance are replaced by that chord first, so they
+//! can be merged the same way.
+//!
+//! The first and last point of every run of cutting moves are kept, so a
+//! closed loop still closes and the rapids between runs still meet the cut.
+//! A move is never merged into one that would join a point to itself, so a
+//! small loop is never collapsed. Ramps are left alone: only level moves at
+//! the same height, feed and speed are merged.
+
+use crate::geometry::distance_to_segment;
+use crate::model::Point;
+use crate::toolpath::{Toolpath, ToolpathSegment, ToolpathSegmentType};
+use crate::toolpath_trim::segment_z;
+
+/// Points closer than this are treated as the same (mm)
+const EPSILON: f64 = 1e-6;
+
+/// Minimum useful move length and the deviation allowed to reach it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SegmentFilter {
+    /// Cutting moves shorter than this are merged into their neighbours (mm)
+    pub min_length: f64,
+    /// Furthest a removed vertex may end up from the merged move (mm)
+    pub tolerance: f64,
+}
+
+impl SegmentFilter {
+    pub fn new(min_length: f64, tolerance: f64) -> Self {
+        Self {
+            min_length,
+            tolerance,
+        }
+    }
+}
+
+/// How many segments a filter pass removed or replaced
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SegmentFilterReport {
+    /// Segments merged into a neighbour
+    pub merged: usize,
+    /// Short arcs replaced by a linear move
+    pub arcs_replaced: usize,
+}
+
+impl SegmentFilterReport {
+    /// Segments filtered in any way
+    pub fn total(&self) -> usize {
+        self.merged + self.arcs_replaced
+    }
+
+    /// Add the counts of another pass
+    pub fn add(&mut self, other: SegmentFilterReport) {
+        self.merged += other.merged;
+        self.arcs_replaced += other.arcs_replaced;
+    }
+}
+
+/// The toolpath with cutting moves shorter than `filter.min_length` merged
+/// into their neighbours where that keeps within `filter.tolerance`.
+///
+/// Rapids are kept as they are. A short move that cannot be merged without
+/// leaving the tolerance is kept too.
+pub fn filter_short_segments(
+    toolpath: &Toolpath,
+    filter: &SegmentFilter,
+) -> (Toolpath, SegmentFilterReport) {
+    let mut report = SegmentFilterReport::default();
+    let mut filtered = Toolpath {
+        segments: Vec::with_capacity(toolpath.segments.len()),
+        tool_diameter: toolpath.tool_diameter,
+        depth: toolpath.depth,
+    };
+    if filter.min_length <= 0.0 || !filter.min_length.is_finite() {
+        filtered.segments = toolpath.segments.clone();
+        return (filtered, report);
+    }
+    let tolerance = filter.tolerance.max(0.0);
+
+    // The move being built, its resolved Z at each end and the vertices merged
+    // out of it
+    let mut pending: Option<(ToolpathSegment, (f64, f64), Vec<Point>)> = None;
+    let mut previous_end_z: Option<f64> = None;
+
+    for segment in &toolpath.segments {
+        if segment.segment_type == ToolpathSegmentType::RapidMove {
+            if let Some((kept, _, _)) = pending.take() {
+                filtered.segments.push(kept);
+            }
+            filtered.segments.push(segment.clone());
+            previous_end_z = None;
+            continue;
+        }
+        let z = segment_z(segment, toolpath.depth, previous_end_z);
+        previous_end_z = Some(z.1);
+
+        let mut segment = segment.clone();
+        if let Some(chord) = short_arc_chord(&segment, filter.min_length, tolerance) {
+            segment = chord;
+            report.arcs_replaced += 1;
+        }
+
+        match pending.as_mut() {
+            Some((kept, kept_z, removed))
+                if can_merge(
+                    kept,
+                    *kept_z,
+                    removed,
+                    &segment,
+                    z,
+                    filter.min_length,
+                    tolerance,
+                ) =>
+            {
+                removed.push(kept.end);
+                kept.end = segment.end;
+                report.merged += 1;
+            }
+            _ => {
+                if let Some((kept, _, _)) = pending.take() {
+                    filtered.segments.push(kept);
+                }
+                pending = Some((segment, z, Vec::new()));
+            }
+        }
+    }
+    if let Some((kept, _, _)) = pending {
+        filtered.segments.push(kept);
+    }
+    (filtered, report)
+}
+
+/// Whether `next` can be merged into the move `kept`
+fn can_merge(
+    kept: &ToolpathSegment,
+    kept_z: (f64, f64),
+    removed: &[Point],
+    next: &ToolpathSegment,
+    next_z: (f64, f64),
+    min_length: f64,
+    tolerance: f64,
+) -> bool {
+    let linear = |s: &ToolpathSegment| s.segment_type == ToolpathSegmentType::LinearMove;
+    let level = |(start, end): (f64, f64)| (start - end).abs() < EPSILON;
+    if !linear(kept)
+        || !linear(next)
+        || !level(kept_z)
+        || !level(next_z)
+        || (kept_z.1 - next_z.0).abs() > EPSILON
+        || kept.feed_rate != next.feed_rate
+        || kept.spindle_speed != next.spindle_speed
+        || kept.end.distance_to(&next.start) > EPSILON
+    {
+        return false;
+    }
+    // Only moves that are themselves short are worth merging; the merge must
+    // not close a loop into a single point
+    let short = kept.start.distance_to(&kept.end) < min_length
+        || next.start.distance_to(&next.end) < min_length;
+    if !short || kept.start.distance_to(&next.end) < EPSILON {
+        return false;
+    }
+    removed
+        .iter()
+        .chain(std::iter::once(&kept.end))
+        .all(|p| distance_to_segment(*p, kept.start, next.end) <= tolerance)
+}
+
+/// A linear move replacing `arc`, if the arc is shorter than `min_length` and
+/// its chord stays within `tolerance` of it
+fn short_arc_chord(
+    arc: &ToolpathSegment,
+    min_length: f64,
+    tolerance: f64,
+) -> Option<ToolpathSegment> {
+    let clockwise = match arc.segment_type {
+        ToolpathSegmentType::ArcCW => true,
+        ToolpathSegmentType::ArcCCW => false,
+        _ => return None,
+    };
+    let center = arc.center?;
+    // A full circle has no chord to replace it with
+    if arc.start.distance_to(&arc.end) < EPSILON {
+        return None;
+    }
+
+    let radius = center.distance_to(&arc.start);
+    let start_angle = (arc.start.y - center.y).atan2(arc.start.x - center.x);
+    let end_angle = (arc.end.y - center.y).atan2(arc.end.x - center.x);
+    let mut sweep = end_angle - start_angle;
+    if clockwise {
+        if sweep > 0.0 {
+            sweep -= std::f64::consts::TAU;
+        }
+    } else if sweep < 0.0 {
+        sweep += std::f64::consts::TAU;
+    }
+    let sweep = sweep.abs();
+
+    let sagitta = radius * (1.0 - (sweep / 2.0).cos());
+    if radius * sweep >= min_length || sagitta > tolerance {
+        return None;
+    }
+    Some(ToolpathSegment {
+        segment_type: ToolpathSegmentType::LinearMove,
+        center: None,
+        ..arc.clone()
+    })
+}
@@ -0,0 +1,124 @@
+//! Bridging of small gaps between cutting moves.
+//!
+//! Offsetting and boolean operations can leave a path that should be one
+//! continuous cut split into pieces whose ends nearly meet. Every rapid between
+//! them retracts and plunges the tool again, which marks the surface and costs
+//! time. [`bridge_gaps`] joins consecutive cutting moves whose ends are within a
+//! tolerance into one run: the rapids between them are dropped and, where the
+//! ends do not quite meet, a short linear move at the cutting height connects
+//! them.
+//!
+//! Moves are only joined at the same height, feed rate and spindle speed, so a
+//! step down between passes or a change of cutting parameters still retracts.
+//! Each toolpath is one operation and is bridged on its own; nothing is joined
+//! across toolpaths.
+
+use crate::toolpath::{Toolpath, ToolpathSegment, ToolpathSegmentType};
+use crate::toolpath_trim::segment_z;
+
+/// Points closer than this are treated as the same (mm)
+const EPSILON: f64 = 1e-6;
+
+/// Largest gap between cutting moves that is bridged
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GapBridging {
+    /// Ends further apart than this are left apart (mm)
+    pub tolerance: f64,
+}
+
+impl GapBridging {
+    pub fn new(tolerance: f64) -> Self {
+        Self { tolerance }
+    }
+}
+
+/// How many gaps a bridging pass closed
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GapBridgingReport {
+    /// Gaps joined into a continuous run
+    pub joined: usize,
+    /// Retracts saved by dropping the rapids across joined gaps
+    pub retracts_removed: usize,
+    /// Widest gap joined (mm)
+    pub max_gap: f64,
+}
+
+impl GapBridgingReport {
+    /// Add the counts of another pass
+    pub fn add(&mut self, other: GapBridgingReport) {
+        self.joined += other.joined;
+        self.retracts_removed += other.retracts_removed;
+        self.max_gap = self.max_gap.max(other.max_gap);
+    }
+}
+
+/// The toolpath with cutting moves whose ends are within `bridging.tolerance`
+/// joined into continuous runs.
+///
+/// Gaps wider than the tolerance, or between moves at different heights,
+/// feeds or spindle speeds, are kept as they are, along with their rapids.
+pub fn bridge_gaps(toolpath: &Toolpath, bridging: &GapBridging) -> (Toolpath, GapBridgingReport) {
+    let mut report = GapBridgingReport::default();
+    let mut bridged = Toolpath {
+        segments: Vec::with_capacity(toolpath.segments.len()),
+        tool_diameter: toolpath.tool_diameter,
+        depth: toolpath.depth,
+    };
+    let tolerance = bridging.tolerance;
+    if tolerance <= 0.0 || !tolerance.is_finite() {
+        bridged.segments = toolpath.segments.clone();
+        return (bridged, report);
+    }
+
+    // Rapids since the last cutting move, held back until it is known whether
+    // the next cut joins on
+    let mut rapids: Vec<ToolpathSegment> = Vec::new();
+    // Resolved Z at the end of the last cutting move kept
+    let mut last_cut_z: Option<f64> = None;
+    let mut previous_end_z: Option<f64> = None;
+
+    for segment in &toolpath.segments {
+        if segment.segment_type == ToolpathSegmentType::RapidMove {
+            rapids.push(segment.clone());
+            previous_end_z = None;
+            continue;
+        }
+        let (start_z, end_z) = segment_z(segment, toolpath.depth, previous_end_z);
+        previous_end_z = Some(end_z);
+
+        let previous = bridged
+            .segments
+            .last()
+            .filter(|last| last.segment_type != ToolpathSegmentType::RapidMove);
+        if let (Some(previous), Some(cut_z)) = (previous, last_cut_z) {
+            let gap = previous.end.distance_to(&segment.start);
+            let broken = !rapids.is_empty() || gap > EPSILON;
+            let joinable = gap <= tolerance
+                && (cut_z - start_z).abs() < EPSILON
+                && previous.feed_rate == segment.feed_rate
+                && previous.spindle_speed == segment.spindle_speed;
+            if broken && joinable {
+                let connector = (gap > EPSILON).then(|| {
+                    ToolpathSegment::new(
+                        ToolpathSegmentType::LinearMove,
+                        previous.end,
+                        segment.start,
+                        previous.feed_rate,
+                        previous.spindle_speed,
+                    )
+                    .with_z_depth(cut_z)
+                });
+                report.joined += 1;
+                report.retracts_removed += usize::from(!rapids.is_empty());
+                report.max_gap = report.max_gap.max(gap);
+                rapids.clear();
+                bridged.segments.extend(connector);
+            }
+        }
+        bridged.segments.append(&mut rapids);
+        bridged.segments.push(segment.clone());
+        last_cut_z = Some(end_z);
+    }
+    bridged.segments.append(&mut rapids);
+    (bridged, report)
+}
@@ -4,6 +4,7 @@
 //! estimating machining time, and detecting potential collisions.
 
 use super::toolpath::{Toolpath, ToolpathSegmentType};
+use crate::keep_out::{KeepOutChecker, KeepOutCollision};
 use crate::model::Point;
 
 /// Simulation state of a toolpath.
@@ -235,6 +236,15 @@ impl ToolpathAnalyzer {
             .sum()
     }
 
+    /// Finds the segments that hit a keep-out region, with rapids at `clearance_z`.
+    pub fn keep_out_collisions(
+        &self,
+        checker: &KeepOutChecker,
+        clearance_z: f64,
+    ) -> Vec<KeepOutCollision> {
+        checker.check(&self.toolpath, clearance_z)
+    }
+
     /// Counts segments by type.
     pub fn count_segments_by_type(&self) -> (u32, u32, u32) {
         let mut rapid = 0;
@@ -0,0 +1,247 @@
+//! Rounding of sharp toolpath corners.
+//!
+//! A machine has to slow down, often nearly to a stop, to follow a sharp
+//! corner between two linear moves. [`smooth_corners`] replaces such corners
+//! with a tangent arc (a fillet), sized so the arc passes no further than a
+//! maximum deviation from the original corner point. The arc joins both moves
+//! tangentially, so the machine carries its speed through the corner.
+//!
+//! Corners that turn by less than a minimum angle are already gentle and are
+//! left alone, as are reversals. A corner next to a move shorter than the
+//! feature size is taken as an intentional small feature, such as a notch or
+//! a sharp inside detail, and is kept sharp. A fillet never takes more than
+//! half of either move, so neighbouring fillets do not overlap and the overall
+//! shape stays; where the half would be exceeded the fillet is made smaller,
+//! which only lowers its deviation.
+//!
+//! Only level linear moves at the same height, feed and speed are rounded. The
+//! first and last point of every run of cutting moves are kept, so the corner
+//! where a closed loop starts stays sharp and the rapids still meet the cut.
+
+use crate::model::Point;
+use crate::toolpath::{Toolpath, ToolpathSegment, ToolpathSegmentType};
+use crate::toolpath_trim::segment_z;
+
+/// Lengths and heights below this are treated as zero (mm)
+const EPSILON: f64 = 1e-6;
+/// Corners turning more than this are reversals and are not rounded (degrees)
+const MAX_TURN_DEGREES: f64 = 179.0;
+
+/// How far corners may be rounded, and which are left sharp
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CornerSmoothing {
+    /// Furthest the fillet may pass from the original corner point (mm)
+    pub max_deviation: f64,
+    /// Corners turning by less than this are left alone (degrees)
+    pub min_turn_degrees: f64,
+    /// Corners next to a move shorter than this are kept sharp (mm)
+    pub min_feature_length: f64,
+}
+
+impl CornerSmoothing {
+    /// Round corners within `max_deviation`, leaving corners under 10 degrees
+    /// and those next to moves under 0.5mm
+    pub fn new(max_deviation: f64) -> Self {
+        Self {
+            max_deviation,
+            min_turn_degrees: 10.0,
+            min_feature_length: 0.5,
+        }
+    }
+}
+
+/// How many corners a smoothing pass rounded or left sharp
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CornerSmoothingReport {
+    /// Corners replaced by a fillet
+    pub rounded: usize,
+    /// Corners kept sharp as small features
+    pub kept_features: usize,
+}
+
+impl CornerSmoothingReport {
+    /// Add the counts of another pass
+    pub fn add(&mut self, other: CornerSmoothingReport) {
+        self.rounded += other.rounded;
+        self.kept_features += other.kept_features;
+    }
+}
+
+/// A corner to round: where the fillet leaves the incoming move and joins the
+/// outgoing one, and its arc
+struct Fillet {
+    /// Distance from the corner to each tangent point (mm)
+    setback: f64,
+    arc: ToolpathSegment,
+}
+
+/// The toolpath with its sharp corners rounded within `smoothing.max_deviation`
+///
+/// A deviation that is not positive leaves the toolpath unchanged.
+pub fn smooth_corners(
+    toolpath: &Toolpath,
+    smoothing: &CornerSmoothing,
+) -> (Toolpath, CornerSmoothingReport) {
+    let mut report = CornerSmoothingReport::default();
+    let mut smoothed = Toolpath {
+        segments: Vec::with_capacity(toolpath.segments.len()),
+        tool_diameter: toolpath.tool_diameter,
+        depth: toolpath.depth,
+    };
+    if !(smoothing.max_deviation > 0.0 && smoothing.max_deviation.is_finite()) {
+        smoothed.segments = toolpath.segments.clone();
+        return (smoothed, report);
+    }
+
+    let segments = &toolpath.segments;
+    let mut heights = Vec::with_capacity(segments.len());
+    let mut previous_end_z: Option<f64> = None;
+    for segment in segments {
+        if segment.segment_type == ToolpathSegmentType::RapidMove {
+            previous_end_z = None;
+            heights.push(None);
+            continue;
+        }
+        let z = segment_z(segment, toolpath.depth, previous_end_z);
+        previous_end_z = Some(z.1);
+        heights.push(Some(z));
+    }
+
+    // The fillet at the end of each segment, if its corner is rounded
+    let mut fillets: Vec<Option<Fillet>> = Vec::with_capacity(segments.len());
+    for i in 0..segments.len() {
+        let fillet = match (segments.get(i + 1), heights[i], heights.get(i + 1)) {
+            (Some(next), Some(z), Some(Some(next_z))) => {
+                corner_fillet(&segments[i], z, next, *next_z, smoothing, &mut report)
+            }
+            _ => None,
+        };
+        fillets.push(fillet);
+    }
+
+    let mut before = 0.0;
+    for (segment, fillet) in segments.iter().zip(fillets) {
+        let after = fillet.as_ref().map_or(0.0, |f| f.setback);
+        if before > 0.0 || after > 0.0 {
+            let length = segment.start.distance_to(&segment.end);
+            let (dx, dy) = (
+                (segment.end.x - segment.start.x) / length,
+                (segment.end.y - segment.start.y) / length,
+            );
+            let start = Point::new(segment.start.x + dx * before, segment.start.y + dy * before);
+            let end = Point::new(segment.end.x - dx * after, segment.end.y - dy * after);
+            if start.distance_to(&end) > EPSILON {
+                smoothed.segments.push(ToolpathSegment {
+                    start,
+                    end,
+                    ..segment.clone()
+                });
+            }
+        } else {
+            smoothed.segments.push(segment.clone());
+        }
+        if let Some(fillet) = fillet {
+            smoothed.segments.push(fillet.arc);
+        }
+        before = after;
+    }
+    (smoothed, report)
+}
+
+/// The fillet for the corner where `segment` meets `next`, if it is rounded
+fn corner_fillet(
+    segment: &ToolpathSegment,
+    z: (f64, f64),
+    next: &ToolpathSegment,
+    next_z: (f64, f64),
+    smoothing: &CornerSmoothing,
+    report: &mut CornerSmoothingReport,
+) -> Option<Fillet> {
+    let linear = |s: &ToolpathSegment| s.segment_type == ToolpathSegmentType::LinearMove;
+    let level = |(start, end): (f64, f64)| (start - end).abs() < EPSILON;
+    if !linear(segment)
+        || !linear(next)
+        || !level(z)
+        || !level(next_z)
+        || (z.1 - next_z.0).abs() > EPSILON
+        || segment.feed_rate != next.feed_rate
+        || segment.spindle_speed != next.spindle_speed
+        || segment.end.distance_to(&next.start) > EPSILON
+    {
+        return None;
+    }
+    let (length_in, length_out) = (
+        segment.start.distance_to(&segment.end),
+        next.start.distance_to(&next.end),
+    );
+    if length_in < EPSILON || length_out < EPSILON {
+        return None;
+    }
+    let incoming = (
+        (segment.end.x - segment.start.x) / length_in,
+        (segment.end.y - segment.start.y) / length_in,
+    );
+    let outgoing = (
+        (next.end.x - next.start.x) / length_out,
+        (next.end.y - next.start.y) / length_out,
+    );
+    let cross = incoming.0 * outgoing.1 - incoming.1 * outgoing.0;
+    let dot = incoming.0 * outgoing.0 + incoming.1 * outgoing.1;
+    let turn = cross.abs().atan2(dot);
+    if turn < smoothing.min_turn_degrees.max(0.0).to_radians()
+        || turn > MAX_TURN_DEGREES.to_radians()
+    {
+        return None;
+    }
+    if length_in < smoothing.min_feature_length || length_out < smoothing.min_feature_length {
+        report.kept_features += 1;
+        return None;
+    }
+
+    // A fillet of radius r deviates from the corner by r (1 / cos(turn / 2) - 1)
+    // and leaves each move r tan(turn / 2) before the corner
+    let half = turn / 2.0;
+    let radius = smoothing.max_deviation * half.cos() / (1.0 - half.cos());
+    let setback = (radius * half.tan())
+        .min(length_in / 2.0)
+        .min(length_out / 2.0);
+    let radius = setback / half.tan();
+    if setback < EPSILON {
+        return None;
+    }
+
+    let corner = segment.end;
+    let enter = Point::new(
+        corner.x - incoming.0 * setback,
+        corner.y - incoming.1 * setback,
+    );
+    let leave = Point::new(
+        corner.x + outgoing.0 * setback,
+        corner.y + outgoing.1 * setback,
+    );
+    // The centre is to the left of the incoming move on a left turn
+    let side = if cross > 0.0 { 1.0 } else { -1.0 };
+    let center = Point::new(
+        enter.x - incoming.1 * radius * side,
+        enter.y + incoming.0 * radius * side,
+    );
+    let explicit_z = segment.z_depth.or(segment.start_z).map(|_| z.1);
+    report.rounded += 1;
+    Some(Fillet {
+        setback,
+        arc: ToolpathSegment {
+            segment_type: if cross > 0.0 {
+                ToolpathSegmentType::ArcCCW
+            } else {
+                ToolpathSegmentType::ArcCW
+            },
+            start: enter,
+            end: leave,
+            center: Some(center),
+            start_z: explicit_z,
+            z_depth: explicit_z,
+            plunge_rate: None,
+            ..segment.clone()
+        },
+    })
+}
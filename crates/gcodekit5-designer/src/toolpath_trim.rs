@@ -0,0 +1,243 @@
+//! Trimming toolpaths to a boundary.
+//!
+//! [`trim_to_boundary`] keeps the parts of a toolpath's cutting moves that lie
+//! inside a boundary polygon, such as the stock rectangle, and drops the rest.
+//! Moves that cross the boundary are split where they cross it. Where the path
+//! leaves the boundary and comes back in, a rapid is inserted between the exit
+//! and the re-entry, so the tool retracts to the clearance height and travels
+//! above the stock, with no feed move (and so no burn in laser mode) outside
+//! the boundary.
+//!
+//! Kept pieces keep their segment's feed rates and spindle speed. Arcs that stay
+//! inside are kept whole; arcs that cross the boundary are split into short
+//! linear moves and clipped as those.
+
+use crate::geometry::point_in_polygon;
+use crate::model::Point;
+use crate::toolpath::{Toolpath, ToolpathSegment, ToolpathSegmentType};
+
+/// Points closer than this are treated as the same (mm)
+const EPSILON: f64 = 1e-6;
+/// Largest distance between an arc and the chords it is clipped as (mm)
+const ARC_TOLERANCE: f64 = 0.01;
+
+/// The toolpath with everything outside `boundary` cut away.
+///
+/// `boundary` is a closed polygon; its last vertex joins the first. A boundary
+/// with fewer than three vertices encloses nothing, so the result is empty.
+/// Original rapids are replaced by the rapids the trimmed path needs. Each
+/// piece entered after a rapid plunges to the height its segment had at that
+/// point, so ramps that are cut into keep their slope.
+pub fn trim_to_boundary(toolpath: &Toolpath, boundary: &[Point]) -> Toolpath {
+    let mut trimmed = Toolpath {
+        segments: Vec::new(),
+        tool_diameter: toolpath.tool_diameter,
+        depth: toolpath.depth,
+    };
+    if boundary.len() < 3 {
+        return trimmed;
+    }
+
+    // Where the tool is cutting after the last kept piece; None once it has
+    // left the boundary or the original path has rapided away
+    let mut down_at: Option<Point> = None;
+    let mut last_xy = toolpath
+        .segments
+        .first()
+        .map_or(Point::new(0.0, 0.0), |segment| segment.start);
+    let mut previous_end_z: Option<f64> = None;
+
+    for segment in &toolpath.segments {
+        if segment.segment_type == ToolpathSegmentType::RapidMove {
+            down_at = None;
+            previous_end_z = None;
+            continue;
+        }
+        let (start_z, end_z) = segment_z(segment, toolpath.depth, previous_end_z);
+        previous_end_z = Some(end_z);
+
+        let points = segment_points(segment);
+        let mut lengths = Vec::with_capacity(points.len());
+        let mut total = 0.0;
+        for pair in points.windows(2) {
+            lengths.push(total);
+            total += pair[0].distance_to(&pair[1]);
+        }
+        let z_at = |distance: f64| {
+            if total > EPSILON {
+                start_z + (end_z - start_z) * distance / total
+            } else {
+                end_z
+            }
+        };
+
+        // Inside pieces as (start, end, distance along the segment at each end)
+        let mut pieces = Vec::new();
+        let mut whole = true;
+        for (i, pair) in points.windows(2).enumerate() {
+            let length = pair[0].distance_to(&pair[1]);
+            let intervals = inside_intervals(pair[0], pair[1], boundary);
+            whole &= matches!(intervals.as_slice(), [(t0, t1)] if *t0 == 0.0 && *t1 == 1.0);
+            for (t0, t1) in intervals {
+                pieces.push((
+                    lerp(pair[0], pair[1], t0),
+                    lerp(pair[0], pair[1], t1),
+                    lengths[i] + t0 * length,
+                    lengths[i] + t1 * length,
+                ));
+            }
+        }
+
+        if whole && down_at.is_some_and(|p| p.distance_to(&segment.start) < EPSILON) {
+            // Inside from end to end and continuing the cut: keep it as it is
+            trimmed.segments.push(segment.clone());
+        } else if whole {
+            push_rapid(&mut trimmed, segment, last_xy, segment.start);
+            let mut kept = segment.clone();
+            kept.start_z = Some(start_z);
+            kept.z_depth = Some(end_z);
+            trimmed.segments.push(kept);
+        } else {
+            for (start, end, from, to) in pieces {
+                if !down_at.is_some_and(|p| p.distance_to(&start) < EPSILON) {
+                    push_rapid(&mut trimmed, segment, last_xy, start);
+                }
+                let mut kept = ToolpathSegment {
+                    segment_type: ToolpathSegmentType::LinearMove,
+                    start,
+                    end,
+                    center: None,
+                    ..segment.clone()
+                };
+                kept.start_z = Some(z_at(from));
+                kept.z_depth = Some(z_at(to));
+                trimmed.segments.push(kept);
+                last_xy = end;
+                down_at = Some(end);
+            }
+            // Whether the cut carries on into the next segment
+            if !points
+                .last()
+                .is_some_and(|p| down_at.is_some_and(|d| d.distance_to(p) < EPSILON))
+            {
+                down_at = None;
+            }
+            continue;
+        }
+        last_xy = segment.end;
+        down_at = Some(segment.end);
+    }
+    trimmed
+}
+
+/// Start and end Z of a cutting segment, as the G-code generator reads them
+pub(crate) fn segment_z(
+    segment: &ToolpathSegment,
+    depth: f64,
+    previous_end: Option<f64>,
+) -> (f64, f64) {
+    let start = match (segment.start_z, segment.z_depth) {
+        (Some(z), _) => Some(z),
+        (None, None) => Some(depth),
+        (None, Some(_)) => previous_end,
+    };
+    let end = segment.z_depth.unwrap_or(if segment.start_z.is_some() {
+        start.unwrap_or(depth)
+    } else {
+        depth
+    });
+    (start.unwrap_or(end), end)
+}
+
+fn push_rapid(toolpath: &mut Toolpath, template: &ToolpathSegment, from: Point, to: Point) {
+    toolpath.segments.push(ToolpathSegment::new(
+        ToolpathSegmentType::RapidMove,
+        from,
+        to,
+        template.feed_rate,
+        template.spindle_speed,
+    ));
+}
+
+fn lerp(a: Point, b: Point, t: f64) -> Point {
+    Point::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+}
+
+/// The segment as a polyline; arcs are split into chords within tolerance
+pub(crate) fn segment_points(segment: &ToolpathSegment) -> Vec<Point> {
+    let (clockwise, center) = match (segment.segment_type, segment.center) {
+        (ToolpathSegmentType::ArcCW, Some(center)) => (true, center),
+        (ToolpathSegmentType::ArcCCW, Some(center)) => (false, center),
+        _ => return vec![segment.start, segment.end],
+    };
+
+    let radius = center.distance_to(&segment.start);
+    if radius < EPSILON {
+        return vec![segment.start, segment.end];
+    }
+    let start_angle = (segment.start.y - center.y).atan2(segment.start.x - center.x);
+    let end_angle = (segment.end.y - center.y).atan2(segment.end.x - center.x);
+    let mut sweep = end_angle - start_angle;
+    if clockwise {
+        if sweep >= -EPSILON {
+            sweep -= std::f64::consts::TAU;
+        }
+    } else if sweep <= EPSILON {
+        sweep += std::f64::consts::TAU;
+    }
+
+    let step = if ARC_TOLERANCE < radius {
+        2.0 * (1.0 - ARC_TOLERANCE / radius).acos()
+    } else {
+        std::f64::consts::FRAC_PI_2
+    };
+    let steps = (sweep.abs() / step).ceil().max(1.0) as usize;
+    let mut points: Vec<Point> = (0..steps)
+        .map(|i| {
+            let angle = start_angle + sweep * i as f64 / steps as f64;
+            Point::new(
+                center.x + radius * angle.cos(),
+                center.y + radius * angle.sin(),
+            )
+        })
+        .collect();
+    points.push(segment.end);
+    points
+}
+
+/// Parameter ranges of the move from `a` to `b` that lie inside the polygon
+fn inside_intervals(a: Point, b: Point, polygon: &[Point]) -> Vec<(f64, f64)> {
+    let mut cuts = vec![0.0, 1.0];
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    for i in 0..polygon.len() {
+        let c = polygon[i];
+        let d = polygon[(i + 1) % polygon.len()];
+        let (ex, ey) = (d.x - c.x, d.y - c.y);
+        let denominator = dx * ey - dy * ex;
+        if denominator.abs() < EPSILON * EPSILON {
+            continue;
+        }
+        let t = ((c.x - a.x) * ey - (c.y - a.y) * ex) / denominator;
+        let u = ((c.x - a.x) * dy - (c.y - a.y) * dx) / denominator;
+        if t > 0.0 && t < 1.0 && (-EPSILON..=1.0 + EPSILON).contains(&u) {
+            cuts.push(t);
+        }
+    }
+    cuts.sort_by(f64::total_cmp);
+
+    let mut intervals: Vec<(f64, f64)> = Vec::new();
+    for pair in cuts.windows(2) {
+        let (t0, t1) = (pair[0], pair[1]);
+        if t1 - t0 < EPSILON {
+            continue;
+        }
+        if !point_in_polygon(lerp(a, b, (t0 + t1) / 2.0), polygon) {
+            continue;
+        }
+        match intervals.last_mut() {
+            Some(last) if (last.1 - t0).abs() < EPSILON => last.1 = t1,
+            _ => intervals.push((t0, t1)),
+        }
+    }
+    intervals
+}
@@ -0,0 +1,235 @@
+//! # Trochoidal Slotting
+//!
+//! Cuts a slot along a centerline with overlapping loops instead of one
+//! full-width pass. The tool circles around centres spaced a small stepover
+//! apart along the centerline, so each loop only takes a thin crescent of
+//! material and the tool's engagement stays low even though the slot is wider
+//! than the tool.
+//!
+//! Each loop is two counter-clockwise half-circle arcs (climb milling with a
+//! clockwise spindle), joined to the next loop by a short linear move. The
+//! loops start on the right of the direction of travel; along a bend that
+//! direction is taken over a stretch of the path on both sides of the loop, so
+//! the loop starts turn smoothly around corners instead of jumping. Loop
+//! centres stay on the centerline, so the cut stays within the slot.
+
+use crate::error::{ToolpathError, ToolpathResult};
+use crate::model::Point;
+use crate::toolpath::{ToolpathSegment, ToolpathSegmentType};
+
+/// Most loops generated for one slot
+const MAX_LOOPS: usize = 100_000;
+/// Lengths below this are treated as zero (mm)
+const EPSILON: f64 = 1e-9;
+
+/// Parameters of a trochoidal slot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrochoidalParams {
+    /// Width of the slot (mm)
+    pub slot_width: f64,
+    /// Diameter of the tool (mm)
+    pub tool_diameter: f64,
+    /// Radius of the loops the tool centre follows (mm); at most
+    /// `(slot_width - tool_diameter) / 2`, which cuts the full slot width
+    pub loop_radius: f64,
+    /// Distance between loop centres along the centerline (mm)
+    pub stepover: f64,
+    /// Largest tool engagement angle allowed, in degrees; the stepover is
+    /// reduced to keep within it
+    pub max_engagement_degrees: f64,
+}
+
+impl TrochoidalParams {
+    /// Loops that cut the whole slot width, with at most 60 degrees of
+    /// engagement
+    pub fn new(slot_width: f64, tool_diameter: f64, stepover: f64) -> Self {
+        Self {
+            slot_width,
+            tool_diameter,
+            loop_radius: ((slot_width - tool_diameter) / 2.0).max(0.0),
+            stepover,
+            max_engagement_degrees: 60.0,
+        }
+    }
+
+    /// The stepover used: the requested one, reduced if it would engage the
+    /// tool by more than the maximum angle
+    ///
+    /// A loop advancing by `s` engages a tool of radius `r` over
+    /// `acos(1 - s / r)`.
+    pub fn effective_stepover(&self) -> f64 {
+        let tool_radius = self.tool_diameter / 2.0;
+        let angle = self.max_engagement_degrees.clamp(0.0, 180.0).to_radians();
+        self.stepover.min(tool_radius * (1.0 - angle.cos()))
+    }
+
+    fn validate(&self) -> ToolpathResult<()> {
+        let values = [
+            self.slot_width,
+            self.tool_diameter,
+            self.loop_radius,
+            self.stepover,
+            self.max_engagement_degrees,
+        ];
+        if values.iter().any(|v| !v.is_finite()) {
+            return Err(ToolpathError::InvalidParameters(format!(
+                "trochoidal parameters must be finite, got {:?}",
+                self
+            )));
+        }
+        if self.tool_diameter <= 0.0 || self.slot_width <= self.tool_diameter {
+            return Err(ToolpathError::ToolTooLarge {
+                tool_diameter: self.tool_diameter,
+                shape_min: self.slot_width,
+            });
+        }
+        let max_radius = (self.slot_width - self.tool_diameter) / 2.0;
+        if self.loop_radius <= 0.0 || self.loop_radius > max_radius + EPSILON {
+            return Err(ToolpathError::InvalidParameters(format!(
+                "loop radius must be above 0 and at most {:.3}mm for this slot and tool, got {:.3}mm",
+                max_radius, self.loop_radius
+            )));
+        }
+        if self.max_engagement_degrees <= 0.0 {
+            return Err(ToolpathError::InvalidParameters(format!(
+                "maximum engagement must be positive, got {} degrees",
+                self.max_engagement_degrees
+            )));
+        }
+        if self.stepover <= 0.0 {
+            return Err(ToolpathError::InvalidStepOver(self.stepover));
+        }
+        Ok(())
+    }
+}
+
+/// Trochoidal loops along `centerline`, as cutting segments at one depth
+///
+/// The first segment starts at the first loop; the caller moves the tool
+/// there. Fails if the parameters are invalid, the centerline has no length
+/// or the slot would need more than 100,000 loops.
+pub fn trochoidal_segments(
+    centerline: &[Point],
+    params: &TrochoidalParams,
+    feed_rate: f64,
+    spindle_speed: u32,
+) -> ToolpathResult<Vec<ToolpathSegment>> {
+    params.validate()?;
+    let path = Centerline::new(centerline);
+    if path.length < EPSILON {
+        return Err(ToolpathError::InvalidParameters(
+            "trochoidal slot centerline has no length".to_string(),
+        ));
+    }
+
+    let stepover = params.effective_stepover();
+    // An end that falls on a stepover gets one loop, not two on top of each other
+    let steps = (path.length / stepover - 1e-9).ceil().max(1.0);
+    if steps >= MAX_LOOPS as f64 {
+        return Err(ToolpathError::InvalidParameters(format!(
+            "slot of {:.3}mm at a {:.4}mm stepover needs more than {} loops",
+            path.length, stepover, MAX_LOOPS
+        )));
+    }
+    // Loop centres every stepover, plus one at the end of the centerline
+    let mut distances: Vec<f64> = (0..steps as usize).map(|i| i as f64 * stepover).collect();
+    distances.push(path.length);
+
+    let radius = params.loop_radius;
+    // Stretch of path the direction of travel is averaged over
+    let blend = radius.max(stepover);
+    let mut segments = Vec::with_capacity(distances.len() * 3);
+    let mut previous_start: Option<Point> = None;
+    for distance in distances {
+        let center = path.point_at(distance);
+        let behind = path.point_at((distance - blend).max(0.0));
+        let ahead = path.point_at((distance + blend).min(path.length));
+        let (tx, ty) = normalized(ahead.x - behind.x, ahead.y - behind.y)
+            .unwrap_or_else(|| path.direction_at(distance));
+
+        // Right and left of the direction of travel
+        let start = Point::new(center.x + ty * radius, center.y - tx * radius);
+        let opposite = Point::new(center.x - ty * radius, center.y + tx * radius);
+
+        if let Some(previous) = previous_start {
+            segments.push(ToolpathSegment::new(
+                ToolpathSegmentType::LinearMove,
+                previous,
+                start,
+                feed_rate,
+                spindle_speed,
+            ));
+        }
+        for (from, to) in [(start, opposite), (opposite, start)] {
+            segments.push(ToolpathSegment::new_arc(
+                ToolpathSegmentType::ArcCCW,
+                from,
+                to,
+                center,
+                feed_rate,
+                spindle_speed,
+            ));
+        }
+        previous_start = Some(start);
+    }
+    Ok(segments)
+}
+
+fn normalized(x: f64, y: f64) -> Option<(f64, f64)> {
+    let length = x.hypot(y);
+    (length > EPSILON).then(|| (x / length, y / length))
+}
+
+/// A polyline measured by distance along it
+struct Centerline<'a> {
+    points: &'a [Point],
+    /// Distance along the polyline at each point
+    distances: Vec<f64>,
+    length: f64,
+}
+
+impl<'a> Centerline<'a> {
+    fn new(points: &'a [Point]) -> Self {
+        let mut distances = Vec::with_capacity(points.len());
+        let mut length = 0.0;
+        for (i, point) in points.iter().enumerate() {
+            if i > 0 {
+                length += points[i - 1].distance_to(point);
+            }
+            distances.push(length);
+        }
+        Self {
+            points,
+            distances,
+            length,
+        }
+    }
+
+    /// Index of the piece containing `distance`
+    fn piece(&self, distance: f64) -> usize {
+        let after = self.distances.partition_point(|d| *d <= distance);
+        after.clamp(1, self.points.len() - 1) - 1
+    }
+
+    fn point_at(&self, distance: f64) -> Point {
+        let i = self.piece(distance);
+        let (a, b) = (self.points[i], self.points[i + 1]);
+        let piece_length = self.distances[i + 1] - self.distances[i];
+        if piece_length < EPSILON {
+            return b;
+        }
+        let t = ((distance - self.distances[i]) / piece_length).clamp(0.0, 1.0);
+        Point::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+    }
+
+    /// Direction of the first piece with length at or after `distance`
+    fn direction_at(&self, distance: f64) -> (f64, f64) {
+        (self.piece(distance)..self.points.len() - 1)
+            .chain(0..self.points.len() - 1)
+            .find_map(|i| {
+                let (a, b) = (self.points[i], self.points[i + 1]);
+                normalized(b.x - a.x, b.y - a.y)
+            })
+            .unwrap_or((1.0, 0.0))
+    }
+}
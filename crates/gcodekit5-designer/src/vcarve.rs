@@ -12,7 +12,9 @@
 //! - Path offset for tool diameter compensation
 //! - Multi-pass cutting for deeper designs
 //! - Toolpath generation and optimization
+//! - Variable-depth carving of line art from stroke width
 
+use crate::toolpath::{Toolpath, ToolpathSegment, ToolpathSegmentType};
 use crate::Point;
 use anyhow::Result;
 
@@ -195,6 +197,73 @@ impl VCarveSegment {
     }
 }
 
+/// A sample along an engraving stroke and the stroke's width there
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StrokePoint {
+    /// Position of the sample
+    pub point: Point,
+    /// Stroke width at the sample (mm); zero or less lifts to the surface
+    pub width: f64,
+}
+
+impl StrokePoint {
+    /// Create a new stroke sample
+    pub fn new(point: Point, width: f64) -> Self {
+        Self { point, width }
+    }
+}
+
+/// Parameters for carving strokes to a depth that follows their width
+#[derive(Debug, Clone)]
+pub struct StrokeCarveParams {
+    /// V-bit tool to use
+    pub tool: VBitTool,
+    /// Deepest cut allowed (mm); wider strokes are carved at this depth
+    pub max_depth: f64,
+    /// Spindle speed (RPM)
+    pub spindle_speed: u32,
+    /// Feed rate (mm/min)
+    pub feed_rate: f64,
+    /// Feed rate for plunges at the start of each cut (mm/min)
+    pub plunge_rate: f64,
+}
+
+impl StrokeCarveParams {
+    /// Create new stroke carving parameters
+    pub fn new(
+        tool: VBitTool,
+        max_depth: f64,
+        spindle_speed: u32,
+        feed_rate: f64,
+        plunge_rate: f64,
+    ) -> Self {
+        Self {
+            tool,
+            max_depth,
+            spindle_speed,
+            feed_rate,
+            plunge_rate,
+        }
+    }
+
+    /// Validate parameters
+    pub fn is_valid(&self) -> bool {
+        self.tool.is_valid()
+            && self.max_depth > 0.0
+            && self.spindle_speed > 0
+            && self.feed_rate > 0.0
+            && self.plunge_rate > 0.0
+    }
+
+    /// Depth whose V-groove is as wide as `width`, clamped to the maximum depth
+    pub fn depth_for_width(&self, width: f64) -> f64 {
+        if width <= 0.0 {
+            return 0.0;
+        }
+        self.tool.calculate_depth(width).min(self.max_depth)
+    }
+}
+
 /// V-carving toolpath generator
 pub struct VCarveGenerator;
 
@@ -260,6 +329,88 @@ impl VCarveGenerator {
         Ok(all_passes)
     }
 
+    /// Generate a variable-depth toolpath that carves strokes as wide as drawn
+    ///
+    /// Each sample's depth is the one whose V-groove matches the stroke width
+    /// there, clamped to `max_depth`. Z ramps linearly between samples, with an
+    /// extra vertex where the clamp is reached mid-span so the groove width stays
+    /// exact. Stretches where the width is zero at both ends are not cut: the
+    /// tool lifts and rapids to where the stroke widens again.
+    pub fn generate_stroke_toolpath(
+        params: &StrokeCarveParams,
+        strokes: &[Vec<StrokePoint>],
+    ) -> Result<Toolpath> {
+        if !params.is_valid() {
+            return Err(anyhow::anyhow!("Invalid stroke carving parameters"));
+        }
+        if strokes.iter().any(|stroke| stroke.len() < 2) {
+            return Err(anyhow::anyhow!("Each stroke must have at least 2 points"));
+        }
+
+        let mut toolpath = Toolpath::new(params.tool.diameter, -params.max_depth);
+        let mut current = Point::new(0.0, 0.0);
+        for stroke in strokes {
+            let samples = Self::stroke_depths(params, stroke);
+            let mut cutting = false;
+            for pair in samples.windows(2) {
+                let ((start, start_depth), (end, end_depth)) = (pair[0], pair[1]);
+                if start_depth <= f64::EPSILON && end_depth <= f64::EPSILON {
+                    cutting = false;
+                    continue;
+                }
+                let mut segment = ToolpathSegment::new(
+                    ToolpathSegmentType::LinearMove,
+                    start,
+                    end,
+                    params.feed_rate,
+                    params.spindle_speed,
+                )
+                .with_z_depth(Self::depth_to_z(end_depth))
+                .with_plunge_rate(params.plunge_rate);
+                if !cutting {
+                    toolpath.add_segment(ToolpathSegment::new(
+                        ToolpathSegmentType::RapidMove,
+                        current,
+                        start,
+                        params.feed_rate,
+                        params.spindle_speed,
+                    ));
+                    segment.start_z = Some(Self::depth_to_z(start_depth));
+                    cutting = true;
+                }
+                toolpath.add_segment(segment);
+                current = end;
+            }
+        }
+        Ok(toolpath)
+    }
+
+    /// Z for a carve depth below the stock top, never negative zero
+    fn depth_to_z(depth: f64) -> f64 {
+        if depth > 0.0 {
+            -depth
+        } else {
+            0.0
+        }
+    }
+
+    /// Stroke samples with their carve depths, split where the depth clamps
+    fn stroke_depths(params: &StrokeCarveParams, stroke: &[StrokePoint]) -> Vec<(Point, f64)> {
+        let unclamped = |width: f64| params.tool.calculate_depth(width.max(0.0));
+        let mut samples = vec![(stroke[0].point, params.depth_for_width(stroke[0].width))];
+        for pair in stroke.windows(2) {
+            let (a, b) = (unclamped(pair[0].width), unclamped(pair[1].width));
+            if (a - params.max_depth) * (b - params.max_depth) < 0.0 {
+                let t = (params.max_depth - a) / (b - a);
+                let (from, to) = (pair[0].point, pair[1].point);
+                let at = Point::new(from.x + (to.x - from.x) * t, from.y + (to.y - from.y) * t);
+                samples.push((at, params.max_depth));
+            }
+            samples.push((pair[1].point, params.depth_for_width(pair[1].width)));
+        }
+        samples
+    }
+
     /// Calculate time estimate for V-carving operation (in minutes)
     pub fn estimate_time(params: &VCarveParams, path_points: &[Point]) -> Result<f64> {
         if path_points.len() < 2 {
@@ -1,5 +1,6 @@
 use gcodekit5_designer::canvas::DrawingMode;
 use gcodekit5_designer::designer_state::DesignerState;
+use gcodekit5_designer::TabSettings;
 // Point not used directly in this test file
 
 #[test]
@@ -41,3 +42,130 @@ fn test_generate_gcode() {
     assert!(state.gcode_generated);
     assert!(gcode.contains("G90"));
 }
+
+#[test]
+fn test_generate_gcode_per_shape_cutting_parameters() {
+    let mut state = DesignerState::new();
+    state.toolpath_generator.set_feed_rate(800.0);
+    state.toolpath_generator.set_spindle_speed(10000);
+    state.canvas.add_rectangle(0.0, 0.0, 10.0, 10.0);
+    let custom = state.canvas.add_rectangle(20.0, 0.0, 10.0, 10.0);
+    for obj in state.canvas.shapes_mut() {
+        if obj.id == custom {
+            obj.feed_rate = Some(300.0);
+            obj.plunge_rate = Some(100.0);
+            obj.spindle_speed = Some(16000);
+        }
+    }
+
+    let gcode = state.generate_gcode();
+
+    // The header starts the spindle for the first shape; the override shape
+    // changes it once and plunges at its own rate before cutting at its feed.
+    assert_eq!(gcode.matches("M3 S").count(), 2);
+    assert!(gcode.contains("M3 S16000"));
+    assert!(gcode.contains(" F100\n"));
+    assert!(gcode.contains(" F300\n"));
+    assert!(gcode.contains(" F800\n"));
+    let f300 = gcode.find(" F300").unwrap();
+    assert!(gcode.find(" F100").unwrap() < f300);
+    assert_eq!(gcode.matches(" F300").count(), 1);
+}
+
+#[test]
+fn test_recovery_snapshot_restores_unsaved_design() {
+    let mut state = DesignerState::new();
+    state.canvas.add_rectangle(0.0, 0.0, 10.0, 10.0);
+    state
+        .canvas
+        .add_circle(gcodekit5_designer::Point::new(30.0, 30.0), 5.0);
+    state.tool_settings.feed_rate = 750.0;
+    let snapshot = state.recovery_snapshot().unwrap();
+
+    let mut restored = DesignerState::new();
+    let source = std::path::PathBuf::from("/tmp/part.gckd");
+    restored
+        .restore_recovery_snapshot(&snapshot, Some(source.clone()))
+        .unwrap();
+    assert_eq!(restored.canvas.shape_count(), 2);
+    assert_eq!(restored.tool_settings.feed_rate, 750.0);
+    assert_eq!(restored.current_file_path, Some(source));
+    // Restored work is still unsaved
+    assert!(restored.is_modified);
+
+    assert!(restored
+        .restore_recovery_snapshot("not json", None)
+        .is_err());
+}
+
+#[test]
+fn test_generate_gcode_per_shape_depth_and_tabs() {
+    let mut state = DesignerState::new();
+    state.set_cut_depth(3.0);
+    let default_id = state.canvas.add_rectangle(0.0, 0.0, 10.0, 10.0);
+    let custom = state.canvas.add_rectangle(20.0, 0.0, 40.0, 40.0);
+    for obj in state.canvas.shapes_mut() {
+        obj.selected = obj.id == custom;
+    }
+    state.set_selected_cut_depth(Some(6.0));
+    state.set_selected_tabs(Some(TabSettings::new(4, 5.0, 2.0)));
+
+    let gcode = state.generate_gcode();
+    assert!(gcode.contains("; ===== Operation 1 of 2 ====="));
+    assert!(gcode.contains("; Cut depth: 3.000mm (job default)"));
+    assert!(gcode.contains("; Cut depth: 6.000mm (shape)"));
+    assert_eq!(
+        gcode
+            .matches("; Tabs: 4 x 5.000mm wide, 2.000mm high")
+            .count(),
+        1
+    );
+    // Only the custom shape reaches its depth, and lifts to the tab tops there
+    let custom_section = &gcode[gcode.find(&format!("Shape ID={}", custom)).unwrap()..];
+    let default_section = &gcode[gcode.find(&format!("Shape ID={}", default_id)).unwrap()..];
+    assert!(custom_section.contains("Z-6.000"));
+    assert_eq!(
+        custom_section
+            .lines()
+            .filter(|l| l.starts_with("G01 Z-4.000"))
+            .count(),
+        4
+    );
+    assert!(!default_section.contains("Z-6.000"));
+
+    // Both overrides are single undoable steps
+    state.undo();
+    let obj = state.canvas.shapes().find(|s| s.id == custom).unwrap();
+    assert_eq!(obj.tabs, None);
+    assert_eq!(obj.cut_depth, Some(6.0));
+    state.undo();
+    let obj = state.canvas.shapes().find(|s| s.id == custom).unwrap();
+    assert_eq!(obj.cut_depth, None);
+    state.redo();
+    state.redo();
+    let obj = state.canvas.shapes().find(|s| s.id == custom).unwrap();
+    assert_eq!(obj.tabs, Some(TabSettings::new(4, 5.0, 2.0)));
+}
+
+#[test]
+fn test_recovery_snapshot_keeps_per_shape_depth_and_tabs() {
+    let mut state = DesignerState::new();
+    state.set_default_tabs(Some(TabSettings::new(2, 4.0, 1.0)));
+    state.canvas.add_rectangle(0.0, 0.0, 10.0, 10.0);
+    state.select_all();
+    state.set_selected_cut_depth(Some(2.5));
+    state.set_selected_tabs(Some(TabSettings::new(0, 0.0, 0.0)));
+    let snapshot = state.recovery_snapshot().unwrap();
+
+    let mut restored = DesignerState::new();
+    restored.restore_recovery_snapshot(&snapshot, None).unwrap();
+    let obj = restored.canvas.shapes().next().unwrap();
+    assert_eq!(obj.cut_depth, Some(2.5));
+    // An explicit "no tabs" overrides the job's tabs
+    assert_eq!(obj.tabs, Some(TabSettings::new(0, 0.0, 0.0)));
+    assert_eq!(obj.effective_tabs(restored.tool_settings.tabs), None);
+    assert_eq!(
+        restored.tool_settings.tabs,
+        Some(TabSettings::new(2, 4.0, 1.0))
+    );
+}
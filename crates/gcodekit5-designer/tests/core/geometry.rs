@@ -0,0 +1,26 @@
+use gcodekit5_designer::geometry::{distance_to_segment, point_in_polygon};
+use gcodekit5_designer::Point;
+
+#[test]
+fn test_point_in_polygon() {
+    let square = [
+        Point::new(0.0, 0.0),
+        Point::new(10.0, 0.0),
+        Point::new(10.0, 10.0),
+        Point::new(0.0, 10.0),
+    ];
+    assert!(point_in_polygon(Point::new(5.0, 5.0), &square));
+    assert!(!point_in_polygon(Point::new(15.0, 5.0), &square));
+    assert!(!point_in_polygon(Point::new(5.0, 5.0), &square[..2]));
+    assert!(!point_in_polygon(Point::new(5.0, 5.0), &[]));
+}
+
+#[test]
+fn test_distance_to_segment() {
+    let (a, b) = (Point::new(0.0, 0.0), Point::new(10.0, 0.0));
+    assert!((distance_to_segment(Point::new(5.0, 3.0), a, b) - 3.0).abs() < 1e-9);
+    // Past the end the nearest point is the endpoint
+    assert!((distance_to_segment(Point::new(13.0, 4.0), a, b) - 5.0).abs() < 1e-9);
+    // A zero-length segment is its one point
+    assert!((distance_to_segment(Point::new(3.0, 4.0), a, a) - 5.0).abs() < 1e-9);
+}
@@ -1,3 +1,4 @@
+use gcodekit5_designer::error::HistoryError;
 use gcodekit5_designer::history::{ActionType, HistoryAction, HistoryTransaction, UndoRedoManager};
 
 #[test]
@@ -249,3 +250,82 @@ fn test_serialization() {
     assert_eq!(action.description, deserialized.description);
     assert_eq!(action.action_type, deserialized.action_type);
 }
+
+fn shape_action(description: &str, shapes: &[u64]) -> HistoryAction {
+    HistoryAction::new(
+        ActionType::ShapeMoved,
+        description.to_string(),
+        format!("{{\"before\":\"{}\"}}", description),
+        format!("{{\"after\":\"{}\"}}", description),
+    )
+    .with_shapes(shapes.iter().copied())
+}
+
+#[test]
+fn test_undo_action_keeps_independent_later_actions() {
+    let mut manager = UndoRedoManager::new(50);
+    manager.record(shape_action("Move A", &[1]));
+    manager.record(shape_action("Move B", &[2]));
+    manager.record(shape_action("Move C", &[3]));
+    let first = manager.undo_history()[0].id;
+
+    let inverse = manager.undo_action(first).unwrap().clone();
+    assert_eq!(inverse.description, "Undo Move A");
+    assert_eq!(inverse.after_state, "{\"before\":\"Move A\"}");
+    assert_eq!(inverse.shape_ids, vec![1]);
+
+    // Later actions stay applied; the inverse is a new, undoable action
+    assert_eq!(manager.undo_depth(), 4);
+    assert_eq!(manager.undo_description(), Some("Undo Move A".to_string()));
+    assert_eq!(manager.action(first).unwrap().description, "Move A");
+}
+
+#[test]
+fn test_undo_action_refuses_conflicting_later_action() {
+    let mut manager = UndoRedoManager::new(50);
+    manager.record(shape_action("Move A", &[1, 2]));
+    manager.record(shape_action("Resize B", &[2, 5]));
+    let first = manager.undo_history()[0].id;
+    let second = manager.undo_history()[1].id;
+
+    assert_eq!(
+        manager.undo_action(first).unwrap_err(),
+        HistoryError::Conflict {
+            action: first,
+            later: second,
+            shapes: vec![2],
+        }
+    );
+    assert_eq!(manager.undo_depth(), 2);
+
+    // Undoing the last action has nothing after it to conflict with
+    assert!(manager.undo_action(second).is_ok());
+}
+
+#[test]
+fn test_undo_action_refuses_unrecorded_scope_and_unknown_ids() {
+    let mut manager = UndoRedoManager::new(50);
+    manager.record(shape_action("Move A", &[1]));
+    manager.record(HistoryAction::simple(
+        ActionType::ToolChanged,
+        "Change tool".to_string(),
+    ));
+    let first = manager.undo_history()[0].id;
+
+    assert!(matches!(
+        manager.undo_action(first),
+        Err(HistoryError::UnknownScope { .. })
+    ));
+    assert_eq!(
+        manager.undo_action(999).unwrap_err(),
+        HistoryError::ActionNotFound(999)
+    );
+
+    // Undone actions are not applied, so they cannot be reverted again
+    manager.undo();
+    manager.undo();
+    assert_eq!(
+        manager.undo_action(first).unwrap_err(),
+        HistoryError::ActionNotFound(first)
+    );
+}
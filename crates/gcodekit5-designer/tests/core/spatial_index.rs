@@ -157,3 +157,104 @@ fn test_spatial_index_large_coordinates() {
     let results2 = index.query_point(50005.0, -49995.0);
     assert!(results2.contains(&2), "Should find item at (50000, -50000)");
 }
+
+fn grid_index() -> SpatialIndex {
+    let mut index = SpatialIndex::new(Bounds::new(0.0, 0.0, 1000.0, 1000.0), 8, 4);
+    let mut id = 0;
+    for row in 0..10 {
+        for col in 0..10 {
+            let (x, y) = (col as f64 * 100.0, row as f64 * 100.0);
+            index.insert(id, &Bounds::new(x, y, x + 10.0, y + 10.0));
+            id += 1;
+        }
+    }
+    index
+}
+
+#[test]
+fn test_k_nearest_matches_brute_force() {
+    let index = grid_index();
+    let (px, py) = (333.0, 512.0);
+
+    let mut expected: Vec<(u64, f64)> = (0..100u64)
+        .map(|id| {
+            let (x, y) = ((id % 10) as f64 * 100.0, (id / 10) as f64 * 100.0);
+            let b = Bounds::new(x, y, x + 10.0, y + 10.0);
+            (id, b.distance_to_point(px, py))
+        })
+        .collect();
+    expected.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+
+    let nearest = index.k_nearest(px, py, 5);
+    assert_eq!(nearest.len(), 5);
+    for ((id, distance), (expected_id, expected_distance)) in nearest.iter().zip(&expected) {
+        assert_eq!(id, expected_id);
+        assert!((*distance as f64 - expected_distance).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn test_k_nearest_inside_bounds_is_zero() {
+    let index = grid_index();
+    let nearest = index.k_nearest(305.0, 205.0, 1);
+    assert_eq!(nearest, vec![(23, 0.0)]);
+}
+
+#[test]
+fn test_k_nearest_ties_are_deterministic() {
+    let mut index = SpatialIndex::new(Bounds::new(-100.0, -100.0, 100.0, 100.0), 8, 2);
+    // Four items at the same distance from the origin, inserted out of order
+    for (id, (x, y)) in [
+        (7, (10.0, 0.0)),
+        (3, (-10.0, 0.0)),
+        (9, (0.0, 10.0)),
+        (1, (0.0, -10.0)),
+    ] {
+        index.insert(id, &Bounds::new(x - 1.0, y - 1.0, x + 1.0, y + 1.0));
+    }
+    let ids: Vec<u64> = index
+        .k_nearest(0.0, 0.0, 4)
+        .iter()
+        .map(|(id, _)| *id)
+        .collect();
+    assert_eq!(ids, vec![1, 3, 7, 9]);
+}
+
+#[test]
+fn test_k_nearest_k_larger_than_count() {
+    let index = grid_index();
+    let all = index.k_nearest(0.0, 0.0, 500);
+    assert_eq!(all.len(), 100);
+    assert!(all.windows(2).all(|w| w[0].1 <= w[1].1));
+    assert!(index.k_nearest(0.0, 0.0, 0).is_empty());
+
+    let mut index = index;
+    index.remove(0, &Bounds::new(0.0, 0.0, 10.0, 10.0));
+    assert_eq!(index.k_nearest(0.0, 0.0, 1)[0].0, 1);
+}
+
+#[test]
+fn test_split_keeps_existing_items_where_they_are() {
+    // One item per node, so the second insert splits the root
+    let mut index = SpatialIndex::new(Bounds::new(0.0, 0.0, 100.0, 100.0), 8, 1);
+    index.insert(1, &Bounds::new(10.0, 10.0, 12.0, 12.0));
+    index.insert(2, &Bounds::new(80.0, 80.0, 82.0, 82.0));
+
+    // Redistributed by the second item's bounds, item 1 would only be in the
+    // top-right quadrant and missing from its own
+    assert_eq!(index.query_point(11.0, 11.0), vec![1]);
+    assert_eq!(index.query_point(81.0, 81.0), vec![2]);
+    assert_eq!(index.query(&Bounds::new(0.0, 0.0, 40.0, 40.0)), vec![1]);
+}
+
+#[test]
+fn test_queries_return_only_items_whose_bounds_match() {
+    // Both items share the root leaf, so the node alone cannot tell them apart
+    let mut index = SpatialIndex::new(Bounds::new(0.0, 0.0, 100.0, 100.0), 8, 16);
+    index.insert(1, &Bounds::new(10.0, 10.0, 12.0, 12.0));
+    index.insert(2, &Bounds::new(80.0, 80.0, 82.0, 82.0));
+
+    assert_eq!(index.query(&Bounds::new(0.0, 0.0, 40.0, 40.0)), vec![1]);
+    assert_eq!(index.query_point(81.0, 81.0), vec![2]);
+    assert!(index.query_point(50.0, 50.0).is_empty());
+}
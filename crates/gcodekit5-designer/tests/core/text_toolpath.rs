@@ -1,4 +1,7 @@
-use gcodekit5_designer::{TextShape, ToolpathGenerator};
+use gcodekit5_designer::model::Point;
+use gcodekit5_designer::{
+    layout_text_on_path, GlyphSource, PathTextOptions, TextOverflow, TextShape, ToolpathGenerator,
+};
 
 #[test]
 fn test_text_toolpath_advances_characters() {
@@ -110,3 +113,102 @@ fn test_text_pocket_toolpath_generates_infill() {
         pmax_y
     );
 }
+
+/// 4 x 6 box glyphs on a 5-wide advance, scaled so `size` is the box height
+struct BoxFont;
+
+impl GlyphSource for BoxFont {
+    fn advance(&self, _c: char, size: f64) -> f64 {
+        size * 5.0 / 6.0
+    }
+
+    fn glyph_paths(&self, _c: char, size: f64) -> Vec<Vec<Point>> {
+        let s = size / 6.0;
+        vec![vec![
+            Point::new(0.0, 0.0),
+            Point::new(4.0 * s, 0.0),
+            Point::new(4.0 * s, 6.0 * s),
+            Point::new(0.0, 6.0 * s),
+            Point::new(0.0, 0.0),
+        ]]
+    }
+}
+
+/// Clockwise circle about the origin, starting at the top
+fn clockwise_circle(radius: f64, steps: usize) -> Vec<Point> {
+    (0..=steps)
+        .map(|i| {
+            let angle =
+                std::f64::consts::FRAC_PI_2 - std::f64::consts::TAU * i as f64 / steps as f64;
+            Point::new(radius * angle.cos(), radius * angle.sin())
+        })
+        .collect()
+}
+
+#[test]
+fn test_text_on_circle_is_rotated_radially() {
+    let path = clockwise_circle(50.0, 720);
+    let options = PathTextOptions {
+        size: 6.0,
+        baseline_offset: 2.0,
+        ..Default::default()
+    };
+    let glyphs = layout_text_on_path("ABCD", &BoxFont, &path, &options);
+    assert_eq!(glyphs.len(), 4);
+
+    for glyph in &glyphs {
+        let radial = glyph.anchor.y.atan2(glyph.anchor.x);
+        assert!((glyph.anchor.distance_to(&Point::new(0.0, 0.0)) - 50.0).abs() < 0.01);
+        // Clockwise travel: the tangent is a quarter turn behind the radius
+        let turn = (radial - std::f64::consts::FRAC_PI_2 - glyph.angle + std::f64::consts::PI)
+            .rem_euclid(std::f64::consts::TAU)
+            - std::f64::consts::PI;
+        assert!(turn.abs() < 0.01);
+
+        // Tops face outwards: the box spans radius + offset to + offset + height
+        let radii: Vec<f64> = glyph.paths[0]
+            .iter()
+            .map(|p| p.distance_to(&Point::new(0.0, 0.0)))
+            .collect();
+        let inner = radii.iter().cloned().fold(f64::INFINITY, f64::min);
+        let outer = radii.iter().cloned().fold(0.0, f64::max);
+        assert!((inner - 52.0).abs() < 0.1, "inner radius {inner}");
+        assert!((outer - 58.0).abs() < 0.2, "outer radius {outer}");
+    }
+
+    // Glyphs are spaced by their advance along the circle
+    let step = glyphs[0].anchor.distance_to(&glyphs[1].anchor);
+    assert!((step - 5.0).abs() < 0.01);
+}
+
+#[test]
+fn test_text_longer_than_path_truncates() {
+    let path = vec![Point::new(0.0, 0.0), Point::new(12.0, 0.0)];
+    let options = PathTextOptions {
+        size: 6.0,
+        ..Default::default()
+    };
+    let glyphs = layout_text_on_path("ABCD", &BoxFont, &path, &options);
+    assert_eq!(glyphs.iter().map(|g| g.ch).collect::<String>(), "AB");
+    assert!((glyphs[1].anchor.x - 7.5).abs() < 1e-9);
+    assert!(glyphs[0].angle.abs() < 1e-9);
+}
+
+#[test]
+fn test_text_longer_than_path_scales_to_fit() {
+    let path = vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0)];
+    let options = PathTextOptions {
+        size: 6.0,
+        overflow: TextOverflow::Scale,
+        ..Default::default()
+    };
+    let glyphs = layout_text_on_path("ABCD", &BoxFont, &path, &options);
+    assert_eq!(glyphs.len(), 4);
+    // 20 mm of text in 10 mm: half size, so boxes 3 mm tall
+    let top = glyphs[3].paths[0]
+        .iter()
+        .map(|p| p.y)
+        .fold(f64::NEG_INFINITY, f64::max);
+    assert!((top - 3.0).abs() < 1e-9);
+    assert!((glyphs[3].anchor.x - 8.75).abs() < 1e-9);
+}
@@ -23,3 +23,578 @@ fn test_toolpath_total_length() {
     let length = toolpath.total_length();
     assert!(length > 0.0);
 }
+
+#[test]
+fn test_toolpath_reversed_traces_points_backwards() {
+    use gcodekit5_designer::model::Point;
+    use gcodekit5_designer::toolpath::{Toolpath, ToolpathSegment, ToolpathSegmentType};
+
+    let origin = Point::new(0.0, 0.0);
+    let a = Point::new(10.0, 0.0);
+    let b = Point::new(20.0, 0.0);
+    let c = Point::new(20.0, 10.0);
+    let mut toolpath = Toolpath::new(3.0, -2.0);
+    toolpath.add_segment(ToolpathSegment::new(
+        ToolpathSegmentType::RapidMove,
+        origin,
+        a,
+        500.0,
+        10000,
+    ));
+    let mut ramp = ToolpathSegment::new(ToolpathSegmentType::LinearMove, a, b, 500.0, 10000);
+    ramp.start_z = Some(0.0);
+    ramp.z_depth = Some(-2.0);
+    toolpath.add_segment(ramp);
+    toolpath.add_segment(
+        ToolpathSegment::new_arc(
+            ToolpathSegmentType::ArcCW,
+            b,
+            c,
+            Point::new(20.0, 5.0),
+            500.0,
+            10000,
+        )
+        .with_z_depth(-2.0),
+    );
+    toolpath.add_segment(ToolpathSegment::new(
+        ToolpathSegmentType::RapidMove,
+        c,
+        origin,
+        500.0,
+        10000,
+    ));
+
+    let reversed = toolpath.reversed();
+    let points = |tp: &Toolpath| {
+        let mut points = vec![tp.segments[0].start];
+        points.extend(tp.segments.iter().map(|s| s.end));
+        points
+    };
+    let mut expected = points(&toolpath);
+    expected.reverse();
+    assert_eq!(points(&reversed), expected);
+
+    let types: Vec<_> = reversed.segments.iter().map(|s| s.segment_type).collect();
+    assert_eq!(
+        types,
+        vec![
+            ToolpathSegmentType::RapidMove,
+            ToolpathSegmentType::ArcCCW,
+            ToolpathSegmentType::LinearMove,
+            ToolpathSegmentType::RapidMove,
+        ]
+    );
+    assert_eq!(reversed.segments[1].center, Some(Point::new(20.0, 5.0)));
+    // The ramp down from Z0 now climbs back out
+    assert_eq!(reversed.segments[1].start_z, Some(-2.0));
+    assert_eq!(reversed.segments[2].start_z, Some(-2.0));
+    assert_eq!(reversed.segments[2].z_depth, Some(0.0));
+    assert_eq!(reversed.reversed().segments.len(), toolpath.segments.len());
+}
+
+#[test]
+fn test_trim_to_boundary_splits_and_retracts() {
+    use gcodekit5_designer::model::Point;
+    use gcodekit5_designer::toolpath::{Toolpath, ToolpathSegment, ToolpathSegmentType};
+    use gcodekit5_designer::trim_to_boundary;
+
+    let stock = [
+        Point::new(0.0, 0.0),
+        Point::new(20.0, 0.0),
+        Point::new(20.0, 10.0),
+        Point::new(0.0, 10.0),
+    ];
+    let line = |a: (f64, f64), b: (f64, f64)| {
+        ToolpathSegment::new(
+            ToolpathSegmentType::LinearMove,
+            Point::new(a.0, a.1),
+            Point::new(b.0, b.1),
+            800.0,
+            12000,
+        )
+        .with_plunge_rate(200.0)
+    };
+    // Out past the right edge and back in again
+    let mut toolpath = Toolpath::new(3.0, -1.0);
+    toolpath.add_segment(ToolpathSegment::new(
+        ToolpathSegmentType::RapidMove,
+        Point::new(0.0, 0.0),
+        Point::new(5.0, 2.0),
+        800.0,
+        12000,
+    ));
+    toolpath.add_segment(line((5.0, 2.0), (30.0, 2.0)));
+    toolpath.add_segment(line((30.0, 2.0), (30.0, 8.0)));
+    toolpath.add_segment(line((30.0, 8.0), (5.0, 8.0)));
+    toolpath.add_segment(line((5.0, 8.0), (5.0, 2.0)));
+
+    let trimmed = trim_to_boundary(&toolpath, &stock);
+    let moves: Vec<_> = trimmed
+        .segments
+        .iter()
+        .map(|s| (s.segment_type, s.start, s.end))
+        .collect();
+    assert_eq!(
+        moves,
+        vec![
+            (
+                ToolpathSegmentType::RapidMove,
+                Point::new(0.0, 0.0),
+                Point::new(5.0, 2.0)
+            ),
+            (
+                ToolpathSegmentType::LinearMove,
+                Point::new(5.0, 2.0),
+                Point::new(20.0, 2.0)
+            ),
+            (
+                ToolpathSegmentType::RapidMove,
+                Point::new(20.0, 2.0),
+                Point::new(20.0, 8.0)
+            ),
+            (
+                ToolpathSegmentType::LinearMove,
+                Point::new(20.0, 8.0),
+                Point::new(5.0, 8.0)
+            ),
+            (
+                ToolpathSegmentType::LinearMove,
+                Point::new(5.0, 8.0),
+                Point::new(5.0, 2.0)
+            ),
+        ]
+    );
+    for segment in trimmed.segments.iter().skip(1).step_by(2) {
+        assert_eq!(segment.feed_rate, 800.0);
+        assert_eq!(segment.plunge_rate, Some(200.0));
+        assert_eq!(segment.spindle_speed, 12000);
+    }
+    // The re-entry plunges back to cutting depth
+    assert_eq!(trimmed.segments[3].start_z, Some(-1.0));
+    assert_eq!(trimmed.segments[3].z_depth, Some(-1.0));
+
+    // A ramp cut at the boundary keeps its slope
+    let mut ramp = Toolpath::new(3.0, -1.0);
+    let mut segment = line((-10.0, 5.0), (10.0, 5.0));
+    segment.start_z = Some(0.0);
+    segment.z_depth = Some(-2.0);
+    ramp.add_segment(segment);
+    let trimmed = trim_to_boundary(&ramp, &stock);
+    assert_eq!(trimmed.segments.len(), 2);
+    assert_eq!(trimmed.segments[1].start, Point::new(0.0, 5.0));
+    let start_z = trimmed.segments[1].start_z.expect("start Z");
+    assert!((start_z + 1.0).abs() < 1e-9);
+    assert_eq!(trimmed.segments[1].z_depth, Some(-2.0));
+}
+
+#[test]
+fn test_trim_to_boundary_arcs() {
+    use gcodekit5_designer::model::Point;
+    use gcodekit5_designer::toolpath::{Toolpath, ToolpathSegment, ToolpathSegmentType};
+    use gcodekit5_designer::trim_to_boundary;
+
+    let stock = [
+        Point::new(0.0, 0.0),
+        Point::new(20.0, 0.0),
+        Point::new(20.0, 20.0),
+        Point::new(0.0, 20.0),
+    ];
+    let arc = |center: Point, radius: f64| {
+        ToolpathSegment::new_arc(
+            ToolpathSegmentType::ArcCCW,
+            Point::new(center.x + radius, center.y),
+            Point::new(center.x - radius, center.y),
+            center,
+            600.0,
+            10000,
+        )
+    };
+
+    // An arc inside the stock is kept as an arc
+    let mut inside = Toolpath::new(3.0, -1.0);
+    inside.add_segment(arc(Point::new(10.0, 10.0), 5.0));
+    let trimmed = trim_to_boundary(&inside, &stock);
+    assert_eq!(trimmed.segments.len(), 2);
+    assert_eq!(
+        trimmed.segments[1].segment_type,
+        ToolpathSegmentType::ArcCCW
+    );
+    assert_eq!(trimmed.segments[1].center, Some(Point::new(10.0, 10.0)));
+
+    // An arc over the top edge becomes line pieces inside the stock
+    let mut crossing = Toolpath::new(3.0, -1.0);
+    crossing.add_segment(arc(Point::new(10.0, 15.0), 8.0));
+    let trimmed = trim_to_boundary(&crossing, &stock);
+    let cuts: Vec<_> = trimmed
+        .segments
+        .iter()
+        .filter(|s| s.segment_type != ToolpathSegmentType::RapidMove)
+        .collect();
+    assert!(cuts.len() > 2);
+    for cut in &cuts {
+        assert_eq!(cut.segment_type, ToolpathSegmentType::LinearMove);
+        for p in [cut.start, cut.end] {
+            assert!(p.y <= 20.0 + 1e-9 && p.y >= 15.0 - 1e-9, "{:?}", p);
+            let radius = p.distance_to(&Point::new(10.0, 15.0));
+            assert!((radius - 8.0).abs() < 0.02, "{:?}", p);
+        }
+    }
+    // Two rapids: to the start, and across the gap above the stock
+    let rapids = trimmed
+        .segments
+        .iter()
+        .filter(|s| s.segment_type == ToolpathSegmentType::RapidMove)
+        .count();
+    assert_eq!(rapids, 2);
+
+    // Nothing survives a degenerate boundary
+    assert!(trim_to_boundary(&crossing, &stock[..2]).segments.is_empty());
+}
+
+#[test]
+fn test_filter_short_segments_merges_within_tolerance() {
+    use gcodekit5_designer::model::Point;
+    use gcodekit5_designer::toolpath::{Toolpath, ToolpathSegment, ToolpathSegmentType};
+    use gcodekit5_designer::{filter_short_segments, SegmentFilter};
+
+    let line = |a: (f64, f64), b: (f64, f64)| {
+        ToolpathSegment::new(
+            ToolpathSegmentType::LinearMove,
+            Point::new(a.0, a.1),
+            Point::new(b.0, b.1),
+            800.0,
+            12000,
+        )
+    };
+    // A nearly straight run of 0.1mm steps, then a sharp corner
+    let mut toolpath = Toolpath::new(3.0, -1.0);
+    for i in 0..10 {
+        let x = i as f64 * 0.1;
+        let y = if i % 2 == 0 { 0.0 } else { 0.002 };
+        let next_y = if i % 2 == 0 { 0.002 } else { 0.0 };
+        toolpath.add_segment(line((x, y), (x + 0.1, next_y)));
+    }
+    toolpath.add_segment(line((1.0, 0.0), (1.0, 5.0)));
+
+    let (filtered, report) = filter_short_segments(&toolpath, &SegmentFilter::new(0.5, 0.01));
+    let moves: Vec<_> = filtered.segments.iter().map(|s| (s.start, s.end)).collect();
+    // Merging the short steps stops where it would cut the corner
+    assert_eq!(
+        moves,
+        vec![
+            (Point::new(0.0, 0.0), Point::new(1.0, 0.0)),
+            (Point::new(1.0, 0.0), Point::new(1.0, 5.0)),
+        ]
+    );
+    assert_eq!(report.merged, 9);
+    assert_eq!(report.total(), 9);
+}
+
+#[test]
+fn test_filter_short_segments_keeps_loops_and_corners() {
+    use gcodekit5_designer::model::Point;
+    use gcodekit5_designer::toolpath::{Toolpath, ToolpathSegment, ToolpathSegmentType};
+    use gcodekit5_designer::{filter_short_segments, SegmentFilter};
+
+    let line = |a: (f64, f64), b: (f64, f64)| {
+        ToolpathSegment::new(
+            ToolpathSegmentType::LinearMove,
+            Point::new(a.0, a.1),
+            Point::new(b.0, b.1),
+            800.0,
+            12000,
+        )
+    };
+    // A tiny closed square: every side is short, but every corner is sharp
+    let mut toolpath = Toolpath::new(3.0, -1.0);
+    toolpath.add_segment(line((0.0, 0.0), (0.2, 0.0)));
+    toolpath.add_segment(line((0.2, 0.0), (0.2, 0.2)));
+    toolpath.add_segment(line((0.2, 0.2), (0.0, 0.2)));
+    toolpath.add_segment(line((0.0, 0.2), (0.0, 0.0)));
+
+    let (filtered, report) = filter_short_segments(&toolpath, &SegmentFilter::new(0.5, 0.01));
+    assert_eq!(filtered.segments.len(), 4);
+    assert_eq!(report.total(), 0);
+
+    // With a loose tolerance the loop is simplified but still closes
+    let (filtered, report) = filter_short_segments(&toolpath, &SegmentFilter::new(0.5, 1.0));
+    let first = filtered.segments.first().unwrap();
+    let last = filtered.segments.last().unwrap();
+    assert!(filtered.segments.len() >= 2);
+    assert_eq!(first.start, Point::new(0.0, 0.0));
+    assert_eq!(last.end, Point::new(0.0, 0.0));
+    assert!(report.merged > 0);
+}
+
+#[test]
+fn test_filter_short_segments_replaces_short_arcs() {
+    use gcodekit5_designer::model::Point;
+    use gcodekit5_designer::toolpath::{Toolpath, ToolpathSegment, ToolpathSegmentType};
+    use gcodekit5_designer::{filter_short_segments, SegmentFilter};
+
+    let mut toolpath = Toolpath::new(3.0, -1.0);
+    // A 0.2mm arc of radius 10 bulges about 0.0005mm from its chord
+    let angle: f64 = 0.02;
+    toolpath.add_segment(ToolpathSegment::new_arc(
+        ToolpathSegmentType::ArcCCW,
+        Point::new(10.0, 0.0),
+        Point::new(10.0 * angle.cos(), 10.0 * angle.sin()),
+        Point::new(0.0, 0.0),
+        800.0,
+        12000,
+    ));
+    // A full circle is never replaced
+    toolpath.add_segment(ToolpathSegment::new_arc(
+        ToolpathSegmentType::ArcCW,
+        Point::new(0.0, 0.0),
+        Point::new(0.0, 0.0),
+        Point::new(0.05, 0.0),
+        800.0,
+        12000,
+    ));
+
+    let (filtered, report) = filter_short_segments(&toolpath, &SegmentFilter::new(0.5, 0.01));
+    let types: Vec<_> = filtered.segments.iter().map(|s| s.segment_type).collect();
+    assert_eq!(
+        types,
+        vec![ToolpathSegmentType::LinearMove, ToolpathSegmentType::ArcCW]
+    );
+    assert_eq!(report.arcs_replaced, 1);
+}
+
+#[test]
+fn test_bridge_gaps_joins_nearby_ends_within_one_feed() {
+    use gcodekit5_designer::model::Point;
+    use gcodekit5_designer::toolpath::{Toolpath, ToolpathSegment, ToolpathSegmentType};
+    use gcodekit5_designer::{bridge_gaps, GapBridging};
+
+    let segment = |kind, a: (f64, f64), b: (f64, f64), feed| {
+        ToolpathSegment::new(
+            kind,
+            Point::new(a.0, a.1),
+            Point::new(b.0, b.1),
+            feed,
+            12000,
+        )
+    };
+    let line = |a, b| segment(ToolpathSegmentType::LinearMove, a, b, 800.0);
+    let rapid = |a, b| segment(ToolpathSegmentType::RapidMove, a, b, 800.0);
+
+    let mut toolpath = Toolpath::new(3.0, -1.0);
+    toolpath.add_segment(line((0.0, 0.0), (10.0, 0.0)));
+    // A tiny gap with a retract across it
+    toolpath.add_segment(rapid((10.0, 0.0), (10.02, 0.0)));
+    toolpath.add_segment(line((10.02, 0.0), (10.0, 10.0)));
+    // Ends that already meet, split by a rapid
+    toolpath.add_segment(rapid((10.0, 10.0), (10.0, 10.0)));
+    toolpath.add_segment(line((10.0, 10.0), (0.0, 10.0)));
+    // A real move to another cut
+    toolpath.add_segment(rapid((0.0, 10.0), (30.0, 0.0)));
+    toolpath.add_segment(line((30.0, 0.0), (40.0, 0.0)));
+    // Close, but at a different feed
+    toolpath.add_segment(rapid((40.0, 0.0), (40.01, 0.0)));
+    toolpath.add_segment(segment(
+        ToolpathSegmentType::LinearMove,
+        (40.01, 0.0),
+        (50.0, 0.0),
+        400.0,
+    ));
+
+    let (bridged, report) = bridge_gaps(&toolpath, &GapBridging::new(0.05));
+    let kinds: Vec<_> = bridged.segments.iter().map(|s| s.segment_type).collect();
+    use ToolpathSegmentType::{LinearMove as L, RapidMove as R};
+    assert_eq!(kinds, vec![L, L, L, L, R, L, R, L]);
+    let connector = &bridged.segments[1];
+    assert_eq!(connector.start, Point::new(10.0, 0.0));
+    assert_eq!(connector.end, Point::new(10.02, 0.0));
+    assert_eq!(connector.z_depth, Some(-1.0));
+    assert_eq!(report.joined, 2);
+    assert_eq!(report.retracts_removed, 2);
+    assert!((report.max_gap - 0.02).abs() < 1e-9);
+
+    // A step down between passes is never joined
+    let mut passes = Toolpath::new(3.0, -2.0);
+    passes.add_segment(line((0.0, 0.0), (10.0, 0.0)).with_z_depth(-1.0));
+    passes.add_segment(rapid((10.0, 0.0), (0.0, 0.0)));
+    passes.add_segment(line((0.0, 0.0), (10.0, 0.0)));
+    let (bridged, report) = bridge_gaps(&passes, &GapBridging::new(20.0));
+    assert_eq!(bridged.segments.len(), 3);
+    assert_eq!(report.joined, 0);
+}
+
+#[test]
+fn test_smooth_corners_rounds_within_deviation() {
+    use gcodekit5_designer::model::Point;
+    use gcodekit5_designer::toolpath::{Toolpath, ToolpathSegment, ToolpathSegmentType};
+    use gcodekit5_designer::{smooth_corners, CornerSmoothing};
+
+    let line = |a: (f64, f64), b: (f64, f64)| {
+        ToolpathSegment::new(
+            ToolpathSegmentType::LinearMove,
+            Point::new(a.0, a.1),
+            Point::new(b.0, b.1),
+            800.0,
+            12000,
+        )
+    };
+    // A counter-clockwise square: three inner corners, and the loop's start
+    let mut toolpath = Toolpath::new(3.0, -1.0);
+    toolpath.add_segment(line((0.0, 0.0), (10.0, 0.0)));
+    toolpath.add_segment(line((10.0, 0.0), (10.0, 10.0)));
+    toolpath.add_segment(line((10.0, 10.0), (0.0, 10.0)));
+    toolpath.add_segment(line((0.0, 10.0), (0.0, 0.0)));
+
+    let (smoothed, report) = smooth_corners(&toolpath, &CornerSmoothing::new(0.1));
+    assert_eq!(report.rounded, 3);
+    assert_eq!(smoothed.segments.len(), 7);
+    assert_eq!(smoothed.segments[0].start, Point::new(0.0, 0.0));
+    assert_eq!(smoothed.segments[6].end, Point::new(0.0, 0.0));
+    for pair in smoothed.segments.windows(2) {
+        assert!(pair[0].end.distance_to(&pair[1].start) < 1e-9);
+    }
+
+    let arc = &smoothed.segments[1];
+    assert_eq!(arc.segment_type, ToolpathSegmentType::ArcCCW);
+    let center = arc.center.unwrap();
+    let radius = center.distance_to(&arc.start);
+    assert!((radius - center.distance_to(&arc.end)).abs() < 1e-9);
+    // The middle of the arc is the deviation away from the corner
+    let deviation = center.distance_to(&Point::new(10.0, 0.0)) - radius;
+    assert!((deviation - 0.1).abs() < 1e-9);
+    // Tangent to both moves: the centre is square to each tangent point
+    assert!((center.x - arc.start.x).abs() < 1e-9);
+    assert!((center.y - arc.end.y).abs() < 1e-9);
+}
+
+#[test]
+fn test_smooth_corners_keeps_small_features_and_gentle_turns() {
+    use gcodekit5_designer::model::Point;
+    use gcodekit5_designer::toolpath::{Toolpath, ToolpathSegment, ToolpathSegmentType};
+    use gcodekit5_designer::{smooth_corners, CornerSmoothing};
+
+    let line = |a: (f64, f64), b: (f64, f64)| {
+        ToolpathSegment::new(
+            ToolpathSegmentType::LinearMove,
+            Point::new(a.0, a.1),
+            Point::new(b.0, b.1),
+            800.0,
+            12000,
+        )
+    };
+    let mut toolpath = Toolpath::new(3.0, -1.0);
+    // A 0.2mm notch is a feature, not a corner to round
+    toolpath.add_segment(line((0.0, 0.0), (5.0, 0.0)));
+    toolpath.add_segment(line((5.0, 0.0), (5.0, -0.2)));
+    toolpath.add_segment(line((5.0, -0.2), (5.2, -0.2)));
+    toolpath.add_segment(line((5.2, -0.2), (5.2, 0.0)));
+    // A 2 degree bend, a clockwise corner, then a corner into a slower move
+    toolpath.add_segment(line((5.2, 0.0), (10.0, 0.0)));
+    toolpath.add_segment(line((10.0, 0.0), (15.0, 0.17)));
+    toolpath.add_segment(line((15.0, 0.17), (15.0, -5.0)));
+    let mut slower = line((15.0, -5.0), (20.0, -5.0));
+    slower.feed_rate = 400.0;
+    toolpath.add_segment(slower);
+
+    let (smoothed, report) = smooth_corners(&toolpath, &CornerSmoothing::new(0.05));
+    assert_eq!(report.rounded, 1);
+    assert_eq!(report.kept_features, 4);
+    let arcs: Vec<_> = smoothed
+        .segments
+        .iter()
+        .filter(|s| s.segment_type != ToolpathSegmentType::LinearMove)
+        .collect();
+    assert_eq!(arcs.len(), 1);
+    assert_eq!(arcs[0].segment_type, ToolpathSegmentType::ArcCW);
+    assert_eq!(arcs[0].feed_rate, 800.0);
+
+    // No deviation, no change
+    let (unchanged, report) = smooth_corners(&toolpath, &CornerSmoothing::new(0.0));
+    assert_eq!(unchanged.segments.len(), toolpath.segments.len());
+    assert_eq!(report.rounded, 0);
+}
+
+#[test]
+fn test_trochoidal_straight_slot_has_evenly_spaced_loops() {
+    use gcodekit5_designer::model::Point;
+    use gcodekit5_designer::toolpath::ToolpathSegmentType;
+    use gcodekit5_designer::{trochoidal_segments, TrochoidalParams};
+
+    let params = TrochoidalParams::new(10.0, 6.0, 0.5);
+    let centerline = [Point::new(0.0, 0.0), Point::new(5.0, 0.0)];
+    let segments = trochoidal_segments(&centerline, &params, 500.0, 10000).unwrap();
+
+    let centers: Vec<Point> = segments
+        .iter()
+        .filter(|s| s.segment_type == ToolpathSegmentType::ArcCCW)
+        .filter_map(|s| s.center)
+        .collect();
+    // Two half-circle arcs per loop, one loop every 0.5mm including both ends
+    assert_eq!(centers.len(), 22);
+    for (i, pair) in centers.chunks(2).enumerate() {
+        assert_eq!(pair[0], pair[1]);
+        assert!((pair[0].x - i as f64 * 0.5).abs() < 1e-9);
+        assert!(pair[0].y.abs() < 1e-9);
+    }
+    // Every arc keeps the tool inside the 10mm slot
+    for segment in &segments {
+        assert!(segment.start.y.abs() <= 2.0 + 1e-9);
+        if let Some(center) = segment.center {
+            assert!((center.distance_to(&segment.start) - 2.0).abs() < 1e-9);
+        }
+    }
+}
+
+#[test]
+fn test_trochoidal_limits_engagement_and_blends_corners() {
+    use gcodekit5_designer::model::Point;
+    use gcodekit5_designer::toolpath::ToolpathSegmentType;
+    use gcodekit5_designer::{trochoidal_segments, TrochoidalParams};
+
+    let mut params = TrochoidalParams::new(10.0, 6.0, 2.0);
+    params.max_engagement_degrees = 45.0;
+    // acos(1 - s / 3) = 45 degrees
+    let expected = 3.0 * (1.0 - 45f64.to_radians().cos());
+    assert!((params.effective_stepover() - expected).abs() < 1e-9);
+
+    // Links between loops stay short around a right-angle corner
+    params.max_engagement_degrees = 60.0;
+    params.stepover = 0.5;
+    let centerline = [
+        Point::new(0.0, 0.0),
+        Point::new(10.0, 0.0),
+        Point::new(10.0, 10.0),
+    ];
+    let segments = trochoidal_segments(&centerline, &params, 500.0, 10000).unwrap();
+    let longest_link = segments
+        .iter()
+        .filter(|s| s.segment_type == ToolpathSegmentType::LinearMove)
+        .map(|s| s.start.distance_to(&s.end))
+        .fold(0.0, f64::max);
+    assert!(longest_link < 1.5, "link of {longest_link}mm at the corner");
+}
+
+#[test]
+fn test_trochoidal_rejects_loops_wider_than_the_slot() {
+    use gcodekit5_designer::error::ToolpathError;
+    use gcodekit5_designer::model::Point;
+    use gcodekit5_designer::toolpath::ToolpathGenerator;
+    use gcodekit5_designer::TrochoidalParams;
+
+    let centerline = [Point::new(0.0, 0.0), Point::new(20.0, 0.0)];
+    let mut params = TrochoidalParams::new(10.0, 6.0, 0.5);
+    params.loop_radius = 2.5;
+    let gen = ToolpathGenerator::new();
+    assert!(matches!(
+        gen.generate_trochoidal_slot(&centerline, &params, 0.0),
+        Err(ToolpathError::InvalidParameters(_))
+    ));
+    assert!(matches!(
+        gen.generate_trochoidal_slot(&centerline, &TrochoidalParams::new(6.0, 6.0, 0.5), 0.0),
+        Err(ToolpathError::ToolTooLarge { .. })
+    ));
+
+    let toolpaths = gen
+        .generate_trochoidal_slot(&centerline, &TrochoidalParams::new(10.0, 6.0, 0.5), 0.0)
+        .unwrap();
+    assert!(!toolpaths.is_empty());
+}
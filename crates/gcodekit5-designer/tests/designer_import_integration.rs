@@ -1,6 +1,7 @@
 // Integration tests for Designer file import functionality (Phase 4.1)
 
-use gcodekit5_designer::{DxfImporter, FileFormat, SvgImporter};
+use gcodekit5_designer::shapes::OperationType;
+use gcodekit5_designer::{DesignerState, DxfImporter, FileFormat, SvgImporter};
 
 #[test]
 fn test_svg_importer_basic_creation() {
@@ -400,3 +401,77 @@ EOF"#;
     // Polyline is converted to a single PathShape
     assert_eq!(design.shapes.len(), 1);
 }
+
+fn dxf_line(layer: &str, x: f64) -> String {
+    format!("0\nLINE\n8\n{layer}\n10\n{x}\n20\n0.0\n11\n{x}\n21\n10.0\n")
+}
+
+#[test]
+fn test_svg_import_tags_shapes_with_layers() {
+    let svg = r#"<svg xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" width="100" height="100">
+    <g id="layer1" inkscape:groupmode="layer" inkscape:label="Engrave">
+        <g id="detail"><circle cx="20" cy="20" r="5"/></g>
+    </g>
+    <rect x="0" y="0" width="5" height="5"/>
+    <g inkscape:groupmode="layer" id="cut">
+        <rect x="10" y="10" width="50" height="50"/>
+    </g>
+</svg>"#;
+    let design = SvgImporter::new(1.0, 0.0, 0.0)
+        .import_string(svg)
+        .expect("import failed");
+    assert_eq!(design.layers, vec!["Engrave", "cut"]);
+    assert_eq!(design.layer_count, 2);
+    // Rectangles are read before circles
+    assert_eq!(design.shape_layers, vec![None, Some(1), Some(0)]);
+    assert_eq!(design.shape_layer(2), Some("Engrave"));
+
+    let plan = design.layer_plan();
+    let names: Vec<(&str, usize)> = plan
+        .iter()
+        .map(|entry| (entry.name.as_str(), entry.shape_count))
+        .collect();
+    assert_eq!(names, vec![("Engrave", 1), ("cut", 1), ("", 1)]);
+}
+
+#[test]
+fn test_dxf_layers_are_imported_in_plan_order() {
+    let dxf = format!(
+        "0\nSECTION\n2\nENTITIES\n{}{}{}0\nENDSEC\n0\nEOF",
+        dxf_line("Cut", 0.0),
+        dxf_line("Engrave", 5.0),
+        dxf_line("Cut", 10.0)
+    );
+    let design = DxfImporter::new(1.0, 0.0, 0.0)
+        .import_string(&dxf)
+        .expect("import failed");
+    assert_eq!(design.layers, vec!["Cut", "Engrave"]);
+    assert_eq!(design.shape_layers, vec![Some(0), Some(1), Some(0)]);
+
+    // Engrave first, then cut the outlines as pockets
+    let mut plan = design.layer_plan();
+    plan.swap(0, 1);
+    plan[1].operation = OperationType::Pocket;
+    plan[1].cut_depth = Some(3.0);
+
+    let mut state = DesignerState::new();
+    let ids = state.add_imported_design(&design, &plan);
+    assert_eq!(ids.len(), 3);
+    let generation_order: Vec<u64> = state.canvas.shape_store.draw_order_iter().rev().collect();
+    assert_eq!(generation_order, ids);
+
+    let objects: Vec<_> = ids
+        .iter()
+        .map(|id| state.canvas.shape_store.get(*id).expect("shape added"))
+        .collect();
+    assert_eq!(objects[0].name, "Engrave");
+    assert_eq!(objects[0].group_id, None);
+    assert_eq!(objects[1].operation_type, OperationType::Pocket);
+    assert_eq!(objects[1].cut_depth, Some(3.0));
+    assert!(objects[1].group_id.is_some());
+    assert_eq!(objects[1].group_id, objects[2].group_id);
+
+    // One undo removes the whole import
+    state.undo();
+    assert_eq!(state.canvas.shape_count(), 0);
+}
@@ -6,6 +6,8 @@ mod arrays;
 mod drilling_patterns;
 #[path = "features/gcode_snapshots.rs"]
 mod gcode_snapshots;
+#[path = "features/keep_out.rs"]
+mod keep_out;
 #[path = "features/multipass.rs"]
 mod multipass;
 #[path = "features/parametric.rs"]
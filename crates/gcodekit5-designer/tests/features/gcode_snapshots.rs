@@ -108,11 +108,13 @@ fn snapshot_rectangle_contour_3d() {
 
     // Verify body contains expected moves
     assert!(output.contains("G00 X10.000 Y10.000 Z5.000"));
+    // F is modal: set once on the plunge, not repeated on the cuts
     assert!(output.contains("G01 Z-2.000 F500"));
-    assert!(output.contains("G01 X50.000 Y10.000 F500"));
-    assert!(output.contains("G01 X50.000 Y40.000 F500"));
-    assert!(output.contains("G01 X10.000 Y40.000 F500"));
-    assert!(output.contains("G01 X10.000 Y10.000 F500"));
+    assert!(output.contains("G01 X50.000 Y10.000\n"));
+    assert!(output.contains("G01 X50.000 Y40.000\n"));
+    assert!(output.contains("G01 X10.000 Y40.000\n"));
+    assert!(output.contains("G01 X10.000 Y10.000\n"));
+    assert_eq!(output.matches(" F500").count(), 1);
 
     // Verify footer
     assert!(output.contains("M5"));
@@ -0,0 +1,181 @@
+use gcodekit5_designer::designer_state::DesignerState;
+use gcodekit5_designer::keep_out::{KeepOutChecker, KeepOutRegion, KeepOutViolation};
+use gcodekit5_designer::toolpath::{Toolpath, ToolpathSegment, ToolpathSegmentType};
+use gcodekit5_designer::toolpath_simulation::ToolpathAnalyzer;
+use gcodekit5_designer::Point;
+
+fn segment(kind: ToolpathSegmentType, start: (f64, f64), end: (f64, f64)) -> ToolpathSegment {
+    ToolpathSegment::new(
+        kind,
+        Point::new(start.0, start.1),
+        Point::new(end.0, end.1),
+        100.0,
+        10000,
+    )
+}
+
+fn clamp() -> KeepOutRegion {
+    KeepOutRegion::rectangle(
+        7,
+        "Clamp",
+        Point::new(40.0, 40.0),
+        Point::new(60.0, 60.0),
+        15.0,
+    )
+}
+
+#[test]
+fn test_cut_through_footprint_is_flagged() {
+    let mut toolpath = Toolpath::new(6.0, -2.0);
+    toolpath.add_segment(segment(
+        ToolpathSegmentType::LinearMove,
+        (0.0, 50.0),
+        (100.0, 50.0),
+    ));
+    toolpath.add_segment(segment(
+        ToolpathSegmentType::LinearMove,
+        (0.0, 10.0),
+        (100.0, 10.0),
+    ));
+    // Passes 2 mm from the clamp edge, inside the 3 mm tool radius
+    toolpath.add_segment(segment(
+        ToolpathSegmentType::LinearMove,
+        (0.0, 62.0),
+        (100.0, 62.0),
+    ));
+
+    let checker = KeepOutChecker::new(&[clamp()]);
+    let collisions = checker.check(&toolpath, 20.0);
+
+    let indices: Vec<usize> = collisions.iter().map(|c| c.segment_index).collect();
+    assert_eq!(indices, vec![0, 2]);
+    assert!(collisions
+        .iter()
+        .all(|c| c.region_id == 7 && c.violation == KeepOutViolation::Cut && c.z == -2.0));
+}
+
+#[test]
+fn test_cut_above_fixture_and_high_rapids_pass() {
+    let mut toolpath = Toolpath::new(3.0, -2.0);
+    toolpath.add_segment(segment(
+        ToolpathSegmentType::RapidMove,
+        (0.0, 50.0),
+        (100.0, 50.0),
+    ));
+    toolpath.add_segment(
+        segment(ToolpathSegmentType::LinearMove, (0.0, 50.0), (100.0, 50.0)).with_z_depth(16.0),
+    );
+
+    let checker = KeepOutChecker::new(&[clamp()]);
+    assert!(checker.check(&toolpath, 20.0).is_empty());
+
+    let low = checker.check(&toolpath, 10.0);
+    assert_eq!(low.len(), 1);
+    assert_eq!(low[0].segment_index, 0);
+    assert_eq!(low[0].violation, KeepOutViolation::Rapid);
+}
+
+#[test]
+fn test_arcs_and_polygons() {
+    let triangle = KeepOutRegion::polygon(
+        1,
+        "Fixture",
+        vec![
+            Point::new(-5.0, 18.0),
+            Point::new(5.0, 18.0),
+            Point::new(0.0, 25.0),
+        ],
+        5.0,
+    );
+    // Quarter arc of radius 20 around the origin, from (20, 0) to (0, 20)
+    let mut ccw = Toolpath::new(1.0, -1.0);
+    ccw.add_segment(ToolpathSegment::new_arc(
+        ToolpathSegmentType::ArcCCW,
+        Point::new(20.0, 0.0),
+        Point::new(0.0, 20.0),
+        Point::new(0.0, 0.0),
+        100.0,
+        10000,
+    ));
+    // A clockwise quarter arc through the third quadrant stays clear
+    let mut cw = Toolpath::new(1.0, -1.0);
+    cw.add_segment(ToolpathSegment::new_arc(
+        ToolpathSegmentType::ArcCW,
+        Point::new(0.0, -20.0),
+        Point::new(-20.0, 0.0),
+        Point::new(0.0, 0.0),
+        100.0,
+        10000,
+    ));
+
+    let checker = KeepOutChecker::new(&[triangle]);
+    assert_eq!(checker.check(&ccw, 10.0).len(), 1);
+    assert!(checker.check(&cw, 10.0).is_empty());
+
+    let analyzer = ToolpathAnalyzer::new(ccw);
+    assert_eq!(analyzer.keep_out_collisions(&checker, 10.0).len(), 1);
+}
+
+#[test]
+fn test_designer_flags_collisions_on_generation() {
+    let mut state = DesignerState::new();
+    let shape_id = state.canvas.add_rectangle(0.0, 0.0, 100.0, 100.0);
+
+    state.generate_gcode();
+    assert!(state.keep_out_collisions.is_empty());
+
+    state.add_keep_out_region(KeepOutRegion::rectangle(
+        1,
+        "Clamp",
+        Point::new(90.0, 40.0),
+        Point::new(110.0, 60.0),
+        20.0,
+    ));
+    state.generate_gcode();
+    assert!(!state.keep_out_collisions.is_empty());
+    assert!(state
+        .keep_out_collisions
+        .iter()
+        .all(|c| c.shape_id == shape_id && c.collision.region_id == 1));
+
+    assert!(state.remove_keep_out_region(1).is_some());
+    state.generate_gcode();
+    assert!(state.keep_out_collisions.is_empty());
+}
+
+#[test]
+fn test_device_no_go_regions_join_the_check() {
+    use gcodekit5_designer::keep_out::DEVICE_REGION_ID_BASE;
+    use gcodekit5_devicedb::{RegionShape, WorkRegion};
+
+    let region = |name: &str, no_go: bool, min: [f64; 2], max: [f64; 2]| WorkRegion {
+        name: name.to_string(),
+        shape: RegionShape::Rectangle { min, max },
+        color: String::new(),
+        no_go,
+        height: None,
+    };
+    let mut state = DesignerState::new();
+    state.canvas.add_rectangle(0.0, 0.0, 100.0, 100.0);
+    state.add_keep_out_region(clamp());
+
+    // Only the no-go zone is checked, at every height, next to the design's own regions
+    let regions = [
+        region("Clamping area", false, [-10.0, -10.0], [110.0, 110.0]),
+        region("Limit switch", true, [95.0, 95.0], [120.0, 120.0]),
+    ];
+    state.set_device_regions(&regions);
+    assert_eq!(state.keep_out_regions.len(), 2);
+    state.generate_gcode();
+    assert!(!state.keep_out_collisions.is_empty());
+    assert!(state
+        .keep_out_collisions
+        .iter()
+        .all(|c| c.collision.region_id == DEVICE_REGION_ID_BASE + 1));
+
+    // A new profile replaces the device regions and keeps the design's
+    state.set_device_regions(&[]);
+    assert_eq!(state.keep_out_regions, vec![clamp()]);
+    state.generate_gcode();
+    assert!(state.keep_out_collisions.is_empty());
+}
@@ -1,5 +1,7 @@
 use gcodekit5_designer::model::Point;
-use gcodekit5_designer::multipass::{DepthStrategy, MultiPassConfig, MultiPassToolpathGenerator};
+use gcodekit5_designer::multipass::{
+    DepthStrategy, FinishingPass, MultiPassConfig, MultiPassToolpathGenerator,
+};
 use gcodekit5_designer::toolpath::{Toolpath, ToolpathSegment, ToolpathSegmentType};
 
 #[test]
@@ -67,3 +69,91 @@ fn test_spiral_ramp_generation() {
     let toolpath = gen.generate_spiral_ramp(Point::new(50.0, 50.0), 10.0, -10.0, 100.0);
     assert!(toolpath.segments.len() > 0);
 }
+
+fn flat_toolpath(points: &[Point]) -> Toolpath {
+    let mut toolpath = Toolpath::new(3.175, -1.0);
+    toolpath.add_segment(ToolpathSegment::new(
+        ToolpathSegmentType::RapidMove,
+        Point::new(0.0, 0.0),
+        points[0],
+        800.0,
+        12000,
+    ));
+    for pair in points.windows(2) {
+        toolpath.add_segment(ToolpathSegment::new(
+            ToolpathSegmentType::LinearMove,
+            pair[0],
+            pair[1],
+            800.0,
+            12000,
+        ));
+    }
+    toolpath
+}
+
+#[test]
+fn test_pass_levels_end_at_total_depth() {
+    let config = MultiPassConfig::new(-3.0, 1.0);
+    assert_eq!(config.pass_levels(), vec![-1.0, -2.0, -3.0]);
+
+    let mut ramped = MultiPassConfig::new(-5.0, 2.0);
+    ramped.set_strategy(DepthStrategy::Ramped);
+    ramped.set_minimum_depth(0.5);
+    let levels = ramped.pass_levels();
+    assert_eq!(levels.last(), Some(&-5.0));
+    assert!(levels
+        .windows(2)
+        .all(|w| w[1] < w[0] && w[0] - w[1] <= 2.0 + 1e-9));
+}
+
+#[test]
+fn test_wrap_closed_loop_stays_down_with_finishing_pass() {
+    let square = [
+        Point::new(0.0, 0.0),
+        Point::new(10.0, 0.0),
+        Point::new(10.0, 10.0),
+        Point::new(0.0, 10.0),
+        Point::new(0.0, 0.0),
+    ];
+    let mut config = MultiPassConfig::new(-3.0, 1.5);
+    config.set_finishing_pass(Some(FinishingPass {
+        allowance: 0.5,
+        feed_rate: Some(200.0),
+    }));
+    let wrapped = MultiPassToolpathGenerator::new(config).wrap_toolpath(&flat_toolpath(&square));
+
+    // One rapid in, then two roughing levels and the finishing level, no retracts
+    assert_eq!(wrapped.segments.len(), 1 + 3 * 4);
+    let rapids = wrapped
+        .segments
+        .iter()
+        .filter(|s| s.segment_type == ToolpathSegmentType::RapidMove)
+        .count();
+    assert_eq!(rapids, 1);
+    let levels: Vec<f64> = wrapped.segments[1..]
+        .chunks(4)
+        .map(|level| level[0].z_depth.unwrap())
+        .collect();
+    assert_eq!(levels, vec![-1.25, -2.5, -3.0]);
+    assert!(wrapped.segments[9..].iter().all(|s| s.feed_rate == 200.0));
+    assert!(wrapped.segments[1..9].iter().all(|s| s.feed_rate == 800.0));
+}
+
+#[test]
+fn test_wrap_open_path_retracts_between_levels() {
+    let line = [
+        Point::new(0.0, 0.0),
+        Point::new(10.0, 0.0),
+        Point::new(10.0, 5.0),
+    ];
+    let config = MultiPassConfig::new(-2.0, 1.0);
+    let wrapped = MultiPassToolpathGenerator::new(config).wrap_toolpath(&flat_toolpath(&line));
+
+    let types: Vec<_> = wrapped.segments.iter().map(|s| s.segment_type).collect();
+    use ToolpathSegmentType::{LinearMove as L, RapidMove as R};
+    assert_eq!(types, vec![R, L, L, R, L, L]);
+    // The link goes back from the path's end to its start
+    assert_eq!(wrapped.segments[3].start, Point::new(10.0, 5.0));
+    assert_eq!(wrapped.segments[3].end, Point::new(0.0, 0.0));
+    assert_eq!(wrapped.segments[5].z_depth, Some(-2.0));
+}
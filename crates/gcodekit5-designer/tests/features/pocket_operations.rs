@@ -1,7 +1,8 @@
 use gcodekit5_designer::pocket_operations::{
     Island, PocketGenerator, PocketOperation, PocketStrategy,
 };
-use gcodekit5_designer::{Circle, Point, Rectangle};
+use gcodekit5_designer::toolpath::ToolpathSegmentType;
+use gcodekit5_designer::{rest_regions, Circle, Point, Rectangle, RestSource};
 
 fn min_start_distance_to_center(
     toolpaths: &[gcodekit5_designer::toolpath::Toolpath],
@@ -164,3 +165,86 @@ fn test_concave_union_cleanup() {
         min_dist
     );
 }
+
+fn square(size: f64) -> Vec<Point> {
+    vec![
+        Point::new(0.0, 0.0),
+        Point::new(size, 0.0),
+        Point::new(size, size),
+        Point::new(0.0, size),
+    ]
+}
+
+#[test]
+fn test_rest_machining_clears_only_corners_left_by_larger_tool() {
+    let rest = RestSource::PreviousTool { diameter: 10.0 };
+    let regions = rest_regions(&square(40.0), &rest, 3.0);
+    assert_eq!(regions.len(), 4);
+
+    let mut op = PocketOperation::new("rest".to_string(), -2.0, 3.0);
+    op.set_parameters(1.0, 500.0, 12000);
+    op.set_rest_machining(Some(rest));
+    let gen = PocketGenerator::new(op);
+    let toolpaths = gen.generate_polygon_pocket(&square(40.0), 1.0);
+    assert_eq!(toolpaths.len(), 2);
+
+    let corners = square(40.0);
+    for seg in toolpaths.iter().flat_map(|tp| tp.segments.iter()) {
+        if seg.segment_type == ToolpathSegmentType::RapidMove {
+            continue;
+        }
+        // Every cut stays in a corner the 10 mm tool could not reach
+        let near_corner = corners
+            .iter()
+            .map(|c| c.distance_to(&seg.end))
+            .fold(f64::INFINITY, f64::min);
+        assert!(
+            near_corner < 5.0,
+            "cut at {:?} is outside the corners",
+            seg.end
+        );
+        assert!(seg.end.x >= 1.5 - 1e-6 && seg.end.x <= 38.5 + 1e-6);
+        assert!(seg.end.y >= 1.5 - 1e-6 && seg.end.y <= 38.5 + 1e-6);
+    }
+}
+
+#[test]
+fn test_rest_machining_is_empty_when_nothing_is_left() {
+    // A tool no smaller than the previous one has nothing to add
+    let rest = RestSource::PreviousTool { diameter: 3.0 };
+    assert!(rest_regions(&square(40.0), &rest, 3.0).is_empty());
+
+    // A circle has no corners, so a tool smaller than it clears it completely
+    let mut op = PocketOperation::new("rest".to_string(), -2.0, 3.0);
+    op.set_rest_machining(Some(RestSource::PreviousTool { diameter: 10.0 }));
+    let gen = PocketGenerator::new(op);
+    let circle = Circle {
+        center: Point::new(50.0, 50.0),
+        radius: 25.0,
+        rotation: 0.0,
+    };
+    assert!(gen.generate_circular_pocket(&circle, 1.0).is_empty());
+}
+
+#[test]
+fn test_rest_machining_from_remaining_stock() {
+    let stock = vec![vec![
+        Point::new(18.0, 18.0),
+        Point::new(22.0, 18.0),
+        Point::new(22.0, 22.0),
+        Point::new(18.0, 22.0),
+    ]];
+    let rest = RestSource::RemainingStock(stock);
+    let regions = rest_regions(&square(40.0), &rest, 2.0);
+    assert_eq!(regions.len(), 1);
+
+    let mut op = PocketOperation::new("rest".to_string(), -1.0, 2.0);
+    op.set_strategy(PocketStrategy::Adaptive);
+    op.set_rest_machining(Some(rest));
+    let toolpaths = PocketGenerator::new(op).generate_polygon_pocket(&square(40.0), 0.0);
+    assert_eq!(toolpaths.len(), 1);
+    let center = Point::new(20.0, 20.0);
+    for seg in &toolpaths[0].segments {
+        assert!(seg.end.distance_to(&center) < 2.0 * 2.0_f64.sqrt() + 1.0 + 1e-6);
+    }
+}
@@ -1,4 +1,11 @@
-use gcodekit5_designer::tool_library::{Tool, ToolLibrary, ToolType};
+use gcodekit5_designer::adaptive::MaterialType;
+use gcodekit5_designer::drilling_patterns::DrillOperation;
+use gcodekit5_designer::pocket_operations::PocketOperation;
+use gcodekit5_designer::tool_library::{
+    scallop_height, stepover_for_scallop, CutRecipe, RecipeStore, StepoverSpec, Tool, ToolLibrary,
+    ToolType,
+};
+use gcodekit5_designer::ToolpathGenerator;
 
 #[test]
 fn test_tool_creation() {
@@ -82,3 +89,134 @@ fn test_tool_type_names() {
     assert_eq!(ToolType::VBit.name(), "V-Bit");
     assert_eq!(ToolType::Drill.name(), "Drill");
 }
+
+#[test]
+fn test_recipe_lookup_prefers_stored_recipe() {
+    let mut library = ToolLibrary::with_defaults();
+    let tuned = CutRecipe::new(900.0, 300.0, 18000, 1.2, 1.5);
+    library
+        .recipes_mut()
+        .set("em_125", MaterialType::Aluminum, tuned);
+
+    let stored = library
+        .recipe_for("em_125", MaterialType::Aluminum)
+        .unwrap();
+    assert!(!stored.estimated);
+    assert_eq!(stored.recipe, tuned);
+
+    let estimated = library.recipe_for("em_125", MaterialType::Wood).unwrap();
+    assert!(estimated.estimated);
+    assert!(estimated.recipe.feed_rate > 0.0);
+    assert!(estimated.recipe.plunge_rate < estimated.recipe.feed_rate);
+    assert!((1000..=24000).contains(&estimated.recipe.spindle_speed));
+
+    assert!(library.recipe_for("missing", MaterialType::Wood).is_none());
+}
+
+#[test]
+fn test_recipe_estimate_is_gentler_in_harder_materials() {
+    let library = ToolLibrary::with_defaults();
+    let tool = library.get_tool("em_125").unwrap();
+    let wood = CutRecipe::estimate(tool, MaterialType::Wood);
+    let steel = CutRecipe::estimate(tool, MaterialType::Steel);
+
+    assert!(steel.feed_rate < wood.feed_rate);
+    assert!(steel.depth_per_pass < wood.depth_per_pass);
+}
+
+#[test]
+fn test_recipe_store_json_round_trip_and_import() {
+    let mut store = RecipeStore::new();
+    store.set(
+        "em_125",
+        MaterialType::Plastic,
+        CutRecipe::new(1200.0, 400.0, 16000, 1.5, 2.0),
+    );
+    store.set(
+        "vbit_90",
+        MaterialType::Wood,
+        CutRecipe::new(1500.0, 500.0, 18000, 0.5, 1.0),
+    );
+
+    let json = store.to_json().unwrap();
+    let restored: RecipeStore = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, store);
+
+    let mut shop = RecipeStore::new();
+    shop.set(
+        "em_125",
+        MaterialType::Plastic,
+        CutRecipe::new(800.0, 200.0, 12000, 1.0, 1.0),
+    );
+    assert_eq!(shop.import_json(&json, false).unwrap(), 2);
+    assert_eq!(shop.len(), 2);
+    assert_eq!(
+        shop.get("em_125", MaterialType::Plastic).unwrap().feed_rate,
+        800.0
+    );
+
+    shop.import_json(&json, true).unwrap();
+    assert_eq!(
+        shop.get("em_125", MaterialType::Plastic).unwrap().feed_rate,
+        1200.0
+    );
+
+    shop.get_mut("vbit_90", MaterialType::Wood)
+        .unwrap()
+        .spindle_speed = 20000;
+    assert_eq!(
+        shop.get("vbit_90", MaterialType::Wood)
+            .unwrap()
+            .spindle_speed,
+        20000
+    );
+    assert!(shop.import_json("not json", true).is_err());
+}
+
+#[test]
+fn test_operations_take_recipe_defaults() {
+    let library = ToolLibrary::with_defaults();
+
+    let resolved = library
+        .recipe_for("em_125", MaterialType::Aluminum)
+        .unwrap();
+    let mut pocket = PocketOperation::new("pocket".to_string(), -3.0, 3.175);
+    pocket.apply_recipe(&resolved);
+    assert_eq!(pocket.feed_rate, resolved.recipe.feed_rate);
+    assert_eq!(pocket.spindle_speed, resolved.recipe.spindle_speed);
+    assert_eq!(pocket.stepover, resolved.recipe.stepover);
+    assert!(pocket.parameters_estimated);
+
+    let resolved = library.recipe_for("drill_32", MaterialType::Brass).unwrap();
+    let mut drill = DrillOperation::new("drill".to_string(), 3.175, 3.175, -5.0);
+    drill.apply_recipe(&resolved);
+    assert_eq!(drill.plunge_rate, resolved.recipe.plunge_rate);
+    assert!(drill.parameters_estimated);
+}
+
+#[test]
+fn test_stepover_from_scallop_height() {
+    // 6 mm ball-nose, 0.01 mm scallop: 2 * sqrt(2 * 3 * 0.01 - 0.0001)
+    let stepover = stepover_for_scallop(3.0, 0.01);
+    assert!((stepover - 0.4894).abs() < 1e-4);
+    assert!((scallop_height(3.0, stepover) - 0.01).abs() < 1e-9);
+
+    // Clamped to the tool diameter
+    assert_eq!(stepover_for_scallop(3.0, 5.0), 6.0);
+    assert_eq!(StepoverSpec::Distance(8.0).resolve(6.0), 6.0);
+    assert_eq!(scallop_height(3.0, 10.0), 3.0);
+    assert_eq!(stepover_for_scallop(3.0, 0.0), 0.0);
+
+    let mut pocket = PocketOperation::new("finish".to_string(), -1.0, 6.0);
+    pocket.set_stepover_spec(StepoverSpec::ScallopHeight(0.01));
+    assert!((pocket.stepover - stepover).abs() < 1e-12);
+    assert!((pocket.scallop_height() - 0.01).abs() < 1e-9);
+    pocket.set_stepover_spec(StepoverSpec::Distance(1.0));
+    assert_eq!(pocket.stepover, 1.0);
+    assert!((pocket.scallop_height() - (3.0 - (9.0f64 - 0.25).sqrt())).abs() < 1e-12);
+
+    let mut gen = ToolpathGenerator::new();
+    gen.set_tool_diameter(6.0);
+    gen.set_stepover_spec(StepoverSpec::ScallopHeight(0.05));
+    assert!((gen.scallop_height() - 0.05).abs() < 1e-9);
+}
@@ -1,5 +1,9 @@
+use gcodekit5_core::Units;
 use gcodekit5_designer::model::Point;
-use gcodekit5_designer::vcarve::{VBitTool, VCarveGenerator, VCarveParams, VCarveSegment};
+use gcodekit5_designer::vcarve::{
+    StrokeCarveParams, StrokePoint, VBitTool, VCarveGenerator, VCarveParams, VCarveSegment,
+};
+use gcodekit5_designer::{ToolpathSegmentType, ToolpathToGcode};
 
 #[test]
 fn test_vbit_creation() {
@@ -229,3 +233,50 @@ fn test_validate_params_invalid_width() {
     let result = VCarveGenerator::validate_params(&params);
     assert!(result.is_err());
 }
+
+#[test]
+fn test_stroke_width_maps_to_clamped_depth_with_lifts() {
+    let params = StrokeCarveParams::new(VBitTool::v90(6.35), 1.5, 18000, 600.0, 200.0);
+    let stroke: Vec<StrokePoint> = [0.0, 2.0, 4.0, 0.0, 0.0, 1.0]
+        .iter()
+        .enumerate()
+        .map(|(i, &width)| StrokePoint::new(Point::new(i as f64, 0.0), width))
+        .collect();
+
+    let toolpath = VCarveGenerator::generate_stroke_toolpath(&params, &[stroke]).unwrap();
+    let round = |v: f64| (v * 1e6).round() / 1e6;
+    let moves: Vec<(ToolpathSegmentType, f64, Option<f64>, Option<f64>)> = toolpath
+        .segments
+        .iter()
+        .map(|s| {
+            let z = (s.start_z.map(round), s.z_depth.map(round));
+            (s.segment_type, round(s.end.x), z.0, z.1)
+        })
+        .collect();
+    let rapid = ToolpathSegmentType::RapidMove;
+    let cut = ToolpathSegmentType::LinearMove;
+    assert_eq!(
+        moves,
+        vec![
+            (rapid, 0.0, None, None),
+            (cut, 1.0, Some(0.0), Some(-1.0)),
+            // The clamp is reached halfway to the widest sample and left a
+            // quarter of the way back down to zero width
+            (cut, 1.5, None, Some(-1.5)),
+            (cut, 2.0, None, Some(-1.5)),
+            (cut, 2.25, None, Some(-1.5)),
+            (cut, 3.0, None, Some(0.0)),
+            // Zero width at both ends is lifted over, not cut
+            (rapid, 4.0, None, None),
+            (cut, 5.0, Some(0.0), Some(-0.5)),
+        ]
+    );
+
+    let gcode = ToolpathToGcode::new(Units::MM, 5.0).generate_body(&toolpath, 1);
+    assert!(gcode.contains("G01 X1.500 Y0.000 Z-1.500\nG01 X2.000 Y0.000\n"));
+    assert!(gcode.contains("G01 X3.000 Y0.000 Z0.000\n"));
+    assert!(gcode.contains("G00 Z5.000\nG00 X4.000 Y0.000 Z5.000\nG01 Z0.000 F200"));
+
+    let too_short = vec![vec![StrokePoint::new(Point::new(0.0, 0.0), 1.0)]];
+    assert!(VCarveGenerator::generate_stroke_toolpath(&params, &too_short).is_err());
+}
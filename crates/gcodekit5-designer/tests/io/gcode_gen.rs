@@ -1,6 +1,9 @@
 use gcodekit5_core::Units;
-use gcodekit5_designer::gcode_gen::ToolpathToGcode;
-use gcodekit5_designer::toolpath::{Toolpath, ToolpathGenerator};
+use gcodekit5_designer::gcode_gen::{CoordinateSnap, EmitState, ToolpathToGcode};
+use gcodekit5_designer::model::Point;
+use gcodekit5_designer::toolpath::{
+    Toolpath, ToolpathGenerator, ToolpathSegment, ToolpathSegmentType,
+};
 use gcodekit5_designer::Rectangle;
 
 #[test]
@@ -70,3 +73,144 @@ fn test_rapid_moves_retract_before_xy() {
     let prev_line = lines[origin_idx - 1];
     assert!(prev_line.contains("G00 Z5.000"));
 }
+
+fn square_toolpath(feed: f64, plunge: Option<f64>, spindle: u32) -> Toolpath {
+    let corners = [
+        Point::new(0.0, 0.0),
+        Point::new(10.0, 0.0),
+        Point::new(10.0, 10.0),
+        Point::new(0.0, 10.0),
+        Point::new(0.0, 0.0),
+    ];
+    let mut toolpath = Toolpath::new(3.175, -1.0);
+    toolpath.add_segment(ToolpathSegment::new(
+        ToolpathSegmentType::RapidMove,
+        Point::new(0.0, 0.0),
+        corners[0],
+        feed,
+        spindle,
+    ));
+    for pair in corners.windows(2) {
+        toolpath.add_segment(ToolpathSegment::new(
+            ToolpathSegmentType::LinearMove,
+            pair[0],
+            pair[1],
+            feed,
+            spindle,
+        ));
+    }
+    toolpath.set_cutting_parameters(feed, plunge, spindle);
+    toolpath
+}
+
+#[test]
+fn test_plunge_uses_plunge_rate_and_feed_is_modal() {
+    let toolpath = square_toolpath(600.0, Some(150.0), 12000);
+    let gcode = ToolpathToGcode::new(Units::MM, 5.0).generate_body(&toolpath, 10);
+    let lines: Vec<&str> = gcode.lines().collect();
+
+    assert_eq!(lines[1], "G01 Z-1.000 F150");
+    assert_eq!(lines[2], "G01 X10.000 Y0.000 F600");
+    assert_eq!(lines[3], "G01 X10.000 Y10.000");
+    assert_eq!(gcode.matches('F').count(), 2);
+    // The header already started the spindle at the toolpath's speed
+    assert!(!gcode.contains("M3"));
+}
+
+#[test]
+fn test_state_carries_feed_and_spindle_between_toolpaths() {
+    let gcode_gen = ToolpathToGcode::new(Units::MM, 5.0);
+    let mut state = EmitState::new(5.0);
+    state.spindle_speed = Some(12000);
+
+    let first =
+        gcode_gen.generate_body_with_state(&square_toolpath(600.0, None, 12000), 10, &mut state);
+    state.z = 5.0;
+    let same =
+        gcode_gen.generate_body_with_state(&square_toolpath(600.0, None, 12000), 100, &mut state);
+    let changed =
+        gcode_gen.generate_body_with_state(&square_toolpath(400.0, None, 18000), 200, &mut state);
+
+    assert_eq!(first.matches(" F600").count(), 1);
+    assert!(!same.contains('F'));
+    assert!(!same.contains("M3"));
+    assert!(changed.starts_with("M3 S18000\n"));
+    assert_eq!(changed.matches(" F400").count(), 1);
+    assert_eq!(state.spindle_speed, Some(18000));
+}
+
+fn snapped_generator(grid: f64, snap_z: bool) -> ToolpathToGcode {
+    let mut gcode_gen = ToolpathToGcode::new(Units::MM, 5.0);
+    gcode_gen.snap = Some(CoordinateSnap {
+        grid_size: grid,
+        snap_z,
+    });
+    gcode_gen
+}
+
+fn axis_value(line: &str, axis: char) -> Option<f64> {
+    line.split_whitespace()
+        .find_map(|word| word.strip_prefix(axis))
+        .and_then(|v| v.parse().ok())
+}
+
+#[test]
+fn test_snap_rounds_absolute_positions_without_drift() {
+    let mut toolpath = Toolpath::new(3.175, -1.0);
+    // Many short moves of 0.33mm: snapping deltas would drift, snapping positions cannot
+    let mut x = 0.0;
+    for _ in 0..30 {
+        toolpath.add_segment(ToolpathSegment::new(
+            ToolpathSegmentType::LinearMove,
+            Point::new(x, 0.0),
+            Point::new(x + 0.33, 0.04),
+            200.0,
+            1000,
+        ));
+        x += 0.33;
+    }
+
+    let gcode = snapped_generator(0.1, false).generate_body(&toolpath, 10);
+    let last = gcode.lines().rfind(|l| l.starts_with("G01 X")).unwrap();
+    assert_eq!(axis_value(last, 'X'), Some(9.9));
+    assert_eq!(axis_value(last, 'Y'), Some(0.0));
+    // Z is left alone unless enabled
+    assert!(gcode.contains("Z-1.000"));
+
+    let snapped_z = snapped_generator(0.5, true).generate_body(&toolpath, 10);
+    assert!(snapped_z.contains("G01 Z-1.000"));
+    let mut toolpath_z = Toolpath::new(3.175, -1.3);
+    toolpath_z.add_segment(ToolpathSegment::new(
+        ToolpathSegmentType::LinearMove,
+        Point::new(0.0, 0.0),
+        Point::new(1.0, 0.0),
+        200.0,
+        1000,
+    ));
+    let snapped_z = snapped_generator(0.5, true).generate_body(&toolpath_z, 10);
+    assert!(snapped_z.contains("G01 Z-1.500"));
+}
+
+#[test]
+fn test_snap_keeps_arc_radius_valid() {
+    let mut toolpath = Toolpath::new(3.175, -1.0);
+    // Quarter arc of radius 5.03 around (0.04, 0.02): snapping endpoints alone
+    // would leave start and end at different distances from the snapped center
+    let center = Point::new(0.04, 0.02);
+    let start = Point::new(center.x + 5.03, center.y);
+    let end = Point::new(center.x, center.y + 5.03);
+    let mut arc = ToolpathSegment::new(ToolpathSegmentType::ArcCCW, start, end, 200.0, 1000);
+    arc.center = Some(center);
+    toolpath.add_segment(arc);
+
+    let gcode = snapped_generator(0.1, false).generate_body(&toolpath, 10);
+    let arc = gcode.lines().find(|l| l.starts_with("G03")).unwrap();
+    let (ex, ey) = (axis_value(arc, 'X').unwrap(), axis_value(arc, 'Y').unwrap());
+    let (i, j) = (axis_value(arc, 'I').unwrap(), axis_value(arc, 'J').unwrap());
+    let (sx, sy) = (5.1, 0.0);
+    let (cx, cy) = (sx + i, sy + j);
+    let start_radius = (sx - cx).hypot(sy - cy);
+    let end_radius = (ex - cx).hypot(ey - cy);
+    assert!((start_radius - end_radius).abs() < 0.005);
+    assert!((start_radius - 5.03).abs() < 0.2);
+}
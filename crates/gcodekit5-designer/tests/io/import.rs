@@ -79,3 +79,71 @@ fn test_dxf_import_framework() {
     let design = result.expect("result failed");
     assert_eq!(design.format, FileFormat::Dxf);
 }
+
+fn dxf_line(a: (f64, f64), b: (f64, f64)) -> String {
+    format!(
+        "0\nLINE\n8\n0\n10\n{}\n20\n{}\n11\n{}\n21\n{}\n",
+        a.0, a.1, b.0, b.1
+    )
+}
+
+#[test]
+fn test_repair_contours_joins_and_closes_within_tolerance() {
+    use gcodekit5_designer::model::Point;
+    use gcodekit5_designer::{repair_contours, Contour, ContourRepair};
+
+    let p = Point::new;
+    let contours = vec![
+        // A triangle drawn as three lines, one reversed and one short of its corner
+        Contour::open(vec![p(0.0, 0.0), p(10.0, 0.0)]),
+        Contour::open(vec![p(5.0, 8.0), p(10.0, 0.01)]),
+        Contour::open(vec![p(5.0, 8.0), p(0.0, 0.0)]),
+        // A U shape with its mouth 2mm wide
+        Contour::open(vec![p(20.0, 2.0), p(20.0, 0.0), p(30.0, 0.0), p(30.0, 2.0)]),
+        Contour::closed(vec![p(40.0, 0.0), p(41.0, 0.0), p(41.0, 1.0)]),
+    ];
+
+    let (repaired, report) = repair_contours(contours, &ContourRepair::new(0.05));
+    assert_eq!(repaired.len(), 3);
+    assert_eq!(report.joined, 2);
+    assert_eq!(report.closed, 1);
+    let triangle = repaired
+        .iter()
+        .find(|c| c.points.len() == 3 && c.points[0] == p(0.0, 0.0));
+    assert!(triangle.is_some_and(|c| c.closed));
+
+    assert!(!report.all_closed());
+    assert_eq!(report.gaps.len(), 1);
+    assert!((report.gaps[0].distance() - 10.0).abs() < 1e-9);
+    let errors = report.errors();
+    assert_eq!(
+        errors[0].to_string(),
+        "Open contour: 10.000mm gap between (20.000, 2.000) and (30.000, 2.000)"
+    );
+}
+
+#[test]
+fn test_dxf_import_repairs_open_outline() {
+    use gcodekit5_designer::ContourRepair;
+
+    // A rounded-end slot outline, its last line stopping 0.02 short of the start
+    let mut dxf = String::from("0\nSECTION\n2\nENTITIES\n");
+    dxf.push_str(&dxf_line((0.0, 0.0), (10.0, 0.0)));
+    dxf.push_str("0\nARC\n8\n0\n10\n10\n20\n5\n40\n5\n50\n-90\n51\n90\n");
+    dxf.push_str(&dxf_line((10.0, 10.0), (0.0, 10.0)));
+    dxf.push_str(&dxf_line((0.0, 0.02), (0.0, 10.0)));
+    dxf.push_str("0\nENDSEC\n0\nEOF\n");
+
+    let plain = DxfImporter::new(1.0, 0.0, 0.0).import_string(&dxf).unwrap();
+    assert_eq!(plain.shapes.len(), 4);
+    assert!(plain.repair_report.is_none());
+
+    let repaired = DxfImporter::new(1.0, 0.0, 0.0)
+        .with_repair(ContourRepair::default())
+        .import_string(&dxf)
+        .unwrap();
+    assert_eq!(repaired.shapes.len(), 1);
+    let report = repaired.repair_report.unwrap();
+    assert_eq!((report.joined, report.closed), (3, 1));
+    assert!(report.all_closed());
+}
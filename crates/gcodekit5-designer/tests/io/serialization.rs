@@ -29,6 +29,8 @@ fn test_save_and_load() {
         use_custom_values: false,
         operation_type: "profile".to_string(),
         pocket_depth: 0.0,
+        cut_depth: Some(None),
+        tabs: None,
         step_down: 0.0,
         step_in: 0.0,
         start_depth: 0.0,
@@ -45,6 +47,9 @@ fn test_save_and_load() {
         ramp_angle: 0.0,
         pocket_strategy: PocketStrategy::ContourParallel,
         raster_fill_ratio: 0.5,
+        feed_rate: None,
+        plunge_rate: None,
+        spindle_speed: None,
         sides: 0,
         teeth: 0,
         module: 0.0,
@@ -178,6 +183,8 @@ fn create_test_shape(id: i32, shape_type: &str) -> ShapeData {
         use_custom_values: false,
         operation_type: "profile".to_string(),
         pocket_depth: 0.0,
+        cut_depth: Some(None),
+        tabs: None,
         step_down: 0.0,
         step_in: 0.0,
         start_depth: 0.0,
@@ -194,6 +201,9 @@ fn create_test_shape(id: i32, shape_type: &str) -> ShapeData {
         ramp_angle: 0.0,
         pocket_strategy: PocketStrategy::ContourParallel,
         raster_fill_ratio: 0.5,
+        feed_rate: None,
+        plunge_rate: None,
+        spindle_speed: None,
         sides: if shape_type == "polygon" { 6 } else { 0 },
         teeth: 0,
         module: 0.0,
@@ -209,3 +219,25 @@ fn create_test_shape(id: i32, shape_type: &str) -> ShapeData {
         lock_aspect_ratio: true,
     }
 }
+
+#[test]
+fn test_cut_depth_migrates_from_pocket_depth() {
+    // Files from before per-shape depths have no cut depth field
+    let mut legacy = create_test_shape(1, "rectangle");
+    legacy.pocket_depth = 4.0;
+    legacy.cut_depth = None;
+    let obj = DesignFile::to_drawing_object(&legacy, 1).unwrap();
+    assert_eq!(obj.cut_depth, Some(4.0));
+
+    legacy.pocket_depth = 0.0;
+    let obj = DesignFile::to_drawing_object(&legacy, 1).unwrap();
+    assert_eq!(obj.cut_depth, None);
+
+    // A saved job default stays the job default even with a pocket depth
+    let mut current = create_test_shape(2, "rectangle");
+    current.pocket_depth = 4.0;
+    current.cut_depth = Some(None);
+    let obj = DesignFile::to_drawing_object(&current, 2).unwrap();
+    assert_eq!(obj.cut_depth, None);
+    assert_eq!(DesignFile::from_drawing_object(&obj).cut_depth, Some(None));
+}
@@ -68,6 +68,10 @@ pub enum ProfileError {
     /// A numeric value is out of valid range.
     #[error("Value out of range for '{field}': {value}")]
     ValueOutOfRange { field: String, value: String },
+
+    /// A preamble or postamble template cannot be expanded.
+    #[error("Invalid {field} template: {reason}")]
+    InvalidTemplate { field: String, reason: String },
 }
 
 /// Result type alias for device management operations.
@@ -7,11 +7,18 @@
 pub mod error;
 pub mod manager;
 pub mod model;
+pub mod program_template;
 pub mod traits;
 pub mod ui_integration;
 
 pub use error::{DeviceError, DeviceResult, ProfileError, ProfileResult};
 pub use manager::DeviceManager;
-pub use model::{AxisLimits, ControllerType, DeviceProfile, DeviceType};
+pub use model::{
+    AxisLimits, ControllerType, DeviceProfile, DeviceType, EndOfJob, EndOfJobPlan, LimitProximity,
+    ParkCoordinates, RegionShape, WorkRegion,
+};
+pub use program_template::{
+    ProgramTemplates, TemplateValue, TemplateValueType, TEMPLATE_VARIABLES,
+};
 pub use traits::DeviceProfileProvider;
 pub use ui_integration::{DeviceProfileUiModel, DeviceUiController};
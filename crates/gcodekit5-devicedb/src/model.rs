@@ -3,6 +3,8 @@
 //! Defines the data structures for CNC device profiles, including
 //! machine dimensions, firmware configuration, and connection settings.
 
+use crate::program_template::ProgramTemplates;
+use gcodekit5_core::format_gcode_number;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -71,6 +73,162 @@ impl Default for AxisLimits {
     }
 }
 
+/// How close a position is to the end of an axis's travel
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LimitProximity {
+    /// Further than the warning margin from both limits, or limits disabled
+    #[default]
+    Clear,
+    /// Within the warning margin of a limit
+    Near,
+    /// On a limit or beyond it
+    AtLimit,
+}
+
+/// Positions this close to a limit count as on it (mm)
+const AT_LIMIT_EPSILON: f64 = 1e-3;
+
+impl AxisLimits {
+    /// How close `position` (machine coordinates, mm) is to either limit, with
+    /// `margin` the distance at which it counts as near
+    pub fn proximity(&self, position: f64, margin: f64) -> LimitProximity {
+        if !self.enabled || !position.is_finite() {
+            return LimitProximity::Clear;
+        }
+        let distance = (position - self.min).min(self.max - position);
+        if distance <= AT_LIMIT_EPSILON {
+            LimitProximity::AtLimit
+        } else if distance <= margin {
+            LimitProximity::Near
+        } else {
+            LimitProximity::Clear
+        }
+    }
+}
+
+/// Outline of a work-area region, in the same coordinates as the axis limits
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RegionShape {
+    /// Axis-aligned rectangle between two corners
+    Rectangle { min: [f64; 2], max: [f64; 2] },
+    /// Closed polygon; the last point joins the first
+    Polygon { points: Vec<[f64; 2]> },
+}
+
+/// A named zone of the work area, such as a clamping area or the space
+/// around a limit switch
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkRegion {
+    pub name: String,
+    pub shape: RegionShape,
+    /// Overlay colour as `#rrggbb`; empty picks one by kind
+    #[serde(default)]
+    pub color: String,
+    /// Toolpaths must stay out of the region
+    #[serde(default)]
+    pub no_go: bool,
+    /// Top of whatever occupies the region (mm); moves above it may cross.
+    /// Unset means no height is safe.
+    #[serde(default)]
+    pub height: Option<f64>,
+}
+
+impl WorkRegion {
+    /// Corners of the outline, in order
+    pub fn outline(&self) -> Vec<[f64; 2]> {
+        match &self.shape {
+            RegionShape::Rectangle { min, max } => vec![
+                [min[0], min[1]],
+                [max[0], min[1]],
+                [max[0], max[1]],
+                [min[0], max[1]],
+            ],
+            RegionShape::Polygon { points } => points.clone(),
+        }
+    }
+
+    /// Overlay colour as RGB in 0..1: the configured one, else red for no-go
+    /// regions and blue for the rest
+    pub fn rgb(&self) -> [f64; 3] {
+        let hex = self.color.trim().trim_start_matches('#');
+        let channel = |i: usize| {
+            hex.get(i..i + 2)
+                .and_then(|c| u8::from_str_radix(c, 16).ok())
+                .map(|c| c as f64 / 255.0)
+        };
+        match (hex.len(), channel(0), channel(2), channel(4)) {
+            (6, Some(r), Some(g), Some(b)) => [r, g, b],
+            _ if self.no_go => [0.9, 0.2, 0.2],
+            _ => [0.2, 0.5, 0.9],
+        }
+    }
+
+    /// Where the label goes: the middle of the outline's bounds
+    pub fn label_position(&self) -> [f64; 2] {
+        let outline = self.outline();
+        if outline.is_empty() {
+            return [0.0, 0.0];
+        }
+        let (mut min, mut max) = ([f64::INFINITY; 2], [f64::NEG_INFINITY; 2]);
+        for point in &outline {
+            for axis in 0..2 {
+                min[axis] = min[axis].min(point[axis]);
+                max[axis] = max[axis].max(point[axis]);
+            }
+        }
+        [(min[0] + max[0]) / 2.0, (min[1] + max[1]) / 2.0]
+    }
+}
+
+/// Coordinates the end-of-job retract and park position are given in
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ParkCoordinates {
+    /// Machine coordinates, sent with `G53`
+    #[default]
+    Machine,
+    /// Coordinates of the active work offset
+    Work,
+}
+
+/// What the machine does once a job has completed cleanly
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct EndOfJob {
+    pub enabled: bool,
+    /// Turn the spindle or laser off first
+    pub power_off: bool,
+    pub coordinates: ParkCoordinates,
+    /// Z to retract to; unset retracts to the top of the Z limits
+    pub clearance_z: Option<f64>,
+    /// Move to the park position after retracting
+    pub park: bool,
+    pub park_x: f64,
+    pub park_y: f64,
+}
+
+impl Default for EndOfJob {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            power_off: true,
+            coordinates: ParkCoordinates::Machine,
+            clearance_z: None,
+            park: false,
+            park_x: 0.0,
+            park_y: 0.0,
+        }
+    }
+}
+
+/// Lines an end-of-job action sends, and why any of it was left out
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EndOfJobPlan {
+    pub commands: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct DeviceProfile {
@@ -111,6 +269,19 @@ pub struct DeviceProfile {
     /// Last known GRBL settings (from `$$`) for this profile (u16 to support grblHAL extended settings up to $680).
     #[serde(default)]
     pub grbl_settings: std::collections::HashMap<u16, String>,
+
+    /// Named zones drawn in the visualizer; no-go zones are also kept out of
+    /// designer toolpaths
+    #[serde(default)]
+    pub work_regions: Vec<WorkRegion>,
+
+    /// Retract and park once a job completes
+    #[serde(default)]
+    pub end_of_job: EndOfJob,
+
+    /// G-code written before and after generated programs
+    #[serde(default)]
+    pub program_templates: ProgramTemplates,
 }
 
 impl Default for DeviceProfile {
@@ -150,6 +321,111 @@ impl Default for DeviceProfile {
             timeout_ms: 5000,
             auto_reconnect: false,
             grbl_settings: std::collections::HashMap::new(),
+            work_regions: Vec::new(),
+            end_of_job: EndOfJob::default(),
+            program_templates: ProgramTemplates::default(),
+        }
+    }
+}
+
+impl DeviceProfile {
+    /// Proximity of a machine position to the X, Y and Z limits
+    pub fn limit_proximity(&self, position: [f64; 3], margin: f64) -> [LimitProximity; 3] {
+        [
+            self.x_axis.proximity(position[0], margin),
+            self.y_axis.proximity(position[1], margin),
+            self.z_axis.proximity(position[2], margin),
+        ]
+    }
+
+    /// Lines for the end-of-job action: power off, retract, then park.
+    ///
+    /// Positions are in mm, so the moves are preceded by G21.
+    /// `work_offset` is the active work offset in machine coordinates, needed
+    /// to check work-coordinate positions against the axis limits. A retract
+    /// beyond the Z limits is lowered to the nearest limit; a park position
+    /// outside the X or Y limits, or one that cannot be checked, is skipped.
+    pub fn end_of_job_commands(&self, work_offset: Option<[f64; 3]>) -> EndOfJobPlan {
+        let action = &self.end_of_job;
+        let mut plan = EndOfJobPlan::default();
+        if !action.enabled {
+            return plan;
+        }
+        if action.power_off {
+            plan.commands.push("M5".to_string());
+        }
+
+        let prefix = match action.coordinates {
+            ParkCoordinates::Machine => "G53 G0",
+            ParkCoordinates::Work => "G90 G0",
+        };
+        let to_machine = |axis: usize, value: f64| match action.coordinates {
+            ParkCoordinates::Machine => Some(value),
+            ParkCoordinates::Work => work_offset.map(|o| value + o[axis]),
+        };
+
+        let retract = match action.clearance_z {
+            Some(z) => {
+                let limits = &self.z_axis;
+                let clamped = match to_machine(2, z) {
+                    Some(machine_z) if limits.enabled => {
+                        let within = machine_z.clamp(limits.min, limits.max);
+                        if within != machine_z {
+                            plan.warnings.push(format!(
+                                "Clearance Z{} is outside the Z limits, retracting to {}",
+                                format_gcode_number(z, 3),
+                                format_gcode_number(z + within - machine_z, 3)
+                            ));
+                        }
+                        z + within - machine_z
+                    }
+                    _ => z,
+                };
+                format!("{} Z{}", prefix, format_gcode_number(clamped, 3))
+            }
+            None if self.z_axis.enabled => {
+                format!("G53 G0 Z{}", format_gcode_number(self.z_axis.max, 3))
+            }
+            None => {
+                plan.warnings.push(
+                    "No clearance Z is set and the Z limits are disabled, so the machine was not retracted or parked"
+                        .to_string(),
+                );
+                return plan;
+            }
+        };
+        // The job may have left the controller in inches
+        plan.commands.push("G21".to_string());
+        plan.commands.push(retract);
+
+        if !action.park {
+            return plan;
+        }
+        for (axis, name, value, limits) in [
+            (0, 'X', action.park_x, &self.x_axis),
+            (1, 'Y', action.park_y, &self.y_axis),
+        ] {
+            let Some(machine) = to_machine(axis, value) else {
+                plan.warnings
+                    .push("The work offset is unknown, so the park move was skipped".to_string());
+                return plan;
+            };
+            if limits.enabled && !(limits.min..=limits.max).contains(&machine) {
+                plan.warnings.push(format!(
+                    "Park position {}{} is outside the {} limits, so the park move was skipped",
+                    name,
+                    format_gcode_number(value, 3),
+                    name
+                ));
+                return plan;
+            }
         }
+        plan.commands.push(format!(
+            "{} X{} Y{}",
+            prefix,
+            format_gcode_number(action.park_x, 3),
+            format_gcode_number(action.park_y, 3)
+        ));
+        plan
     }
 }
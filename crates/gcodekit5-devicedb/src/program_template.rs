@@ -0,0 +1,303 @@
+//! # Program Templates
+//!
+//! Machine-specific G-code written before and after every generated program,
+//! such as a homing cycle one machine needs and another does not. Templates are
+//! plain G-code with `{name}` variables, filled in from the device profile and
+//! from values the generator supplies:
+//!
+//! ```text
+//! G28
+//! G0 Z{safe_z}
+//! M3 S{max_spindle_speed}
+//! ```
+//!
+//! Every variable has a type. Numbers may carry a precision, as in
+//! `{safe_z:.1}`, and are otherwise written with up to three decimals; integers
+//! and text are written as they are. `{{` and `}}` write literal braces.
+
+use crate::error::{ProfileError, ProfileResult};
+use crate::model::DeviceProfile;
+use gcodekit5_core::format_gcode_number;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Most decimals a number variable may be written with
+const MAX_PRECISION: usize = 6;
+
+/// Decimals of a number written without a precision
+const DEFAULT_PRECISION: usize = 3;
+
+/// G-code wrapped around every generated program
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProgramTemplates {
+    /// Written before the program
+    pub preamble: String,
+    /// Written after the program
+    pub postamble: String,
+}
+
+/// Type of a template variable
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateValueType {
+    /// A length, feed or other decimal value
+    Number,
+    /// A whole number, such as a spindle speed in RPM
+    Integer,
+    /// Free text, such as the profile name
+    Text,
+}
+
+/// Value of a template variable
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateValue {
+    Number(f64),
+    Integer(i64),
+    Text(String),
+}
+
+impl TemplateValue {
+    /// The type this value has
+    pub fn value_type(&self) -> TemplateValueType {
+        match self {
+            Self::Number(_) => TemplateValueType::Number,
+            Self::Integer(_) => TemplateValueType::Integer,
+            Self::Text(_) => TemplateValueType::Text,
+        }
+    }
+}
+
+/// Variables a template may use, with their types and descriptions
+pub const TEMPLATE_VARIABLES: [(&str, TemplateValueType, &str); 12] = [
+    (
+        "safe_z",
+        TemplateValueType::Number,
+        "Clearance height for rapids (mm)",
+    ),
+    ("x_min", TemplateValueType::Number, "Lowest X travel (mm)"),
+    ("x_max", TemplateValueType::Number, "Highest X travel (mm)"),
+    ("y_min", TemplateValueType::Number, "Lowest Y travel (mm)"),
+    ("y_max", TemplateValueType::Number, "Highest Y travel (mm)"),
+    ("z_min", TemplateValueType::Number, "Lowest Z travel (mm)"),
+    ("z_max", TemplateValueType::Number, "Highest Z travel (mm)"),
+    (
+        "max_feed_rate",
+        TemplateValueType::Number,
+        "Fastest feed rate (mm/min)",
+    ),
+    (
+        "max_spindle_speed",
+        TemplateValueType::Integer,
+        "Fastest spindle speed (RPM)",
+    ),
+    (
+        "max_s_value",
+        TemplateValueType::Number,
+        "Largest S word the controller accepts",
+    ),
+    ("device_name", TemplateValueType::Text, "Profile name"),
+    ("num_axes", TemplateValueType::Integer, "Number of axes"),
+];
+
+/// Type of a known variable
+fn variable_type(name: &str) -> Option<TemplateValueType> {
+    TEMPLATE_VARIABLES
+        .iter()
+        .find(|(known, _, _)| *known == name)
+        .map(|(_, value_type, _)| *value_type)
+}
+
+/// A piece of a parsed template
+enum Piece<'a> {
+    Text(&'a str),
+    Variable {
+        name: &'a str,
+        precision: Option<usize>,
+    },
+}
+
+/// Split a template into text and variables, checking each variable
+fn parse<'a>(field: &str, template: &'a str) -> ProfileResult<Vec<Piece<'a>>> {
+    let invalid = |reason: String| ProfileError::InvalidTemplate {
+        field: field.to_string(),
+        reason,
+    };
+    let mut pieces = Vec::new();
+    let mut rest = template;
+    while let Some(at) = rest.find(['{', '}']) {
+        pieces.push(Piece::Text(&rest[..at]));
+        let brace = &rest[at..at + 1];
+        if rest[at + 1..].starts_with(brace) {
+            pieces.push(Piece::Text(brace));
+            rest = &rest[at + 2..];
+            continue;
+        }
+        if brace == "}" {
+            return Err(invalid("unmatched '}'".to_string()));
+        }
+        let Some(len) = rest[at + 1..].find('}') else {
+            return Err(invalid("unclosed '{'".to_string()));
+        };
+        let inner = &rest[at + 1..at + 1 + len];
+        let (name, spec) = match inner.split_once(':') {
+            Some((name, spec)) => (name.trim(), Some(spec.trim())),
+            None => (inner.trim(), None),
+        };
+        let Some(value_type) = variable_type(name) else {
+            return Err(invalid(format!("unknown variable '{{{}}}'", name)));
+        };
+        let precision = match spec {
+            None => None,
+            Some(spec) if value_type != TemplateValueType::Number => {
+                return Err(invalid(format!(
+                    "'{{{}:{}}}': only numbers take a precision",
+                    name, spec
+                )))
+            }
+            Some(spec) => match spec.strip_prefix('.').and_then(|p| p.parse().ok()) {
+                Some(p) if p <= MAX_PRECISION => Some(p),
+                _ => {
+                    return Err(invalid(format!(
+                        "'{{{}:{}}}': precision must be '.0' to '.{}'",
+                        name, spec, MAX_PRECISION
+                    )))
+                }
+            },
+        };
+        pieces.push(Piece::Variable { name, precision });
+        rest = &rest[at + len + 2..];
+    }
+    pieces.push(Piece::Text(rest));
+    Ok(pieces)
+}
+
+impl ProgramTemplates {
+    /// Whether neither template writes anything
+    pub fn is_empty(&self) -> bool {
+        self.preamble.trim().is_empty() && self.postamble.trim().is_empty()
+    }
+
+    /// Check that both templates only use known variables, correctly written
+    pub fn validate(&self) -> ProfileResult<()> {
+        parse("preamble", &self.preamble)?;
+        parse("postamble", &self.postamble)?;
+        Ok(())
+    }
+
+    /// `program` with the preamble before it and the postamble after it.
+    ///
+    /// Each variable takes its value from `values`; a variable without a
+    /// value, or with a value of the wrong type, is an error.
+    pub fn wrap(
+        &self,
+        program: &str,
+        values: &BTreeMap<String, TemplateValue>,
+    ) -> ProfileResult<String> {
+        let preamble = expand("preamble", &self.preamble, values)?;
+        let postamble = expand("postamble", &self.postamble, values)?;
+        let mut wrapped = String::with_capacity(preamble.len() + program.len() + postamble.len());
+        for part in [preamble.as_str(), program, postamble.as_str()] {
+            if part.trim().is_empty() {
+                continue;
+            }
+            wrapped.push_str(part);
+            if !part.ends_with('\n') {
+                wrapped.push('\n');
+            }
+        }
+        Ok(wrapped)
+    }
+}
+
+/// A template with its variables filled in
+fn expand(
+    field: &str,
+    template: &str,
+    values: &BTreeMap<String, TemplateValue>,
+) -> ProfileResult<String> {
+    let mut expanded = String::new();
+    for piece in parse(field, template)? {
+        match piece {
+            Piece::Text(text) => expanded.push_str(text),
+            Piece::Variable { name, precision } => {
+                let invalid = |reason: String| ProfileError::InvalidTemplate {
+                    field: field.to_string(),
+                    reason,
+                };
+                let value = values
+                    .get(name)
+                    .ok_or_else(|| invalid(format!("no value for '{{{}}}'", name)))?;
+                if let Some(expected) = variable_type(name).filter(|t| *t != value.value_type()) {
+                    return Err(invalid(format!(
+                        "'{{{}}}' expects a {:?} value, got {:?}",
+                        name,
+                        expected,
+                        value.value_type()
+                    )));
+                }
+                match value {
+                    TemplateValue::Number(n) => expanded.push_str(&format_gcode_number(
+                        *n,
+                        precision.unwrap_or(DEFAULT_PRECISION),
+                    )),
+                    TemplateValue::Integer(i) => expanded.push_str(&i.to_string()),
+                    TemplateValue::Text(t) => expanded.push_str(t),
+                }
+            }
+        }
+    }
+    Ok(expanded)
+}
+
+impl DeviceProfile {
+    /// Template values taken from this profile. `safe_z` comes from the
+    /// end-of-job clearance height when one is set; callers supply it otherwise.
+    pub fn template_values(&self) -> BTreeMap<String, TemplateValue> {
+        let mut values = BTreeMap::new();
+        let mut number = |name: &str, value: f64| {
+            values.insert(name.to_string(), TemplateValue::Number(value));
+        };
+        number("x_min", self.x_axis.min);
+        number("x_max", self.x_axis.max);
+        number("y_min", self.y_axis.min);
+        number("y_max", self.y_axis.max);
+        number("z_min", self.z_axis.min);
+        number("z_max", self.z_axis.max);
+        number("max_feed_rate", self.max_feed_rate);
+        number("max_s_value", self.max_s_value);
+        if let Some(z) = self.end_of_job.clearance_z {
+            number("safe_z", z);
+        }
+        values.insert(
+            "max_spindle_speed".to_string(),
+            TemplateValue::Integer(self.max_spindle_speed_rpm.into()),
+        );
+        values.insert(
+            "num_axes".to_string(),
+            TemplateValue::Integer(self.num_axes.into()),
+        );
+        values.insert(
+            "device_name".to_string(),
+            TemplateValue::Text(self.name.clone()),
+        );
+        values
+    }
+
+    /// `program` wrapped in this profile's preamble and postamble.
+    ///
+    /// `extra` adds values the profile does not have, or overrides its own,
+    /// such as the `safe_z` the generator used. With empty templates the
+    /// program is returned unchanged.
+    pub fn wrap_program(
+        &self,
+        program: &str,
+        extra: &BTreeMap<String, TemplateValue>,
+    ) -> ProfileResult<String> {
+        if self.program_templates.is_empty() {
+            return Ok(program.to_string());
+        }
+        let mut values = self.template_values();
+        values.extend(extra.iter().map(|(k, v)| (k.clone(), v.clone())));
+        self.program_templates.wrap(program, &values)
+    }
+}
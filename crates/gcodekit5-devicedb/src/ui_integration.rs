@@ -4,7 +4,8 @@
 //! device information in the GTK4 user interface.
 
 use crate::manager::DeviceManager;
-use crate::model::{ControllerType, DeviceProfile, DeviceType};
+use crate::model::{ControllerType, DeviceProfile, DeviceType, EndOfJob};
+use crate::program_template::ProgramTemplates;
 use anyhow::Context;
 use std::sync::Arc;
 
@@ -36,6 +37,8 @@ pub struct DeviceProfileUiModel {
     pub tcp_port: String,
     pub timeout_ms: String,
     pub auto_reconnect: bool,
+    pub end_of_job: EndOfJob,
+    pub program_templates: ProgramTemplates,
     pub is_active: bool,
 }
 
@@ -68,6 +71,8 @@ impl From<DeviceProfile> for DeviceProfileUiModel {
             tcp_port: p.tcp_port.to_string(),
             timeout_ms: p.timeout_ms.to_string(),
             auto_reconnect: p.auto_reconnect,
+            end_of_job: p.end_of_job,
+            program_templates: p.program_templates,
             is_active: false, // Set separately
         }
     }
@@ -237,6 +242,9 @@ impl DeviceUiController {
                 format!("Timeout must be an integer (got {})", ui_model.timeout_ms)
             })?;
         profile.auto_reconnect = ui_model.auto_reconnect;
+        profile.end_of_job = ui_model.end_of_job;
+        ui_model.program_templates.validate()?;
+        profile.program_templates = ui_model.program_templates;
 
         self.manager.save_profile(profile)
     }
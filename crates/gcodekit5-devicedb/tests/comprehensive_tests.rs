@@ -1,6 +1,8 @@
 use gcodekit5_devicedb::{
-    ControllerType, DeviceManager, DeviceProfile, DeviceProfileUiModel, DeviceType,
+    ControllerType, DeviceManager, DeviceProfile, DeviceProfileUiModel, DeviceType, EndOfJob,
+    LimitProximity, ParkCoordinates, ProgramTemplates, RegionShape, TemplateValue,
 };
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 fn temp_path(name: &str) -> PathBuf {
@@ -46,6 +48,34 @@ fn test_device_profile_serialization() {
     assert_eq!(deser.num_axes, profile.num_axes);
 }
 
+#[test]
+fn test_device_profile_work_regions() {
+    let json = r##"{
+        "name": "Router",
+        "work_regions": [
+            {"name": "Clamps", "shape": {"type": "rectangle", "min": [0, 0], "max": [50, 20]}},
+            {"name": "Limit switch", "no_go": true, "color": "#00ff80",
+             "shape": {"type": "polygon", "points": [[90, 90], [100, 90], [100, 100]]}}
+        ]
+    }"##;
+    let profile: DeviceProfile = serde_json::from_str(json).unwrap();
+    let [clamps, switch] = profile.work_regions.as_slice() else {
+        panic!("expected two regions");
+    };
+    assert!(!clamps.no_go);
+    assert_eq!(clamps.height, None);
+    assert_eq!(clamps.outline().len(), 4);
+    assert_eq!(clamps.label_position(), [25.0, 10.0]);
+    assert_eq!(clamps.rgb(), [0.2, 0.5, 0.9]);
+    assert!(switch.no_go);
+    assert_eq!(switch.rgb(), [0.0, 1.0, 128.0 / 255.0]);
+    assert!(matches!(switch.shape, RegionShape::Polygon { ref points } if points.len() == 3));
+
+    // Profiles saved before regions existed still load
+    let old: DeviceProfile = serde_json::from_str(r#"{"name": "Old"}"#).unwrap();
+    assert!(old.work_regions.is_empty());
+}
+
 #[test]
 fn test_device_type_display() {
     assert_eq!(DeviceType::CncMill.to_string(), "CNC Mill");
@@ -204,3 +234,144 @@ fn test_profile_grbl_settings() {
     assert_eq!(deser.grbl_settings.len(), 2);
     assert_eq!(deser.grbl_settings.get(&110).unwrap(), "1000.000");
 }
+
+#[test]
+fn test_end_of_job_retracts_and_parks_within_limits() {
+    let mut profile = DeviceProfile::default();
+    assert!(profile.end_of_job_commands(None).commands.is_empty());
+
+    profile.end_of_job = EndOfJob {
+        enabled: true,
+        park: true,
+        park_x: 10.0,
+        park_y: 190.5,
+        ..EndOfJob::default()
+    };
+    let plan = profile.end_of_job_commands(None);
+    assert_eq!(
+        plan.commands,
+        vec!["M5", "G21", "G53 G0 Z100", "G53 G0 X10 Y190.5"]
+    );
+    assert!(plan.warnings.is_empty());
+
+    // Work coordinates are checked through the work offset
+    profile.end_of_job.coordinates = ParkCoordinates::Work;
+    profile.end_of_job.clearance_z = Some(20.0);
+    let plan = profile.end_of_job_commands(Some([195.0, 0.0, 90.0]));
+    assert_eq!(plan.commands, vec!["M5", "G21", "G90 G0 Z10"]);
+    assert_eq!(plan.warnings.len(), 2, "{:?}", plan.warnings);
+    assert!(plan.warnings[1].contains("outside the X limits"));
+
+    let plan = profile.end_of_job_commands(None);
+    assert_eq!(plan.commands, vec!["M5", "G21", "G90 G0 Z20"]);
+    assert!(plan.warnings[0].contains("work offset is unknown"));
+
+    // Profiles saved before the setting existed leave it off
+    let old: DeviceProfile = serde_json::from_str(r#"{"name": "Old"}"#).unwrap();
+    assert_eq!(old.end_of_job, EndOfJob::default());
+}
+
+#[test]
+fn test_end_of_job_moves_run_in_mm_after_an_inch_job() {
+    let profile = DeviceProfile {
+        end_of_job: EndOfJob {
+            enabled: true,
+            park: true,
+            park_x: 10.0,
+            park_y: 20.0,
+            ..EndOfJob::default()
+        },
+        ..DeviceProfile::default()
+    };
+    let plan = profile.end_of_job_commands(None);
+    assert!(plan.commands.len() > 2);
+
+    // The job leaves the controller in inches; the plan runs right after it
+    let mut inches = true;
+    for line in &plan.commands {
+        match line.as_str() {
+            "G20" => inches = true,
+            "G21" => inches = false,
+            _ if line.contains(" G0 ") => assert!(!inches, "{} would run in inches", line),
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn test_program_templates_wrap_output_with_typed_variables() {
+    let mut profile = DeviceProfile {
+        name: "Router".to_string(),
+        ..DeviceProfile::default()
+    };
+    let program = "G21 G90\nG1 X10 F500\n";
+    let none = BTreeMap::new();
+    assert_eq!(profile.wrap_program(program, &none).unwrap(), program);
+
+    profile.program_templates = ProgramTemplates {
+        preamble: "({device_name})\nG28\nG0 Z{safe_z:.1}".to_string(),
+        postamble: "M5 S{max_spindle_speed}\nG53 G0 Z{z_max}\n".to_string(),
+    };
+    assert!(profile.program_templates.validate().is_ok());
+    // safe_z is not part of the profile here and must be supplied
+    assert!(profile.wrap_program(program, &none).is_err());
+
+    let extra = BTreeMap::from([("safe_z".to_string(), TemplateValue::Number(5.0))]);
+    assert_eq!(
+        profile.wrap_program(program, &extra).unwrap(),
+        "(Router)\nG28\nG0 Z5\nG21 G90\nG1 X10 F500\nM5 S12000\nG53 G0 Z100\n"
+    );
+    let wrong_type = BTreeMap::from([("safe_z".to_string(), TemplateValue::Integer(5))]);
+    assert!(profile.wrap_program(program, &wrong_type).is_err());
+
+    for bad in [
+        "G0 Z{clearance}",
+        "G0 Z{safe_z",
+        "M3 S{max_spindle_speed:.2}",
+        "X}",
+    ] {
+        let templates = ProgramTemplates {
+            preamble: bad.to_string(),
+            ..ProgramTemplates::default()
+        };
+        assert!(templates.validate().is_err(), "{} should not validate", bad);
+    }
+
+    // Profiles saved before templates existed load with none
+    let mut value = serde_json::to_value(DeviceProfile::default()).unwrap();
+    value.as_object_mut().unwrap().remove("program_templates");
+    let loaded: DeviceProfile = serde_json::from_value(value).unwrap();
+    assert!(loaded.program_templates.is_empty());
+}
+
+#[test]
+fn test_limit_proximity_flags_axes_near_or_at_their_limits() {
+    let mut profile = DeviceProfile::default();
+    profile.x_axis.min = -300.0;
+    profile.x_axis.max = 0.0;
+    profile.z_axis.enabled = false;
+
+    assert_eq!(
+        profile.limit_proximity([-150.0, 100.0, 50.0], 5.0),
+        [LimitProximity::Clear; 3]
+    );
+    assert_eq!(
+        profile.limit_proximity([-3.0, 197.0, 500.0], 5.0),
+        [
+            LimitProximity::Near,
+            LimitProximity::Near,
+            LimitProximity::Clear
+        ]
+    );
+    assert_eq!(
+        profile.limit_proximity([0.0, 201.0, 0.0], 5.0),
+        [
+            LimitProximity::AtLimit,
+            LimitProximity::AtLimit,
+            LimitProximity::Clear
+        ]
+    );
+    // Back in range clears the warning; a zero margin only flags the limit
+    assert_eq!(profile.x_axis.proximity(-3.0, 0.0), LimitProximity::Clear);
+    assert_eq!(profile.x_axis.proximity(-4.0, 5.0), LimitProximity::Near);
+}
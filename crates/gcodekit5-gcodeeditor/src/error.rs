@@ -60,6 +60,10 @@ pub enum BufferError {
     #[error("Invalid UTF-8 text")]
     InvalidUtf8,
 
+    /// Reading or writing the text failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
     /// The operation would create an empty buffer in an invalid state.
     #[error("Buffer operation would create invalid state: {0}")]
     InvalidOperation(String),
@@ -14,6 +14,7 @@
 //! - **TextBuffer**: Rope-based text storage for efficient large file handling
 //! - Character-indexed operations with line/column mapping
 //! - Efficient slicing and range operations
+//! - Streaming load from a reader, keeping the file's line ending style for saving
 //!
 //! ### Undo/Redo
 //! - **UndoManager**: Full undo/redo history with changeset tracking
@@ -24,6 +25,7 @@
 //! - **Viewport**: Camera control for navigating large files
 //! - Overscan mechanism for smooth scrolling
 //! - Efficient visible line range calculation
+//! - Horizontal scrolling with column clipping for long lines
 //!
 //! ### UI Integration
 //! - **EditorBridge**: Slint UI bridge for text editor rendering
@@ -66,10 +68,20 @@ mod viewport;
 
 pub use editor_bridge::EditorBridgeBackend;
 pub use error::{BufferError, BufferResult, EditorError, EditorResult};
-pub use text_buffer::TextBuffer;
+pub use text_buffer::{LineEnding, TextBuffer};
 pub use undo_manager::{TextChange, UndoManager};
 pub use viewport::Viewport;
 
+use regex::{NoExpand, Regex, Replacer};
+use std::io::{BufRead, Write};
+use std::ops::Range;
+use std::time::Duration;
+use text_buffer::literal_regex;
+
+/// Columns rendered beyond each side of the viewport by
+/// [`EditorState::get_visible_lines_clipped`]
+const VISIBLE_OVERSCAN_COLS: usize = 10;
+
 // Re-export for Slint UI
 #[derive(Clone, Debug)]
 pub struct TextLine {
@@ -85,6 +97,11 @@ pub struct EditorState {
     viewport: Viewport,
     cursor_pos: usize,
     selection: Option<(usize, usize)>,
+    /// Extra cursors for multi-cursor editing, as sorted (start, end) char
+    /// ranges; empty when only the single cursor is in use
+    selections: Vec<(usize, usize)>,
+    /// Line marked for the UI, such as the line being executed
+    highlight_line: Option<usize>,
     modified: bool,
 }
 
@@ -101,16 +118,44 @@ impl EditorState {
             viewport,
             cursor_pos: 0,
             selection: None,
+            selections: Vec::new(),
+            highlight_line: None,
             modified: false,
         }
     }
 
     /// Load text from string
     pub fn load_text(&mut self, text: &str) {
-        self.buffer = TextBuffer::from(text);
+        self.set_buffer(TextBuffer::from(text));
+    }
+
+    /// Load text from a reader without first reading it into a string; see
+    /// [`TextBuffer::from_reader`]. On error the editor is left unchanged
+    pub fn load_from_reader<R: BufRead>(&mut self, reader: R) -> BufferResult<()> {
+        self.set_buffer(TextBuffer::from_reader(reader)?);
+        Ok(())
+    }
+
+    /// Write all text in the line ending style it was loaded with
+    pub fn write_to<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        self.buffer.write_to(writer)
+    }
+
+    /// Get the line ending style the text was loaded with
+    pub fn line_ending(&self) -> LineEnding {
+        self.buffer.line_ending()
+    }
+
+    /// Replace the document, resetting cursor, selections and history
+    fn set_buffer(&mut self, buffer: TextBuffer) {
+        self.buffer = buffer;
         self.viewport.set_total_lines(self.buffer.len_lines());
+        self.viewport
+            .set_max_line_length(self.buffer.max_line_length());
         self.cursor_pos = 0;
         self.selection = None;
+        self.selections.clear();
+        self.highlight_line = None;
         self.undo_manager.clear();
         self.modified = false;
     }
@@ -120,7 +165,7 @@ impl EditorState {
         self.buffer.to_string()
     }
 
-    /// Insert text at cursor
+    /// Insert text at cursor, dropping any extra cursors
     pub fn insert_text(&mut self, text: &str) {
         let old_text = String::new();
         let old_cursor = self.cursor_pos;
@@ -138,7 +183,9 @@ impl EditorState {
 
         self.undo_manager.record(change);
         self.cursor_pos = new_cursor;
+        self.selections.clear();
         self.viewport.set_total_lines(self.buffer.len_lines());
+        self.grow_max_line_length(old_cursor, old_cursor + text.chars().count());
         self.modified = true;
     }
 
@@ -151,6 +198,7 @@ impl EditorState {
             let old_text = self.buffer.slice(self.cursor_pos, end);
 
             self.buffer.delete(self.cursor_pos..end);
+            self.shrink_max_line_length(self.cursor_pos, &old_text);
 
             let change = TextChange::new(
                 self.cursor_pos..end,
@@ -161,6 +209,7 @@ impl EditorState {
             );
 
             self.undo_manager.record(change);
+            self.selections.clear();
             self.viewport.set_total_lines(self.buffer.len_lines());
             self.modified = true;
         }
@@ -175,6 +224,7 @@ impl EditorState {
             let old_text = self.buffer.slice(start, self.cursor_pos);
 
             self.buffer.delete(start..self.cursor_pos);
+            self.shrink_max_line_length(start, &old_text);
 
             let change = TextChange::new(
                 start..self.cursor_pos,
@@ -186,6 +236,7 @@ impl EditorState {
 
             self.undo_manager.record(change);
             self.cursor_pos = start;
+            self.selections.clear();
             self.viewport.set_total_lines(self.buffer.len_lines());
             self.modified = true;
         }
@@ -196,6 +247,7 @@ impl EditorState {
         if let Some((start, end)) = self.selection {
             let old_text = self.buffer.slice(start, end);
             self.buffer.delete(start..end);
+            self.shrink_max_line_length(start, &old_text);
 
             let change =
                 TextChange::new(start..end, old_text, String::new(), self.cursor_pos, start);
@@ -203,6 +255,7 @@ impl EditorState {
             self.undo_manager.record(change);
             self.cursor_pos = start;
             self.selection = None;
+            self.selections.clear();
             self.viewport.set_total_lines(self.buffer.len_lines());
             self.modified = true;
         }
@@ -214,7 +267,10 @@ impl EditorState {
             self.buffer
                 .replace(change.char_range.clone(), &change.new_text);
             self.cursor_pos = change.new_cursor;
+            self.selections.clear();
             self.viewport.set_total_lines(self.buffer.len_lines());
+            self.viewport
+                .set_max_line_length(self.buffer.max_line_length());
             self.modified = true;
             true
         } else {
@@ -228,7 +284,10 @@ impl EditorState {
             self.buffer
                 .replace(change.char_range.clone(), &change.new_text);
             self.cursor_pos = change.new_cursor;
+            self.selections.clear();
             self.viewport.set_total_lines(self.buffer.len_lines());
+            self.viewport
+                .set_max_line_length(self.buffer.max_line_length());
             self.modified = true;
             true
         } else {
@@ -236,6 +295,12 @@ impl EditorState {
         }
     }
 
+    /// Set how soon consecutive typing or deleting must follow to be undone
+    /// together; zero undoes every edit on its own
+    pub fn set_undo_coalesce_window(&mut self, window: Duration) {
+        self.undo_manager.set_coalesce_window(window);
+    }
+
     /// Check if undo is available
     pub fn can_undo(&self) -> bool {
         self.undo_manager.can_undo()
@@ -262,6 +327,37 @@ impl EditorState {
         (start_line, lines)
     }
 
+    /// Get the highlighted line's index within the lines returned by
+    /// [`get_visible_lines`](Self::get_visible_lines), if it is among them
+    pub fn visible_highlight_line(&self) -> Option<usize> {
+        let range = self.viewport.overscan_range(5);
+        self.highlight_line
+            .filter(|line| range.contains(line))
+            .map(|line| line - range.start)
+    }
+
+    /// Get visible lines clipped to the horizontal window, with overscan in
+    /// both directions.
+    /// Returns (start_line, start_col, Vec<String>) where start_col is the
+    /// 0-indexed column each substring starts at; line endings are dropped and
+    /// lines that end before start_col are empty
+    pub fn get_visible_lines_clipped(&self) -> (usize, usize, Vec<String>) {
+        let (start_line, lines) = self.get_visible_lines();
+        let start_col = self.viewport.h_offset.saturating_sub(VISIBLE_OVERSCAN_COLS);
+        let lines = lines
+            .into_iter()
+            .map(|line| {
+                let line = line.trim_end_matches(['\n', '\r']);
+                let cols = self
+                    .viewport
+                    .h_overscan_range(VISIBLE_OVERSCAN_COLS, line.chars().count());
+                line.chars().skip(cols.start).take(cols.len()).collect()
+            })
+            .collect();
+
+        (start_line, start_col, lines)
+    }
+
     /// Scroll viewport by delta lines
     pub fn scroll_by(&mut self, delta: i32) {
         self.viewport.scroll_by(delta);
@@ -272,6 +368,16 @@ impl EditorState {
         self.viewport.set_scroll_offset(line);
     }
 
+    /// Scroll viewport by delta columns
+    pub fn scroll_horizontal_by(&mut self, delta_cols: i32) {
+        self.viewport.scroll_horizontal_by(delta_cols);
+    }
+
+    /// Scroll viewport to absolute column (sets horizontal offset to show that column at the left)
+    pub fn set_h_offset(&mut self, cols: usize) {
+        self.viewport.set_h_offset(cols);
+    }
+
     /// Get viewport info
     pub fn viewport(&self) -> &Viewport {
         &self.viewport
@@ -280,10 +386,13 @@ impl EditorState {
     /// Set cursor position
     pub fn set_cursor(&mut self, pos: usize) {
         self.cursor_pos = pos.min(self.buffer.len_chars());
+        self.selections.clear();
+        self.undo_manager.break_coalescing();
 
         // Scroll to cursor if needed
-        let (line, _) = self.buffer.char_to_line_col(self.cursor_pos);
+        let (line, col) = self.buffer.char_to_line_col(self.cursor_pos);
         self.viewport.scroll_to_line(line);
+        self.viewport.scroll_to_column(col);
     }
 
     /// Get cursor position
@@ -291,6 +400,41 @@ impl EditorState {
         self.cursor_pos
     }
 
+    /// Move the cursor to the first non-whitespace char of a line (0-indexed)
+    /// and scroll so the line is centered. Lines past the end go to the last line
+    pub fn goto_line(&mut self, line: usize) {
+        let line = self.clamp_line(line);
+        let indent = self.buffer.line(line).map_or(0, |text| {
+            text.chars()
+                .take_while(|c| c.is_whitespace() && *c != '\n')
+                .count()
+        });
+        self.set_cursor(self.buffer.line_col_to_char(line, indent));
+        self.viewport
+            .set_scroll_offset(line.saturating_sub(self.viewport.visible_lines / 2));
+    }
+
+    /// Get the line (0-indexed) the cursor is on
+    pub fn current_line(&self) -> usize {
+        self.buffer.char_to_line_col(self.cursor_pos).0
+    }
+
+    /// Mark a line (0-indexed) for the UI to highlight, e.g. as an execution
+    /// marker, or clear the mark. Lines past the end mark the last line
+    pub fn set_highlight_line(&mut self, line: Option<usize>) {
+        self.highlight_line = line.map(|line| self.clamp_line(line));
+    }
+
+    /// Get the highlighted line (0-indexed)
+    pub fn highlight_line(&self) -> Option<usize> {
+        self.highlight_line
+    }
+
+    /// A line index limited to the lines in the document
+    fn clamp_line(&self, line: usize) -> usize {
+        line.min(self.buffer.len_lines().saturating_sub(1))
+    }
+
     /// Get cursor line and column
     pub fn cursor_line_col(&self) -> (usize, usize) {
         self.buffer.char_to_line_col(self.cursor_pos)
@@ -322,6 +466,36 @@ impl EditorState {
             .set_viewport_size(viewport_height, line_height);
     }
 
+    /// Update viewport width (when UI resizes)
+    pub fn set_viewport_width(&mut self, viewport_width: f32, char_width: f32) {
+        self.viewport.set_viewport_width(viewport_width, char_width);
+    }
+
+    /// Grow the longest line length to cover the lines between two char
+    /// positions after an insert
+    fn grow_max_line_length(&mut self, start: usize, end: usize) {
+        let (first, _) = self.buffer.char_to_line_col(start);
+        let (last, _) = self.buffer.char_to_line_col(end);
+        let longest = (first..=last)
+            .map(|line| self.buffer.line_len(line))
+            .max()
+            .unwrap_or(0);
+        if longest > self.viewport.max_line_length() {
+            self.viewport.set_max_line_length(longest);
+        }
+    }
+
+    /// Re-measure the longest line after `removed` was deleted at a char
+    /// position, when the deletion may have shortened it
+    fn shrink_max_line_length(&mut self, char_idx: usize, removed: &str) {
+        let (line, _) = self.buffer.char_to_line_col(char_idx);
+        let old_len = self.buffer.line_len(line) + removed.chars().count();
+        if removed.contains('\n') || old_len >= self.viewport.max_line_length() {
+            self.viewport
+                .set_max_line_length(self.buffer.max_line_length());
+        }
+    }
+
     /// Convert line/column to character index
     pub fn line_col_to_char(&self, line: usize, col: usize) -> usize {
         self.buffer.line_col_to_char(line, col)
@@ -331,4 +505,215 @@ impl EditorState {
     pub fn get_line(&self, line_idx: usize) -> Option<String> {
         self.buffer.line(line_idx)
     }
+
+    /// Add a cursor at a char position for multi-cursor editing.
+    /// The first extra cursor keeps the current cursor as well
+    pub fn add_cursor_at(&mut self, pos: usize) {
+        if self.selections.is_empty() {
+            let current = self.selection.unwrap_or((self.cursor_pos, self.cursor_pos));
+            self.selections.push(current);
+        }
+        let pos = pos.min(self.buffer.len_chars());
+        self.selections.push((pos, pos));
+        self.normalize_selections();
+        self.undo_manager.break_coalescing();
+    }
+
+    /// Add a cursor at the same column on each line from `first_line` to
+    /// `last_line` (inclusive), for block editing; shorter lines get a cursor
+    /// at their end
+    pub fn add_block_cursors(&mut self, first_line: usize, last_line: usize, col: usize) {
+        let last_line = self.clamp_line(last_line);
+        for line in first_line..=last_line {
+            let len = self.buffer.line(line).map_or(0, |text| {
+                text.trim_end_matches(['\n', '\r']).chars().count()
+            });
+            self.add_cursor_at(self.buffer.line_col_to_char(line, col.min(len)));
+        }
+    }
+
+    /// Get the multi-cursor selections as (start, end) char ranges; a single
+    /// cursor is one empty range
+    pub fn selections(&self) -> Vec<(usize, usize)> {
+        if self.selections.is_empty() {
+            vec![self.selection.unwrap_or((self.cursor_pos, self.cursor_pos))]
+        } else {
+            self.selections.clone()
+        }
+    }
+
+    /// Drop the extra cursors, keeping the primary one
+    pub fn clear_cursors(&mut self) {
+        self.selections.clear();
+        self.undo_manager.break_coalescing();
+    }
+
+    /// Insert text at every cursor, replacing selected text, as one undo step
+    pub fn insert_text_multi(&mut self, text: &str) {
+        if self.selections.is_empty() {
+            self.insert_text(text);
+            return;
+        }
+        let edits: Vec<(Range<usize>, &str)> = self
+            .selections
+            .iter()
+            .map(|&(start, end)| (start..end, text))
+            .collect();
+        self.apply_multi_edit(&edits);
+    }
+
+    /// Delete `count` chars before every cursor, or the selected text, as one
+    /// undo step
+    pub fn delete_backward_multi(&mut self, count: usize) {
+        if self.selections.is_empty() {
+            self.delete_backward(count);
+            return;
+        }
+        let mut edits: Vec<(Range<usize>, &str)> = Vec::new();
+        for &(start, end) in &self.selections {
+            let range = if start == end {
+                start.saturating_sub(count)..end
+            } else {
+                start..end
+            };
+            // Deletions reaching into the previous one are merged with it
+            match edits.last_mut() {
+                Some((last, _)) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => edits.push((range, "")),
+            }
+        }
+        // Cursors with nothing before them stay where they are
+        if edits.iter().all(|(range, _)| range.is_empty()) {
+            return;
+        }
+        self.apply_multi_edit(&edits);
+    }
+
+    /// Replace each of the sorted, non-overlapping ranges with its text as a
+    /// single change, leaving a cursor after each replacement
+    fn apply_multi_edit(&mut self, edits: &[(Range<usize>, &str)]) {
+        let (Some((first, _)), Some((last, _))) = (edits.first(), edits.last()) else {
+            return;
+        };
+        let span = first.start..last.end;
+        let old_text = self.buffer.slice(span.start, span.end);
+
+        let mut new_text = String::new();
+        let mut cursors = Vec::with_capacity(edits.len());
+        let mut copied = span.start;
+        let mut new_pos = span.start;
+        for (range, text) in edits {
+            let kept = self.buffer.slice(copied, range.start);
+            new_pos += kept.chars().count() + text.chars().count();
+            new_text.push_str(&kept);
+            new_text.push_str(text);
+            cursors.push((new_pos, new_pos));
+            copied = range.end;
+        }
+
+        let old_cursor = self.cursor_pos;
+        let new_cursor = cursors.last().map_or(old_cursor, |&(pos, _)| pos);
+        self.buffer.replace(span.clone(), &new_text);
+        let change = TextChange::new(span, old_text, new_text, old_cursor, new_cursor);
+
+        self.undo_manager.record(change);
+        self.cursor_pos = new_cursor;
+        self.selection = None;
+        self.selections = cursors;
+        self.normalize_selections();
+        self.viewport.set_total_lines(self.buffer.len_lines());
+        self.viewport
+            .set_max_line_length(self.buffer.max_line_length());
+        self.modified = true;
+    }
+
+    /// Sort the cursors, merging duplicates and overlapping selections
+    fn normalize_selections(&mut self) {
+        self.selections.sort_unstable();
+        let mut merged: Vec<(usize, usize)> = Vec::with_capacity(self.selections.len());
+        for (start, end) in self.selections.drain(..) {
+            match merged.last_mut() {
+                Some(last) if start == last.0 || start < last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        self.selections = merged;
+    }
+
+    /// Replace every occurrence of `pattern` (case-sensitive) with
+    /// `replacement` as a single undo step.
+    /// Returns the number of replacements made
+    pub fn replace_all(&mut self, pattern: &str, replacement: &str) -> usize {
+        if pattern.is_empty() {
+            return 0;
+        }
+        match literal_regex(pattern, true) {
+            Some(re) => self.replace_matches(&re, NoExpand(replacement)),
+            None => 0,
+        }
+    }
+
+    /// Replace every non-empty match of `re` with `replacement` as a single
+    /// undo step; `$1` and `${name}` in the replacement expand to capture groups.
+    /// Returns the number of replacements made
+    pub fn replace_all_regex(&mut self, re: &Regex, replacement: &str) -> usize {
+        self.replace_matches(re, replacement)
+    }
+
+    /// Replace matches of `re` between the first and the last as one change,
+    /// dropping any extra cursors
+    fn replace_matches<R: Replacer>(&mut self, re: &Regex, mut replacer: R) -> usize {
+        let text = self.buffer.to_string();
+        let mut first = None;
+        let mut last = 0;
+        let mut replaced = String::new();
+        let mut count = 0;
+        for caps in re.captures_iter(&text) {
+            let Some(m) = caps.get(0).filter(|m| !m.is_empty()) else {
+                continue;
+            };
+            if first.is_none() {
+                first = Some(m.start());
+                last = m.start();
+            }
+            replaced.push_str(&text[last..m.start()]);
+            replacer.replace_append(&caps, &mut replaced);
+            last = m.end();
+            count += 1;
+        }
+        let Some(first) = first else {
+            return 0;
+        };
+
+        let start = text[..first].chars().count();
+        let end = start + text[first..last].chars().count();
+        let new_len = replaced.chars().count();
+        let old_cursor = self.cursor_pos;
+        let new_cursor = if old_cursor <= start {
+            old_cursor
+        } else if old_cursor >= end {
+            old_cursor - (end - start) + new_len
+        } else {
+            start
+        };
+
+        self.buffer.replace(start..end, &replaced);
+        let change = TextChange::new(
+            start..end,
+            text[first..last].to_string(),
+            replaced,
+            old_cursor,
+            new_cursor,
+        );
+
+        self.undo_manager.record(change);
+        self.cursor_pos = new_cursor;
+        self.selection = None;
+        self.selections.clear();
+        self.viewport.set_total_lines(self.buffer.len_lines());
+        self.viewport
+            .set_max_line_length(self.buffer.max_line_length());
+        self.modified = true;
+        count
+    }
 }
@@ -1,15 +1,29 @@
 //! Text buffer implementation using rope data structure for efficient text manipulation
 
-use ropey::Rope;
+use crate::error::{BufferError, BufferResult};
+use regex::{Regex, RegexBuilder};
+use ropey::{Rope, RopeBuilder};
 use std::fmt;
+use std::io::{BufRead, Write};
 use std::ops::Range;
 
+/// Line ending style of a loaded file
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`
+    CrLf,
+}
+
 /// Efficient text buffer using rope data structure
 /// Optimized for large files with incremental updates
 #[derive(Clone)]
 pub struct TextBuffer {
     rope: Rope,
     dirty_lines: Vec<usize>,
+    line_ending: LineEnding,
 }
 
 impl TextBuffer {
@@ -18,9 +32,66 @@ impl TextBuffer {
         Self {
             rope: Rope::new(),
             dirty_lines: Vec::new(),
+            line_ending: LineEnding::Lf,
         }
     }
 
+    /// Load a buffer from a reader a line at a time, without holding the
+    /// whole file as a string first.
+    /// `\r\n` line endings are stored as `\n`; the style of the first line
+    /// ending is kept for [`write_to`](Self::write_to). Input that is not
+    /// valid UTF-8 is an error
+    pub fn from_reader<R: BufRead>(mut reader: R) -> BufferResult<TextBuffer> {
+        let mut builder = RopeBuilder::new();
+        let mut line_ending = None;
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                break;
+            }
+            if line.ends_with(b"\r\n") {
+                line.truncate(line.len() - 2);
+                line.push(b'\n');
+                line_ending.get_or_insert(LineEnding::CrLf);
+            } else if line.ends_with(b"\n") {
+                line_ending.get_or_insert(LineEnding::Lf);
+            }
+            let text = std::str::from_utf8(&line).map_err(|_| BufferError::InvalidUtf8)?;
+            builder.append(text);
+        }
+        Ok(Self {
+            rope: builder.finish(),
+            dirty_lines: Vec::new(),
+            line_ending: line_ending.unwrap_or_default(),
+        })
+    }
+
+    /// Get the line ending style the buffer was loaded with
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// Write the text, with `\n` written in the loaded line ending style
+    pub fn write_to<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        for chunk in self.rope.chunks() {
+            match self.line_ending {
+                LineEnding::Lf => writer.write_all(chunk.as_bytes())?,
+                LineEnding::CrLf => {
+                    let mut pieces = chunk.split('\n');
+                    if let Some(first) = pieces.next() {
+                        writer.write_all(first.as_bytes())?;
+                    }
+                    for piece in pieces {
+                        writer.write_all(b"\r\n")?;
+                        writer.write_all(piece.as_bytes())?;
+                    }
+                }
+            }
+        }
+        writer.flush()
+    }
+
     /// Get the total length in bytes
     pub fn len_bytes(&self) -> usize {
         self.rope.len_bytes()
@@ -55,6 +126,24 @@ impl TextBuffer {
         }
     }
 
+    /// Get the length of a line in chars, without its line ending
+    pub fn line_len(&self, line_idx: usize) -> usize {
+        if line_idx < self.len_lines() {
+            line_len_without_ending(self.rope.line(line_idx))
+        } else {
+            0
+        }
+    }
+
+    /// Get the length in chars of the longest line, without its line ending
+    pub fn max_line_length(&self) -> usize {
+        self.rope
+            .lines()
+            .map(line_len_without_ending)
+            .max()
+            .unwrap_or(0)
+    }
+
     /// Get lines in a range for viewport rendering
     pub fn lines_in_range(&self, range: Range<usize>) -> Vec<String> {
         let start = range.start.min(self.len_lines());
@@ -65,6 +154,28 @@ impl TextBuffer {
             .collect()
     }
 
+    /// Find every occurrence of `pattern`, as char ranges
+    pub fn find_all(&self, pattern: &str, case_sensitive: bool) -> Vec<Range<usize>> {
+        if pattern.is_empty() {
+            return Vec::new();
+        }
+        match literal_regex(pattern, case_sensitive) {
+            Some(re) => self.find_all_regex(&re),
+            None => Vec::new(),
+        }
+    }
+
+    /// Find every non-empty match of `re`, as char ranges.
+    /// Matches may span lines and are found across the whole text at once,
+    /// so rope chunk boundaries do not split them
+    pub fn find_all_regex(&self, re: &Regex) -> Vec<Range<usize>> {
+        let text = self.rope.to_string();
+        re.find_iter(&text)
+            .filter(|m| !m.is_empty())
+            .map(|m| self.rope.byte_to_char(m.start())..self.rope.byte_to_char(m.end()))
+            .collect()
+    }
+
     /// Insert text at character position
     pub fn insert(&mut self, char_idx: usize, text: &str) {
         let char_idx = char_idx.min(self.len_chars());
@@ -140,6 +251,26 @@ impl TextBuffer {
     }
 }
 
+/// A regex matching `pattern` literally
+pub(crate) fn literal_regex(pattern: &str, case_sensitive: bool) -> Option<Regex> {
+    RegexBuilder::new(&regex::escape(pattern))
+        .case_insensitive(!case_sensitive)
+        .build()
+        .ok()
+}
+
+/// Length of a rope line in chars, not counting a trailing `\n` or `\r\n`
+fn line_len_without_ending(line: ropey::RopeSlice) -> usize {
+    let mut len = line.len_chars();
+    if len > 0 && line.char(len - 1) == '\n' {
+        len -= 1;
+        if len > 0 && line.char(len - 1) == '\r' {
+            len -= 1;
+        }
+    }
+    len
+}
+
 impl Default for TextBuffer {
     fn default() -> Self {
         Self::new()
@@ -151,6 +282,7 @@ impl From<&str> for TextBuffer {
         Self {
             rope: Rope::from_str(text),
             dirty_lines: Vec::new(),
+            line_ending: LineEnding::Lf,
         }
     }
 }
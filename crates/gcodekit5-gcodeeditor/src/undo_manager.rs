@@ -1,6 +1,15 @@
 //! Undo/Redo manager for text editing operations
+//!
+//! Consecutive typing is undone as a whole: an insertion recorded right after
+//! an insertion that ends where it starts, within the coalesce window, is
+//! merged into it, and likewise a deletion next to a deletion. Insertions and
+//! deletions are never merged with each other.
 
 use std::ops::Range;
+use std::time::{Duration, Instant};
+
+/// Default time within which consecutive edits are merged
+const DEFAULT_COALESCE_WINDOW: Duration = Duration::from_millis(500);
 
 /// Represents a single text change for undo/redo
 #[derive(Clone, Debug)]
@@ -35,10 +44,49 @@ impl TextChange {
         }
     }
 
+    /// Whether this change only inserts text
+    fn is_insertion(&self) -> bool {
+        self.old_text.is_empty() && !self.new_text.is_empty()
+    }
+
+    /// Whether this change only deletes text
+    fn is_deletion(&self) -> bool {
+        self.new_text.is_empty() && !self.old_text.is_empty()
+    }
+
+    /// Merge a following insertion or deletion that touches this one into it.
+    /// Returns false, leaving this change as it was, when they cannot be merged
+    fn absorb(&mut self, next: &TextChange) -> bool {
+        if self.is_insertion() && next.is_insertion() {
+            let end = self.char_range.start + self.new_text.chars().count();
+            if next.char_range.start != end {
+                return false;
+            }
+            self.new_text.push_str(&next.new_text);
+        } else if self.is_deletion() && next.is_deletion() {
+            if next.char_range.end == self.char_range.start {
+                // Backspace: the next deletion is just before this one
+                self.char_range.start = next.char_range.start;
+                self.old_text.insert_str(0, &next.old_text);
+            } else if next.char_range.start == self.char_range.start {
+                // Delete key: the next deletion follows at the same position
+                self.char_range.end += next.char_range.len();
+                self.old_text.push_str(&next.old_text);
+            } else {
+                return false;
+            }
+        } else {
+            return false;
+        }
+        self.new_cursor = next.new_cursor;
+        true
+    }
+
     /// Create inverse change for undo
     pub fn inverse(&self) -> Self {
         Self {
-            char_range: self.char_range.start..(self.char_range.start + self.new_text.len()),
+            char_range: self.char_range.start
+                ..(self.char_range.start + self.new_text.chars().count()),
             old_text: self.new_text.clone(),
             new_text: self.old_text.clone(),
             old_cursor: self.new_cursor,
@@ -53,6 +101,10 @@ pub struct UndoManager {
     redo_stack: Vec<TextChange>,
     max_depth: usize,
     current_batch: Option<Vec<TextChange>>,
+    coalesce_window: Duration,
+    /// When the top undo change was last recorded or extended, while the next
+    /// change may still be merged into it
+    last_recorded: Option<Instant>,
 }
 
 impl UndoManager {
@@ -68,18 +120,47 @@ impl UndoManager {
             redo_stack: Vec::with_capacity(max_depth),
             max_depth,
             current_batch: None,
+            coalesce_window: DEFAULT_COALESCE_WINDOW,
+            last_recorded: None,
         }
     }
 
-    /// Record a change to the undo stack
+    /// Set how soon after the previous edit a change must follow to be merged
+    /// into it; zero turns merging off
+    pub fn set_coalesce_window(&mut self, window: Duration) {
+        self.coalesce_window = window;
+    }
+
+    /// Start a new undo step with the next change, e.g. after the cursor moved
+    pub fn break_coalescing(&mut self) {
+        self.last_recorded = None;
+    }
+
+    /// Record a change to the undo stack, merging it into the previous change
+    /// where consecutive insertions or deletions allow
     pub fn record(&mut self, change: TextChange) {
         if let Some(batch) = &mut self.current_batch {
             // Add to current batch
             batch.push(change);
+            return;
+        }
+        let now = Instant::now();
+        let recent = self
+            .last_recorded
+            .is_some_and(|at| now.duration_since(at) <= self.coalesce_window);
+        let merged = recent
+            && !self.coalesce_window.is_zero()
+            && self
+                .undo_stack
+                .last_mut()
+                .is_some_and(|last| last.absorb(&change));
+        if merged {
+            self.redo_stack.clear();
         } else {
             // Direct push
             self.push_undo(change);
         }
+        self.last_recorded = Some(now);
     }
 
     /// Push a change to undo stack
@@ -103,6 +184,7 @@ impl UndoManager {
 
     /// End batch and commit as single undo operation
     pub fn end_batch(&mut self) {
+        self.last_recorded = None;
         if let Some(batch) = self.current_batch.take() {
             if !batch.is_empty() {
                 // Merge batch into single change if possible
@@ -123,6 +205,7 @@ impl UndoManager {
 
     /// Undo last change
     pub fn undo(&mut self) -> Option<TextChange> {
+        self.last_recorded = None;
         self.undo_stack.pop().map(|change| {
             let inverse = change.inverse();
             self.redo_stack.push(change);
@@ -132,6 +215,7 @@ impl UndoManager {
 
     /// Redo last undone change
     pub fn redo(&mut self) -> Option<TextChange> {
+        self.last_recorded = None;
         self.redo_stack.pop().inspect(|change| {
             self.undo_stack.push(change.clone());
         })
@@ -152,6 +236,7 @@ impl UndoManager {
         self.undo_stack.clear();
         self.redo_stack.clear();
         self.current_batch = None;
+        self.last_recorded = None;
     }
 
     /// Get number of undo operations available
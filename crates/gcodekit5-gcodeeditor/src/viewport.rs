@@ -20,6 +20,12 @@ pub struct Viewport {
     pub line_height: f32,
     /// Viewport height in pixels
     pub viewport_height: f32,
+    /// Horizontal scroll offset (first visible column)
+    pub h_offset: usize,
+    /// Number of columns visible in viewport; unbounded until a width is set
+    pub visible_cols: usize,
+    /// Length in chars of the longest line in document
+    pub max_line_length: usize,
 }
 
 impl Viewport {
@@ -34,6 +40,9 @@ impl Viewport {
             scroll_offset: 0,
             line_height,
             viewport_height,
+            h_offset: 0,
+            visible_cols: usize::MAX,
+            max_line_length: 0,
         }
     }
 
@@ -51,6 +60,83 @@ impl Viewport {
         self.update_visible_range();
     }
 
+    /// Update viewport width, so that only the columns in view are rendered
+    pub fn set_viewport_width(&mut self, width: f32, char_width: f32) {
+        self.visible_cols = (width / char_width).ceil().max(1.0) as usize;
+        self.set_h_offset(self.h_offset);
+    }
+
+    /// Update the length of the longest line
+    pub fn set_max_line_length(&mut self, length: usize) {
+        self.max_line_length = length;
+        self.set_h_offset(self.h_offset);
+    }
+
+    /// Get the length of the longest line, for sizing a horizontal scroll bar
+    pub fn max_line_length(&self) -> usize {
+        self.max_line_length
+    }
+
+    /// Scroll by number of columns (positive = right, negative = left)
+    pub fn scroll_horizontal_by(&mut self, delta_cols: i32) {
+        let new_offset = (self.h_offset as i64 + delta_cols as i64).max(0) as usize;
+        self.set_h_offset(new_offset);
+    }
+
+    /// Set absolute horizontal scroll offset
+    pub fn set_h_offset(&mut self, cols: usize) {
+        let max_scroll = self.max_line_length.saturating_sub(self.visible_cols);
+        self.h_offset = cols.min(max_scroll);
+    }
+
+    /// Scroll to make a specific column visible
+    pub fn scroll_to_column(&mut self, col: usize) {
+        if col < self.h_offset {
+            self.set_h_offset(col);
+        } else if col >= self.h_offset.saturating_add(self.visible_cols) {
+            self.set_h_offset(col + 1 - self.visible_cols);
+        }
+    }
+
+    /// Get the columns of a line `line_width_chars` long that are in view
+    pub fn visible_columns(&self, line_width_chars: usize) -> Range<usize> {
+        let start = self.h_offset.min(line_width_chars);
+        let end = self
+            .h_offset
+            .saturating_add(self.visible_cols)
+            .min(line_width_chars);
+        start..end
+    }
+
+    /// Get horizontal overscan range (columns to pre-render left/right of the
+    /// viewport) of a line `line_width_chars` long
+    pub fn h_overscan_range(&self, overscan_cols: usize, line_width_chars: usize) -> Range<usize> {
+        let start = self
+            .h_offset
+            .saturating_sub(overscan_cols)
+            .min(line_width_chars);
+        let end = self
+            .h_offset
+            .saturating_add(self.visible_cols)
+            .saturating_add(overscan_cols)
+            .min(line_width_chars);
+        start..end
+    }
+
+    /// Calculate horizontal scroll bar width ratio
+    pub fn h_scrollbar_ratio(&self) -> f32 {
+        if self.max_line_length <= self.visible_cols {
+            1.0
+        } else {
+            self.visible_cols as f32 / self.max_line_length as f32
+        }
+    }
+
+    /// Check if content is horizontally scrollable
+    pub fn is_h_scrollable(&self) -> bool {
+        self.max_line_length > self.visible_cols
+    }
+
     /// Scroll by number of lines (positive = down, negative = up)
     pub fn scroll_by(&mut self, delta: i32) {
         let new_offset = (self.scroll_offset as i32 + delta).max(0) as usize;
@@ -130,6 +130,7 @@ fn test_redo_returns_false_when_empty() {
 #[test]
 fn test_multiple_undo_redo() {
     let mut editor = EditorState::new(400.0, 20.0);
+    editor.set_undo_coalesce_window(std::time::Duration::ZERO);
     editor.insert_text("A");
     editor.insert_text("B");
     editor.insert_text("C");
@@ -181,6 +182,221 @@ fn test_scroll_operations() {
     assert_eq!(vp.scroll_offset, 50);
 }
 
+#[test]
+fn test_replace_all_is_one_undo_step() {
+    let mut editor = EditorState::new(400.0, 20.0);
+    let text = "M3 S1000\nG1 X10\nM3 S2000\nM30\n";
+    editor.load_text(text);
+    editor.set_cursor(editor.char_count());
+
+    assert_eq!(editor.replace_all("M3 ", "M4 "), 2);
+    assert_eq!(editor.get_text(), "M4 S1000\nG1 X10\nM4 S2000\nM30\n");
+    assert_eq!(editor.cursor_pos(), editor.char_count());
+    assert_eq!(editor.replace_all("M5", "M4"), 0);
+
+    assert!(editor.undo());
+    assert_eq!(editor.get_text(), text);
+    assert!(!editor.undo());
+    assert!(editor.redo());
+    assert_eq!(editor.get_text(), "M4 S1000\nG1 X10\nM4 S2000\nM30\n");
+}
+
+#[test]
+fn test_replace_all_regex_expands_captures() {
+    let mut editor = EditorState::new(400.0, 20.0);
+    editor.load_text("G1 Z-1.0 F100\nG1 Z-1.0\n");
+    let re = regex::Regex::new(r"Z-(\d)\.0").expect("valid regex");
+    assert_eq!(editor.replace_all_regex(&re, "Z-${1}.5 (Ø)"), 2);
+    assert_eq!(editor.get_text(), "G1 Z-1.5 (Ø) F100\nG1 Z-1.5 (Ø)\n");
+    assert!(editor.undo());
+    assert_eq!(editor.get_text(), "G1 Z-1.0 F100\nG1 Z-1.0\n");
+}
+
+#[test]
+fn test_replace_all_drops_extra_cursors() {
+    let mut editor = EditorState::new(400.0, 20.0);
+    editor.load_text("M3 S1000\nM3 S2000\n");
+    editor.set_cursor(17);
+    editor.add_cursor_at(9);
+    editor.add_cursor_at(18);
+    assert_eq!(editor.selections(), vec![(9, 9), (17, 17), (18, 18)]);
+
+    // The last extra cursor would now sit past the end of the text
+    assert_eq!(editor.replace_all("M3 S", "M3 "), 2);
+    assert_eq!(editor.get_text(), "M3 1000\nM3 2000\n");
+    assert_eq!(editor.selections(), vec![(15, 15)]);
+
+    editor.add_cursor_at(0);
+    editor.insert_text(";");
+    assert_eq!(editor.get_text(), "M3 1000\nM3 2000;\n");
+    assert_eq!(editor.selections(), vec![(16, 16)]);
+
+    editor.add_cursor_at(0);
+    editor.delete_backward(1);
+    assert_eq!(editor.get_text(), "M3 1000\nM3 2000\n");
+    assert_eq!(editor.selections(), vec![(15, 15)]);
+}
+
+#[test]
+fn test_block_cursors_edit_every_line_as_one_undo() {
+    let mut editor = EditorState::new(400.0, 20.0);
+    let text = "G1 Z-1.0 F100\nG1 Z-1.0\nG0\n";
+    editor.load_text(text);
+
+    // Column 7 is after "G1 Z-1."; the short last line gets a cursor at its end
+    editor.add_block_cursors(0, 2, 7);
+    assert_eq!(
+        editor.selections(),
+        vec![(0, 0), (7, 7), (21, 21), (25, 25)]
+    );
+    editor.set_cursor(0);
+    assert_eq!(editor.selections(), vec![(0, 0)]);
+
+    editor.add_block_cursors(0, 1, 8);
+    editor.delete_backward_multi(1);
+    editor.insert_text_multi("5");
+    assert_eq!(editor.get_text(), "5G1 Z-1.5 F100\nG1 Z-1.5\nG0\n");
+    assert_eq!(editor.selections(), vec![(1, 1), (9, 9), (23, 23)]);
+
+    assert!(editor.undo());
+    assert!(editor.undo());
+    assert_eq!(editor.get_text(), text);
+    assert!(!editor.undo());
+}
+
+#[test]
+fn test_multi_cursors_that_collapse_are_merged() {
+    let mut editor = EditorState::new(400.0, 20.0);
+    editor.load_text("abcdef");
+    editor.set_cursor(4);
+    editor.add_cursor_at(3);
+    editor.add_cursor_at(3);
+    assert_eq!(editor.selections(), vec![(3, 3), (4, 4)]);
+
+    editor.delete_backward_multi(1);
+    assert_eq!(editor.get_text(), "abef");
+    assert_eq!(editor.selections(), vec![(2, 2)]);
+
+    // Deletions that meet are merged too
+    editor.add_cursor_at(4);
+    editor.delete_backward_multi(2);
+    assert_eq!(editor.get_text(), "");
+    assert_eq!(editor.selections(), vec![(0, 0)]);
+    assert_eq!(editor.cursor_pos(), 0);
+}
+
+#[test]
+fn test_typing_is_undone_as_a_word() {
+    let mut editor = EditorState::new(400.0, 20.0);
+    editor.load_text("G0\n");
+    editor.set_cursor(2);
+    for c in [" ", "X", "1", "0"] {
+        editor.insert_text(c);
+    }
+    editor.set_cursor(0);
+    editor.insert_text(";");
+    assert_eq!(editor.get_text(), ";G0 X10\n");
+
+    assert!(editor.undo());
+    assert_eq!(editor.get_text(), "G0 X10\n");
+    assert!(editor.undo());
+    assert_eq!(editor.get_text(), "G0\n");
+    assert!(!editor.undo());
+}
+
+#[test]
+fn test_goto_line_centers_and_skips_indent() {
+    let mut editor = EditorState::new(400.0, 20.0);
+    let text: String = (0..100).map(|i| format!("  G1 X{}\n", i)).collect();
+    editor.load_text(&text);
+
+    editor.goto_line(50);
+    assert_eq!(editor.current_line(), 50);
+    assert_eq!(editor.cursor_line_col(), (50, 2));
+    assert_eq!(editor.viewport().scroll_offset, 40);
+
+    // Near the top the view cannot scroll further up
+    editor.goto_line(3);
+    assert_eq!(editor.viewport().scroll_offset, 0);
+
+    // Past the end goes to the last line, an empty line after the final newline
+    editor.goto_line(4021);
+    assert_eq!(editor.current_line(), 100);
+    assert_eq!(editor.viewport().scroll_offset, 81);
+}
+
+#[test]
+fn test_highlight_line_is_surfaced_with_visible_lines() {
+    let mut editor = EditorState::new(400.0, 20.0);
+    let text: String = (0..100).map(|i| format!("G1 X{}\n", i)).collect();
+    editor.load_text(&text);
+    assert_eq!(editor.visible_highlight_line(), None);
+
+    editor.set_highlight_line(Some(12));
+    editor.scroll_to_line(10);
+    let (start, lines) = editor.get_visible_lines();
+    let marked = editor.visible_highlight_line().expect("highlight visible");
+    assert_eq!(start + marked, 12);
+    assert_eq!(lines[marked], "G1 X12\n");
+
+    editor.scroll_to_line(60);
+    assert_eq!(editor.visible_highlight_line(), None);
+    editor.set_highlight_line(Some(500));
+    assert_eq!(editor.highlight_line(), Some(100));
+    editor.set_highlight_line(None);
+    assert_eq!(editor.highlight_line(), None);
+}
+
+#[test]
+fn test_load_from_reader_keeps_editor_on_error() {
+    let mut editor = EditorState::new(400.0, 20.0);
+    editor.load_text("G0 X1\n");
+    assert!(editor.load_from_reader(&b"G1 \xc3\n"[..]).is_err());
+    assert_eq!(editor.get_text(), "G0 X1\n");
+
+    editor.insert_text("G4 P1\n");
+    editor
+        .load_from_reader("G21\r\nM30\r\n".as_bytes())
+        .expect("valid input");
+    assert_eq!(editor.get_text(), "G21\nM30\n");
+    assert_eq!(editor.line_count(), 3);
+    assert!(!editor.is_modified());
+    assert!(!editor.can_undo());
+
+    let mut saved = Vec::new();
+    editor.write_to(&mut saved).expect("write succeeds");
+    assert_eq!(saved, b"G21\r\nM30\r\n");
+}
+
+#[test]
+fn test_long_lines_scroll_horizontally() {
+    let mut editor = EditorState::new(400.0, 20.0);
+    let comment = format!("({})", "x".repeat(98));
+    editor.load_text(&format!("G0 X0\r\n{}\nG1 Y1\n", comment));
+    assert_eq!(editor.viewport().max_line_length(), 100);
+
+    editor.set_viewport_width(300.0, 10.0);
+    editor.set_h_offset(50);
+    let (start_line, start_col, lines) = editor.get_visible_lines_clipped();
+    assert_eq!((start_line, start_col), (0, 40));
+    assert_eq!(lines[0], "");
+    assert_eq!(lines[1], "x".repeat(50));
+    assert_eq!(lines[2], "");
+
+    // Typing grows the longest line; deleting it shrinks it again
+    editor.set_cursor(editor.line_col_to_char(2, 5));
+    editor.insert_text(&"y".repeat(150));
+    assert_eq!(editor.viewport().max_line_length(), 155);
+    editor.delete_backward(150);
+    assert_eq!(editor.viewport().max_line_length(), 100);
+
+    editor.scroll_horizontal_by(-100);
+    assert_eq!(editor.viewport().h_offset, 0);
+
+    assert_eq!(editor.replace_all("x", ""), 98);
+    assert_eq!(editor.viewport().max_line_length(), 5);
+}
+
 #[test]
 fn test_set_viewport_size() {
     let mut editor = EditorState::new(400.0, 20.0);
@@ -1,4 +1,4 @@
-use gcodekit5_gcodeeditor::TextBuffer;
+use gcodekit5_gcodeeditor::{BufferError, LineEnding, TextBuffer};
 
 #[test]
 fn test_create_empty() {
@@ -52,3 +52,60 @@ fn test_line_col_conversion() {
     let char_idx = buffer.line_col_to_char(1, 0);
     assert_eq!(char_idx, 7);
 }
+
+#[test]
+fn test_find_all_returns_char_ranges() {
+    let buffer = TextBuffer::from("(Ø6 tool)\nM3 S1000\nG0 X0\nm3 S500\n");
+    assert_eq!(buffer.find_all("M3", true), vec![10..12]);
+    assert_eq!(buffer.find_all("M3", false), vec![10..12, 25..27]);
+    assert_eq!(buffer.find_all("tool", true), vec![4..8]);
+    assert!(buffer.find_all("", true).is_empty());
+}
+
+#[test]
+fn test_find_all_regex_across_lines_and_chunks() {
+    // Large enough to be stored in many rope chunks
+    let text: String = (0..2000).map(|i| format!("G1 X{} Y{}\n", i, i)).collect();
+    let buffer = TextBuffer::from(text.as_str());
+    let re = regex::Regex::new(r"Y1999\nG1").expect("valid regex");
+    assert!(buffer.find_all_regex(&re).is_empty());
+    let re = regex::Regex::new(r"Y1998\nG1 X1999").expect("valid regex");
+    let found = buffer.find_all_regex(&re);
+    assert_eq!(found.len(), 1);
+    assert_eq!(
+        buffer.slice(found[0].start, found[0].end),
+        "Y1998\nG1 X1999"
+    );
+    assert_eq!(buffer.find_all("G1", true).len(), 2000);
+}
+
+#[test]
+fn test_from_reader_normalizes_and_round_trips_crlf() {
+    let input = "G21\r\nG0 X0 (Ø6)\r\nM30\r\n";
+    let buffer = TextBuffer::from_reader(input.as_bytes()).expect("valid input");
+    assert_eq!(buffer.to_string(), "G21\nG0 X0 (Ø6)\nM30\n");
+    assert_eq!(buffer.line_ending(), LineEnding::CrLf);
+
+    let mut saved = Vec::new();
+    buffer.write_to(&mut saved).expect("write succeeds");
+    assert_eq!(saved, input.as_bytes());
+
+    let buffer = TextBuffer::from_reader("G0\nG1".as_bytes()).expect("valid input");
+    assert_eq!(buffer.line_ending(), LineEnding::Lf);
+    assert_eq!(buffer.len_lines(), 2);
+}
+
+#[test]
+fn test_from_reader_builds_large_files_and_rejects_invalid_utf8() {
+    let text: String = (0..5000).map(|i| format!("G1 X{} Y{}\r\n", i, i)).collect();
+    let reader = std::io::BufReader::with_capacity(64, text.as_bytes());
+    let buffer = TextBuffer::from_reader(reader).expect("valid input");
+    assert_eq!(buffer.len_lines(), 5001);
+    assert_eq!(buffer.line(4999), Some("G1 X4999 Y4999\n".to_string()));
+
+    let invalid: &[u8] = b"G0 X0\n\xff\xfe\n";
+    assert!(matches!(
+        TextBuffer::from_reader(invalid),
+        Err(BufferError::InvalidUtf8)
+    ));
+}
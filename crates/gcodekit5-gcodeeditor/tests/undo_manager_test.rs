@@ -1,4 +1,5 @@
 use gcodekit5_gcodeeditor::{TextChange, UndoManager};
+use std::time::Duration;
 
 #[test]
 fn test_record_and_undo() {
@@ -46,6 +47,7 @@ fn test_clear_redo_on_new_change() {
 #[test]
 fn test_max_depth() {
     let mut mgr = UndoManager::with_depth(3);
+    mgr.set_coalesce_window(Duration::ZERO);
 
     mgr.record(TextChange::new(0..0, String::new(), "1".to_string(), 0, 1));
     mgr.record(TextChange::new(1..1, String::new(), "2".to_string(), 1, 2));
@@ -66,3 +68,73 @@ fn test_batch() {
 
     assert_eq!(mgr.undo_count(), 2); // Two changes in batch
 }
+
+#[test]
+fn test_consecutive_insertions_coalesce() {
+    let mut mgr = UndoManager::new();
+    for (i, c) in "G0 X1".chars().enumerate() {
+        mgr.record(TextChange::new(
+            i..i,
+            String::new(),
+            c.to_string(),
+            i,
+            i + 1,
+        ));
+    }
+    assert_eq!(mgr.undo_count(), 1);
+
+    // Not contiguous: a new step
+    mgr.record(TextChange::new(0..0, String::new(), ";".to_string(), 0, 1));
+    assert_eq!(mgr.undo_count(), 2);
+    // A deletion never merges with an insertion
+    mgr.record(TextChange::new(0..1, ";".to_string(), String::new(), 1, 0));
+    assert_eq!(mgr.undo_count(), 3);
+
+    mgr.undo();
+    mgr.undo();
+    let undo_change = mgr.undo().expect("undo failed");
+    assert_eq!(undo_change.old_text, "G0 X1");
+    assert_eq!(undo_change.new_cursor, 0);
+}
+
+#[test]
+fn test_consecutive_deletions_coalesce() {
+    let mut mgr = UndoManager::new();
+    // Backspace three times from the end of "G0 X10"
+    for end in (4..7).rev() {
+        let text = &"G0 X10"[end - 1..end];
+        mgr.record(TextChange::new(
+            end - 1..end,
+            text.to_string(),
+            String::new(),
+            end,
+            end - 1,
+        ));
+    }
+    assert_eq!(mgr.undo_count(), 1);
+    let undo_change = mgr.undo().expect("undo failed");
+    assert_eq!(undo_change.char_range, 3..3);
+    assert_eq!(undo_change.new_text, "X10");
+    assert_eq!(undo_change.new_cursor, 6);
+
+    // Delete key twice at the same position
+    let mut mgr = UndoManager::new();
+    mgr.record(TextChange::new(0..1, "G".to_string(), String::new(), 0, 0));
+    mgr.record(TextChange::new(0..1, "0".to_string(), String::new(), 0, 0));
+    assert_eq!(mgr.undo_count(), 1);
+    assert_eq!(mgr.undo().expect("undo failed").new_text, "G0");
+}
+
+#[test]
+fn test_coalescing_breaks_and_window() {
+    let mut mgr = UndoManager::new();
+    mgr.record(TextChange::new(0..0, String::new(), "A".to_string(), 0, 1));
+    mgr.break_coalescing();
+    mgr.record(TextChange::new(1..1, String::new(), "B".to_string(), 1, 2));
+    assert_eq!(mgr.undo_count(), 2);
+
+    mgr.set_coalesce_window(Duration::from_millis(1));
+    std::thread::sleep(Duration::from_millis(5));
+    mgr.record(TextChange::new(2..2, String::new(), "C".to_string(), 2, 3));
+    assert_eq!(mgr.undo_count(), 3);
+}
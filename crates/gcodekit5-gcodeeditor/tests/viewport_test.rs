@@ -77,3 +77,47 @@ fn test_overscan_range() {
     assert_eq!(range.start, 35);
     assert_eq!(range.end, 65);
 }
+
+#[test]
+fn test_horizontal_scroll() {
+    let mut viewport = Viewport::new(400.0, 20.0);
+    viewport.set_viewport_width(400.0, 10.0);
+    viewport.set_max_line_length(100);
+    assert_eq!(viewport.visible_cols, 40);
+    assert!(viewport.is_h_scrollable());
+    assert_eq!(viewport.h_scrollbar_ratio(), 0.4);
+
+    viewport.scroll_horizontal_by(25);
+    assert_eq!(viewport.h_offset, 25);
+    assert_eq!(viewport.visible_columns(100), 25..65);
+    assert_eq!(viewport.visible_columns(30), 25..30);
+    assert_eq!(viewport.visible_columns(10), 10..10);
+
+    viewport.scroll_horizontal_by(-40);
+    assert_eq!(viewport.h_offset, 0);
+
+    // Clamped so the longest line still fills the view
+    viewport.set_h_offset(90);
+    assert_eq!(viewport.h_offset, 60);
+    viewport.set_max_line_length(50);
+    assert_eq!(viewport.h_offset, 10);
+    assert_eq!(viewport.max_line_length(), 50);
+}
+
+#[test]
+fn test_horizontal_overscan_and_scroll_to_column() {
+    let mut viewport = Viewport::new(400.0, 20.0);
+    // Every column is visible until a width is set
+    viewport.set_max_line_length(200);
+    assert!(!viewport.is_h_scrollable());
+    assert_eq!(viewport.visible_columns(200), 0..200);
+
+    viewport.set_viewport_width(200.0, 10.0);
+    viewport.scroll_to_column(50);
+    assert_eq!(viewport.h_offset, 31);
+    assert_eq!(viewport.h_overscan_range(5, 200), 26..56);
+    assert_eq!(viewport.h_overscan_range(5, 40), 26..40);
+
+    viewport.scroll_to_column(10);
+    assert_eq!(viewport.h_offset, 10);
+}
@@ -10,8 +10,8 @@
 //! - Machine preferences (limits, jog settings)
 //! - Firmware-specific settings
 
-pub use gcodekit5_core::units::{FeedRateUnits, MeasurementSystem};
-use gcodekit5_core::{Error, Result};
+pub use gcodekit5_core::units::{DecimalSeparator, FeedRateUnits, MeasurementSystem};
+use gcodekit5_core::{Error, HoldPowerOff, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -53,6 +53,26 @@ pub struct ConnectionSettings {
     pub timeout_ms: u64,
     /// Auto-reconnect on connection loss
     pub auto_reconnect: bool,
+    /// Status query (`?`) interval in ms while the machine is moving or held
+    #[serde(default = "default_status_poll_active_ms")]
+    pub status_poll_active_ms: u64,
+    /// Status query interval in ms while the machine is idle or alarmed
+    #[serde(default = "default_status_poll_idle_ms")]
+    pub status_poll_idle_ms: u64,
+    /// G-code sent once after connecting, one command per line
+    #[serde(default)]
+    pub startup_block: Vec<String>,
+    /// Send `startup_block` on connect; off skips it without losing it
+    #[serde(default = "default_send_startup_block")]
+    pub send_startup_block: bool,
+    /// Minimum ms between command lines, for controllers that drop commands
+    /// sent back to back (0 = no throttle)
+    #[serde(default)]
+    pub command_delay_ms: u64,
+    /// Ms the controller needs after the soft reset sent on connect before
+    /// it answers queries
+    #[serde(default = "default_reset_settle_ms")]
+    pub reset_settle_ms: u64,
 }
 
 impl Default for ConnectionSettings {
@@ -64,10 +84,180 @@ impl Default for ConnectionSettings {
             tcp_port: 8888,
             timeout_ms: 5000,
             auto_reconnect: true,
+            status_poll_active_ms: default_status_poll_active_ms(),
+            status_poll_idle_ms: default_status_poll_idle_ms(),
+            startup_block: Vec::new(),
+            send_startup_block: default_send_startup_block(),
+            command_delay_ms: 0,
+            reset_settle_ms: default_reset_settle_ms(),
         }
     }
 }
 
+/// Allowed range for the status poll intervals in ms
+pub const STATUS_POLL_RANGE_MS: std::ops::RangeInclusive<u64> = 50..=5000;
+
+fn default_status_poll_active_ms() -> u64 {
+    250
+}
+
+fn default_status_poll_idle_ms() -> u64 {
+    1000
+}
+
+fn default_send_startup_block() -> bool {
+    true
+}
+
+/// Longest inter-command delay in ms
+pub const COMMAND_DELAY_MAX_MS: u64 = 1000;
+/// Longest post-reset settle time in ms
+pub const RESET_SETTLE_MAX_MS: u64 = 10_000;
+
+fn default_reset_settle_ms() -> u64 {
+    250
+}
+
+/// Longest startup block line, GRBL's line buffer size
+pub const STARTUP_LINE_MAX: usize = 80;
+
+/// Parse a startup block, one command per line
+///
+/// Lines may also be separated with `|`, which is how the settings dialog
+/// shows the block on one line. Blank lines are dropped and the rest validated
+/// with [`validate_startup_block`].
+pub fn parse_startup_block(text: &str) -> Result<Vec<String>> {
+    let lines: Vec<String> = text
+        .split(['\n', '|'])
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+    validate_startup_block(&lines)?;
+    Ok(lines)
+}
+
+/// Format a startup block on one line, separated with `|`
+pub fn format_startup_block(lines: &[String]) -> String {
+    lines.join(" | ")
+}
+
+/// Check that every startup line is a G-code block or `$` command that is safe
+/// to send as a normal line
+///
+/// Real-time characters and control bytes are refused, since the controller
+/// would act on them before the line is parsed, as is `$10=`, which would undo
+/// the status report mask set on connect.
+pub fn validate_startup_block(lines: &[String]) -> Result<()> {
+    for (index, line) in lines.iter().enumerate() {
+        let invalid = |reason: String| {
+            Err(Error::other(format!(
+                "Startup line {} ('{}'): {}",
+                index + 1,
+                line,
+                reason
+            )))
+        };
+        if line.len() > STARTUP_LINE_MAX {
+            return invalid(format!("longer than {} characters", STARTUP_LINE_MAX));
+        }
+        if line.contains('|') || line.contains('\n') {
+            return invalid("holds more than one line".to_string());
+        }
+        let code = strip_comments(line);
+        if let Some(c) = code
+            .chars()
+            .find(|c| !c.is_ascii() || c.is_ascii_control() || matches!(c, '?' | '!' | '~'))
+        {
+            return invalid(format!(
+                "'{}' is a real-time or control character",
+                c.escape_default()
+            ));
+        }
+        let code = code.trim().to_ascii_uppercase();
+        if let Some(command) = code.strip_prefix('$') {
+            if command.replace(' ', "").starts_with("10=") {
+                return invalid("changes the status report mask set on connect".to_string());
+            }
+            continue;
+        }
+        let compact: Vec<char> = code.chars().filter(|c| !c.is_whitespace()).collect();
+        let mut i = 0;
+        while i < compact.len() {
+            let letter = compact[i];
+            let start = i + 1;
+            let mut end = start;
+            while end < compact.len() && matches!(compact[end], '0'..='9' | '.' | '-' | '+') {
+                end += 1;
+            }
+            let number: String = compact[start..end].iter().collect();
+            if !letter.is_ascii_alphabetic() || number.parse::<f64>().is_err() {
+                let word: String = compact[i..end.max(start)].iter().collect();
+                return invalid(format!("'{}' is not a G-code word", word));
+            }
+            i = end;
+        }
+    }
+    Ok(())
+}
+
+/// A line without its `(...)` and `;` comments
+fn strip_comments(line: &str) -> String {
+    let mut code = String::new();
+    let mut depth = 0;
+    for c in line.chars() {
+        match c {
+            ';' if depth == 0 => break,
+            '(' => depth += 1,
+            ')' if depth > 0 => depth -= 1,
+            _ if depth == 0 => code.push(c),
+            _ => {}
+        }
+    }
+    code
+}
+
+impl ConnectionSettings {
+    /// Status poll interval for the last reported machine state
+    ///
+    /// Run, Jog, Hold, Home, Door and Check poll at the active rate, as does a
+    /// streaming job or a state not reported yet; Idle, Alarm and Sleep poll at
+    /// the idle rate. Both are clamped to [`STATUS_POLL_RANGE_MS`].
+    pub fn status_poll_interval_ms(&self, machine_state: Option<&str>, streaming: bool) -> u64 {
+        let active = self
+            .status_poll_active_ms
+            .clamp(*STATUS_POLL_RANGE_MS.start(), *STATUS_POLL_RANGE_MS.end());
+        let idle = self
+            .status_poll_idle_ms
+            .clamp(active, *STATUS_POLL_RANGE_MS.end());
+        // States are reported as e.g. "Idle", "Hold:0" or "Alarm"
+        let state =
+            machine_state.map(|s| s.split(':').next().unwrap_or(s).trim().to_ascii_lowercase());
+        match state.as_deref() {
+            _ if streaming => active,
+            Some("idle" | "alarm" | "sleep") => idle,
+            _ => active,
+        }
+    }
+
+    /// Startup lines to send on connect, without comment-only lines
+    ///
+    /// Empty when sending is switched off. Fails if a line is invalid, e.g. in
+    /// a hand-edited config file, so nothing is sent until it is fixed.
+    pub fn startup_commands(&self) -> Result<Vec<&str>> {
+        if !self.send_startup_block {
+            return Ok(Vec::new());
+        }
+        validate_startup_block(&self.startup_block)?;
+        Ok(self
+            .startup_block
+            .iter()
+            .map(|line| line.trim())
+            .filter(|line| !strip_comments(line).trim().is_empty())
+            .collect())
+    }
+}
+
 /// Theme selection
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -157,6 +347,9 @@ pub struct UiSettings {
     /// Feed rate units preference
     #[serde(default)]
     pub feed_rate_units: FeedRateUnits,
+    /// Decimal separator accepted in typed values
+    #[serde(default)]
+    pub decimal_separator: DecimalSeparator,
     /// Startup tab
     #[serde(default)]
     pub startup_tab: StartupTab,
@@ -215,6 +408,22 @@ pub struct UiSettings {
     /// Grid minor line width in pixels (fine grid lines)
     #[serde(default = "default_grid_minor_line_width")]
     pub grid_minor_line_width: f64,
+
+    /// Visualizer grid lines pass through this point (mm, X)
+    #[serde(default)]
+    pub grid_origin_x: f64,
+
+    /// Visualizer grid lines pass through this point (mm, Y)
+    #[serde(default)]
+    pub grid_origin_y: f64,
+
+    /// Mark and label the grid origin in the visualizer
+    #[serde(default)]
+    pub show_grid_origin: bool,
+
+    /// Lines the device console hides from view
+    #[serde(default)]
+    pub console_filter: ConsoleFilter,
 }
 
 impl Default for UiSettings {
@@ -237,6 +446,7 @@ impl Default for UiSettings {
             show_menu_shortcuts: true,
             measurement_system: MeasurementSystem::default(),
             feed_rate_units: FeedRateUnits::default(),
+            decimal_separator: DecimalSeparator::default(),
             startup_tab: StartupTab::default(),
             show_about_on_startup: false,
             enable_stock_removal_3d: false,
@@ -252,7 +462,45 @@ impl Default for UiSettings {
             tools_manager_selected_tool: None,
             grid_major_line_width: 2.0,
             grid_minor_line_width: 1.0,
+            grid_origin_x: 0.0,
+            grid_origin_y: 0.0,
+            show_grid_origin: false,
+            console_filter: ConsoleFilter::default(),
+        }
+    }
+}
+
+/// Device console lines hidden from view
+///
+/// Filtering is applied when the console is drawn; the log itself keeps every
+/// line, so copying or saving it is unaffected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConsoleFilter {
+    /// Hide `?` status polls and the `<...>` reports they return
+    pub hide_status_polls: bool,
+    /// Hide `$J=` jog commands
+    pub hide_jog: bool,
+    /// Hide `ok` acknowledgements
+    pub hide_ok: bool,
+    /// Hide every echoed `>` command
+    pub hide_echoes: bool,
+}
+
+impl ConsoleFilter {
+    /// Whether a console line is shown under this filter
+    pub fn shows(&self, line: &str) -> bool {
+        let line = line.trim();
+        if let Some(command) = line.strip_prefix('>') {
+            let command = command.trim_start();
+            return !(self.hide_echoes
+                || (self.hide_status_polls && command == "?")
+                || (self.hide_jog && command.starts_with("$J=")));
+        }
+        if self.hide_status_polls && line.starts_with('<') && line.ends_with('>') {
+            return false;
         }
+        !(self.hide_ok && line.eq_ignore_ascii_case("ok"))
     }
 }
 
@@ -283,6 +531,26 @@ pub struct FileProcessingSettings {
     pub recent_files_count: usize,
     /// Whether to add N[nnn] line numbers in generated G-code
     pub line_numbers_enabled: bool,
+    /// Whether to auto-save unsaved editor and designer work for crash recovery
+    #[serde(default = "default_autosave_enabled")]
+    pub autosave_enabled: bool,
+    /// Seconds between auto-saves, within [`AUTOSAVE_INTERVAL_RANGE_SECS`]
+    #[serde(default = "default_autosave_interval_secs")]
+    pub autosave_interval_secs: u64,
+    /// Directory for recovery files; empty for `recovery` in the config directory
+    #[serde(default)]
+    pub recovery_directory: PathBuf,
+}
+
+/// Allowed range for the auto-save interval in seconds
+pub const AUTOSAVE_INTERVAL_RANGE_SECS: std::ops::RangeInclusive<u64> = 10..=3600;
+
+fn default_autosave_enabled() -> bool {
+    true
+}
+
+fn default_autosave_interval_secs() -> u64 {
+    60
 }
 
 impl Default for FileProcessingSettings {
@@ -298,6 +566,9 @@ impl Default for FileProcessingSettings {
             output_directory: dirs::home_dir().unwrap_or_else(|| PathBuf::from(".")),
             recent_files_count: 10,
             line_numbers_enabled: false,
+            autosave_enabled: default_autosave_enabled(),
+            autosave_interval_secs: default_autosave_interval_secs(),
+            recovery_directory: PathBuf::new(),
         }
     }
 }
@@ -319,6 +590,331 @@ pub struct MachineSettings {
     pub default_unit: String,
     /// Homing direction per axis (true = negative, false = positive)
     pub homing_direction: HashMap<String, bool>,
+    /// Checks run before a job is streamed
+    #[serde(default)]
+    pub preflight: PreflightSettings,
+    /// Record actual job times locally to calibrate the time estimate (opt-in)
+    #[serde(default)]
+    pub record_job_timing: bool,
+    /// Block delete switch: skip lines starting with `/` when streaming
+    #[serde(default = "default_block_delete")]
+    pub block_delete: bool,
+    /// Optional stop switch: pause at `M1` when streaming
+    #[serde(default = "default_optional_stop")]
+    pub optional_stop: bool,
+    /// Command that turns the spindle or laser off when the operator pauses a job
+    #[serde(default)]
+    pub hold_power_off: HoldPowerOff,
+    /// Feed override (%) a job resumes at after a pause, ramped back up to the
+    /// override before the pause; 0 resumes at full feed
+    #[serde(default)]
+    pub resume_ramp_percent: u16,
+    /// How long the resume ramp takes in ms
+    #[serde(default = "default_resume_ramp_ms")]
+    pub resume_ramp_ms: u64,
+    /// Distance (mm) the resume ramp takes instead of its time; 0 uses the time
+    #[serde(default)]
+    pub resume_ramp_distance: f64,
+    /// Keyboard jog bindings used by Machine Control
+    #[serde(default = "default_jog_keys")]
+    pub jog_keys: Vec<JogKeyBinding>,
+    /// Maximum jog rate per axis letter in units/min; axes without one are not limited
+    #[serde(default)]
+    pub jog_max_rates: HashMap<String, f64>,
+    /// Work Z (mm) to retract to before go-to moves such as Go to Work Zero
+    #[serde(default = "default_safe_z")]
+    pub safe_z: f64,
+    /// Distance (mm) from a soft limit at which the DRO warns that an axis is
+    /// near it; 0 only flags an axis on its limit
+    #[serde(default = "default_limit_warning_margin")]
+    pub limit_warning_margin: f64,
+}
+
+impl MachineSettings {
+    /// Maximum jog rate of an axis, if one is set
+    pub fn jog_max_rate(&self, axis: char) -> Option<f64> {
+        self.jog_max_rates
+            .get(&axis.to_ascii_uppercase().to_string())
+            .copied()
+    }
+}
+
+/// Longest resume ramp in ms
+pub const RESUME_RAMP_MAX_MS: u64 = 60_000;
+
+fn default_resume_ramp_ms() -> u64 {
+    3000
+}
+
+/// Default go-to retract height
+fn default_safe_z() -> f64 {
+    5.0
+}
+
+/// Default near-limit warning distance
+fn default_limit_warning_margin() -> f64 {
+    5.0
+}
+
+/// Default value for the block delete switch
+fn default_block_delete() -> bool {
+    true
+}
+
+/// Default value for the optional stop switch
+fn default_optional_stop() -> bool {
+    true
+}
+
+/// Axes a jog key can move
+pub const JOG_KEY_AXES: [char; 5] = ['X', 'Y', 'Z', 'A', 'B'];
+
+/// A key that jogs one axis in one direction
+///
+/// Written as `key=axis±`, optionally with a second axis for a diagonal jog and a
+/// trailing ` hold` for continuous jogging, e.g. `8=Y+`, `7=X-Y+` or `Page_Up=Z+ hold`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JogKeyBinding {
+    /// GDK key name (`KP_8`, `Page_Up`) or the character the key types (`8`)
+    pub key: String,
+    /// Axis letter, one of [`JOG_KEY_AXES`]
+    pub axis: char,
+    /// Jog towards positive coordinates
+    pub positive: bool,
+    /// Second axis and direction moved at the same time, for diagonal jogs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diagonal: Option<(char, bool)>,
+    /// Jog while the key is held instead of one step per press
+    #[serde(default)]
+    pub continuous: bool,
+}
+
+impl JogKeyBinding {
+    /// Binding that jogs one step per press
+    pub fn step(key: impl Into<String>, axis: char, positive: bool) -> Self {
+        Self {
+            key: key.into(),
+            axis,
+            positive,
+            diagonal: None,
+            continuous: false,
+        }
+    }
+
+    /// Also move a second axis, making the jog diagonal
+    pub fn with_diagonal(mut self, axis: char, positive: bool) -> Self {
+        self.diagonal = Some((axis, positive));
+        self
+    }
+
+    /// Axes and directions (+1/-1) this binding moves
+    pub fn directions(&self) -> Vec<(char, f64)> {
+        let sign = |positive: bool| if positive { 1.0 } else { -1.0 };
+        std::iter::once((self.axis, sign(self.positive)))
+            .chain(self.diagonal.map(|(axis, positive)| (axis, sign(positive))))
+            .collect()
+    }
+
+    /// Binding that jogs while the key is held
+    pub fn continuous(key: impl Into<String>, axis: char, positive: bool) -> Self {
+        Self {
+            continuous: true,
+            ..Self::step(key, axis, positive)
+        }
+    }
+
+    /// Whether a pressed key, given by GDK name and typed character, is this binding's key
+    pub fn matches(&self, key_name: &str, typed: Option<char>) -> bool {
+        if self.key == key_name {
+            return true;
+        }
+        let mut chars = self.key.chars();
+        matches!((chars.next(), chars.next(), typed), (Some(c), None, Some(t)) if c == t)
+    }
+}
+
+impl std::fmt::Display for JogKeyBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = |positive: bool| if positive { '+' } else { '-' };
+        write!(f, "{}={}{}", self.key, self.axis, sign(self.positive))?;
+        if let Some((axis, positive)) = self.diagonal {
+            write!(f, "{}{}", axis, sign(positive))?;
+        }
+        if self.continuous {
+            write!(f, " hold")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for JogKeyBinding {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || {
+            Error::other(format!(
+                "Invalid jog key binding '{}' (expected key=axis+ or key=axis-, optionally followed by 'hold')",
+                s.trim()
+            ))
+        };
+        let (key, action) = s.trim().split_once('=').ok_or_else(invalid)?;
+        let key = key.trim();
+        let mut parts = action.split_whitespace();
+        let motion = parts.next().ok_or_else(invalid)?;
+        let continuous = match parts.next() {
+            None => false,
+            Some(word) if word.eq_ignore_ascii_case("hold") => true,
+            Some(_) => return Err(invalid()),
+        };
+        if key.is_empty() || parts.next().is_some() {
+            return Err(invalid());
+        }
+        let chars: Vec<char> = motion.chars().collect();
+        let mut axes = Vec::new();
+        for pair in chars.chunks(2) {
+            let positive = match pair {
+                [_, '+'] => true,
+                [_, '-'] => false,
+                _ => return Err(invalid()),
+            };
+            axes.push((pair[0].to_ascii_uppercase(), positive));
+        }
+        let (&(axis, positive), diagonal) = match axes.as_slice() {
+            [first] => (first, None),
+            [first, second] => (first, Some(*second)),
+            _ => return Err(invalid()),
+        };
+        Ok(Self {
+            key: key.to_string(),
+            axis,
+            positive,
+            diagonal,
+            continuous,
+        })
+    }
+}
+
+/// Default jog keys: the number pad layout, 8/2 for Y, 4/6 for X and 9/3 for Z
+pub fn default_jog_keys() -> Vec<JogKeyBinding> {
+    vec![
+        JogKeyBinding::step("8", 'Y', true),
+        JogKeyBinding::step("2", 'Y', false),
+        JogKeyBinding::step("4", 'X', false),
+        JogKeyBinding::step("6", 'X', true),
+        JogKeyBinding::step("9", 'Z', true),
+        JogKeyBinding::step("3", 'Z', false),
+    ]
+}
+
+/// Parse a comma-separated list of jog key bindings
+pub fn parse_jog_keys(text: &str) -> Result<Vec<JogKeyBinding>> {
+    text.split(',')
+        .filter(|part| !part.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// Format jog key bindings as a comma-separated list
+pub fn format_jog_keys(bindings: &[JogKeyBinding]) -> String {
+    bindings
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Check jog key bindings for unknown axes and keys bound more than once
+pub fn validate_jog_keys(bindings: &[JogKeyBinding]) -> Result<()> {
+    for (index, binding) in bindings.iter().enumerate() {
+        if binding.key.trim().is_empty() {
+            return Err(Error::other("Jog key binding has no key".to_string()));
+        }
+        let axes = binding.directions();
+        if let Some((axis, _)) = axes.iter().find(|(axis, _)| !JOG_KEY_AXES.contains(axis)) {
+            return Err(Error::other(format!(
+                "Jog key '{}' uses unknown axis '{}'",
+                binding.key, axis
+            )));
+        }
+        if binding
+            .diagonal
+            .is_some_and(|(axis, _)| axis == binding.axis)
+        {
+            return Err(Error::other(format!(
+                "Jog key '{}' moves axis '{}' twice",
+                binding.key, binding.axis
+            )));
+        }
+        if let Some(other) = bindings[..index].iter().find(|b| b.key == binding.key) {
+            return Err(Error::other(format!(
+                "Jog key '{}' is bound twice ({} and {})",
+                binding.key, other, binding
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Parse per-axis jog rates written as `X=5000, Z=500`
+pub fn parse_jog_rates(text: &str) -> Result<HashMap<String, f64>> {
+    let mut rates = HashMap::new();
+    for part in text.split(',').filter(|part| !part.trim().is_empty()) {
+        let invalid = || {
+            Error::other(format!(
+                "Invalid jog rate '{}' (expected axis=rate, e.g. Z=500)",
+                part.trim()
+            ))
+        };
+        let (axis, rate) = part.split_once('=').ok_or_else(invalid)?;
+        let axis = axis.trim().to_ascii_uppercase();
+        let rate: f64 = rate.trim().parse().map_err(|_| invalid())?;
+        rates.insert(axis, rate);
+    }
+    Ok(rates)
+}
+
+/// Format per-axis jog rates in axis order
+pub fn format_jog_rates(rates: &HashMap<String, f64>) -> String {
+    let mut axes: Vec<&String> = rates.keys().collect();
+    axes.sort_by_key(|axis| {
+        let letter = axis.chars().next().unwrap_or_default();
+        (
+            JOG_KEY_AXES
+                .iter()
+                .position(|a| *a == letter)
+                .unwrap_or(JOG_KEY_AXES.len()),
+            axis.to_string(),
+        )
+    });
+    axes.iter()
+        .map(|axis| format!("{}={}", axis, rates[*axis]))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Pre-flight checks to run before starting a job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreflightSettings {
+    /// Ask for spindle/laser safety confirmation
+    pub confirm_safety: bool,
+    /// Check the program against the device profile's axis limits
+    pub soft_limits: bool,
+    /// Warn when the program never turns the spindle or laser on
+    pub spindle_command: bool,
+    /// Warn when the program has no M2/M30
+    pub program_end: bool,
+}
+
+impl Default for PreflightSettings {
+    fn default() -> Self {
+        Self {
+            confirm_safety: true,
+            soft_limits: true,
+            spindle_command: true,
+            program_end: true,
+        }
+    }
 }
 
 impl Default for MachineSettings {
@@ -336,6 +932,18 @@ impl Default for MachineSettings {
             z_limit: 100.0,
             default_unit: "mm".to_string(),
             homing_direction: homing,
+            preflight: PreflightSettings::default(),
+            record_job_timing: false,
+            block_delete: true,
+            optional_stop: true,
+            hold_power_off: HoldPowerOff::default(),
+            resume_ramp_percent: 0,
+            resume_ramp_ms: default_resume_ramp_ms(),
+            resume_ramp_distance: 0.0,
+            jog_keys: default_jog_keys(),
+            jog_max_rates: HashMap::new(),
+            safe_z: default_safe_z(),
+            limit_warning_margin: default_limit_warning_margin(),
         }
     }
 }
@@ -434,6 +1042,41 @@ impl Config {
             return Err(Error::other("Baud rate must be > 0".to_string()));
         }
 
+        for (name, interval) in [
+            ("Active", self.connection.status_poll_active_ms),
+            ("Idle", self.connection.status_poll_idle_ms),
+        ] {
+            if !STATUS_POLL_RANGE_MS.contains(&interval) {
+                return Err(Error::other(format!(
+                    "{} status poll interval must be {}-{} ms, got {}",
+                    name,
+                    STATUS_POLL_RANGE_MS.start(),
+                    STATUS_POLL_RANGE_MS.end(),
+                    interval
+                )));
+            }
+        }
+        if self.connection.status_poll_idle_ms < self.connection.status_poll_active_ms {
+            return Err(Error::other(
+                "Idle status poll interval must not be shorter than the active one".to_string(),
+            ));
+        }
+
+        if self.connection.command_delay_ms > COMMAND_DELAY_MAX_MS {
+            return Err(Error::other(format!(
+                "Command delay must be at most {} ms, got {}",
+                COMMAND_DELAY_MAX_MS, self.connection.command_delay_ms
+            )));
+        }
+        if self.connection.reset_settle_ms > RESET_SETTLE_MAX_MS {
+            return Err(Error::other(format!(
+                "Reset settle time must be at most {} ms, got {}",
+                RESET_SETTLE_MAX_MS, self.connection.reset_settle_ms
+            )));
+        }
+
+        validate_startup_block(&self.connection.startup_block)?;
+
         // Validate UI settings
         if self.ui.window_width == 0 || self.ui.window_height == 0 {
             return Err(Error::other("Window dimensions must be > 0".to_string()));
@@ -443,6 +1086,12 @@ impl Config {
             return Err(Error::other("Font size must be > 0".to_string()));
         }
 
+        if !self.ui.grid_origin_x.is_finite() || !self.ui.grid_origin_y.is_finite() {
+            return Err(Error::other(
+                "Grid origin must be a finite position".to_string(),
+            ));
+        }
+
         // Validate file processing
         if self.file_processing.arc_segment_length <= 0.0 {
             return Err(Error::other("Arc segment length must be > 0".to_string()));
@@ -452,6 +1101,15 @@ impl Config {
             return Err(Error::other("Max line length must be > 0".to_string()));
         }
 
+        if !AUTOSAVE_INTERVAL_RANGE_SECS.contains(&self.file_processing.autosave_interval_secs) {
+            return Err(Error::other(format!(
+                "Auto-save interval must be {}-{} s, got {}",
+                AUTOSAVE_INTERVAL_RANGE_SECS.start(),
+                AUTOSAVE_INTERVAL_RANGE_SECS.end(),
+                self.file_processing.autosave_interval_secs
+            )));
+        }
+
         // Validate machine settings
         if self.machine.jog_feed_rate <= 0.0 {
             return Err(Error::other("Jog feed rate must be > 0".to_string()));
@@ -462,6 +1120,50 @@ impl Config {
             return Err(Error::other("Machine limits must be > 0".to_string()));
         }
 
+        validate_jog_keys(&self.machine.jog_keys)?;
+
+        for (axis, rate) in &self.machine.jog_max_rates {
+            let known = axis.len() == 1 && axis.chars().all(|a| JOG_KEY_AXES.contains(&a));
+            if !known {
+                return Err(Error::other(format!("Unknown jog rate axis '{}'", axis)));
+            }
+            if !rate.is_finite() || *rate <= 0.0 {
+                return Err(Error::other(format!("Jog rate for {} must be > 0", axis)));
+            }
+        }
+
+        if !self.machine.safe_z.is_finite() {
+            return Err(Error::other("Safe height must be a number".to_string()));
+        }
+
+        let margin = self.machine.limit_warning_margin;
+        if !margin.is_finite() || margin < 0.0 {
+            return Err(Error::other(format!(
+                "Limit warning margin must be 0 or more, got {}",
+                margin
+            )));
+        }
+
+        let ramp_percent = self.machine.resume_ramp_percent;
+        if ramp_percent != 0 && !(10..=100).contains(&ramp_percent) {
+            return Err(Error::other(format!(
+                "Resume ramp feed must be 0 (off) or 10-100%, got {}",
+                ramp_percent
+            )));
+        }
+        if self.machine.resume_ramp_ms > RESUME_RAMP_MAX_MS {
+            return Err(Error::other(format!(
+                "Resume ramp time must be at most {} ms, got {}",
+                RESUME_RAMP_MAX_MS, self.machine.resume_ramp_ms
+            )));
+        }
+        let ramp_distance = self.machine.resume_ramp_distance;
+        if !ramp_distance.is_finite() || ramp_distance < 0.0 {
+            return Err(Error::other(
+                "Resume ramp distance must be 0 or more".to_string(),
+            ));
+        }
+
         Ok(())
     }
 
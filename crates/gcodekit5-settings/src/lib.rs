@@ -7,11 +7,16 @@ pub mod controller;
 pub mod error;
 pub mod manager;
 pub mod persistence;
+pub mod recovery;
 pub mod view_model;
 
 pub use config::{
-    Config, ConnectionSettings, ConnectionType, FileProcessingSettings, FirmwareSettings,
-    MachineSettings, Theme, UiSettings,
+    default_jog_keys, format_jog_keys, format_jog_rates, format_startup_block, parse_jog_keys,
+    parse_jog_rates, parse_startup_block, validate_jog_keys, validate_startup_block, Config,
+    ConnectionSettings, ConnectionType, ConsoleFilter, FileProcessingSettings, FirmwareSettings,
+    JogKeyBinding, MachineSettings, PreflightSettings, Theme, UiSettings,
+    AUTOSAVE_INTERVAL_RANGE_SECS, COMMAND_DELAY_MAX_MS, JOG_KEY_AXES, RESET_SETTLE_MAX_MS,
+    RESUME_RAMP_MAX_MS, STARTUP_LINE_MAX, STATUS_POLL_RANGE_MS,
 };
 pub use controller::{SettingUiModel, SettingsController};
 pub use error::{
@@ -19,4 +24,5 @@ pub use error::{
 };
 pub use manager::SettingsManager;
 pub use persistence::SettingsPersistence;
+pub use recovery::{DocumentStamp, RecoveryFile, RecoveryKind, RecoveryStore};
 pub use view_model::{KeyboardShortcut, Setting, SettingValue, SettingsCategory, SettingsDialog};
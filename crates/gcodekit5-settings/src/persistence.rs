@@ -4,11 +4,14 @@
 //! Bridges SettingsDialog (UI) with Config (persistence layer).
 //! Provides validation, migration, and synchronization of settings.
 
-use crate::config::Config;
+use crate::config::{
+    format_jog_keys, format_jog_rates, format_startup_block, parse_jog_keys, parse_jog_rates,
+    parse_startup_block, Config, AUTOSAVE_INTERVAL_RANGE_SECS,
+};
 use crate::view_model::{
     KeyboardShortcut, Setting, SettingValue, SettingsCategory, SettingsDialog,
 };
-use gcodekit5_core::Result;
+use gcodekit5_core::{Error, Result};
 use std::path::Path;
 
 /// Settings persistence layer
@@ -42,6 +45,9 @@ impl SettingsPersistence {
         // Connection Settings - Moved to DeviceDB
         // self.add_connection_settings(dialog);
 
+        // Status polling and the startup block stay app-wide
+        self.add_session_settings(dialog);
+
         // General Settings
         self.add_general_settings(dialog);
 
@@ -51,6 +57,9 @@ impl SettingsPersistence {
         // File Processing Settings
         self.add_file_processing_settings(dialog);
 
+        // Pre-flight Checks
+        self.add_preflight_settings(dialog);
+
         // Advanced Settings
         self.add_advanced_settings(dialog);
 
@@ -63,6 +72,9 @@ impl SettingsPersistence {
         // Update connection settings - Moved to DeviceDB
         // self.update_connection_settings(dialog)?;
 
+        // Update status polling and startup block
+        self.update_session_settings(dialog)?;
+
         // Update General settings
         self.update_general_settings(dialog)?;
 
@@ -72,6 +84,9 @@ impl SettingsPersistence {
         // Update file processing settings
         self.update_file_processing_settings(dialog)?;
 
+        // Update pre-flight checks
+        self.update_preflight_settings(dialog)?;
+
         // Update advanced settings
         self.update_advanced_settings(dialog)?;
 
@@ -130,6 +145,24 @@ impl SettingsPersistence {
             .with_category(SettingsCategory::General),
         );
 
+        // Decimal Separator
+        let separators = vec![
+            "Point".to_string(),
+            "Comma".to_string(),
+            "Either".to_string(),
+        ];
+        dialog.add_setting(
+            Setting::new(
+                "decimal_separator",
+                "Decimal Separator",
+                SettingValue::Enum(ui.decimal_separator.to_string(), separators),
+            )
+            .with_description(
+                "Decimal separator accepted in typed values; G-code always uses a point",
+            )
+            .with_category(SettingsCategory::General),
+        );
+
         // Default Directory
         dialog.add_setting(
             Setting::new(
@@ -140,6 +173,206 @@ impl SettingsPersistence {
             .with_description("Default directory for file operations")
             .with_category(SettingsCategory::General),
         );
+
+        // Block Delete
+        dialog.add_setting(
+            Setting::new(
+                "block_delete",
+                "Block Delete",
+                SettingValue::Boolean(self.config.machine.block_delete),
+            )
+            .with_description(
+                "Skip optional lines starting with / (when off they run without the /)",
+            )
+            .with_category(SettingsCategory::General),
+        );
+
+        // Optional Stop
+        dialog.add_setting(
+            Setting::new(
+                "optional_stop",
+                "Optional Stop",
+                SettingValue::Boolean(self.config.machine.optional_stop),
+            )
+            .with_description("Pause at M1 optional stops while streaming (when off M1 is skipped)")
+            .with_category(SettingsCategory::General),
+        );
+
+        // Power off on pause
+        let hold_commands = vec!["Disabled".to_string(), "M5".to_string(), "S0".to_string()];
+        dialog.add_setting(
+            Setting::new(
+                "hold_power_off",
+                "Power Off on Pause",
+                SettingValue::Enum(self.config.machine.hold_power_off.to_string(), hold_commands),
+            )
+            .with_description(
+                "Turn the spindle or laser off while a job is paused and restore it on resume (skipped where the firmware already does)",
+            )
+            .with_category(SettingsCategory::General),
+        );
+
+        // Feed ramp on resume
+        dialog.add_setting(
+            Setting::new(
+                "resume_ramp_percent",
+                "Resume Feed Ramp",
+                SettingValue::Integer(self.config.machine.resume_ramp_percent as i32),
+            )
+            .with_description(
+                "Feed override (%) to resume a paused job at, ramped back to the override before the pause (0 = off, 10-100; GRBL, grblHAL and FluidNC)",
+            )
+            .with_category(SettingsCategory::General),
+        );
+
+        dialog.add_setting(
+            Setting::new(
+                "resume_ramp_ms",
+                "Resume Ramp Time",
+                SettingValue::Integer(self.config.machine.resume_ramp_ms as i32),
+            )
+            .with_description("Milliseconds the resume feed ramp takes (up to 60000)")
+            .with_category(SettingsCategory::General),
+        );
+
+        dialog.add_setting(
+            Setting::new(
+                "resume_ramp_distance",
+                "Resume Ramp Distance (mm)",
+                SettingValue::String(self.config.machine.resume_ramp_distance.to_string()),
+            )
+            .with_description(
+                "Distance the tool travels during the resume feed ramp, used instead of the time (0 = use the time)",
+            )
+            .with_category(SettingsCategory::General),
+        );
+
+        // Jog Keys
+        dialog.add_setting(
+            Setting::new(
+                "jog_key_bindings",
+                "Jog Keys",
+                SettingValue::String(format_jog_keys(&self.config.machine.jog_keys)),
+            )
+            .with_description(
+                "Machine Control jog keys as key=axis+/-, comma separated; add 'hold' to jog while held (e.g. 8=Y+, Page_Up=Z+ hold, KP_Add=A+)",
+            )
+            .with_category(SettingsCategory::KeyboardShortcuts),
+        );
+
+        // Per-axis jog rates
+        dialog.add_setting(
+            Setting::new(
+                "jog_max_rates",
+                "Maximum Jog Rates",
+                SettingValue::String(format_jog_rates(&self.config.machine.jog_max_rates)),
+            )
+            .with_description(
+                "Maximum jog rate per axis in units/min, e.g. X=5000, Y=5000, Z=500; diagonal jogs slow down to respect every axis",
+            )
+            .with_category(SettingsCategory::General),
+        );
+
+        // Go-to retract height
+        dialog.add_setting(
+            Setting::new(
+                "safe_z",
+                "Safe Height (mm)",
+                SettingValue::String(self.config.machine.safe_z.to_string()),
+            )
+            .with_description(
+                "Work Z that Go to Work Zero retracts to before moving in X/Y; kept below the top of Z travel",
+            )
+            .with_category(SettingsCategory::General),
+        );
+
+        // Near-limit DRO warning
+        dialog.add_setting(
+            Setting::new(
+                "limit_warning_margin",
+                "Limit Warning Margin (mm)",
+                SettingValue::String(self.config.machine.limit_warning_margin.to_string()),
+            )
+            .with_description(
+                "Distance from a soft limit at which the DRO marks an axis as near it; 0 only marks an axis on its limit",
+            )
+            .with_category(SettingsCategory::General),
+        );
+    }
+
+    /// Add status polling and startup block settings to dialog
+    fn add_session_settings(&self, dialog: &mut SettingsDialog) {
+        let connection = &self.config.connection;
+
+        dialog.add_setting(
+            Setting::new(
+                "status_poll_active_ms",
+                "Status Poll Interval (Active)",
+                SettingValue::Integer(connection.status_poll_active_ms as i32),
+            )
+            .with_description(
+                "Milliseconds between status queries while running, jogging or on hold (50-5000)",
+            )
+            .with_category(SettingsCategory::Controller),
+        );
+
+        dialog.add_setting(
+            Setting::new(
+                "status_poll_idle_ms",
+                "Status Poll Interval (Idle)",
+                SettingValue::Integer(connection.status_poll_idle_ms as i32),
+            )
+            .with_description(
+                "Milliseconds between status queries while idle or alarmed; raise it for slow links (50-5000)",
+            )
+            .with_category(SettingsCategory::Controller),
+        );
+
+        dialog.add_setting(
+            Setting::new(
+                "startup_block",
+                "Startup Block",
+                SettingValue::String(format_startup_block(&connection.startup_block)),
+            )
+            .with_description(
+                "G-code sent once after connecting, lines separated by | (e.g. G21 G90 G17 | G54)",
+            )
+            .with_category(SettingsCategory::Controller),
+        );
+
+        dialog.add_setting(
+            Setting::new(
+                "send_startup_block",
+                "Send Startup Block",
+                SettingValue::Boolean(connection.send_startup_block),
+            )
+            .with_description("Send the startup block on connect; off skips it")
+            .with_category(SettingsCategory::Controller),
+        );
+
+        dialog.add_setting(
+            Setting::new(
+                "command_delay_ms",
+                "Command Delay",
+                SettingValue::Integer(connection.command_delay_ms as i32),
+            )
+            .with_description(
+                "Minimum milliseconds between commands, for controllers that drop commands sent too fast (0 = off, up to 1000)",
+            )
+            .with_category(SettingsCategory::Controller),
+        );
+
+        dialog.add_setting(
+            Setting::new(
+                "reset_settle_ms",
+                "Reset Settle Time",
+                SettingValue::Integer(connection.reset_settle_ms as i32),
+            )
+            .with_description(
+                "Milliseconds to wait after the reset on connect before querying the controller (up to 10000)",
+            )
+            .with_category(SettingsCategory::Controller),
+        );
     }
 
     /// Add UI settings to dialog
@@ -318,6 +551,35 @@ impl SettingsPersistence {
             .with_description("Thickness in pixels for fine grid lines (default: 1)")
             .with_category(SettingsCategory::UserInterface),
         );
+
+        // Grid Origin
+        dialog.add_setting(
+            Setting::new(
+                "grid_origin_x",
+                "Grid Origin X (mm)",
+                SettingValue::String(ui.grid_origin_x.to_string()),
+            )
+            .with_description("Visualizer grid lines pass through this X position (default: 0)")
+            .with_category(SettingsCategory::UserInterface),
+        );
+        dialog.add_setting(
+            Setting::new(
+                "grid_origin_y",
+                "Grid Origin Y (mm)",
+                SettingValue::String(ui.grid_origin_y.to_string()),
+            )
+            .with_description("Visualizer grid lines pass through this Y position (default: 0)")
+            .with_category(SettingsCategory::UserInterface),
+        );
+        dialog.add_setting(
+            Setting::new(
+                "show_grid_origin",
+                "Show Grid Origin",
+                SettingValue::Boolean(ui.show_grid_origin),
+            )
+            .with_description("Mark and label the grid origin in the visualizer")
+            .with_category(SettingsCategory::UserInterface),
+        );
     }
 
     /// Add file processing settings to dialog
@@ -356,6 +618,79 @@ impl SettingsPersistence {
             .with_description("Maximum characters per line in output files")
             .with_category(SettingsCategory::FileProcessing),
         );
+
+        // Auto-save
+        dialog.add_setting(
+            Setting::new(
+                "autosave_enabled",
+                "Auto-Save for Recovery",
+                SettingValue::Boolean(file.autosave_enabled),
+            )
+            .with_description(
+                "Periodically save unsaved editor and designer work so it can be restored after a crash",
+            )
+            .with_category(SettingsCategory::FileProcessing),
+        );
+
+        dialog.add_setting(
+            Setting::new(
+                "autosave_interval_secs",
+                "Auto-Save Interval (s)",
+                SettingValue::Integer(file.autosave_interval_secs as i32),
+            )
+            .with_description("Seconds between recovery saves (10-3600)")
+            .with_category(SettingsCategory::FileProcessing),
+        );
+
+        dialog.add_setting(
+            Setting::new(
+                "recovery_directory",
+                "Recovery Directory",
+                SettingValue::Path(file.recovery_directory.to_string_lossy().to_string()),
+            )
+            .with_description(
+                "Where recovery files are kept; leave empty for the configuration directory",
+            )
+            .with_category(SettingsCategory::FileProcessing),
+        );
+    }
+
+    /// Add pre-flight check toggles to dialog
+    fn add_preflight_settings(&self, dialog: &mut SettingsDialog) {
+        let preflight = &self.config.machine.preflight;
+
+        for (id, name, value, description) in [
+            (
+                "preflight_confirm_safety",
+                "Confirm Spindle/Laser Safety",
+                preflight.confirm_safety,
+                "Ask for a safety confirmation before running a program that starts the spindle or laser",
+            ),
+            (
+                "preflight_soft_limits",
+                "Check Soft Limits",
+                preflight.soft_limits,
+                "Refuse to start programs that leave the active device profile's travel",
+            ),
+            (
+                "preflight_spindle_command",
+                "Warn Without Spindle/Laser Command",
+                preflight.spindle_command,
+                "Warn when a program never turns the spindle or laser on (M3/M4)",
+            ),
+            (
+                "preflight_program_end",
+                "Warn Without Program End",
+                preflight.program_end,
+                "Warn when a program has no M2/M30",
+            ),
+        ] {
+            dialog.add_setting(
+                Setting::new(id, name, SettingValue::Boolean(value))
+                    .with_description(description)
+                    .with_category(SettingsCategory::General),
+            );
+        }
     }
 
     /// Add advanced settings to dialog
@@ -385,6 +720,18 @@ impl SettingsPersistence {
             )
             .with_category(SettingsCategory::Advanced),
         );
+
+        dialog.add_setting(
+            Setting::new(
+                "record_job_timing",
+                "Record Job Times",
+                SettingValue::Boolean(self.config.machine.record_job_timing),
+            )
+            .with_description(
+                "Keep a local log of actual versus estimated job times to calibrate estimates per machine",
+            )
+            .with_category(SettingsCategory::Advanced),
+        );
     }
 
     /// Add keyboard shortcuts to dialog
@@ -431,12 +778,111 @@ impl SettingsPersistence {
             };
         }
 
+        if let Some(setting) = dialog.get_setting("decimal_separator") {
+            self.config.ui.decimal_separator = setting.value.as_str().parse().unwrap_or_default();
+        }
+
         if let Some(setting) = dialog.get_setting("default_directory") {
             let path_str = setting.value.as_str();
             if !path_str.is_empty() {
                 self.config.file_processing.output_directory = std::path::PathBuf::from(path_str);
             }
         }
+
+        if let Some(setting) = dialog.get_setting("block_delete") {
+            if let Ok(value) = setting.value.as_str().parse::<bool>() {
+                self.config.machine.block_delete = value;
+            }
+        }
+
+        if let Some(setting) = dialog.get_setting("optional_stop") {
+            if let Ok(value) = setting.value.as_str().parse::<bool>() {
+                self.config.machine.optional_stop = value;
+            }
+        }
+
+        if let Some(setting) = dialog.get_setting("hold_power_off") {
+            if let Ok(value) = setting.value.as_str().parse() {
+                self.config.machine.hold_power_off = value;
+            }
+        }
+
+        if let Some(setting) = dialog.get_setting("resume_ramp_percent") {
+            if let Ok(value) = setting.value.as_str().parse::<u16>() {
+                self.config.machine.resume_ramp_percent = value;
+            }
+        }
+
+        if let Some(setting) = dialog.get_setting("resume_ramp_ms") {
+            if let Ok(value) = setting.value.as_str().parse::<u64>() {
+                self.config.machine.resume_ramp_ms = value;
+            }
+        }
+
+        if let Some(setting) = dialog.get_setting("resume_ramp_distance") {
+            if let Ok(value) = setting.value.as_str().parse::<f64>() {
+                self.config.machine.resume_ramp_distance = value;
+            }
+        }
+
+        if let Some(setting) = dialog.get_setting("jog_key_bindings") {
+            self.config.machine.jog_keys = parse_jog_keys(&setting.value.as_str())?;
+        }
+
+        if let Some(setting) = dialog.get_setting("jog_max_rates") {
+            self.config.machine.jog_max_rates = parse_jog_rates(&setting.value.as_str())?;
+        }
+
+        if let Some(setting) = dialog.get_setting("safe_z") {
+            let text = setting.value.as_str();
+            self.config.machine.safe_z = text
+                .trim()
+                .parse()
+                .map_err(|_| Error::other(format!("Invalid safe height '{}'", text.trim())))?;
+        }
+        if let Some(setting) = dialog.get_setting("limit_warning_margin") {
+            if let Ok(value) = setting.value.as_str().parse::<f64>() {
+                self.config.machine.limit_warning_margin = value;
+            }
+        }
+        Ok(())
+    }
+
+    /// Update status polling and startup block settings in config from dialog
+    fn update_session_settings(&mut self, dialog: &SettingsDialog) -> Result<()> {
+        if let Some(setting) = dialog.get_setting("status_poll_active_ms") {
+            if let Ok(value) = setting.value.as_str().parse::<u64>() {
+                self.config.connection.status_poll_active_ms = value;
+            }
+        }
+
+        if let Some(setting) = dialog.get_setting("status_poll_idle_ms") {
+            if let Ok(value) = setting.value.as_str().parse::<u64>() {
+                self.config.connection.status_poll_idle_ms = value;
+            }
+        }
+
+        if let Some(setting) = dialog.get_setting("startup_block") {
+            self.config.connection.startup_block = parse_startup_block(&setting.value.as_str())?;
+        }
+
+        if let Some(setting) = dialog.get_setting("send_startup_block") {
+            if let Ok(value) = setting.value.as_str().parse::<bool>() {
+                self.config.connection.send_startup_block = value;
+            }
+        }
+
+        if let Some(setting) = dialog.get_setting("command_delay_ms") {
+            if let Ok(value) = setting.value.as_str().parse::<u64>() {
+                self.config.connection.command_delay_ms = value;
+            }
+        }
+
+        if let Some(setting) = dialog.get_setting("reset_settle_ms") {
+            if let Ok(value) = setting.value.as_str().parse::<u64>() {
+                self.config.connection.reset_settle_ms = value;
+            }
+        }
         Ok(())
     }
 
@@ -536,6 +982,28 @@ impl SettingsPersistence {
             }
         }
 
+        if let Some(setting) = dialog.get_setting("grid_origin_x") {
+            if let Ok(value) = setting.value.as_str().parse::<f64>() {
+                if value.is_finite() {
+                    self.config.ui.grid_origin_x = value;
+                }
+            }
+        }
+
+        if let Some(setting) = dialog.get_setting("grid_origin_y") {
+            if let Ok(value) = setting.value.as_str().parse::<f64>() {
+                if value.is_finite() {
+                    self.config.ui.grid_origin_y = value;
+                }
+            }
+        }
+
+        if let Some(setting) = dialog.get_setting("show_grid_origin") {
+            if let Ok(value) = setting.value.as_str().parse::<bool>() {
+                self.config.ui.show_grid_origin = value;
+            }
+        }
+
         Ok(())
     }
 
@@ -559,6 +1027,45 @@ impl SettingsPersistence {
             }
         }
 
+        if let Some(setting) = dialog.get_setting("autosave_enabled") {
+            if let Ok(value) = setting.value.as_str().parse::<bool>() {
+                self.config.file_processing.autosave_enabled = value;
+            }
+        }
+
+        if let Some(setting) = dialog.get_setting("autosave_interval_secs") {
+            if let Ok(value) = setting.value.as_str().parse::<u64>() {
+                self.config.file_processing.autosave_interval_secs = value.clamp(
+                    *AUTOSAVE_INTERVAL_RANGE_SECS.start(),
+                    *AUTOSAVE_INTERVAL_RANGE_SECS.end(),
+                );
+            }
+        }
+
+        if let Some(setting) = dialog.get_setting("recovery_directory") {
+            self.config.file_processing.recovery_directory =
+                std::path::PathBuf::from(setting.value.as_str().trim());
+        }
+
+        Ok(())
+    }
+
+    /// Update pre-flight check toggles in config from dialog
+    fn update_preflight_settings(&mut self, dialog: &SettingsDialog) -> Result<()> {
+        let preflight = &mut self.config.machine.preflight;
+        for (id, field) in [
+            ("preflight_confirm_safety", &mut preflight.confirm_safety),
+            ("preflight_soft_limits", &mut preflight.soft_limits),
+            ("preflight_spindle_command", &mut preflight.spindle_command),
+            ("preflight_program_end", &mut preflight.program_end),
+        ] {
+            if let Some(setting) = dialog.get_setting(id) {
+                if let Ok(value) = setting.value.as_str().parse::<bool>() {
+                    *field = value;
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -576,6 +1083,12 @@ impl SettingsPersistence {
             }
         }
 
+        if let Some(setting) = dialog.get_setting("record_job_timing") {
+            if let Ok(value) = setting.value.as_str().parse::<bool>() {
+                self.config.machine.record_job_timing = value;
+            }
+        }
+
         Ok(())
     }
 }
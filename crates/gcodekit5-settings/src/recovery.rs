@@ -0,0 +1,207 @@
+//! Crash Recovery Files
+//!
+//! While auto-save is enabled the UI periodically writes unsaved editor and
+//! designer work to recovery files, kept apart from the documents themselves.
+//! On startup a recovery file whose document has not been saved since it was
+//! written means the previous session ended without saving, and the work can
+//! be offered for restoring. A clean exit removes the recovery files.
+//!
+//! Each recovery file is a small JSON document holding the content, the path
+//! of the document it belongs to, when it was written and the document's own
+//! modification time and length at that moment. A save changes those, so the
+//! comparison does not depend on the clock agreeing with file timestamps. Files are written to
+//! a temporary name and renamed into place, so a crash mid-write leaves the
+//! previous recovery file intact.
+
+use crate::manager::SettingsManager;
+use gcodekit5_core::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Counter keeping concurrent writes' temporary files apart
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Which document a recovery file belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RecoveryKind {
+    /// The G-code editor's document
+    Editor,
+    /// The designer's design, as design-file JSON
+    Designer,
+}
+
+impl RecoveryKind {
+    pub const ALL: [RecoveryKind; 2] = [RecoveryKind::Editor, RecoveryKind::Designer];
+
+    fn file_name(self) -> &'static str {
+        match self {
+            RecoveryKind::Editor => "editor.recovery.json",
+            RecoveryKind::Designer => "designer.recovery.json",
+        }
+    }
+}
+
+/// Unsaved work written by auto-save
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecoveryFile {
+    pub kind: RecoveryKind,
+    /// Document the work belongs to; `None` for one never saved
+    pub source: Option<PathBuf>,
+    /// When the recovery file was written, in ms since the Unix epoch
+    pub saved_at_ms: u64,
+    /// The document as it was on disk when the recovery file was written;
+    /// `None` when it had no source or the source could not be read
+    #[serde(default)]
+    pub source_stamp: Option<DocumentStamp>,
+    pub content: String,
+}
+
+/// Modification time and length of a document on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocumentStamp {
+    /// Modification time, in ns since the Unix epoch
+    pub modified_ns: u64,
+    pub len: u64,
+}
+
+impl DocumentStamp {
+    /// The stamp of the file at `path`, if it can be read
+    pub fn of(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        let modified = metadata.modified().ok()?;
+        Some(Self {
+            modified_ns: modified
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_nanos() as u64),
+            len: metadata.len(),
+        })
+    }
+}
+
+/// The recovery files in one directory
+///
+/// Clones share their state, so a clone can be moved to a background thread
+/// to write while the original clears the files at exit.
+#[derive(Debug, Clone)]
+pub struct RecoveryStore {
+    directory: PathBuf,
+    /// Whether writes are still accepted; false once cleared at exit
+    open: Arc<Mutex<bool>>,
+}
+
+impl RecoveryStore {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            open: Arc::new(Mutex::new(true)),
+        }
+    }
+
+    /// Store in `directory`, or in `recovery` in the config directory when it
+    /// is empty
+    pub fn for_directory(directory: &Path) -> Result<Self> {
+        if directory.as_os_str().is_empty() {
+            Ok(Self::new(
+                SettingsManager::config_directory()?.join("recovery"),
+            ))
+        } else {
+            Ok(Self::new(directory))
+        }
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    fn path(&self, kind: RecoveryKind) -> PathBuf {
+        self.directory.join(kind.file_name())
+    }
+
+    /// Write `content` as the recovery file for `kind`
+    ///
+    /// Does nothing once the store has been [closed](Self::close).
+    pub fn write(&self, kind: RecoveryKind, source: Option<&Path>, content: &str) -> Result<()> {
+        let open = self.open.lock().unwrap_or_else(PoisonError::into_inner);
+        if !*open {
+            return Ok(());
+        }
+
+        let file = RecoveryFile {
+            kind,
+            source: source.map(Path::to_path_buf),
+            saved_at_ms: now_ms(),
+            source_stamp: source.and_then(DocumentStamp::of),
+            content: content.to_string(),
+        };
+        let json = serde_json::to_string(&file)
+            .map_err(|e| Error::other(format!("Failed to serialize recovery file: {}", e)))?;
+
+        std::fs::create_dir_all(&self.directory)?;
+        let temp = self.directory.join(format!(
+            "{}.{}.tmp",
+            kind.file_name(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&temp, json)?;
+        std::fs::rename(&temp, self.path(kind)).inspect_err(|_| {
+            let _ = std::fs::remove_file(&temp);
+        })?;
+        Ok(())
+    }
+
+    /// The recovery file for `kind`, if one exists and its document has not
+    /// been saved since
+    ///
+    /// A recovery file whose document has changed on disk since it was written
+    /// was superseded by a save and is removed. One whose document is gone, or
+    /// was written without a stamp, is kept.
+    pub fn pending(&self, kind: RecoveryKind) -> Result<Option<RecoveryFile>> {
+        let path = self.path(kind);
+        let json = match std::fs::read_to_string(&path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let file: RecoveryFile = serde_json::from_str(&json)
+            .map_err(|e| Error::other(format!("Invalid recovery file: {}", e)))?;
+
+        let current = file.source.as_deref().and_then(DocumentStamp::of);
+        let saved_since =
+            matches!((file.source_stamp, current), (Some(then), Some(now)) if then != now);
+        if saved_since {
+            self.clear(kind)?;
+            return Ok(None);
+        }
+        Ok(Some(file))
+    }
+
+    /// Remove the recovery file for `kind`
+    pub fn clear(&self, kind: RecoveryKind) -> Result<()> {
+        match std::fs::remove_file(self.path(kind)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Remove all recovery files and stop accepting writes, for a clean exit
+    ///
+    /// Writes still running on other threads finish first, so none of them
+    /// leaves a recovery file behind.
+    pub fn close(&self) -> Result<()> {
+        let mut open = self.open.lock().unwrap_or_else(PoisonError::into_inner);
+        *open = false;
+        for kind in RecoveryKind::ALL {
+            self.clear(kind)?;
+        }
+        Ok(())
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis() as u64)
+}
@@ -1,4 +1,5 @@
-use gcodekit5_settings::Config;
+use gcodekit5_core::HoldPowerOff;
+use gcodekit5_settings::{Config, ConsoleFilter};
 use std::path::PathBuf;
 
 #[test]
@@ -149,3 +150,396 @@ fn test_connection_type_display() {
         "websocket"
     );
 }
+
+#[test]
+fn test_machine_settings_without_preflight_section_load_defaults() {
+    let mut value = serde_json::to_value(Config::default()).unwrap();
+    value["machine"]
+        .as_object_mut()
+        .unwrap()
+        .remove("preflight");
+    let config: Config = serde_json::from_value(value).unwrap();
+    assert_eq!(
+        config.machine.preflight,
+        gcodekit5_settings::PreflightSettings::default()
+    );
+}
+
+#[test]
+fn test_block_delete_defaults_on_when_missing() {
+    let mut value = serde_json::to_value(Config::default()).unwrap();
+    value["machine"]
+        .as_object_mut()
+        .unwrap()
+        .remove("block_delete");
+    let config: Config = serde_json::from_value(value).unwrap();
+    assert!(config.machine.block_delete);
+}
+
+#[test]
+fn test_optional_stop_defaults_on_when_missing() {
+    let mut value = serde_json::to_value(Config::default()).unwrap();
+    value["machine"]
+        .as_object_mut()
+        .unwrap()
+        .remove("optional_stop");
+    let config: Config = serde_json::from_value(value).unwrap();
+    assert!(config.machine.optional_stop);
+}
+
+#[test]
+fn test_hold_power_off_defaults_to_disabled_when_missing() {
+    let mut value = serde_json::to_value(Config::default()).unwrap();
+    value["machine"]["hold_power_off"] = serde_json::json!("zeropower");
+    let config: Config = serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(config.machine.hold_power_off, HoldPowerOff::ZeroPower);
+
+    value["machine"]
+        .as_object_mut()
+        .unwrap()
+        .remove("hold_power_off");
+    let config: Config = serde_json::from_value(value).unwrap();
+    assert_eq!(config.machine.hold_power_off, HoldPowerOff::Disabled);
+}
+
+#[test]
+fn test_jog_keys_default_to_number_pad_when_missing() {
+    let mut value = serde_json::to_value(Config::default()).unwrap();
+    value["machine"].as_object_mut().unwrap().remove("jog_keys");
+    let config: Config = serde_json::from_value(value).unwrap();
+    assert_eq!(
+        config.machine.jog_keys,
+        gcodekit5_settings::default_jog_keys()
+    );
+    assert_eq!(
+        gcodekit5_settings::format_jog_keys(&config.machine.jog_keys),
+        "8=Y+, 2=Y-, 4=X-, 6=X+, 9=Z+, 3=Z-"
+    );
+}
+
+#[test]
+fn test_jog_keys_parse_and_match() {
+    use gcodekit5_settings::{parse_jog_keys, JogKeyBinding};
+
+    let bindings = parse_jog_keys("KP_8=Y+, Page_Up=z+ hold, bracketright=A-").unwrap();
+    assert_eq!(
+        bindings,
+        vec![
+            JogKeyBinding::step("KP_8", 'Y', true),
+            JogKeyBinding::continuous("Page_Up", 'Z', true),
+            JogKeyBinding::step("bracketright", 'A', false),
+        ]
+    );
+    assert!(bindings[0].matches("KP_8", Some('8')));
+    assert!(!bindings[0].matches("8", Some('8')));
+    assert!(JogKeyBinding::step("8", 'Y', true).matches("KP_8", Some('8')));
+
+    assert!(parse_jog_keys("8=Y").is_err());
+    assert!(parse_jog_keys("8=Y+ later").is_err());
+    assert!(parse_jog_keys("=X+").is_err());
+}
+
+#[test]
+fn test_config_validate_jog_key_conflicts() {
+    use gcodekit5_settings::JogKeyBinding;
+
+    let mut config = Config::default();
+    config
+        .machine
+        .jog_keys
+        .push(JogKeyBinding::step("8", 'A', true));
+    assert!(config.validate().is_err());
+
+    let mut config = Config::default();
+    config.machine.jog_keys = vec![JogKeyBinding::step("a", 'C', true)];
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_jog_rates_and_diagonal_keys() {
+    use gcodekit5_settings::{format_jog_rates, parse_jog_keys, parse_jog_rates, JogKeyBinding};
+
+    let mut config = Config::default();
+    assert_eq!(config.machine.jog_max_rate('Z'), None);
+    config.machine.jog_max_rates = parse_jog_rates("z=500, X=5000").unwrap();
+    assert_eq!(config.machine.jog_max_rate('Z'), Some(500.0));
+    assert_eq!(
+        format_jog_rates(&config.machine.jog_max_rates),
+        "X=5000, Z=500"
+    );
+    assert!(config.validate().is_ok());
+    config.machine.jog_max_rates.insert("Z".to_string(), 0.0);
+    assert!(config.validate().is_err());
+    assert!(parse_jog_rates("Z=slow").is_err());
+
+    let bindings = parse_jog_keys("7=X-Y+ hold").unwrap();
+    assert_eq!(
+        bindings,
+        vec![JogKeyBinding::continuous("7", 'X', false).with_diagonal('Y', true)]
+    );
+    assert_eq!(bindings[0].to_string(), "7=X-Y+ hold");
+    assert_eq!(bindings[0].directions(), vec![('X', -1.0), ('Y', 1.0)]);
+
+    let mut config = Config::default();
+    config.machine.jog_keys = parse_jog_keys("7=X-X+").unwrap();
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_status_poll_interval_adapts_to_machine_state() {
+    let mut value = serde_json::to_value(Config::default()).unwrap();
+    let connection = value["connection"].as_object_mut().unwrap();
+    connection.remove("status_poll_active_ms");
+    connection.remove("status_poll_idle_ms");
+    let config: Config = serde_json::from_value(value).unwrap();
+    let connection = &config.connection;
+    assert_eq!(connection.status_poll_active_ms, 250);
+    assert_eq!(connection.status_poll_idle_ms, 1000);
+
+    assert_eq!(
+        connection.status_poll_interval_ms(Some("Idle"), false),
+        1000
+    );
+    assert_eq!(
+        connection.status_poll_interval_ms(Some("Alarm"), false),
+        1000
+    );
+    assert_eq!(connection.status_poll_interval_ms(Some("Run"), false), 250);
+    assert_eq!(connection.status_poll_interval_ms(Some("Jog"), false), 250);
+    assert_eq!(
+        connection.status_poll_interval_ms(Some("Hold:0"), false),
+        250
+    );
+    assert_eq!(connection.status_poll_interval_ms(None, false), 250);
+    assert_eq!(connection.status_poll_interval_ms(Some("Idle"), true), 250);
+}
+
+#[test]
+fn test_config_validate_status_poll_bounds() {
+    let mut config = Config::default();
+    config.connection.status_poll_active_ms = 10;
+    assert!(config.validate().is_err());
+
+    config.connection.status_poll_active_ms = 500;
+    config.connection.status_poll_idle_ms = 200;
+    assert!(config.validate().is_err());
+
+    config.connection.status_poll_idle_ms = 60_000;
+    assert!(config.validate().is_err());
+    // Out-of-range values from hand-edited files are still clamped when used
+    assert_eq!(
+        config
+            .connection
+            .status_poll_interval_ms(Some("Idle"), false),
+        5000
+    );
+
+    config.connection.status_poll_idle_ms = 2000;
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_startup_block_parse_and_validate() {
+    let lines = gcodekit5_settings::parse_startup_block("G21 G90 G17 | G54\n\n$H ; home").unwrap();
+    assert_eq!(lines, ["G21 G90 G17", "G54", "$H ; home"]);
+    assert_eq!(
+        gcodekit5_settings::format_startup_block(&lines),
+        "G21 G90 G17 | G54 | $H ; home"
+    );
+
+    for bad in [
+        "G0 X10 ?",
+        "$10=2",
+        "G21 hello",
+        "M3 S1000 ~",
+        &"G4 P1 ".repeat(20),
+    ] {
+        assert!(
+            gcodekit5_settings::parse_startup_block(bad).is_err(),
+            "{bad} should be rejected"
+        );
+    }
+    // Comments may hold anything
+    assert!(gcodekit5_settings::parse_startup_block("G90 (ready? go!)").is_ok());
+}
+
+#[test]
+fn test_startup_commands_skip_comments_and_honour_switch() {
+    let mut config = Config::default();
+    assert!(config.connection.startup_commands().unwrap().is_empty());
+
+    config.connection.startup_block = vec![
+        "(setup)".to_string(),
+        "G21 G90".to_string(),
+        "G10 L20 P1 Z0 ; probe plate".to_string(),
+    ];
+    assert!(config.validate().is_ok());
+    assert_eq!(
+        config.connection.startup_commands().unwrap(),
+        ["G21 G90", "G10 L20 P1 Z0 ; probe plate"]
+    );
+
+    config.connection.send_startup_block = false;
+    assert!(config.connection.startup_commands().unwrap().is_empty());
+
+    config.connection.send_startup_block = true;
+    config.connection.startup_block.push("$10=0".to_string());
+    assert!(config.validate().is_err());
+    assert!(config.connection.startup_commands().is_err());
+}
+
+#[test]
+fn test_safe_z_defaults_and_validates() {
+    let mut config = Config::default();
+    assert_eq!(config.machine.safe_z, 5.0);
+    config.machine.safe_z = f64::NAN;
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_limit_warning_margin_defaults_and_validates() {
+    let mut config = Config::default();
+    assert_eq!(config.machine.limit_warning_margin, 5.0);
+    config.machine.limit_warning_margin = 0.0;
+    assert!(config.validate().is_ok());
+    config.machine.limit_warning_margin = -1.0;
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_resume_ramp_defaults_off_and_validates() {
+    let mut config = Config::default();
+    assert_eq!(config.machine.resume_ramp_percent, 0);
+    assert_eq!(config.machine.resume_ramp_ms, 3000);
+    config.machine.resume_ramp_percent = 30;
+    config.machine.resume_ramp_distance = 5.0;
+    assert!(config.validate().is_ok());
+    config.machine.resume_ramp_percent = 5;
+    assert!(config.validate().is_err());
+    config.machine.resume_ramp_percent = 30;
+    config.machine.resume_ramp_ms = gcodekit5_settings::RESUME_RAMP_MAX_MS + 1;
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_decimal_separator_defaults_to_point_when_missing() {
+    use gcodekit5_core::units::DecimalSeparator;
+
+    let mut value = serde_json::to_value(Config::default()).unwrap();
+    value["ui"]
+        .as_object_mut()
+        .unwrap()
+        .remove("decimal_separator");
+    let config: Config = serde_json::from_value(value).unwrap();
+    assert_eq!(config.ui.decimal_separator, DecimalSeparator::Point);
+
+    value = serde_json::to_value(Config::default()).unwrap();
+    value["ui"]["decimal_separator"] = "comma".into();
+    let config: Config = serde_json::from_value(value).unwrap();
+    assert_eq!(config.ui.decimal_separator, DecimalSeparator::Comma);
+}
+
+#[test]
+fn test_autosave_defaults_and_validates_interval() {
+    let mut value = serde_json::to_value(Config::default()).unwrap();
+    let file_processing = value["file_processing"].as_object_mut().unwrap();
+    for key in [
+        "autosave_enabled",
+        "autosave_interval_secs",
+        "recovery_directory",
+    ] {
+        file_processing.remove(key);
+    }
+    let mut config: Config = serde_json::from_value(value).unwrap();
+    assert!(config.file_processing.autosave_enabled);
+    assert_eq!(config.file_processing.autosave_interval_secs, 60);
+    assert_eq!(config.file_processing.recovery_directory, PathBuf::new());
+
+    config.file_processing.autosave_interval_secs = 5;
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_command_throttle_defaults_and_bounds() {
+    let mut value = serde_json::to_value(Config::default()).unwrap();
+    let connection = value["connection"].as_object_mut().unwrap();
+    connection.remove("command_delay_ms");
+    connection.remove("reset_settle_ms");
+    let mut config: Config = serde_json::from_value(value).unwrap();
+    assert_eq!(config.connection.command_delay_ms, 0);
+    assert_eq!(config.connection.reset_settle_ms, 250);
+
+    config.connection.command_delay_ms = 50;
+    config.connection.reset_settle_ms = 2000;
+    assert!(config.validate().is_ok());
+    config.connection.command_delay_ms = 5000;
+    assert!(config.validate().is_err());
+    config.connection.command_delay_ms = 0;
+    config.connection.reset_settle_ms = 60_000;
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_grid_origin_defaults_to_world_origin() {
+    let mut value = serde_json::to_value(Config::default()).unwrap();
+    let ui = value["ui"].as_object_mut().unwrap();
+    ui.remove("grid_origin_x");
+    ui.remove("grid_origin_y");
+    ui.remove("show_grid_origin");
+    let mut config: Config = serde_json::from_value(value).unwrap();
+    assert_eq!(
+        (config.ui.grid_origin_x, config.ui.grid_origin_y),
+        (0.0, 0.0)
+    );
+    assert!(!config.ui.show_grid_origin);
+
+    config.ui.grid_origin_x = -12.5;
+    assert!(config.validate().is_ok());
+    config.ui.grid_origin_y = f64::NAN;
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_console_filter_hides_each_kind_independently() {
+    let mut value = serde_json::to_value(Config::default()).unwrap();
+    value["ui"]
+        .as_object_mut()
+        .unwrap()
+        .remove("console_filter");
+    let config: Config = serde_json::from_value(value).unwrap();
+    let lines = [
+        "> ?",
+        "<Idle|MPos:0,0,0>",
+        "> $J=G91 X1 F500",
+        "ok",
+        "> G0 X1",
+        "error:2",
+    ];
+    assert!(lines.iter().all(|l| config.ui.console_filter.shows(l)));
+
+    let shown = |filter: ConsoleFilter| -> Vec<&str> {
+        lines.iter().copied().filter(|l| filter.shows(l)).collect()
+    };
+    let polls = ConsoleFilter {
+        hide_status_polls: true,
+        ..ConsoleFilter::default()
+    };
+    assert_eq!(
+        shown(polls),
+        ["> $J=G91 X1 F500", "ok", "> G0 X1", "error:2"]
+    );
+    let jog_and_ok = ConsoleFilter {
+        hide_jog: true,
+        hide_ok: true,
+        ..ConsoleFilter::default()
+    };
+    assert_eq!(
+        shown(jog_and_ok),
+        ["> ?", "<Idle|MPos:0,0,0>", "> G0 X1", "error:2"]
+    );
+    let echoes = ConsoleFilter {
+        hide_echoes: true,
+        ..ConsoleFilter::default()
+    };
+    assert_eq!(shown(echoes), ["<Idle|MPos:0,0,0>", "ok", "error:2"]);
+}
@@ -56,3 +56,48 @@ fn test_keyboard_shortcuts() {
     assert!(dialog.shortcuts.get("file_open").is_some());
     assert!(dialog.shortcuts.get("machine_home").is_some());
 }
+
+#[test]
+fn test_preflight_settings_round_trip() {
+    let mut persistence = SettingsPersistence::new();
+    let mut dialog = SettingsDialog::new();
+    persistence.populate_dialog(&mut dialog);
+
+    let setting = dialog.get_setting_mut("preflight_program_end").unwrap();
+    assert!(matches!(setting.value, SettingValue::Boolean(true)));
+    setting.value = SettingValue::Boolean(false);
+
+    persistence.load_from_dialog(&dialog).unwrap();
+    let preflight = persistence.config().machine.preflight;
+    assert!(!preflight.program_end);
+    assert!(preflight.soft_limits);
+}
+
+#[test]
+fn test_block_delete_setting_round_trip() {
+    let mut persistence = SettingsPersistence::new();
+    let mut dialog = SettingsDialog::new();
+    persistence.populate_dialog(&mut dialog);
+
+    let setting = dialog.get_setting_mut("block_delete").unwrap();
+    assert!(matches!(setting.value, SettingValue::Boolean(true)));
+    setting.value = SettingValue::Boolean(false);
+
+    persistence.load_from_dialog(&dialog).unwrap();
+    assert!(!persistence.config().machine.block_delete);
+}
+
+#[test]
+fn test_unparsable_safe_z_is_rejected() {
+    let mut persistence = SettingsPersistence::new();
+    let mut dialog = SettingsDialog::new();
+    persistence.populate_dialog(&mut dialog);
+
+    dialog.get_setting_mut("safe_z").unwrap().value = SettingValue::String("12 mm".to_string());
+    assert!(persistence.load_from_dialog(&dialog).is_err());
+    assert_eq!(persistence.config().machine.safe_z, 5.0);
+
+    dialog.get_setting_mut("safe_z").unwrap().value = SettingValue::String(" 12.5 ".to_string());
+    persistence.load_from_dialog(&dialog).unwrap();
+    assert_eq!(persistence.config().machine.safe_z, 12.5);
+}
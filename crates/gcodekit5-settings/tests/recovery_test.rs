@@ -0,0 +1,100 @@
+use gcodekit5_settings::{RecoveryKind, RecoveryStore};
+use std::path::PathBuf;
+
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("gcodekit5_test_recovery_{}", name));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_recovery_round_trip_for_unsaved_document() {
+    let dir = test_dir("round_trip");
+    let store = RecoveryStore::new(&dir);
+    assert_eq!(store.pending(RecoveryKind::Editor).unwrap(), None);
+
+    store
+        .write(RecoveryKind::Editor, None, "G0 X10\nG1 Y5\n")
+        .unwrap();
+    let file = store.pending(RecoveryKind::Editor).unwrap().unwrap();
+    assert_eq!(file.kind, RecoveryKind::Editor);
+    assert_eq!(file.source, None);
+    assert_eq!(file.content, "G0 X10\nG1 Y5\n");
+    assert_eq!(store.pending(RecoveryKind::Designer).unwrap(), None);
+
+    store.clear(RecoveryKind::Editor).unwrap();
+    assert_eq!(store.pending(RecoveryKind::Editor).unwrap(), None);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_recovery_older_than_last_save_is_dropped() {
+    let dir = test_dir("stale");
+    std::fs::create_dir_all(&dir).unwrap();
+    let document = dir.join("part.gcode");
+    std::fs::write(&document, "G0 X0\n").unwrap();
+
+    let store = RecoveryStore::new(dir.join("recovery"));
+    store
+        .write(RecoveryKind::Editor, Some(&document), "G0 X1\n")
+        .unwrap();
+    let file = store.pending(RecoveryKind::Editor).unwrap().unwrap();
+    assert_eq!(file.source.as_deref(), Some(document.as_path()));
+
+    // Writing the recovery file again does not make it stale
+    store
+        .write(RecoveryKind::Editor, Some(&document), "G0 X2\n")
+        .unwrap();
+    assert!(store.pending(RecoveryKind::Editor).unwrap().is_some());
+
+    // Saving the document afterwards supersedes the recovery file, even when
+    // the save lands in the same clock tick as the auto-save
+    std::fs::write(&document, "G0 X2 Y0\n").unwrap();
+    assert_eq!(store.pending(RecoveryKind::Editor).unwrap(), None);
+    assert!(!dir.join("recovery/editor.recovery.json").exists());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_close_clears_and_stops_writes() {
+    let dir = test_dir("close");
+    let store = RecoveryStore::new(&dir);
+    let writer = store.clone();
+    writer.write(RecoveryKind::Designer, None, "{}").unwrap();
+
+    store.close().unwrap();
+    assert_eq!(store.pending(RecoveryKind::Designer).unwrap(), None);
+
+    writer.write(RecoveryKind::Designer, None, "{}").unwrap();
+    assert_eq!(store.pending(RecoveryKind::Designer).unwrap(), None);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_recovery_is_kept_when_document_is_gone_or_unstamped() {
+    let dir = test_dir("unstamped");
+    std::fs::create_dir_all(&dir).unwrap();
+    let document = dir.join("part.gcode");
+    std::fs::write(&document, "G0 X0\n").unwrap();
+
+    let store = RecoveryStore::new(dir.join("recovery"));
+    store
+        .write(RecoveryKind::Editor, Some(&document), "G0 X1\n")
+        .unwrap();
+    std::fs::remove_file(&document).unwrap();
+    assert!(store.pending(RecoveryKind::Editor).unwrap().is_some());
+
+    // A recovery file from before stamps were recorded
+    std::fs::write(&document, "G0 X0\n").unwrap();
+    std::fs::write(
+        dir.join("recovery/editor.recovery.json"),
+        format!(
+            r#"{{"kind":"Editor","source":{:?},"saved_at_ms":0,"content":"G0 X1\n"}}"#,
+            document
+        ),
+    )
+    .unwrap();
+    let file = store.pending(RecoveryKind::Editor).unwrap().unwrap();
+    assert_eq!(file.source_stamp, None);
+    let _ = std::fs::remove_dir_all(&dir);
+}
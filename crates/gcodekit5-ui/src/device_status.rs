@@ -1,5 +1,10 @@
 //! Global device status shared across the application
 
+use gcodekit5_camtools::advanced_features::{CoordinateOffset, WorkCoordinateManager};
+use gcodekit5_communication::firmware::grbl::gcode_parameters::{
+    GcodeParameter, GcodeParameters, ProbeResult,
+};
+use gcodekit5_communication::firmware::grbl::push_message::ParserModalState;
 use gcodekit5_communication::firmware::grbl::status_parser::{
     BufferRxState, FeedSpindleState, MachinePosition, WorkCoordinateOffset, WorkPosition,
 };
@@ -34,6 +39,12 @@ pub struct GrblDeviceStatus {
     /// Device name (e.g., "CNC 3018 Pro")
     pub device_name: Option<String>,
 
+    /// Last reported modal parser state (from `[GC:]`)
+    pub parser_state: Option<ParserModalState>,
+
+    /// Last known G-code parameters (from `$#`): WCS offsets, G92, TLO, probe
+    pub gcode_parameters: GcodeParameters,
+
     /// Last known GRBL settings (from `$$`), keyed by `$n` (u16 to support grblHAL extended settings up to $680)
     pub grbl_settings: HashMap<u16, String>,
 
@@ -57,6 +68,8 @@ impl Default for GrblDeviceStatus {
             firmware_type: None,
             firmware_version: None,
             device_name: None,
+            parser_state: None,
+            gcode_parameters: GcodeParameters::default(),
             grbl_settings: HashMap::new(),
             commanded_feed_rate: None,
             commanded_spindle_speed: None,
@@ -162,6 +175,42 @@ pub fn update_grbl_setting(number: u16, value: String) {
     }
 }
 
+/// Update the modal parser state reported by `[GC:]`
+pub fn update_parser_state(state: ParserModalState) {
+    {
+        let mut status = DEVICE_STATUS.write();
+        status.parser_state = Some(state);
+    }
+}
+
+/// Merge a `$#` response line into the stored G-code parameters
+pub fn update_gcode_parameter(param: GcodeParameter) {
+    {
+        let mut status = DEVICE_STATUS.write();
+        status.gcode_parameters.apply(param);
+    }
+}
+
+/// Get the last probe result reported by `$#`
+pub fn get_last_probe_result() -> Option<ProbeResult> {
+    DEVICE_STATUS.read().gcode_parameters.probe
+}
+
+/// Build a `WorkCoordinateManager` from the G54-G59 offsets reported by the controller
+pub fn get_work_coordinate_manager() -> WorkCoordinateManager {
+    let mut manager = WorkCoordinateManager::new();
+    let status = DEVICE_STATUS.read();
+    for (code, offset) in &status.gcode_parameters.work_offsets {
+        if let Some(wcs) = GcodeParameters::wcs_index(code).filter(|i| *i <= 6) {
+            manager.set_offset(
+                wcs,
+                CoordinateOffset::new(offset.x as f32, offset.y as f32, offset.z as f32),
+            );
+        }
+    }
+    manager
+}
+
 pub fn update_commanded_feed_rate(feed_rate: f32) {
     {
         let mut status = DEVICE_STATUS.write();
@@ -14,7 +14,7 @@ use crate::i18n;
 use crate::t;
 use crate::ui::gtk::device_manager::DeviceManagerWindow;
 use crate::ui::gtk::editor::GcodeEditor;
-use crate::ui::gtk::machine_control::MachineControlView;
+use crate::ui::gtk::machine_control::{MachineControlView, QueuedCommand};
 use crate::ui::gtk::materials_manager::MaterialsManagerView;
 use crate::ui::gtk::settings::SettingsWindow;
 use crate::ui::gtk::status_bar::StatusBar;
@@ -96,6 +96,11 @@ pub fn main() {
         let current_theme = settings_persistence.borrow().config().ui.theme;
         apply_theme(current_theme);
 
+        // Typed values follow the configured decimal separator
+        gcodekit5_core::units::set_decimal_separator(
+            settings_persistence.borrow().config().ui.decimal_separator,
+        );
+
         // Listen for theme changes
         settings_controller.on_setting_changed(move |key, value| {
             if key == "theme" {
@@ -105,6 +110,8 @@ pub fn main() {
                     _ => Theme::System,
                 };
                 apply_theme(theme);
+            } else if key == "decimal_separator" {
+                gcodekit5_core::units::set_decimal_separator(value.parse().unwrap_or_default());
             }
         });
 
@@ -190,12 +197,21 @@ pub fn main() {
         content_box.append(&stack);
 
         // 1. Device Console
-        let device_console = DeviceConsoleView::new();
+        let device_console = DeviceConsoleView::new(Some(settings_controller.clone()));
 
         let status_bar = StatusBar::new();
 
         // 3. G-Code Editor (Moved up to be available for MachineControl)
         let editor = Rc::new(GcodeEditor::new(Some(status_bar.clone())));
+        {
+            let device_manager = device_manager.clone();
+            let settings = settings_controller.clone();
+            editor.set_load_checker(move |text| {
+                let system = settings.persistence.borrow().config().ui.measurement_system;
+                let profile = device_manager.get_active_profile();
+                gcodekit5_camtools::check_loaded_program(text, profile.as_ref(), system)
+            });
+        }
 
         // 4. Visualizer (Created early for MachineControl dependency)
         let visualizer = Rc::new(GcodeVisualizer::new(
@@ -210,6 +226,7 @@ pub fn main() {
             Some(device_console.clone()),
             Some(editor.clone()),
             Some(visualizer.clone()),
+            Some(device_manager.clone()),
             Some(settings_controller.clone()),
         );
 
@@ -234,19 +251,18 @@ pub fn main() {
 
         // Wire up console send
         let communicator = machine_control.communicator.clone();
+        let command_queue = machine_control.command_queue.clone();
         let console_clone = device_console.clone();
 
         let send_cmd = move || {
             let text = console_clone.command_entry.text();
             if !text.is_empty() {
-                let mut comm = communicator.lock();
-                if comm.is_connected() {
-                    if let Err(e) = comm.send_command(&text) {
-                        console_clone.append_log(&format!("Error sending: {}\n", e));
-                    } else {
-                        console_clone.append_log(&format!("> {}\n", text));
-                        console_clone.command_entry.set_text("");
-                    }
+                if communicator.lock().is_connected() {
+                    // Logged as it goes out, once the command throttle allows it
+                    command_queue
+                        .lock()
+                        .push_back(QueuedCommand::Line(text.to_string()));
+                    console_clone.command_entry.set_text("");
                 } else {
                     console_clone.append_log("Not connected\n");
                 }
@@ -292,10 +308,23 @@ pub fn main() {
         let stack_clone_for_cam = stack.clone();
         let settings_controller_cam = settings_controller.clone();
         let machine_control_cam = machine_control.clone();
+        let device_manager_cam = device_manager.clone();
+        let settings_templates = settings_controller.clone();
         let cam_tools_view = CamToolsView::new_with_designer(
             settings_controller_cam,
             Some(machine_control_cam),
             move |gcode| {
+                let safe_z = settings_templates
+                    .persistence
+                    .borrow()
+                    .config()
+                    .machine
+                    .safe_z;
+                let gcode = crate::ui::gtk::profile_templates::wrap_with_active_profile(
+                    gcode,
+                    Some(device_manager_cam.as_ref()),
+                    safe_z,
+                );
                 editor_clone.set_text(&gcode);
                 stack_clone_for_cam.set_visible_child_name("editor");
                 editor_clone.grab_focus();
@@ -393,8 +422,10 @@ pub fn main() {
             let is_streaming = machine_control.is_streaming.clone();
             let is_paused = machine_control.is_paused.clone();
             let waiting_for_ack = machine_control.waiting_for_ack.clone();
+            let command_tracker = machine_control.command_tracker.clone();
             let send_queue = machine_control.send_queue.clone();
             let job_start_time = machine_control.job_start_time.clone();
+            let job_timer = machine_control.job_timer.clone();
             let sb = status_bar.clone();
             let estop_btn = status_bar.estop_btn.clone();
             let device_console = device_console.clone();
@@ -418,10 +449,14 @@ pub fn main() {
                     let mut guard = waiting_for_ack.lock();
                     *guard = false;
                 }
+                command_tracker.lock().clear();
                 {
                     let mut guard = job_start_time.lock();
                     *guard = None;
                 }
+                if let Some(timer) = job_timer.lock().take() {
+                    timer.abort();
+                }
                 {
                     let mut guard = send_queue.lock();
                     guard.clear();
@@ -837,6 +872,15 @@ pub fn main() {
         window.maximize();
         window.present();
 
+        // Offer to restore work left by a crash, then auto-save unsaved work
+        crate::ui::gtk::autosave::setup(
+            app,
+            &window,
+            editor.clone(),
+            designer.clone(),
+            settings_persistence.clone(),
+        );
+
         if settings_persistence
             .borrow()
             .config()
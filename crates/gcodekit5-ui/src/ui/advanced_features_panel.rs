@@ -5,6 +5,7 @@
 //! - Task 112-120: Performance monitoring, logging, alarms
 //! - Task 121-125: Safety, plugins, export, calibration, diagnostics
 
+use gcodekit5_camtools::job_telemetry::JobTimingRecord;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -195,6 +196,9 @@ pub struct PerformanceMetrics {
     pub queued_commands: usize,
     /// Average latency (ms)
     pub avg_latency_ms: f64,
+    /// Timing of the last completed job, with its pre-run estimate
+    #[serde(default)]
+    pub last_job: Option<JobTimingRecord>,
 }
 
 impl Default for PerformanceMetrics {
@@ -205,6 +209,7 @@ impl Default for PerformanceMetrics {
             total_commands: 0,
             queued_commands: 0,
             avg_latency_ms: 0.0,
+            last_job: None,
         }
     }
 }
@@ -371,8 +376,14 @@ impl AdvancedFeaturesPanel {
             total_commands,
             queued_commands,
             avg_latency_ms,
+            last_job: self.performance_metrics.last_job.take(),
         };
     }
+
+    /// Record the timing of a completed job
+    pub fn record_job_timing(&mut self, record: JobTimingRecord) {
+        self.performance_metrics.last_job = Some(record);
+    }
 }
 
 impl Default for AdvancedFeaturesPanel {
@@ -0,0 +1,172 @@
+//! # Auto-Save and Crash Recovery
+//!
+//! Periodically writes unsaved editor and designer work to recovery files (see
+//! [`gcodekit5_settings::recovery`]) while auto-save is enabled. The work is
+//! captured on the main loop and written on a background thread, so a slow
+//! disk never stalls the UI. At startup any recovery file left by a session
+//! that did not exit cleanly is offered for restoring; a clean exit removes
+//! the recovery files.
+
+use crate::t;
+use crate::ui::gtk::designer::DesignerView;
+use crate::ui::gtk::editor::GcodeEditor;
+use gcodekit5_core::Shared;
+use gcodekit5_settings::{RecoveryFile, RecoveryKind, RecoveryStore, SettingsPersistence};
+use gtk4::prelude::*;
+use gtk4::{Application, ApplicationWindow};
+use std::cell::Cell;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Offer to restore recovered work, then start auto-saving
+///
+/// The interval and directory are read once at startup; the enabled switch is
+/// checked at every tick, so turning auto-save off takes effect immediately.
+pub fn setup(
+    app: &Application,
+    window: &ApplicationWindow,
+    editor: Rc<GcodeEditor>,
+    designer: Rc<DesignerView>,
+    settings: Shared<SettingsPersistence>,
+) {
+    let (directory, interval) = {
+        let settings = settings.borrow();
+        let file = &settings.config().file_processing;
+        (file.recovery_directory.clone(), file.autosave_interval_secs)
+    };
+    let store = match RecoveryStore::for_directory(&directory) {
+        Ok(store) => store,
+        Err(e) => {
+            warn!("Crash recovery unavailable: {}", e);
+            return;
+        }
+    };
+
+    // Auto-save waits while a restore is offered, so it does not replace the
+    // recovery file being asked about
+    let offers_open = Rc::new(Cell::new(0usize));
+    for kind in RecoveryKind::ALL {
+        match store.pending(kind) {
+            Ok(Some(file)) => {
+                offers_open.set(offers_open.get() + 1);
+                offer_restore(
+                    window,
+                    &store,
+                    file,
+                    &editor,
+                    &designer,
+                    offers_open.clone(),
+                );
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Ignoring unreadable recovery file: {}", e),
+        }
+    }
+
+    let exit_store = store.clone();
+    app.connect_shutdown(move |_| {
+        if let Err(e) = exit_store.close() {
+            warn!("Failed to remove recovery files: {}", e);
+        }
+    });
+
+    glib::timeout_add_local(Duration::from_secs(interval.max(1)), move || {
+        if offers_open.get() == 0 && settings.borrow().config().file_processing.autosave_enabled {
+            let editor_work = editor
+                .is_modified()
+                .then(|| (editor.get_text(), editor.current_file()));
+            save_in_background(&store, RecoveryKind::Editor, editor_work);
+            save_in_background(&store, RecoveryKind::Designer, designer.recovery_snapshot());
+        }
+        glib::ControlFlow::Continue
+    });
+}
+
+/// Write `work` as the recovery file for `kind`, or remove the file when there
+/// is no unsaved work, off the main thread
+fn save_in_background(
+    store: &RecoveryStore,
+    kind: RecoveryKind,
+    work: Option<(String, Option<PathBuf>)>,
+) {
+    let store = store.clone();
+    std::thread::spawn(move || {
+        let result = match &work {
+            Some((content, source)) => store.write(kind, source.as_deref(), content),
+            None => store.clear(kind),
+        };
+        if let Err(e) = result {
+            warn!("Auto-save of {:?} failed: {}", kind, e);
+        }
+    });
+}
+
+fn offer_restore(
+    window: &ApplicationWindow,
+    store: &RecoveryStore,
+    file: RecoveryFile,
+    editor: &Rc<GcodeEditor>,
+    designer: &Rc<DesignerView>,
+    offers_open: Rc<Cell<usize>>,
+) {
+    let (title, document) = match file.kind {
+        RecoveryKind::Editor => (t!("Recover unsaved G-code?"), t!("the G-code editor")),
+        RecoveryKind::Designer => (t!("Recover unsaved design?"), t!("the designer")),
+    };
+    let name = file
+        .source
+        .as_ref()
+        .and_then(|p| p.file_name())
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| t!("Untitled"));
+    let saved = chrono::DateTime::<chrono::Utc>::from_timestamp_millis(file.saved_at_ms as i64)
+        .map(|time| {
+            time.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default();
+    let secondary = format!(
+        "{} {} ({}), {} {}.",
+        t!("GCodeKit5 did not exit cleanly. Unsaved work in"),
+        document,
+        name,
+        t!("was auto-saved at"),
+        saved
+    );
+
+    let dialog = gtk4::MessageDialog::builder()
+        .message_type(gtk4::MessageType::Question)
+        .text(title)
+        .secondary_text(&secondary)
+        .transient_for(window)
+        .modal(true)
+        .build();
+    dialog.add_button(&t!("Discard"), gtk4::ResponseType::Reject);
+    dialog.add_button(&t!("Restore"), gtk4::ResponseType::Accept);
+    dialog.set_default_response(gtk4::ResponseType::Accept);
+
+    let store = store.clone();
+    let editor = editor.clone();
+    let designer = designer.clone();
+    dialog.connect_response(move |d, response| {
+        if response == gtk4::ResponseType::Accept {
+            info!("Restoring recovered {:?} work", file.kind);
+            match file.kind {
+                RecoveryKind::Editor => {
+                    editor.restore_recovered(&file.content, file.source.clone())
+                }
+                RecoveryKind::Designer => {
+                    designer.restore_recovery(&file.content, file.source.clone())
+                }
+            }
+        } else if let Err(e) = store.clear(file.kind) {
+            warn!("Failed to remove recovery file: {}", e);
+        }
+        offers_open.set(offers_open.get().saturating_sub(1));
+        d.close();
+    });
+    dialog.show();
+}
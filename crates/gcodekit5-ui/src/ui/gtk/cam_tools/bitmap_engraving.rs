@@ -11,7 +11,7 @@ use std::cell::Cell;
 use std::fs;
 use std::rc::Rc;
 
-use super::common::{create_dimension_row, set_paned_initial_fraction};
+use super::common::{create_dimension_row, set_paned_initial_fraction, AirAssistWidgets};
 use super::CamToolsView;
 use crate::ui::gtk::help_browser;
 use gcodekit5_camtools::laser_engraver::{
@@ -27,6 +27,8 @@ struct BitmapEngravingWidgets {
     travel_rate: Entry,
     min_power: Entry,
     max_power: Entry,
+    skip_power: Entry,
+    min_skip_length: Entry,
     pixels_per_mm: Entry,
     line_spacing: Entry,
     power_scale: Entry,
@@ -45,6 +47,7 @@ struct BitmapEngravingWidgets {
     preview_image: gtk4::Picture,
     preview_spinner: gtk4::Spinner,
     home_before: CheckButton,
+    air_assist: AirAssistWidgets,
 }
 
 pub struct BitmapEngravingTool {
@@ -152,6 +155,14 @@ impl BitmapEngravingTool {
         let travel_rate = Entry::builder().text("3000").valign(Align::Center).build();
         let min_power = Entry::builder().text("0").valign(Align::Center).build();
         let max_power = Entry::builder().text("100").valign(Align::Center).build();
+        let skip_power = Entry::builder().text("0").valign(Align::Center).build();
+        skip_power.set_tooltip_text(Some(
+            "Pixels below this power are not burned; the laser rapids over them (0 = off)",
+        ));
+        let min_skip_length = Entry::builder().text("0").valign(Align::Center).build();
+        min_skip_length.set_tooltip_text(Some(
+            "Skipped gaps shorter than this are crossed at S0 without switching the laser off",
+        ));
         let pixels_per_mm = Entry::builder().text("10").valign(Align::Center).build();
         let line_spacing = Entry::builder().text("1.0").valign(Align::Center).build();
         let power_scale = Entry::builder().text("1000").valign(Align::Center).build();
@@ -227,9 +238,14 @@ impl BitmapEngravingTool {
         let power_group = PreferencesGroup::builder().title("Laser Power").build();
         power_group.add(&Self::create_row("Min Power (%):", &min_power));
         power_group.add(&Self::create_row("Max Power (%):", &max_power));
+        power_group.add(&Self::create_row("Skip Below Power (%):", &skip_power));
+        power_group.add(&Self::create_row("Min Skip Length (mm):", &min_skip_length));
         power_group.add(&Self::create_row("Power Scale (S):", &power_scale));
         scroll_content.append(&power_group);
 
+        let air_assist = AirAssistWidgets::new();
+        scroll_content.append(&air_assist.group);
+
         let scan_group = PreferencesGroup::builder().title("Scanning").build();
         scan_group.add(&Self::create_row("Scan Direction:", &scan_direction));
         scan_group.add(&Self::create_row("Pixels per mm:", &pixels_per_mm));
@@ -304,6 +320,8 @@ impl BitmapEngravingTool {
             travel_rate,
             min_power,
             max_power,
+            skip_power,
+            min_skip_length,
             pixels_per_mm,
             line_spacing,
             power_scale,
@@ -322,6 +340,7 @@ impl BitmapEngravingTool {
             preview_image: preview_image.clone(),
             preview_spinner: preview_spinner.clone(),
             home_before,
+            air_assist,
         });
 
         // Unit update listener
@@ -686,6 +705,8 @@ impl BitmapEngravingTool {
             travel_rate: w.travel_rate.text().parse().unwrap_or(3000.0),
             min_power: w.min_power.text().parse().unwrap_or(0.0),
             max_power: w.max_power.text().parse().unwrap_or(100.0),
+            skip_power: w.skip_power.text().parse().unwrap_or(0.0),
+            min_skip_length: w.min_skip_length.text().parse().unwrap_or(0.0),
             pixels_per_mm: w.pixels_per_mm.text().parse().unwrap_or(10.0),
             scan_direction,
             bidirectional: w.bidirectional.is_active(),
@@ -703,6 +724,7 @@ impl BitmapEngravingTool {
             offset_x: w.offset_x.text().parse().unwrap_or(10.0),
             offset_y: w.offset_y.text().parse().unwrap_or(10.0),
             num_axes: crate::device_status::get_active_num_axes(),
+            air_assist: w.air_assist.collect(),
         }
     }
 
@@ -714,6 +736,8 @@ impl BitmapEngravingTool {
             "travel_rate": w.travel_rate.text().to_string(),
             "min_power": w.min_power.text().to_string(),
             "max_power": w.max_power.text().to_string(),
+            "skip_power": w.skip_power.text().to_string(),
+            "min_skip_length": w.min_skip_length.text().to_string(),
             "pixels_per_mm": w.pixels_per_mm.text().to_string(),
             "line_spacing": w.line_spacing.text().to_string(),
             "power_scale": w.power_scale.text().to_string(),
@@ -728,6 +752,7 @@ impl BitmapEngravingTool {
             "halftone": w.halftone.active_id().unwrap_or_default().to_string(),
             "halftone_dot_size": w.halftone_dot_size.text().to_string(),
             "halftone_threshold": w.halftone_threshold.text().to_string(),
+            "air_assist": w.air_assist.to_json(),
         })
     }
 
@@ -780,6 +805,12 @@ impl BitmapEngravingTool {
         if let Some(v) = params.get("max_power").and_then(|v| v.as_str()) {
             w.max_power.set_text(v);
         }
+        if let Some(v) = params.get("skip_power").and_then(|v| v.as_str()) {
+            w.skip_power.set_text(v);
+        }
+        if let Some(v) = params.get("min_skip_length").and_then(|v| v.as_str()) {
+            w.min_skip_length.set_text(v);
+        }
         if let Some(v) = params.get("pixels_per_mm").and_then(|v| v.as_str()) {
             w.pixels_per_mm.set_text(v);
         }
@@ -822,6 +853,7 @@ impl BitmapEngravingTool {
         if let Some(v) = params.get("halftone_threshold").and_then(|v| v.as_str()) {
             w.halftone_threshold.set_text(v);
         }
+        w.air_assist.apply_json(params);
     }
 }
 
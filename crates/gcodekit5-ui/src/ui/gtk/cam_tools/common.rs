@@ -2,12 +2,13 @@
 
 use gtk4::glib;
 use gtk4::prelude::*;
-use gtk4::{Align, Box, Entry, Label, Orientation, Paned};
+use gtk4::{Align, Box, CheckButton, Entry, Label, Orientation, Paned};
 use libadwaita::prelude::*;
-use libadwaita::ActionRow;
+use libadwaita::{ActionRow, PreferencesGroup};
 use std::cell::Cell;
 use std::rc::Rc;
 
+use gcodekit5_camtools::AirAssist;
 use gcodekit5_core::units;
 use gcodekit5_settings::SettingsController;
 
@@ -56,3 +57,85 @@ pub fn create_dimension_row(
 
     (row, entry, label)
 }
+
+/// Air assist controls shared by the laser tools
+pub struct AirAssistWidgets {
+    pub group: PreferencesGroup,
+    enabled: CheckButton,
+    on_code: Entry,
+    off_code: Entry,
+    per_operation: CheckButton,
+}
+
+impl AirAssistWidgets {
+    pub fn new() -> Self {
+        let defaults = AirAssist::default();
+        let enabled = CheckButton::builder()
+            .active(defaults.enabled)
+            .valign(Align::Center)
+            .build();
+        let on_code = Entry::builder()
+            .text(&defaults.on_code)
+            .valign(Align::Center)
+            .width_chars(8)
+            .build();
+        let off_code = Entry::builder()
+            .text(&defaults.off_code)
+            .valign(Align::Center)
+            .width_chars(8)
+            .build();
+        let per_operation = CheckButton::builder()
+            .active(defaults.per_operation)
+            .valign(Align::Center)
+            .build();
+
+        let group = PreferencesGroup::builder().title("Air Assist").build();
+        let enabled_row = ActionRow::builder().title("Enable Air Assist:").build();
+        enabled_row.add_suffix(&enabled);
+        group.add(&enabled_row);
+        let on_row = ActionRow::builder().title("On Code:").build();
+        on_row.add_suffix(&on_code);
+        group.add(&on_row);
+        let off_row = ActionRow::builder().title("Off Code:").build();
+        off_row.add_suffix(&off_code);
+        group.add(&off_row);
+        let per_operation_row = ActionRow::builder().title("Toggle Per Operation:").build();
+        per_operation_row.add_suffix(&per_operation);
+        group.add(&per_operation_row);
+
+        Self {
+            group,
+            enabled,
+            on_code,
+            off_code,
+            per_operation,
+        }
+    }
+
+    pub fn collect(&self) -> AirAssist {
+        AirAssist {
+            enabled: self.enabled.is_active(),
+            on_code: self.on_code.text().trim().to_string(),
+            off_code: self.off_code.text().trim().to_string(),
+            per_operation: self.per_operation.is_active(),
+        }
+    }
+
+    /// Saved form, stored under `"air_assist"` in the tool's parameter file
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self.collect()).unwrap_or_default()
+    }
+
+    pub fn apply_json(&self, params: &serde_json::Value) {
+        let Some(air) = params
+            .get("air_assist")
+            .and_then(|v| serde_json::from_value::<AirAssist>(v.clone()).ok())
+        else {
+            return;
+        };
+        self.enabled.set_active(air.enabled);
+        self.on_code.set_text(&air.on_code);
+        self.off_code.set_text(&air.off_code);
+        self.per_operation.set_active(air.per_operation);
+    }
+}
@@ -11,7 +11,7 @@ use std::cell::Cell;
 use std::fs;
 use std::rc::Rc;
 
-use super::common::{create_dimension_row, set_paned_initial_fraction};
+use super::common::{create_dimension_row, set_paned_initial_fraction, AirAssistWidgets};
 use super::CamToolsView;
 use crate::ui::gtk::help_browser;
 use gcodekit5_camtools::vector_engraver::{VectorEngraver, VectorEngravingParameters};
@@ -43,6 +43,7 @@ struct VectorEngravingWidgets {
     preview_spinner: gtk4::Spinner,
     info_label: Label,
     home_before: CheckButton,
+    air_assist: AirAssistWidgets,
 }
 
 pub struct VectorEngravingTool {
@@ -237,6 +238,9 @@ impl VectorEngravingTool {
         power_group.add(&invert_row);
         scroll_content.append(&power_group);
 
+        let air_assist = AirAssistWidgets::new();
+        scroll_content.append(&air_assist.group);
+
         let multipass_group = PreferencesGroup::builder()
             .title("Multi-Pass Settings")
             .build();
@@ -329,6 +333,7 @@ impl VectorEngravingTool {
             preview_spinner: preview_spinner.clone(),
             info_label: info_label.clone(),
             home_before,
+            air_assist,
         });
 
         // Unit update listener
@@ -657,6 +662,7 @@ impl VectorEngravingTool {
             dwell_time: w.dwell_time.text().parse().unwrap_or(0.1),
             cross_hatch: w.cross_hatch.is_active(),
             num_axes: crate::device_status::get_active_num_axes(),
+            air_assist: w.air_assist.collect(),
         }
     }
 
@@ -686,6 +692,7 @@ impl VectorEngravingTool {
             "dwell_time": w.dwell_time.text().to_string(),
             "cross_hatch": w.cross_hatch.is_active(),
             "vector_path": w.vector_path.text().to_string(),
+            "air_assist": w.air_assist.to_json(),
         })
     }
 
@@ -756,6 +763,7 @@ impl VectorEngravingTool {
         if let Some(v) = params.get("cross_hatch").and_then(|v| v.as_bool()) {
             w.cross_hatch.set_active(v);
         }
+        w.air_assist.apply_json(params);
         if let Some(v) = params.get("vector_path").and_then(|v| v.as_str()) {
             w.vector_path.set_text(v);
             if !v.is_empty() {
@@ -101,6 +101,11 @@ impl CommandHistory {
         }
     }
 
+    /// Stored commands, oldest first
+    pub fn commands(&self) -> impl DoubleEndedIterator<Item = &String> {
+        self.commands.iter()
+    }
+
     pub fn reset_navigation(&mut self) {
         self.current_index = None;
         self.temp_input.clear();
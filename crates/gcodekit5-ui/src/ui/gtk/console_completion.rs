@@ -0,0 +1,250 @@
+//! # Console Completion
+//!
+//! Suggestions for the device console's command entry: previously sent
+//! commands, common controller commands and GRBL `$n` settings. Controller
+//! commands are filtered by the detected firmware, so a Smoothieware board is
+//! not offered `$H` and a GRBL board is not offered TinyG JSON requests.
+
+use gcodekit5_communication::firmware::firmware_version::FirmwareType;
+
+use crate::helpers::get_grbl_setting_info;
+
+/// Most suggestions shown at once
+const MAX_SUGGESTIONS: usize = 10;
+
+/// Standard GRBL settings with a known name
+const GRBL_SETTINGS: &[u16] = &[
+    0, 1, 2, 3, 4, 5, 6, 10, 11, 12, 13, 20, 21, 22, 23, 24, 25, 26, 27, 30, 31, 32, 100, 101, 102,
+    103, 104, 105, 110, 111, 112, 113, 114, 115, 120, 121, 122, 123, 124, 125, 130, 131, 132, 133,
+    134, 135,
+];
+
+/// Firmware families a command is understood by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Family {
+    /// Every controller
+    All,
+    /// GRBL, grblHAL and FluidNC
+    Grbl,
+    GrblHal,
+    FluidNc,
+    /// TinyG and g2core
+    TinyG,
+    Smoothie,
+}
+
+impl Family {
+    fn supports(self, firmware: Option<FirmwareType>) -> bool {
+        let Some(firmware) = firmware.filter(|f| *f != FirmwareType::Unknown) else {
+            // Nothing detected yet: offer the GRBL set, which most boards speak
+            return matches!(self, Self::All | Self::Grbl);
+        };
+        match self {
+            Self::All => true,
+            Self::Grbl => matches!(
+                firmware,
+                FirmwareType::Grbl | FirmwareType::GrblHal | FirmwareType::FluidNC
+            ),
+            Self::GrblHal => firmware == FirmwareType::GrblHal,
+            Self::FluidNc => firmware == FirmwareType::FluidNC,
+            Self::TinyG => matches!(firmware, FirmwareType::TinyG | FirmwareType::G2Core),
+            Self::Smoothie => firmware == FirmwareType::Smoothieware,
+        }
+    }
+}
+
+/// Commands offered by the completer, with a short description
+const COMMANDS: &[(&str, &str, Family)] = &[
+    ("$H", "Run homing cycle", Family::Grbl),
+    ("$X", "Unlock (kill alarm)", Family::Grbl),
+    ("$$", "View settings", Family::Grbl),
+    ("$I", "View build info", Family::Grbl),
+    ("$#", "View coordinate offsets", Family::Grbl),
+    ("$G", "View parser state", Family::Grbl),
+    ("$N", "View startup blocks", Family::Grbl),
+    ("$C", "Toggle check mode", Family::Grbl),
+    ("$SLP", "Enable sleep mode", Family::Grbl),
+    ("$J=G91 ", "Jog (relative)", Family::Grbl),
+    ("$RST=$", "Restore default settings", Family::Grbl),
+    ("$RST=#", "Clear coordinate offsets", Family::Grbl),
+    ("?", "Status report", Family::Grbl),
+    ("!", "Feed hold", Family::Grbl),
+    ("~", "Cycle start / resume", Family::Grbl),
+    ("$ES", "Enumerate settings", Family::GrblHal),
+    ("$EG", "Enumerate setting groups", Family::GrblHal),
+    ("$EA", "Enumerate alarm codes", Family::GrblHal),
+    ("$EE", "Enumerate error codes", Family::GrblHal),
+    ("$CD", "Dump configuration", Family::FluidNc),
+    ("$SS", "Show startup messages", Family::FluidNc),
+    ("$LocalFS/List", "List local files", Family::FluidNc),
+    ("$$", "View settings", Family::TinyG),
+    ("{\"sr\":null}", "Status report", Family::TinyG),
+    ("{\"sys\":null}", "System settings", Family::TinyG),
+    ("version", "Firmware version", Family::Smoothie),
+    ("help", "List commands", Family::Smoothie),
+    ("M114", "Report position", Family::Smoothie),
+    ("G28.2", "Home axes", Family::Smoothie),
+    ("G0 ", "Rapid move", Family::All),
+    ("G1 ", "Linear move", Family::All),
+    ("G10 L20 P1 ", "Set work offset (G54)", Family::All),
+    ("G28", "Go to predefined position", Family::All),
+    ("G90", "Absolute positioning", Family::All),
+    ("G91", "Relative positioning", Family::All),
+    ("G92 ", "Set coordinate offset", Family::All),
+    ("M3 S", "Spindle on (CW)", Family::All),
+    ("M5", "Spindle stop", Family::All),
+    ("M8", "Coolant on", Family::All),
+    ("M9", "Coolant off", Family::All),
+];
+
+/// Where a suggestion comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestionKind {
+    History,
+    Command,
+    Setting,
+}
+
+/// One completion for the console entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConsoleSuggestion {
+    /// Text that replaces the entry when accepted
+    pub text: String,
+    /// Short description shown beside it
+    pub description: String,
+    pub kind: SuggestionKind,
+}
+
+/// Suggestions for the console entry text
+///
+/// `history` is oldest first, as kept by the command history; `device_settings`
+/// are the `$n` settings and values read from the controller, if any. Matching is
+/// a case-insensitive prefix match; empty input has no suggestions.
+pub fn console_suggestions<'a>(
+    input: &str,
+    firmware: Option<FirmwareType>,
+    history: impl DoubleEndedIterator<Item = &'a String>,
+    device_settings: &[(u16, String)],
+) -> Vec<ConsoleSuggestion> {
+    let input = input.trim_start();
+    if input.is_empty() {
+        return Vec::new();
+    }
+    let typed = input.to_ascii_lowercase();
+    let matches = |text: &str| {
+        let text = text.to_ascii_lowercase();
+        text.starts_with(&typed) && text != typed
+    };
+
+    let mut suggestions: Vec<ConsoleSuggestion> = Vec::new();
+    let mut push = |suggestion: ConsoleSuggestion| {
+        if !suggestions.iter().any(|s| s.text == suggestion.text) {
+            suggestions.push(suggestion);
+        }
+    };
+
+    for command in history.rev().filter(|command| matches(command)) {
+        push(ConsoleSuggestion {
+            text: command.clone(),
+            description: "History".to_string(),
+            kind: SuggestionKind::History,
+        });
+    }
+
+    for (text, description, family) in COMMANDS {
+        if family.supports(firmware) && matches(text) {
+            push(ConsoleSuggestion {
+                text: text.to_string(),
+                description: description.to_string(),
+                kind: SuggestionKind::Command,
+            });
+        }
+    }
+
+    if typed.starts_with('$') && Family::Grbl.supports(firmware) {
+        let mut numbers: Vec<u16> = GRBL_SETTINGS.to_vec();
+        numbers.extend(device_settings.iter().map(|(n, _)| *n));
+        numbers.sort_unstable();
+        numbers.dedup();
+        for number in numbers {
+            let text = format!("${}=", number);
+            if !matches(&text) {
+                continue;
+            }
+            let name = if GRBL_SETTINGS.contains(&number) {
+                let (name, _, unit, _) = get_grbl_setting_info(number as i32);
+                if unit.is_empty() {
+                    name.to_string()
+                } else {
+                    format!("{} ({})", name, unit)
+                }
+            } else {
+                "Controller setting".to_string()
+            };
+            let description = match device_settings.iter().find(|(n, _)| *n == number) {
+                Some((_, value)) => format!("{} = {}", name, value),
+                None => name,
+            };
+            push(ConsoleSuggestion {
+                text,
+                description,
+                kind: SuggestionKind::Setting,
+            });
+        }
+    }
+
+    suggestions.truncate(MAX_SUGGESTIONS);
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(suggestions: &[ConsoleSuggestion]) -> Vec<&str> {
+        suggestions.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_history_comes_first_newest_first() {
+        let history = ["G0 X10".to_string(), "G0 Y5".to_string()];
+        let suggestions = console_suggestions("g0", None, history.iter(), &[]);
+        assert_eq!(texts(&suggestions)[..3], ["G0 Y5", "G0 X10", "G0 "]);
+        assert_eq!(suggestions[0].kind, SuggestionKind::History);
+    }
+
+    #[test]
+    fn test_settings_are_named_and_show_device_values() {
+        let device = [(110, "5000".to_string())];
+        let suggestions = console_suggestions(
+            "$110",
+            Some(FirmwareType::Grbl),
+            std::iter::empty(),
+            &device,
+        );
+        assert_eq!(texts(&suggestions), ["$110="]);
+        assert_eq!(suggestions[0].kind, SuggestionKind::Setting);
+        assert!(suggestions[0].description.ends_with("= 5000"));
+
+        let suggestions =
+            console_suggestions("$11", Some(FirmwareType::Grbl), std::iter::empty(), &device);
+        assert_eq!(texts(&suggestions)[..2], ["$11=", "$110="]);
+    }
+
+    #[test]
+    fn test_commands_follow_detected_firmware() {
+        let none: Vec<String> = Vec::new();
+        let grbl = console_suggestions("$", Some(FirmwareType::Grbl), none.iter(), &[]);
+        assert!(texts(&grbl).contains(&"$H"));
+        assert!(!texts(&grbl).contains(&"$ES"));
+
+        let smoothie = console_suggestions("$", Some(FirmwareType::Smoothieware), none.iter(), &[]);
+        assert!(smoothie.is_empty());
+
+        let hal = console_suggestions("$E", Some(FirmwareType::GrblHal), none.iter(), &[]);
+        assert_eq!(texts(&hal), ["$ES", "$EG", "$EA", "$EE"]);
+
+        assert!(console_suggestions("", None, none.iter(), &[]).is_empty());
+        assert!(console_suggestions("$H", None, none.iter(), &[]).is_empty());
+    }
+}
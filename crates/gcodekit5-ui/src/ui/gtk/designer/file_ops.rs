@@ -6,6 +6,7 @@ impl DesignerView {
     pub fn new_file(&self) {
         let mut state = self.canvas.state.borrow_mut();
         state.canvas.clear();
+        state.is_modified = false;
         *self.current_file.borrow_mut() = None;
         drop(state);
 
@@ -127,6 +128,7 @@ impl DesignerView {
                                     state.canvas.set_pan(pan_x, pan_y);
                                 }
 
+                                state.is_modified = false;
                                 *current_file.borrow_mut() = Some(path.clone());
                                 drop(state);
 
@@ -294,7 +296,8 @@ impl DesignerView {
                                 },
                                 "dxf" => {
                                     let importer =
-                                        gcodekit5_designer::import::DxfImporter::new(1.0, 0.0, 0.0);
+                                        gcodekit5_designer::import::DxfImporter::new(1.0, 0.0, 0.0)
+                                            .with_repair(gcodekit5_designer::ContourRepair::default());
                                     importer.import_file(path.to_str().unwrap_or(""))
                                 }
                                 "stl" => {
@@ -332,10 +335,9 @@ impl DesignerView {
                             Ok(design) => {
                                 let mut state = canvas.state.borrow_mut();
 
-                                // Add imported shapes to canvas
-                                for shape in design.shapes {
-                                    state.add_shape_with_undo(shape);
-                                }
+                                // Add imported shapes to canvas, grouped by source layer
+                                let plan = design.layer_plan();
+                                state.add_imported_design(&design, &plan);
 
                                 drop(state);
 
@@ -344,11 +346,33 @@ impl DesignerView {
 
                                 layers.refresh(&canvas.state);
                                 canvas.widget.queue_draw();
-                                status_label.set_text(&format!(
-                                    "{} {}",
-                                    t!("Imported:"),
-                                    path.display()
-                                ));
+                                let gaps = design.repair_report.as_ref().map_or(0, |report| {
+                                    if report.repaired() {
+                                        tracing::info!(
+                                            "Contour repair joined {} and closed {} contours",
+                                            report.joined, report.closed
+                                        );
+                                    }
+                                    for error in report.errors() {
+                                        tracing::warn!("{}: {}", path.display(), error);
+                                    }
+                                    report.gaps.len()
+                                });
+                                if gaps > 0 {
+                                    status_label.set_text(&format!(
+                                        "{} {} ({} {})",
+                                        t!("Imported:"),
+                                        path.display(),
+                                        gaps,
+                                        t!("open contours, see log")
+                                    ));
+                                } else {
+                                    status_label.set_text(&format!(
+                                        "{} {}",
+                                        t!("Imported:"),
+                                        path.display()
+                                    ));
+                                }
                             }
                             Err(e) => {
                                 error!("Error importing file: {}", e);
@@ -466,8 +490,10 @@ impl DesignerView {
                             design.shapes.push(shape_data);
                         }
 
+                        drop(state);
                         match design.save_to_file(&path) {
                             Ok(_) => {
+                                canvas.state.borrow_mut().is_modified = false;
                                 *current_file.borrow_mut() = Some(path.clone());
                                 status_label.set_text(&format!(
                                     "{} {}",
@@ -522,8 +548,10 @@ impl DesignerView {
             design.shapes.push(shape_data);
         }
 
+        drop(state);
         match design.save_to_file(&path) {
             Ok(_) => {
+                self.canvas.state.borrow_mut().is_modified = false;
                 self.set_status(&format!("{} {}", t!("Saved:"), path.display()));
             }
             Err(e) => {
@@ -533,6 +561,45 @@ impl DesignerView {
         }
     }
 
+    /// Unsaved design for crash recovery, with the file it belongs to
+    ///
+    /// `None` when there are no unsaved changes.
+    pub fn recovery_snapshot(&self) -> Option<(String, Option<PathBuf>)> {
+        let state = self.canvas.state.borrow();
+        if !state.is_modified {
+            return None;
+        }
+        match state.recovery_snapshot() {
+            Ok(snapshot) => Some((snapshot, self.current_file.borrow().clone())),
+            Err(e) => {
+                error!("Error serializing design for recovery: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Restore a recovered design for `source`; it stays modified until saved
+    pub fn restore_recovery(&self, snapshot: &str, source: Option<PathBuf>) {
+        let restored = self
+            .canvas
+            .state
+            .borrow_mut()
+            .restore_recovery_snapshot(snapshot, source.clone());
+        match restored {
+            Ok(()) => {
+                *self.current_file.borrow_mut() = source;
+                self.layers.refresh(&self.canvas.state);
+                self.toolbox.refresh_settings();
+                self.canvas.widget.queue_draw();
+                self.set_status(&t!("Recovered unsaved design"));
+            }
+            Err(e) => {
+                error!("Error restoring recovered design: {}", e);
+                self.set_status(&format!("{} {}", t!("Error loading file:"), e));
+            }
+        }
+    }
+
     pub fn export_gcode(&self) {
         let window = self
             .widget
@@ -555,6 +622,12 @@ impl DesignerView {
 
         let canvas = self.canvas.clone();
         let status_label = self.status_label.clone();
+        let device_manager = self.device_manager.clone();
+        let safe_z = self
+            .settings_persistence
+            .as_ref()
+            .map(|p| p.borrow().config().machine.safe_z)
+            .unwrap_or(5.0);
 
         dialog.connect_response(move |dialog, response| {
             if response == ResponseType::Accept {
@@ -583,6 +656,8 @@ impl DesignerView {
                         state.toolpath_generator.set_step_in(tool_diameter * 0.4); // Default stepover
 
                         let gcode = state.generate_gcode();
+                        let gcode =
+                            wrap_with_active_profile(gcode, device_manager.as_deref(), safe_z);
 
                         match std::fs::write(&path, gcode) {
                             Ok(_) => {
@@ -9,6 +9,7 @@ use crate::ui::gtk::designer_layers::LayersPanel;
 use crate::ui::gtk::designer_properties::PropertiesPanel;
 use crate::ui::gtk::designer_toolbox::{DesignerTool, DesignerToolbox};
 use crate::ui::gtk::osd_format::format_zoom_center_cursor;
+use crate::ui::gtk::profile_templates::wrap_with_active_profile;
 use gcodekit5_core::{shared, shared_none, Shared, SharedOption};
 use gcodekit5_designer::designer_state::DesignerState;
 use gcodekit5_designer::model::{DesignerShape, Shape};
@@ -42,6 +43,7 @@ pub struct DesignerView {
     pub(crate) current_file: SharedOption<PathBuf>,
     pub(crate) on_gcode_generated: SharedOption<std::boxed::Box<dyn Fn(String)>>,
     pub(crate) settings_persistence: Option<Shared<gcodekit5_settings::SettingsPersistence>>,
+    pub(crate) device_manager: Option<Arc<DeviceManager>>,
 }
 
 impl DesignerView {
@@ -493,10 +495,20 @@ impl DesignerView {
         let canvas_gen = canvas.clone();
         let on_gen = on_gcode_generated.clone();
         let status_label_gen = status_label.clone();
+        let device_manager_gen = device_manager.clone();
+        let settings_gen = settings_controller.persistence.clone();
 
         toolbox.connect_generate_clicked(move || {
             let mut state = canvas_gen.state.borrow_mut();
 
+            // No-go zones of the active machine join the keep-out check
+            let regions = device_manager_gen
+                .as_ref()
+                .and_then(|manager| manager.get_active_profile())
+                .map(|profile| profile.work_regions)
+                .unwrap_or_default();
+            state.set_device_regions(&regions);
+
             // Copy settings to avoid borrow issues
             let feed_rate = state.tool_settings.feed_rate;
             let spindle_speed = state.tool_settings.spindle_speed;
@@ -513,10 +525,23 @@ impl DesignerView {
             state.toolpath_generator.set_step_in(tool_diameter * 0.4); // Default stepover
 
             let gcode = state.generate_gcode();
+            let collisions = state.keep_out_collisions.len();
             drop(state);
 
-            status_label_gen.set_text(&t!("G-Code generated"));
+            if collisions > 0 {
+                tracing::warn!("{} toolpath moves enter keep-out regions", collisions);
+                status_label_gen.set_text(&format!(
+                    "{} ({} {})",
+                    t!("G-Code generated"),
+                    collisions,
+                    t!("moves enter keep-out regions")
+                ));
+            } else {
+                status_label_gen.set_text(&t!("G-Code generated"));
+            }
 
+            let safe_z = settings_gen.borrow().config().machine.safe_z;
+            let gcode = wrap_with_active_profile(gcode, device_manager_gen.as_deref(), safe_z);
             if let Some(callback) = on_gen.borrow().as_ref() {
                 callback(gcode);
             }
@@ -548,6 +573,7 @@ impl DesignerView {
             current_file,
             on_gcode_generated,
             settings_persistence: Some(settings_controller.persistence.clone()),
+            device_manager: device_manager.clone(),
         });
 
         // Empty state actions
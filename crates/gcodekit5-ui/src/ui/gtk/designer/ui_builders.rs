@@ -108,6 +108,74 @@ impl DesignerView {
         snap_threshold_row.append(&snap_threshold);
         view_controls_box.append(&snap_threshold_row);
 
+        // Export coordinate snapping
+        let export_snap_toggle = gtk4::CheckButton::with_label(&t!("Snap G-code to grid"));
+        export_snap_toggle.set_tooltip_text(Some(&t!(
+            "Round exported coordinates to multiples of the export grid"
+        )));
+        let export_grid = gtk4::SpinButton::with_range(0.001, 10.0, 0.001);
+        export_grid.set_digits(3);
+        export_grid.set_tooltip_text(Some(&t!("Export grid")));
+        export_grid.set_value(match system {
+            gcodekit5_core::units::MeasurementSystem::Metric => 0.1,
+            gcodekit5_core::units::MeasurementSystem::Imperial => 0.005,
+        });
+        let export_snap_z = gtk4::CheckButton::with_label(&t!("Include Z"));
+        {
+            let current = state.borrow().export_snap;
+            export_snap_toggle.set_active(current.is_some());
+            if let Some(snap) = current {
+                export_snap_z.set_active(snap.snap_z);
+                export_grid.set_value(match system {
+                    gcodekit5_core::units::MeasurementSystem::Metric => snap.grid_size,
+                    gcodekit5_core::units::MeasurementSystem::Imperial => snap.grid_size / 25.4,
+                });
+            }
+        }
+        export_grid.set_sensitive(export_snap_toggle.is_active());
+        export_snap_z.set_sensitive(export_snap_toggle.is_active());
+
+        let update_export_snap = {
+            let state = state.clone();
+            let toggle = export_snap_toggle.clone();
+            let grid = export_grid.clone();
+            let snap_z = export_snap_z.clone();
+            Rc::new(move || {
+                let grid_mm = match system {
+                    gcodekit5_core::units::MeasurementSystem::Metric => grid.value(),
+                    gcodekit5_core::units::MeasurementSystem::Imperial => grid.value() * 25.4,
+                };
+                grid.set_sensitive(toggle.is_active());
+                snap_z.set_sensitive(toggle.is_active());
+                state.borrow_mut().export_snap =
+                    toggle
+                        .is_active()
+                        .then_some(gcodekit5_designer::gcode_gen::CoordinateSnap {
+                            grid_size: grid_mm,
+                            snap_z: snap_z.is_active(),
+                        });
+            })
+        };
+        {
+            let update = update_export_snap.clone();
+            export_snap_toggle.connect_toggled(move |_| update());
+        }
+        {
+            let update = update_export_snap.clone();
+            export_snap_z.connect_toggled(move |_| update());
+        }
+        {
+            let update = update_export_snap.clone();
+            export_grid.connect_value_changed(move |_| update());
+        }
+        view_controls_box.append(&export_snap_toggle);
+
+        let export_grid_row = Box::new(Orientation::Horizontal, 6);
+        export_grid_row.append(&Label::new(Some(&t!("Export grid"))));
+        export_grid_row.append(&export_grid);
+        view_controls_box.append(&export_grid_row);
+        view_controls_box.append(&export_snap_z);
+
         // Toolpath toggle
         let toolpath_toggle = gtk4::CheckButton::with_label(&t!("Show Toolpaths"));
         toolpath_toggle.set_active(false);
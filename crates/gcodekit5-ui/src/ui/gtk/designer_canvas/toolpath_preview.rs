@@ -3,6 +3,7 @@
 use super::*;
 use gcodekit5_designer::model::{DesignCircle as Circle, DesignerShape, Point, Shape};
 use gcodekit5_designer::shapes::OperationType;
+use gcodekit5_designer::tabs::apply_tabs;
 use gcodekit5_designer::toolpath::Toolpath;
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -22,7 +23,7 @@ impl DesignerCanvas {
 
         let started_at = std::time::Instant::now();
 
-        let (shapes, feed_rate, spindle_speed, tool_diameter, cut_depth) = {
+        let (shapes, feed_rate, spindle_speed, tool_diameter, cut_depth, job_tabs) = {
             let state = self.state.borrow();
             (
                 state.canvas.shapes().cloned().collect::<Vec<_>>(),
@@ -30,6 +31,7 @@ impl DesignerCanvas {
                 state.tool_settings.spindle_speed,
                 state.tool_settings.tool_diameter,
                 state.tool_settings.cut_depth,
+                state.tool_settings.tabs,
             )
         };
 
@@ -71,7 +73,8 @@ impl DesignerCanvas {
 
                 gen.set_pocket_strategy(shape.pocket_strategy);
                 gen.set_start_depth(shape.start_depth);
-                gen.set_cut_depth(shape.pocket_depth);
+                let depth = shape.effective_cut_depth(cut_depth);
+                gen.set_cut_depth(depth);
                 gen.set_step_in(shape.step_in as f64);
                 gen.set_raster_fill_ratio(shape.raster_fill_ratio);
 
@@ -81,7 +84,7 @@ impl DesignerCanvas {
                         if shape.operation_type == OperationType::Pocket {
                             gen.generate_rectangle_pocket(
                                 rect,
-                                shape.pocket_depth,
+                                depth,
                                 shape.step_down as f64,
                                 shape.step_in as f64,
                             )
@@ -93,7 +96,7 @@ impl DesignerCanvas {
                         if shape.operation_type == OperationType::Pocket {
                             gen.generate_circle_pocket(
                                 circle,
-                                shape.pocket_depth,
+                                depth,
                                 shape.step_down as f64,
                                 shape.step_in as f64,
                             )
@@ -114,7 +117,7 @@ impl DesignerCanvas {
                         if shape.operation_type == OperationType::Pocket {
                             gen.generate_path_pocket(
                                 path_shape,
-                                shape.pocket_depth,
+                                depth,
                                 shape.step_down as f64,
                                 shape.step_in as f64,
                             )
@@ -133,7 +136,7 @@ impl DesignerCanvas {
                         if shape.operation_type == OperationType::Pocket {
                             gen.generate_triangle_pocket(
                                 triangle,
-                                shape.pocket_depth,
+                                depth,
                                 shape.step_down as f64,
                                 shape.step_in as f64,
                             )
@@ -145,7 +148,7 @@ impl DesignerCanvas {
                         if shape.operation_type == OperationType::Pocket {
                             gen.generate_polygon_pocket(
                                 polygon,
-                                shape.pocket_depth,
+                                depth,
                                 shape.step_down as f64,
                                 shape.step_in as f64,
                             )
@@ -157,7 +160,7 @@ impl DesignerCanvas {
                         if shape.operation_type == OperationType::Pocket {
                             gen.generate_gear_pocket(
                                 gear,
-                                shape.pocket_depth,
+                                depth,
                                 shape.step_down as f64,
                                 shape.step_in as f64,
                             )
@@ -169,7 +172,7 @@ impl DesignerCanvas {
                         if shape.operation_type == OperationType::Pocket {
                             gen.generate_sprocket_pocket(
                                 sprocket,
-                                shape.pocket_depth,
+                                depth,
                                 shape.step_down as f64,
                                 shape.step_in as f64,
                             )
@@ -178,7 +181,10 @@ impl DesignerCanvas {
                         }
                     }
                 };
-                toolpaths.extend(shape_toolpaths);
+                match shape.effective_tabs(job_tabs) {
+                    Some(tabs) => toolpaths.extend(apply_tabs(&shape_toolpaths, &tabs)),
+                    None => toolpaths.extend(shape_toolpaths),
+                }
                 done_shapes_thread.fetch_add(1, Ordering::Relaxed);
             }
 
@@ -409,6 +409,9 @@ impl PropertiesPanel {
         Entry,
         DropDown,
         Entry,
+        Entry,
+        Entry,
+        Entry,
         Label,
         Label,
         Label,
@@ -491,6 +494,34 @@ impl PropertiesPanel {
         raster_fill_hint.add_css_class("dim-label");
         raster_fill_hint.set_halign(gtk4::Align::Start);
 
+        // Per-shape feeds and speeds (empty = tool settings)
+        let feed_label = Label::new(Some(&t!("Feed Rate:")));
+        feed_label.set_halign(gtk4::Align::Start);
+        let feed_entry = Entry::new();
+        feed_entry.set_hexpand(true);
+        feed_entry.set_placeholder_text(Some(&t!("Tool default")));
+        let feed_unit_label = Label::new(Some("mm/min"));
+        feed_unit_label.set_halign(gtk4::Align::End);
+        feed_unit_label.set_xalign(1.0);
+
+        let plunge_label = Label::new(Some(&t!("Plunge Rate:")));
+        plunge_label.set_halign(gtk4::Align::Start);
+        let plunge_entry = Entry::new();
+        plunge_entry.set_hexpand(true);
+        plunge_entry.set_placeholder_text(Some(&t!("Feed rate")));
+        let plunge_unit_label = Label::new(Some("mm/min"));
+        plunge_unit_label.set_halign(gtk4::Align::End);
+        plunge_unit_label.set_xalign(1.0);
+
+        let spindle_label = Label::new(Some(&t!("Spindle Speed:")));
+        spindle_label.set_halign(gtk4::Align::Start);
+        let spindle_entry = Entry::new();
+        spindle_entry.set_hexpand(true);
+        spindle_entry.set_placeholder_text(Some(&t!("Tool default")));
+        let spindle_unit_label = Label::new(Some("RPM"));
+        spindle_unit_label.set_halign(gtk4::Align::End);
+        spindle_unit_label.set_xalign(1.0);
+
         grid.attach(&op_label, 0, 0, 1, 1);
         grid.attach(&op_type_combo, 1, 0, 1, 1);
         grid.attach(&depth_label, 0, 1, 1, 1);
@@ -510,6 +541,15 @@ impl PropertiesPanel {
         grid.attach(&raster_fill_label, 0, 6, 1, 1);
         grid.attach(&raster_fill_entry, 1, 6, 1, 1);
         grid.attach(&raster_fill_hint, 0, 7, 3, 1);
+        grid.attach(&feed_label, 0, 8, 1, 1);
+        grid.attach(&feed_entry, 1, 8, 1, 1);
+        grid.attach(&feed_unit_label, 2, 8, 1, 1);
+        grid.attach(&plunge_label, 0, 9, 1, 1);
+        grid.attach(&plunge_entry, 1, 9, 1, 1);
+        grid.attach(&plunge_unit_label, 2, 9, 1, 1);
+        grid.attach(&spindle_label, 0, 10, 1, 1);
+        grid.attach(&spindle_entry, 1, 10, 1, 1);
+        grid.attach(&spindle_unit_label, 2, 10, 1, 1);
 
         frame.set_child(Some(&grid));
         (
@@ -521,6 +561,9 @@ impl PropertiesPanel {
             ramp_angle_entry,
             strategy_combo,
             raster_fill_entry,
+            feed_entry,
+            plunge_entry,
+            spindle_entry,
             depth_unit_label,
             step_down_unit_label,
             step_in_unit_label,
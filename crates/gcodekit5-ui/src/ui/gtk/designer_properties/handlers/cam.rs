@@ -1,4 +1,4 @@
-//! CAM property handlers (operation type, depth, step down, step in, ramp angle, strategy, raster fill).
+//! CAM property handlers (operation type, depth, step down, step in, ramp angle, strategy, raster fill, feeds and speeds).
 
 use gcodekit5_core::units;
 use gcodekit5_core::Shared;
@@ -20,12 +20,13 @@ pub fn setup_operation_type_handler(
         }
         let mut designer_state = state.borrow_mut();
         let is_pocket = combo.selected() == 1;
+        let job_depth = designer_state.tool_settings.cut_depth;
         let depth = designer_state
             .canvas
             .shapes()
             .find(|s| s.selected)
-            .map(|s| s.pocket_depth)
-            .unwrap_or(0.0);
+            .map(|s| s.effective_cut_depth(job_depth))
+            .unwrap_or(job_depth);
         designer_state.set_selected_pocket_properties(is_pocket, depth);
     });
 }
@@ -164,3 +165,58 @@ pub fn setup_strategy_handler(
         designer_state.set_selected_pocket_strategy(strategy);
     });
 }
+
+/// Setup per-shape feed rate, plunge rate and spindle speed entry handlers.
+///
+/// An empty entry clears the override so the shape uses the tool settings.
+pub fn setup_cutting_parameters_handler(
+    feed_entry: &Entry,
+    plunge_entry: &Entry,
+    spindle_entry: &Entry,
+    state: Shared<DesignerState>,
+    updating: Shared<bool>,
+) {
+    fn parse_optional<T: std::str::FromStr + PartialOrd + Default>(
+        entry: &Entry,
+    ) -> Result<Option<T>, ()> {
+        let text = entry.text();
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        match text.parse::<T>() {
+            Ok(val) if val > T::default() => Ok(Some(val)),
+            _ => Err(()),
+        }
+    }
+
+    let entries = [
+        feed_entry.clone(),
+        plunge_entry.clone(),
+        spindle_entry.clone(),
+    ];
+    for entry in &entries {
+        let entries = entries.clone();
+        let state = state.clone();
+        let updating = updating.clone();
+        entry.connect_changed(move |changed| {
+            if *updating.borrow() {
+                return;
+            }
+            let feed = parse_optional::<f64>(&entries[0]);
+            let plunge = parse_optional::<f64>(&entries[1]);
+            let spindle = parse_optional::<u32>(&entries[2]);
+            match (feed, plunge, spindle) {
+                (Ok(feed), Ok(plunge), Ok(spindle)) => {
+                    for entry in &entries {
+                        entry.remove_css_class("entry-invalid");
+                    }
+                    state
+                        .borrow_mut()
+                        .set_selected_cutting_parameters(feed, plunge, spindle);
+                }
+                _ => changed.add_css_class("entry-invalid"),
+            }
+        });
+    }
+}
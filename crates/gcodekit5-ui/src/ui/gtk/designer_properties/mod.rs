@@ -95,6 +95,9 @@ pub struct PropertiesPanel {
     pub(crate) ramp_angle_entry: Entry,
     pub(crate) strategy_combo: DropDown,
     pub(crate) raster_fill_entry: Entry,
+    pub(crate) feed_entry: Entry,
+    pub(crate) plunge_entry: Entry,
+    pub(crate) spindle_entry: Entry,
 
     // Geometry Ops widgets
     pub(crate) offset_entry: Entry,
@@ -219,6 +222,9 @@ impl PropertiesPanel {
             ramp_angle_entry,
             strategy_combo,
             raster_fill_entry,
+            feed_entry,
+            plunge_entry,
+            spindle_entry,
             depth_unit_label,
             step_down_unit_label,
             step_in_unit_label,
@@ -277,6 +283,9 @@ impl PropertiesPanel {
             ramp_angle_entry,
             strategy_combo,
             raster_fill_entry,
+            feed_entry,
+            plunge_entry,
+            spindle_entry,
             offset_entry,
             fillet_entry,
             chamfer_entry,
@@ -522,6 +531,14 @@ impl PropertiesPanel {
             self.updating.clone(),
         );
 
+        handlers::cam::setup_cutting_parameters_handler(
+            &self.feed_entry,
+            &self.plunge_entry,
+            &self.spindle_entry,
+            self.state.clone(),
+            self.updating.clone(),
+        );
+
         // Gear/Sprocket handlers
         handlers::gear_sprocket::setup_gear_module_handler(
             &self.gear_module_entry,
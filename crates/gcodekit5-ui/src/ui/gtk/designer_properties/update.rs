@@ -62,7 +62,7 @@ impl PropertiesPanel {
                     vec![obj.id],
                     Some(obj.shape.clone()),
                     obj.operation_type,
-                    obj.pocket_depth,
+                    obj.effective_cut_depth(designer_state.tool_settings.cut_depth),
                     obj.step_down,
                     obj.step_in,
                     obj.ramp_angle,
@@ -82,7 +82,7 @@ impl PropertiesPanel {
                     selected.iter().map(|s| s.id).collect(),
                     None, // No shape data for multi-selection
                     obj.operation_type,
-                    obj.pocket_depth,
+                    obj.effective_cut_depth(designer_state.tool_settings.cut_depth),
                     obj.step_down,
                     obj.step_in,
                     obj.ramp_angle,
@@ -308,6 +308,26 @@ impl PropertiesPanel {
             self.raster_fill_entry
                 .set_text(&format!("{:.0}", raster_fill * 100.0));
 
+            // Per-shape feeds and speeds (first selected shape's values)
+            let cutting = self
+                .state
+                .borrow()
+                .canvas
+                .shapes()
+                .find(|s| s.selected)
+                .map(|s| (s.feed_rate, s.plunge_rate, s.spindle_speed));
+            if let Some((feed_rate, plunge_rate, spindle_speed)) = cutting {
+                self.feed_entry
+                    .set_text(&feed_rate.map(|f| format!("{:.0}", f)).unwrap_or_default());
+                self.plunge_entry
+                    .set_text(&plunge_rate.map(|f| format!("{:.0}", f)).unwrap_or_default());
+                self.spindle_entry
+                    .set_text(&spindle_speed.map(|s| s.to_string()).unwrap_or_default());
+            }
+            self.feed_entry.set_sensitive(true);
+            self.plunge_entry.set_sensitive(true);
+            self.spindle_entry.set_sensitive(true);
+
             // Update geometry ops values
             self.offset_entry.set_text(&format!("{:.2}", offset));
             self.fillet_entry.set_text(&format!("{:.2}", fillet));
@@ -356,8 +376,14 @@ impl PropertiesPanel {
             self.ramp_angle_entry.set_sensitive(false);
             self.strategy_combo.set_sensitive(false);
             self.raster_fill_entry.set_sensitive(false);
+            self.feed_entry.set_sensitive(false);
+            self.plunge_entry.set_sensitive(false);
+            self.spindle_entry.set_sensitive(false);
 
             self.raster_fill_entry.set_text("");
+            self.feed_entry.set_text("");
+            self.plunge_entry.set_text("");
+            self.spindle_entry.set_text("");
             *self.updating.borrow_mut() = false;
         }
     }
@@ -377,6 +403,9 @@ impl PropertiesPanel {
             &self.step_in_entry,
             &self.ramp_angle_entry,
             &self.raster_fill_entry,
+            &self.feed_entry,
+            &self.plunge_entry,
+            &self.spindle_entry,
             &self.sides_entry,
             &self.gear_module_entry,
             &self.gear_teeth_entry,
@@ -3,16 +3,26 @@
 //! Provides a terminal-like console for direct G-code communication
 //! with the connected CNC device. Displays sent commands and
 //! received responses with syntax highlighting.
+//!
+//! Status polls, jog commands, `ok` acknowledgements and command echoes can be
+//! hidden from view. The filter only changes what is drawn: the full log is kept
+//! and redrawn when the filter changes.
 
 use gtk4::prelude::*;
 use gtk4::{
-    gdk, Box, Button, Entry, EventControllerKey, Label, Orientation, Paned, ScrolledWindow,
-    TextView, WrapMode,
+    gdk, Box, Button, CheckButton, Entry, EventControllerKey, Label, ListBox, Orientation, Paned,
+    Popover, PositionType, ScrolledWindow, TextView, WrapMode,
 };
 use std::{borrow::Cow, rc::Rc};
 
+use crate::device_status;
 use crate::ui::gtk::command_history::CommandHistory;
+use crate::ui::gtk::console_completion::{console_suggestions, ConsoleSuggestion};
+use gcodekit5_communication::firmware::firmware_version::FirmwareType;
 use gcodekit5_core::{shared, Shared};
+use gcodekit5_settings::controller::SettingsController;
+use gcodekit5_settings::manager::SettingsManager;
+use gcodekit5_settings::ConsoleFilter;
 
 pub struct DeviceConsoleView {
     pub widget: Paned,
@@ -20,10 +30,13 @@ pub struct DeviceConsoleView {
     pub command_entry: Entry,
     pub send_btn: Button,
     history: Shared<CommandHistory>,
+    /// Everything logged, including lines the filter hides
+    log: Shared<String>,
+    filter: Shared<ConsoleFilter>,
 }
 
 impl DeviceConsoleView {
-    pub fn new() -> Rc<Self> {
+    pub fn new(settings: Option<Rc<SettingsController>>) -> Rc<Self> {
         let widget = Paned::new(Orientation::Horizontal);
         widget.set_hexpand(true);
         widget.set_vexpand(true);
@@ -76,8 +89,46 @@ impl DeviceConsoleView {
         send_btn.set_tooltip_text(Some("Send Command"));
         input_box.append(&send_btn);
 
+        let filter_btn = Button::from_icon_name("view-filter-symbolic");
+        filter_btn.set_tooltip_text(Some("Console Filters"));
+        input_box.append(&filter_btn);
+
         main_area.append(&input_box);
 
+        let filter = shared(
+            settings
+                .as_ref()
+                .map(|s| s.persistence.borrow().config().ui.console_filter)
+                .unwrap_or_default(),
+        );
+        let filter_popover = Popover::new();
+        filter_popover.set_parent(&filter_btn);
+        filter_popover.set_has_arrow(true);
+        let filter_box = Box::new(Orientation::Vertical, 6);
+        filter_box.set_margin_start(12);
+        filter_box.set_margin_end(12);
+        filter_box.set_margin_top(12);
+        filter_box.set_margin_bottom(12);
+        let hide_status_polls = CheckButton::with_label("Hide status polls (?)");
+        let hide_jog = CheckButton::with_label("Hide jog commands");
+        let hide_ok = CheckButton::with_label("Hide ok acknowledgements");
+        let hide_echoes = CheckButton::with_label("Hide all sent commands");
+        {
+            let f = filter.borrow();
+            hide_status_polls.set_active(f.hide_status_polls);
+            hide_jog.set_active(f.hide_jog);
+            hide_ok.set_active(f.hide_ok);
+            hide_echoes.set_active(f.hide_echoes);
+        }
+        for check in [&hide_status_polls, &hide_jog, &hide_ok, &hide_echoes] {
+            filter_box.append(check);
+        }
+        filter_popover.set_child(Some(&filter_box));
+        {
+            let pop = filter_popover.clone();
+            filter_btn.connect_clicked(move |_| pop.popup());
+        }
+
         // Setup Paned
 
         widget.set_end_child(Some(&main_area));
@@ -101,9 +152,124 @@ impl DeviceConsoleView {
             command_entry: command_entry.clone(),
             send_btn,
             history: history.clone(),
+            log: shared(String::new()),
+            filter: filter.clone(),
         });
 
-        // Setup key event controller for command history navigation
+        {
+            let checks = [
+                hide_status_polls.clone(),
+                hide_jog.clone(),
+                hide_ok.clone(),
+                hide_echoes.clone(),
+            ];
+            let view_weak = Rc::downgrade(&view);
+            let on_toggled = Rc::new(move || {
+                let Some(view) = view_weak.upgrade() else {
+                    return;
+                };
+                let new_filter = ConsoleFilter {
+                    hide_status_polls: hide_status_polls.is_active(),
+                    hide_jog: hide_jog.is_active(),
+                    hide_ok: hide_ok.is_active(),
+                    hide_echoes: hide_echoes.is_active(),
+                };
+                *view.filter.borrow_mut() = new_filter;
+                view.redraw_log();
+                if let Some(settings) = settings.as_ref() {
+                    let mut p = settings.persistence.borrow_mut();
+                    p.config_mut().ui.console_filter = new_filter;
+                    if let Ok(path) = SettingsManager::config_file_path() {
+                        let _ = SettingsManager::ensure_config_dir();
+                        let _ = p.save_to_file(&path);
+                    }
+                }
+            });
+            for check in checks {
+                let on_toggled = on_toggled.clone();
+                check.connect_toggled(move |_| on_toggled());
+            }
+        }
+
+        // Completion suggestions shown above the entry while typing
+        let suggestions: Shared<Vec<ConsoleSuggestion>> = shared(Vec::new());
+        let suggestion_list = ListBox::new();
+        suggestion_list.add_css_class("navigation-sidebar");
+        let suggestion_popover = Popover::builder()
+            .child(&suggestion_list)
+            .position(PositionType::Top)
+            .has_arrow(false)
+            .autohide(false)
+            .can_focus(false)
+            .build();
+        suggestion_popover.set_parent(&command_entry);
+
+        {
+            let history = history.clone();
+            let suggestions = suggestions.clone();
+            let list = suggestion_list.clone();
+            let popover = suggestion_popover.clone();
+            command_entry.connect_changed(move |entry| {
+                let status = device_status::get_status();
+                let firmware = status
+                    .firmware_type
+                    .as_deref()
+                    .map(FirmwareType::from_string);
+                let device_settings: Vec<(u16, String)> =
+                    status.grbl_settings.into_iter().collect();
+                let found = console_suggestions(
+                    &entry.text(),
+                    firmware,
+                    history.borrow().commands(),
+                    &device_settings,
+                );
+
+                while let Some(row) = list.first_child() {
+                    list.remove(&row);
+                }
+                for suggestion in &found {
+                    let row = Box::new(Orientation::Horizontal, 12);
+                    let text = Label::new(Some(&suggestion.text));
+                    text.add_css_class("monospace");
+                    let description = Label::new(Some(&suggestion.description));
+                    description.add_css_class("dim-label");
+                    description.set_hexpand(true);
+                    description.set_halign(gtk4::Align::End);
+                    row.append(&text);
+                    row.append(&description);
+                    list.append(&row);
+                }
+                if found.is_empty() {
+                    popover.popdown();
+                } else {
+                    popover.popup();
+                }
+                *suggestions.borrow_mut() = found;
+            });
+        }
+        {
+            let suggestions = suggestions.clone();
+            let entry = command_entry.clone();
+            let popover = suggestion_popover.clone();
+            suggestion_list.connect_row_activated(move |_, row| {
+                let text = suggestions
+                    .borrow()
+                    .get(row.index() as usize)
+                    .map(|s| s.text.clone());
+                if let Some(text) = text {
+                    entry.set_text(&text);
+                    entry.set_position(-1);
+                    entry.grab_focus();
+                }
+                popover.popdown();
+            });
+        }
+        {
+            let popover = suggestion_popover.clone();
+            command_entry.connect_activate(move |_| popover.popdown());
+        }
+
+        // Setup key event controller for command history navigation and completion
         let key_controller = EventControllerKey::new();
         let history_clone = history.clone();
         let entry_clone = command_entry.clone();
@@ -116,6 +282,7 @@ impl DeviceConsoleView {
                         entry_clone.set_text(&prev_cmd);
                         entry_clone.set_position(-1); // Move cursor to end
                     }
+                    suggestion_popover.popdown();
                     glib::Propagation::Stop
                 }
                 gdk::Key::Down => {
@@ -123,6 +290,30 @@ impl DeviceConsoleView {
                         entry_clone.set_text(&next_cmd);
                         entry_clone.set_position(-1); // Move cursor to end
                     }
+                    suggestion_popover.popdown();
+                    glib::Propagation::Stop
+                }
+                gdk::Key::Tab => {
+                    // Accept the selected suggestion, or the first one
+                    let index = suggestion_list
+                        .selected_row()
+                        .map(|row| row.index() as usize)
+                        .unwrap_or(0);
+                    let text = suggestion_popover
+                        .is_visible()
+                        .then(|| suggestions.borrow().get(index).map(|s| s.text.clone()))
+                        .flatten();
+                    match text {
+                        Some(text) => {
+                            entry_clone.set_text(&text);
+                            entry_clone.set_position(-1);
+                            glib::Propagation::Stop
+                        }
+                        None => glib::Propagation::Proceed,
+                    }
+                }
+                gdk::Key::Escape if suggestion_popover.is_visible() => {
+                    suggestion_popover.popdown();
                     glib::Propagation::Stop
                 }
                 _ => glib::Propagation::Proceed,
@@ -143,9 +334,12 @@ impl DeviceConsoleView {
             Cow::Borrowed(message)
         };
 
+        self.log.borrow_mut().push_str(msg.as_ref());
+
         // Append to bottom and auto-scroll
+        let shown = visible_text(msg.as_ref(), &self.filter.borrow());
         let mut iter = buffer.end_iter();
-        buffer.insert(&mut iter, msg.as_ref());
+        buffer.insert(&mut iter, shown.as_ref());
 
         // Auto-scroll to bottom after inserting
         let mark = buffer.create_mark(None, &buffer.end_iter(), false);
@@ -153,14 +347,24 @@ impl DeviceConsoleView {
         buffer.delete_mark(&mark);
     }
 
+    /// The full log, including lines hidden by the console filter
     pub fn get_log_text(&self) -> String {
+        self.log.borrow().clone()
+    }
+
+    /// Redraw the console from the log under the current filter
+    fn redraw_log(&self) {
         let buffer = self.console_text.buffer();
-        let start = buffer.start_iter();
-        let end = buffer.end_iter();
-        buffer.text(&start, &end, true).to_string()
+        let log = self.log.borrow();
+        buffer.set_text(visible_text(&log, &self.filter.borrow()).as_ref());
+
+        let mark = buffer.create_mark(None, &buffer.end_iter(), false);
+        self.console_text.scroll_to_mark(&mark, 0.0, true, 0.0, 1.0);
+        buffer.delete_mark(&mark);
     }
 
     pub fn clear_log(&self) {
+        self.log.borrow_mut().clear();
         let buffer = self.console_text.buffer();
         let start = buffer.start_iter();
         let end = buffer.end_iter();
@@ -181,3 +385,15 @@ impl DeviceConsoleView {
         self.history.borrow_mut().reset_navigation();
     }
 }
+
+/// The lines of `text` the filter shows
+fn visible_text<'a>(text: &'a str, filter: &ConsoleFilter) -> Cow<'a, str> {
+    if *filter == ConsoleFilter::default() {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(
+        text.split_inclusive('\n')
+            .filter(|line| filter.shows(line))
+            .collect(),
+    )
+}
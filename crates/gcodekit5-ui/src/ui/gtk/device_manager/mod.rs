@@ -11,7 +11,7 @@ use gtk4::prelude::*;
 use gtk4::{
     Align, Box, Button, CheckButton, ComboBoxText, Entry, Grid, Label, ListBox, ListBoxRow,
     MessageDialog, MessageType, Orientation, Paned, PolicyType, ResponseType, ScrolledWindow,
-    SearchEntry, Stack, StackSwitcher,
+    SearchEntry, Stack, StackSwitcher, TextView,
 };
 use std::rc::Rc;
 use tracing::error;
@@ -20,6 +20,7 @@ use crate::ui::gtk::help_browser;
 
 use crate::device_status;
 use gcodekit5_devicedb::ui_integration::{DeviceProfileUiModel, DeviceUiController};
+use gcodekit5_devicedb::{EndOfJob, ParkCoordinates, ProgramTemplates, TEMPLATE_VARIABLES};
 
 use gcodekit5_core::units::{
     format_feed_rate, format_length, get_unit_label, parse_feed_rate, parse_length, FeedRateUnits,
@@ -71,6 +72,16 @@ pub struct DeviceManagerWindow {
     pub(crate) edit_spindle_watts: Entry,
     pub(crate) edit_max_spindle_speed_rpm: Entry,
     pub(crate) edit_laser_watts: Entry,
+    pub(crate) edit_end_of_job_enabled: CheckButton,
+    pub(crate) edit_end_of_job_power_off: CheckButton,
+    pub(crate) edit_end_of_job_coordinates: ComboBoxText,
+    pub(crate) edit_end_of_job_clearance_z: Entry,
+    pub(crate) edit_end_of_job_park: CheckButton,
+    pub(crate) edit_end_of_job_park_x: Entry,
+    pub(crate) edit_end_of_job_park_y: Entry,
+    pub(crate) edit_end_of_job_unit: Label,
+    pub(crate) edit_preamble: TextView,
+    pub(crate) edit_postamble: TextView,
 
     // State
     pub(crate) selected_device: SharedOption<DeviceProfileUiModel>,
@@ -240,11 +251,25 @@ impl DeviceManagerWindow {
             edit_max_spindle_speed_rpm,
             edit_laser_watts,
         ) = Self::create_capabilities_tab(*current_feed_units.borrow());
+        let (
+            end_of_job_page,
+            edit_end_of_job_enabled,
+            edit_end_of_job_power_off,
+            edit_end_of_job_coordinates,
+            edit_end_of_job_clearance_z,
+            edit_end_of_job_park,
+            edit_end_of_job_park_x,
+            edit_end_of_job_park_y,
+            edit_end_of_job_unit,
+        ) = Self::create_end_of_job_tab(*current_units.borrow());
+        let (templates_page, edit_preamble, edit_postamble) = Self::create_templates_tab();
 
         stack.add_titled(&general_page, Some("general"), "General");
         stack.add_titled(&connection_page, Some("connection"), "Connection");
         stack.add_titled(&dimensions_page, Some("dimensions"), "Dimensions");
         stack.add_titled(&capabilities_page, Some("capabilities"), "Capabilities");
+        stack.add_titled(&end_of_job_page, Some("end_of_job"), "End of Job");
+        stack.add_titled(&templates_page, Some("templates"), "Templates");
 
         let switcher = StackSwitcher::new();
         switcher.set_stack(Some(&stack));
@@ -327,6 +352,16 @@ impl DeviceManagerWindow {
             edit_spindle_watts,
             edit_max_spindle_speed_rpm,
             edit_laser_watts,
+            edit_end_of_job_enabled,
+            edit_end_of_job_power_off,
+            edit_end_of_job_coordinates,
+            edit_end_of_job_clearance_z,
+            edit_end_of_job_park,
+            edit_end_of_job_park_x,
+            edit_end_of_job_park_y,
+            edit_end_of_job_unit,
+            edit_preamble,
+            edit_postamble,
             selected_device: shared_none(),
             save_btn,
             cancel_btn,
@@ -388,6 +423,7 @@ impl DeviceManagerWindow {
         self.edit_y_max_unit.set_text(unit_label);
         self.edit_z_min_unit.set_text(unit_label);
         self.edit_z_max_unit.set_text(unit_label);
+        self.edit_end_of_job_unit.set_text(unit_label);
 
         self.edit_max_feed_rate_unit
             .set_text(&feed_units.to_string());
@@ -423,6 +459,18 @@ impl DeviceManagerWindow {
                 profile.max_feed_rate.parse::<f32>().unwrap_or(1000.0),
                 feed_units,
             ));
+
+            let end_of_job = &profile.end_of_job;
+            self.edit_end_of_job_clearance_z.set_text(
+                &end_of_job
+                    .clearance_z
+                    .map(|z| format_length(z as f32, units))
+                    .unwrap_or_default(),
+            );
+            self.edit_end_of_job_park_x
+                .set_text(&format_length(end_of_job.park_x as f32, units));
+            self.edit_end_of_job_park_y
+                .set_text(&format_length(end_of_job.park_y as f32, units));
         }
     }
 }
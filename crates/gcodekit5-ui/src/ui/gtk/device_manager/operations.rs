@@ -199,6 +199,24 @@ impl DeviceManagerWindow {
                 .set_text(profile.max_spindle_speed_rpm.trim());
             self.edit_laser_watts.set_text(profile.laser_watts.trim());
 
+            let end_of_job = &profile.end_of_job;
+            self.edit_end_of_job_enabled.set_active(end_of_job.enabled);
+            self.edit_end_of_job_power_off
+                .set_active(end_of_job.power_off);
+            self.edit_end_of_job_coordinates
+                .set_active_id(Some(match end_of_job.coordinates {
+                    ParkCoordinates::Machine => "machine",
+                    ParkCoordinates::Work => "work",
+                }));
+            self.edit_end_of_job_park.set_active(end_of_job.park);
+
+            self.edit_preamble
+                .buffer()
+                .set_text(&profile.program_templates.preamble);
+            self.edit_postamble
+                .buffer()
+                .set_text(&profile.program_templates.postamble);
+
             self.update_connection_field_sensitivity();
             self.update_capabilities_field_sensitivity();
 
@@ -430,6 +448,58 @@ impl DeviceManagerWindow {
             model.cnc_spindle_watts = format!("{:.0}", spindle_watts);
             model.laser_watts = format!("{:.0}", laser_watts);
 
+            // End of Job
+            let clearance_text = self.edit_end_of_job_clearance_z.text();
+            let clearance_z = if clearance_text.trim().is_empty() {
+                None
+            } else {
+                match parse_length(&clearance_text, units) {
+                    Ok(v) => Some(v as f64),
+                    Err(e) => {
+                        self.show_error_dialog("Invalid Clearance Z", &e);
+                        return;
+                    }
+                }
+            };
+            let park_x_mm = match parse_length(&self.edit_end_of_job_park_x.text(), units) {
+                Ok(v) => v,
+                Err(e) => {
+                    self.show_error_dialog("Invalid Park X", &e);
+                    return;
+                }
+            };
+            let park_y_mm = match parse_length(&self.edit_end_of_job_park_y.text(), units) {
+                Ok(v) => v,
+                Err(e) => {
+                    self.show_error_dialog("Invalid Park Y", &e);
+                    return;
+                }
+            };
+            model.end_of_job = EndOfJob {
+                enabled: self.edit_end_of_job_enabled.is_active(),
+                power_off: self.edit_end_of_job_power_off.is_active(),
+                coordinates: match self.edit_end_of_job_coordinates.active_id().as_deref() {
+                    Some("work") => ParkCoordinates::Work,
+                    _ => ParkCoordinates::Machine,
+                },
+                clearance_z,
+                park: self.edit_end_of_job_park.is_active(),
+                park_x: park_x_mm as f64,
+                park_y: park_y_mm as f64,
+            };
+
+            // Templates
+            let text = |view: &TextView| {
+                let buffer = view.buffer();
+                buffer
+                    .text(&buffer.start_iter(), &buffer.end_iter(), false)
+                    .to_string()
+            };
+            model.program_templates = ProgramTemplates {
+                preamble: text(&self.edit_preamble),
+                postamble: text(&self.edit_postamble),
+            };
+
             // Save
             if let Err(e) = self.controller.update_profile_from_ui(model) {
                 error!("Failed to save device profile: {}", e);
@@ -461,4 +461,154 @@ impl DeviceManagerWindow {
             edit_laser_watts,
         )
     }
+
+    // GTK callback closure type inherently complex.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn create_end_of_job_tab(
+        units: MeasurementSystem,
+    ) -> (
+        ScrolledWindow,
+        CheckButton,
+        CheckButton,
+        ComboBoxText,
+        Entry,
+        CheckButton,
+        Entry,
+        Entry,
+        Label,
+    ) {
+        let scroll = ScrolledWindow::new();
+        scroll.set_policy(PolicyType::Never, PolicyType::Automatic);
+
+        let grid = Grid::new();
+        grid.set_margin_top(10);
+        grid.set_margin_bottom(10);
+        grid.set_margin_start(10);
+        grid.set_margin_end(10);
+        grid.set_column_spacing(10);
+        grid.set_row_spacing(10);
+
+        let mut row = 0;
+
+        // Enabled
+        let edit_end_of_job_enabled = CheckButton::with_label("Retract when a job completes");
+        edit_end_of_job_enabled.set_tooltip_text(Some(
+            "Runs only after a clean finish, never after a stop or abort",
+        ));
+        grid.attach(&edit_end_of_job_enabled, 0, row, 3, 1);
+        row += 1;
+
+        // Power Off
+        let edit_end_of_job_power_off = CheckButton::with_label("Turn spindle/laser off (M5)");
+        edit_end_of_job_power_off.set_active(true);
+        grid.attach(&edit_end_of_job_power_off, 0, row, 3, 1);
+        row += 1;
+
+        // Coordinates
+        let coords_label = Label::new(Some("Coordinates:"));
+        coords_label.set_halign(Align::Start);
+        let edit_end_of_job_coordinates = ComboBoxText::new();
+        edit_end_of_job_coordinates.append(Some("machine"), "Machine (G53)");
+        edit_end_of_job_coordinates.append(Some("work"), "Work");
+        edit_end_of_job_coordinates.set_active_id(Some("machine"));
+        grid.attach(&coords_label, 0, row, 1, 1);
+        grid.attach(&edit_end_of_job_coordinates, 1, row, 1, 1);
+        row += 1;
+
+        // Clearance Z
+        let clearance_label = Label::new(Some("Clearance Z:"));
+        clearance_label.set_halign(Align::Start);
+        let edit_end_of_job_clearance_z = Entry::new();
+        edit_end_of_job_clearance_z.set_input_purpose(gtk4::InputPurpose::Number);
+        edit_end_of_job_clearance_z.set_width_chars(8);
+        edit_end_of_job_clearance_z.set_placeholder_text(Some("Top of Z"));
+        edit_end_of_job_clearance_z.set_tooltip_text(Some(
+            "Leave empty to retract to the Z Max limit in machine coordinates",
+        ));
+        let edit_end_of_job_unit = Label::new(Some(get_unit_label(units)));
+        edit_end_of_job_unit.set_halign(Align::Start);
+        grid.attach(&clearance_label, 0, row, 1, 1);
+        grid.attach(&edit_end_of_job_clearance_z, 1, row, 1, 1);
+        grid.attach(&edit_end_of_job_unit, 2, row, 1, 1);
+        row += 1;
+
+        // Park
+        let edit_end_of_job_park = CheckButton::with_label("Move to park position");
+        edit_end_of_job_park.set_tooltip_text(Some(
+            "Skipped when the position is outside the X or Y limits",
+        ));
+        grid.attach(&edit_end_of_job_park, 0, row, 3, 1);
+        row += 1;
+
+        let park_x_label = Label::new(Some("Park X:"));
+        park_x_label.set_halign(Align::Start);
+        let edit_end_of_job_park_x = Entry::new();
+        edit_end_of_job_park_x.set_input_purpose(gtk4::InputPurpose::Number);
+        edit_end_of_job_park_x.set_width_chars(8);
+        grid.attach(&park_x_label, 0, row, 1, 1);
+        grid.attach(&edit_end_of_job_park_x, 1, row, 1, 1);
+        row += 1;
+
+        let park_y_label = Label::new(Some("Park Y:"));
+        park_y_label.set_halign(Align::Start);
+        let edit_end_of_job_park_y = Entry::new();
+        edit_end_of_job_park_y.set_input_purpose(gtk4::InputPurpose::Number);
+        edit_end_of_job_park_y.set_width_chars(8);
+        grid.attach(&park_y_label, 0, row, 1, 1);
+        grid.attach(&edit_end_of_job_park_y, 1, row, 1, 1);
+
+        scroll.set_child(Some(&grid));
+        (
+            scroll,
+            edit_end_of_job_enabled,
+            edit_end_of_job_power_off,
+            edit_end_of_job_coordinates,
+            edit_end_of_job_clearance_z,
+            edit_end_of_job_park,
+            edit_end_of_job_park_x,
+            edit_end_of_job_park_y,
+            edit_end_of_job_unit,
+        )
+    }
+
+    pub(crate) fn create_templates_tab() -> (ScrolledWindow, TextView, TextView) {
+        let scroll = ScrolledWindow::new();
+        scroll.set_policy(PolicyType::Never, PolicyType::Automatic);
+
+        let vbox = Box::new(Orientation::Vertical, 10);
+        vbox.set_margin_top(10);
+        vbox.set_margin_bottom(10);
+        vbox.set_margin_start(10);
+        vbox.set_margin_end(10);
+
+        let variables: Vec<String> = TEMPLATE_VARIABLES
+            .iter()
+            .map(|(name, _, _)| format!("{{{}}}", name))
+            .collect();
+        let hint = Label::new(Some(&format!(
+            "Written around every generated program. Variables: {}",
+            variables.join(" ")
+        )));
+        hint.set_wrap(true);
+        hint.set_halign(Align::Start);
+        hint.add_css_class("dim-label");
+        vbox.append(&hint);
+
+        let template_view = |title: &str| {
+            let label = Label::new(Some(title));
+            label.set_halign(Align::Start);
+            vbox.append(&label);
+            let view = TextView::new();
+            view.set_monospace(true);
+            view.set_height_request(100);
+            view.add_css_class("view");
+            vbox.append(&view);
+            view
+        };
+        let edit_preamble = template_view("Preamble:");
+        let edit_postamble = template_view("Postamble:");
+
+        scroll.set_child(Some(&vbox));
+        (scroll, edit_preamble, edit_postamble)
+    }
 }
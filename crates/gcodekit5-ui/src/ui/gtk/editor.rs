@@ -2,9 +2,14 @@
 //!
 //! GTK4 text editor widget for G-code files with syntax highlighting,
 //! line numbers, and integration with the editor backend bridge.
+//!
+//! When a file is opened it is checked against the active device and units,
+//! and anything worth knowing is shown in a dismissable bar over the editor.
 
+use crate::ui::gtk::gcode_explain;
 use crate::ui::gtk::status_bar::StatusBar;
-use gcodekit5_core::{shared_none, SharedOption};
+use gcodekit5_camtools::load_check::{convert_units, LoadCheck};
+use gcodekit5_core::{shared_none, SharedOption, Units};
 use glib;
 use gtk4::prelude::*;
 use gtk4::{
@@ -30,6 +35,97 @@ pub struct GcodeEditor {
     _search_context: SearchContext,
     _search_settings: SearchSettings,
     _status_bar: Option<StatusBar>,
+    load_check_bar: LoadCheckBar,
+}
+
+/// Checks a program just opened, e.g. against the active device
+type LoadChecker = std::boxed::Box<dyn Fn(&str) -> LoadCheck>;
+
+/// Bar showing the load check of the file just opened
+#[derive(Clone)]
+struct LoadCheckBar {
+    widget: Box,
+    label: Label,
+    convert_btn: Button,
+    checker: SharedOption<LoadChecker>,
+    check: SharedOption<LoadCheck>,
+}
+
+impl LoadCheckBar {
+    fn new(buffer: &Buffer) -> Self {
+        let widget = Box::new(Orientation::Horizontal, 8);
+        widget.add_css_class("visualizer-osd");
+        widget.set_halign(gtk4::Align::Start);
+        widget.set_valign(gtk4::Align::End);
+        widget.set_margin_bottom(20);
+        widget.set_margin_start(20);
+        widget.set_visible(false);
+
+        let label = Label::new(None);
+        label.set_wrap(true);
+        let convert_btn = Button::new();
+        let dismiss_btn = Button::builder()
+            .icon_name("window-close-symbolic")
+            .tooltip_text("Dismiss")
+            .build();
+        widget.append(&label);
+        widget.append(&convert_btn);
+        widget.append(&dismiss_btn);
+
+        let bar = Self {
+            widget,
+            label,
+            convert_btn,
+            checker: shared_none(),
+            check: shared_none(),
+        };
+        {
+            let widget = bar.widget.clone();
+            dismiss_btn.connect_clicked(move |_| widget.set_visible(false));
+        }
+        {
+            let bar_clone = bar.clone();
+            let buffer = buffer.clone();
+            bar.convert_btn.connect_clicked(move |_| {
+                let Some(check) = bar_clone.check.borrow().clone() else {
+                    return;
+                };
+                let target = match check.units {
+                    Units::INCH => Units::MM,
+                    _ => Units::INCH,
+                };
+                let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), true);
+                let report = convert_units(&text, check.units, target);
+                buffer.set_text(&report.program);
+                buffer.place_cursor(&buffer.start_iter());
+                tracing::info!(
+                    "Converted {} line(s) from {:?} to {:?}",
+                    report.lines_converted,
+                    check.units,
+                    target
+                );
+                bar_clone.show(&report.program);
+            });
+        }
+        bar
+    }
+
+    /// Check `text` and show the result if there is anything to warn about
+    fn show(&self, text: &str) {
+        let check = match self.checker.borrow().as_ref() {
+            Some(checker) => checker(text),
+            None => return,
+        };
+        tracing::info!("Load check: {}", check.summary());
+        self.label.set_text(&check.summary());
+        self.convert_btn.set_visible(check.units_mismatch());
+        self.convert_btn.set_label(match check.units {
+            Units::INCH => "Convert to mm",
+            _ => "Convert to inches",
+        });
+        self.widget.set_visible(!check.is_ok());
+        *self.check.borrow_mut() = Some(check);
+    }
 }
 
 impl GcodeEditor {
@@ -45,6 +141,33 @@ impl GcodeEditor {
         view.set_show_right_margin(true);
         view.set_right_margin_position(80);
 
+        // Explain the line under the pointer, or the cursor's line from the keyboard
+        view.set_has_tooltip(true);
+        view.connect_query_tooltip(|view, x, y, keyboard_mode, tooltip| {
+            let buffer = view.buffer();
+            let iter = if keyboard_mode {
+                Some(buffer.iter_at_mark(&buffer.get_insert()))
+            } else {
+                let (bx, by) = view.window_to_buffer_coords(gtk4::TextWindowType::Widget, x, y);
+                view.iter_at_location(bx, by)
+            };
+            let Some(mut start) = iter else {
+                return false;
+            };
+            start.set_line_offset(0);
+            let mut end = start;
+            if !end.ends_line() {
+                end.forward_to_line_end();
+            }
+            match gcode_explain::tooltip_text(&buffer.text(&start, &end, false)) {
+                Some(text) => {
+                    tooltip.set_text(Some(&text));
+                    true
+                }
+                None => false,
+            }
+        });
+
         // Try to set a dark style scheme if available, matching the app's dark theme
         let scheme_manager = StyleSchemeManager::default();
 
@@ -143,6 +266,10 @@ impl GcodeEditor {
 
         overlay.add_overlay(&line_counter_box);
 
+        // Fit and units check of the file just opened (bottom left)
+        let load_check_bar = LoadCheckBar::new(&buffer);
+        overlay.add_overlay(&load_check_bar.widget);
+
         // --- Search Panel ---
         let search_settings = SearchSettings::new();
         let search_context = SearchContext::new(&buffer, Some(&search_settings));
@@ -499,6 +626,7 @@ impl GcodeEditor {
             _search_context: search_context,
             _search_settings: search_settings,
             _status_bar: status_bar,
+            load_check_bar,
         };
 
         // Update line counter when cursor moves
@@ -559,6 +687,11 @@ impl GcodeEditor {
         self.view.grab_focus();
     }
 
+    /// Check each file opened with `checker`, showing any warning it raises
+    pub fn set_load_checker<F: Fn(&str) -> LoadCheck + 'static>(&self, checker: F) {
+        *self.load_check_bar.checker.borrow_mut() = Some(std::boxed::Box::new(checker));
+    }
+
     pub fn connect_changed<F: Fn(&Buffer) + 'static>(&self, f: F) {
         self.buffer.connect_changed(f);
     }
@@ -593,6 +726,28 @@ impl GcodeEditor {
     pub fn new_file(&self) {
         self.set_text("");
         *self.current_file.borrow_mut() = None;
+        self.mark_unmodified();
+    }
+
+    /// Whether the document has changed since it was opened or saved
+    pub fn is_modified(&self) -> bool {
+        self.buffer.is_modified()
+    }
+
+    pub fn mark_unmodified(&self) {
+        self.buffer.set_modified(false);
+    }
+
+    /// File the document was opened from or last saved to
+    pub fn current_file(&self) -> Option<PathBuf> {
+        self.current_file.borrow().clone()
+    }
+
+    /// Show recovered unsaved work for `source`; it stays modified until saved
+    pub fn restore_recovered(&self, text: &str, source: Option<PathBuf>) {
+        self.set_text(text);
+        *self.current_file.borrow_mut() = source;
+        self.buffer.set_modified(true);
     }
 
     pub fn open_file(&self) {
@@ -614,6 +769,7 @@ impl GcodeEditor {
 
         let buffer = self.buffer.clone();
         let current_file = self.current_file.clone();
+        let load_check_bar = self.load_check_bar.clone();
 
         dialog.connect_response(move |dialog, response| {
             if response == ResponseType::Accept {
@@ -622,10 +778,12 @@ impl GcodeEditor {
                         match fs::read_to_string(&path) {
                             Ok(content) => {
                                 buffer.set_text(&content);
+                                buffer.set_modified(false);
                                 *current_file.borrow_mut() = Some(path);
                                 // Move cursor to start
                                 let start_iter = buffer.start_iter();
                                 buffer.place_cursor(&start_iter);
+                                load_check_bar.show(&content);
                             }
                             Err(e) => {
                                 error!("Error reading file {}: {}", path.display(), e);
@@ -654,14 +812,17 @@ impl GcodeEditor {
             let end = self.buffer.end_iter();
             let content = self.buffer.text(&start, &end, true);
 
-            if let Err(e) = fs::write(&path, content.as_str()) {
-                error!("Error saving file {}: {}", path.display(), e);
-                let parent = super::file_dialog::parent_window(&self.widget);
-                super::file_dialog::show_error_dialog(
-                    "Error Saving File",
-                    &format!("Could not save '{}'.\n\n{}", path.display(), e),
-                    parent.as_ref(),
-                );
+            match fs::write(&path, content.as_str()) {
+                Ok(_) => self.mark_unmodified(),
+                Err(e) => {
+                    error!("Error saving file {}: {}", path.display(), e);
+                    let parent = super::file_dialog::parent_window(&self.widget);
+                    super::file_dialog::show_error_dialog(
+                        "Error Saving File",
+                        &format!("Could not save '{}'.\n\n{}", path.display(), e),
+                        parent.as_ref(),
+                    );
+                }
             }
         } else {
             self.save_as_file();
@@ -698,6 +859,7 @@ impl GcodeEditor {
 
                         match fs::write(&path, content.as_str()) {
                             Ok(_) => {
+                                buffer.set_modified(false);
                                 *current_file.borrow_mut() = Some(path);
                             }
                             Err(e) => {
@@ -0,0 +1,120 @@
+//! # G-code Line Explanations
+//!
+//! Wording for the explanations from [`gcodekit5_visualizer::explain_line`], for the
+//! tooltip on the editor's current line.
+
+use crate::t;
+use gcodekit5_visualizer::{explain_line, KnownCode, WordMeaning};
+
+/// Tooltip text explaining `line`, one word per row; `None` for a blank line
+pub fn tooltip_text(line: &str) -> Option<String> {
+    let explained = explain_line(line);
+    if explained.is_empty() {
+        return None;
+    }
+
+    let mut rows = Vec::new();
+    if explained.block_delete {
+        rows.push(format!("/: {}", t!("skipped while block delete is on")));
+    }
+    for word in &explained.words {
+        rows.push(format!("{}: {}", word.text, describe(&word.meaning)));
+    }
+    if let Some(comment) = explained.comment {
+        rows.push(format!("{}: {}", t!("Comment"), comment));
+    }
+    Some(rows.join("\n"))
+}
+
+/// What a word means, in the user's language
+pub fn describe(meaning: &WordMeaning) -> String {
+    match meaning {
+        WordMeaning::Code(code) => describe_code(code),
+        WordMeaning::Axis { axis, value } => {
+            format!("{} {} {} {}", t!("move"), axis, t!("to"), value)
+        }
+        WordMeaning::ArcCenterOffset { axis, value } => {
+            format!("{} {} {}", t!("arc centre offset"), axis, value)
+        }
+        WordMeaning::Radius(value) => format!("{} {}", t!("arc radius"), value),
+        WordMeaning::FeedRate(value) => {
+            format!("{} {} {}", t!("feed"), value, t!("units/min"))
+        }
+        WordMeaning::SpindleSpeed(value) => {
+            format!("{} {} {}", t!("spindle speed"), value, t!("RPM"))
+        }
+        WordMeaning::Tool(value) => format!("{} {}", t!("select tool"), value),
+        WordMeaning::LineNumber(value) => format!("{} {}", t!("line number"), value),
+        WordMeaning::Parameter { letter, value } => {
+            format!("{} {} = {}", t!("parameter"), letter, value)
+        }
+        WordMeaning::Unknown => t!("unknown"),
+    }
+}
+
+fn describe_code(code: &KnownCode) -> String {
+    match code {
+        KnownCode::RapidMove => t!("rapid move"),
+        KnownCode::LinearMove => t!("linear move"),
+        KnownCode::ArcClockwise => t!("clockwise arc"),
+        KnownCode::ArcCounterClockwise => t!("counter-clockwise arc"),
+        KnownCode::Dwell(Some(seconds)) => {
+            format!("{} {} {}", t!("dwell for"), seconds, t!("seconds"))
+        }
+        KnownCode::Dwell(None) => t!("dwell"),
+        KnownCode::SetCoordinateData => t!("set coordinate system data"),
+        KnownCode::PlaneXy => t!("select XY plane"),
+        KnownCode::PlaneZx => t!("select ZX plane"),
+        KnownCode::PlaneYz => t!("select YZ plane"),
+        KnownCode::Inches => t!("units in inches"),
+        KnownCode::Millimeters => t!("units in millimeters"),
+        KnownCode::GoHome => t!("go to home position"),
+        KnownCode::GoSecondaryHome => t!("go to secondary home position"),
+        KnownCode::Probe {
+            toward,
+            error_if_no_contact,
+        } => match (toward, error_if_no_contact) {
+            (true, true) => t!("probe toward workpiece, error if no contact"),
+            (true, false) => t!("probe toward workpiece"),
+            (false, true) => t!("probe away from workpiece, error if no loss of contact"),
+            (false, false) => t!("probe away from workpiece"),
+        },
+        KnownCode::CutterCompensationOff => t!("cutter compensation off"),
+        KnownCode::ToolLengthOffset => t!("dynamic tool length offset"),
+        KnownCode::ToolLengthOffsetCancel => t!("cancel tool length offset"),
+        KnownCode::MachineCoordinates => t!("move in machine coordinates"),
+        KnownCode::WorkCoordinateSystem(n) => {
+            format!("{} {}", t!("select work coordinate system"), n)
+        }
+        KnownCode::CannedCycleCancel => t!("cancel canned cycle"),
+        KnownCode::DrillCycle => t!("drilling cycle"),
+        KnownCode::PeckDrillCycle => t!("peck drilling cycle"),
+        KnownCode::AbsoluteDistance => t!("absolute distance mode"),
+        KnownCode::IncrementalDistance => t!("incremental distance mode"),
+        KnownCode::SetWorkOffset => t!("set work offset at current position"),
+        KnownCode::InverseTimeFeed => t!("inverse time feed mode"),
+        KnownCode::UnitsPerMinuteFeed => t!("units per minute feed mode"),
+        KnownCode::UnitsPerRevolutionFeed => t!("units per revolution feed mode"),
+        KnownCode::ProgramPause => t!("program pause"),
+        KnownCode::OptionalStop => t!("optional stop"),
+        KnownCode::ProgramEnd => t!("program end"),
+        KnownCode::SpindleClockwise(speed) => with_speed(t!("spindle clockwise"), *speed),
+        KnownCode::SpindleCounterClockwise(speed) => {
+            with_speed(t!("spindle counter-clockwise"), *speed)
+        }
+        KnownCode::SpindleStop => t!("spindle stop"),
+        KnownCode::ToolChange(Some(tool)) => format!("{} {}", t!("change to tool"), tool),
+        KnownCode::ToolChange(None) => t!("tool change"),
+        KnownCode::MistCoolantOn => t!("mist coolant on"),
+        KnownCode::FloodCoolantOn => t!("flood coolant on"),
+        KnownCode::CoolantOff => t!("coolant off"),
+        KnownCode::ProgramEndAndRewind => t!("program end and rewind"),
+    }
+}
+
+fn with_speed(text: String, speed: Option<f64>) -> String {
+    match speed {
+        Some(speed) => format!("{} {} {} {}", text, t!("at"), speed, t!("RPM")),
+        None => text,
+    }
+}
@@ -0,0 +1,88 @@
+//! Lines sent outside the job stream
+//!
+//! Console commands, jogs, the machine and zero buttons, go-to moves, the
+//! end-of-job park and the power steps around a pause all wait in one queue,
+//! which the poll loop sends from as the connection's command throttle allows.
+//! A job line goes only once the queue is empty. Real-time bytes that must
+//! follow queued lines, such as the cycle start after the power comes back on,
+//! wait their turn but are not throttled.
+//!
+//! Queued lines are tracked without a line number, so their responses do not
+//! move the stream on. A line that fails to send drops the lines queued after
+//! it, so a traverse never follows a retract that did not go out. A jog cancel
+//! drops the jogs still queued, and a reset drops the whole queue.
+
+use super::*;
+
+use gcodekit5_core::{CommandTracker, GcodeCommand};
+
+/// A line or real-time byte waiting to be sent
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueuedCommand {
+    /// A command line
+    Line(String),
+    /// A real-time byte, with what the console shows for it
+    Realtime(u8, String),
+}
+
+/// Queue command lines to be sent in order
+pub(super) fn queue_lines(
+    queue: &ThreadSafeDeque<QueuedCommand>,
+    lines: impl IntoIterator<Item = String>,
+) {
+    queue
+        .lock()
+        .extend(lines.into_iter().map(QueuedCommand::Line));
+}
+
+/// Queue a real-time byte to follow the lines already queued
+pub(super) fn queue_realtime(queue: &ThreadSafeDeque<QueuedCommand>, byte: u8, label: String) {
+    queue.lock().push_back(QueuedCommand::Realtime(byte, label));
+}
+
+/// Drop the jog lines still queued, before a jog cancel
+pub(super) fn drop_queued_jogs(queue: &ThreadSafeDeque<QueuedCommand>) {
+    queue
+        .lock()
+        .retain(|command| !matches!(command, QueuedCommand::Line(line) if line.starts_with("$J=")));
+}
+
+/// Send from the front of the queue what the command throttle allows.
+/// Returns whether the queue is empty afterwards
+pub(super) fn send_queued(
+    comm: &mut SerialCommunicator,
+    queue: &ThreadSafeDeque<QueuedCommand>,
+    tracker: &ThreadSafe<CommandTracker>,
+    console: Option<&Rc<DeviceConsoleView>>,
+) -> bool {
+    let mut queue = queue.lock();
+    loop {
+        let throttled = !comm.command_wait_time().is_zero();
+        match queue.front() {
+            None => break,
+            Some(QueuedCommand::Line(_)) if throttled => break,
+            Some(_) => {}
+        }
+        match queue.pop_front() {
+            Some(QueuedCommand::Line(line)) => {
+                if let Some(c) = console {
+                    c.append_log(&format!("> {}\n", line));
+                }
+                if let Err(e) = comm.send_command(&line) {
+                    tracing::warn!("Failed to send '{}': {}", line, e);
+                    queue.clear();
+                    break;
+                }
+                tracker.lock().track_sent(GcodeCommand::new(line.as_str()));
+            }
+            Some(QueuedCommand::Realtime(byte, label)) => {
+                if let Some(c) = console {
+                    c.append_log(&format!("> {}\n", label));
+                }
+                let _ = comm.send(&[byte]);
+            }
+            None => break,
+        }
+    }
+    queue.is_empty()
+}
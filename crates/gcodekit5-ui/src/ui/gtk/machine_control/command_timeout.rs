@@ -0,0 +1,77 @@
+//! Response timeouts for streamed lines
+//!
+//! Every job line sent is tracked by a [`CommandTracker`] until its `ok` or
+//! `error` arrives; only a tracked job line's response moves the stream on. A
+//! line that gets no response within its window is resent as often as the
+//! timeout policy allows, after which the job is held with the line back at the
+//! front of the queue: Resume sends it again, Stop abandons the job. While the
+//! machine is held the window is restarted, since responses wait for the resume,
+//! and likewise while it runs or moves, since a line queued behind a long slow
+//! move is answered only once the planner has room for it.
+
+use super::*;
+
+use gcodekit5_core::{CommandTracker, GcodeCommand};
+
+/// Track a job line just sent, `index` being its position in the job
+pub(super) fn track_job_line(tracker: &ThreadSafe<CommandTracker>, line: &str, index: usize) {
+    let mut command = GcodeCommand::new(line);
+    command.set_line_number(u32::try_from(index).unwrap_or(u32::MAX));
+    tracker.lock().track_sent(command);
+}
+
+/// Whether a tracked command is a job line, not another line sent alongside the job
+pub(super) fn is_job_line(command: &GcodeCommand) -> bool {
+    command.line_number.is_some()
+}
+
+/// Resend or give up on lines that got no response in time; `delayed` says the
+/// machine is held or busy, so a response may be late without being lost
+#[allow(clippy::too_many_arguments)]
+pub(super) fn check_command_timeouts(
+    comm: &mut SerialCommunicator,
+    tracker: &ThreadSafe<CommandTracker>,
+    delayed: bool,
+    is_streaming: &ThreadSafe<bool>,
+    send_queue: &ThreadSafeDeque<String>,
+    waiting_for_ack: &ThreadSafe<bool>,
+    is_paused: &ThreadSafe<bool>,
+    console: Option<&Rc<DeviceConsoleView>>,
+) {
+    let mut tracker = tracker.lock();
+    if delayed {
+        tracker.restart_timeout_now();
+        return;
+    }
+    for command in tracker.check_timeouts_now() {
+        if tracker.resend(command.clone()) {
+            tracing::warn!("No response to '{}', resending", command.line);
+            if let Some(c) = console {
+                c.append_log(&format!(
+                    "{} {}\n> {}\n",
+                    t!("No response, resending:"),
+                    command.line,
+                    command.line
+                ));
+            }
+            let _ = comm.send_command(&command.line);
+            continue;
+        }
+
+        tracing::warn!("No response to '{}'", command.line);
+        if let Some(c) = console {
+            c.append_log(&format!("{} {}\n", t!("No response:"), command.line));
+        }
+        if is_job_line(&command) && *is_streaming.lock() {
+            send_queue.lock().push_front(command.line.clone());
+            *waiting_for_ack.lock() = false;
+            *is_paused.lock() = true;
+            if let Some(c) = console {
+                c.append_log(&format!(
+                    "{}\n",
+                    t!("Job held: Resume sends the line again, Stop abandons the job.")
+                ));
+            }
+        }
+    }
+}
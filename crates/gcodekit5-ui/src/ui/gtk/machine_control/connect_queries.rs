@@ -0,0 +1,62 @@
+//! Queries sent after connecting
+//!
+//! The soft reset sent on connecting brings up the startup banner; the
+//! controller is then asked for its build info, settings and stored offsets,
+//! and sent the user's startup block one line at a time. Every line waits for
+//! the connection's command throttle, which also covers the settle time after
+//! the reset, and each query leaves the controller time to answer before the
+//! next line goes.
+
+use super::*;
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Queries with the time the controller is given to answer each one, in ms.
+/// `$10=47` makes the status reports include overrides and feed/speed
+/// (WPos | Buf | Ln | FS | Ov).
+const QUERIES: [(&str, u64); 4] = [("$I", 550), ("$$", 0), ("$10=47", 400), ("$#", 300)];
+
+/// Send the connect queries, then `startup` lines, as the connection allows
+pub(super) fn send_connect_queries(
+    communicator: &ThreadSafe<SerialCommunicator>,
+    startup: Vec<String>,
+    console: Option<Rc<DeviceConsoleView>>,
+) {
+    let mut pending: VecDeque<(String, Duration)> = QUERIES
+        .iter()
+        .map(|(line, answer_ms)| (line.to_string(), Duration::from_millis(*answer_ms)))
+        .chain(startup.into_iter().map(|line| (line, Duration::ZERO)))
+        .collect();
+    let communicator = communicator.clone();
+    let mut next_at = Instant::now();
+    glib::timeout_add_local(Duration::from_millis(50), move || {
+        if Instant::now() < next_at {
+            return glib::ControlFlow::Continue;
+        }
+        let Some(mut comm) = communicator.try_lock() else {
+            return glib::ControlFlow::Continue;
+        };
+        if !comm.is_connected() {
+            return glib::ControlFlow::Break;
+        }
+        if !comm.command_wait_time().is_zero() {
+            return glib::ControlFlow::Continue;
+        }
+        let Some((line, answer_time)) = pending.pop_front() else {
+            return glib::ControlFlow::Break;
+        };
+        if let Some(c) = console.as_ref() {
+            c.append_log(&format!("> {}\n", line));
+        }
+        if let Err(e) = comm.send_command(&line) {
+            tracing::warn!("Failed to send '{}' after connecting: {}", line, e);
+        }
+        next_at = Instant::now() + answer_time;
+        if pending.is_empty() {
+            glib::ControlFlow::Break
+        } else {
+            glib::ControlFlow::Continue
+        }
+    });
+}
@@ -0,0 +1,37 @@
+//! Retract and park once a job completes
+//!
+//! The active device profile's [`EndOfJob`](gcodekit5_devicedb::EndOfJob)
+//! action runs only when the completion detector reports a clean finish; a stop
+//! or e-stop resets the detector, so nothing moves after an abort. The
+//! operator can skip it for the current session with the Park at Job End
+//! checkbox. A line that fails to send drops the park moves after it.
+
+use super::*;
+
+/// Queue the end-of-job lines for the active profile, if any
+pub(super) fn run_end_of_job(
+    command_queue: &ThreadSafeDeque<QueuedCommand>,
+    device_manager: Option<&Arc<DeviceManager>>,
+    console: Option<&Rc<DeviceConsoleView>>,
+) {
+    let Some(profile) = device_manager.and_then(|manager| manager.get_active_profile()) else {
+        return;
+    };
+    let status = device_status::get_status();
+    let work_offset = match (&status.machine_position, &status.work_position) {
+        (Some(m), Some(w)) => Some([m.x - w.x, m.y - w.y, m.z - w.z]),
+        _ => status
+            .work_coordinate_offset
+            .as_ref()
+            .map(|o| [o.x, o.y, o.z]),
+    };
+
+    let plan = profile.end_of_job_commands(work_offset);
+    for warning in &plan.warnings {
+        tracing::warn!("End of job: {}", warning);
+        if let Some(c) = console {
+            c.append_log(&format!("{}: {}\n", t!("End of job"), warning));
+        }
+    }
+    queue_lines(command_queue, plan.commands);
+}
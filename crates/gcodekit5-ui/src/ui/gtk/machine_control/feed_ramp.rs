@@ -0,0 +1,129 @@
+//! Feed override ramp when a job resumes from a pause
+//!
+//! With a resume ramp configured, resuming drops the feed override before the
+//! cycle start and steps it back to where it was when the job was paused, as
+//! [`FeedRamp`] plans it for the connected firmware. The ramp gives way as soon
+//! as the operator changes the override by hand.
+
+use super::*;
+
+use gcodekit5_communication::firmware::firmware_version::FirmwareType;
+use gcodekit5_communication::firmware::{FeedRamp, RampLength};
+use std::time::{Duration, Instant};
+
+/// How often a running ramp is stepped
+const RAMP_TICK: Duration = Duration::from_millis(100);
+
+/// A running ramp, keyed by when it started so a stale timer can tell it apart
+pub type RunningRamp = Option<(Instant, FeedRamp)>;
+
+/// Remember the feed override to return to for a pause just sent
+pub(super) fn note_pause(
+    ramp: &ThreadSafe<RunningRamp>,
+    restore: &ThreadSafe<Option<u16>>,
+    overrides: &ThreadSafe<OverrideState>,
+) {
+    // A ramp still running returns to its own target, not the lowered override
+    let feed = match ramp.lock().take() {
+        Some((_, running)) => running.restore(),
+        None => overrides.lock().feed,
+    };
+    *restore.lock() = Some(feed);
+}
+
+/// Drop the feed override for a resume about to be sent, and ramp it back up
+/// once the cycle runs
+pub(super) fn start_resume_ramp(
+    communicator: &ThreadSafe<SerialCommunicator>,
+    settings: Option<&Rc<SettingsController>>,
+    overrides: &ThreadSafe<OverrideState>,
+    ramp: &ThreadSafe<RunningRamp>,
+    restore: &ThreadSafe<Option<u16>>,
+    console: Option<&Rc<DeviceConsoleView>>,
+) {
+    let (Some(restore), Some(controller)) = (restore.lock().take(), settings) else {
+        return;
+    };
+    let (percent, length) = {
+        let persistence = controller.persistence.borrow();
+        let machine = &persistence.config().machine;
+        let length = if machine.resume_ramp_distance > 0.0 {
+            RampLength::Distance(machine.resume_ramp_distance)
+        } else {
+            RampLength::Time(Duration::from_millis(machine.resume_ramp_ms))
+        };
+        (machine.resume_ramp_percent, length)
+    };
+    let status = device_status::get_status();
+    let firmware = FirmwareType::from_string(status.firmware_type.as_deref().unwrap_or(""));
+    let current = overrides.lock().feed;
+    let Some(mut planned) = FeedRamp::plan(firmware, current, restore, percent, length) else {
+        if percent != 0 {
+            tracing::debug!(
+                "No resume feed ramp ({:?}, override {}%, ramp from {}%)",
+                firmware,
+                restore,
+                percent
+            );
+        }
+        return;
+    };
+
+    if let Some(c) = console {
+        c.append_log(&format!(
+            "> {} ({}% -> {}%)\n",
+            t!("Resume Feed Ramp"),
+            percent,
+            restore
+        ));
+    }
+    send_bytes(communicator, &planned.start_bytes());
+    let started = Instant::now();
+    *ramp.lock() = Some((started, planned));
+
+    let communicator = communicator.clone();
+    let overrides = overrides.clone();
+    let ramp = ramp.clone();
+    let console = console.cloned();
+    let mut last_position = status.machine_position;
+    let mut travelled = 0.0;
+    glib::timeout_add_local(RAMP_TICK, move || {
+        let mut guard = ramp.lock();
+        let running = match guard.as_mut() {
+            Some((at, running)) if *at == started => running,
+            // Paused, stopped or replaced by a newer ramp
+            _ => return glib::ControlFlow::Break,
+        };
+        let position = device_status::get_status().machine_position;
+        if let (Some(from), Some(to)) = (last_position, position) {
+            travelled +=
+                ((to.x - from.x).powi(2) + (to.y - from.y).powi(2) + (to.z - from.z).powi(2))
+                    .sqrt();
+        }
+        last_position = position.or(last_position);
+
+        let reported = overrides.lock().feed;
+        let bytes = running.advance(started.elapsed(), travelled, Some(reported));
+        send_bytes(&communicator, &bytes);
+        if running.is_active() {
+            return glib::ControlFlow::Continue;
+        }
+        if running.was_interrupted() {
+            tracing::info!("Resume feed ramp stopped: feed override changed by hand");
+            if let Some(c) = console.as_ref() {
+                c.append_log(&format!("{}\n", t!("Resume feed ramp stopped")));
+            }
+        }
+        *guard = None;
+        glib::ControlFlow::Break
+    });
+}
+
+fn send_bytes(communicator: &ThreadSafe<SerialCommunicator>, bytes: &[u8]) {
+    if bytes.is_empty() {
+        return;
+    }
+    if let Err(e) = communicator.lock().send(bytes) {
+        tracing::warn!("Failed to send resume feed ramp override: {}", e);
+    }
+}
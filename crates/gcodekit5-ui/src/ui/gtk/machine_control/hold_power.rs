@@ -0,0 +1,66 @@
+//! Spindle and laser power around an operator pause
+//!
+//! With the power-off-on-pause setting on, pausing a job also turns the
+//! spindle or laser off and resuming turns it back on at the speed the job was
+//! running, as [`hold_power_plan`] decides for the connected firmware. Program
+//! stops (`M0`/`M1`) are left to the program. The steps go through the
+//! [command queue](super::command_queue), ahead of any further job line.
+
+use super::*;
+
+use gcodekit5_communication::firmware::firmware_version::FirmwareType;
+use gcodekit5_communication::firmware::hold_power_plan;
+use gcodekit5_core::HoldPowerOff;
+
+/// Queue the power-off steps for a pause just sent, and keep what resuming needs
+pub(super) fn power_off_for_pause(
+    command_queue: &ThreadSafeDeque<QueuedCommand>,
+    settings: Option<&Rc<SettingsController>>,
+    spindle_power: &ThreadSafe<SpindlePower>,
+    resume_steps: &ThreadSafe<Vec<HoldPowerStep>>,
+) {
+    let off = settings.map_or(HoldPowerOff::Disabled, |controller| {
+        controller
+            .persistence
+            .borrow()
+            .config()
+            .machine
+            .hold_power_off
+    });
+    let status = device_status::get_status();
+    let firmware = FirmwareType::from_string(status.firmware_type.as_deref().unwrap_or(""));
+    let laser_mode = device_status::get_grbl_setting_numeric(32).unwrap_or(0.0) > 0.5;
+
+    let plan = hold_power_plan(firmware, laser_mode, off, &spindle_power.lock());
+    if off != HoldPowerOff::Disabled && plan.is_empty() {
+        tracing::debug!(
+            "Not turning power off for pause ({:?}, laser mode {})",
+            firmware,
+            laser_mode
+        );
+    }
+    queue_steps(command_queue, &plan.on_hold);
+    *resume_steps.lock() = plan.on_resume;
+}
+
+/// Queue the steps that restore the power before a resume
+pub(super) fn restore_power_for_resume(
+    command_queue: &ThreadSafeDeque<QueuedCommand>,
+    resume_steps: &ThreadSafe<Vec<HoldPowerStep>>,
+) {
+    let steps = std::mem::take(&mut *resume_steps.lock());
+    queue_steps(command_queue, &steps);
+}
+
+fn queue_steps(command_queue: &ThreadSafeDeque<QueuedCommand>, steps: &[HoldPowerStep]) {
+    for step in steps {
+        match step {
+            HoldPowerStep::Realtime(byte) => queue_realtime(
+                command_queue,
+                *byte,
+                format!("0x{:02X} ({})", byte, t!("Spindle Stop")),
+            ),
+            HoldPowerStep::Command(command) => queue_lines(command_queue, [command.clone()]),
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Near-limit warnings on the DRO
+//!
+//! Each status report's machine position is compared against the soft limits
+//! of the active device profile. An axis within the configured margin of a
+//! limit is marked `limit-near`, one on or beyond it `limit-at`; the mark goes
+//! away once the axis moves back into range.
+
+use super::*;
+
+use gcodekit5_communication::firmware::grbl::status_parser::MachinePosition;
+use gcodekit5_devicedb::LimitProximity;
+
+/// Mark each axis's DRO and machine position labels with how close `mpos` is
+/// to its limits
+pub(super) fn show_limit_proximity(
+    mpos: &MachinePosition,
+    device_manager: Option<&Arc<DeviceManager>>,
+    settings: Option<&Rc<SettingsController>>,
+    labels: [(&Label, &Label); 3],
+) {
+    let margin = settings.map_or(5.0, |controller| {
+        controller
+            .persistence
+            .borrow()
+            .config()
+            .machine
+            .limit_warning_margin
+    });
+    let proximity = device_manager
+        .and_then(|manager| manager.get_active_profile())
+        .map(|profile| profile.limit_proximity([mpos.x, mpos.y, mpos.z], margin))
+        .unwrap_or_default();
+
+    for ((axis, (dro, world)), state) in ["X", "Y", "Z"].iter().zip(labels).zip(proximity) {
+        let tooltip = match state {
+            LimitProximity::Clear => None,
+            LimitProximity::Near => Some(format!("{} {}", axis, t!("is near its soft limit"))),
+            LimitProximity::AtLimit => Some(format!("{} {}", axis, t!("is at its soft limit"))),
+        };
+        for label in [dro, world] {
+            label.remove_css_class("limit-near");
+            label.remove_css_class("limit-at");
+            match state {
+                LimitProximity::Clear => {}
+                LimitProximity::Near => label.add_css_class("limit-near"),
+                LimitProximity::AtLimit => label.add_css_class("limit-at"),
+            }
+            label.set_tooltip_text(tooltip.as_deref());
+        }
+    }
+}
@@ -4,17 +4,25 @@
 //! homing, work coordinate setting, spindle/laser control,
 //! and job execution (start/pause/stop).
 
+use gcodekit5_camtools::{
+    has_failures, preflight, JobTimer, JobTimingLog, PreflightConfig, PreflightWarning,
+    StatsCalculator,
+};
 use gcodekit5_communication::firmware::grbl::status_parser::{
-    FeedSpindleState, OverrideState, StatusParser,
+    FeedSpindleState, MachinePosition, OverrideState, StatusParser,
 };
+use gcodekit5_communication::firmware::HoldPowerStep;
 use gcodekit5_communication::{
-    Communicator, ConnectionDriver, ConnectionParams, SerialCommunicator,
+    Communicator, ConnectionDriver, ConnectionParams, JobCompletionDetector, SerialCommunicator,
+    TextDecoder,
 };
 use gcodekit5_core::units::{
-    format_feed_rate, format_length, get_unit_label, parse_feed_rate, FeedRateUnits,
+    format_feed_rate, format_length, get_unit_label, parse_feed_rate_expr, FeedRateUnits,
     MeasurementSystem,
 };
+use gcodekit5_devicedb::DeviceManager;
 use gcodekit5_settings::controller::SettingsController;
+use gcodekit5_settings::{default_jog_keys, ConnectionSettings, JogKeyBinding};
 use gtk4::glib;
 use gtk4::prelude::*;
 use gtk4::{
@@ -22,7 +30,8 @@ use gtk4::{
     CheckButton, ComboBoxText, EventControllerKey, Grid, Image, Label, Orientation, Overlay, Paned,
     PolicyType, ScrolledWindow, SizeGroup, SizeGroupMode, ToggleButton,
 };
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 
 use crate::device_status;
 use crate::t;
@@ -32,9 +41,11 @@ use crate::ui::gtk::help_browser;
 use crate::ui::gtk::status_bar::StatusBar;
 use crate::ui::gtk::visualizer::GcodeVisualizer;
 use gcodekit5_core::{
-    thread_safe, thread_safe_deque, thread_safe_none, ThreadSafe, ThreadSafeDeque, ThreadSafeOption,
+    safe_goto_commands, thread_safe, thread_safe_deque, thread_safe_none, CommandTracker,
+    SpindlePower, ThreadSafe, ThreadSafeDeque, ThreadSafeOption,
 };
 use std::rc::Rc;
+use std::sync::Arc;
 
 fn set_button_icon_label(btn: &Button, icon: &str, label: &str) {
     let content = Box::new(Orientation::Horizontal, 6);
@@ -78,6 +89,63 @@ fn make_icon_label_toggle(icon: &str, label: &str) -> ToggleButton {
     btn
 }
 
+/// Feed override range (percent) used when projecting the remaining job time.
+const ETA_FEED_OVERRIDE_MIN: f64 = 10.0;
+const ETA_FEED_OVERRIDE_MAX: f64 = 200.0;
+
+/// Feed override as a clamped speed factor (1.0 = 100%).
+fn eta_feed_factor(feed_override: u16) -> f64 {
+    (feed_override as f64).clamp(ETA_FEED_OVERRIDE_MIN, ETA_FEED_OVERRIDE_MAX) / 100.0
+}
+
+/// File in the config directory holding the local job timing log.
+const JOB_TIMING_FILE: &str = "job_timing.json";
+
+/// Record a completed job's actual time in the local job timing log.
+fn record_job_timing(timer: JobTimer) {
+    let record = timer.complete();
+    let path = match gcodekit5_settings::SettingsManager::config_directory() {
+        Ok(dir) => dir.join(JOB_TIMING_FILE),
+        Err(e) => {
+            tracing::warn!("Job timing not recorded: {}", e);
+            return;
+        }
+    };
+    let mut log = JobTimingLog::load(&path).unwrap_or_else(|e| {
+        tracing::warn!("Starting a new job timing log: {}", e);
+        JobTimingLog::new()
+    });
+    tracing::info!(
+        "Job on '{}' took {:.0}s (estimated {:.0}s, {:.1} lines/s)",
+        record.machine,
+        record.actual_secs,
+        record.estimated_secs,
+        record.lines_per_sec()
+    );
+    log.record(record);
+    if let Err(e) = log.save(&path) {
+        tracing::warn!("Failed to save job timing log: {}", e);
+    }
+}
+
+/// Estimate the remaining job time in seconds.
+///
+/// `nominal_elapsed` is the elapsed streaming time normalised to 100% feed (each interval
+/// weighted by the override active during it), so the projection can be rescaled by the
+/// current override without double-counting earlier override changes.
+fn estimate_remaining_secs(
+    nominal_elapsed: f64,
+    sent: usize,
+    remaining: usize,
+    feed_override: u16,
+) -> f64 {
+    if sent == 0 || nominal_elapsed <= 0.0 {
+        return 0.0;
+    }
+    let nominal_per_line = nominal_elapsed / sent as f64;
+    remaining as f64 * nominal_per_line / eta_feed_factor(feed_override)
+}
+
 #[derive(Clone)]
 pub struct MachineControlView {
     pub widget: Paned,
@@ -111,6 +179,10 @@ pub struct MachineControlView {
     pub feed_reset: Button,
     pub feed_inc1: Button,
     pub feed_inc10: Button,
+    pub rapid_value: Label,
+    pub rapid_25: Button,
+    pub rapid_50: Button,
+    pub rapid_100: Button,
     pub spindle_value: Label,
     pub spindle_dec10: Button,
     pub spindle_dec1: Button,
@@ -128,6 +200,7 @@ pub struct MachineControlView {
     pub zero_all_btn: Button,
     pub goto_zero_btn: Button,
     pub goto_zero_include_z: CheckButton,
+    pub park_at_job_end: CheckButton,
     pub world_x: Label,
     pub world_y: Label,
     pub world_z: Label,
@@ -150,6 +223,8 @@ pub struct MachineControlView {
     pub device_console: Option<Rc<DeviceConsoleView>>,
     pub editor: Option<Rc<GcodeEditor>>,
     pub visualizer: Option<Rc<GcodeVisualizer>>,
+    pub device_manager: Option<Arc<DeviceManager>>,
+    pub settings_controller: Option<Rc<SettingsController>>,
     pub send_queue: ThreadSafeDeque<String>,
     pub total_lines: ThreadSafe<usize>,
     pub is_streaming: ThreadSafe<bool>,
@@ -158,6 +233,25 @@ pub struct MachineControlView {
     pub current_units: ThreadSafe<MeasurementSystem>,
     pub last_overrides: ThreadSafe<OverrideState>,
     pub job_start_time: ThreadSafeOption<std::time::Instant>,
+    pub job_completion: ThreadSafe<JobCompletionDetector>,
+    /// Actual-time measurement of the running job, when job timing is enabled
+    pub job_timer: ThreadSafeOption<JobTimer>,
+    /// Program stop sent and not yet acknowledged
+    pub pending_stop: ThreadSafe<Option<PendingStop>>,
+    /// Operator message of each program stop in the running job, by line index
+    pub stop_messages: ThreadSafe<HashMap<usize, String>>,
+    /// Spindle state after the job lines sent so far
+    pub spindle_power: ThreadSafe<SpindlePower>,
+    /// Steps that restore the spindle or laser when resuming from a pause
+    pub hold_resume: ThreadSafe<Vec<HoldPowerStep>>,
+    /// Feed override to ramp back to when resuming from a pause
+    pub ramp_restore: ThreadSafe<Option<u16>>,
+    /// Resume feed ramp in progress
+    pub feed_ramp: ThreadSafe<RunningRamp>,
+    /// Lines sent and awaiting their ok or error
+    pub command_tracker: ThreadSafe<CommandTracker>,
+    /// Lines waiting to be sent outside the job stream
+    pub command_queue: ThreadSafeDeque<QueuedCommand>,
 }
 
 impl MachineControlView {
@@ -166,6 +260,7 @@ impl MachineControlView {
         device_console: Option<Rc<DeviceConsoleView>>,
         editor: Option<Rc<GcodeEditor>>,
         visualizer: Option<Rc<GcodeVisualizer>>,
+        device_manager: Option<Arc<DeviceManager>>,
         settings_controller: Option<Rc<SettingsController>>,
     ) -> Self {
         let widget = Paned::new(Orientation::Horizontal);
@@ -494,6 +589,34 @@ impl MachineControlView {
         feed_row.append(&feed_value);
         feed_row.append(&feed_controls);
 
+        // Rapid Override Row (GRBL only offers fixed levels)
+        let rapid_row = Box::new(Orientation::Horizontal, 8);
+
+        let rapid_label = Label::new(Some(&t!("Rapid:")));
+        rapid_label.add_css_class("dim-label");
+        rapid_label.set_width_request(50);
+
+        let rapid_value = Label::new(Some("100%"));
+        rapid_value.add_css_class("mc-override-value");
+        rapid_value.set_width_chars(20);
+
+        let rapid_controls = Box::new(Orientation::Horizontal, 2);
+        let rapid_25 = Button::with_label("25%");
+        rapid_25.set_tooltip_text(Some(&t!("Limit rapid moves to 25%")));
+        let rapid_50 = Button::with_label("50%");
+        rapid_50.set_tooltip_text(Some(&t!("Limit rapid moves to 50%")));
+        let rapid_100 = Button::with_label("100%");
+        rapid_100.set_tooltip_text(Some(&t!("Rapid moves at full speed")));
+        rapid_100.add_css_class("suggested-action");
+
+        rapid_controls.append(&rapid_25);
+        rapid_controls.append(&rapid_50);
+        rapid_controls.append(&rapid_100);
+
+        rapid_row.append(&rapid_label);
+        rapid_row.append(&rapid_value);
+        rapid_row.append(&rapid_controls);
+
         // Spindle Speed Row (compact)
         let spindle_row = Box::new(Orientation::Horizontal, 8);
 
@@ -532,6 +655,7 @@ impl MachineControlView {
         spindle_row.append(&spindle_controls);
 
         override_box.append(&feed_row);
+        override_box.append(&rapid_row);
         override_box.append(&spindle_row);
         override_section.append(&override_box);
         main_area.append(&override_section);
@@ -597,7 +721,7 @@ impl MachineControlView {
 
         let goto_zero_btn = make_icon_label_button("go-jump-symbolic", &t!("Go to Work Zero"));
         goto_zero_btn.set_tooltip_text(Some(&t!(
-            "Rapid move to work origin (X/Y, or X/Y/Z if Include Z is checked)"
+            "Retract to the safe height, then rapid to the work origin (X/Y, then Z if Include Z is checked)"
         )));
 
         // Checkbox for including Z axis in go to work zero
@@ -613,6 +737,13 @@ impl MachineControlView {
         goto_zero_row.append(&goto_zero_include_z);
         zero_actions.append(&goto_zero_row);
 
+        let park_at_job_end = CheckButton::with_label(&t!("Park at Job End"));
+        park_at_job_end.set_active(true);
+        park_at_job_end.set_tooltip_text(Some(&t!(
+            "Run the device profile's end-of-job retract and park when a job completes"
+        )));
+        zero_actions.append(&park_at_job_end);
+
         dro_container.append(&dro_box);
         dro_container.append(&zero_actions);
 
@@ -1111,6 +1242,10 @@ impl MachineControlView {
             feed_reset,
             feed_inc1,
             feed_inc10,
+            rapid_value,
+            rapid_25,
+            rapid_50,
+            rapid_100,
             spindle_value,
             spindle_dec10,
             spindle_dec1,
@@ -1128,6 +1263,7 @@ impl MachineControlView {
             zero_all_btn,
             goto_zero_btn,
             goto_zero_include_z,
+            park_at_job_end,
             world_x,
             world_y,
             world_z,
@@ -1150,6 +1286,8 @@ impl MachineControlView {
             device_console: device_console.clone(),
             editor,
             visualizer,
+            device_manager,
+            settings_controller,
             send_queue: thread_safe_deque(),
             total_lines: thread_safe(0),
             is_streaming: thread_safe(false),
@@ -1162,6 +1300,16 @@ impl MachineControlView {
                 spindle: 100,
             }),
             job_start_time: thread_safe_none(),
+            job_completion: thread_safe(JobCompletionDetector::default()),
+            job_timer: thread_safe_none(),
+            pending_stop: thread_safe(None),
+            stop_messages: thread_safe(HashMap::new()),
+            spindle_power: thread_safe(SpindlePower::default()),
+            hold_resume: thread_safe(Vec::new()),
+            ramp_restore: thread_safe(None),
+            feed_ramp: thread_safe(None),
+            command_tracker: thread_safe(CommandTracker::default()),
+            command_queue: thread_safe_deque(),
         };
 
         // Keep internal jog values in base units (mm, mm/min)
@@ -1181,8 +1329,17 @@ impl MachineControlView {
             let current_feed_units = view.current_feed_units.clone();
             view.jog_feed_entry.connect_changed(move |e| {
                 let units = *current_feed_units.lock();
-                if let Ok(v) = parse_feed_rate(&e.text(), units) {
-                    *jog_feed_mm_per_min.lock() = v;
+                // Accepts expressions and unit suffixes, e.g. "1000/2" or "10 mm/s"
+                match parse_feed_rate_expr(&e.text(), units) {
+                    Ok(v) => {
+                        *jog_feed_mm_per_min.lock() = v;
+                        e.remove_css_class("error");
+                        e.set_tooltip_text(None);
+                    }
+                    Err(err) => {
+                        e.add_css_class("error");
+                        e.set_tooltip_text(Some(&err.to_string()));
+                    }
                 }
             });
         }
@@ -1216,87 +1373,90 @@ impl MachineControlView {
         );
 
         // Setup jog button handlers
+        /// Jog one or more axes together; the feed is lowered so no axis exceeds
+        /// its maximum jog rate from settings
         fn send_jog(
-            axis: char,
-            delta: f32,
-            communicator: &ThreadSafe<SerialCommunicator>,
+            deltas: &[(char, f32)],
+            command_queue: &ThreadSafeDeque<QueuedCommand>,
             feed_mm_per_min: f32,
-            console: &Option<Rc<DeviceConsoleView>>,
+            settings: &Option<Rc<SettingsController>>,
         ) {
-            let jog_cmd = format!("$J=G91 {axis}{delta} F{feed_mm_per_min}\n");
-            if let Some(c) = console {
-                c.append_log(&format!("> {}\n", jog_cmd.trim()));
-            }
-            {
-                let mut comm = communicator.lock();
-                let _ = comm.send(jog_cmd.as_bytes());
-            }
+            let deltas: Vec<(char, f64)> = deltas.iter().map(|(a, d)| (*a, *d as f64)).collect();
+            let machine = settings
+                .as_ref()
+                .map(|c| c.persistence.borrow().config().machine.clone());
+            let Some(jog_cmd) = gcodekit5_core::jog_command(&deltas, feed_mm_per_min as f64, |a| {
+                machine.as_ref().and_then(|m| m.jog_max_rate(a))
+            }) else {
+                return;
+            };
+            queue_lines(command_queue, [jog_cmd]);
         }
 
         {
-            let communicator = view.communicator.clone();
+            let command_queue = view.command_queue.clone();
             let jog_step_mm = view.jog_step_mm.clone();
             let jog_feed_mm_per_min = view.jog_feed_mm_per_min.clone();
-            let console = view.device_console.clone();
+            let settings = view.settings_controller.clone();
             view.jog_x_pos.connect_clicked(move |_| {
                 let step = *jog_step_mm.lock();
                 let feed = *jog_feed_mm_per_min.lock();
-                send_jog('X', step, &communicator, feed, &console);
+                send_jog(&[('X', step)], &command_queue, feed, &settings);
             });
         }
         {
-            let communicator = view.communicator.clone();
+            let command_queue = view.command_queue.clone();
             let jog_step_mm = view.jog_step_mm.clone();
             let jog_feed_mm_per_min = view.jog_feed_mm_per_min.clone();
-            let console = view.device_console.clone();
+            let settings = view.settings_controller.clone();
             view.jog_x_neg.connect_clicked(move |_| {
                 let step = *jog_step_mm.lock();
                 let feed = *jog_feed_mm_per_min.lock();
-                send_jog('X', -step, &communicator, feed, &console);
+                send_jog(&[('X', -step)], &command_queue, feed, &settings);
             });
         }
         {
-            let communicator = view.communicator.clone();
+            let command_queue = view.command_queue.clone();
             let jog_step_mm = view.jog_step_mm.clone();
             let jog_feed_mm_per_min = view.jog_feed_mm_per_min.clone();
-            let console = view.device_console.clone();
+            let settings = view.settings_controller.clone();
             view.jog_y_pos.connect_clicked(move |_| {
                 let step = *jog_step_mm.lock();
                 let feed = *jog_feed_mm_per_min.lock();
-                send_jog('Y', step, &communicator, feed, &console);
+                send_jog(&[('Y', step)], &command_queue, feed, &settings);
             });
         }
         {
-            let communicator = view.communicator.clone();
+            let command_queue = view.command_queue.clone();
             let jog_step_mm = view.jog_step_mm.clone();
             let jog_feed_mm_per_min = view.jog_feed_mm_per_min.clone();
-            let console = view.device_console.clone();
+            let settings = view.settings_controller.clone();
             view.jog_y_neg.connect_clicked(move |_| {
                 let step = *jog_step_mm.lock();
                 let feed = *jog_feed_mm_per_min.lock();
-                send_jog('Y', -step, &communicator, feed, &console);
+                send_jog(&[('Y', -step)], &command_queue, feed, &settings);
             });
         }
         {
-            let communicator = view.communicator.clone();
+            let command_queue = view.command_queue.clone();
             let jog_step_mm = view.jog_step_mm.clone();
             let jog_feed_mm_per_min = view.jog_feed_mm_per_min.clone();
-            let console = view.device_console.clone();
+            let settings = view.settings_controller.clone();
             view.jog_z_pos.connect_clicked(move |_| {
                 let step = *jog_step_mm.lock();
                 let feed = *jog_feed_mm_per_min.lock();
-                send_jog('Z', step, &communicator, feed, &console);
+                send_jog(&[('Z', step)], &command_queue, feed, &settings);
             });
         }
         {
-            let communicator = view.communicator.clone();
+            let command_queue = view.command_queue.clone();
             let jog_step_mm = view.jog_step_mm.clone();
             let jog_feed_mm_per_min = view.jog_feed_mm_per_min.clone();
-            let console = view.device_console.clone();
+            let settings = view.settings_controller.clone();
             view.jog_z_neg.connect_clicked(move |_| {
                 let step = *jog_step_mm.lock();
                 let feed = *jog_feed_mm_per_min.lock();
-                send_jog('Z', -step, &communicator, feed, &console);
+                send_jog(&[('Z', -step)], &command_queue, feed, &settings);
             });
         }
 
@@ -1304,6 +1464,7 @@ impl MachineControlView {
         {
             let controller = EventControllerKey::new();
             let communicator = view.communicator.clone();
+            let command_queue = view.command_queue.clone();
             let jog_step_mm = view.jog_step_mm.clone();
             let jog_feed_mm_per_min = view.jog_feed_mm_per_min.clone();
             let console_entry = view
@@ -1311,32 +1472,89 @@ impl MachineControlView {
                 .as_ref()
                 .map(|c| c.command_entry.clone());
             let console = view.device_console.clone();
+            let settings = view.settings_controller.clone();
+            // Continuous jog in progress, so auto-repeat is ignored and release cancels it
+            let held: Rc<RefCell<Option<JogKeyBinding>>> = Rc::default();
 
-            controller.connect_key_pressed(move |_, key, _, _| {
-                if let Some(entry) = console_entry.as_ref() {
-                    if entry.has_focus() {
-                        return glib::Propagation::Proceed;
+            {
+                let command_queue = command_queue.clone();
+                let held = held.clone();
+                controller.connect_key_pressed(move |_, key, _, _| {
+                    if let Some(entry) = console_entry.as_ref() {
+                        if entry.has_focus() {
+                            return glib::Propagation::Proceed;
+                        }
                     }
-                }
 
-                let Some(ch) = key.to_unicode() else {
-                    return glib::Propagation::Proceed;
-                };
+                    let (bindings, limits) = match settings.as_ref() {
+                        Some(controller) => {
+                            let persistence = controller.persistence.borrow();
+                            let machine = &persistence.config().machine;
+                            (
+                                machine.jog_keys.clone(),
+                                (machine.x_limit, machine.y_limit, machine.z_limit),
+                            )
+                        }
+                        None => (default_jog_keys(), (200.0, 200.0, 100.0)),
+                    };
+                    let name = key.name().map(|n| n.to_string()).unwrap_or_default();
+                    let Some(binding) =
+                        bindings.iter().find(|b| b.matches(&name, key.to_unicode()))
+                    else {
+                        return glib::Propagation::Proceed;
+                    };
 
-                let step = *jog_step_mm.lock();
-                let feed = *jog_feed_mm_per_min.lock();
+                    let feed = *jog_feed_mm_per_min.lock();
+                    let directions = binding.directions();
+                    if binding.continuous {
+                        if held.borrow().as_ref() == Some(binding) {
+                            return glib::Propagation::Stop;
+                        }
+                        // Jog across the whole travel; releasing the key cancels it.
+                        // Diagonals use the shorter travel so they stay at 45 degrees.
+                        let travel = directions
+                            .iter()
+                            .map(|(axis, _)| match axis {
+                                'X' => limits.0,
+                                'Y' => limits.1,
+                                'Z' => limits.2,
+                                _ => 360.0,
+                            })
+                            .fold(f64::INFINITY, f64::min);
+                        let deltas: Vec<(char, f32)> = directions
+                            .iter()
+                            .map(|(axis, sign)| (*axis, (sign * travel) as f32))
+                            .collect();
+                        *held.borrow_mut() = Some(binding.clone());
+                        send_jog(&deltas, &command_queue, feed, &settings);
+                    } else {
+                        let step = *jog_step_mm.lock();
+                        let deltas: Vec<(char, f32)> = directions
+                            .iter()
+                            .map(|(axis, sign)| (*axis, *sign as f32 * step))
+                            .collect();
+                        send_jog(&deltas, &command_queue, feed, &settings);
+                    }
 
-                match ch {
-                    '8' => send_jog('Y', step, &communicator, feed, &console),
-                    '2' => send_jog('Y', -step, &communicator, feed, &console),
-                    '4' => send_jog('X', -step, &communicator, feed, &console),
-                    '6' => send_jog('X', step, &communicator, feed, &console),
-                    '9' => send_jog('Z', step, &communicator, feed, &console),
-                    '3' => send_jog('Z', -step, &communicator, feed, &console),
-                    _ => return glib::Propagation::Proceed,
-                }
+                    glib::Propagation::Stop
+                });
+            }
 
-                glib::Propagation::Stop
+            controller.connect_key_released(move |_, key, _, _| {
+                let name = key.name().map(|n| n.to_string()).unwrap_or_default();
+                let released = held
+                    .borrow()
+                    .as_ref()
+                    .is_some_and(|binding| binding.matches(&name, key.to_unicode()));
+                if released {
+                    *held.borrow_mut() = None;
+                    drop_queued_jogs(&command_queue);
+                    if let Some(c) = console.as_ref() {
+                        c.append_log("> 0x85 (Jog Cancel)\n");
+                    }
+                    let mut comm = communicator.lock();
+                    let _ = comm.send(&[0x85]);
+                }
             });
 
             view.widget.add_controller(controller);
@@ -1351,6 +1569,13 @@ impl MachineControlView {
         {
             let communicator = view.communicator.clone();
             let is_paused = view.is_paused.clone();
+            let settings_controller = view.settings_controller.clone();
+            let spindle_power = view.spindle_power.clone();
+            let hold_resume = view.hold_resume.clone();
+            let last_overrides = view.last_overrides.clone();
+            let ramp_restore = view.ramp_restore.clone();
+            let feed_ramp = view.feed_ramp.clone();
+            let command_queue = view.command_queue.clone();
             let console = view.device_console.clone();
             view.pause_btn.connect_clicked(move |_| {
                 if let Some(c) = console.as_ref() {
@@ -1360,41 +1585,41 @@ impl MachineControlView {
                     let mut comm = communicator.lock();
                     let _ = comm.send(b"!");
                 }
+                hold_power::power_off_for_pause(
+                    &command_queue,
+                    settings_controller.as_ref(),
+                    &spindle_power,
+                    &hold_resume,
+                );
+                feed_ramp::note_pause(&feed_ramp, &ramp_restore, &last_overrides);
                 *is_paused.lock() = true;
             });
         }
         {
             let communicator = view.communicator.clone();
             let is_paused = view.is_paused.clone();
-            let is_streaming = view.is_streaming.clone();
-            let waiting_for_ack = view.waiting_for_ack.clone();
-            let send_queue = view.send_queue.clone();
+            let hold_resume = view.hold_resume.clone();
+            let settings_controller = view.settings_controller.clone();
+            let last_overrides = view.last_overrides.clone();
+            let ramp_restore = view.ramp_restore.clone();
+            let feed_ramp = view.feed_ramp.clone();
+            let command_queue = view.command_queue.clone();
             let console = view.device_console.clone();
 
             view.resume_btn.connect_clicked(move |_| {
-                if let Some(c) = console.as_ref() {
-                    c.append_log("> ~ (Resume)\n");
-                }
-                {
-                    let mut comm = communicator.lock();
-                    let _ = comm.send(b"~");
-                }
+                hold_power::restore_power_for_resume(&command_queue, &hold_resume);
+                feed_ramp::start_resume_ramp(
+                    &communicator,
+                    settings_controller.as_ref(),
+                    &last_overrides,
+                    &feed_ramp,
+                    &ramp_restore,
+                    console.as_ref(),
+                );
+                // The cycle start follows the power steps
+                queue_realtime(&command_queue, b'~', "~ (Resume)".to_string());
+                // A stalled stream picks up again from the poll loop
                 *is_paused.lock() = false;
-
-                // Kickstart if stalled (streaming, not waiting for ack, and has commands)
-                if *is_streaming.lock() && !*waiting_for_ack.lock() {
-                    let mut queue = send_queue.lock();
-                    if let Some(cmd) = queue.pop_front() {
-                        if let Some(c) = console.as_ref() {
-                            c.append_log(&format!("> {}\n", cmd));
-                        }
-                        {
-                            let mut comm = communicator.lock();
-                            let _ = comm.send_command(&cmd);
-                            *waiting_for_ack.lock() = true;
-                        }
-                    }
-                }
             });
         }
         {
@@ -1405,6 +1630,14 @@ impl MachineControlView {
             let send_queue = view.send_queue.clone();
             let status_bar = view.status_bar.clone();
             let job_start_time = view.job_start_time.clone();
+            let job_completion = view.job_completion.clone();
+            let job_timer = view.job_timer.clone();
+            let pending_stop = view.pending_stop.clone();
+            let hold_resume = view.hold_resume.clone();
+            let ramp_restore = view.ramp_restore.clone();
+            let feed_ramp = view.feed_ramp.clone();
+            let command_tracker = view.command_tracker.clone();
+            let command_queue = view.command_queue.clone();
             let console = view.device_console.clone();
             view.stop_btn.connect_clicked(move |_| {
                 if let Some(c) = console.as_ref() {
@@ -1418,7 +1651,17 @@ impl MachineControlView {
                 *is_streaming.lock() = false;
                 *is_paused.lock() = false;
                 *waiting_for_ack.lock() = false;
+                command_tracker.lock().clear();
+                command_queue.lock().clear();
+                *pending_stop.lock() = None;
+                hold_resume.lock().clear();
+                *ramp_restore.lock() = None;
+                *feed_ramp.lock() = None;
                 *job_start_time.lock() = None;
+                job_completion.lock().reset();
+                if let Some(timer) = job_timer.lock().take() {
+                    timer.abort();
+                }
                 send_queue.lock().clear();
 
                 // Reset progress
@@ -1459,123 +1702,82 @@ impl MachineControlView {
                     return;
                 }
 
-                view_clone.start_job(&content);
+                let warnings = view_clone.preflight_warnings(&content);
+                if warnings.is_empty() {
+                    view_clone.start_job(&content);
+                    return;
+                }
+                view_clone.show_preflight_dialog(&widget_for_dialog, warnings, content);
             });
         }
 
         // Machine State Controls
         {
-            let communicator = view.communicator.clone();
-            let console = view.device_console.clone();
+            let command_queue = view.command_queue.clone();
             view.home_btn.connect_clicked(move |_| {
-                if let Some(c) = console.as_ref() {
-                    c.append_log("> $H\n");
-                }
-                {
-                    let mut comm = communicator.lock();
-                    let _ = comm.send_command("$H");
-                }
+                queue_lines(&command_queue, ["$H".to_string()]);
             });
         }
         {
-            let communicator = view.communicator.clone();
-            let console = view.device_console.clone();
+            let command_queue = view.command_queue.clone();
             view.unlock_btn.connect_clicked(move |_| {
-                if let Some(c) = console.as_ref() {
-                    c.append_log("> $X\n");
-                }
-                {
-                    let mut comm = communicator.lock();
-                    let _ = comm.send_command("$X");
-                }
+                queue_lines(&command_queue, ["$X".to_string()]);
             });
         }
 
         // WCS Controls
         for (i, btn) in view.wcs_btns.iter().enumerate() {
-            let communicator = view.communicator.clone();
-            let console = view.device_console.clone();
+            let command_queue = view.command_queue.clone();
             let cmd = format!("G{}", 54 + i);
             btn.connect_toggled(move |b| {
                 if !b.is_active() {
                     return;
                 }
-                if let Some(c) = console.as_ref() {
-                    c.append_log(&format!("> {}\n", cmd));
-                }
-                {
-                    let mut comm = communicator.lock();
-                    let _ = comm.send_command(&cmd);
-                }
+                queue_lines(&command_queue, [cmd.clone()]);
             });
         }
 
         // Zero Controls
         {
-            let communicator = view.communicator.clone();
+            let command_queue = view.command_queue.clone();
             let wcs_btns = view.wcs_btns.clone();
-            let console = view.device_console.clone();
             view.x_zero_btn.connect_clicked(move |_| {
                 let p = wcs_btns
                     .iter()
                     .position(|b| b.is_active())
                     .map(|i| i + 1)
                     .unwrap_or(1);
-                let cmd = format!("G10 L20 P{p} X0");
-                if let Some(c) = console.as_ref() {
-                    c.append_log(&format!("> {}\n", cmd));
-                }
-                {
-                    let mut comm = communicator.lock();
-                    let _ = comm.send_command(&cmd);
-                }
+                queue_lines(&command_queue, [format!("G10 L20 P{p} X0")]);
             });
         }
         {
-            let communicator = view.communicator.clone();
+            let command_queue = view.command_queue.clone();
             let wcs_btns = view.wcs_btns.clone();
-            let console = view.device_console.clone();
             view.y_zero_btn.connect_clicked(move |_| {
                 let p = wcs_btns
                     .iter()
                     .position(|b| b.is_active())
                     .map(|i| i + 1)
                     .unwrap_or(1);
-                let cmd = format!("G10 L20 P{p} Y0");
-                if let Some(c) = console.as_ref() {
-                    c.append_log(&format!("> {}\n", cmd));
-                }
-                {
-                    let mut comm = communicator.lock();
-                    let _ = comm.send_command(&cmd);
-                }
+                queue_lines(&command_queue, [format!("G10 L20 P{p} Y0")]);
             });
         }
         {
-            let communicator = view.communicator.clone();
+            let command_queue = view.command_queue.clone();
             let wcs_btns = view.wcs_btns.clone();
-            let console = view.device_console.clone();
             view.z_zero_btn.connect_clicked(move |_| {
                 let p = wcs_btns
                     .iter()
                     .position(|b| b.is_active())
                     .map(|i| i + 1)
                     .unwrap_or(1);
-                let cmd = format!("G10 L20 P{p} Z0");
-                if let Some(c) = console.as_ref() {
-                    c.append_log(&format!("> {}\n", cmd));
-                }
-                {
-                    let mut comm = communicator.lock();
-                    let _ = comm.send_command(&cmd);
-                }
+                queue_lines(&command_queue, [format!("G10 L20 P{p} Z0")]);
             });
         }
         {
-            let communicator = view.communicator.clone();
+            let command_queue = view.command_queue.clone();
             let wcs_btns = view.wcs_btns.clone();
             let widget_for_dialog = view.widget.clone();
-            let console = view.device_console.clone();
             view.zero_all_btn.connect_clicked(move |_| {
                 let dialog = gtk4::MessageDialog::builder()
                     .message_type(gtk4::MessageType::Question)
@@ -1594,9 +1796,8 @@ impl MachineControlView {
                     }
                 }
 
-                let communicator = communicator.clone();
+                let command_queue = command_queue.clone();
                 let wcs_btns = wcs_btns.clone();
-                let console = console.clone();
                 dialog.connect_response(move |d, resp| {
                     if resp == gtk4::ResponseType::Yes {
                         let p = wcs_btns
@@ -1604,11 +1805,7 @@ impl MachineControlView {
                             .position(|b| b.is_active())
                             .map(|i| i + 1)
                             .unwrap_or(1);
-                        let cmd = format!("G10 L20 P{p} X0 Y0 Z0");
-                        if let Some(c) = console.as_ref() { c.append_log(&format!("> {}\n", cmd)); }
-                        { let mut comm = communicator.lock();
-                            let _ = comm.send_command(&cmd);
-                        }
+                        queue_lines(&command_queue, [format!("G10 L20 P{p} X0 Y0 Z0")]);
                     }
                     d.close();
                 });
@@ -1618,22 +1815,14 @@ impl MachineControlView {
         }
 
         {
-            let communicator = view.communicator.clone();
-            let console = view.device_console.clone();
-            let include_z_check = view.goto_zero_include_z.clone();
+            let view_clone = view.clone();
             view.goto_zero_btn.connect_clicked(move |_| {
-                let cmd = if include_z_check.is_active() {
-                    "G0 X0 Y0 Z0"
-                } else {
-                    "G0 X0 Y0"
-                };
-                if let Some(c) = console.as_ref() {
-                    c.append_log(&format!("> {}\n", cmd));
-                }
-                {
-                    let mut comm = communicator.lock();
-                    let _ = comm.send_command(cmd);
-                }
+                let include_z = view_clone.goto_zero_include_z.is_active();
+                // Retract, traverse, lower: GRBL runs the lines in order
+                queue_lines(
+                    &view_clone.command_queue,
+                    view_clone.goto_work_zero_commands(include_z),
+                );
             });
         }
 
@@ -1647,7 +1836,7 @@ impl MachineControlView {
 
         // Console Command Send
         if let Some(console) = view.device_console.as_ref() {
-            let communicator = view.communicator.clone();
+            let command_queue = view.command_queue.clone();
             let console_clone = console.clone();
             let entry_clone = console.command_entry.clone();
 
@@ -1662,14 +1851,8 @@ impl MachineControlView {
                 console_clone.add_to_history(cmd.clone());
                 console_clone.reset_history_navigation();
 
-                // Log to console
-                console_clone.append_log(&format!("> {}\n", cmd));
-
-                // Send command
-                {
-                    let mut comm = communicator.lock();
-                    let _ = comm.send_command(&cmd);
-                }
+                // Send command; it is logged to the console as it goes out
+                queue_lines(&command_queue, [cmd]);
 
                 // Clear entry
                 entry_clone.set_text("");
@@ -1693,6 +1876,7 @@ impl MachineControlView {
 
             if is_connected {
                 // Disconnect
+                view_clone.command_queue.lock().clear();
                 let mut comm = view_clone.communicator.lock();
                 match comm.disconnect() {
                     Ok(_) => {
@@ -1765,10 +1949,22 @@ impl MachineControlView {
                     view_clone.state_label.set_text(&t!("Connecting…"));
                     view_clone.conn_status_state.set_text(&t!("State: Connecting…"));
 
+                    // Command spacing and post-reset settle time for fragile controllers
+                    let (command_delay_ms, reset_settle_ms) = view_clone
+                        .settings_controller
+                        .as_ref()
+                        .map(|settings| {
+                            let persistence = settings.persistence.borrow();
+                            let connection = &persistence.config().connection;
+                            (connection.command_delay_ms, connection.reset_settle_ms)
+                        })
+                        .unwrap_or((0, 250));
                     let params = ConnectionParams {
                         driver: ConnectionDriver::Serial,
                         port: port_name.to_string(),
                         baud_rate: 115200,
+                        command_delay_ms,
+                        reset_settle_ms,
                         ..Default::default()
                     };
 
@@ -1843,39 +2039,34 @@ impl MachineControlView {
                                 if let Some(c) = view_clone.device_console.as_ref() { c.append_log("> 0x18 (Reset)\n"); }
                                 let _ = comm.send(&[0x18]); // Ctrl-X (soft reset)
                             }
-
-                            // Delay info/settings queries a bit to let the controller reboot.
-                            {
-                                let communicator_init = view_clone.communicator.clone();
-                                let console = view_clone.device_console.clone();
-                                glib::timeout_add_local(std::time::Duration::from_millis(250), move || {
-                                    if let Some(mut comm) = communicator_init.try_lock() {
-                                        if comm.is_connected() {
-                                            if let Some(c) = console.as_ref() { c.append_log("> $I\n"); }
-                                            let _ = comm.send_command("$I");
-                                        }
-                                    }
-                                    glib::ControlFlow::Break
-                                });
-                            }
-
-                            {
-                                let communicator_init = view_clone.communicator.clone();
-                                let console = view_clone.device_console.clone();
-                                glib::timeout_add_local(std::time::Duration::from_millis(800), move || {
-                                    if let Some(mut comm) = communicator_init.try_lock() {
-                                        if comm.is_connected() {
-                                            if let Some(c) = console.as_ref() { c.append_log("> $$\n"); }
-                                            let _ = comm.send_command("$$");
-                                            // Ensure status report mask includes Overrides (32) and Feed/Speed (8)
-                                            // $10=47 (1+2+4+8+32) = WPos | Buf | Ln | FS | Ov
-                                            if let Some(c) = console.as_ref() { c.append_log("> $10=47\n"); }
-                                            let _ = comm.send_command("$10=47");
-                                        }
-                                    }
-                                    glib::ControlFlow::Break
-                                });
-                            }
+                            // Nothing clicked while disconnected is sent after the reset
+                            view_clone.command_queue.lock().clear();
+
+                            // Then query build info, settings and offsets and send the user's startup
+                            // block, spaced by the command throttle and its post-reset settle time
+                            let startup: Result<Vec<String>, String> = match view_clone.settings_controller.as_ref() {
+                                Some(settings) => settings
+                                    .persistence
+                                    .borrow()
+                                    .config()
+                                    .connection
+                                    .startup_commands()
+                                    .map(|lines| lines.into_iter().map(str::to_string).collect())
+                                    .map_err(|e| e.to_string()),
+                                None => Ok(Vec::new()),
+                            };
+                            let startup = startup.unwrap_or_else(|e| {
+                                tracing::warn!("Startup block not sent: {}", e);
+                                if let Some(c) = view_clone.device_console.as_ref() {
+                                    c.append_log(&format!("{} {}\n", t!("Startup block not sent:"), e));
+                                }
+                                Vec::new()
+                            });
+                            send_connect_queries(
+                                &view_clone.communicator,
+                                startup,
+                                view_clone.device_console.clone(),
+                            );
 
                             // Simple polling using glib::timeout_add_local - runs on main thread, no blocking
                             let state_label_poll = view_clone.state_label.clone();
@@ -1893,7 +2084,14 @@ impl MachineControlView {
                             let world_z_poll = view_clone.world_z.clone();
                             let feed_value_poll = view_clone.feed_value.clone();
                             let spindle_value_poll = view_clone.spindle_value.clone();
+                            let rapid_value_poll = view_clone.rapid_value.clone();
+                            let rapid_btns_poll = [
+                                view_clone.rapid_25.clone(),
+                                view_clone.rapid_50.clone(),
+                                view_clone.rapid_100.clone(),
+                            ];
                             let unlock_btn_poll = view_clone.unlock_btn.clone();
+                            let wcs_btns_poll = view_clone.wcs_btns.clone();
                             let communicator_poll = view_clone.communicator.clone();
                             let status_bar_poll = view_clone.status_bar.clone();
                             let is_streaming_poll = view_clone.is_streaming.clone();
@@ -1908,9 +2106,34 @@ impl MachineControlView {
                             let last_overrides_poll = view_clone.last_overrides.clone();
                             let widget_poll = view_clone.widget.clone();
                             let job_start_time_poll = view_clone.job_start_time.clone();
-
-                            let mut query_counter = 0u32;
+                            let job_completion_poll = view_clone.job_completion.clone();
+                            let park_at_job_end_poll = view_clone.park_at_job_end.clone();
+                            let device_manager_poll = view_clone.device_manager.clone();
+                            let job_timer_poll = view_clone.job_timer.clone();
+                            let settings_poll = view_clone.settings_controller.clone();
+                            let pending_stop_poll = view_clone.pending_stop.clone();
+                            let stop_messages_poll = view_clone.stop_messages.clone();
+                            let spindle_power_poll = view_clone.spindle_power.clone();
+                            let resume_btn_poll = view_clone.resume_btn.clone();
+                            let stop_btn_poll = view_clone.stop_btn.clone();
+                            let command_tracker_poll = view_clone.command_tracker.clone();
+                            let command_queue_poll = view_clone.command_queue.clone();
+
+                            // Status queries back off while idle; see ConnectionSettings::status_poll_interval_ms
+                            let mut last_query: Option<std::time::Instant> = None;
+                            let mut last_machine_state: Option<String> = None;
+                            // Whether the last two status reports put the machine in different places
+                            let mut last_mpos: Option<MachinePosition> = None;
+                            let mut position_changing = false;
+                            // Acknowledged program stop, shown once the machine has actually stopped
+                            let mut stop_awaiting_hold: Option<PendingStop> = None;
+                            // Override-normalised streaming time for the remaining-time estimate
+                            let mut eta_job_start: Option<std::time::Instant> = None;
+                            let mut eta_last_tick: Option<std::time::Instant> = None;
+                            let mut eta_nominal_elapsed = 0.0f64;
                             let mut response_buffer = String::new();
+                            // Keeps multi-byte characters split across reads whole
+                            let mut decoder = TextDecoder::new(params.encoding);
                             let mut firmware_detected = false;
 
                             // Cache the last known Work Coordinate Offset (WCO)
@@ -1918,8 +2141,6 @@ impl MachineControlView {
                             let mut last_wco: Option<gcodekit5_communication::firmware::grbl::status_parser::WorkCoordinateOffset> = None;
 
                             glib::timeout_add_local(std::time::Duration::from_millis(50), move || {
-                                query_counter += 1;
-
                                 // Check if still connected
                                 let is_connected = {
                                     if let Some(comm) = communicator_poll.try_lock() {
@@ -1937,9 +2158,7 @@ impl MachineControlView {
                                 if let Some(mut comm) = communicator_poll.try_lock() {
                                     if let Ok(response_bytes) = comm.receive() {
                                         if !response_bytes.is_empty() {
-                                            let s = String::from_utf8_lossy(&response_bytes);
-
-                                            response_buffer.push_str(&s);
+                                            response_buffer.push_str(&decoder.decode(&response_bytes));
 
                                             // Process complete lines (support both \n and \r line endings)
                                             while let Some(idx) = {
@@ -1975,6 +2194,41 @@ impl MachineControlView {
                                                     }
                                                 }
 
+                                                // Capture `$#` parameter lines ([G54:...], [G92:...], [TLO:...], [PRB:...]).
+                                                if line.starts_with('[') {
+                                                    use gcodekit5_communication::firmware::grbl::gcode_parameters::GcodeParameter;
+                                                    if let Some(param) = GcodeParameter::parse(&line) {
+                                                        if let GcodeParameter::WorkOffset { code, offset } = &param {
+                                                            let idx = code.trim_start_matches('G').parse::<usize>().ok()
+                                                                .and_then(|n| n.checked_sub(54));
+                                                            if let Some(btn) = idx.and_then(|i| wcs_btns_poll.get(i)) {
+                                                                let units = *current_units_poll.lock();
+                                                                let unit_label = gcodekit5_core::units::get_unit_label(units);
+                                                                btn.set_tooltip_text(Some(&format!(
+                                                                    "{}: X {} Y {} Z {} {}",
+                                                                    code,
+                                                                    format_length(offset.x as f32, units),
+                                                                    format_length(offset.y as f32, units),
+                                                                    format_length(offset.z as f32, units),
+                                                                    unit_label
+                                                                )));
+                                                            }
+                                                        }
+                                                        device_status::update_gcode_parameter(param);
+                                                    } else {
+                                                        use gcodekit5_communication::firmware::grbl::push_message::GrblPushMessage;
+                                                        match GrblPushMessage::parse(&line) {
+                                                            Some(GrblPushMessage::ParserState(modal)) => {
+                                                                device_status::update_parser_state(*modal);
+                                                            }
+                                                            Some(msg) if msg.is_program_end() => {
+                                                                job_completion_poll.lock().on_program_end();
+                                                            }
+                                                            _ => {}
+                                                        }
+                                                    }
+                                                }
+
                                                 // Handle 'ok' or 'error' for streaming
                                                 let is_ack = line == "ok";
                                                 let lower = line.to_ascii_lowercase();
@@ -2024,84 +2278,147 @@ impl MachineControlView {
                                                 }
 
                                                 if is_ack || is_error {
-                                                     *waiting_for_ack_poll.lock() = false;
-
-                                                     // If error, we might want to stop, but for now we continue
-                                                     // if is_error { ... logic to stop ... }
-
-                                                     if *is_streaming_poll.lock()
-                                                         && !*is_paused_poll.lock() {
-                                                              let mut queue = send_queue_poll.lock();
-                                                              let total_lines_val = *total_lines_poll.lock();
-                                                              let remaining = queue.len();
-                                                              let sent = total_lines_val - remaining;
-
-                                                              // Update progress bar
-                                                              if let Some(sb) = status_bar_poll.as_ref() {
-                                                                  let progress = if total_lines_val > 0 {
-                                                                      (sent as f64 / total_lines_val as f64) * 100.0
-                                                                  } else {
-                                                                      0.0
-                                                                  };
-
-                                                                  // Calculate actual elapsed time
-                                                                  let elapsed_secs = if let Some(start) = *job_start_time_poll.lock() {
-                                                                      start.elapsed().as_secs_f64()
-                                                                  } else {
-                                                                      0.0
-                                                                  };
-
-                                                                  // Estimate remaining time based on average time per line so far
-                                                                  let remaining_secs = if sent > 0 && elapsed_secs > 0.0 {
-                                                                      let avg_per_line = elapsed_secs / sent as f64;
-                                                                      remaining as f64 * avg_per_line
-                                                                  } else {
-                                                                      0.0
-                                                                  };
-
-                                                                  let format_time = |secs: f64| {
-                                                                      let h = (secs / 3600.0).floor();
-                                                                      let m = ((secs % 3600.0) / 60.0).floor();
-                                                                      let s = (secs % 60.0).floor();
-                                                                      format!("{:02}:{:02}:{:02}", h, m, s)
-                                                                  };
-
-                                                                  sb.set_progress(
-                                                                      progress,
-                                                                      &format_time(elapsed_secs),
-                                                                      &format_time(remaining_secs)
-                                                                  );
-                                                              }
-
-                                                              if let Some(next_cmd) = queue.pop_front() {
-                                                                   if let Some(c) = device_console_poll.as_ref() {
-                                                                       c.append_log(&format!("> {}\n", next_cmd));
-                                                                   }
-                                                                   let _ = comm.send_command(&next_cmd);
-                                                                    *waiting_for_ack_poll.lock() = true;
-                                                              } else {
-                                                                   // Done streaming
-                                                                   *is_streaming_poll.lock() = false;
-                                                                   *is_paused_poll.lock() = false;
-
-                                                                   // Don't clear job_start_time yet - wait for machine to be Idle
-                                                                   // *job_start_time_poll.lock() = None;
-
-                                                                   if let Some(c) = device_console_poll.as_ref() {
-                                                                       c.append_log(&format!("{}\n", t!("Streaming Completed.")));
-                                                                   }
-                                                                   // Don't reset progress yet
-                                                                   // if let Some(sb) = status_bar_poll.as_ref() {
-                                                                   //    sb.set_progress(0.0, "", "");
-                                                                   // }
-                                                              }
+                                                    // Only the response to a tracked job line moves the stream on; console
+                                                    // commands and lines sent alongside the job are answered too
+                                                    let answered = {
+                                                        let mut tracker = command_tracker_poll.lock();
+                                                        if is_ack {
+                                                            tracker.handle_ok()
+                                                        } else {
+                                                            let code = lower_trim
+                                                                .trim_start_matches("error:")
+                                                                .trim_start()
+                                                                .split(|c: char| !c.is_ascii_digit())
+                                                                .next()
+                                                                .and_then(|digits| digits.parse().ok());
+                                                            tracker.handle_error(code, line.clone())
+                                                        }
+                                                    };
+                                                    if answered.as_ref().is_some_and(is_job_line) {
+                                                         *waiting_for_ack_poll.lock() = false;
+                                                         if is_ack {
+                                                             job_completion_poll.lock().on_ack();
+                                                         }
+
+                                                         // A program stop was acknowledged: hold the queue until the operator
+                                                         // resumes. The controller acks on parsing, so the dialog waits for the
+                                                         // machine to reach the stop (see the status report handling).
+                                                         let stop = pending_stop_poll.lock().take();
+                                                         if let Some(stop) = stop.filter(|_| *is_streaming_poll.lock()) {
+                                                             *is_paused_poll.lock() = true;
+                                                             stop_awaiting_hold = Some(stop);
                                                          }
+
+                                                         // If error, we might want to stop, but for now we continue
+                                                         // if is_error { ... logic to stop ... }
+
+                                                         if *is_streaming_poll.lock()
+                                                             && !*is_paused_poll.lock() {
+                                                                  let queue = send_queue_poll.lock();
+                                                                  let total_lines_val = *total_lines_poll.lock();
+                                                                  let remaining = queue.len();
+                                                                  let sent = total_lines_val - remaining;
+
+                                                                  // Update progress bar
+                                                                  if let Some(sb) = status_bar_poll.as_ref() {
+                                                                      let progress = if total_lines_val > 0 {
+                                                                          (sent as f64 / total_lines_val as f64) * 100.0
+                                                                      } else {
+                                                                          0.0
+                                                                      };
+
+                                                                      // Calculate actual elapsed time
+                                                                      let elapsed_secs = if let Some(start) = *job_start_time_poll.lock() {
+                                                                          start.elapsed().as_secs_f64()
+                                                                      } else {
+                                                                          0.0
+                                                                      };
+
+                                                                      // Accumulate elapsed time normalised to 100% feed, then project the
+                                                                      // remaining lines at the current feed override.
+                                                                      let feed_ov = last_overrides_poll.lock().feed;
+                                                                      let job_start = *job_start_time_poll.lock();
+                                                                      if job_start != eta_job_start {
+                                                                          eta_job_start = job_start;
+                                                                          eta_last_tick = job_start;
+                                                                          eta_nominal_elapsed = 0.0;
+                                                                      }
+                                                                      let now = std::time::Instant::now();
+                                                                      if let Some(prev) = eta_last_tick {
+                                                                          eta_nominal_elapsed += now.duration_since(prev).as_secs_f64()
+                                                                              * eta_feed_factor(feed_ov);
+                                                                      }
+                                                                      eta_last_tick = Some(now);
+
+                                                                      let remaining_secs = estimate_remaining_secs(
+                                                                          eta_nominal_elapsed,
+                                                                          sent,
+                                                                          remaining,
+                                                                          feed_ov,
+                                                                      );
+
+                                                                      let format_time = |secs: f64| {
+                                                                          let h = (secs / 3600.0).floor();
+                                                                          let m = ((secs % 3600.0) / 60.0).floor();
+                                                                          let s = (secs % 60.0).floor();
+                                                                          format!("{:02}:{:02}:{:02}", h, m, s)
+                                                                      };
+
+                                                                      sb.set_progress(
+                                                                          progress,
+                                                                          &format_time(elapsed_secs),
+                                                                          &format_time(remaining_secs)
+                                                                      );
+                                                                  }
+
+                                                                  // The next line goes out below, once the command throttle allows it
+                                                                  if queue.is_empty() {
+                                                                      // Done streaming
+                                                                      *is_streaming_poll.lock() = false;
+                                                                      *is_paused_poll.lock() = false;
+
+                                                                      // Don't clear job_start_time yet - the job completes on
+                                                                      // program end or sustained Idle (see JobCompletionDetector)
+                                                                      job_completion_poll
+                                                                          .lock()
+                                                                          .on_queue_drained(std::time::Instant::now());
+
+                                                                      if let Some(c) = device_console_poll.as_ref() {
+                                                                          c.append_log(&format!("{}\n", t!("Streaming Completed.")));
+                                                                      }
+                                                                      // Don't reset progress yet
+                                                                      // if let Some(sb) = status_bar_poll.as_ref() {
+                                                                      //    sb.set_progress(0.0, "", "");
+                                                                      // }
+                                                                  }
+                                                             }
+                                                    }
                                                 }
 
                                                 // Parse GRBL status: <Idle|MPos:0.000,0.000,0.000|...>
                                                 if line.starts_with('<') && line.ends_with('>') {
                                                     // Update machine state
                                                     if let Some(state) = StatusParser::parse_machine_state(&line) {
+                                                        last_machine_state = Some(state.clone());
+
+                                                        let stopped = state.starts_with("Hold") || state.starts_with("Idle");
+                                                        if let Some(stop) = stop_awaiting_hold.take_if(|_| stopped) {
+                                                            if *is_streaming_poll.lock() {
+                                                                if let Some(c) = device_console_poll.as_ref() {
+                                                                    c.append_log(&format!(
+                                                                        "{} {}\n",
+                                                                        t!("Program stop, waiting for the operator:"),
+                                                                        stop.message.as_deref().unwrap_or("")
+                                                                    ));
+                                                                }
+                                                                show_program_stop_dialog(
+                                                                    &widget_poll,
+                                                                    &stop,
+                                                                    &resume_btn_poll,
+                                                                    &stop_btn_poll,
+                                                                );
+                                                            }
+                                                        }
                                                         conn_status_state_poll.set_text(&format!(
                                                             "{} {}",
                                                             t!("State:"),
@@ -2136,19 +2453,9 @@ impl MachineControlView {
                                                             sb.set_state(&state);
                                                         }
 
-                                                        // If machine is Idle and not streaming, clear job timer
-                                                        if state == "Idle" && !*is_streaming_poll.lock() {
-                                                            let mut start_time = job_start_time_poll.lock();
-                                                            if start_time.is_some() {
-                                                                *start_time = None;
-                                                                if let Some(sb) = status_bar_poll.as_ref() {
-                                                                    sb.set_progress(0.0, "", "");
-                                                                }
-                                                                if let Some(c) = device_console_poll.as_ref() {
-                                                                    c.append_log(&format!("{}\n", t!("Job Finished.")));
-                                                                }
-                                                            }
-                                                        }
+                                                        job_completion_poll
+                                                            .lock()
+                                                            .on_state(&state, std::time::Instant::now());
 
                                                         // Unlock button only enabled in ALARM state
                                                         let is_alarm = low.starts_with("alarm");
@@ -2165,6 +2472,8 @@ impl MachineControlView {
 
                                                     // Update machine position (MPos)
                                                     if let Some(mpos) = full_status.mpos {
+                                                        position_changing = last_mpos.is_some_and(|last| last != mpos);
+                                                        last_mpos = Some(mpos);
                                                         let units = *current_units_poll.lock();
                                                         let unit_label = gcodekit5_core::units::get_unit_label(units);
 
@@ -2183,6 +2492,16 @@ impl MachineControlView {
                                                             format_length(mpos.z as f32, units),
                                                             unit_label
                                                         ));
+                                                        show_limit_proximity(
+                                                            &mpos,
+                                                            device_manager_poll.as_ref(),
+                                                            settings_poll.as_ref(),
+                                                            [
+                                                                (&x_dro_poll, &world_x_poll),
+                                                                (&y_dro_poll, &world_y_poll),
+                                                                (&z_dro_poll, &world_z_poll),
+                                                            ],
+                                                        );
 
                                                         device_status::update_machine_position(mpos);
 
@@ -2291,6 +2610,7 @@ impl MachineControlView {
                                                             let mut last = last_overrides_poll.lock();
                                                             *last = ov;
                                                         }
+                                                        overrides::show_rapid_override(&rapid_value_poll, &rapid_btns_poll, ov.rapid);
                                                         (ov.feed, ov.spindle)
                                                     } else {
                                                         let last = last_overrides_poll.lock();
@@ -2335,6 +2655,9 @@ impl MachineControlView {
                                                         } else {
                                                             feed_value_poll.remove_css_class("error");
                                                         }
+                                                    } else {
+                                                        // No feed rate reported: still show the override
+                                                        feed_value_poll.set_text(&format!("{}%", feed_ov));
                                                     }
 
                                                     // Handle spindle speed even if feed is None
@@ -2362,6 +2685,8 @@ impl MachineControlView {
                                                         } else {
                                                             spindle_value_poll.remove_css_class("error");
                                                         }
+                                                    } else {
+                                                        spindle_value_poll.set_text(&format!("{}%", spindle_ov));
                                                     }
 
                                                     // Update status bar and device_status if we have both
@@ -2384,9 +2709,103 @@ impl MachineControlView {
                                         }
                                     }
 
-                                    // Send status query every ~250ms (every 5 cycles of 50ms)
-                                    if query_counter.is_multiple_of(5) {
+                                    // Lines sent outside the job go first, then the next job line once the
+                                    // last one is answered and the command throttle allows it, without
+                                    // blocking the main loop
+                                    let queue_sent = send_queued(
+                                        &mut comm,
+                                        &command_queue_poll,
+                                        &command_tracker_poll,
+                                        device_console_poll.as_ref(),
+                                    );
+                                    if queue_sent
+                                        && *is_streaming_poll.lock()
+                                        && !*is_paused_poll.lock()
+                                        && !*waiting_for_ack_poll.lock()
+                                        && comm.command_wait_time().is_zero()
+                                    {
+                                        let mut queue = send_queue_poll.lock();
+                                        let index = total_lines_poll.lock().saturating_sub(queue.len());
+                                        if let Some(next_cmd) = queue.pop_front() {
+                                            if let Some(c) = device_console_poll.as_ref() {
+                                                c.append_log(&format!("> {}\n", next_cmd));
+                                            }
+                                            note_sent_line(&next_cmd, index, &stop_messages_poll, &pending_stop_poll);
+                                            spindle_power_poll.lock().observe(&next_cmd);
+                                            let _ = comm.send_command(&next_cmd);
+                                            track_job_line(&command_tracker_poll, &next_cmd, index);
+                                            job_completion_poll.lock().on_line_sent(&next_cmd);
+                                            if let Some(timer) = job_timer_poll.lock().as_mut() {
+                                                timer.on_line_sent();
+                                            }
+                                            *waiting_for_ack_poll.lock() = true;
+                                        }
+                                    }
+
+                                    // A line that never got a response would stall the stream.
+                                    // Held, running or moving, the controller answers once it is
+                                    // resumed or has room in its planner, e.g. after a long slow move
+                                    let held = *is_paused_poll.lock()
+                                        || last_machine_state
+                                            .as_deref()
+                                            .is_some_and(|state| state.starts_with("Hold") || state.starts_with("Door"));
+                                    let busy = position_changing
+                                        || last_machine_state
+                                            .as_deref()
+                                            .is_some_and(|state| state.starts_with("Run") || state.starts_with("Jog"));
+                                    check_command_timeouts(
+                                        &mut comm,
+                                        &command_tracker_poll,
+                                        held || busy,
+                                        &is_streaming_poll,
+                                        &send_queue_poll,
+                                        &waiting_for_ack_poll,
+                                        &is_paused_poll,
+                                        device_console_poll.as_ref(),
+                                    );
+
+                                    // Clear the job timer once the job has actually finished
+                                    let completion = job_completion_poll
+                                        .lock()
+                                        .poll(std::time::Instant::now());
+                                    if let Some(reason) = completion {
+                                        tracing::info!("Job finished: {:?}", reason);
+                                        *job_start_time_poll.lock() = None;
+                                        if let Some(timer) = job_timer_poll.lock().take() {
+                                            record_job_timing(timer);
+                                        }
+                                        if let Some(sb) = status_bar_poll.as_ref() {
+                                            sb.set_progress(0.0, "", "");
+                                        }
+                                        if let Some(c) = device_console_poll.as_ref() {
+                                            c.append_log(&format!("{}\n", t!("Job Finished.")));
+                                        }
+                                        if park_at_job_end_poll.is_active() {
+                                            run_end_of_job(
+                                                &command_queue_poll,
+                                                device_manager_poll.as_ref(),
+                                                device_console_poll.as_ref(),
+                                            );
+                                        }
+                                    }
+
+                                    // Send status queries faster while moving than while idle
+                                    let streaming = *is_streaming_poll.lock();
+                                    let interval_ms = match settings_poll.as_ref() {
+                                        Some(settings) => settings
+                                            .persistence
+                                            .borrow()
+                                            .config()
+                                            .connection
+                                            .status_poll_interval_ms(last_machine_state.as_deref(), streaming),
+                                        None => ConnectionSettings::default()
+                                            .status_poll_interval_ms(last_machine_state.as_deref(), streaming),
+                                    };
+                                    if last_query.is_none_or(|at| {
+                                        at.elapsed() >= std::time::Duration::from_millis(interval_ms)
+                                    }) {
                                         let _ = comm.send(b"?");
+                                        last_query = Some(std::time::Instant::now());
                                     }
                                 }
 
@@ -2412,9 +2831,31 @@ impl MachineControlView {
         // Setup override handlers
         Self::setup_override_handlers(&view);
 
+        // Gamepad pendant jogging
+        Self::setup_pendant(&view);
+
         view
     }
 }
 
+mod command_queue;
+mod command_timeout;
+mod connect_queries;
+mod end_of_job;
+mod feed_ramp;
+mod hold_power;
+mod limit_warning;
 mod operations;
 mod overrides;
+mod pendant;
+mod program_stop;
+
+pub use command_queue::QueuedCommand;
+use command_queue::{drop_queued_jogs, queue_lines, queue_realtime, send_queued};
+use command_timeout::{check_command_timeouts, is_job_line, track_job_line};
+use connect_queries::send_connect_queries;
+use end_of_job::run_end_of_job;
+pub use feed_ramp::RunningRamp;
+use limit_warning::show_limit_proximity;
+pub use program_stop::PendingStop;
+use program_stop::{note_sent_line, show_program_stop_dialog};
@@ -27,16 +27,158 @@ impl MachineControlView {
         *self.jog_step_mm.lock() as f64
     }
 
+    /// Lines for Go to Work Zero: retract to the safe height unless already above
+    /// it, move to X0 Y0, then lower to Z0 if `include_z`
+    pub fn goto_work_zero_commands(&self, include_z: bool) -> Vec<String> {
+        let safe_z = self.settings_controller.as_ref().map_or_else(
+            || gcodekit5_settings::MachineSettings::default().safe_z,
+            |controller| controller.persistence.borrow().config().machine.safe_z,
+        );
+
+        let status = device_status::get_status();
+        let current_z = status.work_position.as_ref().map(|w| w.z);
+        let offset_z = match (&status.machine_position, &status.work_position) {
+            (Some(m), Some(w)) => Some(m.z - w.z),
+            _ => status.work_coordinate_offset.as_ref().map(|o| o.z),
+        };
+        // The top of Z travel (soft limit) in work coordinates
+        let z_ceiling = self
+            .device_manager
+            .as_ref()
+            .and_then(|manager| manager.get_active_profile())
+            .filter(|profile| profile.z_axis.enabled)
+            .zip(offset_z)
+            .map(|(profile, offset_z)| profile.z_axis.max - offset_z);
+
+        safe_goto_commands(
+            0.0,
+            0.0,
+            include_z.then_some(0.0),
+            current_z,
+            safe_z,
+            z_ceiling,
+        )
+    }
+
+    /// Run the enabled pre-flight checks against a program before streaming it
+    pub fn preflight_warnings(&self, content: &str) -> Vec<PreflightWarning> {
+        let settings = self
+            .settings_controller
+            .as_ref()
+            .map(|controller| controller.persistence.borrow().config().machine.preflight)
+            .unwrap_or_default();
+
+        // Without a reported work offset program coordinates cannot be placed in
+        // machine space, so the soft limit check is skipped rather than guessed.
+        let offset = device_status::get_status().work_coordinate_offset;
+        if settings.soft_limits && offset.is_none() {
+            tracing::debug!("Skipping soft limit pre-flight check: work offset unknown");
+        }
+        let config = PreflightConfig {
+            confirm_safety: settings.confirm_safety,
+            soft_limits: settings.soft_limits && offset.is_some(),
+            spindle_command: settings.spindle_command,
+            program_end: settings.program_end,
+            work_offset: offset.map_or([0.0; 3], |o| [o.x, o.y, o.z]),
+        };
+
+        let profile = self
+            .device_manager
+            .as_ref()
+            .and_then(|manager| manager.get_active_profile());
+        preflight(content, profile.as_ref(), &config)
+    }
+
+    /// Show pre-flight findings; the job starts only if the operator confirms and
+    /// nothing failed
+    pub fn show_preflight_dialog(
+        &self,
+        parent: &impl IsA<gtk4::Widget>,
+        warnings: Vec<PreflightWarning>,
+        content: String,
+    ) {
+        let failed = has_failures(&warnings);
+        let details = warnings
+            .iter()
+            .map(|w| match w.line {
+                Some(line) => format!("• {} ({} {})", w.message, t!("line"), line + 1),
+                None => format!("• {}", w.message),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let (message_type, title) = if failed {
+            tracing::warn!("Job blocked by pre-flight checks:\n{}", details);
+            (gtk4::MessageType::Error, t!("Job Cannot Start"))
+        } else {
+            (gtk4::MessageType::Warning, t!("Pre-flight Warnings"))
+        };
+        let dialog = gtk4::MessageDialog::builder()
+            .message_type(message_type)
+            .buttons(gtk4::ButtonsType::None)
+            .text(title)
+            .secondary_text(details)
+            .build();
+
+        if failed {
+            dialog.add_button(&t!("Close"), gtk4::ResponseType::Close);
+        } else {
+            dialog.add_button(&t!("Cancel"), gtk4::ResponseType::Cancel);
+            let start = dialog.add_button(&t!("Start Job"), gtk4::ResponseType::Accept);
+            start.add_css_class("suggested-action");
+        }
+
+        if let Some(root) = parent.root() {
+            if let Ok(win) = root.downcast::<gtk4::Window>() {
+                dialog.set_transient_for(Some(&win));
+                dialog.set_modal(true);
+            }
+        }
+
+        let view = self.clone();
+        dialog.connect_response(move |d, response| {
+            d.close();
+            if response == gtk4::ResponseType::Accept {
+                view.start_job(&content);
+            }
+        });
+        dialog.show();
+    }
+
     pub fn start_job(&self, content: &str) {
         if *self.is_streaming.lock() {
             return;
         }
 
-        let lines: Vec<String> = content
-            .lines()
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty() && !s.starts_with(';') && !s.starts_with('('))
-            .collect();
+        let (block_delete, optional_stop) = match self.settings_controller.as_ref() {
+            Some(controller) => {
+                let persistence = controller.persistence.borrow();
+                let machine = &persistence.config().machine;
+                (machine.block_delete, machine.optional_stop)
+            }
+            None => (true, true),
+        };
+        let stream = program_stop::stream_lines(
+            content
+                .lines()
+                .filter_map(|s| gcodekit5_core::apply_block_delete(s, block_delete))
+                .map(str::trim),
+            optional_stop,
+        );
+        if stream.skipped_optional_stops > 0 {
+            if let Some(c) = self.device_console.as_ref() {
+                c.append_log(&format!(
+                    "{} {}\n",
+                    t!("Optional stop is off, skipping M1 stops:"),
+                    stream.skipped_optional_stops
+                ));
+            }
+        }
+        let lines = stream.lines;
+        *self.stop_messages.lock() = stream.stop_messages;
+        *self.pending_stop.lock() = None;
+        *self.spindle_power.lock() = SpindlePower::default();
+        self.hold_resume.lock().clear();
 
         if lines.is_empty() {
             if let Some(c) = self.device_console.as_ref() {
@@ -96,20 +238,33 @@ impl MachineControlView {
         *self.is_streaming.lock() = true;
         *self.is_paused.lock() = false;
         *self.waiting_for_ack.lock() = false;
+        self.command_tracker.lock().clear();
         *self.job_start_time.lock() = Some(std::time::Instant::now());
+        self.job_completion.lock().start(&lines);
+        *self.job_timer.lock() = self.start_job_timer(&lines);
+        // The connection's poll loop sends the lines, as the command throttle allows
+    }
 
-        // Kickstart
-        {
-            let mut comm = self.communicator.lock();
-            let mut queue = self.send_queue.lock();
-            if let Some(cmd) = queue.pop_front() {
-                if let Some(c) = self.device_console.as_ref() {
-                    c.append_log(&format!("> {}\n", cmd));
-                }
-                let _ = comm.send_command(&cmd);
-                *self.waiting_for_ack.lock() = true;
-            }
+    /// Start measuring a job against its estimate, if job timing is enabled
+    fn start_job_timer(&self, lines: &[String]) -> Option<JobTimer> {
+        let enabled = self.settings_controller.as_ref().is_some_and(|controller| {
+            controller
+                .persistence
+                .borrow()
+                .config()
+                .machine
+                .record_job_timing
+        });
+        if !enabled {
+            return None;
         }
+        let machine = self
+            .device_manager
+            .as_ref()
+            .and_then(|manager| manager.get_active_profile())
+            .map_or_else(|| "default".to_string(), |profile| profile.name);
+        let estimate = StatsCalculator::calculate(lines).estimated_time_secs;
+        Some(JobTimer::start(machine, estimate))
     }
 
     pub fn emergency_stop(&self) {
@@ -121,7 +276,15 @@ impl MachineControlView {
         *self.is_streaming.lock() = false;
         *self.is_paused.lock() = false;
         *self.waiting_for_ack.lock() = false;
+        self.command_tracker.lock().clear();
+        self.command_queue.lock().clear();
+        *self.pending_stop.lock() = None;
+        self.hold_resume.lock().clear();
         *self.job_start_time.lock() = None;
+        self.job_completion.lock().reset();
+        if let Some(timer) = self.job_timer.lock().take() {
+            timer.abort();
+        }
         self.send_queue.lock().clear();
 
         // Reset progress
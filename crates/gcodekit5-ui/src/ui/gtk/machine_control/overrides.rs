@@ -1,168 +1,65 @@
-//! Feed rate and spindle override handlers
+//! Feed rate, rapid and spindle override handlers
+//!
+//! Each button sends a single GRBL real-time override byte; the controller
+//! applies it immediately, even mid-job, and reports the new percentages in the
+//! `Ov:` field of its status reports, which is what the labels show.
 
 use super::*;
 
+use gcodekit5_communication::firmware::grbl::RealTimeOverrideCommand;
+
+/// Rapid override levels offered by the rapid buttons, in button order
+pub(super) const RAPID_LEVELS: [u16; 3] = [25, 50, 100];
+
 impl MachineControlView {
     // Feed Rate Override Controls Setup
     pub(crate) fn setup_override_handlers(view: &Self) {
-        // Feed Rate Override Controls
-        // GRBL Realtime Commands for Feed Override:
-        // 0x90 = Reset to 100%
-        // 0x91 = +10%
-        // 0x92 = -10%
-        // 0x93 = +1%
-        // 0x94 = -1%
-        {
-            let communicator = view.communicator.clone();
-            let console = view.device_console.clone();
-            view.feed_inc10.connect_clicked(move |_| {
-                if let Some(c) = console.as_ref() {
-                    c.append_log("> Feed +10%\n");
-                }
-                {
-                    let mut comm = communicator.lock();
-                    let _ = comm.send(&[0x91]); // Feed +10%
-                }
-            });
-        }
-        {
-            let communicator = view.communicator.clone();
-            let console = view.device_console.clone();
-            view.feed_inc1.connect_clicked(move |_| {
-                if let Some(c) = console.as_ref() {
-                    c.append_log("> Feed +1%\n");
-                }
-                {
-                    let mut comm = communicator.lock();
-                    let _ = comm.send(&[0x93]); // Feed +1%
-                }
-            });
-        }
-        {
-            let communicator = view.communicator.clone();
-            let console = view.device_console.clone();
-            view.feed_dec1.connect_clicked(move |_| {
-                if let Some(c) = console.as_ref() {
-                    c.append_log("> Feed -1%\n");
-                }
-                {
-                    let mut comm = communicator.lock();
-                    let _ = comm.send(&[0x94]); // Feed -1%
-                }
-            });
-        }
-        {
-            let communicator = view.communicator.clone();
-            let console = view.device_console.clone();
-            view.feed_dec10.connect_clicked(move |_| {
-                if let Some(c) = console.as_ref() {
-                    c.append_log("> Feed -10%\n");
-                }
-                {
-                    let mut comm = communicator.lock();
-                    let _ = comm.send(&[0x92]); // Feed -10%
-                }
-            });
-        }
-        {
-            let communicator = view.communicator.clone();
-            let console = view.device_console.clone();
-            view.feed_reset.connect_clicked(move |_| {
-                if let Some(c) = console.as_ref() {
-                    c.append_log("> Feed Reset (100%)\n");
-                }
-                {
-                    let mut comm = communicator.lock();
-                    let _ = comm.send(&[0x90]); // Feed override reset to 100%
-                }
-            });
-        }
+        use RealTimeOverrideCommand as Ov;
 
-        // Spindle Override Controls
-        // GRBL Realtime Commands for Spindle Override:
-        // 0x99 = 100% (Reset)
-        // 0x9A = +10%
-        // 0x9B = -10%
-        // 0x9C = +1%
-        // 0x9D = -1%
-        // 0x9E = Spindle Stop (Toggle)
-        {
+        let buttons = [
+            (&view.feed_inc10, "Feed +10%", Ov::FeedIncrease10),
+            (&view.feed_inc1, "Feed +1%", Ov::FeedIncrease1),
+            (&view.feed_dec1, "Feed -1%", Ov::FeedDecrease1),
+            (&view.feed_dec10, "Feed -10%", Ov::FeedDecrease10),
+            (&view.feed_reset, "Feed Reset (100%)", Ov::FeedReset),
+            (&view.rapid_25, "Rapid 25%", Ov::RapidOv25),
+            (&view.rapid_50, "Rapid 50%", Ov::RapidOv50),
+            (&view.rapid_100, "Rapid 100%", Ov::RapidOv100),
+            (&view.spindle_inc10, "Spindle +10%", Ov::SpindleIncrease10),
+            (&view.spindle_inc1, "Spindle +1%", Ov::SpindleIncrease1),
+            (&view.spindle_dec1, "Spindle -1%", Ov::SpindleDecrease1),
+            (&view.spindle_dec10, "Spindle -10%", Ov::SpindleDecrease10),
+            (&view.spindle_stop, "Spindle Stop", Ov::SpindleStop),
+            (
+                &view.spindle_reset,
+                "Spindle Reset (100%)",
+                Ov::SpindleReset,
+            ),
+        ];
+        for (button, label, command) in buttons {
             let communicator = view.communicator.clone();
             let console = view.device_console.clone();
-            view.spindle_inc10.connect_clicked(move |_| {
+            button.connect_clicked(move |_| {
                 if let Some(c) = console.as_ref() {
-                    c.append_log("> Spindle +10%\n");
+                    c.append_log(&format!("> {}\n", label));
                 }
-                {
-                    let mut comm = communicator.lock();
-                    let _ = comm.send(&[0x9A]); // Spindle +10%
+                let mut comm = communicator.lock();
+                if let Err(e) = comm.send(&[command.as_byte()]) {
+                    tracing::warn!("Failed to send {} override: {}", label, e);
                 }
             });
         }
-        {
-            let communicator = view.communicator.clone();
-            let console = view.device_console.clone();
-            view.spindle_inc1.connect_clicked(move |_| {
-                if let Some(c) = console.as_ref() {
-                    c.append_log("> Spindle +1%\n");
-                }
-                {
-                    let mut comm = communicator.lock();
-                    let _ = comm.send(&[0x9C]); // Spindle +1%
-                }
-            });
-        }
-        {
-            let communicator = view.communicator.clone();
-            let console = view.device_console.clone();
-            view.spindle_dec1.connect_clicked(move |_| {
-                if let Some(c) = console.as_ref() {
-                    c.append_log("> Spindle -1%\n");
-                }
-                {
-                    let mut comm = communicator.lock();
-                    let _ = comm.send(&[0x9D]); // Spindle -1%
-                }
-            });
-        }
-        {
-            let communicator = view.communicator.clone();
-            let console = view.device_console.clone();
-            view.spindle_dec10.connect_clicked(move |_| {
-                if let Some(c) = console.as_ref() {
-                    c.append_log("> Spindle -10%\n");
-                }
-                {
-                    let mut comm = communicator.lock();
-                    let _ = comm.send(&[0x9B]); // Spindle -10%
-                }
-            });
-        }
-        {
-            let communicator = view.communicator.clone();
-            let console = view.device_console.clone();
-            view.spindle_stop.connect_clicked(move |_| {
-                if let Some(c) = console.as_ref() {
-                    c.append_log("> Spindle Stop\n");
-                }
-                {
-                    let mut comm = communicator.lock();
-                    let _ = comm.send(&[0x9E]); // Spindle stop
-                }
-            });
-        }
-        {
-            let communicator = view.communicator.clone();
-            let console = view.device_console.clone();
-            view.spindle_reset.connect_clicked(move |_| {
-                if let Some(c) = console.as_ref() {
-                    c.append_log("> Spindle Reset (100%)\n");
-                }
-                {
-                    let mut comm = communicator.lock();
-                    let _ = comm.send(&[0x99]); // Spindle override reset to 100%
-                }
-            });
+    }
+}
+
+/// Show the reported rapid override and highlight its button
+pub(super) fn show_rapid_override(value: &Label, buttons: &[Button; 3], rapid: u16) {
+    value.set_text(&format!("{}%", rapid));
+    for (button, level) in buttons.iter().zip(RAPID_LEVELS) {
+        if level == rapid {
+            button.add_css_class("suggested-action");
+        } else {
+            button.remove_css_class("suggested-action");
         }
     }
 }
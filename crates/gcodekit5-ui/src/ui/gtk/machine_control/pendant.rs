@@ -0,0 +1,115 @@
+//! Gamepad pendant handlers
+
+use super::*;
+
+use gcodekit5_settings::JOG_KEY_AXES;
+use gcodekit5_visualizer::{
+    GamepadDevice, GamepadJogMapper, PendantButton, PendantCommand, PendantConfig,
+};
+use std::sync::mpsc::TryRecvError;
+
+/// Pendant configuration file in the config directory
+const PENDANT_FILE: &str = "pendant.json";
+
+/// Whether a modal dialog is open; pendant input is ignored meanwhile
+fn modal_dialog_open() -> bool {
+    gtk4::Window::list_toplevels()
+        .into_iter()
+        .filter_map(|w| w.downcast::<gtk4::Window>().ok())
+        .any(|w| w.is_visible() && w.is_modal())
+}
+
+impl MachineControlView {
+    pub(crate) fn setup_pendant(view: &Self) {
+        let config = match gcodekit5_settings::SettingsManager::config_directory() {
+            Ok(dir) => PendantConfig::load(&dir.join(PENDANT_FILE)).unwrap_or_else(|e| {
+                tracing::warn!("Invalid pendant configuration: {}", e);
+                PendantConfig::default()
+            }),
+            Err(_) => return,
+        };
+        if !config.enabled {
+            return;
+        }
+        let events = match GamepadDevice::spawn(&config.device_path) {
+            Ok(events) => events,
+            Err(e) => {
+                tracing::warn!("Gamepad {} not available: {}", config.device_path, e);
+                return;
+            }
+        };
+        tracing::info!("Gamepad pendant on {}", config.device_path);
+
+        let max_rates = view
+            .settings_controller
+            .as_ref()
+            .map(|c| {
+                let persistence = c.persistence.borrow();
+                let machine = &persistence.config().machine;
+                JOG_KEY_AXES
+                    .iter()
+                    .filter_map(|axis| machine.jog_max_rate(*axis).map(|rate| (*axis, rate)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut mapper = GamepadJogMapper::new(config).with_max_rates(max_rates);
+        let view = view.clone();
+        glib::timeout_add_local(std::time::Duration::from_millis(20), move || {
+            let blocked = modal_dialog_open();
+            loop {
+                let event = match events.try_recv() {
+                    Ok(event) => event,
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        if mapper.is_jogging() {
+                            view.run_pendant_command(PendantCommand::JogCancel);
+                        }
+                        return glib::ControlFlow::Break;
+                    }
+                };
+                for command in mapper.handle(event, blocked) {
+                    view.run_pendant_command(command);
+                }
+            }
+            if blocked {
+                // A dialog may have opened mid-jog without further gamepad input
+                for command in mapper.release_all() {
+                    view.run_pendant_command(command);
+                }
+            }
+            glib::ControlFlow::Continue
+        });
+    }
+
+    fn run_pendant_command(&self, command: PendantCommand) {
+        match command {
+            PendantCommand::Jog(line) => queue_lines(&self.command_queue, [line]),
+            PendantCommand::JogCancel => {
+                drop_queued_jogs(&self.command_queue);
+                if let Some(c) = self.device_console.as_ref() {
+                    c.append_log("> 0x85 (Jog Cancel)\n");
+                }
+                let mut comm = self.communicator.lock();
+                let _ = comm.send(&[0x85]);
+            }
+            PendantCommand::Action(button) => {
+                let target = match button {
+                    PendantButton::Start => &self.send_btn,
+                    PendantButton::Stop => &self.stop_btn,
+                    PendantButton::PauseResume if *self.is_paused.lock() => &self.resume_btn,
+                    PendantButton::PauseResume | PendantButton::FeedHold => &self.pause_btn,
+                    PendantButton::Resume => &self.resume_btn,
+                    PendantButton::FeedUp => &self.feed_inc10,
+                    PendantButton::FeedDown => &self.feed_dec10,
+                    PendantButton::SpeedUp => &self.spindle_inc10,
+                    PendantButton::SpeedDown => &self.spindle_dec10,
+                    PendantButton::Home => &self.home_btn,
+                    PendantButton::ZeroAll => &self.zero_all_btn,
+                };
+                if target.is_sensitive() {
+                    target.emit_clicked();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Program stop (M0/M1) handling while streaming
+
+use super::*;
+
+use gcodekit5_core::{is_only_program_stop, message_comment, program_stop, ProgramStop};
+use std::collections::HashMap;
+
+/// A program stop sent to the controller; once it is acknowledged the queue
+/// holds and the operator is asked to resume
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingStop {
+    pub kind: ProgramStop,
+    pub message: Option<String>,
+}
+
+/// Program lines ready to stream
+pub(super) struct StreamLines {
+    pub lines: Vec<String>,
+    /// Operator message of each stop, by line index
+    pub stop_messages: HashMap<usize, String>,
+    /// `M1` lines dropped because the optional stop switch is off
+    pub skipped_optional_stops: usize,
+}
+
+/// Prepare trimmed program lines for streaming
+///
+/// Comment-only lines are dropped, but a `(MSG,...)` comment is kept for the next
+/// stop. Lines that are only an `M1` are dropped when `optional_stop` is off.
+pub(super) fn stream_lines<'a>(
+    lines: impl Iterator<Item = &'a str>,
+    optional_stop: bool,
+) -> StreamLines {
+    let mut stream = StreamLines {
+        lines: Vec::new(),
+        stop_messages: HashMap::new(),
+        skipped_optional_stops: 0,
+    };
+    let mut message: Option<String> = None;
+    for line in lines {
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+        if line.starts_with('(') {
+            if let Some(text) = message_comment(line) {
+                message = Some(text.to_string());
+            }
+            continue;
+        }
+        match program_stop(line) {
+            Some(ProgramStop::Optional) if !optional_stop && is_only_program_stop(line) => {
+                stream.skipped_optional_stops += 1;
+                message = None;
+                continue;
+            }
+            Some(_) => {
+                if let Some(text) = message_comment(line).map(str::to_string).or(message.take()) {
+                    stream.stop_messages.insert(stream.lines.len(), text);
+                }
+            }
+            None => {}
+        }
+        stream.lines.push(line.to_string());
+    }
+    stream
+}
+
+/// Record the line about to be sent as job line `index`, noting it if it is a stop
+pub(super) fn note_sent_line(
+    line: &str,
+    index: usize,
+    stop_messages: &ThreadSafe<HashMap<usize, String>>,
+    pending_stop: &ThreadSafe<Option<PendingStop>>,
+) {
+    *pending_stop.lock() = program_stop(line).map(|kind| PendingStop {
+        kind,
+        message: stop_messages.lock().get(&index).cloned(),
+    });
+}
+
+/// Ask the operator to resume after a program stop
+///
+/// Resume clicks the Resume button (cycle start, then the next line unless one is
+/// still waiting for its ack), Stop Job clicks Stop, and Keep Paused leaves the
+/// job held for the Resume button later.
+pub(super) fn show_program_stop_dialog(
+    widget: &impl IsA<gtk4::Widget>,
+    stop: &PendingStop,
+    resume_btn: &Button,
+    stop_btn: &Button,
+) {
+    let title = match stop.kind {
+        ProgramStop::Mandatory => t!("Program paused (M0)"),
+        ProgramStop::Optional => t!("Optional stop (M1)"),
+    };
+    let secondary = stop
+        .message
+        .clone()
+        .unwrap_or_else(|| t!("The program is paused. Resume when ready."));
+    let dialog = gtk4::MessageDialog::builder()
+        .message_type(gtk4::MessageType::Question)
+        .text(title)
+        .secondary_text(&secondary)
+        .build();
+    dialog.add_button(&t!("Stop Job"), gtk4::ResponseType::Reject);
+    dialog.add_button(&t!("Keep Paused"), gtk4::ResponseType::Cancel);
+    dialog.add_button(&t!("Resume"), gtk4::ResponseType::Accept);
+    dialog.set_default_response(gtk4::ResponseType::Accept);
+
+    if let Some(root) = widget.root() {
+        if let Ok(win) = root.downcast::<gtk4::Window>() {
+            dialog.set_transient_for(Some(&win));
+            dialog.set_modal(true);
+        }
+    }
+
+    let resume_btn = resume_btn.clone();
+    let stop_btn = stop_btn.clone();
+    dialog.connect_response(move |d, response| {
+        match response {
+            gtk4::ResponseType::Accept => resume_btn.emit_clicked(),
+            gtk4::ResponseType::Reject => stop_btn.emit_clicked(),
+            _ => {}
+        }
+        d.close();
+    });
+    dialog.show();
+}
@@ -4,8 +4,10 @@
 //! Organizes the UI into sub-modules for each panel, dialog,
 //! and functional area of the application.
 
+pub mod autosave;
 pub mod cam_tools;
 pub mod command_history;
+pub mod console_completion;
 pub mod designer;
 pub mod designer_canvas;
 pub mod designer_layers;
@@ -16,10 +18,12 @@ pub mod device_manager;
 pub mod editor;
 pub mod fast_shape_gallery;
 pub mod file_dialog;
+pub mod gcode_explain;
 pub mod help_browser;
 pub mod machine_control;
 pub mod nav_cube;
 pub mod osd_format;
+pub mod profile_templates;
 pub mod renderer_3d;
 pub mod settings;
 pub mod shaders;
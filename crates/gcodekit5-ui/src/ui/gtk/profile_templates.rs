@@ -0,0 +1,33 @@
+//! # Profile Templates
+//!
+//! Wraps programs produced by the designer and CAM tools in the active device
+//! profile's preamble and postamble before they reach the editor or a file.
+
+use gcodekit5_devicedb::{DeviceManager, TemplateValue};
+use std::collections::BTreeMap;
+
+/// `gcode` in the active profile's templates, with `safe_z` (mm) from the
+/// machine settings unless the profile sets its own clearance height.
+///
+/// A template that cannot be expanded is logged and the program is returned
+/// as generated.
+pub fn wrap_with_active_profile(
+    gcode: String,
+    device_manager: Option<&DeviceManager>,
+    safe_z: f64,
+) -> String {
+    let Some(profile) = device_manager.and_then(|manager| manager.get_active_profile()) else {
+        return gcode;
+    };
+    let mut extra = BTreeMap::new();
+    if profile.end_of_job.clearance_z.is_none() {
+        extra.insert("safe_z".to_string(), TemplateValue::Number(safe_z));
+    }
+    match profile.wrap_program(&gcode, &extra) {
+        Ok(wrapped) => wrapped,
+        Err(e) => {
+            tracing::warn!("Device templates of '{}' not applied: {}", profile.name, e);
+            gcode
+        }
+    }
+}
@@ -4,12 +4,18 @@
 //! Handles shader compilation, buffer management, and draw calls
 //! for toolpath, grid, axis, and stock visualization.
 
+use gcodekit5_core::{ArcMove, ArcPlane};
 use gcodekit5_visualizer::{GCodeCommand, Point3D, Visualizer};
 use glow::{
     Context, HasContext, NativeBuffer, NativeVertexArray, ARRAY_BUFFER, FLOAT, STATIC_DRAW,
 };
 use std::rc::Rc;
 
+/// Target length of the line segments an arc is drawn with
+const ARC_SEGMENT_LENGTH_MM: f64 = 0.5;
+const MIN_ARC_SEGMENTS: usize = 4;
+const MAX_ARC_SEGMENTS: usize = 100;
+
 pub struct RenderBuffers {
     pub vao: NativeVertexArray,
     pub vbo: NativeBuffer,
@@ -208,7 +214,7 @@ pub fn generate_vertex_data(visualizer: &Visualizer) -> (Vec<f32>, Vec<f32>) {
     let cut_color = [1.0, 1.0, 0.0, 1.0]; // Yellow (matches 2D)
     let arc_color = [1.0, 1.0, 0.0, 1.0]; // Yellow (matches 2D)
 
-    for cmd in visualizer.commands() {
+    for cmd in visualizer.visible_commands() {
         match cmd {
             GCodeCommand::Move {
                 from, to, rapid, ..
@@ -360,52 +366,25 @@ fn push_arc(
     clockwise: bool,
     color: [f32; 4],
 ) {
-    let radius = ((from.x - center.x).powi(2) + (from.y - center.y).powi(2)).sqrt();
-    let start_angle = (from.y - center.y).atan2(from.x - center.x);
-    let end_angle = (to.y - center.y).atan2(to.x - center.x);
-
-    let mut angle_diff = end_angle - start_angle;
-    use std::f32::consts::PI;
-
-    if clockwise {
-        if angle_diff > 0.0 {
-            angle_diff -= 2.0 * PI;
-        }
-    } else if angle_diff < 0.0 {
-        angle_diff += 2.0 * PI;
-    }
-
-    let segments = (angle_diff.abs() * radius * 2.0).ceil() as i32; // Adaptive segments
-    let segments = segments.clamp(4, 100);
-
-    let z_diff = to.z - from.z;
-
-    let mut prev_x = from.x;
-    let mut prev_y = from.y;
-    let mut prev_z = from.z;
-
-    for i in 1..=segments {
-        let t = i as f32 / segments as f32;
-        let angle = start_angle + angle_diff * t;
-
-        let x = center.x + radius * angle.cos();
-        let y = center.y + radius * angle.sin();
-        let z = from.z + z_diff * t;
-
-        // Line segment
-        vertices.push(prev_x);
-        vertices.push(prev_y);
-        vertices.push(prev_z);
-        vertices.extend_from_slice(&color);
-
-        vertices.push(x);
-        vertices.push(y);
-        vertices.push(z);
-        vertices.extend_from_slice(&color);
-
-        prev_x = x;
-        prev_y = y;
-        prev_z = z;
+    let arc = ArcMove {
+        start: [from.x as f64, from.y as f64, from.z as f64],
+        end: [to.x as f64, to.y as f64, to.z as f64],
+        center: [center.x as f64, center.y as f64, from.z as f64],
+        clockwise,
+        plane: ArcPlane::XY,
+    };
+
+    // Adaptive segments: ~0.5mm along the path, so helical Z travel adds segments too
+    let segments = arc
+        .segments_for(ARC_SEGMENT_LENGTH_MM)
+        .clamp(MIN_ARC_SEGMENTS, MAX_ARC_SEGMENTS);
+
+    // Z is interpolated across the sweep and the last point is the commanded end
+    let mut prev = *from;
+    for [x, y, z] in arc.expand(segments) {
+        let point = Point3D::new(x as f32, y as f32, z as f32);
+        push_line(vertices, &prev, &point, color);
+        prev = point;
     }
 }
 
@@ -0,0 +1,95 @@
+//! # Laser Burn Preview
+//!
+//! Save dialog for the simulated burn image of a laser program, with the
+//! resolution and material chosen in the dialog. Laser power is scaled to the
+//! controller's `$30` when it has been read, 1000 otherwise.
+
+use super::*;
+
+use crate::device_status;
+use crate::ui::gtk::file_dialog::{parent_window, save_dialog, show_error_dialog};
+use gcodekit5_visualizer::{render_burn_preview, BurnPreviewOptions, MaterialTint};
+
+const DPI_CHOICES: [(&str, &str); 4] = [
+    ("127", "127 DPI"),
+    ("254", "254 DPI"),
+    ("318", "318 DPI"),
+    ("508", "508 DPI"),
+];
+
+impl GcodeVisualizer {
+    /// Ask for a file and save the burn preview of the loaded program to it
+    pub(crate) fn show_burn_preview_dialog(
+        da: &DrawingArea,
+        settings: &Rc<SettingsController>,
+        gcode: &Shared<String>,
+    ) {
+        let parent = parent_window(da);
+        let dialog = save_dialog(&t!("Save Laser Burn Preview"), parent.as_ref());
+        dialog.set_current_name("burn-preview.png");
+
+        let filter = gtk4::FileFilter::new();
+        filter.set_name(Some("PNG (*.png)"));
+        filter.add_pattern("*.png");
+        dialog.add_filter(&filter);
+
+        dialog.add_choice("dpi", t!("Resolution"), &DPI_CHOICES);
+        dialog.set_choice("dpi", "254");
+        let grayscale = t!("Grayscale");
+        let wood = t!("Wood");
+        let cardboard = t!("Cardboard");
+        let leather = t!("Leather");
+        dialog.add_choice(
+            "material",
+            t!("Material"),
+            &[
+                ("grayscale", grayscale.as_str()),
+                ("wood", wood.as_str()),
+                ("cardboard", cardboard.as_str()),
+                ("leather", leather.as_str()),
+            ],
+        );
+        dialog.set_choice("material", "grayscale");
+
+        let settings = settings.clone();
+        let gcode = gcode.clone();
+        dialog.connect_response(move |dialog, resp| {
+            if resp == gtk4::ResponseType::Accept {
+                if let Some(path) = dialog.file().and_then(|file| file.path()) {
+                    let tint = match dialog.choice("material").as_deref() {
+                        Some("wood") => MaterialTint::Wood,
+                        Some("cardboard") => MaterialTint::Cardboard,
+                        Some("leather") => MaterialTint::Leather,
+                        _ => MaterialTint::Grayscale,
+                    };
+                    let defaults = BurnPreviewOptions::default();
+                    let options = BurnPreviewOptions {
+                        dpi: dialog
+                            .choice("dpi")
+                            .and_then(|dpi| dpi.parse().ok())
+                            .unwrap_or(defaults.dpi),
+                        max_power: device_status::get_grbl_setting_numeric(30)
+                            .filter(|max| *max > 0.0)
+                            .unwrap_or(defaults.max_power),
+                        block_delete: settings.persistence.borrow().config().machine.block_delete,
+                        tint,
+                        ..defaults
+                    };
+                    let result = render_burn_preview(&gcode.borrow(), &options)
+                        .and_then(|preview| preview.save(&path));
+                    if let Err(e) = result {
+                        tracing::error!("Failed to save burn preview to {}: {}", path.display(), e);
+                        show_error_dialog(
+                            &t!("Burn Preview Failed"),
+                            &e.to_string(),
+                            parent_window(dialog).as_ref(),
+                        );
+                    }
+                }
+            }
+            dialog.destroy();
+        });
+
+        dialog.show();
+    }
+}
@@ -8,15 +8,17 @@
 //! The visualizer uses `Rc<RefCell<>>` for state and must be accessed
 //! from the GTK main thread only.
 
+mod burn_preview;
 mod gl_loader;
 mod interaction;
 mod rendering;
+mod view_export;
 
 use gcodekit5_core::constants as core_constants;
 use gcodekit5_designer::stock_removal::{SimulationResult, StockMaterial};
 use gcodekit5_devicedb::DeviceManager;
 use gcodekit5_visualizer::visualizer::GCodeCommand;
-use gcodekit5_visualizer::{Camera3D, Visualizer};
+use gcodekit5_visualizer::{Camera3D, ProgramOperation, Visualizer};
 // use gcodekit5_designer::stock_removal::visualization::generate_2d_contours;
 use crate::t;
 use crate::ui::gtk::osd_format::format_zoom_center_cursor;
@@ -57,7 +59,7 @@ use gcodekit5_core::{shared, shared_none, thread_safe_none, Shared, SharedOption
 use gtk4::prelude::{BoxExt, ButtonExt, CheckButtonExt, WidgetExt};
 use gtk4::{
     accessible::Property as AccessibleProperty, gdk::ModifierType, Adjustment, Box, Button,
-    CheckButton, ComboBoxText, DrawingArea, EventControllerMotion, EventControllerScroll,
+    CheckButton, ComboBoxText, DrawingArea, Entry, EventControllerMotion, EventControllerScroll,
     EventControllerScrollFlags, Expander, GLArea, GestureDrag, Grid, Image, Label, ListBox,
     ListBoxRow, Orientation, Overlay, Paned, Revealer, Scrollbar, SelectionMode, Spinner, Stack,
     ToggleButton,
@@ -120,8 +122,16 @@ pub struct GcodeVisualizer {
     // Visibility toggles
     pub(crate) _show_rapid: CheckButton,
     pub(crate) _show_cut: CheckButton,
+    pub(crate) _show_block_deleted: CheckButton,
+    // Operations list
+    pub(crate) operations_list: ListBox,
+    pub(crate) operations_row: ListBoxRow,
+    pub(crate) operation_toggles: Shared<Vec<CheckButton>>,
+    // Last program passed to set_gcode, for the view export's time estimate
+    pub(crate) gcode_text: Shared<String>,
     pub(crate) _show_grid: CheckButton,
     pub(crate) _show_bounds: CheckButton,
+    pub(crate) _show_regions: CheckButton,
     pub(crate) _show_intensity: CheckButton,
     pub(crate) show_laser: CheckButton,
     pub(crate) show_stock_removal: CheckButton,
@@ -344,6 +354,11 @@ impl GcodeVisualizer {
             .label(t!("Show Cutting Moves"))
             .active(true)
             .build();
+        let show_block_deleted = CheckButton::builder()
+            .label(t!("Show Skipped Blocks (/)"))
+            .tooltip_text(t!("Draw optional blocks skipped by block delete, dimmed"))
+            .active(false)
+            .build();
         let show_grid = CheckButton::builder()
             .label(t!("Show Grid"))
             .active(true)
@@ -390,10 +405,53 @@ impl GcodeVisualizer {
         grid_spacing_row.append(&grid_spacing_label);
         grid_spacing_row.append(&grid_spacing_combo);
 
+        // Grid lines pass through the grid origin, world zero by default
+        let (grid_origin_x_mm, grid_origin_y_mm, show_grid_origin_init) = {
+            let persistence = settings_controller.persistence.borrow();
+            let ui = &persistence.config().ui;
+            (ui.grid_origin_x, ui.grid_origin_y, ui.show_grid_origin)
+        };
+        let grid_origin_row = Box::new(Orientation::Horizontal, 6);
+        let grid_origin_label = Label::new(Some(&t!("Grid origin")));
+        grid_origin_label.add_css_class("caption");
+        let grid_origin_x = Entry::builder()
+            .width_chars(6)
+            .tooltip_text(t!("Grid origin X"))
+            .text(gcodekit5_core::units::format_length(
+                grid_origin_x_mm as f32,
+                system,
+            ))
+            .build();
+        let grid_origin_y = Entry::builder()
+            .width_chars(6)
+            .tooltip_text(t!("Grid origin Y"))
+            .text(gcodekit5_core::units::format_length(
+                grid_origin_y_mm as f32,
+                system,
+            ))
+            .build();
+        let grid_origin_here = Button::builder()
+            .icon_name("find-location-symbolic")
+            .tooltip_text(t!("Set grid origin to the current position"))
+            .build();
+        grid_origin_row.append(&grid_origin_label);
+        grid_origin_row.append(&grid_origin_x);
+        grid_origin_row.append(&grid_origin_y);
+        grid_origin_row.append(&grid_origin_here);
+        let show_grid_origin = CheckButton::builder()
+            .label(t!("Show Grid Origin"))
+            .tooltip_text(t!("Mark and label the grid origin"))
+            .active(show_grid_origin_init)
+            .build();
+
         let show_bounds = CheckButton::builder()
             .label(t!("Show Machine Bounds"))
             .active(true)
             .build();
+        let show_regions = CheckButton::builder()
+            .label(t!("Show Work Regions"))
+            .active(true)
+            .build();
         let show_intensity = CheckButton::builder()
             .label(t!("Show Intensity"))
             .active(false)
@@ -443,6 +501,7 @@ impl GcodeVisualizer {
         toolpath_box.set_margin_bottom(6);
         toolpath_box.append(&show_rapid);
         toolpath_box.append(&show_cut);
+        toolpath_box.append(&show_block_deleted);
         toolpath_box.append(&show_laser);
 
         let toolpath_expander = Expander::builder()
@@ -456,6 +515,42 @@ impl GcodeVisualizer {
             sidebar_list.append(&row);
         }
 
+        // Operations (split at tool changes and operation comments)
+        let operations_list = ListBox::new();
+        operations_list.set_selection_mode(SelectionMode::None);
+        operations_list.add_css_class("boxed-list");
+        let show_all_ops_btn = Button::builder()
+            .label(t!("Show All"))
+            .hexpand(true)
+            .build();
+        let fit_visible_btn = Button::builder()
+            .label(t!("Fit to Visible"))
+            .hexpand(true)
+            .build();
+        let operations_buttons = Box::new(Orientation::Horizontal, 6);
+        operations_buttons.append(&show_all_ops_btn);
+        operations_buttons.append(&fit_visible_btn);
+
+        let operations_box = Box::new(Orientation::Vertical, 6);
+        operations_box.set_margin_start(6);
+        operations_box.set_margin_end(6);
+        operations_box.set_margin_top(6);
+        operations_box.set_margin_bottom(6);
+        operations_box.append(&operations_list);
+        operations_box.append(&operations_buttons);
+
+        let operations_expander = Expander::builder()
+            .label(t!("Operations"))
+            .expanded(true)
+            .child(&operations_box)
+            .build();
+        let operations_row = ListBoxRow::new();
+        operations_row.set_child(Some(&operations_expander));
+        operations_row.set_visible(false);
+        sidebar_list.append(&operations_row);
+        let operation_toggles: Shared<Vec<CheckButton>> = shared(Vec::new());
+        let gcode_text: Shared<String> = shared(String::new());
+
         let guides_box = Box::new(Orientation::Vertical, 4);
         guides_box.set_margin_start(6);
         guides_box.set_margin_end(6);
@@ -463,7 +558,10 @@ impl GcodeVisualizer {
         guides_box.set_margin_bottom(6);
         guides_box.append(&show_grid);
         guides_box.append(&grid_spacing_row);
+        guides_box.append(&grid_origin_row);
+        guides_box.append(&show_grid_origin);
         guides_box.append(&show_bounds);
+        guides_box.append(&show_regions);
 
         let guides_expander = Expander::builder()
             .label(t!("Guides"))
@@ -730,7 +828,74 @@ impl GcodeVisualizer {
 
         // Initialize Visualizer logic
         let visualizer = shared(Visualizer::new());
+        visualizer.borrow_mut().set_block_delete(
+            settings_controller
+                .persistence
+                .borrow()
+                .config()
+                .machine
+                .block_delete,
+        );
+        {
+            // Applies from the next parse of the program
+            let visualizer = visualizer.clone();
+            settings_controller.on_setting_changed(move |key, value| {
+                if key == "block_delete" {
+                    visualizer.borrow_mut().set_block_delete(value == "true");
+                }
+            });
+        }
         let current_pos = shared((0.0f32, 0.0f32, 0.0f32));
+
+        // Persist grid origin changes and redraw
+        {
+            let save_origin = {
+                let settings = settings_controller.clone();
+                let drawing_area = drawing_area.clone();
+                let entry_x = grid_origin_x.clone();
+                let entry_y = grid_origin_y.clone();
+                let show = show_grid_origin.clone();
+                Rc::new(move || {
+                    let parse = |entry: &Entry| {
+                        gcodekit5_core::units::parse_length(&entry.text(), system)
+                            .ok()
+                            .filter(|mm| mm.is_finite())
+                    };
+                    let mut p = settings.persistence.borrow_mut();
+                    let ui = &mut p.config_mut().ui;
+                    if let Some(x) = parse(&entry_x) {
+                        ui.grid_origin_x = x as f64;
+                    }
+                    if let Some(y) = parse(&entry_y) {
+                        ui.grid_origin_y = y as f64;
+                    }
+                    ui.show_grid_origin = show.is_active();
+                    if let Ok(path) = SettingsManager::config_file_path() {
+                        let _ = SettingsManager::ensure_config_dir();
+                        let _ = p.save_to_file(&path);
+                    }
+                    drawing_area.queue_draw();
+                })
+            };
+            for entry in [&grid_origin_x, &grid_origin_y] {
+                let save_origin = save_origin.clone();
+                entry.connect_changed(move |_| save_origin());
+            }
+            {
+                let save_origin = save_origin.clone();
+                show_grid_origin.connect_toggled(move |_| save_origin());
+            }
+            let current_pos = current_pos.clone();
+            let entry_x = grid_origin_x.clone();
+            let entry_y = grid_origin_y.clone();
+            grid_origin_here.connect_clicked(move |_| {
+                let (x, y, _) = *current_pos.borrow();
+                entry_x.set_text(&gcodekit5_core::units::format_length(x, system));
+                entry_y.set_text(&gcodekit5_core::units::format_length(y, system));
+                save_origin();
+            });
+        }
+
         let camera = shared(Camera3D::default());
         let renderer_state = shared_none();
         let is_updating_3d = shared(false);
@@ -1193,8 +1358,10 @@ impl GcodeVisualizer {
             let device_mgr_menu = device_manager.clone();
             let show_grid_menu = show_grid.clone();
             let show_bounds_menu = show_bounds.clone();
+            let show_regions_menu = show_regions.clone();
             let show_rapid_menu = show_rapid.clone();
             let show_cut_menu = show_cut.clone();
+            let gcode_menu = gcode_text.clone();
             right_click.connect_pressed(move |_g, _n, x, y| {
                 let menu = Popover::new();
                 menu.set_parent(&da_menu);
@@ -1297,6 +1464,38 @@ impl GcodeVisualizer {
                         }),
                     );
                 }
+                {
+                    let da = da_menu.clone();
+                    let vis = vis_menu.clone();
+                    let settings = settings_menu.clone();
+                    let gcode = gcode_menu.clone();
+                    let show_rapid = show_rapid_menu.clone();
+                    let show_cut = show_cut_menu.clone();
+                    add_item(
+                        "Export View (SVG/PDF)…",
+                        std::boxed::Box::new(move || {
+                            Self::show_export_view_dialog(
+                                &da,
+                                &vis,
+                                &settings,
+                                &gcode,
+                                show_cut.is_active(),
+                                show_rapid.is_active(),
+                            );
+                        }),
+                    );
+                }
+                {
+                    let da = da_menu.clone();
+                    let settings = settings_menu.clone();
+                    let gcode = gcode_menu.clone();
+                    add_item(
+                        "Save Laser Burn Preview (PNG)…",
+                        std::boxed::Box::new(move || {
+                            Self::show_burn_preview_dialog(&da, &settings, &gcode);
+                        }),
+                    );
+                }
 
                 vbox.append(&Separator::new(Orientation::Horizontal));
 
@@ -1329,6 +1528,20 @@ impl GcodeVisualizer {
                     });
                     vbox.append(&btn);
                 }
+                {
+                    let btn = Button::builder()
+                        .label("Toggle Work Regions")
+                        .has_frame(false)
+                        .halign(gtk4::Align::Start)
+                        .build();
+                    let menu = menu.clone();
+                    let cb = show_regions_menu.clone();
+                    btn.connect_clicked(move |_| {
+                        menu.popdown();
+                        cb.set_active(!cb.is_active());
+                    });
+                    vbox.append(&btn);
+                }
                 {
                     let btn = Button::builder()
                         .label("Toggle Rapid Moves")
@@ -1745,8 +1958,10 @@ impl GcodeVisualizer {
         let render_cache_draw = shared(RenderCache::default());
         let show_rapid_draw = show_rapid.clone();
         let show_cut_draw = show_cut.clone();
+        let show_block_deleted_draw = show_block_deleted.clone();
         let show_grid_draw = show_grid.clone();
         let show_bounds_draw = show_bounds.clone();
+        let show_regions_draw = show_regions.clone();
         let show_intensity_draw = show_intensity.clone();
         let show_laser_draw = show_laser.clone();
         let show_stock_removal_draw = show_stock_removal.clone();
@@ -1756,6 +1971,7 @@ impl GcodeVisualizer {
         let device_manager_draw = device_manager.clone();
         let current_pos_draw = current_pos.clone();
         let grid_spacing_draw = grid_spacing_mm.clone();
+        let show_grid_origin_draw = show_grid_origin.clone();
         let settings_draw = settings_controller.clone();
 
         drawing_area.set_draw_func(move |da, cr, width, height| {
@@ -1766,7 +1982,20 @@ impl GcodeVisualizer {
             let config = settings_draw.persistence.borrow();
             let grid_major_width = config.config().ui.grid_major_line_width;
             let grid_minor_width = config.config().ui.grid_minor_line_width;
+            let grid_origin = (
+                config.config().ui.grid_origin_x,
+                config.config().ui.grid_origin_y,
+            );
+            let system = config.config().ui.measurement_system;
             drop(config);
+            let grid_origin_label = show_grid_origin_draw.is_active().then(|| {
+                format!(
+                    "{}, {} {}",
+                    gcodekit5_core::units::format_length(grid_origin.0 as f32, system),
+                    gcodekit5_core::units::format_length(grid_origin.1 as f32, system),
+                    gcodekit5_core::units::get_unit_label(system)
+                )
+            });
             Self::draw(
                 cr,
                 &vis,
@@ -1775,8 +2004,10 @@ impl GcodeVisualizer {
                 height as f64,
                 show_rapid_draw.is_active(),
                 show_cut_draw.is_active(),
+                show_block_deleted_draw.is_active(),
                 show_grid_draw.is_active(),
                 show_bounds_draw.is_active(),
+                show_regions_draw.is_active(),
                 show_intensity_draw.is_active(),
                 show_laser_draw.is_active(),
                 show_stock_removal_draw.is_active(),
@@ -1786,6 +2017,8 @@ impl GcodeVisualizer {
                 pos,
                 &device_manager_draw,
                 grid_spacing_draw.get(),
+                grid_origin,
+                grid_origin_label.as_deref(),
                 grid_major_width,
                 grid_minor_width,
                 &style,
@@ -1837,6 +2070,27 @@ impl GcodeVisualizer {
             }
         });
 
+        {
+            let toggles = operation_toggles.clone();
+            show_all_ops_btn.connect_clicked(move |_| {
+                for toggle in toggles.borrow().iter() {
+                    toggle.set_active(true);
+                }
+            });
+        }
+        {
+            let vis_fit = visualizer.clone();
+            let da_fit = drawing_area.clone();
+            let update_status = update_ui.clone();
+            fit_visible_btn.connect_clicked(move |_| {
+                let width = da_fit.width() as f32;
+                let height = da_fit.height() as f32;
+                vis_fit.borrow_mut().fit_to_visible(width, height);
+                update_status();
+                da_fit.queue_draw();
+            });
+        }
+
         // Fit to Device button
         if let Some(device_mgr) = device_manager.clone() {
             let vis_fit_dev = visualizer.clone();
@@ -1885,6 +2139,10 @@ impl GcodeVisualizer {
             gl_update.queue_render();
         });
         let da_update = drawing_area.clone();
+        show_block_deleted.connect_toggled(move |_| {
+            da_update.queue_draw();
+        });
+        let da_update = drawing_area.clone();
         let gl_update = gl_area.clone();
         show_grid.connect_toggled(move |_| {
             da_update.queue_draw();
@@ -1897,6 +2155,10 @@ impl GcodeVisualizer {
             gl_update.queue_render();
         });
         let da_update = drawing_area.clone();
+        show_regions.connect_toggled(move |_| {
+            da_update.queue_draw();
+        });
+        let da_update = drawing_area.clone();
         let gl_update = gl_area.clone();
         show_intensity.connect_toggled(move |_| {
             da_update.queue_draw();
@@ -2678,8 +2940,14 @@ impl GcodeVisualizer {
             render_cache: shared(RenderCache::default()),
             _show_rapid: show_rapid,
             _show_cut: show_cut,
+            _show_block_deleted: show_block_deleted,
+            operations_list,
+            operations_row,
+            operation_toggles,
+            gcode_text,
             _show_grid: show_grid,
             _show_bounds: show_bounds,
+            _show_regions: show_regions,
             _show_intensity: show_intensity,
             show_laser,
             show_stock_removal,
@@ -2707,9 +2975,66 @@ impl GcodeVisualizer {
         }
     }
 
+    /// Rebuild the Operations list after a parse; hidden for single-operation programs
+    fn populate_operations(&self, operations: &[ProgramOperation]) {
+        while let Some(row) = self.operations_list.first_child() {
+            self.operations_list.remove(&row);
+        }
+        self.operation_toggles.borrow_mut().clear();
+        self.operations_row.set_visible(operations.len() > 1);
+
+        for (index, operation) in operations.iter().enumerate() {
+            let toggle = CheckButton::builder()
+                .label(&operation.name)
+                .active(operation.visible)
+                .hexpand(true)
+                .tooltip_text(format!("{} {}", t!("Starts at line"), operation.line))
+                .build();
+            let solo = Button::builder()
+                .icon_name("find-location-symbolic")
+                .tooltip_text(t!("Show only this operation"))
+                .css_classes(vec!["flat"])
+                .build();
+            solo.update_property(&[AccessibleProperty::Label(&t!("Show only this operation"))]);
+
+            {
+                let vis = self.visualizer.clone();
+                let da = self.drawing_area.clone();
+                let gl = self.gl_area.clone();
+                toggle.connect_toggled(move |toggle| {
+                    vis.borrow_mut()
+                        .set_operation_visible(index, toggle.is_active());
+                    da.queue_draw();
+                    gl.queue_render();
+                });
+            }
+            {
+                let toggles = self.operation_toggles.clone();
+                solo.connect_clicked(move |_| {
+                    for (i, toggle) in toggles.borrow().iter().enumerate() {
+                        toggle.set_active(i == index);
+                    }
+                });
+            }
+
+            let row_box = Box::new(Orientation::Horizontal, 6);
+            row_box.set_margin_start(6);
+            row_box.set_margin_end(6);
+            row_box.append(&toggle);
+            row_box.append(&solo);
+            let row = ListBoxRow::new();
+            row.set_activatable(false);
+            row.set_child(Some(&row_box));
+            self.operations_list.append(&row);
+            self.operation_toggles.borrow_mut().push(toggle);
+        }
+    }
+
     pub fn set_gcode(&self, gcode: &str) {
         let mut vis = self.visualizer.borrow_mut();
         vis.parse_gcode(gcode);
+        *self.gcode_text.borrow_mut() = gcode.to_string();
+        self.populate_operations(vis.operations());
 
         // Phase 4: Invalidate render cache when G-code changes
         let mut cache = self.render_cache.borrow_mut();
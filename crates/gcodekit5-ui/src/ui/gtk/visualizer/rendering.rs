@@ -8,9 +8,9 @@ use super::*;
 
 use gcodekit5_core::constants as core_constants;
 use gcodekit5_designer::stock_removal::{SimulationResult, StockMaterial};
-use gcodekit5_devicedb::DeviceManager;
+use gcodekit5_devicedb::{DeviceManager, WorkRegion};
 use gcodekit5_visualizer::visualizer::GCodeCommand;
-use gcodekit5_visualizer::Visualizer;
+use gcodekit5_visualizer::{grid_lines, Visualizer};
 use std::sync::Arc;
 
 impl GcodeVisualizer {
@@ -24,8 +24,10 @@ impl GcodeVisualizer {
         height: f64,
         show_rapid: bool,
         show_cut: bool,
+        show_block_deleted: bool,
         show_grid: bool,
         show_bounds: bool,
+        show_regions: bool,
         show_intensity: bool,
         show_laser: bool,
         show_stock_removal: bool,
@@ -35,6 +37,8 @@ impl GcodeVisualizer {
         current_pos: (f32, f32, f32),
         device_manager: &Option<Arc<DeviceManager>>,
         grid_spacing_mm: f64,
+        grid_origin: (f64, f64),
+        grid_origin_label: Option<&str>,
         grid_major_line_width: f64,
         grid_minor_line_width: f64,
         style_context: &gtk4::StyleContext,
@@ -45,6 +49,9 @@ impl GcodeVisualizer {
 
         let mut hasher = DefaultHasher::new();
         vis.commands().len().hash(&mut hasher);
+        for operation in vis.operations() {
+            operation.visible.hash(&mut hasher);
+        }
         show_intensity.hash(&mut hasher);
         let new_hash = hasher.finish();
         let fg_color = style_context.color();
@@ -91,10 +98,14 @@ impl GcodeVisualizer {
                 cr,
                 vis,
                 grid_spacing_mm.max(0.1),
+                grid_origin,
                 &fg_color,
                 grid_major_line_width,
                 grid_minor_line_width,
             );
+            if let Some(label) = grid_origin_label {
+                Self::draw_grid_origin(cr, vis, grid_origin, label, &warning_color);
+            }
         }
 
         // Draw Machine Bounds
@@ -122,6 +133,13 @@ impl GcodeVisualizer {
             }
         }
 
+        // Draw Work Regions
+        if show_regions {
+            if let Some(profile) = device_manager.as_ref().and_then(|m| m.get_active_profile()) {
+                Self::draw_work_regions(cr, vis, &profile.work_regions);
+            }
+        }
+
         // Draw Origin Axes (Full World Extent)
         let extent = core_constants::WORLD_EXTENT_MM;
         cr.set_line_width(1.0 / vis.zoom_scale as f64);
@@ -189,7 +207,7 @@ impl GcodeVisualizer {
             );
 
             let mut line_counter = 0u32;
-            for cmd in vis.commands() {
+            for cmd in vis.visible_commands() {
                 if let GCodeCommand::Move {
                     from,
                     to,
@@ -245,7 +263,7 @@ impl GcodeVisualizer {
                     cache.total_lines = 0;
                     cache.cut_lines = 0;
 
-                    for cmd in vis.commands() {
+                    for cmd in vis.visible_commands() {
                         cache.total_lines += 1;
                         if let GCodeCommand::Move {
                             from,
@@ -324,7 +342,7 @@ impl GcodeVisualizer {
                 }
 
                 // Draw arcs separately (usually fewer)
-                for cmd in vis.commands() {
+                for cmd in vis.visible_commands() {
                     if let GCodeCommand::Arc {
                         from,
                         to,
@@ -390,7 +408,7 @@ impl GcodeVisualizer {
                 );
 
                 let mut line_counter = 0u32;
-                for cmd in vis.commands() {
+                for cmd in vis.visible_commands() {
                     match cmd {
                         GCodeCommand::Move {
                             from,
@@ -480,6 +498,57 @@ impl GcodeVisualizer {
             }
         }
 
+        // Optional blocks skipped by block delete, dimmed where they would have run
+        if show_block_deleted && lod_level < 3 && !vis.block_deleted_commands().is_empty() {
+            cr.new_path();
+            cr.set_source_rgba(
+                fg_color.red() as f64,
+                fg_color.green() as f64,
+                fg_color.blue() as f64,
+                0.25,
+            );
+            for cmd in vis.block_deleted_commands() {
+                match cmd {
+                    GCodeCommand::Move { from, to, .. } => {
+                        cr.move_to(from.x as f64, from.y as f64);
+                        cr.line_to(to.x as f64, to.y as f64);
+                    }
+                    GCodeCommand::Arc {
+                        from,
+                        to,
+                        center,
+                        clockwise,
+                        ..
+                    } => {
+                        let radius =
+                            ((from.x - center.x).powi(2) + (from.y - center.y).powi(2)).sqrt();
+                        let start_angle = (from.y - center.y).atan2(from.x - center.x) as f64;
+                        let end_angle = (to.y - center.y).atan2(to.x - center.x) as f64;
+                        cr.new_sub_path();
+                        if *clockwise {
+                            cr.arc_negative(
+                                center.x as f64,
+                                center.y as f64,
+                                radius as f64,
+                                start_angle,
+                                end_angle,
+                            );
+                        } else {
+                            cr.arc(
+                                center.x as f64,
+                                center.y as f64,
+                                radius as f64,
+                                start_angle,
+                                end_angle,
+                            );
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            let _ = cr.stroke();
+        }
+
         // Phase 3 + 4: LOD Level 3 (Minimal) - Draw bounding box only at extreme zoom out
         if lod_level == 3 && show_cut {
             if cache.cutting_bounds.is_none() && cache.needs_rebuild(new_hash) {
@@ -491,7 +560,7 @@ impl GcodeVisualizer {
                 let mut bounds_max_z = f32::MIN;
                 let mut has_bounds = false;
 
-                for cmd in vis.commands() {
+                for cmd in vis.visible_commands() {
                     match cmd {
                         GCodeCommand::Move {
                             from,
@@ -580,17 +649,67 @@ impl GcodeVisualizer {
         let _ = cr.restore();
     }
 
+    /// Fill and outline each region of the device profile, with its name at a
+    /// fixed screen size. No-go regions get a dashed outline.
+    pub(crate) fn draw_work_regions(
+        cr: &gtk4::cairo::Context,
+        vis: &Visualizer,
+        regions: &[WorkRegion],
+    ) {
+        let zoom = vis.zoom_scale as f64;
+        for region in regions {
+            let outline = region.outline();
+            let Some((first, rest)) = outline.split_first() else {
+                continue;
+            };
+            let [r, g, b] = region.rgb();
+
+            cr.new_path();
+            cr.move_to(first[0], first[1]);
+            for point in rest {
+                cr.line_to(point[0], point[1]);
+            }
+            cr.close_path();
+            cr.set_source_rgba(r, g, b, if region.no_go { 0.25 } else { 0.15 });
+            let _ = cr.fill_preserve();
+            cr.set_source_rgba(r, g, b, 0.9);
+            cr.set_line_width(2.0 / zoom);
+            if region.no_go {
+                cr.set_dash(&[6.0 / zoom, 4.0 / zoom], 0.0);
+            }
+            let _ = cr.stroke();
+            cr.set_dash(&[], 0.0);
+
+            if region.name.is_empty() {
+                continue;
+            }
+            let [x, y] = region.label_position();
+            let _ = cr.save();
+            cr.translate(x, y);
+            // Back to screen pixels, upright
+            cr.scale(1.0 / zoom, -1.0 / zoom);
+            cr.set_font_size(12.0);
+            if let Ok(extents) = cr.text_extents(&region.name) {
+                cr.move_to(-extents.width() / 2.0, extents.height() / 2.0);
+            }
+            cr.set_source_rgba(r, g, b, 1.0);
+            let _ = cr.show_text(&region.name);
+            let _ = cr.restore();
+        }
+    }
+
     pub(crate) fn draw_grid(
         cr: &gtk4::cairo::Context,
         vis: &Visualizer,
         grid_size: f64,
+        origin: (f64, f64),
         fg_color: &gtk4::gdk::RGBA,
         major_line_width: f64,
         minor_line_width: f64,
     ) {
         let range = core_constants::WORLD_EXTENT_MM;
-
-        let minor_spacing = grid_size / 5.0;
+        let (major_x, minor_x) = grid_lines(-range, range, grid_size, origin.0);
+        let (major_y, minor_y) = grid_lines(-range, range, grid_size, origin.1);
 
         // Minor grid lines (lighter)
         cr.set_source_rgba(
@@ -600,25 +719,14 @@ impl GcodeVisualizer {
             0.2,
         );
         cr.set_line_width(minor_line_width / vis.zoom_scale as f64);
-
-        let mut x = -range;
-        while x <= range {
-            if ((x / grid_size).round() - x / grid_size).abs() > 0.01 {
-                cr.move_to(x, -range);
-                cr.line_to(x, range);
-            }
-            x += minor_spacing;
+        for &x in &minor_x {
+            cr.move_to(x, -range);
+            cr.line_to(x, range);
         }
-
-        let mut y = -range;
-        while y <= range {
-            if ((y / grid_size).round() - y / grid_size).abs() > 0.01 {
-                cr.move_to(-range, y);
-                cr.line_to(range, y);
-            }
-            y += minor_spacing;
+        for &y in &minor_y {
+            cr.move_to(-range, y);
+            cr.line_to(range, y);
         }
-
         let _ = cr.stroke();
 
         // Major grid lines (darker)
@@ -629,22 +737,53 @@ impl GcodeVisualizer {
             0.4,
         );
         cr.set_line_width(major_line_width / vis.zoom_scale as f64);
-
-        let mut x = -range;
-        while x <= range {
+        for &x in &major_x {
             cr.move_to(x, -range);
             cr.line_to(x, range);
-            x += grid_size;
         }
-
-        let mut y = -range;
-        while y <= range {
+        for &y in &major_y {
             cr.move_to(-range, y);
             cr.line_to(range, y);
-            y += grid_size;
         }
+        let _ = cr.stroke();
+    }
 
+    /// Marker and coordinate label at the grid origin
+    pub(crate) fn draw_grid_origin(
+        cr: &gtk4::cairo::Context,
+        vis: &Visualizer,
+        origin: (f64, f64),
+        label: &str,
+        color: &gtk4::gdk::RGBA,
+    ) {
+        let zoom = vis.zoom_scale as f64;
+        let (x, y) = origin;
+        // Sizes in screen pixels
+        let arm = 12.0 / zoom;
+
+        cr.set_source_rgba(
+            color.red() as f64,
+            color.green() as f64,
+            color.blue() as f64,
+            0.9,
+        );
+        cr.set_line_width(2.0 / zoom);
+        cr.move_to(x - arm, y);
+        cr.line_to(x + arm, y);
+        cr.move_to(x, y - arm);
+        cr.line_to(x, y + arm);
         let _ = cr.stroke();
+        cr.arc(x, y, arm / 2.0, 0.0, std::f64::consts::TAU);
+        let _ = cr.stroke();
+
+        let _ = cr.save();
+        cr.translate(x, y);
+        // Back to screen pixels, upright
+        cr.scale(1.0 / zoom, -1.0 / zoom);
+        cr.set_font_size(11.0);
+        cr.move_to(8.0, -8.0);
+        let _ = cr.show_text(label);
+        let _ = cr.restore();
     }
 
     pub(crate) fn draw_stock_removal_cached(
@@ -0,0 +1,98 @@
+//! # Visualizer View Export
+//!
+//! Save dialog for exporting the visible 2D toolpath to an SVG or PDF job
+//! sheet, with paper size and orientation chosen in the dialog.
+
+use super::*;
+
+use crate::ui::gtk::file_dialog::{parent_window, save_dialog, show_error_dialog};
+use gcodekit5_camtools::StatsCalculator;
+use gcodekit5_visualizer::{export_view, PaperOrientation, PaperSize, ViewExportOptions};
+
+const PAPER_CHOICES: [(&str, &str); 4] = [
+    ("a4", "A4"),
+    ("a3", "A3"),
+    ("letter", "Letter"),
+    ("legal", "Legal"),
+];
+
+impl GcodeVisualizer {
+    /// Ask for a file and export the visible toolpath to it
+    pub(crate) fn show_export_view_dialog(
+        da: &DrawingArea,
+        vis: &Shared<Visualizer>,
+        settings: &Rc<SettingsController>,
+        gcode: &Shared<String>,
+        show_cuts: bool,
+        show_rapids: bool,
+    ) {
+        let parent = parent_window(da);
+        let dialog = save_dialog(&t!("Export View"), parent.as_ref());
+        dialog.set_current_name("toolpath.pdf");
+
+        let filter = gtk4::FileFilter::new();
+        filter.set_name(Some("PDF / SVG (*.pdf, *.svg)"));
+        filter.add_pattern("*.pdf");
+        filter.add_pattern("*.svg");
+        dialog.add_filter(&filter);
+
+        dialog.add_choice("paper", t!("Paper"), &PAPER_CHOICES);
+        dialog.set_choice("paper", "a4");
+        let portrait = t!("Portrait");
+        let landscape = t!("Landscape");
+        dialog.add_choice(
+            "orientation",
+            t!("Orientation"),
+            &[
+                ("landscape", landscape.as_str()),
+                ("portrait", portrait.as_str()),
+            ],
+        );
+        dialog.set_choice("orientation", "landscape");
+
+        let vis = vis.clone();
+        let settings = settings.clone();
+        let gcode = gcode.clone();
+        dialog.connect_response(move |dialog, resp| {
+            if resp == gtk4::ResponseType::Accept {
+                if let Some(path) = dialog.file().and_then(|file| file.path()) {
+                    let paper = match dialog.choice("paper").as_deref() {
+                        Some("a3") => PaperSize::A3,
+                        Some("letter") => PaperSize::Letter,
+                        Some("legal") => PaperSize::Legal,
+                        _ => PaperSize::A4,
+                    };
+                    let orientation = match dialog.choice("orientation").as_deref() {
+                        Some("portrait") => PaperOrientation::Portrait,
+                        _ => PaperOrientation::Landscape,
+                    };
+                    let lines: Vec<String> = gcode.borrow().lines().map(str::to_string).collect();
+                    let estimate = StatsCalculator::calculate(&lines).estimated_time_secs;
+                    let options = ViewExportOptions {
+                        paper,
+                        orientation,
+                        title: path
+                            .file_stem()
+                            .map(|stem| stem.to_string_lossy().into_owned())
+                            .unwrap_or_default(),
+                        show_cuts,
+                        show_rapids,
+                        units: settings.persistence.borrow().config().ui.measurement_system,
+                        estimated_time_secs: (estimate > 0.0).then_some(estimate),
+                    };
+                    if let Err(e) = export_view(&vis.borrow(), &path, &options) {
+                        tracing::error!("Failed to export view to {}: {}", path.display(), e);
+                        show_error_dialog(
+                            &t!("Export Failed"),
+                            &e.to_string(),
+                            parent_window(dialog).as_ref(),
+                        );
+                    }
+                }
+            }
+            dialog.destroy();
+        });
+
+        dialog.show();
+    }
+}
@@ -0,0 +1,368 @@
+//! G-code line explanations
+//!
+//! [`explain_line`] splits a single line into its words and says what each one
+//! means, for tooltips and help aimed at people who do not know every code by
+//! heart. The meanings are structured rather than text so the UI can word and
+//! translate them. A word that only makes sense with another one on the line
+//! is explained with it: `M3 S1000` is one spindle-on entry and `G4 P2` one
+//! dwell.
+
+use gcodekit5_core::gcode::split_block_delete;
+use serde::{Deserialize, Serialize};
+
+/// A G or M code the explanations know
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum KnownCode {
+    /// G0
+    RapidMove,
+    /// G1
+    LinearMove,
+    /// G2
+    ArcClockwise,
+    /// G3
+    ArcCounterClockwise,
+    /// G4, with the P word's seconds when present
+    Dwell(Option<f64>),
+    /// G10
+    SetCoordinateData,
+    /// G17
+    PlaneXy,
+    /// G18
+    PlaneZx,
+    /// G19
+    PlaneYz,
+    /// G20
+    Inches,
+    /// G21
+    Millimeters,
+    /// G28
+    GoHome,
+    /// G30
+    GoSecondaryHome,
+    /// G38.2 to G38.5
+    Probe {
+        toward: bool,
+        error_if_no_contact: bool,
+    },
+    /// G40
+    CutterCompensationOff,
+    /// G43.1
+    ToolLengthOffset,
+    /// G49
+    ToolLengthOffsetCancel,
+    /// G53
+    MachineCoordinates,
+    /// G54 to G59, as 1 to 6
+    WorkCoordinateSystem(u8),
+    /// G80
+    CannedCycleCancel,
+    /// G81
+    DrillCycle,
+    /// G83
+    PeckDrillCycle,
+    /// G90
+    AbsoluteDistance,
+    /// G91
+    IncrementalDistance,
+    /// G92
+    SetWorkOffset,
+    /// G93
+    InverseTimeFeed,
+    /// G94
+    UnitsPerMinuteFeed,
+    /// G95
+    UnitsPerRevolutionFeed,
+    /// M0
+    ProgramPause,
+    /// M1
+    OptionalStop,
+    /// M2
+    ProgramEnd,
+    /// M3, with the S word's speed when present
+    SpindleClockwise(Option<f64>),
+    /// M4, with the S word's speed when present
+    SpindleCounterClockwise(Option<f64>),
+    /// M5
+    SpindleStop,
+    /// M6, with the T word's tool when present
+    ToolChange(Option<f64>),
+    /// M7
+    MistCoolantOn,
+    /// M8
+    FloodCoolantOn,
+    /// M9
+    CoolantOff,
+    /// M30
+    ProgramEndAndRewind,
+}
+
+/// What a word, or a group of words, means
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum WordMeaning {
+    Code(KnownCode),
+    /// X, Y, Z, A, B or C: target position on an axis
+    Axis {
+        axis: char,
+        value: f64,
+    },
+    /// I, J or K: arc centre offset along X, Y or Z
+    ArcCenterOffset {
+        axis: char,
+        value: f64,
+    },
+    /// R: arc radius, or the retract height in a canned cycle
+    Radius(f64),
+    /// F
+    FeedRate(f64),
+    /// S
+    SpindleSpeed(f64),
+    /// T: tool to select
+    Tool(f64),
+    /// N
+    LineNumber(f64),
+    /// P, Q or L away from a code that gives them a meaning
+    Parameter {
+        letter: char,
+        value: f64,
+    },
+    /// A code or letter the explanations do not know
+    Unknown,
+}
+
+/// One explained entry of a line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExplainedWord {
+    /// The words as written, upper-cased, e.g. `G1` or `M3 S1000`
+    pub text: String,
+    pub meaning: WordMeaning,
+}
+
+/// Explanation of a whole line
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LineExplanation {
+    /// The line starts with `/` and is skipped while block delete is on
+    pub block_delete: bool,
+    pub words: Vec<ExplainedWord>,
+    /// Comment text, without the parentheses or `;`
+    pub comment: Option<String>,
+}
+
+impl LineExplanation {
+    /// Whether there is nothing to explain
+    pub fn is_empty(&self) -> bool {
+        !self.block_delete && self.words.is_empty() && self.comment.is_none()
+    }
+}
+
+/// Explain each word of a G-code line
+///
+/// Comments are collected rather than explained, and a word whose letter is
+/// not followed by a number, or whose code is not known, is explained as
+/// [`WordMeaning::Unknown`].
+pub fn explain_line(line: &str) -> LineExplanation {
+    let (code, block_delete) = split_block_delete(line);
+    let (code, comment) = split_comment(code);
+
+    let words = tokenize(&code);
+    let word = |letter: char| words.iter().find(|(l, _, _)| *l == letter);
+    let value_of = |letter: char| word(letter).and_then(|(_, _, value)| *value);
+
+    // Codes with the letter of the word each takes along, if any
+    let codes: Vec<Option<(KnownCode, Option<char>)>> = words
+        .iter()
+        .map(|(letter, _, value)| {
+            let known = match (letter, value) {
+                ('G', Some(value)) => g_code(*value, value_of('P')),
+                ('M', Some(value)) => m_code(*value, value_of('S'), value_of('T')),
+                _ => None,
+            }?;
+            let paired = match known {
+                KnownCode::SpindleClockwise(_) | KnownCode::SpindleCounterClockwise(_) => Some('S'),
+                KnownCode::Dwell(_) => Some('P'),
+                KnownCode::ToolChange(_) => Some('T'),
+                _ => None,
+            };
+            Some((known, paired.filter(|&p| word(p).is_some())))
+        })
+        .collect();
+    let taken: Vec<char> = codes.iter().flatten().filter_map(|(_, p)| *p).collect();
+
+    let mut explained = Vec::new();
+    for ((letter, text, value), code) in words.iter().zip(&codes) {
+        if let Some((known, paired)) = code {
+            let mut text = text.clone();
+            if let Some((_, paired_text, _)) = paired.and_then(word) {
+                text = format!("{} {}", text, paired_text);
+            }
+            explained.push(ExplainedWord {
+                text,
+                meaning: WordMeaning::Code(*known),
+            });
+            continue;
+        }
+        if taken.contains(letter) {
+            continue;
+        }
+        let meaning = match (letter, *value) {
+            (_, None) | ('G' | 'M', _) => WordMeaning::Unknown,
+            ('X' | 'Y' | 'Z' | 'A' | 'B' | 'C', Some(value)) => WordMeaning::Axis {
+                axis: *letter,
+                value,
+            },
+            ('I' | 'J' | 'K', Some(value)) => WordMeaning::ArcCenterOffset {
+                axis: match letter {
+                    'I' => 'X',
+                    'J' => 'Y',
+                    _ => 'Z',
+                },
+                value,
+            },
+            ('R', Some(value)) => WordMeaning::Radius(value),
+            ('F', Some(value)) => WordMeaning::FeedRate(value),
+            ('S', Some(value)) => WordMeaning::SpindleSpeed(value),
+            ('T', Some(value)) => WordMeaning::Tool(value),
+            ('N', Some(value)) => WordMeaning::LineNumber(value),
+            ('P' | 'Q' | 'L', Some(value)) => WordMeaning::Parameter {
+                letter: *letter,
+                value,
+            },
+            _ => WordMeaning::Unknown,
+        };
+        explained.push(ExplainedWord {
+            text: text.clone(),
+            meaning,
+        });
+    }
+
+    LineExplanation {
+        block_delete,
+        words: explained,
+        comment,
+    }
+}
+
+/// Code without comments, and the comments' text joined by spaces
+fn split_comment(line: &str) -> (String, Option<String>) {
+    let mut code = String::new();
+    let mut comments: Vec<String> = Vec::new();
+    let mut current: Option<String> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (&mut current, c) {
+            (Some(comment), ')') => {
+                comments.push(comment.trim().to_string());
+                current = None;
+            }
+            (Some(comment), c) => comment.push(c),
+            (None, '(') => current = Some(String::new()),
+            (None, ';') => {
+                comments.push(chars.as_str().trim().to_string());
+                break;
+            }
+            (None, c) => code.push(c),
+        }
+    }
+    if let Some(unclosed) = current {
+        comments.push(unclosed.trim().to_string());
+    }
+    comments.retain(|c| !c.is_empty());
+    let comment = (!comments.is_empty()).then(|| comments.join(" "));
+    (code, comment)
+}
+
+/// Words as (letter, text as written, value), upper-cased
+///
+/// The value is `None` when the letter has no number after it.
+fn tokenize(code: &str) -> Vec<(char, String, Option<f64>)> {
+    let chars: Vec<char> = code.chars().collect();
+    let is_number = |c: char| c.is_ascii_digit() || matches!(c, '.' | '-' | '+');
+    let mut words = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let letter = chars[i].to_ascii_uppercase();
+        i += 1;
+        if letter.is_whitespace() {
+            continue;
+        }
+        let mut number = String::new();
+        if letter.is_ascii_alphabetic() {
+            // Spaces may separate a letter from its number, as in "X -1.5"
+            let mut j = i;
+            while j < chars.len() && chars[j].is_whitespace() {
+                j += 1;
+            }
+            while j < chars.len() && is_number(chars[j]) {
+                number.push(chars[j]);
+                j += 1;
+            }
+            if !number.is_empty() {
+                i = j;
+            }
+        }
+        let value = number.parse::<f64>().ok();
+        words.push((letter, format!("{}{}", letter, number), value));
+    }
+    words
+}
+
+fn g_code(value: f64, dwell: Option<f64>) -> Option<KnownCode> {
+    // Codes are matched on tenths: G38.2 is 382
+    let tenths = (value * 10.0).round() as i64;
+    if (value * 10.0 - tenths as f64).abs() > 1e-6 {
+        return None;
+    }
+    Some(match tenths {
+        0 => KnownCode::RapidMove,
+        10 => KnownCode::LinearMove,
+        20 => KnownCode::ArcClockwise,
+        30 => KnownCode::ArcCounterClockwise,
+        40 => KnownCode::Dwell(dwell),
+        100 => KnownCode::SetCoordinateData,
+        170 => KnownCode::PlaneXy,
+        180 => KnownCode::PlaneZx,
+        190 => KnownCode::PlaneYz,
+        200 => KnownCode::Inches,
+        210 => KnownCode::Millimeters,
+        280 => KnownCode::GoHome,
+        300 => KnownCode::GoSecondaryHome,
+        382..=385 => KnownCode::Probe {
+            toward: tenths <= 383,
+            error_if_no_contact: tenths % 2 == 0,
+        },
+        400 => KnownCode::CutterCompensationOff,
+        431 => KnownCode::ToolLengthOffset,
+        490 => KnownCode::ToolLengthOffsetCancel,
+        530 => KnownCode::MachineCoordinates,
+        540..=590 if tenths % 10 == 0 => KnownCode::WorkCoordinateSystem((tenths / 10 - 53) as u8),
+        800 => KnownCode::CannedCycleCancel,
+        810 => KnownCode::DrillCycle,
+        830 => KnownCode::PeckDrillCycle,
+        900 => KnownCode::AbsoluteDistance,
+        910 => KnownCode::IncrementalDistance,
+        920 => KnownCode::SetWorkOffset,
+        930 => KnownCode::InverseTimeFeed,
+        940 => KnownCode::UnitsPerMinuteFeed,
+        950 => KnownCode::UnitsPerRevolutionFeed,
+        _ => return None,
+    })
+}
+
+fn m_code(value: f64, speed: Option<f64>, tool: Option<f64>) -> Option<KnownCode> {
+    if value.fract() != 0.0 {
+        return None;
+    }
+    Some(match value as i64 {
+        0 => KnownCode::ProgramPause,
+        1 => KnownCode::OptionalStop,
+        2 => KnownCode::ProgramEnd,
+        3 => KnownCode::SpindleClockwise(speed),
+        4 => KnownCode::SpindleCounterClockwise(speed),
+        5 => KnownCode::SpindleStop,
+        6 => KnownCode::ToolChange(tool),
+        7 => KnownCode::MistCoolantOn,
+        8 => KnownCode::FloodCoolantOn,
+        9 => KnownCode::CoolantOff,
+        30 => KnownCode::ProgramEndAndRewind,
+        _ => return None,
+    })
+}
@@ -7,15 +7,18 @@
 //! - Preprocessor framework
 //! - Command lifecycle management
 //! - Command listener framework
+//! - Per-word explanations of a line
 //! - Stream management (reading from files or strings)
 
 pub mod command;
+pub mod explain;
 pub mod parser;
 pub mod pipeline;
 pub mod processors;
 pub mod stream;
 
 pub use command::*;
+pub use explain::{explain_line, ExplainedWord, KnownCode, LineExplanation, WordMeaning};
 pub use parser::*;
 pub use pipeline::*;
 pub use processors::*;
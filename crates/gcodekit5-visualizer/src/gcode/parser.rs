@@ -5,10 +5,13 @@ use serde::{Deserialize, Serialize};
 
 use super::{CommandNumberGenerator, GcodeCommand};
 
+pub use gcodekit5_core::FeedMode;
+
 /// G-Code parser with modal state tracking
 pub struct GcodeParser {
     current_state: GcodeState,
     command_generator: CommandNumberGenerator,
+    block_delete: bool,
 }
 
 /// Modal state for G-Code execution
@@ -28,6 +31,13 @@ pub struct ModalState {
     pub feed_rate_mode: u8,
 }
 
+impl ModalState {
+    /// The active feed rate mode
+    pub fn feed_mode(&self) -> FeedMode {
+        FeedMode::from_gcode(self.feed_rate_mode).unwrap_or_default()
+    }
+}
+
 impl Default for ModalState {
     fn default() -> Self {
         Self {
@@ -287,6 +297,40 @@ impl GcodeState {
         }
     }
 
+    /// The active feed rate mode
+    pub fn feed_mode(&self) -> FeedMode {
+        FeedMode::from_gcode(self.feed_rate_mode).unwrap_or_default()
+    }
+
+    /// Feed of a move `length` units long in units per minute, from the
+    /// current F word, feed rate mode and spindle speed
+    ///
+    /// `None` in G95 with the spindle stopped.
+    pub fn feed_rate_per_minute(&self, length: f64) -> Option<f64> {
+        self.feed_mode()
+            .units_per_minute(self.feed_rate, length, self.spindle_speed)
+    }
+
+    /// Get a human-readable description of the feed rate mode
+    pub fn feed_rate_mode_description(&self) -> &'static str {
+        match self.feed_rate_mode {
+            93 => "Inverse time (G93)",
+            94 => "Units per minute (G94)",
+            95 => "Units per revolution (G95)",
+            _ => "Unknown feed rate mode",
+        }
+    }
+
+    /// The current F word with its unit, e.g. `F500 mm/min` or `F0.1 mm/rev`
+    pub fn feed_rate_description(&self) -> String {
+        let unit = if self.units_mode == 20 { "in" } else { "mm" };
+        match self.feed_mode() {
+            FeedMode::InverseTime => format!("F{} 1/min", self.feed_rate),
+            FeedMode::UnitsPerMinute => format!("F{} {}/min", self.feed_rate, unit),
+            FeedMode::UnitsPerRevolution => format!("F{} {}/rev", self.feed_rate, unit),
+        }
+    }
+
     /// Get a human-readable description of units
     pub fn units_description(&self) -> &'static str {
         match self.units_mode {
@@ -303,6 +347,7 @@ impl GcodeParser {
         Self {
             current_state: GcodeState::default(),
             command_generator: CommandNumberGenerator::new(),
+            block_delete: true,
         }
     }
 }
@@ -326,12 +371,28 @@ impl GcodeParser {
         let sequence = self.command_generator.next();
         let command = GcodeCommand::with_sequence(cleaned, sequence);
 
+        // Skipped optional blocks do not change the modal state
+        if command.is_block_deleted(self.block_delete) {
+            return Ok(command);
+        }
+
         // Update modal state
         self.update_modal_state(&command)?;
 
         Ok(command)
     }
 
+    /// Set the block delete switch: when on (the default), lines starting with `/`
+    /// are skipped; when off they run without the prefix
+    pub fn set_block_delete(&mut self, enabled: bool) {
+        self.block_delete = enabled;
+    }
+
+    /// Whether the block delete switch is on
+    pub fn block_delete(&self) -> bool {
+        self.block_delete
+    }
+
     /// Remove comments from a G-Code line
     fn remove_comments(&self, line: &str) -> String {
         static COMMENT_REGEX: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
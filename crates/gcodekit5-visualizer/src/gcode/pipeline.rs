@@ -102,12 +102,42 @@ pub trait CommandProcessor: Send + Sync {
 /// Arc-wrapped processor for thread-safe sharing
 pub type ProcessorHandle = Arc<dyn CommandProcessor>;
 
+/// A processor in a pipeline, with its switch in that pipeline
+#[derive(Clone)]
+struct PipelineEntry {
+    processor: ProcessorHandle,
+    enabled: bool,
+}
+
+impl PipelineEntry {
+    /// Whether the processor runs: switched on here and enabled itself
+    fn is_active(&self) -> bool {
+        self.enabled && self.processor.is_enabled()
+    }
+}
+
+/// A processor's place in a pipeline, as listed by
+/// [`ProcessorPipeline::processor_states`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessorState {
+    pub name: String,
+    pub description: String,
+    /// Switched on in the pipeline
+    pub enabled: bool,
+    /// Switched on and enabled by its own configuration, so it runs
+    pub active: bool,
+}
+
 /// G-Code command processor pipeline
 ///
 /// Manages a sequence of command processors that are applied to G-Code commands
 /// in order. Each processor can transform the command, skip it, or expand it
 /// into multiple commands.
 ///
+/// Order matters (strip comments before numbering lines, say), so processors
+/// can be moved and switched off and on again without removing them; switched
+/// off processors are skipped.
+///
 /// # Example
 /// ```ignore
 /// let mut pipeline = ProcessorPipeline::new();
@@ -118,7 +148,7 @@ pub type ProcessorHandle = Arc<dyn CommandProcessor>;
 /// let commands = pipeline.process_commands(&input_commands)?;
 /// ```
 pub struct ProcessorPipeline {
-    processors: Vec<ProcessorHandle>,
+    processors: Vec<PipelineEntry>,
     config: ProcessorConfig,
 }
 
@@ -135,13 +165,18 @@ impl ProcessorPipeline {
     ///
     /// Processors are applied in the order they are registered.
     pub fn register(&mut self, processor: ProcessorHandle) -> &mut Self {
-        self.processors.push(processor);
+        self.processors.push(PipelineEntry {
+            processor,
+            enabled: true,
+        });
         self
     }
 
     /// Register multiple processors at once
     pub fn register_all(&mut self, processors: Vec<ProcessorHandle>) -> &mut Self {
-        self.processors.extend(processors);
+        for processor in processors {
+            self.register(processor);
+        }
         self
     }
 
@@ -152,22 +187,99 @@ impl ProcessorPipeline {
 
     /// Get a reference to a processor by index
     pub fn get_processor(&self, index: usize) -> Option<&ProcessorHandle> {
-        self.processors.get(index)
+        self.processors.get(index).map(|e| &e.processor)
     }
 
     /// Get a reference to a processor by name
     pub fn get_processor_by_name(&self, name: &str) -> Option<&ProcessorHandle> {
-        self.processors.iter().find(|p| p.name() == name)
+        self.position(name).map(|i| &self.processors[i].processor)
     }
 
-    /// List all registered processors
+    /// Index of the first processor called `name`
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.processors
+            .iter()
+            .position(|e| e.processor.name() == name)
+    }
+
+    /// List all registered processors in order, with whether each runs
     pub fn list_processors(&self) -> Vec<(&str, &str, bool)> {
         self.processors
             .iter()
-            .map(|p| (p.name(), p.description(), p.is_enabled()))
+            .map(|e| (e.processor.name(), e.processor.description(), e.is_active()))
+            .collect()
+    }
+
+    /// The processors in order, with their switches
+    pub fn processor_states(&self) -> Vec<ProcessorState> {
+        self.processors
+            .iter()
+            .map(|e| ProcessorState {
+                name: e.processor.name().to_string(),
+                description: e.processor.description().to_string(),
+                enabled: e.enabled,
+                active: e.is_active(),
+            })
             .collect()
     }
 
+    /// Switch the processor called `name` on or off, keeping its place
+    pub fn set_processor_enabled(&mut self, name: &str, enabled: bool) -> Result<(), String> {
+        let index = self
+            .position(name)
+            .ok_or_else(|| format!("Unknown processor: {}", name))?;
+        self.processors[index].enabled = enabled;
+        Ok(())
+    }
+
+    /// Whether the processor called `name` is switched on in this pipeline
+    pub fn is_processor_enabled(&self, name: &str) -> Option<bool> {
+        self.position(name).map(|i| self.processors[i].enabled)
+    }
+
+    /// Move the processor at `from` so it ends up at index `to`, shifting the
+    /// ones in between, as when dragging it in a list
+    pub fn move_processor(&mut self, from: usize, to: usize) -> Result<(), String> {
+        let count = self.processors.len();
+        if from >= count || to >= count {
+            return Err(format!(
+                "Processor index out of range: {} -> {} with {} processors",
+                from, to, count
+            ));
+        }
+        let entry = self.processors.remove(from);
+        self.processors.insert(to, entry);
+        Ok(())
+    }
+
+    /// Put the processors in the order of `names`
+    ///
+    /// `names` must name every processor exactly once; the switches move with
+    /// their processors.
+    pub fn set_order(&mut self, names: &[&str]) -> Result<(), String> {
+        if names.len() != self.processors.len() {
+            return Err(format!(
+                "Order names {} processors, pipeline has {}",
+                names.len(),
+                self.processors.len()
+            ));
+        }
+        let mut used = vec![false; self.processors.len()];
+        let mut order = Vec::with_capacity(names.len());
+        for name in names {
+            let index = (0..self.processors.len())
+                .find(|&i| !used[i] && self.processors[i].processor.name() == *name)
+                .ok_or_else(|| format!("Unknown or repeated processor: {}", name))?;
+            used[index] = true;
+            order.push(index);
+        }
+        self.processors = order
+            .into_iter()
+            .map(|i| self.processors[i].clone())
+            .collect();
+        Ok(())
+    }
+
     /// Process a single command through the entire pipeline
     ///
     /// Returns a vector of commands. Most processors return one command,
@@ -179,10 +291,11 @@ impl ProcessorPipeline {
     ) -> Result<Vec<GcodeCommand>, String> {
         let mut current_commands = vec![command.clone()];
 
-        for processor in &self.processors {
-            if !processor.is_enabled() {
+        for entry in &self.processors {
+            if !entry.is_active() {
                 continue;
             }
+            let processor = &entry.processor;
 
             let mut next_commands = Vec::with_capacity(current_commands.len());
 
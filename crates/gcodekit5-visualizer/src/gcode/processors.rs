@@ -178,6 +178,55 @@ impl CommandProcessor for EmptyLineRemoverProcessor {
     }
 }
 
+/// Honors the block delete switch
+///
+/// With the switch on, optional blocks (lines starting with `/`) are removed from
+/// the command stream. With it off they pass through; their command text already
+/// has the prefix stripped, so they run normally.
+#[derive(Debug, Clone)]
+pub struct BlockDeleteProcessor {
+    enabled: bool,
+}
+
+impl BlockDeleteProcessor {
+    /// Create a block delete processor with the switch on or off
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+}
+
+impl Default for BlockDeleteProcessor {
+    fn default() -> Self {
+        Self::new(true)
+    }
+}
+
+impl CommandProcessor for BlockDeleteProcessor {
+    fn name(&self) -> &str {
+        "block_delete"
+    }
+
+    fn description(&self) -> &str {
+        "Skips optional blocks marked with a leading / when block delete is on"
+    }
+
+    fn process(
+        &self,
+        command: &GcodeCommand,
+        _state: &GcodeState,
+    ) -> Result<Vec<GcodeCommand>, String> {
+        if command.is_block_deleted(self.enabled) {
+            Ok(vec![])
+        } else {
+            Ok(vec![command.clone()])
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        true
+    }
+}
+
 /// Validates G-code command length
 ///
 /// Some GRBL versions have maximum command length limits (typically 128-255 characters).
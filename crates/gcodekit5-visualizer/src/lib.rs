@@ -10,30 +10,38 @@ pub mod utils;
 pub mod visualizer;
 
 pub use visualizer::{
-    generate_surface_mesh, render_g1_to_path, render_g2_to_path, render_g3_to_path,
+    export_view, export_view_pdf, export_view_svg, generate_surface_mesh, grid_lines,
+    render_burn_preview, render_g1_to_path, render_g2_to_path, render_g3_to_path,
     render_g4_to_path, render_grid_to_path, render_intensity_overlay, render_origin_to_path,
-    render_rapid_moves_to_path, render_toolpath_to_path, Camera, Camera3D, GCodeCommand, Point3D,
-    Renderer, Scene, StockSimulator3D, ToolpathSegment, ToolpathSegmentType, Visualizer,
-    VisualizerControls, VoxelGrid,
+    render_rapid_moves_to_path, render_toolpath_to_path, BurnPreview, BurnPreviewOptions, Camera,
+    Camera3D, GCodeCommand, MaterialTint, PaperOrientation, PaperSize, Point3D, ProgramOperation,
+    Renderer, Scene, StockSimulator3D, ToolpathSegment, ToolpathSegmentType, ViewExportFormat,
+    ViewExportOptions, Visualizer, VisualizerControls, VoxelGrid,
 };
 
 pub use gcode::{
+    explain_line,
     stream::{FileStreamReader, GcodeStreamReader, PausableStream, StringStreamReader},
-    CommandId, CommandLengthProcessor, CommandListener, CommandListenerHandle,
-    CommandNumberGenerator, CommandProcessor, CommandResponse, CommandState, CommentProcessor,
-    DecimalProcessor, EmptyLineRemoverProcessor, GcodeCommand, GcodeParser, GcodeState, ModalState,
-    ProcessorConfig, ProcessorHandle, ProcessorPipeline, ProcessorRegistry, WhitespaceProcessor,
+    BlockDeleteProcessor, CommandId, CommandLengthProcessor, CommandListener,
+    CommandListenerHandle, CommandNumberGenerator, CommandProcessor, CommandResponse, CommandState,
+    CommentProcessor, DecimalProcessor, EmptyLineRemoverProcessor, ExplainedWord, FeedMode,
+    GcodeCommand, GcodeParser, GcodeState, KnownCode, LineExplanation, ModalState, ProcessorConfig,
+    ProcessorHandle, ProcessorPipeline, ProcessorRegistry, ProcessorState, WhitespaceProcessor,
+    WordMeaning,
 };
 
 pub use utils::{
-    AdvancedProber, Alarm, AlarmManager, AlarmType, AutoConnectConfig, BackupEntry, BackupManager,
-    BasicProber, Bookmark, BookmarkManager, BoundingBox, CommandHistory, CustomAction, CustomMacro,
-    DataLogger, DropEvent, DropFileType, DropIndicatorState, DropTarget, DropZone, ExportOptions,
-    FeedRateStats, FileComparison, FileEncoding, FileExporter, FileFormat, FileProcessingPipeline,
-    FileReadStats, FileStatistics, FileValidation, GcodeFileReader, GcodeTemplate, HeightPoint,
-    HistoryEntry, LogEntry, NetworkConfig, PendantButton, PendantConfig, PerformanceMetrics,
-    ProbeMesh, ProbePoint, ProcessedFile, ProgramState, RecentFileEntry, RecentFilesManager,
-    SimulationPosition, Simulator, SoftLimits, SpindleStats, Stepper, TemplateLibrary,
-    TemplateVariable, ToolInfo, ToolLibrary, ToolOffset, ToolOffsetManager, ValidationIssue,
-    ValidationResult, ValidationSeverity, WorkCoordinateSystem, WorkOffset,
+    collect_gcode_files, parse_js_event, process_batch, AdvancedProber, Alarm, AlarmManager,
+    AlarmType, AutoConnectConfig, BackupEntry, BackupManager, BasicProber, BatchFileResult,
+    BatchOptions, BatchReport, Bookmark, BookmarkManager, BoundingBox, CommandHistory,
+    CustomAction, CustomMacro, DataLogger, DropEvent, DropFileType, DropHandler,
+    DropIndicatorState, DropQueue, DropSummary, DropTarget, DropZone, ExportOptions, FeedRateStats,
+    FileComparison, FileEncoding, FileExporter, FileFormat, FileProcessingPipeline, FileReadStats,
+    FileStatistics, FileValidation, GamepadDevice, GamepadEvent, GamepadJogMapper, GcodeFileReader,
+    GcodeTemplate, HeightPoint, HistoryEntry, LogEntry, NetworkConfig, PendantAxis, PendantButton,
+    PendantCommand, PendantConfig, PerformanceMetrics, ProbeMesh, ProbePoint, ProcessedFile,
+    ProgramState, RecentFileEntry, RecentFilesManager, SimulationPosition, Simulator, SoftLimits,
+    SpindleStats, Stepper, TemplateLibrary, TemplateVariable, ToolInfo, ToolLibrary, ToolOffset,
+    ToolOffsetManager, ValidationIssue, ValidationResult, ValidationSeverity, WorkCoordinateSystem,
+    WorkOffset,
 };
@@ -0,0 +1,353 @@
+//! Batch Processing
+//!
+//! Runs a configured [`ProcessorPipeline`] over many G-code files and writes the
+//! results to an output directory, preserving each file's path relative to the
+//! input base directory. Files are processed independently: a file that fails to
+//! read, process or write is reported in its [`BatchFileResult`] and the rest of
+//! the batch continues.
+//!
+//! Nothing is written over an input: an output directory that is the input
+//! directory fails every file, and files whose outputs would land on the same
+//! path (two inputs outside the base directory with the same name, say) fail
+//! rather than overwrite each other.
+
+use std::collections::HashMap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use anyhow::{Context, Result};
+
+use crate::gcode::{GcodeCommand, GcodeState, ProcessorPipeline};
+use crate::utils::{
+    FileFormat, FileProcessingPipeline, FileStatistics, FileValidation, GcodeFileReader,
+};
+
+/// Batch processing options
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    /// Directory that input paths are made relative to. Defaults to the deepest
+    /// directory containing all inputs.
+    pub base_dir: Option<PathBuf>,
+    /// Number of worker threads (0 = one per available CPU, 1 = sequential)
+    pub threads: usize,
+    /// Replace existing output files
+    pub overwrite: bool,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            base_dir: None,
+            threads: 1,
+            overwrite: true,
+        }
+    }
+}
+
+/// Result of processing one file
+#[derive(Debug, Clone)]
+pub struct BatchFileResult {
+    /// Input file
+    pub source: PathBuf,
+    /// Written output file, if processing succeeded
+    pub output: Option<PathBuf>,
+    /// Statistics of the processed output
+    pub statistics: Option<FileStatistics>,
+    /// Validation of the processed output
+    pub validation: Option<FileValidation>,
+    /// Error that stopped this file, if any
+    pub error: Option<String>,
+}
+
+impl BatchFileResult {
+    /// Whether the file was processed and written
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Whether the output was written but has validation errors
+    pub fn has_validation_errors(&self) -> bool {
+        self.validation.as_ref().is_some_and(|v| !v.is_valid)
+    }
+}
+
+/// Results of a batch run, in input order
+#[derive(Debug, Clone, Default)]
+pub struct BatchReport {
+    /// Per-file results
+    pub results: Vec<BatchFileResult>,
+}
+
+impl BatchReport {
+    /// Number of files processed and written
+    pub fn succeeded(&self) -> usize {
+        self.results.iter().filter(|r| r.is_success()).count()
+    }
+
+    /// Files that failed
+    pub fn failures(&self) -> impl Iterator<Item = &BatchFileResult> {
+        self.results.iter().filter(|r| !r.is_success())
+    }
+
+    /// Get formatted summary
+    pub fn summary(&self) -> String {
+        format!(
+            "Processed: {} | Failed: {}",
+            self.succeeded(),
+            self.results.len() - self.succeeded()
+        )
+    }
+}
+
+/// Recursively collect G-code files (`.nc`, `.gcode`, `.ngc`, `.gco`) under a directory
+///
+/// Paths are returned sorted so batch output order is stable.
+pub fn collect_gcode_files(dir: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.as_ref().to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        let entries = std::fs::read_dir(&current)
+            .with_context(|| format!("Failed to read directory {}", current.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .and_then(FileFormat::from_extension)
+                .is_some()
+            {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Process files through a pipeline, writing results under `output_dir`
+pub fn process_batch(
+    inputs: &[PathBuf],
+    pipeline: &ProcessorPipeline,
+    output_dir: impl AsRef<Path>,
+    options: &BatchOptions,
+) -> BatchReport {
+    let output_dir = output_dir.as_ref();
+    let base_dir = options
+        .base_dir
+        .clone()
+        .unwrap_or_else(|| common_base_dir(inputs));
+
+    let threads = match options.threads {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+    .min(inputs.len().max(1));
+
+    if same_path(output_dir, &base_dir) {
+        let error = format!(
+            "Output directory {} is the input directory",
+            output_dir.display()
+        );
+        return BatchReport {
+            results: inputs
+                .iter()
+                .map(|source| failed(source, error.clone()))
+                .collect(),
+        };
+    }
+
+    let outputs = output_paths(inputs, &base_dir, output_dir);
+    let process = |index: usize| {
+        let source = &inputs[index];
+        match &outputs[index] {
+            Ok(output) => catch_unwind(AssertUnwindSafe(|| {
+                process_one(source, pipeline, output, options.overwrite)
+            }))
+            .unwrap_or_else(|_| failed(source, "Processing panicked".to_string())),
+            Err(e) => failed(source, e.clone()),
+        }
+    };
+
+    let results = if threads <= 1 {
+        (0..inputs.len()).map(process).collect()
+    } else {
+        let next = AtomicUsize::new(0);
+        let slots: Mutex<Vec<Option<BatchFileResult>>> = Mutex::new(vec![None; inputs.len()]);
+        std::thread::scope(|scope| {
+            for _ in 0..threads {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    if index >= inputs.len() {
+                        break;
+                    }
+                    let result = process(index);
+                    slots.lock().unwrap_or_else(PoisonError::into_inner)[index] = Some(result);
+                });
+            }
+        });
+        slots
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+            .into_iter()
+            .zip(inputs)
+            .map(|(slot, source)| {
+                slot.unwrap_or_else(|| failed(source, "Not processed".to_string()))
+            })
+            .collect()
+    };
+
+    BatchReport { results }
+}
+
+fn process_one(
+    source: &Path,
+    pipeline: &ProcessorPipeline,
+    output: &Path,
+    overwrite: bool,
+) -> BatchFileResult {
+    match run_file(source, pipeline, output, overwrite) {
+        Ok(()) => BatchFileResult {
+            source: source.to_path_buf(),
+            output: Some(output.to_path_buf()),
+            statistics: statistics_for(output),
+            validation: GcodeFileReader::new(output)
+                .and_then(|reader| reader.validate())
+                .ok(),
+            error: None,
+        },
+        Err(e) => failed(source, format!("{:#}", e)),
+    }
+}
+
+/// Result for a file that could not be processed
+fn failed(source: &Path, error: String) -> BatchFileResult {
+    tracing::warn!(
+        "Batch processing failed for {}: {}",
+        source.display(),
+        error
+    );
+    BatchFileResult {
+        source: source.to_path_buf(),
+        output: None,
+        statistics: None,
+        validation: None,
+        error: Some(error),
+    }
+}
+
+fn run_file(
+    source: &Path,
+    pipeline: &ProcessorPipeline,
+    output: &Path,
+    overwrite: bool,
+) -> Result<()> {
+    if output.exists() {
+        if same_path(output, source) {
+            anyhow::bail!("Output file {} is the input file", output.display());
+        }
+        if !overwrite {
+            anyhow::bail!("Output file {} already exists", output.display());
+        }
+    }
+
+    let content = GcodeFileReader::new(source)?.read_all()?;
+    let commands: Vec<GcodeCommand> = content.lines().map(GcodeCommand::new).collect();
+    let mut state = GcodeState::new();
+    let processed = pipeline
+        .process_commands(&commands, &mut state)
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let mut text = processed
+        .iter()
+        .map(|cmd| cmd.command.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    text.push('\n');
+
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    std::fs::write(output, text)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+    Ok(())
+}
+
+/// Output path of each input, or why it has none
+///
+/// Inputs outside the base directory keep just their file name, so two of
+/// them can land on the same output; every input sharing an output fails.
+fn output_paths(
+    inputs: &[PathBuf],
+    base_dir: &Path,
+    output_dir: &Path,
+) -> Vec<std::result::Result<PathBuf, String>> {
+    let outputs: Vec<PathBuf> = inputs
+        .iter()
+        .map(|source| {
+            let relative = source
+                .strip_prefix(base_dir)
+                .unwrap_or_else(|_| Path::new(source.file_name().unwrap_or(source.as_os_str())));
+            output_dir.join(relative)
+        })
+        .collect();
+
+    let mut sources: HashMap<&Path, Vec<String>> = HashMap::new();
+    for (output, source) in outputs.iter().zip(inputs) {
+        sources
+            .entry(output.as_path())
+            .or_default()
+            .push(source.display().to_string());
+    }
+    outputs
+        .iter()
+        .map(|output| match sources.get(output.as_path()) {
+            Some(shared) if shared.len() > 1 => Err(format!(
+                "Output file {} would be written from {}",
+                output.display(),
+                shared.join(", ")
+            )),
+            _ => Ok(output.clone()),
+        })
+        .collect()
+}
+
+/// Whether two existing paths are the same file or directory
+fn same_path(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn statistics_for(output: &Path) -> Option<FileStatistics> {
+    let mut stats_pipeline = FileProcessingPipeline::new();
+    stats_pipeline.set_cache_enabled(false);
+    stats_pipeline
+        .process_file(output)
+        .ok()
+        .map(|processed| processed.statistics)
+}
+
+/// Deepest directory containing all inputs
+fn common_base_dir(inputs: &[PathBuf]) -> PathBuf {
+    let mut dirs = inputs.iter().filter_map(|p| p.parent());
+    let Some(first) = dirs.next() else {
+        return PathBuf::new();
+    };
+    let mut base = first.to_path_buf();
+    for dir in dirs {
+        while !dir.starts_with(&base) {
+            if !base.pop() {
+                return PathBuf::new();
+            }
+        }
+    }
+    base
+}
@@ -11,6 +11,7 @@
 //! - Support multiple file types
 //! - Show drop indicators
 //! - Handle drop events
+//! - Queue multi-file drops, routing each file to the handler for its type
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -205,6 +206,8 @@ pub enum DropFileType {
     GCode,
     /// Image file (for reference)
     Image,
+    /// Vector drawing (DXF, SVG)
+    Vector,
     /// Text file
     Text,
     /// All files
@@ -227,6 +230,7 @@ impl DropFileType {
             DropFileType::Image => {
                 matches!(ext.as_str(), "png" | "jpg" | "jpeg" | "gif" | "bmp")
             }
+            DropFileType::Vector => matches!(ext.as_str(), "dxf" | "svg"),
             DropFileType::Text => {
                 matches!(ext.as_str(), "txt" | "gcode" | "nc" | "ngc" | "gco")
             }
@@ -239,10 +243,169 @@ impl DropFileType {
         match self {
             DropFileType::GCode => vec!["nc", "gcode", "ngc", "gco"],
             DropFileType::Image => vec!["png", "jpg", "jpeg", "gif", "bmp"],
+            DropFileType::Vector => vec!["dxf", "svg"],
             DropFileType::Text => vec!["txt", "gcode", "nc", "ngc", "gco"],
             DropFileType::All => vec![],
         }
     }
+
+    /// Classify a file by extension. G-code takes precedence over text.
+    pub fn classify(path: &Path) -> Option<DropFileType> {
+        [
+            DropFileType::GCode,
+            DropFileType::Image,
+            DropFileType::Vector,
+            DropFileType::Text,
+        ]
+        .into_iter()
+        .find(|file_type| file_type.matches(path))
+    }
+
+    /// Handler that files of this type are routed to, if any
+    pub fn handler(&self) -> Option<DropHandler> {
+        match self {
+            DropFileType::GCode => Some(DropHandler::Playlist),
+            DropFileType::Image => Some(DropHandler::Engraver),
+            DropFileType::Vector => Some(DropHandler::Designer),
+            DropFileType::Text | DropFileType::All => None,
+        }
+    }
+}
+
+/// Where a dropped file is sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DropHandler {
+    /// G-code files are queued for sending
+    Playlist,
+    /// Images are opened in the bitmap engraver
+    Engraver,
+    /// DXF/SVG drawings are imported into the designer
+    Designer,
+}
+
+/// Counts of the files in a drag, by type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DropSummary {
+    /// G-code files
+    pub gcode: usize,
+    /// Image files
+    pub images: usize,
+    /// DXF/SVG files
+    pub vectors: usize,
+    /// Files no handler accepts
+    pub unsupported: usize,
+}
+
+impl DropSummary {
+    /// Count files by type
+    pub fn from_files(files: &[PathBuf]) -> Self {
+        let mut summary = Self::default();
+        for file in files {
+            match DropFileType::classify(file).and_then(|t| t.handler()) {
+                Some(DropHandler::Playlist) => summary.gcode += 1,
+                Some(DropHandler::Engraver) => summary.images += 1,
+                Some(DropHandler::Designer) => summary.vectors += 1,
+                None => summary.unsupported += 1,
+            }
+        }
+        summary
+    }
+
+    /// Total number of files
+    pub fn total(&self) -> usize {
+        self.supported() + self.unsupported
+    }
+
+    /// Number of files that will be handled
+    pub fn supported(&self) -> usize {
+        self.gcode + self.images + self.vectors
+    }
+
+    /// Get formatted summary, e.g. "3 files: 2 G-code, 1 image"
+    pub fn label(&self) -> String {
+        let mut parts = Vec::new();
+        for (count, singular, plural) in [
+            (self.gcode, "G-code", "G-code"),
+            (self.images, "image", "images"),
+            (self.vectors, "drawing", "drawings"),
+            (self.unsupported, "unsupported", "unsupported"),
+        ] {
+            if count > 0 {
+                parts.push(format!(
+                    "{} {}",
+                    count,
+                    if count == 1 { singular } else { plural }
+                ));
+            }
+        }
+        let total = self.total();
+        format!(
+            "{} file{}: {}",
+            total,
+            if total == 1 { "" } else { "s" },
+            parts.join(", ")
+        )
+    }
+}
+
+/// Files from a drop, grouped by the handler they go to
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DropQueue {
+    /// G-code files for the playlist, in drop order
+    pub playlist: Vec<PathBuf>,
+    /// Images for the engraver
+    pub engraver: Vec<PathBuf>,
+    /// DXF/SVG files for the designer
+    pub designer: Vec<PathBuf>,
+    /// Files that no handler accepts; reported but not opened
+    pub unsupported: Vec<PathBuf>,
+}
+
+impl DropQueue {
+    /// Route each file to the handler for its type
+    pub fn from_files(files: &[PathBuf]) -> Self {
+        let mut queue = Self::default();
+        for file in files {
+            let bucket = match DropFileType::classify(file).and_then(|t| t.handler()) {
+                Some(handler) => queue.files_for_mut(handler),
+                None => &mut queue.unsupported,
+            };
+            bucket.push(file.clone());
+        }
+        queue
+    }
+
+    /// Files routed to a handler
+    pub fn files_for(&self, handler: DropHandler) -> &[PathBuf] {
+        match handler {
+            DropHandler::Playlist => &self.playlist,
+            DropHandler::Engraver => &self.engraver,
+            DropHandler::Designer => &self.designer,
+        }
+    }
+
+    fn files_for_mut(&mut self, handler: DropHandler) -> &mut Vec<PathBuf> {
+        match handler {
+            DropHandler::Playlist => &mut self.playlist,
+            DropHandler::Engraver => &mut self.engraver,
+            DropHandler::Designer => &mut self.designer,
+        }
+    }
+
+    /// Whether nothing can be handled
+    pub fn is_empty(&self) -> bool {
+        self.playlist.is_empty() && self.engraver.is_empty() && self.designer.is_empty()
+    }
+
+    /// Counts by type
+    pub fn summary(&self) -> DropSummary {
+        DropSummary {
+            gcode: self.playlist.len(),
+            images: self.engraver.len(),
+            vectors: self.designer.len(),
+            unsupported: self.unsupported.len(),
+        }
+    }
 }
 
 /// Drop event data
@@ -294,6 +457,16 @@ impl DropEvent {
     pub fn is_valid_for_target(&self, file_type: DropFileType) -> bool {
         self.files.iter().any(|f| file_type.matches(f))
     }
+
+    /// Counts of the dropped files by type
+    pub fn summary(&self) -> DropSummary {
+        DropSummary::from_files(&self.files)
+    }
+
+    /// Route every dropped file to its handler
+    pub fn queue(&self) -> DropQueue {
+        DropQueue::from_files(&self.files)
+    }
 }
 
 /// Drop indicator visual feedback
@@ -307,6 +480,8 @@ pub enum DropIndicatorState {
     Valid,
     /// Dragging with invalid files
     Invalid,
+    /// Dragging several files, at least one of which can be handled
+    Multiple(DropSummary),
 }
 
 /// Drop zone for drag and drop
@@ -340,7 +515,15 @@ impl DropZone {
             return;
         }
 
-        if event.is_valid_for_target(self.file_type) {
+        if event.files.len() > 1 {
+            // Multi-file drops are routed per file, so any supported file makes it valid
+            let summary = event.summary();
+            self.state = if summary.supported() > 0 {
+                DropIndicatorState::Multiple(summary)
+            } else {
+                DropIndicatorState::Invalid
+            };
+        } else if event.is_valid_for_target(self.file_type) {
             self.state = DropIndicatorState::Valid;
         } else {
             self.state = DropIndicatorState::Invalid;
@@ -357,6 +540,28 @@ impl DropZone {
         self.state = DropIndicatorState::None;
     }
 
+    /// Finish a drop and route its files. Unsupported files are logged and left
+    /// in [`DropQueue::unsupported`]; the rest are still queued.
+    pub fn accept_drop(&mut self, event: &DropEvent) -> DropQueue {
+        self.on_drop();
+        if !self.enabled {
+            return DropQueue::default();
+        }
+        let queue = event.queue();
+        for file in &queue.unsupported {
+            tracing::warn!("Unsupported file type dropped: {}", file.display());
+        }
+        queue
+    }
+
+    /// Number of files in the current drag, if several are being dropped
+    pub fn indicator_label(&self) -> Option<String> {
+        match self.state {
+            DropIndicatorState::Multiple(summary) => Some(summary.label()),
+            _ => None,
+        }
+    }
+
     /// Get CSS class for indicator
     pub fn indicator_class(&self) -> &'static str {
         match self.state {
@@ -364,6 +569,7 @@ impl DropZone {
             DropIndicatorState::Over => "drop-over",
             DropIndicatorState::Valid => "drop-valid",
             DropIndicatorState::Invalid => "drop-invalid",
+            DropIndicatorState::Multiple(_) => "drop-multiple",
         }
     }
 
@@ -374,6 +580,7 @@ impl DropZone {
             DropIndicatorState::Over => "rgba(100, 100, 100, 0.3)",
             DropIndicatorState::Valid => "rgba(0, 255, 0, 0.2)",
             DropIndicatorState::Invalid => "rgba(255, 0, 0, 0.2)",
+            DropIndicatorState::Multiple(_) => "rgba(0, 255, 0, 0.2)",
         }
     }
 }
@@ -520,6 +727,87 @@ mod tests {
         assert_eq!(zone.state, DropIndicatorState::None);
     }
 
+    #[test]
+    fn test_drop_file_type_classify() {
+        assert_eq!(
+            DropFileType::classify(Path::new("a.gcode")),
+            Some(DropFileType::GCode)
+        );
+        assert_eq!(
+            DropFileType::classify(Path::new("a.PNG")),
+            Some(DropFileType::Image)
+        );
+        assert_eq!(
+            DropFileType::classify(Path::new("a.svg")),
+            Some(DropFileType::Vector)
+        );
+        assert_eq!(
+            DropFileType::classify(Path::new("a.txt")),
+            Some(DropFileType::Text)
+        );
+        assert_eq!(DropFileType::classify(Path::new("a.zip")), None);
+    }
+
+    #[test]
+    fn test_drop_queue_routes_mixed_files() {
+        let files = vec![
+            PathBuf::from("part1.nc"),
+            PathBuf::from("logo.png"),
+            PathBuf::from("outline.dxf"),
+            PathBuf::from("notes.zip"),
+            PathBuf::from("part2.gcode"),
+        ];
+        let queue = DropQueue::from_files(&files);
+        assert_eq!(
+            queue.files_for(DropHandler::Playlist),
+            &[PathBuf::from("part1.nc"), PathBuf::from("part2.gcode")]
+        );
+        assert_eq!(queue.engraver, vec![PathBuf::from("logo.png")]);
+        assert_eq!(queue.designer, vec![PathBuf::from("outline.dxf")]);
+        assert_eq!(queue.unsupported, vec![PathBuf::from("notes.zip")]);
+        assert!(!queue.is_empty());
+        assert_eq!(
+            queue.summary().label(),
+            "5 files: 2 G-code, 1 image, 1 drawing, 1 unsupported"
+        );
+    }
+
+    #[test]
+    fn test_drop_zone_multiple_files_indicator() {
+        let mut zone = DropZone::new("main", DropFileType::GCode);
+        let event = DropEvent::new(
+            vec![PathBuf::from("a.nc"), PathBuf::from("b.svg")],
+            DropTarget::Generic,
+        );
+        zone.on_drag_over(&event);
+        assert_eq!(
+            zone.state,
+            DropIndicatorState::Multiple(DropSummary {
+                gcode: 1,
+                vectors: 1,
+                ..Default::default()
+            })
+        );
+        assert_eq!(zone.indicator_class(), "drop-multiple");
+        assert_eq!(
+            zone.indicator_label().unwrap(),
+            "2 files: 1 G-code, 1 drawing"
+        );
+
+        let queue = zone.accept_drop(&event);
+        assert_eq!(zone.state, DropIndicatorState::None);
+        assert_eq!(queue.playlist.len(), 1);
+        assert_eq!(queue.designer.len(), 1);
+
+        let unsupported = DropEvent::new(
+            vec![PathBuf::from("a.zip"), PathBuf::from("b.txt")],
+            DropTarget::Generic,
+        );
+        zone.on_drag_over(&unsupported);
+        assert_eq!(zone.state, DropIndicatorState::Invalid);
+        assert!(zone.accept_drop(&unsupported).is_empty());
+    }
+
     #[test]
     fn test_drop_target_equality() {
         assert_eq!(DropTarget::Editor, DropTarget::Editor);
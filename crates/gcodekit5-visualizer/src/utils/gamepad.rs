@@ -0,0 +1,242 @@
+//! Gamepad pendant input
+//!
+//! Reads a USB gamepad through the Linux joystick interface (`/dev/input/jsN`)
+//! and turns its input into machine commands according to a [`PendantConfig`]:
+//! stick deflection becomes a continuous `$J=` jog whose feed follows the
+//! deflection, releasing the stick cancels the jog, and mapped buttons become
+//! [`PendantButton`] actions.
+//!
+//! Deflection is quantized into speed levels so a jog is only replaced when the
+//! speed or direction changes noticeably, not on every small stick movement.
+
+use super::phase6_extended::{PendantButton, PendantConfig};
+use anyhow::Result;
+use gcodekit5_core::limited_jog_feed;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::Read;
+use std::sync::mpsc::{self, Receiver};
+
+/// Speed levels between the deadzone and full deflection
+const SPEED_LEVELS: f64 = 10.0;
+
+/// Joystick API event types
+const JS_EVENT_BUTTON: u8 = 0x01;
+const JS_EVENT_AXIS: u8 = 0x02;
+const JS_EVENT_INIT: u8 = 0x80;
+
+/// Raw gamepad input
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GamepadEvent {
+    /// An axis moved; `value` is -1..1
+    Axis { number: u8, value: f64 },
+    /// A button was pressed or released
+    Button { number: u8, pressed: bool },
+}
+
+/// What the machine should do in response to gamepad input
+#[derive(Debug, Clone, PartialEq)]
+pub enum PendantCommand {
+    /// Send a jog command line (`$J=...`)
+    Jog(String),
+    /// Cancel the jog in progress (GRBL realtime 0x85)
+    JogCancel,
+    /// Run a pendant action
+    Action(PendantButton),
+}
+
+/// Decode one 8-byte Linux joystick event
+pub fn parse_js_event(bytes: &[u8; 8]) -> Option<GamepadEvent> {
+    let value = i16::from_ne_bytes([bytes[4], bytes[5]]);
+    let number = bytes[7];
+    match bytes[6] & !JS_EVENT_INIT {
+        JS_EVENT_BUTTON => Some(GamepadEvent::Button {
+            number,
+            pressed: value != 0,
+        }),
+        JS_EVENT_AXIS => Some(GamepadEvent::Axis {
+            number,
+            value: (value as f64 / i16::MAX as f64).clamp(-1.0, 1.0),
+        }),
+        _ => None,
+    }
+}
+
+/// A gamepad opened through the Linux joystick interface
+pub struct GamepadDevice {
+    file: File,
+}
+
+impl GamepadDevice {
+    /// Open a joystick device such as `/dev/input/js0`
+    pub fn open(path: &str) -> Result<Self> {
+        Ok(Self {
+            file: File::open(path)?,
+        })
+    }
+
+    /// Wait for the next event
+    pub fn read_event(&mut self) -> Result<GamepadEvent> {
+        let mut bytes = [0u8; 8];
+        loop {
+            self.file.read_exact(&mut bytes)?;
+            if let Some(event) = parse_js_event(&bytes) {
+                return Ok(event);
+            }
+        }
+    }
+
+    /// Read events on a background thread; the channel closes when the device does
+    pub fn spawn(path: &str) -> Result<Receiver<GamepadEvent>> {
+        let mut device = Self::open(path)?;
+        let (sender, receiver) = mpsc::channel();
+        let path = path.to_string();
+        std::thread::spawn(move || loop {
+            match device.read_event() {
+                Ok(event) => {
+                    if sender.send(event).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Gamepad {} closed: {}", path, e);
+                    break;
+                }
+            }
+        });
+        Ok(receiver)
+    }
+}
+
+/// Turns gamepad events into pendant commands
+pub struct GamepadJogMapper {
+    config: PendantConfig,
+    /// Deflection of each mapped gamepad axis after the deadzone, -1..1
+    deflection: HashMap<u8, f64>,
+    /// Signed speed level per machine axis of the jog in progress
+    jogging: BTreeMap<char, i32>,
+    /// Maximum jog rate per machine axis in units/min
+    max_rates: HashMap<char, f64>,
+}
+
+impl GamepadJogMapper {
+    /// Create new mapper
+    pub fn new(config: PendantConfig) -> Self {
+        Self {
+            config,
+            deflection: HashMap::new(),
+            jogging: BTreeMap::new(),
+            max_rates: HashMap::new(),
+        }
+    }
+
+    /// Limit jogs so no axis moves faster than its maximum rate
+    pub fn with_max_rates(mut self, max_rates: HashMap<char, f64>) -> Self {
+        self.max_rates = max_rates;
+        self
+    }
+
+    /// Whether a jog is in progress
+    pub fn is_jogging(&self) -> bool {
+        !self.jogging.is_empty()
+    }
+
+    /// Handle one event; while `blocked` (e.g. a modal dialog is open) input is
+    /// ignored and a jog in progress is cancelled
+    pub fn handle(&mut self, event: GamepadEvent, blocked: bool) -> Vec<PendantCommand> {
+        if !self.config.enabled {
+            return Vec::new();
+        }
+        if blocked {
+            return self.release_all();
+        }
+        match event {
+            GamepadEvent::Axis { number, value } => {
+                let Some(mapping) = self.config.axis_mappings.get(&number) else {
+                    return Vec::new();
+                };
+                let value = if mapping.invert { -value } else { value };
+                self.deflection
+                    .insert(number, apply_deadzone(value, self.config.deadzone));
+                self.update_jog()
+            }
+            GamepadEvent::Button { number, pressed } => {
+                match (pressed, self.config.button_mappings.get(&(number as u32))) {
+                    (true, Some(button)) => vec![PendantCommand::Action(*button)],
+                    _ => Vec::new(),
+                }
+            }
+        }
+    }
+
+    /// Treat every axis as centred, cancelling the jog in progress
+    pub fn release_all(&mut self) -> Vec<PendantCommand> {
+        self.deflection.clear();
+        self.update_jog()
+    }
+
+    /// Replace the jog in progress if the quantized deflection changed
+    fn update_jog(&mut self) -> Vec<PendantCommand> {
+        let mut levels: BTreeMap<char, i32> = BTreeMap::new();
+        for (number, value) in &self.deflection {
+            let Some(mapping) = self.config.axis_mappings.get(number) else {
+                continue;
+            };
+            let level = if *value == 0.0 {
+                0
+            } else {
+                let level = (value * SPEED_LEVELS).round() as i32;
+                if level == 0 {
+                    value.signum() as i32
+                } else {
+                    level
+                }
+            };
+            let entry = levels.entry(mapping.axis).or_insert(0);
+            if level.abs() > entry.abs() {
+                *entry = level;
+            }
+        }
+        levels.retain(|_, level| *level != 0);
+        if levels == self.jogging {
+            return Vec::new();
+        }
+
+        let mut commands = Vec::new();
+        if self.is_jogging() {
+            commands.push(PendantCommand::JogCancel);
+        }
+        if !levels.is_empty() {
+            commands.push(PendantCommand::Jog(self.jog_command(&levels)));
+        }
+        self.jogging = levels;
+        commands
+    }
+
+    fn jog_command(&self, levels: &BTreeMap<char, i32>) -> String {
+        let strongest = levels.values().map(|l| l.abs()).max().unwrap_or(1) as f64;
+        let deltas: Vec<(char, f64)> = levels
+            .iter()
+            .map(|(axis, level)| (*axis, self.config.jog_travel * *level as f64 / strongest))
+            .collect();
+        let mut command = "$J=G91".to_string();
+        for (axis, distance) in &deltas {
+            command.push_str(&format!(" {}{:.3}", axis, distance));
+        }
+        let feed = self.config.max_jog_feed * self.config.sensitivity * strongest / SPEED_LEVELS;
+        let feed = limited_jog_feed(&deltas, feed, |axis| self.max_rates.get(&axis).copied());
+        command.push_str(&format!(" F{:.0}", feed));
+        command
+    }
+}
+
+/// Rescale a -1..1 value so the deadzone maps to 0 and full deflection to ±1
+fn apply_deadzone(value: f64, deadzone: f64) -> f64 {
+    let deadzone = deadzone.clamp(0.0, 0.99);
+    let magnitude = value.abs().min(1.0);
+    if magnitude <= deadzone {
+        0.0
+    } else {
+        value.signum() * (magnitude - deadzone) / (1.0 - deadzone)
+    }
+}
@@ -1,8 +1,10 @@
 //! Utility functions and helpers
 
 pub mod advanced;
+pub mod batch;
 pub mod export;
 pub mod file_io;
+pub mod gamepad;
 pub mod phase6_extended;
 pub mod phase7;
 pub mod processing;
@@ -12,20 +14,22 @@ pub use advanced::{
     ProbePoint, TemplateLibrary, TemplateVariable, ValidationIssue, ValidationResult,
     ValidationSeverity,
 };
+pub use batch::{collect_gcode_files, process_batch, BatchFileResult, BatchOptions, BatchReport};
 pub use export::{
-    DropEvent, DropFileType, DropIndicatorState, DropTarget, DropZone, ExportOptions, FileExporter,
-    FileFormat,
+    DropEvent, DropFileType, DropHandler, DropIndicatorState, DropQueue, DropSummary, DropTarget,
+    DropZone, ExportOptions, FileExporter, FileFormat,
 };
 pub use file_io::{
     FileEncoding, FileReadStats, FileValidation, GcodeFileReader, RecentFileEntry,
     RecentFilesManager,
 };
+pub use gamepad::{parse_js_event, GamepadDevice, GamepadEvent, GamepadJogMapper, PendantCommand};
 pub use phase6_extended::{
     Alarm, AlarmManager, AlarmType, AutoConnectConfig, Bookmark, BookmarkManager, CommandHistory,
     CustomAction, CustomMacro, DataLogger, HeightPoint, HistoryEntry, LogEntry, NetworkConfig,
-    PendantButton, PendantConfig, PerformanceMetrics, ProbeMesh, ProgramState, SimulationPosition,
-    Simulator, SoftLimits, Stepper, ToolInfo, ToolLibrary, ToolOffset, ToolOffsetManager,
-    WorkCoordinateSystem, WorkOffset,
+    PendantAxis, PendantButton, PendantConfig, PerformanceMetrics, ProbeMesh, ProgramState,
+    SimulationPosition, Simulator, SoftLimits, Stepper, ToolInfo, ToolLibrary, ToolOffset,
+    ToolOffsetManager, WorkCoordinateSystem, WorkOffset,
 };
 pub use phase7::{
     BufferDiagnostics, CalibrationResult, CalibrationStep, CalibrationStepType, CalibrationWizard,
@@ -22,86 +22,14 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 // ============================================================================
 // TASK 103: AUTO-LEVELING PROBE MESH
 // ============================================================================
 
-/// Height map data point
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-pub struct HeightPoint {
-    /// X coordinate
-    pub x: f64,
-    /// Y coordinate
-    pub y: f64,
-    /// Z height at this point
-    pub z: f64,
-}
-
-/// Probe mesh for auto-leveling
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ProbeMesh {
-    /// Grid points
-    pub points: Vec<HeightPoint>,
-    /// Grid X spacing
-    pub x_spacing: f64,
-    /// Grid Y spacing
-    pub y_spacing: f64,
-    /// Minimum Z found
-    pub z_min: f64,
-    /// Maximum Z found
-    pub z_max: f64,
-}
-
-impl ProbeMesh {
-    /// Create new probe mesh
-    pub fn new(x_spacing: f64, y_spacing: f64) -> Self {
-        Self {
-            points: Vec::new(),
-            x_spacing,
-            y_spacing,
-            z_min: f64::MAX,
-            z_max: f64::MIN,
-        }
-    }
-
-    /// Add probe point
-    pub fn add_point(&mut self, point: HeightPoint) {
-        self.z_min = self.z_min.min(point.z);
-        self.z_max = self.z_max.max(point.z);
-        self.points.push(point);
-    }
-
-    /// Get Z offset at position (interpolated)
-    pub fn get_z_offset(&self, x: f64, y: f64) -> Option<f64> {
-        if self.points.is_empty() {
-            return None;
-        }
-
-        // Find 4 nearest points for bilinear interpolation
-        let mut nearest = self
-            .points
-            .iter()
-            .map(|p| (p, (p.x - x).powi(2) + (p.y - y).powi(2)))
-            .collect::<Vec<_>>();
-        nearest.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
-
-        if nearest.len() < 4 {
-            return Some(nearest[0].0.z);
-        }
-
-        // Simple average of 4 nearest
-        let avg = nearest[..4].iter().map(|(p, _)| p.z).sum::<f64>() / 4.0;
-        Some(avg)
-    }
-
-    /// Get mesh statistics
-    pub fn stats(&self) -> (usize, f64, f64) {
-        (self.points.len(), self.z_min, self.z_max)
-    }
-}
+pub use gcodekit5_core::{HeightPoint, ProbeMesh};
 
 // ============================================================================
 // TASK 104: TOOL CHANGE MANAGEMENT
@@ -892,15 +820,50 @@ pub enum PendantButton {
     SpeedUp,
     /// Speed override -
     SpeedDown,
+    /// Home the machine
+    Home,
+    /// Zero all work axes
+    ZeroAll,
+    /// Feed hold
+    FeedHold,
+    /// Resume after a feed hold
+    Resume,
+}
+
+/// Machine axis driven by a pendant/gamepad axis
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendantAxis {
+    /// Machine axis letter (X, Y, Z, A or B)
+    pub axis: char,
+    /// Reverse the direction (gamepad sticks report up as negative)
+    pub invert: bool,
+}
+
+impl PendantAxis {
+    /// Create new axis mapping
+    pub fn new(axis: char, invert: bool) -> Self {
+        Self { axis, invert }
+    }
 }
 
 /// Pendant configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PendantConfig {
     /// Device path
     pub device_path: String,
     /// Button mappings
     pub button_mappings: HashMap<u32, PendantButton>,
+    /// Gamepad axis mappings, by axis number
+    pub axis_mappings: HashMap<u8, PendantAxis>,
+    /// Fraction of an axis' travel (0..1) ignored around the centre
+    pub deadzone: f64,
+    /// Multiplier applied to the jog feed at full deflection
+    pub sensitivity: f64,
+    /// Jog feed rate at full deflection in mm/min
+    pub max_jog_feed: f64,
+    /// Length of a continuous jog in mm; it is cancelled when the axis is released
+    pub jog_travel: f64,
     /// Enabled
     pub enabled: bool,
 }
@@ -911,9 +874,49 @@ impl PendantConfig {
         Self {
             device_path: device_path.into(),
             button_mappings: HashMap::new(),
+            axis_mappings: HashMap::new(),
+            deadzone: 0.15,
+            sensitivity: 1.0,
+            max_jog_feed: 2000.0,
+            jog_travel: 1000.0,
             enabled: false,
         }
     }
+
+    /// Config with the usual gamepad layout: left stick X/Y, right stick Z,
+    /// A resume, B feed hold, X zero all, Y home
+    pub fn gamepad(device_path: impl Into<String>) -> Self {
+        let mut config = Self::new(device_path);
+        config.axis_mappings.insert(0, PendantAxis::new('X', false));
+        config.axis_mappings.insert(1, PendantAxis::new('Y', true));
+        config.axis_mappings.insert(4, PendantAxis::new('Z', true));
+        config.button_mappings.insert(0, PendantButton::Resume);
+        config.button_mappings.insert(1, PendantButton::FeedHold);
+        config.button_mappings.insert(2, PendantButton::ZeroAll);
+        config.button_mappings.insert(3, PendantButton::Home);
+        config
+    }
+
+    /// Load a config, or the disabled default if the file does not exist
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Save the config as JSON
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+impl Default for PendantConfig {
+    fn default() -> Self {
+        Self::gamepad("/dev/input/js0")
+    }
 }
 
 // ============================================================================
@@ -0,0 +1,384 @@
+//! Simulated laser burn preview
+//!
+//! Rasterizes the burning moves of a laser program into an image of how dark
+//! the engraving comes out on the material. The darkness of a pass follows the
+//! energy it puts into the material, power over speed: `S / max_power` scaled
+//! by `full_burn_feed / F`, so full power at the full-burn feed or slower burns
+//! black and half power or double speed burns half as dark. Passes over the
+//! same spot add up, as repeated passes char the material further; within one
+//! pass touching lines do not.
+//!
+//! The moves come from the visualizer's parser, so arcs, planes and block
+//! delete match the toolpath view. The laser fires only between `M3`/`M4` and
+//! `M5` with a positive `S`; rapids never burn and dwells are not drawn.
+
+use super::view_export::arc_points;
+use super::viewport::Bounds;
+use super::visualizer::{GCodeCommand, Point3D, Visualizer};
+use crate::error::{VisualizationError, VisualizationResult};
+use gcodekit5_core::{split_block_delete, ArcPlane, SpindlePower};
+use image::{ImageFormat, Rgb, RgbImage};
+use std::path::Path;
+
+/// Largest image rendered, in pixels
+const MAX_PIXELS: usize = 100_000_000;
+const MM_PER_INCH: f64 = 25.4;
+
+/// Colors of the material and of a full burn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MaterialTint {
+    /// White to black
+    #[default]
+    Grayscale,
+    /// Light plywood charring to dark brown
+    Wood,
+    Cardboard,
+    Leather,
+}
+
+impl MaterialTint {
+    /// The (material, full burn) colors
+    pub fn colors(&self) -> ([u8; 3], [u8; 3]) {
+        match self {
+            Self::Grayscale => ([0xff, 0xff, 0xff], [0x00, 0x00, 0x00]),
+            Self::Wood => ([0xe8, 0xc9, 0x9b], [0x2b, 0x17, 0x0b]),
+            Self::Cardboard => ([0xc8, 0xa2, 0x78], [0x33, 0x22, 0x15]),
+            Self::Leather => ([0xa8, 0x6b, 0x3c], [0x24, 0x12, 0x08]),
+        }
+    }
+}
+
+/// How the burn preview is rendered
+#[derive(Debug, Clone, PartialEq)]
+pub struct BurnPreviewOptions {
+    /// Image resolution, pixels per inch of material
+    pub dpi: f64,
+    /// `S` value of full laser power, as in GRBL's `$30`
+    pub max_power: f64,
+    /// Feed rate (mm/min) at which full power burns black
+    pub full_burn_feed: f64,
+    /// Diameter of the laser spot on the material (mm)
+    pub spot_size_mm: f64,
+    /// Blank material around the burn (mm)
+    pub margin_mm: f64,
+    /// Skip lines starting with `/`, as the controller would
+    pub block_delete: bool,
+    pub tint: MaterialTint,
+}
+
+impl Default for BurnPreviewOptions {
+    fn default() -> Self {
+        Self {
+            dpi: 254.0,
+            max_power: 1000.0,
+            full_burn_feed: 1000.0,
+            spot_size_mm: 0.1,
+            margin_mm: 2.0,
+            block_delete: true,
+            tint: MaterialTint::Grayscale,
+        }
+    }
+}
+
+/// A rendered burn preview: darkness from 0 (untouched) to 1 (fully burnt)
+#[derive(Debug, Clone, PartialEq)]
+pub struct BurnPreview {
+    pub width: u32,
+    pub height: u32,
+    pub dpi: f64,
+    /// Model X and Y (mm) of the image's top-left corner
+    pub origin: (f64, f64),
+    darkness: Vec<f32>,
+    tint: MaterialTint,
+}
+
+impl BurnPreview {
+    /// Darkness of every pixel, row by row from the top
+    pub fn darkness(&self) -> &[f32] {
+        &self.darkness
+    }
+
+    /// Darkness of the pixel at column `x`, row `y` from the top
+    pub fn darkness_at(&self, x: u32, y: u32) -> f32 {
+        self.darkness[y as usize * self.width as usize + x as usize]
+    }
+
+    /// Darkness of the pixel covering model point (`x`, `y`), if it is in the image
+    pub fn darkness_at_point(&self, x: f64, y: f64) -> Option<f32> {
+        let pixel = MM_PER_INCH / self.dpi;
+        let column = ((x - self.origin.0) / pixel).floor();
+        let row = ((self.origin.1 - y) / pixel).floor();
+        let inside =
+            column >= 0.0 && row >= 0.0 && column < self.width as f64 && row < self.height as f64;
+        inside.then(|| self.darkness_at(column as u32, row as u32))
+    }
+
+    /// The preview in the material's colors
+    pub fn to_image(&self) -> RgbImage {
+        let (material, burn) = self.tint.colors();
+        RgbImage::from_fn(self.width, self.height, |x, y| {
+            let d = self.darkness_at(x, y);
+            Rgb(std::array::from_fn(|i| {
+                (material[i] as f32 + (burn[i] as f32 - material[i] as f32) * d).round() as u8
+            }))
+        })
+    }
+
+    /// Write the preview to an image file; the format follows the extension
+    pub fn save(&self, path: &Path) -> VisualizationResult<()> {
+        let format = ImageFormat::from_path(path)
+            .map_err(|_| VisualizationError::UnsupportedFormat(path.display().to_string()))?;
+        self.to_image()
+            .save_with_format(path, format)
+            .map_err(|e| match e {
+                image::ImageError::IoError(e) => VisualizationError::IoError(e),
+                e => VisualizationError::RenderingFailed(e.to_string()),
+            })
+    }
+}
+
+/// A burning move, flattened to a polyline in model space (mm)
+struct Burn {
+    points: Vec<(f64, f64)>,
+    /// Darkness of one pass, 0 to 1
+    darkness: f32,
+    /// First move of a new pass
+    starts_pass: bool,
+}
+
+/// Render how `gcode` would burn into the material
+///
+/// Fails if the options are out of range, nothing in the program burns, or the
+/// image would be larger than 100 megapixels.
+pub fn render_burn_preview(
+    gcode: &str,
+    options: &BurnPreviewOptions,
+) -> VisualizationResult<BurnPreview> {
+    let positive = |v: f64| v.is_finite() && v > 0.0;
+    if !(positive(options.dpi)
+        && positive(options.max_power)
+        && positive(options.full_burn_feed)
+        && positive(options.spot_size_mm)
+        && options.margin_mm.is_finite()
+        && options.margin_mm >= 0.0)
+    {
+        return Err(VisualizationError::RenderingFailed(format!(
+            "burn preview options out of range: {:?}",
+            options
+        )));
+    }
+    let pixel = MM_PER_INCH / options.dpi;
+    let burns = burning_moves(gcode, options, pixel / 2.0);
+
+    let radius = (options.spot_size_mm / 2.0).max(pixel / 2.0);
+    let (min_x, min_y, max_x, max_y) = burns
+        .iter()
+        .flat_map(|burn| burn.points.iter())
+        .fold(None, |acc: Option<(f64, f64, f64, f64)>, &(x, y)| {
+            Some(match acc {
+                None => (x, y, x, y),
+                Some((a, b, c, d)) => (a.min(x), b.min(y), c.max(x), d.max(y)),
+            })
+        })
+        .ok_or(VisualizationError::InvalidToolpath)?;
+    let pad = radius + options.margin_mm;
+    let origin = (min_x - pad, max_y + pad);
+    let width = ((max_x - min_x + 2.0 * pad) / pixel).ceil().max(1.0);
+    let height = ((max_y - min_y + 2.0 * pad) / pixel).ceil().max(1.0);
+    let pixels = width * height;
+    if pixels > MAX_PIXELS as f64 {
+        return Err(VisualizationError::AllocationFailed {
+            required_bytes: (pixels as usize).saturating_mul(2 * std::mem::size_of::<f32>()),
+        });
+    }
+    let (width, height) = (width as usize, height as usize);
+
+    let mut raster = Raster {
+        width,
+        height,
+        origin,
+        pixel,
+        radius,
+        darkness: vec![0.0; width * height],
+        pass: vec![0.0; width * height],
+        touched: Vec::new(),
+    };
+    for burn in &burns {
+        if burn.starts_pass {
+            raster.end_pass();
+        }
+        for pair in burn.points.windows(2) {
+            raster.stroke(pair[0], pair[1], burn.darkness);
+        }
+    }
+    raster.end_pass();
+    tracing::debug!(
+        "Burn preview: {} burning moves, {}x{} px at {} dpi",
+        burns.len(),
+        width,
+        height,
+        options.dpi
+    );
+
+    Ok(BurnPreview {
+        width: width as u32,
+        height: height as u32,
+        dpi: options.dpi,
+        origin,
+        darkness: raster.darkness,
+        tint: options.tint,
+    })
+}
+
+/// The moves that burn, with arcs flattened to within `tolerance`
+fn burning_moves(gcode: &str, options: &BurnPreviewOptions, tolerance: f64) -> Vec<Burn> {
+    let mut burns: Vec<Burn> = Vec::new();
+    let mut commands = Vec::new();
+    let mut position = Point3D::new(0.0, 0.0, 0.0);
+    let mut intensity = 0.0;
+    let mut plane = ArcPlane::XY;
+    let mut power = SpindlePower::default();
+    let mut feed = options.full_burn_feed;
+    let mut burning = false;
+
+    for (line_num, line) in gcode.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') || line.starts_with('(') {
+            continue;
+        }
+        let (line, optional) = split_block_delete(line);
+        if optional && options.block_delete {
+            continue;
+        }
+        // The spindle words of a line apply before its motion
+        power.observe(line);
+        for part in line.split_whitespace() {
+            if let Some(value) = part.strip_prefix('F').and_then(|v| v.parse::<f64>().ok()) {
+                if value > 0.0 {
+                    feed = value;
+                }
+            }
+        }
+
+        commands.clear();
+        Visualizer::parse_motion_line(
+            &mut commands,
+            line,
+            line_num,
+            &mut position,
+            &mut intensity,
+            &mut Bounds::new(),
+            &mut plane,
+            &mut Vec::new(),
+        );
+        for command in &commands {
+            let points = match command {
+                GCodeCommand::Move {
+                    from,
+                    to,
+                    rapid: false,
+                    ..
+                } => vec![(from.x as f64, from.y as f64), (to.x as f64, to.y as f64)],
+                GCodeCommand::Arc {
+                    from,
+                    to,
+                    center,
+                    clockwise,
+                    ..
+                } => arc_points(
+                    (from.x as f64, from.y as f64),
+                    (to.x as f64, to.y as f64),
+                    (center.x as f64, center.y as f64),
+                    *clockwise,
+                    tolerance,
+                ),
+                _ => {
+                    burning = false;
+                    continue;
+                }
+            };
+            // S words on lines without motion count too, unlike the move intensity
+            let ratio = (power.speed.unwrap_or(0.0) / options.max_power).clamp(0.0, 1.0);
+            let darkness = (ratio * options.full_burn_feed / feed).clamp(0.0, 1.0) as f32;
+            if !power.is_on() || darkness <= 0.0 {
+                burning = false;
+                continue;
+            }
+            burns.push(Burn {
+                points,
+                darkness,
+                starts_pass: !burning,
+            });
+            burning = true;
+        }
+    }
+    burns
+}
+
+/// Darkness being accumulated pass by pass
+struct Raster {
+    width: usize,
+    height: usize,
+    origin: (f64, f64),
+    pixel: f64,
+    /// Radius of the laser spot (mm)
+    radius: f64,
+    /// Passes finished so far
+    darkness: Vec<f32>,
+    /// The pass being drawn
+    pass: Vec<f32>,
+    /// Pixels the pass being drawn has touched
+    touched: Vec<usize>,
+}
+
+impl Raster {
+    /// Burn a straight line with the spot, antialiased over one pixel
+    fn stroke(&mut self, a: (f64, f64), b: (f64, f64), darkness: f32) {
+        let reach = self.radius + self.pixel;
+        let column = |x: f64| ((x - self.origin.0) / self.pixel).floor();
+        let row = |y: f64| ((self.origin.1 - y) / self.pixel).floor();
+        let clamp_x = |v: f64| v.clamp(0.0, self.width as f64 - 1.0) as usize;
+        let clamp_y = |v: f64| v.clamp(0.0, self.height as f64 - 1.0) as usize;
+        let (x0, x1) = (
+            clamp_x(column(a.0.min(b.0) - reach)),
+            clamp_x(column(a.0.max(b.0) + reach)),
+        );
+        let (y0, y1) = (
+            clamp_y(row(a.1.max(b.1) + reach)),
+            clamp_y(row(a.1.min(b.1) - reach)),
+        );
+
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let length_sq = dx * dx + dy * dy;
+        for y in y0..=y1 {
+            let py = self.origin.1 - (y as f64 + 0.5) * self.pixel;
+            for x in x0..=x1 {
+                let px = self.origin.0 + (x as f64 + 0.5) * self.pixel;
+                let t = if length_sq > 0.0 {
+                    (((px - a.0) * dx + (py - a.1) * dy) / length_sq).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let distance = (px - (a.0 + dx * t)).hypot(py - (a.1 + dy * t));
+                let coverage = ((self.radius - distance) / self.pixel + 0.5).clamp(0.0, 1.0);
+                if coverage <= 0.0 {
+                    continue;
+                }
+                let index = y * self.width + x;
+                let value = darkness * coverage as f32;
+                if self.pass[index] == 0.0 {
+                    self.touched.push(index);
+                }
+                self.pass[index] = self.pass[index].max(value);
+            }
+        }
+    }
+
+    /// Add the pass being drawn onto the finished ones
+    fn end_pass(&mut self) {
+        for index in self.touched.drain(..) {
+            let pass = std::mem::take(&mut self.pass[index]);
+            self.darkness[index] = 1.0 - (1.0 - self.darkness[index]) * (1.0 - pass);
+        }
+    }
+}
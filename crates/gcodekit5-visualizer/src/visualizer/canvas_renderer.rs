@@ -127,6 +127,35 @@ pub fn render_grid_to_path(visualizer: &Visualizer, width: u32, height: u32) ->
     (path, step)
 }
 
+/// Minor grid lines between two major lines
+pub const GRID_MINOR_DIVISIONS: i64 = 5;
+
+/// Positions of major and minor grid lines from `min` to `max` along one axis
+///
+/// Major lines are `spacing` apart and pass through `origin`, so the grid can
+/// be aligned to a work zero or any other point; minor lines split each major
+/// cell into [`GRID_MINOR_DIVISIONS`]. World origin is `origin` 0.
+pub fn grid_lines(min: f64, max: f64, spacing: f64, origin: f64) -> (Vec<f64>, Vec<f64>) {
+    let mut major = Vec::new();
+    let mut minor = Vec::new();
+    if !(spacing > 0.0 && min <= max && origin.is_finite()) {
+        return (major, minor);
+    }
+    let minor_spacing = spacing / GRID_MINOR_DIVISIONS as f64;
+    // Counting whole steps from the origin keeps lines from drifting
+    let first = ((min - origin) / minor_spacing).ceil() as i64;
+    let last = ((max - origin) / minor_spacing).floor() as i64;
+    for step in first..=last {
+        let position = origin + step as f64 * minor_spacing;
+        if step.rem_euclid(GRID_MINOR_DIVISIONS) == 0 {
+            major.push(position);
+        } else {
+            minor.push(position);
+        }
+    }
+    (major, minor)
+}
+
 /// Render intensity overlay as multiple SVG path layers (buckets)
 /// Returns 10 layers corresponding to 10%, 20%, ..., 100% opacity
 pub fn render_intensity_overlay(
@@ -280,6 +309,18 @@ mod tests {
         assert!(path.contains("L"));
     }
 
+    #[test]
+    fn test_grid_lines_align_to_origin() {
+        let (major, minor) = grid_lines(-10.0, 30.0, 10.0, 0.0);
+        assert_eq!(major, vec![-10.0, 0.0, 10.0, 20.0, 30.0]);
+        assert_eq!(minor.len(), 16);
+
+        let (major, minor) = grid_lines(0.0, 30.0, 10.0, 12.5);
+        assert_eq!(major, vec![2.5, 12.5, 22.5]);
+        assert!((minor[0] - 0.5).abs() < 1e-9);
+        assert!(minor.iter().all(|m| !major.contains(m)));
+    }
+
     #[test]
     fn test_grid_visibility() {
         let mut visualizer = Visualizer::new();
@@ -4,9 +4,12 @@
 //! - 3D rendering engine (setup)
 //! - Toolpath visualization (rendering)
 //! - Interactive camera controls (controls)
+//! - SVG/PDF export of the 2D toolpath (view_export)
+//! - Simulated laser burn image (burn_preview)
 //! - Grid and axis rendering
 //! - 3D mesh rendering for STL models
 
+pub mod burn_preview;
 pub mod camera;
 pub mod canvas_renderer;
 pub mod controls;
@@ -14,19 +17,22 @@ pub mod features;
 pub mod mesh_renderer;
 pub mod mesh_rendering;
 pub mod mesh_shaders;
+pub mod operations;
 pub mod scene3d;
 pub mod setup;
 pub mod stock_removal_3d;
 pub mod toolpath_cache;
 pub mod toolpath_rendering;
+pub mod view_export;
 pub mod viewport;
 // Module name matches parent — acceptable for the primary visualizer type.
 #[allow(clippy::module_inception)]
 pub mod visualizer;
 
+pub use burn_preview::{render_burn_preview, BurnPreview, BurnPreviewOptions, MaterialTint};
 pub use camera::Camera as Camera3D;
 pub use canvas_renderer::{
-    render_g1_to_path, render_g2_to_path, render_g3_to_path, render_g4_to_path,
+    grid_lines, render_g1_to_path, render_g2_to_path, render_g3_to_path, render_g4_to_path,
     render_grid_to_path, render_intensity_overlay, render_origin_to_path,
     render_rapid_moves_to_path, render_toolpath_to_path,
 };
@@ -36,6 +42,7 @@ pub use features::{
 };
 pub use mesh_renderer::{LightingParams, MeshRenderError, MeshRenderer};
 pub use mesh_rendering::{MeshCollection, MeshMaterial, RenderableMesh};
+pub use operations::ProgramOperation;
 pub use scene3d::{stl_integration, Renderer3D, Scene3D, Scene3DStats};
 pub use setup::{Camera, CameraType, Color, Light, LightType, Renderer, Scene, Vector3};
 pub use stock_removal_3d::{
@@ -45,6 +52,10 @@ pub use toolpath_cache::ToolpathCache;
 pub use toolpath_rendering::{
     ArcSegment, LineSegment, MovementType, PathSegment, Toolpath, ToolpathStats,
 };
+pub use view_export::{
+    export_view, export_view_pdf, export_view_svg, PaperOrientation, PaperSize, ViewExportFormat,
+    ViewExportOptions,
+};
 pub use viewport::{Bounds, ViewportTransform};
 pub use visualizer::{GCodeCommand, Point3D, Visualizer};
 
@@ -0,0 +1,197 @@
+//! Operation segmentation for the visualizer
+//!
+//! Splits a parsed program into operations at tool changes (`M6`, with the tool
+//! from the same line or the last `T` word) and at operation comments such as
+//! `; Operation: Pocket` or `(Toolpath: Contour 1)`. Each operation covers a
+//! contiguous range of the visualizer's commands, so it can be hidden or soloed.
+
+use std::ops::Range;
+
+/// Comment prefixes that start a new operation (case-insensitive, followed by `:`)
+const LABEL_PREFIXES: [&str; 3] = ["operation", "toolpath", "op"];
+
+/// One operation of a program
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgramOperation {
+    /// Display name: the operation comment, the tool, or "Operation N"
+    pub name: String,
+    /// Tool in use, if a tool change selected one
+    pub tool: Option<u32>,
+    /// 1-based line where the operation starts
+    pub line: usize,
+    /// Indices of the operation's commands in [`super::Visualizer::commands`]
+    pub commands: Range<usize>,
+    /// Whether the operation is drawn
+    pub visible: bool,
+}
+
+/// What a line contributes to segmentation
+#[derive(Debug, Clone, PartialEq)]
+enum OperationMark {
+    /// `Tn` without a tool change: selects the tool for the next `M6`
+    Tool(u32),
+    /// `M6`, with the tool it loads if known
+    ToolChange(Option<u32>),
+    /// An operation comment
+    Label(String),
+}
+
+/// An operation being built
+struct Pending {
+    label: Option<String>,
+    tool: Option<u32>,
+    line: usize,
+    start: usize,
+}
+
+/// Builds operations while the visualizer parses a program
+#[derive(Default)]
+pub(crate) struct OperationSegmenter {
+    done: Vec<Pending>,
+    current: Option<Pending>,
+    selected_tool: Option<u32>,
+    tool: Option<u32>,
+}
+
+impl OperationSegmenter {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Look at a trimmed line (comments included) before its moves are parsed;
+    /// `command_count` is the number of commands parsed so far
+    pub(crate) fn mark(&mut self, line: &str, line_num: usize, command_count: usize) {
+        match operation_mark(line) {
+            Some(OperationMark::Tool(tool)) => self.selected_tool = Some(tool),
+            Some(OperationMark::ToolChange(tool)) => {
+                let tool = tool.or(self.selected_tool);
+                self.tool = tool;
+                self.start(line_num, command_count).tool = tool;
+            }
+            Some(OperationMark::Label(label)) => {
+                self.start(line_num, command_count).label = Some(label);
+            }
+            None => {}
+        }
+    }
+
+    /// The operation a new mark applies to: the current one if it has no commands
+    /// yet, otherwise a new one starting at `command_count`
+    fn start(&mut self, line_num: usize, command_count: usize) -> &mut Pending {
+        match self.current.take() {
+            Some(current) if current.start == command_count => self.current.insert(current),
+            previous => {
+                // Moves before the first mark form an operation of their own
+                let leading = previous.or_else(|| {
+                    (command_count > 0).then_some(Pending {
+                        label: None,
+                        tool: None,
+                        line: 1,
+                        start: 0,
+                    })
+                });
+                self.done.extend(leading);
+                self.current.insert(Pending {
+                    label: None,
+                    tool: self.tool,
+                    line: line_num + 1,
+                    start: command_count,
+                })
+            }
+        }
+    }
+
+    /// The operations of the program, all visible; empty ones are dropped
+    pub(crate) fn finish(mut self, command_count: usize) -> Vec<ProgramOperation> {
+        self.done.extend(self.current.take());
+        if self.done.is_empty() {
+            // No marks: the whole program is one operation
+            self.done.push(Pending {
+                label: None,
+                tool: None,
+                line: 1,
+                start: 0,
+            });
+        }
+        let ends: Vec<usize> = self
+            .done
+            .iter()
+            .skip(1)
+            .map(|pending| pending.start)
+            .chain(std::iter::once(command_count))
+            .collect();
+        self.done
+            .into_iter()
+            .zip(ends)
+            .filter(|(pending, end)| pending.start < *end)
+            .enumerate()
+            .map(|(index, (pending, end))| ProgramOperation {
+                name: pending
+                    .label
+                    .or_else(|| pending.tool.map(|tool| format!("T{}", tool)))
+                    .unwrap_or_else(|| format!("Operation {}", index + 1)),
+                tool: pending.tool,
+                line: pending.line,
+                commands: pending.start..end,
+                visible: true,
+            })
+            .collect()
+    }
+}
+
+fn operation_mark(line: &str) -> Option<OperationMark> {
+    if let Some(comment) = line.strip_prefix(';') {
+        return operation_label(comment);
+    }
+    if let Some(comment) = line.strip_prefix('(') {
+        return operation_label(comment.trim_end().trim_end_matches(')'));
+    }
+
+    let code = line.split(';').next().unwrap_or_default();
+    let mut tool = None;
+    let mut change = false;
+    for (letter, value) in words(code) {
+        match letter {
+            'T' => tool = value.parse::<u32>().ok(),
+            'M' => change |= value.parse::<f64>().is_ok_and(|m| m == 6.0),
+            _ => {}
+        }
+    }
+    match (change, tool) {
+        (true, tool) => Some(OperationMark::ToolChange(tool)),
+        (false, Some(tool)) => Some(OperationMark::Tool(tool)),
+        (false, None) => None,
+    }
+}
+
+/// The name in an operation comment such as `Operation: Pocket`
+fn operation_label(comment: &str) -> Option<OperationMark> {
+    let comment = comment.trim();
+    let lower = comment.to_ascii_lowercase();
+    LABEL_PREFIXES.iter().find_map(|prefix| {
+        let rest = lower.strip_prefix(prefix)?.trim_start();
+        rest.strip_prefix(':')?;
+        let name = comment[comment.len() - rest.len() + 1..].trim();
+        (!name.is_empty()).then(|| OperationMark::Label(name.to_string()))
+    })
+}
+
+/// Letter/value words of a line, skipping parenthesized comments
+fn words(code: &str) -> Vec<(char, String)> {
+    let mut words: Vec<(char, String)> = Vec::new();
+    let mut in_comment = false;
+    for c in code.chars() {
+        match c {
+            '(' => in_comment = true,
+            ')' => in_comment = false,
+            _ if in_comment || c.is_whitespace() => {}
+            _ if c.is_ascii_alphabetic() => words.push((c.to_ascii_uppercase(), String::new())),
+            _ => {
+                if let Some((_, value)) = words.last_mut() {
+                    value.push(c);
+                }
+            }
+        }
+    }
+    words
+}
@@ -0,0 +1,600 @@
+//! Vector export of the 2D toolpath for job sheets
+//!
+//! Draws the visible operations of a parsed program onto a paper page, scaled to
+//! the largest standard ratio (1:1, 1:2, 2:1, ...) that fits, with a scale bar in
+//! the chosen units and a title block (name, bounds, estimated time and scale).
+//! Cutting moves and rapids are separate, colored layers. The page is written as
+//! SVG or PDF; both use millimetres on paper, so printed lengths match the scale.
+
+use super::visualizer::{GCodeCommand, Visualizer};
+use crate::error::{VisualizationError, VisualizationResult};
+use gcodekit5_core::units::{format_length, get_unit_label, MeasurementSystem};
+use std::path::Path;
+
+/// Page margin around the drawing (mm)
+const MARGIN_MM: f64 = 10.0;
+/// Height reserved for the title block (mm)
+const TITLE_BLOCK_MM: f64 = 24.0;
+/// Flattening tolerance for arcs, on paper (mm)
+const ARC_TOLERANCE_MM: f64 = 0.05;
+/// PDF points per millimetre
+const PT_PER_MM: f64 = 72.0 / 25.4;
+/// Standard drawing scales, as paper length per model length
+const SCALES: [(f64, &str); 15] = [
+    (10.0, "10:1"),
+    (5.0, "5:1"),
+    (2.0, "2:1"),
+    (1.0, "1:1"),
+    (0.5, "1:2"),
+    (0.4, "1:2.5"),
+    (0.2, "1:5"),
+    (0.1, "1:10"),
+    (0.05, "1:20"),
+    (0.04, "1:25"),
+    (0.02, "1:50"),
+    (0.01, "1:100"),
+    (0.005, "1:200"),
+    (0.002, "1:500"),
+    (0.001, "1:1000"),
+];
+const CUT_COLOR: [u8; 3] = [0x15, 0x65, 0xc0];
+const RAPID_COLOR: [u8; 3] = [0xe6, 0x7e, 0x22];
+const INK_COLOR: [u8; 3] = [0x20, 0x20, 0x20];
+
+/// Paper sizes for the export
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PaperSize {
+    #[default]
+    A4,
+    A3,
+    Letter,
+    Legal,
+    /// Width and height in mm (portrait)
+    Custom {
+        width_mm: f64,
+        height_mm: f64,
+    },
+}
+
+impl PaperSize {
+    /// Portrait width and height in mm
+    pub fn dimensions_mm(&self) -> (f64, f64) {
+        match *self {
+            Self::A4 => (210.0, 297.0),
+            Self::A3 => (297.0, 420.0),
+            Self::Letter => (215.9, 279.4),
+            Self::Legal => (215.9, 355.6),
+            Self::Custom {
+                width_mm,
+                height_mm,
+            } => (width_mm, height_mm),
+        }
+    }
+}
+
+/// Page orientation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PaperOrientation {
+    Portrait,
+    #[default]
+    Landscape,
+}
+
+/// File format of the export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewExportFormat {
+    Svg,
+    Pdf,
+}
+
+impl ViewExportFormat {
+    /// Format for a file name's extension (`.svg` or `.pdf`)
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "svg" => Some(Self::Svg),
+            "pdf" => Some(Self::Pdf),
+            _ => None,
+        }
+    }
+}
+
+/// What goes on the exported page
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewExportOptions {
+    pub paper: PaperSize,
+    pub orientation: PaperOrientation,
+    /// Title shown in the title block, typically the file name
+    pub title: String,
+    /// Draw cutting moves (G1/G2/G3)
+    pub show_cuts: bool,
+    /// Draw rapid moves (G0)
+    pub show_rapids: bool,
+    /// Units of the bounds and scale bar
+    pub units: MeasurementSystem,
+    /// Estimated run time in seconds, if known
+    pub estimated_time_secs: Option<f64>,
+}
+
+impl Default for ViewExportOptions {
+    fn default() -> Self {
+        Self {
+            paper: PaperSize::default(),
+            orientation: PaperOrientation::default(),
+            title: String::new(),
+            show_cuts: true,
+            show_rapids: true,
+            units: MeasurementSystem::Metric,
+            estimated_time_secs: None,
+        }
+    }
+}
+
+/// A polyline on the page, in mm from the top-left corner
+struct Stroke {
+    points: Vec<(f64, f64)>,
+    color: [u8; 3],
+    width: f64,
+    dashed: bool,
+}
+
+/// A line of text on the page; `y` is the baseline
+struct Text {
+    x: f64,
+    y: f64,
+    size: f64,
+    content: String,
+}
+
+/// The laid-out page
+struct Page {
+    width: f64,
+    height: f64,
+    /// Strokes in layers: cuts, rapids, then annotations
+    layers: Vec<(&'static str, Vec<Stroke>)>,
+    texts: Vec<Text>,
+}
+
+/// Render the visible toolpath as an SVG document
+pub fn export_view_svg(
+    visualizer: &Visualizer,
+    options: &ViewExportOptions,
+) -> VisualizationResult<String> {
+    Ok(render_svg(&layout(visualizer, options)?))
+}
+
+/// Render the visible toolpath as a single-page PDF document
+pub fn export_view_pdf(
+    visualizer: &Visualizer,
+    options: &ViewExportOptions,
+) -> VisualizationResult<Vec<u8>> {
+    Ok(render_pdf(&layout(visualizer, options)?))
+}
+
+/// Write the visible toolpath to an `.svg` or `.pdf` file
+pub fn export_view(
+    visualizer: &Visualizer,
+    path: &Path,
+    options: &ViewExportOptions,
+) -> VisualizationResult<()> {
+    let bytes = match ViewExportFormat::from_path(path) {
+        Some(ViewExportFormat::Svg) => export_view_svg(visualizer, options)?.into_bytes(),
+        Some(ViewExportFormat::Pdf) => export_view_pdf(visualizer, options)?,
+        None => {
+            return Err(VisualizationError::UnsupportedFormat(
+                path.display().to_string(),
+            ))
+        }
+    };
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// Polylines in model space (mm)
+type Polylines = Vec<Vec<(f64, f64)>>;
+
+/// The visible moves as (cuts, rapids) polylines; arcs are flattened for `scale`
+fn toolpath_polylines(visualizer: &Visualizer, scale: f64) -> (Polylines, Polylines) {
+    let mut cuts: Polylines = Vec::new();
+    let mut rapids: Polylines = Vec::new();
+    for cmd in visualizer.visible_commands() {
+        match cmd {
+            GCodeCommand::Move {
+                from, to, rapid, ..
+            } => {
+                let target = if *rapid { &mut rapids } else { &mut cuts };
+                let start = (from.x as f64, from.y as f64);
+                match target.last_mut() {
+                    Some(line) if line.last() == Some(&start) => {
+                        line.push((to.x as f64, to.y as f64))
+                    }
+                    _ => target.push(vec![start, (to.x as f64, to.y as f64)]),
+                }
+            }
+            GCodeCommand::Arc {
+                from,
+                to,
+                center,
+                clockwise,
+                ..
+            } => {
+                let points = arc_points(
+                    (from.x as f64, from.y as f64),
+                    (to.x as f64, to.y as f64),
+                    (center.x as f64, center.y as f64),
+                    *clockwise,
+                    ARC_TOLERANCE_MM / scale,
+                );
+                match cuts.last_mut() {
+                    Some(line) if line.last() == points.first() => {
+                        line.extend_from_slice(&points[1..])
+                    }
+                    _ => cuts.push(points),
+                }
+            }
+            GCodeCommand::Dwell { .. } => {}
+        }
+    }
+    (cuts, rapids)
+}
+
+pub(super) fn arc_points(
+    from: (f64, f64),
+    to: (f64, f64),
+    center: (f64, f64),
+    clockwise: bool,
+    tolerance: f64,
+) -> Vec<(f64, f64)> {
+    let radius = (from.0 - center.0).hypot(from.1 - center.1);
+    let start = (from.1 - center.1).atan2(from.0 - center.0);
+    let end = (to.1 - center.1).atan2(to.0 - center.0);
+    let mut sweep = end - start;
+    if clockwise && sweep >= -1e-9 {
+        sweep -= std::f64::consts::TAU;
+    } else if !clockwise && sweep <= 1e-9 {
+        sweep += std::f64::consts::TAU;
+    }
+    let step = if radius > tolerance {
+        2.0 * (1.0 - tolerance / radius).acos()
+    } else {
+        sweep.abs()
+    };
+    let segments = (sweep.abs() / step).ceil().clamp(1.0, 3600.0) as usize;
+    let mut points: Vec<(f64, f64)> = (0..segments)
+        .map(|i| {
+            let angle = start + sweep * i as f64 / segments as f64;
+            (
+                center.0 + radius * angle.cos(),
+                center.1 + radius * angle.sin(),
+            )
+        })
+        .collect();
+    points.push(to);
+    points
+}
+
+fn layout(visualizer: &Visualizer, options: &ViewExportOptions) -> VisualizationResult<Page> {
+    let (portrait_w, portrait_h) = options.paper.dimensions_mm();
+    let (width, height) = match options.orientation {
+        PaperOrientation::Portrait => (portrait_w, portrait_h),
+        PaperOrientation::Landscape => (portrait_h, portrait_w),
+    };
+    let area_w = width - 2.0 * MARGIN_MM;
+    let area_h = height - 2.0 * MARGIN_MM - TITLE_BLOCK_MM;
+    if !(area_w > 0.0 && area_h > 0.0) {
+        return Err(VisualizationError::InvalidViewport(format!(
+            "paper {:.1} x {:.1} mm is too small for the drawing",
+            width, height
+        )));
+    }
+
+    // Extents from the unscaled geometry; arcs are refined once the scale is known
+    let (cuts, rapids) = toolpath_polylines(visualizer, 1.0);
+    let extent = |lines: &[&Polylines]| {
+        lines.iter().flat_map(|l| l.iter()).flatten().fold(
+            None,
+            |acc: Option<(f64, f64, f64, f64)>, &(x, y)| {
+                Some(match acc {
+                    None => (x, y, x, y),
+                    Some((a, b, c, d)) => (a.min(x), b.min(y), c.max(x), d.max(y)),
+                })
+            },
+        )
+    };
+    let mut shown = Vec::new();
+    if options.show_cuts {
+        shown.push(&cuts);
+    }
+    if options.show_rapids {
+        shown.push(&rapids);
+    }
+    let (min_x, min_y, max_x, max_y) = extent(&shown).ok_or(VisualizationError::InvalidToolpath)?;
+    let (model_w, model_h) = ((max_x - min_x).max(1e-6), (max_y - min_y).max(1e-6));
+    let (scale, scale_label) = SCALES
+        .iter()
+        .copied()
+        .find(|(s, _)| model_w * s <= area_w && model_h * s <= area_h)
+        .unwrap_or(SCALES[SCALES.len() - 1]);
+
+    let (cuts, rapids) = toolpath_polylines(visualizer, scale);
+    let origin_x = MARGIN_MM + (area_w - model_w * scale) / 2.0;
+    let origin_y = MARGIN_MM + (area_h - model_h * scale) / 2.0;
+    let to_page = |(x, y): (f64, f64)| {
+        (
+            origin_x + (x - min_x) * scale,
+            origin_y + (max_y - y) * scale,
+        )
+    };
+    let strokes = |lines: Polylines, color: [u8; 3], dashed: bool| -> Vec<Stroke> {
+        lines
+            .into_iter()
+            .map(|line| Stroke {
+                points: line.into_iter().map(to_page).collect(),
+                color,
+                width: if dashed { 0.2 } else { 0.3 },
+                dashed,
+            })
+            .collect()
+    };
+
+    let mut layers = Vec::new();
+    if options.show_cuts {
+        layers.push(("cuts", strokes(cuts, CUT_COLOR, false)));
+    }
+    if options.show_rapids {
+        layers.push(("rapids", strokes(rapids, RAPID_COLOR, true)));
+    }
+
+    // Title block frame
+    let block_top = height - MARGIN_MM - TITLE_BLOCK_MM;
+    let (left, right, bottom) = (MARGIN_MM, width - MARGIN_MM, height - MARGIN_MM);
+    let mut annotations = vec![Stroke {
+        points: vec![
+            (left, block_top),
+            (right, block_top),
+            (right, bottom),
+            (left, bottom),
+            (left, block_top),
+        ],
+        color: INK_COLOR,
+        width: 0.35,
+        dashed: false,
+    }];
+
+    let units = options.units;
+    let unit = get_unit_label(units);
+    let length = |mm: f64| format_length(mm as f32, units);
+    let title = if options.title.is_empty() {
+        "Toolpath".to_string()
+    } else {
+        options.title.clone()
+    };
+    let time = options
+        .estimated_time_secs
+        .map_or_else(|| "-".to_string(), format_duration);
+    let mut texts = vec![
+        Text {
+            x: left + 3.0,
+            y: block_top + 8.0,
+            size: 5.0,
+            content: title,
+        },
+        Text {
+            x: left + 3.0,
+            y: block_top + 15.0,
+            size: 3.2,
+            content: format!(
+                "Bounds: X {} to {} {unit}, Y {} to {} {unit}",
+                length(min_x),
+                length(max_x),
+                length(min_y),
+                length(max_y),
+            ),
+        },
+        Text {
+            x: left + 3.0,
+            y: block_top + 20.5,
+            size: 3.2,
+            content: format!("Estimated time: {}    Scale: {}", time, scale_label),
+        },
+    ];
+
+    // Scale bar: a round length in the chosen units, at most a quarter of the block
+    let unit_mm = match units {
+        MeasurementSystem::Metric => 1.0,
+        MeasurementSystem::Imperial => 25.4,
+    };
+    let max_bar = (right - left) / 4.0;
+    let bar_units = nice_length(max_bar / scale / unit_mm);
+    let bar = bar_units * unit_mm * scale;
+    let bar_y = block_top + 14.0;
+    let bar_x = right - 5.0 - bar;
+    annotations.push(Stroke {
+        points: vec![
+            (bar_x, bar_y - 1.5),
+            (bar_x, bar_y),
+            (bar_x + bar, bar_y),
+            (bar_x + bar, bar_y - 1.5),
+        ],
+        color: INK_COLOR,
+        width: 0.35,
+        dashed: false,
+    });
+    texts.push(Text {
+        x: bar_x,
+        y: bar_y + 5.0,
+        size: 3.2,
+        content: format!("{} {}", trim_number(bar_units), unit),
+    });
+    layers.push(("annotations", annotations));
+
+    Ok(Page {
+        width,
+        height,
+        layers,
+        texts,
+    })
+}
+
+/// Largest 1/2/5 × 10ⁿ length not above `max`
+fn nice_length(max: f64) -> f64 {
+    let exponent = 10f64.powf(max.log10().floor());
+    [5.0, 2.0, 1.0]
+        .iter()
+        .map(|m| m * exponent)
+        .find(|l| *l <= max)
+        .unwrap_or(exponent)
+}
+
+fn trim_number(value: f64) -> String {
+    let text = format!("{:.4}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn format_duration(secs: f64) -> String {
+    let total = secs.max(0.0).round() as u64;
+    format!("{}:{:02}:{:02}", total / 3600, total / 60 % 60, total % 60)
+}
+
+fn render_svg(page: &Page) -> String {
+    let mut svg = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}mm\" height=\"{h}mm\" viewBox=\"0 0 {w} {h}\">\n<rect width=\"{w}\" height=\"{h}\" fill=\"white\"/>\n",
+        w = trim_number(page.width),
+        h = trim_number(page.height)
+    );
+    for (name, strokes) in &page.layers {
+        svg.push_str(&format!("<g id=\"{}\" fill=\"none\">\n", name));
+        for stroke in strokes {
+            let points: Vec<String> = stroke
+                .points
+                .iter()
+                .map(|(x, y)| format!("{:.3},{:.3}", x, y))
+                .collect();
+            let [r, g, b] = stroke.color;
+            svg.push_str(&format!(
+                "<polyline points=\"{}\" stroke=\"#{:02x}{:02x}{:02x}\" stroke-width=\"{}\"{}/>\n",
+                points.join(" "),
+                r,
+                g,
+                b,
+                stroke.width,
+                if stroke.dashed {
+                    " stroke-dasharray=\"1 1\""
+                } else {
+                    ""
+                }
+            ));
+        }
+        svg.push_str("</g>\n");
+    }
+    svg.push_str("<g id=\"title-block\" font-family=\"sans-serif\" fill=\"#202020\">\n");
+    for text in &page.texts {
+        svg.push_str(&format!(
+            "<text x=\"{:.3}\" y=\"{:.3}\" font-size=\"{}\">{}</text>\n",
+            text.x,
+            text.y,
+            text.size,
+            xml_escape(&text.content)
+        ));
+    }
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_pdf(page: &Page) -> Vec<u8> {
+    let (page_w, page_h) = (page.width * PT_PER_MM, page.height * PT_PER_MM);
+    let pt = |(x, y): (f64, f64)| (x * PT_PER_MM, page_h - y * PT_PER_MM);
+
+    let mut content = String::new();
+    for (_, strokes) in &page.layers {
+        for stroke in strokes {
+            let [r, g, b] = stroke.color;
+            content.push_str(&format!(
+                "{:.3} {:.3} {:.3} RG {:.3} w {} d\n",
+                r as f64 / 255.0,
+                g as f64 / 255.0,
+                b as f64 / 255.0,
+                stroke.width * PT_PER_MM,
+                if stroke.dashed {
+                    format!("[{0:.3} {0:.3}] 0", PT_PER_MM)
+                } else {
+                    "[] 0".to_string()
+                }
+            ));
+            for (i, point) in stroke.points.iter().enumerate() {
+                let (x, y) = pt(*point);
+                content.push_str(&format!(
+                    "{:.3} {:.3} {}\n",
+                    x,
+                    y,
+                    if i == 0 { "m" } else { "l" }
+                ));
+            }
+            content.push_str("S\n");
+        }
+    }
+    content.push_str("0.125 0.125 0.125 rg\n");
+    for text in &page.texts {
+        let (x, y) = pt((text.x, text.y));
+        content.push_str(&format!(
+            "BT /F1 {:.2} Tf {:.3} {:.3} Td ({}) Tj ET\n",
+            text.size * PT_PER_MM,
+            x,
+            y,
+            pdf_escape(&text.content)
+        ));
+    }
+
+    let objects = [
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.3} {:.3}] /Contents 4 0 R /Resources << /Font << /F1 5 0 R >> >> >>",
+            page_w, page_h
+        ),
+        format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            content.len(),
+            content
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+    ];
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, object));
+    }
+    let xref = pdf.len();
+    pdf.push_str(&format!(
+        "xref\n0 {}\n0000000000 65535 f \n",
+        objects.len() + 1
+    ));
+    for offset in offsets {
+        pdf.push_str(&format!("{:010} 00000 n \n", offset));
+    }
+    pdf.push_str(&format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref
+    ));
+    pdf.into_bytes()
+}
+
+/// Text for a PDF string literal; characters outside ASCII become `?`
+fn pdf_escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{}", c),
+            ' '..='~' => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
@@ -1,13 +1,15 @@
 //! 2D G-Code Visualizer
 //! Parses G-Code toolpaths for canvas-based visualization
 
+use super::operations::{OperationSegmenter, ProgramOperation};
 use super::toolpath_cache::ToolpathCache;
 use super::viewport::{Bounds, ViewportTransform};
 use gcodekit5_core::constants as core_constants;
+use gcodekit5_core::{split_block_delete, ArcMove, ArcPlane};
 use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
 
 const CANVAS_PADDING: f32 = core_constants::CANVAS_PADDING_PX as f32;
 const _CANVAS_PADDING_2X: f32 = 40.0;
@@ -23,6 +25,9 @@ const _MAX_GRID_ITERATIONS: usize = 500;
 const _MAX_SCALE: f32 = 100.0;
 const _MIN_SCALE: f32 = 0.1;
 const DEFAULT_SCALE_FACTOR: f32 = 1.0;
+/// Segment length used when flattening G18/G19 arcs into line moves (mm)
+const PLANE_ARC_SEGMENT_MM: f64 = 0.5;
+const MAX_PLANE_ARC_SEGMENTS: usize = 720;
 const _GRID_MAJOR_STEP_MM: f32 = 10.0;
 const _GRID_MINOR_STEP_MM: f32 = 1.0;
 const _GRID_MAJOR_VISIBILITY_SCALE: f32 = 0.3;
@@ -137,6 +142,14 @@ pub struct Visualizer {
     viewport: ViewportTransform,
     /// Dirty flag — set when vertex data needs regeneration
     dirty: bool,
+    /// Arcs that could not be resolved in the last parse (1-based line, reason)
+    arc_errors: Vec<(usize, String)>,
+    /// Block delete switch: skip lines starting with `/`
+    block_delete: bool,
+    /// Moves of the optional blocks skipped in the last parse
+    block_deleted: Vec<GCodeCommand>,
+    /// Operations of the last parse, split at tool changes and operation comments
+    operations: Vec<ProgramOperation>,
 }
 
 impl Visualizer {
@@ -159,6 +172,10 @@ impl Visualizer {
             toolpath_cache: ToolpathCache::new(),
             viewport: ViewportTransform::new(CANVAS_PADDING),
             dirty: true,
+            arc_errors: Vec::new(),
+            block_delete: true,
+            block_deleted: Vec::new(),
+            operations: Vec::new(),
         }
     }
 
@@ -232,12 +249,28 @@ impl Visualizer {
         after_g[..end_idx].parse::<u32>().ok()
     }
 
+    /// Update the modal plane from any G17/G18/G19 word and pick the G-code to
+    /// dispatch: the motion word (G0-G4) if present, otherwise the first G word
+    fn scan_gcode_words(line: &str, plane: &mut ArcPlane) -> Option<u32> {
+        let first = Self::extract_gcode_num(line)?;
+        let mut motion = None;
+        for num in line.split_whitespace().filter_map(Self::extract_gcode_num) {
+            if let Some(p) = u8::try_from(num).ok().and_then(ArcPlane::from_gcode) {
+                *plane = p;
+            } else if num <= 4 && motion.is_none() {
+                motion = Some(num);
+            }
+        }
+        Some(motion.unwrap_or(first))
+    }
+
     /// Parse G-Code and extract movement commands
     pub fn parse_gcode(&mut self, gcode: &str) {
         debug!("Starting G-code parse, input size: {} bytes", gcode.len());
 
         let mut hasher = DefaultHasher::new();
         gcode.hash(&mut hasher);
+        self.block_delete.hash(&mut hasher);
         let new_hash = hasher.finish();
 
         if !self.toolpath_cache.needs_update(new_hash) {
@@ -251,80 +284,61 @@ impl Visualizer {
         let mut current_pos = Point3D::new(0.0, 0.0, 0.0);
         self.current_intensity = 0.0;
         let mut bounds = Bounds::new();
-        let mut _g0_count = 0;
-        let mut _g1_count = 0;
-        let mut _g2_count = 0;
-        let mut _g3_count = 0;
+        let mut plane = ArcPlane::XY;
+        self.arc_errors.clear();
+        self.block_deleted.clear();
+        let mut segmenter = OperationSegmenter::new();
 
         for (line_num, line) in gcode.lines().enumerate() {
             let line = line.trim();
 
-            if line.is_empty() || line.starts_with(';') || line.starts_with('(') {
+            if line.is_empty() {
+                continue;
+            }
+            if line.starts_with(';') || line.starts_with('(') {
+                segmenter.mark(line, line_num, commands.len());
                 continue;
             }
 
-            if let Some(gcode_num) = Self::extract_gcode_num(line) {
-                trace!("Line {}: G{} command", line_num, gcode_num);
-                match gcode_num {
-                    0 => {
-                        _g0_count += 1;
-                        Self::parse_linear_move(
-                            &mut commands,
-                            line,
-                            &mut current_pos,
-                            &mut self.current_intensity,
-                            &mut bounds,
-                            true,
-                        );
-                    }
-                    1 => {
-                        _g1_count += 1;
-                        Self::parse_linear_move(
-                            &mut commands,
-                            line,
-                            &mut current_pos,
-                            &mut self.current_intensity,
-                            &mut bounds,
-                            false,
-                        );
-                    }
-                    2 => {
-                        _g2_count += 1;
-                        Self::parse_arc_move(
-                            &mut commands,
-                            line,
-                            &mut current_pos,
-                            &mut self.current_intensity,
-                            &mut bounds,
-                            true,
-                        );
-                    }
-                    3 => {
-                        _g3_count += 1;
-                        Self::parse_arc_move(
-                            &mut commands,
-                            line,
-                            &mut current_pos,
-                            &mut self.current_intensity,
-                            &mut bounds,
-                            false,
-                        );
-                    }
-                    4 => {
-                        Self::parse_dwell(&mut commands, line, &mut current_pos);
-                    }
-                    _ => {}
-                }
+            let (line, optional) = split_block_delete(line);
+            if optional && self.block_delete {
+                // Parse the skipped block from the current state without applying it,
+                // so it can be shown dimmed where it would have run
+                let mut pos = current_pos;
+                let mut intensity = self.current_intensity;
+                let mut scratch_plane = plane;
+                Self::parse_motion_line(
+                    &mut self.block_deleted,
+                    line,
+                    line_num,
+                    &mut pos,
+                    &mut intensity,
+                    &mut Bounds::new(),
+                    &mut scratch_plane,
+                    &mut Vec::new(),
+                );
+                continue;
             }
+
+            segmenter.mark(line, line_num, commands.len());
+            Self::parse_motion_line(
+                &mut commands,
+                line,
+                line_num,
+                &mut current_pos,
+                &mut self.current_intensity,
+                &mut bounds,
+                &mut plane,
+                &mut self.arc_errors,
+            );
         }
 
+        self.operations = segmenter.finish(commands.len());
         debug!(
-            "Parse complete: G0={}, G1={}, G2={}, G3={}, total commands={}",
-            _g0_count,
-            _g1_count,
-            _g2_count,
-            _g3_count,
-            commands.len()
+            "Parse complete: {} commands in {} operation(s), {} in skipped optional blocks",
+            commands.len(),
+            self.operations.len(),
+            self.block_deleted.len()
         );
 
         (
@@ -354,6 +368,50 @@ impl Visualizer {
         )
     }
 
+    /// Parse one line's motion into `commands`, updating the position and modal state
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn parse_motion_line(
+        commands: &mut Vec<GCodeCommand>,
+        line: &str,
+        line_num: usize,
+        current_pos: &mut Point3D,
+        current_intensity: &mut f32,
+        bounds: &mut Bounds,
+        plane: &mut ArcPlane,
+        arc_errors: &mut Vec<(usize, String)>,
+    ) {
+        let Some(gcode_num) = Self::scan_gcode_words(line, plane) else {
+            return;
+        };
+        trace!("Line {}: G{} command", line_num, gcode_num);
+        match gcode_num {
+            0 | 1 => Self::parse_linear_move(
+                commands,
+                line,
+                current_pos,
+                current_intensity,
+                bounds,
+                gcode_num == 0,
+            ),
+            2 | 3 => {
+                if let Err(reason) = Self::parse_arc_move(
+                    commands,
+                    line,
+                    current_pos,
+                    current_intensity,
+                    bounds,
+                    gcode_num == 2,
+                    *plane,
+                ) {
+                    warn!("Line {}: {}", line_num + 1, reason);
+                    arc_errors.push((line_num + 1, reason));
+                }
+            }
+            4 => Self::parse_dwell(commands, line, current_pos),
+            _ => {}
+        }
+    }
+
     fn parse_dwell(commands: &mut Vec<GCodeCommand>, line: &str, current_pos: &mut Point3D) {
         let mut duration = 0.0;
         for part in line.split_whitespace() {
@@ -445,6 +503,7 @@ impl Visualizer {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn parse_arc_move(
         commands: &mut Vec<GCodeCommand>,
         line: &str,
@@ -452,12 +511,14 @@ impl Visualizer {
         current_intensity: &mut f32,
         bounds: &mut Bounds,
         clockwise: bool,
-    ) {
-        let mut new_x = None;
-        let mut new_y = None;
-        let mut new_z = None;
-        let mut offset_i = None;
-        let mut offset_j = None;
+        plane: ArcPlane,
+    ) -> Result<(), String> {
+        let mut end = [current_pos.x, current_pos.y, current_pos.z];
+        // I, J, K
+        let mut offsets = [0.0f32; 3];
+        let (first_word, second_word) = plane.offset_words();
+        let mut has_offset = false;
+        let mut radius_word = None;
 
         for part in line.split_whitespace() {
             if part.len() < 2 {
@@ -466,50 +527,58 @@ impl Visualizer {
             let Some(first_char) = part.chars().next() else {
                 continue;
             };
+            let Ok(val) = part[1..].parse::<f32>() else {
+                continue;
+            };
             match first_char {
-                'X' => {
-                    if let Ok(val) = part[1..].parse::<f32>() {
-                        new_x = Some(val);
-                    }
-                }
-                'Y' => {
-                    if let Ok(val) = part[1..].parse::<f32>() {
-                        new_y = Some(val);
-                    }
-                }
-                'Z' => {
-                    if let Ok(val) = part[1..].parse::<f32>() {
-                        new_z = Some(val);
-                    }
-                }
-                'I' => {
-                    if let Ok(val) = part[1..].parse::<f32>() {
-                        offset_i = Some(val);
-                    }
-                }
-                'J' => {
-                    if let Ok(val) = part[1..].parse::<f32>() {
-                        offset_j = Some(val);
-                    }
-                }
-                'S' => {
-                    if let Ok(val) = part[1..].parse::<f32>() {
-                        *current_intensity = val;
-                    }
+                'X' => end[0] = val,
+                'Y' => end[1] = val,
+                'Z' => end[2] = val,
+                'I' | 'J' | 'K' => {
+                    offsets[(first_char as u8 - b'I') as usize] = val;
+                    has_offset |= first_char == first_word || first_char == second_word;
                 }
+                'R' => radius_word = Some(val),
+                'S' => *current_intensity = val,
                 _ => {}
             }
         }
 
-        if let (Some(x), Some(y), Some(i), Some(j)) = (new_x, new_y, offset_i, offset_j) {
-            let z = new_z.unwrap_or(current_pos.z);
-            let to = Point3D::new(x, y, z);
-            let center = Point3D::new(current_pos.x + i, current_pos.y + j, current_pos.z);
+        let start = [
+            current_pos.x as f64,
+            current_pos.y as f64,
+            current_pos.z as f64,
+        ];
+        let end_f64 = [end[0] as f64, end[1] as f64, end[2] as f64];
+        // I/J/K takes precedence over R when both are given
+        let arc = if has_offset {
+            ArcMove::from_offsets(
+                start,
+                end_f64,
+                [offsets[0] as f64, offsets[1] as f64, offsets[2] as f64],
+                clockwise,
+                plane,
+            )
+        } else if let Some(r) = radius_word {
+            ArcMove::from_radius(start, end_f64, r as f64, clockwise, plane)
+                .map_err(|e| e.to_string())?
+        } else {
+            return Err(format!(
+                "G{} needs {}/{} offsets or an R radius",
+                if clockwise { 2 } else { 3 },
+                first_word,
+                second_word
+            ));
+        };
+
+        let to = Point3D::new(end[0], end[1], end[2]);
+        if plane == ArcPlane::XY {
+            let center = Point3D::new(arc.center[0] as f32, arc.center[1] as f32, current_pos.z);
 
             let radius =
                 ((current_pos.x - center.x).powi(2) + (current_pos.y - center.y).powi(2)).sqrt();
-            trace!("Arc: from=({:.2},{:.2}), to=({:.2},{:.2}), center=({:.2},{:.2}), radius={:.4}, cw={}", 
-                   current_pos.x, current_pos.y, x, y, center.x, center.y, radius, clockwise);
+            trace!("Arc: from=({:.2},{:.2}), to=({:.2},{:.2}), center=({:.2},{:.2}), radius={:.4}, cw={}",
+                   current_pos.x, current_pos.y, to.x, to.y, center.x, center.y, radius, clockwise);
 
             commands.push(GCodeCommand::Arc {
                 from: *current_pos,
@@ -518,11 +587,37 @@ impl Visualizer {
                 clockwise,
                 intensity: Some(*current_intensity),
             });
-
             bounds.update(current_pos.x, current_pos.y, current_pos.z);
-            bounds.update(x, y, z);
-            *current_pos = to;
+            bounds.update(to.x, to.y, to.z);
+        } else {
+            // Renderers draw `Arc` in XY, so G18/G19 arcs are flattened into line moves
+            let segments = arc
+                .segments_for(PLANE_ARC_SEGMENT_MM)
+                .min(MAX_PLANE_ARC_SEGMENTS);
+            trace!(
+                "Arc in G{} plane: radius={:.4}, segments={}",
+                plane.gcode(),
+                arc.radius(),
+                segments
+            );
+
+            let mut from = *current_pos;
+            bounds.update(from.x, from.y, from.z);
+            for [x, y, z] in arc.expand(segments) {
+                let point = Point3D::new(x as f32, y as f32, z as f32);
+                commands.push(GCodeCommand::Move {
+                    from,
+                    to: point,
+                    rapid: false,
+                    intensity: Some(*current_intensity),
+                });
+                bounds.update(point.x, point.y, point.z);
+                from = point;
+            }
         }
+
+        *current_pos = to;
+        Ok(())
     }
 
     /// Extract multiple parameters from G-Code line
@@ -553,6 +648,93 @@ impl Visualizer {
         self.toolpath_cache.len()
     }
 
+    /// Arcs that could not be resolved in the last parse, as (1-based line, reason).
+    ///
+    /// These moves are left out of the toolpath, as a controller would reject them.
+    pub fn arc_errors(&self) -> &[(usize, String)] {
+        &self.arc_errors
+    }
+
+    /// Set the block delete switch; when on (the default), lines starting with `/`
+    /// are left out of the toolpath. Takes effect on the next parse.
+    pub fn set_block_delete(&mut self, enabled: bool) {
+        self.block_delete = enabled;
+    }
+
+    /// Whether the block delete switch is on
+    pub fn block_delete(&self) -> bool {
+        self.block_delete
+    }
+
+    /// Moves of the optional blocks skipped in the last parse, each starting where the
+    /// program was when it reached the block, for drawing them dimmed
+    pub fn block_deleted_commands(&self) -> &[GCodeCommand] {
+        &self.block_deleted
+    }
+
+    /// Operations of the last parse, in program order
+    ///
+    /// Empty when the program has no commands.
+    pub fn operations(&self) -> &[ProgramOperation] {
+        &self.operations
+    }
+
+    /// Index of the operation a command belongs to
+    pub fn operation_of(&self, command_index: usize) -> Option<usize> {
+        let index = self
+            .operations
+            .partition_point(|op| op.commands.end <= command_index);
+        self.operations
+            .get(index)
+            .filter(|op| op.commands.contains(&command_index))
+            .map(|_| index)
+    }
+
+    /// Show or hide one operation
+    pub fn set_operation_visible(&mut self, index: usize, visible: bool) {
+        if let Some(op) = self.operations.get_mut(index) {
+            if op.visible != visible {
+                op.visible = visible;
+                self.dirty = true;
+            }
+        }
+    }
+
+    /// Show only one operation
+    pub fn solo_operation(&mut self, index: usize) {
+        if index >= self.operations.len() {
+            return;
+        }
+        for (i, op) in self.operations.iter_mut().enumerate() {
+            op.visible = i == index;
+        }
+        self.dirty = true;
+    }
+
+    /// Show every operation
+    pub fn show_all_operations(&mut self) {
+        for op in &mut self.operations {
+            op.visible = true;
+        }
+        self.dirty = true;
+    }
+
+    /// Whether a command is drawn (commands outside any operation always are)
+    pub fn is_command_visible(&self, command_index: usize) -> bool {
+        self.operation_of(command_index)
+            .is_none_or(|index| self.operations[index].visible)
+    }
+
+    /// Commands of the visible operations, in program order
+    pub fn visible_commands(&self) -> impl Iterator<Item = &GCodeCommand> + '_ {
+        self.toolpath_cache
+            .commands()
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.is_command_visible(*i))
+            .map(|(_, cmd)| cmd)
+    }
+
     /// Get bounds information
     pub fn get_bounds(&self) -> (f32, f32, f32, f32) {
         (self.min_x, self.max_x, self.min_y, self.max_y)
@@ -634,11 +816,23 @@ impl Visualizer {
 
     /// Calculate zoom and offset to fit all cutting commands in view with margin
     pub fn fit_to_view(&mut self, canvas_width: f32, canvas_height: f32) {
+        let bounds = Self::content_bounds(self.toolpath_cache.commands().iter());
+        self.fit_bounds(bounds, canvas_width, canvas_height);
+    }
+
+    /// Like [`Self::fit_to_view`], but only the visible operations are fitted
+    pub fn fit_to_visible(&mut self, canvas_width: f32, canvas_height: f32) {
+        let bounds = Self::content_bounds(self.visible_commands());
+        self.fit_bounds(bounds, canvas_width, canvas_height);
+    }
+
+    /// Bounds of the cutting moves, arcs and dwells among `commands`
+    fn content_bounds<'a>(commands: impl Iterator<Item = &'a GCodeCommand>) -> Option<Bounds> {
         let mut bounds = Bounds::new();
         let mut has_content = false;
 
         // Collect bounds of all cutting moves
-        for cmd in self.toolpath_cache.commands() {
+        for cmd in commands {
             match cmd {
                 GCodeCommand::Move {
                     from, to, rapid, ..
@@ -668,12 +862,16 @@ impl Visualizer {
             }
         }
 
-        if !has_content || !bounds.is_valid() {
+        (has_content && bounds.is_valid()).then_some(bounds)
+    }
+
+    fn fit_bounds(&mut self, bounds: Option<Bounds>, canvas_width: f32, canvas_height: f32) {
+        let Some(bounds) = bounds else {
             self.zoom_scale = 1.0;
             self.x_offset = 0.0;
             self.y_offset = 0.0;
             return;
-        }
+        };
 
         let content_width = bounds.max_x - bounds.min_x;
         let content_height = bounds.max_y - bounds.min_y;
@@ -0,0 +1,274 @@
+use gcodekit5_visualizer::{
+    collect_gcode_files, process_batch, BatchOptions, CommandProcessor, CommentProcessor,
+    EmptyLineRemoverProcessor, GcodeCommand, GcodeState, ProcessorPipeline,
+};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Processor that rejects any line containing "BAD"
+struct RejectBad;
+
+impl CommandProcessor for RejectBad {
+    fn name(&self) -> &str {
+        "reject_bad"
+    }
+
+    fn description(&self) -> &str {
+        "Fails on lines containing BAD"
+    }
+
+    fn process(
+        &self,
+        command: &GcodeCommand,
+        _state: &GcodeState,
+    ) -> Result<Vec<GcodeCommand>, String> {
+        if command.command.contains("BAD") {
+            Err("bad line".to_string())
+        } else {
+            Ok(vec![command.clone()])
+        }
+    }
+}
+
+fn pipeline() -> ProcessorPipeline {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(CommentProcessor::new()));
+    pipeline.register(Arc::new(EmptyLineRemoverProcessor::new()));
+    pipeline.register(Arc::new(RejectBad));
+    pipeline
+}
+
+fn write(path: &Path, content: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+#[test]
+fn test_batch_preserves_relative_paths_and_isolates_failures() {
+    let input = tempfile::tempdir().unwrap();
+    let output = tempfile::tempdir().unwrap();
+
+    write(
+        &input.path().join("a.nc"),
+        "; header\nG0 X0 Y0\n\nG1 X10 F100\n",
+    );
+    write(&input.path().join("sub/b.gcode"), "G0 Z5\nG1 Z-1 F50\n");
+    write(&input.path().join("sub/bad.ngc"), "G0 X0\nG1 BAD\n");
+    write(&input.path().join("notes.txt"), "not gcode");
+
+    let files = collect_gcode_files(input.path()).unwrap();
+    assert_eq!(files.len(), 3);
+
+    let options = BatchOptions {
+        base_dir: Some(input.path().to_path_buf()),
+        ..BatchOptions::default()
+    };
+    let report = process_batch(&files, &pipeline(), output.path(), &options);
+
+    assert_eq!(report.results.len(), 3);
+    assert_eq!(report.succeeded(), 2);
+    let failed: Vec<_> = report.failures().collect();
+    assert_eq!(failed.len(), 1);
+    assert!(failed[0].source.ends_with("sub/bad.ngc"));
+    assert!(!output.path().join("sub/bad.ngc").exists());
+
+    let a = fs::read_to_string(output.path().join("a.nc")).unwrap();
+    assert!(!a.contains("header"));
+    assert!(a.contains("G1 X10 F100"));
+    assert!(output.path().join("sub/b.gcode").exists());
+
+    let a_result = &report.results[0];
+    let stats = a_result.statistics.as_ref().unwrap();
+    assert_eq!(stats.total_lines, 2);
+    assert!(a_result.validation.as_ref().unwrap().is_valid);
+}
+
+#[test]
+fn test_batch_parallel_matches_sequential() {
+    let input = tempfile::tempdir().unwrap();
+    let files: Vec<_> = (0..8)
+        .map(|i| {
+            let path = input.path().join(format!("dir{}/part{}.nc", i % 3, i));
+            write(&path, &format!("G0 X{}\nG1 Y{} F200\n", i, i));
+            path
+        })
+        .collect();
+
+    let sequential_out = tempfile::tempdir().unwrap();
+    let parallel_out = tempfile::tempdir().unwrap();
+    let sequential = process_batch(
+        &files,
+        &pipeline(),
+        sequential_out.path(),
+        &BatchOptions::default(),
+    );
+    let parallel = process_batch(
+        &files,
+        &pipeline(),
+        parallel_out.path(),
+        &BatchOptions {
+            threads: 4,
+            ..BatchOptions::default()
+        },
+    );
+
+    assert_eq!(parallel.succeeded(), 8);
+    for (seq, par) in sequential.results.iter().zip(&parallel.results) {
+        assert_eq!(seq.source, par.source);
+        let seq_rel = seq
+            .output
+            .as_ref()
+            .unwrap()
+            .strip_prefix(sequential_out.path());
+        let par_rel = par
+            .output
+            .as_ref()
+            .unwrap()
+            .strip_prefix(parallel_out.path());
+        assert_eq!(seq_rel.unwrap(), par_rel.unwrap());
+    }
+}
+
+#[test]
+fn test_batch_refuses_to_overwrite_when_disabled() {
+    let input = tempfile::tempdir().unwrap();
+    let output = tempfile::tempdir().unwrap();
+    let source = input.path().join("a.nc");
+    write(&source, "G0 X0\n");
+    write(&output.path().join("a.nc"), "existing\n");
+
+    let report = process_batch(
+        &[source],
+        &pipeline(),
+        output.path(),
+        &BatchOptions {
+            overwrite: false,
+            ..BatchOptions::default()
+        },
+    );
+    assert_eq!(report.succeeded(), 0);
+    assert_eq!(
+        fs::read_to_string(output.path().join("a.nc")).unwrap(),
+        "existing\n"
+    );
+}
+
+#[test]
+fn test_batch_fails_inputs_whose_outputs_collide() {
+    let input = tempfile::tempdir().unwrap();
+    let output = tempfile::tempdir().unwrap();
+    let first = input.path().join("a/part.nc");
+    let second = input.path().join("b/part.nc");
+    let other = input.path().join("b/other.nc");
+    for path in [&first, &second, &other] {
+        write(path, "G0 X0\n");
+    }
+
+    // Outside the base directory both part.nc files keep just their name
+    let options = BatchOptions {
+        base_dir: Some(input.path().join("elsewhere")),
+        ..BatchOptions::default()
+    };
+    let report = process_batch(
+        &[first, second, other],
+        &pipeline(),
+        output.path(),
+        &options,
+    );
+
+    assert_eq!(report.results.len(), 3);
+    assert_eq!(report.succeeded(), 1);
+    assert!(report.results[2].is_success());
+    for failed in report.failures() {
+        assert!(failed.error.as_ref().unwrap().contains("part.nc"));
+    }
+    assert!(!output.path().join("part.nc").exists());
+}
+
+#[test]
+fn test_batch_refuses_to_write_into_the_input_directory() {
+    let input = tempfile::tempdir().unwrap();
+    let source = input.path().join("a.nc");
+    write(&source, "; keep me\nG0 X0\n");
+
+    let options = BatchOptions {
+        base_dir: Some(input.path().to_path_buf()),
+        ..BatchOptions::default()
+    };
+    let report = process_batch(
+        std::slice::from_ref(&source),
+        &pipeline(),
+        input.path().join("."),
+        &options,
+    );
+    assert_eq!(report.succeeded(), 0);
+    assert_eq!(report.results.len(), 1);
+    assert_eq!(fs::read_to_string(&source).unwrap(), "; keep me\nG0 X0\n");
+
+    // An input outside the base directory landing on itself is refused too
+    let report = process_batch(
+        std::slice::from_ref(&source),
+        &pipeline(),
+        input.path(),
+        &BatchOptions {
+            base_dir: Some(input.path().join("elsewhere")),
+            ..BatchOptions::default()
+        },
+    );
+    assert_eq!(report.succeeded(), 0);
+    assert_eq!(fs::read_to_string(&source).unwrap(), "; keep me\nG0 X0\n");
+}
+
+#[test]
+fn test_batch_reports_a_panicking_file_and_keeps_the_rest() {
+    struct Panics;
+
+    impl CommandProcessor for Panics {
+        fn name(&self) -> &str {
+            "panics"
+        }
+
+        fn description(&self) -> &str {
+            "Panics on lines containing PANIC"
+        }
+
+        fn process(
+            &self,
+            command: &GcodeCommand,
+            _state: &GcodeState,
+        ) -> Result<Vec<GcodeCommand>, String> {
+            assert!(!command.command.contains("PANIC"), "processor panicked");
+            Ok(vec![command.clone()])
+        }
+    }
+
+    let input = tempfile::tempdir().unwrap();
+    let output = tempfile::tempdir().unwrap();
+    let files: Vec<_> = (0..4)
+        .map(|i| {
+            let path = input.path().join(format!("part{}.nc", i));
+            let body = if i == 1 { "G0 PANIC\n" } else { "G0 X0\n" };
+            write(&path, body);
+            path
+        })
+        .collect();
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register(Arc::new(Panics));
+
+    for threads in [1, 3] {
+        let report = process_batch(
+            &files,
+            &pipeline,
+            output.path(),
+            &BatchOptions {
+                threads,
+                ..BatchOptions::default()
+            },
+        );
+        assert_eq!(report.results.len(), 4);
+        assert_eq!(report.succeeded(), 3);
+        assert!(!report.results[1].is_success());
+        assert_eq!(report.results[1].source, files[1]);
+    }
+}
@@ -0,0 +1,90 @@
+use gcodekit5_visualizer::{explain_line, KnownCode, WordMeaning};
+
+fn meanings(line: &str) -> Vec<(String, WordMeaning)> {
+    explain_line(line)
+        .words
+        .into_iter()
+        .map(|w| (w.text, w.meaning))
+        .collect()
+}
+
+#[test]
+fn test_explains_motion_axes_and_feed() {
+    assert_eq!(
+        meanings("g1 x10 Y-2.5 F100"),
+        vec![
+            ("G1".to_string(), WordMeaning::Code(KnownCode::LinearMove)),
+            (
+                "X10".to_string(),
+                WordMeaning::Axis {
+                    axis: 'X',
+                    value: 10.0
+                }
+            ),
+            (
+                "Y-2.5".to_string(),
+                WordMeaning::Axis {
+                    axis: 'Y',
+                    value: -2.5
+                }
+            ),
+            ("F100".to_string(), WordMeaning::FeedRate(100.0)),
+        ]
+    );
+}
+
+#[test]
+fn test_pairs_words_with_their_codes() {
+    assert_eq!(
+        meanings("M3 S1000"),
+        vec![(
+            "M3 S1000".to_string(),
+            WordMeaning::Code(KnownCode::SpindleClockwise(Some(1000.0)))
+        )]
+    );
+    assert_eq!(
+        meanings("G4 P2"),
+        vec![(
+            "G4 P2".to_string(),
+            WordMeaning::Code(KnownCode::Dwell(Some(2.0)))
+        )]
+    );
+    // Without M3 or M4 the S word stands on its own
+    assert_eq!(
+        meanings("S500"),
+        vec![("S500".to_string(), WordMeaning::SpindleSpeed(500.0))]
+    );
+}
+
+#[test]
+fn test_decimal_and_work_offset_codes() {
+    assert_eq!(
+        meanings("G38.2 Z-10")[0].1,
+        WordMeaning::Code(KnownCode::Probe {
+            toward: true,
+            error_if_no_contact: true
+        })
+    );
+    assert_eq!(
+        meanings("G55")[0].1,
+        WordMeaning::Code(KnownCode::WorkCoordinateSystem(2))
+    );
+}
+
+#[test]
+fn test_unknown_codes_comments_and_block_delete() {
+    assert_eq!(
+        meanings("G123 M99 X"),
+        vec![
+            ("G123".to_string(), WordMeaning::Unknown),
+            ("M99".to_string(), WordMeaning::Unknown),
+            ("X".to_string(), WordMeaning::Unknown),
+        ]
+    );
+
+    let explained = explain_line("/G0 Z5 (retract) ; to clearance");
+    assert!(explained.block_delete);
+    assert_eq!(explained.words.len(), 2);
+    assert_eq!(explained.comment.as_deref(), Some("retract to clearance"));
+    assert!(explain_line("   ").is_empty());
+}
@@ -0,0 +1,35 @@
+//! Feed rate mode tracking in the parser
+
+use gcodekit5_visualizer::{FeedMode, GcodeParser};
+
+#[test]
+fn test_parser_tracks_feed_mode_and_interprets_feed() {
+    let mut parser = GcodeParser::new();
+    assert_eq!(
+        parser.get_modal_state().feed_mode(),
+        FeedMode::UnitsPerMinute
+    );
+
+    parser.parse("G95 S1200 F0.1").unwrap();
+    parser.parse("G1 X10").unwrap();
+    let state = parser.get_state();
+    assert_eq!(state.feed_mode(), FeedMode::UnitsPerRevolution);
+    assert_eq!(
+        parser.get_modal_state().feed_mode(),
+        FeedMode::UnitsPerRevolution
+    );
+    assert!((state.feed_rate_per_minute(10.0).unwrap() - 120.0).abs() < 1e-9);
+    assert_eq!(state.feed_rate_description(), "F0.1 mm/rev");
+
+    parser.parse("G93 G1 X20 F4").unwrap();
+    let state = parser.get_state();
+    assert_eq!(state.feed_rate_mode_description(), "Inverse time (G93)");
+    // A 10 mm move done in a quarter minute
+    assert_eq!(state.feed_rate_per_minute(10.0), Some(40.0));
+
+    parser.parse("G94 G20 F20").unwrap();
+    assert_eq!(parser.get_state().feed_rate_description(), "F20 in/min");
+
+    parser.parse("G95 S0").unwrap();
+    assert_eq!(parser.get_state().feed_rate_per_minute(1.0), None);
+}
@@ -1 +1,5 @@
+pub mod batch_processing;
+pub mod explain;
+pub mod feed_mode;
 pub mod phase7_integration;
+pub mod pipeline_order;
@@ -0,0 +1,92 @@
+use gcodekit5_visualizer::{CommandProcessor, GcodeCommand, GcodeState, ProcessorPipeline};
+use std::sync::Arc;
+
+/// Processor that appends its tag to each command
+struct Tag(&'static str);
+
+impl CommandProcessor for Tag {
+    fn name(&self) -> &str {
+        self.0
+    }
+
+    fn description(&self) -> &str {
+        "Appends a tag"
+    }
+
+    fn process(
+        &self,
+        command: &GcodeCommand,
+        _state: &GcodeState,
+    ) -> Result<Vec<GcodeCommand>, String> {
+        let mut tagged = command.clone();
+        tagged.command = format!("{} {}", command.command, self.0);
+        Ok(vec![tagged])
+    }
+}
+
+fn pipeline() -> ProcessorPipeline {
+    let mut pipeline = ProcessorPipeline::new();
+    pipeline.register_all(vec![
+        Arc::new(Tag("a")),
+        Arc::new(Tag("b")),
+        Arc::new(Tag("c")),
+    ]);
+    pipeline
+}
+
+fn run(pipeline: &ProcessorPipeline) -> String {
+    let command = GcodeCommand::new("G0");
+    let output = pipeline
+        .process_command(&command, &GcodeState::default())
+        .unwrap();
+    output[0].command.clone()
+}
+
+fn names(pipeline: &ProcessorPipeline) -> Vec<String> {
+    pipeline
+        .processor_states()
+        .into_iter()
+        .map(|s| s.name)
+        .collect()
+}
+
+#[test]
+fn test_pipeline_runs_processors_in_configured_order() {
+    let mut pipeline = pipeline();
+    assert_eq!(run(&pipeline), "G0 a b c");
+
+    // Drag "c" to the front
+    pipeline.move_processor(2, 0).unwrap();
+    assert_eq!(names(&pipeline), ["c", "a", "b"]);
+    assert_eq!(run(&pipeline), "G0 c a b");
+
+    pipeline.set_order(&["b", "c", "a"]).unwrap();
+    assert_eq!(run(&pipeline), "G0 b c a");
+
+    assert!(pipeline.move_processor(0, 3).is_err());
+    assert!(pipeline.set_order(&["a", "a", "b"]).is_err());
+    assert!(pipeline.set_order(&["a", "b"]).is_err());
+    assert_eq!(names(&pipeline), ["b", "c", "a"]);
+}
+
+#[test]
+fn test_disabled_processors_are_skipped_and_keep_their_place() {
+    let mut pipeline = pipeline();
+    pipeline.set_processor_enabled("b", false).unwrap();
+    assert_eq!(run(&pipeline), "G0 a c");
+    assert_eq!(pipeline.is_processor_enabled("b"), Some(false));
+
+    let states = pipeline.processor_states();
+    assert_eq!(states[1].name, "b");
+    assert!(!states[1].enabled && !states[1].active);
+    assert!(!pipeline.list_processors()[1].2);
+
+    // The switch moves with the processor
+    pipeline.move_processor(1, 2).unwrap();
+    assert_eq!(names(&pipeline), ["a", "c", "b"]);
+    pipeline.set_processor_enabled("b", true).unwrap();
+    assert_eq!(run(&pipeline), "G0 a c b");
+
+    assert!(pipeline.set_processor_enabled("missing", false).is_err());
+    assert_eq!(pipeline.is_processor_enabled("missing"), None);
+}
@@ -0,0 +1,115 @@
+//! Tests for mapping gamepad input to pendant jogs and actions
+
+use gcodekit5_visualizer::{
+    parse_js_event, GamepadEvent, GamepadJogMapper, PendantButton, PendantCommand, PendantConfig,
+};
+
+fn mapper() -> GamepadJogMapper {
+    let mut config = PendantConfig::gamepad("/dev/input/js0");
+    config.enabled = true;
+    config.deadzone = 0.2;
+    config.max_jog_feed = 1000.0;
+    config.jog_travel = 100.0;
+    GamepadJogMapper::new(config)
+}
+
+fn axis(number: u8, value: f64) -> GamepadEvent {
+    GamepadEvent::Axis { number, value }
+}
+
+#[test]
+fn test_stick_deflection_jogs_and_release_cancels() {
+    let mut mapper = mapper();
+    assert!(mapper.handle(axis(0, 0.1), false).is_empty());
+
+    assert_eq!(
+        mapper.handle(axis(0, 1.0), false),
+        vec![PendantCommand::Jog("$J=G91 X100.000 F1000".to_string())]
+    );
+    // Same speed level: the jog keeps running
+    assert!(mapper.handle(axis(0, 0.99), false).is_empty());
+
+    // Slower, and stick Y is inverted
+    assert_eq!(
+        mapper.handle(axis(1, -0.6), false),
+        vec![
+            PendantCommand::JogCancel,
+            PendantCommand::Jog("$J=G91 X100.000 Y50.000 F1000".to_string()),
+        ]
+    );
+
+    mapper.handle(axis(1, 0.0), false);
+    assert_eq!(
+        mapper.handle(axis(0, 0.05), false),
+        vec![PendantCommand::JogCancel]
+    );
+    assert!(!mapper.is_jogging());
+}
+
+#[test]
+fn test_buttons_map_to_actions_on_press() {
+    let mut mapper = mapper();
+    let press = |number, pressed| GamepadEvent::Button { number, pressed };
+    assert_eq!(
+        mapper.handle(press(3, true), false),
+        vec![PendantCommand::Action(PendantButton::Home)]
+    );
+    assert!(mapper.handle(press(3, false), false).is_empty());
+    assert!(mapper.handle(press(9, true), false).is_empty());
+}
+
+#[test]
+fn test_input_is_ignored_while_blocked() {
+    let mut mapper = mapper();
+    mapper.handle(axis(4, -1.0), false);
+    assert!(mapper.is_jogging());
+
+    assert_eq!(
+        mapper.handle(axis(4, -1.0), true),
+        vec![PendantCommand::JogCancel]
+    );
+    assert!(mapper
+        .handle(
+            GamepadEvent::Button {
+                number: 1,
+                pressed: true
+            },
+            true
+        )
+        .is_empty());
+    assert!(!mapper.is_jogging());
+
+    let mut disabled = GamepadJogMapper::new(PendantConfig::default());
+    assert!(disabled.handle(axis(0, 1.0), false).is_empty());
+}
+
+#[test]
+fn test_parse_js_event_and_config_defaults() {
+    let mut bytes = [0u8; 8];
+    bytes[4..6].copy_from_slice(&i16::MAX.to_ne_bytes());
+    bytes[6] = 0x02 | 0x80;
+    bytes[7] = 1;
+    assert_eq!(parse_js_event(&bytes), Some(axis(1, 1.0)));
+    bytes[6] = 0x01;
+    assert_eq!(
+        parse_js_event(&bytes),
+        Some(GamepadEvent::Button {
+            number: 1,
+            pressed: true
+        })
+    );
+
+    let config: PendantConfig = serde_json::from_str(r#"{"enabled": true}"#).unwrap();
+    assert!(config.enabled);
+    assert_eq!(config.deadzone, PendantConfig::default().deadzone);
+    assert_eq!(config.button_mappings.len(), 4);
+}
+
+#[test]
+fn test_gamepad_jog_respects_axis_rates() {
+    let mut mapper = mapper().with_max_rates([('Z', 300.0)].into_iter().collect());
+    assert_eq!(
+        mapper.handle(axis(4, -1.0), false),
+        vec![PendantCommand::Jog("$J=G91 Z100.000 F300".to_string())]
+    );
+}
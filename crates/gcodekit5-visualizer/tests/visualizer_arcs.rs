@@ -0,0 +1,125 @@
+//! Tests for arc parsing in the visualizer
+
+use gcodekit5_visualizer::{GCodeCommand, Point3D, Visualizer};
+
+fn parse(gcode: &str) -> Vec<GCodeCommand> {
+    let mut viz = Visualizer::new();
+    viz.parse_gcode(gcode);
+    viz.commands().to_vec()
+}
+
+fn move_endpoints(commands: &[GCodeCommand]) -> Vec<Point3D> {
+    commands
+        .iter()
+        .filter_map(|cmd| match cmd {
+            GCodeCommand::Move {
+                to, rapid: false, ..
+            } => Some(*to),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_xy_arc_stays_an_arc() {
+    let commands = parse("G0 X10 Y0\nG17 G3 X0 Y10 I-10 J0\n");
+    assert!(matches!(
+        commands.last(),
+        Some(GCodeCommand::Arc {
+            clockwise: false,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_xz_arc_is_drawn_in_xz_plane() {
+    let commands = parse("G0 X0 Y3 Z0\nG18\nG2 X10 Z0 I5 K0\n");
+    let points = move_endpoints(&commands);
+    assert!(points.len() > 4);
+
+    for p in &points {
+        assert!((p.y - 3.0).abs() < 1e-4, "Y must stay constant in G18");
+        let r = ((p.x - 5.0).powi(2) + p.z.powi(2)).sqrt();
+        assert!((r - 5.0).abs() < 1e-3, "point off the XZ circle: {:?}", p);
+    }
+    let last = points.last().unwrap();
+    assert_eq!((last.x, last.y, last.z), (10.0, 3.0, 0.0));
+    // G2 in G18 sweeps through -Z going from X0 to X10
+    assert!(points.iter().any(|p| p.z < -4.9));
+}
+
+#[test]
+fn test_yz_arc_uses_plane_offsets() {
+    let commands = parse("G0 X1 Y0 Z0\nG19 G3 Y0 Z4 J0 K2\n");
+    let points = move_endpoints(&commands);
+    assert!(!points.is_empty());
+    for p in &points {
+        assert!((p.x - 1.0).abs() < 1e-4);
+        let r = (p.y.powi(2) + (p.z - 2.0).powi(2)).sqrt();
+        assert!((r - 2.0).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn test_plane_is_modal_until_changed() {
+    let commands = parse("G18\nG0 X0 Z0\nG2 X10 I5\nG17\nG2 X20 Y0 I5 J0\n");
+    assert!(matches!(commands.last(), Some(GCodeCommand::Arc { .. })));
+    assert!(move_endpoints(&commands).len() > 4);
+}
+
+#[test]
+fn test_r_word_arc_resolves_center() {
+    let mut viz = Visualizer::new();
+    viz.parse_gcode("G0 X0 Y0\nG2 X10 Y0 R5\n");
+    assert!(viz.arc_errors().is_empty());
+    match viz.commands().last() {
+        Some(GCodeCommand::Arc { center, to, .. }) => {
+            assert!((center.x - 5.0).abs() < 1e-4 && center.y.abs() < 1e-4);
+            assert_eq!((to.x, to.y), (10.0, 0.0));
+        }
+        other => panic!("expected arc, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_r_word_arc_in_xz_plane() {
+    let commands = parse("G0 X0 Y0 Z0\nG18 G3 X10 R5\n");
+    let points = move_endpoints(&commands);
+    assert!(points.len() > 4);
+    assert!(points.iter().all(|p| p.y.abs() < 1e-4));
+    // G3 in G18 from X0 to X10 passes through +Z
+    assert!(points.iter().any(|p| p.z > 4.9));
+}
+
+#[test]
+fn test_r_word_arc_too_small_is_reported() {
+    let mut viz = Visualizer::new();
+    viz.parse_gcode("G0 X0 Y0\nG2 X10 Y0 R2\nG1 X20\n");
+    let errors = viz.arc_errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].0, 2);
+    assert!(errors[0].1.contains("too small"));
+    assert!(!viz
+        .commands()
+        .iter()
+        .any(|cmd| matches!(cmd, GCodeCommand::Arc { .. })));
+    for cmd in viz.commands() {
+        if let GCodeCommand::Move { to, .. } = cmd {
+            assert!(to.x.is_finite() && to.y.is_finite());
+        }
+    }
+}
+
+#[test]
+fn test_helical_xz_arc_interpolates_y() {
+    // G18 helix: the linear axis is Y
+    let commands = parse("G0 X0 Y0 Z0\nG18 G2 X10 Y4 I5\n");
+    let points = move_endpoints(&commands);
+    assert!(points.len() > 4);
+    let last = points.last().unwrap();
+    assert_eq!((last.x, last.y, last.z), (10.0, 4.0, 0.0));
+    for pair in points.windows(2) {
+        assert!(pair[1].y > pair[0].y);
+    }
+}
@@ -0,0 +1,80 @@
+//! Tests for block delete (`/`) handling in the parser, pipeline and visualizer
+
+use gcodekit5_visualizer::{
+    BlockDeleteProcessor, CommandProcessor, GCodeCommand, GcodeCommand, GcodeParser, GcodeState,
+    Point3D, Visualizer,
+};
+
+const PROGRAM: &str = "G0 X0 Y0\nG1 X10 F500\n/G1 X10 Y20\nG1 X20 Y0\n";
+
+fn endpoints(commands: &[GCodeCommand]) -> Vec<Point3D> {
+    commands
+        .iter()
+        .filter_map(|cmd| match cmd {
+            GCodeCommand::Move { to, .. } => Some(*to),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_visualizer_skips_optional_blocks_by_default() {
+    let mut viz = Visualizer::new();
+    viz.parse_gcode(PROGRAM);
+    assert!(viz.block_delete());
+    assert_eq!(
+        endpoints(viz.commands()),
+        vec![
+            Point3D::new(0.0, 0.0, 0.0),
+            Point3D::new(10.0, 0.0, 0.0),
+            Point3D::new(20.0, 0.0, 0.0),
+        ]
+    );
+
+    // The skipped block is kept for dimmed display, from where it would have run
+    assert!(matches!(
+        viz.block_deleted_commands(),
+        [GCodeCommand::Move { from, to, .. }]
+            if *from == Point3D::new(10.0, 0.0, 0.0) && *to == Point3D::new(10.0, 20.0, 0.0)
+    ));
+}
+
+#[test]
+fn test_visualizer_runs_optional_blocks_with_switch_off() {
+    let mut viz = Visualizer::new();
+    viz.parse_gcode(PROGRAM);
+    viz.set_block_delete(false);
+    viz.parse_gcode(PROGRAM);
+    assert_eq!(endpoints(viz.commands()).len(), 4);
+    assert_eq!(endpoints(viz.commands())[2], Point3D::new(10.0, 20.0, 0.0));
+    assert!(viz.block_deleted_commands().is_empty());
+}
+
+#[test]
+fn test_parser_does_not_apply_skipped_blocks() {
+    let mut parser = GcodeParser::new();
+    let command = parser.parse("/G91").unwrap();
+    assert!(command.block_delete);
+    assert_eq!(command.command, "G91");
+    assert_eq!(parser.get_state().distance_mode, 90);
+
+    parser.set_block_delete(false);
+    parser.parse("/G91").unwrap();
+    assert_eq!(parser.get_state().distance_mode, 91);
+}
+
+#[test]
+fn test_processor_follows_switch() {
+    let state = GcodeState::default();
+    let optional = GcodeCommand::new("/M8");
+    let plain = GcodeCommand::new("M8");
+
+    let on = BlockDeleteProcessor::new(true);
+    assert!(on.process(&optional, &state).unwrap().is_empty());
+    assert_eq!(on.process(&plain, &state).unwrap().len(), 1);
+
+    let off = BlockDeleteProcessor::new(false);
+    let kept = off.process(&optional, &state).unwrap();
+    assert_eq!(kept.len(), 1);
+    assert_eq!(kept[0].command, "M8");
+}
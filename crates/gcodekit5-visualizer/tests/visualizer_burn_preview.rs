@@ -0,0 +1,90 @@
+//! Tests for the simulated laser burn preview
+
+use gcodekit5_visualizer::error::VisualizationError;
+use gcodekit5_visualizer::{render_burn_preview, BurnPreviewOptions, MaterialTint};
+
+fn darkness_at(gcode: &str, x: f64, y: f64) -> f32 {
+    let preview = render_burn_preview(gcode, &BurnPreviewOptions::default()).unwrap();
+    preview.darkness_at_point(x, y).unwrap()
+}
+
+#[test]
+fn test_burn_darkness_follows_power_over_speed() {
+    let full = "M4 S1000\nG1 X10 Y0 F1000\nM5\n";
+    let half_power = "M4 S500\nG1 X10 Y0 F1000\nM5\n";
+    let double_speed = "M4 S1000\nG1 X10 Y0 F2000\nM5\n";
+    let slower = "M4 S1000\nG1 X10 Y0 F500\nM5\n";
+
+    assert!(darkness_at(full, 5.0, 0.0) > 0.99);
+    assert!((darkness_at(half_power, 5.0, 0.0) - 0.5).abs() < 0.01);
+    assert!((darkness_at(double_speed, 5.0, 0.0) - 0.5).abs() < 0.01);
+    // Slower than the full-burn feed is already black
+    assert!(darkness_at(slower, 5.0, 0.0) > 0.99);
+    // Away from the line the material is untouched
+    assert_eq!(darkness_at(full, 5.0, 1.0), 0.0);
+}
+
+#[test]
+fn test_only_burning_moves_are_drawn() {
+    let gcode = "G0 X0 Y5\nM4 S1000 F1000\nG1 X10 Y5\nG1 X10 Y3 S0\nG0 X0 Y3\nM5\nG1 X0 Y0 S1000\nM3\nG3 X10 Y0 I5 J0\nM5\n";
+    let preview = render_burn_preview(gcode, &BurnPreviewOptions::default()).unwrap();
+
+    assert!(preview.darkness_at_point(5.0, 5.0).unwrap() > 0.99);
+    // S0 move, rapid, and a cut with the laser off
+    assert_eq!(preview.darkness_at_point(10.0, 4.0), Some(0.0));
+    assert_eq!(preview.darkness_at_point(5.0, 3.0), Some(0.0));
+    assert_eq!(preview.darkness_at_point(0.0, 1.5), Some(0.0));
+    // Arc back down through (5, -5) with M3
+    assert!(preview.darkness_at_point(5.0, -5.0).unwrap() > 0.99);
+
+    let cold = render_burn_preview("G1 X10 Y0 S1000 F1000\n", &BurnPreviewOptions::default());
+    assert!(matches!(cold, Err(VisualizationError::InvalidToolpath)));
+}
+
+#[test]
+fn test_repeated_passes_add_up() {
+    let one = "M4 S500 F1000\nG1 X10 Y0\nM5\n";
+    let two = "M4 S500 F1000\nG1 X10 Y0\nG0 X0 Y0\nG1 X10 Y0\nM5\n";
+    // Back and forth without a break is one pass
+    let joined = "M4 S500 F1000\nG1 X10 Y0\nG1 X0 Y0\nM5\n";
+
+    assert!((darkness_at(one, 5.0, 0.0) - 0.5).abs() < 0.01);
+    assert!((darkness_at(two, 5.0, 0.0) - 0.75).abs() < 0.01);
+    assert!((darkness_at(joined, 5.0, 0.0) - 0.5).abs() < 0.01);
+}
+
+#[test]
+fn test_preview_size_follows_dpi_and_saves_as_image() {
+    let gcode = "M4 S1000 F1000\nG1 X10 Y0\nG1 X10 Y5\nM5\n";
+    let options = BurnPreviewOptions {
+        dpi: 254.0,
+        margin_mm: 1.0,
+        tint: MaterialTint::Wood,
+        ..Default::default()
+    };
+    let preview = render_burn_preview(gcode, &options).unwrap();
+    // 10 x 5 mm plus the spot and margin on each side, at 0.1 mm per pixel
+    assert_eq!((preview.width, preview.height), (121, 71));
+    let finer = render_burn_preview(
+        gcode,
+        &BurnPreviewOptions {
+            dpi: 508.0,
+            ..options.clone()
+        },
+    )
+    .unwrap();
+    assert_eq!((finer.width, finer.height), (242, 142));
+
+    let (material, _) = MaterialTint::Wood.colors();
+    let image = preview.to_image();
+    assert_eq!(image.get_pixel(0, 0).0, material);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("burn.png");
+    preview.save(&path).unwrap();
+    let saved = image::open(&path).unwrap();
+    assert_eq!((saved.width(), saved.height()), (121, 71));
+
+    let err = preview.save(&dir.path().join("burn.nc")).unwrap_err();
+    assert!(matches!(err, VisualizationError::UnsupportedFormat(_)));
+}
@@ -0,0 +1,87 @@
+//! Tests for splitting a program into operations and hiding them in the visualizer
+
+use gcodekit5_visualizer::{GCodeCommand, Visualizer};
+
+const PROGRAM: &str = "\
+G21 G90
+G0 X0 Y0
+T1 M6
+; Operation: Contour
+G1 X10 Y0 F500
+G1 X10 Y10
+(Tool change)
+T2
+M6
+G0 X100 Y100
+G1 X110 Y100
+; Operation: Pocket
+G1 X110 Y110
+";
+
+fn parsed() -> Visualizer {
+    let mut viz = Visualizer::new();
+    viz.parse_gcode(PROGRAM);
+    viz
+}
+
+#[test]
+fn test_operations_split_at_tool_changes_and_comments() {
+    let viz = parsed();
+    let ops = viz.operations();
+    let summary: Vec<_> = ops
+        .iter()
+        .map(|op| (op.name.as_str(), op.tool, op.line, op.commands.clone()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("Operation 1", None, 1, 0..1),
+            ("Contour", Some(1), 3, 1..3),
+            ("T2", Some(2), 9, 3..5),
+            ("Pocket", Some(2), 12, 5..6),
+        ]
+    );
+    assert_eq!(viz.operation_of(0), Some(0));
+    assert_eq!(viz.operation_of(4), Some(2));
+    assert_eq!(viz.operation_of(6), None);
+}
+
+#[test]
+fn test_program_without_marks_is_one_operation() {
+    let mut viz = Visualizer::new();
+    viz.parse_gcode("G0 X0 Y0\nG1 X10 F500\n");
+    assert_eq!(viz.operations().len(), 1);
+    assert_eq!(viz.operations()[0].commands, 0..2);
+
+    viz.parse_gcode("");
+    assert!(viz.operations().is_empty());
+}
+
+#[test]
+fn test_hidden_and_soloed_operations_are_not_drawn() {
+    let mut viz = parsed();
+    viz.set_operation_visible(1, false);
+    assert_eq!(viz.visible_commands().count(), 4);
+    assert!(!viz.is_command_visible(1));
+
+    viz.solo_operation(2);
+    let visible: Vec<_> = viz.visible_commands().collect();
+    assert_eq!(visible.len(), 2);
+    assert!(matches!(visible[0], GCodeCommand::Move { rapid: true, .. }));
+
+    viz.show_all_operations();
+    assert_eq!(viz.visible_commands().count(), viz.commands().len());
+}
+
+#[test]
+fn test_fit_to_visible_ignores_hidden_operations() {
+    let mut viz = parsed();
+    viz.fit_to_view(1000.0, 1000.0);
+    let all_zoom = viz.zoom_scale;
+
+    viz.solo_operation(1);
+    viz.fit_to_visible(1000.0, 1000.0);
+    assert!(viz.zoom_scale > all_zoom);
+    assert!((viz.x_offset + 5.0).abs() < 1e-4);
+    assert!((viz.y_offset + 5.0).abs() < 1e-4);
+}
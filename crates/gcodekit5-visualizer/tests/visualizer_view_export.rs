@@ -0,0 +1,89 @@
+//! Tests for the SVG/PDF export of the 2D toolpath
+
+use gcodekit5_core::units::MeasurementSystem;
+use gcodekit5_visualizer::{
+    export_view, export_view_pdf, export_view_svg, PaperOrientation, PaperSize, ViewExportFormat,
+    ViewExportOptions, Visualizer,
+};
+use std::path::Path;
+
+fn square() -> Visualizer {
+    let mut viz = Visualizer::new();
+    viz.parse_gcode(
+        "G0 X0 Y0\nG1 X100 Y0 F500\nG1 X100 Y100\nG1 X0 Y100\nG1 X0 Y0\nG0 X50 Y50\nG2 X60 Y50 I5 J0\n",
+    );
+    viz
+}
+
+fn options() -> ViewExportOptions {
+    ViewExportOptions {
+        title: "square.nc".to_string(),
+        estimated_time_secs: Some(3725.0),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_svg_uses_paper_size_scale_and_title_block() {
+    let svg = export_view_svg(&square(), &options()).unwrap();
+
+    // A4 landscape, drawn at 1:1 since 100 mm fits
+    assert!(svg.contains("width=\"297mm\" height=\"210mm\" viewBox=\"0 0 297 210\""));
+    assert!(svg.contains("<g id=\"cuts\""));
+    assert!(svg.contains("<g id=\"rapids\""));
+    assert!(svg.contains("square.nc"));
+    assert!(svg.contains("Estimated time: 1:02:05    Scale: 1:1"));
+    assert!(svg.contains("Bounds: X 0.000 to 100.000 mm, Y 0.000 to 100.000 mm"));
+    assert!(svg.contains(">50 mm</text>"));
+}
+
+#[test]
+fn test_layers_units_and_scale_follow_options() {
+    let portrait = ViewExportOptions {
+        paper: PaperSize::Custom {
+            width_mm: 100.0,
+            height_mm: 120.0,
+        },
+        orientation: PaperOrientation::Portrait,
+        show_rapids: false,
+        units: MeasurementSystem::Imperial,
+        ..options()
+    };
+    let svg = export_view_svg(&square(), &portrait).unwrap();
+
+    // 80 x 76 mm drawing area: the 100 mm square is drawn at 1:2
+    assert!(svg.contains("Scale: 1:2"));
+    assert!(!svg.contains("id=\"rapids\""));
+    assert!(svg.contains(" in, Y "));
+    assert!(svg.contains(" in</text>"));
+}
+
+#[test]
+fn test_pdf_is_a_single_page_document() {
+    let pdf = export_view_pdf(&square(), &options()).unwrap();
+    let text = String::from_utf8(pdf).unwrap();
+
+    assert!(text.starts_with("%PDF-1.4"));
+    assert!(text.trim_end().ends_with("%%EOF"));
+    assert!(text.contains("/MediaBox [0 0 841.890 595.276]"));
+    assert!(text.contains("(square.nc) Tj"));
+    let xref = text.rfind("startxref\n").unwrap();
+    let offset: usize = text[xref + 10..].lines().next().unwrap().parse().unwrap();
+    assert!(text[offset..].starts_with("xref"));
+}
+
+#[test]
+fn test_export_rejects_empty_toolpaths_and_unknown_formats() {
+    let empty = Visualizer::new();
+    assert!(export_view_svg(&empty, &options()).is_err());
+
+    let dir = tempfile::tempdir().unwrap();
+    assert!(export_view(&square(), &dir.path().join("view.png"), &options()).is_err());
+    let path = dir.path().join("view.SVG");
+    export_view(&square(), &path, &options()).unwrap();
+    assert!(std::fs::read_to_string(&path).unwrap().starts_with("<?xml"));
+    assert_eq!(
+        ViewExportFormat::from_path(Path::new("a.pdf")),
+        Some(ViewExportFormat::Pdf)
+    );
+}
@@ -77,6 +77,7 @@ pub fn register_callbacks(
             parity: SerialParity::None,
             auto_reconnect: true,
             max_retries: 3,
+            ..Default::default()
         };
 
         // Try to connect